@@ -0,0 +1,138 @@
+import { useState, useEffect } from 'react';
+import { Wallet, ChevronLeft, ChevronRight, Activity } from 'lucide-react';
+import { apiClient } from '../../api/client';
+
+interface CategoryTotal {
+    category: string;
+    total: number;
+    count: number;
+}
+
+interface CurrencyTotal {
+    currency: string;
+    total: number;
+    categories: CategoryTotal[];
+}
+
+interface ExpenseSummary {
+    month: string;
+    count: number;
+    totals: CurrencyTotal[];
+}
+
+interface Expense {
+    id: string;
+    amount: number;
+    currency: string;
+    category: string;
+    date: string;
+    note: string;
+}
+
+const currentMonth = () => new Date().toISOString().slice(0, 7);
+
+const shiftMonth = (month: string, delta: number) => {
+    const [year, m] = month.split('-').map(Number);
+    const d = new Date(Date.UTC(year, m - 1 + delta, 1));
+    return d.toISOString().slice(0, 7);
+};
+
+const formatAmount = (amount: number, currency: string) =>
+    new Intl.NumberFormat(undefined, { style: 'currency', currency }).format(amount);
+
+export function ExpensesWidget() {
+    const [month, setMonth] = useState(currentMonth());
+    const [summary, setSummary] = useState<ExpenseSummary | null>(null);
+    const [expenses, setExpenses] = useState<Expense[]>([]);
+    const [loading, setLoading] = useState(true);
+
+    useEffect(() => {
+        const fetchMonth = async () => {
+            setLoading(true);
+            try {
+                const [s, list] = await Promise.all([
+                    apiClient.get<ExpenseSummary>(`/expenses/summary?month=${month}`),
+                    apiClient.get<Expense[]>(`/expenses?month=${month}`),
+                ]);
+                setSummary(s);
+                setExpenses(list.slice(0, 8)); // Latest 8
+            } catch (e) {
+                console.error(e);
+            } finally {
+                setLoading(false);
+            }
+        };
+        fetchMonth();
+    }, [month]);
+
+    return (
+        <div className="glass-panel p-6 min-h-[500px]">
+            <div className="flex items-center justify-between mb-6">
+                <h3 className="font-black text-xl flex items-center gap-3">
+                    <Wallet size={20} className="text-mymolt-yellow" /> Household Expenses
+                </h3>
+                <div className="flex items-center gap-2 text-sm">
+                    <button onClick={() => setMonth(shiftMonth(month, -1))} className="p-1.5 hover:bg-white/10 rounded-lg" title="Previous month">
+                        <ChevronLeft size={16} />
+                    </button>
+                    <span className="font-mono w-20 text-center">{month}</span>
+                    <button
+                        onClick={() => setMonth(shiftMonth(month, 1))}
+                        disabled={month >= currentMonth()}
+                        className="p-1.5 hover:bg-white/10 rounded-lg disabled:opacity-30"
+                        title="Next month"
+                    >
+                        <ChevronRight size={16} />
+                    </button>
+                </div>
+            </div>
+
+            {loading ? (
+                <div className="text-center py-8 opacity-50"><Activity className="animate-spin mx-auto mb-2" /> Decrypting...</div>
+            ) : !summary || summary.count === 0 ? (
+                <div className="text-center py-8 text-mymolt-text-muted text-sm border-2 border-dashed border-white/10 rounded-xl">
+                    No expenses recorded this month. Ask the agent or send a receipt photo.
+                </div>
+            ) : (
+                <div className="space-y-6">
+                    {summary.totals.map((t) => (
+                        <div key={t.currency} className="p-5 bg-black/20 rounded-2xl border border-mymolt-glassBorder">
+                            <div className="flex items-baseline justify-between mb-4">
+                                <span className="text-[10px] font-black text-mymolt-primary uppercase tracking-widest">Total</span>
+                                <span className="text-2xl font-black">{formatAmount(t.total, t.currency)}</span>
+                            </div>
+                            <div className="space-y-2">
+                                {t.categories.map((c) => (
+                                    <div key={c.category}>
+                                        <div className="flex justify-between text-xs mb-1">
+                                            <span className="capitalize">{c.category} <span className="text-mymolt-text-muted">({c.count})</span></span>
+                                            <span className="font-mono">{formatAmount(c.total, t.currency)}</span>
+                                        </div>
+                                        <div className="h-1.5 w-full bg-white/5 rounded-full overflow-hidden">
+                                            <div className="h-full bg-mymolt-yellow" style={{ width: `${(c.total / t.total) * 100}%` }} />
+                                        </div>
+                                    </div>
+                                ))}
+                            </div>
+                        </div>
+                    ))}
+
+                    <div className="space-y-2">
+                        {expenses.map((e) => (
+                            <div key={e.id} className="flex justify-between text-sm px-1">
+                                <span className="text-mymolt-text-muted">
+                                    {e.date} · <span className="capitalize">{e.category}</span>{e.note && ` · ${e.note}`}
+                                </span>
+                                <span className="font-mono">{formatAmount(e.amount, e.currency)}</span>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+
+            <div className="mt-4 pt-4 border-t border-white/10 text-xs text-mymolt-text-muted">
+                {summary?.count ?? 0} expenses · encrypted at rest
+            </div>
+        </div>
+    );
+}
@@ -18,6 +18,7 @@ export const IdentityWidget: React.FC = () => {
     const [modalMessage, setModalMessage] = useState('');
 
     const [vpInput, setVpInput] = useState('');
+    const [vpChallenge, setVpChallenge] = useState<{ challenge: string; domain?: string } | null>(null);
     const fileInputRef = useRef<HTMLInputElement>(null);
 
     const fetchData = async () => {
@@ -40,11 +41,22 @@ export const IdentityWidget: React.FC = () => {
         fetchData();
     }, []);
 
+    // Single-use challenge the wallet signs into its presentation
+    const fetchVpChallenge = () => {
+        apiClient.post<{ challenge: string; domain?: string }>('/api/identity/vp-challenge', {})
+            .then(setVpChallenge)
+            .catch(console.error);
+    };
+
     // Reset modal status when view changes
     useEffect(() => {
         setModalStatus('idle');
         setModalMessage('');
         setVpInput('');
+        setVpChallenge(null);
+        if (modalView === 'ssi-wallet') {
+            fetchVpChallenge();
+        }
     }, [modalView]);
 
     // ── eIDAS Certificate Upload Handler ─────────────────────────────
@@ -89,7 +101,7 @@ export const IdentityWidget: React.FC = () => {
         try {
             const data = await apiClient.post<{ success: boolean; result?: any; error?: string }>(
                 '/api/identity/verify-vp',
-                { vp: vpInput }
+                { vp: vpInput, challenge: vpChallenge?.challenge }
             );
 
             if (data.success) {
@@ -100,6 +112,8 @@ export const IdentityWidget: React.FC = () => {
             } else {
                 setModalStatus('error');
                 setModalMessage(data.error || 'VP verification failed');
+                // Challenges are single-use; fetch a new one for the next attempt
+                fetchVpChallenge();
             }
         } catch (err) {
             setModalStatus('error');
@@ -429,10 +443,19 @@ export const IdentityWidget: React.FC = () => {
                                     </div>
 
                                     <p className="text-sm text-mymolt-text-muted/70 leading-relaxed">
-                                        Paste your Verifiable Presentation (VP) in JSON-LD format from your SSI wallet
+                                        Paste your Verifiable Presentation (VP) as JSON-LD or JWT, signed with the challenge and domain below, from your SSI wallet
                                         (e.g., Sphereon, walt.id, or EU Digital Identity Wallet).
                                     </p>
 
+                                    {vpChallenge && (
+                                        <div className="text-xs font-mono text-mymolt-text-muted/60 bg-black/20 border border-mymolt-glassBorder rounded-xl p-3 space-y-1 break-all">
+                                            <div>challenge: <span className="text-white/80">{vpChallenge.challenge}</span></div>
+                                            {vpChallenge.domain && (
+                                                <div>domain: <span className="text-white/80">{vpChallenge.domain}</span></div>
+                                            )}
+                                        </div>
+                                    )}
+
                                     <textarea
                                         value={vpInput}
                                         onChange={(e) => setVpInput(e.target.value)}
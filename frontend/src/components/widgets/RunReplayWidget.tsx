@@ -0,0 +1,163 @@
+import { useState, useEffect } from 'react';
+import { PlayCircle, ChevronLeft, ChevronRight, Activity, ArrowLeft } from 'lucide-react';
+import { apiClient } from '../../api/client';
+
+interface RunSummary {
+    id: string;
+    started_at: string;
+    duration_ms: number;
+    steps: number;
+    provider: string | null;
+    model: string | null;
+    tool_calls: number;
+    error: string | null;
+}
+
+type Step =
+    | { kind: 'message'; role: string; content: string }
+    | { kind: 'llm_request'; provider: string; model: string; messages: number }
+    | { kind: 'llm_response'; duration_ms: number; success: boolean; error?: string }
+    | { kind: 'tool_start'; tool: string }
+    | { kind: 'tool_call'; tool: string; duration_ms: number; success: boolean }
+    | { kind: 'tool_io'; tool: string; arguments: string; output: string }
+    | { kind: 'error'; component: string; message: string };
+
+type RecordedStep = Step & {
+    at: string;
+    elapsed_ms: number;
+    agent?: string;
+};
+
+const formatMs = (ms: number) => (ms < 1000 ? `${ms} ms` : `${(ms / 1000).toFixed(1)} s`);
+
+const stepTitle = (s: RecordedStep) => {
+    switch (s.kind) {
+        case 'message': return `Message · ${s.role}`;
+        case 'llm_request': return `LLM request · ${s.model} (${s.messages} messages)`;
+        case 'llm_response': return `LLM response · ${formatMs(s.duration_ms)}${s.success ? '' : ' · failed'}`;
+        case 'tool_start': return `Tool start · ${s.tool}`;
+        case 'tool_call': return `Tool done · ${s.tool} · ${formatMs(s.duration_ms)}${s.success ? '' : ' · failed'}`;
+        case 'tool_io': return `Tool I/O · ${s.tool}`;
+        case 'error': return `Error · ${s.component}`;
+    }
+};
+
+const stepBody = (s: RecordedStep) => {
+    switch (s.kind) {
+        case 'message': return s.content;
+        case 'llm_response': return s.error ?? '';
+        case 'tool_io': return `Arguments:\n${s.arguments}\n\nOutput:\n${s.output}`;
+        case 'error': return s.message;
+        default: return '';
+    }
+};
+
+export function RunReplayWidget() {
+    const [runs, setRuns] = useState<RunSummary[]>([]);
+    const [selected, setSelected] = useState<string | null>(null);
+    const [steps, setSteps] = useState<RecordedStep[]>([]);
+    const [cursor, setCursor] = useState(0);
+    const [loading, setLoading] = useState(true);
+
+    useEffect(() => {
+        apiClient.get<RunSummary[]>('/agent/runs')
+            .then(setRuns)
+            .catch(console.error)
+            .finally(() => setLoading(false));
+    }, []);
+
+    useEffect(() => {
+        if (!selected) return;
+        setLoading(true);
+        apiClient.get<RecordedStep[]>(`/agent/runs/${selected}`)
+            .then((s) => {
+                setSteps(s);
+                setCursor(0);
+            })
+            .catch(console.error)
+            .finally(() => setLoading(false));
+    }, [selected]);
+
+    const step = steps[cursor];
+
+    return (
+        <div className="glass-panel p-6 min-h-[500px]">
+            <div className="flex items-center justify-between mb-6">
+                <h3 className="font-black text-xl flex items-center gap-3">
+                    <PlayCircle size={20} className="text-mymolt-yellow" /> Run Replay
+                </h3>
+                {selected && (
+                    <button onClick={() => setSelected(null)} className="flex items-center gap-1 text-sm p-1.5 hover:bg-white/10 rounded-lg">
+                        <ArrowLeft size={16} /> All runs
+                    </button>
+                )}
+            </div>
+
+            {loading ? (
+                <div className="text-center py-8 opacity-50"><Activity className="animate-spin mx-auto mb-2" /> Loading...</div>
+            ) : !selected ? (
+                runs.length === 0 ? (
+                    <div className="text-center py-8 text-mymolt-text-muted text-sm border-2 border-dashed border-white/10 rounded-xl">
+                        No recorded runs. Set <code>record_runs = true</code> under <code>[observability]</code> to record agent turns.
+                    </div>
+                ) : (
+                    <div className="space-y-2">
+                        {runs.map((r) => (
+                            <button
+                                key={r.id}
+                                onClick={() => setSelected(r.id)}
+                                className="w-full text-left p-4 bg-black/20 rounded-2xl border border-mymolt-glassBorder hover:bg-white/5"
+                            >
+                                <div className="flex justify-between text-sm">
+                                    <span className="font-mono">{new Date(r.started_at).toLocaleString()}</span>
+                                    <span className={r.error ? 'text-red-400' : 'text-mymolt-text-muted'}>
+                                        {r.error ? 'failed' : formatMs(r.duration_ms)}
+                                    </span>
+                                </div>
+                                <div className="text-xs text-mymolt-text-muted mt-1">
+                                    {r.model ?? 'unknown model'} · {r.steps} steps · {r.tool_calls} tool calls
+                                </div>
+                            </button>
+                        ))}
+                    </div>
+                )
+            ) : !step ? (
+                <div className="text-center py-8 text-mymolt-text-muted text-sm">This recording is empty.</div>
+            ) : (
+                <div className="space-y-4">
+                    <div className="flex items-center justify-between text-sm">
+                        <button onClick={() => setCursor(cursor - 1)} disabled={cursor === 0} className="p-1.5 hover:bg-white/10 rounded-lg disabled:opacity-30" title="Previous step">
+                            <ChevronLeft size={16} />
+                        </button>
+                        <span className="font-mono">
+                            Step {cursor + 1} / {steps.length} · +{formatMs(step.elapsed_ms)}
+                        </span>
+                        <button onClick={() => setCursor(cursor + 1)} disabled={cursor >= steps.length - 1} className="p-1.5 hover:bg-white/10 rounded-lg disabled:opacity-30" title="Next step">
+                            <ChevronRight size={16} />
+                        </button>
+                    </div>
+                    <input
+                        type="range"
+                        min={0}
+                        max={steps.length - 1}
+                        value={cursor}
+                        onChange={(e) => setCursor(Number(e.target.value))}
+                        className="w-full"
+                    />
+                    <div className="p-5 bg-black/20 rounded-2xl border border-mymolt-glassBorder">
+                        <div className="text-[10px] font-black text-mymolt-primary uppercase tracking-widest mb-3">
+                            {step.agent && `${step.agent} · `}{stepTitle(step)}
+                        </div>
+                        {stepBody(step) && (
+                            <pre className="text-xs whitespace-pre-wrap break-words max-h-96 overflow-y-auto font-mono">{stepBody(step)}</pre>
+                        )}
+                    </div>
+                </div>
+            )}
+
+            <div className="mt-4 pt-4 border-t border-white/10 text-xs text-mymolt-text-muted">
+                Secrets are redacted before recording
+            </div>
+        </div>
+    );
+}
@@ -16,7 +16,9 @@ import {
     Box,
     Plug,
     Settings,
-    Globe
+    Globe,
+    Wallet,
+    PlayCircle
 } from 'lucide-react';
 import Security from './Security';
 import Skills from './Skills';
@@ -35,13 +37,15 @@ import { SchedulerWidget } from '../components/widgets/SchedulerWidget';
 import { AdBlockWidget } from '../components/widgets/AdBlockWidget';
 import { FilesWidget } from '../components/widgets/FilesWidget';
 import { BrowserWidget } from '../components/widgets/BrowserWidget';
+import { ExpensesWidget } from '../components/widgets/ExpensesWidget';
+import { RunReplayWidget } from '../components/widgets/RunReplayWidget';
 import { VoiceButton } from '../components/ui/VoiceButton';
 import { useAudio } from '../hooks/useAudio';
 import { useSocket } from '../hooks/useSocket';
 import { motion, AnimatePresence } from 'framer-motion';
 import { AdminPanel } from './AdminPanel';
 
-type TabId = 'chat' | 'browser' | 'sigil' | 'adblock' | 'soul' | 'vpn' | 'vault' | 'files' | 'diary' | 'system' | 'skills' | 'integrations' | 'security';
+type TabId = 'chat' | 'browser' | 'sigil' | 'adblock' | 'soul' | 'vpn' | 'vault' | 'files' | 'diary' | 'expenses' | 'runs' | 'system' | 'skills' | 'integrations' | 'security';
 
 interface DashboardProps {
     role: UserRole;
@@ -118,11 +122,13 @@ export const Dashboard: React.FC<DashboardProps> = ({ role }) => {
         { id: 'browser' as const, icon: Globe, label: 'Sovereign Browser', roles: ['Root', 'Adult', 'Child', 'Senior'] },
         { id: 'soul' as const, icon: Fingerprint, label: 'Soul Identity', roles: ['Root', 'Adult'] },
         { id: 'sigil' as const, icon: Eye, label: 'Sigil Transparency', roles: ['Root'] },
+        { id: 'runs' as const, icon: PlayCircle, label: 'Run Replay', roles: ['Root'] },
         { id: 'adblock' as const, icon: Shield, label: 'DNS Shield', roles: ['Root', 'Adult'] },
         { id: 'vpn' as const, icon: Network, label: 'VPN Connect', roles: ['Root', 'Adult'] },
         { id: 'vault' as const, icon: Lock, label: 'Secure Vault', roles: ['Root'] },
         { id: 'files' as const, icon: HardDrive, label: 'Sovereign Files', roles: ['Root', 'Adult'] },
         { id: 'diary' as const, icon: Brain, label: 'Cognitive Diary', roles: ['Root', 'Adult', 'Senior'] },
+        { id: 'expenses' as const, icon: Wallet, label: 'Expenses', roles: ['Root', 'Adult'] },
         { id: 'skills' as const, icon: Box, label: 'SkillForge', roles: ['Root'] },
         { id: 'integrations' as const, icon: Plug, label: 'Integration Store', roles: ['Root'] },
         { id: 'system' as const, icon: Activity, label: 'System Health', roles: ['Root', 'Adult'] },
@@ -304,11 +310,13 @@ export const Dashboard: React.FC<DashboardProps> = ({ role }) => {
                             {activeTab === 'chat' && <ChatWidget />}
                             {activeTab === 'browser' && <BrowserWidget />}
                             {activeTab === 'sigil' && isRoot && <SigilWidget />}
+                            {activeTab === 'runs' && isRoot && <RunReplayWidget />}
                             {activeTab === 'adblock' && isAdult && <AdBlockWidget />}
                             {activeTab === 'soul' && isAdult && <IdentityWidget />}
                             {activeTab === 'vpn' && isAdult && <VPNWidget />}
                             {activeTab === 'vault' && isRoot && <VaultWidget />}
                             {activeTab === 'files' && isAdult && <FilesWidget />}
+                            {activeTab === 'expenses' && isAdult && <ExpensesWidget />}
 
                             {activeTab === 'diary' && (role === 'Root' || role === 'Adult' || role === 'Senior') && <DiaryWidget />}
                             {activeTab === 'skills' && isRoot && <Skills />}
@@ -38,6 +38,8 @@ export interface WsMessage {
         sender?: string;
         code?: string;
         message?: string;
+        /** Set to run several chats over one socket; replies echo it back. */
+        conversation_id?: string;
     };
 }
 
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Per-request limits for the agent tool-call loop.
+//!
+//! A misbehaving model can keep requesting tools forever. `run_tool_call_loop`
+//! checks a [`LoopBudget`] before every LLM call and stops with
+//! [`BudgetExceeded`] once the iteration count, the estimated token total or
+//! the wall-clock deadline is spent. A turn making several loop runs and
+//! LLM calls, like plan-execute, charges them all to one [`BudgetUsage`].
+
+use crate::config::AgentConfig;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Limits applied to one agent turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopBudget {
+    /// LLM round-trips allowed before giving up.
+    pub max_iterations: usize,
+    /// Estimated prompt + completion tokens across all round-trips; `None` is unlimited.
+    pub max_tokens: Option<u64>,
+    /// Wall-clock limit for the whole turn; `None` is unlimited.
+    pub max_wall_time: Option<Duration>,
+}
+
+impl LoopBudget {
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self {
+            max_iterations: config.max_iterations.max(1),
+            max_tokens: (config.max_tokens > 0).then_some(config.max_tokens),
+            max_wall_time: (config.max_wall_secs > 0)
+                .then(|| Duration::from_secs(config.max_wall_secs)),
+        }
+    }
+
+    /// Error when an LLM call sending `prompt_tokens` would go over the token
+    /// limit after what `usage` has spent.
+    pub fn check_tokens(
+        &self,
+        usage: &BudgetUsage,
+        prompt_tokens: u64,
+    ) -> Result<(), BudgetExceeded> {
+        match self.max_tokens {
+            Some(max_tokens) if usage.tokens + prompt_tokens > max_tokens => Err(BudgetExceeded {
+                limit: BudgetLimit::Tokens,
+                used: usage.tokens + prompt_tokens,
+                max: max_tokens,
+                iterations: usage.iterations,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// When the wall-time limit of the turn `usage` tracks runs out.
+    pub fn deadline(&self, usage: &BudgetUsage) -> Option<tokio::time::Instant> {
+        self.max_wall_time
+            .map(|limit| tokio::time::Instant::from_std(usage.started_at) + limit)
+    }
+
+    /// Error for a turn that ran past its wall-time limit.
+    pub fn wall_time_exceeded(&self, usage: &BudgetUsage) -> BudgetExceeded {
+        BudgetExceeded {
+            limit: BudgetLimit::WallTime,
+            used: usage.started_at.elapsed().as_secs(),
+            max: self.max_wall_time.unwrap_or_default().as_secs(),
+            iterations: usage.iterations,
+        }
+    }
+}
+
+impl Default for LoopBudget {
+    fn default() -> Self {
+        Self::from_config(&AgentConfig::default())
+    }
+}
+
+/// What one turn has spent of its [`LoopBudget`] so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetUsage {
+    /// LLM round-trips made.
+    pub iterations: usize,
+    /// Estimated prompt + completion tokens sent and received.
+    pub tokens: u64,
+    /// Start of the turn; the wall-time limit counts from here.
+    pub started_at: Instant,
+}
+
+impl BudgetUsage {
+    /// Nothing spent, starting now.
+    pub fn start() -> Self {
+        Self {
+            iterations: 0,
+            tokens: 0,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Which limit stopped the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    Iterations,
+    Tokens,
+    WallTime,
+}
+
+/// Error returned by an agent loop that ran out of budget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetExceeded {
+    pub limit: BudgetLimit,
+    /// Amount used when stopped: iterations, estimated tokens or seconds.
+    pub used: u64,
+    /// The configured maximum, in the same unit as `used`.
+    pub max: u64,
+    /// LLM round-trips completed before stopping.
+    pub iterations: usize,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self.limit {
+            BudgetLimit::Iterations => "tool iterations",
+            BudgetLimit::Tokens => "estimated tokens",
+            BudgetLimit::WallTime => "seconds",
+        };
+        write!(
+            f,
+            "Agent budget exceeded: {}/{} {unit} after {} iterations",
+            self.used, self.max, self.iterations
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// Rough token count (~4 characters per token) for providers that do not
+/// report usage.
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_config_values_mean_unlimited() {
+        let budget = LoopBudget::from_config(&AgentConfig {
+            max_iterations: 0,
+            max_tokens: 0,
+            max_wall_secs: 0,
+            ..AgentConfig::default()
+        });
+        assert_eq!(budget.max_iterations, 1);
+        assert_eq!(budget.max_tokens, None);
+        assert_eq!(budget.max_wall_time, None);
+    }
+
+    #[test]
+    fn budget_exceeded_serializes_limit_kind() {
+        let err = BudgetExceeded {
+            limit: BudgetLimit::WallTime,
+            used: 30,
+            max: 30,
+            iterations: 2,
+        };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["limit"], "wall_time");
+        assert!(err.to_string().contains("30/30 seconds"));
+    }
+
+    #[test]
+    fn token_limit_counts_what_the_turn_spent() {
+        let budget = LoopBudget {
+            max_tokens: Some(100),
+            ..LoopBudget::default()
+        };
+        let usage = BudgetUsage {
+            iterations: 2,
+            tokens: 90,
+            ..BudgetUsage::start()
+        };
+        assert!(budget.check_tokens(&usage, 10).is_ok());
+        let err = budget.check_tokens(&usage, 11).unwrap_err();
+        assert_eq!(err.used, 101);
+        assert_eq!(err.iterations, 2);
+    }
+
+    #[test]
+    fn estimate_tokens_rounds_up() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abc"), 1);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+    }
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Cancellation of running agent loops.
+//!
+//! `run_tool_call_loop` races the LLM call and every tool execution against a
+//! [`CancellationToken`]; when it fires, the pending future is dropped and the
+//! loop returns [`Cancelled`]. [`AgentRuns`] maps client-chosen ids (the chat
+//! `conversation_id`) to tokens so a run can be stopped from another request.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Error returned by an agent loop whose token was cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancelled {
+    /// Tool that was executing when the run was aborted, if any.
+    pub tool: Option<String>,
+}
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.tool {
+            Some(tool) => write!(f, "Agent run cancelled while executing '{tool}'"),
+            None => write!(f, "Agent run cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Agent runs that can be cancelled by id.
+#[derive(Debug, Default)]
+pub struct AgentRuns {
+    next: AtomicU64,
+    runs: Mutex<HashMap<String, (u64, CancellationToken)>>,
+}
+
+impl AgentRuns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `token` cancellable under `id` until the returned guard is dropped.
+    /// A later registration under the same id takes over the id.
+    pub fn register(self: &Arc<Self>, id: String, token: CancellationToken) -> RunGuard {
+        let generation = self.next.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(id.clone(), (generation, token));
+        RunGuard {
+            runs: Arc::clone(self),
+            id,
+            generation,
+        }
+    }
+
+    /// Cancel the run registered under `id`. Returns `false` if there is none.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.lock().get(id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel every registered run. Returns how many there were.
+    pub fn cancel_all(&self) -> usize {
+        let runs = self.lock();
+        for (_, token) in runs.values() {
+            token.cancel();
+        }
+        runs.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (u64, CancellationToken)>> {
+        self.runs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Unregisters a run when dropped.
+#[derive(Debug)]
+pub struct RunGuard {
+    runs: Arc<AgentRuns>,
+    id: String,
+    generation: u64,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        let mut runs = self.runs.lock();
+        if runs.get(&self.id).is_some_and(|(g, _)| *g == self.generation) {
+            runs.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_reaches_registered_token_until_guard_drops() {
+        let runs = Arc::new(AgentRuns::new());
+        let token = CancellationToken::new();
+        let guard = runs.register("chat-1".into(), token.clone());
+
+        assert!(!runs.cancel("other"));
+        assert!(runs.cancel("chat-1"));
+        assert!(token.is_cancelled());
+
+        drop(guard);
+        assert!(!runs.cancel("chat-1"));
+    }
+
+    #[test]
+    fn stale_guard_keeps_newer_registration() {
+        let runs = Arc::new(AgentRuns::new());
+        let first = runs.register("chat".into(), CancellationToken::new());
+        let newer = CancellationToken::new();
+        let _second = runs.register("chat".into(), newer.clone());
+
+        drop(first);
+        assert!(runs.cancel("chat"));
+        assert!(newer.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_all_reaches_every_run() {
+        let runs = Arc::new(AgentRuns::new());
+        let a = CancellationToken::new();
+        let b = CancellationToken::new();
+        let _a = runs.register("a".into(), a.clone());
+        let _b = runs.register("b".into(), b.clone());
+
+        assert_eq!(runs.cancel_all(), 2);
+        assert!(a.is_cancelled() && b.is_cancelled());
+    }
+}
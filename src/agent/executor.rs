@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Admission control for agent runs.
+//!
+//! Every gateway entry point (webhook, `WhatsApp`, dashboard chat) asks the
+//! [`AgentExecutor`] for a slot before calling the provider. At most
+//! `max_concurrent` runs execute at once; the rest wait in a bounded queue
+//! ordered by channel priority, then arrival. A full queue rejects new runs
+//! with [`QueueFull`] instead of piling up provider calls.
+
+use crate::config::ExecutorConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Error returned when no slot is free and the queue is at capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueFull {
+    pub queued: usize,
+}
+
+impl std::fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Agent queue is full ({} runs waiting)", self.queued)
+    }
+}
+
+impl std::error::Error for QueueFull {}
+
+/// Current load, for status endpoints.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutorStats {
+    pub running: usize,
+    pub queued: usize,
+    pub max_concurrent: usize,
+    pub max_queued: usize,
+}
+
+struct Waiter {
+    priority: u8,
+    seq: u64,
+    /// Fired when a slot has been handed to this waiter.
+    tx: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct Slots {
+    running: usize,
+    next_seq: u64,
+    waiting: Vec<Waiter>,
+}
+
+impl Slots {
+    /// Remove the waiter that should run next: highest priority, then oldest.
+    fn pop_next(&mut self) -> Option<Waiter> {
+        let (index, _) = self
+            .waiting
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.priority.cmp(&b.priority).then(b.seq.cmp(&a.seq)))?;
+        Some(self.waiting.remove(index))
+    }
+}
+
+pub struct AgentExecutor {
+    max_concurrent: usize,
+    max_queued: usize,
+    priorities: HashMap<String, u8>,
+    slots: Mutex<Slots>,
+}
+
+impl AgentExecutor {
+    pub fn new(config: &ExecutorConfig) -> Self {
+        Self {
+            max_concurrent: config.max_concurrent.max(1),
+            max_queued: config.max_queued,
+            priorities: config
+                .priorities
+                .iter()
+                .map(|(channel, priority)| (channel.to_lowercase(), *priority))
+                .collect(),
+            slots: Mutex::new(Slots::default()),
+        }
+    }
+
+    /// Ask for a slot to run an agent turn for `channel`.
+    ///
+    /// The returned ticket either holds a slot already or is queued; await
+    /// [`Ticket::ready`] for the permit. Dropping the ticket leaves the queue.
+    pub fn submit(self: &Arc<Self>, channel: &str) -> Result<Ticket, QueueFull> {
+        let priority = self
+            .priorities
+            .get(&channel.to_lowercase())
+            .copied()
+            .unwrap_or(0);
+        let mut slots = self.lock();
+
+        if slots.running < self.max_concurrent && slots.waiting.is_empty() {
+            slots.running += 1;
+            return Ok(Ticket {
+                executor: Arc::clone(self),
+                slot: Slot::Held,
+                position: 0,
+            });
+        }
+        if slots.waiting.len() >= self.max_queued {
+            return Err(QueueFull {
+                queued: slots.waiting.len(),
+            });
+        }
+
+        let position = 1 + slots
+            .waiting
+            .iter()
+            .filter(|w| w.priority >= priority)
+            .count();
+        let seq = slots.next_seq;
+        slots.next_seq += 1;
+        let (tx, rx) = oneshot::channel();
+        slots.waiting.push(Waiter { priority, seq, tx });
+        Ok(Ticket {
+            executor: Arc::clone(self),
+            slot: Slot::Waiting(seq, rx),
+            position,
+        })
+    }
+
+    pub fn stats(&self) -> ExecutorStats {
+        let slots = self.lock();
+        ExecutorStats {
+            running: slots.running,
+            queued: slots.waiting.len(),
+            max_concurrent: self.max_concurrent,
+            max_queued: self.max_queued,
+        }
+    }
+
+    /// Hand a finished run's slot to the next waiter, or free it.
+    fn release(&self) {
+        let mut slots = self.lock();
+        while let Some(waiter) = slots.pop_next() {
+            if waiter.tx.send(()).is_ok() {
+                return;
+            }
+        }
+        slots.running = slots.running.saturating_sub(1);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Slots> {
+        self.slots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+enum Slot {
+    Held,
+    Waiting(u64, oneshot::Receiver<()>),
+    /// Moved into an `ExecutionPermit`.
+    Claimed,
+}
+
+/// A place in the executor: holding a slot or queued for one.
+pub struct Ticket {
+    executor: Arc<AgentExecutor>,
+    slot: Slot,
+    position: usize,
+}
+
+impl Ticket {
+    /// Place in the queue when submitted; 0 if a slot was free.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Wait for a slot. Holding the permit counts as a running agent turn.
+    pub async fn ready(mut self) -> ExecutionPermit {
+        if let Slot::Waiting(_, rx) = &mut self.slot {
+            // Waiters leave the queue only by being fired or by dropping
+            // their ticket, so the sender is never dropped unsent.
+            let _ = rx.await;
+        }
+        self.slot = Slot::Claimed;
+        ExecutionPermit {
+            executor: Arc::clone(&self.executor),
+        }
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let (seq, mut rx) = match std::mem::replace(&mut self.slot, Slot::Claimed) {
+            Slot::Held => return self.executor.release(),
+            Slot::Waiting(seq, rx) => (seq, rx),
+            Slot::Claimed => return,
+        };
+        let mut slots = self.executor.lock();
+        let before = slots.waiting.len();
+        slots.waiting.retain(|w| w.seq != seq);
+        if slots.waiting.len() < before {
+            return;
+        }
+        // Already handed a slot (sends happen under the lock) — pass it on.
+        drop(slots);
+        if rx.try_recv().is_ok() {
+            self.executor.release();
+        }
+    }
+}
+
+/// A running agent turn. The slot is released when dropped.
+pub struct ExecutionPermit {
+    executor: Arc<AgentExecutor>,
+}
+
+impl Drop for ExecutionPermit {
+    fn drop(&mut self) {
+        self.executor.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn executor(max_concurrent: usize, max_queued: usize) -> Arc<AgentExecutor> {
+        Arc::new(AgentExecutor::new(&ExecutorConfig {
+            max_concurrent,
+            max_queued,
+            ..ExecutorConfig::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn limits_concurrency_and_rejects_when_queue_full() {
+        let exec = executor(1, 1);
+        let first = exec.submit("webhook").unwrap();
+        assert_eq!(first.position(), 0);
+        let running = first.ready().await;
+
+        let queued = exec.submit("webhook").unwrap();
+        assert_eq!(queued.position(), 1);
+        assert_eq!(exec.submit("webhook").err(), Some(QueueFull { queued: 1 }));
+        assert_eq!(exec.stats().running, 1);
+        assert_eq!(exec.stats().queued, 1);
+
+        drop(running);
+        let _next = tokio::time::timeout(Duration::from_secs(1), queued.ready())
+            .await
+            .expect("queued run should get the freed slot");
+        assert_eq!(exec.stats().running, 1);
+        assert_eq!(exec.stats().queued, 0);
+    }
+
+    #[tokio::test]
+    async fn higher_priority_channels_run_first() {
+        let exec = executor(1, 8);
+        let running = exec.submit("webhook").unwrap().ready().await;
+
+        let webhook = exec.submit("webhook").unwrap();
+        let dashboard = exec.submit("dashboard").unwrap();
+        assert_eq!(webhook.position(), 1);
+        assert_eq!(dashboard.position(), 1);
+
+        drop(running);
+        let permit = tokio::time::timeout(Duration::from_millis(200), dashboard.ready())
+            .await
+            .expect("dashboard outranks webhook");
+        assert_eq!(exec.stats().queued, 1);
+        drop(permit);
+        let _last = webhook.ready().await;
+    }
+
+    #[tokio::test]
+    async fn dropping_tickets_frees_queue_and_slots() {
+        let exec = executor(1, 4);
+        let running = exec.submit("whatsapp").unwrap().ready().await;
+
+        let abandoned = exec.submit("whatsapp").unwrap();
+        drop(abandoned);
+        assert_eq!(exec.stats().queued, 0);
+
+        // Handed a slot but dropped before claiming it.
+        let unclaimed = exec.submit("whatsapp").unwrap();
+        drop(running);
+        drop(unclaimed);
+        assert_eq!(exec.stats().running, 0);
+
+        let again = exec.submit("whatsapp").unwrap();
+        assert_eq!(again.position(), 0);
+    }
+}
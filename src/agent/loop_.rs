@@ -2,23 +2,30 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::config::Config;
+use crate::agent::budget::{estimate_tokens, BudgetExceeded, BudgetLimit, BudgetUsage, LoopBudget};
+use crate::agent::cancel::{CancellationToken, Cancelled};
+use crate::agent::planner;
+use crate::agent::tool_router::{ToolRouter, SEARCH_TOOL};
+use crate::config::{AgentConfig, AgentMode, Config};
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
+use crate::providers::router::{routed_vision, ModelRouter, TaskClass};
 use crate::providers::{self, ChatMessage, Provider, ToolCall};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, Tool, ToolSpec};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use std::borrow::Cow;
 use std::fmt::Write;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
 use std::time::Instant;
 use uuid::Uuid;
 
-/// Maximum agentic tool-use iterations per user message to prevent runaway loops.
-const MAX_TOOL_ITERATIONS: usize = 10;
+/// Upper bound on tool-use iterations per user message, whatever
+/// `[agent] max_iterations` says.
+const MAX_TOOL_ITERATIONS: usize = 100;
 
 /// Trigger auto-compaction when non-system message count exceeds this threshold.
 const MAX_HISTORY_MESSAGES: usize = 50;
@@ -395,12 +402,37 @@ pub(crate) async fn agent_turn(
         provider_name,
         model,
         temperature,
+        &LoopBudget::default(),
+        None,
+        &CancellationToken::new(),
     )
     .await
 }
 
+/// Resolves at `deadline`, or never when there is none.
+async fn wait_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Completes when `cancel` fires or the emergency stop is engaged.
+async fn stopped(cancel: &CancellationToken) {
+    tokio::select! {
+        () = cancel.cancelled() => {}
+        () = crate::security::emergency::engaged() => {}
+    }
+}
+
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
+///
+/// When `cancel` fires or the emergency stop is engaged, the pending LLM
+/// request or tool execution is dropped and the loop returns a [`Cancelled`]
+/// error. Running out of `budget` (iterations, estimated tokens or wall
+/// time) returns [`BudgetExceeded`].
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_tool_call_loop(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
@@ -409,8 +441,89 @@ pub(crate) async fn run_tool_call_loop(
     provider_name: &str,
     model: &str,
     temperature: f64,
+    budget: &LoopBudget,
+    tool_router: Option<&ToolRouter>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    run_metered_tool_loop(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        provider_name,
+        model,
+        temperature,
+        budget,
+        &mut BudgetUsage::start(),
+        tool_router,
+        cancel,
+    )
+    .await
+}
+
+/// [`run_tool_call_loop`] charging its LLM calls to `usage`, which holds what
+/// earlier parts of the turn spent.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+async fn run_metered_tool_loop(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    budget: &LoopBudget,
+    usage: &mut BudgetUsage,
+    tool_router: Option<&ToolRouter>,
+    cancel: &CancellationToken,
 ) -> Result<String> {
-    for _iteration in 0..MAX_TOOL_ITERATIONS {
+    let max_iterations = max_iterations(budget);
+    let deadline = budget.deadline(usage);
+    // Offered natively to providers that take them; the others read the
+    // `<tool_call>` protocol from the system prompt.
+    let tool_specs: Vec<_> = tools_registry.iter().map(|tool| tool.spec()).collect();
+    // With a tool router only the tools and skills picked for this request
+    // are offered, and their part of the prompt goes into each request.
+    let native_tools = provider.supports_native_tools();
+    let mut selection = match tool_router {
+        Some(router) => {
+            let query = history
+                .iter()
+                .rev()
+                .find(|m| m.role == "user")
+                .map_or("", |m| m.content.as_str());
+            let selecting = router.select(query, tools_registry);
+            tokio::select! {
+                biased;
+                () = stopped(cancel) => return Err(Cancelled { tool: None }.into()),
+                () = wait_until(deadline) => return Err(budget.wall_time_exceeded(usage).into()),
+                selection = selecting => Some(selection),
+            }
+        }
+        None => None,
+    };
+
+    for message in history.iter() {
+        observer.record_event(&ObserverEvent::Message {
+            role: message.role.clone(),
+            content: message.content.clone(),
+        });
+    }
+
+    while usage.iterations < max_iterations {
+        let (messages, specs) = match &selection {
+            Some(selection) => (
+                Cow::Owned(selection.messages(history, tools_registry, native_tools)),
+                Cow::Owned(selection.specs(tools_registry)),
+            ),
+            None => (
+                Cow::Borrowed(history.as_slice()),
+                Cow::Borrowed(&tool_specs[..]),
+            ),
+        };
+        let prompt_tokens: u64 = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        budget.check_tokens(usage, prompt_tokens)?;
+
         observer.record_event(&ObserverEvent::LlmRequest {
             provider: provider_name.to_string(),
             model: model.to_string(),
@@ -418,10 +531,14 @@ pub(crate) async fn run_tool_call_loop(
         });
 
         let llm_started_at = Instant::now();
-        let response = match provider
-            .chat_with_history(history, model, temperature)
-            .await
-        {
+        let request = provider.chat_with_tools(&messages, &specs, model, temperature);
+        let response = tokio::select! {
+            biased;
+            () = stopped(cancel) => return Err(Cancelled { tool: None }.into()),
+            () = wait_until(deadline) => return Err(budget.wall_time_exceeded(usage).into()),
+            response = request => response,
+        };
+        let response = match response {
             Ok(resp) => {
                 observer.record_event(&ObserverEvent::LlmResponse {
                     provider: provider_name.to_string(),
@@ -444,6 +561,15 @@ pub(crate) async fn run_tool_call_loop(
             }
         };
 
+        usage.iterations += 1;
+        usage.tokens += prompt_tokens
+            + response.text.as_deref().map_or(0, estimate_tokens)
+            + response
+                .tool_calls
+                .iter()
+                .map(|call| estimate_tokens(&call.name) + estimate_tokens(&call.arguments))
+                .sum::<u64>();
+
         let response_text = response.text.unwrap_or_default();
         let mut assistant_history_content = response_text.clone();
         let mut parsed_text = response_text.clone();
@@ -467,10 +593,19 @@ pub(crate) async fn run_tool_call_loop(
             } else {
                 parsed_text
             };
+            observer.record_event(&ObserverEvent::Message {
+                role: "assistant".into(),
+                content: final_text.clone(),
+            });
             history.push(ChatMessage::assistant(&final_text));
             return Ok(final_text);
         }
 
+        observer.record_event(&ObserverEvent::Message {
+            role: "assistant".into(),
+            content: assistant_history_content.clone(),
+        });
+
         // Print any text the LLM produced alongside tool calls
         if !parsed_text.is_empty() {
             print!("{parsed_text}");
@@ -484,8 +619,54 @@ pub(crate) async fn run_tool_call_loop(
                 tool: call.name.clone(),
             });
             let start = Instant::now();
-            let result = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
+            let search = tool_router
+                .zip(selection.as_mut())
+                .filter(|_| call.name == SEARCH_TOOL);
+            let result = if let Some((router, selection)) = search {
+                let query = call
+                    .arguments
+                    .get("query")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default();
+                let found = router.search(query, tools_registry, selection).await;
+                observer.record_event(&ObserverEvent::ToolCall {
+                    tool: call.name.clone(),
+                    duration: start.elapsed(),
+                    success: true,
+                });
+                found
+            } else if let Some(tool) = find_tool(tools_registry, &call.name) {
+                // Called without being offered: offer it for the rest of the turn
+                if let Some(selection) = selection.as_mut() {
+                    selection.offer(&call.name, tools_registry);
+                }
+                let outcome = tokio::select! {
+                    biased;
+                    () = stopped(cancel) => {
+                        observer.record_event(&ObserverEvent::ToolCall {
+                            tool: call.name.clone(),
+                            duration: start.elapsed(),
+                            success: false,
+                        });
+                        return Err(Cancelled {
+                            tool: Some(call.name.clone()),
+                        }
+                        .into());
+                    }
+                    () = wait_until(deadline) => {
+                        observer.record_event(&ObserverEvent::ToolCall {
+                            tool: call.name.clone(),
+                            duration: start.elapsed(),
+                            success: false,
+                        });
+                        return Err(budget.wall_time_exceeded(usage).into());
+                    }
+                    outcome = observability::subagent::forward_to(
+                        observer,
+                        tool.execute(call.arguments.clone()),
+                    ) => outcome,
+                };
+                match outcome {
                     Ok(r) => {
                         observer.record_event(&ObserverEvent::ToolCall {
                             tool: call.name.clone(),
@@ -493,7 +674,7 @@ pub(crate) async fn run_tool_call_loop(
                             success: r.success,
                         });
                         if r.success {
-                            r.output
+                            r.model_content()
                         } else {
                             format!("Error: {}", r.error.unwrap_or_else(|| r.output))
                         }
@@ -510,6 +691,11 @@ pub(crate) async fn run_tool_call_loop(
             } else {
                 format!("Unknown tool: {}", call.name)
             };
+            observer.record_event(&ObserverEvent::ToolIo {
+                tool: call.name.clone(),
+                arguments: call.arguments.to_string(),
+                output: result.clone(),
+            });
 
             let _ = writeln!(
                 tool_results,
@@ -523,12 +709,306 @@ pub(crate) async fn run_tool_call_loop(
         history.push(ChatMessage::user(format!("[Tool results]\n{tool_results}")));
     }
 
-    anyhow::bail!("Agent exceeded maximum tool iterations ({MAX_TOOL_ITERATIONS})")
+    Err(iterations_exceeded(max_iterations).into())
+}
+
+/// LLM round-trips a turn may make under `budget`.
+fn max_iterations(budget: &LoopBudget) -> usize {
+    budget.max_iterations.min(MAX_TOOL_ITERATIONS)
+}
+
+fn iterations_exceeded(max_iterations: usize) -> BudgetExceeded {
+    BudgetExceeded {
+        limit: BudgetLimit::Iterations,
+        used: max_iterations as u64,
+        max: max_iterations as u64,
+        iterations: max_iterations,
+    }
+}
+
+/// Settings for plan-execute turns (`[agent] mode = "plan-execute"`).
+#[derive(Clone)]
+pub struct Planner {
+    pub security: Arc<SecurityPolicy>,
+    pub max_step_retries: usize,
+}
+
+impl Planner {
+    /// `None` unless `config.mode` is plan-execute.
+    pub fn from_config(config: &AgentConfig, security: Arc<SecurityPolicy>) -> Option<Self> {
+        (config.mode == AgentMode::PlanExecute).then(|| Self {
+            security,
+            max_step_retries: config.max_step_retries,
+        })
+    }
+}
+
+/// Run one agent turn: the plain tool-call loop, or plan-execute when a
+/// [`Planner`] is given. The turn is one run for
+/// [`RecorderObserver`](observability::RecorderObserver).
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_agent_turn(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    budget: &LoopBudget,
+    planner: Option<&Planner>,
+    tool_router: Option<&ToolRouter>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    observability::recorder::recorded(async {
+        let result = run_turn(
+            provider,
+            history,
+            tools_registry,
+            observer,
+            provider_name,
+            model,
+            temperature,
+            budget,
+            planner,
+            tool_router,
+            cancel,
+        )
+        .await;
+        if let Err(e) = &result {
+            observer.record_event(&ObserverEvent::Error {
+                component: "agent".into(),
+                message: crate::providers::sanitize_api_error(&e.to_string()),
+            });
+        }
+        result
+    })
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_turn(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    budget: &LoopBudget,
+    planner: Option<&Planner>,
+    tool_router: Option<&ToolRouter>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    match planner {
+        // Boxed: the plan-execute future nests several tool loops.
+        Some(planner) => {
+            Box::pin(run_plan_execute(
+                provider,
+                history,
+                tools_registry,
+                observer,
+                provider_name,
+                model,
+                temperature,
+                budget,
+                planner,
+                tool_router,
+                cancel,
+            ))
+            .await
+        }
+        None => {
+            run_tool_call_loop(
+                provider,
+                history,
+                tools_registry,
+                observer,
+                provider_name,
+                model,
+                temperature,
+                budget,
+                tool_router,
+                cancel,
+            )
+            .await
+        }
+    }
+}
+
+/// A single LLM call outside the tool loop (planning, review), charged to
+/// `usage` like a loop iteration.
+async fn chat_once(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    model: &str,
+    temperature: f64,
+    budget: &LoopBudget,
+    usage: &mut BudgetUsage,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    let max_iterations = max_iterations(budget);
+    if usage.iterations >= max_iterations {
+        return Err(iterations_exceeded(max_iterations).into());
+    }
+    let prompt_tokens: u64 = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+    budget.check_tokens(usage, prompt_tokens)?;
+
+    let text = tokio::select! {
+        biased;
+        () = stopped(cancel) => return Err(Cancelled { tool: None }.into()),
+        () = wait_until(budget.deadline(usage)) => {
+            return Err(budget.wall_time_exceeded(usage).into());
+        }
+        response = provider.chat_with_history(messages, model, temperature) => {
+            response?.text.unwrap_or_default()
+        }
+    };
+    usage.iterations += 1;
+    usage.tokens += prompt_tokens + estimate_tokens(&text);
+    Ok(text)
+}
+
+/// Plan, check the plan against the security policy, then run each step
+/// through the tool loop. A critic call reviews every step and a rejected
+/// step is retried up to `max_step_retries` times.
+///
+/// A reply without a usable plan runs as a single step that may use every
+/// tool, so it passes the check only when the policy allows all of them.
+/// `budget` covers the whole turn: planning, every step and every review.
+#[allow(clippy::too_many_arguments)]
+async fn run_plan_execute(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    budget: &LoopBudget,
+    planner: &Planner,
+    tool_router: Option<&ToolRouter>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    let mut usage = BudgetUsage::start();
+
+    let mut planning = history.clone();
+    planning.push(ChatMessage::user(planner::planning_prompt(tools_registry)));
+    let reply = chat_once(
+        provider,
+        &planning,
+        model,
+        temperature,
+        budget,
+        &mut usage,
+        cancel,
+    )
+    .await?;
+    let Some(plan) = planner::parse_plan(&reply) else {
+        tracing::debug!("No usable plan in reply; running the tool loop directly");
+        planner::check_plan(
+            &planner::Plan::unplanned(tools_registry),
+            tools_registry,
+            &planner.security,
+        )?;
+        return run_metered_tool_loop(
+            provider,
+            history,
+            tools_registry,
+            observer,
+            provider_name,
+            model,
+            temperature,
+            budget,
+            &mut usage,
+            tool_router,
+            cancel,
+        )
+        .await;
+    };
+    planner::check_plan(&plan, tools_registry, &planner.security)?;
+    history.push(ChatMessage::assistant(plan.summary()));
+
+    let total = plan.steps.len();
+    for (i, step) in plan.steps.iter().enumerate() {
+        let mut prompt = format!("Carry out step {} of {total}: {}", i + 1, step.description);
+        let mut retries = 0;
+        loop {
+            history.push(ChatMessage::user(&prompt));
+            let output = run_metered_tool_loop(
+                provider,
+                history,
+                tools_registry,
+                observer,
+                provider_name,
+                model,
+                temperature,
+                budget,
+                &mut usage,
+                tool_router,
+                cancel,
+            )
+            .await?;
+            if retries == planner.max_step_retries {
+                break;
+            }
+
+            let review = [
+                ChatMessage::system(planner::CRITIC_SYSTEM_PROMPT),
+                ChatMessage::user(planner::review_prompt(step, &output)),
+            ];
+            let verdict = chat_once(
+                provider,
+                &review,
+                model,
+                temperature,
+                budget,
+                &mut usage,
+                cancel,
+            )
+            .await?;
+            let review = planner::parse_review(&verdict);
+            if review.ok {
+                break;
+            }
+            retries += 1;
+            tracing::info!("Step {} rejected by review: {}", i + 1, review.feedback);
+            prompt = format!(
+                "A review found step {} incomplete: {} Try the step again.",
+                i + 1,
+                review.feedback
+            );
+        }
+    }
+
+    history.push(ChatMessage::user(
+        "All steps are done. Reply to the original request using their results.",
+    ));
+    run_metered_tool_loop(
+        provider,
+        history,
+        &[],
+        observer,
+        provider_name,
+        model,
+        temperature,
+        budget,
+        &mut usage,
+        tool_router,
+        cancel,
+    )
+    .await
 }
 
 /// Build the tool instruction block for the system prompt so the LLM knows
 /// how to invoke tools.
 pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
+    let specs: Vec<_> = tools_registry.iter().map(|tool| tool.spec()).collect();
+    build_spec_instructions(&specs)
+}
+
+/// [`build_tool_instructions`] for a set of tool specs.
+pub(crate) fn build_spec_instructions(specs: &[ToolSpec]) -> String {
     let mut instructions = String::new();
     instructions.push_str("\n## Tool Use Protocol\n\n");
     instructions.push_str("To use a tool, wrap a JSON object in <tool_call></tool_call> tags:\n\n");
@@ -539,14 +1019,16 @@ pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> Strin
         .push_str("Continue reasoning with the results until you can give a final answer.\n\n");
     instructions.push_str("### Available Tools\n\n");
 
-    for tool in tools_registry {
+    for spec in specs {
         let _ = writeln!(
             instructions,
-            "**{}**: {}\nParameters: `{}`\n",
-            tool.name(),
-            tool.description(),
-            tool.parameters_schema()
+            "**{}**: {}\nParameters: `{}`",
+            spec.name, spec.description, spec.parameters
         );
+        if let Some(schema) = &spec.output_schema {
+            let _ = writeln!(instructions, "Returns JSON: `{schema}`");
+        }
+        instructions.push('\n');
     }
 
     instructions
@@ -562,7 +1044,8 @@ pub async fn run(
     verbose: bool,
 ) -> Result<()> {
     // ── Wire up agnostic subsystems ──────────────────────────────
-    let base_observer = observability::create_observer(&config.observability);
+    let base_observer =
+        observability::create_observer(&config.observability, &config.workspace_dir);
     let observer: Arc<dyn Observer> = if verbose {
         Arc::from(Box::new(observability::MultiObserver::new(vec![
             base_observer,
@@ -592,6 +1075,7 @@ pub async fn run(
             actor_name = None;
         }
     }
+    security.leak_guard = Some(Arc::new(crate::security::leak_guard::LeakGuard::from_config(&config)?));
     let security = Arc::new(security);
 
     // ── Memory (the brain) ────────────────────────────────────────
@@ -599,8 +1083,10 @@ pub async fn run(
         config.security.audit.clone(),
         config.workspace_dir.clone(),
     )?);
+    crate::network::egress::install_from_config(&config.security.egress, Some(audit.clone()));
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
+        &config.security.sensitivity,
         &config.workspace_dir,
         config.api_key.as_deref(),
         Arc::clone(&audit),
@@ -612,7 +1098,7 @@ pub async fn run(
         &config.mcp,
         &security,
         &audit,
-    ).await;
+    ).await.tools;
 
     // ── Tools (including memory tools) ────────────────────────────
     let composio_key = if config.composio.enabled {
@@ -627,12 +1113,18 @@ pub async fn run(
         composio_key,
         &config.browser,
         &config.http_request,
+        &routed_vision(&config),
+        &config.db_query,
+        &config.feeds,
+        &config.plugins,
+        &config.reliability,
         &config.workspace_dir,
         &config.agents,
         config.api_key.as_deref(),
         mcp_tools,
         Some(Arc::clone(&audit)),
         actor_name,
+        None,
     );
 
     // ── Resolve provider ─────────────────────────────────────────
@@ -645,14 +1137,23 @@ pub async fn run(
         .as_deref()
         .or(config.default_model.as_deref())
         .unwrap_or("anthropic/claude-sonnet-4");
+    let budget = LoopBudget::from_config(&config.agent);
+    let planner = Planner::from_config(&config.agent, Arc::clone(&security));
 
-    let provider: Box<dyn Provider> = providers::create_routed_provider(
+    let models = Arc::new(ModelRouter::new(model_name, &config.model_routes));
+    let provider: Arc<dyn Provider> = Arc::from(providers::create_routed_provider(
         provider_name,
         config.api_key.as_deref(),
         &config.reliability,
-        &config.model_routes,
-        model_name,
-    )?;
+        &models,
+    )?);
+    let provider = providers::guarded::GuardedProvider::wrap(
+        provider,
+        providers::guarded::all_local(&config, provider_name),
+        security.leak_guard.clone(),
+        None,
+        Some(Arc::clone(&audit)),
+    );
 
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
@@ -661,6 +1162,7 @@ pub async fn run(
 
     // ── Build system prompt from workspace MD files (OpenClaw framework) ──
     let skills = crate::skills::load_skills(&config.workspace_dir);
+    let tool_router = ToolRouter::from_config(&config, skills.clone());
     let mut tool_descs: Vec<(&str, &str)> = vec![
         (
             "shell",
@@ -672,7 +1174,11 @@ pub async fn run(
         ),
         (
             "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
+            "Write file contents. Use when: creating new files, scaffolding, or replacing a file entirely. Don't use when: changing part of an existing file (use file_patch), side effects are unclear or file ownership is uncertain.",
+        ),
+        (
+            "file_patch",
+            "Edit part of a file with a unified diff or search/replace blocks. Use when: applying focused edits to existing code/docs; preview with dry_run first. Don't use when: creating a new file.",
         ),
         (
             "memory_store",
@@ -686,6 +1192,18 @@ pub async fn run(
             "memory_forget",
             "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
         ),
+        (
+            "archive",
+            "Create, list or extract zip/tar.gz archives in the workspace. Use when: packing backups, unpacking downloaded datasets. Don't use when: the archive comes from an untrusted source and you have not listed it first.",
+        ),
+        (
+            "shell_job_start",
+            "Run a long shell command in the background and get a job id. Use when: builds, test suites or downloads that may exceed the shell timeout. Don't use when: the command finishes quickly; use shell.",
+        ),
+        (
+            "shell_job_logs",
+            "Read new output of a background job (pass next_offset as since). Use when: checking on a job from shell_job_start; see also shell_job_status and shell_job_kill.",
+        ),
     ];
     tool_descs.push((
         "screenshot",
@@ -695,6 +1213,28 @@ pub async fn run(
         "image_info",
         "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
     ));
+    if config.db_query.enabled {
+        tool_descs.push((
+            "db_query",
+            "Run SQL against the user's configured SQLite/Postgres databases (read-only by default) with bound params. Use when: answering questions about data in local databases. Add LIMIT for large tables.",
+        ));
+    }
+    if config.feeds.enabled {
+        tool_descs.push((
+            "feeds",
+            "Manage RSS/Atom subscriptions and read new items (subscribe, unsubscribe, list, refresh, digest). Items are saved to memory. Use when: the user wants to follow a news source, blog or release feed, or asks what's new.",
+        ));
+    }
+    if config.vision.enabled {
+        tool_descs.push((
+            "image_describe",
+            "Caption an image and list its objects and text with a multimodal model. Uploads the image after a local sensitivity check. Use when: understanding photos, diagrams, UI screenshots. Don't use when: only the text is needed; use image_ocr.",
+        ));
+    }
+    tool_descs.push((
+        "image_ocr",
+        "Extract text from an image with local OCR (Tesseract), e.g. language 'deu+eng'. Use when: reading error dialogs in screenshots, scanned letters, photos of documents.",
+    ));
     if config.browser.enabled {
         tool_descs.push((
             "browser_open",
@@ -715,16 +1255,21 @@ pub async fn run(
              prompt and returns its response.",
         ));
     }
+    let (tool_descs, listed_skills) =
+        ToolRouter::prompt_inputs(tool_router.as_ref(), tool_descs, &skills);
     let mut system_prompt = crate::channels::build_system_prompt(
         &config.workspace_dir,
         model_name,
         &tool_descs,
-        &skills,
+        listed_skills,
         Some(&config.identity),
     );
 
-    // Append structured tool-use instructions with schemas
-    system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    // Append structured tool-use instructions with schemas, unless the
+    // provider takes the tools natively
+    if !provider.supports_native_tools() && tool_router.is_none() {
+        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    }
 
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
@@ -751,7 +1296,7 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let response = run_tool_call_loop(
+        let response = run_agent_turn(
             provider.as_ref(),
             &mut history,
             &tools_registry,
@@ -759,6 +1304,10 @@ pub async fn run(
             provider_name,
             model_name,
             temperature,
+            &budget,
+            planner.as_ref(),
+            tool_router.as_ref(),
+            &CancellationToken::new(),
         )
         .await?;
         println!("{response}");
@@ -786,6 +1335,8 @@ pub async fn run(
 
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
+        // Compaction may use a cheaper `summarize` route
+        let summary_model = models.model_for(TaskClass::Summarize);
 
         while let Some(msg) = rx.recv().await {
             // Auto-save conversation turns
@@ -806,7 +1357,7 @@ pub async fn run(
 
             history.push(ChatMessage::user(&enriched));
 
-            let response = match run_tool_call_loop(
+            let response = match run_agent_turn(
                 provider.as_ref(),
                 &mut history,
                 &tools_registry,
@@ -814,6 +1365,10 @@ pub async fn run(
                 provider_name,
                 model_name,
                 temperature,
+                &budget,
+                planner.as_ref(),
+                tool_router.as_ref(),
+                &CancellationToken::new(),
             )
             .await
             {
@@ -828,7 +1383,7 @@ pub async fn run(
 
             // Auto-compaction before hard trimming to preserve long-context signal.
             if let Ok(compacted) =
-                auto_compact_history(&mut history, provider.as_ref(), model_name).await
+                auto_compact_history(&mut history, provider.as_ref(), &summary_model).await
             {
                 if compacted {
                     println!("🧹 Auto-compaction complete");
@@ -1127,6 +1682,348 @@ I will now call the tool with this payload:
         assert!(recalled.iter().any(|entry| entry.content.contains("45")));
     }
 
+    // ── Cancellation ────────────────────────────────────────────
+
+    struct ToolCallingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<providers::ChatResponse> {
+            Ok(providers::ChatResponse::with_text(
+                r#"<tool_call>{"name": "slow", "arguments": {}}</tool_call>"#,
+            ))
+        }
+    }
+
+    struct SlowTool;
+
+    #[async_trait::async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "Never finishes"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<tools::ToolResult> {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+            unreachable!("cancelled before completion")
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_aborts_pending_tool_execution() {
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(SlowTool)];
+        let mut history = vec![ChatMessage::user("go")];
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let err = run_tool_call_loop(
+            &ToolCallingProvider,
+            &mut history,
+            &tools_registry,
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            None,
+            &cancel,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Cancelled>(),
+            Some(&Cancelled {
+                tool: Some("slow".into())
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_token_stops_before_llm_request() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = run_tool_call_loop(
+            &ToolCallingProvider,
+            &mut vec![ChatMessage::user("go")],
+            &[],
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            None,
+            &cancel,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.downcast_ref::<Cancelled>().is_some_and(|c| c.tool.is_none()));
+    }
+
+    async fn run_with_budget(tools_registry: &[Box<dyn Tool>], budget: LoopBudget) -> BudgetExceeded {
+        let err = run_tool_call_loop(
+            &ToolCallingProvider,
+            &mut vec![ChatMessage::user("go")],
+            tools_registry,
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &budget,
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+        err.downcast_ref::<BudgetExceeded>()
+            .cloned()
+            .expect("budget error")
+    }
+
+    #[tokio::test]
+    async fn looping_model_stops_at_iteration_budget() {
+        let budget = LoopBudget {
+            max_iterations: 3,
+            max_tokens: None,
+            max_wall_time: None,
+        };
+        let exceeded = run_with_budget(&[], budget).await;
+        assert_eq!(exceeded.limit, BudgetLimit::Iterations);
+        assert_eq!(exceeded.iterations, 3);
+    }
+
+    #[tokio::test]
+    async fn looping_model_stops_at_token_budget() {
+        let budget = LoopBudget {
+            max_iterations: 50,
+            max_tokens: Some(60),
+            max_wall_time: None,
+        };
+        let exceeded = run_with_budget(&[], budget).await;
+        assert_eq!(exceeded.limit, BudgetLimit::Tokens);
+        assert!(exceeded.used > 60);
+        assert!(exceeded.iterations > 0 && exceeded.iterations < 50);
+    }
+
+    #[tokio::test]
+    async fn slow_tool_stops_at_wall_time_budget() {
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(SlowTool)];
+        let budget = LoopBudget {
+            max_wall_time: Some(std::time::Duration::from_millis(50)),
+            ..LoopBudget::default()
+        };
+        let exceeded = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            run_with_budget(&tools_registry, budget),
+        )
+        .await
+        .expect("deadline should abort the tool");
+        assert_eq!(exceeded.limit, BudgetLimit::WallTime);
+        assert_eq!(exceeded.iterations, 1);
+    }
+
+    /// Replies with canned texts in order and records every prompt it saw.
+    struct ScriptedProvider {
+        replies: std::sync::Mutex<std::collections::VecDeque<&'static str>>,
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&'static str]) -> Self {
+            Self {
+                replies: std::sync::Mutex::new(replies.iter().copied().collect()),
+                prompts: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<providers::ChatResponse> {
+            self.prompts.lock().unwrap().push(message.to_string());
+            let reply = self.replies.lock().unwrap().pop_front().unwrap_or("done");
+            Ok(providers::ChatResponse::with_text(reply))
+        }
+    }
+
+    fn planner(security: SecurityPolicy) -> Planner {
+        Planner {
+            security: Arc::new(security),
+            max_step_retries: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_with_forbidden_tool_fails_before_running_it() {
+        let provider = ScriptedProvider::new(&[
+            r#"{"steps": [{"description": "wait", "tools": ["slow"]}]}"#,
+        ]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(SlowTool)];
+        let security = SecurityPolicy {
+            disabled_skills: vec!["slow".into()],
+            ..SecurityPolicy::default()
+        };
+
+        let err = run_agent_turn(
+            &provider,
+            &mut vec![ChatMessage::user("go")],
+            &tools_registry,
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            Some(&planner(security)),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+        let rejected = err.downcast_ref::<planner::PlanRejected>().unwrap();
+        assert_eq!(rejected.step, 1);
+        assert_eq!(provider.prompts.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn critic_rejection_retries_step() {
+        let provider = ScriptedProvider::new(&[
+            r#"{"steps": [{"description": "count the apples", "tools": []}]}"#,
+            "3 apples",
+            r#"{"ok": false, "feedback": "Count the pears too."}"#,
+            "3 apples, 2 pears",
+            "You have 3 apples and 2 pears.",
+        ]);
+        let mut history = vec![ChatMessage::user("what fruit do I have?")];
+
+        let reply = run_agent_turn(
+            &provider,
+            &mut history,
+            &[],
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            Some(&planner(SecurityPolicy::default())),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply, "You have 3 apples and 2 pears.");
+        assert!(history
+            .iter()
+            .any(|m| m.content.contains("incomplete: Count the pears too.")));
+        // plan, step, review, retried step (last retry is not reviewed), final
+        assert_eq!(provider.prompts.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn reply_without_plan_runs_tool_loop_directly() {
+        let provider = ScriptedProvider::new(&["I can answer right away.", "42"]);
+        let reply = run_agent_turn(
+            &provider,
+            &mut vec![ChatMessage::user("meaning of life?")],
+            &[],
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            Some(&planner(SecurityPolicy::default())),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply, "42");
+    }
+
+    #[tokio::test]
+    async fn reply_without_plan_is_checked_against_policy() {
+        let provider = ScriptedProvider::new(&["I will just wait."]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(SlowTool)];
+        let security = SecurityPolicy {
+            disabled_skills: vec!["slow".into()],
+            ..SecurityPolicy::default()
+        };
+
+        let err = run_agent_turn(
+            &provider,
+            &mut vec![ChatMessage::user("go")],
+            &tools_registry,
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &LoopBudget::default(),
+            Some(&planner(security)),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.downcast_ref::<planner::PlanRejected>().is_some());
+        assert_eq!(provider.prompts.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn plan_execute_shares_one_budget_across_steps() {
+        let provider = ScriptedProvider::new(&[
+            r#"{"steps": [{"description": "one", "tools": []}, {"description": "two", "tools": []}]}"#,
+            "did one",
+            r#"{"ok": true}"#,
+        ]);
+        let budget = LoopBudget {
+            max_iterations: 3,
+            max_tokens: None,
+            max_wall_time: None,
+        };
+
+        let err = run_agent_turn(
+            &provider,
+            &mut vec![ChatMessage::user("go")],
+            &[],
+            &observability::NoopObserver,
+            "test",
+            "model",
+            0.0,
+            &budget,
+            Some(&planner(SecurityPolicy::default())),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap_err();
+        let exceeded = err.downcast_ref::<BudgetExceeded>().unwrap();
+        assert_eq!(exceeded.limit, BudgetLimit::Iterations);
+        // plan, step one, its review; step two is over the budget
+        assert_eq!(provider.prompts.lock().unwrap().len(), 3);
+    }
+
     // ═══════════════════════════════════════════════════════════════════════
     // Recovery Tests - Tool Call Parsing Edge Cases
     // ═══════════════════════════════════════════════════════════════════════
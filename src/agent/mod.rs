@@ -2,7 +2,12 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+pub mod budget;
+pub mod cancel;
+pub mod executor;
 pub mod loop_;
+pub mod planner;
+pub mod tool_router;
 
 pub use loop_::run;
 
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Plans and reviews for `[agent] mode = "plan-execute"`.
+//!
+//! The model first answers with a JSON plan of steps and the tools each step
+//! needs. [`check_plan`] rejects plans naming unknown tools or tools the
+//! security policy would block, before any tool runs. After each step a critic
+//! call answers with a [`Review`]; the driver in `loop_` retries rejected steps.
+
+use crate::security::SecurityPolicy;
+use crate::tools::security::check_tool_access;
+use crate::tools::Tool;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Longest plan accepted.
+pub const MAX_PLAN_STEPS: usize = 8;
+
+/// System prompt for the critic call.
+pub const CRITIC_SYSTEM_PROMPT: &str = "You review the work of an AI assistant. \
+Answer only with JSON: {\"ok\": true} when the step was completed, or \
+{\"ok\": false, \"feedback\": \"what is wrong and how to fix it\"} when it was not.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub description: String,
+    /// Tools the step expects to call.
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    /// Stand-in for a reply without a usable plan: one step that may use
+    /// every tool offered.
+    pub fn unplanned(tools: &[Box<dyn Tool>]) -> Self {
+        Self {
+            steps: vec![PlanStep {
+                description: "answer without a plan".into(),
+                tools: tools.iter().map(|t| t.name().to_string()).collect(),
+            }],
+        }
+    }
+
+    /// Numbered steps, for the conversation history.
+    pub fn summary(&self) -> String {
+        let mut out = String::from("Plan:\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            let _ = write!(out, "{}. {}", i + 1, step.description);
+            if !step.tools.is_empty() {
+                let _ = write!(out, " [{}]", step.tools.join(", "));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Error returned when a plan fails the policy check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanRejected {
+    /// 1-based step number.
+    pub step: usize,
+    pub reason: String,
+}
+
+impl std::fmt::Display for PlanRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Plan rejected at step {}: {}", self.step, self.reason)
+    }
+}
+
+impl std::error::Error for PlanRejected {}
+
+/// Critic verdict on one step.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Review {
+    pub ok: bool,
+    #[serde(default)]
+    pub feedback: String,
+}
+
+/// Instruction appended to the conversation to get a plan.
+pub fn planning_prompt(tools: &[Box<dyn Tool>]) -> String {
+    let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+    format!(
+        "Before doing anything, write a plan for the request above. Answer only with JSON: \
+         {{\"steps\": [{{\"description\": \"...\", \"tools\": [\"tool_name\"]}}]}}. \
+         Use at most {MAX_PLAN_STEPS} steps and only these tools: {}. \
+         A step that needs no tool has an empty list.",
+        if names.is_empty() {
+            "(none)".to_string()
+        } else {
+            names.join(", ")
+        }
+    )
+}
+
+/// Prompt for reviewing the result of `step`.
+pub fn review_prompt(step: &PlanStep, output: &str) -> String {
+    format!(
+        "Step: {}\n\nResult:\n{output}\n\nWas the step completed?",
+        step.description
+    )
+}
+
+/// Parse a JSON value of type `T` from a model reply, tolerating prose or a
+/// code fence around it.
+fn parse_json_object<T: serde::de::DeserializeOwned>(text: &str) -> Option<T> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    serde_json::from_str(text.get(start..=end)?).ok()
+}
+
+/// The plan in a model reply; `None` when there is no usable plan.
+pub fn parse_plan(text: &str) -> Option<Plan> {
+    parse_json_object::<Plan>(text).filter(|plan| !plan.steps.is_empty())
+}
+
+/// The critic verdict. An unreadable review counts as a rejection; retries
+/// are bounded by `max_step_retries` and the turn's budget.
+pub fn parse_review(text: &str) -> Review {
+    parse_json_object(text).unwrap_or_else(|| Review {
+        ok: false,
+        feedback: "The review could not be read.".into(),
+    })
+}
+
+/// Reject plans that are too long, name unknown tools, or need tools the
+/// security policy blocks.
+pub fn check_plan(
+    plan: &Plan,
+    tools: &[Box<dyn Tool>],
+    security: &SecurityPolicy,
+) -> Result<(), PlanRejected> {
+    if plan.steps.len() > MAX_PLAN_STEPS {
+        return Err(PlanRejected {
+            step: MAX_PLAN_STEPS + 1,
+            reason: format!("plan has {} steps (max {MAX_PLAN_STEPS})", plan.steps.len()),
+        });
+    }
+    for (i, step) in plan.steps.iter().enumerate() {
+        for tool in &step.tools {
+            if !tools.iter().any(|t| t.name() == tool) {
+                return Err(PlanRejected {
+                    step: i + 1,
+                    reason: format!("unknown tool '{tool}'"),
+                });
+            }
+            if let Err(reason) = check_tool_access(security, tool) {
+                return Err(PlanRejected { step: i + 1, reason });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+
+    struct NamedTool(&'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            unreachable!("plans are only checked")
+        }
+    }
+
+    fn plan(tools: &[&str]) -> Plan {
+        Plan {
+            steps: vec![
+                PlanStep {
+                    description: "look around".into(),
+                    tools: vec![],
+                },
+                PlanStep {
+                    description: "act".into(),
+                    tools: tools.iter().map(|t| (*t).to_string()).collect(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parse_plan_accepts_fenced_json_and_rejects_empty() {
+        let reply = "Here is my plan:\n```json\n{\"steps\": [{\"description\": \"read file\", \"tools\": [\"file_read\"]}]}\n```";
+        let plan = parse_plan(reply).unwrap();
+        assert_eq!(plan.steps[0].tools, vec!["file_read"]);
+        assert!(parse_plan("{\"steps\": []}").is_none());
+        assert!(parse_plan("I will just answer.").is_none());
+    }
+
+    #[test]
+    fn parse_review_rejects_unreadable_reply() {
+        assert!(!parse_review("{\"ok\": false, \"feedback\": \"missing total\"}").ok);
+        assert!(parse_review("Sure! {\"ok\": true}").ok);
+        assert!(!parse_review("looks fine to me").ok);
+    }
+
+    #[test]
+    fn check_plan_rejects_disabled_and_unknown_tools() {
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(NamedTool("shell")), Box::new(NamedTool("file_read"))];
+        let security = SecurityPolicy {
+            enabled_skills: vec![],
+            disabled_skills: vec!["shell".into()],
+            ..SecurityPolicy::default()
+        };
+
+        assert!(check_plan(&plan(&["file_read"]), &tools, &security).is_ok());
+        let err = check_plan(&plan(&["shell"]), &tools, &security).unwrap_err();
+        assert_eq!(err.step, 2);
+        assert!(err.reason.contains("disabled by security policy"));
+        let err = check_plan(&plan(&["rm_rf"]), &tools, &security).unwrap_err();
+        assert!(err.reason.contains("unknown tool"));
+    }
+}
@@ -0,0 +1,572 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Per-request tool and skill selection (`[agent.tool_selection]`).
+//!
+//! With dozens of tools, MCP servers and skills the full list no longer fits
+//! comfortably in every prompt. [`ToolRouter`] ranks tool and skill
+//! descriptions against the request, by embedding similarity or by keyword
+//! overlap when no embedding provider is configured, and the tool loop offers
+//! only the best matches plus [`SEARCH_TOOL`]. Calling it offers further
+//! tools for the rest of the turn, so the whole registry stays reachable.
+
+use crate::config::{Config, ToolSelectionConfig};
+use crate::memory::embeddings::{self, EmbeddingProvider};
+use crate::memory::vector::cosine_similarity;
+use crate::providers::ChatMessage;
+use crate::skills::Skill;
+use crate::tools::{Tool, ToolSpec};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Name of the lookup tool offered next to the selected tools.
+pub const SEARCH_TOOL: &str = "tool_search";
+
+/// Tools a single `tool_search` call offers.
+const SEARCH_RESULTS: usize = 5;
+
+pub struct ToolRouter {
+    embedder: Arc<dyn EmbeddingProvider>,
+    max_tools: usize,
+    max_skills: usize,
+    pinned: Vec<String>,
+    skills: Vec<Skill>,
+    workspace_dir: PathBuf,
+    /// Embeddings of tool and skill descriptions, by text. Descriptions
+    /// change only when tools are added, so each is embedded once.
+    vectors: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl ToolRouter {
+    pub fn new(
+        embedder: Arc<dyn EmbeddingProvider>,
+        config: &ToolSelectionConfig,
+        skills: Vec<Skill>,
+        workspace_dir: &Path,
+    ) -> Self {
+        Self {
+            embedder,
+            max_tools: config.max_tools.max(1),
+            max_skills: config.max_skills,
+            pinned: config.pinned_tools.clone(),
+            skills,
+            workspace_dir: workspace_dir.to_path_buf(),
+            vectors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tool descriptions and skills to list in the system prompt: none when
+    /// `router` offers them per request instead.
+    pub fn prompt_inputs<'a, T>(
+        router: Option<&Self>,
+        tool_descs: Vec<T>,
+        skills: &'a [Skill],
+    ) -> (Vec<T>, &'a [Skill]) {
+        if router.is_some() {
+            (Vec::new(), &[])
+        } else {
+            (tool_descs, skills)
+        }
+    }
+
+    /// `None` unless `[agent.tool_selection]` is enabled. Uses the
+    /// `[memory]` embedding provider.
+    pub fn from_config(config: &Config, skills: Vec<Skill>) -> Option<Self> {
+        if !config.agent.tool_selection.enabled {
+            return None;
+        }
+        let embedder = embeddings::create_embedding_provider(
+            &config.memory.embedding_provider,
+            config.api_key.as_deref(),
+            &config.memory.embedding_model,
+            config.memory.embedding_dimensions,
+        );
+        Some(Self::new(
+            Arc::from(embedder),
+            &config.agent.tool_selection,
+            skills,
+            &config.workspace_dir,
+        ))
+    }
+
+    /// The tools and skills to offer for `query`, usually the user's message.
+    pub async fn select(&self, query: &str, tools: &[Box<dyn Tool>]) -> Selection {
+        let pinned: BTreeSet<usize> = tools
+            .iter()
+            .enumerate()
+            .filter(|(_, tool)| self.pinned.iter().any(|name| name == tool.name()))
+            .map(|(index, _)| index)
+            .collect();
+        let candidates: Vec<usize> = (0..tools.len())
+            .filter(|index| !pinned.contains(index))
+            .collect();
+        let rank_tools = candidates.len() > self.max_tools;
+        let rank_skills = self.skills.len() > self.max_skills;
+
+        let mut documents: Vec<String> = Vec::new();
+        if rank_tools {
+            documents.extend(
+                candidates
+                    .iter()
+                    .map(|&index| describe_tool(&*tools[index])),
+            );
+        }
+        if rank_skills {
+            documents.extend(self.skills.iter().map(describe_skill));
+        }
+        let scores = if documents.is_empty() {
+            Vec::new()
+        } else {
+            self.scores(query, &documents).await
+        };
+        let (tool_scores, skill_scores) =
+            scores.split_at(if rank_tools { candidates.len() } else { 0 });
+
+        let mut offered = pinned;
+        if rank_tools {
+            let best = best_first(tool_scores).into_iter().take(self.max_tools);
+            offered.extend(best.map(|position| candidates[position]));
+        } else {
+            offered.extend(candidates);
+        }
+
+        let skills: Vec<Skill> = if rank_skills {
+            best_first(skill_scores)
+                .into_iter()
+                .take(self.max_skills)
+                .map(|index| self.skills[index].clone())
+                .collect()
+        } else {
+            self.skills.clone()
+        };
+        tracing::debug!(
+            tools = offered.len(),
+            of = tools.len(),
+            skills = skills.len(),
+            "Selected tools for the request"
+        );
+        Selection {
+            offered,
+            skills_prompt: crate::channels::build_skills_prompt(&self.workspace_dir, &skills),
+        }
+    }
+
+    /// Answer a [`SEARCH_TOOL`] call: offer the tools not offered yet that
+    /// best match `query` (those it names first) and describe them.
+    pub async fn search(
+        &self,
+        query: &str,
+        tools: &[Box<dyn Tool>],
+        selection: &mut Selection,
+    ) -> String {
+        let hidden: Vec<usize> = (0..tools.len())
+            .filter(|index| !selection.offered.contains(index))
+            .collect();
+        if hidden.is_empty() {
+            return "All tools are already available.".to_string();
+        }
+
+        let named: Vec<usize> = hidden
+            .iter()
+            .copied()
+            .filter(|&index| {
+                query
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                    .any(|word| word == tools[index].name())
+            })
+            .collect();
+        let found: Vec<usize> = if named.is_empty() {
+            let documents: Vec<String> = hidden
+                .iter()
+                .map(|&index| describe_tool(&*tools[index]))
+                .collect();
+            let scores = self.scores(query, &documents).await;
+            best_first(&scores)
+                .into_iter()
+                .filter(|&position| scores[position] > 0.0)
+                .take(SEARCH_RESULTS)
+                .map(|position| hidden[position])
+                .collect()
+        } else {
+            named
+        };
+        if found.is_empty() {
+            let names: Vec<&str> = hidden.iter().map(|&index| tools[index].name()).collect();
+            return format!(
+                "No tool matches \"{query}\". Not offered yet: {}. Search again naming one.",
+                names.join(", ")
+            );
+        }
+
+        let mut answer = String::from("These tools are now available:\n");
+        for &index in &found {
+            selection.offered.insert(index);
+            let tool = &tools[index];
+            let _ = writeln!(answer, "- **{}**: {}", tool.name(), tool.description());
+        }
+        let others = hidden.len() - found.len();
+        if others > 0 {
+            let _ = write!(
+                answer,
+                "{others} more tools exist; search again with other words to find them."
+            );
+        }
+        answer
+    }
+
+    /// Similarity of `query` to each document, 0.0–1.0.
+    async fn scores(&self, query: &str, documents: &[String]) -> Vec<f32> {
+        if self.embedder.dimensions() > 0 {
+            match self.embedding_scores(query, documents).await {
+                Ok(scores) => return scores,
+                Err(e) => tracing::warn!("Tool selection falls back to keywords: {e}"),
+            }
+        }
+        keyword_scores(query, documents)
+    }
+
+    async fn embedding_scores(
+        &self,
+        query: &str,
+        documents: &[String],
+    ) -> anyhow::Result<Vec<f32>> {
+        let mut texts: Vec<&str> = {
+            let vectors = self.vectors.lock().unwrap_or_else(PoisonError::into_inner);
+            documents
+                .iter()
+                .filter(|document| !vectors.contains_key(*document))
+                .map(String::as_str)
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        texts.push(query);
+        let mut embedded = self.embedder.embed(&texts).await?;
+        anyhow::ensure!(
+            embedded.len() == texts.len(),
+            "expected {} embeddings, got {}",
+            texts.len(),
+            embedded.len()
+        );
+        let query_vector = embedded.pop().unwrap_or_default();
+
+        let mut vectors = self.vectors.lock().unwrap_or_else(PoisonError::into_inner);
+        for (text, vector) in texts.into_iter().zip(embedded) {
+            vectors.insert(text.to_string(), vector);
+        }
+        Ok(documents
+            .iter()
+            .map(|document| {
+                vectors
+                    .get(document)
+                    .map_or(0.0, |vector| cosine_similarity(&query_vector, vector))
+            })
+            .collect())
+    }
+}
+
+/// What one request is offered; grows when the model calls [`SEARCH_TOOL`]
+/// or a tool it was not offered.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Registry indices of the offered tools
+    offered: BTreeSet<usize>,
+    /// `## Available Skills` block for the selected skills
+    skills_prompt: String,
+}
+
+impl Selection {
+    /// Offer the tool called `name` from now on.
+    pub fn offer(&mut self, name: &str, tools: &[Box<dyn Tool>]) {
+        if let Some(index) = tools.iter().position(|tool| tool.name() == name) {
+            self.offered.insert(index);
+        }
+    }
+
+    /// Specs of the offered tools, plus [`SEARCH_TOOL`] while some are not.
+    pub fn specs(&self, tools: &[Box<dyn Tool>]) -> Vec<ToolSpec> {
+        let mut specs: Vec<ToolSpec> = self
+            .offered
+            .iter()
+            .filter_map(|&index| tools.get(index))
+            .map(|tool| tool.spec())
+            .collect();
+        if self.offered.len() < tools.len() {
+            specs.push(search_spec());
+        }
+        specs
+    }
+
+    /// `history` with the selected skills, and for providers without native
+    /// tools the offered tools' instructions, added to the system message.
+    pub fn messages(
+        &self,
+        history: &[ChatMessage],
+        tools: &[Box<dyn Tool>],
+        native_tools: bool,
+    ) -> Vec<ChatMessage> {
+        let mut prompt = self.skills_prompt.clone();
+        if !native_tools && !tools.is_empty() {
+            prompt.push_str(&super::loop_::build_spec_instructions(&self.specs(tools)));
+        }
+        let mut messages = history.to_vec();
+        if prompt.is_empty() {
+            return messages;
+        }
+        match messages
+            .first_mut()
+            .filter(|message| message.role == "system")
+        {
+            Some(system) => {
+                system.content.push_str("\n\n");
+                system.content.push_str(&prompt);
+            }
+            None => messages.insert(0, ChatMessage::system(prompt)),
+        }
+        messages
+    }
+}
+
+fn search_spec() -> ToolSpec {
+    ToolSpec {
+        name: SEARCH_TOOL.to_string(),
+        description: "Find more tools. Only the tools most relevant to this request are \
+                      offered; describe what you need (or name a tool) and the best matches \
+                      become available."
+            .to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What the tool should do, e.g. \"send an email\""
+                }
+            },
+            "required": ["query"]
+        }),
+        output_schema: None,
+    }
+}
+
+fn describe_tool(tool: &dyn Tool) -> String {
+    format!("{}: {}", tool.name(), tool.description())
+}
+
+fn describe_skill(skill: &Skill) -> String {
+    format!(
+        "{}: {} {}",
+        skill.name,
+        skill.description,
+        skill.tags.join(" ")
+    )
+}
+
+/// Indices of `scores`, highest first; ties keep their order.
+fn best_first(scores: &[f32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    order
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of query words in each document, damped for long documents.
+#[allow(clippy::cast_precision_loss)]
+fn keyword_scores(query: &str, documents: &[String]) -> Vec<f32> {
+    let query = words(query);
+    documents
+        .iter()
+        .map(|document| {
+            let document = words(document);
+            let shared = query.intersection(&document).count();
+            if shared == 0 {
+                0.0
+            } else {
+                shared as f32 / (document.len() as f32).sqrt()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::NoopEmbedding;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+
+    struct NamedTool(&'static str, &'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            self.1
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            anyhow::bail!("not used")
+        }
+    }
+
+    fn registry() -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(NamedTool("shell", "Execute terminal commands")),
+            Box::new(NamedTool(
+                "memory_recall",
+                "Search memory for past decisions",
+            )),
+            Box::new(NamedTool(
+                "email_send",
+                "Send an email message to a contact",
+            )),
+            Box::new(NamedTool("feeds", "Read RSS and Atom news feeds")),
+            Box::new(NamedTool("image_ocr", "Extract text from an image")),
+        ]
+    }
+
+    fn router(max_tools: usize, pinned: &[&str]) -> ToolRouter {
+        let config = ToolSelectionConfig {
+            enabled: true,
+            max_tools,
+            max_skills: 1,
+            pinned_tools: pinned.iter().map(ToString::to_string).collect(),
+        };
+        ToolRouter::new(
+            Arc::new(NoopEmbedding),
+            &config,
+            Vec::new(),
+            Path::new("/tmp"),
+        )
+    }
+
+    fn names(specs: &[ToolSpec]) -> Vec<&str> {
+        specs.iter().map(|spec| spec.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn offers_best_matches_pinned_tools_and_search() {
+        let tools = registry();
+        let selection = router(1, &["memory_recall"])
+            .select("Please send an email to Ada", &tools)
+            .await;
+        assert_eq!(
+            names(&selection.specs(&tools)),
+            ["memory_recall", "email_send", SEARCH_TOOL]
+        );
+    }
+
+    #[tokio::test]
+    async fn small_registries_are_offered_whole() {
+        let tools = registry();
+        let selection = router(10, &[]).select("anything", &tools).await;
+        let specs = selection.specs(&tools);
+        assert_eq!(specs.len(), tools.len());
+        assert!(!names(&specs).contains(&SEARCH_TOOL));
+    }
+
+    #[tokio::test]
+    async fn search_offers_more_tools() {
+        let tools = registry();
+        let router = router(1, &[]);
+        let mut selection = router.select("read the news feeds", &tools).await;
+        assert_eq!(names(&selection.specs(&tools)), ["feeds", SEARCH_TOOL]);
+
+        let answer = router
+            .search("text in an image", &tools, &mut selection)
+            .await;
+        assert!(answer.contains("**image_ocr**"));
+        assert!(!answer.contains("**email_send**"));
+        assert!(names(&selection.specs(&tools)).contains(&"image_ocr"));
+
+        let answer = router.search("zzz", &tools, &mut selection).await;
+        assert!(answer.contains("Not offered yet: shell, memory_recall, email_send"));
+
+        // Naming a tool offers exactly that tool
+        let answer = router.search("shell", &tools, &mut selection).await;
+        assert!(answer.starts_with("These tools are now available:\n- **shell**"));
+        selection.offer("memory_recall", &tools);
+        selection.offer("email_send", &tools);
+        assert_eq!(selection.specs(&tools).len(), tools.len());
+    }
+
+    /// Mail-ness, news-ness and a constant.
+    struct TopicEmbedding(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl EmbeddingProvider for TopicEmbedding {
+        fn name(&self) -> &str {
+            "topics"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            self.0
+                .fetch_add(texts.len(), std::sync::atomic::Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mail = ["mail", "inbox"].iter().any(|w| text.contains(w));
+                    let news = ["news", "headline"].iter().any(|w| text.contains(w));
+                    vec![f32::from(u8::from(mail)), f32::from(u8::from(news)), 0.1]
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn ranks_by_embedding_and_embeds_descriptions_once() {
+        let tools = registry();
+        let embedder = Arc::new(TopicEmbedding(std::sync::atomic::AtomicUsize::new(0)));
+        let config = ToolSelectionConfig {
+            enabled: true,
+            max_tools: 1,
+            ..ToolSelectionConfig::default()
+        };
+        let router = ToolRouter::new(embedder.clone(), &config, Vec::new(), Path::new("/tmp"));
+
+        let selection = router.select("anything in my inbox?", &tools).await;
+        assert_eq!(names(&selection.specs(&tools)), ["email_send", SEARCH_TOOL]);
+        let selection = router.select("today's headlines", &tools).await;
+        assert_eq!(names(&selection.specs(&tools)), ["feeds", SEARCH_TOOL]);
+
+        // Five descriptions, then one query per request
+        let calls = embedder.0.load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(calls, tools.len() + 2);
+    }
+
+    #[test]
+    fn messages_extend_system_prompt_for_prompted_tools() {
+        let tools = registry();
+        let mut selection = Selection::default();
+        selection.offer("shell", &tools);
+        let history = [ChatMessage::system("Base"), ChatMessage::user("hi")];
+
+        let prompted = selection.messages(&history, &tools, false);
+        assert!(prompted[0].content.starts_with("Base\n\n"));
+        assert!(prompted[0].content.contains("**shell**"));
+        assert!(prompted[0].content.contains(SEARCH_TOOL));
+        assert!(!prompted[0].content.contains("email_send"));
+
+        let native = selection.messages(&history, &tools, true);
+        assert_eq!(native[0].content, "Base");
+    }
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Runs automation actions for events published on the bus.
+//!
+//! A minute ticker feeds cron triggers. Every matching rule runs in its own
+//! task so a slow agent prompt does not hold up other events. Each run
+//! needs the security policy to allow acting and spends one action from its
+//! hourly budget; tools enforce their own policy checks on top. Runs are
+//! written to the audit log.
+
+use super::{render, render_json, subscribe, Action, Automation, AutomationStore, Event, Target};
+use crate::agent::budget::LoopBudget;
+use crate::agent::cancel::CancellationToken;
+use crate::agent::executor::AgentExecutor;
+use crate::agent::loop_::Planner;
+use crate::agent::tool_router::ToolRouter;
+use crate::channels::Channel;
+use crate::config::Config;
+use crate::observability::Observer;
+use crate::providers::router::{ModelRouter, TaskClass};
+use crate::providers::{ChatMessage, Provider};
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger, SecurityPolicy};
+use crate::tools::{Tool, ToolRegistry};
+use anyhow::{bail, Result};
+use chrono::{Local, Utc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{self, Duration};
+
+const TICK_SECONDS: u64 = 60;
+/// Longest output kept in an audit entry.
+const AUDIT_OUTPUT_CHARS: usize = 200;
+
+pub struct AutomationEngine {
+    pub config: Arc<RwLock<Config>>,
+    pub workspace_dir: PathBuf,
+    pub provider: Arc<dyn Provider>,
+    pub observer: Arc<dyn Observer>,
+    pub tools: Arc<ToolRegistry>,
+    pub system_prompt: Arc<RwLock<String>>,
+    pub models: Arc<ModelRouter>,
+    pub temperature: Arc<RwLock<f64>>,
+    pub security: Arc<SecurityPolicy>,
+    pub audit: Arc<AuditLogger>,
+    pub executor: Arc<AgentExecutor>,
+    pub budget: LoopBudget,
+    pub planner: Option<Planner>,
+    pub tool_router: Option<Arc<ToolRouter>>,
+    /// Channels actions may send through, keyed by lowercase name.
+    pub channels: HashMap<String, Arc<dyn Channel>>,
+}
+
+impl AutomationEngine {
+    /// Process events until the bus closes.
+    pub async fn run(self: Arc<Self>) {
+        let mut events = subscribe();
+        let mut ticker = time::interval(Duration::from_secs(TICK_SECONDS));
+        let mut last_tick = Utc::now();
+        crate::health::mark_component_ok("automations");
+
+        loop {
+            let event = tokio::select! {
+                _ = ticker.tick() => {
+                    let now = Utc::now();
+                    let from = std::mem::replace(&mut last_tick, now);
+                    Event::Tick { from, to: now }
+                }
+                received = events.recv() => match received {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Automation engine skipped {skipped} events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            self.dispatch(event).await;
+        }
+    }
+
+    /// Spawn a run for every enabled rule matching `event`.
+    pub async fn dispatch(self: &Arc<Self>, event: Event) {
+        let now = Local::now();
+        for rule in self.rules().await {
+            if !rule.matches(&event, now) {
+                continue;
+            }
+            let engine = Arc::clone(self);
+            let event = event.clone();
+            tokio::spawn(async move {
+                let result = engine.execute(&rule, &event).await;
+                engine.record(&rule, &event, &result);
+            });
+        }
+    }
+
+    /// Rules from the config followed by rules created through the API.
+    async fn rules(&self) -> Vec<Automation> {
+        let config = self.config.read().await;
+        // The feed digest runs on the engine even with user automations off.
+        let mut rules: Vec<Automation> = crate::tools::feeds::digest_automation(&config.feeds)
+            .into_iter()
+            .collect();
+        if config.automations.enabled {
+            rules.extend(config.automations.rules.iter().cloned());
+            let secrets = Some(SecretStore::new(&self.workspace_dir.join(".mymolt"), true));
+            rules.extend(AutomationStore::load(&self.workspace_dir, &secrets).automations);
+        }
+        rules
+    }
+
+    async fn execute(&self, rule: &Automation, event: &Event) -> Result<String> {
+        if !self.security.can_act() {
+            bail!("blocked by security policy: autonomy is read-only");
+        }
+        if !self.security.record_action() {
+            bail!("blocked by security policy: action budget exhausted");
+        }
+
+        match &rule.action {
+            Action::Tool { name, args } => {
+                let tools = self.tools.snapshot();
+                let Some(tool) = tools.iter().find(|t| t.name() == name) else {
+                    bail!("Unknown tool '{name}'");
+                };
+                let result = tool.execute(render_json(args, event)).await?;
+                if result.success {
+                    Ok(result.output)
+                } else {
+                    bail!(result.error.unwrap_or(result.output))
+                }
+            }
+            Action::Prompt { prompt, reply } => {
+                let reply_text = self.prompt(&render(prompt, event)).await?;
+                if let Some(target) = reply {
+                    self.send(target, &reply_text).await?;
+                }
+                Ok(reply_text)
+            }
+            Action::Message { to, text } => {
+                let text = render(text, event);
+                self.send(to, &text).await?;
+                Ok(text)
+            }
+        }
+    }
+
+    async fn prompt(&self, prompt: &str) -> Result<String> {
+        let _permit = self.executor.submit("automation")?.ready().await;
+        let system_prompt = self.system_prompt.read().await.clone();
+        let model = self.models.model_for(TaskClass::Tools);
+        let temperature = *self.temperature.read().await;
+        let mut history = vec![ChatMessage::system(&system_prompt), ChatMessage::user(prompt)];
+        let tools = self.tools.snapshot();
+        crate::agent::loop_::run_agent_turn(
+            self.provider.as_ref(),
+            &mut history,
+            tools.as_slice(),
+            self.observer.as_ref(),
+            "automation",
+            &model,
+            temperature,
+            &self.budget,
+            self.planner.as_ref(),
+            self.tool_router.as_deref(),
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    async fn send(&self, target: &Target, text: &str) -> Result<()> {
+        let Some(channel) = self.channels.get(&target.channel.to_lowercase()) else {
+            bail!("Channel '{}' is not configured", target.channel);
+        };
+        channel.send(text, &target.recipient).await
+    }
+
+    fn record(&self, rule: &Automation, event: &Event, result: &Result<String>) {
+        let summary = match result {
+            Ok(output) => crate::util::truncate_with_ellipsis(output, AUDIT_OUTPUT_CHARS),
+            Err(e) => {
+                tracing::warn!("Automation '{}' failed: {e}", rule.name);
+                format!("error: {e}")
+            }
+        };
+        tracing::info!("Automation '{}' ran on {} event", rule.name, event.kind());
+        let _ = self.audit.log(
+            &AuditEvent::new(AuditEventType::CommandExecution)
+                .with_actor("automation".to_string(), None, Some(rule.name.clone()))
+                .with_action(
+                    format!("automation:{}:{} → {summary}", event.kind(), rule.id),
+                    "medium".to_string(),
+                    true,
+                    result.is_ok(),
+                ),
+        );
+    }
+}
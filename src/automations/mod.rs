@@ -0,0 +1,602 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Declarative automation rules: trigger → conditions → action.
+//!
+//! Rules come from `[automations]` in the config (read-only) or from the
+//! `/api/automations` endpoints (stored encrypted in the workspace). Gateway
+//! components and channels [`publish`] [`Event`]s onto a process-wide bus;
+//! the [`engine::AutomationEngine`] matches them against the rules and runs
+//! the actions — a tool call, an agent prompt or a channel message — under
+//! the gateway's `SecurityPolicy`.
+//!
+//! Action text may use `{{text}}`, `{{sender}}`, `{{channel}}`, `{{event}}`,
+//! `{{place}}`, `{{component}}` and `{{state}}` placeholders, filled from the
+//! triggering event.
+
+pub mod engine;
+
+use crate::security::secrets::SecretStore;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before the slowest one starts lagging.
+const BUS_CAPACITY: usize = 64;
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+// ── Rules ──────────────────────────────────────────────────────────
+
+/// What starts an automation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    /// A chat message whose text matches `pattern` (case-insensitive regex),
+    /// optionally only on one channel.
+    Message {
+        #[serde(default)]
+        channel: Option<String>,
+        pattern: String,
+    },
+    /// A cron schedule (5-field crontab or 6/7-field with seconds).
+    Cron { expression: String },
+    /// `POST /api/automations/events/{event}`
+    Webhook { event: String },
+    /// A location reminder firing, optionally only for one place.
+    Reminder {
+        #[serde(default)]
+        place: Option<String>,
+    },
+    /// A component changing state, e.g. `adblock` → `disabled` or
+    /// `vpn` → `peer_added`. Without `state`, any change matches.
+    StateChange {
+        component: String,
+        #[serde(default)]
+        state: Option<String>,
+    },
+}
+
+/// Extra checks evaluated when the trigger matched. All must hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    /// Local time in `[after, before)` (`HH:MM`); wraps past midnight.
+    TimeBetween { after: String, before: String },
+    /// Local weekday is one of `days` (`mon` … `sun`).
+    Weekdays { days: Vec<String> },
+    /// The event text contains `text` (case-insensitive).
+    Contains { text: String },
+    /// The message sender is one of `senders`.
+    Sender { senders: Vec<String> },
+}
+
+/// Where a message or agent reply is delivered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    pub channel: String,
+    pub recipient: String,
+}
+
+/// What an automation does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Call a registered tool; string arguments are templated.
+    Tool {
+        name: String,
+        #[serde(default)]
+        args: serde_json::Value,
+    },
+    /// Run the agent on `prompt`, optionally sending the reply somewhere.
+    Prompt {
+        prompt: String,
+        #[serde(default)]
+        reply: Option<Target>,
+    },
+    /// Send `text` through a configured channel.
+    Message {
+        #[serde(flatten)]
+        to: Target,
+        text: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Automation {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    pub action: Action,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .with_context(|| format!("Invalid time '{s}' (expected HH:MM)"))
+}
+
+fn message_regex(pattern: &str) -> Result<regex::Regex> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(1 << 20)
+        .build()
+        .with_context(|| format!("Invalid message pattern '{pattern}'"))
+}
+
+impl Automation {
+    /// Reject rules that could never run.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("Automation name must not be empty");
+        }
+        match &self.trigger {
+            Trigger::Message { pattern, .. } => {
+                message_regex(pattern)?;
+            }
+            Trigger::Cron { expression } => {
+                crate::cron::next_run_for(expression, Utc::now())?;
+            }
+            Trigger::Webhook { event } if event.trim().is_empty() => {
+                bail!("Webhook event name must not be empty");
+            }
+            Trigger::StateChange { component, .. } if component.trim().is_empty() => {
+                bail!("State change component must not be empty");
+            }
+            _ => {}
+        }
+        for condition in &self.conditions {
+            match condition {
+                Condition::TimeBetween { after, before } => {
+                    parse_time(after)?;
+                    parse_time(before)?;
+                }
+                Condition::Weekdays { days } => {
+                    if let Some(day) = days.iter().find(|d| !WEEKDAYS.contains(&d.to_lowercase().as_str())) {
+                        bail!("Invalid weekday '{day}' (expected mon … sun)");
+                    }
+                }
+                Condition::Contains { .. } | Condition::Sender { .. } => {}
+            }
+        }
+        match &self.action {
+            Action::Tool { name, .. } if name.trim().is_empty() => bail!("Tool name must not be empty"),
+            Action::Prompt { prompt, .. } if prompt.trim().is_empty() => {
+                bail!("Prompt must not be empty");
+            }
+            Action::Message { text, .. } if text.trim().is_empty() => {
+                bail!("Message text must not be empty");
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether this rule fires for `event` at local time `now`.
+    pub fn matches(&self, event: &Event, now: DateTime<Local>) -> bool {
+        self.enabled
+            && self.trigger_matches(event)
+            && self.conditions.iter().all(|c| condition_holds(c, event, now))
+    }
+
+    fn trigger_matches(&self, event: &Event) -> bool {
+        match (&self.trigger, event) {
+            (
+                Trigger::Message { channel, pattern },
+                Event::Message {
+                    channel: from, text, ..
+                },
+            ) => {
+                channel.as_ref().is_none_or(|c| c.eq_ignore_ascii_case(from))
+                    && message_regex(pattern).is_ok_and(|re| re.is_match(text))
+            }
+            (Trigger::Cron { expression }, Event::Tick { from, to }) => {
+                crate::cron::next_run_for(expression, *from).is_ok_and(|next| next <= *to)
+            }
+            (Trigger::Webhook { event: name }, Event::Webhook { event, .. }) => {
+                name.eq_ignore_ascii_case(event)
+            }
+            (Trigger::Reminder { place }, Event::Reminder { place: fired, .. }) => {
+                place.as_ref().is_none_or(|p| p.eq_ignore_ascii_case(fired))
+            }
+            (
+                Trigger::StateChange { component, state },
+                Event::StateChange {
+                    component: changed,
+                    state: new_state,
+                },
+            ) => {
+                component.eq_ignore_ascii_case(changed)
+                    && state.as_ref().is_none_or(|s| s.eq_ignore_ascii_case(new_state))
+            }
+            _ => false,
+        }
+    }
+}
+
+fn condition_holds(condition: &Condition, event: &Event, now: DateTime<Local>) -> bool {
+    match condition {
+        Condition::TimeBetween { after, before } => {
+            let (Ok(after), Ok(before)) = (parse_time(after), parse_time(before)) else {
+                return false;
+            };
+            let time = now.time();
+            if after <= before {
+                after <= time && time < before
+            } else {
+                time >= after || time < before
+            }
+        }
+        Condition::Weekdays { days } => {
+            let today = WEEKDAYS[now.weekday().num_days_from_monday() as usize];
+            days.iter().any(|d| d.eq_ignore_ascii_case(today))
+        }
+        Condition::Contains { text } => event
+            .text()
+            .to_lowercase()
+            .contains(&text.to_lowercase()),
+        Condition::Sender { senders } => match event {
+            Event::Message { sender, .. } => senders.iter().any(|s| s.eq_ignore_ascii_case(sender)),
+            _ => false,
+        },
+    }
+}
+
+// ── Events ─────────────────────────────────────────────────────────
+
+/// Something that happened and may trigger automations.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Message {
+        channel: String,
+        sender: String,
+        text: String,
+    },
+    /// The engine's clock advanced over `(from, to]`.
+    Tick {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    Webhook {
+        event: String,
+        payload: serde_json::Value,
+    },
+    Reminder {
+        place: String,
+        text: String,
+    },
+    StateChange {
+        component: String,
+        state: String,
+    },
+}
+
+impl Event {
+    /// The free text carried by the event, for conditions and `{{text}}`.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Message { text, .. } | Self::Reminder { text, .. } => text.clone(),
+            Self::Webhook { payload, .. } => match payload {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            },
+            Self::Tick { .. } | Self::StateChange { .. } => String::new(),
+        }
+    }
+
+    /// Short description for audit entries.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Message { .. } => "message",
+            Self::Tick { .. } => "cron",
+            Self::Webhook { .. } => "webhook",
+            Self::Reminder { .. } => "reminder",
+            Self::StateChange { .. } => "state_change",
+        }
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        match (name, self) {
+            ("text", _) => Some(self.text()),
+            ("sender", Self::Message { sender, .. }) => Some(sender.clone()),
+            ("channel", Self::Message { channel, .. }) => Some(channel.clone()),
+            ("event", Self::Webhook { event, .. }) => Some(event.clone()),
+            ("place", Self::Reminder { place, .. }) => Some(place.clone()),
+            ("component", Self::StateChange { component, .. }) => Some(component.clone()),
+            ("state", Self::StateChange { state, .. }) => Some(state.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Fill `{{name}}` placeholders from `event`. Unknown placeholders become empty.
+pub fn render(template: &str, event: &Event) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        out.push_str(&event.var(after[..end].trim()).unwrap_or_default());
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// [`render`] every string inside a JSON value.
+pub fn render_json(value: &serde_json::Value, event: &Event) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => serde_json::Value::String(render(s, event)),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| render_json(v, event)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_json(v, event)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn bus() -> &'static broadcast::Sender<Event> {
+    static BUS: OnceLock<broadcast::Sender<Event>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(BUS_CAPACITY).0)
+}
+
+/// Announce an event to the automation engine. A no-op when none is running.
+pub fn publish(event: Event) {
+    let _ = bus().send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    bus().subscribe()
+}
+
+// ── Storage ────────────────────────────────────────────────────────
+
+/// Rules created through the API. Encrypted at rest like the PIM store.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AutomationStore {
+    #[serde(default)]
+    pub automations: Vec<Automation>,
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join(".mymolt").join("automations.json")
+}
+
+impl AutomationStore {
+    pub fn load(workspace: &Path, secrets: &Option<SecretStore>) -> Self {
+        let Ok(raw) = std::fs::read_to_string(store_path(workspace)) else {
+            return Self::default();
+        };
+        let json = match secrets {
+            Some(store) => store.decrypt(&raw).unwrap_or(raw),
+            None => raw,
+        };
+        serde_json::from_str(&json).unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path, secrets: &Option<SecretStore>) -> Result<()> {
+        let path = store_path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let data = match secrets {
+            Some(store) => store.encrypt(&json)?,
+            None => json,
+        };
+        std::fs::write(&path, data)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rule(trigger: Trigger, conditions: Vec<Condition>) -> Automation {
+        Automation {
+            id: "r1".into(),
+            name: "test".into(),
+            enabled: true,
+            trigger,
+            conditions,
+            action: Action::Message {
+                to: Target {
+                    channel: "telegram".into(),
+                    recipient: "123".into(),
+                },
+                text: "Got: {{text}}".into(),
+            },
+        }
+    }
+
+    fn message(channel: &str, text: &str) -> Event {
+        Event::Message {
+            channel: channel.into(),
+            sender: "alice".into(),
+            text: text.into(),
+        }
+    }
+
+    // Wednesday
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn message_trigger_matches_pattern_and_channel() {
+        let r = rule(
+            Trigger::Message {
+                channel: Some("whatsapp".into()),
+                pattern: r"^buy\s+(milk|bread)".into(),
+            },
+            vec![],
+        );
+        assert!(r.matches(&message("WhatsApp", "Buy milk please"), at(9, 0)));
+        assert!(!r.matches(&message("telegram", "buy milk"), at(9, 0)));
+        assert!(!r.matches(&message("whatsapp", "sell milk"), at(9, 0)));
+
+        let disabled = Automation {
+            enabled: false,
+            ..r
+        };
+        assert!(!disabled.matches(&message("whatsapp", "buy milk"), at(9, 0)));
+    }
+
+    #[test]
+    fn cron_trigger_fires_once_per_window() {
+        let r = rule(
+            Trigger::Cron {
+                expression: "0 7 * * *".into(),
+            },
+            vec![],
+        );
+        // Schedules are evaluated in UTC; pick a window around 07:00 UTC.
+        let seven = Utc.with_ymd_and_hms(2026, 3, 4, 7, 0, 0).unwrap();
+        let window = Event::Tick {
+            from: seven - chrono::Duration::seconds(30),
+            to: seven + chrono::Duration::seconds(30),
+        };
+        assert!(r.matches(&window, at(7, 0)));
+        let after = Event::Tick {
+            from: seven + chrono::Duration::seconds(30),
+            to: seven + chrono::Duration::seconds(90),
+        };
+        assert!(!r.matches(&after, at(7, 1)));
+        assert!(!r.matches(&message("cli", "0 7 * * *"), at(7, 0)));
+    }
+
+    #[test]
+    fn conditions_gate_time_weekday_text_and_sender() {
+        let night = rule(
+            Trigger::StateChange {
+                component: "adblock".into(),
+                state: Some("disabled".into()),
+            },
+            vec![Condition::TimeBetween {
+                after: "22:00".into(),
+                before: "06:00".into(),
+            }],
+        );
+        let off = Event::StateChange {
+            component: "adblock".into(),
+            state: "disabled".into(),
+        };
+        assert!(night.matches(&off, at(23, 30)));
+        assert!(night.matches(&off, at(5, 59)));
+        assert!(!night.matches(&off, at(12, 0)));
+
+        let weekend = rule(
+            Trigger::Webhook { event: "door".into() },
+            vec![Condition::Weekdays {
+                days: vec!["sat".into(), "sun".into()],
+            }],
+        );
+        let door = Event::Webhook {
+            event: "door".into(),
+            payload: serde_json::json!("opened"),
+        };
+        assert!(!weekend.matches(&door, at(10, 0)));
+
+        let filtered = rule(
+            Trigger::Message {
+                channel: None,
+                pattern: ".".into(),
+            },
+            vec![
+                Condition::Contains { text: "URGENT".into() },
+                Condition::Sender {
+                    senders: vec!["Alice".into()],
+                },
+            ],
+        );
+        assert!(filtered.matches(&message("telegram", "this is urgent"), at(9, 0)));
+        assert!(!filtered.matches(&message("telegram", "no rush"), at(9, 0)));
+    }
+
+    #[test]
+    fn validate_rejects_broken_rules() {
+        assert!(rule(Trigger::Cron { expression: "0 7 * * *".into() }, vec![])
+            .validate()
+            .is_ok());
+        assert!(rule(Trigger::Cron { expression: "every day".into() }, vec![])
+            .validate()
+            .is_err());
+        assert!(rule(
+            Trigger::Message {
+                channel: None,
+                pattern: "(unclosed".into()
+            },
+            vec![]
+        )
+        .validate()
+        .is_err());
+        assert!(rule(
+            Trigger::Reminder { place: None },
+            vec![Condition::Weekdays {
+                days: vec!["funday".into()]
+            }]
+        )
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn renders_placeholders_from_event() {
+        let event = Event::Reminder {
+            place: "home".into(),
+            text: "water the plants".into(),
+        };
+        assert_eq!(
+            render("At {{ place }}: {{text}}{{missing}} {{", &event),
+            "At home: water the plants {{"
+        );
+        let args = render_json(&serde_json::json!({"note": "{{text}}", "n": 2}), &event);
+        assert_eq!(args, serde_json::json!({"note": "water the plants", "n": 2}));
+    }
+
+    #[test]
+    fn rules_parse_from_toml_and_roundtrip_encrypted() {
+        let parsed: Automation = toml::from_str(
+            r#"
+            name = "Morning briefing"
+            trigger = { type = "cron", expression = "0 7 * * mon-fri" }
+            action = { type = "prompt", prompt = "Summarize my calendar for today" }
+            "#,
+        )
+        .unwrap();
+        assert!(parsed.enabled);
+        assert!(parsed.validate().is_ok());
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let secrets = Some(SecretStore::new(&tmp.path().join(".mymolt"), true));
+        let store = AutomationStore {
+            automations: vec![parsed.clone()],
+        };
+        store.save(tmp.path(), &secrets).unwrap();
+        let raw = std::fs::read_to_string(store_path(tmp.path())).unwrap();
+        assert!(!raw.contains("Morning briefing"));
+        assert_eq!(AutomationStore::load(tmp.path(), &secrets).automations, vec![parsed]);
+    }
+}
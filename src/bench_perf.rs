@@ -21,7 +21,7 @@ mod bench {
     // ── 1. Trust level check ─────────────────────────────────────────────────
     #[test]
     fn bench_check_trust() {
-        let mut policy = SecurityPolicy::default();
+        let policy = SecurityPolicy::default();
         policy.set_trust_level(TrustLevel::High);
 
         let start = Instant::now();
@@ -160,7 +160,7 @@ mod bench {
     // ── 7. Full security gate stack (simulate one agent tool call check) ─────
     #[test]
     fn bench_full_gate_stack() {
-        let mut policy = SecurityPolicy::default();
+        let policy = SecurityPolicy::default();
         policy.set_trust_level(TrustLevel::High);
 
         let start = Instant::now();
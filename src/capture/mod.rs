@@ -0,0 +1,545 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Photo capture — posters and receipts into the PIM.
+//!
+//! A photo sent through a channel (or uploaded to `/api/capture`) is OCR'd
+//! locally, parsed by a cheap model into a [`CaptureProposal`], and only
+//! written to the PIM once someone approves it through the
+//! [`ConfirmationGate`]. Events become calendar entries; receipts become
+//! expenses.
+
+pub mod ocr;
+
+use crate::config::{CaptureConfig, ModelRouteConfig};
+use crate::providers::Provider;
+use crate::security::confirmation::ConfirmationGate;
+use crate::security::secrets::SecretStore;
+use crate::tools::pim::{self, CalendarEvent, Expense};
+use anyhow::{bail, Result};
+use chrono::{NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Model route hint preferred for parsing OCR text.
+pub const CHEAP_HINT: &str = "cheap";
+
+/// OCR text beyond this is cut before it is sent to the model.
+const MAX_OCR_CHARS: usize = 8000;
+
+const CONFIRM_TOOL: &str = "pim_capture";
+
+/// What a photo turned out to contain, awaiting confirmation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureProposal {
+    Event {
+        title: String,
+        /// `YYYY-MM-DD`
+        date: String,
+        /// `HH:MM`
+        #[serde(default)]
+        time: Option<String>,
+        #[serde(default)]
+        location: Option<String>,
+        #[serde(default)]
+        description: String,
+    },
+    Expense {
+        merchant: String,
+        total: f64,
+        /// ISO 4217 code, e.g. `EUR`
+        currency: String,
+        #[serde(default)]
+        category: String,
+        /// `YYYY-MM-DD`
+        date: String,
+    },
+}
+
+impl CaptureProposal {
+    /// One-line description shown in the confirmation prompt.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Event {
+                title,
+                date,
+                time,
+                location,
+                ..
+            } => {
+                let mut s = format!("📅 {title} on {date}");
+                if let Some(t) = time {
+                    let _ = write!(s, " at {t}");
+                }
+                if let Some(l) = location {
+                    let _ = write!(s, " ({l})");
+                }
+                s
+            }
+            Self::Expense {
+                merchant,
+                total,
+                currency,
+                category,
+                date,
+            } => format!("🧾 {total:.2} {currency} at {merchant} on {date} ({category})"),
+        }
+    }
+
+    /// Write the proposal to the PIM store. Returns the new entry's ID.
+    pub fn save(&self, workspace: &Path) -> Result<String> {
+        let secrets = Some(SecretStore::new(&workspace.join(".mymolt"), true));
+        let mut store = pim::load_store(workspace, &secrets);
+        let id = uuid::Uuid::new_v4().to_string();
+        match self {
+            Self::Event {
+                title,
+                date,
+                time,
+                location,
+                description,
+            } => store.events.push(CalendarEvent {
+                id: id.clone(),
+                title: title.clone(),
+                date: date.clone(),
+                time: time.clone(),
+                description: match location {
+                    Some(l) if description.is_empty() => format!("📍 {l}"),
+                    Some(l) => format!("{description}\n📍 {l}"),
+                    None => description.clone(),
+                },
+            }),
+            Self::Expense {
+                merchant,
+                total,
+                currency,
+                category,
+                date,
+            } => store.expenses.push(Expense {
+                id: id.clone(),
+                amount: *total,
+                currency: currency.clone(),
+                category: category.clone(),
+                date: date.clone(),
+                note: merchant.clone(),
+            }),
+        }
+        pim::save_store(workspace, &store, &secrets)?;
+        Ok(id)
+    }
+
+    fn validate(self) -> Option<Self> {
+        let valid_date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok();
+        match self {
+            Self::Event {
+                title,
+                date,
+                time,
+                location,
+                description,
+            } => {
+                let title = title.trim().to_string();
+                if title.is_empty() || !valid_date(&date) {
+                    return None;
+                }
+                Some(Self::Event {
+                    title,
+                    date,
+                    time: time.filter(|t| NaiveTime::parse_from_str(t, "%H:%M").is_ok()),
+                    location: location
+                        .map(|l| l.trim().to_string())
+                        .filter(|l| !l.is_empty()),
+                    description: description.trim().to_string(),
+                })
+            }
+            Self::Expense {
+                merchant,
+                total,
+                currency,
+                category,
+                date,
+            } => {
+                let merchant = merchant.trim().to_string();
+                let currency = currency.trim().to_uppercase();
+                let category = match category.trim().to_lowercase() {
+                    c if c.is_empty() => "other".to_string(),
+                    c => c,
+                };
+                if merchant.is_empty()
+                    || !total.is_finite()
+                    || total <= 0.0
+                    || currency.len() != 3
+                    || !valid_date(&date)
+                {
+                    return None;
+                }
+                Some(Self::Expense {
+                    merchant,
+                    total,
+                    currency,
+                    category,
+                    date,
+                })
+            }
+        }
+    }
+}
+
+/// Model used to parse OCR text: `capture.model`, else the `cheap` route for
+/// the active provider, else the default model.
+pub fn parse_model(
+    config: &CaptureConfig,
+    routes: &[ModelRouteConfig],
+    provider_name: &str,
+    default_model: &str,
+) -> String {
+    if let Some(model) = config
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+    {
+        return model.to_string();
+    }
+    routes
+        .iter()
+        .find(|r| r.hint == CHEAP_HINT && r.provider == provider_name)
+        .map_or_else(|| default_model.to_string(), |r| r.model.clone())
+}
+
+fn parse_prompt(today: NaiveDate) -> String {
+    format!(
+        "You read OCR text from a photo of a poster, flyer, letter or receipt. Reply with JSON \
+         only, no prose or code fences. For an event: {{\"kind\": \"event\", \"title\": \"...\", \
+         \"date\": \"YYYY-MM-DD\", \"time\": \"HH:MM\" or null, \"location\": \"...\" or null, \
+         \"description\": \"one short sentence\"}}. For a receipt or bill: {{\"kind\": \"expense\", \
+         \"merchant\": \"...\", \"total\": 12.34, \"currency\": \"EUR\", \"category\": \
+         \"groceries\" | \"household\" | \"transport\" | \"school\" | \"health\" | \"leisure\" | \
+         \"other\", \"date\": \"YYYY-MM-DD\"}}. \
+         If it is neither, reply {{\"kind\": \"none\"}}. Today is {today}; resolve relative or \
+         year-less dates against it. The OCR text may contain errors."
+    )
+}
+
+/// Parse the model's reply, tolerating code fences and surrounding prose.
+fn parse_reply(reply: &str) -> Option<CaptureProposal> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(reply.get(start..=end)?).ok()?;
+    if value.get("kind").and_then(serde_json::Value::as_str) == Some("none") {
+        return None;
+    }
+    serde_json::from_value::<CaptureProposal>(value)
+        .ok()?
+        .validate()
+}
+
+/// Turn OCR text into a proposal, or `None` if it is neither an event nor a receipt.
+pub async fn propose(
+    provider: &dyn Provider,
+    model: &str,
+    text: &str,
+) -> Result<Option<CaptureProposal>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let text: String = text.chars().take(MAX_OCR_CHARS).collect();
+    let prompt = parse_prompt(Utc::now().date_naive());
+    let reply = provider
+        .chat_with_system(Some(&prompt), &text, model, 0.0)
+        .await?;
+    Ok(reply.text.as_deref().and_then(parse_reply))
+}
+
+/// OCR a photo and turn it into a proposal.
+pub async fn read_photo(
+    provider: &dyn Provider,
+    model: &str,
+    config: &CaptureConfig,
+    image: &[u8],
+) -> Result<Option<CaptureProposal>> {
+    if !config.enabled {
+        bail!("Photo capture is disabled");
+    }
+    let text = ocr::recognize(image, &config.ocr_languages).await?;
+    propose(provider, model, &text).await
+}
+
+/// Ask for confirmation and save the proposal once approved.
+///
+/// Returns the confirmation request ID immediately; the save happens in the
+/// background.
+pub fn confirm_and_save(
+    gate: &Arc<ConfirmationGate>,
+    workspace: PathBuf,
+    proposal: CaptureProposal,
+    timeout_secs: u64,
+) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let gate = Arc::clone(gate);
+    let request_id = id.clone();
+    tokio::spawn(async move {
+        let summary = proposal.summary();
+        let approved = gate
+            .request_with_id(&request_id, CONFIRM_TOOL, &summary, "low", timeout_secs)
+            .await;
+        if !approved {
+            tracing::debug!("Capture discarded: {summary}");
+            return;
+        }
+        match proposal.save(&workspace) {
+            Ok(entry) => tracing::info!("Capture saved as {entry}: {summary}"),
+            Err(e) => tracing::error!("Failed to save capture: {e}"),
+        }
+    });
+    id
+}
+
+/// Read a chat reply as an answer to a pending proposal.
+pub fn parse_answer(text: &str) -> Option<bool> {
+    let answer = text.trim().trim_end_matches(['.', '!']).to_lowercase();
+    match answer.as_str() {
+        "yes" | "y" | "ok" | "save" | "ja" | "✅" | "👍" => Some(true),
+        "no" | "n" | "discard" | "cancel" | "nein" | "❌" | "👎" => Some(false),
+        _ => None,
+    }
+}
+
+/// Photo capture for chat channels: proposals are confirmed by replying
+/// "yes" or "no" in the same conversation.
+pub struct CaptureInbox {
+    config: CaptureConfig,
+    model: String,
+    workspace: PathBuf,
+    gate: Arc<ConfirmationGate>,
+    /// `channel:sender` → pending confirmation request ID
+    pending: Mutex<HashMap<String, String>>,
+}
+
+impl CaptureInbox {
+    pub fn new(config: CaptureConfig, model: String, workspace: PathBuf) -> Self {
+        let timeout = config.confirm_timeout_secs;
+        Self {
+            config,
+            model,
+            workspace,
+            gate: ConfirmationGate::new(timeout),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(channel: &str, sender: &str) -> String {
+        format!("{channel}:{sender}")
+    }
+
+    /// Read a photo and reply with the proposal and how to confirm it.
+    pub async fn handle_photo(
+        &self,
+        provider: &dyn Provider,
+        channel: &str,
+        sender: &str,
+        image: &[u8],
+    ) -> String {
+        let proposal = match read_photo(provider, &self.model, &self.config, image).await {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                return "I couldn't find an event or a receipt in that photo.".into();
+            }
+            Err(e) => return format!("⚠️ Couldn't read the photo: {e}"),
+        };
+        let summary = proposal.summary();
+        let what = match proposal {
+            CaptureProposal::Event { .. } => "add it to the calendar",
+            CaptureProposal::Expense { .. } => "save it as an expense",
+        };
+        let id = confirm_and_save(
+            &self.gate,
+            self.workspace.clone(),
+            proposal,
+            self.config.confirm_timeout_secs,
+        );
+        let previous = self
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(Self::key(channel, sender), id);
+        if let Some(previous) = previous {
+            self.gate.resolve(&previous, false).await;
+        }
+        format!("{summary}\nReply \"yes\" to {what} or \"no\" to discard.")
+    }
+
+    /// Resolve a pending proposal from a yes/no reply.
+    ///
+    /// Returns `None` when the message is not an answer or nothing is pending,
+    /// so it is handled as a normal message.
+    pub async fn handle_answer(&self, channel: &str, sender: &str, text: &str) -> Option<String> {
+        let approved = parse_answer(text)?;
+        let id = self
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&Self::key(channel, sender))?;
+        let reply = if !self.gate.resolve(&id, approved).await {
+            "That proposal has expired — send the photo again."
+        } else if approved {
+            "✅ Saved."
+        } else {
+            "Discarded."
+        };
+        Some(reply.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ChatResponse;
+    use async_trait::async_trait;
+
+    struct ReplyProvider(&'static str);
+
+    #[async_trait]
+    impl Provider for ReplyProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            Ok(ChatResponse::with_text(self.0))
+        }
+    }
+
+    fn event() -> CaptureProposal {
+        CaptureProposal::Event {
+            title: "Summer fete".into(),
+            date: "2026-07-12".into(),
+            time: Some("14:00".into()),
+            location: Some("School hall".into()),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_event_and_expense_replies() {
+        let reply = "```json\n{\"kind\": \"event\", \"title\": \"Summer fete\", \
+                     \"date\": \"2026-07-12\", \"time\": \"14:00\", \
+                     \"location\": \"School hall\", \"description\": \"\"}\n```";
+        assert_eq!(parse_reply(reply), Some(event()));
+
+        let reply = r#"{"kind": "expense", "merchant": "REWE", "total": 23.4, "currency": "eur", "category": "Groceries", "date": "2026-10-17"}"#;
+        assert_eq!(
+            parse_reply(reply),
+            Some(CaptureProposal::Expense {
+                merchant: "REWE".into(),
+                total: 23.4,
+                currency: "EUR".into(),
+                category: "groceries".into(),
+                date: "2026-10-17".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_none_and_invalid_replies() {
+        assert_eq!(parse_reply(r#"{"kind": "none"}"#), None);
+        assert_eq!(parse_reply("no idea"), None);
+        assert_eq!(
+            parse_reply(r#"{"kind": "event", "title": "Fete", "date": "July 12th"}"#),
+            None
+        );
+        assert_eq!(
+            parse_reply(
+                r#"{"kind": "expense", "merchant": "X", "total": -1, "currency": "EUR", "date": "2026-10-17"}"#
+            ),
+            None
+        );
+        // A garbled time is dropped rather than rejecting the event.
+        let CaptureProposal::Event { time, .. } = parse_reply(
+            r#"{"kind": "event", "title": "Fete", "date": "2026-07-12", "time": "2pm"}"#,
+        )
+        .unwrap() else {
+            panic!("expected an event");
+        };
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn prefers_configured_then_cheap_route() {
+        let routes = vec![ModelRouteConfig {
+            hint: CHEAP_HINT.into(),
+            provider: "openrouter".into(),
+            model: "small-model".into(),
+            api_key: None,
+            fallbacks: Vec::new(),
+            daily_token_budget: None,
+        }];
+        let mut config = CaptureConfig::default();
+        assert_eq!(
+            parse_model(&config, &routes, "openrouter", "big"),
+            "small-model"
+        );
+        assert_eq!(parse_model(&config, &routes, "ollama", "big"), "big");
+        config.model = Some("custom".into());
+        assert_eq!(parse_model(&config, &routes, "openrouter", "big"), "custom");
+    }
+
+    #[test]
+    fn parses_chat_answers() {
+        assert_eq!(parse_answer(" Yes! "), Some(true));
+        assert_eq!(parse_answer("👍"), Some(true));
+        assert_eq!(parse_answer("nein"), Some(false));
+        assert_eq!(parse_answer("yes please add it"), None);
+    }
+
+    #[tokio::test]
+    async fn propose_uses_provider_reply() {
+        let provider = ReplyProvider(
+            r#"{"kind": "event", "title": "Summer fete", "date": "2026-07-12", "time": "14:00", "location": "School hall"}"#,
+        );
+        let proposal = propose(&provider, "m", "SUMMER FETE\n12.07. 14 Uhr")
+            .await
+            .unwrap();
+        assert_eq!(proposal, Some(event()));
+        assert_eq!(propose(&provider, "m", "  ").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn saves_only_after_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let gate = ConfirmationGate::new(5);
+        let secrets = Some(SecretStore::new(&dir.path().join(".mymolt"), true));
+
+        let denied = confirm_and_save(&gate, dir.path().to_path_buf(), event(), 5);
+        while gate.pending_count().await == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(gate.resolve(&denied, false).await);
+
+        let approved = confirm_and_save(&gate, dir.path().to_path_buf(), event(), 5);
+        while gate.pending_count().await == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(gate.resolve(&approved, true).await);
+
+        for _ in 0..50 {
+            let store = pim::load_store(dir.path(), &secrets);
+            if !store.events.is_empty() {
+                assert_eq!(store.events.len(), 1);
+                assert_eq!(store.events[0].title, "Summer fete");
+                assert!(store.events[0].description.contains("School hall"));
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("approved capture was not saved");
+    }
+}
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Local OCR through the `tesseract` command-line tool.
+//!
+//! Images never leave the machine: the bytes are piped to `tesseract stdin
+//! stdout` and only the recognised text is passed on.
+
+use anyhow::{bail, Context, Result};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Largest image accepted for OCR.
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+const OCR_TIMEOUT_SECS: u64 = 60;
+
+/// Check if the tesseract CLI is available
+pub async fn is_available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Installed tesseract language packs (`eng`, `deu`, `osd`, …).
+pub async fn list_languages() -> Result<Vec<String>> {
+    let output = match Command::new("tesseract")
+        .arg("--list-langs")
+        .kill_on_drop(true)
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("tesseract is not installed; install it to read photos")
+        }
+        Err(e) => return Err(e).context("Failed to start tesseract"),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("tesseract failed: {}", stderr.trim());
+    }
+    // The first line is a header: `List of available languages in "…" (3):`
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Language codes joined by `+`, e.g. `eng+deu`.
+pub(crate) fn valid_languages(languages: &str) -> bool {
+    !languages.is_empty()
+        && languages.len() <= 64
+        && languages
+            .split('+')
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+/// Collapse runs of blank lines and trailing spaces in tesseract output.
+fn tidy(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push('\n');
+            blank = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+/// Recognise the text in an image (PNG, JPEG, TIFF, …).
+pub async fn recognize(image: &[u8], languages: &str) -> Result<String> {
+    if image.is_empty() {
+        bail!("Image is empty");
+    }
+    if image.len() > MAX_IMAGE_BYTES {
+        bail!("Image exceeds {} MB", MAX_IMAGE_BYTES / (1024 * 1024));
+    }
+    if !valid_languages(languages) {
+        bail!("Invalid OCR languages '{languages}'");
+    }
+
+    let mut child = match Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", languages])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("tesseract is not installed; install it to read photos")
+        }
+        Err(e) => return Err(e).context("Failed to start tesseract"),
+    };
+
+    let mut stdin = child.stdin.take().context("tesseract stdin unavailable")?;
+    let input = image.to_vec();
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(&input).await;
+    });
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(OCR_TIMEOUT_SECS),
+        child.wait_with_output(),
+    )
+    .await
+    .context("OCR timed out")??;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("tesseract failed: {}", stderr.trim());
+    }
+    Ok(tidy(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_language_codes() {
+        assert!(valid_languages("eng"));
+        assert!(valid_languages("eng+deu+chi_sim"));
+        assert!(!valid_languages(""));
+        assert!(!valid_languages("eng+"));
+        assert!(!valid_languages("eng; rm -rf /"));
+    }
+
+    #[test]
+    fn tidy_collapses_blank_lines() {
+        let raw = "\n\nSUMMER FETE  \n\n\n\nSat 12 July\n14:00\n\n";
+        assert_eq!(tidy(raw), "SUMMER FETE\n\nSat 12 July\n14:00");
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_and_oversized_images() {
+        assert!(recognize(&[], "eng").await.is_err());
+        let big = vec![0u8; MAX_IMAGE_BYTES + 1];
+        assert!(recognize(&big, "eng").await.is_err());
+    }
+}
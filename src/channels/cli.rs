@@ -50,6 +50,8 @@ impl Channel for CliChannel {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs(),
+                attachments: Vec::new(),
+                group: None,
             };
 
             if tx.send(msg).await.is_err() {
@@ -97,6 +99,8 @@ mod tests {
             content: "hello".into(),
             channel: "cli".into(),
             timestamp: 1_234_567_890,
+            attachments: Vec::new(),
+            group: None,
         };
         assert_eq!(msg.id, "test-id");
         assert_eq!(msg.sender, "user");
@@ -113,6 +117,8 @@ mod tests {
             content: "c".into(),
             channel: "ch".into(),
             timestamp: 0,
+            attachments: Vec::new(),
+            group: None,
         };
         let cloned = msg.clone();
         assert_eq!(cloned.id, msg.id);
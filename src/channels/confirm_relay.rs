@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Confirmation requests over chat (`[security.confirmation]`).
+//!
+//! The relay pushes every request raised on the [`ConfirmationGate`] to the
+//! configured chats and resolves it from the answer: Telegram sends the
+//! button's callback data, WhatsApp users reply "yes" or "no" followed by
+//! the request code. Each component relays the channels whose replies it
+//! receives — the channel server Telegram, the gateway WhatsApp.
+//!
+//! Recipients approve with their role in the family registry; those not
+//! registered approve as adults. For dual control each family member counts
+//! as one person, and unregistered recipients as the owner — the same person
+//! as on the dashboard.
+
+use super::traits::Channel;
+use crate::capture::parse_answer;
+use crate::identity::family::FamilyRegistry;
+use crate::identity::UserRole;
+use crate::security::confirmation::{
+    approver_identity, Answer, ConfirmationGate, ConfirmationRequest,
+};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Leading characters of a request ID that identify it in a reply.
+pub const CODE_LEN: usize = 6;
+
+/// The code users type to answer `request`.
+pub fn reply_code(request_id: &str) -> &str {
+    request_id.get(..CODE_LEN).unwrap_or(request_id)
+}
+
+/// Message asking to approve `request` by replying with its code.
+pub fn confirmation_text(request: &ConfirmationRequest) -> String {
+    let code = reply_code(&request.id);
+    let dual_control = if request.approvals_required > 1 {
+        format!(
+            "\nNeeds approval from {} people.",
+            request.approvals_required
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "🔐 Approval needed ({} risk)\n{}: {}{dual_control}\n\nReply \"yes {code}\" to approve or \"no {code}\" to deny within {}s.",
+        request.risk_level, request.tool_name, request.description, request.timeout_secs
+    )
+}
+
+/// Read `text` as "yes <code>" / "no <code>". Returns the answer and the
+/// code, which may be a full request ID.
+pub fn parse_reply(text: &str) -> Option<(bool, &str)> {
+    let mut words = text.split_whitespace();
+    let approved = parse_answer(words.next()?)?;
+    let code = words.next()?;
+    if words.next().is_some() || code.len() < CODE_LEN {
+        return None;
+    }
+    Some((approved, code))
+}
+
+/// Pushes confirmation requests to chats and resolves them from replies.
+pub struct ConfirmationRelay {
+    gate: Arc<ConfirmationGate>,
+    /// Channels with the recipients they deliver to and accept answers from
+    targets: Vec<(Arc<dyn Channel>, Vec<String>)>,
+    /// Resolves recipients to the role they approve with
+    family: Option<Arc<FamilyRegistry>>,
+}
+
+impl ConfirmationRelay {
+    pub fn new(gate: Arc<ConfirmationGate>) -> Self {
+        Self {
+            gate,
+            targets: Vec::new(),
+            family: None,
+        }
+    }
+
+    /// Look up the role of recipients in `family`.
+    #[must_use]
+    pub fn with_family(mut self, family: Arc<FamilyRegistry>) -> Self {
+        self.family = Some(family);
+        self
+    }
+
+    /// Deliver requests over `channel` to `recipients`; ignored when there
+    /// are none.
+    #[must_use]
+    pub fn with_target(mut self, channel: Arc<dyn Channel>, recipients: Vec<String>) -> Self {
+        if !recipients.is_empty() {
+            self.targets.push((channel, recipients));
+        }
+        self
+    }
+
+    /// Whether any chat receives requests.
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Forward every new request to the recipients until the gate closes.
+    pub fn spawn(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let relay = Arc::clone(self);
+        let mut rx = self.gate.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(request) => relay.deliver(&request).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!("Confirmation relay missed {missed} requests");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    async fn deliver(&self, request: &ConfirmationRequest) {
+        for (channel, recipients) in &self.targets {
+            for recipient in recipients {
+                if let Err(e) = channel.send_confirmation(request, recipient).await {
+                    tracing::warn!(
+                        request_id = %request.id,
+                        "Failed to send confirmation to {}:{recipient}: {e}",
+                        channel.name()
+                    );
+                }
+            }
+        }
+    }
+
+    fn is_recipient(&self, channel: &str, sender: &str) -> bool {
+        self.targets
+            .iter()
+            .any(|(ch, recipients)| ch.name() == channel && recipients.iter().any(|r| r == sender))
+    }
+
+    /// Resolve the request answered by `text` from `sender` on `channel`.
+    ///
+    /// Returns the reply when the message was an answer from a recipient.
+    pub async fn handle_reply(&self, channel: &str, sender: &str, text: &str) -> Option<String> {
+        let (approved, code) = parse_reply(text)?;
+        if !self.is_recipient(channel, sender) {
+            return None;
+        }
+        let request = self
+            .gate
+            .get_pending()
+            .await
+            .into_iter()
+            .find(|r| r.id.starts_with(code));
+        let Some(request) = request else {
+            return Some(format!(
+                "No pending request {}: it was already answered or has expired.",
+                reply_code(code)
+            ));
+        };
+        let member = self
+            .family
+            .as_ref()
+            .and_then(|family| family.resolve(channel, sender));
+        let role = member.map_or(UserRole::Adult, |m| m.role);
+        let identity = approver_identity(member.map(|m| m.name.as_str()));
+        match self
+            .gate
+            .resolve_as(&request.id, approved, role, &identity)
+            .await
+        {
+            Ok(Answer::Resolved) => {}
+            Ok(Answer::Partial {
+                approvals,
+                required,
+            }) => {
+                return Some(format!(
+                    "👍 Approval {approvals} of {required} for {}; waiting for another person.",
+                    request.tool_name
+                ))
+            }
+            Ok(Answer::NotFound) => {
+                return Some(format!("Request {} has expired.", reply_code(&request.id)))
+            }
+            Err(reason) => return Some(format!("⛔ {reason}")),
+        }
+        tracing::info!(
+            request_id = %request.id,
+            approved,
+            "Confirmation answered on {channel} by {sender}"
+        );
+        Some(if approved {
+            format!("✅ Approved: {}", request.tool_name)
+        } else {
+            format!("❌ Denied: {}", request.tool_name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "whatsapp"
+        }
+
+        async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((recipient.to_string(), message.to_string()));
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parses_answers_with_codes() {
+        assert_eq!(parse_reply("yes 3f2a9c"), Some((true, "3f2a9c")));
+        assert_eq!(parse_reply("Nein 3f2a9c"), Some((false, "3f2a9c")));
+        assert_eq!(parse_reply("yes"), None);
+        assert_eq!(parse_reply("yes 3f2"), None);
+        assert_eq!(parse_reply("maybe 3f2a9c"), None);
+        assert_eq!(parse_reply("yes 3f2a9c please"), None);
+    }
+
+    #[tokio::test]
+    async fn requests_are_pushed_and_resolved_from_replies() {
+        let gate = ConfirmationGate::new(5);
+        let channel = Arc::new(RecordingChannel::default());
+        let relay = Arc::new(
+            ConfirmationRelay::new(Arc::clone(&gate))
+                .with_target(channel.clone(), vec!["+491701234567".into()]),
+        );
+        relay.spawn();
+
+        let pending = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move { gate.request("shell", "rm -rf build").await })
+        };
+        let text = loop {
+            if let Some((_, text)) = channel.sent.lock().unwrap().first().cloned() {
+                break text;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert!(text.contains("shell: rm -rf build"));
+        let code = text.split("\"yes ").nth(1).unwrap()[..CODE_LEN].to_string();
+
+        let reply = format!("yes {code}");
+        assert_eq!(
+            relay.handle_reply("whatsapp", "+4930000000", &reply).await,
+            None
+        );
+        assert_eq!(
+            relay
+                .handle_reply("whatsapp", "+491701234567", &reply)
+                .await
+                .as_deref(),
+            Some("✅ Approved: shell")
+        );
+        assert!(pending.await.unwrap());
+        assert!(relay
+            .handle_reply("whatsapp", "+491701234567", &reply)
+            .await
+            .unwrap()
+            .starts_with("No pending request"));
+    }
+
+    #[tokio::test]
+    async fn dashboard_and_chat_approvals_of_one_person_count_once() {
+        use crate::config::ConfirmationConfig;
+        use crate::identity::family::FamilyMember;
+        use crate::security::confirmation::ConfirmationPolicy;
+
+        let gate = ConfirmationGate::new(5);
+        let anna = FamilyMember {
+            name: "Anna".into(),
+            role: UserRole::Adult,
+            channels: [("whatsapp".into(), "+4915111111".into())].into(),
+        };
+        let relay = ConfirmationRelay::new(Arc::clone(&gate))
+            .with_family(Arc::new(FamilyRegistry::new(vec![anna], 5).unwrap()))
+            .with_target(
+                Arc::new(RecordingChannel::default()),
+                vec!["+4915111111".into(), "+491701234567".into()],
+            );
+        let config: ConfirmationConfig =
+            toml::from_str("[tools.vault_reveal]\napprovals = 2").unwrap();
+        let policy = ConfirmationPolicy::for_tool(&config, "vault_reveal");
+        for id in ["anna01", "owner1"] {
+            let gate = Arc::clone(&gate);
+            let policy = policy.clone();
+            tokio::spawn(async move {
+                gate.request_with_id_and_policy(id, "vault_reveal", "Reveal", &policy)
+                    .await
+            });
+        }
+        while gate.pending_count().await < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // Anna on the dashboard, then on her WhatsApp; the owner on the
+        // dashboard, then on a recipient account not in the registry.
+        for (id, member, sender) in [
+            ("anna01", Some("Anna"), "+4915111111"),
+            ("owner1", None, "+491701234567"),
+        ] {
+            let dashboard = gate
+                .resolve_as(id, true, UserRole::Root, &approver_identity(member))
+                .await;
+            assert_eq!(
+                dashboard,
+                Ok(Answer::Partial {
+                    approvals: 1,
+                    required: 2
+                })
+            );
+            let reply = relay
+                .handle_reply("whatsapp", sender, &format!("yes {id}"))
+                .await
+                .unwrap();
+            assert!(reply.contains("already approved"), "{reply}");
+        }
+        let pending = gate.get_pending().await;
+        assert_eq!(pending.len(), 2);
+        assert!(pending.iter().all(|r| r.approved_by.len() == 1));
+    }
+}
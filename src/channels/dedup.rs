@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Processed channel messages, so redelivered ones are not answered twice.
+//!
+//! Meta, Telegram and Discord deliver a message again when they think the
+//! first delivery failed. Every channel handler records the platform's
+//! message ID here before running an agent turn and drops the message if
+//! it was seen within `[channels_config] message_dedup_ttl_secs`. The IDs
+//! are kept in `<workspace>/.mymolt/seen_messages.json`, so a restart in
+//! the middle of a redelivery does not answer it again.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Most IDs kept; the oldest go first.
+const MAX_ENTRIES: usize = 10_000;
+
+/// One store per workspace, shared by the gateway and channel listeners.
+static OPEN: OnceLock<Mutex<HashMap<PathBuf, Arc<SeenMessages>>>> = OnceLock::new();
+
+#[derive(Debug)]
+pub struct SeenMessages {
+    path: PathBuf,
+    ttl: Duration,
+    /// `channel:message_id` → when it was first seen (Unix seconds)
+    seen: Mutex<HashMap<String, u64>>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl SeenMessages {
+    /// The store of `workspace_dir`, opened on first use.
+    pub fn shared(workspace_dir: &Path, ttl: Duration) -> Arc<Self> {
+        let path = workspace_dir.join(".mymolt").join("seen_messages.json");
+        let mut open = OPEN
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(
+            open.entry(path.clone())
+                .or_insert_with(|| Arc::new(Self::load(path, ttl))),
+        )
+    }
+
+    fn load(path: PathBuf, ttl: Duration) -> Self {
+        let seen = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            ttl,
+            seen: Mutex::new(seen),
+        }
+    }
+
+    /// Returns true if `message_id` is new on `channel` and is now recorded.
+    /// Messages without an ID cannot be matched and always count as new.
+    pub fn record_if_new(&self, channel: &str, message_id: &str) -> bool {
+        if message_id.is_empty() {
+            return true;
+        }
+        let now = unix_now();
+        let key = format!("{channel}:{message_id}");
+        let mut seen = self
+            .seen
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        seen.retain(|_, seen_at| now.saturating_sub(*seen_at) < self.ttl.as_secs());
+        if seen.contains_key(&key) {
+            return false;
+        }
+        if seen.len() >= MAX_ENTRIES {
+            if let Some(oldest) = seen
+                .iter()
+                .min_by_key(|(_, seen_at)| **seen_at)
+                .map(|(key, _)| key.clone())
+            {
+                seen.remove(&oldest);
+            }
+        }
+        seen.insert(key, now);
+
+        // Still deduplicated in memory if the file cannot be written
+        if let Err(e) = self.persist(&seen) {
+            tracing::warn!("Failed to save seen message IDs: {e}");
+        }
+        true
+    }
+
+    fn persist(&self, seen: &HashMap<String, u64>) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(seen)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redelivered_messages_are_not_new() {
+        let workspace = tempfile::tempdir().unwrap();
+        let seen = SeenMessages::shared(workspace.path(), Duration::from_mins(5));
+        assert!(seen.record_if_new("whatsapp", "wamid.1"));
+        assert!(!seen.record_if_new("whatsapp", "wamid.1"));
+        // The same ID on another channel is another message
+        assert!(seen.record_if_new("telegram", "wamid.1"));
+        assert!(seen.record_if_new("whatsapp", ""));
+        assert!(seen.record_if_new("whatsapp", ""));
+    }
+
+    #[test]
+    fn seen_ids_survive_a_restart_until_they_expire() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().join(".mymolt").join("seen_messages.json");
+        let seen = SeenMessages::load(path.clone(), Duration::from_mins(5));
+        assert!(seen.record_if_new("discord", "42"));
+
+        let reopened = SeenMessages::load(path.clone(), Duration::from_mins(5));
+        assert!(!reopened.record_if_new("discord", "42"));
+
+        let expired = SeenMessages::load(path, Duration::ZERO);
+        assert!(expired.record_if_new("discord", "42"));
+    }
+
+    #[test]
+    fn workspaces_share_one_store() {
+        let workspace = tempfile::tempdir().unwrap();
+        let gateway = SeenMessages::shared(workspace.path(), Duration::from_mins(5));
+        let listener = SeenMessages::shared(workspace.path(), Duration::from_mins(5));
+        assert!(gateway.record_if_new("telegram", "7"));
+        assert!(!listener.record_if_new("telegram", "7"));
+    }
+}
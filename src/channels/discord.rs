@@ -2,21 +2,54 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use super::traits::{Channel, ChannelMessage};
+use super::traits::{Attachment, Channel, ChannelMessage};
+use crate::util::truncate_with_ellipsis;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
+/// Largest attachment downloaded for the tools (10 MB)
+const MAX_ATTACHMENT_BYTES: u64 = 10 * 1024 * 1024;
+
 /// Discord channel — connects via Gateway WebSocket for real-time messages
 pub struct DiscordChannel {
     bot_token: String,
     guild_id: Option<String>,
     allowed_users: Vec<String>,
     listen_to_bots: bool,
+    allowed_guilds: Vec<String>,
+    allowed_channels: Vec<String>,
+    /// `/ask`, threads and attachments; see [`Self::with_conversations`]
+    conversations: bool,
     client: reqwest::Client,
     typing_handle: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// `/ask` interactions waiting for their answer, by message id
+    interactions: std::sync::Mutex<HashMap<String, PendingInteraction>>,
+    /// Threads the bot opened, mapped to their parent channel
+    bot_threads: std::sync::Mutex<HashMap<String, String>>,
+    commands_registered: AtomicBool,
+}
+
+/// Interaction whose deferred response still has to be edited
+struct PendingInteraction {
+    application_id: String,
+    token: String,
+}
+
+/// An `/ask` slash command invocation
+#[derive(Debug, PartialEq, Eq)]
+struct AskCommand {
+    id: String,
+    application_id: String,
+    token: String,
+    user_id: String,
+    guild_id: Option<String>,
+    channel_id: String,
+    question: String,
 }
 
 impl DiscordChannel {
@@ -31,11 +64,34 @@ impl DiscordChannel {
             guild_id,
             allowed_users,
             listen_to_bots,
+            allowed_guilds: Vec::new(),
+            allowed_channels: Vec::new(),
+            conversations: false,
             client: reqwest::Client::new(),
             typing_handle: std::sync::Mutex::new(None),
+            interactions: std::sync::Mutex::new(HashMap::new()),
+            bot_threads: std::sync::Mutex::new(HashMap::new()),
+            commands_registered: AtomicBool::new(false),
         }
     }
 
+    /// Only answer in these guilds (besides `guild_id`) and guild channels.
+    /// Empty lists allow everything.
+    pub fn with_allowlists(mut self, guilds: Vec<String>, channels: Vec<String>) -> Self {
+        self.allowed_guilds = guilds;
+        self.allowed_channels = channels;
+        self
+    }
+
+    /// Register and answer the `/ask` slash command, open a thread for each
+    /// guild message outside the bot's threads so every conversation has its
+    /// own `sender`, and download attachments up to 10 MB. Answers go
+    /// through [`Self::respond`].
+    pub fn with_conversations(mut self) -> Self {
+        self.conversations = true;
+        self
+    }
+
     /// Check if a Discord user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
@@ -43,6 +99,360 @@ impl DiscordChannel {
         self.allowed_users.iter().any(|u| u == "*" || u == user_id)
     }
 
+    /// Check a message's guild and channel against the allowlists. Direct
+    /// messages have no guild and are only subject to the user allowlist.
+    fn is_place_allowed(&self, guild_id: Option<&str>, channel_id: &str) -> bool {
+        let Some(guild) = guild_id else {
+            return true;
+        };
+        let guild_allowed = (self.guild_id.is_none() && self.allowed_guilds.is_empty())
+            || self.guild_id.as_deref() == Some(guild)
+            || self.allowed_guilds.iter().any(|g| g == guild);
+        if !guild_allowed {
+            return false;
+        }
+        if self.allowed_channels.is_empty() {
+            return true;
+        }
+        let parent = self
+            .bot_threads
+            .lock()
+            .ok()
+            .and_then(|threads| threads.get(channel_id).cloned());
+        self.allowed_channels
+            .iter()
+            .any(|c| c == channel_id || parent.as_deref() == Some(c.as_str()))
+    }
+
+    fn is_bot_thread(&self, channel_id: &str) -> bool {
+        self.bot_threads
+            .lock()
+            .is_ok_and(|threads| threads.contains_key(channel_id))
+    }
+
+    /// Send an authorized request and return its JSON body (`null` when empty).
+    async fn api(
+        &self,
+        request: reqwest::RequestBuilder,
+        what: &str,
+    ) -> anyhow::Result<serde_json::Value> {
+        let resp = request
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if !status.is_success() {
+            anyhow::bail!("Discord {what} failed ({status}): {body}");
+        }
+        Ok(serde_json::from_str(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Register `/ask` in the configured guilds, or globally without any.
+    async fn register_commands(&self, application_id: &str) -> anyhow::Result<()> {
+        let commands = json!([{
+            "name": "ask",
+            "description": "Ask MyMolt a question",
+            "options": [{
+                "type": 3,
+                "name": "question",
+                "description": "What you want to know",
+                "required": true
+            }]
+        }]);
+        let guilds: Vec<&String> = self.guild_id.iter().chain(&self.allowed_guilds).collect();
+        let urls: Vec<String> = if guilds.is_empty() {
+            vec![format!(
+                "https://discord.com/api/v10/applications/{application_id}/commands"
+            )]
+        } else {
+            guilds
+                .iter()
+                .map(|g| {
+                    format!(
+                        "https://discord.com/api/v10/applications/{application_id}/guilds/{g}/commands"
+                    )
+                })
+                .collect()
+        };
+        for url in urls {
+            self.api(
+                self.client.put(&url).json(&commands),
+                "command registration",
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn on_ready(&self, d: &serde_json::Value) {
+        if !self.conversations || self.commands_registered.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(application_id) = d
+            .get("application")
+            .and_then(|a| a.get("id"))
+            .and_then(serde_json::Value::as_str)
+        else {
+            return;
+        };
+        match self.register_commands(application_id).await {
+            Ok(()) => {
+                self.commands_registered.store(true, Ordering::Relaxed);
+                tracing::info!("Discord: registered /ask");
+            }
+            Err(e) => tracing::warn!("Discord: {e}"),
+        }
+    }
+
+    async fn interaction_callback(
+        &self,
+        command: &AskCommand,
+        body: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let url = format!(
+            "https://discord.com/api/v10/interactions/{}/{}/callback",
+            command.id, command.token
+        );
+        self.api(self.client.post(&url).json(&body), "interaction callback")
+            .await
+            .map(drop)
+    }
+
+    /// Acknowledge an `/ask` from an allowed user; the answer follows
+    /// through [`Self::respond`].
+    async fn handle_interaction(&self, d: &serde_json::Value) -> Option<ChannelMessage> {
+        if !self.conversations {
+            return None;
+        }
+        let command = parse_ask_command(d)?;
+        if !self.is_user_allowed(&command.user_id)
+            || !self.is_place_allowed(command.guild_id.as_deref(), &command.channel_id)
+        {
+            tracing::warn!(
+                "Discord: ignoring /ask from unauthorized user: {}",
+                command.user_id
+            );
+            // Ephemeral, so only the caller sees it
+            let denied = json!({
+                "type": 4,
+                "data": { "content": "You are not allowed to use this bot here.", "flags": 64 }
+            });
+            let _ = self.interaction_callback(&command, denied).await;
+            return None;
+        }
+        // Discord wants an answer within three seconds; "thinking…" buys 15 minutes
+        if let Err(e) = self
+            .interaction_callback(&command, json!({ "type": 5 }))
+            .await
+        {
+            tracing::warn!("Discord: {e}");
+            return None;
+        }
+        if let Ok(mut pending) = self.interactions.lock() {
+            pending.insert(
+                command.id.clone(),
+                PendingInteraction {
+                    application_id: command.application_id,
+                    token: command.token,
+                },
+            );
+        }
+        Some(ChannelMessage {
+            id: command.id,
+            sender: command.channel_id,
+            content: command.question,
+            channel: "discord".to_string(),
+            timestamp: unix_now(),
+            attachments: Vec::new(),
+            group: None,
+        })
+    }
+
+    async fn handle_message_create(
+        &self,
+        d: &serde_json::Value,
+        bot_user_id: &str,
+    ) -> Option<ChannelMessage> {
+        let author = d.get("author");
+        // Skip messages from the bot itself
+        let author_id = author
+            .and_then(|a| a.get("id"))
+            .and_then(|i| i.as_str())
+            .unwrap_or("");
+        if author_id == bot_user_id {
+            return None;
+        }
+
+        // Skip bot messages (unless listen_to_bots is enabled)
+        let from_bot = author
+            .and_then(|a| a.get("bot"))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        if from_bot && !self.listen_to_bots {
+            return None;
+        }
+
+        // Sender validation
+        if !self.is_user_allowed(author_id) {
+            tracing::warn!("Discord: ignoring message from unauthorized user: {author_id}");
+            return None;
+        }
+
+        let guild_id = d.get("guild_id").and_then(serde_json::Value::as_str);
+        let mut channel_id = d
+            .get("channel_id")
+            .and_then(|c| c.as_str())
+            .unwrap_or("")
+            .to_string();
+        if !self.is_place_allowed(guild_id, &channel_id) {
+            return None;
+        }
+
+        let message_id = d.get("id").and_then(|i| i.as_str()).unwrap_or("");
+        let content = d.get("content").and_then(|c| c.as_str()).unwrap_or("");
+        let attachments = if self.conversations {
+            self.download_attachments(d).await
+        } else {
+            Vec::new()
+        };
+        if content.is_empty() && attachments.is_empty() {
+            return None;
+        }
+
+        if self.conversations && guild_id.is_some() && !self.is_bot_thread(&channel_id) {
+            match self.open_thread(&channel_id, message_id, content).await {
+                Ok(thread_id) => channel_id = thread_id,
+                // E.g. the message already is in a thread; answer in place
+                Err(e) => tracing::debug!("Discord: no thread opened: {e}"),
+            }
+        }
+
+        Some(ChannelMessage {
+            id: if message_id.is_empty() {
+                Uuid::new_v4().to_string()
+            } else {
+                message_id.to_string()
+            },
+            sender: channel_id,
+            content: content.to_string(),
+            channel: "discord".to_string(),
+            timestamp: unix_now(),
+            attachments,
+            group: None,
+        })
+    }
+
+    /// Open a thread on a guild message and remember it as the bot's.
+    async fn open_thread(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        content: &str,
+    ) -> anyhow::Result<String> {
+        let url = format!(
+            "https://discord.com/api/v10/channels/{channel_id}/messages/{message_id}/threads"
+        );
+        let name = if content.trim().is_empty() {
+            "Attachment".to_string()
+        } else {
+            // Thread names are limited to 100 characters
+            truncate_with_ellipsis(content.trim(), 90)
+        };
+        let body = json!({ "name": name, "auto_archive_duration": 1440 });
+        let thread = self
+            .api(self.client.post(&url).json(&body), "thread creation")
+            .await?;
+        let thread_id = thread
+            .get("id")
+            .and_then(|i| i.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Discord thread creation returned no id"))?
+            .to_string();
+        if let Ok(mut threads) = self.bot_threads.lock() {
+            threads.insert(thread_id.clone(), channel_id.to_string());
+        }
+        Ok(thread_id)
+    }
+
+    async fn download_attachments(&self, d: &serde_json::Value) -> Vec<Attachment> {
+        let mut attachments = Vec::new();
+        let listed = d
+            .get("attachments")
+            .and_then(serde_json::Value::as_array)
+            .map_or(&[][..], Vec::as_slice);
+        for item in listed {
+            let size = item
+                .get("size")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0);
+            let file_name = item
+                .get("filename")
+                .and_then(|f| f.as_str())
+                .unwrap_or("attachment");
+            if size > MAX_ATTACHMENT_BYTES {
+                tracing::warn!("Discord: skipping attachment {file_name} ({size} bytes)");
+                continue;
+            }
+            let Some(url) = item.get("url").and_then(|u| u.as_str()) else {
+                continue;
+            };
+            let download = async {
+                self.client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .bytes()
+                    .await
+            };
+            match download.await {
+                Ok(data) => attachments.push(Attachment {
+                    mime_type: item
+                        .get("content_type")
+                        .and_then(|c| c.as_str())
+                        .unwrap_or("application/octet-stream")
+                        .to_string(),
+                    data: data.to_vec(),
+                    file_name: Some(file_name.to_string()),
+                }),
+                Err(e) => tracing::warn!("Discord: failed to download {file_name}: {e}"),
+            }
+        }
+        attachments
+    }
+
+    /// Answer `msg`: by editing its `/ask` response when it came from one,
+    /// otherwise as a message in its channel or thread.
+    pub async fn respond(&self, msg: &ChannelMessage, reply: &str) -> anyhow::Result<()> {
+        let pending = self
+            .interactions
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(&msg.id));
+        let Some(pending) = pending else {
+            return self.send(reply, &msg.sender).await;
+        };
+        let webhook = format!(
+            "https://discord.com/api/v10/webhooks/{}/{}",
+            pending.application_id, pending.token
+        );
+        for (i, chunk) in split_message_for_discord(reply).iter().enumerate() {
+            let body = json!({ "content": chunk });
+            let request = if i == 0 {
+                self.client
+                    .patch(format!("{webhook}/messages/@original"))
+                    .json(&body)
+            } else {
+                self.client.post(&webhook).json(&body)
+            };
+            self.api(request, "interaction response").await?;
+        }
+        Ok(())
+    }
+
     fn bot_user_id_from_token(token: &str) -> Option<String> {
         // Discord bot tokens are base64(bot_user_id).timestamp.hmac
         let part = token.split('.').next()?;
@@ -50,6 +460,46 @@ impl DiscordChannel {
     }
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Read an `/ask` invocation from an `INTERACTION_CREATE` payload.
+fn parse_ask_command(d: &serde_json::Value) -> Option<AskCommand> {
+    let str_at = |v: &serde_json::Value, key: &str| v.get(key)?.as_str().map(str::to_string);
+    // Type 2: application command
+    if d.get("type").and_then(serde_json::Value::as_u64) != Some(2) {
+        return None;
+    }
+    let data = d.get("data")?;
+    if data.get("name").and_then(|n| n.as_str()) != Some("ask") {
+        return None;
+    }
+    let question = data
+        .get("options")?
+        .as_array()?
+        .iter()
+        .find(|o| o.get("name").and_then(|n| n.as_str()) == Some("question"))
+        .and_then(|o| str_at(o, "value"))?;
+    // Guild invocations carry the user in `member`, DMs at the top level
+    let user = d
+        .get("member")
+        .and_then(|m| m.get("user"))
+        .or_else(|| d.get("user"))?;
+    Some(AskCommand {
+        id: str_at(d, "id")?,
+        application_id: str_at(d, "application_id")?,
+        token: str_at(d, "token")?,
+        user_id: str_at(user, "id")?,
+        guild_id: str_at(d, "guild_id"),
+        channel_id: str_at(d, "channel_id")?,
+        question,
+    })
+}
+
 const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 
 /// Discord's maximum message length for regular messages.
@@ -251,8 +701,6 @@ impl Channel for DiscordChannel {
             }
         });
 
-        let guild_filter = self.guild_id.clone();
-
         loop {
             tokio::select! {
                 _ = hb_rx.recv() => {
@@ -304,60 +752,22 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
-                    if event_type != "MESSAGE_CREATE" {
-                        continue;
-                    }
-
                     let Some(d) = event.get("d") else {
                         continue;
                     };
 
-                    // Skip messages from the bot itself
-                    let author_id = d.get("author").and_then(|a| a.get("id")).and_then(|i| i.as_str()).unwrap_or("");
-                    if author_id == bot_user_id {
-                        continue;
-                    }
-
-                    // Skip bot messages (unless listen_to_bots is enabled)
-                    if !self.listen_to_bots && d.get("author").and_then(|a| a.get("bot")).and_then(serde_json::Value::as_bool).unwrap_or(false) {
-                        continue;
-                    }
-
-                    // Sender validation
-                    if !self.is_user_allowed(author_id) {
-                        tracing::warn!("Discord: ignoring message from unauthorized user: {author_id}");
-                        continue;
-                    }
-
-                    // Guild filter
-                    if let Some(ref gid) = guild_filter {
-                        let msg_guild = d.get("guild_id").and_then(serde_json::Value::as_str);
-                        // DMs have no guild_id — let them through; for guild messages, enforce the filter
-                        if let Some(g) = msg_guild {
-                            if g != gid {
-                                continue;
-                            }
+                    let channel_msg = match event_type {
+                        "READY" => {
+                            self.on_ready(d).await;
+                            continue;
                         }
-                    }
-
-                    let content = d.get("content").and_then(|c| c.as_str()).unwrap_or("");
-                    if content.is_empty() {
+                        "MESSAGE_CREATE" => self.handle_message_create(d, &bot_user_id).await,
+                        "INTERACTION_CREATE" => self.handle_interaction(d).await,
+                        _ => continue,
+                    };
+                    let Some(channel_msg) = channel_msg else {
                         continue;
-                    }
-
-                    let channel_id = d.get("channel_id").and_then(|c| c.as_str()).unwrap_or("").to_string();
-
-                    let channel_msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        sender: channel_id,
-                        content: content.to_string(),
-                        channel: "discord".to_string(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -493,6 +903,58 @@ mod tests {
         assert!(!ch.is_user_allowed("Abc"));
     }
 
+    #[test]
+    fn guild_and_channel_allowlists() {
+        let ch = DiscordChannel::new("fake".into(), Some("g1".into()), vec![], false)
+            .with_allowlists(vec!["g2".into()], vec!["c1".into()]);
+        assert!(ch.is_place_allowed(Some("g1"), "c1"));
+        assert!(ch.is_place_allowed(Some("g2"), "c1"));
+        assert!(!ch.is_place_allowed(Some("g3"), "c1"));
+        assert!(!ch.is_place_allowed(Some("g1"), "c2"));
+        // Direct messages are only subject to the user allowlist
+        assert!(ch.is_place_allowed(None, "dm"));
+
+        // The bot's threads count as their parent channel
+        ch.bot_threads
+            .lock()
+            .unwrap()
+            .insert("t1".into(), "c1".into());
+        assert!(ch.is_place_allowed(Some("g1"), "t1"));
+
+        let open = DiscordChannel::new("fake".into(), None, vec![], false);
+        assert!(open.is_place_allowed(Some("any"), "any"));
+    }
+
+    #[test]
+    fn ask_command_is_parsed_from_guilds_and_dms() {
+        let guild = json!({
+            "type": 2,
+            "id": "i1",
+            "application_id": "app",
+            "token": "tok",
+            "guild_id": "g1",
+            "channel_id": "c1",
+            "member": { "user": { "id": "u1" } },
+            "data": { "name": "ask", "options": [{ "name": "question", "value": "Hi?" }] }
+        });
+        let command = parse_ask_command(&guild).unwrap();
+        assert_eq!(command.user_id, "u1");
+        assert_eq!(command.guild_id.as_deref(), Some("g1"));
+        assert_eq!(command.question, "Hi?");
+
+        let mut dm = guild.clone();
+        dm.as_object_mut().unwrap().remove("member");
+        dm.as_object_mut().unwrap().remove("guild_id");
+        dm["user"] = json!({ "id": "u2" });
+        let command = parse_ask_command(&dm).unwrap();
+        assert_eq!(command.user_id, "u2");
+        assert!(command.guild_id.is_none());
+
+        let mut other = guild;
+        other["data"]["name"] = json!("help");
+        assert!(parse_ask_command(&other).is_none());
+    }
+
     #[test]
     fn base64_decode_empty_string() {
         let decoded = base64_decode("");
@@ -160,7 +160,7 @@ impl EmailChannel {
     }
 
     /// Extract readable text from a parsed email
-    fn extract_text(parsed: &mail_parser::Message) -> String {
+    pub(crate) fn extract_text(parsed: &mail_parser::Message) -> String {
         if let Some(text) = parsed.body_text(0) {
             return text.to_string();
         }
@@ -181,7 +181,7 @@ impl EmailChannel {
         "(no readable content)".to_string()
     }
 
-    fn build_imap_tls_config() -> Result<std::sync::Arc<tokio_rustls::rustls::ClientConfig>> {
+    pub(crate) fn build_imap_tls_config() -> Result<std::sync::Arc<tokio_rustls::rustls::ClientConfig>> {
         use rustls::ClientConfig as TlsConfig;
         use std::sync::Arc;
         use tokio_rustls::rustls;
@@ -432,6 +432,8 @@ impl Channel for EmailChannel {
                             content,
                             channel: "email".to_string(),
                             timestamp: ts,
+                            attachments: Vec::new(),
+                            group: None,
                         };
                         if tx.send(msg).await.is_err() {
                             return Ok(());
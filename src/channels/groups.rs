@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! When MyMolt answers in a group chat, and in which role.
+//!
+//! Channels mark messages posted in a group with a [`GroupMessage`]. Only
+//! groups listed in `[[channels_config.groups]]` are answered, and only
+//! messages addressed to MyMolt — a mention, a reply to it or the group's
+//! trigger word — from members with a role there. A group shares one
+//! session and one memory scope; confirmation requests are not answered
+//! from groups.
+//!
+//! [`GroupMessage`]: super::traits::GroupMessage
+
+use super::traits::ChannelMessage;
+use crate::config::GroupChatConfig;
+use crate::identity::family::{group_scope, role_from_config, FamilyRegistry};
+use crate::identity::UserRole;
+
+/// A group message MyMolt answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupTurn {
+    /// The message without the trigger word
+    pub content: String,
+    /// The author's role in the group
+    pub role: UserRole,
+    /// Memory scope of the group
+    pub scope: String,
+}
+
+/// The turn to run for group message `msg`, or `None` when MyMolt stays
+/// quiet. `msg.sender` is the group.
+pub fn group_turn(
+    groups: &[GroupChatConfig],
+    family: &FamilyRegistry,
+    msg: &ChannelMessage,
+) -> Option<GroupTurn> {
+    let posted = msg.group.as_ref()?;
+    let Some(group) = groups
+        .iter()
+        .find(|g| g.channel.eq_ignore_ascii_case(&msg.channel) && g.id == msg.sender)
+    else {
+        tracing::debug!(
+            "Ignoring message in unlisted {} group {}",
+            msg.channel,
+            msg.sender
+        );
+        return None;
+    };
+
+    let content = match strip_trigger(&msg.content, group.trigger.as_deref()) {
+        Some(rest) => rest,
+        None if posted.mentioned => msg.content.trim(),
+        None => return None,
+    };
+    let role = if group.roles.is_empty() {
+        family.resolve(&msg.channel, &posted.author)?.role
+    } else {
+        role_from_config(group.roles.get(&posted.author)?)
+    };
+    if content.is_empty() {
+        return None;
+    }
+    Some(GroupTurn {
+        content: content.to_string(),
+        role,
+        scope: group_scope(&msg.channel, &msg.sender),
+    })
+}
+
+/// `text` after a leading `trigger` word ("molt, hi" → "hi"), if it starts
+/// with one.
+fn strip_trigger<'a>(text: &'a str, trigger: Option<&str>) -> Option<&'a str> {
+    let trigger = trigger.map(str::trim).filter(|t| !t.is_empty())?;
+    let text = text.trim_start();
+    if !text.get(..trigger.len())?.eq_ignore_ascii_case(trigger) {
+        return None;
+    }
+    let rest = &text[trigger.len()..];
+    // A whole word: "molt, hi" but not "molten lava"
+    if rest.chars().next().is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    Some(
+        rest.trim_start_matches(|c: char| c.is_whitespace() || ",:;!".contains(c))
+            .trim_end(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::GroupMessage;
+    use crate::identity::family::FamilyMember;
+    use std::collections::HashMap;
+
+    fn family_group() -> GroupChatConfig {
+        GroupChatConfig {
+            channel: "telegram".into(),
+            id: "-10042".into(),
+            trigger: Some("molt".into()),
+            roles: [("1".into(), "adult".into()), ("2".into(), "child".into())].into(),
+        }
+    }
+
+    fn posted(author: &str, content: &str, mentioned: bool) -> ChannelMessage {
+        ChannelMessage {
+            id: "7".into(),
+            sender: "-10042".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+            group: Some(GroupMessage {
+                author: author.into(),
+                mentioned,
+            }),
+        }
+    }
+
+    #[test]
+    fn answers_mentions_and_the_trigger_word_only() {
+        let groups = [family_group()];
+        let family = FamilyRegistry::empty();
+
+        let turn = group_turn(
+            &groups,
+            &family,
+            &posted("1", "Molt, what's for dinner?", false),
+        );
+        assert_eq!(
+            turn,
+            Some(GroupTurn {
+                content: "what's for dinner?".into(),
+                role: UserRole::Adult,
+                scope: "group:telegram:-10042".into(),
+            })
+        );
+        assert!(group_turn(&groups, &family, &posted("1", "what's for dinner?", true)).is_some());
+        assert!(group_turn(&groups, &family, &posted("1", "what's for dinner?", false)).is_none());
+        assert!(group_turn(&groups, &family, &posted("1", "molten cheese", false)).is_none());
+        assert!(group_turn(&groups, &family, &posted("1", "molt", false)).is_none());
+    }
+
+    #[test]
+    fn members_get_their_group_role() {
+        let groups = [family_group()];
+        let family = FamilyRegistry::empty();
+        let child = group_turn(&groups, &family, &posted("2", "molt hi", false)).unwrap();
+        assert_eq!(child.role, UserRole::Child);
+        // Not listed in the group
+        assert!(group_turn(&groups, &family, &posted("3", "molt hi", false)).is_none());
+    }
+
+    #[test]
+    fn without_roles_family_members_talk_in_their_family_role() {
+        let groups = [GroupChatConfig {
+            roles: HashMap::new(),
+            ..family_group()
+        }];
+        let family = FamilyRegistry::new(
+            vec![FamilyMember {
+                name: "Oma Helga".into(),
+                role: UserRole::Senior,
+                channels: [("telegram".into(), "3".into())].into(),
+            }],
+            8,
+        )
+        .unwrap();
+        let turn = group_turn(&groups, &family, &posted("3", "hi", true)).unwrap();
+        assert_eq!(turn.role, UserRole::Senior);
+        assert!(group_turn(&groups, &family, &posted("1", "hi", true)).is_none());
+    }
+
+    #[test]
+    fn unlisted_groups_and_direct_messages_are_not_group_turns() {
+        let family = FamilyRegistry::empty();
+        assert!(group_turn(&[], &family, &posted("1", "molt hi", true)).is_none());
+        let direct = ChannelMessage {
+            group: None,
+            ..posted("1", "molt hi", true)
+        };
+        assert!(group_turn(&[family_group()], &family, &direct).is_none());
+    }
+}
@@ -6,11 +6,23 @@ use crate::channels::traits::{Channel, ChannelMessage};
 use async_trait::async_trait;
 use directories::UserDirs;
 use rusqlite::{Connection, OpenFlags};
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::mpsc;
 
+/// Health registry entry of the listener (see `spawn_supervised_listener`).
+const COMPONENT: &str = "channel:imessage";
+
+/// Set while macOS refuses to let us control Messages, cleared by the
+/// next reply that goes out.
+static AUTOMATION_DENIED: AtomicBool = AtomicBool::new(false);
+
 /// iMessage channel using macOS `AppleScript` bridge.
 /// Polls the Messages database for new messages and sends replies via `osascript`.
+///
+/// Missing permissions are reported as [`SetupIssue`]s on the
+/// `channel:imessage` component of `/health`.
 #[derive(Clone)]
 pub struct IMessageChannel {
     allowed_contacts: Vec<String>,
@@ -35,6 +47,83 @@ impl IMessageChannel {
     }
 }
 
+/// Something the bridge needs from macOS but does not have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetupIssue {
+    NotMacOs,
+    NoDatabase(PathBuf),
+    /// `chat.db` exists but cannot be read, nearly always for lack of Full
+    /// Disk Access
+    Unreadable(PathBuf, String),
+    /// Messages refused our Apple events (`-1743`)
+    NoAutomation,
+}
+
+impl fmt::Display for SetupIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotMacOs => write!(f, "iMessage needs macOS"),
+            Self::NoDatabase(path) => write!(
+                f,
+                "Messages database not found at {}; sign in to Messages.app first",
+                path.display()
+            ),
+            Self::Unreadable(path, error) => write!(
+                f,
+                "cannot read {} ({error}); grant Full Disk Access to the app running MyMolt \
+                 in System Settings → Privacy & Security → Full Disk Access",
+                path.display()
+            ),
+            Self::NoAutomation => write!(
+                f,
+                "not allowed to control Messages; allow it in System Settings → \
+                 Privacy & Security → Automation"
+            ),
+        }
+    }
+}
+
+fn chat_db_path() -> Option<PathBuf> {
+    UserDirs::new().map(|u| u.home_dir().join("Library/Messages/chat.db"))
+}
+
+/// Whether `osascript` failed because macOS denied us Messages automation.
+fn is_automation_denied(stderr: &str) -> bool {
+    stderr.contains("-1743") || stderr.contains("Not authorized to send Apple events")
+}
+
+/// Check what the bridge needs: a readable `chat.db` and permission to
+/// control Messages. Empty when everything is in place.
+pub async fn diagnose() -> Vec<SetupIssue> {
+    if !cfg!(target_os = "macos") {
+        return vec![SetupIssue::NotMacOs];
+    }
+    let mut issues = Vec::new();
+    match chat_db_path() {
+        Some(path) if !path.exists() => issues.push(SetupIssue::NoDatabase(path)),
+        Some(path) => {
+            if let Err(e) = get_max_rowid(&path).await {
+                issues.push(SetupIssue::Unreadable(path, e.to_string()));
+            }
+        }
+        None => issues.push(SetupIssue::NoDatabase(PathBuf::from(
+            "~/Library/Messages/chat.db",
+        ))),
+    }
+
+    let output = tokio::process::Command::new("osascript")
+        .arg("-e")
+        .arg(r#"tell application "Messages" to get name"#)
+        .output()
+        .await;
+    if let Ok(output) = output {
+        if is_automation_denied(&String::from_utf8_lossy(&output.stderr)) {
+            issues.push(SetupIssue::NoAutomation);
+        }
+    }
+    issues
+}
+
 /// Escape a string for safe interpolation into `AppleScript`.
 ///
 /// This prevents injection attacks by escaping:
@@ -128,27 +217,39 @@ end tell"#
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_automation_denied(&stderr) {
+                AUTOMATION_DENIED.store(true, Ordering::Relaxed);
+                crate::health::mark_component_error(COMPONENT, SetupIssue::NoAutomation);
+                anyhow::bail!("iMessage send failed: {}", SetupIssue::NoAutomation);
+            }
             anyhow::bail!("iMessage send failed: {stderr}");
         }
 
+        if AUTOMATION_DENIED.swap(false, Ordering::Relaxed) {
+            crate::health::mark_component_ok(COMPONENT);
+        }
         Ok(())
     }
 
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         tracing::info!("iMessage channel listening (AppleScript bridge)...");
 
+        // Without chat.db there is nothing to poll; without automation we
+        // still read messages but cannot answer them
+        let issues = diagnose().await;
+        if let Some(issue) = issues.iter().find(|i| **i != SetupIssue::NoAutomation) {
+            anyhow::bail!("{issue}");
+        }
+        if !issues.is_empty() {
+            tracing::warn!("iMessage: {}", SetupIssue::NoAutomation);
+            AUTOMATION_DENIED.store(true, Ordering::Relaxed);
+            crate::health::mark_component_error(COMPONENT, SetupIssue::NoAutomation);
+        }
+
         // Query the Messages SQLite database for new messages
         // The database is at ~/Library/Messages/chat.db
-        let db_path = UserDirs::new()
-            .map(|u| u.home_dir().join("Library/Messages/chat.db"))
-            .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-
-        if !db_path.exists() {
-            anyhow::bail!(
-                "Messages database not found at {}. Ensure Messages.app is set up and Full Disk Access is granted.",
-                db_path.display()
-            );
-        }
+        let db_path =
+            chat_db_path().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
 
         // Track the last ROWID we've seen
         let mut last_rowid = get_max_rowid(&db_path).await.unwrap_or(0);
@@ -182,6 +283,8 @@ end tell"#
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
                                 .as_secs(),
+                            attachments: Vec::new(),
+                            group: None,
                         };
 
                         if tx.send(msg).await.is_err() {
@@ -197,15 +300,7 @@ end tell"#
     }
 
     async fn health_check(&self) -> bool {
-        if !cfg!(target_os = "macos") {
-            return false;
-        }
-
-        let db_path = UserDirs::new()
-            .map(|u| u.home_dir().join("Library/Messages/chat.db"))
-            .unwrap_or_default();
-
-        db_path.exists()
+        diagnose().await.is_empty()
     }
 }
 
@@ -226,6 +321,32 @@ async fn get_max_rowid(db_path: &Path) -> anyhow::Result<i64> {
     Ok(result)
 }
 
+/// Text of an `attributedBody`, the archived `NSAttributedString` macOS 13
+/// and later often store instead of `text`.
+///
+/// The string follows the `NSString` class name: a few bytes of class
+/// info, `+`, then its UTF-8 length (one byte, or `0x81` and two bytes
+/// little-endian, or `0x82` and four) and the bytes themselves.
+fn attributed_body_text(blob: &[u8]) -> Option<String> {
+    const CLASS: &[u8] = b"NSString";
+    let start = blob.windows(CLASS.len()).position(|w| w == CLASS)? + CLASS.len();
+    let rest = blob.get(start..)?;
+    let plus = rest.iter().take(8).position(|&b| b == b'+')?;
+    let rest = rest.get(plus + 1..)?;
+    let (len, rest) = match *rest.first()? {
+        0x81 => (
+            usize::from(u16::from_le_bytes(rest.get(1..3)?.try_into().ok()?)),
+            rest.get(3..)?,
+        ),
+        0x82 => (
+            usize::try_from(u32::from_le_bytes(rest.get(1..5)?.try_into().ok()?)).ok()?,
+            rest.get(5..)?,
+        ),
+        len => (usize::from(len), rest.get(1..)?),
+    };
+    String::from_utf8(rest.get(..len)?.to_vec()).ok()
+}
+
 /// Fetch messages newer than `since_rowid`.
 /// Uses rusqlite with parameterized queries for security (CWE-89 prevention).
 /// The `since_rowid` parameter is bound safely, preventing SQL injection.
@@ -241,21 +362,25 @@ async fn fetch_new_messages(
                 OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
             )?;
             let mut stmt = conn.prepare(
-                "SELECT m.ROWID, h.id, m.text \
+                "SELECT m.ROWID, h.id, m.text, m.attributedBody \
              FROM message m \
              JOIN handle h ON m.handle_id = h.ROWID \
              WHERE m.ROWID > ?1 \
              AND m.is_from_me = 0 \
-             AND m.text IS NOT NULL \
+             AND (m.text IS NOT NULL OR m.attributedBody IS NOT NULL) \
              ORDER BY m.ROWID ASC \
              LIMIT 20",
             )?;
             let rows = stmt.query_map([since_rowid], |row| {
-                Ok((
-                    row.get::<_, i64>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                ))
+                let text = match row.get::<_, Option<String>>(2)? {
+                    Some(text) => text,
+                    None => row
+                        .get::<_, Option<Vec<u8>>>(3)?
+                        .as_deref()
+                        .and_then(attributed_body_text)
+                        .unwrap_or_default(),
+                };
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, text))
             })?;
             rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
         })
@@ -558,6 +683,7 @@ mod tests {
                 ROWID INTEGER PRIMARY KEY,
                 handle_id INTEGER,
                 text TEXT,
+                attributedBody BLOB,
                 is_from_me INTEGER DEFAULT 0,
                 FOREIGN KEY (handle_id) REFERENCES handle(ROWID)
             );",
@@ -929,4 +1055,94 @@ mod tests {
         let result = fetch_new_messages(&db_path, i64::MAX - 1).await.unwrap();
         assert!(result.is_empty());
     }
+
+    // ══════════════════════════════════════════════════════════
+    // macOS 13+ attributedBody and Setup Diagnostics
+    // ══════════════════════════════════════════════════════════
+
+    /// An `attributedBody` as Messages archives it, cut down to the string.
+    fn attributed_body(text: &str) -> Vec<u8> {
+        let mut blob = b"\x04\x0bstreamtyped\x81\xe8\x03\x84\x01@\x84\x84\x84\x12NSAttributedString\x00\x84\x84\x08NSObject\x00\x85\x92\x84\x84\x84\x08NSString\x01\x94\x84\x01+".to_vec();
+        let len = text.len();
+        if len < 0x80 {
+            blob.push(u8::try_from(len).unwrap());
+        } else {
+            blob.push(0x81);
+            blob.extend_from_slice(&u16::try_from(len).unwrap().to_le_bytes());
+        }
+        blob.extend_from_slice(text.as_bytes());
+        blob.extend_from_slice(b"\x86\x84\x02iI\x01");
+        blob
+    }
+
+    #[test]
+    fn attributed_body_text_short_and_long() {
+        assert_eq!(
+            attributed_body_text(&attributed_body("Hi 🦀")).as_deref(),
+            Some("Hi 🦀")
+        );
+        let long = "x".repeat(300);
+        assert_eq!(attributed_body_text(&attributed_body(&long)), Some(long));
+    }
+
+    #[test]
+    fn attributed_body_text_rejects_garbage() {
+        assert_eq!(attributed_body_text(b""), None);
+        assert_eq!(attributed_body_text(b"no string class here"), None);
+        // Length runs past the end of the blob
+        assert_eq!(
+            attributed_body_text(b"NSString\x01\x94\x84\x01+\x40abc"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_new_messages_reads_attributed_body() {
+        let (_dir, db_path) = create_test_db();
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute(
+                "INSERT INTO handle (ROWID, id) VALUES (1, '+1234567890')",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO message (ROWID, handle_id, text, attributedBody, is_from_me) VALUES (10, 1, NULL, ?1, 0)",
+                [attributed_body("Sent from Ventura")],
+            )
+            .unwrap();
+        }
+
+        let result = fetch_new_messages(&db_path, 0).await.unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].2, "Sent from Ventura");
+    }
+
+    #[test]
+    fn automation_denial_is_recognised() {
+        assert!(is_automation_denied(
+            "execution error: Not authorized to send Apple events to Messages. (-1743)"
+        ));
+        assert!(!is_automation_denied(
+            "execution error: Can't get participant \"+1555\". (-1728)"
+        ));
+    }
+
+    #[test]
+    fn setup_issues_say_how_to_fix_them() {
+        let unreadable = SetupIssue::Unreadable(
+            PathBuf::from("/Users/a/Library/Messages/chat.db"),
+            "unable to open database file".into(),
+        );
+        assert!(unreadable.to_string().contains("Full Disk Access"));
+        assert!(SetupIssue::NoAutomation.to_string().contains("Automation"));
+    }
+
+    #[tokio::test]
+    async fn diagnose_outside_macos() {
+        if !cfg!(target_os = "macos") {
+            assert_eq!(diagnose().await, [SetupIssue::NotMacOs]);
+        }
+    }
 }
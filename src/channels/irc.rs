@@ -565,6 +565,8 @@ impl Channel for IrcChannel {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_secs(),
+                        attachments: Vec::new(),
+                        group: None,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
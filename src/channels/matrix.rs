@@ -2,14 +2,26 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::channels::traits::{Channel, ChannelMessage};
+use crate::channels::traits::{Attachment, Channel, ChannelMessage};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
-use tokio::sync::mpsc;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, OnceCell};
+
+/// Largest media file downloaded from the homeserver (10 MB)
+const MAX_MEDIA_BYTES: u64 = 10 * 1024 * 1024;
+
+/// How long a typing notice lasts; it is renewed while the agent runs
+const TYPING_TIMEOUT_MS: u64 = 30_000;
 
 /// Matrix channel using the Client-Server API (no SDK needed).
 /// Connects to any Matrix homeserver (Element, Synapse, etc.).
+///
+/// Encrypted rooms need [`Self::with_e2ee`], which runs the room through
+/// matrix-sdk when built with `--features matrix-e2ee`.
 #[derive(Clone)]
 pub struct MatrixChannel {
     homeserver: String,
@@ -17,6 +29,10 @@ pub struct MatrixChannel {
     room_id: String,
     allowed_users: Vec<String>,
     client: Client,
+    user_id: Arc<OnceCell<String>>,
+    typing_handle: Arc<std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    warned_encrypted: Arc<AtomicBool>,
+    e2ee: Option<Arc<e2ee::Session>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,6 +66,8 @@ struct TimelineEvent {
     event_type: String,
     sender: String,
     #[serde(default)]
+    event_id: Option<String>,
+    #[serde(default)]
     content: EventContent,
 }
 
@@ -59,11 +77,53 @@ struct EventContent {
     body: Option<String>,
     #[serde(default)]
     msgtype: Option<String>,
+    /// `mxc://` URI of unencrypted media
+    #[serde(default)]
+    url: Option<String>,
+    /// Set when `body` is a caption rather than the file name
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    info: Option<MediaInfo>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct MediaInfo {
+    #[serde(default)]
+    mimetype: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct WhoAmIResponse {
     user_id: String,
+    #[serde(default)]
+    device_id: Option<String>,
+}
+
+/// Split an `mxc://server/media-id` URI.
+fn parse_mxc(uri: &str) -> Option<(&str, &str)> {
+    let (server, media_id) = uri.strip_prefix("mxc://")?.split_once('/')?;
+    let valid = !server.is_empty() && !media_id.is_empty() && !media_id.contains('/');
+    valid.then_some((server, media_id))
+}
+
+/// What the agent reads for a media message: its caption, or the kind of
+/// media and the file name.
+fn media_text(msgtype: &str, body: &str, filename: Option<&str>) -> String {
+    match filename {
+        Some(name) if name != body && !body.is_empty() => body.to_string(),
+        name => format!(
+            "[{}: {}]",
+            msgtype.trim_start_matches("m."),
+            name.unwrap_or(body)
+        ),
+    }
+}
+
+fn is_media(msgtype: &str) -> bool {
+    matches!(msgtype, "m.image" | "m.file" | "m.audio" | "m.video")
 }
 
 impl MatrixChannel {
@@ -84,9 +144,20 @@ impl MatrixChannel {
             room_id,
             allowed_users,
             client: Client::new(),
+            user_id: Arc::new(OnceCell::new()),
+            typing_handle: Arc::new(std::sync::Mutex::new(None)),
+            warned_encrypted: Arc::new(AtomicBool::new(false)),
+            e2ee: None,
         }
     }
 
+    /// Send and receive through an end-to-end encrypting client whose
+    /// device keys are kept in `store_dir`.
+    pub fn with_e2ee(mut self, store_dir: PathBuf) -> Self {
+        self.e2ee = Some(Arc::new(e2ee::Session::new(store_dir)));
+        self
+    }
+
     fn is_user_allowed(&self, sender: &str) -> bool {
         if self.allowed_users.iter().any(|u| u == "*") {
             return true;
@@ -96,7 +167,7 @@ impl MatrixChannel {
             .any(|u| u.eq_ignore_ascii_case(sender))
     }
 
-    async fn get_my_user_id(&self) -> anyhow::Result<String> {
+    async fn whoami(&self) -> anyhow::Result<WhoAmIResponse> {
         let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
         let resp = self
             .client
@@ -110,8 +181,116 @@ impl MatrixChannel {
             anyhow::bail!("Matrix whoami failed: {err}");
         }
 
-        let who: WhoAmIResponse = resp.json().await?;
-        Ok(who.user_id)
+        Ok(resp.json().await?)
+    }
+
+    async fn get_my_user_id(&self) -> anyhow::Result<String> {
+        self.user_id
+            .get_or_try_init(|| async { Ok(self.whoami().await?.user_id) })
+            .await
+            .cloned()
+    }
+
+    async fn set_typing(&self, typing: bool) -> anyhow::Result<()> {
+        let user_id = self.get_my_user_id().await?;
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/typing/{}",
+            self.homeserver, self.room_id, user_id
+        );
+        let body = if typing {
+            serde_json::json!({ "typing": true, "timeout": TYPING_TIMEOUT_MS })
+        } else {
+            serde_json::json!({ "typing": false })
+        };
+        let resp = self
+            .client
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix typing notice failed: {err}");
+        }
+        Ok(())
+    }
+
+    /// Download media through the authenticated media API.
+    async fn download_media(&self, mxc: &str) -> anyhow::Result<Vec<u8>> {
+        let (server, media_id) =
+            parse_mxc(mxc).ok_or_else(|| anyhow::anyhow!("not an mxc URI: {mxc}"))?;
+        let url = format!(
+            "{}/_matrix/client/v1/media/download/{server}/{media_id}",
+            self.homeserver
+        );
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix media download failed: {err}");
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    async fn media_attachment(&self, content: &EventContent) -> Option<Attachment> {
+        let url = content.url.as_deref()?;
+        let info = content.info.as_ref();
+        let file_name = content.filename.clone().or_else(|| content.body.clone());
+        if let Some(size) = info.and_then(|i| i.size).filter(|s| *s > MAX_MEDIA_BYTES) {
+            tracing::warn!("Matrix: skipping media of {size} bytes");
+            return None;
+        }
+        match self.download_media(url).await {
+            Ok(data) => Some(Attachment {
+                mime_type: info
+                    .and_then(|i| i.mimetype.clone())
+                    .unwrap_or_else(|| "application/octet-stream".to_string()),
+                data,
+                file_name,
+            }),
+            Err(e) => {
+                tracing::warn!("Matrix: {e}");
+                None
+            }
+        }
+    }
+
+    /// Turn a room message into a channel message; text and media only.
+    async fn to_message(&self, event: &TimelineEvent) -> Option<ChannelMessage> {
+        let body = event.content.body.as_deref()?;
+        let msgtype = event.content.msgtype.as_deref()?;
+        let (content, attachments) = if msgtype == "m.text" {
+            (body.to_string(), Vec::new())
+        } else if is_media(msgtype) {
+            let text = media_text(msgtype, body, event.content.filename.as_deref());
+            let attachment = self.media_attachment(&event.content).await;
+            (text, attachment.into_iter().collect())
+        } else {
+            return None;
+        };
+
+        Some(ChannelMessage {
+            id: event
+                .event_id
+                .clone()
+                .unwrap_or_else(|| format!("mx_{}", chrono::Utc::now().timestamp_millis())),
+            sender: event.sender.clone(),
+            content,
+            channel: "matrix".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+            group: None,
+        })
     }
 }
 
@@ -122,6 +301,10 @@ impl Channel for MatrixChannel {
     }
 
     async fn send(&self, message: &str, _target: &str) -> anyhow::Result<()> {
+        if let Some(session) = &self.e2ee {
+            return session.send(self, message).await;
+        }
+
         let txn_id = format!("zc_{}", chrono::Utc::now().timestamp_millis());
         let url = format!(
             "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
@@ -150,6 +333,14 @@ impl Channel for MatrixChannel {
     }
 
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        if let Some(session) = &self.e2ee {
+            tracing::info!(
+                "Matrix channel listening on room {} (end-to-end encrypted)...",
+                self.room_id
+            );
+            return session.listen(self, tx).await;
+        }
+
         tracing::info!("Matrix channel listening on room {}...", self.room_id);
 
         let my_user_id = self.get_my_user_id().await?;
@@ -214,32 +405,27 @@ impl Channel for MatrixChannel {
                         continue;
                     }
 
-                    // Only process text messages
-                    if event.event_type != "m.room.message" {
-                        continue;
+                    // Without the SDK encrypted messages stay unreadable
+                    if event.event_type == "m.room.encrypted"
+                        && !self.warned_encrypted.swap(true, Ordering::Relaxed)
+                    {
+                        tracing::warn!(
+                            "Matrix room {} is encrypted; set e2ee = true and build with --features matrix-e2ee",
+                            self.room_id
+                        );
                     }
 
-                    if event.content.msgtype.as_deref() != Some("m.text") {
+                    // Only process room messages
+                    if event.event_type != "m.room.message" {
                         continue;
                     }
 
-                    let Some(ref body) = event.content.body else {
-                        continue;
-                    };
-
                     if !self.is_user_allowed(&event.sender) {
                         continue;
                     }
 
-                    let msg = ChannelMessage {
-                        id: format!("mx_{}", chrono::Utc::now().timestamp_millis()),
-                        sender: event.sender.clone(),
-                        content: body.clone(),
-                        channel: "matrix".to_string(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
+                    let Some(msg) = self.to_message(event).await else {
+                        continue;
                     };
 
                     if tx.send(msg).await.is_err() {
@@ -251,18 +437,266 @@ impl Channel for MatrixChannel {
     }
 
     async fn health_check(&self) -> bool {
-        let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
-        let Ok(resp) = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
-            .send()
-            .await
-        else {
-            return false;
+        self.whoami().await.is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.stop_typing(recipient).await?;
+
+        let channel = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                if let Err(e) = channel.set_typing(true).await {
+                    tracing::debug!("Matrix: {e}");
+                }
+                // Renew before the notice times out
+                tokio::time::sleep(std::time::Duration::from_millis(TYPING_TIMEOUT_MS * 5 / 6))
+                    .await;
+            }
+        });
+
+        if let Ok(mut guard) = self.typing_handle.lock() {
+            *guard = Some(handle);
+        }
+
+        Ok(())
+    }
+
+    async fn stop_typing(&self, _recipient: &str) -> anyhow::Result<()> {
+        let handle = self
+            .typing_handle
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take());
+        if let Some(handle) = handle {
+            handle.abort();
+            self.set_typing(false).await?;
+        }
+        Ok(())
+    }
+}
+
+// ── End-to-end encryption ───────────────────────────────────────────────
+
+#[cfg(feature = "matrix-e2ee")]
+mod e2ee {
+    use super::{media_text, Attachment, ChannelMessage, MatrixChannel, MAX_MEDIA_BYTES};
+    use anyhow::{Context, Result};
+    use matrix_sdk::config::SyncSettings;
+    use matrix_sdk::matrix_auth::{MatrixSession, MatrixSessionTokens};
+    use matrix_sdk::media::{MediaFormat, MediaRequest};
+    use matrix_sdk::ruma::events::room::message::{
+        MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent,
+    };
+    use matrix_sdk::ruma::events::room::MediaSource;
+    use matrix_sdk::ruma::{RoomId, UInt, UserId};
+    use matrix_sdk::{Client, Room, SessionMeta};
+    use std::path::PathBuf;
+    use tokio::sync::{mpsc, OnceCell};
+
+    /// Client with an Olm machine; its keys live in a store per device.
+    pub struct Session {
+        store_dir: PathBuf,
+        client: OnceCell<Client>,
+    }
+
+    impl Session {
+        pub fn new(store_dir: PathBuf) -> Self {
+            Self {
+                store_dir,
+                client: OnceCell::new(),
+            }
+        }
+
+        /// Restore the access token's session and sync once, so the room
+        /// and its keys are known before the first send.
+        async fn client(&self, ch: &MatrixChannel) -> Result<&Client> {
+            self.client
+                .get_or_try_init(|| async {
+                    let who = ch.whoami().await?;
+                    let device_id = who
+                        .device_id
+                        .context("the homeserver did not name the access token's device")?;
+                    // The store is encrypted with the access token; a new
+                    // token is a new device and gets a fresh store
+                    let client = Client::builder()
+                        .homeserver_url(&ch.homeserver)
+                        .sqlite_store(self.store_dir.join(&device_id), Some(&ch.access_token))
+                        .build()
+                        .await?;
+                    let session = MatrixSession {
+                        meta: SessionMeta {
+                            user_id: UserId::parse(&who.user_id)?,
+                            device_id: device_id.into(),
+                        },
+                        tokens: MatrixSessionTokens {
+                            access_token: ch.access_token.clone(),
+                            refresh_token: None,
+                        },
+                    };
+                    client.restore_session(session).await?;
+                    client.sync_once(SyncSettings::default()).await?;
+                    Ok(client)
+                })
+                .await
+        }
+
+        async fn room(&self, ch: &MatrixChannel) -> Result<Room> {
+            let client = self.client(ch).await?;
+            let room_id = <&RoomId>::try_from(ch.room_id.as_str())?;
+            client
+                .get_room(room_id)
+                .with_context(|| format!("not a member of Matrix room {}", ch.room_id))
+        }
+
+        pub async fn send(&self, ch: &MatrixChannel, message: &str) -> Result<()> {
+            let room = self.room(ch).await?;
+            room.send(RoomMessageEventContent::text_plain(message))
+                .await?;
+            Ok(())
+        }
+
+        pub async fn listen(
+            &self,
+            ch: &MatrixChannel,
+            tx: mpsc::Sender<ChannelMessage>,
+        ) -> Result<()> {
+            let client = self.client(ch).await?;
+            let me = client.user_id().context("no Matrix session")?.to_owned();
+            let channel = ch.clone();
+            let handler = client.add_event_handler(
+                move |event: OriginalSyncRoomMessageEvent, room: Room, client: Client| {
+                    let (channel, tx, me) = (channel.clone(), tx.clone(), me.clone());
+                    async move {
+                        if room.room_id().as_str() != channel.room_id
+                            || event.sender == me
+                            || !channel.is_user_allowed(event.sender.as_str())
+                        {
+                            return;
+                        }
+                        if let Some(msg) = to_message(&client, &event).await {
+                            let _ = tx.send(msg).await;
+                        }
+                    }
+                },
+            );
+            let result = client.sync(SyncSettings::default()).await;
+            client.remove_event_handler(handler);
+            result.map_err(Into::into)
+        }
+    }
+
+    /// Fetch (and decrypt) the media of a message.
+    async fn download(
+        client: &Client,
+        source: &MediaSource,
+        mimetype: Option<String>,
+        size: Option<UInt>,
+        name: &str,
+    ) -> Option<Attachment> {
+        if let Some(size) = size.map(u64::from).filter(|s| *s > MAX_MEDIA_BYTES) {
+            tracing::warn!("Matrix: skipping media of {size} bytes");
+            return None;
+        }
+        let request = MediaRequest {
+            source: source.clone(),
+            format: MediaFormat::File,
         };
+        match client.media().get_media_content(&request, true).await {
+            Ok(data) => Some(Attachment {
+                mime_type: mimetype.unwrap_or_else(|| "application/octet-stream".to_string()),
+                data,
+                file_name: Some(name.to_string()),
+            }),
+            Err(e) => {
+                tracing::warn!("Matrix: media download failed: {e}");
+                None
+            }
+        }
+    }
 
-        resp.status().is_success()
+    async fn to_message(
+        client: &Client,
+        event: &OriginalSyncRoomMessageEvent,
+    ) -> Option<ChannelMessage> {
+        let (msgtype, body, source, info) = match &event.content.msgtype {
+            MessageType::Text(text) => {
+                return Some(message(event, text.body.clone(), Vec::new()));
+            }
+            MessageType::Image(c) => {
+                let info = c.info.as_deref().map(|i| (i.mimetype.clone(), i.size));
+                ("m.image", &c.body, &c.source, info)
+            }
+            MessageType::File(c) => {
+                let info = c.info.as_deref().map(|i| (i.mimetype.clone(), i.size));
+                ("m.file", &c.body, &c.source, info)
+            }
+            MessageType::Audio(c) => {
+                let info = c.info.as_deref().map(|i| (i.mimetype.clone(), i.size));
+                ("m.audio", &c.body, &c.source, info)
+            }
+            MessageType::Video(c) => {
+                let info = c.info.as_deref().map(|i| (i.mimetype.clone(), i.size));
+                ("m.video", &c.body, &c.source, info)
+            }
+            _ => return None,
+        };
+        let (mimetype, size) = info.unwrap_or_default();
+        let attachment = download(client, source, mimetype, size, body).await;
+        let text = media_text(msgtype, body, None);
+        Some(message(event, text, attachment.into_iter().collect()))
+    }
+
+    fn message(
+        event: &OriginalSyncRoomMessageEvent,
+        content: String,
+        attachments: Vec<Attachment>,
+    ) -> ChannelMessage {
+        ChannelMessage {
+            id: event.event_id.to_string(),
+            sender: event.sender.to_string(),
+            content,
+            channel: "matrix".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+            group: None,
+        }
+    }
+}
+
+#[cfg(not(feature = "matrix-e2ee"))]
+mod e2ee {
+    use super::{ChannelMessage, MatrixChannel};
+    use anyhow::{bail, Result};
+    use std::path::PathBuf;
+    use tokio::sync::mpsc;
+
+    const UNAVAILABLE: &str =
+        "Matrix end-to-end encryption is not compiled in (build with --features matrix-e2ee)";
+
+    pub struct Session;
+
+    impl Session {
+        pub fn new(_store_dir: PathBuf) -> Self {
+            Self
+        }
+
+        #[allow(clippy::unused_async)]
+        pub async fn send(&self, _ch: &MatrixChannel, _message: &str) -> Result<()> {
+            bail!(UNAVAILABLE)
+        }
+
+        #[allow(clippy::unused_async)]
+        pub async fn listen(
+            &self,
+            _ch: &MatrixChannel,
+            _tx: mpsc::Sender<ChannelMessage>,
+        ) -> Result<()> {
+            bail!(UNAVAILABLE)
+        }
     }
 }
 
@@ -462,6 +896,61 @@ mod tests {
         assert!(event.content.msgtype.is_none());
     }
 
+    #[test]
+    fn whoami_response_reports_the_device() {
+        let json = r#"{"user_id":"@bot:matrix.org","device_id":"ABCDEF"}"#;
+        let resp: WhoAmIResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.device_id.as_deref(), Some("ABCDEF"));
+    }
+
+    #[test]
+    fn media_event_deserializes() {
+        let json = r#"{
+            "type": "m.room.message",
+            "sender": "@u:m",
+            "event_id": "$e1",
+            "content": {
+                "msgtype": "m.image",
+                "body": "What is this?",
+                "filename": "photo.jpg",
+                "url": "mxc://m.org/abc123",
+                "info": { "mimetype": "image/jpeg", "size": 2048 }
+            }
+        }"#;
+        let event: TimelineEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.event_id.as_deref(), Some("$e1"));
+        assert_eq!(event.content.url.as_deref(), Some("mxc://m.org/abc123"));
+        let info = event.content.info.unwrap();
+        assert_eq!(info.mimetype.as_deref(), Some("image/jpeg"));
+        assert_eq!(info.size, Some(2048));
+    }
+
+    #[test]
+    fn mxc_uris_are_split() {
+        assert_eq!(parse_mxc("mxc://m.org/abc123"), Some(("m.org", "abc123")));
+        assert_eq!(parse_mxc("https://m.org/abc123"), None);
+        assert_eq!(parse_mxc("mxc://m.org/"), None);
+        assert_eq!(parse_mxc("mxc://m.org/a/../b"), None);
+    }
+
+    #[test]
+    fn media_text_prefers_the_caption() {
+        assert_eq!(
+            media_text("m.image", "What is this?", Some("photo.jpg")),
+            "What is this?"
+        );
+        assert_eq!(
+            media_text("m.file", "report.pdf", None),
+            "[file: report.pdf]"
+        );
+        assert_eq!(
+            media_text("m.audio", "voice.ogg", Some("voice.ogg")),
+            "[audio: voice.ogg]"
+        );
+        assert!(is_media("m.video"));
+        assert!(!is_media("m.notice"));
+    }
+
     #[test]
     fn sync_response_missing_rooms_defaults() {
         let json = r#"{"next_batch":"s0"}"#;
@@ -3,11 +3,15 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 pub mod cli;
+pub mod confirm_relay;
+pub mod dedup;
 pub mod discord;
 pub mod email_channel;
+pub mod groups;
 pub mod imessage;
 pub mod irc;
 pub mod matrix;
+pub mod router;
 pub mod slack;
 pub mod telegram;
 pub mod traits;
@@ -24,13 +28,20 @@ pub use telegram::TelegramChannel;
 pub use traits::Channel;
 pub use whatsapp::WhatsAppChannel;
 
-use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
-use crate::config::Config;
-use crate::identity;
-use crate::memory::{self, Memory};
+use crate::agent::budget::LoopBudget;
+use crate::agent::cancel::CancellationToken;
+use crate::agent::loop_::{build_tool_instructions, run_agent_turn, Planner};
+use crate::agent::tool_router::ToolRouter;
+use crate::capture::{self, CaptureInbox};
+use crate::channels::confirm_relay::ConfirmationRelay;
+use crate::config::{Config, GroupChatConfig};
+use crate::identity::{self, family::FamilyRegistry, UserRole};
+use crate::memory::{self, scoped, Memory};
 use crate::observability::{self, Observer};
+use crate::providers::router::{routed_vision, ModelRouter, TaskClass};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
+use crate::security::confirmation::{self, ConfirmationGate};
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
@@ -59,9 +70,54 @@ struct ChannelRuntimeContext {
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
     observer: Arc<dyn Observer>,
     system_prompt: Arc<String>,
-    model: Arc<String>,
+    /// Default model and the routes of each task class
+    models: Arc<ModelRouter>,
     temperature: f64,
+    budget: LoopBudget,
+    planner: Option<Planner>,
+    /// Per-request tool and skill selection; `None` when disabled.
+    tool_router: Option<Arc<ToolRouter>>,
     auto_save_memory: bool,
+    /// Photo capture; `None` when `[capture]` is disabled.
+    capture: Option<Arc<CaptureInbox>>,
+    /// Answers to confirmation requests; `None` when no chat receives them.
+    confirm_relay: Option<Arc<ConfirmationRelay>>,
+    /// Resolves senders to memory scopes in family mode.
+    family: Arc<FamilyRegistry>,
+    /// Group chats answered, from `[[channels_config.groups]]`.
+    groups: Arc<Vec<GroupChatConfig>>,
+    /// Messages already processed, to drop redeliveries.
+    seen: Arc<dedup::SeenMessages>,
+}
+
+/// Handle photo capture and yes/no answers to its proposals.
+///
+/// Returns the reply when the message was consumed.
+async fn handle_capture(ctx: &ChannelRuntimeContext, msg: &traits::ChannelMessage) -> Option<String> {
+    let inbox = ctx.capture.as_ref()?;
+    if let Some(image) = msg.attachments.iter().find(|a| a.is_image()) {
+        return Some(
+            inbox
+                .handle_photo(ctx.provider.as_ref(), &msg.channel, &msg.sender, &image.data)
+                .await,
+        );
+    }
+    inbox
+        .handle_answer(&msg.channel, &msg.sender, &msg.content)
+        .await
+}
+
+/// Handle "yes <code>" / "no <code>" answers to confirmation requests.
+///
+/// Returns the reply when the message was consumed.
+async fn handle_confirmation(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+) -> Option<String> {
+    ctx.confirm_relay
+        .as_ref()?
+        .handle_reply(&msg.channel, &msg.sender, &msg.content)
+        .await
 }
 
 fn conversation_memory_key(msg: &traits::ChannelMessage) -> String {
@@ -133,13 +189,18 @@ fn compute_max_in_flight_messages(channel_count: usize) -> usize {
         )
 }
 
-fn log_worker_join_result(result: Result<(), tokio::task::JoinError>) {
-    if let Err(error) = result {
-        tracing::error!("Channel message worker crashed: {error}");
-    }
-}
+async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, mut msg: traits::ChannelMessage) {
+    // In groups only messages addressed to MyMolt by members with a role
+    let group_turn = if msg.group.is_some() {
+        let Some(turn) = groups::group_turn(&ctx.groups, &ctx.family, &msg) else {
+            return;
+        };
+        msg.content.clone_from(&turn.content);
+        Some(turn)
+    } else {
+        None
+    };
 
-async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::ChannelMessage) {
     println!(
         "  💬 [{}] from {}: {}",
         msg.channel,
@@ -147,12 +208,42 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         truncate_with_ellipsis(&msg.content, 80)
     );
 
-    let memory_context = build_memory_context(ctx.memory.as_ref(), &msg.content).await;
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: msg.channel.clone(),
+        sender: msg.sender.clone(),
+        text: msg.content.clone(),
+    });
+
+    // Confirmations and captures are answered in private chats only
+    let reply = if group_turn.is_some() {
+        None
+    } else {
+        match handle_confirmation(&ctx, &msg).await {
+            Some(reply) => Some(reply),
+            None => handle_capture(&ctx, &msg).await,
+        }
+    };
+    if let Some(reply) = reply {
+        if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+            if let Err(e) = channel.send(&reply, &msg.sender).await {
+                eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+            }
+        }
+        return;
+    }
+
+    // In family mode each sender only sees their own and shared memories;
+    // a group has memories of its own.
+    let scope = match &group_turn {
+        Some(turn) => Some(turn.scope.clone()),
+        None => ctx.family.memory_scope(&msg.channel, &msg.sender),
+    };
+    let memory = scoped::confine(&ctx.memory, scope.clone());
+    let memory_context = build_memory_context(memory.as_ref(), &msg.content).await;
 
     if ctx.auto_save_memory {
         let autosave_key = conversation_memory_key(&msg);
-        let _ = ctx
-            .memory
+        let _ = memory
             .store(
                 &autosave_key,
                 &msg.content,
@@ -183,16 +274,39 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         ChatMessage::user(&enriched_message),
     ];
 
+    // Approvals remembered during this chat cover only its sender.
+    let session = format!("{}:{}", msg.channel, msg.sender);
+    // Below adult a group member's turn gets no tools, as on the dashboard
+    let no_tools = Vec::new();
+    let tools = if group_turn
+        .as_ref()
+        .is_some_and(|turn| turn.role < UserRole::Adult)
+    {
+        &no_tools
+    } else {
+        ctx.tools_registry.as_ref()
+    };
+    let model = ctx.models.model_for(TaskClass::for_turn(!tools.is_empty()));
     let llm_result = tokio::time::timeout(
         Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
-        run_tool_call_loop(
-            ctx.provider.as_ref(),
-            &mut history,
-            ctx.tools_registry.as_ref(),
-            ctx.observer.as_ref(),
-            ctx.provider_name.as_str(),
-            ctx.model.as_str(),
-            ctx.temperature,
+        confirmation::with_session(
+            session,
+            scoped::with_turn_scope(
+                scope,
+                run_agent_turn(
+                    ctx.provider.as_ref(),
+                    &mut history,
+                    tools,
+                    ctx.observer.as_ref(),
+                    ctx.provider_name.as_str(),
+                    &model,
+                    ctx.temperature,
+                    &ctx.budget,
+                    ctx.planner.as_ref(),
+                    ctx.tool_router.as_deref(),
+                    &CancellationToken::new(),
+                ),
+            ),
         ),
     )
     .await;
@@ -247,34 +361,25 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     }
 }
 
+/// Answer messages through the [`router`]; at most `max_in_flight_messages`
+/// run at once across all channels.
 async fn run_message_dispatch_loop(
-    mut rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
+    rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
     ctx: Arc<ChannelRuntimeContext>,
     max_in_flight_messages: usize,
 ) {
     let semaphore = Arc::new(tokio::sync::Semaphore::new(max_in_flight_messages));
-    let mut workers = tokio::task::JoinSet::new();
-
-    while let Some(msg) = rx.recv().await {
-        let permit = match Arc::clone(&semaphore).acquire_owned().await {
-            Ok(permit) => permit,
-            Err(_) => break,
-        };
-
-        let worker_ctx = Arc::clone(&ctx);
-        workers.spawn(async move {
-            let _permit = permit;
-            process_channel_message(worker_ctx, msg).await;
-        });
-
-        while let Some(result) = workers.try_join_next() {
-            log_worker_join_result(result);
+    let seen = Arc::clone(&ctx.seen);
+    router::route(rx, seen, CHANNEL_PARALLELISM_PER_CHANNEL, move |msg| {
+        let (ctx, semaphore) = (Arc::clone(&ctx), Arc::clone(&semaphore));
+        async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return;
+            };
+            process_channel_message(ctx, msg).await;
         }
-    }
-
-    while let Some(result) = workers.join_next().await {
-        log_worker_join_result(result);
-    }
+    })
+    .await;
 }
 
 /// Load OpenClaw format bootstrap files into the prompt.
@@ -358,31 +463,7 @@ pub fn build_system_prompt(
     );
 
     // ── 3. Skills (compact list — load on-demand) ───────────────
-    if !skills.is_empty() {
-        prompt.push_str("## Available Skills\n\n");
-        prompt.push_str(
-            "Skills are loaded on demand. Use `read` on the skill path to get full instructions.\n\n",
-        );
-        prompt.push_str("<available_skills>\n");
-        for skill in skills {
-            let _ = writeln!(prompt, "  <skill>");
-            let _ = writeln!(prompt, "    <name>{}</name>", skill.name);
-            let _ = writeln!(
-                prompt,
-                "    <description>{}</description>",
-                skill.description
-            );
-            let location = skill.location.clone().unwrap_or_else(|| {
-                workspace_dir
-                    .join("skills")
-                    .join(&skill.name)
-                    .join("SKILL.md")
-            });
-            let _ = writeln!(prompt, "    <location>{}</location>", location.display());
-            let _ = writeln!(prompt, "  </skill>");
-        }
-        prompt.push_str("</available_skills>\n\n");
-    }
+    prompt.push_str(&build_skills_prompt(workspace_dir, skills));
 
     // ── 4. Workspace ────────────────────────────────────────────
     let _ = writeln!(
@@ -449,6 +530,43 @@ pub fn build_system_prompt(
     }
 }
 
+/// The `## Available Skills` section of the system prompt: name, description
+/// and location of each skill; the model reads a skill file when it needs it.
+pub fn build_skills_prompt(
+    workspace_dir: &std::path::Path,
+    skills: &[crate::skills::Skill],
+) -> String {
+    use std::fmt::Write;
+    let mut prompt = String::new();
+    if skills.is_empty() {
+        return prompt;
+    }
+    prompt.push_str("## Available Skills\n\n");
+    prompt.push_str(
+        "Skills are loaded on demand. Use `read` on the skill path to get full instructions.\n\n",
+    );
+    prompt.push_str("<available_skills>\n");
+    for skill in skills {
+        let _ = writeln!(prompt, "  <skill>");
+        let _ = writeln!(prompt, "    <name>{}</name>", skill.name);
+        let _ = writeln!(
+            prompt,
+            "    <description>{}</description>",
+            skill.description
+        );
+        let location = skill.location.clone().unwrap_or_else(|| {
+            workspace_dir
+                .join("skills")
+                .join(&skill.name)
+                .join("SKILL.md")
+        });
+        let _ = writeln!(prompt, "    <location>{}</location>", location.display());
+        let _ = writeln!(prompt, "  </skill>");
+    }
+    prompt.push_str("</available_skills>\n\n");
+    prompt
+}
+
 /// Inject a single workspace file into the prompt with truncation and missing-file markers.
 fn inject_workspace_file(prompt: &mut String, workspace_dir: &std::path::Path, filename: &str) {
     use std::fmt::Write;
@@ -566,12 +684,15 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     if let Some(ref dc) = config.channels_config.discord {
         channels.push((
             "Discord",
-            Arc::new(DiscordChannel::new(
-                dc.bot_token.clone(),
-                dc.guild_id.clone(),
-                dc.allowed_users.clone(),
-                dc.listen_to_bots,
-            )),
+            Arc::new(
+                DiscordChannel::new(
+                    dc.bot_token.clone(),
+                    dc.guild_id.clone(),
+                    dc.allowed_users.clone(),
+                    dc.listen_to_bots,
+                )
+                .with_allowlists(dc.allowed_guilds.clone(), dc.allowed_channels.clone()),
+            ),
         ));
     }
 
@@ -594,15 +715,7 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     }
 
     if let Some(ref mx) = config.channels_config.matrix {
-        channels.push((
-            "Matrix",
-            Arc::new(MatrixChannel::new(
-                mx.homeserver.clone(),
-                mx.access_token.clone(),
-                mx.room_id.clone(),
-                mx.allowed_users.clone(),
-            )),
-        ));
+        channels.push(("Matrix", Arc::new(matrix_channel(&config, mx))));
     }
 
     if let Some(ref wa) = config.channels_config.whatsapp {
@@ -682,15 +795,136 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
 
 /// Start all configured channels and route messages to the agent
 #[allow(clippy::too_many_lines)]
+/// Instantiate every channel configured in `[channels_config]`.
+///
+/// Listening is left to the caller; the gateway also uses this to send
+/// automation messages.
+/// Matrix channel; encrypted rooms keep their device keys in the workspace.
+fn matrix_channel(config: &Config, mx: &crate::config::MatrixConfig) -> MatrixChannel {
+    let channel = MatrixChannel::new(
+        mx.homeserver.clone(),
+        mx.access_token.clone(),
+        mx.room_id.clone(),
+        mx.allowed_users.clone(),
+    );
+    if mx.e2ee {
+        channel.with_e2ee(config.workspace_dir.join(".mymolt").join("matrix"))
+    } else {
+        channel
+    }
+}
+
+pub fn configured_channels(config: &Config) -> Vec<Arc<dyn Channel>> {
+    let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
+
+    if let Some(ref tg) = config.channels_config.telegram {
+        channels.push(Arc::new(TelegramChannel::new(
+            tg.bot_token.clone(),
+            tg.allowed_users.clone(),
+        )));
+    }
+
+    if let Some(ref dc) = config.channels_config.discord {
+        channels.push(Arc::new(
+            DiscordChannel::new(
+                dc.bot_token.clone(),
+                dc.guild_id.clone(),
+                dc.allowed_users.clone(),
+                dc.listen_to_bots,
+            )
+            .with_allowlists(dc.allowed_guilds.clone(), dc.allowed_channels.clone()),
+        ));
+    }
+
+    if let Some(ref sl) = config.channels_config.slack {
+        channels.push(Arc::new(SlackChannel::new(
+            sl.bot_token.clone(),
+            sl.channel_id.clone(),
+            sl.allowed_users.clone(),
+        )));
+    }
+
+    if let Some(ref im) = config.channels_config.imessage {
+        channels.push(Arc::new(IMessageChannel::new(im.allowed_contacts.clone())));
+    }
+
+    if let Some(ref mx) = config.channels_config.matrix {
+        channels.push(Arc::new(matrix_channel(config, mx)));
+    }
+
+    if let Some(ref wa) = config.channels_config.whatsapp {
+        channels.push(Arc::new(WhatsAppChannel::new(
+            wa.access_token.clone(),
+            wa.phone_number_id.clone(),
+            wa.verify_token.clone(),
+            wa.allowed_numbers.clone(),
+        )));
+    }
+
+    if let Some(ref email_cfg) = config.channels_config.email {
+        channels.push(Arc::new(EmailChannel::new(email_cfg.clone())));
+    }
+
+    if let Some(ref irc) = config.channels_config.irc {
+        channels.push(Arc::new(IrcChannel::new(
+            irc.server.clone(),
+            irc.port,
+            irc.nickname.clone(),
+            irc.username.clone(),
+            irc.channels.clone(),
+            irc.allowed_users.clone(),
+            irc.server_password.clone(),
+            irc.nickserv_password.clone(),
+            irc.sasl_password.clone(),
+            irc.verify_tls.unwrap_or(true),
+        )));
+    }
+
+    channels
+}
+
+/// Send `message` to every Root family member on the configured channels
+/// they are bound to. Returns how many messages were delivered.
+pub async fn notify_root(config: &Config, message: &str) -> usize {
+    let family = FamilyRegistry::from_config(&config.family);
+    let channels = configured_channels(config);
+    let mut sent = 0;
+    let roots = family
+        .members()
+        .iter()
+        .filter(|member| member.role == identity::UserRole::Root);
+    for member in roots {
+        for (name, recipient) in &member.channels {
+            let Some(channel) = channels
+                .iter()
+                .find(|c| c.name().eq_ignore_ascii_case(name))
+            else {
+                continue;
+            };
+            match channel.send(message, recipient).await {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::warn!("Failed to notify {} on {name}: {e}", member.name),
+            }
+        }
+    }
+    sent
+}
+
 pub async fn start_channels(config: Config) -> Result<()> {
     let provider_name = config
         .default_provider
         .clone()
         .unwrap_or_else(|| "openrouter".to_string());
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
+    let model = config
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
+    let models = Arc::new(ModelRouter::new(model.as_str(), &config.model_routes));
+    let provider: Arc<dyn Provider> = Arc::from(providers::create_routed_provider(
         provider_name.as_str(),
         config.api_key.as_deref(),
         &config.reliability,
+        &models,
     )?);
 
     // Warm up the provider connection pool (TLS handshake, DNS, HTTP/2 setup)
@@ -699,8 +933,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
         tracing::warn!("Provider warmup failed (non-fatal): {e}");
     }
 
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
+    let observer: Arc<dyn Observer> = Arc::from(observability::create_observer(
+        &config.observability,
+        &config.workspace_dir,
+    ));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let mut security = SecurityPolicy::from_config(
@@ -720,24 +956,34 @@ pub async fn start_channels(config: Config) -> Result<()> {
             actor_name = None;
         }
     }
+    security.leak_guard = Some(Arc::new(crate::security::leak_guard::LeakGuard::from_config(&config)?));
     let security = Arc::new(security);
 
-    let model = config
-        .default_model
-        .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
     let temperature = config.default_temperature;
     let audit = Arc::new(crate::security::AuditLogger::new(
         config.security.audit.clone(),
         config.workspace_dir.clone(),
     )?);
+    crate::network::egress::install_from_config(&config.security.egress, Some(audit.clone()));
+    let provider = providers::guarded::GuardedProvider::wrap(
+        provider,
+        providers::guarded::all_local(&config, &provider_name),
+        security.leak_guard.clone(),
+        None,
+        Some(Arc::clone(&audit)),
+    );
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
+        &config.security.sensitivity,
         &config.workspace_dir,
         config.api_key.as_deref(),
         Arc::clone(&audit),
     )?);
 
+    // Shared with the gateway, so requests can be answered on the dashboard
+    // as well as in chat.
+    let confirm_gate = ConfirmationGate::shared(config.security.confirmation.timeout_secs);
+
     let composio_key = if config.composio.enabled {
         config.composio.api_key.as_deref()
     } else {
@@ -749,7 +995,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.mcp,
         &security,
         &audit,
-    ).await;
+    ).await.tools;
 
     let tools_registry = Arc::new(tools::all_tools_with_runtime(
         &security,
@@ -758,17 +1004,24 @@ pub async fn start_channels(config: Config) -> Result<()> {
         composio_key,
         &config.browser,
         &config.http_request,
+        &routed_vision(&config),
+        &config.db_query,
+        &config.feeds,
+        &config.plugins,
+        &config.reliability,
         &config.workspace_dir,
         &config.agents,
         config.api_key.as_deref(),
         mcp_tools,
         Some(Arc::clone(&audit)),
         actor_name,
+        Some(Arc::clone(&confirm_gate)),
     ));
 
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
     let skills = crate::skills::load_skills(&workspace);
+    let tool_router = ToolRouter::from_config(&config, skills.clone()).map(Arc::new);
 
     // Collect tool descriptions for the prompt
     let mut tool_descs: Vec<(&str, &str)> = vec![
@@ -782,7 +1035,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
         ),
         (
             "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
+            "Write file contents. Use when: creating new files, scaffolding, or replacing a file entirely. Don't use when: changing part of an existing file (use file_patch), side effects are unclear or file ownership is uncertain.",
+        ),
+        (
+            "file_patch",
+            "Edit part of a file with a unified diff or search/replace blocks. Use when: applying focused edits to existing code/docs; preview with dry_run first. Don't use when: creating a new file.",
         ),
         (
             "memory_store",
@@ -796,6 +1053,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
             "memory_forget",
             "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
         ),
+        (
+            "archive",
+            "Create, list or extract zip/tar.gz archives in the workspace. Use when: packing backups, unpacking downloaded datasets. Don't use when: the archive comes from an untrusted source and you have not listed it first.",
+        ),
+        (
+            "shell_job_start",
+            "Run a long shell command in the background and get a job id. Use when: builds, test suites or downloads that may exceed the shell timeout. Don't use when: the command finishes quickly; use shell.",
+        ),
+        (
+            "shell_job_logs",
+            "Read new output of a background job (pass next_offset as since). Use when: checking on a job from shell_job_start; see also shell_job_status and shell_job_kill.",
+        ),
     ];
 
     if config.browser.enabled {
@@ -817,14 +1086,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
         ));
     }
 
+    let (tool_descs, listed_skills) =
+        ToolRouter::prompt_inputs(tool_router.as_deref(), tool_descs, &skills);
     let mut system_prompt = build_system_prompt(
         &workspace,
         &model,
         &tool_descs,
-        &skills,
+        listed_skills,
         Some(&config.identity),
     );
-    system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
+    if !provider.supports_native_tools() && tool_router.is_none() {
+        system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
+    }
 
     if !skills.is_empty() {
         println!(
@@ -837,73 +1110,23 @@ pub async fn start_channels(config: Config) -> Result<()> {
         );
     }
 
-    // Collect active channels
-    let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
-
-    if let Some(ref tg) = config.channels_config.telegram {
-        channels.push(Arc::new(TelegramChannel::new(
-            tg.bot_token.clone(),
-            tg.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref dc) = config.channels_config.discord {
-        channels.push(Arc::new(DiscordChannel::new(
-            dc.bot_token.clone(),
-            dc.guild_id.clone(),
-            dc.allowed_users.clone(),
-            dc.listen_to_bots,
-        )));
-    }
-
-    if let Some(ref sl) = config.channels_config.slack {
-        channels.push(Arc::new(SlackChannel::new(
-            sl.bot_token.clone(),
-            sl.channel_id.clone(),
-            sl.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref im) = config.channels_config.imessage {
-        channels.push(Arc::new(IMessageChannel::new(im.allowed_contacts.clone())));
-    }
-
-    if let Some(ref mx) = config.channels_config.matrix {
-        channels.push(Arc::new(MatrixChannel::new(
-            mx.homeserver.clone(),
-            mx.access_token.clone(),
-            mx.room_id.clone(),
-            mx.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref wa) = config.channels_config.whatsapp {
-        channels.push(Arc::new(WhatsAppChannel::new(
-            wa.access_token.clone(),
-            wa.phone_number_id.clone(),
-            wa.verify_token.clone(),
-            wa.allowed_numbers.clone(),
-        )));
-    }
-
-    if let Some(ref email_cfg) = config.channels_config.email {
-        channels.push(Arc::new(EmailChannel::new(email_cfg.clone())));
-    }
-
-    if let Some(ref irc) = config.channels_config.irc {
-        channels.push(Arc::new(IrcChannel::new(
-            irc.server.clone(),
-            irc.port,
-            irc.nickname.clone(),
-            irc.username.clone(),
-            irc.channels.clone(),
-            irc.allowed_users.clone(),
-            irc.server_password.clone(),
-            irc.nickserv_password.clone(),
-            irc.sasl_password.clone(),
-            irc.verify_tls.unwrap_or(true),
-        )));
-    }
+    // Collect active channels; a Telegram bot in polling or webhook mode
+    // and a Discord bot with `gateway = true` are run by the gateway
+    let gateway_telegram = config
+        .channels_config
+        .telegram
+        .as_ref()
+        .is_some_and(|tg| tg.mode != crate::config::TelegramMode::Channels);
+    let gateway_discord = config
+        .channels_config
+        .discord
+        .as_ref()
+        .is_some_and(|dc| dc.gateway);
+    let channels: Vec<_> = configured_channels(&config)
+        .into_iter()
+        .filter(|ch| !(gateway_telegram && ch.name() == "telegram"))
+        .filter(|ch| !(gateway_discord && ch.name() == "discord"))
+        .collect();
 
     if channels.is_empty() {
         println!("No channels configured. Run `mymolt onboard` to set up channels.");
@@ -941,7 +1164,9 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .max(DEFAULT_CHANNEL_MAX_BACKOFF_SECS);
 
     // Single message bus — all channels send messages here
-    let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
+    let (bus, rx) = router::ChannelRouter::new();
+    let tx = bus.sender();
+    drop(bus);
 
     // Spawn a listener for each channel
     let mut handles = Vec::new();
@@ -965,6 +1190,31 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
     println!("  🚦 In-flight message limit: {max_in_flight_messages}");
 
+    let capture = config.capture.enabled.then(|| {
+        Arc::new(CaptureInbox::new(
+            config.capture.clone(),
+            capture::parse_model(&config.capture, &config.model_routes, &provider_name, &model),
+            config.workspace_dir.clone(),
+        ))
+    });
+
+    // Telegram button presses arrive through this server's listener, so it
+    // delivers the requests there; WhatsApp replies reach the gateway.
+    let family = Arc::new(FamilyRegistry::from_config(&config.family));
+    let mut relay =
+        ConfirmationRelay::new(Arc::clone(&confirm_gate)).with_family(Arc::clone(&family));
+    if let Some(telegram) = channels_by_name.get("telegram") {
+        relay = relay.with_target(
+            Arc::clone(telegram),
+            config.security.confirmation.telegram_chats.clone(),
+        );
+    }
+    let confirm_relay = (!relay.is_empty()).then(|| {
+        let relay = Arc::new(relay);
+        relay.spawn();
+        relay
+    });
+
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
         provider: Arc::clone(&provider),
@@ -973,9 +1223,20 @@ pub async fn start_channels(config: Config) -> Result<()> {
         tools_registry: Arc::clone(&tools_registry),
         observer,
         system_prompt: Arc::new(system_prompt),
-        model: Arc::new(model.clone()),
+        models,
         temperature,
+        budget: LoopBudget::from_config(&config.agent),
+        planner: Planner::from_config(&config.agent, Arc::clone(&security)),
+        tool_router,
         auto_save_memory: config.memory.auto_save,
+        capture,
+        confirm_relay,
+        family,
+        groups: Arc::new(config.channels_config.groups.clone()),
+        seen: dedup::SeenMessages::shared(
+            &config.workspace_dir,
+            Duration::from_secs(config.channels_config.message_dedup_ttl_secs),
+        ),
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
@@ -1139,6 +1400,7 @@ mod tests {
                     success: false,
                     output: String::new(),
                     error: Some("unexpected symbol".to_string()),
+                    data: None,
                 });
             }
 
@@ -1146,12 +1408,14 @@ mod tests {
                 success: true,
                 output: r#"{"symbol":"BTC","price_usd":65000}"#.to_string(),
                 error: None,
+                data: None,
             })
         }
     }
 
     #[tokio::test]
     async fn process_channel_message_executes_tool_calls_instead_of_sending_raw_json() {
+        let workspace = tempfile::tempdir().unwrap();
         let channel_impl = Arc::new(RecordingChannel::default());
         let channel: Arc<dyn Channel> = channel_impl.clone();
 
@@ -1166,9 +1430,17 @@ mod tests {
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
-            model: Arc::new("test-model".to_string()),
+            models: Arc::new(ModelRouter::new("test-model", &[])),
             temperature: 0.0,
+            budget: LoopBudget::default(),
+            planner: None,
+            tool_router: None,
             auto_save_memory: false,
+            capture: None,
+            confirm_relay: None,
+            family: Arc::new(FamilyRegistry::empty()),
+            groups: Arc::new(Vec::new()),
+            seen: dedup::SeenMessages::shared(workspace.path(), Duration::from_mins(5)),
         });
 
         process_channel_message(
@@ -1179,6 +1451,8 @@ mod tests {
                 content: "What is the BTC price now?".to_string(),
                 channel: "test-channel".to_string(),
                 timestamp: 1,
+                attachments: Vec::new(),
+                group: None,
             },
         )
         .await;
@@ -1241,6 +1515,7 @@ mod tests {
 
     #[tokio::test]
     async fn message_dispatch_processes_messages_in_parallel() {
+        let workspace = tempfile::tempdir().unwrap();
         let channel_impl = Arc::new(RecordingChannel::default());
         let channel: Arc<dyn Channel> = channel_impl.clone();
 
@@ -1257,9 +1532,17 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
-            model: Arc::new("test-model".to_string()),
+            models: Arc::new(ModelRouter::new("test-model", &[])),
             temperature: 0.0,
+            budget: LoopBudget::default(),
+            planner: None,
+            tool_router: None,
             auto_save_memory: false,
+            capture: None,
+            confirm_relay: None,
+            family: Arc::new(FamilyRegistry::empty()),
+            groups: Arc::new(Vec::new()),
+            seen: dedup::SeenMessages::shared(workspace.path(), Duration::from_mins(5)),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
@@ -1269,6 +1552,8 @@ mod tests {
             content: "hello".to_string(),
             channel: "test-channel".to_string(),
             timestamp: 1,
+            attachments: Vec::new(),
+            group: None,
         })
         .await
         .unwrap();
@@ -1278,6 +1563,8 @@ mod tests {
             content: "world".to_string(),
             channel: "test-channel".to_string(),
             timestamp: 2,
+            attachments: Vec::new(),
+            group: None,
         })
         .await
         .unwrap();
@@ -1297,6 +1584,60 @@ mod tests {
         assert_eq!(sent_messages.len(), 2);
     }
 
+    #[tokio::test]
+    async fn message_dispatch_answers_a_redelivered_message_once() {
+        let workspace = tempfile::tempdir().unwrap();
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(SlowProvider {
+                delay: Duration::from_millis(1),
+            }),
+            provider_name: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            models: Arc::new(ModelRouter::new("test-model", &[])),
+            temperature: 0.0,
+            budget: LoopBudget::default(),
+            planner: None,
+            tool_router: None,
+            auto_save_memory: false,
+            capture: None,
+            confirm_relay: None,
+            family: Arc::new(FamilyRegistry::empty()),
+            groups: Arc::new(Vec::new()),
+            seen: dedup::SeenMessages::shared(workspace.path(), Duration::from_mins(5)),
+        });
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
+        for _ in 0..2 {
+            tx.send(traits::ChannelMessage {
+                id: "wamid.1".to_string(),
+                sender: "alice".to_string(),
+                content: "hello".to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                attachments: Vec::new(),
+                group: None,
+            })
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        run_message_dispatch_loop(rx, runtime_ctx, 2).await;
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 1);
+    }
+
     #[test]
     fn prompt_contains_all_sections() {
         let ws = make_workspace();
@@ -1521,6 +1862,8 @@ mod tests {
             content: "hello".into(),
             channel: "slack".into(),
             timestamp: 1,
+            attachments: Vec::new(),
+            group: None,
         };
 
         assert_eq!(conversation_memory_key(&msg), "slack_U123_msg_abc123");
@@ -1534,6 +1877,8 @@ mod tests {
             content: "first".into(),
             channel: "slack".into(),
             timestamp: 1,
+            attachments: Vec::new(),
+            group: None,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -1541,6 +1886,8 @@ mod tests {
             content: "second".into(),
             channel: "slack".into(),
             timestamp: 2,
+            attachments: Vec::new(),
+            group: None,
         };
 
         assert_ne!(
@@ -1560,6 +1907,8 @@ mod tests {
             content: "I'm Paul".into(),
             channel: "slack".into(),
             timestamp: 1,
+            attachments: Vec::new(),
+            group: None,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -1567,6 +1916,8 @@ mod tests {
             content: "I'm 45".into(),
             channel: "slack".into(),
             timestamp: 2,
+            attachments: Vec::new(),
+            group: None,
         };
 
         mem.store(
@@ -1637,6 +1988,8 @@ mod tests {
             aieos_path: Some("aieos_identity.json".into()),
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
 
         let prompt = build_system_prompt(tmp.path(), "model", &[], &[], Some(&config));
@@ -1672,6 +2025,8 @@ mod tests {
             aieos_path: None,
             aieos_inline: Some(r#"{"identity":{"names":{"first":"Claw"}}}"#.into()),
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
 
         let prompt = build_system_prompt(
@@ -1695,6 +2050,8 @@ mod tests {
             aieos_path: Some("nonexistent.json".into()),
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
 
         let ws = make_workspace();
@@ -1715,6 +2072,8 @@ mod tests {
             aieos_path: None,
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
 
         let ws = make_workspace();
@@ -1734,6 +2093,8 @@ mod tests {
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
 
         let ws = make_workspace();
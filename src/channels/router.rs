@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! One inbound queue for the messages of every channel.
+//!
+//! Listeners and webhook handlers hand messages to a [`ChannelRouter`] and
+//! return at once, so a slow agent turn never holds up a webhook's answer.
+//! [`route`] then drops redelivered messages (see [`super::dedup`]),
+//! answers one sender's messages in the order they came and runs at most
+//! `per_channel` messages of a channel at a time.
+
+use super::dedup::SeenMessages;
+use super::traits::ChannelMessage;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+/// Messages waiting to be routed.
+pub const QUEUE_SIZE: usize = 256;
+
+/// A sender's lane closes after this long without messages.
+const LANE_IDLE: Duration = Duration::from_mins(1);
+
+/// Where channels put inbound messages; the receiver goes to [`route`].
+#[derive(Clone)]
+pub struct ChannelRouter {
+    tx: mpsc::Sender<ChannelMessage>,
+}
+
+impl ChannelRouter {
+    pub fn new() -> (Self, mpsc::Receiver<ChannelMessage>) {
+        let (tx, rx) = mpsc::channel(QUEUE_SIZE);
+        (Self { tx }, rx)
+    }
+
+    /// Sender for channel listeners, which wait while the queue is full.
+    pub fn sender(&self) -> mpsc::Sender<ChannelMessage> {
+        self.tx.clone()
+    }
+
+    /// Queue a message without waiting. Returns false when the queue is
+    /// full, so a webhook can ask the platform to deliver it again later.
+    pub fn submit(&self, msg: ChannelMessage) -> bool {
+        match self.tx.try_send(msg) {
+            Ok(()) => true,
+            Err(e) => {
+                let msg = e.into_inner();
+                tracing::warn!("Channel queue is full; {} message not queued", msg.channel);
+                false
+            }
+        }
+    }
+}
+
+/// Hand each message from `rx` to `handle` until the queue closes, then
+/// wait for the messages still being handled.
+pub async fn route<F, Fut>(
+    mut rx: mpsc::Receiver<ChannelMessage>,
+    seen: Arc<SeenMessages>,
+    per_channel: usize,
+    handle: F,
+) where
+    F: Fn(ChannelMessage) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    // One lane per channel and sender; its task ends when the lane idles
+    let mut lanes: HashMap<String, mpsc::UnboundedSender<ChannelMessage>> = HashMap::new();
+    let mut limits: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let mut workers = JoinSet::new();
+
+    while let Some(msg) = rx.recv().await {
+        if !seen.record_if_new(&msg.channel, &msg.id) {
+            tracing::info!("Duplicate {} message {} ignored", msg.channel, msg.id);
+            continue;
+        }
+        let key = format!("{}:{}", msg.channel, msg.sender);
+        let msg = match lanes.get(&key) {
+            Some(lane) => match lane.send(msg) {
+                Ok(()) => continue,
+                // The lane just closed; open a new one
+                Err(e) => e.0,
+            },
+            None => msg,
+        };
+
+        lanes.retain(|_, lane| !lane.is_closed());
+        while let Some(result) = workers.try_join_next() {
+            log_worker_join_result(result);
+        }
+        let limit = limits
+            .entry(msg.channel.clone())
+            .or_insert_with(|| Arc::new(Semaphore::new(per_channel.max(1))));
+        let (tx, lane) = mpsc::unbounded_channel();
+        let _ = tx.send(msg);
+        lanes.insert(key, tx);
+        workers.spawn(drain_lane(lane, Arc::clone(limit), handle.clone()));
+    }
+
+    drop(lanes);
+    while let Some(result) = workers.join_next().await {
+        log_worker_join_result(result);
+    }
+}
+
+fn log_worker_join_result(result: Result<(), tokio::task::JoinError>) {
+    if let Err(error) = result {
+        tracing::error!("Channel message worker crashed: {error}");
+    }
+}
+
+/// Handle one sender's messages in order.
+async fn drain_lane<F, Fut>(
+    mut lane: mpsc::UnboundedReceiver<ChannelMessage>,
+    limit: Arc<Semaphore>,
+    handle: F,
+) where
+    F: Fn(ChannelMessage) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    loop {
+        let msg = match tokio::time::timeout(LANE_IDLE, lane.recv()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => return,
+            Err(_) => {
+                // Refuse new messages, then finish the ones already queued
+                lane.close();
+                continue;
+            }
+        };
+        let Ok(_permit) = limit.acquire().await else {
+            return;
+        };
+        handle(msg).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn message(id: &str, sender: &str, channel: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: sender.into(),
+            content: format!("message {id}"),
+            channel: channel.into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+            group: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn one_senders_messages_are_handled_in_order() {
+        let workspace = tempfile::tempdir().unwrap();
+        let seen = SeenMessages::shared(workspace.path(), Duration::from_mins(5));
+        let (router, rx) = ChannelRouter::new();
+        for id in ["1", "2", "3", "2"] {
+            assert!(router.submit(message(id, "alice", "whatsapp")));
+        }
+        drop(router);
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&handled);
+        route(rx, seen, 4, move |msg| {
+            let log = Arc::clone(&log);
+            async move {
+                // Later messages finish sooner unless they wait their turn
+                let delay = 30 - 10 * msg.id.parse::<u64>().unwrap();
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                log.lock().unwrap().push(msg.id);
+            }
+        })
+        .await;
+
+        // The redelivered "2" is dropped
+        assert_eq!(*handled.lock().unwrap(), ["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn channels_run_at_most_per_channel_messages_at_once() {
+        let workspace = tempfile::tempdir().unwrap();
+        let seen = SeenMessages::shared(workspace.path(), Duration::from_mins(5));
+        let (router, rx) = ChannelRouter::new();
+        for i in 0..6 {
+            assert!(router.submit(message(&i.to_string(), &format!("s{i}"), "telegram")));
+        }
+        assert!(router.submit(message("x", "s0", "discord")));
+        drop(router);
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (r, p) = (Arc::clone(&running), Arc::clone(&peak));
+        route(rx, seen, 2, move |msg| {
+            let (running, peak) = (Arc::clone(&r), Arc::clone(&p));
+            async move {
+                if msg.channel == "telegram" {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,7 +4,6 @@
 
 use super::traits::{Channel, ChannelMessage};
 use async_trait::async_trait;
-use uuid::Uuid;
 
 /// Slack channel — polls conversations.history via Web API
 pub struct SlackChannel {
@@ -163,8 +162,9 @@ impl Channel for SlackChannel {
 
                     last_ts = ts.to_string();
 
+                    // `ts` identifies the message, also after a restart
                     let channel_msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                        id: format!("{channel_id}:{ts}"),
                         sender: channel_id.clone(),
                         content: text.to_string(),
                         channel: "slack".to_string(),
@@ -172,6 +172,8 @@ impl Channel for SlackChannel {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_secs(),
+                        attachments: Vec::new(),
+                        group: None,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -2,11 +2,13 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use super::traits::{Channel, ChannelMessage};
+use super::traits::{Attachment, Channel, ChannelMessage, GroupMessage};
+use crate::security::confirmation::ConfirmationRequest;
 use async_trait::async_trait;
 use reqwest::multipart::{Form, Part};
 use std::path::Path;
 use std::time::Duration;
+use tokio::sync::OnceCell;
 use uuid::Uuid;
 
 /// Telegram's maximum message length for text messages
@@ -56,11 +58,124 @@ fn split_message_for_telegram(message: &str) -> Vec<String> {
     chunks
 }
 
+/// Largest attachment downloaded from Telegram.
+const MAX_DOWNLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+/// File ID and MIME type of the image in a message: the largest size of a
+/// photo, or a document sent as an image file.
+fn image_file(message: &serde_json::Value) -> Option<(&str, String)> {
+    if let Some(largest) = message
+        .get("photo")
+        .and_then(serde_json::Value::as_array)
+        .and_then(|sizes| sizes.last())
+    {
+        let file_id = largest.get("file_id").and_then(serde_json::Value::as_str)?;
+        return Some((file_id, "image/jpeg".to_string()));
+    }
+    let document = message.get("document")?;
+    let mime_type = document.get("mime_type").and_then(serde_json::Value::as_str)?;
+    if !mime_type.starts_with("image/") {
+        return None;
+    }
+    let file_id = document.get("file_id").and_then(serde_json::Value::as_str)?;
+    Some((file_id, mime_type.to_string()))
+}
+
+/// File ID of the voice note in a message.
+fn voice_file(message: &serde_json::Value) -> Option<&str> {
+    message
+        .get("voice")
+        .and_then(|voice| voice.get("file_id"))
+        .and_then(serde_json::Value::as_str)
+}
+
+/// Inline keyboard answering confirmation `request_id`; the callback data
+/// reads like a typed reply ("yes <id>"), so both resolve the same way.
+fn confirmation_keyboard(request_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "inline_keyboard": [[
+            {"text": "✅ Approve", "callback_data": format!("yes {request_id}")},
+            {"text": "❌ Deny", "callback_data": format!("no {request_id}")}
+        ]]
+    })
+}
+
+/// The bot's own account, to recognise mentions of it in groups.
+#[derive(Debug)]
+struct BotUser {
+    id: i64,
+    username: String,
+}
+
+fn is_group_chat(message: &serde_json::Value) -> bool {
+    matches!(
+        message
+            .get("chat")
+            .and_then(|c| c.get("type"))
+            .and_then(serde_json::Value::as_str),
+        Some("group" | "supergroup")
+    )
+}
+
+/// Whether a group message is addressed to `bot`: it mentions the bot's
+/// @username (also in a `/command@bot`), links to its account or replies to
+/// one of its messages.
+fn mentions_bot(message: &serde_json::Value, text: &str, bot: &BotUser) -> bool {
+    let replied_to = message
+        .get("reply_to_message")
+        .and_then(|r| r.get("from"))
+        .and_then(|f| f.get("id"))
+        .and_then(serde_json::Value::as_i64);
+    if replied_to == Some(bot.id) {
+        return true;
+    }
+    let linked = message
+        .get("entities")
+        .or_else(|| message.get("caption_entities"))
+        .and_then(serde_json::Value::as_array)
+        .is_some_and(|entities| {
+            entities.iter().any(|e| {
+                e.get("user")
+                    .and_then(|u| u.get("id"))
+                    .and_then(serde_json::Value::as_i64)
+                    == Some(bot.id)
+            })
+        });
+    linked || find_mention(text, &bot.username).is_some()
+}
+
+/// Byte range of the first `@username` in `text`, in any case.
+fn find_mention(text: &str, username: &str) -> Option<std::ops::Range<usize>> {
+    let mention = format!("@{}", username.to_ascii_lowercase());
+    let lower = text.to_ascii_lowercase();
+    let start = lower.match_indices(&mention).map(|(i, _)| i).find(|&i| {
+        // Not the start of a longer username
+        !lower[i + mention.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+    })?;
+    Some(start..start + mention.len())
+}
+
+/// `text` without its mentions of `username`.
+fn strip_mentions(text: &str, username: &str) -> String {
+    let mut text = text.to_string();
+    while let Some(range) = find_mention(&text, username) {
+        text.replace_range(range, "");
+    }
+    text.trim().to_string()
+}
+
 /// Telegram channel — long-polls the Bot API for updates
 pub struct TelegramChannel {
     bot_token: String,
     allowed_users: Vec<String>,
     client: reqwest::Client,
+    /// Pass voice notes on as `audio/ogg` attachments
+    voice_messages: bool,
+    /// Fetched with `getMe` at the first group message
+    me: OnceCell<BotUser>,
 }
 
 impl TelegramChannel {
@@ -69,13 +184,79 @@ impl TelegramChannel {
             bot_token,
             allowed_users,
             client: reqwest::Client::new(),
+            voice_messages: false,
+            me: OnceCell::new(),
         }
     }
 
+    /// Download voice notes as `audio/ogg` attachments for the caller to
+    /// transcribe.
+    pub fn with_voice_messages(mut self) -> Self {
+        self.voice_messages = true;
+        self
+    }
+
+    async fn me(&self) -> anyhow::Result<&BotUser> {
+        self.me
+            .get_or_try_init(|| async {
+                let resp: serde_json::Value = self
+                    .client
+                    .post(self.api_url("getMe"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                let user = &resp["result"];
+                Ok(BotUser {
+                    id: user["id"]
+                        .as_i64()
+                        .ok_or_else(|| anyhow::anyhow!("getMe returned no bot ID"))?,
+                    username: user["username"].as_str().unwrap_or_default().to_string(),
+                })
+            })
+            .await
+    }
+
     fn api_url(&self, method: &str) -> String {
         format!("https://api.telegram.org/bot{}/{method}", self.bot_token)
     }
 
+    fn file_url(&self, file_path: &str) -> String {
+        format!("https://api.telegram.org/file/bot{}/{file_path}", self.bot_token)
+    }
+
+    /// Download a file sent to the bot (photos, documents).
+    async fn download_file(&self, file_id: &str) -> anyhow::Result<Vec<u8>> {
+        let info: serde_json::Value = self
+            .client
+            .post(self.api_url("getFile"))
+            .json(&serde_json::json!({ "file_id": file_id }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let file_path = info
+            .get("result")
+            .and_then(|r| r.get("file_path"))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("getFile returned no file_path"))?;
+
+        let bytes = self
+            .client
+            .get(self.file_url(file_path))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        if bytes.len() > MAX_DOWNLOAD_BYTES {
+            anyhow::bail!("file exceeds {} MB", MAX_DOWNLOAD_BYTES / (1024 * 1024));
+        }
+        Ok(bytes.to_vec())
+    }
+
     fn is_user_allowed(&self, username: &str) -> bool {
         self.allowed_users.iter().any(|u| u == "*" || u == username)
     }
@@ -87,6 +268,237 @@ impl TelegramChannel {
         identities.into_iter().any(|id| self.is_user_allowed(id))
     }
 
+    /// A button press as a message from the chat it was pressed in, if the
+    /// user is allowed.
+    fn callback_message(&self, query: &serde_json::Value) -> Option<ChannelMessage> {
+        let data = query.get("data").and_then(serde_json::Value::as_str)?;
+        let from = query.get("from")?;
+        let username = from
+            .get("username")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown");
+        let user_id = from
+            .get("id")
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id {
+            identities.push(id.as_str());
+        }
+        if !self.is_any_user_allowed(identities) {
+            tracing::warn!("Telegram: ignoring button press from unauthorized user {username}");
+            return None;
+        }
+        let chat_id = query
+            .get("message")
+            .and_then(|m| m.get("chat"))
+            .and_then(|c| c.get("id"))
+            .and_then(serde_json::Value::as_i64)?;
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id.to_string(),
+            content: data.to_string(),
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments: Vec::new(),
+            group: None,
+        })
+    }
+
+    /// Have Telegram post updates to `url`, sending `secret` in the
+    /// `X-Telegram-Bot-Api-Secret-Token` header.
+    pub async fn set_webhook(&self, url: &str, secret: &str) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "url": url,
+            "secret_token": secret,
+            "allowed_updates": ["message", "callback_query"]
+        });
+        let resp = self
+            .client
+            .post(self.api_url("setWebhook"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram setWebhook failed: {err}");
+        }
+        Ok(())
+    }
+
+    /// Remove a registered webhook; `getUpdates` fails while one is set.
+    pub async fn delete_webhook(&self) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .post(self.api_url("deleteWebhook"))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram deleteWebhook failed: {err}");
+        }
+        Ok(())
+    }
+
+    /// The message carried by one update from `getUpdates` or a webhook,
+    /// if it comes from an allowed user. Button presses are acknowledged
+    /// and become replies from the chat; images (and voice notes, see
+    /// [`Self::with_voice_messages`]) are downloaded as attachments.
+    /// Messages in groups come from the group, with a [`GroupMessage`]
+    /// naming their author.
+    ///
+    /// The message keeps the update's ID, which Telegram repeats when it
+    /// delivers an update again.
+    pub async fn handle_update(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let mut msg = self.update_message(update).await?;
+        if let Some(id) = update.get("update_id").and_then(serde_json::Value::as_i64) {
+            msg.id = id.to_string();
+        }
+        Some(msg)
+    }
+
+    async fn update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        if let Some(query) = update.get("callback_query") {
+            // Stop the button's loading indicator
+            if let Some(id) = query.get("id").and_then(serde_json::Value::as_str) {
+                let _ = self
+                    .client
+                    .post(self.api_url("answerCallbackQuery"))
+                    .json(&serde_json::json!({ "callback_query_id": id }))
+                    .send()
+                    .await;
+            }
+            return self.callback_message(query);
+        }
+
+        let message = update.get("message")?;
+
+        let text = message
+            .get("text")
+            .or_else(|| message.get("caption"))
+            .and_then(serde_json::Value::as_str);
+        let image = image_file(message);
+        let voice = voice_file(message).filter(|_| self.voice_messages);
+        if text.is_none() && image.is_none() && voice.is_none() {
+            return None;
+        }
+
+        let username_opt = message
+            .get("from")
+            .and_then(|f| f.get("username"))
+            .and_then(|u| u.as_str());
+        let username = username_opt.unwrap_or("unknown");
+
+        let user_id = message
+            .get("from")
+            .and_then(|f| f.get("id"))
+            .and_then(serde_json::Value::as_i64);
+        let user_id_str = user_id.map(|id| id.to_string());
+
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id_str {
+            identities.push(id.as_str());
+        }
+
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            tracing::warn!(
+                "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
+Allowlist Telegram @username or numeric user ID, then run `mymolt onboard --channels-only`.",
+                user_id_str.as_deref().unwrap_or("unknown")
+            );
+            return None;
+        }
+
+        let chat_id = message
+            .get("chat")
+            .and_then(|c| c.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+
+        let Some(chat_id) = chat_id else {
+            tracing::warn!("Telegram: missing chat_id in message, skipping");
+            return None;
+        };
+
+        let mut text = text.map(str::to_string);
+        let group = if is_group_chat(message) {
+            let me = match self.me().await {
+                Ok(me) => Some(me),
+                Err(e) => {
+                    tracing::warn!("Telegram: cannot look up the bot's account: {e}");
+                    None
+                }
+            };
+            let mentioned =
+                me.is_some_and(|me| mentions_bot(message, text.as_deref().unwrap_or_default(), me));
+            if let (Some(me), Some(t)) = (me, text.as_mut()) {
+                *t = strip_mentions(t, &me.username);
+            }
+            Some(GroupMessage {
+                author: user_id_str.clone().unwrap_or_else(|| username.to_string()),
+                mentioned,
+            })
+        } else {
+            None
+        };
+
+        // Send "typing" indicator immediately when we receive a message
+        // that is likely to be answered
+        if group.as_ref().is_none_or(|g| g.mentioned) {
+            let typing_body = serde_json::json!({
+                "chat_id": &chat_id,
+                "action": "typing"
+            });
+            let _ = self
+                .client
+                .post(self.api_url("sendChatAction"))
+                .json(&typing_body)
+                .send()
+                .await; // Ignore errors for typing indicator
+        }
+
+        let mut attachments = Vec::new();
+        if let Some((file_id, mime_type)) = image {
+            match self.download_file(file_id).await {
+                Ok(data) => attachments.push(Attachment {
+                    mime_type,
+                    data,
+                    file_name: None,
+                }),
+                Err(e) => tracing::warn!("Telegram: failed to download image: {e}"),
+            }
+        }
+        if let Some(file_id) = voice {
+            match self.download_file(file_id).await {
+                Ok(data) => attachments.push(Attachment {
+                    mime_type: "audio/ogg".to_string(),
+                    data,
+                    file_name: None,
+                }),
+                Err(e) => tracing::warn!("Telegram: failed to download voice note: {e}"),
+            }
+        }
+        if text.is_none() && attachments.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id,
+            content: text.unwrap_or_default(),
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+            group,
+        })
+    }
+
     /// Send a document/file to a Telegram chat
     pub async fn send_document(
         &self,
@@ -510,7 +922,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.client.post(&url).json(&body).send().await {
@@ -538,82 +950,42 @@ impl Channel for TelegramChannel {
                         offset = uid + 1;
                     }
 
-                    let Some(message) = update.get("message") else {
-                        continue;
-                    };
-
-                    let Some(text) = message.get("text").and_then(serde_json::Value::as_str) else {
-                        continue;
-                    };
-
-                    let username_opt = message
-                        .get("from")
-                        .and_then(|f| f.get("username"))
-                        .and_then(|u| u.as_str());
-                    let username = username_opt.unwrap_or("unknown");
-
-                    let user_id = message
-                        .get("from")
-                        .and_then(|f| f.get("id"))
-                        .and_then(serde_json::Value::as_i64);
-                    let user_id_str = user_id.map(|id| id.to_string());
-
-                    let mut identities = vec![username];
-                    if let Some(ref id) = user_id_str {
-                        identities.push(id.as_str());
-                    }
-
-                    if !self.is_any_user_allowed(identities.iter().copied()) {
-                        tracing::warn!(
-                            "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
-Allowlist Telegram @username or numeric user ID, then run `mymolt onboard --channels-only`.",
-                            user_id_str.as_deref().unwrap_or("unknown")
-                        );
-                        continue;
-                    }
-
-                    let chat_id = message
-                        .get("chat")
-                        .and_then(|c| c.get("id"))
-                        .and_then(serde_json::Value::as_i64)
-                        .map(|id| id.to_string());
-
-                    let Some(chat_id) = chat_id else {
-                        tracing::warn!("Telegram: missing chat_id in message, skipping");
-                        continue;
-                    };
-
-                    // Send "typing" indicator immediately when we receive a message
-                    let typing_body = serde_json::json!({
-                        "chat_id": &chat_id,
-                        "action": "typing"
-                    });
-                    let _ = self
-                        .client
-                        .post(self.api_url("sendChatAction"))
-                        .json(&typing_body)
-                        .send()
-                        .await; // Ignore errors for typing indicator
-
-                    let msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        sender: chat_id,
-                        content: text.to_string(),
-                        channel: "telegram".to_string(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
-                    };
-
-                    if tx.send(msg).await.is_err() {
-                        return Ok(());
+                    if let Some(msg) = self.handle_update(update).await {
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
                     }
                 }
             }
         }
     }
 
+    async fn send_confirmation(
+        &self,
+        request: &ConfirmationRequest,
+        recipient: &str,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "chat_id": recipient,
+            "text": format!(
+                "🔐 Approval needed ({} risk)\n{}: {}\n\nAnswer within {}s.",
+                request.risk_level, request.tool_name, request.description, request.timeout_secs
+            ),
+            "reply_markup": confirmation_keyboard(&request.id),
+        });
+        let resp = self
+            .client
+            .post(self.api_url("sendMessage"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram sendMessage with buttons failed: {err}");
+        }
+        Ok(())
+    }
+
     async fn health_check(&self) -> bool {
         let timeout_duration = Duration::from_secs(5);
 
@@ -655,6 +1027,141 @@ mod tests {
         );
     }
 
+    #[test]
+    fn telegram_file_url() {
+        let ch = TelegramChannel::new("123:ABC".into(), vec![]);
+        assert_eq!(
+            ch.file_url("photos/file_1.jpg"),
+            "https://api.telegram.org/file/bot123:ABC/photos/file_1.jpg"
+        );
+    }
+
+    #[test]
+    fn telegram_image_file_picks_largest_photo() {
+        let message = serde_json::json!({
+            "photo": [
+                {"file_id": "small", "width": 90},
+                {"file_id": "large", "width": 1280}
+            ],
+            "caption": "school fete"
+        });
+        assert_eq!(
+            image_file(&message),
+            Some(("large", "image/jpeg".to_string()))
+        );
+
+        let document = serde_json::json!({
+            "document": {"file_id": "doc", "mime_type": "image/png"}
+        });
+        assert_eq!(
+            image_file(&document),
+            Some(("doc", "image/png".to_string()))
+        );
+
+        let pdf = serde_json::json!({
+            "document": {"file_id": "doc", "mime_type": "application/pdf"}
+        });
+        assert_eq!(image_file(&pdf), None);
+    }
+
+    #[tokio::test]
+    async fn telegram_voice_notes_are_opt_in() {
+        let message = serde_json::json!({
+            "voice": {"file_id": "voice-1", "duration": 3},
+            "from": {"id": 42, "username": "alice"},
+            "chat": {"id": 42}
+        });
+        assert_eq!(voice_file(&message), Some("voice-1"));
+        assert_eq!(voice_file(&serde_json::json!({"text": "hi"})), None);
+
+        // Without `with_voice_messages` a voice note carries nothing to answer
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()]);
+        let update = serde_json::json!({"update_id": 1, "message": message});
+        assert!(ch.handle_update(&update).await.is_none());
+    }
+
+    #[test]
+    fn telegram_mentions_of_the_bot() {
+        let bot = BotUser {
+            id: 99,
+            username: "MyMoltBot".into(),
+        };
+        let plain = serde_json::json!({});
+        assert!(mentions_bot(&plain, "hey @mymoltbot, lights off", &bot));
+        assert!(mentions_bot(&plain, "/ask@MyMoltBot weather", &bot));
+        assert!(!mentions_bot(&plain, "ask @MyMoltBot_fan", &bot));
+        assert!(!mentions_bot(&plain, "lights off", &bot));
+
+        let reply = serde_json::json!({"reply_to_message": {"from": {"id": 99}}});
+        assert!(mentions_bot(&reply, "and the kitchen?", &bot));
+
+        assert_eq!(
+            strip_mentions("@MyMoltBot what's for dinner? @mymoltbot", "MyMoltBot"),
+            "what's for dinner?"
+        );
+    }
+
+    #[tokio::test]
+    async fn telegram_group_messages_come_from_the_group() {
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()]);
+        ch.me
+            .set(BotUser {
+                id: 99,
+                username: "MyMoltBot".into(),
+            })
+            .unwrap();
+        let update = serde_json::json!({
+            "update_id": 5,
+            "message": {
+                "text": "@MyMoltBot lights off",
+                "from": {"id": 42, "username": "alice"},
+                "chat": {"id": -10042, "type": "supergroup"}
+            }
+        });
+        let msg = ch.handle_update(&update).await.unwrap();
+        assert_eq!(msg.sender, "-10042");
+        assert_eq!(msg.content, "lights off");
+        let group = msg.group.unwrap();
+        assert_eq!(group.author, "42");
+        assert!(group.mentioned);
+
+        let direct = serde_json::json!({
+            "update_id": 6,
+            "message": {
+                "text": "lights off",
+                "from": {"id": 42, "username": "alice"},
+                "chat": {"id": 42, "type": "private"}
+            }
+        });
+        assert!(ch.handle_update(&direct).await.unwrap().group.is_none());
+    }
+
+    #[test]
+    fn telegram_button_press_becomes_a_reply_from_the_chat() {
+        let ch = TelegramChannel::new("123:ABC".into(), vec!["alice".into()]);
+        let keyboard = confirmation_keyboard("3f2a9c10-0000");
+        let data = keyboard["inline_keyboard"][0][0]["callback_data"].clone();
+        assert_eq!(data, "yes 3f2a9c10-0000");
+
+        let query = serde_json::json!({
+            "id": "q1",
+            "from": {"id": 42, "username": "alice"},
+            "message": {"chat": {"id": 4242}},
+            "data": data
+        });
+        let msg = ch.callback_message(&query).unwrap();
+        assert_eq!(msg.sender, "4242");
+        assert_eq!(msg.content, "yes 3f2a9c10-0000");
+
+        let stranger = serde_json::json!({
+            "id": "q2",
+            "from": {"id": 7, "username": "mallory"},
+            "message": {"chat": {"id": 7}},
+            "data": "yes 3f2a9c10-0000"
+        });
+        assert!(ch.callback_message(&stranger).is_none());
+    }
+
     #[test]
     fn telegram_user_allowed_wildcard() {
         let ch = TelegramChannel::new("t".into(), vec!["*".into()]);
@@ -2,6 +2,7 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use crate::security::confirmation::ConfirmationRequest;
 use async_trait::async_trait;
 
 /// A message received from or sent to a channel
@@ -12,6 +13,36 @@ pub struct ChannelMessage {
     pub content: String,
     pub channel: String,
     pub timestamp: u64,
+    /// Media sent with the message (photos, documents). Empty for plain text.
+    pub attachments: Vec<Attachment>,
+    /// Set for messages posted in a group chat; `sender` is then the group,
+    /// where replies go.
+    pub group: Option<GroupMessage>,
+}
+
+/// Who wrote a group-chat message and whether it was addressed to the bot.
+#[derive(Debug, Clone)]
+pub struct GroupMessage {
+    /// Platform ID of the member who wrote it
+    pub author: String,
+    /// Whether it mentions the bot or replies to one of the bot's messages
+    pub mentioned: bool,
+}
+
+/// A file received with a channel message, already downloaded.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    /// MIME type as reported by the platform (e.g. `image/jpeg`).
+    pub mime_type: String,
+    pub data: Vec<u8>,
+    /// File name given by the sender, when the platform has one.
+    pub file_name: Option<String>,
+}
+
+impl Attachment {
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -41,6 +72,18 @@ pub trait Channel: Send + Sync {
     async fn stop_typing(&self, _recipient: &str) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Ask `recipient` to approve or deny a confirmation request.
+    /// Defaults to a text message answered by replying "yes" or "no" with
+    /// the request code; platforms with buttons should override this.
+    async fn send_confirmation(
+        &self,
+        request: &ConfirmationRequest,
+        recipient: &str,
+    ) -> anyhow::Result<()> {
+        self.send(&super::confirm_relay::confirmation_text(request), recipient)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -69,6 +112,8 @@ mod tests {
                 content: "hello".into(),
                 channel: "dummy".into(),
                 timestamp: 123,
+                attachments: Vec::new(),
+                group: None,
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -83,6 +128,8 @@ mod tests {
             content: "ping".into(),
             channel: "dummy".into(),
             timestamp: 999,
+            attachments: Vec::new(),
+            group: None,
         };
 
         let cloned = message.clone();
@@ -2,10 +2,15 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use super::traits::{Channel, ChannelMessage};
+use super::traits::{Attachment, Channel, ChannelMessage};
 use async_trait::async_trait;
 use uuid::Uuid;
 
+const GRAPH_API: &str = "https://graph.facebook.com/v18.0";
+
+/// Largest media file downloaded from a message
+const MAX_MEDIA_BYTES: u64 = 10 * 1024 * 1024;
+
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -46,8 +51,45 @@ impl WhatsAppChannel {
         &self.verify_token
     }
 
-    /// Parse an incoming webhook payload from Meta and extract messages
+    /// Parse an incoming webhook payload from Meta and extract its text
+    /// messages; [`Self::receive`] also takes media.
     pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        self.inbound(payload)
+            .into_iter()
+            .filter(|(_, media)| media.is_none())
+            .map(|(msg, _)| msg)
+            .collect()
+    }
+
+    /// Parse a webhook payload and download the voice notes, images and
+    /// documents it carries as attachments. Media that cannot be fetched
+    /// is dropped with its message.
+    pub async fn receive(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let mut messages = Vec::new();
+        for (mut msg, media) in self.inbound(payload) {
+            if let Some(media) = media {
+                match self.download_media(&media.id).await {
+                    Ok(data) => msg.attachments.push(Attachment {
+                        mime_type: media.mime_type,
+                        data,
+                        file_name: media.file_name,
+                    }),
+                    Err(e) => {
+                        tracing::warn!(
+                            "WhatsApp: failed to download media from {}: {e}",
+                            msg.sender
+                        );
+                        continue;
+                    }
+                }
+            }
+            messages.push(msg);
+        }
+        messages
+    }
+
+    /// Messages from allowed numbers, each with the media to fetch for it
+    fn inbound(&self, payload: &serde_json::Value) -> Vec<(ChannelMessage, Option<MediaRef>)> {
         let mut messages = Vec::new();
 
         // WhatsApp Cloud API webhook structure:
@@ -92,20 +134,24 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
-                    let content = if let Some(text_obj) = msg.get("text") {
-                        text_obj
-                            .get("body")
-                            .and_then(|b| b.as_str())
-                            .unwrap_or("")
-                            .to_string()
+                    // Text, or a voice note, image or document with its caption
+                    let (content, media) = if let Some(text_obj) = msg.get("text") {
+                        let body = text_obj.get("body").and_then(|b| b.as_str()).unwrap_or("");
+                        (body.to_string(), None)
+                    } else if let Some(media) = MediaRef::from_message(msg) {
+                        let caption = msg
+                            .get(media.kind)
+                            .and_then(|m| m.get("caption"))
+                            .and_then(|c| c.as_str())
+                            .unwrap_or("");
+                        (caption.to_string(), Some(media))
                     } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
+                        // Video, stickers, locations, ... are not handled
+                        tracing::debug!("WhatsApp: skipping unsupported message from {from}");
                         continue;
                     };
 
-                    if content.is_empty() {
+                    if content.is_empty() && media.is_none() {
                         continue;
                     }
 
@@ -121,19 +167,152 @@ impl WhatsAppChannel {
                                 .as_secs()
                         });
 
-                    messages.push(ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                    // The wamid, which Meta repeats when it redelivers a webhook
+                    let id = msg
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string);
+                    let message = ChannelMessage {
+                        id,
                         sender: normalized_from,
                         content,
                         channel: "whatsapp".to_string(),
                         timestamp,
-                    });
+                        attachments: Vec::new(),
+                        group: None,
+                    };
+                    messages.push((message, media));
                 }
             }
         }
 
         messages
     }
+
+    /// Fetch a media object: the Graph API resolves its id to a short-lived
+    /// URL, which needs the access token as well.
+    async fn download_media(&self, media_id: &str) -> anyhow::Result<Vec<u8>> {
+        let url = format!("{GRAPH_API}/{media_id}");
+        let info: serde_json::Value = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let size = info.get("file_size").and_then(serde_json::Value::as_u64);
+        if size.is_some_and(|size| size > MAX_MEDIA_BYTES) {
+            anyhow::bail!("media is larger than {MAX_MEDIA_BYTES} bytes");
+        }
+        let Some(media_url) = info.get("url").and_then(|u| u.as_str()) else {
+            anyhow::bail!("no download URL for media {media_id}");
+        };
+        let data = self
+            .client
+            .get(media_url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        if data.len() as u64 > MAX_MEDIA_BYTES {
+            anyhow::bail!("media is larger than {MAX_MEDIA_BYTES} bytes");
+        }
+        Ok(data.to_vec())
+    }
+
+    /// Send a file as an image or document message: upload it to the Graph
+    /// API, then send the uploaded media.
+    pub async fn send_file(
+        &self,
+        data: Vec<u8>,
+        file_name: &str,
+        mime_type: &str,
+        recipient: &str,
+    ) -> anyhow::Result<()> {
+        let part = reqwest::multipart::Part::bytes(data)
+            .file_name(file_name.to_string())
+            .mime_str(mime_type)?;
+        let form = reqwest::multipart::Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime_type.to_string())
+            .part("file", part);
+        let upload: serde_json::Value = self
+            .client
+            .post(format!("{GRAPH_API}/{}/media", self.phone_number_id))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let Some(media_id) = upload.get("id").and_then(|id| id.as_str()) else {
+            anyhow::bail!("WhatsApp media upload returned no id");
+        };
+
+        let to = recipient.strip_prefix('+').unwrap_or(recipient);
+        let body = if mime_type.starts_with("image/") {
+            serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "image",
+                "image": { "id": media_id }
+            })
+        } else {
+            serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "document",
+                "document": { "id": media_id, "filename": file_name }
+            })
+        };
+        let resp = self
+            .client
+            .post(format!("{GRAPH_API}/{}/messages", self.phone_number_id))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp file send failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+        Ok(())
+    }
+}
+
+/// A voice note, image or document to download from the Graph API
+#[derive(Debug, PartialEq)]
+struct MediaRef {
+    /// Message type, also the key of the media object
+    kind: &'static str,
+    id: String,
+    mime_type: String,
+    file_name: Option<String>,
+}
+
+impl MediaRef {
+    fn from_message(msg: &serde_json::Value) -> Option<Self> {
+        let kind = ["audio", "image", "document"]
+            .into_iter()
+            .find(|kind| msg.get(kind).is_some())?;
+        let media = msg.get(kind)?;
+        let field = |name: &str| media.get(name).and_then(|v| v.as_str()).map(str::to_string);
+        Some(Self {
+            kind,
+            id: field("id")?,
+            mime_type: field("mime_type").unwrap_or_else(|| "application/octet-stream".into()),
+            file_name: field("filename"),
+        })
+    }
 }
 
 #[async_trait]
@@ -144,10 +323,7 @@ impl Channel for WhatsAppChannel {
 
     async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
         // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.phone_number_id
-        );
+        let url = format!("{GRAPH_API}/{}/messages", self.phone_number_id);
 
         // Normalize recipient (remove leading + if present for API)
         let to = recipient.strip_prefix('+').unwrap_or(recipient);
@@ -199,7 +375,7 @@ impl Channel for WhatsAppChannel {
 
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
-        let url = format!("https://graph.facebook.com/v18.0/{}", self.phone_number_id);
+        let url = format!("{GRAPH_API}/{}", self.phone_number_id);
 
         self.client
             .get(&url)
@@ -299,6 +475,7 @@ mod tests {
         assert_eq!(msgs[0].content, "Hello MyMolt!");
         assert_eq!(msgs[0].channel, "whatsapp");
         assert_eq!(msgs[0].timestamp, 1_699_999_999);
+        assert_eq!(msgs[0].id, "wamid.xxx", "redeliveries are matched by wamid");
     }
 
     #[test]
@@ -1111,4 +1288,74 @@ mod tests {
             "<script>alert('xss')</script> & \"quotes\" 'apostrophe'"
         );
     }
+
+    #[test]
+    fn whatsapp_media_messages_are_inbound_with_their_media() {
+        let ch = make_channel();
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [
+                            {
+                                "from": "1234567890",
+                                "type": "audio",
+                                "audio": { "id": "a1", "mime_type": "audio/ogg; codecs=opus", "voice": true }
+                            },
+                            {
+                                "from": "1234567890",
+                                "type": "image",
+                                "image": { "id": "i1", "mime_type": "image/jpeg", "caption": "What is this?" }
+                            },
+                            {
+                                "from": "1234567890",
+                                "type": "document",
+                                "document": { "id": "d1", "mime_type": "application/pdf", "filename": "bill.pdf" }
+                            },
+                            {
+                                "from": "1234567890",
+                                "type": "video",
+                                "video": { "id": "v1", "mime_type": "video/mp4" }
+                            }
+                        ]
+                    }
+                }]
+            }]
+        });
+        let inbound = ch.inbound(&payload);
+        assert_eq!(inbound.len(), 3, "video is not handled");
+
+        let (voice, media) = &inbound[0];
+        assert_eq!(voice.content, "");
+        let media = media.as_ref().unwrap();
+        assert_eq!((media.kind, media.id.as_str()), ("audio", "a1"));
+        assert_eq!(media.mime_type, "audio/ogg; codecs=opus");
+
+        let (image, media) = &inbound[1];
+        assert_eq!(image.content, "What is this?");
+        assert_eq!(media.as_ref().unwrap().kind, "image");
+
+        let media = inbound[2].1.as_ref().unwrap();
+        assert_eq!(media.file_name.as_deref(), Some("bill.pdf"));
+        assert_eq!(media.mime_type, "application/pdf");
+    }
+
+    #[test]
+    fn whatsapp_media_without_id_is_skipped() {
+        let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [{
+                            "from": "111",
+                            "type": "image",
+                            "image": { "mime_type": "image/png" }
+                        }]
+                    }
+                }]
+            }]
+        });
+        assert!(ch.inbound(&payload).is_empty());
+    }
 }
@@ -6,13 +6,21 @@ pub mod schema;
 
 #[allow(unused_imports)]
 pub use schema::{
-    AuditConfig, AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config,
-    DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, FamilyConfig, FamilyMemberConfig,
-    GatewayConfig, HeartbeatConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig,
-    MatrixConfig, McpConfig, McpServerConfig, MemoryConfig, ModelRouteConfig, ObservabilityConfig,
-    ReliabilityConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
-    SecretsConfig, SecurityConfig, SlackConfig, SttConfig, TelegramConfig, TrustConfig,
-    TunnelConfig, WebhookConfig,
+    AgentConfig, AgentMode, AuditConfig, AuditSignature, AuthLockoutConfig, AutomationsConfig,
+    AutonomyConfig, BrowserConfig, CaptureConfig, ChannelsConfig, ComposioConfig, Config,
+    ConfirmationConfig, ConfirmationPolicyConfig, ContentCategory, ContentFilterConfig,
+    DbQueryConfig, DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, EgressConfig,
+    EgressRoleConfig, ExecutorConfig, FamilyConfig, FamilyMemberConfig, FeedDigestConfig,
+    FeedsConfig, GatewayConfig, GroupChatConfig, HeartbeatConfig, HttpRequestConfig,
+    IMessageConfig, IdentityConfig, IngestConfig, InjectionConfig, InjectionMode, KeyBackend,
+    LarkConfig, LeakGuardConfig, LeakGuardMode, LocationConfig, MatrixConfig, McpConfig,
+    McpServerConfig, MemberOidcLink, MemoryConfig, ModelRouteConfig, ObservabilityConfig,
+    PluginsConfig, ReliabilityConfig, ResourceLimitsConfig, RetryableError, RoleContentPolicy,
+    RoutePolicyRuleConfig, RuntimeConfig, SandboxBackend, SandboxConfig, SandboxProfile,
+    SecretsConfig, SecurityConfig, SensitivityConfig, SlackConfig, SovereignConfig, SovereignMode,
+    SpeakerIdConfig, SttConfig, SyncConfig, SyncPeerConfig, TelegramConfig, TelegramMode,
+    ToolRetryConfig, ToolRuleConfig, ToolSelectionConfig, TrustConfig, TtsConfig, TunnelConfig,
+    UsageLimits, VisionConfig, WebhookConfig,
 };
 
 #[cfg(test)]
@@ -33,6 +41,7 @@ mod tests {
         let telegram = TelegramConfig {
             bot_token: "token".into(),
             allowed_users: vec!["alice".into()],
+            mode: TelegramMode::Channels,
         };
 
         let discord = DiscordConfig {
@@ -40,6 +49,9 @@ mod tests {
             guild_id: Some("123".into()),
             allowed_users: vec![],
             listen_to_bots: false,
+            allowed_guilds: vec![],
+            allowed_channels: vec![],
+            gateway: false,
         };
 
         let lark = LarkConfig {
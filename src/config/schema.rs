@@ -6,7 +6,7 @@ use crate::security::AutonomyLevel;
 use anyhow::{Context, Result};
 use directories::UserDirs;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -69,6 +69,15 @@ pub struct Config {
     #[serde(default)]
     pub http_request: HttpRequestConfig,
 
+    #[serde(default)]
+    pub db_query: DbQueryConfig,
+
+    #[serde(default)]
+    pub feeds: FeedsConfig,
+
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+
     #[serde(default)]
     pub identity: IdentityConfig,
 
@@ -101,6 +110,13 @@ pub struct Config {
     #[serde(default)]
     pub stt: SttConfig,
 
+    #[serde(default)]
+    pub tts: TtsConfig,
+
+    /// Multimodal model for the `image_describe` tool, separate from the chat model.
+    #[serde(default)]
+    pub vision: VisionConfig,
+
     /// MCP (Model Context Protocol) server connections
     #[serde(default)]
     pub mcp: McpConfig,
@@ -108,23 +124,644 @@ pub struct Config {
     /// Family mode: register multiple users with per-channel role mapping.
     #[serde(default)]
     pub family: FamilyConfig,
+
+    /// Encrypted peer sync between this instance and other `MyMolt` devices.
+    #[serde(default)]
+    pub sync: SyncConfig,
+
+    /// Photo capture: posters and receipts to calendar events and expense notes.
+    #[serde(default)]
+    pub capture: CaptureConfig,
+
+    /// Coarse location events from companion apps that trigger PIM reminders.
+    #[serde(default)]
+    pub location: LocationConfig,
+
+    /// Concurrency limit and queue for agent runs started by the gateway.
+    #[serde(default)]
+    pub executor: ExecutorConfig,
+
+    /// Trigger → condition → action rules run by the gateway.
+    #[serde(default)]
+    pub automations: AutomationsConfig,
+
+    /// Agent tool-call loop settings: per-request budgets.
+    #[serde(default)]
+    pub agent: AgentConfig,
+
+    /// Documents in the workspace knowledge folder, indexed into memory.
+    #[serde(default)]
+    pub ingest: IngestConfig,
+}
+
+// ── Speech-to-Text ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SttConfig {
+    /// STT provider: "openai" (default)
+    #[serde(default = "default_stt_provider")]
+    pub provider: String,
+
+    /// Model name (e.g. "whisper-1")
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// On-device speaker identification for voice input
+    #[serde(default)]
+    pub speaker_id: SpeakerIdConfig,
+}
+
+fn default_stt_provider() -> String {
+    "openai".into()
+}
+
+/// Maps voice input to an enrolled family member so it runs with that
+/// member's role and memory scope instead of the owner's.
+///
+/// ```toml
+/// [stt.speaker_id]
+/// enabled = true
+/// threshold = 0.9
+/// unknown_as_owner = false   # unrecognized voices run as a guest
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerIdConfig {
+    /// Identify speakers from enrolled voiceprints (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Minimum cosine similarity for a match (default: 0.9)
+    #[serde(default = "default_speaker_threshold")]
+    pub threshold: f32,
+
+    /// Run unrecognized voices as the owner (default: true). When false they
+    /// run as a guest with child permissions, no tools and shared memory.
+    #[serde(default = "default_true")]
+    pub unknown_as_owner: bool,
+}
+
+fn default_speaker_threshold() -> f32 {
+    0.9
+}
+
+impl Default for SpeakerIdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: default_speaker_threshold(),
+            unknown_as_owner: true,
+        }
+    }
+}
+
+// ── Text-to-Speech ──────────────────────────────────────────────
+
+/// Spoken replies for voice clients.
+///
+/// ```toml
+/// [tts]
+/// enabled = true
+/// provider = "piper"                      # "openai" | "elevenlabs" | "piper"
+/// model = "~/.mymolt/voices/de_DE-thorsten-medium.onnx"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TtsConfig {
+    /// Enable synthesized voice replies (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// TTS provider: "openai" (default), "elevenlabs", or "piper" (local)
+    #[serde(default = "default_tts_provider")]
+    pub provider: String,
+
+    /// Model name (e.g. "tts-1"); for piper, the path to the .onnx voice
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Voice name or ID (e.g. "alloy", or an ElevenLabs voice ID)
+    #[serde(default)]
+    pub voice: Option<String>,
+}
+
+fn default_tts_provider() -> String {
+    "openai".into()
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_tts_provider(),
+            model: None,
+            voice: None,
+        }
+    }
+}
+
+// ── Vision ──────────────────────────────────────────────────────
+
+/// Image understanding for the `image_describe` tool.
+///
+/// ```toml
+/// [vision]
+/// enabled = true
+/// provider = "ollama"                      # "openai" | "openrouter" | "ollama"
+/// model = "llava"
+/// # api_url = "http://localhost:1234/v1"   # any OpenAI-compatible endpoint
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionConfig {
+    /// Register the `image_describe` tool (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Vision provider: "openai" (default), "openrouter", or "ollama" (local)
+    #[serde(default = "default_vision_provider")]
+    pub provider: String,
+
+    /// Multimodal model name (default: "gpt-4o-mini")
+    #[serde(default = "default_vision_model")]
+    pub model: String,
+
+    /// OpenAI-compatible endpoint that overrides the provider default
+    #[serde(default)]
+    pub api_url: Option<String>,
+
+    /// API key; falls back to the provider's environment variable
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// Largest image uploaded, in bytes (default: 5 MB)
+    #[serde(default = "default_vision_max_image_bytes")]
+    pub max_image_bytes: u64,
+
+    /// OCR images locally and refuse to upload those whose text looks
+    /// sensitive (default: true)
+    #[serde(default = "default_true")]
+    pub scan_images: bool,
+
+    /// Tesseract language codes for that scan (default: "eng+deu")
+    #[serde(default = "default_capture_ocr_languages")]
+    pub ocr_languages: String,
+}
+
+fn default_vision_provider() -> String {
+    "openai".into()
+}
+
+fn default_vision_model() -> String {
+    "gpt-4o-mini".into()
+}
+
+fn default_vision_max_image_bytes() -> u64 {
+    5_242_880
+}
+
+impl Default for VisionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_vision_provider(),
+            model: default_vision_model(),
+            api_url: None,
+            api_key: None,
+            max_image_bytes: default_vision_max_image_bytes(),
+            scan_images: true,
+            ocr_languages: default_capture_ocr_languages(),
+        }
+    }
+}
+
+// ── Peer Sync ───────────────────────────────────────────────────
+
+/// End-to-end encrypted sync with other `MyMolt` instances (e.g. desktop ↔ home server).
+///
+/// ```toml
+/// [sync]
+/// enabled = true
+/// listen = "0.0.0.0:7373"
+///
+/// [[sync.peers]]
+/// name = "home-server"
+/// address = "10.100.0.1:7373"
+/// public_key = "base64 X25519 key from `mymolt sync id` on the peer"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Enable the sync listener and periodic sync (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address to accept incoming sync sessions on (default: "127.0.0.1:7373")
+    #[serde(default = "default_sync_listen")]
+    pub listen: String,
+
+    /// Seconds between outgoing sync rounds (default: 300)
+    #[serde(default = "default_sync_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Trusted peers. Only these static keys may open a session.
+    #[serde(default)]
+    pub peers: Vec<SyncPeerConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPeerConfig {
+    /// Display name for logs
+    pub name: String,
+    /// `host:port` of the peer's sync listener
+    pub address: String,
+    /// Peer's static X25519 public key (base64)
+    pub public_key: String,
+}
+
+fn default_sync_listen() -> String {
+    "127.0.0.1:7373".into()
+}
+
+fn default_sync_interval_secs() -> u64 {
+    300
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_sync_listen(),
+            interval_secs: default_sync_interval_secs(),
+            peers: Vec::new(),
+        }
+    }
+}
+
+// ── Photo capture ───────────────────────────────────────────────
+
+/// Turn photos of posters and receipts into calendar events or expense notes.
+///
+/// Images are OCR'd locally with `tesseract`; the text is parsed by a model
+/// and the result is only saved after the user confirms it.
+///
+/// ```toml
+/// [capture]
+/// enabled = true
+/// model = "hint:cheap"
+/// ocr_languages = "eng+deu"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    /// Process image attachments from channels (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Model used to parse OCR text. Defaults to `hint:cheap` when such a
+    /// model route exists, otherwise the default model.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Tesseract language codes joined by `+` (default: "eng+deu")
+    #[serde(default = "default_capture_ocr_languages")]
+    pub ocr_languages: String,
+
+    /// Seconds a proposal waits for confirmation before it is discarded (default: 3600)
+    #[serde(default = "default_capture_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+}
+
+fn default_capture_ocr_languages() -> String {
+    "eng+deu".into()
+}
+
+fn default_capture_confirm_timeout_secs() -> u64 {
+    3600
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: None,
+            ocr_languages: default_capture_ocr_languages(),
+            confirm_timeout_secs: default_capture_confirm_timeout_secs(),
+        }
+    }
+}
+
+// ── Location ────────────────────────────────────────────────────
+
+/// Location-scoped reminders. Companion apps geofence on the device and only
+/// report a place name and arrive/leave — coordinates are never accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationConfig {
+    /// Accept location events from companion clients (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Place names clients may report (default: home, work, school)
+    #[serde(default = "default_location_places")]
+    pub places: Vec<String>,
+}
+
+fn default_location_places() -> Vec<String> {
+    vec!["home".into(), "work".into(), "school".into()]
+}
+
+impl Default for LocationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            places: default_location_places(),
+        }
+    }
+}
+
+// ── Agent executor ──────────────────────────────────────────────
+
+/// Bounds how many agent runs (webhook, `WhatsApp`, dashboard chat) call the
+/// provider at once. Further runs wait in a queue ordered by channel priority;
+/// when the queue is full they are rejected.
+///
+/// ```toml
+/// [executor]
+/// max_concurrent = 4
+/// max_queued = 32
+/// channel_concurrency = 2
+///
+/// [executor.priorities]   # higher runs first
+/// dashboard = 2
+/// whatsapp = 1
+/// webhook = 0
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutorConfig {
+    /// Agent runs executing at the same time (default: 4)
+    #[serde(default = "default_executor_max_concurrent")]
+    pub max_concurrent: usize,
+
+    /// Runs waiting for a slot before new ones are rejected (default: 32)
+    #[serde(default = "default_executor_max_queued")]
+    pub max_queued: usize,
+
+    /// Messages of one chat channel handled at the same time; one sender's
+    /// messages are always handled in order (default: 2)
+    #[serde(default = "default_executor_channel_concurrency")]
+    pub channel_concurrency: usize,
+
+    /// Queue priority per channel; unlisted channels get 0
+    #[serde(default = "default_executor_priorities")]
+    pub priorities: HashMap<String, u8>,
+}
+
+fn default_executor_max_concurrent() -> usize {
+    4
+}
+
+fn default_executor_max_queued() -> usize {
+    32
+}
+
+fn default_executor_channel_concurrency() -> usize {
+    2
+}
+
+fn default_executor_priorities() -> HashMap<String, u8> {
+    HashMap::from([
+        ("dashboard".into(), 2),
+        ("whatsapp".into(), 1),
+        ("webhook".into(), 0),
+    ])
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_executor_max_concurrent(),
+            max_queued: default_executor_max_queued(),
+            channel_concurrency: default_executor_channel_concurrency(),
+            priorities: default_executor_priorities(),
+        }
+    }
+}
+
+// ── Agent ───────────────────────────────────────────────────────
+
+/// Agent loop settings. The limits apply to a single agent turn; a run that
+/// hits one stops with a "budget exceeded" error instead of looping on a
+/// misbehaving model.
+///
+/// In `plan-execute` mode the model first writes a plan of steps and tools.
+/// Plans using tools the security policy forbids are rejected before anything
+/// runs; each step is then executed and reviewed, and rejected steps retried.
+///
+/// ```toml
+/// [agent]
+/// mode = "plan-execute"
+/// max_step_retries = 1
+/// max_iterations = 10
+/// max_tokens = 100000
+/// max_wall_secs = 300
+///
+/// [agent.tool_selection]
+/// enabled = true
+/// max_tools = 12
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// `direct` or `plan-execute` (default: direct)
+    #[serde(default)]
+    pub mode: AgentMode,
+
+    /// Retries for a step the critic rejects in plan-execute mode (default: 1)
+    #[serde(default = "default_agent_max_step_retries")]
+    pub max_step_retries: usize,
+
+    /// LLM round-trips per turn, including tool calls and plan-execute's
+    /// planning and review calls (default: 10)
+    #[serde(default = "default_agent_max_iterations")]
+    pub max_iterations: usize,
+
+    /// Estimated prompt + completion tokens per turn; 0 = unlimited (default: 100000)
+    #[serde(default = "default_agent_max_tokens")]
+    pub max_tokens: u64,
+
+    /// Wall-clock seconds per turn; 0 = unlimited (default: 300)
+    #[serde(default = "default_agent_max_wall_secs")]
+    pub max_wall_secs: u64,
+
+    /// Offer each request only the tools and skills relevant to it
+    #[serde(default)]
+    pub tool_selection: ToolSelectionConfig,
+}
+
+/// How an agent turn is run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AgentMode {
+    /// Tool-call loop until the model answers (default)
+    #[default]
+    Direct,
+    /// Plan, check the plan against the security policy, then run and review each step
+    PlanExecute,
+}
+
+fn default_agent_max_step_retries() -> usize {
+    1
+}
+
+fn default_agent_max_iterations() -> usize {
+    10
+}
+
+fn default_agent_max_tokens() -> u64 {
+    100_000
+}
+
+fn default_agent_max_wall_secs() -> u64 {
+    300
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            mode: AgentMode::default(),
+            max_step_retries: default_agent_max_step_retries(),
+            max_iterations: default_agent_max_iterations(),
+            max_tokens: default_agent_max_tokens(),
+            max_wall_secs: default_agent_max_wall_secs(),
+            tool_selection: ToolSelectionConfig::default(),
+        }
+    }
+}
+
+/// Per-request tool and skill retrieval (`[agent.tool_selection]`).
+///
+/// Tool and skill descriptions are embedded with the `[memory]` embedding
+/// provider (keyword overlap when there is none) and only the closest ones
+/// go into the prompt. The model can look up the rest with `tool_search`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSelectionConfig {
+    /// Select tools and skills per request instead of listing all (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Tools offered per request, besides pinned ones (default: 12)
+    #[serde(default = "default_tool_selection_max_tools")]
+    pub max_tools: usize,
+
+    /// Skills listed per request (default: 5)
+    #[serde(default = "default_tool_selection_max_skills")]
+    pub max_skills: usize,
+
+    /// Tools offered on every request, e.g. `memory_recall` (default: none)
+    #[serde(default)]
+    pub pinned_tools: Vec<String>,
+}
+
+fn default_tool_selection_max_tools() -> usize {
+    12
+}
+
+fn default_tool_selection_max_skills() -> usize {
+    5
+}
+
+impl Default for ToolSelectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_tools: default_tool_selection_max_tools(),
+            max_skills: default_tool_selection_max_skills(),
+            pinned_tools: Vec::new(),
+        }
+    }
+}
+
+// ── Knowledge ingestion ─────────────────────────────────────────
+
+/// Knowledge folder indexing. Markdown, text and PDF files (PDFs through
+/// the `pdftotext` tool) dropped into the folder are chunked and stored in
+/// memory under the `knowledge` category; changed files are re-indexed and
+/// deleted ones forgotten on the next scan.
+///
+/// ```toml
+/// [ingest]
+/// enabled = true
+/// dir = "knowledge"
+/// interval_secs = 60
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestConfig {
+    /// Run the knowledge watcher in the daemon (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Folder to index, relative to the workspace (default: "knowledge")
+    #[serde(default = "default_ingest_dir")]
+    pub dir: String,
+
+    /// Seconds between scans for changed files (default: 60)
+    #[serde(default = "default_ingest_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Approximate tokens per stored chunk (default: 512)
+    #[serde(default = "default_ingest_chunk_tokens")]
+    pub chunk_tokens: usize,
+
+    /// Larger files are skipped (default: 20 MiB)
+    #[serde(default = "default_ingest_max_file_bytes")]
+    pub max_file_bytes: u64,
+}
+
+fn default_ingest_dir() -> String {
+    "knowledge".into()
+}
+
+fn default_ingest_interval_secs() -> u64 {
+    60
+}
+
+fn default_ingest_chunk_tokens() -> usize {
+    512
+}
+
+fn default_ingest_max_file_bytes() -> u64 {
+    20 * 1024 * 1024
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: default_ingest_dir(),
+            interval_secs: default_ingest_interval_secs(),
+            chunk_tokens: default_ingest_chunk_tokens(),
+            max_file_bytes: default_ingest_max_file_bytes(),
+        }
+    }
 }
 
-// ── Speech-to-Text ──────────────────────────────────────────────
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SttConfig {
-    /// STT provider: "openai" (default)
-    #[serde(default = "default_stt_provider")]
-    pub provider: String,
+// ── Automations ─────────────────────────────────────────────────
 
-    /// Model name (e.g. "whisper-1")
+/// Local automation rules. Rules listed here are read-only in the API;
+/// rules created through `/api/automations` are stored in the workspace.
+///
+/// ```toml
+/// [automations]
+/// enabled = true
+///
+/// [[automations.rules]]
+/// name = "Morning briefing"
+/// trigger = { type = "cron", expression = "0 7 * * mon-fri" }
+/// action = { type = "prompt", prompt = "Summarize today's calendar",
+///            reply = { channel = "telegram", recipient = "12345678" } }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutomationsConfig {
+    /// Run the automation engine in the gateway (default: false)
     #[serde(default)]
-    pub model: Option<String>,
-}
+    pub enabled: bool,
 
-fn default_stt_provider() -> String {
-    "openai".into()
+    #[serde(default)]
+    pub rules: Vec<crate::automations::Automation>,
 }
 
 // ── Family ──────────────────────────────────────────────────────
@@ -139,6 +776,15 @@ pub struct FamilyConfig {
     /// Registered family members.
     #[serde(default)]
     pub members: Vec<FamilyMemberConfig>,
+
+    /// Per-role web content filtering (browser proxy and extension rules).
+    #[serde(default)]
+    pub content_filter: ContentFilterConfig,
+
+    /// Daily usage limits keyed by role: "root", "adult", "senior", "child".
+    /// Roles without an entry are unlimited.
+    #[serde(default = "default_family_quotas")]
+    pub quotas: HashMap<String, UsageLimits>,
 }
 
 impl Default for FamilyConfig {
@@ -146,6 +792,8 @@ impl Default for FamilyConfig {
         Self {
             max_members: default_family_max_members(),
             members: Vec::new(),
+            content_filter: ContentFilterConfig::default(),
+            quotas: default_family_quotas(),
         }
     }
 }
@@ -167,17 +815,156 @@ pub struct FamilyMemberConfig {
     /// Channel bindings: `{ "telegram": "12345678", "whatsapp": "+49..." }`.
     #[serde(default)]
     pub channels: std::collections::HashMap<String, String>,
+
+    /// Salted hash of the member's dashboard login PIN. Set it through
+    /// `PUT /api/family/members/{name}/credentials` rather than by hand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin_hash: Option<String>,
+
+    /// OIDC identity that logs in as this member:
+    /// `oidc = { provider = "google", subject = "1098..." }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oidc: Option<MemberOidcLink>,
+
+    /// Daily usage limits for this member, replacing those of their role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quotas: Option<UsageLimits>,
+}
+
+/// Links an account at one of `[identity] providers` to a family member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberOidcLink {
+    /// Provider id, as in `/api/auth/login/{provider_id}`
+    pub provider: String,
+    /// The account's id claim at that provider
+    pub subject: String,
 }
 
 fn default_family_role() -> String {
     "adult".into()
 }
 
+/// Daily usage limits of a family member, enforced by the gateway on their
+/// sessions. Unset limits are unlimited; usage resets at local midnight.
+///
+/// ```toml
+/// [family.quotas.child]
+/// chat_turns = 100
+/// browse_minutes = 120
+/// tools = { web_search = 20 }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageLimits {
+    /// Agent turns per day, typed or spoken.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_turns: Option<u32>,
+    /// Minutes per day with browsing activity in the Sovereign Browser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browse_minutes: Option<u32>,
+    /// Calls per day of individual tools.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, u32>,
+}
+
+fn default_family_quotas() -> HashMap<String, UsageLimits> {
+    HashMap::from([(
+        "child".to_string(),
+        UsageLimits {
+            chat_turns: Some(100),
+            browse_minutes: Some(120),
+            tools: HashMap::new(),
+        },
+    )])
+}
+
+/// Web content categories with built-in domain lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentCategory {
+    /// Pornography and adult dating.
+    Adult,
+    /// Betting, casinos, poker.
+    Gambling,
+    /// Gore and shock sites.
+    Violence,
+    /// Social networks and messaging communities.
+    Social,
+}
+
+/// Content filter settings shared by the browser proxy and DNS rules.
+///
+/// ```toml
+/// [family.content_filter.policies.child]
+/// blocked_categories = ["adult", "gambling", "violence", "social"]
+/// allow_domains = ["kids.youtube.com"]
+///
+/// [family.content_filter.extra_domains]
+/// gambling = ["lotto.example"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentFilterConfig {
+    /// Policies keyed by role: "root", "adult", "senior", "child".
+    /// Roles without an entry are unfiltered.
+    #[serde(default = "default_content_policies")]
+    pub policies: HashMap<String, RoleContentPolicy>,
+
+    /// Additional domains per category, on top of the built-in lists.
+    #[serde(default)]
+    pub extra_domains: HashMap<ContentCategory, Vec<String>>,
+
+    /// How long an approved appeal unblocks a domain, in minutes (default: 60).
+    #[serde(default = "default_appeal_override_minutes")]
+    pub appeal_override_minutes: u64,
+}
+
+/// What one role may not visit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleContentPolicy {
+    #[serde(default)]
+    pub blocked_categories: Vec<ContentCategory>,
+    /// Always allowed, even if a blocked category lists them.
+    #[serde(default)]
+    pub allow_domains: Vec<String>,
+    /// Always blocked, regardless of category.
+    #[serde(default)]
+    pub block_domains: Vec<String>,
+}
+
+fn default_content_policies() -> HashMap<String, RoleContentPolicy> {
+    HashMap::from([(
+        "child".to_string(),
+        RoleContentPolicy {
+            blocked_categories: vec![
+                ContentCategory::Adult,
+                ContentCategory::Gambling,
+                ContentCategory::Violence,
+                ContentCategory::Social,
+            ],
+            ..RoleContentPolicy::default()
+        },
+    )])
+}
+
+fn default_appeal_override_minutes() -> u64 {
+    60
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        Self {
+            policies: default_content_policies(),
+            extra_domains: HashMap::new(),
+            appeal_override_minutes: default_appeal_override_minutes(),
+        }
+    }
+}
+
 impl Default for SttConfig {
     fn default() -> Self {
         Self {
             provider: default_stt_provider(),
             model: None,
+            speaker_id: SpeakerIdConfig::default(),
         }
     }
 }
@@ -191,12 +978,18 @@ impl Default for SttConfig {
 /// name = "filesystem"
 /// command = "npx"
 /// args = ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]
+///
+/// [[mcp.servers]]                      # hosted server, no local wrapper
+/// name = "tickets"
+/// url = "https://mcp.example.com/mcp"  # Streamable HTTP; ws(s):// for WebSocket
+/// headers = { Authorization = "Bearer …" }
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfig {
     /// Human-readable name for this server
     pub name: String,
-    /// Command to spawn the MCP server
+    /// Command to spawn the MCP server (stdio transport)
+    #[serde(default)]
     pub command: String,
     /// Arguments to pass to the command
     #[serde(default)]
@@ -204,10 +997,16 @@ pub struct McpServerConfig {
     /// Additional environment variables
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// URL of a remote server; when set, `command` is not spawned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// HTTP headers sent to a remote server, e.g. `Authorization`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
 }
 
 /// MCP configuration — declares MCP servers to connect to.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpConfig {
     /// Enable MCP integration (default: true if servers are configured)
     #[serde(default = "default_true")]
@@ -215,6 +1014,31 @@ pub struct McpConfig {
     /// List of MCP servers to connect to on startup
     #[serde(default)]
     pub servers: Vec<McpServerConfig>,
+    /// Seconds between liveness pings to each connected server
+    #[serde(default = "default_mcp_ping_interval_secs")]
+    pub ping_interval_secs: u64,
+    /// Upper bound for the reconnect backoff of a dead server
+    #[serde(default = "default_mcp_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+}
+
+fn default_mcp_ping_interval_secs() -> u64 {
+    30
+}
+
+fn default_mcp_max_backoff_secs() -> u64 {
+    300
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            servers: Vec::new(),
+            ping_interval_secs: default_mcp_ping_interval_secs(),
+            max_backoff_secs: default_mcp_max_backoff_secs(),
+        }
+    }
 }
 
 // ── Identity (AIEOS / OpenClaw format) ──────────────────────────
@@ -234,6 +1058,27 @@ pub struct IdentityConfig {
     /// List of configured OIDC providers (e.g. ID Austria, SPID)
     #[serde(default)]
     pub providers: Vec<OIDCProviderConfig>,
+
+    /// Encrypt diary entries in SOUL.md with the secret store key. The
+    /// agent then no longer sees their content in its prompt.
+    ///
+    /// ```toml
+    /// [identity]
+    /// encrypt_diary = true
+    /// ```
+    #[serde(default)]
+    pub encrypt_diary: bool,
+
+    /// Origin the browser sees for passkey registration and step-up, e.g.
+    /// `https://home.example.org`. Its host is the WebAuthn relying party
+    /// id. Defaults to the gateway's public URL. Needs the `passkeys` feature.
+    ///
+    /// ```toml
+    /// [identity]
+    /// passkey_origin = "https://home.example.org"
+    /// ```
+    #[serde(default)]
+    pub passkey_origin: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -257,6 +1102,10 @@ pub struct OIDCProviderConfig {
     /// Trust level matching Identity bindings: 1=Low, 2=Medium, 3=High
     #[serde(default = "default_oidc_trust_level")]
     pub trust_level: u8,
+    /// Request the `offline_access` scope, which most providers need before
+    /// they issue a refresh token
+    #[serde(default)]
+    pub offline_access: bool,
 }
 
 fn default_oidc_trust_level() -> u8 {
@@ -274,6 +1123,8 @@ impl Default for IdentityConfig {
             aieos_path: None,
             aieos_inline: None,
             providers: Vec::new(),
+            encrypt_diary: false,
+            passkey_origin: None,
         }
     }
 }
@@ -324,7 +1175,8 @@ pub struct GatewayConfig {
     /// Allow binding to non-localhost without a tunnel (default: false)
     #[serde(default)]
     pub allow_public_bind: bool,
-    /// Paired bearer tokens (managed automatically, not user-edited)
+    /// Bearer tokens paired before the device registry existed; moved into
+    /// it on gateway startup and then cleared (managed automatically)
     #[serde(default)]
     pub paired_tokens: Vec<String>,
 
@@ -339,6 +1191,31 @@ pub struct GatewayConfig {
     /// TTL for webhook idempotency keys.
     #[serde(default = "default_idempotency_ttl_secs")]
     pub idempotency_ttl_secs: u64,
+
+    /// Lockout of clients that keep presenting invalid bearer tokens.
+    #[serde(default)]
+    pub auth_lockout: AuthLockoutConfig,
+
+    /// Lifetime of a paired device's access token in seconds; devices renew
+    /// it at `/api/devices/refresh` (default: 30 days)
+    #[serde(default = "default_device_token_ttl_secs")]
+    pub device_token_ttl_secs: u64,
+
+    /// Lifetime of a device's refresh token in seconds; a device that does
+    /// not refresh within it has to pair again (default: 180 days)
+    #[serde(default = "default_device_refresh_ttl_secs")]
+    pub device_refresh_ttl_secs: u64,
+
+    /// Least role per API route, checked before the built-in matrix.
+    #[serde(default)]
+    pub route_policy: Vec<RoutePolicyRuleConfig>,
+
+    /// IP addresses of reverse proxies in front of the gateway. Only
+    /// requests arriving from one of them may name the client through
+    /// `X-Forwarded-For` / `X-Real-IP`; otherwise rate limits and the
+    /// authentication lockout key on the connection's peer address.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 fn default_gateway_port() -> u16 {
@@ -361,6 +1238,14 @@ fn default_idempotency_ttl_secs() -> u64 {
     300
 }
 
+fn default_device_token_ttl_secs() -> u64 {
+    30 * 24 * 3600
+}
+
+fn default_device_refresh_ttl_secs() -> u64 {
+    180 * 24 * 3600
+}
+
 fn default_true() -> bool {
     true
 }
@@ -376,6 +1261,95 @@ impl Default for GatewayConfig {
             pair_rate_limit_per_minute: default_pair_rate_limit(),
             webhook_rate_limit_per_minute: default_webhook_rate_limit(),
             idempotency_ttl_secs: default_idempotency_ttl_secs(),
+            auth_lockout: AuthLockoutConfig::default(),
+            device_token_ttl_secs: default_device_token_ttl_secs(),
+            device_refresh_ttl_secs: default_device_refresh_ttl_secs(),
+            route_policy: Vec::new(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// One row of the API route policy matrix (`[[gateway.route_policy]]`).
+///
+/// Rules from config are checked in order before the built-in ones, and the
+/// first rule matching a request decides the least role it needs. The
+/// effective matrix is listed at `GET /api/security/policy/routes`.
+///
+/// ```toml
+/// # Let seniors see household expenses
+/// [[gateway.route_policy]]
+/// path = "/api/expenses/**"
+/// min_role = "senior"
+///
+/// # Only Root may export contacts
+/// [[gateway.route_policy]]
+/// path = "/api/pim/contacts.vcf"
+/// methods = ["GET"]
+/// min_role = "root"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutePolicyRuleConfig {
+    /// Path pattern; `*` matches one segment, `**` any number of them
+    pub path: String,
+    /// HTTP methods the rule applies to (default: all)
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// "child", "senior", "adult" or "root"
+    pub min_role: String,
+}
+
+/// Lockout of API clients after repeated authentication failures
+/// (`[gateway.auth_lockout]`).
+///
+/// After `max_failures` invalid tokens a client is locked out for
+/// `lockout_secs`; every further lockout doubles that, up to
+/// `max_lockout_secs`. A successful authentication clears the client's
+/// record.
+///
+/// ```toml
+/// [gateway.auth_lockout]
+/// max_failures = 5
+/// lockout_secs = 60
+/// max_lockout_secs = 3600
+/// # Message Root family members on their channels when a lockout starts
+/// notify_root = true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthLockoutConfig {
+    /// Invalid tokens before a lockout; 0 disables lockouts (default: 5)
+    #[serde(default = "default_auth_max_failures")]
+    pub max_failures: u32,
+    /// Length of the first lockout in seconds (default: 60)
+    #[serde(default = "default_auth_lockout_secs")]
+    pub lockout_secs: u64,
+    /// Longest lockout in seconds (default: 3600)
+    #[serde(default = "default_auth_max_lockout_secs")]
+    pub max_lockout_secs: u64,
+    /// Notify Root family members when a lockout starts (default: false)
+    #[serde(default)]
+    pub notify_root: bool,
+}
+
+fn default_auth_max_failures() -> u32 {
+    5
+}
+
+fn default_auth_lockout_secs() -> u64 {
+    60
+}
+
+fn default_auth_max_lockout_secs() -> u64 {
+    3600
+}
+
+impl Default for AuthLockoutConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: default_auth_max_failures(),
+            lockout_secs: default_auth_lockout_secs(),
+            max_lockout_secs: default_auth_max_lockout_secs(),
+            notify_root: false,
         }
     }
 }
@@ -416,14 +1390,35 @@ pub struct SecretsConfig {
     /// Enable encryption for API keys and tokens in config.toml
     #[serde(default = "default_true")]
     pub encrypt: bool,
+    /// Where new master keys (the secret store key, the vault admin key)
+    /// are kept. Existing keys move with `mymolt migrate key-backend`.
+    #[serde(default)]
+    pub key_backend: KeyBackend,
 }
 
 impl Default for SecretsConfig {
     fn default() -> Self {
-        Self { encrypt: true }
+        Self {
+            encrypt: true,
+            key_backend: KeyBackend::default(),
+        }
     }
 }
 
+/// Storage for master keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyBackend {
+    /// Key file next to the data it protects (default)
+    #[default]
+    File,
+    /// OS keyring: macOS Keychain, Windows Credential Manager (DPAPI),
+    /// Linux Secret Service. Needs the `os-keyring` build feature.
+    Keyring,
+    /// Sealed to this machine's TPM 2.0 via `tpm2-tools`
+    Tpm,
+}
+
 // ── Browser (friendly-service browsing only) ───────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -449,6 +1444,10 @@ pub struct BrowserConfig {
     /// Optional Chrome/Chromium executable path for rust-native backend
     #[serde(default)]
     pub native_chrome_path: Option<String>,
+    /// Largest page body the Sovereign Browser proxy reads (default: 5MB).
+    /// Longer pages are cut off at this size.
+    #[serde(default = "default_browser_proxy_max_bytes")]
+    pub proxy_max_bytes: usize,
 }
 
 fn default_browser_backend() -> String {
@@ -459,6 +1458,10 @@ fn default_browser_webdriver_url() -> String {
     "http://127.0.0.1:9515".into()
 }
 
+fn default_browser_proxy_max_bytes() -> usize {
+    5_000_000 // 5MB
+}
+
 impl Default for BrowserConfig {
     fn default() -> Self {
         Self {
@@ -469,6 +1472,7 @@ impl Default for BrowserConfig {
             native_headless: default_true(),
             native_webdriver_url: default_browser_webdriver_url(),
             native_chrome_path: None,
+            proxy_max_bytes: default_browser_proxy_max_bytes(),
         }
     }
 }
@@ -499,9 +1503,212 @@ fn default_http_timeout_secs() -> u64 {
     30
 }
 
+// ── Database query tool ─────────────────────────────────────────
+
+/// Databases the `db_query` tool may query.
+///
+/// ```toml
+/// [db_query]
+/// enabled = true
+///
+/// [db_query.connections]
+/// notes = "sqlite:data/notes.db"               # relative to the workspace
+/// shop = "postgres://reader@localhost/shop"    # needs the `db-postgres` feature
+/// ```
+///
+/// Postgres connections are made without TLS, so point them at local or
+/// LAN servers and use a role that can only read what the agent may see.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbQueryConfig {
+    /// Enable the `db_query` tool (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name → `sqlite:<workspace path>` or `postgres://` DSN
+    #[serde(default)]
+    pub connections: HashMap<String, String>,
+    /// Only allow read-only statements (default: true)
+    #[serde(default = "default_true")]
+    pub read_only: bool,
+    /// Maximum rows returned per query (default: 200)
+    #[serde(default = "default_db_max_rows")]
+    pub max_rows: usize,
+    /// Maximum size of the returned rows in bytes (default: 64KB)
+    #[serde(default = "default_db_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Query timeout in seconds (default: 10)
+    #[serde(default = "default_db_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_db_max_rows() -> usize {
+    200
+}
+
+fn default_db_max_output_bytes() -> usize {
+    65_536
+}
+
+fn default_db_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for DbQueryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            connections: HashMap::new(),
+            read_only: true,
+            max_rows: default_db_max_rows(),
+            max_output_bytes: default_db_max_output_bytes(),
+            timeout_secs: default_db_timeout_secs(),
+        }
+    }
+}
+
+// ── Feeds ─────────────────────────────────────────────────────
+
+/// RSS/Atom subscriptions for the `feeds` tool.
+///
+/// ```toml
+/// [feeds]
+/// enabled = true
+///
+/// [feeds.digest]                 # optional morning briefing
+/// schedule = "0 7 * * *"
+/// channel = "telegram"
+/// recipient = "123456789"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedsConfig {
+    /// Enable the `feeds` tool (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum number of subscriptions (default: 50)
+    #[serde(default = "default_feeds_max_feeds")]
+    pub max_feeds: usize,
+    /// Newest items read from a feed per fetch (default: 20)
+    #[serde(default = "default_feeds_max_items")]
+    pub max_items_per_feed: usize,
+    /// Maximum feed document size in bytes (default: 2MB)
+    #[serde(default = "default_feeds_max_bytes")]
+    pub max_feed_bytes: usize,
+    /// Fetch timeout in seconds (default: 20)
+    #[serde(default = "default_feeds_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Scheduled digest of new items sent to a channel
+    #[serde(default)]
+    pub digest: Option<FeedDigestConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedDigestConfig {
+    /// Cron expression, e.g. "0 7 * * *"
+    pub schedule: String,
+    /// Channel to send the digest through, e.g. "telegram"
+    pub channel: String,
+    /// Channel-specific recipient (chat id, address, …)
+    pub recipient: String,
+    /// Maximum items per digest (default: 30)
+    #[serde(default = "default_feeds_digest_max_items")]
+    pub max_items: usize,
+}
+
+fn default_feeds_max_feeds() -> usize {
+    50
+}
+
+fn default_feeds_max_items() -> usize {
+    20
+}
+
+fn default_feeds_max_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+fn default_feeds_timeout_secs() -> u64 {
+    20
+}
+
+fn default_feeds_digest_max_items() -> usize {
+    30
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_feeds: default_feeds_max_feeds(),
+            max_items_per_feed: default_feeds_max_items(),
+            max_feed_bytes: default_feeds_max_bytes(),
+            timeout_secs: default_feeds_timeout_secs(),
+            digest: None,
+        }
+    }
+}
+
+// ── WASM plugins ────────────────────────────────────────────────
+
+/// Third-party tools loaded from `<workspace>/plugins/*.wasm`.
+///
+/// ```toml
+/// [plugins]
+/// enabled = true     # needs the `wasm-plugins` feature
+/// max_memory_mb = 64
+/// ```
+///
+/// Plugins are WebAssembly components implementing `wit/plugin.wit`. They
+/// get no filesystem, network, clock or environment access.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// Load WASM plugins (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Linear memory limit per plugin call in MB (default: 64)
+    #[serde(default = "default_plugins_max_memory_mb")]
+    pub max_memory_mb: usize,
+    /// Fuel (roughly, instructions) per plugin call (default: 1e9)
+    #[serde(default = "default_plugins_fuel")]
+    pub fuel: u64,
+    /// Wall-clock limit per plugin call in seconds (default: 10)
+    #[serde(default = "default_plugins_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Maximum output returned by a plugin in characters (default: 65536)
+    #[serde(default = "default_plugins_max_output_chars")]
+    pub max_output_chars: usize,
+}
+
+fn default_plugins_max_memory_mb() -> usize {
+    64
+}
+
+fn default_plugins_fuel() -> u64 {
+    1_000_000_000
+}
+
+fn default_plugins_timeout_secs() -> u64 {
+    10
+}
+
+fn default_plugins_max_output_chars() -> usize {
+    65_536
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_memory_mb: default_plugins_max_memory_mb(),
+            fuel: default_plugins_fuel(),
+            timeout_secs: default_plugins_timeout_secs(),
+            max_output_chars: default_plugins_max_output_chars(),
+        }
+    }
+}
+
 // ── Memory ───────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct MemoryConfig {
     /// "sqlite" | "markdown" | "none"
     pub backend: String,
@@ -540,6 +1747,61 @@ pub struct MemoryConfig {
     /// Max tokens per chunk for document splitting
     #[serde(default = "default_chunk_size")]
     pub chunk_max_tokens: usize,
+    /// Languages whose PIN/code trigger words the sensitivity scanner knows
+    /// (built-in: "en", "de"; empty = both)
+    #[serde(default = "default_sensitivity_languages")]
+    pub sensitivity_languages: Vec<String>,
+    /// Extra PIN/code trigger words for other languages (e.g. "codice", "clave")
+    #[serde(default)]
+    pub sensitivity_extra_keywords: Vec<String>,
+    /// For sqlite backend: squash duplicate conversation memories on store and
+    /// during hygiene, counting references instead of keeping copies
+    #[serde(default = "default_dedup")]
+    pub dedup: bool,
+    /// Cosine similarity (0.0–1.0) at which two conversation memories count as
+    /// near-duplicates; needs an embedding provider, 1.0 = exact matches only
+    #[serde(default = "default_dedup_similarity")]
+    pub dedup_similarity: f64,
+    /// Recall ranking: weight of how well an entry matches the query
+    #[serde(default = "default_similarity_weight")]
+    pub similarity_weight: f64,
+    /// Recall ranking: weight of importance (recall and duplicate counts,
+    /// core category, `pinned` tag); 0 with `recency_weight` 0 = relevance only
+    #[serde(default = "default_importance_weight")]
+    pub importance_weight: f64,
+    /// Recall ranking: weight of how recently an entry was stored or recalled
+    #[serde(default = "default_recency_weight")]
+    pub recency_weight: f64,
+    /// Days after which the recency of an unpinned entry halves
+    #[serde(default = "default_recency_half_life_days")]
+    pub recency_half_life_days: f64,
+    /// Encrypt SQLite memory content with the workspace secret key; convert
+    /// an existing store with `mymolt migrate encrypt-memory`
+    #[serde(default)]
+    pub encrypt_at_rest: bool,
+    /// How sensitive content (PINs, IBANs, API keys) is kept out of memory
+    #[serde(default)]
+    pub sovereign: SovereignConfig,
+}
+
+/// `[memory.sovereign]` — the guard in front of every memory backend.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SovereignConfig {
+    /// "vault" replaces a sensitive entry with a vault pointer; "redact"
+    /// keeps its text with the secrets masked and vaults only the secrets
+    #[serde(default)]
+    pub mode: SovereignMode,
+}
+
+/// What the sovereign guard stores in place of sensitive content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SovereignMode {
+    /// Vault the whole entry, store only a pointer (default)
+    #[default]
+    Vault,
+    /// Vault the matched secrets, store the text with them masked
+    Redact,
 }
 
 fn default_embedding_provider() -> String {
@@ -575,6 +1837,27 @@ fn default_cache_size() -> usize {
 fn default_chunk_size() -> usize {
     512
 }
+fn default_sensitivity_languages() -> Vec<String> {
+    vec!["en".into(), "de".into()]
+}
+fn default_dedup() -> bool {
+    true
+}
+fn default_dedup_similarity() -> f64 {
+    0.95
+}
+fn default_similarity_weight() -> f64 {
+    1.0
+}
+fn default_importance_weight() -> f64 {
+    0.2
+}
+fn default_recency_weight() -> f64 {
+    0.1
+}
+fn default_recency_half_life_days() -> f64 {
+    30.0
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -592,6 +1875,16 @@ impl Default for MemoryConfig {
             keyword_weight: default_keyword_weight(),
             embedding_cache_size: default_cache_size(),
             chunk_max_tokens: default_chunk_size(),
+            sensitivity_languages: default_sensitivity_languages(),
+            sensitivity_extra_keywords: Vec::new(),
+            dedup: default_dedup(),
+            dedup_similarity: default_dedup_similarity(),
+            similarity_weight: default_similarity_weight(),
+            importance_weight: default_importance_weight(),
+            recency_weight: default_recency_weight(),
+            recency_half_life_days: default_recency_half_life_days(),
+            encrypt_at_rest: false,
+            sovereign: SovereignConfig::default(),
         }
     }
 }
@@ -610,6 +1903,20 @@ pub struct ObservabilityConfig {
     /// Service name reported to the OTel collector. Defaults to "mymolt".
     #[serde(default)]
     pub otel_service_name: Option<String>,
+
+    /// Record every agent turn (prompts, tool calls with redacted arguments
+    /// and results, timings) to `<workspace>/recordings` for replay in the
+    /// dashboard. Works alongside any backend.
+    #[serde(default)]
+    pub record_runs: bool,
+
+    /// Number of recordings to keep; the oldest are deleted first.
+    #[serde(default = "default_max_recordings")]
+    pub max_recordings: usize,
+}
+
+fn default_max_recordings() -> usize {
+    100
 }
 
 impl Default for ObservabilityConfig {
@@ -618,6 +1925,8 @@ impl Default for ObservabilityConfig {
             backend: "none".into(),
             otel_endpoint: None,
             otel_service_name: None,
+            record_runs: false,
+            max_recordings: default_max_recordings(),
         }
     }
 }
@@ -640,6 +1949,11 @@ pub struct AutonomyConfig {
     /// Block high-risk shell commands even if allowlisted.
     #[serde(default = "default_true")]
     pub block_high_risk_commands: bool,
+
+    /// Maximum calls per UTC day, keyed by tool name.
+    /// Example: `tool_daily_budgets = { http_request = 50 }`.
+    #[serde(default)]
+    pub tool_daily_budgets: std::collections::HashMap<String, u32>,
 }
 
 impl Default for AutonomyConfig {
@@ -685,6 +1999,7 @@ impl Default for AutonomyConfig {
             max_cost_per_day_cents: 500,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            tool_daily_budgets: std::collections::HashMap::new(),
         }
     }
 }
@@ -797,6 +2112,14 @@ pub struct ReliabilityConfig {
     /// Example: `{ "claude-opus-4-20250514" = ["claude-sonnet-4-20250514", "gpt-4o"] }`
     #[serde(default)]
     pub model_fallbacks: std::collections::HashMap<String, Vec<String>>,
+    /// Consecutive provider failures (server errors, timeouts, rate limits)
+    /// that open the provider's circuit: calls then skip it and fail over
+    /// to the next provider until the cooldown is over. 0 disables.
+    #[serde(default = "default_circuit_failure_threshold")]
+    pub circuit_failure_threshold: u32,
+    /// Seconds an open circuit skips its provider before a trial call.
+    #[serde(default = "default_circuit_cooldown_secs")]
+    pub circuit_cooldown_secs: u64,
     /// Initial backoff for channel/daemon restarts.
     #[serde(default = "default_channel_backoff_secs")]
     pub channel_initial_backoff_secs: u64,
@@ -809,6 +2132,11 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Automatic retries for transient tool failures, keyed by tool name.
+    /// The `"*"` entry applies to every tool without an entry of its own.
+    /// Example: `[reliability.tool_retries.http_request]` with `max_attempts = 3`.
+    #[serde(default)]
+    pub tool_retries: std::collections::HashMap<String, ToolRetryConfig>,
 }
 
 fn default_provider_retries() -> u32 {
@@ -819,6 +2147,14 @@ fn default_provider_backoff_ms() -> u64 {
     500
 }
 
+fn default_circuit_failure_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_cooldown_secs() -> u64 {
+    60
+}
+
 fn default_channel_backoff_secs() -> u64 {
     2
 }
@@ -843,10 +2179,85 @@ impl Default for ReliabilityConfig {
             fallback_providers: Vec::new(),
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
+            circuit_failure_threshold: default_circuit_failure_threshold(),
+            circuit_cooldown_secs: default_circuit_cooldown_secs(),
             channel_initial_backoff_secs: default_channel_backoff_secs(),
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            tool_retries: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl ReliabilityConfig {
+    /// Retry policy for tool `name`, falling back to the `"*"` entry.
+    pub fn tool_retry(&self, name: &str) -> Option<&ToolRetryConfig> {
+        self.tool_retries
+            .get(name)
+            .or_else(|| self.tool_retries.get("*"))
+    }
+}
+
+/// Kind of tool failure that is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryableError {
+    /// Connection refused/reset, DNS failure, request could not be sent.
+    Network,
+    /// The request or operation timed out.
+    Timeout,
+    /// HTTP 429 / "too many requests".
+    RateLimit,
+    /// HTTP 5xx.
+    Server,
+}
+
+/// Retry policy for one tool, applied by `SecurityWrapper`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRetryConfig {
+    /// Total attempts including the first one. 1 disables retries.
+    #[serde(default = "default_tool_retry_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    #[serde(default = "default_tool_retry_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Upper bound for the delay between attempts.
+    #[serde(default = "default_tool_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Failure kinds that trigger a retry.
+    #[serde(default = "default_tool_retry_on")]
+    pub retry_on: Vec<RetryableError>,
+}
+
+fn default_tool_retry_attempts() -> u32 {
+    3
+}
+
+fn default_tool_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_tool_retry_max_backoff_ms() -> u64 {
+    10_000
+}
+
+fn default_tool_retry_on() -> Vec<RetryableError> {
+    vec![
+        RetryableError::Network,
+        RetryableError::Timeout,
+        RetryableError::RateLimit,
+        RetryableError::Server,
+    ]
+}
+
+impl Default for ToolRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_tool_retry_attempts(),
+            backoff_ms: default_tool_retry_backoff_ms(),
+            max_backoff_ms: default_tool_retry_max_backoff_ms(),
+            retry_on: default_tool_retry_on(),
         }
     }
 }
@@ -857,20 +2268,26 @@ impl Default for ReliabilityConfig {
 ///
 /// ```toml
 /// [[model_routes]]
-/// hint = "reasoning"
+/// hint = "tools"                          # agent turns with tools
 /// provider = "openrouter"
 /// model = "anthropic/claude-opus-4-20250514"
+/// fallbacks = ["chat", "gpt-4o-mini"]      # other routes or default-provider models
+/// daily_token_budget = 200000
 ///
 /// [[model_routes]]
-/// hint = "fast"
+/// hint = "chat"
 /// provider = "groq"
 /// model = "llama-3.3-70b-versatile"
 /// ```
 ///
-/// Usage: pass `hint:reasoning` as the model parameter to route the request.
+/// The gateway and channels pick the route of each request's class:
+/// `chat`, `tools`, `summarize` or `vision` (which replaces the `[vision]`
+/// provider and model). Other hints are used by passing `hint:<name>` as
+/// the model parameter. When a route's provider fails, its `fallbacks` are
+/// tried in order, then the default model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelRouteConfig {
-    /// Task hint name (e.g. "reasoning", "fast", "code", "summarize")
+    /// Task hint name (e.g. "chat", "tools", "summarize", "vision", "cheap")
     pub hint: String,
     /// Provider to route to (must match a known provider name)
     pub provider: String,
@@ -879,6 +2296,14 @@ pub struct ModelRouteConfig {
     /// Optional API key override for this route's provider
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Tried in order when the provider fails: hints of other routes, or
+    /// models of the default provider
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    /// Estimated tokens per day; once spent, requests skip this route until
+    /// local midnight (default: unlimited)
+    #[serde(default)]
+    pub daily_token_budget: Option<u64>,
 }
 
 // ── Heartbeat ────────────────────────────────────────────────────
@@ -991,6 +2416,17 @@ pub struct ChannelsConfig {
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     pub irc: Option<IrcConfig>,
     pub lark: Option<LarkConfig>,
+    /// How long processed message IDs are remembered, so messages a
+    /// platform delivers again are not answered twice. `0` turns this off.
+    #[serde(default = "default_message_dedup_ttl_secs")]
+    pub message_dedup_ttl_secs: u64,
+    /// Group chats MyMolt answers in; messages in other groups are ignored.
+    #[serde(default)]
+    pub groups: Vec<GroupChatConfig>,
+}
+
+fn default_message_dedup_ttl_secs() -> u64 {
+    86_400
 }
 
 impl Default for ChannelsConfig {
@@ -1007,16 +2443,84 @@ impl Default for ChannelsConfig {
             email: None,
             irc: None,
             lark: None,
+            message_dedup_ttl_secs: default_message_dedup_ttl_secs(),
+            groups: Vec::new(),
         }
     }
 }
 
+/// A group chat MyMolt takes part in. It answers when mentioned, when
+/// someone replies to it or when a message starts with `trigger`, and only
+/// to the members listed in `roles` — or, without any, to the `[family]`
+/// members on this channel, in their family role. Each group has its own
+/// session and memory.
+///
+/// ```toml
+/// [[channels_config.groups]]
+/// channel = "telegram"
+/// id = "-1001234567890"
+/// trigger = "molt"
+/// roles = { "12345678" = "adult", "87654321" = "child" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupChatConfig {
+    pub channel: String,
+    /// The platform's ID of the group chat
+    pub id: String,
+    /// Word that addresses MyMolt at the start of a message, like a mention
+    #[serde(default)]
+    pub trigger: Option<String>,
+    /// Member ID → role in this group ("root", "adult", "senior", "child")
+    #[serde(default)]
+    pub roles: HashMap<String, String>,
+}
+
+/// Telegram bot. The channel listener (`mymolt channel start`) receives its
+/// updates unless `mode` hands them to the gateway.
+///
+/// ```toml
+/// [channels_config.telegram]
+/// bot_token = "123456:ABC-DEF"
+/// allowed_users = ["alice", "123456789"]
+/// mode = "webhook"
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramConfig {
     pub bot_token: String,
     pub allowed_users: Vec<String>,
+    /// Who receives updates: "channels" (the channel listener), "polling"
+    /// (the gateway long-polls) or "webhook" (Telegram posts to the
+    /// gateway's `/telegram`, which needs an HTTPS public URL)
+    #[serde(default)]
+    pub mode: TelegramMode,
+}
+
+/// Where a Telegram bot's updates are received
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelegramMode {
+    /// The channel listener long-polls
+    #[default]
+    Channels,
+    /// The gateway long-polls
+    Polling,
+    /// Telegram posts to the gateway's webhook
+    Webhook,
 }
 
+/// Discord bot. The channel listener (`mymolt channel start`) connects it
+/// unless `gateway` hands it to the gateway, which adds the `/ask` slash
+/// command, a thread per conversation and attachments for the file and
+/// image tools.
+///
+/// ```toml
+/// [channels_config.discord]
+/// bot_token = "MTIz..."
+/// guild_id = "123456789012345678"
+/// allowed_users = ["234567890123456789"]
+/// allowed_channels = ["345678901234567890"]
+/// gateway = true
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscordConfig {
     pub bot_token: String,
@@ -1027,6 +2531,17 @@ pub struct DiscordConfig {
     /// The bot still ignores its own messages to prevent feedback loops.
     #[serde(default)]
     pub listen_to_bots: bool,
+    /// Further guilds the bot answers in besides `guild_id`. With neither
+    /// set, every guild is allowed; direct messages always are.
+    #[serde(default)]
+    pub allowed_guilds: Vec<String>,
+    /// Guild channels the bot answers in; threads it opened count as their
+    /// parent channel. Empty allows every channel.
+    #[serde(default)]
+    pub allowed_channels: Vec<String>,
+    /// Run the bot in the gateway instead of the channel listener
+    #[serde(default)]
+    pub gateway: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1049,12 +2564,27 @@ pub struct IMessageConfig {
     pub allowed_contacts: Vec<String>,
 }
 
+/// Matrix room the bot talks in, as the user of `access_token`.
+///
+/// ```toml
+/// [channels_config.matrix]
+/// homeserver = "https://matrix.example.org"
+/// access_token = "syt_..."
+/// room_id = "!abc123:example.org"
+/// allowed_users = ["@alice:example.org"]
+/// e2ee = true
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatrixConfig {
     pub homeserver: String,
     pub access_token: String,
     pub room_id: String,
     pub allowed_users: Vec<String>,
+    /// Read and send end-to-end encrypted messages; needs a build with
+    /// `--features matrix-e2ee`. Device keys are kept under
+    /// `.mymolt/matrix/` in the workspace.
+    #[serde(default)]
+    pub e2ee: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1159,6 +2689,337 @@ pub struct SecurityConfig {
     /// SIGIL trust requirements per capability
     #[serde(default)]
     pub trust: TrustConfig,
+
+    /// Patterns the sensitivity scanner looks for in memory
+    #[serde(default)]
+    pub sensitivity: SensitivityConfig,
+
+    /// Per-tool permission rules, keyed by tool name
+    #[serde(default)]
+    pub tools: BTreeMap<String, ToolRuleConfig>,
+
+    /// Prompt-injection defense for tool outputs and browsed pages
+    #[serde(default)]
+    pub injection: InjectionConfig,
+
+    /// Outbound network policy for tools and the browser proxy
+    #[serde(default)]
+    pub egress: EgressConfig,
+
+    /// Secret scanning of outgoing tool arguments and provider prompts
+    #[serde(default)]
+    pub leak_guard: LeakGuardConfig,
+
+    /// Timeout of confirmation requests and the chats they are pushed to
+    #[serde(default)]
+    pub confirmation: ConfirmationConfig,
+}
+
+/// Permission rules for one tool, checked by `SecurityWrapper` on top of
+/// the autonomy level, skill lists and trust gates. Empty lists impose no
+/// restriction.
+///
+/// ```toml
+/// [security.tools.file_write]
+/// allowed_paths = ["projects"]
+///
+/// [security.tools.shell]
+/// denied_commands = ["curl", "wget", "ssh"]
+///
+/// [security.tools.http_request.domains]
+/// low = ["wikipedia.org"]
+/// high = ["*"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolRuleConfig {
+    /// Minimum SIGIL trust level for the tool ("low", "medium" or "high")
+    #[serde(default)]
+    pub min_trust: Option<String>,
+    /// Path arguments must lie under one of these (relative to the workspace)
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// Path arguments must not lie under any of these
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
+    /// Executables a `command` argument must not run, in any segment
+    #[serde(default)]
+    pub denied_commands: Vec<String>,
+    /// Hosts a `url` argument may point to, per trust level ("low",
+    /// "medium", "high"). A level may use its own hosts and those of lower
+    /// levels; subdomains match, "*" allows any host.
+    #[serde(default)]
+    pub domains: BTreeMap<String, Vec<String>>,
+}
+
+/// Prompt-injection scanner applied to tool results and page text before
+/// they reach the model.
+///
+/// ```toml
+/// [security.injection]
+/// mode = "strip"
+/// disabled = ["Role Hijack"]
+///
+/// [security.injection.patterns]
+/// "Fake Admin" = "(?i)message from the administrator"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InjectionConfig {
+    /// "flag" (default) marks suspicious content as untrusted, "strip"
+    /// also removes the matched text, "off" disables the scanner
+    #[serde(default)]
+    pub mode: InjectionMode,
+    /// Extra patterns as name → regex; the name shows up in notices and
+    /// audit events
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>,
+    /// Built-in patterns to turn off: "Instruction Override", "New
+    /// Instructions", "Role Hijack", "Chat Template Token", "Fake Tool
+    /// Marker", "Exfiltration Link", "Exfiltration Request"
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+/// What the injection scanner does with suspicious content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionMode {
+    /// Pass content through unchanged
+    Off,
+    /// Keep the content, prefixed with a notice naming what was found (default)
+    #[default]
+    Flag,
+    /// Replace each match with a marker and prefix the notice
+    Strip,
+}
+
+/// Outbound network policy enforced by the shared HTTP client factory in
+/// `network::egress`. Domains match their subdomains; denials win.
+///
+/// ```toml
+/// [security.egress]
+/// denied_domains = ["pastebin.com"]
+/// proxy = "http://10.100.0.1:3128"
+///
+/// [security.egress.roles.child]
+/// allowed_domains = ["wikipedia.org", "kids.youtube.com"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressConfig {
+    /// Hosts requests may reach; empty allows every host not denied
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Hosts requests may never reach
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+    /// Further restrictions keyed by role: "root", "adult", "senior", "child"
+    #[serde(default)]
+    pub roles: BTreeMap<String, EgressRoleConfig>,
+    /// HTTP(S) proxy all requests are routed through, e.g. one reachable
+    /// only over the VPN
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Write every outbound host to the audit log (default: true)
+    #[serde(default = "default_true")]
+    pub audit_hosts: bool,
+}
+
+impl Default for EgressConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: Vec::new(),
+            denied_domains: Vec::new(),
+            roles: BTreeMap::new(),
+            proxy: None,
+            audit_hosts: true,
+        }
+    }
+}
+
+/// Egress restrictions for one role, on top of the global lists.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EgressRoleConfig {
+    /// Hosts this role may reach; empty adds no restriction
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Hosts this role may never reach
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+}
+
+/// Pre-flight check that keeps credentials from leaving the machine: the
+/// arguments of outbound tools and prompts to remote providers are scanned
+/// with the sensitivity scanner and for the secrets in this config.
+///
+/// ```toml
+/// [security.leak_guard]
+/// mode = "confirm"
+/// tools = ["http_request", "browser"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakGuardConfig {
+    /// "block" (default) refuses, "confirm" asks through the confirmation
+    /// gate (refused where there is none), "off" disables the guard
+    #[serde(default)]
+    pub mode: LeakGuardMode,
+    /// Tools whose arguments are scanned
+    #[serde(default = "default_leak_guard_tools")]
+    pub tools: Vec<String>,
+    /// Scan prompts sent to remote LLM providers (default: true)
+    #[serde(default = "default_true")]
+    pub prompts: bool,
+}
+
+fn default_leak_guard_tools() -> Vec<String> {
+    vec![
+        "http_request".into(),
+        "browser".into(),
+        "browser_open".into(),
+    ]
+}
+
+impl Default for LeakGuardConfig {
+    fn default() -> Self {
+        Self {
+            mode: LeakGuardMode::default(),
+            tools: default_leak_guard_tools(),
+            prompts: true,
+        }
+    }
+}
+
+/// What the leak guard does when outgoing data contains a secret.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeakGuardMode {
+    /// Send it anyway
+    Off,
+    /// Refuse the call (default)
+    #[default]
+    Block,
+    /// Ask the user first
+    Confirm,
+}
+
+/// Confirmation requests (high-risk tools, leak guard, memory erasure) are
+/// shown on the dashboard and pushed to these chats, where they can be
+/// answered with inline buttons (Telegram) or a "yes"/"no" reply carrying
+/// the request code (WhatsApp).
+///
+/// Policies per risk level and per tool set who may approve, how long a
+/// request waits and for how many minutes an approval covers further calls
+/// of the tool in the same session. A tool's policy takes precedence over
+/// the one for its risk level.
+///
+/// ```toml
+/// [security.confirmation]
+/// timeout_secs = 120
+/// telegram_chats = ["123456789"]
+/// whatsapp_numbers = ["+491701234567"]
+///
+/// [security.confirmation.risk.high]
+/// approver = "root"
+///
+/// [security.confirmation.tools.email_send]
+/// required = true
+/// risk = "medium"
+/// remember_minutes = 15
+///
+/// # Dual control: two different people must approve
+/// [security.confirmation.tools.vault_reveal]
+/// approvals = 2
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationConfig {
+    /// Seconds a request waits for an answer before it is denied (default: 30)
+    #[serde(default = "default_confirmation_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Telegram chat IDs that receive requests; replies are only accepted
+    /// from these chats
+    #[serde(default)]
+    pub telegram_chats: Vec<String>,
+    /// WhatsApp numbers (E.164) that receive requests; replies are only
+    /// accepted from these numbers
+    #[serde(default)]
+    pub whatsapp_numbers: Vec<String>,
+    /// Policies keyed by risk level: "low", "medium" or "high"
+    #[serde(default)]
+    pub risk: BTreeMap<String, ConfirmationPolicyConfig>,
+    /// Policies keyed by tool name
+    #[serde(default)]
+    pub tools: BTreeMap<String, ConfirmationPolicyConfig>,
+}
+
+fn default_confirmation_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_confirmation_timeout_secs(),
+            telegram_chats: Vec::new(),
+            whatsapp_numbers: Vec::new(),
+            risk: BTreeMap::new(),
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+/// How requests of one risk level or tool are confirmed. Unset fields fall
+/// back to the risk level's policy, then to the defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfirmationPolicyConfig {
+    /// Risk level of the tool's requests (tools only; default: "high")
+    #[serde(default)]
+    pub risk: Option<String>,
+    /// Ask before every call of the tool, or never (tools only; default:
+    /// `security.confirmation_required`)
+    #[serde(default)]
+    pub required: Option<bool>,
+    /// Least role that may approve: "child", "senior", "adult" or "root"
+    /// (default: "adult"); any other value requires root. Anyone who may
+    /// answer can deny.
+    #[serde(default)]
+    pub approver: Option<String>,
+    /// Seconds the request waits for an answer (default: `timeout_secs`)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Minutes an approval also covers further calls of the tool in the
+    /// same session (default: 0, ask every time)
+    #[serde(default)]
+    pub remember_minutes: Option<u64>,
+    /// Distinct people who must approve before the request passes
+    /// (default: 1). Each family member counts once, on the dashboard and
+    /// in chat; the owner's dashboard and unregistered chat recipients
+    /// count as the owner.
+    #[serde(default)]
+    pub approvals: Option<u32>,
+}
+
+/// Tuning of the sensitivity scanner that keeps secrets out of memory.
+///
+/// ```toml
+/// [security.sensitivity]
+/// disabled = ["Credit Card"]
+/// allowlist = ["DE89 3704 0044 0532 0130 00"]
+///
+/// [security.sensitivity.patterns]
+/// "Employee ID" = "EMP-[0-9]{6}"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensitivityConfig {
+    /// Extra patterns as name → regex; the name shows up in placeholders
+    /// and audit events, a capture group marks the secret within a match
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>,
+    /// Built-in patterns to turn off: "OpenAI Key", "Google API Key",
+    /// "AWS Access Key", "Private Key Block", "IBAN", "Credit Card", "Bank PIN"
+    #[serde(default)]
+    pub disabled: Vec<String>,
+    /// Values never treated as sensitive, e.g. test IBANs (compared ignoring
+    /// whitespace and case)
+    #[serde(default)]
+    pub allowlist: Vec<String>,
 }
 
 /// Trust level requirements for different capabilities.
@@ -1170,6 +3031,9 @@ pub struct SecurityConfig {
 ///     delegation: "high"
 ///     vault: "high"
 ///     mcp: "low"
+///     tiers:
+///       medium: ["shell", "vault_reveal"]
+///       high: ["vpn"]
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustConfig {
@@ -1185,6 +3049,12 @@ pub struct TrustConfig {
     /// Trust level required for MCP tool calls ("low" or "high")
     #[serde(default = "default_trust_low")]
     pub mcp: String,
+    /// Capabilities unlocked at each trust level ("medium", "high"), on top
+    /// of the requirements above. A capability is a tool name (`calendar_*`
+    /// matches a prefix) or a gateway operation: `vault_reveal`, `vpn`.
+    /// Capabilities in no tier are open at every level.
+    #[serde(default = "default_trust_tiers")]
+    pub tiers: BTreeMap<String, Vec<String>>,
 }
 
 fn default_trust_low() -> String {
@@ -1195,6 +3065,13 @@ fn default_trust_high() -> String {
     "high".into()
 }
 
+fn default_trust_tiers() -> BTreeMap<String, Vec<String>> {
+    BTreeMap::from([
+        ("medium".into(), vec!["vault_reveal".into()]),
+        ("high".into(), vec!["vpn".into()]),
+    ])
+}
+
 impl Default for TrustConfig {
     fn default() -> Self {
         Self {
@@ -1202,6 +3079,7 @@ impl Default for TrustConfig {
             delegation: "high".into(),
             vault: "high".into(),
             mcp: "low".into(),
+            tiers: default_trust_tiers(),
         }
     }
 }
@@ -1215,6 +3093,20 @@ impl TrustConfig {
             _ => crate::identity::soul::TrustLevel::Low,
         }
     }
+
+    /// Trust level each capability in `tiers` requires. A capability listed
+    /// in several tiers requires the highest of them.
+    pub fn tier_requirements(&self) -> BTreeMap<String, crate::identity::soul::TrustLevel> {
+        let mut required = BTreeMap::new();
+        for (level, capabilities) in &self.tiers {
+            let level = Self::parse_level(level);
+            for capability in capabilities {
+                let entry = required.entry(capability.clone()).or_insert(level);
+                *entry = (*entry).max(level);
+            }
+        }
+        required
+    }
 }
 
 impl Default for SecurityConfig {
@@ -1227,11 +3119,26 @@ impl Default for SecurityConfig {
             disabled_skills: default_disabled_skills(),
             confirmation_required: default_confirmation_policy(),
             trust: TrustConfig::default(),
+            sensitivity: SensitivityConfig::default(),
+            tools: BTreeMap::new(),
+            injection: InjectionConfig::default(),
+            egress: EgressConfig::default(),
+            leak_guard: LeakGuardConfig::default(),
+            confirmation: ConfirmationConfig::default(),
         }
     }
 }
 
 /// Sandbox configuration for OS-level isolation
+///
+/// Tools declare the [`SandboxProfile`] their commands need; `profiles`
+/// overrides it per tool:
+///
+/// ```toml
+/// [security.sandbox.profiles]
+/// shell = "full"
+/// git_operations = "readonly-fs"
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxConfig {
     /// Enable sandboxing (None = auto-detect, Some = explicit)
@@ -1245,6 +3152,10 @@ pub struct SandboxConfig {
     /// Custom Firejail arguments (when backend = firejail)
     #[serde(default)]
     pub firejail_args: Vec<String>,
+
+    /// Profile per tool name, replacing the one the tool declares
+    #[serde(default)]
+    pub profiles: BTreeMap<String, SandboxProfile>,
 }
 
 impl Default for SandboxConfig {
@@ -1253,8 +3164,51 @@ impl Default for SandboxConfig {
             enabled: None, // Auto-detect
             backend: SandboxBackend::Auto,
             firejail_args: Vec::new(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+/// Isolation a tool's commands run under.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxProfile {
+    /// No isolation beyond the application-layer policy (default)
+    #[default]
+    None,
+    /// Filesystem read-only except the workspace and `/tmp`
+    ReadonlyFs,
+    /// No network access
+    NoNetwork,
+    /// Read-only filesystem and no network
+    Full,
+}
+
+impl SandboxProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::ReadonlyFs => "readonly-fs",
+            Self::NoNetwork => "no-network",
+            Self::Full => "full",
         }
     }
+
+    /// Whether writes outside the workspace are blocked.
+    pub fn restricts_filesystem(self) -> bool {
+        matches!(self, Self::ReadonlyFs | Self::Full)
+    }
+
+    /// Whether network access is blocked.
+    pub fn restricts_network(self) -> bool {
+        matches!(self, Self::NoNetwork | Self::Full)
+    }
+}
+
+impl std::fmt::Display for SandboxProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Sandbox backend selection
@@ -1281,7 +3235,7 @@ impl Default for SandboxBackend {
     }
 }
 
-/// Resource limits for command execution
+/// Resource limits for command execution (`0` = unlimited)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceLimitsConfig {
     /// Maximum memory in MB per command
@@ -1292,10 +3246,15 @@ pub struct ResourceLimitsConfig {
     #[serde(default = "default_max_cpu_time_seconds")]
     pub max_cpu_time_seconds: u64,
 
-    /// Maximum number of subprocesses
+    /// Maximum number of subprocesses (enforced by Windows job objects and
+    /// the Docker runtime; Unix has no per-command process limit)
     #[serde(default = "default_max_subprocesses")]
     pub max_subprocesses: u32,
 
+    /// Maximum bytes of stdout and of stderr kept per command
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+
     /// Enable memory monitoring
     #[serde(default = "default_memory_monitoring_enabled")]
     pub memory_monitoring: bool,
@@ -1313,6 +3272,10 @@ fn default_max_subprocesses() -> u32 {
     10
 }
 
+fn default_max_output_bytes() -> usize {
+    1_048_576
+}
+
 fn default_memory_monitoring_enabled() -> bool {
     true
 }
@@ -1323,6 +3286,7 @@ impl Default for ResourceLimitsConfig {
             max_memory_mb: default_max_memory_mb(),
             max_cpu_time_seconds: default_max_cpu_time_seconds(),
             max_subprocesses: default_max_subprocesses(),
+            max_output_bytes: default_max_output_bytes(),
             memory_monitoring: default_memory_monitoring_enabled(),
         }
     }
@@ -1343,9 +3307,38 @@ pub struct AuditConfig {
     #[serde(default = "default_audit_max_size_mb")]
     pub max_size_mb: u32,
 
-    /// Sign events with HMAC for tamper evidence
+    /// Rotate once the oldest event in the log is this many days old
+    /// (0 = rotate by size only)
+    #[serde(default = "default_audit_rotate_days")]
+    pub rotate_days: u32,
+
+    /// Gzip rotated log files
+    #[serde(default = "default_true")]
+    pub compress: bool,
+
+    /// Delete rotated log files after this many days (0 = keep forever)
+    #[serde(default)]
+    pub retention_days: u32,
+
+    /// Sign every event for tamper evidence (some, like erasures, always are)
     #[serde(default)]
     pub sign_events: bool,
+
+    /// Signature scheme: "hmac" (secret key, checkable only here) or
+    /// "ed25519" (checkable by anyone holding `audit_ed25519.pub`)
+    #[serde(default)]
+    pub signature: AuditSignature,
+}
+
+/// How signed audit events are signed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSignature {
+    /// HMAC-SHA256 with a key from the secret store (default)
+    #[default]
+    Hmac,
+    /// Ed25519 with a signing key from the secret store
+    Ed25519,
 }
 
 fn default_audit_enabled() -> bool {
@@ -1360,13 +3353,21 @@ fn default_audit_max_size_mb() -> u32 {
     100
 }
 
+fn default_audit_rotate_days() -> u32 {
+    30
+}
+
 impl Default for AuditConfig {
     fn default() -> Self {
         Self {
             enabled: default_audit_enabled(),
             log_path: default_audit_log_path(),
             max_size_mb: default_audit_max_size_mb(),
+            rotate_days: default_audit_rotate_days(),
+            compress: true,
+            retention_days: 0,
             sign_events: false,
+            signature: AuditSignature::default(),
         }
     }
 }
@@ -1426,13 +3427,25 @@ impl Default for Config {
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
+            db_query: DbQueryConfig::default(),
+            feeds: FeedsConfig::default(),
+            plugins: PluginsConfig::default(),
             identity: IdentityConfig::default(),
             hardware: crate::hardware::HardwareConfig::default(),
             agents: std::collections::HashMap::new(),
             security: SecurityConfig::default(),
             stt: SttConfig::default(),
+            tts: TtsConfig::default(),
+            vision: VisionConfig::default(),
             mcp: McpConfig::default(),
             family: FamilyConfig::default(),
+            sync: SyncConfig::default(),
+            capture: CaptureConfig::default(),
+            location: LocationConfig::default(),
+            executor: ExecutorConfig::default(),
+            automations: AutomationsConfig::default(),
+            agent: AgentConfig::default(),
+            ingest: IngestConfig::default(),
         }
     }
 }
@@ -1461,7 +3474,8 @@ impl Config {
             config.workspace_dir = mymolt_dir.join("workspace");
 
             // Decrypt agent API keys if encryption is enabled
-            let store = crate::security::SecretStore::new(&mymolt_dir, config.secrets.encrypt);
+            let store = crate::security::SecretStore::new(&mymolt_dir, config.secrets.encrypt)
+                .with_backend(config.secrets.key_backend);
             for agent in config.agents.values_mut() {
                 if let Some(ref encrypted_key) = agent.api_key {
                     agent.api_key = Some(
@@ -1552,7 +3566,8 @@ impl Config {
             .config_path
             .parent()
             .context("Config path must have a parent directory")?;
-        let store = crate::security::SecretStore::new(mymolt_dir, self.secrets.encrypt);
+        let store = crate::security::SecretStore::new(mymolt_dir, self.secrets.encrypt)
+            .with_backend(self.secrets.key_backend);
         for agent in config_to_save.agents.values_mut() {
             if let Some(ref plaintext_key) = agent.api_key {
                 if !crate::security::SecretStore::is_encrypted(plaintext_key) {
@@ -1750,6 +3765,7 @@ mod tests {
                 max_cost_per_day_cents: 1000,
                 require_approval_for_medium_risk: false,
                 block_high_risk_commands: true,
+                tool_daily_budgets: HashMap::from([("http_request".into(), 50)]),
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
@@ -1766,6 +3782,7 @@ mod tests {
                 telegram: Some(TelegramConfig {
                     bot_token: "123:ABC".into(),
                     allowed_users: vec!["user1".into()],
+                    mode: TelegramMode::Channels,
                 }),
                 discord: None,
                 slack: None,
@@ -1776,6 +3793,8 @@ mod tests {
                 email: None,
                 irc: None,
                 lark: None,
+                message_dedup_ttl_secs: 86_400,
+                groups: Vec::new(),
             },
             memory: MemoryConfig::default(),
             tunnel: TunnelConfig::default(),
@@ -1784,13 +3803,25 @@ mod tests {
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
+            db_query: DbQueryConfig::default(),
+            feeds: FeedsConfig::default(),
+            plugins: PluginsConfig::default(),
             identity: IdentityConfig::default(),
             hardware: crate::hardware::HardwareConfig::default(),
             agents: HashMap::new(),
             security: SecurityConfig::default(),
             stt: SttConfig::default(),
+            tts: TtsConfig::default(),
+            vision: VisionConfig::default(),
             mcp: McpConfig::default(),
             family: FamilyConfig::default(),
+            sync: SyncConfig::default(),
+            capture: CaptureConfig::default(),
+            location: LocationConfig::default(),
+            executor: ExecutorConfig::default(),
+            automations: AutomationsConfig::default(),
+            agent: AgentConfig::default(),
+            ingest: IngestConfig::default(),
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -1803,6 +3834,10 @@ mod tests {
         assert_eq!(parsed.observability.backend, "log");
         assert_eq!(parsed.autonomy.level, AutonomyLevel::Full);
         assert!(!parsed.autonomy.workspace_only);
+        assert_eq!(
+            parsed.autonomy.tool_daily_budgets.get("http_request"),
+            Some(&50)
+        );
         assert_eq!(parsed.runtime.kind, "docker");
         assert!(parsed.heartbeat.enabled);
         assert_eq!(parsed.heartbeat.interval_minutes, 15);
@@ -1862,13 +3897,25 @@ default_temperature = 0.7
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
+            db_query: DbQueryConfig::default(),
+            feeds: FeedsConfig::default(),
+            plugins: PluginsConfig::default(),
             identity: IdentityConfig::default(),
             hardware: crate::hardware::HardwareConfig::default(),
             agents: HashMap::new(),
             security: SecurityConfig::default(),
             stt: SttConfig::default(),
+            tts: TtsConfig::default(),
+            vision: VisionConfig::default(),
             mcp: McpConfig::default(),
             family: FamilyConfig::default(),
+            sync: SyncConfig::default(),
+            capture: CaptureConfig::default(),
+            location: LocationConfig::default(),
+            executor: ExecutorConfig::default(),
+            automations: AutomationsConfig::default(),
+            agent: AgentConfig::default(),
+            ingest: IngestConfig::default(),
         };
 
         config.save().unwrap();
@@ -1920,11 +3967,17 @@ default_temperature = 0.7
         let tc = TelegramConfig {
             bot_token: "123:XYZ".into(),
             allowed_users: vec!["alice".into(), "bob".into()],
+            mode: TelegramMode::Webhook,
         };
         let json = serde_json::to_string(&tc).unwrap();
         let parsed: TelegramConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.bot_token, "123:XYZ");
         assert_eq!(parsed.allowed_users.len(), 2);
+        assert_eq!(parsed.mode, TelegramMode::Webhook);
+
+        let legacy: TelegramConfig =
+            toml::from_str("bot_token = \"123:XYZ\"\nallowed_users = []").unwrap();
+        assert_eq!(legacy.mode, TelegramMode::Channels);
     }
 
     #[test]
@@ -1934,6 +3987,9 @@ default_temperature = 0.7
             guild_id: Some("12345".into()),
             allowed_users: vec![],
             listen_to_bots: false,
+            allowed_guilds: vec![],
+            allowed_channels: vec![],
+            gateway: false,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -1948,12 +4004,23 @@ default_temperature = 0.7
             guild_id: None,
             allowed_users: vec![],
             listen_to_bots: false,
+            allowed_guilds: vec![],
+            allowed_channels: vec![],
+            gateway: false,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
         assert!(parsed.guild_id.is_none());
     }
 
+    #[test]
+    fn discord_config_defaults_to_the_channel_listener() {
+        let legacy: DiscordConfig = toml::from_str("bot_token = \"tok\"").unwrap();
+        assert!(!legacy.gateway);
+        assert!(legacy.allowed_guilds.is_empty());
+        assert!(legacy.allowed_channels.is_empty());
+    }
+
     // ── iMessage / Matrix config ────────────────────────────
 
     #[test]
@@ -1994,6 +4061,7 @@ default_temperature = 0.7
             access_token: "syt_token_abc".into(),
             room_id: "!room123:matrix.org".into(),
             allowed_users: vec!["@user:matrix.org".into()],
+            e2ee: false,
         };
         let json = serde_json::to_string(&mc).unwrap();
         let parsed: MatrixConfig = serde_json::from_str(&json).unwrap();
@@ -2010,11 +4078,19 @@ default_temperature = 0.7
             access_token: "tok".into(),
             room_id: "!abc:synapse.local".into(),
             allowed_users: vec!["@admin:synapse.local".into(), "*".into()],
+            e2ee: true,
         };
         let toml_str = toml::to_string(&mc).unwrap();
         let parsed: MatrixConfig = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.homeserver, "https://synapse.local:8448");
         assert_eq!(parsed.allowed_users.len(), 2);
+        assert!(parsed.e2ee);
+
+        let legacy: MatrixConfig = toml::from_str(
+            "homeserver = \"https://m.org\"\naccess_token = \"tok\"\nroom_id = \"!r:m\"\nallowed_users = []",
+        )
+        .unwrap();
+        assert!(!legacy.e2ee);
     }
 
     #[test]
@@ -2033,11 +4109,14 @@ default_temperature = 0.7
                 access_token: "tok".into(),
                 room_id: "!r:m".into(),
                 allowed_users: vec!["@u:m".into()],
+                e2ee: false,
             }),
             whatsapp: None,
             email: None,
             irc: None,
             lark: None,
+            message_dedup_ttl_secs: 86_400,
+            groups: Vec::new(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -2197,6 +4276,8 @@ channel_id = "C123"
             email: None,
             irc: None,
             lark: None,
+            message_dedup_ttl_secs: 86_400,
+            groups: Vec::new(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -2212,6 +4293,13 @@ channel_id = "C123"
         assert!(c.whatsapp.is_none());
     }
 
+    #[test]
+    fn channels_config_remembers_message_ids_for_a_day() {
+        assert_eq!(ChannelsConfig::default().message_dedup_ttl_secs, 86_400);
+        let legacy: ChannelsConfig = toml::from_str("cli = true").unwrap();
+        assert_eq!(legacy.message_dedup_ttl_secs, 86_400);
+    }
+
     // ══════════════════════════════════════════════════════════
     // SECURITY CHECKLIST TESTS — Gateway config
     // ══════════════════════════════════════════════════════════
@@ -2269,6 +4357,19 @@ channel_id = "C123"
             pair_rate_limit_per_minute: 12,
             webhook_rate_limit_per_minute: 80,
             idempotency_ttl_secs: 600,
+            auth_lockout: AuthLockoutConfig {
+                max_failures: 3,
+                notify_root: true,
+                ..AuthLockoutConfig::default()
+            },
+            device_token_ttl_secs: 3600,
+            device_refresh_ttl_secs: 86_400,
+            route_policy: vec![RoutePolicyRuleConfig {
+                path: "/api/expenses/**".into(),
+                methods: vec!["GET".into()],
+                min_role: "senior".into(),
+            }],
+            trusted_proxies: vec!["10.0.0.1".into()],
         };
         let toml_str = toml::to_string(&g).unwrap();
         let parsed: GatewayConfig = toml::from_str(&toml_str).unwrap();
@@ -2278,6 +4379,13 @@ channel_id = "C123"
         assert_eq!(parsed.pair_rate_limit_per_minute, 12);
         assert_eq!(parsed.webhook_rate_limit_per_minute, 80);
         assert_eq!(parsed.idempotency_ttl_secs, 600);
+        assert_eq!(parsed.auth_lockout.max_failures, 3);
+        assert_eq!(parsed.auth_lockout.lockout_secs, 60);
+        assert!(parsed.auth_lockout.notify_root);
+        assert_eq!(parsed.device_token_ttl_secs, 3600);
+        assert_eq!(parsed.route_policy.len(), 1);
+        assert_eq!(parsed.route_policy[0].min_role, "senior");
+        assert_eq!(parsed.trusted_proxies, vec!["10.0.0.1"]);
     }
 
     #[test]
@@ -2381,10 +4489,14 @@ enabled = true
 
     #[test]
     fn secrets_config_serde_roundtrip() {
-        let s = SecretsConfig { encrypt: false };
+        let s = SecretsConfig {
+            encrypt: false,
+            key_backend: KeyBackend::Tpm,
+        };
         let toml_str = toml::to_string(&s).unwrap();
         let parsed: SecretsConfig = toml::from_str(&toml_str).unwrap();
         assert!(!parsed.encrypt);
+        assert_eq!(parsed.key_backend, KeyBackend::Tpm);
     }
 
     #[test]
@@ -2432,6 +4544,7 @@ default_temperature = 0.7
             native_headless: false,
             native_webdriver_url: "http://localhost:4444".into(),
             native_chrome_path: Some("/usr/bin/chromium".into()),
+            proxy_max_bytes: 1_000_000,
         };
         let toml_str = toml::to_string(&b).unwrap();
         let parsed: BrowserConfig = toml::from_str(&toml_str).unwrap();
@@ -2828,7 +4941,10 @@ temperature = 0.3
         let config = Config {
             config_path: config_path.clone(),
             workspace_dir: mymolt_dir.join("workspace"),
-            secrets: SecretsConfig { encrypt: true },
+            secrets: SecretsConfig {
+                encrypt: true,
+                key_backend: KeyBackend::File,
+            },
             agents,
             ..Config::default()
         };
@@ -2882,7 +4998,10 @@ temperature = 0.3
         let config = Config {
             config_path: config_path.clone(),
             workspace_dir: mymolt_dir.join("workspace"),
-            secrets: SecretsConfig { encrypt: false },
+            secrets: SecretsConfig {
+                encrypt: false,
+                key_backend: KeyBackend::File,
+            },
             agents,
             ..Config::default()
         };
@@ -2896,4 +5015,25 @@ temperature = 0.3
         );
         assert!(!raw.contains("enc2:"), "No encryption prefix when disabled");
     }
+
+    #[test]
+    fn trust_tiers_keep_the_highest_requirement() {
+        use crate::identity::soul::TrustLevel;
+        let parsed: TrustConfig = toml::from_str(
+            r#"
+[tiers]
+medium = ["shell", "vpn"]
+high = ["vpn"]
+"#,
+        )
+        .unwrap();
+        let required = parsed.tier_requirements();
+        assert_eq!(required["shell"], TrustLevel::Medium);
+        assert_eq!(required["vpn"], TrustLevel::High);
+        assert!(!required.contains_key("vault_reveal"));
+
+        let defaults = TrustConfig::default().tier_requirements();
+        assert_eq!(defaults["vault_reveal"], TrustLevel::Medium);
+        assert_eq!(defaults["vpn"], TrustLevel::High);
+    }
 }
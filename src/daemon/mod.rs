@@ -3,6 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use crate::config::Config;
+use crate::security::emergency;
 use anyhow::Result;
 use chrono::Utc;
 use std::future::Future;
@@ -56,7 +57,11 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
                 max_backoff,
                 move || {
                     let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    async move {
+                        emergency::global()
+                            .supervise(crate::channels::start_channels(cfg))
+                            .await
+                    }
                 },
             ));
         } else {
@@ -73,7 +78,11 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = heartbeat_cfg.clone();
-                async move { run_heartbeat_worker(cfg).await }
+                async move {
+                    emergency::global()
+                        .supervise(run_heartbeat_worker(cfg))
+                        .await
+                }
             },
         ));
     }
@@ -86,7 +95,37 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
+                async move {
+                    emergency::global()
+                        .supervise(crate::cron::scheduler::run(cfg))
+                        .await
+                }
+            },
+        ));
+    }
+
+    if config.sync.enabled {
+        let sync_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "sync",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = sync_cfg.clone();
+                async move { emergency::global().supervise(crate::sync::run(cfg)).await }
+            },
+        ));
+    }
+
+    if config.ingest.enabled {
+        let ingest_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "ingest",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = ingest_cfg.clone();
+                async move { emergency::global().supervise(crate::ingest::run(cfg)).await }
             },
         ));
     }
@@ -180,8 +219,9 @@ where
 }
 
 async fn run_heartbeat_worker(config: Config) -> Result<()> {
-    let observer: std::sync::Arc<dyn crate::observability::Observer> =
-        std::sync::Arc::from(crate::observability::create_observer(&config.observability));
+    let observer: std::sync::Arc<dyn crate::observability::Observer> = std::sync::Arc::from(
+        crate::observability::create_observer(&config.observability, &config.workspace_dir),
+    );
     let engine = crate::heartbeat::engine::HeartbeatEngine::new(
         config.heartbeat.clone(),
         config.workspace_dir.clone(),
@@ -202,8 +242,10 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
+            // Boxed: the agent run future is large
             if let Err(e) =
-                crate::agent::run(config.clone(), Some(prompt), None, None, temp, false).await
+                Box::pin(crate::agent::run(config.clone(), Some(prompt), None, None, temp, false))
+                    .await
             {
                 crate::health::mark_component_error("heartbeat", e.to_string());
                 tracing::warn!("Heartbeat task failed: {e}");
@@ -215,8 +257,16 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
 }
 
 fn has_supervised_channels(config: &Config) -> bool {
-    config.channels_config.telegram.is_some()
-        || config.channels_config.discord.is_some()
+    config
+        .channels_config
+        .telegram
+        .as_ref()
+        .is_some_and(|tg| tg.mode == crate::config::TelegramMode::Channels)
+        || config
+            .channels_config
+            .discord
+            .as_ref()
+            .is_some_and(|dc| !dc.gateway)
         || config.channels_config.slack.is_some()
         || config.channels_config.imessage.is_some()
         || config.channels_config.matrix.is_some()
@@ -298,7 +348,34 @@ mod tests {
         config.channels_config.telegram = Some(crate::config::TelegramConfig {
             bot_token: "token".into(),
             allowed_users: vec![],
+            mode: crate::config::TelegramMode::default(),
         });
         assert!(has_supervised_channels(&config));
     }
+
+    #[test]
+    fn telegram_run_by_the_gateway_is_not_supervised_here() {
+        let mut config = Config::default();
+        config.channels_config.telegram = Some(crate::config::TelegramConfig {
+            bot_token: "token".into(),
+            allowed_users: vec![],
+            mode: crate::config::TelegramMode::Polling,
+        });
+        assert!(!has_supervised_channels(&config));
+    }
+
+    #[test]
+    fn discord_run_by_the_gateway_is_not_supervised_here() {
+        let mut config = Config::default();
+        config.channels_config.discord = Some(crate::config::DiscordConfig {
+            bot_token: "token".into(),
+            guild_id: None,
+            allowed_users: vec![],
+            listen_to_bots: false,
+            allowed_guilds: vec![],
+            allowed_channels: vec![],
+            gateway: true,
+        });
+        assert!(!has_supervised_channels(&config));
+    }
 }
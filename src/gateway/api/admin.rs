@@ -3,14 +3,13 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use axum::{
-    extract::{State, Json, Path},
-    http::StatusCode,
+    extract::{State, Json, Path, Query},
+    http::{HeaderMap, StatusCode},
     routing::{get, delete, post},
     Router,
 };
-use crate::gateway::AppState;
-use crate::gateway::api::auth::AuthenticatedUser;
-use crate::identity::UserRole;
+use crate::gateway::{AppState, ClientKey};
+use crate::gateway::api::auth::{require_step_up, AuthenticatedUser};
 use crate::skills::{self};
 use crate::integrations::{registry, IntegrationStatus};
 use crate::cron::{list_jobs, add_job, remove_job};
@@ -46,13 +45,13 @@ pub struct InstallSkillRequest {
 }
 
 pub async fn install_skill(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
     Json(payload): Json<InstallSkillRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can install skills".into()));
-    }
+    require_step_up(&state, &headers, &client)?;
 
     let workspace_dir = state.workspace_dir.clone();
     let url = payload.url.clone();
@@ -68,14 +67,10 @@ pub async fn install_skill(
 }
 
 pub async fn remove_skill(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can remove skills".into()));
-    }
-    
     let workspace_dir = state.workspace_dir.clone();
     let name_clone = name.clone();
     let result = tokio::task::spawn_blocking(move || {
@@ -99,13 +94,9 @@ pub struct IntegrationView {
 }
 
 pub async fn list_integrations(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<Vec<IntegrationView>>, StatusCode> {
-    if user.role != UserRole::Root {
-         return Err(StatusCode::FORBIDDEN);
-    }
-    
     let entries = registry::all_integrations();
     let config_guard = state.config.read().await;
     let config = &*config_guard;
@@ -138,13 +129,9 @@ pub struct ModelConfigView {
 }
 
 pub async fn get_model_config(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<ModelConfigView>, StatusCode> {
-    if user.role != UserRole::Root && user.role != UserRole::Adult {
-         return Err(StatusCode::FORBIDDEN);
-    }
-    
     let prompt = state.system_prompt.read().await.clone();
     let temp = *state.temperature.read().await;
     
@@ -155,14 +142,10 @@ pub async fn get_model_config(
 }
 
 pub async fn update_model_config(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Json(payload): Json<ModelConfigView>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    if user.role != UserRole::Root {
-        return Err(StatusCode::FORBIDDEN);
-    }
-    
     let mut prompt_guard = state.system_prompt.write().await;
     *prompt_guard = payload.system_prompt;
     
@@ -179,15 +162,11 @@ pub struct ConfigureIntegrationRequest {
 }
 
 pub async fn configure_integration(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
     Json(payload): Json<ConfigureIntegrationRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can configure integrations".into()));
-    }
-
     let mut config = state.config.write().await;
     let name_lower = name.to_lowercase();
 
@@ -205,6 +184,7 @@ pub async fn configure_integration(
                     config.channels_config.telegram = Some(crate::config::TelegramConfig {
                         bot_token: key,
                         allowed_users: vec!["*".into()],
+                        mode: crate::config::TelegramMode::default(),
                     });
                 }
             }
@@ -219,6 +199,9 @@ pub async fn configure_integration(
                         guild_id: None,
                         allowed_users: vec!["*".into()],
                         listen_to_bots: false,
+                        allowed_guilds: vec![],
+                        allowed_channels: vec![],
+                        gateway: false,
                     });
                 }
             }
@@ -275,13 +258,9 @@ pub struct CronJobView {
 }
 
 pub async fn get_cron_jobs(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<Vec<CronJobView>>, (StatusCode, String)> {
-    if user.role != UserRole::Root && user.role != UserRole::Adult {
-        return Err((StatusCode::FORBIDDEN, "Access denied".into()));
-    }
-
     let config = state.config.read().await;
     let jobs = list_jobs(&config).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -304,14 +283,10 @@ pub struct AddCronJobRequest {
 }
 
 pub async fn create_cron_job(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Json(payload): Json<AddCronJobRequest>,
 ) -> Result<Json<CronJobView>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can create cron jobs".into()));
-    }
-
     let config = state.config.read().await;
     let job = add_job(&config, &payload.expression, &payload.command)
         .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
@@ -327,14 +302,10 @@ pub async fn create_cron_job(
 }
 
 pub async fn delete_cron_job(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can remove cron jobs".into()));
-    }
-
     let config = state.config.read().await;
     remove_job(&config, &id).map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
 
@@ -342,13 +313,10 @@ pub async fn delete_cron_job(
 }
 
 pub async fn run_cron_job_now(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(_state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Access denied".into()));
-    }
     // We can't actively run it inline securely without the full autonomy policy context.
     // Since `scheduler.rs` is its own loop, we will just return a placeholder or allow it.
     // A robust "Run Now" would either spawn a command if allowed or edit the next_run time.
@@ -356,27 +324,58 @@ pub async fn run_cron_job_now(
     Ok(Json(serde_json::json!({"status": "queued", "id": id, "message": "Manual trigger requested."})))
 }
 
+// ── Tool stats ─────────────────────────────────────────────────────
+
+#[derive(serde::Deserialize)]
+pub struct ToolStatsQuery {
+    /// Window in days, including today (default: 7).
+    pub days: Option<u32>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ToolStatsView {
+    pub days: u32,
+    pub tools: Vec<crate::tools::stats::ToolUsage>,
+    /// Configured daily call budgets, keyed by tool name.
+    pub budgets: std::collections::HashMap<String, u32>,
+}
+
+pub async fn get_tool_stats(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ToolStatsQuery>,
+) -> Result<Json<ToolStatsView>, (StatusCode, String)> {
+    let days = query.days.unwrap_or(7).clamp(1, 365);
+    let budgets = state.config.read().await.autonomy.tool_daily_budgets.clone();
+    let workspace_dir = state.workspace_dir.clone();
+    let tools = tokio::task::spawn_blocking(move || {
+        crate::tools::stats::ToolStats::open(&workspace_dir)?.summary(days)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(ToolStatsView { days, tools, budgets }))
+}
+
 // ── Security ───────────────────────────────────────────────────────
 
 pub async fn get_security_policy(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<crate::config::SecurityConfig>, StatusCode> {
-    if user.role != UserRole::Root {
-        return Err(StatusCode::FORBIDDEN);
-    }
     let config = state.config.read().await;
     Ok(Json(config.security.clone()))
 }
 
 pub async fn update_security_policy(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
     Json(payload): Json<crate::config::SecurityConfig>,
 ) -> Result<Json<crate::config::SecurityConfig>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can update security policy".into()));
-    }
+    require_step_up(&state, &headers, &client)?;
 
     let mut config = state.config.write().await;
     config.security = payload;
@@ -406,4 +405,5 @@ pub fn router() -> Router<AppState> {
         .route("/api/system/cron", get(get_cron_jobs).post(create_cron_job))
         .route("/api/system/cron/{id}", delete(delete_cron_job))
         .route("/api/system/cron/{id}/run", post(run_cron_job_now))
+        .route("/api/system/tools/stats", get(get_tool_stats))
 }
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Agent run control — stop a runaway tool-call loop from outside the chat.
+//!
+//! Chat turns started over `/ws/chat` with a `conversation_id` are registered
+//! under that id. Cancelling drops the pending LLM request or tool execution;
+//! the socket then reports `cancelled` and the abort is written to the audit log.
+//! The executor's current load is exposed so clients can show queue state.
+//! With `observability.record_runs`, recorded turns can be replayed step by
+//! step (Root only — recordings hold prompts and tool output).
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use crate::agent::executor::ExecutorStats;
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::observability::recorder::{self, RecordedStep, RunSummary};
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// POST /api/agent/cancel/{id} — cancel the running conversation `id`
+pub async fn cancel_run(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    if !state.agent_runs.cancel(&id) {
+        return Err((StatusCode::NOT_FOUND, format!("No running conversation '{id}'")));
+    }
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "status": "cancelling", "conversation_id": id })),
+    ))
+}
+
+/// GET /api/agent/queue — running and queued agent runs
+pub async fn queue_stats(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<ExecutorStats> {
+    Json(state.executor.stats())
+}
+
+/// GET /api/agent/runs — recorded turns, newest first
+pub async fn list_recordings(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<RunSummary>>, (StatusCode, String)> {
+    let dir = recorder::recordings_dir(&state.workspace_dir);
+    let runs = tokio::task::spawn_blocking(move || recorder::list_runs(&dir))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(runs))
+}
+
+/// GET /api/agent/runs/{id} — every step of recorded turn `id`
+pub async fn get_recording(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<RecordedStep>>, (StatusCode, String)> {
+    let dir = recorder::recordings_dir(&state.workspace_dir);
+    let lookup = id.clone();
+    let steps = tokio::task::spawn_blocking(move || recorder::load_run(&dir, &lookup))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    steps
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No recording '{id}'")))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/agent/cancel/{id}", post(cancel_run))
+        .route("/api/agent/queue", get(queue_stats))
+        .route("/api/agent/runs", get(list_recordings))
+        .route("/api/agent/runs/{id}", get(get_recording))
+}
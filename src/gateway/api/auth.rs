@@ -3,14 +3,30 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use axum::{
-    extract::FromRequestParts,
-    http::{header, request::Parts, StatusCode},
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
 };
 use crate::gateway::AppState;
+use crate::security::{AuditEvent, AuditEventType};
 use serde::Deserialize;
 
+#[derive(Clone)]
 pub struct AuthenticatedUser {
     pub role: crate::identity::UserRole,
+    /// Family member whose session the token belongs to; `None` for the owner
+    pub member: Option<String>,
+}
+
+impl AuthenticatedUser {
+    /// Who the request is from, for audit events: the member's name, or the
+    /// role for the owner's devices.
+    pub fn actor(&self) -> String {
+        self.member
+            .clone()
+            .unwrap_or_else(|| format!("{:?}", self.role))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,27 +41,27 @@ impl FromRequestParts<AppState> for AuthenticatedUser {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        // Helper to validate token and return role
-        let check_token = |token: &str| -> Option<crate::identity::UserRole> {
-            if state.pairing.is_authenticated(token) {
-                 // For MVP: The main pairing token grants ROOT access.
-                 // Future: Look up token in DB to find specific user/role.
-                 Some(crate::identity::UserRole::Root) 
-            } else if token.is_empty() && state.pairing.is_authenticated("") {
-                 // No-auth mode (development only) -> Root
-                 Some(crate::identity::UserRole::Root)
-            } else {
-                None
-            }
-        };
+        // Already authenticated by the route policy middleware
+        if let Some(user) = parts.extensions.get::<Self>() {
+            return Ok(user.clone());
+        }
+        let client = crate::gateway::client_key_of(parts, state);
+        // Whether the client sent a token at all; only wrong ones count as failures
+        let mut presented = false;
+
+        // Helper to validate token and return the user. Any token, the empty one
+        // included, grants Root in no-auth mode (development only).
+        let check_token = |token: &str| authenticate(state, token);
 
         // 1. Check Authorization header
         if let Some(auth_header) = parts.headers.get(header::AUTHORIZATION) {
             if let Ok(auth_str) = auth_header.to_str() {
                 if auth_str.starts_with("Bearer ") {
                     let token = &auth_str[7..];
-                    if let Some(role) = check_token(token) {
-                        return Ok(AuthenticatedUser { role });
+                    presented = true;
+                    if let Some(user) = check_token(token) {
+                        state.auth_lockout.record_success(&client);
+                        return Ok(user);
                     }
                 }
             }
@@ -55,18 +71,271 @@ impl FromRequestParts<AppState> for AuthenticatedUser {
         if let Some(query) = parts.uri.query() {
             if let Ok(params) = serde_urlencoded::from_str::<AuthQuery>(query) {
                 if let Some(token) = params.token {
-                    if let Some(role) = check_token(&token) {
-                        return Ok(AuthenticatedUser { role });
+                    presented = true;
+                    if let Some(user) = check_token(&token) {
+                        state.auth_lockout.record_success(&client);
+                        return Ok(user);
                     }
                 }
             }
         }
 
         // 3. Check for "no auth required" case (empty strings)
-        if let Some(role) = check_token("") {
-             return Ok(AuthenticatedUser { role });
+        if let Some(user) = check_token("") {
+             return Ok(user);
         }
 
+        if !presented {
+            return Err((StatusCode::UNAUTHORIZED, "Unauthorized"));
+        }
+        // The lockout only applies once a presented token has failed, so a
+        // valid token gets through even while its client key is locked
+        if let Err(retry_after) = state.auth_lockout.check(&client) {
+            tracing::warn!(
+                "Rejected API request from locked-out client {client} ({retry_after}s left)"
+            );
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many failed authentication attempts",
+            ));
+        }
+        record_auth_failure(state, &client);
         Err((StatusCode::UNAUTHORIZED, "Unauthorized"))
     }
 }
+
+/// User a bearer token acts as: a registered device's scopes and member, or
+/// Root for any token while pairing is disabled. `None` for unknown, expired
+/// or revoked tokens.
+pub(crate) fn authenticate(state: &AppState, token: &str) -> Option<AuthenticatedUser> {
+    if let Some(device) = state.devices.authenticate(token) {
+        return Some(AuthenticatedUser {
+            role: device.role(),
+            member: device.member,
+        });
+    }
+    // Paired tokens moved to the registry on startup, so this only passes
+    // with pairing disabled (development only)
+    state
+        .pairing
+        .is_authenticated(token)
+        .then(|| AuthenticatedUser {
+            role: crate::identity::UserRole::Root,
+            member: None,
+        })
+}
+
+/// Middleware enforcing the route policy matrix
+/// ([`crate::security::route_policy`]): an API request must carry a token
+/// whose role is at least the one the matrix requires, Root for routes it
+/// does not name. The user is stored in the request extensions, so handlers
+/// taking an [`AuthenticatedUser`] do not authenticate again.
+pub(crate) async fn enforce_route_policy(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(min_role) = state
+        .route_policy
+        .required_role(request.method().as_str(), request.uri().path())
+    else {
+        return next.run(request).await;
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let user = match AuthenticatedUser::from_request_parts(&mut parts, &state).await {
+        Ok(user) => user,
+        Err(rejection) => return rejection.into_response(),
+    };
+    if user.role < min_role {
+        tracing::warn!(
+            "Route policy denied {:?} on {} {} (needs {:?})",
+            user.role,
+            parts.method,
+            parts.uri.path(),
+            min_role
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            format!("This endpoint requires the {min_role:?} role or higher"),
+        )
+            .into_response();
+    }
+    parts.extensions.insert(user);
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Header carrying the TOTP code for Root-level actions.
+pub const TOTP_HEADER: &str = "X-TOTP-Code";
+
+/// Header carrying a passkey step-up token for Root-level actions.
+pub const PASSKEY_STEP_UP_HEADER: &str = "X-Passkey-Step-Up";
+
+/// Once Root has enrolled TOTP (see [`crate::security::totp`]) or registered
+/// a passkey (`passkeys` feature), require a second factor:
+/// a valid code or recovery code in `X-TOTP-Code`, or a step-up token from
+/// a passkey assertion in `X-Passkey-Step-Up`. Wrong codes and tokens count
+/// towards the authentication lockout of `client` (see
+/// [`crate::gateway::ClientKey`]).
+pub(crate) fn require_step_up(
+    state: &AppState,
+    headers: &HeaderMap,
+    client: &str,
+) -> Result<(), (StatusCode, String)> {
+    let totp = state.totp.is_enrolled();
+    #[cfg(feature = "passkeys")]
+    let passkey = state.passkeys.is_registered();
+    #[cfg(not(feature = "passkeys"))]
+    let passkey = false;
+    if !totp && !passkey {
+        return Ok(());
+    }
+    // Second factors are short; a locked-out client gets no more guesses
+    if let Err(retry_after) = state.auth_lockout.check(client) {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("Too many failed authentication attempts; retry in {retry_after}s"),
+        ));
+    }
+    #[cfg(feature = "passkeys")]
+    {
+        let step_up = headers
+            .get(PASSKEY_STEP_UP_HEADER)
+            .and_then(|v| v.to_str().ok());
+        if let (true, Some(token)) = (passkey, step_up) {
+            if state.passkeys.consume_step_up(token) {
+                return Ok(());
+            }
+            record_auth_failure(state, client);
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                "Invalid or expired passkey step-up token".into(),
+            ));
+        }
+    }
+    let code = headers.get(TOTP_HEADER).and_then(|v| v.to_str().ok());
+    let Some(code) = code.filter(|_| totp) else {
+        let factors = match (totp, passkey) {
+            (true, true) => format!("{TOTP_HEADER} or {PASSKEY_STEP_UP_HEADER} header"),
+            (true, false) => format!("{TOTP_HEADER} header"),
+            _ => format!("{PASSKEY_STEP_UP_HEADER} header"),
+        };
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            format!("A second factor is required for this action ({factors})"),
+        ));
+    };
+    match state.totp.verify(code) {
+        Ok(Some(crate::security::totp::Verified::Totp)) => Ok(()),
+        Ok(Some(crate::security::totp::Verified::Recovery(left))) => {
+            tracing::warn!("🔐 TOTP recovery code used by {client}; {left} left");
+            let _ = state.audit.log(
+                &AuditEvent::new(AuditEventType::AuthSuccess)
+                    .with_actor("gateway".to_string(), Some(client.to_string()), None)
+                    .with_action(
+                        format!("totp:recovery_code ({left} left)"),
+                        "high".to_string(),
+                        true,
+                        true,
+                    ),
+            );
+            Ok(())
+        }
+        Ok(None) => {
+            record_auth_failure(state, client);
+            Err((StatusCode::UNAUTHORIZED, "Invalid TOTP code".into()))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// Refuse `capability` (e.g. `vault_reveal`, `vpn`) while the SIGIL trust
+/// level is below the tier `[security.trust.tiers]` puts it in.
+pub(crate) fn require_trust(
+    state: &AppState,
+    capability: &str,
+) -> Result<(), (StatusCode, String)> {
+    state
+        .security
+        .check_capability(capability)
+        .map_err(|reason| {
+            let message = format!("SIGIL trust gate: '{capability}' blocked — {reason}");
+            tracing::warn!("{message}");
+            (StatusCode::FORBIDDEN, message)
+        })
+}
+
+/// Count an invalid token from `client`; audit a resulting lockout and tell
+/// Root about it when `[gateway.auth_lockout] notify_root` is set.
+pub(crate) fn record_auth_failure(state: &AppState, client: &str) {
+    let Some(lockout) = state.auth_lockout.record_failure(client) else {
+        return;
+    };
+    let secs = lockout.duration.as_secs();
+    tracing::warn!(
+        "🔒 Client {client} locked out for {secs}s after {} failed authentication attempts",
+        lockout.failures
+    );
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::AuthFailure)
+            .with_actor("gateway".to_string(), Some(client.to_string()), None)
+            .with_action(
+                format!(
+                    "auth_lockout:{secs}s (strike {}, {} failures)",
+                    lockout.strike, lockout.failures
+                ),
+                "high".to_string(),
+                false,
+                false,
+            ),
+    );
+
+    if state.auth_lockout.notify_root() {
+        let config = state.config.clone();
+        let message = format!(
+            "🔒 MyMolt locked out {client} for {secs}s after {} failed login attempts.",
+            lockout.failures
+        );
+        tokio::spawn(async move {
+            let config = config.read().await.clone();
+            crate::channels::notify_root(&config, &message).await;
+        });
+    }
+}
+
+/// A visitor holding a guest share token (see [`crate::security::share`]).
+///
+/// Distinct from [`AuthenticatedUser`] on purpose: share tokens are never
+/// accepted by regular endpoints, only by handlers that take a `GuestUser`.
+/// Extraction does not count a use; handlers call `ShareTokenStore::redeem`.
+pub struct GuestUser {
+    pub token: String,
+    pub grant: crate::security::share::ShareGrant,
+}
+
+impl FromRequestParts<AppState> for GuestUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or((StatusCode::UNAUTHORIZED, "Missing share token"))?;
+
+        let grant = state
+            .share_tokens
+            .peek(token)
+            .ok_or((StatusCode::UNAUTHORIZED, "Share link is invalid or has expired"))?;
+
+        Ok(GuestUser {
+            token: token.to_string(),
+            grant,
+        })
+    }
+}
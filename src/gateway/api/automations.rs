@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Automations API — manage trigger → condition → action rules and post
+//! webhook events.
+//!
+//! Managing rules is Root-only since actions run tools without confirmation.
+//! Rules from `[automations]` in the config are listed but cannot be changed
+//! here. Any paired client may post an event for `webhook` triggers.
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::automations::{self, Automation, AutomationStore, Event};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct AutomationView {
+    #[serde(flatten)]
+    pub automation: Automation,
+    /// "config" (read-only) or "api"
+    pub source: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutomationListResponse {
+    /// Whether the engine runs (`[automations] enabled`).
+    pub enabled: bool,
+    pub automations: Vec<AutomationView>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToggleRequest {
+    pub enabled: bool,
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+fn save(state: &AppState, store: &AutomationStore) -> Result<(), (StatusCode, String)> {
+    store
+        .save(&state.workspace_dir, &secrets(state))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+fn audit(state: &AppState, action: String) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("automations".to_string(), None, Some("Root".to_string()))
+            .with_action(action, "medium".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/automations — all rules, config-defined first
+pub async fn list_automations(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<AutomationListResponse>, (StatusCode, String)> {
+    let config = state.config.read().await.automations.clone();
+    let stored = AutomationStore::load(&state.workspace_dir, &secrets(&state));
+
+    let automations = config
+        .rules
+        .into_iter()
+        .map(|automation| AutomationView {
+            automation,
+            source: "config",
+        })
+        .chain(stored.automations.into_iter().map(|automation| AutomationView {
+            automation,
+            source: "api",
+        }))
+        .collect();
+    Ok(Json(AutomationListResponse {
+        enabled: config.enabled,
+        automations,
+    }))
+}
+
+/// POST /api/automations — create a rule
+pub async fn create_automation(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(mut automation): Json<Automation>,
+) -> Result<Json<Automation>, (StatusCode, String)> {
+    automation
+        .validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    automation.id = uuid::Uuid::new_v4().to_string();
+
+    let mut store = AutomationStore::load(&state.workspace_dir, &secrets(&state));
+    store.automations.push(automation.clone());
+    save(&state, &store)?;
+
+    audit(&state, format!("automation:create:{} ({})", automation.id, automation.name));
+    Ok(Json(automation))
+}
+
+/// POST /api/automations/{id}/toggle — enable or disable a rule
+pub async fn toggle_automation(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<ToggleRequest>,
+) -> Result<Json<Automation>, (StatusCode, String)> {
+    let mut store = AutomationStore::load(&state.workspace_dir, &secrets(&state));
+    let Some(automation) = store.automations.iter_mut().find(|a| a.id == id) else {
+        return Err((StatusCode::NOT_FOUND, format!("Automation '{id}' not found")));
+    };
+    automation.enabled = payload.enabled;
+    let updated = automation.clone();
+    save(&state, &store)?;
+
+    let verb = if payload.enabled { "enable" } else { "disable" };
+    audit(&state, format!("automation:{verb}:{id}"));
+    Ok(Json(updated))
+}
+
+/// DELETE /api/automations/{id} — remove a rule
+pub async fn delete_automation(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let mut store = AutomationStore::load(&state.workspace_dir, &secrets(&state));
+    let before = store.automations.len();
+    store.automations.retain(|a| a.id != id);
+    if store.automations.len() == before {
+        return Err((StatusCode::NOT_FOUND, format!("Automation '{id}' not found")));
+    }
+    save(&state, &store)?;
+
+    audit(&state, format!("automation:delete:{id}"));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/automations/events/{event} — fire `webhook` triggers named `event`
+pub async fn post_event(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(event): Path<String>,
+    payload: Option<Json<serde_json::Value>>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if !state.config.read().await.automations.enabled {
+        return Err((StatusCode::FORBIDDEN, "Automations are disabled".into()));
+    }
+    automations::publish(Event::Webhook {
+        event,
+        payload: payload.map(|Json(value)| value).unwrap_or_default(),
+    });
+    Ok(StatusCode::ACCEPTED)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/automations", get(list_automations).post(create_automation))
+        .route("/api/automations/{id}", delete(delete_automation))
+        .route("/api/automations/{id}/toggle", post(toggle_automation))
+        .route("/api/automations/events/{event}", post(post_event))
+}
@@ -7,15 +7,23 @@
 //! Powers both the Sovereign Browser widget and the Chrome extension.
 
 use axum::{
-    extract::{State, Json, Query},
+    extract::{State, Json, Path, Query},
     http::StatusCode,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use crate::gateway::AppState;
 use crate::gateway::api::auth::AuthenticatedUser;
+use crate::identity::family::{member_scope, role_from_config};
 use crate::identity::UserRole;
+use crate::network::browsing::profile_key;
+use crate::network::{content_filter, digest, egress, fetch, html, BrowsingStore};
+use crate::providers::router::TaskClass;
+use crate::security::quotas::Quota;
+use crate::security::{AuditEvent, AuditEventType};
+pub use crate::network::browsing::{BookmarkEntry, HistoryEntry};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 // ── Types ──────────────────────────────────────────────────────────
 
@@ -27,11 +35,18 @@ pub struct ProxyQuery {
 
 #[derive(Debug, Serialize)]
 pub struct ProxyResponse {
+    /// Sanitized full page.
     pub html: String,
+    /// Readable article text (paragraphs separated by blank lines).
     pub text: String,
+    /// Sanitized HTML of the main article only.
+    pub article_html: String,
+    pub byline: Option<String>,
     pub title: String,
     pub blocked: bool,
     pub reason: Option<String>,
+    /// The page was larger than `browser.proxy_max_bytes` and was cut off.
+    pub truncated: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,103 +91,215 @@ pub struct BookmarkRequest {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct BookmarkEntry {
+/// Query for history and bookmark listings.
+///
+/// `profile` names the family member whose data to read (defaults to the
+/// caller's own); Adults may pass a child's name to review their browsing.
+#[derive(Debug, Deserialize)]
+pub struct BrowseDataQuery {
+    pub q: Option<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SummarizeRequest {
     pub url: String,
-    pub title: String,
-    pub created_at: String,
+    pub page_text: String,
+    pub role: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct HistoryEntry {
+#[derive(Debug, Deserialize)]
+pub struct TranslateRequest {
     pub url: String,
-    pub title: String,
-    pub visited_at: String,
+    pub page_text: String,
+    /// Target language, e.g. "German" or "fr".
+    pub language: String,
 }
 
-// ── Blocked domain lists ───────────────────────────────────────────
+/// Largest page text accepted by summarize/translate (≈ 100k tokens).
+const MAX_PAGE_TEXT_BYTES: usize = 400_000;
 
-const CHILD_BLOCKED_PATTERNS: &[&str] = &[
-    "adult", "porn", "xxx", "gambling", "casino", "bet",
-    "onlyfans", "tinder", "grindr",
-];
+#[derive(Debug, Deserialize)]
+pub struct AppealRequest {
+    pub url: String,
+    /// Why the user wants to open the page, shown to the approving Adult.
+    #[serde(default)]
+    pub reason: String,
+}
 
-fn is_blocked_for_role(url: &str, role: &UserRole) -> Option<String> {
-    let url_lower = url.to_lowercase();
+fn parse_role(role: &str) -> Option<UserRole> {
     match role {
-        UserRole::Child => {
-            for pattern in CHILD_BLOCKED_PATTERNS {
-                if url_lower.contains(pattern) {
-                    return Some(format!("Content filtered: '{}' is not available for your role", pattern));
-                }
-            }
-            None
-        }
+        "Root" => Some(UserRole::Root),
+        "Adult" => Some(UserRole::Adult),
+        "Senior" => Some(UserRole::Senior),
+        "Child" => Some(UserRole::Child),
         _ => None,
     }
 }
 
+/// Profile an answer is tailored for: callers may step down to Child/Senior, never up.
+fn requested_role(user: &AuthenticatedUser, role: Option<&str>) -> UserRole {
+    match role {
+        Some("Child") => UserRole::Child,
+        Some("Senior") => UserRole::Senior,
+        _ => user.role,
+    }
+}
+
+fn role_instruction(role: UserRole) -> &'static str {
+    match role {
+        UserRole::Child => "Answer simply, use short sentences, be friendly and encouraging. Use emojis. Explain like talking to a 10-year-old.",
+        UserRole::Senior => "Answer clearly with larger concepts. Be patient and thorough. Avoid jargon. Summarize key points at the start.",
+        _ => "Answer thoroughly with full detail. Include relevant sources and technical depth where appropriate.",
+    }
+}
+
+fn check_page_text(text: &str) -> Result<(), (StatusCode, String)> {
+    if text.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "page_text is empty".into()));
+    }
+    if text.len() > MAX_PAGE_TEXT_BYTES {
+        return Err((StatusCode::PAYLOAD_TOO_LARGE, "Page is too long to process".into()));
+    }
+    Ok(())
+}
+
+/// Resolve the browsing profile a request targets — the caller's own, or
+/// that of the family member named `profile` — and check the caller may
+/// access it.
+async fn resolve_profile(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    profile: Option<&str>,
+) -> Result<String, (StatusCode, String)> {
+    let own = profile_key(user.member.as_deref());
+    let Some(name) = profile.map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(own);
+    };
+    let target = member_scope(name);
+    let config = state.config.read().await;
+    let member = config
+        .family
+        .members
+        .iter()
+        .find(|m| member_scope(&m.name) == target)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Unknown profile: {name}")))?;
+    if target != own && !BrowsingStore::can_view(user.role, role_from_config(&member.role)) {
+        let name = &member.name;
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("{name}'s browsing data is not visible to your role"),
+        ));
+    }
+    Ok(target)
+}
+
+/// `text` with instructions aimed at the model flagged or stripped, per
+/// `[security.injection]`; neutralizations are audited.
+fn neutralize_page_text<'a>(state: &AppState, url: &str, text: &'a str) -> Cow<'a, str> {
+    let scanner = &state.security.injection;
+    let Some(neutralized) = scanner.neutralize(text) else {
+        return Cow::Borrowed(text);
+    };
+    tracing::warn!(
+        url,
+        patterns = ?neutralized.patterns,
+        "Neutralized prompt injection in page text"
+    );
+    let _ = state.audit.log(&scanner.audit_event(&format!("page:{url}"), &neutralized.patterns));
+    Cow::Owned(neutralized.text)
+}
+
+fn storage_error(e: &anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("Browsing storage error: {e}"))
+}
+
 // ── Handlers ───────────────────────────────────────────────────────
 
 /// GET /api/browse/proxy?url=...&role=... — fetch and sanitize a page
 pub async fn browse_proxy(
-    _user: AuthenticatedUser,
-    State(_state): State<AppState>,
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
     Query(params): Query<ProxyQuery>,
 ) -> Result<Json<ProxyResponse>, (StatusCode, String)> {
-    let role = match params.role.as_deref() {
-        Some("Root") => UserRole::Root,
-        Some("Senior") => UserRole::Senior,
-        Some("Child") => UserRole::Child,
-        _ => UserRole::Adult,
+    // The requested role can only narrow the caller's own privileges.
+    let role = params
+        .role
+        .as_deref()
+        .and_then(parse_role)
+        .unwrap_or(UserRole::Adult)
+        .min(user.role);
+
+    // A member's browsing counts against their daily minutes
+    let over_quota = match &user.member {
+        Some(member) => {
+            let family = &state.config.read().await.family;
+            let quota = state.quotas.for_member(family, member, user.role);
+            quota.charge(Quota::BrowseMinutes).err()
+        }
+        None => None,
     };
-
-    // Check if blocked for role
-    if let Some(reason) = is_blocked_for_role(&params.url, &role) {
+    let blocked = over_quota
+        .map(|exceeded| exceeded.reply())
+        .or_else(|| state.content_filter.check(&params.url, role).map(|reason| reason.to_string()))
+        .or_else(|| egress::check(&params.url, Some(role)).err().map(|d| d.reason));
+    if let Some(reason) = blocked {
         return Ok(Json(ProxyResponse {
             html: String::new(),
             text: String::new(),
+            article_html: String::new(),
+            byline: None,
             title: String::new(),
             blocked: true,
             reason: Some(reason),
+            truncated: false,
         }));
     }
 
     // Fetch the page
-    let client = reqwest::Client::builder()
+    let client = egress::client_builder(Some(role))
         .timeout(std::time::Duration::from_secs(15))
         .user_agent("MyMolt/1.0 (Sovereign Browser)")
         .build()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("HTTP client error: {e}")))?;
 
-    let response = client.get(&params.url)
-        .send()
+    let max_bytes = state.config.read().await.browser.proxy_max_bytes;
+    let page = fetch::fetch_page(&client, &params.url, max_bytes)
         .await
-        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Failed to fetch: {e}")))?;
-
-    let status = response.status();
-    if !status.is_success() {
-        return Err((StatusCode::BAD_GATEWAY, format!("Remote returned {status}")));
+        .map_err(|e| {
+            let status = match e {
+                fetch::FetchError::Remote(_) => StatusCode::BAD_GATEWAY,
+                fetch::FetchError::UnsupportedType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            };
+            (status, e.to_string())
+        })?;
+    tracing::debug!("Fetched {} ({}, truncated: {})", params.url, page.charset, page.truncated);
+    let body = page.body;
+
+    let article = html::extract_article(&body, Some(&params.url));
+    let title = article.title.clone();
+
+    // Links to filtered destinations are defused.
+    let filter = state.content_filter.clone();
+    let html = html::sanitize_html(&body, Some(&params.url), move |link| {
+        filter.check(link, role).is_some()
+    });
+
+    // A preview in a narrower role is still the caller's own visit
+    let profile = profile_key(user.member.as_deref());
+    if let Err(e) = state.browsing.record_visit(&profile, &params.url, &title) {
+        tracing::warn!("Failed to record browsing history: {e}");
     }
 
-    let body = response.text().await
-        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Failed to read body: {e}")))?;
-
-    // Extract title
-    let title = extract_title(&body);
-
-    // Extract readable text (simple approach)
-    let text = extract_readable_text(&body);
-
-    // Sanitize HTML based on role
-    let html = sanitize_html(&body, &role);
-
     Ok(Json(ProxyResponse {
         html,
-        text,
+        text: article.text,
+        article_html: article.html,
+        byline: article.byline,
         title,
         blocked: false,
         reason: None,
+        truncated: page.truncated,
     }))
 }
 
@@ -182,29 +309,19 @@ pub async fn browse_ask(
     State(state): State<AppState>,
     Json(payload): Json<AskRequest>,
 ) -> Result<Json<AskResponse>, (StatusCode, String)> {
-    let role = match payload.role.as_deref() {
-        Some("Child") => UserRole::Child,
-        Some("Senior") => UserRole::Senior,
-        _ => user.role.clone(),
-    };
-
-    // Build the prompt based on role
-    let role_instruction = match role {
-        UserRole::Child => "Answer simply, use short sentences, be friendly and encouraging. Use emojis. Explain like talking to a 10-year-old.",
-        UserRole::Senior => "Answer clearly with larger concepts. Be patient and thorough. Avoid jargon. Summarize key points at the start.",
-        _ => "Answer thoroughly with full detail. Include relevant sources and technical depth where appropriate.",
-    };
+    let role_instruction = role_instruction(requested_role(&user, payload.role.as_deref()));
 
+    let excerpt = &payload.page_text[..payload.page_text.len().min(8000)];
     let context = format!(
         "The user is viewing this webpage: {}\n\nPage content (excerpt):\n{}\n\n---\nInstruction: {}\n\nUser question: {}",
         payload.url,
-        &payload.page_text[..payload.page_text.len().min(8000)],
+        neutralize_page_text(&state, &payload.url, excerpt),
         role_instruction,
         payload.question,
     );
 
     // Use the agent to generate an answer
-    let answer = crate::gateway::gateway_agent_reply(&state, &context)
+    let answer = crate::gateway::gateway_agent_reply(&state, &context, None)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Agent error: {e}")))?;
 
@@ -215,25 +332,139 @@ pub async fn browse_ask(
     }))
 }
 
-/// GET /api/browse/history — browsing history (stub — needs per-user storage)
-pub async fn browse_history(
+/// POST /api/browse/summarize — map-reduce summary of the current page
+pub async fn browse_summarize(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<SummarizeRequest>,
+) -> Result<Json<digest::PageSummary>, (StatusCode, String)> {
+    check_page_text(&payload.page_text)?;
+    let instruction = role_instruction(requested_role(&user, payload.role.as_deref()));
+    let model = state.models.model_for(TaskClass::Summarize);
+    let temperature = *state.temperature.read().await;
+
+    let page_text = neutralize_page_text(&state, &payload.url, &payload.page_text);
+
+    tracing::debug!("Summarizing {}", payload.url);
+    digest::summarize(state.provider.as_ref(), &model, temperature, &page_text, instruction)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Summarization failed: {e}")))
+}
+
+/// POST /api/browse/translate — translate the current page, chunk by chunk
+pub async fn browse_translate(
     _user: AuthenticatedUser,
-) -> Json<Vec<HistoryEntry>> {
-    // Stub — real implementation would query per-user encrypted storage
-    Json(vec![])
+    State(state): State<AppState>,
+    Json(payload): Json<TranslateRequest>,
+) -> Result<Json<digest::PageTranslation>, (StatusCode, String)> {
+    check_page_text(&payload.page_text)?;
+    let language = payload.language.trim();
+    if language.is_empty() || language.len() > 40 {
+        return Err((StatusCode::BAD_REQUEST, "language must be 1–40 characters".into()));
+    }
+    let model = state.models.model_for(TaskClass::Chat);
+    let temperature = *state.temperature.read().await;
+
+    let page_text = neutralize_page_text(&state, &payload.url, &payload.page_text);
+
+    tracing::debug!("Translating {} into {language}", payload.url);
+    digest::translate(state.provider.as_ref(), &model, temperature, &page_text, language)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Translation failed: {e}")))
+}
+
+/// GET /api/browse/history?q=...&profile=... — browsing history, most recent first
+pub async fn browse_history(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<BrowseDataQuery>,
+) -> Result<Json<Vec<HistoryEntry>>, (StatusCode, String)> {
+    let profile = resolve_profile(&state, &user, params.profile.as_deref()).await?;
+    state
+        .browsing
+        .history(&profile, params.q.as_deref())
+        .map(Json)
+        .map_err(|e| storage_error(&e))
 }
 
-/// POST /api/browse/bookmark — save a bookmark
+/// DELETE /api/browse/history?profile=... — clear browsing history
+pub async fn clear_browse_history(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<BrowseDataQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let profile = resolve_profile(&state, &user, params.profile.as_deref()).await?;
+    let removed = state
+        .browsing
+        .clear_history(&profile)
+        .map_err(|e| storage_error(&e))?;
+    Ok(Json(serde_json::json!({ "status": "cleared", "removed": removed })))
+}
+
+/// DELETE /api/browse/history/{id}?profile=... — remove one history entry
+pub async fn delete_browse_history_entry(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<BrowseDataQuery>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let profile = resolve_profile(&state, &user, params.profile.as_deref()).await?;
+    match state.browsing.delete_history(&profile, &id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, "History entry not found".into())),
+        Err(e) => Err(storage_error(&e)),
+    }
+}
+
+/// GET /api/browse/bookmarks?q=...&profile=... — list bookmarks
+pub async fn list_bookmarks(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<BrowseDataQuery>,
+) -> Result<Json<Vec<BookmarkEntry>>, (StatusCode, String)> {
+    let profile = resolve_profile(&state, &user, params.profile.as_deref()).await?;
+    state
+        .browsing
+        .bookmarks(&profile, params.q.as_deref())
+        .map(Json)
+        .map_err(|e| storage_error(&e))
+}
+
+/// POST /api/browse/bookmark — save a bookmark to the caller's profile
 pub async fn browse_bookmark(
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
     Json(payload): Json<BookmarkRequest>,
-) -> Json<serde_json::Value> {
-    // Stub — real implementation would persist to user's memory/vault
-    Json(serde_json::json!({
-        "status": "saved",
-        "url": payload.url,
-        "title": payload.title,
-    }))
+) -> Result<Json<BookmarkEntry>, (StatusCode, String)> {
+    if payload.url.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Bookmark URL is required".into()));
+    }
+    state
+        .browsing
+        .add_bookmark(
+            &profile_key(user.member.as_deref()),
+            payload.url.trim(),
+            payload.title.as_deref().unwrap_or(""),
+        )
+        .map(Json)
+        .map_err(|e| storage_error(&e))
+}
+
+/// DELETE /api/browse/bookmark/{id}?profile=... — remove a bookmark
+pub async fn delete_bookmark(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<BrowseDataQuery>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let profile = resolve_profile(&state, &user, params.profile.as_deref()).await?;
+    match state.browsing.delete_bookmark(&profile, &id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, "Bookmark not found".into())),
+        Err(e) => Err(storage_error(&e)),
+    }
 }
 
 // ── Vault match for extension autofill ─────────────────────────────
@@ -270,6 +501,40 @@ pub async fn vault_autofill_log(
     Json(serde_json::json!({"status": "logged"}))
 }
 
+/// POST /api/browse/appeal — ask an Adult to unblock a filtered page
+pub async fn browse_appeal(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<AppealRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let Some(block) = state.content_filter.check(&payload.url, user.role) else {
+        return Err((StatusCode::BAD_REQUEST, "This page is not blocked".into()));
+    };
+    if payload.reason.len() > 500 {
+        return Err((StatusCode::BAD_REQUEST, "Reason too long (max 500 chars)".into()));
+    }
+
+    state.content_filter.appeal(
+        &state.confirm_gate,
+        block.host.clone(),
+        user.role,
+        &user.actor(),
+        &payload.reason,
+    );
+
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::SecurityEvent)
+            .with_actor("gateway".to_string(), None, Some(user.actor()))
+            .with_action(format!("content_filter:appeal:{}", block.host), "low".to_string(), true, true),
+    );
+
+    Ok(Json(serde_json::json!({
+        "status": "pending",
+        "host": block.host,
+        "timeout_secs": content_filter::APPEAL_TIMEOUT_SECS,
+    })))
+}
+
 // ── DNS rules for extension ────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -287,121 +552,51 @@ pub struct DnsBlockRule {
 
 /// GET /api/dns/rules?role=... — DNS block rules for Chrome extension
 pub async fn dns_rules(
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
     Query(params): Query<DnsRulesQuery>,
 ) -> Json<Vec<DnsBlockRule>> {
-    let role = params.role.as_deref().unwrap_or("Adult");
-
-    let mut rules = vec![];
-
-    if role == "Child" {
-        let patterns = vec![
-            ("*://*.xxx/*", 1),
-            ("*://adult*/*", 2),
-            ("*://*.porn*/*", 3),
-            ("*://gambling*/*", 4),
-            ("*://*.casino*/*", 5),
-            ("*://*.bet*/*", 6),
-            ("*://*.onlyfans.com/*", 7),
-        ];
-
-        for (pattern, id) in patterns {
-            rules.push(DnsBlockRule {
-                id: 80000 + id,
-                priority: 1,
-                action: serde_json::json!({"type": "block"}),
-                condition: serde_json::json!({
-                    "urlFilter": pattern,
-                    "resourceTypes": ["main_frame", "sub_frame"]
-                }),
-            });
-        }
-    }
+    // The requested role can only narrow the caller's own privileges.
+    let role = params
+        .role
+        .as_deref()
+        .and_then(parse_role)
+        .unwrap_or(UserRole::Adult)
+        .min(user.role);
+
+    let rules = state
+        .content_filter
+        .blocked_domains(role)
+        .into_iter()
+        .zip(80_001u32..)
+        .map(|(domain, id)| DnsBlockRule {
+            id,
+            priority: 1,
+            action: serde_json::json!({"type": "block"}),
+            condition: serde_json::json!({
+                "urlFilter": format!("||{domain}^"),
+                "resourceTypes": ["main_frame", "sub_frame"]
+            }),
+        })
+        .collect();
 
     Json(rules)
 }
 
-// ── Helpers ────────────────────────────────────────────────────────
-
-fn extract_title(html: &str) -> String {
-    // Simple regex-free title extraction
-    if let Some(start) = html.find("<title") {
-        if let Some(tag_end) = html[start..].find('>') {
-            let after_tag = start + tag_end + 1;
-            if let Some(close) = html[after_tag..].find("</title>") {
-                return html[after_tag..after_tag + close].trim().to_string();
-            }
-        }
-    }
-    String::new()
-}
-
-fn extract_readable_text(html: &str) -> String {
-    // Strip HTML tags for readable text extraction
-    let mut text = String::new();
-    let mut in_tag = false;
-    let mut in_script = false;
-    let mut in_style = false;
-
-    for ch in html.chars() {
-        if ch == '<' {
-            in_tag = true;
-            // Check for script/style
-            let remaining = &html[html.len().saturating_sub(100)..]; // approximate
-            if remaining.to_lowercase().starts_with("<script") {
-                in_script = true;
-            } else if remaining.to_lowercase().starts_with("<style") {
-                in_style = true;
-            }
-            continue;
-        }
-        if ch == '>' {
-            in_tag = false;
-            continue;
-        }
-        if !in_tag && !in_script && !in_style {
-            text.push(ch);
-        }
-        if in_tag {
-            let lower = ch.to_lowercase().to_string();
-            if lower == "/" {
-                in_script = false;
-                in_style = false;
-            }
-        }
-    }
-
-    // Clean up whitespace
-    text.split_whitespace().collect::<Vec<_>>().join(" ")
-}
-
-fn sanitize_html(html: &str, role: &UserRole) -> String {
-    match role {
-        UserRole::Child => {
-            // Strip all scripts for children
-            let mut result = html.to_string();
-            // Remove script tags (simple approach)
-            while let Some(start) = result.find("<script") {
-                if let Some(end) = result[start..].find("</script>") {
-                    result = format!("{}{}", &result[..start], &result[start + end + 9..]);
-                } else {
-                    break;
-                }
-            }
-            result
-        }
-        _ => html.to_string(),
-    }
-}
-
 // ── Router ─────────────────────────────────────────────────────────
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/browse/proxy", get(browse_proxy))
         .route("/api/browse/ask", post(browse_ask))
-        .route("/api/browse/history", get(browse_history))
+        .route("/api/browse/summarize", post(browse_summarize))
+        .route("/api/browse/translate", post(browse_translate))
+        .route("/api/browse/appeal", post(browse_appeal))
+        .route("/api/browse/history", get(browse_history).delete(clear_browse_history))
+        .route("/api/browse/history/{id}", delete(delete_browse_history_entry))
+        .route("/api/browse/bookmarks", get(list_bookmarks))
         .route("/api/browse/bookmark", post(browse_bookmark))
+        .route("/api/browse/bookmark/{id}", delete(delete_bookmark))
         .route("/api/vault/match", get(vault_match))
         .route("/api/vault/autofill-log", post(vault_autofill_log))
         .route("/api/dns/rules", get(dns_rules))
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Calendar API — iCalendar export and import for the PIM calendar.
+//!
+//! The export can be subscribed to from phone and desktop calendars by
+//! passing the session token as `?token=`. Imports skip events whose `UID`
+//! is already in the calendar.
+
+use axum::{
+    extract::{Json, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::{ics, pim};
+use serde::Serialize;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub imported: usize,
+    /// Events whose UID was already in the calendar
+    pub duplicates: usize,
+    /// Events without a usable start date
+    pub skipped: usize,
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/pim/calendar.ics — all calendar events as iCalendar
+pub async fn export_calendar(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let store = pim::load_store(&state.workspace_dir, &secrets(&state));
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ics::export_calendar(&store.events),
+    )
+}
+
+/// POST /api/pim/import-ics — add the events of an `.ics` file
+pub async fn import_calendar(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<ImportResponse>, (StatusCode, String)> {
+    let parsed = ics::parse_calendar(&body);
+    if parsed.events.is_empty() && parsed.skipped == 0 {
+        return Err((StatusCode::BAD_REQUEST, "No VEVENT found in the upload".into()));
+    }
+
+    let total = parsed.events.len();
+    let secrets = secrets(&state);
+    let mut store = pim::load_store(&state.workspace_dir, &secrets);
+    let imported = ics::merge_events(&mut store.events, parsed.events);
+    if imported > 0 {
+        pim::save_store(&state.workspace_dir, &store, &secrets)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("calendar".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!("calendar:import:{imported}"),
+                "low".to_string(),
+                true,
+                true,
+            ),
+    );
+
+    Ok(Json(ImportResponse {
+        imported,
+        duplicates: total - imported,
+        skipped: parsed.skipped,
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/pim/calendar.ics", get(export_calendar))
+        .route("/api/pim/import-ics", post(import_calendar))
+}
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Capabilities — one machine-readable summary of what this instance can do.
+//!
+//! The frontend adapts its UI from this, and support/diagnostics exports
+//! attach it verbatim. Secrets (API keys, tokens, passwords) never appear.
+
+use crate::config::Config;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::identity::UserRole;
+use crate::providers::router::RouteUsage;
+use axum::{extract::State, routing::get, Json, Router};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub version: String,
+    /// Role of the caller, so the UI can hide what it may not use.
+    pub role: UserRole,
+    /// Family member the caller is logged in as; absent for the owner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub tools: Vec<ToolCapability>,
+    pub channels: Vec<String>,
+    pub models: ModelCapabilities,
+    pub autonomy: AutonomyCapabilities,
+    pub sandbox: SandboxCapabilities,
+    pub memory: MemoryCapabilities,
+    pub mcp_servers: Vec<String>,
+    /// Optional features switched on for this instance (e.g. `voice_output`, `sync`).
+    pub features: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolCapability {
+    pub name: String,
+    pub description: String,
+    /// Schema of the tool's structured `data`, when it returns any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelCapabilities {
+    pub provider: Option<String>,
+    pub active: String,
+    pub temperature: f64,
+    /// Task hints with a dedicated route (`hint:<name>`).
+    pub route_hints: Vec<String>,
+    /// Tokens each route used today, against its budget.
+    pub route_usage: Vec<RouteUsage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutonomyCapabilities {
+    pub level: crate::security::AutonomyLevel,
+    pub workspace_only: bool,
+    pub max_actions_per_hour: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SandboxCapabilities {
+    /// Backend requested in config (`auto`, `landlock`, …).
+    pub configured: crate::config::SandboxBackend,
+    /// Backend actually in effect after detection and fallback.
+    pub active: String,
+    pub runtime: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryCapabilities {
+    pub backend: String,
+    pub auto_save: bool,
+    pub entries: Option<usize>,
+    pub healthy: bool,
+}
+
+/// Names of the channels enabled in `[channels_config]`.
+fn enabled_channels(config: &Config) -> Vec<String> {
+    let c = &config.channels_config;
+    [
+        ("cli", c.cli),
+        ("telegram", c.telegram.is_some()),
+        ("discord", c.discord.is_some()),
+        ("slack", c.slack.is_some()),
+        ("webhook", c.webhook.is_some()),
+        ("imessage", c.imessage.is_some()),
+        ("matrix", c.matrix.is_some()),
+        ("whatsapp", c.whatsapp.is_some()),
+        ("email", c.email.is_some()),
+        ("irc", c.irc.is_some()),
+        ("lark", c.lark.is_some()),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+/// GET /api/capabilities — enabled tools, channels, models, autonomy, sandbox and memory
+pub async fn get_capabilities(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<Capabilities> {
+    let config = state.config.read().await.clone();
+
+    let tools = state
+        .tools_registry
+        .snapshot()
+        .iter()
+        .map(|t| ToolCapability {
+            name: t.name().to_string(),
+            description: t.description().to_string(),
+            output_schema: t.output_schema(),
+        })
+        .collect();
+
+    let mut route_hints: Vec<String> = config.model_routes.iter().map(|r| r.hint.clone()).collect();
+    route_hints.sort();
+    route_hints.dedup();
+
+    let sandbox_config = config.security.clone();
+    let active_sandbox = tokio::task::spawn_blocking(move || {
+        crate::security::create_sandbox(&sandbox_config)
+            .name()
+            .to_string()
+    })
+    .await
+    .unwrap_or_else(|_| "unknown".into());
+
+    Json(Capabilities {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        role: user.role,
+        member: user.member,
+        tools,
+        channels: enabled_channels(&config),
+        models: ModelCapabilities {
+            provider: config.default_provider.clone(),
+            active: state.models.default_model(),
+            temperature: *state.temperature.read().await,
+            route_hints,
+            route_usage: state.models.usage(),
+        },
+        autonomy: AutonomyCapabilities {
+            level: config.autonomy.level,
+            workspace_only: config.autonomy.workspace_only,
+            max_actions_per_hour: config.autonomy.max_actions_per_hour,
+        },
+        sandbox: SandboxCapabilities {
+            configured: config.security.sandbox.backend.clone(),
+            active: active_sandbox,
+            runtime: config.runtime.kind.clone(),
+        },
+        memory: MemoryCapabilities {
+            backend: state.mem.name().to_string(),
+            auto_save: state.auto_save,
+            entries: state.mem.count().await.ok(),
+            healthy: state.mem.health_check().await,
+        },
+        mcp_servers: if config.mcp.enabled {
+            config.mcp.servers.iter().map(|s| s.name.clone()).collect()
+        } else {
+            Vec::new()
+        },
+        features: [
+            ("pairing", state.pairing.require_pairing()),
+            ("voice_input", true),
+            ("voice_output", state.tts.is_some()),
+            (
+                "voice_echo",
+                state
+                    .voice_echo_enabled
+                    .load(std::sync::atomic::Ordering::Relaxed),
+            ),
+            ("adblock", state.adblock.is_enabled().await),
+            ("browser_open", config.browser.enabled),
+            ("sync", config.sync.enabled),
+            ("guest_sharing", true),
+            ("whatsapp", state.whatsapp.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect(),
+    })
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/capabilities", get(get_capabilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enabled_channels_lists_configured_only() {
+        let mut config = Config::default();
+        assert_eq!(enabled_channels(&config), vec!["cli"]);
+
+        config.channels_config.cli = false;
+        config.channels_config.webhook = Some(crate::config::WebhookConfig {
+            port: 8080,
+            secret: None,
+        });
+        assert_eq!(enabled_channels(&config), vec!["webhook"]);
+    }
+}
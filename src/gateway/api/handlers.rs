@@ -7,17 +7,18 @@ use axum::{
     routing::{get, post},
     Router,
     extract::Query,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
-use crate::gateway::AppState;
+use crate::gateway::{AppState, ClientKey};
 use super::types::*;
 
-use super::auth::AuthenticatedUser;
+use super::auth::{require_step_up, AuthenticatedUser};
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/system/status", get(get_system_status))
         .route("/api/system/widgets", get(get_widgets))
+        .route("/api/system/panic", post(trigger_panic))
         .route("/api/identity", get(get_identities))
         .route("/api/identity/simulate", post(simulate_identity_link))
         .route("/api/identity/eidas/verify", post(verify_eidas_upload))
@@ -30,6 +31,7 @@ pub fn router() -> Router<AppState> {
         .route("/api/auth/login/{provider_id}", get(handle_oidc_login))
         .route("/api/auth/callback/{provider_id}", get(handle_oidc_callback))
         // SSI
+        .route("/api/identity/vp-challenge", post(vp_challenge_endpoint))
         .route("/api/identity/verify-vp", post(verify_vp_endpoint))
         
         // Vault & Security (Root-only)
@@ -44,97 +46,29 @@ pub fn router() -> Router<AppState> {
         .route("/api/config/adblock", get(get_adblock_config))
         .route("/api/config/adblock/toggle", post(toggle_adblock))
 
-        // VPN routes are in vpn.rs (merged via api::routes())
-        
-        // Diary
-        .route("/api/soul/diary", get(get_diary_entries_handler))
-        .route("/api/soul/diary", post(create_diary_entry))
-}
-
-// ── Diary Handlers ────────────────────────────────────────────────
-
-async fn get_diary_entries_handler(user: AuthenticatedUser, State(state): State<AppState>) -> Result<Json<Vec<crate::identity::soul::DiaryEntry>>, (StatusCode, String)> {
-    if user.role == crate::identity::UserRole::Child {
-         return Err((StatusCode::FORBIDDEN, "Children cannot access the diary".into()));
-    }
-    let soul = state.soul.lock().await;
-    // Default limit 50
-    Ok(Json(soul.get_diary_entries(50)))
-}
-
-async fn create_diary_entry(
-    user: AuthenticatedUser, 
-    State(state): State<AppState>, 
-    Json(payload): Json<CreateDiaryEntryRequest>
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    // RBAC: Senior, Adult, and Root can write to diary (Child cannot)
-    if user.role < crate::identity::UserRole::Senior {
-         return Err((StatusCode::FORBIDDEN, "Only Adults/Seniors can update the diary".into()));
-    }
-
-    // Rate limiting: 20 diary writes per minute
-    if !state.rate_limiter.allow_diary("diary_global") {
-        return Err((StatusCode::TOO_MANY_REQUESTS, "Too many diary writes. Please wait.".into()));
-    }
-
-    // Input sanitization: limit length and strip dangerous characters
-    let content = payload.content.trim();
-    if content.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "Diary entry cannot be empty".into()));
-    }
-    if content.len() > 10_000 {
-        return Err((StatusCode::BAD_REQUEST, "Diary entry too long (max 10000 chars)".into()));
-    }
-    // Strip markdown heading syntax that could corrupt SOUL.md structure
-    let sanitized = content
-        .replace("# ", "")
-        .replace("## ", "")
-        .replace("### ", "");
-
-    let mut soul = state.soul.lock().await;
-    
-    soul.append_diary_entry(&sanitized)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        
-    Ok(Json(serde_json::json!({ "success": true })))
+        // VPN routes are in vpn.rs, the diary in diary.rs (merged via api::routes())
 }
 
 // ── Encrypted Memories / Vault ───────────────────────────────────
 
-async fn get_vault_entries(user: AuthenticatedUser, State(state): State<AppState>) -> Result<Json<Vec<VaultEntryMetadata>>, (StatusCode, String)> {
-    if user.role != crate::identity::UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can audit vault".into()));
-    }
-
+async fn get_vault_entries(_user: AuthenticatedUser, State(state): State<AppState>) -> Result<Json<Vec<VaultEntryMetadata>>, (StatusCode, String)> {
     let entries = state.vault.list_entries()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let metadata = entries.into_iter().map(|e| VaultEntryMetadata {
-        id: e.id,
-        description: e.description,
-        created_at: e.created_at,
-        tags: e.tags,
-    }).collect();
+    let metadata = entries.into_iter().map(VaultEntryMetadata::from).collect();
 
     Ok(Json(metadata))
 }
 
-async fn get_sigil_logs(user: AuthenticatedUser, State(state): State<AppState>) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, String)> {
-    if user.role != crate::identity::UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can view Sigil interception logs".into()));
-    }
-
-    // Read the audit log and filter for Sigil interception events
-    let log_path = state.audit.log_path();
-    
-    if !log_path.exists() {
-        return Ok(Json(vec![]));
-    }
-
-    let content = std::fs::read_to_string(log_path)
+async fn get_sigil_logs(_user: AuthenticatedUser, State(state): State<AppState>) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, String)> {
+    // Read the audit log, including rotated files, and filter for Sigil interception events
+    let audit = state.audit.clone();
+    let lines = tokio::task::spawn_blocking(move || audit.read_lines())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     
-    let logs: Vec<serde_json::Value> = content.lines()
+    let logs: Vec<serde_json::Value> = lines.iter()
         .filter_map(|l| serde_json::from_str(l).ok())
         .filter(|v: &serde_json::Value| {
             let event_type = v["event_type"].as_str().unwrap_or("");
@@ -152,23 +86,69 @@ async fn get_sigil_logs(user: AuthenticatedUser, State(state): State<AppState>)
 #[derive(serde::Deserialize)]
 struct VerifyVPRequest {
     vp: String, // JSON-LD or JWT string
+    /// Challenge from `POST /api/identity/vp-challenge` the holder signed
+    #[serde(default)]
+    challenge: Option<String>,
+}
+
+/// Domain presentations have to be bound to: the gateway's public host.
+fn vp_domain(state: &AppState) -> Option<String> {
+    reqwest::Url::parse(&state.public_url)
+        .ok()?
+        .host_str()
+        .map(str::to_string)
 }
 
+/// POST /api/identity/vp-challenge — a single-use challenge and the domain
+/// a wallet has to sign into its presentation
+async fn vp_challenge_endpoint(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "challenge": state.vp_challenges.issue(),
+        "domain": vp_domain(&state),
+        "expires_in": state.vp_challenges.ttl().as_secs(),
+    }))
+}
+
+/// POST /api/identity/verify-vp — verify a presentation. Only one bound to
+/// a challenge from this gateway links the holder's DID to the soul.
 async fn verify_vp_endpoint(
     _user: AuthenticatedUser,
     State(state): State<AppState>,
     Json(payload): Json<VerifyVPRequest>,
 ) -> Json<serde_json::Value> {
-    match crate::identity::ssi::SSIGuardian::verify_vp(&payload.vp).await {
+    if let Some(challenge) = &payload.challenge {
+        if !state.vp_challenges.consume(challenge) {
+            let error = "Unknown or expired challenge; request a new one";
+            return Json(serde_json::json!({ "success": false, "error": error }));
+        }
+    }
+    let options = crate::identity::ssi::VerifyOptions {
+        domain: payload.challenge.as_ref().and_then(|_| vp_domain(&state)),
+        challenge: payload.challenge,
+        check_status: true,
+    };
+    match crate::identity::ssi::SSIGuardian::verify_vp(&payload.vp, &options).await {
         Ok(result) => {
-            if result.is_valid {
+            let bound = options.challenge.is_some();
+            let linked = result.is_valid && bound;
+            if linked {
                 // Link the DID to the soul
                 if let Some(holder) = &result.holder_did {
                     let mut soul = state.soul.lock().await;
                     let _ = soul.add_binding("SSI-Wallet", holder, crate::identity::soul::TrustLevel::High);
                 }
             }
-            Json(serde_json::json!({ "success": true, "result": result }))
+            let error = if !result.is_valid {
+                Some(format!("Verification failed: {}", result.errors.join("; ")))
+            } else if !bound {
+                Some("Presentation is not bound to a challenge; not linked".to_string())
+            } else {
+                None
+            };
+            Json(serde_json::json!({ "success": linked, "result": result, "error": error }))
         }
         Err(e) => {
              Json(serde_json::json!({ "success": false, "error": format!("{e}") }))
@@ -197,6 +177,16 @@ async fn get_widgets(_user: AuthenticatedUser, State(_state): State<AppState>) -
     ])
 }
 
+/// POST /api/system/panic — emergency stop. Any family member may press it;
+/// resuming requires pairing again with the code printed on the console.
+async fn trigger_panic(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<serde_json::Value> {
+    let report = state.kill_switch.engage(&user.actor()).await;
+    Json(serde_json::json!({ "success": true, "report": report }))
+}
+
 async fn get_identities(_user: AuthenticatedUser, State(state): State<AppState>) -> Json<Vec<IdentityStatus>> {
     let soul = state.soul.lock().await;
     
@@ -333,6 +323,11 @@ async fn get_system_status(_user: AuthenticatedUser, State(state): State<AppStat
         voice_echo_enabled: state.voice_echo_enabled.load(std::sync::atomic::Ordering::Relaxed),
         adblock_enabled: state.adblock.is_enabled().await,
         adblock_count: state.adblock.count().await,
+        last_voice_turn: state
+            .last_voice_turn
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone(),
     })
 }
 
@@ -343,12 +338,12 @@ async fn get_adblock_config(_user: AuthenticatedUser, State(state): State<AppSta
     }))
 }
 
-async fn toggle_adblock(user: AuthenticatedUser, State(state): State<AppState>, Json(payload): Json<AdBlockToggleRequest>) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role < crate::identity::UserRole::Adult {
-        return Err((StatusCode::FORBIDDEN, "Only Adults/Root can manage AdBlock".into()));
-    }
-
+async fn toggle_adblock(_user: AuthenticatedUser, State(state): State<AppState>, Json(payload): Json<AdBlockToggleRequest>) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     state.adblock.toggle(payload.enabled).await;
+    crate::automations::publish(crate::automations::Event::StateChange {
+        component: "adblock".into(),
+        state: if payload.enabled { "enabled" } else { "disabled" }.into(),
+    });
     Ok(Json(serde_json::json!({ "success": true, "enabled": payload.enabled })))
 }
 
@@ -413,11 +408,12 @@ async fn handle_oidc_login(
 
     let provider = crate::identity::oidc_generic::GenericOIDCProvider::new(config.clone());
     
-    // Generate a cryptographically secure random state token (CSRF protection)
-    let state_param = state.oidc_states.generate(&provider_id);
+    // Generate a cryptographically secure random state token (CSRF protection),
+    // with the PKCE verifier and nonce the callback checks
+    let (state_param, challenge) = state.oidc_states.begin(&provider_id);
     let redirect_uri = format!("{}/api/auth/callback/{}", state.public_url.trim_end_matches('/'), provider_id);
 
-    let url = provider.get_login_url(&redirect_uri, &state_param).await
+    let url = provider.get_login_url(&redirect_uri, &state_param, &challenge).await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(axum::response::Redirect::to(&url))
@@ -427,9 +423,10 @@ async fn handle_oidc_callback(
     State(state): State<AppState>,
     axum::extract::Path(provider_id): axum::extract::Path<String>,
     Query(query): Query<OICDCallbackQuery>,
+    headers: HeaderMap,
 ) -> Result<axum::response::Redirect, (StatusCode, String)> {
     // Validate and consume the state token (single-use, prevents CSRF + replay)
-    let validated_provider = state.oidc_states.validate(&query.state)
+    let (validated_provider, challenge) = state.oidc_states.complete(&query.state)
         .ok_or((StatusCode::BAD_REQUEST, "Invalid or expired OIDC state parameter (possible CSRF)".to_string()))?;
 
     // Verify the state was generated for THIS provider
@@ -444,8 +441,31 @@ async fn handle_oidc_callback(
     let provider = crate::identity::oidc_generic::GenericOIDCProvider::new(config.clone());
     let redirect_uri = format!("{}/api/auth/callback/{}", state.public_url.trim_end_matches('/'), provider_id);
 
-    let user_info = provider.exchange_code(&query.code, &redirect_uri).await
-         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Token exchange failed: {}", e)))?;
+    let login = provider.exchange_code(&query.code, &redirect_uri, &challenge).await
+         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Token exchange failed: {:#}", e)))?;
+    let user_info = &login.user;
+
+    // Keep the refresh token so the identity stays linked without logging in again
+    if let Some(refresh_token) = &login.refresh_token {
+        if let Err(e) = state.oidc_tokens.store(&provider_id, &login.subject, refresh_token, login.expires_in) {
+            tracing::warn!("Failed to store OIDC refresh token for {provider_id}: {e}");
+        }
+    }
+
+    // A linked family member logs in with a session of their own
+    let member = {
+        let config = state.config.read().await;
+        crate::identity::family::member_for_oidc(&config.family, &provider_id, &user_info.id)
+            .map(crate::identity::family::FamilyMember::from_config)
+    };
+    if let Some(member) = member {
+        let session = super::family::start_session(&state, &headers, &member)?;
+        // Tokens travel in the fragment so they never reach server logs
+        return Ok(axum::response::Redirect::to(&format!(
+            "/?login_success=true#token={}&refresh_token={}",
+            session.token, session.refresh_token
+        )));
+    }
 
     let trust_level = match config.trust_level {
         3 => crate::identity::soul::TrustLevel::High,
@@ -463,8 +483,13 @@ async fn handle_oidc_callback(
 }
 
 async fn get_models(_user: AuthenticatedUser, State(state): State<AppState>) -> Json<Vec<ModelInfo>> {
-    let current = state.model.read().await.clone();
-    json_models(current)
+    let current = state.models.default_model();
+    let provider = state.config.read().await.default_provider.clone();
+    let Json(mut models) = json_models(current);
+    if provider.as_deref() == Some("ollama") {
+        models.extend(super::ollama::installed_models().await);
+    }
+    Json(models)
 }
 
 fn json_models(current: String) -> Json<Vec<ModelInfo>> {
@@ -491,23 +516,21 @@ fn json_models(current: String) -> Json<Vec<ModelInfo>> {
 }
 
 async fn set_model(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
     Json(payload): Json<SelectModelRequest>
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != crate::identity::UserRole::Root {
-         return Err((StatusCode::FORBIDDEN, "Only Root can change models".into()));
-    }
+    require_step_up(&state, &headers, &client)?;
 
     // Rate limiting: 3 model switches per minute
     if !state.rate_limiter.allow_model_switch("model_global") {
         return Err((StatusCode::TOO_MANY_REQUESTS, "Too many model switches. Please wait.".into()));
     }
     
-    // Hot-swap: update the model at runtime
-    let mut model = state.model.write().await;
-    let old_model = model.clone();
-    *model = payload.model_id.clone();
+    // Hot-swap: update the default model at runtime; task routes keep theirs
+    let old_model = state.models.set_default_model(payload.model_id.clone());
     
     tracing::info!("Model changed: {} -> {}", old_model, payload.model_id);
     
@@ -525,28 +548,59 @@ async fn resolve_confirmation(
     State(state): State<AppState>,
     Json(payload): Json<crate::security::confirmation::ConfirmationResponse>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    // Only Root/Adult can resolve confirmations
-    if user.role < crate::identity::UserRole::Adult {
-        return Err((StatusCode::FORBIDDEN, "Only Adults/Root can resolve confirmations".into()));
-    }
+    use crate::security::confirmation::Answer;
+
+    // Approving needs the role the tool's confirmation policy names. For
+    // dual control the approver is the same person as in chat.
+    let identity = crate::security::confirmation::approver_identity(user.member.as_deref());
+    let answer = state
+        .confirm_gate
+        .resolve_as(&payload.id, payload.approved, user.role, &identity)
+        .await
+        .map_err(|reason| (StatusCode::FORBIDDEN, reason))?;
+
+    let decision = match answer {
+        Answer::Resolved if payload.approved => "approved".to_string(),
+        Answer::Resolved => "denied".to_string(),
+        Answer::Partial {
+            approvals,
+            required,
+        } => format!("approved {approvals}/{required}"),
+        Answer::NotFound => {
+            return Ok(Json(serde_json::json!({
+                "success": false,
+                "resolved": false,
+                "error": "Request not found or expired",
+            })));
+        }
+    };
 
-    let resolved = state.confirm_gate.resolve(&payload.id, payload.approved).await;
-
-    if resolved {
-        // Audit the decision
-        let _ = state.audit.log(
-            &crate::security::AuditEvent::new(crate::security::AuditEventType::SecurityEvent)
-                .with_actor("gateway".to_string(), None, None)
-                .with_action(
-                    format!("confirm:{}", payload.id),
-                    if payload.approved { "approved" } else { "denied" }.to_string(),
-                    payload.approved,
-                    true,
-                ),
-        );
-        Ok(Json(serde_json::json!({ "success": true, "resolved": true })))
-    } else {
-        Ok(Json(serde_json::json!({ "success": false, "resolved": false, "error": "Request not found or expired" })))
+    // Audit the decision
+    let _ = state.audit.log(
+        &crate::security::AuditEvent::new(crate::security::AuditEventType::SecurityEvent)
+            .with_actor("gateway".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!("confirm:{}", payload.id),
+                decision,
+                payload.approved,
+                true,
+            ),
+    );
+    match answer {
+        // Dual control: still waiting for another person
+        Answer::Partial {
+            approvals,
+            required,
+        } => Ok(Json(serde_json::json!({
+            "success": true,
+            "resolved": false,
+            "approvals": approvals,
+            "required": required,
+        }))),
+        _ => Ok(Json(serde_json::json!({
+            "success": true,
+            "resolved": true,
+        }))),
     }
 }
 
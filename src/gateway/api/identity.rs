@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Removing identity bindings (`DELETE /api/identity/{provider}/{id}`).
+//!
+//! A compromised or outdated identity is unbound from SOUL.md once Root
+//! approves it through the confirmation gate. The trust level is then
+//! recomputed from the remaining bindings and applied to the live security
+//! policy right away, so tools gated on trust lose access without a
+//! restart. Credentials kept for the binding (a passkey, an OIDC refresh
+//! token) are dropped with it.
+
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::identity::soul::TrustLevel;
+use crate::security::confirmation::ConfirmationPolicy;
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::delete,
+    Router,
+};
+use serde::Serialize;
+
+const CONFIRM_TOOL: &str = "identity_unbind";
+const CONFIRM_TIMEOUT_SECS: u64 = 120;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct UnbindResponse {
+    pub provider: String,
+    pub id: String,
+    /// Trust level of the removed binding
+    pub removed_trust_level: TrustLevel,
+    pub previous_trust_level: TrustLevel,
+    /// Trust level from the remaining bindings, now in effect
+    pub trust_level: TrustLevel,
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn audit(state: &AppState, user: &AuthenticatedUser, action: String, approved: bool) {
+    let event = AuditEvent::new(AuditEventType::SecurityEvent)
+        .with_actor("api".to_string(), None, Some(user.actor()))
+        .with_action(action, "high".to_string(), approved, approved);
+    if let Err(e) = state.audit.log_signed(&event) {
+        tracing::error!("Failed to record identity unbinding: {e}");
+    }
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// DELETE /api/identity/{provider}/{id} — unbind an identity once Root
+/// approves, and downgrade the trust level accordingly
+pub async fn unbind_identity(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((provider, id)): Path<(String, String)>,
+) -> Result<Json<UnbindResponse>, (StatusCode, String)> {
+    let removed_trust_level = state
+        .soul
+        .lock()
+        .await
+        .bindings
+        .iter()
+        .find(|b| b.provider == provider && b.id == id)
+        .map(|b| b.trust_level)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No identity binding {provider}:{id}"),
+        ))?;
+
+    // The soul stays unlocked while Root decides
+    let policy = ConfirmationPolicy::with_default_timeout(
+        &state.config.read().await.security.confirmation,
+        CONFIRM_TOOL,
+        CONFIRM_TIMEOUT_SECS,
+    );
+    let approved = state
+        .confirm_gate
+        .request_with_policy(
+            CONFIRM_TOOL,
+            &format!("Remove identity binding {provider}:{id} ({removed_trust_level:?} trust)"),
+            &policy,
+        )
+        .await;
+    if !approved {
+        audit(
+            &state,
+            &user,
+            format!("identity:unbind {provider}:{id}"),
+            false,
+        );
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("{CONFIRM_TOOL} was not approved"),
+        ));
+    }
+
+    let previous_trust_level = state.security.trust_level();
+    let trust_level = {
+        let mut soul = state.soul.lock().await;
+        if !soul
+            .remove_binding(&provider, &id)
+            .map_err(internal_error)?
+        {
+            return Err((
+                StatusCode::NOT_FOUND,
+                format!("No identity binding {provider}:{id}"),
+            ));
+        }
+        soul.max_trust_level()
+    };
+    state.security.set_trust_level(trust_level);
+
+    // Credentials that would let the identity back in
+    #[cfg(feature = "passkeys")]
+    if provider == crate::identity::passkey::BINDING_PROVIDER {
+        if let Err(e) = state.passkeys.remove(&id) {
+            tracing::warn!("Failed to drop passkey {id}: {e}");
+        }
+    }
+    let oidc_provider = state
+        .identity_config
+        .providers
+        .iter()
+        .find(|p| p.name == provider);
+    if let Some(config) = oidc_provider {
+        if let Err(e) = state.oidc_tokens.remove(&config.id, &id) {
+            tracing::warn!("Failed to drop refresh token of {}: {e}", config.id);
+        }
+    }
+
+    tracing::warn!(
+        "🪪 Identity {provider}:{id} unbound; trust {previous_trust_level:?} → {trust_level:?}"
+    );
+    audit(
+        &state,
+        &user,
+        format!(
+            "identity:unbind {provider}:{id} (trust {previous_trust_level:?} -> {trust_level:?})"
+        ),
+        true,
+    );
+    Ok(Json(UnbindResponse {
+        provider,
+        id,
+        removed_trust_level,
+        previous_trust_level,
+        trust_level,
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/identity/{provider}/{id}", delete(unbind_identity))
+}
@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Location API — coarse location events from companion apps (mobile/Tauri).
+//!
+//! Clients geofence on the device and report only a configured place name
+//! and whether the user arrived or left; coordinates are rejected. Matching
+//! reminders from the encrypted PIM are returned for the client to show.
+//! No location history is stored, reminders are excluded from peer sync, and
+//! each event is logged as a Sigil interception for the transparency view.
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::config::LocationConfig;
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::pim::{self, LocationReminder, LocationTrigger};
+use serde::{Deserialize, Serialize};
+
+const MAX_TEXT_LEN: usize = 500;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+/// A location event. Unknown fields (e.g. `lat`/`lon`) are rejected so precise
+/// geodata never reaches the gateway.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocationEventRequest {
+    pub place: String,
+    /// "arrive" or "leave"
+    pub trigger: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocationEventResponse {
+    pub place: String,
+    pub trigger: LocationTrigger,
+    pub fired: Vec<LocationReminder>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReminderRequest {
+    pub place: String,
+    pub trigger: String,
+    pub text: String,
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlacesResponse {
+    pub enabled: bool,
+    pub places: Vec<String>,
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+async fn location_config(state: &AppState) -> Result<LocationConfig, (StatusCode, String)> {
+    let config = state.config.read().await.location.clone();
+    if !config.enabled {
+        return Err((StatusCode::FORBIDDEN, "Location events are disabled".into()));
+    }
+    Ok(config)
+}
+
+/// Validate a place against the configured names and an arrive/leave trigger.
+fn parse_event(
+    config: &LocationConfig,
+    place: &str,
+    trigger: &str,
+) -> Result<(String, LocationTrigger), (StatusCode, String)> {
+    let place = pim::normalize_place(place);
+    if !config.places.iter().any(|p| pim::normalize_place(p) == place) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unknown place '{place}' (known: {})", config.places.join(", ")),
+        ));
+    }
+    let trigger = LocationTrigger::parse(trigger).ok_or_else(|| {
+        (StatusCode::BAD_REQUEST, format!("Invalid trigger '{trigger}' (expected arrive or leave)"))
+    })?;
+    Ok((place, trigger))
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/location/places — whether events are accepted and for which places
+pub async fn list_places(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<PlacesResponse> {
+    let config = state.config.read().await.location.clone();
+    Json(PlacesResponse {
+        enabled: config.enabled,
+        places: config.places,
+    })
+}
+
+/// POST /api/location/event — report arriving at or leaving a place
+pub async fn location_event(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<LocationEventRequest>,
+) -> Result<Json<LocationEventResponse>, (StatusCode, String)> {
+    let config = location_config(&state).await?;
+    let (place, trigger) = parse_event(&config, &payload.place, &payload.trigger)?;
+
+    let secrets = secrets(&state);
+    let mut store = pim::load_store(&state.workspace_dir, &secrets);
+    let fired = pim::fire_location_reminders(&mut store, &place, trigger);
+    for reminder in &fired {
+        crate::automations::publish(crate::automations::Event::Reminder {
+            place: reminder.place.clone(),
+            text: reminder.text.clone(),
+        });
+    }
+    if fired.iter().any(|r| !r.repeat) {
+        pim::save_store(&state.workspace_dir, &store, &secrets)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    // The place name stays out of the log so it does not become a location history.
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::SigilInterception)
+            .with_actor("location".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!(
+                    "Location event ({}) kept on device, {} reminder(s) fired",
+                    trigger.as_str(),
+                    fired.len()
+                ),
+                "low".to_string(),
+                true,
+                true,
+            ),
+    );
+
+    Ok(Json(LocationEventResponse {
+        place,
+        trigger,
+        fired,
+    }))
+}
+
+/// GET /api/location/reminders — pending location reminders
+pub async fn list_reminders(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<Vec<LocationReminder>> {
+    Json(pim::load_store(&state.workspace_dir, &secrets(&state)).location_reminders)
+}
+
+/// POST /api/location/reminders — create a reminder for a configured place
+pub async fn create_reminder(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateReminderRequest>,
+) -> Result<Json<LocationReminder>, (StatusCode, String)> {
+    let config = state.config.read().await.location.clone();
+    let (place, trigger) = parse_event(&config, &payload.place, &payload.trigger)?;
+    let text = payload.text.trim();
+    if text.is_empty() || text.len() > MAX_TEXT_LEN {
+        return Err((StatusCode::BAD_REQUEST, format!("Text must be 1-{MAX_TEXT_LEN} characters")));
+    }
+
+    let reminder = LocationReminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        place,
+        trigger,
+        text: text.to_string(),
+        repeat: payload.repeat,
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    let secrets = secrets(&state);
+    let mut store = pim::load_store(&state.workspace_dir, &secrets);
+    store.location_reminders.push(reminder.clone());
+    pim::save_store(&state.workspace_dir, &store, &secrets)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(reminder))
+}
+
+/// DELETE /api/location/reminders/{id} — remove a reminder
+pub async fn delete_reminder(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let secrets = secrets(&state);
+    let mut store = pim::load_store(&state.workspace_dir, &secrets);
+    let before = store.location_reminders.len();
+    store.location_reminders.retain(|r| r.id != id);
+    if store.location_reminders.len() == before {
+        return Err((StatusCode::NOT_FOUND, "Reminder not found".into()));
+    }
+    pim::save_store(&state.workspace_dir, &store, &secrets)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/location/places", get(list_places))
+        .route("/api/location/event", post(location_event))
+        .route("/api/location/reminders", get(list_reminders).post(create_reminder))
+        .route("/api/location/reminders/{id}", delete(delete_reminder))
+}
@@ -2,21 +2,27 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-//! MCP server management API endpoints — list, add, remove MCP servers.
+//! MCP server management API endpoints — list, add, remove MCP servers,
+//! browse their resources and prompts, and insert prompts into the system
+//! prompt.
 //!
 //! Root-only. Modifies the config.mcp section and persists.
 
 use axum::{
     extract::{State, Json, Path},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     routing::{get, delete},
     Router,
 };
-use crate::gateway::AppState;
-use crate::gateway::api::auth::AuthenticatedUser;
-use crate::identity::UserRole;
+use crate::gateway::{AppState, ClientKey};
+use crate::gateway::api::auth::{require_step_up, AuthenticatedUser};
+use crate::mcp::{McpResourceReadTool, SigilGatekeeper};
+use crate::security::{AuditEvent, AuditEventType};
+use crate::tools::{self, Tool};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
 
 // ── Types ──────────────────────────────────────────────────────────
 
@@ -26,7 +32,13 @@ pub struct McpServerView {
     pub command: String,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
-    pub status: String, // "configured" — runtime status requires actual connection check
+    pub url: Option<String>,
+    /// Header names only; values may hold credentials.
+    pub headers: Vec<String>,
+    /// "ok", "error" or "configured" when no connection was attempted yet.
+    pub status: String,
+    pub last_error: Option<String>,
+    pub restart_count: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,112 +48,282 @@ pub struct McpToolView {
     pub description: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct McpResourceView {
+    pub server: String,
+    #[serde(flatten)]
+    pub resource: crate::mcp::transport::McpResourceInfo,
+}
+
+#[derive(Debug, Serialize)]
+pub struct McpPromptView {
+    pub server: String,
+    #[serde(flatten)]
+    pub prompt: crate::mcp::transport::McpPromptInfo,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InsertPromptRequest {
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddMcpServerRequest {
     pub name: String,
+    #[serde(default)]
     pub command: String,
     pub args: Option<Vec<String>>,
     pub env: Option<HashMap<String, String>>,
+    pub url: Option<String>,
+    pub headers: Option<HashMap<String, String>>,
+}
+
+// ── Helpers ────────────────────────────────────────────────────────
+
+fn audit(state: &AppState, action: String, success: bool) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("mcp".to_string(), None, Some("Root".to_string()))
+            .with_action(action, "high".to_string(), true, success),
+    );
+}
+
+/// Heading of the system prompt block that lists a hot-added server's tools.
+pub(crate) fn tools_heading(server: &str) -> String {
+    format!("## MCP server: {server}")
+}
+
+/// System prompt block for tools added after startup. Descriptions are kept
+/// on one line so the block ends at the next `## ` heading.
+fn tools_block(server: &str, tools: &[Box<dyn Tool>]) -> String {
+    let mut block = format!("\n\n{}\n\n", tools_heading(server));
+    for tool in tools {
+        let _ = writeln!(
+            block,
+            "**{}**: {}\nParameters: `{}`\n",
+            tool.name(),
+            tool.description().replace('\n', " "),
+            tool.parameters_schema()
+        );
+    }
+    block
+}
+
+/// Remove the block starting at `heading`, up to the next `## ` heading.
+pub(crate) fn remove_section(prompt: &mut String, heading: &str) {
+    let marker = format!("\n\n{heading}\n");
+    let Some(start) = prompt.find(&marker) else {
+        return;
+    };
+    let end = prompt[start + marker.len()..]
+        .find("\n\n## ")
+        .map_or(prompt.len(), |offset| start + marker.len() + offset);
+    prompt.replace_range(start..end, "");
 }
 
 // ── Handlers ───────────────────────────────────────────────────────
 
 /// GET /api/mcp/servers — list configured MCP servers
 pub async fn list_mcp_servers(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<Vec<McpServerView>>, StatusCode> {
-    if user.role != UserRole::Root {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
     let config = state.config.read().await;
-    let servers = config.mcp.servers.iter().map(|s| McpServerView {
-        name: s.name.clone(),
-        command: s.command.clone(),
-        args: s.args.clone(),
-        env: s.env.clone(),
-        status: "configured".into(),
+    let health = crate::health::snapshot();
+    let servers = config.mcp.servers.iter().map(|s| {
+        let runtime = health
+            .components
+            .get(&crate::mcp::supervisor::component_name(&s.name));
+        McpServerView {
+            name: s.name.clone(),
+            command: s.command.clone(),
+            args: s.args.clone(),
+            env: s.env.clone(),
+            url: s.url.clone(),
+            headers: s.headers.keys().cloned().collect(),
+            status: runtime.map_or_else(|| "configured".into(), |h| h.status.clone()),
+            last_error: runtime.and_then(|h| h.last_error.clone()),
+            restart_count: runtime.map_or(0, |h| h.restart_count),
+        }
     }).collect();
 
     Ok(Json(servers))
 }
 
-/// POST /api/mcp/servers — add a new MCP server config
+/// POST /api/mcp/servers — connect a new MCP server, register its tools in
+/// the live registry and persist it to config. Starting a stdio server runs
+/// an arbitrary command, so this needs Root's second factor.
 pub async fn add_mcp_server(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
     Json(payload): Json<AddMcpServerRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can manage MCP servers".into()));
-    }
-
-    let mut config = state.config.write().await;
+    require_step_up(&state, &headers, &client)?;
+    let (mcp_config, reliability) = {
+        let config = state.config.read().await;
+        (config.mcp.clone(), config.reliability.clone())
+    };
 
     // Check for duplicate name
-    if config.mcp.servers.iter().any(|s| s.name.eq_ignore_ascii_case(&payload.name)) {
+    if mcp_config.servers.iter().any(|s| s.name.eq_ignore_ascii_case(&payload.name))
+        || state.mcp.get(&payload.name).await.is_some()
+    {
         return Err((
             StatusCode::CONFLICT,
             format!("MCP server '{}' already exists", payload.name),
         ));
     }
 
+    let url = payload.url.filter(|u| !u.trim().is_empty());
+    match &url {
+        Some(u) if !["http://", "https://", "ws://", "wss://"].iter().any(|p| u.starts_with(p)) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "url must start with http(s):// or ws(s)://".into(),
+            ));
+        }
+        None if payload.command.trim().is_empty() => {
+            return Err((StatusCode::BAD_REQUEST, "Either command or url is required".into()));
+        }
+        _ => {}
+    }
+
     let new_server = crate::config::schema::McpServerConfig {
         name: payload.name.clone(),
         command: payload.command,
         args: payload.args.unwrap_or_default(),
         env: payload.env.unwrap_or_default(),
+        url,
+        headers: payload.headers.unwrap_or_default(),
     };
 
-    config.mcp.servers.push(new_server);
-    config.mcp.enabled = true;
-    config.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+    // Record what is about to run before it runs
+    let target = match &new_server.url {
+        Some(url) => format!("url {url}"),
+        None => format!(
+            "command {}",
+            std::iter::once(new_server.command.as_str())
+                .chain(new_server.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    };
+    audit(
+        &state,
+        format!("mcp_server_spawn:{} {target}", new_server.name),
+        true,
+    );
+
+    // Connect and discover before persisting, so a broken server is never saved
+    let gatekeeper = Arc::new(SigilGatekeeper::new(
+        Arc::clone(&state.security),
+        Some(Arc::clone(&state.audit)),
+    ));
+    let (server, discovered) = match crate::mcp::start_server(&new_server, &mcp_config, Arc::clone(&gatekeeper)).await {
+        Ok(connected) => connected,
+        Err(e) => {
+            audit(&state, format!("mcp_server_add:{} failed: {e}", new_server.name), false);
+            return Err((StatusCode::BAD_GATEWAY, format!("Failed to connect to MCP server: {e:#}")));
+        }
+    };
+
+    let mut bridged = tools::wrap_mcp_tools(
+        discovered,
+        &state.security,
+        &reliability,
+        &state.workspace_dir,
+        Some(Arc::clone(&state.audit)),
+    );
+    let names: Vec<String> = bridged.iter().map(|t| t.name().to_string()).collect();
+    let block = tools_block(server.name(), &bridged);
+    let needs_resource_tool = server.client().await.is_some_and(|c| c.supports("resources"))
+        && !state.tools_registry.contains("mcp_resource_read");
+    if needs_resource_tool {
+        bridged.extend(tools::wrap_mcp_tools(
+            vec![Box::new(McpResourceReadTool::new(Arc::clone(&state.mcp), gatekeeper))],
+            &state.security,
+            &reliability,
+            &state.workspace_dir,
+            Some(Arc::clone(&state.audit)),
+        ));
+    }
+    if let Err(e) = state.tools_registry.extend(bridged) {
+        server.stop().await;
+        return Err((StatusCode::CONFLICT, e.to_string()));
+    }
+
+    {
+        let mut config = state.config.write().await;
+        config.mcp.servers.push(new_server);
+        config.mcp.enabled = true;
+        if let Err(e) = config.save() {
+            config.mcp.servers.pop();
+            state.tools_registry.remove(&names);
+            server.stop().await;
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")));
+        }
+    }
+    state.mcp.add(Arc::clone(&server), names.clone()).await;
+    // A tool router offers the new tools per request
+    if state.tool_router.is_none() {
+        state.system_prompt.write().await.push_str(&block);
+    }
+
+    audit(&state, format!("mcp_server_add:{} ({} tools)", server.name(), names.len()), true);
 
     Ok(Json(serde_json::json!({
         "status": "added",
         "name": payload.name,
+        "tools": names,
     })))
 }
 
-/// DELETE /api/mcp/servers/:name — remove an MCP server
+/// DELETE /api/mcp/servers/:name — disconnect an MCP server, unregister its
+/// tools and remove it from config
 pub async fn remove_mcp_server(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Only Root can manage MCP servers".into()));
+    let connected = state.mcp.remove(&name).await;
+    let mut removed_tools = 0;
+    if let Some((server, names)) = &connected {
+        removed_tools = state.tools_registry.remove(names);
+        server.stop().await;
+        remove_section(&mut *state.system_prompt.write().await, &tools_heading(server.name()));
     }
 
     let mut config = state.config.write().await;
     let before = config.mcp.servers.len();
     config.mcp.servers.retain(|s| !s.name.eq_ignore_ascii_case(&name));
 
-    if config.mcp.servers.len() == before {
+    if config.mcp.servers.len() == before && connected.is_none() {
         return Err((StatusCode::NOT_FOUND, format!("MCP server '{}' not found", name)));
     }
 
     config.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+    drop(config);
+
+    audit(&state, format!("mcp_server_remove:{name} ({removed_tools} tools)"), true);
 
     Ok(Json(serde_json::json!({
         "status": "removed",
         "name": name,
+        "tools_removed": removed_tools,
     })))
 }
 
 /// GET /api/mcp/tools — list all tools from registered MCP servers
 pub async fn list_mcp_tools(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<Vec<McpToolView>>, StatusCode> {
-    if user.role != UserRole::Root {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
     // Tools are dynamically registered in the tools_registry
     // We return what's available from the registry
-    let tools: Vec<McpToolView> = state.tools_registry.iter().map(|t| McpToolView {
+    let tools: Vec<McpToolView> = state.tools_registry.snapshot().iter().map(|t| McpToolView {
         server: "mymolt".into(), // All tools are surfaced through MyMolt's registry
         name: t.name().to_string(),
         description: t.description().to_string(),
@@ -157,16 +339,13 @@ pub struct CallToolRequest {
 
 /// POST /api/mcp/tools/:name/call — manually test a tool
 pub async fn call_mcp_tool(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(name): Path<String>,
     Json(payload): Json<CallToolRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    if user.role != UserRole::Root {
-        return Err((StatusCode::FORBIDDEN, "Access denied".into()));
-    }
-
-    let tool = state.tools_registry.iter().find(|t| t.name() == name)
+    let registry = state.tools_registry.snapshot();
+    let tool = registry.iter().find(|t| t.name() == name)
         .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Tool {name} not found")))?;
 
     let result = tool.execute(payload.payload).await
@@ -176,6 +355,77 @@ pub async fn call_mcp_tool(
     Ok(Json(serde_json::to_value(result).unwrap_or_else(|_| serde_json::json!({ "error": "failed to serialize result" }))))
 }
 
+/// GET /api/mcp/resources — list resources of connected MCP servers
+pub async fn list_mcp_resources(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<McpResourceView>>, StatusCode> {
+    let mut resources = Vec::new();
+    for server in state.mcp.servers().await {
+        resources.extend(server.resources().await.into_iter().map(|resource| McpResourceView {
+            server: server.name().to_string(),
+            resource,
+        }));
+    }
+    Ok(Json(resources))
+}
+
+/// GET /api/mcp/prompts — list prompt templates of connected MCP servers
+pub async fn list_mcp_prompts(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<McpPromptView>>, StatusCode> {
+    let mut prompts = Vec::new();
+    for server in state.mcp.servers().await {
+        prompts.extend(server.prompts().await.into_iter().map(|prompt| McpPromptView {
+            server: server.name().to_string(),
+            prompt,
+        }));
+    }
+    Ok(Json(prompts))
+}
+
+/// Heading that marks an inserted MCP prompt in the system prompt.
+fn prompt_heading(server: &str, name: &str) -> String {
+    format!("## MCP prompt: {server}/{name}")
+}
+
+/// POST /api/mcp/prompts/:server/:name/insert — render a prompt and append it
+/// to the system prompt
+pub async fn insert_mcp_prompt(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((server_name, name)): Path<(String, String)>,
+    payload: Option<Json<InsertPromptRequest>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let server = state.mcp.get(&server_name).await
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("MCP server '{server_name}' not connected")))?;
+    let prompt = server.prompts().await.into_iter().find(|p| p.name == name)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Prompt '{name}' not found")))?;
+
+    let arguments = payload.map(|Json(p)| p.arguments).unwrap_or_default();
+    if let Some(missing) = prompt.arguments.iter().find(|a| a.required && !arguments.contains_key(&a.name)) {
+        return Err((StatusCode::BAD_REQUEST, format!("Missing argument '{}'", missing.name)));
+    }
+
+    let heading = prompt_heading(server.name(), &name);
+    if state.system_prompt.read().await.contains(&heading) {
+        return Err((StatusCode::CONFLICT, format!("Prompt '{name}' is already inserted")));
+    }
+    let text = server.render_prompt(&name, &arguments).await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("{e:#}")))?;
+
+    let mut system_prompt = state.system_prompt.write().await;
+    system_prompt.push_str(&format!("\n\n{heading}\n\n{text}\n"));
+
+    Ok(Json(serde_json::json!({
+        "status": "inserted",
+        "server": server.name(),
+        "name": name,
+        "text": text,
+    })))
+}
+
 // ── Router ─────────────────────────────────────────────────────────
 
 pub fn router() -> Router<AppState> {
@@ -184,4 +434,25 @@ pub fn router() -> Router<AppState> {
         .route("/api/mcp/servers/{name}", delete(remove_mcp_server))
         .route("/api/mcp/tools", get(list_mcp_tools))
         .route("/api/mcp/tools/{name}/call", axum::routing::post(call_mcp_tool))
+        .route("/api/mcp/resources", get(list_mcp_resources))
+        .route("/api/mcp/prompts", get(list_mcp_prompts))
+        .route("/api/mcp/prompts/{server}/{name}/insert", axum::routing::post(insert_mcp_prompt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_blocks_are_removed_up_to_the_next_heading() {
+        let mut prompt = String::from("## Tools\n\nshell");
+        prompt.push_str(&format!("\n\n{}\n\n**search**: Find\nthings\n", tools_heading("docs")));
+        prompt.push_str("\n\n## MCP prompt: docs/review\n\nBe brief.\n");
+
+        remove_section(&mut prompt, &tools_heading("docs"));
+        assert_eq!(prompt, "## Tools\n\nshell\n\n## MCP prompt: docs/review\n\nBe brief.\n");
+
+        remove_section(&mut prompt, &tools_heading("missing"));
+        assert!(prompt.ends_with("Be brief.\n"));
+    }
 }
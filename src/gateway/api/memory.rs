@@ -0,0 +1,566 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Memory API — browse, edit and erase what the agent remembers.
+//!
+//! Root-only. Browsing lists entries page by page with category facets;
+//! vaulted entries only ever show their placeholder or redacted text, never
+//! the vault.
+//! Bulk erasure by text, category, date or tag filter (right to erasure) is
+//! queued on the confirmation gate; once approved via
+//! `/api/security/confirm`, the entries and the vault entries holding their
+//! redacted content are erased and a signed record is written to the audit
+//! log.
+
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::memory::traits::{erasure_matches, normalize_tags};
+use crate::memory::{sovereign, MemoryCategory, MemoryEntry, RecallFilter};
+use crate::security::confirmation::ConfirmationPolicy;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use crate::tools::memory_recall::{parse_category, parse_filter};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const CONFIRM_TOOL: &str = "memory_erase";
+/// Seconds Root has to approve an erasure before it is dropped.
+const CONFIRM_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_PER_PAGE: usize = 50;
+const MAX_PER_PAGE: usize = 200;
+/// Characters of content shown per entry in the list.
+const PREVIEW_CHARS: usize = 200;
+/// Category of the vault's own index entries, which are not browsable.
+const VAULT_CATEGORY: &str = "vault";
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct BrowseQuery {
+    /// Text to look for in key or content
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// RFC 3339, `YYYY-MM-DD` or an age like `7d`
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub until: Option<String>,
+    #[serde(default)]
+    pub key_prefix: Option<String>,
+    /// Comma-separated; entries must carry all of them
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// 1-based (default: 1)
+    #[serde(default)]
+    pub page: Option<usize>,
+    #[serde(default)]
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryPage {
+    /// Entries matching the query and filter
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    /// Matches per category, ignoring the category filter
+    pub facets: BTreeMap<String, usize>,
+    pub entries: Vec<MemorySummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemorySummary {
+    pub key: String,
+    pub category: MemoryCategory,
+    pub timestamp: String,
+    /// Start of the content; the placeholder for vaulted entries
+    pub preview: String,
+    /// Content was moved to the vault and is not shown
+    pub vaulted: bool,
+    pub tags: Vec<String>,
+    pub ref_count: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryDetail {
+    #[serde(flatten)]
+    pub entry: MemoryEntry,
+    pub vaulted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemoryUpdate {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Replaces the entry's tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EraseResponse {
+    /// "pending_confirmation" or "nothing_found"
+    pub status: &'static str,
+    /// Entries matching now; the filter is applied again once approved
+    pub matches: usize,
+    pub summary: String,
+    /// ID to approve or deny via `/api/security/confirm`
+    pub confirmation_id: Option<String>,
+}
+
+impl From<MemoryEntry> for MemorySummary {
+    fn from(entry: MemoryEntry) -> Self {
+        let vaulted = is_vaulted(&entry);
+        let preview = if entry.content.chars().count() > PREVIEW_CHARS {
+            let cut: String = entry.content.chars().take(PREVIEW_CHARS).collect();
+            format!("{cut}…")
+        } else {
+            entry.content
+        };
+        Self {
+            key: entry.key,
+            category: entry.category,
+            timestamp: entry.timestamp,
+            preview,
+            vaulted,
+            tags: entry.tags,
+            ref_count: entry.ref_count,
+        }
+    }
+}
+
+fn is_vaulted(entry: &MemoryEntry) -> bool {
+    sovereign::is_vaulted(&entry.content)
+}
+
+fn is_vault_index(entry: &MemoryEntry) -> bool {
+    matches!(&entry.category, MemoryCategory::Custom(name) if name == VAULT_CATEGORY)
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Filter from the browse query, parsed like the `memory_recall` arguments.
+fn browse_filter(query: &BrowseQuery) -> anyhow::Result<RecallFilter> {
+    let tags: Vec<&str> = query
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .collect();
+    parse_filter(&serde_json::json!({
+        "category": query.category,
+        "since": query.since,
+        "until": query.until,
+        "key_prefix": query.key_prefix,
+        "tags": tags,
+    }))
+}
+
+/// Page `page` (1-based) of the entries matching `text` and `filter`, newest
+/// first, with per-category counts for everything but the category filter.
+fn browse(
+    mut entries: Vec<MemoryEntry>,
+    text: &str,
+    filter: &RecallFilter,
+    page: usize,
+    per_page: usize,
+) -> MemoryPage {
+    let uncategorized = RecallFilter {
+        category: None,
+        ..filter.clone()
+    };
+    entries.retain(|e| !is_vault_index(e) && erasure_matches(e, text, &uncategorized));
+
+    let mut facets = BTreeMap::new();
+    for entry in &entries {
+        *facets.entry(entry.category.to_string()).or_insert(0) += 1;
+    }
+    if let Some(category) = &filter.category {
+        entries.retain(|e| &e.category == category);
+    }
+    entries.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    MemoryPage {
+        total: entries.len(),
+        page,
+        per_page,
+        facets,
+        entries: entries
+            .into_iter()
+            .skip(page.saturating_sub(1).saturating_mul(per_page))
+            .take(per_page)
+            .map(MemorySummary::from)
+            .collect(),
+    }
+}
+
+/// Entry `key`, unless it is missing or one of the vault's index entries.
+async fn browsable_entry(state: &AppState, key: &str) -> Result<MemoryEntry, (StatusCode, String)> {
+    state
+        .mem
+        .get(key)
+        .await
+        .map_err(internal_error)?
+        .filter(|entry| !is_vault_index(entry))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No memory '{key}'")))
+}
+
+/// What an erasure request selects, for the confirmation and audit record.
+fn describe(query: &str, filter: &RecallFilter) -> String {
+    let mut parts = Vec::new();
+    if !query.is_empty() {
+        parts.push(format!("text \"{query}\""));
+    }
+    if let Some(category) = &filter.category {
+        parts.push(format!("category {category}"));
+    }
+    if let Some(since) = filter.created_after {
+        parts.push(format!("since {}", since.to_rfc3339()));
+    }
+    if let Some(until) = filter.created_before {
+        parts.push(format!("until {}", until.to_rfc3339()));
+    }
+    if let Some(prefix) = &filter.key_prefix {
+        parts.push(format!("keys starting with \"{prefix}\""));
+    }
+    if !filter.tags.is_empty() {
+        parts.push(format!("tags #{}", filter.tags.join(" #")));
+    }
+    if parts.is_empty() {
+        "all memories".into()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Signed record of an erasure. Erased entries are identified by a digest of
+/// their ids only, so the log keeps nothing of what was erased.
+fn record_erasure(
+    audit: &AuditLogger,
+    confirmation_id: &str,
+    summary: &str,
+    outcome: &anyhow::Result<Vec<MemoryEntry>>,
+) {
+    let event = AuditEvent::new(AuditEventType::DataErasure).with_actor(
+        "api".to_string(),
+        None,
+        Some("Root".to_string()),
+    );
+    let event = match outcome {
+        Ok(removed) => {
+            let mut ids: Vec<&str> = removed.iter().map(|e| e.id.as_str()).collect();
+            ids.sort_unstable();
+            let digest = hex::encode(Sha256::digest(ids.join("\n").as_bytes()));
+            event.with_action(
+                format!(
+                    "memory_erase {confirmation_id}: {} entries ({summary}); ids sha256:{digest}",
+                    removed.len()
+                ),
+                "high".to_string(),
+                true,
+                true,
+            )
+        }
+        Err(e) => event
+            .with_action(
+                format!("memory_erase {confirmation_id}: failed ({summary})"),
+                "high".to_string(),
+                true,
+                true,
+            )
+            .with_result(false, None, 0, Some(e.to_string())),
+    };
+    if let Err(e) = audit.log_signed(&event) {
+        tracing::error!("Failed to record memory erasure: {e}");
+    }
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/memory?q=...&category=...&page=1 — browse memories, newest first
+pub async fn list_memories(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<BrowseQuery>,
+) -> Result<Json<MemoryPage>, (StatusCode, String)> {
+    let filter = browse_filter(&query).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let text = query.q.as_deref().unwrap_or_default().trim();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+
+    let entries = state.mem.list(None).await.map_err(internal_error)?;
+    Ok(Json(browse(entries, text, &filter, page, per_page)))
+}
+
+/// GET /api/memory/{key} — one entry with tags and metadata
+pub async fn get_memory(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<Json<MemoryDetail>, (StatusCode, String)> {
+    let entry = browsable_entry(&state, &key).await?;
+    Ok(Json(MemoryDetail {
+        vaulted: is_vaulted(&entry),
+        entry,
+    }))
+}
+
+/// PATCH /api/memory/{key} — change content, category or tags. New content
+/// goes through the same redaction as any stored memory.
+pub async fn update_memory(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(update): Json<MemoryUpdate>,
+) -> Result<Json<MemoryDetail>, (StatusCode, String)> {
+    let entry = browsable_entry(&state, &key).await?;
+    if update.content.is_some() && is_vaulted(&entry) {
+        return Err((
+            StatusCode::CONFLICT,
+            "Vaulted content cannot be edited; delete the memory instead".into(),
+        ));
+    }
+    let content = match update.content {
+        Some(content) if content.trim().is_empty() => {
+            return Err((StatusCode::BAD_REQUEST, "content is empty".into()));
+        }
+        Some(content) => content,
+        None => entry.content,
+    };
+    let category = update
+        .category
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map_or(entry.category, parse_category);
+    if matches!(&category, MemoryCategory::Custom(name) if name == VAULT_CATEGORY) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "The vault category is reserved".into(),
+        ));
+    }
+    let tags = update.tags.map_or(entry.tags, |tags| normalize_tags(&tags));
+
+    state
+        .mem
+        .store_with_metadata(&key, &content, category, &tags, &entry.metadata)
+        .await
+        .map_err(internal_error)?;
+    let entry = browsable_entry(&state, &key).await?;
+    Ok(Json(MemoryDetail {
+        vaulted: is_vaulted(&entry),
+        entry,
+    }))
+}
+
+/// DELETE /api/memory/{key} — remove one entry, and its vaulted content
+pub async fn delete_memory(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    browsable_entry(&state, &key).await?;
+    state.mem.forget(&key).await.map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/memory — erase memories matching `query` (in key or content)
+/// and the filter fields of `memory_recall`; `"all": true` erases everything
+pub async fn erase_memories(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<EraseResponse>, (StatusCode, String)> {
+    let filter = parse_filter(&payload).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let query = payload
+        .get("query")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let all = payload
+        .get("all")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    if query.is_empty() && filter.is_empty() && !all {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Give a query or filter, or \"all\": true to erase every memory".into(),
+        ));
+    }
+
+    let matches = state
+        .mem
+        .list(filter.category.as_ref())
+        .await
+        .map_err(internal_error)?
+        .iter()
+        .filter(|entry| erasure_matches(entry, &query, &filter))
+        .count();
+    let summary = describe(&query, &filter);
+    if matches == 0 {
+        return Ok(Json(EraseResponse {
+            status: "nothing_found",
+            matches,
+            summary,
+            confirmation_id: None,
+        }));
+    }
+
+    let confirmation_id = uuid::Uuid::new_v4().to_string();
+    let request_id = confirmation_id.clone();
+    let description = format!("Erase {matches} memories: {summary}");
+    let policy = ConfirmationPolicy::with_default_timeout(
+        &state.config.read().await.security.confirmation,
+        CONFIRM_TOOL,
+        CONFIRM_TIMEOUT_SECS,
+    );
+    let gate = Arc::clone(&state.confirm_gate);
+    let memory = Arc::clone(&state.mem);
+    let audit = Arc::clone(&state.audit);
+    let record_summary = summary.clone();
+    tokio::spawn(async move {
+        let approved = gate
+            .request_with_id_and_policy(&request_id, CONFIRM_TOOL, &description, &policy)
+            .await;
+        if !approved {
+            tracing::info!("Memory erasure not approved: {record_summary}");
+            return;
+        }
+        let outcome = memory.forget_matching(&query, &filter).await;
+        match &outcome {
+            Ok(removed) => tracing::info!("Erased {} memories: {record_summary}", removed.len()),
+            Err(e) => tracing::error!("Memory erasure failed: {e}"),
+        }
+        record_erasure(&audit, &request_id, &record_summary, &outcome);
+    });
+
+    Ok(Json(EraseResponse {
+        status: "pending_confirmation",
+        matches,
+        summary,
+        confirmation_id: Some(confirmation_id),
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/memory", get(list_memories).delete(erase_memories))
+        .route(
+            "/api/memory/{key}",
+            get(get_memory).patch(update_memory).delete(delete_memory),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, content: &str, category: MemoryCategory, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+            tags: Vec::new(),
+            metadata: serde_json::Value::Null,
+            ref_count: 1,
+            access_count: 0,
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn browse_pages_newest_first_with_facets() {
+        let entries = vec![
+            entry(
+                "a",
+                "Likes tea",
+                MemoryCategory::Core,
+                "2026-01-01T00:00:00Z",
+            ),
+            entry(
+                "b",
+                "Tea at five",
+                MemoryCategory::Conversation,
+                "2026-01-03T00:00:00Z",
+            ),
+            entry(
+                "c",
+                "[VAULT: Bank PIN - Access Required] tea",
+                MemoryCategory::Conversation,
+                "2026-01-02T00:00:00Z",
+            ),
+            entry("d", "Coffee", MemoryCategory::Core, "2026-01-04T00:00:00Z"),
+            entry(
+                "vault:1",
+                "Vaulted content for c: tea",
+                MemoryCategory::Custom("vault".into()),
+                "2026-01-02T00:00:00Z",
+            ),
+        ];
+        let filter = RecallFilter {
+            category: Some(MemoryCategory::Conversation),
+            ..RecallFilter::default()
+        };
+
+        let page = browse(entries, "tea", &filter, 1, 1);
+
+        assert_eq!(page.total, 2);
+        assert_eq!(page.facets.get("core"), Some(&1));
+        assert_eq!(page.facets.get("conversation"), Some(&2));
+        assert!(!page.facets.contains_key("vault"));
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].key, "b");
+        assert!(!page.entries[0].vaulted);
+    }
+
+    #[test]
+    fn summary_truncates_long_content() {
+        let long = "x".repeat(PREVIEW_CHARS + 10);
+        let summary = MemorySummary::from(entry("k", &long, MemoryCategory::Core, ""));
+        assert_eq!(summary.preview.chars().count(), PREVIEW_CHARS + 1);
+        assert!(summary.preview.ends_with('…'));
+    }
+
+    #[test]
+    fn describe_lists_every_criterion() {
+        assert_eq!(describe("", &RecallFilter::default()), "all memories");
+        let filter = RecallFilter {
+            category: Some(MemoryCategory::Conversation),
+            key_prefix: Some("whatsapp_".into()),
+            tags: vec!["health".into()],
+            ..RecallFilter::default()
+        };
+        assert_eq!(
+            describe("Anna", &filter),
+            "text \"Anna\", category conversation, keys starting with \"whatsapp_\", tags #health"
+        );
+    }
+}
@@ -3,14 +3,39 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 pub mod admin;
+pub mod agent;
 pub mod auth;
+pub mod automations;
 pub mod browse;
+pub mod calendar;
+pub mod capabilities;
+pub mod capture;
+pub mod contacts;
+pub mod devices;
+pub mod diary;
+pub mod email;
+pub mod expenses;
 pub mod family;
+pub mod git_forge;
 pub mod handlers;
+pub mod identity;
+pub mod location;
 pub mod mcp;
+pub mod memory;
+pub mod ollama;
+pub mod onboarding;
+#[cfg(feature = "passkeys")]
+pub mod passkey;
 pub mod proxy;
+pub mod quotas;
 pub mod security;
+pub mod share;
+pub mod structured;
+pub mod tasks;
+pub mod totp;
 pub mod types;
+pub mod vault;
+pub mod voice;
 pub mod vpn;
 pub mod ws;
 
@@ -18,14 +43,40 @@ use crate::gateway::AppState;
 use axum::{routing::get, Router};
 
 pub fn routes() -> Router<AppState> {
-    Router::new()
+    let router = Router::new()
         .merge(handlers::router())
+        .merge(diary::router())
         .merge(vpn::router())
         .merge(admin::router())
         .merge(proxy::router())
         .merge(family::router())
+        .merge(quotas::router())
         .merge(mcp::router())
+        .merge(memory::router())
+        .merge(vault::router())
         .merge(security::router())
         .merge(browse::router())
-        .route("/ws/chat", get(ws::ws_handler))
+        .merge(share::router())
+        .merge(devices::router())
+        .merge(totp::router())
+        .merge(identity::router())
+        .merge(capabilities::router())
+        .merge(onboarding::router())
+        .merge(capture::router())
+        .merge(email::router())
+        .merge(git_forge::router())
+        .merge(tasks::router())
+        .merge(calendar::router())
+        .merge(contacts::router())
+        .merge(expenses::router())
+        .merge(location::router())
+        .merge(agent::router())
+        .merge(voice::router())
+        .merge(automations::router())
+        .merge(ollama::router())
+        .merge(structured::router())
+        .route("/ws/chat", get(ws::ws_handler));
+    #[cfg(feature = "passkeys")]
+    let router = router.merge(passkey::router());
+    router
 }
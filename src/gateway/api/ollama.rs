@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Ollama API — check the local model server and pull models for offline
+//! use.
+//!
+//! Root-only: a pull downloads gigabytes onto the host. Installed models
+//! are also listed by `GET /api/config/models` while Ollama is the default
+//! provider.
+
+use axum::{
+    body::Body,
+    extract::{Json, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::api::types::ModelInfo;
+use crate::providers::ollama::{LocalModel, OllamaProvider, PullProgress};
+use crate::security::{AuditEvent, AuditEventType};
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct OllamaStatus {
+    pub base_url: String,
+    pub running: bool,
+    /// Server version while running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Why the server could not be reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub models: Vec<LocalModel>,
+    /// Whether Ollama is the default provider
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    /// e.g. "llama3.1:8b"
+    pub model: String,
+}
+
+/// Models installed on the Ollama server, for the model picker. Empty when
+/// the server is not running.
+pub async fn installed_models() -> Vec<ModelInfo> {
+    match OllamaProvider::from_env().list_models().await {
+        Ok(models) => models
+            .into_iter()
+            .map(|model| ModelInfo {
+                id: model.name.clone(),
+                provider: "ollama".into(),
+                description: format!("Local · {}", model.summary()),
+                name: model.name,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::debug!("Ollama models unavailable: {e}");
+            Vec::new()
+        }
+    }
+}
+
+fn ndjson_line(progress: &PullProgress) -> Vec<u8> {
+    let mut line = serde_json::to_vec(progress).unwrap_or_default();
+    line.push(b'\n');
+    line
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/ollama — whether the server runs, and its installed models
+pub async fn get_status(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<OllamaStatus> {
+    let active = state.config.read().await.default_provider.as_deref() == Some("ollama");
+    let ollama = OllamaProvider::from_env();
+    let mut status = OllamaStatus {
+        base_url: ollama.base_url().to_string(),
+        running: false,
+        version: None,
+        error: None,
+        models: Vec::new(),
+        active,
+    };
+    match ollama.version().await {
+        Ok(version) => {
+            status.running = true;
+            status.version = Some(version);
+            match ollama.list_models().await {
+                Ok(models) => status.models = models,
+                Err(e) => status.error = Some(e.to_string()),
+            }
+        }
+        Err(e) => status.error = Some(e.to_string()),
+    }
+    Json(status)
+}
+
+/// POST /api/ollama/pull — download a model. The response streams one JSON
+/// progress line at a time (`{"status","total","completed"}`) and ends with
+/// `{"status":"success"}`, or `{"status":"error","error":...}` on failure.
+pub async fn pull_model(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<PullRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let model = payload.model.trim().to_string();
+    if model.is_empty() || model.chars().any(char::is_whitespace) {
+        return Err((StatusCode::BAD_REQUEST, "Invalid model name".into()));
+    }
+    let ollama = OllamaProvider::from_env();
+    ollama
+        .version()
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    let action = format!("ollama:pull:{model}");
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("ollama".to_string(), None, Some("Root".to_string()))
+            .with_action(action, "low".to_string(), true, true),
+    );
+
+    // The pull carries on if the client goes away; Ollama resumes it anyway
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+    tokio::spawn(async move {
+        let progress_tx = tx.clone();
+        let result = ollama
+            .pull(&model, |progress| {
+                let _ = progress_tx.send(ndjson_line(&progress));
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Ollama pull of {model} failed: {e}");
+            let _ = tx.send(ndjson_line(&PullProgress {
+                status: "error".into(),
+                error: Some(e.to_string()),
+                ..PullProgress::default()
+            }));
+        } else {
+            tracing::info!("Ollama pulled {model}");
+        }
+    });
+
+    let lines = futures::stream::unfold(rx, |mut rx| async move {
+        let line = rx.recv().await?;
+        Some((Ok::<_, std::convert::Infallible>(line), rx))
+    });
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/ollama", get(get_status))
+        .route("/api/ollama/pull", post(pull_model))
+}
@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Onboarding API — first-run workspace setup from the dashboard.
+//!
+//! `GET` describes the flow (steps and presets), `preview` renders the
+//! SOUL.md a profile would produce, and `complete` scaffolds the workspace,
+//! registers household members and schedules starter jobs. Root-only.
+
+use axum::{
+    extract::{State, Json},
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::onboard::templates::{self, HouseholdPreset, OnboardingProfile, ScaffoldReport};
+use serde::Serialize;
+
+const MEMBER_ROLES: [&str; 3] = ["adult", "senior", "child"];
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingStatus {
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    pub steps: Vec<OnboardingStep>,
+    pub presets: Vec<PresetView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingStep {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// Profile fields answered on this step.
+    pub fields: &'static [&'static str],
+}
+
+#[derive(Debug, Serialize)]
+pub struct PresetView {
+    pub id: HouseholdPreset,
+    pub label: &'static str,
+    pub description: &'static str,
+    pub suggested_roles: &'static [&'static str],
+    pub starter_jobs: Vec<StarterJobView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StarterJobView {
+    pub expression: &'static str,
+    pub command: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingPreview {
+    pub soul: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingResult {
+    pub files: ScaffoldReport,
+    pub members_added: Vec<String>,
+    pub jobs_scheduled: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+const STEPS: [OnboardingStep; 3] = [
+    OnboardingStep {
+        id: "about",
+        title: "About you",
+        fields: &["user_name", "agent_name", "timezone", "language"],
+    },
+    OnboardingStep {
+        id: "household",
+        title: "Who uses MyMolt?",
+        fields: &["preset", "members"],
+    },
+    OnboardingStep {
+        id: "extras",
+        title: "Get started faster",
+        fields: &["example_skills", "starter_jobs"],
+    },
+];
+
+fn validate(profile: &OnboardingProfile) -> Result<(), (StatusCode, String)> {
+    for member in &profile.members {
+        if member.name.trim().is_empty() || member.name.len() > 64 {
+            return Err((StatusCode::BAD_REQUEST, "Member names must be 1–64 characters".into()));
+        }
+        if !MEMBER_ROLES.contains(&member.role.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Invalid role '{}' (expected adult, senior or child)", member.role),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/onboarding — completion status and the flow definition
+pub async fn get_onboarding(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<OnboardingStatus> {
+    let done = templates::load_state(&state.workspace_dir);
+    Json(OnboardingStatus {
+        completed: done.is_some(),
+        completed_at: done.map(|s| s.completed_at.to_rfc3339()),
+        steps: STEPS.into(),
+        presets: HouseholdPreset::ALL
+            .into_iter()
+            .map(|p| PresetView {
+                id: p,
+                label: p.label(),
+                description: p.description(),
+                suggested_roles: p.suggested_roles(),
+                starter_jobs: templates::starter_jobs(p)
+                    .into_iter()
+                    .map(|(expression, command)| StarterJobView { expression, command })
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+/// POST /api/onboarding/preview — render the SOUL.md for a profile without writing it
+pub async fn preview_onboarding(
+    _user: AuthenticatedUser,
+    Json(profile): Json<OnboardingProfile>,
+) -> Result<Json<OnboardingPreview>, (StatusCode, String)> {
+    validate(&profile)?;
+    Ok(Json(OnboardingPreview {
+        soul: templates::soul_skeleton(&profile),
+    }))
+}
+
+/// POST /api/onboarding/complete — scaffold the workspace and finish onboarding
+pub async fn complete_onboarding(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(profile): Json<OnboardingProfile>,
+) -> Result<Json<OnboardingResult>, (StatusCode, String)> {
+    validate(&profile)?;
+    if templates::load_state(&state.workspace_dir).is_some() {
+        return Err((StatusCode::CONFLICT, "Onboarding is already complete".into()));
+    }
+
+    let files = templates::scaffold(&state.workspace_dir, &profile)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Scaffolding failed: {e}")))?;
+    {
+        let mut soul = state.soul.lock().await;
+        if let Err(e) = soul.load() {
+            tracing::warn!("Failed to reload SOUL.md after onboarding: {e}");
+        }
+    }
+
+    let mut members_added = Vec::new();
+    let mut jobs_scheduled = Vec::new();
+    let mut warnings = Vec::new();
+    {
+        let mut config = state.config.write().await;
+
+        for member in &profile.members {
+            let name = member.name.trim();
+            if config.family.members.len() >= config.family.max_members {
+                warnings.push(format!("Skipped '{name}': household is full"));
+                continue;
+            }
+            if config.family.members.iter().any(|m| m.name.eq_ignore_ascii_case(name)) {
+                warnings.push(format!("Skipped '{name}': already a member"));
+                continue;
+            }
+            config.family.members.push(crate::config::schema::FamilyMemberConfig {
+                name: name.to_string(),
+                role: member.role.clone(),
+                channels: std::collections::HashMap::new(),
+                pin_hash: None,
+                oidc: None,
+                quotas: None,
+            });
+            members_added.push(name.to_string());
+        }
+        if !members_added.is_empty() {
+            config
+                .save()
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save: {e}")))?;
+        }
+
+        if profile.starter_jobs {
+            for (expression, command) in templates::starter_jobs(profile.preset) {
+                match crate::cron::add_job(&config, expression, command) {
+                    Ok(job) => jobs_scheduled.push(job.id),
+                    Err(e) => warnings.push(format!("Could not schedule '{command}': {e}")),
+                }
+            }
+            if !jobs_scheduled.is_empty()
+                && !config.autonomy.allowed_commands.iter().any(|c| c == "mymolt")
+            {
+                warnings.push(
+                    "Starter jobs run `mymolt`; add it to autonomy.allowed_commands to enable them"
+                        .into(),
+                );
+            }
+        }
+    }
+
+    templates::mark_complete(&state.workspace_dir, profile.preset)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(OnboardingResult {
+        files,
+        members_added,
+        jobs_scheduled,
+        warnings,
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/onboarding", get(get_onboarding))
+        .route("/api/onboarding/preview", post(preview_onboarding))
+        .route("/api/onboarding/complete", post(complete_onboarding))
+}
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Passkeys for Root (`/api/identity/passkeys`, `/api/auth/passkey`).
+//!
+//! `POST /api/identity/passkeys` starts a registration and returns the
+//! options for `navigator.credentials.create()`; `.../finish` keeps the
+//! passkey and binds it in SOUL.md at `TrustLevel::High`. Registering and
+//! removing need a second factor once one exists.
+//!
+//! `POST /api/auth/passkey/step-up` and `.../finish` run an assertion and
+//! return a single-use token for the `X-Passkey-Step-Up` header, which
+//! Root actions accept instead of a TOTP code.
+
+use crate::gateway::api::auth::{record_auth_failure, require_step_up, AuthenticatedUser};
+use crate::gateway::{AppState, ClientKey};
+use crate::identity::passkey::{PasskeyInfo, BINDING_PROVIDER, STEP_UP_TTL};
+use crate::identity::soul::TrustLevel;
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse,
+};
+
+const MAX_LABEL_LEN: usize = 64;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterPasskeyRequest {
+    /// Name shown in the passkey list, e.g. "YubiKey 5C"
+    pub label: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegistrationChallenge {
+    pub ceremony: String,
+    /// Pass to `navigator.credentials.create()`
+    pub options: CreationChallengeResponse,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishRegistrationRequest {
+    pub ceremony: String,
+    pub credential: RegisterPublicKeyCredential,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepUpChallenge {
+    pub ceremony: String,
+    /// Pass to `navigator.credentials.get()`
+    pub options: RequestChallengeResponse,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishStepUpRequest {
+    pub ceremony: String,
+    pub credential: PublicKeyCredential,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepUpToken {
+    /// Single-use value for the `X-Passkey-Step-Up` header
+    pub token: String,
+    pub expires_in: u64,
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn unavailable(e: &anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::SERVICE_UNAVAILABLE, format!("{e:#}"))
+}
+
+fn audit(state: &AppState, event: AuditEventType, action: String) {
+    let _ = state.audit.log(
+        &AuditEvent::new(event)
+            .with_actor("gateway".to_string(), None, None)
+            .with_action(action, "high".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/identity/passkeys — registered passkeys
+pub async fn list_passkeys(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<Vec<PasskeyInfo>> {
+    Json(state.passkeys.list())
+}
+
+/// POST /api/identity/passkeys — start registering a passkey
+pub async fn begin_registration(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
+    Json(payload): Json<RegisterPasskeyRequest>,
+) -> Result<Json<RegistrationChallenge>, (StatusCode, String)> {
+    require_step_up(&state, &headers, &client)?;
+    let label = payload.label.trim();
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Passkey label must be 1-{MAX_LABEL_LEN} characters"),
+        ));
+    }
+    let (ceremony, options) = state
+        .passkeys
+        .start_registration(label)
+        .map_err(|e| unavailable(&e))?;
+    Ok(Json(RegistrationChallenge { ceremony, options }))
+}
+
+/// POST /api/identity/passkeys/finish — keep the passkey and bind it in SOUL.md
+pub async fn finish_registration(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<FinishRegistrationRequest>,
+) -> Result<Json<PasskeyInfo>, (StatusCode, String)> {
+    let info = state
+        .passkeys
+        .finish_registration(&payload.ceremony, &payload.credential)
+        .map_err(internal_error)?
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "Passkey could not be verified, or the registration expired".to_string(),
+        ))?;
+    state
+        .soul
+        .lock()
+        .await
+        .add_binding(BINDING_PROVIDER, &info.id, TrustLevel::High)
+        .map_err(internal_error)?;
+    tracing::info!("🔑 Passkey '{}' registered for Root", info.label);
+    audit(
+        &state,
+        AuditEventType::ConfigChange,
+        format!("passkey:register:{}", info.id),
+    );
+    Ok(Json(info))
+}
+
+/// DELETE /api/identity/passkeys/{id} — remove a passkey and its binding
+pub async fn remove_passkey(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_step_up(&state, &headers, &client)?;
+    if !state.passkeys.remove(&id).map_err(internal_error)? {
+        return Err((StatusCode::NOT_FOUND, format!("Passkey '{id}' not found")));
+    }
+    state
+        .soul
+        .lock()
+        .await
+        .remove_binding(BINDING_PROVIDER, &id)
+        .map_err(internal_error)?;
+    tracing::warn!("🔑 Passkey {id} removed for Root");
+    audit(
+        &state,
+        AuditEventType::ConfigChange,
+        format!("passkey:remove:{id}"),
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/auth/passkey/step-up — start a passkey assertion
+pub async fn begin_step_up(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<StepUpChallenge>, (StatusCode, String)> {
+    if !state.passkeys.is_registered() {
+        return Err((StatusCode::NOT_FOUND, "No passkey is registered".into()));
+    }
+    let (ceremony, options) = state
+        .passkeys
+        .start_authentication()
+        .map_err(|e| unavailable(&e))?;
+    Ok(Json(StepUpChallenge { ceremony, options }))
+}
+
+/// POST /api/auth/passkey/step-up/finish — verify the assertion and return
+/// a step-up token
+pub async fn finish_step_up(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    Json(payload): Json<FinishStepUpRequest>,
+) -> Result<Json<StepUpToken>, (StatusCode, String)> {
+    match state
+        .passkeys
+        .finish_authentication(&payload.ceremony, &payload.credential)
+    {
+        Ok(Some(token)) => {
+            audit(
+                &state,
+                AuditEventType::AuthSuccess,
+                format!("passkey:step_up ({client})"),
+            );
+            Ok(Json(StepUpToken {
+                token,
+                expires_in: STEP_UP_TTL.as_secs(),
+            }))
+        }
+        Ok(None) => {
+            record_auth_failure(&state, &client);
+            Err((
+                StatusCode::UNAUTHORIZED,
+                "Passkey assertion could not be verified, or it expired".into(),
+            ))
+        }
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/identity/passkeys",
+            get(list_passkeys).post(begin_registration),
+        )
+        .route("/api/identity/passkeys/finish", post(finish_registration))
+        .route("/api/identity/passkeys/{id}", delete(remove_passkey))
+        .route("/api/auth/passkey/step-up", post(begin_step_up))
+        .route("/api/auth/passkey/step-up/finish", post(finish_step_up))
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Parental controls — family members' daily usage and extensions.
+//!
+//! `GET /api/quotas` and extensions require Adult. A member's own session
+//! reads its usage from `GET /api/quotas/me`.
+
+use crate::config::FamilyMemberConfig;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::identity::family::FamilyMember;
+use crate::identity::UserRole;
+use crate::security::quotas::{limits_for, Quota, QuotaUsage};
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct MemberUsageView {
+    pub member: String,
+    pub role: UserRole,
+    /// Today's usage of each limited quota; empty when unlimited
+    pub quotas: Vec<QuotaUsage>,
+}
+
+/// `{ "quota": "chat_turns", "amount": 10 }`, or for a tool
+/// `{ "quota": "tool", "tool": "web_search", "amount": 5 }`.
+#[derive(Debug, Deserialize)]
+pub struct ExtendQuotaRequest {
+    #[serde(flatten)]
+    pub quota: Quota,
+    pub amount: u32,
+}
+
+fn usage_view(
+    state: &AppState,
+    family: &crate::config::FamilyConfig,
+    config: &FamilyMemberConfig,
+) -> MemberUsageView {
+    let member = FamilyMember::from_config(config);
+    let limits = limits_for(family, &member.name, member.role);
+    MemberUsageView {
+        quotas: state.quotas.usage(&member.name, &limits),
+        member: member.name,
+        role: member.role,
+    }
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/quotas — today's usage of every family member
+pub async fn list_quotas(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<Vec<MemberUsageView>> {
+    let config = state.config.read().await;
+    Json(
+        config
+            .family
+            .members
+            .iter()
+            .map(|m| usage_view(&state, &config.family, m))
+            .collect(),
+    )
+}
+
+/// GET /api/quotas/me — the calling member's own usage
+pub async fn my_quotas(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<MemberUsageView>, (StatusCode, String)> {
+    let config = state.config.read().await;
+    user.member
+        .as_deref()
+        .and_then(|name| {
+            config
+                .family
+                .members
+                .iter()
+                .find(|m| m.name.eq_ignore_ascii_case(name))
+        })
+        .map(|m| Json(usage_view(&state, &config.family, m)))
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            "Not signed in as a family member".into(),
+        ))
+}
+
+/// POST /api/quotas/{member}/extend — grant a member extra usage for today
+pub async fn extend_quota(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<ExtendQuotaRequest>,
+) -> Result<Json<MemberUsageView>, (StatusCode, String)> {
+    if payload.amount == 0 {
+        return Err((StatusCode::BAD_REQUEST, "Amount must be at least 1".into()));
+    }
+    let config = state.config.read().await;
+    let member = config
+        .family
+        .members
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Family member '{name}' not found"),
+            )
+        })?;
+
+    tracing::info!(
+        "⏳ {} granted {} {} more {} today",
+        user.actor(),
+        member.name,
+        payload.amount,
+        payload.quota
+    );
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("gateway".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!(
+                    "quota:extend:{}:+{} {}",
+                    member.name, payload.amount, payload.quota
+                ),
+                "low".to_string(),
+                true,
+                true,
+            ),
+    );
+    state
+        .quotas
+        .extend(&member.name, payload.quota, payload.amount);
+    Ok(Json(usage_view(&state, &config.family, member)))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/quotas", get(list_quotas))
+        .route("/api/quotas/me", get(my_quotas))
+        .route("/api/quotas/{member}/extend", post(extend_quota))
+}
@@ -13,7 +13,8 @@ use axum::{
 };
 use crate::gateway::AppState;
 use crate::gateway::api::auth::AuthenticatedUser;
-use crate::identity::UserRole;
+use crate::security::audit::AuditVerification;
+use crate::security::route_policy::RouteRule;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -47,13 +48,9 @@ pub struct SecurityOverview {
 
 /// GET /api/security/overview — aggregated security stats
 pub async fn get_security_overview(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> Result<Json<SecurityOverview>, StatusCode> {
-    if user.role != UserRole::Root && user.role != UserRole::Adult {
-        return Err(StatusCode::FORBIDDEN);
-    }
-
     let config = state.config.read().await;
 
     // Gather stats from various subsystems
@@ -87,7 +84,34 @@ pub async fn get_security_overview(
     Ok(Json(overview))
 }
 
+/// GET /api/security/audit/verify — check the audit log's hash chain and signatures
+pub async fn verify_audit_log(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<AuditVerification>, StatusCode> {
+    let audit = state.audit.clone();
+    let report = tokio::task::spawn_blocking(move || audit.verify_log())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            tracing::error!("Audit log verification failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(report))
+}
+
+/// GET /api/security/policy/routes — the effective role-to-route matrix,
+/// in the order rules are checked
+pub async fn get_route_policy(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Json<Vec<RouteRule>> {
+    Json(state.route_policy.rules().to_vec())
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/security/overview", get(get_security_overview))
+        .route("/api/security/audit/verify", get(verify_audit_log))
+        .route("/api/security/policy/routes", get(get_route_policy))
 }
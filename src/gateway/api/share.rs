@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Guest share links — owners issue expiring tokens, guests ask questions.
+//!
+//! Owner endpoints (`/api/share`) require Adult or Root; members see and
+//! revoke only the links they issued, Root sees all. Guest endpoints
+//! (`/api/guest/*`) accept only share tokens and never run tools; answers
+//! draw only on the link's collection, as the member who issued it sees it.
+
+use crate::gateway::api::auth::{AuthenticatedUser, GuestUser};
+use crate::gateway::AppState;
+use crate::identity::family::member_scope;
+use crate::identity::UserRole;
+use crate::memory::{scoped, MemoryCategory, RecallFilter};
+use crate::providers::router::TaskClass;
+use crate::security::share::{ShareGrant, ShareScope, DEFAULT_SHARE_TTL_SECS};
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+/// Memories pulled into a guest answer.
+const GUEST_RECALL_LIMIT: usize = 5;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct CreateShareRequest {
+    pub label: String,
+    /// Memory category the guest may query; omit for no memory access.
+    pub collection: Option<String>,
+    pub ttl_secs: Option<u64>,
+    pub max_uses: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateShareResponse {
+    pub grant: ShareGrantView,
+    /// Plaintext token — shown once, never retrievable again.
+    pub token: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareGrantView {
+    pub id: String,
+    pub label: String,
+    pub scope: ShareScope,
+    pub created_at: String,
+    pub expires_at: String,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub active: bool,
+}
+
+impl From<&ShareGrant> for ShareGrantView {
+    fn from(g: &ShareGrant) -> Self {
+        Self {
+            id: g.id.clone(),
+            label: g.label.clone(),
+            scope: g.scope.clone(),
+            created_at: g.created_at.to_rfc3339(),
+            expires_at: g.expires_at.to_rfc3339(),
+            max_uses: g.max_uses,
+            uses: g.uses,
+            active: g.is_active(chrono::Utc::now()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GuestAskRequest {
+    pub question: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GuestAskResponse {
+    pub answer: String,
+    pub expires_at: String,
+}
+
+// ── Owner handlers ─────────────────────────────────────────────────
+
+/// Whether `user` may see and revoke `grant`: Root any link, everyone else
+/// only the links they issued.
+fn may_manage(user: &AuthenticatedUser, grant: &ShareGrant) -> bool {
+    user.role == UserRole::Root || grant.memory_scope == user.member.as_deref().map(member_scope)
+}
+
+/// POST /api/share — issue a guest share link
+pub async fn create_share(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateShareRequest>,
+) -> Result<Json<CreateShareResponse>, (StatusCode, String)> {
+    let label = payload.label.trim();
+    if label.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "A label is required".into()));
+    }
+    let collection = payload
+        .collection
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+
+    let (token, grant) = state
+        .share_tokens
+        .create(
+            label,
+            ShareScope::Ask { collection },
+            user.member.as_deref().map(member_scope),
+            payload.ttl_secs.unwrap_or(DEFAULT_SHARE_TTL_SECS),
+            payload.max_uses,
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("gateway".to_string(), None, Some(user.actor()))
+            .with_action(
+                format!("share:create:{}", grant.id),
+                "medium".to_string(),
+                true,
+                true,
+            ),
+    );
+
+    Ok(Json(CreateShareResponse {
+        url: format!(
+            "{}/guest#token={token}",
+            state.public_url.trim_end_matches('/')
+        ),
+        grant: ShareGrantView::from(&grant),
+        token,
+    }))
+}
+
+/// GET /api/share — list the share links the caller may manage
+pub async fn list_shares(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ShareGrantView>>, (StatusCode, String)> {
+    Ok(Json(
+        state
+            .share_tokens
+            .list()
+            .iter()
+            .filter(|grant| may_manage(&user, grant))
+            .map(ShareGrantView::from)
+            .collect(),
+    ))
+}
+
+/// DELETE /api/share/{id} — revoke a share link immediately
+pub async fn revoke_share(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    // Links issued by someone else look the same as unknown ones
+    let issued = state
+        .share_tokens
+        .list()
+        .iter()
+        .any(|grant| grant.id == id && may_manage(&user, grant));
+    if !issued {
+        return Err((StatusCode::NOT_FOUND, "Share link not found".into()));
+    }
+    match state.share_tokens.revoke(&id) {
+        Ok(true) => {
+            let _ = state.audit.log(
+                &AuditEvent::new(AuditEventType::ConfigChange)
+                    .with_actor("gateway".to_string(), None, Some(user.actor()))
+                    .with_action(format!("share:revoke:{id}"), "low".to_string(), true, true),
+            );
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err((StatusCode::NOT_FOUND, "Share link not found".into())),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// ── Guest handlers ─────────────────────────────────────────────────
+
+/// GET /api/guest/info — what this share link allows
+pub async fn guest_info(guest: GuestUser) -> Json<ShareGrantView> {
+    Json(ShareGrantView::from(&guest.grant))
+}
+
+/// POST /api/guest/ask — answer a guest question within the link's scope
+pub async fn guest_ask(
+    guest: GuestUser,
+    State(state): State<AppState>,
+    Json(payload): Json<GuestAskRequest>,
+) -> Result<Json<GuestAskResponse>, (StatusCode, String)> {
+    let question = payload.question.trim();
+    if question.is_empty() || question.len() > 2000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Question must be 1–2000 characters".into(),
+        ));
+    }
+    if !state.rate_limiter.allow_guest(&guest.grant.id) {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many questions. Please wait.".into(),
+        ));
+    }
+    let grant = state.share_tokens.redeem(&guest.token).ok_or((
+        StatusCode::UNAUTHORIZED,
+        "Share link is invalid or has expired".into(),
+    ))?;
+
+    let ShareScope::Ask { collection } = &grant.scope;
+    let context = match collection {
+        Some(name) => {
+            // Only the collection, and only as the issuer sees it
+            let filter = RecallFilter {
+                category: Some(MemoryCategory::Custom(name.clone())),
+                ..RecallFilter::default()
+            };
+            let entries = scoped::confine(&state.mem, grant.memory_scope.clone())
+                .recall_filtered(question, GUEST_RECALL_LIMIT, &filter)
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Recall failed: {e}"),
+                    )
+                })?;
+            entries
+                .into_iter()
+                .map(|e| format!("- {}", e.content))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        None => String::new(),
+    };
+
+    let system_prompt = format!(
+        "You are answering a guest on behalf of your owner via a temporary share link \
+         (\"{}\"). You have no tools. Never reveal private information about your owner \
+         beyond the context below. If the context does not contain the answer, say so.\n\n\
+         Context:\n{}",
+        grant.label,
+        if context.is_empty() {
+            "(none)"
+        } else {
+            &context
+        },
+    );
+
+    let model = state.models.model_for(TaskClass::Chat);
+    let temperature = *state.temperature.read().await;
+    let response = state
+        .provider
+        .chat_with_system(Some(&system_prompt), question, &model, temperature)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Agent error: {e}"),
+            )
+        })?;
+
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::AuthSuccess)
+            .with_actor(
+                "guest".to_string(),
+                Some(grant.id.clone()),
+                Some(grant.label.clone()),
+            )
+            .with_action("guest:ask".to_string(), "low".to_string(), true, true),
+    );
+
+    Ok(Json(GuestAskResponse {
+        answer: response.text.unwrap_or_default(),
+        expires_at: grant.expires_at.to_rfc3339(),
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/share", post(create_share).get(list_shares))
+        .route("/api/share/{id}", delete(revoke_share))
+        .route("/api/guest/info", get(guest_info))
+        .route("/api/guest/ask", post(guest_ask))
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Structured answers — JSON matching a caller's schema, for integrations
+//! that need machine-parseable output instead of chat text.
+//!
+//! Adult or Root. No tools run; the answer is checked against the schema
+//! and repaired by the model when it does not match (see
+//! [`crate::providers::structured`]).
+
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::gateway::AppState;
+use crate::providers::router::TaskClass;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+/// Longest prompt accepted, in characters.
+const MAX_PROMPT_CHARS: usize = 100_000;
+/// Largest schema accepted, serialized.
+const MAX_SCHEMA_BYTES: usize = 16 * 1024;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct StructuredRequest {
+    /// The question or extraction task, with any input text
+    pub prompt: String,
+    /// JSON schema the answer must match, e.g. `{"type":"object",...}`
+    pub schema: serde_json::Value,
+    /// Defaults to the gateway temperature
+    pub temperature: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StructuredResponse {
+    /// The answer, valid against the request schema
+    pub data: serde_json::Value,
+    pub model: String,
+}
+
+fn check_request(payload: &StructuredRequest) -> Result<(), (StatusCode, String)> {
+    let prompt_chars = payload.prompt.chars().count();
+    if payload.prompt.trim().is_empty() || prompt_chars > MAX_PROMPT_CHARS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("prompt must be 1–{MAX_PROMPT_CHARS} characters"),
+        ));
+    }
+    if !payload.schema.is_object() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "schema must be a JSON object".into(),
+        ));
+    }
+    if payload.schema.to_string().len() > MAX_SCHEMA_BYTES {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("schema is larger than {MAX_SCHEMA_BYTES} bytes"),
+        ));
+    }
+    if payload
+        .temperature
+        .is_some_and(|t| !(0.0..=2.0).contains(&t))
+    {
+        return Err((StatusCode::BAD_REQUEST, "temperature must be 0–2".into()));
+    }
+    Ok(())
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// POST /api/structured — answer `prompt` as JSON matching `schema`
+pub async fn structured_answer(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<StructuredRequest>,
+) -> Result<Json<StructuredResponse>, (StatusCode, String)> {
+    check_request(&payload)?;
+    let model = state.models.model_for(TaskClass::Chat);
+    let temperature = match payload.temperature {
+        Some(temperature) => temperature,
+        None => *state.temperature.read().await,
+    };
+
+    state
+        .provider
+        .chat_structured(None, &payload.prompt, &payload.schema, &model, temperature)
+        .await
+        .map(|data| Json(StructuredResponse { data, model }))
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Structured answer failed: {e}"),
+            )
+        })
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/structured", post(structured_answer))
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Tasks API — the PIM to-do list for the dashboard task widget.
+//!
+//! Read-only: tasks are added and completed through the `task_*` tools, which
+//! keep the store in memory and would overwrite edits made here.
+
+use axum::{
+    extract::{Json, Query, State},
+    http::StatusCode,
+    routing::get,
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::security::secrets::SecretStore;
+use crate::tools::pim::{self, Task, TaskStatus};
+use serde::{Deserialize, Serialize};
+
+const MAX_LISTED: usize = 200;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct TaskQuery {
+    /// open (default), overdue, done or all
+    #[serde(default)]
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskListResponse {
+    /// `YYYY-MM-DD` the overdue flags were computed for
+    pub today: String,
+    /// Open tasks past their due date, regardless of the filter
+    pub overdue: usize,
+    pub tasks: Vec<TaskView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskView {
+    #[serde(flatten)]
+    pub task: Task,
+    pub overdue: bool,
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/pim/tasks?status=open&tag=... — tasks for the widget, most urgent first
+pub async fn list_tasks(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(query): Query<TaskQuery>,
+) -> Result<Json<TaskListResponse>, (StatusCode, String)> {
+    let secrets = Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true));
+    let store = pim::load_store(&state.workspace_dir, &secrets);
+    let today = chrono::Local::now().date_naive();
+
+    let overdue = store.tasks.iter().filter(|t| t.is_overdue(today)).count();
+    let tasks = pim::query_tasks(&store.tasks, query.status, query.tag.as_deref(), today)
+        .into_iter()
+        .take(MAX_LISTED)
+        .map(|task| TaskView {
+            overdue: task.is_overdue(today),
+            task,
+        })
+        .collect();
+    Ok(Json(TaskListResponse {
+        today: today.format("%Y-%m-%d").to_string(),
+        overdue,
+        tasks,
+    }))
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/pim/tasks", get(list_tasks))
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! TOTP enrollment for Root (`/api/auth/totp`).
+//!
+//! `POST /api/auth/totp` starts an enrollment and returns the secret with a
+//! QR code; `POST /api/auth/totp/confirm` activates it with a first code and
+//! returns the recovery codes. Disabling needs a current code.
+
+use crate::gateway::api::auth::{require_step_up, AuthenticatedUser};
+use crate::gateway::{AppState, ClientKey};
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+/// Account name shown in authenticator apps.
+const TOTP_ACCOUNT: &str = "root";
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct TotpStatus {
+    pub enrolled: bool,
+    pub recovery_codes_left: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollment {
+    /// Base32 secret for manual entry — shown once.
+    pub secret: String,
+    pub uri: String,
+    /// SVG QR code of `uri`
+    pub qr_svg: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfirmTotpResponse {
+    pub enrolled: bool,
+    /// Single-use recovery codes — shown once.
+    pub recovery_codes: Vec<String>,
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn audit(state: &AppState, action: &str) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::ConfigChange)
+            .with_actor("gateway".to_string(), None, None)
+            .with_action(action.to_string(), "high".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/auth/totp — whether TOTP is enrolled
+pub async fn get_totp_status(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<TotpStatus>, (StatusCode, String)> {
+    Ok(Json(TotpStatus {
+        enrolled: state.totp.is_enrolled(),
+        recovery_codes_left: state.totp.recovery_codes_left().map_err(internal_error)?,
+    }))
+}
+
+/// POST /api/auth/totp — start enrolling an authenticator app
+pub async fn begin_totp_enrollment(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<TotpEnrollment>, (StatusCode, String)> {
+    if state.totp.is_enrolled() {
+        return Err((
+            StatusCode::CONFLICT,
+            "TOTP is already enrolled; disable it before enrolling again".into(),
+        ));
+    }
+    let enrollment = state
+        .totp
+        .begin_enrollment(TOTP_ACCOUNT)
+        .map_err(internal_error)?;
+    let qr_svg = qrcode::QrCode::new(enrollment.uri.as_bytes())
+        .map_err(internal_error)?
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+    Ok(Json(TotpEnrollment {
+        secret: enrollment.secret,
+        uri: enrollment.uri,
+        qr_svg,
+    }))
+}
+
+/// POST /api/auth/totp/confirm — activate the enrollment with a first code
+pub async fn confirm_totp_enrollment(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<ConfirmTotpRequest>,
+) -> Result<Json<ConfirmTotpResponse>, (StatusCode, String)> {
+    match state.totp.confirm_enrollment(&payload.code) {
+        Ok(Some(recovery_codes)) => {
+            tracing::info!("🔐 TOTP enrolled for Root");
+            audit(&state, "totp:enroll");
+            Ok(Json(ConfirmTotpResponse {
+                enrolled: true,
+                recovery_codes,
+            }))
+        }
+        Ok(None) => Err((
+            StatusCode::BAD_REQUEST,
+            "Invalid code, or no enrollment is pending".into(),
+        )),
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+/// DELETE /api/auth/totp — remove the enrollment (needs a current code)
+pub async fn disable_totp(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
+) -> Result<StatusCode, (StatusCode, String)> {
+    require_step_up(&state, &headers, &client)?;
+    state.totp.disable().map_err(internal_error)?;
+    tracing::warn!("🔐 TOTP disabled for Root");
+    audit(&state, "totp:disable");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/auth/totp",
+            get(get_totp_status)
+                .post(begin_totp_enrollment)
+                .delete(disable_totp),
+        )
+        .route("/api/auth/totp/confirm", post(confirm_totp_enrollment))
+}
@@ -18,6 +18,19 @@ pub struct SystemStatus {
     pub voice_echo_enabled: bool,
     pub adblock_enabled: bool,
     pub adblock_count: usize,
+    /// Stage breakdown of the most recent voice turn, if any.
+    pub last_voice_turn: Option<VoiceTurnLatency>,
+}
+
+/// Millisecond timings of one voice turn, as reported by `/api/system/status`.
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct VoiceTurnLatency {
+    pub stt_ms: u64,
+    pub agent_ms: u64,
+    /// Audio receipt → first reply byte sent (includes STT and agent).
+    pub first_byte_ms: u64,
+    pub tts_ms: Option<u64>,
+    pub total_ms: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -82,6 +95,10 @@ pub struct ToggleAdBlockRequest {
 
 // ── Chat / WebSocket ─────────────────────────────────────────────
 
+/// A WebSocket frame. `conversation_id` lets one socket carry several chats at
+/// once: replies, thoughts and errors echo the id of the message they answer,
+/// and a `cancel` control with an id stops that conversation only. Messages
+/// without an id share one default conversation.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", content = "payload")]
 pub enum WsMessage {
@@ -91,6 +108,8 @@ pub enum WsMessage {
         content: String,
         sender: String, // "user", "agent", "system"
         is_final: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conversation_id: Option<String>,
     },
 
     /// Audio chunk (base64 encoded for JSON, but binary preferred over raw WS)
@@ -98,21 +117,34 @@ pub enum WsMessage {
     Audio {
         data: String,   // Base64
         format: String, // "pcm", "opus", "mp3"
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conversation_id: Option<String>,
     },
 
     /// Control events
     #[serde(rename = "control")]
     Control {
-        event: String, // "voice_start", "voice_end", "interrupt"
+        event: String, // "voice_start", "voice_end", "interrupt", "voice_reply_on", "voice_reply_off", "cancel", "cancelled"
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conversation_id: Option<String>,
     },
 
     /// Error message
     #[serde(rename = "error")]
-    Error { code: String, message: String },
+    Error {
+        code: String,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conversation_id: Option<String>,
+    },
 
     /// Agent internal thought (for UI streaming)
     #[serde(rename = "thought")]
-    Thought { content: String },
+    Thought {
+        content: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        conversation_id: Option<String>,
+    },
 
     /// Confirmation request from security gate
     #[serde(rename = "confirm")]
@@ -154,13 +186,23 @@ pub struct VaultEntryMetadata {
     pub description: String,
     pub created_at: String,
     pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
 }
 
-// ── Diary ────────────────────────────────────────────────────────
-
-#[derive(Debug, Deserialize)]
-pub struct CreateDiaryEntryRequest {
-    pub content: String,
+impl From<crate::security::vault::VaultMetadata> for VaultEntryMetadata {
+    fn from(meta: crate::security::vault::VaultMetadata) -> Self {
+        Self {
+            id: meta.id,
+            description: meta.description,
+            created_at: meta.created_at,
+            tags: meta.tags,
+            updated_at: meta.updated_at,
+            recipients: meta.recipients,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +217,7 @@ mod tests {
             content: "Hello world".into(),
             sender: "user".into(),
             is_final: true,
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"text\""));
@@ -187,6 +230,7 @@ mod tests {
                 content,
                 sender,
                 is_final,
+                ..
             } => {
                 assert_eq!(content, "Hello world");
                 assert_eq!(sender, "user");
@@ -201,6 +245,7 @@ mod tests {
         let msg = WsMessage::Audio {
             data: "SGVsbG8=".into(), // "Hello" in base64
             format: "webm".into(),
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"audio\""));
@@ -209,7 +254,7 @@ mod tests {
 
         let parsed: WsMessage = serde_json::from_str(&json).unwrap();
         match parsed {
-            WsMessage::Audio { data, format } => {
+            WsMessage::Audio { data, format, .. } => {
                 assert_eq!(data, "SGVsbG8=");
                 assert_eq!(format, "webm");
             }
@@ -221,6 +266,7 @@ mod tests {
     fn ws_thought_message_serialization() {
         let msg = WsMessage::Thought {
             content: "👂 Listening...".into(),
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"thought\""));
@@ -228,7 +274,7 @@ mod tests {
 
         let parsed: WsMessage = serde_json::from_str(&json).unwrap();
         match parsed {
-            WsMessage::Thought { content } => {
+            WsMessage::Thought { content, .. } => {
                 assert!(content.contains("Listening"));
             }
             _ => panic!("Expected Thought variant"),
@@ -240,6 +286,7 @@ mod tests {
         let msg = WsMessage::Error {
             code: "STT_ERROR".into(),
             message: "Speech-to-text failed".into(),
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"error\""));
@@ -247,7 +294,7 @@ mod tests {
 
         let parsed: WsMessage = serde_json::from_str(&json).unwrap();
         match parsed {
-            WsMessage::Error { code, message } => {
+            WsMessage::Error { code, message, .. } => {
                 assert_eq!(code, "STT_ERROR");
                 assert_eq!(message, "Speech-to-text failed");
             }
@@ -259,13 +306,14 @@ mod tests {
     fn ws_control_message_serialization() {
         let msg = WsMessage::Control {
             event: "voice_start".into(),
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"control\""));
 
         let parsed: WsMessage = serde_json::from_str(&json).unwrap();
         match parsed {
-            WsMessage::Control { event } => assert_eq!(event, "voice_start"),
+            WsMessage::Control { event, .. } => assert_eq!(event, "voice_start"),
             _ => panic!("Expected Control variant"),
         }
     }
@@ -275,6 +323,7 @@ mod tests {
         let msg = WsMessage::Error {
             code: "AUDIO_DECODE_ERROR".into(),
             message: "Invalid base64 audio data".into(),
+            conversation_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("AUDIO_DECODE_ERROR"));
@@ -289,7 +338,7 @@ mod tests {
             r#"{"type":"audio","payload":{"data":"SGVsbG8gV29ybGQ=","format":"webm"}}"#;
         let parsed: WsMessage = serde_json::from_str(frontend_json).unwrap();
         match parsed {
-            WsMessage::Audio { data, format } => {
+            WsMessage::Audio { data, format, .. } => {
                 assert_eq!(data, "SGVsbG8gV29ybGQ=");
                 assert_eq!(format, "webm");
             }
@@ -312,4 +361,27 @@ mod tests {
             _ => panic!("Expected Text variant from frontend payload"),
         }
     }
+
+    #[test]
+    fn conversation_id_is_optional_and_roundtrips() {
+        let msg = WsMessage::Thought {
+            content: "x".into(),
+            conversation_id: None,
+        };
+        assert!(!serde_json::to_string(&msg).unwrap().contains("conversation_id"));
+
+        let json = r#"{"type":"control","payload":{"event":"cancel","conversation_id":"browser-1"}}"#;
+        let parsed: WsMessage = serde_json::from_str(json).unwrap();
+        match &parsed {
+            WsMessage::Control {
+                event,
+                conversation_id,
+            } => {
+                assert_eq!(event, "cancel");
+                assert_eq!(conversation_id.as_deref(), Some("browser-1"));
+            }
+            _ => panic!("Expected Control variant"),
+        }
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
 }
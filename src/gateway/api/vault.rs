@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Vault API — reveal, update, rotate and delete vault entries.
+//!
+//! Root-only. Listing stays at `GET /api/vault`; every operation here waits
+//! for approval on the confirmation gate (answered via
+//! `/api/security/confirm`) and writes a signed record to the audit log,
+//! whether it was approved or not. Revealed content is never logged.
+
+use crate::gateway::api::auth::{require_step_up, require_trust, AuthenticatedUser};
+use crate::gateway::api::types::VaultEntryMetadata;
+use crate::gateway::{AppState, ClientKey};
+use crate::security::confirmation::ConfirmationPolicy;
+use crate::security::vault::VaultMetadata;
+use crate::security::{AuditEvent, AuditEventType};
+use axum::{
+    extract::{Json, Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{post, put},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// Seconds Root has to approve a vault operation before it is denied,
+/// unless its confirmation policy sets a timeout.
+const CONFIRM_TIMEOUT_SECS: u64 = 120;
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+pub struct RevealedEntry {
+    #[serde(flatten)]
+    pub meta: VaultEntryMetadata,
+    pub content: String,
+}
+
+#[derive(Deserialize)]
+pub struct VaultUpdate {
+    pub content: String,
+}
+
+impl Drop for VaultUpdate {
+    fn drop(&mut self) {
+        self.content.zeroize();
+    }
+}
+
+// ── Helpers ────────────────────────────────────────────────────────
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn entry_meta(state: &AppState, id: &str) -> Result<VaultMetadata, (StatusCode, String)> {
+    state
+        .vault
+        .metadata(id)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, format!("Vault entry {id} not found")))
+}
+
+/// Ask Root to approve `operation` (e.g. "Reveal") on entry `meta`, under
+/// the tool's confirmation policy (which may require dual control); a
+/// denial is recorded and turned into 403.
+async fn confirm(
+    state: &AppState,
+    tool: &str,
+    operation: &str,
+    meta: &VaultMetadata,
+) -> Result<(), (StatusCode, String)> {
+    let policy = ConfirmationPolicy::with_default_timeout(
+        &state.config.read().await.security.confirmation,
+        tool,
+        CONFIRM_TIMEOUT_SECS,
+    );
+    let approved = state
+        .confirm_gate
+        .request_with_policy(
+            tool,
+            &format!("{operation} vault entry \"{}\"", meta.description),
+            &policy,
+        )
+        .await;
+    if approved {
+        return Ok(());
+    }
+    record(state, tool, &meta.id, false, None);
+    Err((StatusCode::FORBIDDEN, format!("{tool} was not approved")))
+}
+
+fn record(state: &AppState, tool: &str, id: &str, approved: bool, error: Option<String>) {
+    let mut event = AuditEvent::new(AuditEventType::VaultAccess)
+        .with_actor("api".to_string(), None, Some("Root".to_string()))
+        .with_action(
+            format!("{tool} {id}"),
+            "high".to_string(),
+            approved,
+            approved,
+        );
+    if error.is_some() {
+        event = event.with_result(false, None, 0, error);
+    }
+    if let Err(e) = state.audit.log_signed(&event) {
+        tracing::error!("Failed to record {tool} {id}: {e}");
+    }
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// POST /api/vault/{id}/reveal — decrypt an entry once approved
+pub async fn reveal_entry(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    ClientKey(client): ClientKey,
+    headers: HeaderMap,
+) -> Result<Json<RevealedEntry>, (StatusCode, String)> {
+    require_trust(&state, "vault_reveal")?;
+    require_step_up(&state, &headers, &client)?;
+    let meta = entry_meta(&state, &id)?;
+    confirm(&state, "vault_reveal", "Reveal", &meta).await?;
+
+    let content = state.vault.decrypt_from_vault(&id);
+    let error = content.as_ref().err().map(ToString::to_string);
+    record(&state, "vault_reveal", &id, true, error);
+    Ok(Json(RevealedEntry {
+        meta: meta.into(),
+        content: content.map_err(internal_error)?,
+    }))
+}
+
+/// PUT /api/vault/{id} — replace an entry's content
+pub async fn update_entry(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut update): Json<VaultUpdate>,
+) -> Result<Json<VaultEntryMetadata>, (StatusCode, String)> {
+    if update.content.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "content is empty".into()));
+    }
+    let meta = entry_meta(&state, &id)?;
+    confirm(&state, "vault_update", "Replace the content of", &meta).await?;
+
+    let outcome = state.vault.update(&id, std::mem::take(&mut update.content));
+    finish(&state, "vault_update", &id, outcome)
+}
+
+/// POST /api/vault/{id}/rotate — re-encrypt an entry under a fresh key
+pub async fn rotate_entry(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<VaultEntryMetadata>, (StatusCode, String)> {
+    let meta = entry_meta(&state, &id)?;
+    confirm(&state, "vault_rotate", "Re-encrypt", &meta).await?;
+
+    let outcome = state.vault.rotate(&id);
+    finish(&state, "vault_rotate", &id, outcome)
+}
+
+/// DELETE /api/vault/{id} — shred an entry and drop it from the memory index
+pub async fn delete_entry(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let meta = entry_meta(&state, &id)?;
+    confirm(&state, "vault_delete", "Delete", &meta).await?;
+
+    let outcome = match state.vault.erase(&id) {
+        Ok(_) => state.mem.forget(&format!("vault:{id}")).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    let error = outcome.as_ref().err().map(ToString::to_string);
+    record(&state, "vault_delete", &id, true, error);
+    outcome.map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn finish(
+    state: &AppState,
+    tool: &str,
+    id: &str,
+    outcome: anyhow::Result<VaultMetadata>,
+) -> Result<Json<VaultEntryMetadata>, (StatusCode, String)> {
+    let error = outcome.as_ref().err().map(ToString::to_string);
+    record(state, tool, id, true, error);
+    outcome
+        .map(|meta| Json(meta.into()))
+        .map_err(internal_error)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/vault/{id}", put(update_entry).delete(delete_entry))
+        .route("/api/vault/{id}/reveal", post(reveal_entry))
+        .route("/api/vault/{id}/rotate", post(rotate_entry))
+}
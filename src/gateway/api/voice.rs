@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Voice enrollment API — voiceprints for on-device speaker identification.
+//!
+//! Root-only. Each enrollment sample is a short recording of a configured
+//! family member; only the derived embedding is kept, encrypted in the
+//! workspace. Identification itself happens in the chat socket's voice path
+//! when `[stt.speaker_id]` is enabled.
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Router,
+};
+use crate::gateway::AppState;
+use crate::gateway::api::auth::AuthenticatedUser;
+use crate::identity::voiceprint::{self, VoiceprintStore, MAX_SAMPLES_PER_MEMBER};
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+// ── Types ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct EnrollRequest {
+    /// Family member name as configured in `[family]`.
+    pub member: String,
+    /// Base64-encoded recording in any format ffmpeg can decode.
+    pub audio: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoiceProfile {
+    pub member: String,
+    pub samples: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoiceProfilesResponse {
+    pub enabled: bool,
+    pub max_samples: usize,
+    pub profiles: Vec<VoiceProfile>,
+}
+
+fn secrets(state: &AppState) -> Option<SecretStore> {
+    Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true))
+}
+
+fn audit(state: &AppState, action: String) {
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::SecurityEvent)
+            .with_actor("voice".to_string(), None, Some("Root".to_string()))
+            .with_action(action, "low".to_string(), true, true),
+    );
+}
+
+// ── Handlers ───────────────────────────────────────────────────────
+
+/// GET /api/voice/profiles — enrolled members and their sample counts
+pub async fn list_profiles(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<VoiceProfilesResponse>, (StatusCode, String)> {
+    let enabled = state.config.read().await.stt.speaker_id.enabled;
+    let profiles = VoiceprintStore::load(&state.workspace_dir, &secrets(&state))
+        .profiles()
+        .into_iter()
+        .map(|(member, samples)| VoiceProfile { member, samples })
+        .collect();
+    Ok(Json(VoiceProfilesResponse {
+        enabled,
+        max_samples: MAX_SAMPLES_PER_MEMBER,
+        profiles,
+    }))
+}
+
+/// POST /api/voice/enroll — add a voice sample for a family member
+pub async fn enroll(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<EnrollRequest>,
+) -> Result<Json<VoiceProfile>, (StatusCode, String)> {
+    let member = {
+        let config = state.config.read().await;
+        config
+            .family
+            .members
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(payload.member.trim()))
+            .map(|m| m.name.clone())
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Family member '{}' not found", payload.member),
+                )
+            })?
+    };
+
+    let audio = base64::engine::general_purpose::STANDARD
+        .decode(payload.audio.trim())
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid base64 audio: {e}")))?;
+    let embedding = voiceprint::voiceprint(&audio)
+        .await
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    let secrets = secrets(&state);
+    let mut store = VoiceprintStore::load(&state.workspace_dir, &secrets);
+    let samples = store.enroll(&member, embedding);
+    store
+        .save(&state.workspace_dir, &secrets)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit(&state, format!("voice:enroll:{member}"));
+    Ok(Json(VoiceProfile { member, samples }))
+}
+
+/// DELETE /api/voice/profiles/{member} — forget a member's voiceprint
+pub async fn delete_profile(
+    _user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(member): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let secrets = secrets(&state);
+    let mut store = VoiceprintStore::load(&state.workspace_dir, &secrets);
+    let Some((name, _)) = store
+        .profiles()
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&member))
+    else {
+        return Err((StatusCode::NOT_FOUND, format!("No voice profile for '{member}'")));
+    };
+    store.remove(&name);
+    store
+        .save(&state.workspace_dir, &secrets)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit(&state, format!("voice:forget:{name}"));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Router ─────────────────────────────────────────────────────────
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/voice/profiles", get(list_profiles))
+        .route("/api/voice/profiles/{member}", delete(delete_profile))
+        .route("/api/voice/enroll", post(enroll))
+}
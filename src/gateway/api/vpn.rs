@@ -10,7 +10,7 @@ use axum::{
     Router,
 };
 use crate::gateway::AppState;
-use super::auth::AuthenticatedUser;
+use super::auth::{require_trust, AuthenticatedUser};
 use serde::Deserialize;
 
 pub fn router() -> Router<AppState> {
@@ -25,14 +25,12 @@ struct AddPeerRequest {
 }
 
 async fn add_peer(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Json(payload): Json<AddPeerRequest>,
 ) -> impl IntoResponse {
-    // RBAC: Only Root can manage VPN
-    if user.role != crate::identity::UserRole::Root {
-        let body: serde_json::Value = serde_json::json!({"error": "Only Root can manage VPN"});
-        return (StatusCode::FORBIDDEN, Json(body)).into_response();
+    if let Err((status, error)) = require_trust(&state, "vpn") {
+        return (status, Json(serde_json::json!({"error": error}))).into_response();
     }
 
     // Rate limiting: 5 VPN operations per minute
@@ -43,6 +41,10 @@ async fn add_peer(
 
     match state.vpn_manager.add_peer(&payload.name).await {
         Ok((peer, client_conf)) => {
+            crate::automations::publish(crate::automations::Event::StateChange {
+                component: "vpn".into(),
+                state: "peer_added".into(),
+            });
             let response: serde_json::Value = serde_json::json!({
                 "peer": peer,
                 "config_file": client_conf
@@ -58,15 +60,9 @@ async fn add_peer(
 }
 
 async fn list_peers(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // RBAC: Only Root can manage VPN
-    if user.role != crate::identity::UserRole::Root {
-        let body: serde_json::Value = serde_json::json!({"error": "Only Root can manage VPN"});
-        return (StatusCode::FORBIDDEN, Json(body)).into_response();
-    }
-
     match state.vpn_manager.list_peers() {
         Ok(peers) => {
              let body: serde_json::Value = serde_json::json!(peers);
@@ -80,18 +76,19 @@ async fn list_peers(
 }
 
 async fn delete_peer(
-    user: AuthenticatedUser,
+    _user: AuthenticatedUser,
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> impl IntoResponse {
-    // RBAC: Only Root can manage VPN
-    if user.role != crate::identity::UserRole::Root {
-        let body: serde_json::Value = serde_json::json!({"error": "Only Root can manage VPN"});
-        return (StatusCode::FORBIDDEN, Json(body)).into_response();
+    if let Err((status, error)) = require_trust(&state, "vpn") {
+        return (status, Json(serde_json::json!({"error": error}))).into_response();
     }
-
     match state.vpn_manager.delete_peer(&id) {
         Ok(_) => {
+            crate::automations::publish(crate::automations::Event::StateChange {
+                component: "vpn".into(),
+                state: "peer_removed".into(),
+            });
              let body: serde_json::Value = serde_json::json!({"status": "deleted"});
              (StatusCode::OK, Json(body)).into_response()
         },
@@ -7,11 +7,27 @@ use axum::{
     response::IntoResponse,
     http::StatusCode,
 };
+use crate::agent::budget::BudgetExceeded;
+use crate::agent::cancel::{CancellationToken, Cancelled};
+use crate::config::SpeakerIdConfig;
 use crate::gateway::AppState;
-use super::types::WsMessage;
+use crate::identity::family::{FamilyMember, SCOPE_SHARED};
+use crate::identity::voiceprint::{self, VoiceprintStore};
+use crate::identity::UserRole;
+use crate::memory::scoped;
+use crate::providers::router::TaskClass;
+use crate::security::confirmation;
+use crate::security::quotas::{self, Quota};
+use crate::security::secrets::SecretStore;
+use crate::security::{AuditEvent, AuditEventType};
+use super::types::{VoiceTurnLatency, WsMessage};
 use super::auth::AuthQuery;
 use serde_json;
 use base64::Engine;
+use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 pub async fn ws_handler(
@@ -19,29 +35,322 @@ pub async fn ws_handler(
     Query(params): Query<AuthQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    if !state.pairing.is_authenticated(params.token.as_deref().unwrap_or("")) {
+    let token = params.token.as_deref().unwrap_or("");
+    let Some(user) = super::auth::authenticate(&state, token) else {
         return (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    };
+
+    // A family member's own session talks as them on every turn
+    let member = match user.member {
+        Some(name) => {
+            let config = state.config.read().await;
+            let Some(member) = config
+                .family
+                .members
+                .iter()
+                .find(|m| m.name.eq_ignore_ascii_case(&name))
+                .map(FamilyMember::from_config)
+            else {
+                let reason = "Family member no longer exists";
+                return (StatusCode::UNAUTHORIZED, reason).into_response();
+            };
+            Some(member)
+        }
+        None => None,
+    };
+
+    ws.on_upgrade(|socket| handle_socket(socket, state, member))
+}
+
+/// Per-connection preferences negotiated via control events.
+#[derive(Debug, Default)]
+struct SessionState {
+    /// Client asked for spoken replies (`voice_reply_on` / `voice_reply_off`).
+    voice_replies: bool,
+}
+
+/// Conversations one socket may run in parallel.
+const MAX_CONVERSATIONS: usize = 8;
+
+/// Who spoke a voice turn, as determined by speaker identification.
+enum Speaker {
+    /// An enrolled family member; the turn runs with their role and scope.
+    Member(FamilyMember),
+    /// An unrecognized voice with `unknown_as_owner = false`.
+    Guest,
+}
+
+impl Speaker {
+    fn name(&self) -> &str {
+        match self {
+            Self::Member(member) => &member.name,
+            Self::Guest => "guest",
+        }
+    }
+
+    fn role(&self) -> UserRole {
+        match self {
+            Self::Member(member) => member.role,
+            Self::Guest => UserRole::Child,
+        }
+    }
+
+    fn scope(&self) -> String {
+        match self {
+            Self::Member(member) => member.scope(),
+            Self::Guest => SCOPE_SHARED.to_string(),
+        }
+    }
+}
+
+/// Timing of one agent turn as seen from the socket.
+struct TurnTiming {
+    agent: Duration,
+    /// When the final text reply was written to the socket.
+    first_byte_at: Instant,
+    /// Total synthesis time, if the reply was also spoken.
+    tts: Option<Duration>,
+}
+
+/// Outgoing messages of one conversation, tagged with its id.
+#[derive(Clone)]
+struct Outbox {
+    tx: mpsc::UnboundedSender<WsMessage>,
+    conversation_id: Option<String>,
+}
+
+impl Outbox {
+    /// Queue a message for the socket. Returns `false` once the socket is gone.
+    fn send(&self, msg: WsMessage) -> bool {
+        self.tx.send(msg).is_ok()
+    }
+
+    fn text(&self, content: String, sender: &str) -> bool {
+        self.send(WsMessage::Text {
+            content,
+            sender: sender.into(),
+            is_final: true,
+            conversation_id: self.conversation_id.clone(),
+        })
+    }
+
+    fn thought(&self, content: String) -> bool {
+        self.send(WsMessage::Thought {
+            content,
+            conversation_id: self.conversation_id.clone(),
+        })
+    }
+
+    fn audio(&self, data: String, format: String) -> bool {
+        self.send(WsMessage::Audio {
+            data,
+            format,
+            conversation_id: self.conversation_id.clone(),
+        })
+    }
+
+    fn control(&self, event: &str) -> bool {
+        self.send(WsMessage::Control {
+            event: event.into(),
+            conversation_id: self.conversation_id.clone(),
+        })
+    }
+
+    fn error(&self, code: &str, message: impl Into<String>) -> bool {
+        self.send(WsMessage::Error {
+            code: code.into(),
+            message: message.into(),
+            conversation_id: self.conversation_id.clone(),
+        })
     }
+}
+
+/// One unit of work for a conversation.
+enum TurnInput {
+    Text(String),
+    Audio { data: String, format: String },
+}
+
+struct Turn {
+    input: TurnInput,
+    /// Voice reply preference at the time the message arrived.
+    voice_replies: bool,
+}
 
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+/// The queue feeding a conversation's worker and the token that stops it.
+struct Conversation {
+    turns: mpsc::UnboundedSender<Turn>,
+    cancel: CancellationToken,
 }
 
-async fn handle_socket(mut socket: WebSocket, state: AppState) {
+/// The conversations running on one socket. Turns within a conversation run
+/// in order; different conversations run concurrently.
+struct Conversations {
+    running: HashMap<String, Conversation>,
+    tx: mpsc::UnboundedSender<WsMessage>,
+    state: AppState,
+    /// Identifies this socket in confirmation sessions.
+    socket_id: String,
+    /// Family member the socket's session belongs to; `None` for the owner.
+    member: Option<FamilyMember>,
+}
+
+impl Conversations {
+    fn new(
+        tx: mpsc::UnboundedSender<WsMessage>,
+        state: AppState,
+        member: Option<FamilyMember>,
+    ) -> Self {
+        Self {
+            running: HashMap::new(),
+            tx,
+            state,
+            socket_id: Uuid::new_v4().to_string(),
+            member,
+        }
+    }
+
+    fn outbox(&self, conversation_id: Option<String>) -> Outbox {
+        Outbox {
+            tx: self.tx.clone(),
+            conversation_id,
+        }
+    }
+
+    /// Queue a turn, starting a worker for the conversation if none is running.
+    fn submit(&mut self, conversation_id: Option<String>, turn: Turn) {
+        let key = conversation_id.clone().unwrap_or_default();
+        let turn = match self.running.get(&key) {
+            Some(conversation) => match conversation.turns.send(turn) {
+                Ok(()) => return,
+                // The worker went idle and exited; start a new one.
+                Err(mpsc::error::SendError(turn)) => turn,
+            },
+            None => turn,
+        };
+
+        self.running.retain(|_, c| !c.turns.is_closed());
+        if self.running.len() >= MAX_CONVERSATIONS {
+            self.outbox(conversation_id).error(
+                "TOO_MANY_CONVERSATIONS",
+                format!("At most {MAX_CONVERSATIONS} conversations can run at once"),
+            );
+            return;
+        }
+
+        let (turns, rx) = mpsc::unbounded_channel();
+        let _ = turns.send(turn);
+        let cancel = CancellationToken::new();
+        // Approvals remembered in a conversation do not carry over to others.
+        let session = format!("dashboard:{}:{key}", self.socket_id);
+        tokio::spawn(confirmation::with_session(
+            session,
+            run_conversation(
+                rx,
+                self.outbox(conversation_id),
+                self.state.clone(),
+                self.member.clone(),
+                cancel.clone(),
+            ),
+        ));
+        self.running.insert(key, Conversation { turns, cancel });
+    }
+
+    /// Stop a conversation's current and queued turns.
+    fn cancel(&mut self, conversation_id: Option<String>) {
+        let key = conversation_id.clone().unwrap_or_default();
+        match self.running.remove(&key) {
+            // The worker reports `cancelled` once it has stopped.
+            Some(conversation) if !conversation.turns.is_closed() => conversation.cancel.cancel(),
+            _ => {
+                self.outbox(conversation_id)
+                    .error("NOT_RUNNING", "No running conversation with this id");
+            }
+        }
+    }
+}
+
+impl Drop for Conversations {
+    fn drop(&mut self) {
+        for conversation in self.running.values() {
+            conversation.cancel.cancel();
+        }
+    }
+}
+
+/// Work through a conversation's turns, exiting once its queue runs dry or
+/// `cancel` fires. Conversations with an id can also be cancelled via
+/// `/api/agent/cancel/{id}`.
+async fn run_conversation(
+    mut turns: mpsc::UnboundedReceiver<Turn>,
+    outbox: Outbox,
+    state: AppState,
+    member: Option<FamilyMember>,
+    cancel: CancellationToken,
+) {
+    let _registration = outbox
+        .conversation_id
+        .clone()
+        .map(|id| state.agent_runs.register(id, cancel.clone()));
+
+    loop {
+        let turn = tokio::select! {
+            biased;
+            () = cancel.cancelled() => break,
+            turn = turns.recv() => match turn {
+                Some(turn) => turn,
+                None => break,
+            },
+        };
+        run_turn(turn, &outbox, &state, member.as_ref(), &cancel).await;
+        if turns.is_empty() {
+            // Turns sent before this still arrive; later ones start a new worker.
+            turns.close();
+        }
+    }
+
+    if cancel.is_cancelled() {
+        outbox.control("cancelled");
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, member: Option<FamilyMember>) {
     tracing::info!("New WebSocket connection established");
+    let (mut sink, mut stream) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<WsMessage>();
+
+    // Single writer so concurrent conversations never interleave frames.
+    let writer = tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let Ok(json) = serde_json::to_string(&msg) else {
+                continue;
+            };
+            if sink.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = SessionState::default();
+    let mut conversations = Conversations::new(tx, state.clone(), member);
 
     // Send welcome message
-    let welcome = WsMessage::Text {
-        content: "Connected to MyMolt Core Gateway".into(),
-        sender: "system".into(),
-        is_final: true,
-    };
-    
-    if let Ok(msg) = serde_json::to_string(&welcome) {
-        let _ = socket.send(Message::Text(msg.into())).await;
-    }
+    conversations
+        .outbox(None)
+        .text("Connected to MyMolt Core Gateway".into(), "system");
 
-    // Main loop
-    while let Some(msg) = socket.recv().await {
+    // Main loop; the emergency stop closes the socket.
+    loop {
+        let msg = tokio::select! {
+            msg = stream.next() => msg,
+            () = crate::security::emergency::engaged() => {
+                tracing::warn!("Closing WebSocket: emergency stop engaged");
+                break;
+            }
+        };
+        let Some(msg) = msg else {
+            break;
+        };
         let msg = match msg {
             Ok(msg) => msg,
             Err(e) => {
@@ -53,7 +362,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
         match msg {
             Message::Text(text) => {
                 if let Ok(ws_msg) = serde_json::from_str::<WsMessage>(&text) {
-                    process_message(ws_msg, &mut socket, &state).await;
+                    process_message(ws_msg, &state, &mut session, &mut conversations);
                 }
             }
             Message::Binary(data) => {
@@ -68,198 +377,496 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
             _ => {}
         }
     }
+
+    drop(conversations);
+    writer.abort();
 }
 
-async fn handle_text_interaction(content: String, socket: &mut WebSocket, state: &AppState) {
+/// Runs one agent turn and streams the reply (text, then audio when voice
+/// replies are on). Returns the turn timing, or `None` if the run failed.
+///
+/// Without a `speaker` the turn runs as the owner. An identified speaker gets
+/// their own memory scope, below adult no tools, and is charged the turn
+/// against their daily quotas.
+async fn handle_text_interaction(
+    content: String,
+    outbox: &Outbox,
+    state: &AppState,
+    voice_replies: bool,
+    speaker: Option<&Speaker>,
+    cancel: &CancellationToken,
+) -> Option<TurnTiming> {
+    let quota = match speaker {
+        Some(Speaker::Member(member)) => {
+            let family = &state.config.read().await.family;
+            Some(state.quotas.for_member(family, &member.name, member.role))
+        }
+        _ => None,
+    };
+    if let Some(Err(exceeded)) = quota.as_ref().map(|q| q.charge(Quota::ChatTurns)) {
+        tracing::info!("{exceeded}");
+        // A child is told gently, as the assistant, rather than shown an error
+        if exceeded.role > UserRole::Child {
+            outbox.error("QUOTA_EXCEEDED", exceeded.reply());
+        } else {
+            outbox.text(exceeded.reply(), "agent");
+        }
+        return None;
+    }
+
+    // The owner's turns must not see what members keep in their scopes
+    let mem = scoped::confine(&state.mem, speaker.map(Speaker::scope));
+
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: "dashboard".into(),
+        sender: speaker.map_or("owner", Speaker::name).to_string(),
+        text: content.clone(),
+    });
+
     // 1. Store user message in memory for Sigil scanning (Crucial step!)
-    if state.auto_save {
+    // Guests share the household scope, so nothing they say is kept.
+    if state.auto_save && !matches!(speaker, Some(Speaker::Guest)) {
         let key = format!("user_msg_{}", Uuid::new_v4());
-        let _ = state
-            .mem
+        let _ = mem
             .store(&key, &content, crate::memory::MemoryCategory::Conversation)
             .await;
     }
 
-    // 2. Prepare observer and channels
-    let (thought_tx, mut thought_rx) = tokio::sync::mpsc::unbounded_channel();
-    let observer = WsObserver::new(thought_tx);
-    
-    let model = state.model.read().await.clone();
-    let state_clone = state.clone();
-    let content_clone = content.clone();
-    
-    // Channel for the final result
-    let (result_tx, mut result_rx) = tokio::sync::mpsc::channel(1);
-
-    tokio::spawn(async move {
-        // Build context preamble (same as loop_.rs)
-        // We do a simplified version here: retrieve relevant memories first
-        let context = if let Ok(entries) = state_clone.mem.recall(&content_clone, 5).await {
-            if entries.is_empty() {
-                String::new()
+    // 2. Build context preamble (same as loop_.rs)
+    // We do a simplified version here: retrieve relevant memories first
+    let context = if let Ok(entries) = mem.recall(&content, 5).await {
+        if entries.is_empty() {
+            String::new()
+        } else {
+            let mut ctx = String::from("[Memory context]\n");
+            for entry in entries {
+                use std::fmt::Write;
+                let _ = writeln!(ctx, "- {}: {}", entry.key, entry.content);
+            }
+            ctx.push('\n');
+            ctx
+        }
+    } else {
+        String::new()
+    };
+
+    let content = match speaker {
+        Some(speaker) => format!("[Speaker: {}]\n{content}", speaker.name()),
+        None => content,
+    };
+    let enriched = if context.is_empty() {
+        content
+    } else {
+        format!("{context}{content}")
+    };
+    let registry = state.tools_registry.snapshot();
+    let tools = match speaker {
+        Some(speaker) if speaker.role() < UserRole::Adult => &[],
+        _ => registry.as_slice(),
+    };
+
+    // Read dynamic config
+    let model = state
+        .models
+        .model_for(TaskClass::for_turn(!tools.is_empty()));
+    let system_prompt = state.system_prompt.read().await.clone();
+    let temperature = *state.temperature.read().await;
+
+    let mut history = vec![
+        crate::providers::ChatMessage::system(&system_prompt),
+        crate::providers::ChatMessage::user(&enriched),
+    ];
+
+    // 3. Wait for an executor slot, then run the agent; the observer streams
+    // progress as thoughts
+    let ticket = match state.executor.submit("dashboard") {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            outbox.error("QUEUE_FULL", e.to_string());
+            return None;
+        }
+    };
+    if ticket.position() > 0 {
+        outbox.thought(format!("⏳ Queued (position {})", ticket.position()));
+    }
+    let _permit = tokio::select! {
+        biased;
+        () = cancel.cancelled() => {
+            record_abort(state, outbox, &Cancelled { tool: None });
+            return None;
+        }
+        permit = ticket.ready() => permit,
+    };
+
+    let observer = WsObserver::new(outbox.clone());
+    let agent_started = Instant::now();
+    let res = scoped::with_turn_scope(
+        speaker.map(Speaker::scope),
+        quotas::with_member(
+            quota,
+            crate::agent::loop_::run_agent_turn(
+                state.provider.as_ref(),
+                &mut history,
+                tools,
+                &observer,
+                "dashboard",
+                &model,
+                temperature,
+                &state.budget,
+                state.planner.as_ref(),
+                state.tool_router.as_deref(),
+                cancel,
+            ),
+        ),
+    )
+    .await;
+    let agent_duration = agent_started.elapsed();
+
+    match res {
+        Ok(reply) => {
+            outbox.text(reply.clone(), "agent");
+            let first_byte_at = Instant::now();
+
+            let tts = match &state.tts {
+                Some(tts) if voice_replies => Some(stream_speech(tts.as_ref(), &reply, outbox).await),
+                _ => None,
+            };
+            Some(TurnTiming {
+                agent: agent_duration,
+                first_byte_at,
+                tts,
+            })
+        }
+        Err(e) => {
+            if let Some(cancelled) = e.downcast_ref::<Cancelled>() {
+                record_abort(state, outbox, cancelled);
+            } else if let Some(exceeded) = e.downcast_ref::<BudgetExceeded>() {
+                outbox.error("BUDGET_EXCEEDED", exceeded.to_string());
             } else {
-                let mut ctx = String::from("[Memory context]\n");
-                for entry in entries {
-                    use std::fmt::Write;
-                    let _ = writeln!(ctx, "- {}: {}", entry.key, entry.content);
-                }
-                ctx.push('\n');
-                ctx
+                outbox.error("AGENT_ERROR", e.to_string());
             }
-        } else {
-            String::new()
-        };
+            None
+        }
+    }
+}
 
-        let enriched = if context.is_empty() {
-            content_clone
-        } else {
-            format!("{context}{content_clone}")
+/// Audit an agent run stopped by the user.
+fn record_abort(state: &AppState, outbox: &Outbox, cancelled: &Cancelled) {
+    tracing::info!("{cancelled}");
+    let conversation = outbox.conversation_id.as_deref().unwrap_or("default");
+    let command = match &cancelled.tool {
+        Some(tool) => format!("agent:cancel:{conversation}:{tool}"),
+        None => format!("agent:cancel:{conversation}"),
+    };
+    let _ = state.audit.log(
+        &AuditEvent::new(AuditEventType::SecurityEvent)
+            .with_actor("dashboard".to_string(), None, None)
+            .with_action(command, "low".to_string(), true, true),
+    );
+}
+
+/// Synthesize `reply` sentence by sentence and send each chunk as `WsMessage::Audio`.
+/// Returns the total time spent synthesizing.
+async fn stream_speech(
+    tts: &dyn crate::providers::tts::TtsProvider,
+    reply: &str,
+    outbox: &Outbox,
+) -> Duration {
+    let mut synth_time = Duration::ZERO;
+
+    for chunk in crate::providers::tts::split_into_speech_chunks(
+        reply,
+        crate::providers::tts::MAX_TTS_CHUNK_CHARS,
+    ) {
+        let chunk_started = Instant::now();
+        let audio = match tts.synthesize(&chunk).await {
+            Ok(audio) => audio,
+            Err(e) => {
+                tracing::error!("TTS error ({}): {}", tts.name(), e);
+                outbox.error("TTS_ERROR", "Text-to-speech failed");
+                break;
+            }
         };
+        synth_time += chunk_started.elapsed();
 
-        // Read dynamic config
-        let system_prompt = state_clone.system_prompt.read().await.clone();
-        let temperature = *state_clone.temperature.read().await;
-
-        let mut history = vec![
-            crate::providers::ChatMessage::system(&system_prompt),
-            crate::providers::ChatMessage::user(&enriched),
-        ];
-
-        let res = crate::agent::loop_::run_tool_call_loop(
-            state_clone.provider.as_ref(),
-            &mut history,
-            state_clone.tools_registry.as_ref(),
-            &observer,
-            "dashboard",
-            &model,
-            temperature,
-        ).await;
-        let _ = result_tx.send(res).await;
-    });
+        let data = base64::engine::general_purpose::STANDARD.encode(&audio);
+        if !outbox.audio(data, tts.format().to_string()) {
+            break;
+        }
+    }
 
-    // Handle streaming thoughts and the final result
-    loop {
-        tokio::select! {
-             Some(thought) = thought_rx.recv() => {
-                if let Ok(json) = serde_json::to_string(&thought) {
-                    let _ = socket.send(Message::Text(json.into())).await;
+    synth_time
+}
+
+/// Runs one queued turn of a conversation. On a member's session every
+/// turn runs as that member.
+async fn run_turn(
+    turn: Turn,
+    outbox: &Outbox,
+    state: &AppState,
+    member: Option<&FamilyMember>,
+    cancel: &CancellationToken,
+) {
+    match turn.input {
+        TurnInput::Text(content) => {
+            let speaker = member.cloned().map(Speaker::Member);
+            handle_text_interaction(
+                content,
+                outbox,
+                state,
+                turn.voice_replies,
+                speaker.as_ref(),
+                cancel,
+            )
+            .await;
+        }
+        TurnInput::Audio { data, format } => {
+            let received_at = Instant::now();
+
+            // 1. Decode base64
+            let audio_bytes = match base64::engine::general_purpose::STANDARD.decode(&data) {
+                Ok(b) => b,
+                Err(e) => {
+                    tracing::error!("Failed to decode audio base64: {}", e);
+                    outbox.error("AUDIO_DECODE_ERROR", "Invalid base64 audio data");
+                    return;
                 }
-            }
-            Some(res) = result_rx.recv() => {
-                match res {
-                    Ok(reply) => {
-                        let resp_msg = WsMessage::Text {
-                            content: reply,
-                            sender: "agent".into(),
-                            is_final: true,
-                        };
-                        if let Ok(json) = serde_json::to_string(&resp_msg) {
-                            let _ = socket.send(Message::Text(json.into())).await;
-                        }
-                    }
-                    Err(e) => {
-                        let err_msg = WsMessage::Error {
-                            code: "AGENT_ERROR".into(),
-                            message: e.to_string(),
-                        };
-                        if let Ok(json) = serde_json::to_string(&err_msg) {
-                            let _ = socket.send(Message::Text(json.into())).await;
-                        }
-                    }
+            };
+
+            // 2. Transcribe
+            // Send a thought first so user knows we are processing
+            outbox.thought("👂 Listening...".into());
+
+            let speaker_id = state.config.read().await.stt.speaker_id.clone();
+            let stt_started = Instant::now();
+            let (transcription, speaker) = match member {
+                // The session decides who speaks, whoever's voice it is
+                Some(member) => (
+                    state.stt.transcribe(audio_bytes, &format).await,
+                    Some(Speaker::Member(member.clone())),
+                ),
+                None if speaker_id.enabled => tokio::join!(
+                    state.stt.transcribe(audio_bytes.clone(), &format),
+                    identify_speaker(state, &audio_bytes, &speaker_id)
+                ),
+                None => (state.stt.transcribe(audio_bytes, &format).await, None),
+            };
+            let transcription = match transcription {
+                Ok(t) => t,
+                Err(e) => {
+                    tracing::error!("STT error: {}", e);
+                    outbox.error("STT_ERROR", "Speech-to-text failed");
+                    return;
                 }
-                break;
+            };
+
+            let stt_duration = stt_started.elapsed();
+            tracing::info!("Transcribed audio: '{}'", transcription);
+
+            // Send transcription back to UI as a 'thought'
+            outbox.thought(format!("🎤 Heard: \"{}\"", transcription));
+            match &speaker {
+                Some(Speaker::Member(_)) if member.is_some() => {}
+                Some(Speaker::Member(member)) => {
+                    outbox.thought(format!("🗣️ Recognized {}", member.name));
+                }
+                Some(Speaker::Guest) => {
+                    outbox.thought("🗣️ Voice not recognized, answering as guest".into());
+                }
+                None => {}
+            }
+
+            // 3. Process as text message
+            if let Some(timing) = handle_text_interaction(
+                transcription,
+                outbox,
+                state,
+                turn.voice_replies,
+                speaker.as_ref(),
+                cancel,
+            )
+            .await
+            {
+                record_voice_turn(
+                    state,
+                    stt_duration,
+                    timing.agent,
+                    timing.first_byte_at.duration_since(received_at),
+                    timing.tts,
+                    received_at.elapsed(),
+                );
             }
         }
     }
 }
 
-async fn process_message(msg: WsMessage, socket: &mut WebSocket, state: &AppState) {
+/// Match `audio` against the enrolled voiceprints. `None` means the turn runs
+/// as the owner.
+async fn identify_speaker(
+    state: &AppState,
+    audio: &[u8],
+    config: &SpeakerIdConfig,
+) -> Option<Speaker> {
+    let unknown = if config.unknown_as_owner {
+        None
+    } else {
+        Some(Speaker::Guest)
+    };
+    let embedding = match voiceprint::voiceprint(audio).await {
+        Ok(embedding) => embedding,
+        Err(e) => {
+            tracing::warn!("Speaker identification failed: {e}");
+            return unknown;
+        }
+    };
+
+    let secrets = Some(SecretStore::new(&state.workspace_dir.join(".mymolt"), true));
+    let Some(found) = VoiceprintStore::load(&state.workspace_dir, &secrets)
+        .identify(&embedding, config.threshold)
+    else {
+        return unknown;
+    };
+    tracing::info!("Voice matched {} ({:.3})", found.member, found.similarity);
+
+    let config = state.config.read().await;
+    config
+        .family
+        .members
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(&found.member))
+        .map(|m| Speaker::Member(FamilyMember::from_config(m)))
+        .or(unknown)
+}
+
+fn process_message(
+    msg: WsMessage,
+    state: &AppState,
+    session: &mut SessionState,
+    conversations: &mut Conversations,
+) {
     match msg {
-        WsMessage::Text { content, .. } => {
-            handle_text_interaction(content, socket, state).await;
+        WsMessage::Text {
+            content,
+            conversation_id,
+            ..
+        } => {
+            let turn = Turn {
+                input: TurnInput::Text(content),
+                voice_replies: session.voice_replies,
+            };
+            conversations.submit(conversation_id, turn);
         }
-        WsMessage::Audio { data, format } => {
+        WsMessage::Audio {
+            data,
+            format,
+            conversation_id,
+        } => {
             tracing::info!("Received audio chunk: {} bytes, format: {}", data.len(), format);
-            
+
             // WebSocket Echo (Loopback) Mode
             if state.voice_echo_enabled.load(std::sync::atomic::Ordering::Relaxed) {
-                let echo_msg = WsMessage::Audio {
-                    data: data.clone(),
-                    format: format.clone(),
-                };
-                if let Ok(json) = serde_json::to_string(&echo_msg) {
-                    let _ = socket.send(Message::Text(json.into())).await;
-                }
+                conversations.outbox(conversation_id).audio(data, format);
             } else {
-                // 1. Decode base64
-                let audio_bytes = match base64::engine::general_purpose::STANDARD.decode(&data) {
-                    Ok(b) => b,
-                    Err(e) => {
-                        tracing::error!("Failed to decode audio base64: {}", e);
-                         let _ = socket.send(Message::Text(serde_json::to_string(&WsMessage::Error {
-                            code: "AUDIO_DECODE_ERROR".into(),
-                            message: "Invalid base64 audio data".into(),
-                        }).unwrap().into())).await;
-                        return;
-                    }
-                };
-
-                // 2. Transcribe
-                // Send a thought first so user knows we are processing
-                let _ = socket.send(Message::Text(serde_json::to_string(&WsMessage::Thought {
-                    content: "👂 Listening...".into()
-                }).unwrap().into())).await;
-
-                let transcription = match state.stt.transcribe(audio_bytes, &format).await {
-                    Ok(t) => t,
-                    Err(e) => {
-                        tracing::error!("STT error: {}", e);
-                        let _ = socket.send(Message::Text(serde_json::to_string(&WsMessage::Error {
-                            code: "STT_ERROR".into(),
-                            message: "Speech-to-text failed".into(),
-                        }).unwrap().into())).await;
-                        return;
-                    }
+                let turn = Turn {
+                    input: TurnInput::Audio { data, format },
+                    voice_replies: session.voice_replies,
                 };
-                
-                tracing::info!("Transcribed audio: '{}'", transcription);
-
-                // Send transcription back to UI as a 'thought'
-                let _ = socket.send(Message::Text(serde_json::to_string(&WsMessage::Thought {
-                    content: format!("🎤 Heard: \"{}\"", transcription)
-                }).unwrap().into())).await;
-
-                // 3. Process as text message
-                handle_text_interaction(transcription, socket, state).await;
+                conversations.submit(conversation_id, turn);
             }
         }
-        WsMessage::Control { event } => {
+        WsMessage::Control {
+            event,
+            conversation_id,
+        } => {
             tracing::info!("Received control event: {}", event);
-            
-            if event == "voice_test" {
+            let outbox = conversations.outbox(conversation_id.clone());
+
+            if event == "cancel" {
+                conversations.cancel(conversation_id);
+            } else if event == "voice_reply_on" || event == "voice_reply_off" {
+                session.voice_replies = event == "voice_reply_on";
+                if session.voice_replies && state.tts.is_none() {
+                    outbox.error(
+                        "TTS_DISABLED",
+                        "Voice replies are not enabled on this gateway ([tts] enabled = false)",
+                    );
+                }
+            } else if event == "voice_test" {
                 // Send mock bot response
                 let mock_audio = crate::providers::mock_voice::MockVoiceProvider::get_response_audio();
-                let resp = WsMessage::Audio {
-                    data: mock_audio,
-                    format: "wav".into(),
-                };
-                if let Ok(json) = serde_json::to_string(&resp) {
-                    let _ = socket.send(Message::Text(json.into())).await;
-                }
+                outbox.audio(mock_audio, "wav".into());
             }
         }
         _ => {}
     }
 }
 
+/// Emits a `VoiceTurn` event and keeps the breakdown for `/api/system/status`.
+fn record_voice_turn(
+    state: &AppState,
+    stt: Duration,
+    agent: Duration,
+    first_byte: Duration,
+    tts: Option<Duration>,
+    total: Duration,
+) {
+    state.observer.record_event(&crate::observability::ObserverEvent::VoiceTurn {
+        channel: "dashboard".into(),
+        stt,
+        agent,
+        first_byte,
+        tts,
+        total,
+    });
+
+    let ms = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+    let latency = VoiceTurnLatency {
+        stt_ms: ms(stt),
+        agent_ms: ms(agent),
+        first_byte_ms: ms(first_byte),
+        tts_ms: tts.map(ms),
+        total_ms: ms(total),
+    };
+    *state
+        .last_voice_turn
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(latency);
+}
+
 /// A specialized observer that streams agent progress over a WebSocket.
 struct WsObserver {
-    tx: tokio::sync::mpsc::UnboundedSender<WsMessage>,
+    outbox: Outbox,
 }
 
 impl WsObserver {
-    fn new(tx: tokio::sync::mpsc::UnboundedSender<WsMessage>) -> Self {
-        Self { tx }
+    fn new(outbox: Outbox) -> Self {
+        Self { outbox }
+    }
+}
+
+/// Convert internal events to UI thoughts. Sub-agent events are prefixed
+/// with the agent name so nested progress shows who is working.
+fn thought_for(event: &crate::observability::ObserverEvent) -> Option<String> {
+    use crate::observability::ObserverEvent;
+
+    match event {
+        ObserverEvent::ToolCallStart { tool } => {
+            Some(format!("🔧 Executing tool: {}...", tool))
+        }
+        ObserverEvent::ToolCall { tool, duration, success } => {
+            let status = if *success { "Completed" } else { "Failed" };
+            Some(format!("✅ {} {} ({}ms)", tool, status, duration.as_millis()))
+        }
+        ObserverEvent::LlmRequest { model, .. } => {
+            Some(format!("🧠 Consulting {}...", model))
+        }
+        ObserverEvent::AgentEnd { duration, .. } => {
+            Some(format!("🏁 Finished ({}ms)", duration.as_millis()))
+        }
+        ObserverEvent::SubAgent { agent, event } => {
+            thought_for(event).map(|thought| format!("[{agent}] {thought}"))
+        }
+        _ => None,
     }
 }
 
@@ -269,25 +876,8 @@ impl crate::observability::Observer for WsObserver {
     }
 
     fn record_event(&self, event: &crate::observability::ObserverEvent) {
-        use crate::observability::ObserverEvent;
-        
-        // Convert internal events to UI thoughts
-        let thought = match event {
-            ObserverEvent::ToolCallStart { tool } => {
-                Some(format!("🔧 Executing tool: {}...", tool))
-            }
-            ObserverEvent::ToolCall { tool, duration, success } => {
-                let status = if *success { "Completed" } else { "Failed" };
-                Some(format!("✅ {} {} ({}ms)", tool, status, duration.as_millis()))
-            }
-            ObserverEvent::LlmRequest { model, .. } => {
-                Some(format!("🧠 Consulting {}...", model))
-            }
-            _ => None,
-        };
-
-        if let Some(content) = thought {
-            let _ = self.tx.send(WsMessage::Thought { content });
+        if let Some(content) = thought_for(event) {
+            self.outbox.thought(content);
         }
     }
 
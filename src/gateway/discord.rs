@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Discord bot run by the gateway (`[channels_config.discord] gateway = true`).
+//!
+//! The gateway keeps the bot's WebSocket connection. Guild messages open a
+//! thread, and every thread, direct message channel and channel where `/ask`
+//! is used is its own session. Attachments are saved to the workspace for
+//! the agent (see [`super::media`]).
+
+use super::{family_memory_scope, gateway_agent_reply, media, AppState};
+use crate::agent::budget::BudgetExceeded;
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, DiscordChannel};
+use crate::identity::UserRole;
+use crate::memory::{scoped, MemoryCategory};
+use crate::util::truncate_with_ellipsis;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Keep the bot connected, reconnecting when Discord closes the session,
+/// and queue its messages on `tx`; the emergency stop pauses it.
+pub fn spawn(channel: Arc<DiscordChannel>, tx: mpsc::Sender<ChannelMessage>) {
+    tokio::spawn(async move {
+        while !tx.is_closed() {
+            let listen = channel.listen(tx.clone());
+            if let Err(e) = crate::security::emergency::global().supervise(listen).await {
+                tracing::warn!("Discord connection lost: {e}");
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
+
+pub(super) async fn handle_message(state: &AppState, mut msg: ChannelMessage) {
+    let Some(dc) = &state.discord else {
+        return;
+    };
+
+    if let Err(e) = media::save_attachments(&state.workspace_dir, &mut msg).await {
+        tracing::error!("Failed to save Discord attachments: {e}");
+        let _ = dc
+            .respond(&msg, "Sorry, I couldn't save your attachment.")
+            .await;
+        return;
+    }
+    if msg.content.trim().is_empty() {
+        return;
+    }
+    tracing::info!(
+        "Discord message in {}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    let scope = family_memory_scope(state, "discord", &msg.sender).await;
+    if state.auto_save {
+        let key = format!("discord_{}_{}", msg.sender, msg.id);
+        let _ = scoped::confine(&state.mem, scope.clone())
+            .store(&key, &msg.content, MemoryCategory::Conversation)
+            .await;
+    }
+
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: "discord".into(),
+        sender: msg.sender.clone(),
+        text: msg.content.clone(),
+    });
+
+    let ticket = match state.executor.submit("discord") {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            tracing::warn!("Discord message rejected: {e}");
+            let _ = dc
+                .respond(&msg, "I'm busy right now, please try again in a moment.")
+                .await;
+            return;
+        }
+    };
+    if ticket.position() > 0 {
+        // A plain message, so an `/ask` keeps its response for the answer
+        let _ = dc
+            .send(
+                &format!(
+                    "⏳ Your message is queued (position {}).",
+                    ticket.position()
+                ),
+                &msg.sender,
+            )
+            .await;
+    }
+    let _permit = ticket.ready().await;
+
+    // One session per thread or channel
+    let session = format!("discord:{}", msg.sender);
+    let reply = gateway_agent_reply(state, &msg.content, session, scope, UserRole::Root, None);
+    match reply.await {
+        Ok(reply) => {
+            if let Err(e) = dc.respond(&msg, &reply).await {
+                tracing::error!("Failed to send Discord reply: {e}");
+            }
+        }
+        Err(e) => {
+            tracing::error!("LLM error for Discord message: {e:#}");
+            let reply = if e.downcast_ref::<BudgetExceeded>().is_some() {
+                "Sorry, that request needed more steps than I'm allowed. Try breaking it up."
+            } else {
+                "Sorry, I couldn't process your message right now."
+            };
+            let _ = dc.respond(&msg, reply).await;
+        }
+    }
+}
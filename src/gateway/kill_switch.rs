@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Panic button (`POST /api/system/panic`).
+//!
+//! [`KillSwitch::engage`] engages the emergency stop, which aborts agent
+//! runs, closes dashboard sockets, locks the vault and halts the daemon's
+//! channels and background workers. It then stops the MCP servers, the
+//! tunnel and the VPN, and revokes every paired token and device. A new pairing code is
+//! printed to the console; pairing with it calls [`KillSwitch::resume`].
+//! MCP servers, the tunnel and the VPN come back on the next gateway start.
+
+use crate::agent::cancel::AgentRuns;
+use crate::config::Config;
+use crate::gateway::api::mcp::{remove_section, tools_heading};
+use crate::mcp::McpRegistry;
+use crate::network::VpnManager;
+use crate::security::devices::DeviceRegistry;
+use crate::security::emergency::EmergencyStop;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger, PairingGuard};
+use crate::tools::ToolRegistry;
+use crate::tunnel::Tunnel;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// What engaging the kill switch shut down.
+#[derive(Debug, Default, Serialize)]
+pub struct PanicReport {
+    /// Dashboard chat turns that were cancelled.
+    pub agent_runs_cancelled: usize,
+    /// MCP servers that were disconnected.
+    pub mcp_servers_stopped: Vec<String>,
+    pub tunnel_stopped: bool,
+    pub vpn_stopped: bool,
+    /// Steps that failed; the stop is engaged regardless.
+    pub errors: Vec<String>,
+}
+
+/// Shuts the assistant down until the owner pairs again.
+pub struct KillSwitch {
+    pub stop: Arc<EmergencyStop>,
+    pub agent_runs: Arc<AgentRuns>,
+    pub mcp: Arc<McpRegistry>,
+    pub tools_registry: Arc<ToolRegistry>,
+    pub system_prompt: Arc<RwLock<String>>,
+    pub tunnel: Option<Arc<dyn Tunnel>>,
+    pub vpn: Arc<VpnManager>,
+    pub pairing: Arc<PairingGuard>,
+    pub devices: Arc<DeviceRegistry>,
+    pub config: Arc<RwLock<Config>>,
+    pub audit: Arc<AuditLogger>,
+}
+
+impl KillSwitch {
+    pub fn is_engaged(&self) -> bool {
+        self.stop.is_engaged()
+    }
+
+    /// Stop everything and require pairing again. `actor` names who pressed
+    /// the button in the audit log.
+    pub async fn engage(&self, actor: &str) -> PanicReport {
+        self.stop.engage();
+        tracing::warn!("🛑 Emergency stop engaged by {actor}");
+        let mut report = PanicReport {
+            agent_runs_cancelled: self.agent_runs.cancel_all(),
+            ..PanicReport::default()
+        };
+
+        for server in self.mcp.servers().await {
+            let Some((server, tools)) = self.mcp.remove(server.name()).await else {
+                continue;
+            };
+            self.tools_registry.remove(&tools);
+            server.stop().await;
+            let heading = tools_heading(server.name());
+            remove_section(&mut *self.system_prompt.write().await, &heading);
+            report.mcp_servers_stopped.push(server.name().to_string());
+        }
+
+        if let Some(tunnel) = &self.tunnel {
+            match tunnel.stop().await {
+                Ok(()) => report.tunnel_stopped = true,
+                Err(e) => report.errors.push(format!("tunnel: {e}")),
+            }
+        }
+
+        match self.vpn.shutdown() {
+            Ok(stopped) => report.vpn_stopped = stopped,
+            Err(e) => report.errors.push(format!("vpn: {e}")),
+        }
+
+        if let Err(e) = self.revoke_pairing().await {
+            report.errors.push(format!("pairing: {e}"));
+        }
+
+        for error in &report.errors {
+            tracing::error!("Emergency stop: {error}");
+        }
+        self.log(actor, "panic:engage", report.errors.is_empty());
+        report
+    }
+
+    /// Release the stop after a successful pairing. Returns `false` if it
+    /// was not engaged.
+    pub fn resume(&self) -> bool {
+        if !self.stop.release() {
+            return false;
+        }
+        tracing::info!("Emergency stop released by pairing");
+        self.log("pairing", "panic:resume", true);
+        true
+    }
+
+    /// Drop all paired tokens, also from config so a restart keeps them
+    /// revoked, revoke every device and print the code to pair with.
+    async fn revoke_pairing(&self) -> anyhow::Result<()> {
+        let code = self.pairing.revoke_all();
+        // Revoked in memory even if persisting fails; report that after saving config
+        let devices = self.devices.revoke_all();
+        println!();
+        println!("  🛑 EMERGENCY STOP — pair again to resume with this code:");
+        println!("     ┌──────────────┐");
+        println!("     │  {code}  │");
+        println!("     └──────────────┘");
+        println!("     Send: POST /pair with header X-Pairing-Code: {code}");
+
+        let mut config = self.config.write().await;
+        config.gateway.require_pairing = true;
+        config.gateway.paired_tokens.clear();
+        config.save()?;
+        tracing::warn!("Emergency stop revoked {} device(s)", devices?);
+        Ok(())
+    }
+
+    fn log(&self, actor: &str, action: &str, success: bool) {
+        let _ = self.audit.log(
+            &AuditEvent::new(AuditEventType::SecurityEvent)
+                .with_actor("gateway".to_string(), None, Some(actor.to_string()))
+                .with_action(action.to_string(), "critical".to_string(), true, success),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::cancel::CancellationToken;
+
+    fn kill_switch(dir: &std::path::Path, pairing: Arc<PairingGuard>) -> KillSwitch {
+        let config = Config {
+            config_path: dir.join("config.toml"),
+            workspace_dir: dir.to_path_buf(),
+            ..Config::default()
+        };
+        KillSwitch {
+            stop: Arc::new(EmergencyStop::new()),
+            agent_runs: Arc::new(AgentRuns::new()),
+            mcp: Arc::new(McpRegistry::default()),
+            tools_registry: Arc::new(ToolRegistry::new(Vec::new())),
+            system_prompt: Arc::new(RwLock::new(String::new())),
+            tunnel: None,
+            vpn: Arc::new(VpnManager::new(dir)),
+            pairing,
+            devices: Arc::new(DeviceRegistry::new(dir, 3600, 86_400).unwrap()),
+            config: Arc::new(RwLock::new(config)),
+            audit: Arc::new(
+                AuditLogger::new(crate::config::AuditConfig::default(), dir.to_path_buf()).unwrap(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn engage_cancels_runs_and_revokes_pairing_until_resumed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pairing = Arc::new(PairingGuard::new(false, &["zc_owner".into()]));
+        let switch = kill_switch(tmp.path(), Arc::clone(&pairing));
+        let token = CancellationToken::new();
+        let _run = switch.agent_runs.register("chat".into(), token.clone());
+        let laptop = switch
+            .devices
+            .register("Laptop", vec!["root".into()])
+            .unwrap();
+
+        let report = switch.engage("Root").await;
+        assert!(switch.is_engaged());
+        assert_eq!(report.agent_runs_cancelled, 1);
+        assert!(token.is_cancelled());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(!pairing.is_authenticated("zc_owner"));
+        assert!(switch.devices.authenticate(&laptop.token).is_none());
+
+        let config = switch.config.read().await;
+        assert!(config.gateway.require_pairing);
+        assert!(config.gateway.paired_tokens.is_empty());
+        drop(config);
+
+        let code = pairing.pairing_code().unwrap();
+        assert!(pairing.try_pair(&code).unwrap().is_some());
+        assert!(switch.resume());
+        assert!(!switch.is_engaged());
+        assert!(!switch.resume());
+    }
+}
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Media on the chat channels the gateway runs.
+//!
+//! Incoming voice notes become text through STT. Other attachments are
+//! saved under [`INBOX_DIR`] in the workspace and their paths handed to the
+//! agent, which reads them with the file and image tools. Images and
+//! documents the agent's tools write during a turn can go back as files.
+
+use super::AppState;
+use crate::channels::traits::ChannelMessage;
+use anyhow::Result;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// Workspace directory attachments are saved to, one folder per channel
+pub const INBOX_DIR: &str = "inbox";
+
+/// Most files sent back after one turn
+const MAX_OUTGOING_FILES: usize = 5;
+
+/// Audio format STT expects for a MIME type (`audio/ogg; codecs=opus` → `ogg`).
+fn audio_format(mime_type: &str) -> &str {
+    let subtype = mime_type
+        .split(';')
+        .next()
+        .and_then(|m| m.trim().strip_prefix("audio/"))
+        .unwrap_or("ogg");
+    match subtype {
+        "mpeg" => "mp3",
+        "x-wav" => "wav",
+        other => other,
+    }
+}
+
+/// Replace a voice note among the attachments with its transcript.
+pub(super) async fn transcribe_voice(state: &AppState, msg: &mut ChannelMessage) -> Result<()> {
+    let Some(index) = msg
+        .attachments
+        .iter()
+        .position(|a| a.mime_type.starts_with("audio/"))
+    else {
+        return Ok(());
+    };
+    let voice = msg.attachments.remove(index);
+    let format = audio_format(&voice.mime_type).to_string();
+    let transcript = state.stt.transcribe(voice.data, &format).await?;
+    msg.content = if msg.content.is_empty() {
+        transcript
+    } else {
+        format!("{}\n{transcript}", msg.content)
+    };
+    Ok(())
+}
+
+/// Keep a sender's file name to a single, harmless path component.
+fn safe_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Append the saved files to a message, each with the tools that read it.
+fn with_attachment_note(content: &str, saved: &[(String, bool)]) -> String {
+    let mut text = content.to_string();
+    if !text.is_empty() {
+        text.push_str("\n\n");
+    }
+    text.push_str("[Attachments saved in the workspace]");
+    for (path, is_image) in saved {
+        let tools = if *is_image {
+            "image_describe or image_ocr"
+        } else {
+            "file_read"
+        };
+        let _ = write!(text, "\n- {path} (read with {tools})");
+    }
+    text
+}
+
+/// Move the attachments into the channel's folder under [`INBOX_DIR`] and
+/// point the agent to them.
+pub(super) async fn save_attachments(workspace: &Path, msg: &mut ChannelMessage) -> Result<()> {
+    if msg.attachments.is_empty() {
+        return Ok(());
+    }
+    let folder = format!("{INBOX_DIR}/{}", safe_file_name(&msg.channel));
+    let dir = workspace.join(&folder);
+    tokio::fs::create_dir_all(&dir).await?;
+    let mut saved = Vec::new();
+    for attachment in msg.attachments.drain(..) {
+        let prefix = Uuid::new_v4().simple().to_string();
+        let name = format!(
+            "{}-{}",
+            &prefix[..8],
+            safe_file_name(attachment.file_name.as_deref().unwrap_or_default())
+        );
+        tokio::fs::write(dir.join(&name), &attachment.data).await?;
+        saved.push((format!("{folder}/{name}"), attachment.is_image()));
+    }
+    msg.content = with_attachment_note(&msg.content, &saved);
+    Ok(())
+}
+
+/// MIME type of a file a chat can carry, and whether it is an image.
+pub(super) fn outgoing_kind(path: &Path) -> Option<(&'static str, bool)> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let kind = match extension.as_str() {
+        "png" => ("image/png", true),
+        "jpg" | "jpeg" => ("image/jpeg", true),
+        "pdf" => ("application/pdf", false),
+        "txt" | "md" => ("text/plain", false),
+        "csv" => ("text/csv", false),
+        "docx" => (
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            false,
+        ),
+        "xlsx" => (
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            false,
+        ),
+        "pptx" => (
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            false,
+        ),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Images and documents named in `tool_results` that lie in the workspace
+/// and were written since `since`, i.e. produced by this turn's tools.
+pub(super) fn produced_files(
+    tool_results: &str,
+    workspace: &Path,
+    since: SystemTime,
+) -> Vec<PathBuf> {
+    let Ok(workspace) = workspace.canonicalize() else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = Vec::new();
+    let candidates = tool_results
+        .split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | ',' | '(' | ')'))
+        .map(|token| token.trim_end_matches(['.', ':', ';']))
+        .filter(|token| outgoing_kind(Path::new(token)).is_some());
+    for token in candidates {
+        let Ok(path) = workspace.join(token).canonicalize() else {
+            continue;
+        };
+        let fresh = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified >= since);
+        if path.starts_with(&workspace) && path.is_file() && fresh && !files.contains(&path) {
+            files.push(path);
+        }
+        if files.len() == MAX_OUTGOING_FILES {
+            break;
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::Attachment;
+    use std::time::Duration;
+
+    #[test]
+    fn audio_formats_follow_the_mime_type() {
+        assert_eq!(audio_format("audio/ogg; codecs=opus"), "ogg");
+        assert_eq!(audio_format("audio/mpeg"), "mp3");
+        assert_eq!(audio_format("audio/mp4"), "mp4");
+        assert_eq!(audio_format("application/octet-stream"), "ogg");
+    }
+
+    #[test]
+    fn file_names_stay_inside_the_inbox() {
+        assert_eq!(safe_file_name("report.pdf"), "report.pdf");
+        assert_eq!(safe_file_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(safe_file_name(".hidden"), "hidden");
+        assert_eq!(safe_file_name(""), "attachment");
+    }
+
+    #[tokio::test]
+    async fn attachments_are_saved_and_named_for_the_tools() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut msg = ChannelMessage {
+            id: "1".into(),
+            sender: "c1".into(),
+            content: "What is this?".into(),
+            channel: "discord".into(),
+            timestamp: 0,
+            attachments: vec![
+                Attachment {
+                    mime_type: "image/png".into(),
+                    data: b"png".to_vec(),
+                    file_name: Some("shot.png".into()),
+                },
+                Attachment {
+                    mime_type: "application/pdf".into(),
+                    data: b"pdf".to_vec(),
+                    file_name: Some("doc.pdf".into()),
+                },
+            ],
+            group: None,
+        };
+        save_attachments(workspace.path(), &mut msg).await.unwrap();
+
+        assert!(msg.attachments.is_empty());
+        assert!(msg.content.starts_with("What is this?\n\n"));
+        let paths: Vec<&str> = msg
+            .content
+            .lines()
+            .filter_map(|line| line.strip_prefix("- "))
+            .map(|line| line.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].ends_with("-shot.png"));
+        assert!(msg
+            .content
+            .contains("(read with image_describe or image_ocr)"));
+        assert!(msg.content.contains("(read with file_read)"));
+        for path in paths {
+            assert!(path.starts_with("inbox/discord/"));
+            assert!(workspace.path().join(path).is_file());
+        }
+    }
+
+    #[test]
+    fn only_fresh_workspace_files_go_back() {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let before = SystemTime::now() - Duration::from_mins(1);
+        std::fs::write(workspace.path().join("chart.png"), b"png").unwrap();
+        std::fs::write(workspace.path().join("notes.rs"), b"fn").unwrap();
+        std::fs::write(outside.path().join("secret.pdf"), b"pdf").unwrap();
+        let results = format!(
+            "Saved chart.png. Read notes.rs and {}; missing.pdf",
+            outside.path().join("secret.pdf").display()
+        );
+
+        let files = produced_files(&results, workspace.path(), before);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("chart.png"));
+
+        // Files older than the turn were not produced by it
+        let later = SystemTime::now() + Duration::from_mins(1);
+        assert!(produced_files(&results, workspace.path(), later).is_empty());
+    }
+}
@@ -11,31 +11,47 @@
 //! - Request timeouts (30s) to prevent slow-loris attacks
 //! - Header sanitization (handled by axum/hyper)
 
-use crate::channels::{Channel, WhatsAppChannel};
+use crate::agent::budget::BudgetExceeded;
+use crate::agent::planner::PlanRejected;
+use crate::channels::groups::GroupTurn;
+use crate::channels::router::{route, ChannelRouter};
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, DiscordChannel, TelegramChannel, WhatsAppChannel};
 use crate::config::Config;
 
 pub mod api;
-use crate::memory::{self, Memory, MemoryCategory};
+pub mod discord;
+pub mod kill_switch;
+pub mod media;
+pub mod telegram;
+pub mod whatsapp;
+use crate::identity::family::{member_scope, FamilyRegistry};
+use crate::identity::oidc_generic::LoginChallenge;
+use crate::identity::UserRole;
+use crate::memory::{self, scoped, Memory, MemoryCategory};
 use crate::observability::{self, Observer};
+use crate::providers::router::{routed_vision, ModelRouter, TaskClass};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
 use crate::security::{
+    confirmation,
     pairing::{constant_time_eq, is_public_bind, PairingGuard},
+    quotas::{self, MemberQuota, Quota},
     SecurityPolicy,
 };
 use crate::tools::{self, Tool};
-use crate::util::truncate_with_ellipsis;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::{
     body::Bytes,
-    extract::{Query, State},
-    http::{header, HeaderMap, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, Query, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tower_http::limit::RequestBodyLimitLayer;
@@ -65,7 +81,47 @@ fn normalize_gateway_reply(reply: String) -> String {
     reply
 }
 
-async fn gateway_agent_reply(state: &AppState, message: &str) -> Result<String> {
+/// Memory scope for `sender` on `channel` under the current `[family]` config.
+async fn family_memory_scope(state: &AppState, channel: &str, sender: &str) -> Option<String> {
+    let config = state.config.read().await;
+    FamilyRegistry::from_config(&config.family).memory_scope(channel, sender)
+}
+
+/// The turn for group message `msg` under the current `[channels_config]`
+/// and `[family]` config; `None` when MyMolt stays quiet.
+async fn group_turn(state: &AppState, msg: &ChannelMessage) -> Option<GroupTurn> {
+    let config = state.config.read().await;
+    let family = FamilyRegistry::from_config(&config.family);
+    crate::channels::groups::group_turn(&config.channels_config.groups, &family, msg)
+}
+
+/// Run one agent turn in confirmation `session`; memory tools are confined
+/// to `scope` when set. Below Adult the turn gets no tools, as on the
+/// dashboard. A member's tool calls are charged to their `quota`.
+async fn gateway_agent_reply(
+    state: &AppState,
+    message: &str,
+    session: String,
+    scope: Option<String>,
+    role: UserRole,
+    quota: Option<MemberQuota>,
+) -> Result<String> {
+    let (reply, _) =
+        gateway_agent_reply_with_files(state, message, session, scope, role, quota).await?;
+    Ok(reply)
+}
+
+/// [`gateway_agent_reply`], plus the images and documents the turn's tools
+/// wrote to the workspace, for channels that can send files back.
+async fn gateway_agent_reply_with_files(
+    state: &AppState,
+    message: &str,
+    session: String,
+    scope: Option<String>,
+    role: UserRole,
+    quota: Option<MemberQuota>,
+) -> Result<(String, Vec<std::path::PathBuf>)> {
+    let started = std::time::SystemTime::now();
     let system_prompt = state.system_prompt.read().await;
     let temperature = *state.temperature.read().await;
 
@@ -74,18 +130,56 @@ async fn gateway_agent_reply(state: &AppState, message: &str) -> Result<String>
         ChatMessage::user(message),
     ];
 
-    let reply = crate::agent::loop_::run_tool_call_loop(
-        state.provider.as_ref(),
-        &mut history,
-        state.tools_registry.as_ref(),
-        state.observer.as_ref(),
-        "gateway",
-        &state.model.read().await,
-        temperature,
+    let registry = state.tools_registry.snapshot();
+    let tools = if role < UserRole::Adult {
+        &[]
+    } else {
+        registry.as_slice()
+    };
+    let class = TaskClass::for_turn(!tools.is_empty());
+    let model = state.models.model_for(class);
+    let reply = confirmation::with_session(
+        session,
+        scoped::with_turn_scope(
+            scope,
+            quotas::with_member(
+                quota,
+                crate::agent::loop_::run_agent_turn(
+                    state.provider.as_ref(),
+                    &mut history,
+                    tools,
+                    state.observer.as_ref(),
+                    "gateway",
+                    &model,
+                    temperature,
+                    &state.budget,
+                    state.planner.as_ref(),
+                    state.tool_router.as_deref(),
+                    &crate::agent::cancel::CancellationToken::new(),
+                ),
+            ),
+        ),
     )
     .await?;
 
-    Ok(normalize_gateway_reply(reply))
+    let tool_results: String = history
+        .iter()
+        .filter(|m| m.role == "user" && m.content.starts_with("[Tool results]"))
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let files = media::produced_files(&tool_results, &state.workspace_dir, started);
+    Ok((normalize_gateway_reply(reply), files))
+}
+
+/// Answer a message the channel router hands over.
+async fn handle_channel_message(state: AppState, msg: ChannelMessage) {
+    match msg.channel.as_str() {
+        "whatsapp" => whatsapp::handle_message(&state, msg).await,
+        "telegram" => telegram::handle_message(&state, msg).await,
+        "discord" => discord::handle_message(&state, msg).await,
+        other => tracing::warn!("No handler for {other} messages"),
+    }
 }
 
 #[derive(Debug)]
@@ -136,6 +230,7 @@ pub struct GatewayRateLimiter {
     vpn: SlidingWindowRateLimiter,
     diary: SlidingWindowRateLimiter,
     model_switch: SlidingWindowRateLimiter,
+    guest: SlidingWindowRateLimiter,
 }
 
 impl GatewayRateLimiter {
@@ -147,6 +242,7 @@ impl GatewayRateLimiter {
             vpn: SlidingWindowRateLimiter::new(5, window),            // 5 VPN ops/min
             diary: SlidingWindowRateLimiter::new(20, window),          // 20 diary writes/min
             model_switch: SlidingWindowRateLimiter::new(3, window),    // 3 model switches/min
+            guest: SlidingWindowRateLimiter::new(10, window),          // 10 guest questions/min per link
         }
     }
 
@@ -169,6 +265,10 @@ impl GatewayRateLimiter {
     pub fn allow_model_switch(&self, key: &str) -> bool {
         self.model_switch.allow(key)
     }
+
+    pub fn allow_guest(&self, key: &str) -> bool {
+        self.guest.allow(key)
+    }
 }
 
 #[derive(Debug)]
@@ -208,11 +308,12 @@ impl IdempotencyStore {
 ///
 /// Generates random state tokens, stores them with a TTL, and validates
 /// (consumes) them on callback. This prevents CSRF attacks in the OAuth flow.
+/// Each state also keeps the login's PKCE verifier and nonce.
 #[derive(Debug)]
 pub struct OidcStateStore {
     ttl: Duration,
-    /// Maps state_token → (provider_id, created_at)
-    states: Mutex<HashMap<String, (String, Instant)>>,
+    /// Maps state_token → (provider_id, challenge, created_at)
+    states: Mutex<HashMap<String, (String, LoginChallenge, Instant)>>,
 }
 
 impl OidcStateStore {
@@ -226,6 +327,12 @@ impl OidcStateStore {
     /// Generate a cryptographically random state token and store it.
     /// Returns the generated token.
     pub fn generate(&self, provider_id: &str) -> String {
+        self.begin(provider_id).0
+    }
+
+    /// Start a login: a state token and the PKCE verifier and nonce that
+    /// go with it.
+    pub fn begin(&self, provider_id: &str) -> (String, LoginChallenge) {
         use rand::Rng;
         use std::fmt::Write;
         let buf: [u8; 32] = rand::thread_rng().gen();
@@ -241,37 +348,109 @@ impl OidcStateStore {
 
         // Purge expired entries while we have the lock
         let now = Instant::now();
-        states.retain(|_, (_, ts)| now.duration_since(*ts) < self.ttl);
+        states.retain(|_, (_, _, ts)| now.duration_since(*ts) < self.ttl);
 
-        states.insert(token.clone(), (provider_id.to_owned(), now));
-        token
+        let challenge = LoginChallenge::generate();
+        states.insert(
+            token.clone(),
+            (provider_id.to_owned(), challenge.clone(), now),
+        );
+        (token, challenge)
     }
 
     /// Validate and consume a state token. Returns the provider_id if valid.
     /// The token is removed on successful validation (single-use).
     pub fn validate(&self, state_token: &str) -> Option<String> {
+        self.complete(state_token)
+            .map(|(provider_id, _)| provider_id)
+    }
+
+    /// Validate and consume a state token, returning the provider_id and
+    /// the login's challenge.
+    pub fn complete(&self, state_token: &str) -> Option<(String, LoginChallenge)> {
         let mut states = self
             .states
             .lock()
             .unwrap_or_else(std::sync::PoisonError::into_inner);
 
         let now = Instant::now();
-        states.retain(|_, (_, ts)| now.duration_since(*ts) < self.ttl);
+        states.retain(|_, (_, _, ts)| now.duration_since(*ts) < self.ttl);
 
-        states.remove(state_token).map(|(provider_id, _)| provider_id)
+        states
+            .remove(state_token)
+            .map(|(provider_id, challenge, _)| (provider_id, challenge))
     }
 }
 
-fn client_key_from_headers(headers: &HeaderMap) -> String {
-    for header_name in ["X-Forwarded-For", "X-Real-IP"] {
-        if let Some(value) = headers.get(header_name).and_then(|v| v.to_str().ok()) {
-            let first = value.split(',').next().unwrap_or("").trim();
-            if !first.is_empty() {
-                return first.to_owned();
-            }
-        }
+/// Key identifying a client for rate limits and the authentication lockout:
+/// the connection's peer address. Only a peer listed in
+/// `[gateway] trusted_proxies` may name the client through `X-Forwarded-For`
+/// (its rightmost untrusted entry) or `X-Real-IP`, so a client cannot pick
+/// its own key.
+pub(crate) fn client_key(
+    peer: Option<SocketAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpAddr],
+) -> String {
+    let Some(peer) = peer.map(|addr| addr.ip()) else {
+        return "unknown".into();
+    };
+    if !trusted_proxies.contains(&peer) {
+        return peer.to_string();
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let forwarded = header("X-Forwarded-For").and_then(|value| {
+        value
+            .rsplit(',')
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+            .find(|hop| {
+                hop.parse::<IpAddr>()
+                    .map_or(true, |ip| !trusted_proxies.contains(&ip))
+            })
+    });
+    forwarded
+        .or_else(|| header("X-Real-IP").map(str::trim).filter(|v| !v.is_empty()))
+        .map_or_else(|| peer.to_string(), str::to_owned)
+}
+
+/// Extractor for the request's [`client_key`].
+pub(crate) struct ClientKey(pub String);
+
+impl FromRequestParts<AppState> for ClientKey {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self(client_key_of(parts, state)))
     }
-    "unknown".into()
+}
+
+/// [`client_key`] of a request, from the peer address axum's
+/// `ConnectInfo` recorded and the request headers.
+pub(crate) fn client_key_of(parts: &Parts, state: &AppState) -> String {
+    let peer = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    client_key(peer, &parts.headers, &state.trusted_proxies)
+}
+
+/// Parse `[gateway] trusted_proxies`, skipping (and warning about) entries
+/// that are not IP addresses.
+fn parse_trusted_proxies(entries: &[String]) -> Vec<IpAddr> {
+    entries
+        .iter()
+        .filter_map(|entry| match entry.trim().parse() {
+            Ok(ip) => Some(ip),
+            Err(_) => {
+                tracing::warn!("Ignoring trusted proxy '{entry}': not an IP address");
+                None
+            }
+        })
+        .collect()
 }
 
 /// Shared state for all axum handlers
@@ -279,35 +458,93 @@ fn client_key_from_headers(headers: &HeaderMap) -> String {
 pub struct AppState {
     pub provider: Arc<dyn Provider>,
     pub observer: Arc<dyn Observer>,
-    pub tools_registry: Arc<Vec<Box<dyn Tool>>>,
+    /// Live tools; MCP servers added via `/api/mcp/servers` extend it.
+    pub tools_registry: Arc<tools::ToolRegistry>,
     pub system_prompt: Arc<tokio::sync::RwLock<String>>,
-    pub model: Arc<tokio::sync::RwLock<String>>,
+    /// Default model and the routes of each task class (`[[model_routes]]`)
+    pub models: Arc<ModelRouter>,
     pub temperature: Arc<tokio::sync::RwLock<f64>>,
     pub mem: Arc<dyn Memory>,
     pub auto_save: bool,
     pub webhook_secret: Option<Arc<str>>,
     pub pairing: Arc<PairingGuard>,
     pub rate_limiter: Arc<GatewayRateLimiter>,
+    /// Failed bearer tokens per client (`[gateway.auth_lockout]`).
+    pub auth_lockout: Arc<crate::security::lockout::AuthLockout>,
+    /// Reverse proxies allowed to forward the client address (`[gateway] trusted_proxies`)
+    pub trusted_proxies: Arc<[IpAddr]>,
     pub idempotency_store: Arc<IdempotencyStore>,
+    /// Chat messages already answered, shared with the channel listeners
+    pub seen_messages: Arc<crate::channels::dedup::SeenMessages>,
+    /// Queue of inbound WhatsApp, Telegram and Discord messages
+    pub router: ChannelRouter,
     pub whatsapp: Option<Arc<WhatsAppChannel>>,
     /// `WhatsApp` app secret for webhook signature verification (`X-Hub-Signature-256`)
     pub whatsapp_app_secret: Option<Arc<str>>,
+    /// Telegram bot whose updates the gateway receives (polling or webhook mode)
+    pub telegram: Option<Arc<telegram::TelegramBot>>,
+    /// Discord bot whose connection the gateway keeps (`gateway = true`)
+    pub discord: Option<Arc<DiscordChannel>>,
     pub soul: Arc<tokio::sync::Mutex<crate::identity::Soul>>,
     pub voice_echo_enabled: Arc<std::sync::atomic::AtomicBool>,
     pub identity_config: Arc<crate::config::IdentityConfig>,
     pub vpn_manager: Arc<crate::network::VpnManager>,
+    /// Encrypted per-profile Sovereign Browser history and bookmarks.
+    pub browsing: Arc<crate::network::BrowsingStore>,
+    /// Per-role web content filter (`[family.content_filter]`).
+    pub content_filter: Arc<crate::network::ContentFilter>,
+    /// Family members' daily usage against `[family.quotas]`.
+    pub quotas: Arc<crate::security::quotas::UsageQuotas>,
     pub vault: Arc<crate::security::VaultManager>,
     pub audit: Arc<crate::security::AuditLogger>,
     pub adblock: Arc<crate::network::adblock::DnsBlocker>,
     pub stt: Arc<dyn crate::providers::stt::SttProvider>,
+    /// Speech synthesis for voice replies (`None` when `[tts]` is disabled).
+    pub tts: Option<Arc<dyn crate::providers::tts::TtsProvider>>,
     pub public_url: String,
     pub oidc_states: Arc<OidcStateStore>,
+    /// Refresh tokens of linked OIDC identities
+    pub oidc_tokens: Arc<crate::identity::oidc_tokens::OidcTokenStore>,
+    /// Challenges handed to SSI wallets for presentations
+    pub vp_challenges: Arc<crate::identity::ssi::ChallengeStore>,
+    /// Expiring guest share links (`/api/share`, `/api/guest/*`).
+    pub share_tokens: Arc<crate::security::share::ShareTokenStore>,
+    /// Paired devices and their expiring tokens
+    pub devices: Arc<crate::security::devices::DeviceRegistry>,
+    /// Root's TOTP second factor for Root-level actions
+    pub totp: Arc<crate::security::totp::TotpStore>,
+    /// Root's passkeys, the other second factor for Root-level actions
+    #[cfg(feature = "passkeys")]
+    pub passkeys: Arc<crate::identity::passkey::PasskeyStore>,
+    /// Minimum role per API route, enforced as middleware
+    pub route_policy: Arc<crate::security::route_policy::RoutePolicy>,
     pub workspace_dir: std::path::PathBuf,
     pub config: Arc<tokio::sync::RwLock<Config>>,
     /// Monotonic start instant for uptime calculation.
     pub started_at: std::time::Instant,
     /// Confirmation gate for interactive approval flow.
     pub confirm_gate: Arc<crate::security::confirmation::ConfirmationGate>,
+    /// Pushes confirmations to `WhatsApp` and resolves them from replies
+    /// (`None` when no number receives them).
+    pub confirm_relay: Option<Arc<crate::channels::confirm_relay::ConfirmationRelay>>,
+    /// Timings of the most recent voice turn (surfaced in `/api/system/status`).
+    pub last_voice_turn: Arc<Mutex<Option<api::types::VoiceTurnLatency>>>,
+    /// Running chat turns, cancellable by conversation id (`/api/agent/cancel/{id}`).
+    pub agent_runs: Arc<crate::agent::cancel::AgentRuns>,
+    /// Concurrency limit and priority queue shared by all agent entry points.
+    pub executor: Arc<crate::agent::executor::AgentExecutor>,
+    /// Iteration, token and wall-time limits for each agent turn (`[agent]`).
+    pub budget: crate::agent::budget::LoopBudget,
+    /// Plan-execute settings; `None` in direct mode (`[agent] mode`).
+    pub planner: Option<crate::agent::loop_::Planner>,
+    /// Per-request tool and skill selection (`[agent.tool_selection]`).
+    pub tool_router: Option<Arc<crate::agent::tool_router::ToolRouter>>,
+    /// Connected MCP servers (`/api/mcp/prompts`, `/api/mcp/resources`).
+    pub mcp: Arc<crate::mcp::McpRegistry>,
+    /// SIGIL policy of the gateway, applied to hot-added MCP tools.
+    pub security: Arc<SecurityPolicy>,
+    /// Panic button (`/api/system/panic`).
+    pub kill_switch: Arc<kill_switch::KillSwitch>,
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
@@ -330,10 +567,16 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
 
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
+    let model = config
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
+    let models = Arc::new(ModelRouter::new(model.as_str(), &config.model_routes));
+    let provider: Arc<dyn Provider> = Arc::from(providers::create_routed_provider(
         config.default_provider.as_deref().unwrap_or("openrouter"),
         config.api_key.as_deref(),
         &config.reliability,
+        &models,
     )?);
 
     let stt_key = providers::resolve_api_key(&config.stt.provider, config.api_key.as_deref())
@@ -343,22 +586,33 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         &stt_key,
         config.stt.model.clone(),
     )?);
-    let model = config
-        .default_model
-        .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
+    let tts: Option<Arc<dyn crate::providers::tts::TtsProvider>> = if config.tts.enabled {
+        let tts_key = providers::resolve_api_key(&config.tts.provider, config.api_key.as_deref());
+        Some(Arc::from(crate::providers::tts::create_tts_provider(
+            &config.tts.provider,
+            tts_key.as_deref(),
+            config.tts.model.clone(),
+            config.tts.voice.clone(),
+        )?))
+    } else {
+        None
+    };
     let temperature = config.default_temperature;
     let audit: Arc<crate::security::AuditLogger> = Arc::new(
         crate::security::AuditLogger::new(config.security.audit.clone(), config.workspace_dir.clone())?,
     );
+    crate::network::egress::install_from_config(&config.security.egress, Some(audit.clone()));
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
+        &config.security.sensitivity,
         &config.workspace_dir,
         config.api_key.as_deref(),
         Arc::clone(&audit),
     )?);
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
+    let observer: Arc<dyn Observer> = Arc::from(observability::create_observer(
+        &config.observability,
+        &config.workspace_dir,
+    ));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let adblock = Arc::new(crate::network::adblock::DnsBlocker::new());
@@ -383,6 +637,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             actor_name = None;
         }
     }
+    security.leak_guard = Some(Arc::new(crate::security::leak_guard::LeakGuard::from_config(&config)?));
     let security = Arc::new(security);
 
     let composio_key = if config.composio.enabled {
@@ -392,42 +647,71 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     };
 
     // Discover MCP tools from configured servers
-    let mcp_tools = crate::mcp::discover_mcp_tools(
+    let mcp = crate::mcp::discover_mcp_tools(
         &config.mcp,
         &security,
         &audit,
     ).await;
+    let mcp_tools = mcp.tools;
 
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    // Created before the tools so `email_send` can ask the dashboard for approval
+    // Shared with the channel server so chat replies can resolve requests
+    let confirm_gate = crate::security::confirmation::ConfirmationGate::shared(
+        config.security.confirmation.timeout_secs,
+    );
+    let provider = providers::guarded::GuardedProvider::wrap(
+        provider,
+        providers::guarded::all_local(&config, config.default_provider.as_deref().unwrap_or("openrouter")),
+        security.leak_guard.clone(),
+        Some(Arc::clone(&confirm_gate)),
+        Some(Arc::clone(&audit)),
+    );
+    let tools_registry = Arc::new(tools::ToolRegistry::new(tools::all_tools_with_runtime(
         &security,
         runtime,
         Arc::clone(&mem),
         composio_key,
         &config.browser,
         &config.http_request,
+        &routed_vision(&config),
+        &config.db_query,
+        &config.feeds,
+        &config.plugins,
+        &config.reliability,
         &config.workspace_dir,
         &config.agents,
         config.api_key.as_deref(),
         mcp_tools,
         Some(Arc::clone(&audit)),
         actor_name,
-    ));
+        Some(Arc::clone(&confirm_gate)),
+    )));
+    let initial_tools = tools_registry.snapshot();
     let skills = crate::skills::load_skills(&config.workspace_dir);
-    let tool_descs: Vec<(&str, &str)> = tools_registry
+    let tool_router =
+        crate::agent::tool_router::ToolRouter::from_config(&config, skills.clone()).map(Arc::new);
+    let tool_descs: Vec<(&str, &str)> = initial_tools
         .iter()
         .map(|tool| (tool.name(), tool.description()))
         .collect();
+    let (tool_descs, listed_skills) = crate::agent::tool_router::ToolRouter::prompt_inputs(
+        tool_router.as_deref(),
+        tool_descs,
+        &skills,
+    );
 
     let mut system_prompt = crate::channels::build_system_prompt(
         &config.workspace_dir,
         &model,
         &tool_descs,
-        &skills,
+        listed_skills,
         Some(&config.identity),
     );
-    system_prompt.push_str(&crate::agent::loop_::build_tool_instructions(
-        tools_registry.as_ref(),
-    ));
+    if !provider.supports_native_tools() && tool_router.is_none() {
+        system_prompt.push_str(&crate::agent::loop_::build_tool_instructions(
+            initial_tools.as_slice(),
+        ));
+    }
     let system_prompt = Arc::new(tokio::sync::RwLock::new(system_prompt));
 
     // Extract webhook secret for authentication
@@ -449,6 +733,67 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             ))
         });
 
+    // Inbound chat messages, answered by `handle_channel_message`
+    let (router, router_rx) = ChannelRouter::new();
+
+    // Telegram bot, when its updates come to the gateway
+    let telegram_bot = config
+        .channels_config
+        .telegram
+        .as_ref()
+        .filter(|tg| tg.mode != crate::config::TelegramMode::Channels)
+        .map(|tg| {
+            let channel = TelegramChannel::new(tg.bot_token.clone(), tg.allowed_users.clone())
+                .with_voice_messages();
+            Arc::new(telegram::TelegramBot::new(
+                Arc::new(channel),
+                tg.mode,
+                router.sender(),
+            ))
+        });
+
+    // Discord bot, when the gateway keeps its connection
+    let discord_channel = config
+        .channels_config
+        .discord
+        .as_ref()
+        .filter(|dc| dc.gateway)
+        .map(|dc| {
+            let channel = DiscordChannel::new(
+                dc.bot_token.clone(),
+                dc.guild_id.clone(),
+                dc.allowed_users.clone(),
+                dc.listen_to_bots,
+            )
+            .with_allowlists(dc.allowed_guilds.clone(), dc.allowed_channels.clone())
+            .with_conversations();
+            Arc::new(channel)
+        });
+
+    // WhatsApp replies arrive at this gateway's webhook, and so do Telegram
+    // updates when it runs the bot, so it delivers the confirmation
+    // requests there.
+    let mut relay =
+        crate::channels::confirm_relay::ConfirmationRelay::new(Arc::clone(&confirm_gate))
+            .with_family(Arc::new(FamilyRegistry::from_config(&config.family)));
+    if let Some(wa) = &whatsapp_channel {
+        relay = relay.with_target(
+            Arc::clone(wa) as Arc<dyn Channel>,
+            config.security.confirmation.whatsapp_numbers.clone(),
+        );
+    }
+    if let Some(bot) = &telegram_bot {
+        relay = relay.with_target(
+            Arc::clone(&bot.channel) as Arc<dyn Channel>,
+            config.security.confirmation.telegram_chats.clone(),
+        );
+    }
+    let confirm_relay = (!relay.is_empty()).then(|| {
+        let relay = Arc::new(relay);
+        relay.spawn();
+        relay
+    });
+
     // WhatsApp app secret for webhook signature verification
     // Priority: environment variable > config file
     let whatsapp_app_secret: Option<Arc<str>> = std::env::var("MYMOLT_WHATSAPP_APP_SECRET")
@@ -482,7 +827,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     )));
 
     // ── Tunnel ────────────────────────────────────────────────
-    let tunnel = crate::tunnel::create_tunnel(&config.tunnel)?;
+    let tunnel: Option<Arc<dyn crate::tunnel::Tunnel>> =
+        crate::tunnel::create_tunnel(&config.tunnel)?.map(Arc::from);
     let mut tunnel_url: Option<String> = None;
 
     if let Some(ref tun) = tunnel {
@@ -509,6 +855,12 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         println!("  GET  /whatsapp  — Meta webhook verification");
         println!("  POST /whatsapp  — WhatsApp message webhook");
     }
+    if telegram_bot
+        .as_ref()
+        .is_some_and(|bot| bot.mode() == crate::config::TelegramMode::Webhook)
+    {
+        println!("  POST /telegram  — Telegram bot webhook");
+    }
     println!("  GET  /health    — health check");
     if let Some(code) = pairing.pairing_code() {
         println!();
@@ -529,24 +881,82 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 
     crate::health::mark_component_ok("gateway");
 
+    let agent_runs = Arc::new(crate::agent::cancel::AgentRuns::new());
+    let vpn_manager = Arc::new(crate::network::VpnManager::new(
+        &config.workspace_dir.join("network").join("wg0.conf"),
+    ));
+    let devices = Arc::new(crate::security::devices::DeviceRegistry::new(
+        &config.workspace_dir,
+        config.gateway.device_token_ttl_secs,
+        config.gateway.device_refresh_ttl_secs,
+    )?);
+    adopt_legacy_tokens(&pairing, &devices, &shared_config).await?;
+    let kill_switch = Arc::new(kill_switch::KillSwitch {
+        stop: Arc::clone(crate::security::emergency::global()),
+        agent_runs: Arc::clone(&agent_runs),
+        mcp: Arc::clone(&mcp.registry),
+        tools_registry: Arc::clone(&tools_registry),
+        system_prompt: Arc::clone(&system_prompt),
+        tunnel,
+        vpn: Arc::clone(&vpn_manager),
+        pairing: Arc::clone(&pairing),
+        devices: Arc::clone(&devices),
+        config: Arc::clone(&shared_config),
+        audit: Arc::clone(&audit),
+    });
+
+    // Use tunnel URL if available, otherwise host:port
+    let public_url = tunnel_url.unwrap_or_else(|| format!("http://{display_addr}"));
+    #[cfg(feature = "passkeys")]
+    let passkeys = Arc::new(crate::identity::passkey::PasskeyStore::new(
+        &config.workspace_dir,
+        config
+            .identity
+            .passkey_origin
+            .as_deref()
+            .unwrap_or(&public_url),
+    ));
+    #[cfg(not(feature = "passkeys"))]
+    if config.identity.passkey_origin.is_some() {
+        tracing::warn!(
+            "[identity] passkey_origin is set, but passkeys are not compiled in (build with --features passkeys)"
+        );
+    }
+
     // Build shared state
     let state = AppState {
         provider,
         observer,
         tools_registry,
         system_prompt,
-        model: Arc::new(tokio::sync::RwLock::new(model)),
+        models,
         temperature: Arc::new(tokio::sync::RwLock::new(temperature)),
         mem,
         auto_save: config.memory.auto_save,
         webhook_secret,
         pairing,
         rate_limiter,
+        auth_lockout: Arc::new(crate::security::lockout::AuthLockout::new(
+            config.gateway.auth_lockout.clone(),
+        )),
+        trusted_proxies: parse_trusted_proxies(&config.gateway.trusted_proxies).into(),
         idempotency_store,
+        seen_messages: crate::channels::dedup::SeenMessages::shared(
+            &config.workspace_dir,
+            Duration::from_secs(config.channels_config.message_dedup_ttl_secs),
+        ),
+        router,
         whatsapp: whatsapp_channel,
         whatsapp_app_secret,
+        telegram: telegram_bot,
+        discord: discord_channel,
         soul: Arc::new(tokio::sync::Mutex::new({
-            let mut s = crate::identity::Soul::new(&config.workspace_dir);
+            let secrets = crate::security::SecretStore::new(
+                &config.workspace_dir.join(".mymolt"),
+                config.identity.encrypt_diary,
+            )
+            .with_backend(config.secrets.key_backend);
+            let mut s = crate::identity::Soul::new(&config.workspace_dir).with_secrets(secrets);
             if let Err(e) = s.load() {
                 tracing::warn!("Failed to load Soul: {e}");
             }
@@ -554,22 +964,123 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         })),
         voice_echo_enabled: Arc::new(std::sync::atomic::AtomicBool::new(false)),
         identity_config: Arc::new(config.identity),
-        vpn_manager: Arc::new(crate::network::VpnManager::new(
-            &config.workspace_dir.join("network").join("wg0.conf")
+        vpn_manager,
+        browsing: Arc::new(crate::network::BrowsingStore::new(&config.workspace_dir)),
+        content_filter: Arc::new(crate::network::ContentFilter::new(
+            config.family.content_filter.clone(),
         )),
-        vault: Arc::new(crate::security::VaultManager::new(&config.workspace_dir)),
+        quotas: Arc::new(crate::security::quotas::UsageQuotas::new()),
+        vault: Arc::new(
+            crate::security::VaultManager::new(&config.workspace_dir)
+                .with_key_backend(config.secrets.key_backend),
+        ),
         audit,
         adblock,
         stt,
-        // Use tunnel URL if available, otherwise host:port
-        public_url: tunnel_url.unwrap_or_else(|| format!("http://{display_addr}")),
+        tts,
+        public_url,
         oidc_states: Arc::new(OidcStateStore::new(Duration::from_secs(600))), // 10 min TTL
+        oidc_tokens: Arc::new(crate::identity::oidc_tokens::OidcTokenStore::new(
+            &config.workspace_dir,
+            crate::security::SecretStore::new(&config.workspace_dir.join(".mymolt"), true)
+                .with_backend(config.secrets.key_backend),
+        )),
+        vp_challenges: Arc::new(crate::identity::ssi::ChallengeStore::new(
+            Duration::from_mins(10),
+        )),
+        share_tokens: Arc::new(crate::security::share::ShareTokenStore::new(&config.workspace_dir)),
+        devices,
+        totp: Arc::new(crate::security::totp::TotpStore::new(
+            &config.workspace_dir,
+            crate::security::SecretStore::new(&config.workspace_dir.join(".mymolt"), true)
+                .with_backend(config.secrets.key_backend),
+        )?),
+        #[cfg(feature = "passkeys")]
+        passkeys,
+        route_policy: Arc::new(crate::security::route_policy::RoutePolicy::new(
+            &config.gateway.route_policy,
+        )),
         workspace_dir: config.workspace_dir.clone(),
         config: Arc::clone(&shared_config),
         started_at: std::time::Instant::now(),
-        confirm_gate: crate::security::confirmation::ConfirmationGate::new(30),
+        confirm_gate,
+        confirm_relay,
+        last_voice_turn: Arc::new(Mutex::new(None)),
+        agent_runs,
+        executor: Arc::new(crate::agent::executor::AgentExecutor::new(&config.executor)),
+        budget: crate::agent::budget::LoopBudget::from_config(&config.agent),
+        planner: crate::agent::loop_::Planner::from_config(&config.agent, Arc::clone(&security)),
+        tool_router,
+        mcp: mcp.registry,
+        security: Arc::clone(&security),
+        kill_switch,
     };
 
+    let feed_digest = crate::tools::feeds::digest_automation(&config.feeds);
+    if let Some(rule) = &feed_digest {
+        if let Err(e) = rule.validate() {
+            tracing::warn!("Feed digest disabled: {e}");
+        }
+    }
+    if config.automations.enabled || feed_digest.is_some() {
+        let channels = crate::channels::configured_channels(&*state.config.read().await)
+            .into_iter()
+            .map(|channel| (channel.name().to_lowercase(), channel))
+            .collect();
+        let engine = Arc::new(crate::automations::engine::AutomationEngine {
+            config: Arc::clone(&state.config),
+            workspace_dir: state.workspace_dir.clone(),
+            provider: Arc::clone(&state.provider),
+            observer: Arc::clone(&state.observer),
+            tools: Arc::clone(&state.tools_registry),
+            system_prompt: Arc::clone(&state.system_prompt),
+            models: Arc::clone(&state.models),
+            temperature: Arc::clone(&state.temperature),
+            security: Arc::clone(&security),
+            audit: Arc::clone(&state.audit),
+            executor: Arc::clone(&state.executor),
+            budget: state.budget,
+            planner: state.planner.clone(),
+            tool_router: state.tool_router.clone(),
+            channels,
+        });
+        tokio::spawn(engine.run());
+        println!("  ⚙️  Automations enabled");
+    }
+    // Agent turns for the chat channels
+    let channel_state = state.clone();
+    tokio::spawn(route(
+        router_rx,
+        Arc::clone(&state.seen_messages),
+        config.executor.channel_concurrency,
+        move |msg| handle_channel_message(channel_state.clone(), msg),
+    ));
+    if let Some(bot) = &state.telegram {
+        match bot.start(&state.public_url).await {
+            Ok(()) => println!("  ✈️  Telegram bot enabled ({:?} mode)", bot.mode()),
+            Err(e) => println!("⚠️  Telegram bot failed to start: {e}"),
+        }
+    }
+    if let Some(dc) = &state.discord {
+        discord::spawn(Arc::clone(dc), state.router.sender());
+        println!("  🎮 Discord bot enabled");
+    }
+    if !state.identity_config.providers.is_empty() {
+        // Keep linked identities' refresh tokens alive
+        let (tokens, identity) = (
+            Arc::clone(&state.oidc_tokens),
+            Arc::clone(&state.identity_config),
+        );
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(crate::identity::oidc_tokens::REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                crate::identity::oidc_tokens::refresh_all(&tokens, &identity.providers).await;
+            }
+        });
+    }
+
 
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::{CorsLayer, AllowOrigin};
@@ -621,7 +1132,8 @@ use axum::http::HeaderValue;
         .route("/webhook", post(handle_webhook))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
-        .merge(api::routes()) // Merge API routes
+        .route("/telegram", post(telegram::handle_webhook))
+        .merge(api_routes(&state)) // Merge API routes
         .with_state(state)
         .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
         .layer(TimeoutLayer::with_status_code(
@@ -639,7 +1151,12 @@ use axum::http::HeaderValue;
             .not_found_service(ServeFile::new("frontend/dist/index.html"))
     );
 
-    axum::serve(listener, app).await?;
+    // Peer addresses key rate limits and the lockout (see `client_key`)
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -652,16 +1169,59 @@ use axum::http::HeaderValue;
 async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     let body = serde_json::json!({
         "status": "ok",
-        "paired": state.pairing.is_paired(),
+        "paired": state.pairing.is_paired() || state.devices.has_active(),
         "pairing_enabled": state.pairing.require_pairing(),
         "runtime": crate::health::snapshot_json(),
+        "providers": crate::providers::circuit::snapshot(),
     });
     Json(body)
 }
 
-/// POST /pair — exchange one-time code for bearer token
-async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
-    let client_key = client_key_from_headers(&headers);
+/// Move `[gateway] paired_tokens` into the device registry, where they
+/// expire and can be revoked, and drop them from the pairing guard and
+/// config. With pairing disabled the guard keeps accepting any token.
+async fn adopt_legacy_tokens(
+    pairing: &PairingGuard,
+    devices: &crate::security::devices::DeviceRegistry,
+    config: &tokio::sync::RwLock<Config>,
+) -> Result<()> {
+    let legacy = pairing.tokens();
+    if legacy.is_empty() {
+        return Ok(());
+    }
+    let adopted = devices.adopt_legacy(&legacy)?;
+    pairing.clear_tokens();
+    if adopted > 0 {
+        tracing::warn!(
+            "🔐 Moved {adopted} legacy paired token(s) to the device registry; they expire \
+             like device tokens, so pair those clients again"
+        );
+    }
+    let mut config = config.write().await;
+    config.gateway.paired_tokens.clear();
+    config
+        .save()
+        .context("Failed to drop adopted paired tokens from config")
+}
+
+/// The API routes behind the role-to-route matrix, which is checked before
+/// their handlers run.
+fn api_routes(state: &AppState) -> Router<AppState> {
+    api::routes().route_layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        api::auth::enforce_route_policy,
+    ))
+}
+
+/// Name for a device that pairs without an `X-Device-Name` header.
+const DEFAULT_DEVICE_NAME: &str = "Paired device";
+
+/// POST /pair — exchange one-time code for a device's bearer and refresh tokens
+async fn handle_pair(
+    State(state): State<AppState>,
+    ClientKey(client_key): ClientKey,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     if !state.rate_limiter.allow_pair(&client_key) {
         tracing::warn!("/pair rate limit exceeded for key: {client_key}");
         let err = serde_json::json!({
@@ -676,17 +1236,37 @@ async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
 
-    match state.pairing.try_pair(code) {
-        Ok(Some(token)) => {
-            tracing::info!("🔐 New client paired successfully");
+    match state.pairing.redeem_code(code) {
+        Ok(true) => {
+            let name = headers
+                .get("X-Device-Name")
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(DEFAULT_DEVICE_NAME);
+            let issued = match state.devices.register(name, vec!["root".into()]) {
+                Ok(issued) => issued,
+                Err(e) => {
+                    tracing::error!("Failed to register paired device: {e}");
+                    let err = serde_json::json!({"error": "Failed to register device"});
+                    return (StatusCode::INTERNAL_SERVER_ERROR, Json(err));
+                }
+            };
+            tracing::info!("🔐 New device paired successfully: {name}");
+            // Pairing after the panic button is what releases the emergency stop.
+            let resumed = state.kill_switch.resume();
             let body = serde_json::json!({
                 "paired": true,
-                "token": token,
-                "message": "Save this token — use it as Authorization: Bearer <token>"
+                "token": issued.token,
+                "refresh_token": issued.refresh_token,
+                "expires_at": issued.device.expires_at.to_rfc3339(),
+                "device_id": issued.device.id,
+                "resumed": resumed,
+                "message": "Save these tokens — use the token as Authorization: Bearer <token> and renew it at POST /api/devices/refresh before it expires"
             });
             (StatusCode::OK, Json(body))
         }
-        Ok(None) => {
+        Ok(false) => {
             tracing::warn!("🔐 Pairing attempt with invalid code");
             let err = serde_json::json!({"error": "Invalid pairing code"});
             (StatusCode::FORBIDDEN, Json(err))
@@ -713,10 +1293,10 @@ pub struct WebhookBody {
 /// POST /webhook — main webhook endpoint
 async fn handle_webhook(
     State(state): State<AppState>,
+    ClientKey(client_key): ClientKey,
     headers: HeaderMap,
     body: Result<Json<WebhookBody>, axum::extract::rejection::JsonRejection>,
 ) -> impl IntoResponse {
-    let client_key = client_key_from_headers(&headers);
     if !state.rate_limiter.allow_webhook(&client_key) {
         tracing::warn!("/webhook rate limit exceeded for key: {client_key}");
         let err = serde_json::json!({
@@ -727,19 +1307,24 @@ async fn handle_webhook(
     }
 
     // ── Bearer token auth (pairing) ──
+    // A family member's session token runs the message as that member
+    let mut session_member = None;
+    let mut role = UserRole::Root;
     if state.pairing.require_pairing() {
         let auth = headers
             .get(header::AUTHORIZATION)
             .and_then(|v| v.to_str().ok())
             .unwrap_or("");
         let token = auth.strip_prefix("Bearer ").unwrap_or("");
-        if !state.pairing.is_authenticated(token) {
+        let Some(user) = api::auth::authenticate(&state, token) else {
             tracing::warn!("Webhook: rejected — not paired / invalid bearer token");
             let err = serde_json::json!({
                 "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
             });
             return (StatusCode::UNAUTHORIZED, Json(err));
-        }
+        };
+        session_member = user.member;
+        role = user.role;
     }
 
     // ── Webhook secret auth (optional, additional layer) ──
@@ -786,23 +1371,85 @@ async fn handle_webhook(
         }
     }
 
+    // ── Daily quota of a member's session ──
+    let quota = match &session_member {
+        Some(member) => {
+            let config = state.config.read().await;
+            Some(state.quotas.for_member(&config.family, member, role))
+        }
+        None => None,
+    };
+    if let Some(Err(exceeded)) = quota.as_ref().map(|q| q.charge(Quota::ChatTurns)) {
+        tracing::info!("Webhook: {exceeded}");
+        // A child gets a gentle answer rather than an error
+        let (status, key) = if role > UserRole::Child {
+            (StatusCode::TOO_MANY_REQUESTS, "error")
+        } else {
+            (StatusCode::OK, "response")
+        };
+        let body = serde_json::json!({ key: exceeded.reply(), "quota_exceeded": exceeded });
+        return (status, Json(body));
+    }
+
     let message = &webhook_body.message;
 
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: "webhook".into(),
+        sender: client_key.clone(),
+        text: message.clone(),
+    });
+
+    // ── Admission (bounded queue) ──
+    let ticket = match state.executor.submit("webhook") {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            tracing::warn!("Webhook rejected: {e}");
+            let err = serde_json::json!({
+                "error": "Agent is busy. Please retry later.",
+                "queued": e.queued,
+            });
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(err));
+        }
+    };
+    let queue_position = ticket.position();
+    let _permit = ticket.ready().await;
+
+    // The memory scope follows the authenticated member, never the body;
+    // the owner's devices get the owner's view
+    let sender = session_member.as_deref().unwrap_or(&client_key);
+    let scope = session_member.as_deref().map(member_scope);
     if state.auto_save {
         let key = webhook_memory_key();
-        let _ = state
-            .mem
+        let _ = scoped::confine(&state.mem, scope.clone())
             .store(&key, message, MemoryCategory::Conversation)
             .await;
     }
 
-    match gateway_agent_reply(&state, message).await {
+    let session = format!("webhook:{sender}");
+    match gateway_agent_reply(&state, message, session, scope, role, quota).await {
         Ok(reply) => {
-            let model = state.model.read().await.clone();
-            let body = serde_json::json!({"response": reply, "model": model});
+            let model = state.models.default_model();
+            let body = serde_json::json!({
+                "response": reply,
+                "model": model,
+                "queue_position": queue_position,
+            });
             (StatusCode::OK, Json(body))
         }
         Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<BudgetExceeded>() {
+                tracing::warn!("Webhook run stopped: {exceeded}");
+                let body = serde_json::json!({
+                    "error": "Agent budget exceeded",
+                    "budget_exceeded": exceeded,
+                });
+                return (StatusCode::UNPROCESSABLE_ENTITY, Json(body));
+            }
+            if let Some(rejected) = e.downcast_ref::<PlanRejected>() {
+                tracing::warn!("Webhook run stopped: {rejected}");
+                let err = serde_json::json!({"error": rejected.to_string()});
+                return (StatusCode::FORBIDDEN, Json(err));
+            }
             tracing::error!(
                 "Webhook provider error: {}",
                 providers::sanitize_api_error(&e.to_string())
@@ -921,48 +1568,22 @@ async fn handle_whatsapp_message(
         );
     };
 
-    // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
+    // Parse messages from the webhook payload, fetching their media
+    let messages = wa.receive(&payload).await;
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status updates)
         return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
     }
 
-    // Process each message
-    for msg in &messages {
-        tracing::info!(
-            "WhatsApp message from {}: {}",
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 50)
-        );
-
-        // Auto-save to memory
-        if state.auto_save {
-            let key = whatsapp_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation)
-                .await;
-        }
-
-        // Call the LLM
-        match gateway_agent_reply(&state, &msg.content).await {
-            Ok(reply) => {
-                // Send reply via WhatsApp
-                if let Err(e) = wa.send(&reply, &msg.sender).await {
-                    tracing::error!("Failed to send WhatsApp reply: {e}");
-                }
-            }
-            Err(e) => {
-                tracing::error!("LLM error for WhatsApp message: {e:#}");
-                let _ = wa
-                    .send(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.sender,
-                    )
-                    .await;
-            }
+    // Answer Meta right away; agent turns run from the channel router
+    for msg in messages {
+        if !state.router.submit(msg) {
+            // Meta delivers the webhook again later
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({"error": "Message queue is full"})),
+            );
         }
     }
 
@@ -1055,6 +1676,8 @@ mod tests {
             content: "hello".into(),
             channel: "whatsapp".into(),
             timestamp: 1,
+            attachments: Vec::new(),
+            group: None,
         };
 
         let key = whatsapp_memory_key(&msg);
@@ -1196,38 +1819,486 @@ mod tests {
             )
             .unwrap(),
         );
+        let pairing = Arc::new(PairingGuard::new(false, &[]));
+        let config = Arc::new(tokio::sync::RwLock::new(crate::config::Config::default()));
+        let devices = Arc::new(
+            crate::security::devices::DeviceRegistry::new(tmp.path(), 3600, 86_400).unwrap(),
+        );
+        let kill_switch = Arc::new(kill_switch::KillSwitch {
+            stop: Arc::new(crate::security::emergency::EmergencyStop::new()),
+            agent_runs: Arc::new(crate::agent::cancel::AgentRuns::new()),
+            mcp: Arc::new(crate::mcp::McpRegistry::default()),
+            tools_registry: Arc::new(tools::ToolRegistry::new(Vec::new())),
+            system_prompt: Arc::new(tokio::sync::RwLock::new(String::new())),
+            tunnel: None,
+            vpn: Arc::new(crate::network::VpnManager::new(tmp.path())),
+            pairing: Arc::clone(&pairing),
+            devices: Arc::clone(&devices),
+            config: Arc::clone(&config),
+            audit: Arc::clone(&audit),
+        });
         AppState {
             provider,
             observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
+            tools_registry: Arc::new(tools::ToolRegistry::new(Vec::new())),
             system_prompt: Arc::new(tokio::sync::RwLock::new("test-system-prompt".into())),
-            model: Arc::new(tokio::sync::RwLock::new("test-model".into())),
+            models: Arc::new(ModelRouter::new("test-model", &[])),
             temperature: Arc::new(tokio::sync::RwLock::new(0.0)),
             mem: memory,
             auto_save,
             webhook_secret: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
+            pairing,
             rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            auth_lockout: Arc::new(crate::security::lockout::AuthLockout::new(
+                crate::config::AuthLockoutConfig::default(),
+            )),
+            trusted_proxies: Arc::from([]),
             idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            seen_messages: crate::channels::dedup::SeenMessages::shared(
+                tmp.path(),
+                Duration::from_mins(5),
+            ),
+            router: ChannelRouter::new().0,
             whatsapp: None,
             whatsapp_app_secret: None,
+            telegram: None,
+            discord: None,
             soul: Arc::new(tokio::sync::Mutex::new(crate::identity::Soul::new(tmp.path()))),
             voice_echo_enabled: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             identity_config: Arc::new(crate::config::IdentityConfig::default()),
             vpn_manager: Arc::new(crate::network::VpnManager::new(tmp.path())),
+            browsing: Arc::new(crate::network::BrowsingStore::new(tmp.path())),
+            content_filter: Arc::new(crate::network::ContentFilter::new(
+                crate::config::ContentFilterConfig::default(),
+            )),
+            quotas: Arc::new(crate::security::quotas::UsageQuotas::new()),
             vault: Arc::new(crate::security::VaultManager::new(tmp.path())),
             audit,
             adblock: Arc::new(crate::network::adblock::DnsBlocker::new()),
             stt: Arc::new(crate::providers::stt::MockSttProvider::new("test transcription")),
+            tts: None,
             public_url: "http://localhost:3000".into(),
             oidc_states: Arc::new(OidcStateStore::new(Duration::from_secs(600))),
+            oidc_tokens: Arc::new(crate::identity::oidc_tokens::OidcTokenStore::new(
+                tmp.path(),
+                crate::security::SecretStore::new(&tmp.path().join(".mymolt"), true),
+            )),
+            vp_challenges: Arc::new(crate::identity::ssi::ChallengeStore::new(
+                Duration::from_mins(10),
+            )),
+            share_tokens: Arc::new(crate::security::share::ShareTokenStore::new(tmp.path())),
+            devices,
+            totp: Arc::new(
+                crate::security::totp::TotpStore::new(
+                    tmp.path(),
+                    crate::security::SecretStore::new(&tmp.path().join(".mymolt"), true),
+                )
+                .unwrap(),
+            ),
+            #[cfg(feature = "passkeys")]
+            passkeys: Arc::new(crate::identity::passkey::PasskeyStore::new(
+                tmp.path(),
+                "http://localhost:3000",
+            )),
+            route_policy: Arc::new(crate::security::route_policy::RoutePolicy::default()),
             workspace_dir: tmp.path().to_path_buf(),
-            config: Arc::new(tokio::sync::RwLock::new(crate::config::Config::default())),
+            config,
             started_at: std::time::Instant::now(),
             confirm_gate: crate::security::confirmation::ConfirmationGate::new(5),
+            confirm_relay: None,
+            last_voice_turn: Arc::new(Mutex::new(None)),
+            agent_runs: Arc::new(crate::agent::cancel::AgentRuns::new()),
+            executor: Arc::new(crate::agent::executor::AgentExecutor::new(
+                &crate::config::ExecutorConfig::default(),
+            )),
+            budget: crate::agent::budget::LoopBudget::default(),
+            planner: None,
+            tool_router: None,
+            mcp: Arc::new(crate::mcp::McpRegistry::default()),
+            security: Arc::new(SecurityPolicy::default()),
+            kill_switch,
         }
     }
 
+    #[tokio::test]
+    async fn repeated_invalid_tokens_lock_the_client_out() {
+        use api::auth::AuthenticatedUser;
+        use axum::extract::FromRequestParts;
+
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_app_state(provider, Arc::new(MockMemory), false);
+        state.pairing = Arc::new(PairingGuard::new(true, &["zc_owner".into()]));
+        let request = |token: &str, forwarded_for: &str| {
+            let mut parts = axum::http::Request::builder()
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header("X-Forwarded-For", forwarded_for)
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0;
+            parts
+                .extensions
+                .insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 40_000))));
+            parts
+        };
+
+        // Rotating the forwarding header does not dodge the lockout
+        for i in 0..5 {
+            let denied = AuthenticatedUser::from_request_parts(
+                &mut request("zc_guess", &format!("198.51.100.{i}")),
+                &state,
+            )
+            .await
+            .err()
+            .unwrap();
+            assert_eq!(denied.0, StatusCode::UNAUTHORIZED);
+        }
+        let locked =
+            AuthenticatedUser::from_request_parts(&mut request("zc_guess", "198.51.100.9"), &state)
+                .await
+                .err()
+                .unwrap();
+        assert_eq!(locked.0, StatusCode::TOO_MANY_REQUESTS);
+        assert!(state.auth_lockout.check("203.0.113.7").is_err());
+    }
+
+    #[tokio::test]
+    async fn valid_token_authenticates_while_client_is_locked_out() {
+        use api::auth::AuthenticatedUser;
+        use axum::extract::FromRequestParts;
+
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_app_state(provider, Arc::new(MockMemory), false);
+        state.pairing = Arc::new(PairingGuard::new(true, &["zc_owner".into()]));
+        let request = |token: &str| {
+            axum::http::Request::builder()
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0
+        };
+        for _ in 0..5 {
+            let _ = AuthenticatedUser::from_request_parts(&mut request("zc_guess"), &state).await;
+        }
+        assert!(state.auth_lockout.check("unknown").is_err());
+
+        let owner = AuthenticatedUser::from_request_parts(&mut request("zc_owner"), &state).await;
+        assert_eq!(owner.ok().unwrap().role, UserRole::Root);
+        let guess = AuthenticatedUser::from_request_parts(&mut request("zc_guess"), &state).await;
+        assert_eq!(guess.err().unwrap().0, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn route_policy_forbids_child_token_on_root_routes() {
+        use tower::ServiceExt;
+
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let state = test_app_state(provider, Arc::new(MockMemory), false);
+        let child = state
+            .devices
+            .register_member("Tablet", "mia", UserRole::Child)
+            .unwrap()
+            .token;
+        let app = api_routes(&state).with_state(state);
+        let request = |method: &str, path: &str| {
+            axum::http::Request::builder()
+                .method(method)
+                .uri(path)
+                .header(header::AUTHORIZATION, format!("Bearer {child}"))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // Root routes in the matrix, and one it does not name
+        for (method, path) in [
+            ("GET", "/api/devices"),
+            ("GET", "/api/family/members"),
+            ("POST", "/api/config/pairing"),
+        ] {
+            let response = app.clone().oneshot(request(method, path)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method} {path}");
+        }
+        // A member route reaches its handler, which finds no such member
+        let response = app.oneshot(request("GET", "/api/quotas/me")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// A state whose family has Maria, PIN 4711, and saves its config in `tmp`.
+    async fn family_state(tmp: &tempfile::TempDir) -> AppState {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let state = test_app_state(provider, Arc::new(MockMemory), false);
+        let mut config = state.config.write().await;
+        config.config_path = tmp.path().join("config.toml");
+        let maria = crate::config::FamilyMemberConfig {
+            name: "Maria".into(),
+            role: "adult".into(),
+            channels: std::collections::HashMap::new(),
+            pin_hash: Some(crate::identity::family::hash_pin("4711")),
+            oidc: None,
+            quotas: None,
+        };
+        config.family.members.push(maria);
+        drop(config);
+        state
+    }
+
+    async fn member_login(
+        state: &AppState,
+        pin: &str,
+    ) -> Result<api::family::MemberSession, (StatusCode, String)> {
+        let payload = api::family::MemberLoginRequest {
+            name: "maria".into(),
+            pin: pin.into(),
+        };
+        api::family::member_login(
+            State(state.clone()),
+            ClientKey("203.0.113.7".into()),
+            HeaderMap::new(),
+            Json(payload),
+        )
+        .await
+        .map(|Json(session)| session)
+    }
+
+    #[tokio::test]
+    async fn member_login_rejects_a_wrong_pin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = family_state(&tmp).await;
+
+        let denied = member_login(&state, "4712").await.err().unwrap();
+        assert_eq!(denied.0, StatusCode::UNAUTHORIZED);
+        let session = member_login(&state, "4711").await.unwrap();
+        assert_eq!(session.member, "Maria");
+        assert_eq!(session.role, UserRole::Adult);
+    }
+
+    #[tokio::test]
+    async fn member_login_locks_out_after_repeated_wrong_pins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = family_state(&tmp).await;
+
+        for _ in 0..5 {
+            let denied = member_login(&state, "0000").await.err().unwrap();
+            assert_eq!(denied.0, StatusCode::UNAUTHORIZED);
+        }
+        // Not even the right PIN gets through while locked out
+        let locked = member_login(&state, "4711").await.err().unwrap();
+        assert_eq!(locked.0, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn member_session_ends_when_role_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = family_state(&tmp).await;
+        let session = member_login(&state, "4711").await.unwrap();
+        assert!(api::auth::authenticate(&state, &session.token).is_some());
+
+        let root = api::auth::AuthenticatedUser {
+            role: UserRole::Root,
+            member: None,
+        };
+        let update = api::family::UpdateFamilyMemberRequest {
+            role: Some("child".into()),
+            channels: None,
+        };
+        api::family::update_family_member(
+            root,
+            State(state.clone()),
+            axum::extract::Path("Maria".into()),
+            Json(update),
+        )
+        .await
+        .unwrap();
+        assert!(api::auth::authenticate(&state, &session.token).is_none());
+        let session = member_login(&state, "4711").await.unwrap();
+        assert_eq!(session.role, UserRole::Child);
+    }
+
+    #[tokio::test]
+    async fn share_links_are_managed_by_their_issuer() {
+        use api::auth::AuthenticatedUser;
+        use api::share::{create_share, list_shares, revoke_share, CreateShareRequest};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_app_state(provider, Arc::new(MockMemory), false);
+        state.share_tokens = Arc::new(crate::security::share::ShareTokenStore::new(tmp.path()));
+        let member = |name: &str| AuthenticatedUser {
+            role: UserRole::Adult,
+            member: Some(name.to_string()),
+        };
+        let root = || AuthenticatedUser {
+            role: UserRole::Root,
+            member: None,
+        };
+        let share = |label: &str| {
+            Json(CreateShareRequest {
+                label: label.into(),
+                collection: Some("recipes".into()),
+                ttl_secs: None,
+                max_uses: None,
+            })
+        };
+        create_share(member("Maria"), State(state.clone()), share("Maria's link"))
+            .await
+            .unwrap();
+        let Json(anna) = create_share(member("Anna"), State(state.clone()), share("Anna's link"))
+            .await
+            .unwrap();
+
+        let Json(seen) = list_shares(member("Maria"), State(state.clone()))
+            .await
+            .unwrap();
+        assert_eq!(
+            seen.iter().map(|v| v.label.as_str()).collect::<Vec<_>>(),
+            ["Maria's link"]
+        );
+        let Json(seen) = list_shares(root(), State(state.clone())).await.unwrap();
+        assert_eq!(seen.len(), 2);
+
+        let anna_link = || axum::extract::Path(anna.grant.id.clone());
+        let denied = revoke_share(member("Maria"), State(state.clone()), anna_link())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(denied.0, StatusCode::NOT_FOUND);
+        assert!(state.share_tokens.list().iter().all(|g| !g.revoked));
+        let revoked = revoke_share(root(), State(state.clone()), anna_link()).await;
+        assert_eq!(revoked.unwrap(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn browsing_history_is_kept_per_person() {
+        use api::auth::AuthenticatedUser;
+        use api::browse::{browse_history, BrowseDataQuery};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = family_state(&tmp).await;
+        let maria = || AuthenticatedUser {
+            role: UserRole::Adult,
+            member: Some("Maria".into()),
+        };
+        let anna = AuthenticatedUser {
+            role: UserRole::Adult,
+            member: Some("Anna".into()),
+        };
+        let owner = AuthenticatedUser {
+            role: UserRole::Root,
+            member: None,
+        };
+        let profile = |name: Option<&str>| {
+            Query(BrowseDataQuery {
+                q: None,
+                profile: name.map(str::to_string),
+            })
+        };
+        state
+            .browsing
+            .record_visit("user:maria", "https://maria.example", "Maria's page")
+            .unwrap();
+
+        let Json(own) = browse_history(maria(), State(state.clone()), profile(None))
+            .await
+            .unwrap();
+        assert_eq!(own.len(), 1);
+        let Json(own) = browse_history(maria(), State(state.clone()), profile(Some("maria")))
+            .await
+            .unwrap();
+        assert_eq!(own.len(), 1);
+        // Another Adult neither shares nor sees Maria's history
+        let Json(other) = browse_history(anna.clone(), State(state.clone()), profile(None))
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+        let denied = browse_history(anna, State(state.clone()), profile(Some("Maria")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(denied.0, StatusCode::FORBIDDEN);
+        let Json(reviewed) = browse_history(owner, State(state.clone()), profile(Some("Maria")))
+            .await
+            .unwrap();
+        assert_eq!(reviewed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn telegram_webhook_requires_the_secret_token() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_app_state(provider, Arc::new(MockMemory), false);
+        let update = || Json(serde_json::json!({"update_id": 1, "edited_message": {}}));
+
+        let status =
+            telegram::handle_webhook(State(state.clone()), HeaderMap::new(), update()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let channel = TelegramChannel::new("fake-token".into(), vec!["*".into()]);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let bot = telegram::TelegramBot::new(
+            Arc::new(channel),
+            crate::config::TelegramMode::Webhook,
+            tx,
+        );
+        let secret = bot.webhook_secret.clone().unwrap();
+        state.telegram = Some(Arc::new(bot));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            telegram::WEBHOOK_SECRET_HEADER,
+            HeaderValue::from_static("guess"),
+        );
+        let status = telegram::handle_webhook(State(state.clone()), headers, update()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(telegram::WEBHOOK_SECRET_HEADER, secret.parse().unwrap());
+        let status = telegram::handle_webhook(State(state), headers, update()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn paired_device_token_works_until_revoked() {
+        use api::auth::AuthenticatedUser;
+        use axum::extract::FromRequestParts;
+
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_app_state(provider, Arc::new(MockMemory), false);
+        state.pairing = Arc::new(PairingGuard::new(true, &[]));
+        let code = state.pairing.pairing_code().unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Pairing-Code", HeaderValue::from_str(&code).unwrap());
+        headers.insert("X-Device-Name", HeaderValue::from_static("Laptop"));
+        let response = handle_pair(State(state.clone()), ClientKey("test".into()), headers)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let token = body["token"].as_str().unwrap().to_string();
+        assert!(body["refresh_token"].as_str().is_some());
+
+        let request = || {
+            axum::http::Request::builder()
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(())
+                .unwrap()
+                .into_parts()
+                .0
+        };
+        let user = AuthenticatedUser::from_request_parts(&mut request(), &state).await;
+        assert_eq!(user.ok().unwrap().role, crate::identity::UserRole::Root);
+
+        let device = &state.devices.list()[0];
+        assert_eq!(device.name, "Laptop");
+        assert!(state.devices.revoke(&device.id).unwrap());
+        let denied = AuthenticatedUser::from_request_parts(&mut request(), &state).await;
+        assert_eq!(denied.err().unwrap().0, StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn webhook_idempotency_skips_duplicate_provider_calls() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -1242,15 +2313,20 @@ mod tests {
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
         }));
-        let first = handle_webhook(State(state.clone()), headers.clone(), body)
-            .await
-            .into_response();
+        let first = handle_webhook(
+            State(state.clone()),
+            ClientKey("test".into()),
+            headers.clone(),
+            body,
+        )
+        .await
+        .into_response();
         assert_eq!(first.status(), StatusCode::OK);
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
         }));
-        let second = handle_webhook(State(state), headers, body)
+        let second = handle_webhook(State(state), ClientKey("test".into()), headers, body)
             .await
             .into_response();
         assert_eq!(second.status(), StatusCode::OK);
@@ -1277,15 +2353,20 @@ mod tests {
         let body1 = Ok(Json(WebhookBody {
             message: "hello one".into(),
         }));
-        let first = handle_webhook(State(state.clone()), headers.clone(), body1)
-            .await
-            .into_response();
+        let first = handle_webhook(
+            State(state.clone()),
+            ClientKey("test".into()),
+            headers.clone(),
+            body1,
+        )
+        .await
+        .into_response();
         assert_eq!(first.status(), StatusCode::OK);
 
         let body2 = Ok(Json(WebhookBody {
             message: "hello two".into(),
         }));
-        let second = handle_webhook(State(state), headers, body2)
+        let second = handle_webhook(State(state), ClientKey("test".into()), headers, body2)
             .await
             .into_response();
         assert_eq!(second.status(), StatusCode::OK);
@@ -1370,6 +2451,7 @@ mod tests {
                 success: true,
                 output: "ok".into(),
                 error: None,
+                data: None,
             })
         }
     }
@@ -1386,10 +2468,11 @@ mod tests {
         })];
 
         let mut state = test_app_state(provider, memory, false);
-        state.tools_registry = Arc::new(tools);
+        state.tools_registry = Arc::new(tools::ToolRegistry::new(tools));
 
         let response = handle_webhook(
             State(state),
+            ClientKey("test".into()),
             HeaderMap::new(),
             Ok(Json(WebhookBody {
                 message: "please use tool".into(),
@@ -1864,24 +2947,45 @@ mod tests {
             content: "Hello".to_string(),
             channel: "whatsapp".to_string(),
             timestamp: 0,
+            attachments: Vec::new(),
+            group: None,
         };
         let key = whatsapp_memory_key(&msg);
         assert_eq!(key, "whatsapp_4915123456789_msg123");
     }
 
     #[test]
-    fn client_key_from_empty_headers() {
+    fn client_key_without_peer_is_unknown() {
         let headers = HeaderMap::new();
-        let key = client_key_from_headers(&headers);
-        assert!(!key.is_empty());
+        assert_eq!(client_key(None, &headers, &[]), "unknown");
     }
 
     #[test]
-    fn client_key_from_forwarded_header() {
+    fn client_key_ignores_forwarded_header_from_untrusted_peer() {
         let mut headers = HeaderMap::new();
         headers.insert("x-forwarded-for", HeaderValue::from_static("192.168.1.1"));
-        let key = client_key_from_headers(&headers);
-        assert!(key.contains("192.168.1.1"));
+        let peer = SocketAddr::from(([203, 0, 113, 7], 40_000));
+        assert_eq!(client_key(Some(peer), &headers, &[]), "203.0.113.7");
+    }
+
+    #[test]
+    fn client_key_from_trusted_proxy_uses_rightmost_untrusted_hop() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("1.2.3.4, 192.168.1.1, 10.0.0.1"),
+        );
+        let peer = SocketAddr::new(proxy, 40_000);
+        assert_eq!(client_key(Some(peer), &headers, &[proxy]), "192.168.1.1");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("192.168.1.2"));
+        assert_eq!(client_key(Some(peer), &headers, &[proxy]), "192.168.1.2");
+        assert_eq!(
+            client_key(Some(peer), &HeaderMap::new(), &[proxy]),
+            "10.0.0.1"
+        );
     }
 
     #[test]
@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Telegram bot run by the gateway (`[channels_config.telegram] mode`).
+//!
+//! In polling mode the gateway long-polls the Bot API. In webhook mode it
+//! registers `<public_url>/telegram` with a secret token drawn at start-up,
+//! and Telegram posts updates there. Both feed the channel router: answers
+//! to confirmation requests resolve them, voice notes are transcribed, and
+//! everything else runs an agent turn in the chat's own session. In group
+//! chats the bot answers as [`crate::channels::groups`] decides.
+
+use super::{family_memory_scope, gateway_agent_reply, group_turn, media, AppState};
+use crate::agent::budget::BudgetExceeded;
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, TelegramChannel};
+use crate::config::TelegramMode;
+use crate::identity::UserRole;
+use crate::memory::{scoped, MemoryCategory};
+use crate::security::pairing::constant_time_eq;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Result};
+use axum::{
+    extract::{Json, State},
+    http::{HeaderMap, StatusCode},
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Header carrying the secret token of webhook updates.
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// The gateway's Telegram bot and the router queue its updates go to.
+pub struct TelegramBot {
+    pub channel: Arc<TelegramChannel>,
+    mode: TelegramMode,
+    /// Secret Telegram sends with every webhook update; `None` when polling
+    pub(super) webhook_secret: Option<String>,
+    tx: mpsc::Sender<ChannelMessage>,
+}
+
+impl TelegramBot {
+    /// Set up `channel` for `mode`, queueing updates on `tx`.
+    pub fn new(
+        channel: Arc<TelegramChannel>,
+        mode: TelegramMode,
+        tx: mpsc::Sender<ChannelMessage>,
+    ) -> Self {
+        let webhook_secret =
+            (mode == TelegramMode::Webhook).then(|| Uuid::new_v4().simple().to_string());
+        Self {
+            channel,
+            mode,
+            webhook_secret,
+            tx,
+        }
+    }
+
+    /// Register the webhook at `public_url`, or start long polling.
+    pub async fn start(&self, public_url: &str) -> Result<()> {
+        let Some(secret) = &self.webhook_secret else {
+            self.spawn_polling();
+            return Ok(());
+        };
+        if !public_url.starts_with("https://") {
+            bail!("webhook mode needs an HTTPS public URL, not {public_url}");
+        }
+        let url = format!("{}/telegram", public_url.trim_end_matches('/'));
+        self.channel.set_webhook(&url, secret).await?;
+        tracing::info!("Telegram webhook registered at {url}");
+        Ok(())
+    }
+
+    /// Long-poll until the queue closes; the emergency stop pauses polling.
+    fn spawn_polling(&self) {
+        let (channel, tx) = (Arc::clone(&self.channel), self.tx.clone());
+        tokio::spawn(async move {
+            loop {
+                let poll = async {
+                    channel.delete_webhook().await?;
+                    channel.listen(tx.clone()).await
+                };
+                match crate::security::emergency::global().supervise(poll).await {
+                    Ok(()) => break,
+                    Err(e) => {
+                        tracing::warn!("Telegram polling stopped: {e}");
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn mode(&self) -> TelegramMode {
+        self.mode
+    }
+}
+
+/// POST /telegram — updates in webhook mode
+pub async fn handle_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<serde_json::Value>,
+) -> StatusCode {
+    let Some(bot) = &state.telegram else {
+        return StatusCode::NOT_FOUND;
+    };
+    let Some(secret) = &bot.webhook_secret else {
+        return StatusCode::NOT_FOUND;
+    };
+    let given = headers
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok());
+    if !given.is_some_and(|given| constant_time_eq(given, secret)) {
+        tracing::warn!("Telegram webhook: rejected update with a missing or wrong secret token");
+        return StatusCode::UNAUTHORIZED;
+    }
+    if let Some(msg) = bot.channel.handle_update(&update).await {
+        // Answer Telegram right away; it redelivers updates that time out
+        // or are refused
+        if bot.tx.try_send(msg).is_err() {
+            tracing::warn!("Channel queue is full; Telegram will deliver the update again");
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+    }
+    StatusCode::OK
+}
+
+pub(super) async fn handle_message(state: &AppState, mut msg: ChannelMessage) {
+    let Some(bot) = &state.telegram else {
+        return;
+    };
+    let tg = &bot.channel;
+
+    if let Err(e) = media::transcribe_voice(state, &mut msg).await {
+        tracing::error!("STT error for Telegram voice note: {e}");
+        let _ = tg
+            .send(
+                "Sorry, I couldn't understand that voice message.",
+                &msg.sender,
+            )
+            .await;
+        return;
+    }
+    if msg.content.trim().is_empty() {
+        return;
+    }
+
+    // In groups only messages addressed to MyMolt by members with a role
+    let group = if msg.group.is_some() {
+        let Some(turn) = group_turn(state, &msg).await else {
+            return;
+        };
+        msg.content.clone_from(&turn.content);
+        Some(turn)
+    } else {
+        None
+    };
+    tracing::info!(
+        "Telegram message from {}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    // Answers to confirmation requests never reach the agent; groups
+    // cannot answer them
+    if let (Some(relay), None) = (&state.confirm_relay, &group) {
+        if let Some(reply) = relay
+            .handle_reply("telegram", &msg.sender, &msg.content)
+            .await
+        {
+            let _ = tg.send(&reply, &msg.sender).await;
+            return;
+        }
+    }
+
+    let (scope, role) = match &group {
+        Some(turn) => (Some(turn.scope.clone()), turn.role),
+        None => (
+            family_memory_scope(state, "telegram", &msg.sender).await,
+            UserRole::Root,
+        ),
+    };
+    if state.auto_save {
+        let key = format!("telegram_{}_{}", msg.sender, msg.id);
+        let _ = scoped::confine(&state.mem, scope.clone())
+            .store(&key, &msg.content, MemoryCategory::Conversation)
+            .await;
+    }
+
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: "telegram".into(),
+        sender: msg.sender.clone(),
+        text: msg.content.clone(),
+    });
+
+    let ticket = match state.executor.submit("telegram") {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            tracing::warn!("Telegram message rejected: {e}");
+            let _ = tg
+                .send(
+                    "I'm busy right now, please try again in a moment.",
+                    &msg.sender,
+                )
+                .await;
+            return;
+        }
+    };
+    if ticket.position() > 0 {
+        let _ = tg
+            .send(
+                &format!(
+                    "⏳ Your message is queued (position {}).",
+                    ticket.position()
+                ),
+                &msg.sender,
+            )
+            .await;
+    }
+    let _permit = ticket.ready().await;
+
+    // One session per chat, so per group
+    let session = format!("telegram:{}", msg.sender);
+    let reply = gateway_agent_reply(state, &msg.content, session, scope, role, None);
+    match reply.await {
+        Ok(reply) => {
+            if let Err(e) = tg.send(&reply, &msg.sender).await {
+                tracing::error!("Failed to send Telegram reply: {e}");
+            }
+        }
+        Err(e) => {
+            tracing::error!("LLM error for Telegram message: {e:#}");
+            let reply = if e.downcast_ref::<BudgetExceeded>().is_some() {
+                "Sorry, that request needed more steps than I'm allowed. Try breaking it up."
+            } else {
+                "Sorry, I couldn't process your message right now."
+            };
+            let _ = tg.send(reply, &msg.sender).await;
+        }
+    }
+}
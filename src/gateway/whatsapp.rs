@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! `WhatsApp` messages the gateway's webhook queued on the channel router.
+//!
+//! Voice notes are transcribed and images and documents saved for the
+//! agent (see [`super::media`]); images and documents the agent's tools
+//! write during the turn are sent back after the reply.
+
+use super::{
+    family_memory_scope, gateway_agent_reply_with_files, media, whatsapp_memory_key, AppState,
+};
+use crate::agent::budget::BudgetExceeded;
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, WhatsAppChannel};
+use crate::identity::UserRole;
+use crate::memory::{scoped, MemoryCategory};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use std::path::Path;
+
+/// Send a workspace file the agent produced as a `WhatsApp` image or document.
+async fn send_file(wa: &WhatsAppChannel, path: &Path, recipient: &str) -> Result<()> {
+    let Some((mime_type, _)) = media::outgoing_kind(path) else {
+        anyhow::bail!("not an image or document");
+    };
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let data = tokio::fs::read(path).await?;
+    wa.send_file(data, file_name, mime_type, recipient).await
+}
+
+pub(super) async fn handle_message(state: &AppState, mut msg: ChannelMessage) {
+    let Some(wa) = &state.whatsapp else {
+        return;
+    };
+
+    // Voice notes become text; images and documents go to the workspace
+    if let Err(e) = media::transcribe_voice(state, &mut msg).await {
+        tracing::error!("STT error for WhatsApp voice note: {e}");
+        let _ = wa
+            .send(
+                "Sorry, I couldn't understand that voice message.",
+                &msg.sender,
+            )
+            .await;
+        return;
+    }
+    if let Err(e) = media::save_attachments(&state.workspace_dir, &mut msg).await {
+        tracing::error!("Failed to save WhatsApp attachment: {e}");
+        let _ = wa
+            .send("Sorry, I couldn't save your attachment.", &msg.sender)
+            .await;
+        return;
+    }
+    if msg.content.trim().is_empty() {
+        return;
+    }
+    tracing::info!(
+        "WhatsApp message from {}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    // Answers to confirmation requests never reach the agent
+    if let Some(relay) = &state.confirm_relay {
+        if let Some(reply) = relay
+            .handle_reply("whatsapp", &msg.sender, &msg.content)
+            .await
+        {
+            let _ = wa.send(&reply, &msg.sender).await;
+            return;
+        }
+    }
+
+    // Auto-save to memory
+    let scope = family_memory_scope(state, "whatsapp", &msg.sender).await;
+    if state.auto_save {
+        let key = whatsapp_memory_key(&msg);
+        let _ = scoped::confine(&state.mem, scope.clone())
+            .store(&key, &msg.content, MemoryCategory::Conversation)
+            .await;
+    }
+
+    crate::automations::publish(crate::automations::Event::Message {
+        channel: "whatsapp".into(),
+        sender: msg.sender.clone(),
+        text: msg.content.clone(),
+    });
+
+    let ticket = match state.executor.submit("whatsapp") {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            tracing::warn!("WhatsApp message rejected: {e}");
+            let _ = wa
+                .send(
+                    "I'm busy right now, please try again in a moment.",
+                    &msg.sender,
+                )
+                .await;
+            return;
+        }
+    };
+    if ticket.position() > 0 {
+        let _ = wa
+            .send(
+                &format!(
+                    "⏳ Your message is queued (position {}).",
+                    ticket.position()
+                ),
+                &msg.sender,
+            )
+            .await;
+    }
+    let _permit = ticket.ready().await;
+
+    // One session per number
+    let session = format!("whatsapp:{}", msg.sender);
+    let reply =
+        gateway_agent_reply_with_files(state, &msg.content, session, scope, UserRole::Root, None);
+    match reply.await {
+        Ok((reply, files)) => {
+            // The reply, then the images and documents it made
+            if let Err(e) = wa.send(&reply, &msg.sender).await {
+                tracing::error!("Failed to send WhatsApp reply: {e}");
+            }
+            for path in files {
+                if let Err(e) = send_file(wa, &path, &msg.sender).await {
+                    tracing::error!("Failed to send {} via WhatsApp: {e}", path.display());
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!("LLM error for WhatsApp message: {e:#}");
+            let reply = if e.downcast_ref::<BudgetExceeded>().is_some() {
+                "Sorry, that request needed more steps than I'm allowed. Try breaking it up."
+            } else {
+                "Sorry, I couldn't process your message right now."
+            };
+            let _ = wa.send(reply, &msg.sender).await;
+        }
+    }
+}
@@ -86,6 +86,13 @@ pub fn bump_component_restart(component: &str) {
     });
 }
 
+/// Forget a component that no longer exists (e.g. a removed MCP server).
+pub fn remove_component(component: &str) {
+    if let Ok(mut map) = registry().components.lock() {
+        map.remove(component);
+    }
+}
+
 pub fn snapshot() -> HealthSnapshot {
     let components = registry()
         .components
@@ -174,6 +181,16 @@ mod tests {
         assert_eq!(entry.restart_count, 2);
     }
 
+    #[test]
+    fn remove_component_forgets_it() {
+        let component = unique_component("health-remove");
+
+        mark_component_ok(&component);
+        remove_component(&component);
+
+        assert!(!snapshot().components.contains_key(&component));
+    }
+
     #[test]
     fn snapshot_json_contains_registered_component_fields() {
         let component = unique_component("health-json");
@@ -735,6 +735,8 @@ mod tests {
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
         assert!(is_aieos_configured(&config));
     }
@@ -746,6 +748,8 @@ mod tests {
             aieos_path: None,
             aieos_inline: Some("{\"identity\":{}}".into()),
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
         assert!(is_aieos_configured(&config));
     }
@@ -757,6 +761,8 @@ mod tests {
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
         assert!(!is_aieos_configured(&config));
     }
@@ -768,6 +774,8 @@ mod tests {
             aieos_path: None,
             aieos_inline: None,
             providers: vec![],
+            encrypt_diary: false,
+            passkey_origin: None,
         };
         assert!(!is_aieos_configured(&config));
     }
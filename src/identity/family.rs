@@ -8,9 +8,16 @@
 //! (Telegram ID, WhatsApp number, Discord ID, etc.). At runtime,
 //! the registry resolves an incoming `(channel, user_id)` pair to a
 //! `FamilyMember` with a role and a unique memory scope.
+//!
+//! Members can also log in to the dashboard themselves, with a PIN or a
+//! linked OIDC account; the gateway then issues them their own session.
 
+use crate::config::{FamilyConfig, FamilyMemberConfig};
 use crate::identity::UserRole;
+use crate::security::pairing::{constant_time_eq, hash_token};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use std::collections::HashMap;
 
 /// A registered family member.
@@ -25,19 +32,43 @@ pub struct FamilyMember {
 }
 
 impl FamilyMember {
+    /// Build a member from its config entry. Unknown roles fall back to adult.
+    pub fn from_config(config: &FamilyMemberConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            role: role_from_config(&config.role),
+            channels: config.channels.clone(),
+        }
+    }
+
     /// Unique memory scope key for this member.
     /// Format: `user:<name_lowercase_ascii>` (stable across channel changes).
     pub fn scope(&self) -> String {
-        let slug: String = self
-            .name
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
-            .collect();
-        format!("user:{slug}")
+        member_scope(&self.name)
+    }
+}
+
+/// A role as written in config ("root", "adult", "senior", "child", any
+/// case). Unknown roles fall back to adult.
+pub fn role_from_config(role: &str) -> UserRole {
+    match role.to_lowercase().as_str() {
+        "root" => UserRole::Root,
+        "senior" => UserRole::Senior,
+        "child" => UserRole::Child,
+        _ => UserRole::Adult,
     }
 }
 
+/// Memory scope of the member called `name`: `user:<name_lowercase_ascii>`.
+pub fn member_scope(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    format!("user:{slug}")
+}
+
 /// Registry of all family members, loaded from config.
 #[derive(Debug, Clone)]
 pub struct FamilyRegistry {
@@ -77,6 +108,17 @@ impl FamilyRegistry {
         })
     }
 
+    /// Build from `[family]`, keeping at most `max_members` members.
+    pub fn from_config(config: &FamilyConfig) -> Self {
+        let members = config
+            .members
+            .iter()
+            .take(config.max_members)
+            .map(FamilyMember::from_config)
+            .collect();
+        Self::new(members, config.max_members).unwrap_or_else(|_| Self::empty())
+    }
+
     /// Create an empty (single-user, no family) registry.
     pub fn empty() -> Self {
         Self {
@@ -94,6 +136,20 @@ impl FamilyRegistry {
         self.index.get(&key).map(|&i| &self.members[i])
     }
 
+    /// Memory scope for a message from `user_id` on `channel`: the member's
+    /// own scope, or a scope per channel account for unregistered senders.
+    ///
+    /// Returns `None` outside family mode, where all memory is the owner's.
+    pub fn memory_scope(&self, channel: &str, user_id: &str) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+        Some(match self.resolve(channel, user_id) {
+            Some(member) => member.scope(),
+            None => channel_scope(channel, user_id),
+        })
+    }
+
     /// Check if a channel user is a registered family member.
     pub fn is_known(&self, channel: &str, user_id: &str) -> bool {
         self.resolve(channel, user_id).is_some()
@@ -123,6 +179,115 @@ impl FamilyRegistry {
 /// Shared memory scope constant.
 pub const SCOPE_SHARED: &str = "shared";
 
+/// Memory scope of an unregistered sender: `channel:<channel>:<user_id>`.
+/// `:` in the user id is replaced so one scope is never a prefix of another.
+pub fn channel_scope(channel: &str, user_id: &str) -> String {
+    format!(
+        "channel:{}:{}",
+        channel.to_lowercase(),
+        user_id.replace(':', "_")
+    )
+}
+
+/// Memory scope of a group chat, shared by everyone talking in it:
+/// `group:<channel>:<group_id>`.
+pub fn group_scope(channel: &str, group_id: &str) -> String {
+    format!(
+        "group:{}:{}",
+        channel.to_lowercase(),
+        group_id.replace(':', "_")
+    )
+}
+
+/// Scheme tag of PIN hashes made by [`hash_pin`].
+const PIN_KDF: &str = "pbkdf2-sha256";
+
+/// PBKDF2 rounds for new PIN hashes. A PIN has few digits, so every guess
+/// against a leaked config has to be expensive; tests use a cheap count.
+const PIN_KDF_ROUNDS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+/// PBKDF2-HMAC-SHA256 of `pin`, one 32-byte block, hex-encoded.
+fn pin_key(pin: &str, salt: &str, rounds: u32) -> String {
+    let prf =
+        <Hmac<Sha256> as Mac>::new_from_slice(pin.as_bytes()).expect("HMAC accepts any key length");
+    let mut block = prf
+        .clone()
+        .chain_update(salt.as_bytes())
+        .chain_update(1_u32.to_be_bytes())
+        .finalize()
+        .into_bytes();
+    let mut key = block;
+    for _ in 1..rounds {
+        block = prf.clone().chain_update(block).finalize().into_bytes();
+        key.iter_mut().zip(&block).for_each(|(k, b)| *k ^= b);
+    }
+    hex::encode(key)
+}
+
+/// Hash a login PIN for `FamilyMemberConfig::pin_hash`, as
+/// `pbkdf2-sha256$<rounds>$<salt>$<key>`. Slow by design; call it off the
+/// async workers.
+pub fn hash_pin(pin: &str) -> String {
+    let salt = uuid::Uuid::new_v4().as_simple().to_string();
+    let key = pin_key(pin, &salt, PIN_KDF_ROUNDS);
+    format!("{PIN_KDF}${PIN_KDF_ROUNDS}${salt}${key}")
+}
+
+/// Whether `pin` matches a hash made by [`hash_pin`], or by its former
+/// `<salt>$<sha256(salt:pin)>` scheme.
+pub fn verify_pin(pin_hash: &str, pin: &str) -> bool {
+    let parts: Vec<&str> = pin_hash.split('$').collect();
+    match parts[..] {
+        [PIN_KDF, rounds, salt, key] => rounds
+            .parse()
+            .is_ok_and(|rounds| rounds > 0 && constant_time_eq(&pin_key(pin, salt, rounds), key)),
+        [salt, digest] => constant_time_eq(&hash_token(&format!("{salt}:{pin}")), digest),
+        _ => false,
+    }
+}
+
+/// Whether `pin_hash` was made with fewer rounds or a weaker scheme than
+/// [`hash_pin`] uses now, so it should be replaced on the next login.
+pub fn pin_hash_is_outdated(pin_hash: &str) -> bool {
+    match pin_hash.split('$').collect::<Vec<_>>()[..] {
+        [PIN_KDF, rounds, _, _] => rounds
+            .parse::<u32>()
+            .ok()
+            .is_none_or(|rounds| rounds < PIN_KDF_ROUNDS),
+        _ => true,
+    }
+}
+
+/// The member called `name` (any case), if `pin` is their login PIN.
+pub fn member_for_pin<'a>(
+    config: &'a FamilyConfig,
+    name: &str,
+    pin: &str,
+) -> Option<&'a FamilyMemberConfig> {
+    config
+        .members
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(name.trim()))
+        .filter(|m| {
+            m.pin_hash
+                .as_deref()
+                .is_some_and(|hash| verify_pin(hash, pin))
+        })
+}
+
+/// The member linked to account `subject` at OIDC provider `provider`.
+pub fn member_for_oidc<'a>(
+    config: &'a FamilyConfig,
+    provider: &str,
+    subject: &str,
+) -> Option<&'a FamilyMemberConfig> {
+    config.members.iter().find(|m| {
+        m.oidc
+            .as_ref()
+            .is_some_and(|link| link.provider == provider && link.subject == subject)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,6 +343,22 @@ mod tests {
         assert!(reg.resolve("telegram", "99999999").is_none());
     }
 
+    #[test]
+    fn memory_scope_per_member_and_sender() {
+        let reg = test_family();
+        assert_eq!(
+            reg.memory_scope("discord", "987654321").as_deref(),
+            Some("user:luca")
+        );
+        assert_eq!(
+            reg.memory_scope("WhatsApp", "+4915:1").as_deref(),
+            Some("channel:whatsapp:+4915_1")
+        );
+        assert!(FamilyRegistry::empty()
+            .memory_scope("whatsapp", "+4915")
+            .is_none());
+    }
+
     #[test]
     fn resolve_unknown_channel_returns_none() {
         let reg = test_family();
@@ -201,6 +382,27 @@ mod tests {
         assert_eq!(member.scope(), "user:omahelga");
     }
 
+    #[test]
+    fn member_from_config_maps_role() {
+        let config = FamilyMemberConfig {
+            name: "Luca".into(),
+            role: "Child".into(),
+            channels: HashMap::new(),
+            pin_hash: None,
+            oidc: None,
+            quotas: None,
+        };
+        let member = FamilyMember::from_config(&config);
+        assert_eq!(member.role, UserRole::Child);
+        assert_eq!(member.scope(), "user:luca");
+
+        let unknown = FamilyMemberConfig {
+            role: "wizard".into(),
+            ..config
+        };
+        assert_eq!(FamilyMember::from_config(&unknown).role, UserRole::Adult);
+    }
+
     #[test]
     fn member_count() {
         let reg = test_family();
@@ -239,4 +441,58 @@ mod tests {
         assert_eq!(via_telegram.name, via_ws.name);
         assert_eq!(via_telegram.scope(), via_ws.scope());
     }
+
+    #[test]
+    fn members_log_in_with_pin_or_linked_account() {
+        let pin_hash = hash_pin("4711");
+        assert!(!pin_hash.contains("4711"));
+        assert_ne!(pin_hash, hash_pin("4711"));
+
+        let config = FamilyConfig {
+            members: vec![FamilyMemberConfig {
+                name: "Maria".into(),
+                role: "adult".into(),
+                channels: HashMap::new(),
+                pin_hash: Some(pin_hash),
+                oidc: Some(crate::config::MemberOidcLink {
+                    provider: "google".into(),
+                    subject: "1098".into(),
+                }),
+                quotas: None,
+            }],
+            ..FamilyConfig::default()
+        };
+        assert_eq!(
+            member_for_pin(&config, "maria", "4711").unwrap().name,
+            "Maria"
+        );
+        assert!(member_for_pin(&config, "maria", "4712").is_none());
+        assert!(member_for_pin(&config, "luca", "4711").is_none());
+        assert!(member_for_oidc(&config, "google", "1098").is_some());
+        assert!(member_for_oidc(&config, "github", "1098").is_none());
+    }
+
+    #[test]
+    fn pin_key_is_pbkdf2_hmac_sha256() {
+        // RFC 7914, section 11 test vector (first block)
+        assert_eq!(
+            pin_key("passwd", "salt", 1),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    #[test]
+    fn legacy_pin_hashes_verify_and_are_outdated() {
+        let legacy = format!("abc${}", hash_token("abc:4711"));
+        assert!(verify_pin(&legacy, "4711"));
+        assert!(!verify_pin(&legacy, "4712"));
+        assert!(pin_hash_is_outdated(&legacy));
+
+        let current = hash_pin("4711");
+        assert!(current.starts_with("pbkdf2-sha256$"));
+        assert!(verify_pin(&current, "4711"));
+        assert!(!verify_pin(&current, "4712"));
+        assert!(!pin_hash_is_outdated(&current));
+        assert!(!verify_pin("pbkdf2-sha256$0$abc$00", "4711"));
+    }
 }
@@ -7,9 +7,13 @@ pub mod crypto;
 pub mod family;
 pub mod oidc;
 pub mod oidc_generic;
+pub mod oidc_tokens;
+#[cfg(feature = "passkeys")]
+pub mod passkey;
 pub mod roles;
 pub mod soul;
 pub mod ssi;
+pub mod voiceprint;
 
 pub use aieos::{aieos_to_system_prompt, is_aieos_configured, load_aieos_identity};
 pub use roles::{resolve_role, resolve_role_from_soul, Role, RoleCapabilities, RoleConfig};
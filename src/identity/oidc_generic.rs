@@ -2,12 +2,39 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+//! OpenID Connect login with any configured provider.
+//!
+//! The login redirect carries a PKCE (S256) code challenge and a nonce,
+//! kept in a [`LoginChallenge`] until the callback. The ID token from the
+//! code exchange is verified against the provider's JWKS — signature,
+//! issuer, audience, expiry and nonce — before its user info is trusted.
+//! Refresh tokens are kept by [`OidcTokenStore`](super::oidc_tokens::OidcTokenStore).
+
 use crate::config::schema::OIDCProviderConfig;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use rand::Rng;
 use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
+/// Signature algorithms accepted for ID tokens. HMAC is left out: its key
+/// would be the client secret, not one the provider publishes.
+const ID_TOKEN_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
 #[derive(Debug, Clone)]
 pub struct GenericOIDCProvider {
     config: OIDCProviderConfig,
@@ -16,16 +43,139 @@ pub struct GenericOIDCProvider {
 
 #[derive(Debug, Deserialize)]
 struct OIDCDiscovery {
+    issuer: String,
     authorization_endpoint: String,
     token_endpoint: String,
     userinfo_endpoint: String,
+    jwks_uri: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
     access_token: String,
-    #[allow(dead_code)]
     id_token: Option<String>,
+    refresh_token: Option<String>,
+    /// Access token lifetime in seconds
+    expires_in: Option<u64>,
+}
+
+/// Error for a grant the token endpoint refused (a 4xx response), e.g. an
+/// expired or revoked refresh token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenRejected {
+    pub status: u16,
+    pub body: String,
+}
+
+impl std::fmt::Display for TokenRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Token endpoint refused the grant ({}): {}",
+            self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for TokenRejected {}
+
+/// Per-login secrets kept between the redirect and the callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginChallenge {
+    /// PKCE code verifier, sent with the code exchange
+    pub code_verifier: String,
+    /// Expected in the ID token
+    pub nonce: String,
+}
+
+impl LoginChallenge {
+    pub fn generate() -> Self {
+        let random = || {
+            let bytes: [u8; 32] = rand::thread_rng().gen();
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        };
+        Self {
+            code_verifier: random(),
+            nonce: random(),
+        }
+    }
+
+    /// S256 code challenge: `BASE64URL(SHA256(code_verifier))`
+    pub fn code_challenge(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(Sha256::digest(self.code_verifier.as_bytes()))
+    }
+}
+
+/// Claims of a verified ID token this module needs; `iss`, `aud` and
+/// `exp` are checked while verifying.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+/// A completed login.
+#[derive(Debug)]
+pub struct OidcLogin {
+    pub user: UserInfo,
+    /// Subject of the ID token
+    pub subject: String,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+}
+
+/// Tokens from a refresh. Providers may rotate the refresh token.
+#[derive(Debug)]
+pub struct RefreshedTokens {
+    /// Subject of the new ID token, when the provider sent one
+    pub subject: Option<String>,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+}
+
+/// Verify an ID token against the provider's keys. `nonce` is checked when
+/// given; tokens from a refresh carry none.
+pub fn verify_id_token(
+    token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: Option<&str>,
+) -> Result<IdTokenClaims> {
+    let header = decode_header(token).context("Malformed ID token")?;
+    anyhow::ensure!(
+        ID_TOKEN_ALGORITHMS.contains(&header.alg),
+        "ID token signed with unsupported algorithm {:?}",
+        header.alg
+    );
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        // Without a key id the provider must publish a single key
+        None => match jwks.keys.as_slice() {
+            [only] => Some(only),
+            _ => None,
+        },
+    }
+    .ok_or_else(|| anyhow!("No JWKS key for the ID token (kid {:?})", header.kid))?;
+    let key = DecodingKey::from_jwk(jwk).context("Unusable JWKS key")?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&[issuer]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+    let claims = decode::<IdTokenClaims>(token, &key, &validation)
+        .context("ID token validation failed")?
+        .claims;
+
+    if let Some(expected) = nonce {
+        anyhow::ensure!(
+            claims.nonce.as_deref() == Some(expected),
+            "ID token nonce does not match the login"
+        );
+    }
+    Ok(claims)
 }
 
 impl GenericOIDCProvider {
@@ -45,51 +195,165 @@ impl GenericOIDCProvider {
         Ok(resp)
     }
 
+    /// Fetched for every verification so rotated keys are picked up.
+    async fn jwks(&self, discovery: &OIDCDiscovery) -> Result<JwkSet> {
+        self.client
+            .get(&discovery.jwks_uri)
+            .send()
+            .await?
+            .json::<JwkSet>()
+            .await
+            .context("Failed to fetch OIDC provider keys")
+    }
+
     /// Generate the login URL for the frontend to redirect to
-    pub async fn get_login_url(&self, redirect_uri: &str, state: &str) -> Result<String> {
+    pub async fn get_login_url(
+        &self,
+        redirect_uri: &str,
+        state: &str,
+        challenge: &LoginChallenge,
+    ) -> Result<String> {
         let discovery = self.discover().await?;
-        
+        let scope = if self.config.offline_access {
+            "openid email profile offline_access"
+        } else {
+            "openid email profile"
+        };
+
         let mut url = Url::parse(&discovery.authorization_endpoint)?;
         url.query_pairs_mut()
             .append_pair("client_id", &self.config.client_id)
             .append_pair("redirect_uri", redirect_uri)
             .append_pair("response_type", "code")
-            .append_pair("scope", "openid email profile")
-            .append_pair("state", state);
-            
+            .append_pair("scope", scope)
+            .append_pair("state", state)
+            .append_pair("nonce", &challenge.nonce)
+            .append_pair("code_challenge", &challenge.code_challenge())
+            .append_pair("code_challenge_method", "S256");
+
         Ok(url.to_string())
     }
 
-    /// Exchange code for token and fetch user info
-    pub async fn exchange_code(&self, code: &str, redirect_uri: &str) -> Result<UserInfo> {
-        let discovery = self.discover().await?;
-        
-        // 1. Exchange Code
-        let mut params = HashMap::new();
-        params.insert("grant_type", "authorization_code");
-        params.insert("code", code);
-        params.insert("redirect_uri", redirect_uri);
+    async fn request_tokens(
+        &self,
+        discovery: &OIDCDiscovery,
+        mut params: HashMap<&str, &str>,
+    ) -> Result<TokenResponse> {
         params.insert("client_id", &self.config.client_id);
-        
         if let Some(secret) = &self.config.client_secret {
             params.insert("client_secret", secret);
         }
-
-        let token_resp = self.client.post(&discovery.token_endpoint)
+        let resp = self
+            .client
+            .post(&discovery.token_endpoint)
             .form(&params)
-            .send().await?
-            .json::<TokenResponse>().await
+            .send()
+            .await?;
+        let status = resp.status();
+        if status.is_client_error() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(TokenRejected {
+                status: status.as_u16(),
+                body,
+            }
+            .into());
+        }
+        if !status.is_success() {
+            anyhow::bail!("Token endpoint returned {status}");
+        }
+        resp.json::<TokenResponse>()
+            .await
+            .context("Malformed token response")
+    }
+
+    /// Exchange code for token, verify the ID token and fetch user info
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        redirect_uri: &str,
+        challenge: &LoginChallenge,
+    ) -> Result<OidcLogin> {
+        let discovery = self.discover().await?;
+
+        // 1. Exchange Code
+        let params = HashMap::from([
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", challenge.code_verifier.as_str()),
+        ]);
+        let token_resp = self
+            .request_tokens(&discovery, params)
+            .await
             .context("Failed to exchange OIDC code for token")?;
 
-        // 2. Fetch User Info
+        // 2. Verify the ID token
+        let id_token = token_resp
+            .id_token
+            .as_deref()
+            .ok_or_else(|| anyhow!("Provider returned no ID token"))?;
+        let jwks = self.jwks(&discovery).await?;
+        let claims = verify_id_token(
+            id_token,
+            &jwks,
+            &discovery.issuer,
+            &self.config.client_id,
+            Some(&challenge.nonce),
+        )?;
+
+        // 3. Fetch User Info
         let user_info_resp = self.client.get(&discovery.userinfo_endpoint)
-            .bearer_auth(token_resp.access_token)
+            .bearer_auth(&token_resp.access_token)
             .send().await?
             .json::<serde_json::Value>().await
             .context("Failed to fetch user info")?;
+        if let Some(sub) = user_info_resp.get("sub").and_then(|v| v.as_str()) {
+            anyhow::ensure!(
+                sub == claims.sub,
+                "User info belongs to a different subject than the ID token"
+            );
+        }
+
+        // 4. Map to UserInfo struct based on config mapping rules
+        Ok(OidcLogin {
+            user: self.map_user_info(&user_info_resp),
+            subject: claims.sub,
+            refresh_token: token_resp.refresh_token,
+            expires_in: token_resp.expires_in,
+        })
+    }
+
+    /// Use a refresh token. A new ID token, if any, is verified too.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<RefreshedTokens> {
+        let discovery = self.discover().await?;
+        let params = HashMap::from([
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ]);
+        let token_resp = self
+            .request_tokens(&discovery, params)
+            .await
+            .context("Failed to refresh OIDC tokens")?;
 
-        // 3. Map to UserInfo struct based on config mapping rules
-        Ok(self.map_user_info(&user_info_resp))
+        let subject = match token_resp.id_token.as_deref() {
+            Some(id_token) => {
+                let jwks = self.jwks(&discovery).await?;
+                let claims = verify_id_token(
+                    id_token,
+                    &jwks,
+                    &discovery.issuer,
+                    &self.config.client_id,
+                    None,
+                )?;
+                Some(claims.sub)
+            }
+            None => None,
+        };
+        Ok(RefreshedTokens {
+            subject,
+            refresh_token: token_resp.refresh_token,
+            expires_in: token_resp.expires_in,
+        })
     }
 
     fn map_user_info(&self, data: &serde_json::Value) -> UserInfo {
@@ -121,3 +385,82 @@ pub struct UserInfo {
     pub name: Option<String>,
     pub raw: serde_json::Value,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    const ISSUER: &str = "https://id.example.com";
+    const CLIENT_ID: &str = "mymolt";
+    const SEED: [u8; 32] = [7; 32];
+
+    fn jwks() -> JwkSet {
+        let public = ed25519_dalek::SigningKey::from_bytes(&SEED).verifying_key();
+        let x = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(public.as_bytes());
+        serde_json::from_value(serde_json::json!({
+            "keys": [{ "kty": "OKP", "crv": "Ed25519", "kid": "k1", "x": x }]
+        }))
+        .unwrap()
+    }
+
+    fn id_token(claims: serde_json::Value) -> String {
+        // PKCS#8 v1 wrapping of the Ed25519 seed
+        let mut der = hex::decode("302e020100300506032b657004220420").unwrap();
+        der.extend_from_slice(&SEED);
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("k1".into());
+        encode(&header, &claims, &EncodingKey::from_ed_der(&der)).unwrap()
+    }
+
+    fn claims(nonce: &str) -> serde_json::Value {
+        serde_json::json!({
+            "iss": ISSUER,
+            "aud": CLIENT_ID,
+            "sub": "user-1",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "nonce": nonce,
+        })
+    }
+
+    #[test]
+    fn code_challenge_is_base64url_sha256_of_verifier() {
+        let challenge = LoginChallenge {
+            code_verifier: "dBjftJeZ4CVP-mJ92K9hHAqVw6lo7O1_bMfuQL0Ds1o".into(),
+            nonce: String::new(),
+        };
+        assert_eq!(
+            challenge.code_challenge(),
+            "HyUfuPyThwng5iEL7TpfDshhGhmQg7q44Vp0dmaTQQw"
+        );
+        assert_ne!(LoginChallenge::generate(), LoginChallenge::generate());
+    }
+
+    #[test]
+    fn id_token_is_verified_against_jwks_and_nonce() {
+        let token = id_token(claims("n-1"));
+        let verified = verify_id_token(&token, &jwks(), ISSUER, CLIENT_ID, Some("n-1")).unwrap();
+        assert_eq!(verified.sub, "user-1");
+        assert!(verify_id_token(&token, &jwks(), ISSUER, CLIENT_ID, None).is_ok());
+
+        assert!(verify_id_token(&token, &jwks(), ISSUER, CLIENT_ID, Some("n-2")).is_err());
+        assert!(verify_id_token(&token, &jwks(), ISSUER, "other-client", None).is_err());
+        assert!(verify_id_token(&token, &jwks(), "https://evil.example", CLIENT_ID, None).is_err());
+    }
+
+    #[test]
+    fn id_token_with_foreign_signature_or_hmac_is_rejected() {
+        let mut token = id_token(claims("n-1"));
+        token.replace_range(token.len() - 4.., "AAAA");
+        assert!(verify_id_token(&token, &jwks(), ISSUER, CLIENT_ID, None).is_err());
+
+        let hmac = encode(
+            &Header::new(Algorithm::HS256),
+            &claims("n-1"),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        let err = verify_id_token(&hmac, &jwks(), ISSUER, CLIENT_ID, None).unwrap_err();
+        assert!(err.to_string().contains("unsupported algorithm"));
+    }
+}
@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Refresh tokens of linked OIDC identities.
+//!
+//! A login that returns a refresh token keeps it here, encrypted with the
+//! [`SecretStore`] key in `<workspace>/.mymolt/oidc_tokens.json`. The
+//! gateway uses them periodically ([`refresh_all`]) so providers with
+//! expiring refresh tokens keep the identity linked without a new login.
+//! A token the provider rejects is dropped and the identity has to log in
+//! again to renew it.
+
+use super::oidc_generic::{GenericOIDCProvider, TokenRejected};
+use crate::config::schema::OIDCProviderConfig;
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// How often the gateway refreshes the stored tokens.
+pub const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_hours(12);
+
+/// A linked identity's refresh token; `refresh_token` is `SecretStore` ciphertext.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+    pub provider_id: String,
+    /// ID token subject
+    pub subject: String,
+    refresh_token: String,
+    /// When the last access token expires
+    #[serde(default)]
+    pub access_expires_at: Option<DateTime<Utc>>,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+pub struct OidcTokenStore {
+    path: PathBuf,
+    secrets: SecretStore,
+    tokens: Mutex<Vec<StoredToken>>,
+}
+
+impl OidcTokenStore {
+    pub fn new(workspace_dir: &Path, secrets: SecretStore) -> Self {
+        let path = workspace_dir.join(".mymolt").join("oidc_tokens.json");
+        let tokens = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            secrets,
+            tokens: Mutex::new(tokens),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<StoredToken>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn persist(&self, tokens: &[StoredToken]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(tokens)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Keep `refresh_token` for the identity, replacing an older one.
+    pub fn store(
+        &self,
+        provider_id: &str,
+        subject: &str,
+        refresh_token: &str,
+        expires_in: Option<u64>,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let token = StoredToken {
+            provider_id: provider_id.to_string(),
+            subject: subject.to_string(),
+            refresh_token: self.secrets.encrypt(refresh_token)?,
+            access_expires_at: expires_in.and_then(|secs| {
+                now.checked_add_signed(Duration::try_seconds(i64::try_from(secs).ok()?)?)
+            }),
+            refreshed_at: now,
+        };
+        let mut tokens = self.lock();
+        tokens.retain(|t| !(t.provider_id == provider_id && t.subject == subject));
+        tokens.push(token);
+        self.persist(&tokens)
+    }
+
+    /// The decrypted refresh token of an identity.
+    pub fn refresh_token(&self, provider_id: &str, subject: &str) -> Result<Option<String>> {
+        self.lock()
+            .iter()
+            .find(|t| t.provider_id == provider_id && t.subject == subject)
+            .map(|t| self.secrets.decrypt(&t.refresh_token))
+            .transpose()
+    }
+
+    /// Forget an identity's token. Returns whether there was one.
+    pub fn remove(&self, provider_id: &str, subject: &str) -> Result<bool> {
+        let mut tokens = self.lock();
+        let before = tokens.len();
+        tokens.retain(|t| !(t.provider_id == provider_id && t.subject == subject));
+        if tokens.len() == before {
+            return Ok(false);
+        }
+        self.persist(&tokens)?;
+        Ok(true)
+    }
+
+    /// Stored identities, without their tokens.
+    pub fn list(&self) -> Vec<StoredToken> {
+        self.lock()
+            .iter()
+            .map(|t| StoredToken {
+                refresh_token: String::new(),
+                ..t.clone()
+            })
+            .collect()
+    }
+}
+
+/// Refresh every stored token with its provider and keep the rotated ones.
+/// Tokens of providers no longer configured are left alone.
+pub async fn refresh_all(store: &OidcTokenStore, providers: &[OIDCProviderConfig]) {
+    for linked in store.list() {
+        let Some(config) = providers.iter().find(|p| p.id == linked.provider_id) else {
+            continue;
+        };
+        let (provider_id, subject) = (&linked.provider_id, &linked.subject);
+        let refresh_token = match store.refresh_token(provider_id, subject) {
+            Ok(Some(token)) => token,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("OIDC: cannot read refresh token for {provider_id}: {e}");
+                continue;
+            }
+        };
+
+        let provider = GenericOIDCProvider::new(config.clone());
+        let refreshed = match provider.refresh(&refresh_token).await {
+            Ok(refreshed) if refreshed.subject.as_ref().is_none_or(|s| s == subject) => refreshed,
+            Ok(_) => {
+                tracing::warn!("OIDC: {provider_id} refreshed a different subject; dropping token");
+                let _ = store.remove(provider_id, subject);
+                continue;
+            }
+            Err(e) if e.downcast_ref::<TokenRejected>().is_some() => {
+                tracing::warn!(
+                    "OIDC: {provider_id} refused the refresh token, login needed: {e:#}"
+                );
+                let _ = store.remove(provider_id, subject);
+                continue;
+            }
+            // Unreachable provider and the like: try again next time
+            Err(e) => {
+                tracing::warn!("OIDC: refresh for {provider_id} failed: {e:#}");
+                continue;
+            }
+        };
+        let token = refreshed.refresh_token.as_deref().unwrap_or(&refresh_token);
+        if let Err(e) = store.store(provider_id, subject, token, refreshed.expires_in) {
+            tracing::warn!("OIDC: failed to keep refreshed token for {provider_id}: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn refresh_tokens_are_encrypted_and_replaced() {
+        let dir = tempdir().unwrap();
+        let secrets = SecretStore::new(&dir.path().join(".mymolt"), true);
+        let store = OidcTokenStore::new(dir.path(), secrets.clone());
+        store
+            .store("id-austria", "sub-1", "rt-first", Some(3600))
+            .unwrap();
+        store
+            .store("id-austria", "sub-1", "rt-second", None)
+            .unwrap();
+
+        let raw = std::fs::read_to_string(dir.path().join(".mymolt/oidc_tokens.json")).unwrap();
+        assert!(!raw.contains("rt-second"));
+
+        let reopened = OidcTokenStore::new(dir.path(), secrets);
+        assert_eq!(reopened.list().len(), 1);
+        assert!(reopened.list()[0].refresh_token.is_empty());
+        assert_eq!(
+            reopened
+                .refresh_token("id-austria", "sub-1")
+                .unwrap()
+                .as_deref(),
+            Some("rt-second")
+        );
+        assert!(reopened.remove("id-austria", "sub-1").unwrap());
+        assert!(reopened
+            .refresh_token("id-austria", "sub-1")
+            .unwrap()
+            .is_none());
+    }
+}
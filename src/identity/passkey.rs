@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Passkeys (WebAuthn) as an identity provider for Root.
+//!
+//! Root registers a hardware key or platform authenticator; the credential
+//! is kept in `<workspace>/.mymolt/passkeys.json` and bound in SOUL.md at
+//! [`TrustLevel::High`](super::soul::TrustLevel::High) by the gateway. An
+//! assertion with a registered passkey is a step-up factor for Root actions
+//! next to TOTP: it yields a short-lived, single-use token that the gateway
+//! accepts in `X-Passkey-Step-Up`.
+//!
+//! Ceremonies run in two halves (`start_*` returns the options for
+//! `navigator.credentials`, `finish_*` checks the browser's answer); the
+//! state between them is only kept in memory.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use webauthn_rs::prelude::{
+    CreationChallengeResponse, Passkey, PasskeyAuthentication, PasskeyRegistration,
+    PublicKeyCredential, RegisterPublicKeyCredential, RequestChallengeResponse, Url, Uuid,
+    Webauthn, WebauthnBuilder,
+};
+
+/// Provider name of passkey bindings in SOUL.md.
+pub const BINDING_PROVIDER: &str = "Passkey";
+
+/// How long a started ceremony waits for the authenticator.
+const CEREMONY_TTL: Duration = Duration::from_mins(5);
+
+/// How long a step-up token from an assertion stays valid.
+pub const STEP_UP_TTL: Duration = Duration::from_mins(2);
+
+/// A registered passkey as shown to Root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyInfo {
+    /// Base64url credential id, also the id of the SOUL.md binding
+    pub id: String,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredPasskey {
+    #[serde(flatten)]
+    info: PasskeyInfo,
+    passkey: Passkey,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PasskeyFile {
+    /// WebAuthn user handle of Root, shared by all its passkeys
+    #[serde(default)]
+    user_id: Option<Uuid>,
+    #[serde(default)]
+    passkeys: Vec<StoredPasskey>,
+}
+
+enum Ceremony {
+    Registration {
+        label: String,
+        state: PasskeyRegistration,
+    },
+    Authentication(PasskeyAuthentication),
+}
+
+pub struct PasskeyStore {
+    path: PathBuf,
+    /// `None` when the origin is not usable for WebAuthn (e.g. a bare IP)
+    webauthn: Option<Webauthn>,
+    data: Mutex<PasskeyFile>,
+    ceremonies: Mutex<HashMap<String, (Ceremony, Instant)>>,
+    step_ups: Mutex<HashMap<String, Instant>>,
+}
+
+fn credential_id(passkey: &Passkey) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(passkey.cred_id())
+}
+
+fn random_token() -> String {
+    use rand::Rng;
+    use std::fmt::Write;
+    let buf: [u8; 32] = rand::thread_rng().gen();
+    buf.iter()
+        .fold(String::with_capacity(64), |mut token, byte| {
+            let _ = write!(token, "{byte:02x}");
+            token
+        })
+}
+
+fn build_webauthn(origin: &str) -> Result<Webauthn> {
+    let origin = Url::parse(origin).with_context(|| format!("Invalid passkey origin {origin}"))?;
+    let rp_id = origin
+        .domain()
+        .context("Passkeys need an origin with a domain name, not an IP address")?
+        .to_string();
+    Ok(WebauthnBuilder::new(&rp_id, &origin)?
+        .rp_name("MyMolt")
+        .build()?)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+impl PasskeyStore {
+    /// Load the passkeys of `workspace_dir`; ceremonies use `origin`.
+    pub fn new(workspace_dir: &Path, origin: &str) -> Self {
+        let path = workspace_dir.join(".mymolt").join("passkeys.json");
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        let webauthn = build_webauthn(origin)
+            .inspect_err(|e| tracing::debug!("Passkeys unavailable for {origin}: {e:#}"))
+            .ok();
+        Self {
+            path,
+            webauthn,
+            data: Mutex::new(data),
+            ceremonies: Mutex::new(HashMap::new()),
+            step_ups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn webauthn(&self) -> Result<&Webauthn> {
+        self.webauthn.as_ref().context(
+            "Passkeys are unavailable: set [identity] passkey_origin to the https URL of the gateway",
+        )
+    }
+
+    fn persist(&self, data: &PasskeyFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(data)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    fn start(&self, ceremony: Ceremony) -> String {
+        let token = random_token();
+        let mut ceremonies = lock(&self.ceremonies);
+        let now = Instant::now();
+        ceremonies.retain(|_, (_, ts)| now.duration_since(*ts) < CEREMONY_TTL);
+        ceremonies.insert(token.clone(), (ceremony, now));
+        token
+    }
+
+    fn take(&self, token: &str) -> Option<Ceremony> {
+        let mut ceremonies = lock(&self.ceremonies);
+        let now = Instant::now();
+        ceremonies.retain(|_, (_, ts)| now.duration_since(*ts) < CEREMONY_TTL);
+        ceremonies.remove(token).map(|(ceremony, _)| ceremony)
+    }
+
+    /// Whether Root has registered any passkey.
+    pub fn is_registered(&self) -> bool {
+        !lock(&self.data).passkeys.is_empty()
+    }
+
+    pub fn list(&self) -> Vec<PasskeyInfo> {
+        lock(&self.data)
+            .passkeys
+            .iter()
+            .map(|p| p.info.clone())
+            .collect()
+    }
+
+    /// Start registering a passkey named `label`. Returns the ceremony id
+    /// and the options for `navigator.credentials.create()`.
+    pub fn start_registration(&self, label: &str) -> Result<(String, CreationChallengeResponse)> {
+        let webauthn = self.webauthn()?;
+        let (user_id, exclude) = {
+            let mut data = lock(&self.data);
+            let user_id = match data.user_id {
+                Some(id) => id,
+                None => {
+                    let id = Uuid::new_v4();
+                    data.user_id = Some(id);
+                    self.persist(&data)?;
+                    id
+                }
+            };
+            let exclude: Vec<_> = data
+                .passkeys
+                .iter()
+                .map(|p| p.passkey.cred_id().clone())
+                .collect();
+            (user_id, exclude)
+        };
+        let (options, state) =
+            webauthn.start_passkey_registration(user_id, "root", "Root", Some(exclude))?;
+        let ceremony = self.start(Ceremony::Registration {
+            label: label.to_string(),
+            state,
+        });
+        Ok((ceremony, options))
+    }
+
+    /// Check the authenticator's answer and keep the new passkey. `None`
+    /// when the ceremony is unknown or expired or the answer does not verify.
+    pub fn finish_registration(
+        &self,
+        ceremony: &str,
+        credential: &RegisterPublicKeyCredential,
+    ) -> Result<Option<PasskeyInfo>> {
+        let webauthn = self.webauthn()?;
+        let Some(Ceremony::Registration { label, state }) = self.take(ceremony) else {
+            return Ok(None);
+        };
+        let passkey = match webauthn.finish_passkey_registration(credential, &state) {
+            Ok(passkey) => passkey,
+            Err(e) => {
+                tracing::warn!("🔑 Passkey registration rejected: {e}");
+                return Ok(None);
+            }
+        };
+        let info = PasskeyInfo {
+            id: credential_id(&passkey),
+            label,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        let mut data = lock(&self.data);
+        data.passkeys.retain(|p| p.info.id != info.id);
+        data.passkeys.push(StoredPasskey {
+            info: info.clone(),
+            passkey,
+        });
+        self.persist(&data)?;
+        Ok(Some(info))
+    }
+
+    /// Start a step-up assertion with any registered passkey. Returns the
+    /// ceremony id and the options for `navigator.credentials.get()`.
+    pub fn start_authentication(&self) -> Result<(String, RequestChallengeResponse)> {
+        let webauthn = self.webauthn()?;
+        let passkeys: Vec<Passkey> = lock(&self.data)
+            .passkeys
+            .iter()
+            .map(|p| p.passkey.clone())
+            .collect();
+        if passkeys.is_empty() {
+            bail!("No passkey is registered");
+        }
+        let (options, state) = webauthn.start_passkey_authentication(&passkeys)?;
+        Ok((self.start(Ceremony::Authentication(state)), options))
+    }
+
+    /// Check an assertion and hand out a step-up token for it. `None` when
+    /// the ceremony is unknown or expired or the assertion does not verify.
+    pub fn finish_authentication(
+        &self,
+        ceremony: &str,
+        credential: &PublicKeyCredential,
+    ) -> Result<Option<String>> {
+        let webauthn = self.webauthn()?;
+        let Some(Ceremony::Authentication(state)) = self.take(ceremony) else {
+            return Ok(None);
+        };
+        let result = match webauthn.finish_passkey_authentication(credential, &state) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("🔑 Passkey assertion rejected: {e}");
+                return Ok(None);
+            }
+        };
+
+        let mut data = lock(&self.data);
+        let now = Utc::now();
+        for stored in &mut data.passkeys {
+            // Some(_) marks the passkey that signed; keeps its counter current
+            if stored.passkey.update_credential(&result).is_some() {
+                stored.info.last_used_at = Some(now);
+            }
+        }
+        self.persist(&data)?;
+        drop(data);
+
+        Ok(Some(self.issue_step_up()))
+    }
+
+    fn issue_step_up(&self) -> String {
+        let token = random_token();
+        let mut step_ups = lock(&self.step_ups);
+        let now = Instant::now();
+        step_ups.retain(|_, ts| now.duration_since(*ts) < STEP_UP_TTL);
+        step_ups.insert(token.clone(), now);
+        token
+    }
+
+    /// Validate and consume a step-up token (single-use).
+    pub fn consume_step_up(&self, token: &str) -> bool {
+        let mut step_ups = lock(&self.step_ups);
+        let now = Instant::now();
+        step_ups.retain(|_, ts| now.duration_since(*ts) < STEP_UP_TTL);
+        step_ups.remove(token).is_some()
+    }
+
+    /// Forget a passkey. Returns whether there was one.
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let mut data = lock(&self.data);
+        let before = data.passkeys.len();
+        data.passkeys.retain(|p| p.info.id != id);
+        if data.passkeys.len() == before {
+            return Ok(false);
+        }
+        self.persist(&data)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn step_up_tokens_are_single_use() {
+        let dir = tempdir().unwrap();
+        let store = PasskeyStore::new(dir.path(), "https://home.example.org");
+        let token = store.issue_step_up();
+
+        assert!(!store.consume_step_up("not-a-token"));
+        assert!(store.consume_step_up(&token));
+        assert!(!store.consume_step_up(&token));
+    }
+
+    #[test]
+    fn ceremonies_need_a_registered_passkey_and_a_domain() {
+        let dir = tempdir().unwrap();
+        let store = PasskeyStore::new(dir.path(), "https://home.example.org");
+        assert!(!store.is_registered());
+        assert!(store.start_authentication().is_err());
+        assert!(store.take("unknown").is_none());
+
+        let (ceremony, _) = store.start_registration("YubiKey").unwrap();
+        assert!(matches!(
+            store.take(&ceremony),
+            Some(Ceremony::Registration { label, .. }) if label == "YubiKey"
+        ));
+        assert!(store.take(&ceremony).is_none());
+
+        let by_ip = PasskeyStore::new(dir.path(), "http://127.0.0.1:3000");
+        assert!(by_ip.start_registration("YubiKey").is_err());
+    }
+}
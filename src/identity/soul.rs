@@ -2,12 +2,52 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+//! The soul file (`SOUL.md`): free-form text written by the user, plus the
+//! sections MyMolt manages — identity bindings, the diary and preferences.
+//!
+//! Each managed section is a delimited block:
+//!
+//! ```text
+//! <!-- mymolt:begin bindings -->
+//! ## Identity Bindings
+//!
+//! - **eIDAS**: DE-abc12345 (Level 3)
+//!
+//! <!-- mymolt:data [{"provider":"eIDAS","id":"DE-abc12345",…}] -->
+//! <!-- mymolt:end bindings sha256=1f0c93a27d4e5b68 -->
+//! ```
+//!
+//! The data line holds the section's items; the list above it is for
+//! people and the agent's prompt. The checksum covers everything between
+//! the markers: when it no longer matches, the block was edited by hand and
+//! its items are read back from the list instead, so the edit is kept.
+//! Text outside the blocks is written back exactly as it was read.
+//!
+//! Files from before managed blocks have plain `## Identity Bindings` and
+//! `## Diary` sections; [`Soul::load`] turns them into blocks in place.
+//!
+//! With a secret store ([`Soul::with_secrets`]) diary content is encrypted
+//! in the data line and its list only shows that an entry exists.
+
+use crate::security::SecretStore;
 use anyhow::{Context, Result};
-use chrono::Local;
+use chrono::{Local, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+const BEGIN: &str = "<!-- mymolt:begin ";
+const END: &str = "<!-- mymolt:end ";
+const DATA: &str = "<!-- mymolt:data ";
+const CLOSE: &str = " -->";
+
+/// List text of a diary entry whose content is encrypted
+const LOCKED: &str = "🔒 (encrypted)";
+const DIARY_TIMESTAMP: &str = "%Y-%m-%d %H:%M";
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TrustLevel {
     /// Anonymous / unverified
@@ -18,24 +58,136 @@ pub enum TrustLevel {
     High = 3,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TrustLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level.trim() {
+            "3" | "High" => Some(Self::High),
+            "2" | "Medium" => Some(Self::Medium),
+            "1" | "Low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IdentityBinding {
     pub provider: String,
     pub id: String,
     pub trust_level: TrustLevel,
+    /// RFC 3339; empty for bindings migrated from plain markdown
+    #[serde(default)]
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DiaryEntry {
+    /// Assigned on first load for entries from older files
+    #[serde(default)]
+    pub id: String,
     pub timestamp: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<String>,
+}
+
+impl DiaryEntry {
+    /// Local day the entry was written, from its `YYYY-MM-DD HH:MM` timestamp.
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(self.timestamp.get(..10)?, "%Y-%m-%d").ok()
+    }
+}
+
+fn diary_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..8].to_string()
+}
+
+/// Diary entries as a markdown document, in the order given.
+pub fn diary_markdown(entries: &[DiaryEntry]) -> String {
+    entries
+        .iter()
+        .fold(String::from("# Diary\n"), |mut out, e| {
+            let _ = write!(out, "\n## {}\n\n{}\n", e.timestamp, e.content.trim_end());
+            if let Some(edited) = &e.edited_at {
+                let _ = write!(out, "\n*Edited {edited}*\n");
+            }
+            out
+        })
+}
+
+/// A section of SOUL.md that MyMolt manages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Bindings,
+    Diary,
+    Preferences,
+}
+
+impl Section {
+    const ALL: [Self; 3] = [Self::Bindings, Self::Diary, Self::Preferences];
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Bindings => "bindings",
+            Self::Diary => "diary",
+            Self::Preferences => "preferences",
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Bindings => "## Identity Bindings",
+            Self::Diary => "## Diary",
+            Self::Preferences => "## Preferences",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    /// Plain markdown section of an older file, migrated on load.
+    /// Preferences were never managed, so a `## Preferences` written by the
+    /// user stays theirs.
+    fn legacy(heading: &str) -> Option<Self> {
+        [Self::Bindings, Self::Diary]
+            .into_iter()
+            .find(|s| heading.starts_with(s.heading()))
+    }
+}
+
+/// A piece of SOUL.md in file order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// The user's own text, kept byte for byte
+    Text(String),
+    Managed(Section),
+}
+
+/// `- **label**: value` list item of a rendered section.
+fn parse_item(line: &str) -> Option<(&str, &str)> {
+    let (label, value) = line.trim().strip_prefix("- **")?.split_once("**:")?;
+    Some((label.trim(), value.trim()))
+}
+
+/// One line of list text; line breaks would end the item.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn checksum(body: &str) -> String {
+    hex::encode(&Sha256::digest(body.as_bytes())[..8])
 }
 
 pub struct Soul {
     pub path: PathBuf,
     pub bindings: Vec<IdentityBinding>,
-    pub raw_content: String,
+    /// Oldest first
+    pub diary: Vec<DiaryEntry>,
+    pub preferences: BTreeMap<String, String>,
+    /// The file as last read: the user's text and where each managed section sits
+    segments: Vec<Segment>,
+    /// Encrypts diary content on save and decrypts it on load
+    secrets: Option<SecretStore>,
 }
 
 impl Soul {
@@ -44,162 +196,373 @@ impl Soul {
         Self {
             path,
             bindings: Vec::new(),
-            raw_content: String::new(),
+            diary: Vec::new(),
+            preferences: BTreeMap::new(),
+            segments: Vec::new(),
+            secrets: None,
         }
     }
 
-    /// Load bindings from SOUL.md
-    /// Expected format in markdown:
-    /// ## Identity Bindings
-    /// - **Google**: 12345 (Level 1)
-    /// - **eIDAS**: DE/123 (Level 3)
+    /// Keep diary content encrypted with `secrets`. Entries that are still
+    /// plain text are encrypted the next time the file is written; a
+    /// disabled store decrypts them instead.
+    pub fn with_secrets(mut self, secrets: SecretStore) -> Self {
+        self.secrets = Some(secrets);
+        self
+    }
+
+    /// Read SOUL.md, creating it if missing. Older plain-markdown sections
+    /// are migrated and hand-edited blocks re-sealed, which rewrites the file.
     pub fn load(&mut self) -> Result<()> {
         if !self.path.exists() {
-            // Create default if missing
-            self.raw_content = "# Soul\n\n## Identity Bindings\n\n".to_string();
-            self.save()?;
-            return Ok(());
+            self.bindings.clear();
+            self.diary.clear();
+            self.preferences.clear();
+            self.segments = vec![Segment::Text("# Soul\n\n".to_string())];
+            return self.save();
         }
 
         let content = fs::read_to_string(&self.path).context("Failed to read SOUL.md")?;
-        self.raw_content = content.clone();
-        self.bindings.clear();
-
-        let mut in_bindings_section = false;
+        if self.parse(&content) {
+            self.save()?;
+        }
+        Ok(())
+    }
 
-        for line in content.lines() {
+    /// Take the sections from `content`. Returns whether the file needs
+    /// rewriting (migrated or hand-edited sections).
+    fn parse(&mut self, content: &str) -> bool {
+        self.bindings.clear();
+        self.diary.clear();
+        self.preferences.clear();
+        self.segments.clear();
+
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let mut text = String::new();
+        let mut rewrite = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
             let trimmed = line.trim();
-            if trimmed.starts_with("## Identity Bindings") {
-                in_bindings_section = true;
+
+            if let Some((section, end)) = Self::block_at(&lines, i) {
+                let body: String = lines[i + 1..end].concat();
+                let sealed = lines[end]
+                    .trim()
+                    .strip_suffix(CLOSE)
+                    .and_then(|l| l.rsplit_once("sha256="))
+                    .is_some_and(|(_, sum)| sum == checksum(&body));
+                if !(sealed && self.read_data(section, &body)) {
+                    tracing::warn!(
+                        "SOUL.md: {} section was edited by hand; reading its list",
+                        section.key()
+                    );
+                    // Unchanged diary items keep their ids and encrypted
+                    // content from the data line
+                    let before = self.diary.len();
+                    if section == Section::Diary {
+                        self.read_data(section, &body);
+                    }
+                    let stored = self.diary.split_off(before);
+                    self.read_items(section, &lines[i + 1..end], stored);
+                    rewrite = true;
+                }
+                self.place(section, &mut text);
+                i = end + 1;
                 continue;
-            } else if trimmed.starts_with("##") && in_bindings_section {
-                in_bindings_section = false;
+            }
+
+            if let Some(section) = Section::legacy(trimmed) {
+                // Items up to the next heading move into the block; any
+                // other text in the old section stays where it was
+                let end = lines[i + 1..]
+                    .iter()
+                    .position(|l| l.trim_start().starts_with('#'))
+                    .map_or(lines.len(), |n| i + 1 + n);
+                self.place(section, &mut text);
+                let (items, rest): (Vec<&str>, Vec<&str>) = lines[i + 1..end]
+                    .iter()
+                    .skip_while(|l| l.trim().is_empty())
+                    .partition(|l| parse_item(l).is_some());
+                self.read_items(section, &items, Vec::new());
+                text.extend(rest);
+                rewrite = true;
+                i = end;
                 continue;
             }
 
-            if in_bindings_section && trimmed.starts_with("- **") {
-                // Parse line: - **Provider**: ID (Level N)
-                if let Some(binding) = Self::parse_binding_line(trimmed) {
-                    self.bindings.push(binding);
+            text.push_str(line);
+            i += 1;
+        }
+        if !text.is_empty() {
+            self.segments.push(Segment::Text(text));
+        }
+        // Migrated diaries were written newest first
+        self.diary.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        for entry in self.diary.iter_mut().filter(|e| e.id.is_empty()) {
+            entry.id = diary_id();
+            rewrite = true;
+        }
+        if let Some(secrets) = &self.secrets {
+            for entry in &mut self.diary {
+                if !SecretStore::is_encrypted(&entry.content) {
+                    continue;
+                }
+                match secrets.decrypt(&entry.content) {
+                    Ok(content) => entry.content = content,
+                    Err(e) => {
+                        tracing::warn!("SOUL.md: cannot decrypt diary entry {}: {e}", entry.id);
+                    }
                 }
             }
         }
-
-        Ok(())
+        rewrite
     }
 
-    fn parse_binding_line(line: &str) -> Option<IdentityBinding> {
-        // Regex would be cleaner but avoiding extra deps for now.
-        // Format: "- **Provider**: ID (Level N)"
-
-        let parts: Vec<&str> = line.splitn(2, "**").collect();
-        if parts.len() < 2 {
-            return None;
-        }
+    /// The managed block starting at `lines[start]`, with the index of its
+    /// end marker.
+    fn block_at(lines: &[&str], start: usize) -> Option<(Section, usize)> {
+        let key = lines[start]
+            .trim()
+            .strip_prefix(BEGIN)?
+            .strip_suffix(CLOSE)?;
+        let section = Section::from_key(key.trim())?;
+        let end_marker = format!("{END}{} ", section.key());
+        let len = lines[start + 1..]
+            .iter()
+            .position(|l| l.trim_start().starts_with(&end_marker))?;
+        Some((section, start + 1 + len))
+    }
 
-        let rest = parts[1]; // "Provider**: ID (Level N)"
-        let parts2: Vec<&str> = rest.splitn(2, "**:").collect();
-        if parts2.len() < 2 {
-            return None;
+    /// Record that `section` sits here, after the text read so far. A
+    /// section that appears twice keeps its first place.
+    fn place(&mut self, section: Section, text: &mut String) {
+        if !text.is_empty() {
+            self.segments.push(Segment::Text(std::mem::take(text)));
         }
-
-        let provider = parts2[0].trim().to_string();
-        let val_part = parts2[1].trim(); // "ID (Level N)"
-
-        // Find last "(" to split ID and Level
-        if let Some(idx) = val_part.rfind(" (Level ") {
-            let id = val_part[..idx].trim().to_string();
-            let level_str = &val_part[idx + 8..val_part.len() - 1]; // "N"
-
-            let trust_level = match level_str {
-                "3" | "High" => TrustLevel::High,
-                _ => TrustLevel::Low,
-            };
-
-            return Some(IdentityBinding {
-                provider,
-                id,
-                trust_level,
-                created_at: String::new(), // Not stored in simple markdown
-            });
+        if !self.segments.contains(&Segment::Managed(section)) {
+            self.segments.push(Segment::Managed(section));
         }
-
-        None
     }
 
-    pub fn save(&self) -> Result<()> {
-        // Simpler implementation: Just recreate the file content if we modify it.
-        // For now, if we just want to Initialize, standard write is enough.
-        // If we want to Preserve other content, we need a smarter replacer.
-
-        // Current strategy: If file exists, read it, replace/append Indentity Bindings section.
-        // But for MVP, let's just Append if not present or Rewrite if we manage the whole file.
-
-        // For this task, strict "rewrite bindings" is acceptable if we claim ownership of that section.
-        // But let's just write to file if it was empty.
+    /// Items from the data line of a sealed block.
+    fn read_data(&mut self, section: Section, body: &str) -> bool {
+        let Some(json) = body
+            .lines()
+            .find_map(|l| l.trim().strip_prefix(DATA)?.strip_suffix(CLOSE))
+        else {
+            return false;
+        };
+        let read = match section {
+            Section::Bindings => {
+                serde_json::from_str(json).map(|b: Vec<_>| self.bindings.extend(b))
+            }
+            Section::Diary => serde_json::from_str(json).map(|d: Vec<_>| self.diary.extend(d)),
+            Section::Preferences => {
+                serde_json::from_str(json).map(|p: BTreeMap<_, _>| self.preferences.extend(p))
+            }
+        };
+        read.is_ok()
+    }
 
-        if !self.path.exists() {
-            fs::write(&self.path, &self.raw_content)?;
+    /// Items from the list lines of a section, for hand-edited blocks and
+    /// older files. `stored` are the diary entries of the block's data line.
+    fn read_items(&mut self, section: Section, lines: &[&str], mut stored: Vec<DiaryEntry>) {
+        for line in lines {
+            match section {
+                Section::Bindings => {
+                    if let Some(binding) = Self::parse_binding_line(line) {
+                        if !self.bindings.contains(&binding) {
+                            self.bindings.push(binding);
+                        }
+                    }
+                }
+                Section::Diary => {
+                    let Some((timestamp, content)) = parse_item(line) else {
+                        continue;
+                    };
+                    let kept = stored.iter().position(|e| {
+                        e.timestamp == timestamp
+                            && if content == LOCKED {
+                                SecretStore::is_encrypted(&e.content)
+                            } else {
+                                single_line(&e.content) == content
+                            }
+                    });
+                    match kept {
+                        Some(n) => self.diary.push(stored.remove(n)),
+                        // The list only says an encrypted entry was there
+                        None if content == LOCKED => {}
+                        None => self.diary.push(DiaryEntry {
+                            id: String::new(),
+                            timestamp: timestamp.to_string(),
+                            content: content.to_string(),
+                            edited_at: None,
+                        }),
+                    }
+                }
+                Section::Preferences => {
+                    if let Some((key, value)) = parse_item(line) {
+                        self.preferences.insert(key.to_string(), value.to_string());
+                    }
+                }
+            }
         }
+    }
 
-        // TODO: Implement smart section replacement to update bindings persistently without wiping user notes.
-        // For now, load() reads it, but simple save() might not update bindings in-place perfectly without regex.
-        // We will assumes 'save' is mostly for initialization or simple appends.
-
-        Ok(())
+    /// Parse a rendered binding: `- **Provider**: ID (Level N)`.
+    fn parse_binding_line(line: &str) -> Option<IdentityBinding> {
+        let (provider, value) = parse_item(line)?;
+        let (id, level) = value.rsplit_once(" (Level ")?;
+        Some(IdentityBinding {
+            provider: provider.to_string(),
+            id: id.trim().to_string(),
+            trust_level: TrustLevel::parse(level.strip_suffix(')')?)?,
+            created_at: String::new(),
+        })
     }
 
-    /// Add a binding (Appends to file immediately for persistence)
-    pub fn add_binding(&mut self, provider: &str, id: &str, level: TrustLevel) -> Result<()> {
-        // check for duplicates
-        if self
-            .bindings
-            .iter()
-            .any(|b| b.provider == provider && b.id == id)
-        {
-            return Ok(());
+    fn has_items(&self, section: Section) -> bool {
+        match section {
+            Section::Bindings => !self.bindings.is_empty(),
+            Section::Diary => !self.diary.is_empty(),
+            Section::Preferences => !self.preferences.is_empty(),
         }
+    }
 
-        // Access file content
-        let mut content = self.raw_content.clone();
-
-        // Check if section exists
-        if !content.contains("## Identity Bindings") {
-            content.push_str("\n\n## Identity Bindings\n");
+    /// A diary entry as written to the file.
+    fn stored_entry(&self, entry: &DiaryEntry) -> Result<DiaryEntry> {
+        let mut stored = entry.clone();
+        if let Some(secrets) = &self.secrets {
+            if !SecretStore::is_encrypted(&entry.content) {
+                stored.content = secrets.encrypt(&entry.content)?;
+            }
         }
+        Ok(stored)
+    }
 
-        let level_int = match level {
-            TrustLevel::High => 3,
-            TrustLevel::Medium => 2,
-            TrustLevel::Low => 1,
+    /// `section` as a sealed block.
+    fn render(&self, section: Section) -> Result<String> {
+        let diary = match section {
+            Section::Diary => self
+                .diary
+                .iter()
+                .map(|e| self.stored_entry(e))
+                .collect::<Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+        let (items, data): (Vec<(&str, String)>, _) = match section {
+            Section::Bindings => (
+                self.bindings
+                    .iter()
+                    .map(|b| {
+                        let level = b.trust_level as u8;
+                        (b.provider.as_str(), format!("{} (Level {level})", b.id))
+                    })
+                    .collect(),
+                serde_json::to_string(&self.bindings),
+            ),
+            Section::Diary => (
+                diary
+                    .iter()
+                    .map(|e| {
+                        let locked = SecretStore::is_encrypted(&e.content);
+                        let content = if locked { LOCKED } else { &e.content };
+                        (e.timestamp.as_str(), content.to_string())
+                    })
+                    .collect(),
+                serde_json::to_string(&diary),
+            ),
+            Section::Preferences => (
+                self.preferences
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.clone()))
+                    .collect(),
+                serde_json::to_string(&self.preferences),
+            ),
         };
+        let mut list = String::new();
+        for (label, value) in &items {
+            let _ = writeln!(list, "- **{}**: {}", single_line(label), single_line(value));
+        }
+        if !list.is_empty() {
+            list.push('\n');
+        }
+        // `-->` only occurs inside JSON strings, where `>` reads the same
+        let data = data.unwrap_or_default().replace("-->", "--\\u003e");
+        let body = format!("{}\n\n{list}{DATA}{data}{CLOSE}\n", section.heading());
+        let key = section.key();
+        Ok(format!(
+            "{BEGIN}{key}{CLOSE}\n{body}{END}{key} sha256={}{CLOSE}\n",
+            checksum(&body)
+        ))
+    }
 
-        let line = format!("- **{}**: {} (Level {})\n", provider, id, level_int);
-
-        // Insert after header
-        // Simple string manipulation:
-        let header = "## Identity Bindings";
-        if let Some(idx) = content.find(header) {
-            let insert_pos = idx + header.len();
-            // Find next newline
-            if let Some(mut next_nl) = content[insert_pos..].find('\n') {
-                // Insert after the newline of header
-                next_nl += insert_pos + 1;
-                content.insert_str(next_nl, &line);
-            } else {
-                // EOF after header
-                content.push('\n');
-                content.push_str(&line);
+    /// Write SOUL.md: the user's text as read, each managed section in its
+    /// place, and sections that gained their first items appended.
+    pub fn save(&self) -> Result<()> {
+        let mut out = String::new();
+        let mut placed = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => out.push_str(text),
+                Segment::Managed(section) => {
+                    out.push_str(&self.render(*section)?);
+                    placed.push(*section);
+                }
             }
         }
+        for section in Section::ALL {
+            if placed.contains(&section) || !self.has_items(section) {
+                continue;
+            }
+            if !out.is_empty() && !out.ends_with("\n\n") {
+                out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+            }
+            out.push_str(&self.render(section)?);
+        }
+        fs::write(&self.path, out)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
 
-        self.raw_content = content;
-        fs::write(&self.path, &self.raw_content)?;
-
-        // Reload to update memory struct
+    /// Re-read the file, apply `change` and write it back, so edits made to
+    /// SOUL.md since the last load are kept.
+    fn update(&mut self, change: impl FnOnce(&mut Self)) -> Result<()> {
         self.load()?;
+        change(self);
+        self.save()
+    }
 
-        Ok(())
+    /// Add a binding and persist it. Adding the same provider and id again
+    /// does nothing.
+    pub fn add_binding(&mut self, provider: &str, id: &str, level: TrustLevel) -> Result<()> {
+        self.update(|soul| {
+            if soul
+                .bindings
+                .iter()
+                .any(|b| b.provider == provider && b.id == id)
+            {
+                return;
+            }
+            soul.bindings.push(IdentityBinding {
+                provider: provider.to_string(),
+                id: id.to_string(),
+                trust_level: level,
+                created_at: Utc::now().to_rfc3339(),
+            });
+        })
+    }
+
+    /// Remove a binding and persist. Returns whether there was one.
+    pub fn remove_binding(&mut self, provider: &str, id: &str) -> Result<bool> {
+        let mut removed = false;
+        self.update(|soul| {
+            let before = soul.bindings.len();
+            soul.bindings
+                .retain(|b| !(b.provider == provider && b.id == id));
+            removed = soul.bindings.len() != before;
+        })?;
+        Ok(removed)
     }
 
     /// Compute the maximum trust level across all bindings.
@@ -216,69 +579,95 @@ impl Soul {
         self.bindings.iter().any(|b| b.provider == provider)
     }
 
+    /// The newest `limit` diary entries, newest first.
     pub fn get_diary_entries(&self, limit: usize) -> Vec<DiaryEntry> {
-        let mut entries = Vec::new();
-        let content = &self.raw_content;
-
-        let mut in_diary = false;
-
-        for line in content.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("## Diary") {
-                in_diary = true;
-                continue;
-            } else if trimmed.starts_with("##") && in_diary {
-                in_diary = false;
-                continue;
-            }
-
-            if in_diary && trimmed.starts_with("- **") {
-                // - **YYYY-MM-DD HH:MM**: content
-                if let Some((ts, text)) = trimmed
-                    .strip_prefix("- **")
-                    .and_then(|s| s.split_once("**: "))
-                {
-                    entries.push(DiaryEntry {
-                        timestamp: ts.to_string(),
-                        content: text.to_string(),
-                    });
-                }
-            }
-        }
+        self.diary.iter().rev().take(limit).cloned().collect()
+    }
 
-        // Return last N entries
-        entries.into_iter().rev().take(limit).collect()
+    /// Diary entries newest first, written between `from` and `to`
+    /// (inclusive, local days) and containing `text` in any case.
+    pub fn search_diary(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        text: Option<&str>,
+    ) -> Vec<DiaryEntry> {
+        let text = text.map(str::to_lowercase).filter(|t| !t.is_empty());
+        self.diary
+            .iter()
+            .rev()
+            .filter(|e| {
+                let date = e.date();
+                from.is_none_or(|from| date.is_some_and(|d| d >= from))
+                    && to.is_none_or(|to| date.is_some_and(|d| d <= to))
+                    && text
+                        .as_ref()
+                        .is_none_or(|t| e.content.to_lowercase().contains(t))
+            })
+            .cloned()
+            .collect()
     }
 
-    pub fn append_diary_entry(&mut self, content: &str) -> Result<()> {
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M").to_string();
-        let line = format!("- **{}**: {}\n", timestamp, content.trim());
+    pub fn diary_entry(&self, id: &str) -> Option<&DiaryEntry> {
+        self.diary.iter().find(|e| e.id == id)
+    }
 
-        let mut file_content = self.raw_content.clone();
+    /// Add a diary entry stamped with the local time and persist it.
+    pub fn append_diary_entry(&mut self, content: &str) -> Result<DiaryEntry> {
+        let entry = DiaryEntry {
+            id: diary_id(),
+            timestamp: Local::now().format(DIARY_TIMESTAMP).to_string(),
+            content: content.trim().to_string(),
+            edited_at: None,
+        };
+        self.update(|soul| soul.diary.push(entry.clone()))?;
+        Ok(entry)
+    }
 
-        // Check if section exists
-        if !file_content.contains("## Diary") {
-            file_content.push_str("\n\n## Diary\n");
-        }
+    /// Replace the content of entry `id`. Returns the edited entry, or
+    /// `None` when there is no such entry.
+    pub fn edit_diary_entry(&mut self, id: &str, content: &str) -> Result<Option<DiaryEntry>> {
+        let edited_at = Local::now().format(DIARY_TIMESTAMP).to_string();
+        let mut edited = None;
+        self.update(|soul| {
+            if let Some(entry) = soul.diary.iter_mut().find(|e| e.id == id) {
+                entry.content = content.trim().to_string();
+                entry.edited_at = Some(edited_at);
+                edited = Some(entry.clone());
+            }
+        })?;
+        Ok(edited)
+    }
 
-        // Append to Diary section
-        let header = "## Diary";
-        if let Some(idx) = file_content.find(header) {
-            let insert_pos = idx + header.len();
-            if let Some(mut next_nl) = file_content[insert_pos..].find('\n') {
-                next_nl += insert_pos + 1;
-                file_content.insert_str(next_nl, &line);
-            } else {
-                file_content.push('\n');
-                file_content.push_str(&line);
+    /// Remove entry `id`. Returns the removed entry, or `None` when there
+    /// is no such entry.
+    pub fn delete_diary_entry(&mut self, id: &str) -> Result<Option<DiaryEntry>> {
+        let mut removed = None;
+        self.update(|soul| {
+            if let Some(n) = soul.diary.iter().position(|e| e.id == id) {
+                removed = Some(soul.diary.remove(n));
             }
-        }
+        })?;
+        Ok(removed)
+    }
 
-        self.raw_content = file_content;
-        fs::write(&self.path, &self.raw_content)?;
-        self.load()?;
+    pub fn preference(&self, key: &str) -> Option<&str> {
+        self.preferences.get(key).map(String::as_str)
+    }
 
-        Ok(())
+    /// Set a preference and persist it.
+    pub fn set_preference(&mut self, key: &str, value: &str) -> Result<()> {
+        let (key, value) = (key.trim().to_string(), value.trim().to_string());
+        self.update(|soul| {
+            soul.preferences.insert(key, value);
+        })
+    }
+
+    /// Remove a preference. Returns whether it was set.
+    pub fn remove_preference(&mut self, key: &str) -> Result<bool> {
+        let mut removed = false;
+        self.update(|soul| removed = soul.preferences.remove(key.trim()).is_some())?;
+        Ok(removed)
     }
 }
 
@@ -327,7 +716,14 @@ mod tests {
             assert_eq!(soul.bindings.len(), 1);
             assert_eq!(soul.bindings[0].provider, "eIDAS");
             assert_eq!(soul.bindings[0].trust_level, TrustLevel::High);
+
+            assert!(!soul.remove_binding("eIDAS", "AT-eid-002").unwrap());
+            assert!(soul.remove_binding("eIDAS", "AT-eid-001").unwrap());
         }
+
+        let mut soul = Soul::new(dir.path());
+        soul.load().unwrap();
+        assert!(soul.bindings.is_empty());
     }
 
     // ── Google OIDC Binding Tests ────────────────────────────────────
@@ -540,6 +936,180 @@ mod tests {
         assert_eq!(b.trust_level, TrustLevel::Low);
     }
 
+    // ── Managed Sections ─────────────────────────────────────────────
+
+    #[test]
+    fn text_outside_managed_sections_is_kept() {
+        let dir = tempdir().unwrap();
+        let notes = "# Soul\n\nI like tea.\n\n## Rules\nBe brief.\n";
+        fs::write(dir.path().join("SOUL.md"), notes).unwrap();
+
+        let mut soul = Soul::new(dir.path());
+        soul.load().unwrap();
+        soul.add_binding("eIDAS", "DE-abc12345", TrustLevel::High)
+            .unwrap();
+        soul.append_diary_entry("First line\nsecond line --> done")
+            .unwrap();
+        soul.set_preference("language", "German").unwrap();
+
+        let written = fs::read_to_string(&soul.path).unwrap();
+        assert!(written.starts_with(notes));
+        assert_eq!(written.matches("<!-- mymolt:begin ").count(), 3);
+
+        let mut reloaded = Soul::new(dir.path());
+        reloaded.load().unwrap();
+        assert_eq!(fs::read_to_string(&soul.path).unwrap(), written);
+        assert_eq!(reloaded.bindings, soul.bindings);
+        assert_eq!(
+            reloaded.diary[0].content,
+            "First line\nsecond line --> done"
+        );
+        assert_eq!(reloaded.preference("language"), Some("German"));
+    }
+
+    #[test]
+    fn plain_markdown_sections_are_migrated_in_place() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("SOUL.md"),
+            "# Soul\n\n## Identity Bindings\n\n- **Google**: 123 (Level 2)\n\n\
+             ## Diary\n- **2026-03-02 09:00**: Newer\n- **2026-03-01 09:00**: Older\n\n\
+             ## Rules\nBe brief.\n",
+        )
+        .unwrap();
+
+        let mut soul = Soul::new(dir.path());
+        soul.load().unwrap();
+        assert_eq!(soul.bindings[0].trust_level, TrustLevel::Medium);
+        assert_eq!(soul.get_diary_entries(1)[0].content, "Newer");
+        assert_eq!(soul.diary[0].content, "Older");
+
+        let written = fs::read_to_string(&soul.path).unwrap();
+        assert!(written.starts_with("# Soul\n\n<!-- mymolt:begin bindings -->\n"));
+        assert_eq!(written.matches("## Identity Bindings").count(), 1);
+        assert!(written.find("mymolt:end diary").unwrap() < written.find("## Rules").unwrap());
+        assert!(written.ends_with("## Rules\nBe brief.\n"));
+    }
+
+    #[test]
+    fn hand_edited_section_is_read_from_its_list() {
+        let (mut soul, dir) = make_soul();
+        soul.add_binding("eIDAS", "DE-abc12345", TrustLevel::High)
+            .unwrap();
+        let written = fs::read_to_string(&soul.path).unwrap();
+        fs::write(
+            &soul.path,
+            written.replace("- **eIDAS**: DE-abc12345", "- **eIDAS**: DE-xyz98765"),
+        )
+        .unwrap();
+
+        let mut edited = Soul::new(dir.path());
+        edited.load().unwrap();
+        assert_eq!(edited.bindings[0].id, "DE-xyz98765");
+        assert!(fs::read_to_string(&soul.path)
+            .unwrap()
+            .contains(r#""id":"DE-xyz98765""#));
+    }
+
+    #[test]
+    fn preferences_can_be_set_and_removed() {
+        let (mut soul, _dir) = make_soul();
+        soul.set_preference("units", "metric").unwrap();
+        assert_eq!(soul.preference("units"), Some("metric"));
+        assert!(soul.remove_preference("units").unwrap());
+        assert!(!soul.remove_preference("units").unwrap());
+        assert!(soul.preference("units").is_none());
+    }
+
+    // ── Diary ────────────────────────────────────────────────────────
+
+    fn entry(timestamp: &str, content: &str) -> DiaryEntry {
+        DiaryEntry {
+            id: diary_id(),
+            timestamp: timestamp.to_string(),
+            content: content.to_string(),
+            edited_at: None,
+        }
+    }
+
+    #[test]
+    fn diary_search_filters_by_day_and_text() {
+        let (mut soul, _dir) = make_soul();
+        soul.diary = vec![
+            entry("2026-03-01 09:00", "Walk in the park"),
+            entry("2026-03-02 18:30", "Doctor said all is well"),
+            entry("2026-03-04 08:15", "Another PARK visit"),
+        ];
+        let day = |d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+
+        let found = soul.search_diary(day("2026-03-02"), day("2026-03-04"), None);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].content, "Another PARK visit");
+
+        let found = soul.search_diary(None, None, Some("park"));
+        assert_eq!(found.len(), 2);
+        assert!(soul
+            .search_diary(day("2026-03-02"), None, Some("park"))
+            .iter()
+            .all(|e| e.timestamp.starts_with("2026-03-04")));
+    }
+
+    #[test]
+    fn diary_entries_are_edited_and_deleted_by_id() {
+        let (mut soul, dir) = make_soul();
+        let first = soul.append_diary_entry("Frist entry").unwrap();
+        let second = soul.append_diary_entry("Second entry").unwrap();
+
+        let mut reloaded = Soul::new(dir.path());
+        reloaded.load().unwrap();
+        let edited = reloaded
+            .edit_diary_entry(&first.id, "First entry")
+            .unwrap()
+            .unwrap();
+        assert_eq!(edited.content, "First entry");
+        assert!(edited.edited_at.is_some());
+        assert_eq!(
+            reloaded.delete_diary_entry(&second.id).unwrap(),
+            Some(second)
+        );
+        assert!(reloaded.delete_diary_entry("missing").unwrap().is_none());
+
+        soul.load().unwrap();
+        assert_eq!(soul.diary, vec![edited]);
+        assert!(diary_markdown(&soul.diary).contains("\n\nFirst entry\n\n*Edited "));
+    }
+
+    #[test]
+    fn encrypted_diary_content_stays_out_of_the_file() {
+        let dir = tempdir().unwrap();
+        let secrets = SecretStore::new(&dir.path().join(".mymolt"), true);
+        let mut soul = Soul::new(dir.path()).with_secrets(secrets.clone());
+        soul.load().unwrap();
+        soul.append_diary_entry("Felt lonely today").unwrap();
+
+        let written = fs::read_to_string(&soul.path).unwrap();
+        assert!(!written.contains("lonely"));
+        assert!(written.contains(LOCKED));
+
+        // Without the key, entries survive other changes as they are
+        let mut keyless = Soul::new(dir.path());
+        keyless.load().unwrap();
+        keyless.set_preference("units", "metric").unwrap();
+        // …and a hand edit elsewhere in the block
+        let edited = fs::read_to_string(&soul.path).unwrap().replace(
+            "## Diary\n",
+            "## Diary\n- **2026-01-01 10:00**: Added by hand\n",
+        );
+        fs::write(&soul.path, edited).unwrap();
+
+        let mut reloaded = Soul::new(dir.path()).with_secrets(secrets);
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.diary.len(), 2);
+        assert_eq!(reloaded.diary[1].content, "Felt lonely today");
+        assert_eq!(reloaded.diary[1].id, soul.diary[0].id);
+        assert!(!fs::read_to_string(&soul.path).unwrap().contains("by hand"));
+    }
+
     #[test]
     fn parse_binding_line_invalid() {
         assert!(Soul::parse_binding_line("random text").is_none());
@@ -2,87 +2,729 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use anyhow::{anyhow, Result};
-use didkit::{ContextLoader, DID_METHODS, LinkedDataProofOptions, VerifiablePresentation};
+//! SSI wallet verification.
+//!
+//! A Verifiable Presentation arrives as JSON-LD or as a JWT (`vp` claim).
+//! [`SSIGuardian::verify_vp`] resolves the holder's and issuers' `did:key`
+//! and `did:web` DIDs, verifies the presentation proof and every
+//! credential's proof, checks that the presentation is bound to the
+//! challenge and domain the gateway handed out ([`ChallengeStore`]) and
+//! rejects credentials that are expired, not yet valid or revoked in their
+//! status list. Every step is reported as a [`Check`] so the API can say
+//! why a presentation failed.
 
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use didkit::ssi::did_resolve::{DIDResolver, ResolutionInputMetadata};
+use didkit::{
+    ContextLoader, LinkedDataProofOptions, ProofPurpose, VerifiableCredential,
+    VerifiablePresentation, DID_METHODS,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// DID methods a holder or issuer may use.
+const SUPPORTED_DID_METHODS: &[&str] = &["did:key:", "did:web:"];
+
+/// Timeout for fetching a status list credential.
+const STATUS_LIST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VpFormat {
+    JsonLd,
+    Jwt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// One step of a verification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    /// `did_resolution`, `presentation_proof`, `credential_proof`,
+    /// `validity_period`, `status`, `challenge` or `domain`
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was checked, or why it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VerificationResult {
+    /// No check failed
     pub is_valid: bool,
+    pub format: VpFormat,
     pub issuer_did: Option<String>,
     pub holder_did: Option<String>,
+    pub checks: Vec<Check>,
+    /// Details of the failed checks
     pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl VerificationResult {
+    fn push(&mut self, name: &str, status: CheckStatus, detail: impl Into<Option<String>>) {
+        let detail = detail.into();
+        if status == CheckStatus::Failed {
+            self.is_valid = false;
+            self.errors.push(match &detail {
+                Some(detail) => format!("{name}: {detail}"),
+                None => name.to_string(),
+            });
+        }
+        self.checks.push(Check {
+            name: name.to_string(),
+            status,
+            detail,
+        });
+    }
+
+    /// Record a proof check from didkit's errors and warnings.
+    fn push_proof(&mut self, name: &str, subject: &str, errors: &[String], warnings: &[String]) {
+        self.warnings
+            .extend(warnings.iter().map(|w| format!("{subject}: {w}")));
+        if errors.is_empty() {
+            self.push(name, CheckStatus::Passed, subject.to_string());
+        } else {
+            self.push(
+                name,
+                CheckStatus::Failed,
+                format!("{subject}: {}", errors.join("; ")),
+            );
+        }
+    }
+}
+
+/// What a presentation has to be bound to.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    /// Challenge the holder's proof must carry (JWT: `nonce`). Without one
+    /// the presentation can be replayed.
+    pub challenge: Option<String>,
+    /// Domain the holder's proof must carry (JWT: `aud`)
+    pub domain: Option<String>,
+    /// Fetch the status lists of credentials with a `credentialStatus`
+    pub check_status: bool,
+}
+
+// ── JSON views ─────────────────────────────────────────────────────
+
+/// `value` as a list: arrays as they are, a single value as one item.
+fn one_or_many(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(value) => vec![value],
+    }
+}
+
+/// A URI given as a string or as an object with an `id`.
+fn id_of(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(id) => Some(id.clone()),
+        Value::Object(object) => object.get("id")?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// The claims of a compact JWT, without checking its signature.
+fn jwt_claims(jwt: &str) -> Option<Value> {
+    let payload = jwt.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn rfc3339(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?.as_str()?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn timestamp(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(value?.as_i64()?, 0)
+}
+
+/// The presentation as JSON, whichever its format.
+struct PresentationView {
+    format: VpFormat,
+    /// The JSON-LD document, or the `vp` claim of a JWT
+    document: Value,
+    /// JWT claims (`iss`, `nonce`, `aud`, `exp`); `Null` for JSON-LD
+    claims: Value,
+}
+
+impl PresentationView {
+    fn parse(vp: &str) -> Result<Self> {
+        if vp.starts_with('{') {
+            let document: Value =
+                serde_json::from_str(vp).map_err(|e| anyhow!("Failed to parse VP JSON: {e}"))?;
+            return Ok(Self {
+                format: VpFormat::JsonLd,
+                document,
+                claims: Value::Null,
+            });
+        }
+        let claims = jwt_claims(vp).context("VP is neither JSON-LD nor a compact JWT")?;
+        let document = claims
+            .get("vp")
+            .cloned()
+            .context("VP JWT has no `vp` claim")?;
+        Ok(Self {
+            format: VpFormat::Jwt,
+            document,
+            claims,
+        })
+    }
+
+    fn holder(&self) -> Option<String> {
+        id_of(self.document.get("holder")).or_else(|| id_of(self.claims.get("iss")))
+    }
+
+    fn credentials(&self) -> Vec<CredentialView> {
+        one_or_many(self.document.get("verifiableCredential"))
+            .into_iter()
+            .filter_map(CredentialView::parse)
+            .collect()
+    }
+
+    /// Challenges the holder signed: the proofs' `challenge`, or `nonce`.
+    fn challenges(&self) -> Vec<String> {
+        match self.format {
+            VpFormat::JsonLd => one_or_many(self.document.get("proof"))
+                .into_iter()
+                .filter_map(|proof| proof.get("challenge")?.as_str().map(str::to_string))
+                .collect(),
+            VpFormat::Jwt => id_of(self.claims.get("nonce")).into_iter().collect(),
+        }
+    }
+
+    /// Domains the holder signed: the proofs' `domain`, or `aud`.
+    fn domains(&self) -> Vec<String> {
+        let values = match self.format {
+            VpFormat::JsonLd => one_or_many(self.document.get("proof"))
+                .into_iter()
+                .flat_map(|proof| one_or_many(proof.get("domain")))
+                .collect(),
+            VpFormat::Jwt => one_or_many(self.claims.get("aud")),
+        };
+        values
+            .into_iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    }
+}
+
+/// A credential of the presentation, as JSON whichever its format.
+struct CredentialView {
+    /// The compact JWT of a JWT credential
+    jwt: Option<String>,
+    /// The JSON-LD credential, or the `vc` claim of a JWT
+    document: Value,
+    /// JWT claims (`iss`, `exp`, `nbf`); `Null` for JSON-LD
+    claims: Value,
+}
+
+impl CredentialView {
+    fn parse(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(jwt) => {
+                let claims = jwt_claims(jwt)?;
+                Some(Self {
+                    jwt: Some(jwt.clone()),
+                    document: claims.get("vc").cloned().unwrap_or(Value::Null),
+                    claims,
+                })
+            }
+            Value::Object(_) => Some(Self {
+                jwt: None,
+                document: value.clone(),
+                claims: Value::Null,
+            }),
+            _ => None,
+        }
+    }
+
+    fn issuer(&self) -> Option<String> {
+        id_of(self.document.get("issuer")).or_else(|| id_of(self.claims.get("iss")))
+    }
+
+    /// Check the credential's proof (or JWT signature) with didkit.
+    async fn verify_proof(&self, context_loader: &mut ContextLoader) -> (Vec<String>, Vec<String>) {
+        let resolver = &*DID_METHODS;
+        let result = match &self.jwt {
+            Some(jwt) => {
+                VerifiableCredential::decode_verify_jwt(jwt, None, resolver, context_loader)
+                    .await
+                    .1
+            }
+            None => match serde_json::from_value::<VerifiableCredential>(self.document.clone()) {
+                Ok(credential) => credential.verify(None, resolver, context_loader).await,
+                Err(e) => return (vec![format!("invalid credential: {e}")], Vec::new()),
+            },
+        };
+        (result.errors, result.warnings)
+    }
+
+    /// Failure of the validity period at `now`, if any.
+    fn validity_error(&self, now: DateTime<Utc>) -> Option<String> {
+        let doc = &self.document;
+        let until = timestamp(self.claims.get("exp"))
+            .or_else(|| rfc3339(doc.get("expirationDate")))
+            .or_else(|| rfc3339(doc.get("validUntil")));
+        let from = timestamp(self.claims.get("nbf"))
+            .or_else(|| rfc3339(doc.get("validFrom")))
+            .or_else(|| rfc3339(doc.get("issuanceDate")));
+        match (from, until) {
+            (_, Some(until)) if until <= now => Some(format!("expired at {until}")),
+            (Some(from), _) if from > now => Some(format!("not valid before {from}")),
+            _ => None,
+        }
+    }
+
+    fn status_entries(&self) -> Vec<&Value> {
+        one_or_many(self.document.get("credentialStatus"))
+    }
+}
+
+// ── DID resolution ─────────────────────────────────────────────────
+
+/// Resolve `did` with didkit; `Err` says why it cannot be used.
+async fn resolve_did(did: &str) -> Result<(), String> {
+    if !SUPPORTED_DID_METHODS.iter().any(|m| did.starts_with(m)) {
+        return Err(format!("{did}: only did:key and did:web are accepted"));
+    }
+    let (metadata, document, _) = DID_METHODS
+        .resolve(did, &ResolutionInputMetadata::default())
+        .await;
+    match (metadata.error, document) {
+        (Some(error), _) => Err(format!("{did}: {error}")),
+        (None, None) => Err(format!("{did}: no DID document")),
+        (None, Some(_)) => Ok(()),
+    }
+}
+
+// ── Status lists ───────────────────────────────────────────────────
+
+/// Whether bit `index` of a GZIP-compressed, base64url encoded status list
+/// is set. Index 0 is the most significant bit of the first byte.
+fn status_bit(encoded_list: &str, index: usize) -> Result<bool> {
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded_list.trim_end_matches('='))
+        .context("status list is not base64url")?;
+    let mut bits = Vec::new();
+    flate2::read::GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut bits)
+        .context("status list is not GZIP compressed")?;
+    let byte = bits
+        .get(index / 8)
+        .with_context(|| format!("index {index} is outside the status list"))?;
+    Ok(byte & (0x80 >> (index % 8)) != 0)
+}
+
+/// Look up a `StatusList2021Entry` / `BitstringStatusListEntry`. Returns
+/// the status purpose when the credential's bit is set.
+async fn check_status_entry(
+    entry: &Value,
+    issuer: Option<&str>,
+    context_loader: &mut ContextLoader,
+) -> Result<Option<String>> {
+    let kind = entry
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !matches!(kind, "StatusList2021Entry" | "BitstringStatusListEntry") {
+        anyhow::bail!("unsupported credentialStatus type '{kind}'");
+    }
+    let purpose = entry
+        .get("statusPurpose")
+        .and_then(Value::as_str)
+        .unwrap_or("revocation");
+    let index: usize = match entry.get("statusListIndex") {
+        Some(Value::String(index)) => index.parse().ok(),
+        Some(Value::Number(index)) => index.as_u64().and_then(|i| usize::try_from(i).ok()),
+        _ => None,
+    }
+    .context("credentialStatus has no valid statusListIndex")?;
+    let url = entry
+        .get("statusListCredential")
+        .and_then(Value::as_str)
+        .context("credentialStatus has no statusListCredential")?;
+    if !url.starts_with("https://") {
+        anyhow::bail!("status list {url} is not served over https");
+    }
+    crate::network::egress::check(url, None)?;
+
+    let body = crate::network::egress::client_builder(None)
+        .timeout(STATUS_LIST_TIMEOUT)
+        .build()?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let list = CredentialView::parse(&match serde_json::from_str(&body) {
+        Ok(document) => document,
+        Err(_) => Value::String(body.trim().to_string()),
+    })
+    .with_context(|| format!("{url} is not a credential"))?;
+
+    let (errors, _) = list.verify_proof(context_loader).await;
+    if !errors.is_empty() {
+        anyhow::bail!("status list {url} does not verify: {}", errors.join("; "));
+    }
+    if list.issuer().as_deref() != issuer {
+        anyhow::bail!("status list {url} is not from the credential's issuer");
+    }
+    if let Some(error) = list.validity_error(Utc::now()) {
+        anyhow::bail!("status list {url} is {error}");
+    }
+    let subject = list.document.get("credentialSubject");
+    let encoded = subject
+        .and_then(|s| s.get("encodedList"))
+        .and_then(Value::as_str)
+        .context("status list has no encodedList")?;
+    // Bitstring Status List uses a multibase base64url ('u') prefix
+    let encoded = match kind {
+        "BitstringStatusListEntry" => encoded.strip_prefix('u').unwrap_or(encoded),
+        _ => encoded,
+    };
+    Ok(status_bit(encoded, index)?.then(|| purpose.to_string()))
 }
 
+// ── Challenges ─────────────────────────────────────────────────────
+
+/// Single-use challenges for presentations, so a presentation recorded
+/// elsewhere cannot be replayed to the gateway.
+pub struct ChallengeStore {
+    ttl: Duration,
+    issued: Mutex<HashMap<String, Instant>>,
+}
+
+impl ChallengeStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            issued: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// A fresh random challenge.
+    pub fn issue(&self) -> String {
+        let challenge = uuid::Uuid::new_v4().simple().to_string();
+        let mut issued = self
+            .issued
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        issued.retain(|_, ts| now.duration_since(*ts) < self.ttl);
+        issued.insert(challenge.clone(), now);
+        challenge
+    }
+
+    /// Validate and consume a challenge (single-use).
+    pub fn consume(&self, challenge: &str) -> bool {
+        let mut issued = self
+            .issued
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        issued.retain(|_, ts| now.duration_since(*ts) < self.ttl);
+        issued.remove(challenge).is_some()
+    }
+}
+
+// ── Verification ───────────────────────────────────────────────────
+
 pub struct SSIGuardian;
 
 impl SSIGuardian {
-    /// Verifies a Verifiable Presentation (VP) string (JSON-LD).
+    /// Verify a Verifiable Presentation given as JSON-LD or compact JWT.
     ///
-    /// Performs real cryptographic verification using didkit's linked-data
-    /// proof verification pipeline:
-    ///   1. Parse the VP JSON-LD
-    ///   2. Verify all proofs using DID resolution + LD signatures
-    ///   3. Extract holder/issuer DIDs
-    pub async fn verify_vp(vp_string: &str) -> Result<VerificationResult> {
-        // 1. Parse VP
-        let vp: VerifiablePresentation = serde_json::from_str(vp_string)
-            .map_err(|e| anyhow!("Failed to parse VP JSON: {}", e))?;
-
-        // 2. Resolve DIDs using the built-in resolver that covers did:key, did:web, etc.
-        let resolver = &*DID_METHODS;
+    /// Malformed input is an `Err`; everything else ends up in the
+    /// [`VerificationResult`]'s checks.
+    pub async fn verify_vp(vp_string: &str, options: &VerifyOptions) -> Result<VerificationResult> {
+        let vp_string = vp_string.trim();
+        let vp = PresentationView::parse(vp_string)?;
+        let credentials = vp.credentials();
+        let holder_did = vp.holder();
+        let issuers: Vec<Option<String>> = credentials.iter().map(CredentialView::issuer).collect();
 
-        // 3. Real cryptographic verification via didkit/ssi linked-data proofs
-        let mut context_loader = ContextLoader::default();
-        let ldp_options = LinkedDataProofOptions::default();
+        let mut result = VerificationResult {
+            is_valid: true,
+            format: vp.format,
+            issuer_did: issuers.iter().flatten().next().cloned(),
+            holder_did: holder_did.clone(),
+            checks: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
 
-        let ssi_result = vp
-            .verify(Some(ldp_options), resolver, &mut context_loader)
-            .await;
-
-        let is_valid = ssi_result.errors.is_empty();
-        let mut errors: Vec<String> = ssi_result.errors;
+        // 1. DIDs of the holder and the issuers
+        let mut dids: Vec<&String> = holder_did.iter().chain(issuers.iter().flatten()).collect();
+        dids.sort();
+        dids.dedup();
+        if holder_did.is_none() {
+            result.push(
+                "did_resolution",
+                CheckStatus::Failed,
+                "presentation names no holder".to_string(),
+            );
+        }
+        for did in dids {
+            match resolve_did(did).await {
+                Ok(()) => result.push("did_resolution", CheckStatus::Passed, did.clone()),
+                Err(e) => result.push("did_resolution", CheckStatus::Failed, e),
+            }
+        }
 
-        // Append any warnings as informational
-        if !ssi_result.warnings.is_empty() {
-            for w in &ssi_result.warnings {
-                errors.push(format!("warning: {w}"));
+        // 2. The holder's proof over the presentation
+        let resolver = &*DID_METHODS;
+        let mut context_loader = ContextLoader::default();
+        let ldp_options = LinkedDataProofOptions {
+            proof_purpose: Some(ProofPurpose::Authentication),
+            challenge: options.challenge.clone(),
+            domain: options.domain.clone(),
+            ..Default::default()
+        };
+        let ssi_result = match vp.format {
+            VpFormat::JsonLd => {
+                let presentation: VerifiablePresentation = serde_json::from_str(vp_string)
+                    .map_err(|e| anyhow!("Failed to parse VP JSON: {e}"))?;
+                presentation
+                    .verify(Some(ldp_options), resolver, &mut context_loader)
+                    .await
             }
+            VpFormat::Jwt => {
+                VerifiablePresentation::decode_verify_jwt(
+                    vp_string,
+                    Some(ldp_options),
+                    resolver,
+                    &mut context_loader,
+                )
+                .await
+                .1
+            }
+        };
+        result.push_proof(
+            "presentation_proof",
+            holder_did.as_deref().unwrap_or("presentation"),
+            &ssi_result.errors,
+            &ssi_result.warnings,
+        );
+        if let Some(until) = timestamp(vp.claims.get("exp")).filter(|t| *t <= Utc::now()) {
+            result.push(
+                "validity_period",
+                CheckStatus::Failed,
+                format!("presentation expired at {until}"),
+            );
         }
 
-        // 4. Extract DIDs
-        let mut issuer_did = None;
-        let mut holder_did = None;
+        // 3. Binding to the gateway's challenge and domain
+        for (name, expected, signed) in [
+            ("challenge", &options.challenge, vp.challenges()),
+            ("domain", &options.domain, vp.domains()),
+        ] {
+            match expected {
+                Some(expected) if signed.contains(expected) => {
+                    result.push(name, CheckStatus::Passed, expected.clone());
+                }
+                Some(expected) => result.push(
+                    name,
+                    CheckStatus::Failed,
+                    format!("presentation is not bound to '{expected}'"),
+                ),
+                None => {
+                    result.push(name, CheckStatus::Skipped, None::<String>);
+                    result.warnings.push(format!(
+                        "no {name} requested; the presentation can be replayed"
+                    ));
+                }
+            }
+        }
 
-        // Holder is usually a URI in the `holder` field
-        if let Some(holder) = &vp.holder {
-             holder_did = Some(holder.to_string());
+        // 4. Each credential: proof, validity period, status
+        if credentials.is_empty() {
+            result
+                .warnings
+                .push("presentation carries no credentials".into());
         }
+        let now = Utc::now();
+        for (i, credential) in credentials.iter().enumerate() {
+            let subject = format!("credential {i}");
+            let (errors, warnings) = credential.verify_proof(&mut context_loader).await;
+            result.push_proof("credential_proof", &subject, &errors, &warnings);
+
+            match credential.validity_error(now) {
+                Some(error) => result.push(
+                    "validity_period",
+                    CheckStatus::Failed,
+                    format!("{subject} {error}"),
+                ),
+                None => result.push("validity_period", CheckStatus::Passed, subject.clone()),
+            }
 
-        // Issuer is inside the credential(s)
-        if let Some(creds) = &vp.verifiable_credential {
-            for cred in creds {
-                 // cred is CredentialOrJWT
-                 match cred {
-                     didkit::ssi::vc::CredentialOrJWT::Credential(c) => {
-                         if let Some(issuer) = &c.issuer {
-                             issuer_did = Some(issuer.get_id());
-                             break; 
-                         }
-                     },
-                     didkit::ssi::vc::CredentialOrJWT::JWT(_) => {
-                         errors.push("JWT credential extraction not yet implemented".into());
-                     }
-                 }
+            let entries = credential.status_entries();
+            if entries.is_empty() || !options.check_status {
+                result.push("status", CheckStatus::Skipped, subject.clone());
+                continue;
+            }
+            for entry in entries {
+                let issuer = issuers[i].as_deref();
+                match check_status_entry(entry, issuer, &mut context_loader).await {
+                    Ok(None) => result.push("status", CheckStatus::Passed, subject.clone()),
+                    Ok(Some(purpose)) => result.push(
+                        "status",
+                        CheckStatus::Failed,
+                        format!("{subject} is marked for {purpose}"),
+                    ),
+                    Err(e) => {
+                        result.push("status", CheckStatus::Failed, format!("{subject}: {e:#}"))
+                    }
+                }
             }
         }
 
-        Ok(VerificationResult {
-            is_valid,
-            issuer_did,
-            holder_did,
-            errors,
-        })
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn encode_jwt(claims: &Value) -> String {
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        format!(
+            "{}.{}.c2ln",
+            engine.encode(br#"{"alg":"EdDSA"}"#),
+            engine.encode(claims.to_string())
+        )
+    }
+
+    #[test]
+    fn status_bits_are_read_most_significant_first() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&[0b0100_0000, 0b0000_0001]).unwrap();
+        let encoded =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(encoder.finish().unwrap());
+
+        assert!(!status_bit(&encoded, 0).unwrap());
+        assert!(status_bit(&encoded, 1).unwrap());
+        assert!(status_bit(&encoded, 15).unwrap());
+        assert!(status_bit(&encoded, 16).is_err());
+    }
+
+    #[test]
+    fn jwt_presentation_exposes_binding_and_credentials() {
+        let credential = encode_jwt(&serde_json::json!({
+            "iss": "did:key:z6MkIssuer",
+            "exp": 1,
+            "vc": { "credentialSubject": { "id": "did:key:z6MkHolder" } }
+        }));
+        let vp = encode_jwt(&serde_json::json!({
+            "iss": "did:key:z6MkHolder",
+            "nonce": "abc",
+            "aud": ["home.example.org"],
+            "vp": { "verifiableCredential": [credential] }
+        }));
+
+        let view = PresentationView::parse(&vp).unwrap();
+        assert_eq!(view.format, VpFormat::Jwt);
+        assert_eq!(view.holder().as_deref(), Some("did:key:z6MkHolder"));
+        assert_eq!(view.challenges(), vec!["abc"]);
+        assert_eq!(view.domains(), vec!["home.example.org"]);
+
+        let credentials = view.credentials();
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(
+            credentials[0].issuer().as_deref(),
+            Some("did:key:z6MkIssuer")
+        );
+        assert!(credentials[0]
+            .validity_error(Utc::now())
+            .unwrap()
+            .starts_with("expired"));
+    }
+
+    #[test]
+    fn json_ld_presentation_reads_proof_binding_and_validity() {
+        let view = PresentationView::parse(
+            r#"{
+                "holder": "did:web:alice.example",
+                "proof": { "challenge": "abc", "domain": "home.example.org" },
+                "verifiableCredential": {
+                    "issuer": { "id": "did:web:issuer.example" },
+                    "issuanceDate": "2999-01-01T00:00:00Z"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(view.format, VpFormat::JsonLd);
+        assert_eq!(view.challenges(), vec!["abc"]);
+        assert_eq!(view.domains(), vec!["home.example.org"]);
+
+        let credentials = view.credentials();
+        assert_eq!(
+            credentials[0].issuer().as_deref(),
+            Some("did:web:issuer.example")
+        );
+        assert!(credentials[0]
+            .validity_error(Utc::now())
+            .unwrap()
+            .starts_with("not valid before"));
+        assert!(PresentationView::parse("not a presentation").is_err());
+    }
+
+    #[test]
+    fn challenges_are_single_use() {
+        let store = ChallengeStore::new(Duration::from_mins(1));
+        let challenge = store.issue();
+        assert!(!store.consume("other"));
+        assert!(store.consume(&challenge));
+        assert!(!store.consume(&challenge));
+    }
+
+    #[tokio::test]
+    async fn unsigned_presentation_fails_with_diagnostics() {
+        let vp = r#"{
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "type": ["VerifiablePresentation"],
+            "holder": "did:example:123"
+        }"#;
+        let result = SSIGuardian::verify_vp(vp, &VerifyOptions::default())
+            .await
+            .unwrap();
+
+        assert!(!result.is_valid);
+        let status = |name: &str| {
+            result
+                .checks
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.status)
+        };
+        assert_eq!(status("did_resolution"), Some(CheckStatus::Failed));
+        assert_eq!(status("presentation_proof"), Some(CheckStatus::Failed));
+        assert_eq!(status("challenge"), Some(CheckStatus::Skipped));
     }
 }
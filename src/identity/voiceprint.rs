@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! On-device speaker identification.
+//!
+//! Voice input is decoded to 16 kHz mono PCM with `ffmpeg` and reduced to a
+//! fixed-size embedding: the mean and spread of the MFCCs over voiced frames.
+//! Enrolled family members' voiceprints are stored encrypted in the workspace
+//! and matched by cosine similarity, so neither audio nor embeddings leave
+//! the device.
+
+use crate::security::secrets::SecretStore;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const SAMPLE_RATE: f32 = 16_000.0;
+/// 25 ms analysis window.
+const FRAME_LEN: usize = 400;
+/// 10 ms hop between windows.
+const FRAME_HOP: usize = 160;
+const FFT_LEN: usize = 512;
+const MEL_BANDS: usize = 26;
+/// Cepstral coefficients kept per frame (c0, the loudness term, is dropped).
+const MFCC_COUNT: usize = 13;
+/// About one second of speech is needed for a usable voiceprint.
+const MIN_VOICED_FRAMES: usize = 100;
+/// Frames this far (in dB) below the loudest one count as silence.
+const SILENCE_DB: f32 = 35.0;
+const DECODE_TIMEOUT_SECS: u64 = 30;
+
+/// Samples kept per enrolled member; older ones are dropped first.
+pub const MAX_SAMPLES_PER_MEMBER: usize = 10;
+
+/// Decode any audio container `ffmpeg` understands to 16 kHz mono samples.
+pub async fn decode_pcm(audio: &[u8]) -> Result<Vec<f32>> {
+    if audio.is_empty() {
+        bail!("Audio is empty");
+    }
+
+    let mut child = match Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-i", "pipe:0"])
+        .args(["-ac", "1", "-ar", "16000", "-f", "f32le", "pipe:1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("ffmpeg is not installed; install it to identify speakers")
+        }
+        Err(e) => return Err(e).context("Failed to start ffmpeg"),
+    };
+
+    let mut stdin = child.stdin.take().context("ffmpeg stdin unavailable")?;
+    let input = audio.to_vec();
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(&input).await;
+    });
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(DECODE_TIMEOUT_SECS),
+        child.wait_with_output(),
+    )
+    .await
+    .context("Audio decoding timed out")??;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("ffmpeg failed: {}", stderr.trim());
+    }
+    Ok(output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Decode `audio` and compute its voiceprint.
+pub async fn voiceprint(audio: &[u8]) -> Result<Vec<f32>> {
+    let samples = decode_pcm(audio).await?;
+    embed(&samples).context("Not enough speech to recognise the voice (about a second is needed)")
+}
+
+/// In-place iterative radix-2 FFT; `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular mel filters over the FFT bins, 20 Hz to Nyquist.
+fn mel_filterbank() -> Vec<Vec<f32>> {
+    let bins = FFT_LEN / 2 + 1;
+    let (low, high) = (hz_to_mel(20.0), hz_to_mel(SAMPLE_RATE / 2.0));
+    let edges: Vec<f32> = (0..MEL_BANDS + 2)
+        .map(|i| {
+            let hz = mel_to_hz(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32);
+            hz * FFT_LEN as f32 / SAMPLE_RATE
+        })
+        .collect();
+
+    (0..MEL_BANDS)
+        .map(|m| {
+            let (left, center, right) = (edges[m], edges[m + 1], edges[m + 2]);
+            (0..bins)
+                .map(|bin| {
+                    let bin = bin as f32;
+                    if bin <= left || bin >= right {
+                        0.0
+                    } else if bin <= center {
+                        (bin - left) / (center - left)
+                    } else {
+                        (right - bin) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Voiceprint of 16 kHz mono samples, or `None` if there is too little speech.
+pub fn embed(samples: &[f32]) -> Option<Vec<f32>> {
+    if samples.len() < FRAME_LEN {
+        return None;
+    }
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| 0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos())
+        .collect();
+
+    // Pre-emphasised, windowed frames with their energy in dB.
+    let frames: Vec<(Vec<f32>, f32)> = (0..=samples.len() - FRAME_LEN)
+        .step_by(FRAME_HOP)
+        .map(|start| {
+            let frame: Vec<f32> = (0..FRAME_LEN)
+                .map(|i| {
+                    let prev = if start + i == 0 { 0.0 } else { samples[start + i - 1] };
+                    (samples[start + i] - 0.97 * prev) * window[i]
+                })
+                .collect();
+            let energy = frame.iter().map(|x| x * x).sum::<f32>();
+            (frame, 10.0 * energy.max(1e-12).log10())
+        })
+        .collect();
+    let loudest = frames.iter().map(|(_, db)| *db).fold(f32::MIN, f32::max);
+    if loudest < -60.0 {
+        return None;
+    }
+
+    let filters = mel_filterbank();
+    let mut coefficients: Vec<[f32; MFCC_COUNT]> = Vec::new();
+    for (frame, _) in frames.iter().filter(|(_, db)| *db >= loudest - SILENCE_DB) {
+        let mut re = vec![0.0; FFT_LEN];
+        re[..FRAME_LEN].copy_from_slice(frame);
+        let mut im = vec![0.0; FFT_LEN];
+        fft(&mut re, &mut im);
+        let power: Vec<f32> = (0..=FFT_LEN / 2).map(|k| re[k] * re[k] + im[k] * im[k]).collect();
+
+        let log_mel: Vec<f32> = filters
+            .iter()
+            .map(|f| f.iter().zip(&power).map(|(w, p)| w * p).sum::<f32>().max(1e-10).ln())
+            .collect();
+        let mut mfcc = [0.0; MFCC_COUNT];
+        for (k, c) in mfcc.iter_mut().enumerate() {
+            *c = log_mel
+                .iter()
+                .enumerate()
+                .map(|(m, e)| {
+                    e * (std::f32::consts::PI * (k + 1) as f32 * (m as f32 + 0.5) / MEL_BANDS as f32)
+                        .cos()
+                })
+                .sum();
+        }
+        coefficients.push(mfcc);
+    }
+    if coefficients.len() < MIN_VOICED_FRAMES {
+        return None;
+    }
+
+    let count = coefficients.len() as f32;
+    let mut embedding = Vec::with_capacity(MFCC_COUNT * 2);
+    for k in 0..MFCC_COUNT {
+        let mean = coefficients.iter().map(|c| c[k]).sum::<f32>() / count;
+        embedding.push(mean);
+    }
+    for k in 0..MFCC_COUNT {
+        let mean = embedding[k];
+        let variance = coefficients.iter().map(|c| (c[k] - mean).powi(2)).sum::<f32>() / count;
+        embedding.push(variance.sqrt());
+    }
+    Some(embedding)
+}
+
+/// Cosine similarity of two embeddings (0 when either is empty or mismatched).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// A recognised speaker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerMatch {
+    /// Family member name as configured in `[family]`.
+    pub member: String,
+    pub similarity: f32,
+}
+
+/// Enrolled voiceprints, keyed by family member name. Encrypted at rest.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VoiceprintStore {
+    #[serde(default)]
+    members: BTreeMap<String, Vec<Vec<f32>>>,
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join(".mymolt").join("voiceprints.json")
+}
+
+impl VoiceprintStore {
+    pub fn load(workspace: &Path, secrets: &Option<SecretStore>) -> Self {
+        let Ok(raw) = std::fs::read_to_string(store_path(workspace)) else {
+            return Self::default();
+        };
+        let json = match secrets {
+            Some(store) => store.decrypt(&raw).unwrap_or(raw),
+            None => raw,
+        };
+        serde_json::from_str(&json).unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path, secrets: &Option<SecretStore>) -> Result<()> {
+        let path = store_path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self)?;
+        let data = match secrets {
+            Some(store) => store.encrypt(&json)?,
+            None => json,
+        };
+        std::fs::write(&path, data)?;
+        Ok(())
+    }
+
+    /// Add a sample for `member`. Returns how many samples they now have.
+    pub fn enroll(&mut self, member: &str, embedding: Vec<f32>) -> usize {
+        let samples = self.members.entry(member.to_string()).or_default();
+        samples.push(embedding);
+        if samples.len() > MAX_SAMPLES_PER_MEMBER {
+            samples.remove(0);
+        }
+        samples.len()
+    }
+
+    /// Forget a member's voice. Returns `false` if they were not enrolled.
+    pub fn remove(&mut self, member: &str) -> bool {
+        self.members.remove(member).is_some()
+    }
+
+    /// Enrolled members and their sample counts.
+    pub fn profiles(&self) -> Vec<(String, usize)> {
+        self.members
+            .iter()
+            .map(|(name, samples)| (name.clone(), samples.len()))
+            .collect()
+    }
+
+    /// The enrolled member whose average voiceprint is closest to `embedding`,
+    /// if the similarity reaches `threshold`.
+    pub fn identify(&self, embedding: &[f32], threshold: f32) -> Option<SpeakerMatch> {
+        self.members
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(member, samples)| {
+                let mut centroid = vec![0.0; embedding.len()];
+                for sample in samples.iter().filter(|s| s.len() == embedding.len()) {
+                    for (c, x) in centroid.iter_mut().zip(sample) {
+                        *c += x / samples.len() as f32;
+                    }
+                }
+                SpeakerMatch {
+                    member: member.clone(),
+                    similarity: cosine_similarity(&centroid, embedding),
+                }
+            })
+            .filter(|m| m.similarity >= threshold)
+            .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A buzzy "voice": harmonics of `pitch` shaped by a formant at `formant` Hz.
+    fn synthetic_voice(pitch: f32, formant: f32, millis: usize, phase: f32) -> Vec<f32> {
+        (0..millis * 16)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                (1..40)
+                    .map(|h| {
+                        let f = pitch * h as f32;
+                        let gain = (-((f - formant) / 400.0).powi(2)).exp() + 0.05;
+                        gain * (2.0 * std::f32::consts::PI * f * t + phase * h as f32).sin()
+                    })
+                    .sum::<f32>()
+                    * 0.05
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_matches_naive_dft() {
+        let signal: Vec<f32> = (0..16).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let mut re = signal.clone();
+        let mut im = vec![0.0; 16];
+        fft(&mut re, &mut im);
+        for k in 0..16 {
+            let (mut dr, mut di) = (0.0f32, 0.0f32);
+            for (n, x) in signal.iter().enumerate() {
+                let a = -2.0 * std::f32::consts::PI * (k * n) as f32 / 16.0;
+                dr += x * a.cos();
+                di += x * a.sin();
+            }
+            assert!((re[k] - dr).abs() < 1e-3 && (im[k] - di).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn silence_and_short_clips_have_no_voiceprint() {
+        assert!(embed(&vec![0.0; 32_000]).is_none());
+        assert!(embed(&synthetic_voice(120.0, 500.0, 500, 0.0)).is_none());
+    }
+
+    #[test]
+    fn identifies_enrolled_voice_and_ignores_loudness() {
+        let mut store = VoiceprintStore::default();
+        store.enroll("Anna", embed(&synthetic_voice(110.0, 600.0, 2000, 0.0)).unwrap());
+        store.enroll("Ben", embed(&synthetic_voice(240.0, 2200.0, 2000, 0.0)).unwrap());
+
+        let quiet_anna: Vec<f32> = synthetic_voice(110.0, 600.0, 2000, 0.7)
+            .into_iter()
+            .map(|x| x * 0.2)
+            .collect();
+        let found = store.identify(&embed(&quiet_anna).unwrap(), 0.9).unwrap();
+        assert_eq!(found.member, "Anna");
+
+        let ben = embed(&synthetic_voice(240.0, 2200.0, 2000, 1.3)).unwrap();
+        assert_eq!(store.identify(&ben, 0.9).unwrap().member, "Ben");
+        assert!(store.identify(&ben, 1.01).is_none());
+    }
+
+    #[test]
+    fn enroll_keeps_latest_samples_and_roundtrips_encrypted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = Some(SecretStore::new(&tmp.path().join(".mymolt"), true));
+        let mut store = VoiceprintStore::default();
+        for i in 0..=MAX_SAMPLES_PER_MEMBER {
+            store.enroll("Anna", vec![i as f32, 1.0]);
+        }
+        assert_eq!(store.profiles(), vec![("Anna".to_string(), MAX_SAMPLES_PER_MEMBER)]);
+        store.save(tmp.path(), &secrets).unwrap();
+
+        let raw = std::fs::read_to_string(store_path(tmp.path())).unwrap();
+        assert!(!raw.contains("Anna"));
+        let mut loaded = VoiceprintStore::load(tmp.path(), &secrets);
+        assert!(loaded.remove("Anna"));
+        assert!(!loaded.remove("Anna"));
+    }
+
+    #[test]
+    fn cosine_similarity_handles_edge_cases() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+}
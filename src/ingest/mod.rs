@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Knowledge ingestion — RAG over the documents in the workspace.
+//!
+//! Markdown, text and PDF files dropped into `<workspace>/knowledge/` are
+//! split with [`chunk_markdown`] and stored in memory under
+//! [`MemoryCategory::Knowledge`], where the backend embeds them like any
+//! other entry and `memory_recall` finds them. A manifest in
+//! `state/knowledge_index.json` remembers what was indexed: each scan only
+//! reads files whose size or modification time changed, re-indexes those
+//! whose content did, and forgets the chunks of deleted files.
+
+pub mod pdf;
+
+use crate::config::{Config, IngestConfig};
+use crate::identity::family::{FamilyRegistry, SCOPE_SHARED};
+use crate::memory::chunker::chunk_markdown;
+use crate::memory::{self, scoped, Memory, MemoryCategory};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+const MANIFEST_FILE: &str = "knowledge_index.json";
+const TEXT_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "text"];
+
+/// What one scan changed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct IngestReport {
+    /// Files new or changed since the last scan, now (re-)indexed
+    pub indexed: usize,
+    pub unchanged: usize,
+    /// Files gone from the folder whose chunks were forgotten
+    pub removed: usize,
+    /// Files over `max_file_bytes`
+    pub skipped: usize,
+    /// Files that could not be read or stored; retried on the next scan
+    pub failed: usize,
+    /// Chunks stored for the indexed files
+    pub chunks: usize,
+}
+
+impl IngestReport {
+    fn changed(&self) -> bool {
+        self.indexed > 0 || self.removed > 0
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    files: BTreeMap<String, IndexedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    size: u64,
+    /// Modification time in Unix milliseconds
+    modified: i64,
+    sha256: String,
+    chunks: usize,
+}
+
+enum FileOutcome {
+    Unchanged,
+    /// Touched but with the same content; only the manifest entry changes
+    Touched(IndexedFile),
+    Indexed(IndexedFile),
+    Skipped,
+}
+
+/// Indexes one knowledge folder into memory.
+pub struct Ingestor {
+    root: PathBuf,
+    manifest_path: PathBuf,
+    memory: Arc<dyn Memory>,
+    chunk_tokens: usize,
+    max_file_bytes: u64,
+}
+
+impl Ingestor {
+    pub fn new(config: &IngestConfig, workspace_dir: &Path, memory: Arc<dyn Memory>) -> Self {
+        Self {
+            root: workspace_dir.join(&config.dir),
+            manifest_path: workspace_dir.join("state").join(MANIFEST_FILE),
+            memory,
+            chunk_tokens: config.chunk_tokens.max(32),
+            max_file_bytes: config.max_file_bytes,
+        }
+    }
+
+    /// Memory key of chunk `index` of the file at `relative` (a `/`-separated
+    /// path inside the knowledge folder).
+    pub fn chunk_key(relative: &str, index: usize) -> String {
+        format!("knowledge:{relative}#{index}")
+    }
+
+    /// Bring memory in line with the folder.
+    pub async fn scan(&self) -> Result<IngestReport> {
+        let mut manifest = self.load_manifest();
+        let mut report = IngestReport::default();
+        let mut seen = HashSet::new();
+
+        for (relative, path) in self.documents()? {
+            let previous = manifest.files.get(&relative).cloned();
+            match self.index_file(&relative, &path, previous.as_ref()).await {
+                Ok(FileOutcome::Unchanged) => report.unchanged += 1,
+                Ok(FileOutcome::Touched(file)) => {
+                    report.unchanged += 1;
+                    manifest.files.insert(relative.clone(), file);
+                }
+                Ok(FileOutcome::Indexed(file)) => {
+                    report.indexed += 1;
+                    report.chunks += file.chunks;
+                    manifest.files.insert(relative.clone(), file);
+                }
+                Ok(FileOutcome::Skipped) => {
+                    // Counts as gone: chunks of a smaller earlier version are forgotten.
+                    report.skipped += 1;
+                    continue;
+                }
+                Err(e) => {
+                    // Keep the old entry and its chunks; the next scan tries again.
+                    tracing::warn!("Failed to index knowledge file {relative}: {e}");
+                    report.failed += 1;
+                }
+            }
+            seen.insert(relative);
+        }
+
+        let gone: Vec<String> = manifest
+            .files
+            .keys()
+            .filter(|relative| !seen.contains(*relative))
+            .cloned()
+            .collect();
+        for relative in gone {
+            let chunks = manifest.files[&relative].chunks;
+            match self.forget_chunks(&relative, 0..chunks).await {
+                Ok(()) => {
+                    manifest.files.remove(&relative);
+                    report.removed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to forget knowledge file {relative}: {e}");
+                    report.failed += 1;
+                }
+            }
+        }
+
+        self.save_manifest(&manifest)?;
+        Ok(report)
+    }
+
+    async fn index_file(
+        &self,
+        relative: &str,
+        path: &Path,
+        previous: Option<&IndexedFile>,
+    ) -> Result<FileOutcome> {
+        let meta = tokio::fs::metadata(path).await?;
+        let size = meta.len();
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX));
+        if previous.is_some_and(|p| p.size == size && p.modified == modified) {
+            return Ok(FileOutcome::Unchanged);
+        }
+        if size > self.max_file_bytes {
+            tracing::debug!("Skipping knowledge file {relative}: {size} bytes");
+            return Ok(FileOutcome::Skipped);
+        }
+
+        let bytes = tokio::fs::read(path).await?;
+        let sha256 = hex::encode(Sha256::digest(&bytes));
+        if let Some(previous) = previous.filter(|p| p.sha256 == sha256) {
+            return Ok(FileOutcome::Touched(IndexedFile {
+                size,
+                modified,
+                sha256,
+                chunks: previous.chunks,
+            }));
+        }
+
+        let text = if is_pdf(path) {
+            pdf::extract_text(path).await?
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+        let chunks = chunk_markdown(&text, self.chunk_tokens);
+        for chunk in &chunks {
+            let metadata = serde_json::json!({
+                "source": relative,
+                "chunk": chunk.index,
+                "heading": chunk.heading,
+            });
+            self.memory
+                .store_with_metadata(
+                    &Self::chunk_key(relative, chunk.index),
+                    &chunk.content,
+                    MemoryCategory::Knowledge,
+                    &[],
+                    &metadata,
+                )
+                .await?;
+        }
+        // Chunks past the new end belong to the previous version.
+        if let Some(previous) = previous {
+            self.forget_chunks(relative, chunks.len()..previous.chunks)
+                .await?;
+        }
+
+        Ok(FileOutcome::Indexed(IndexedFile {
+            size,
+            modified,
+            sha256,
+            chunks: chunks.len(),
+        }))
+    }
+
+    async fn forget_chunks(&self, relative: &str, indices: Range<usize>) -> Result<()> {
+        for index in indices {
+            self.memory
+                .forget(&Self::chunk_key(relative, index))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Supported files under the folder, by `/`-separated relative path.
+    /// Hidden files and directories and symlinks are left out.
+    fn documents(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut found = Vec::new();
+        if !self.root.is_dir() {
+            return Ok(found);
+        }
+        let mut pending = vec![self.root.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?;
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                let file_type = entry.file_type()?;
+                let path = entry.path();
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else if file_type.is_file() && is_supported(&path) {
+                    let relative = path
+                        .strip_prefix(&self.root)?
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    found.push((relative, path));
+                }
+            }
+        }
+        found.sort();
+        Ok(found)
+    }
+
+    fn load_manifest(&self) -> Manifest {
+        let Ok(raw) = std::fs::read_to_string(&self.manifest_path) else {
+            return Manifest::default();
+        };
+        serde_json::from_str(&raw).unwrap_or_else(|e| {
+            tracing::warn!("Corrupt knowledge manifest, re-indexing everything: {e}");
+            Manifest::default()
+        })
+    }
+
+    fn save_manifest(&self, manifest: &Manifest) -> Result<()> {
+        if let Some(parent) = self.manifest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.manifest_path, serde_json::to_vec_pretty(manifest)?)?;
+        Ok(())
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn is_pdf(path: &Path) -> bool {
+    extension(path) == "pdf"
+}
+
+fn is_supported(path: &Path) -> bool {
+    is_pdf(path) || TEXT_EXTENSIONS.contains(&extension(path).as_str())
+}
+
+/// Daemon component: scan the knowledge folder every `interval_secs`.
+pub async fn run(config: Config) -> Result<()> {
+    let audit = Arc::new(crate::security::AuditLogger::new(
+        config.security.audit.clone(),
+        config.workspace_dir.clone(),
+    )?);
+    let memory: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        &config.memory,
+        &config.security.sensitivity,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        audit,
+    )?);
+    // In family mode knowledge goes to the shared scope, visible to every member.
+    let scope = FamilyRegistry::from_config(&config.family)
+        .is_active()
+        .then(|| SCOPE_SHARED.to_string());
+    let ingestor = Ingestor::new(
+        &config.ingest,
+        &config.workspace_dir,
+        scoped::confine(&memory, scope),
+    );
+    tracing::info!("📚 Indexing knowledge files in {}", ingestor.root.display());
+    crate::health::mark_component_ok("ingest");
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(config.ingest.interval_secs.max(5)));
+    loop {
+        interval.tick().await;
+        match ingestor.scan().await {
+            Ok(report) => {
+                if report.changed() {
+                    tracing::info!("📚 Knowledge re-indexed: {report:?}");
+                }
+                crate::health::mark_component_ok("ingest");
+            }
+            Err(e) => {
+                crate::health::mark_component_error("ingest", e.to_string());
+                tracing::warn!("Knowledge scan failed: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::simple::SimpleMemory;
+
+    fn ingestor(workspace: &Path, memory: &Arc<dyn Memory>) -> Ingestor {
+        Ingestor::new(&IngestConfig::default(), workspace, Arc::clone(memory))
+    }
+
+    /// Rewrite a file with a distinct modification time, so a scan in the
+    /// same millisecond still sees the change.
+    fn rewrite(path: &Path, content: &str, age_secs: u64) {
+        std::fs::write(path, content).unwrap();
+        let mtime = std::time::SystemTime::now() - Duration::from_secs(age_secs);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn scan_indexes_changes_and_forgets_deleted_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("knowledge");
+        std::fs::create_dir_all(dir.join("recipes")).unwrap();
+        std::fs::write(dir.join(".draft.md"), "hidden").unwrap();
+        std::fs::write(dir.join("photo.jpg"), "not text").unwrap();
+        rewrite(
+            &dir.join("recipes/bread.md"),
+            "# Bread\nFlour, water, salt.",
+            30,
+        );
+        rewrite(&dir.join("wifi.txt"), "Guest network: molt-guest", 30);
+
+        let memory: Arc<dyn Memory> = Arc::new(SimpleMemory::new());
+        let ingestor = ingestor(tmp.path(), &memory);
+
+        let first = ingestor.scan().await.unwrap();
+        assert_eq!((first.indexed, first.unchanged), (2, 0));
+        let bread = memory
+            .get(&Ingestor::chunk_key("recipes/bread.md", 0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bread.category, MemoryCategory::Knowledge);
+        assert!(bread.content.contains("Flour"));
+        assert_eq!(bread.metadata["source"], "recipes/bread.md");
+
+        let again = ingestor.scan().await.unwrap();
+        assert_eq!((again.indexed, again.unchanged), (0, 2));
+
+        rewrite(
+            &dir.join("recipes/bread.md"),
+            "# Bread\nRye, water, salt.",
+            10,
+        );
+        std::fs::remove_file(dir.join("wifi.txt")).unwrap();
+        let changed = ingestor.scan().await.unwrap();
+        assert_eq!((changed.indexed, changed.removed), (1, 1));
+        let bread = memory
+            .get(&Ingestor::chunk_key("recipes/bread.md", 0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(bread.content.contains("Rye"));
+        assert!(memory
+            .get(&Ingestor::chunk_key("wifi.txt", 0))
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(memory.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn scan_forgets_trailing_chunks_when_a_file_shrinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("knowledge");
+        std::fs::create_dir_all(&dir).unwrap();
+        let long = (1..=3)
+            .map(|i| format!("## Part {i}\n{}", "word ".repeat(200)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        rewrite(&dir.join("manual.md"), &long, 30);
+
+        let memory: Arc<dyn Memory> = Arc::new(SimpleMemory::new());
+        let ingestor = ingestor(tmp.path(), &memory);
+        let first = ingestor.scan().await.unwrap();
+        assert!(first.chunks > 1);
+
+        rewrite(&dir.join("manual.md"), "## Part 1\nShort now.", 10);
+        let second = ingestor.scan().await.unwrap();
+        assert_eq!(second.chunks, 1);
+        assert_eq!(memory.count().await.unwrap(), 1);
+    }
+}
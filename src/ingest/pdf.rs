@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! PDF text extraction through the `pdftotext` command-line tool (poppler).
+//!
+//! Like OCR, extraction runs locally; only the text ends up in memory.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+const EXTRACT_TIMEOUT_SECS: u64 = 120;
+
+/// Text of the PDF at `path`, pages separated by blank lines.
+pub async fn extract_text(path: &Path) -> Result<String> {
+    let child = match Command::new("pdftotext")
+        .args(["-enc", "UTF-8", "-nopgbrk"])
+        .arg(path)
+        .arg("-")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("pdftotext is not installed; install poppler-utils to index PDFs")
+        }
+        Err(e) => return Err(e).context("Failed to start pdftotext"),
+    };
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(EXTRACT_TIMEOUT_SECS),
+        child.wait_with_output(),
+    )
+    .await
+    .context("PDF extraction timed out")??;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("pdftotext failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -746,6 +746,7 @@ mod tests {
         config.channels_config.telegram = Some(TelegramConfig {
             bot_token: "123:ABC".into(),
             allowed_users: vec!["user".into()],
+            mode: crate::config::TelegramMode::default(),
         });
         let entries = all_integrations();
         let tg = entries.iter().find(|e| e.name == "Telegram").unwrap();
@@ -793,6 +794,7 @@ mod tests {
             access_token: "tok".into(),
             room_id: "!r:m".into(),
             allowed_users: vec![],
+            e2ee: false,
         });
         let entries = all_integrations();
         let mx = entries.iter().find(|e| e.name == "Matrix").unwrap();
@@ -43,6 +43,8 @@ use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 
 pub mod agent;
+pub mod automations;
+pub mod capture;
 pub mod channels;
 pub mod config;
 pub mod cron;
@@ -53,6 +55,7 @@ pub mod hardware;
 pub mod health;
 pub mod heartbeat;
 pub mod identity;
+pub mod ingest;
 pub mod integrations;
 pub mod mcp;
 pub mod memory;
@@ -65,6 +68,7 @@ pub mod runtime;
 pub mod security;
 pub mod service;
 pub mod skills;
+pub mod sync;
 pub mod tools;
 pub mod tunnel;
 pub mod util;
@@ -139,6 +143,20 @@ pub enum MigrateCommands {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Encrypt the content of an existing `SQLite` memory store at rest
+    EncryptMemory {
+        /// Decrypt the store back to plaintext instead
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// Re-wrap vault entry keys for the admin key and `hoodik/keys/recipients/*.pub`
+    VaultKeys {
+        /// Generate a new admin keypair first and retire the old one
+        #[arg(long)]
+        rotate: bool,
+    },
+    /// Move master key files into the backend set in `secrets.key_backend`
+    KeyBackend,
 }
 
 /// Cron subcommands
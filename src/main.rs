@@ -42,6 +42,8 @@ use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
 mod agent;
+mod automations;
+mod capture;
 mod channels;
 mod config;
 mod cron;
@@ -52,6 +54,7 @@ mod hardware;
 mod health;
 mod heartbeat;
 mod identity;
+mod ingest;
 mod integrations;
 mod memory;
 mod migration;
@@ -63,6 +66,7 @@ mod security;
 mod service;
 mod skillforge;
 mod skills;
+mod sync;
 mod tools;
 mod tunnel;
 mod util;
@@ -178,6 +182,9 @@ enum Commands {
     /// Show system status (full details)
     Status,
 
+    /// Check the audit log for gaps, edits and bad signatures
+    VerifyAudit,
+
     /// Configure and manage scheduled tasks
     Cron {
         #[command(subcommand)]
@@ -190,6 +197,12 @@ enum Commands {
         model_command: ModelCommands,
     },
 
+    /// Encrypted sync with your other MyMolt devices
+    Sync {
+        #[command(subcommand)]
+        sync_command: SyncCommands,
+    },
+
     /// Manage channels (telegram, discord, slack)
     Channel {
         #[command(subcommand)]
@@ -227,6 +240,20 @@ enum MigrateCommands {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Encrypt the content of an existing `SQLite` memory store at rest
+    EncryptMemory {
+        /// Decrypt the store back to plaintext instead
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// Re-wrap vault entry keys for the admin key and `hoodik/keys/recipients/*.pub`
+    VaultKeys {
+        /// Generate a new admin keypair first and retire the old one
+        #[arg(long)]
+        rotate: bool,
+    },
+    /// Move master key files into the backend set in `secrets.key_backend`
+    KeyBackend,
 }
 
 #[derive(Subcommand, Debug)]
@@ -261,6 +288,14 @@ enum ModelCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum SyncCommands {
+    /// Print this device's sync public key (add it to the peer's [[sync.peers]])
+    Id,
+    /// Sync once with every configured peer
+    Now,
+}
+
 #[derive(Subcommand, Debug)]
 enum ChannelCommands {
     /// List configured channels
@@ -473,10 +508,60 @@ async fn main() -> Result<()> {
             }
         },
 
+        Commands::Sync { sync_command } => match sync_command {
+            SyncCommands::Id => {
+                println!("{}", sync::public_identity(&config.workspace_dir)?);
+                Ok(())
+            }
+            SyncCommands::Now => {
+                let engine = sync::SyncEngine::new(&config)?;
+                for (peer, result) in engine.sync_all().await {
+                    match result {
+                        Ok(report) => println!(
+                            "✅ {peer}: {} memories, {} PIM items, soul {}",
+                            report.memories,
+                            report.pim_items,
+                            if report.soul { "updated" } else { "unchanged" }
+                        ),
+                        Err(e) => println!("❌ {peer}: {e}"),
+                    }
+                }
+                Ok(())
+            }
+        },
+
         Commands::Service { service_command } => service::handle_command(&service_command, &config),
 
         Commands::Doctor => doctor::run(&config),
 
+        Commands::VerifyAudit => {
+            let audit = security::AuditLogger::new(
+                config.security.audit.clone(),
+                config.workspace_dir.clone(),
+            )?;
+            let report = audit.verify_log()?;
+            println!(
+                "🔏 {} events in {} files ({} signed, {} from before chaining)",
+                report.events,
+                report.files.len(),
+                report.signed,
+                report.unchained
+            );
+            for issue in &report.issues {
+                println!(
+                    "  ❌ {}:{} {}",
+                    issue.file.display(),
+                    issue.line,
+                    issue.problem
+                );
+            }
+            if !report.is_intact() {
+                bail!("Audit log has {} problems", report.issues.len());
+            }
+            println!("✅ Audit log intact");
+            Ok(())
+        }
+
         Commands::Channel { channel_command } => match channel_command {
             ChannelCommands::Start => channels::start_channels(config).await,
             ChannelCommands::Doctor => channels::doctor_channels(config).await,
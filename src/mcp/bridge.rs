@@ -8,7 +8,7 @@
 //! so they appear alongside native tools in the agent loop.
 
 use crate::mcp::gatekeeper::SigilGatekeeper;
-use crate::mcp::transport::McpClient;
+use crate::mcp::supervisor::McpServer;
 use crate::tools::traits::{Tool, ToolResult};
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -16,14 +16,14 @@ use std::sync::Arc;
 /// An MCP tool exposed as a MyMolt `Tool`.
 ///
 /// Wraps a single tool from an MCP server, forwarding calls through
-/// the SIGIL gatekeeper.
+/// the SIGIL gatekeeper to the server's current connection.
 pub struct McpTool {
     tool_name: String,
     description: String,
     schema: serde_json::Value,
-    client: Arc<McpClient>,
+    output_schema: Option<serde_json::Value>,
+    server: Arc<McpServer>,
     gatekeeper: Arc<SigilGatekeeper>,
-    server_name: String,
 }
 
 impl McpTool {
@@ -32,19 +32,24 @@ impl McpTool {
         tool_name: String,
         description: String,
         schema: serde_json::Value,
-        client: Arc<McpClient>,
+        server: Arc<McpServer>,
         gatekeeper: Arc<SigilGatekeeper>,
-        server_name: String,
     ) -> Self {
         Self {
             tool_name,
             description,
             schema,
-            client,
+            output_schema: None,
+            server,
             gatekeeper,
-            server_name,
         }
     }
+
+    /// Declare the schema of the server's `structuredContent`.
+    pub fn with_output_schema(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.output_schema = schema;
+        self
+    }
 }
 
 #[async_trait]
@@ -61,6 +66,10 @@ impl Tool for McpTool {
         self.schema.clone()
     }
 
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        self.output_schema.clone()
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         // 1. SIGIL gate check
         if let Err(reason) = self.gatekeeper.gate_request(&self.tool_name) {
@@ -68,12 +77,24 @@ impl Tool for McpTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("SIGIL: {reason}")),
+                data: None,
             });
         }
 
         // 2. Forward to MCP server
+        let Some(client) = self.server.client().await else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "MCP server '{}' is unavailable; reconnecting",
+                    self.server.name()
+                )),
+                data: None,
+            });
+        };
         let arguments = args.as_object().cloned();
-        match self.client.call_tool(&self.tool_name, arguments).await {
+        match client.call_tool(&self.tool_name, arguments).await {
             Ok(result) => {
                 // Extract text content from MCP response
                 let output: String = result
@@ -89,16 +110,20 @@ impl Tool for McpTool {
                     success: !is_error,
                     output: output.clone(),
                     error: if is_error { Some(output) } else { None },
+                    data: result.structured_content,
+                })
+            }
+            Err(e) => {
+                // Transport or protocol failure: let the supervisor check
+                // whether the server is still alive.
+                self.server.report_failure();
+                Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("MCP server '{}' error: {e}", self.server.name())),
+                    data: None,
                 })
             }
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!(
-                    "MCP server '{}' error: {e}",
-                    self.server_name
-                )),
-            }),
         }
     }
 }
@@ -107,13 +132,13 @@ impl Tool for McpTool {
 pub struct McpToolBridge;
 
 impl McpToolBridge {
-    /// Discover tools from an MCP client and wrap them as MyMolt `Tool` instances.
+    /// Wrap the tools discovered on a supervised server as MyMolt `Tool` instances.
     pub async fn discover_tools(
-        client: Arc<McpClient>,
+        server: Arc<McpServer>,
         gatekeeper: Arc<SigilGatekeeper>,
     ) -> anyhow::Result<Vec<Box<dyn Tool>>> {
-        let tools_info = client.list_tools().await?;
-        let server_name = client.server_name().to_string();
+        let tools_info = server.tools().await;
+        let server_name = server.name().to_string();
         let mut tools: Vec<Box<dyn Tool>> = Vec::new();
 
         for tool_info in tools_info {
@@ -123,14 +148,16 @@ impl McpToolBridge {
                 "Discovered MCP tool"
             );
 
-            tools.push(Box::new(McpTool::new(
-                tool_info.name,
-                tool_info.description.unwrap_or_default(),
-                tool_info.input_schema,
-                client.clone(),
-                gatekeeper.clone(),
-                server_name.clone(),
-            )));
+            tools.push(Box::new(
+                McpTool::new(
+                    tool_info.name,
+                    tool_info.description.unwrap_or_default(),
+                    tool_info.input_schema,
+                    server.clone(),
+                    gatekeeper.clone(),
+                )
+                .with_output_schema(tool_info.output_schema),
+            ));
         }
 
         tracing::info!(
@@ -4,77 +4,161 @@
 
 //! MCP (Model Context Protocol) client integration.
 //!
-//! Connects MyMolt to MCP servers, discovers their tools, and exposes them
-//! to the agent loop — all gated through SIGIL security.
+//! Connects MyMolt to MCP servers, discovers their tools, resources and
+//! prompts, and exposes them to the agent loop — all gated through SIGIL
+//! security.
 
 pub mod bridge;
 pub mod gatekeeper;
+pub mod resources;
+pub mod supervisor;
 pub mod transport;
 
 pub use bridge::McpToolBridge;
 pub use gatekeeper::SigilGatekeeper;
+pub use resources::McpResourceReadTool;
+pub use supervisor::McpServer;
 pub use transport::McpClient;
 
-use crate::config::McpConfig;
+use crate::config::{McpConfig, McpServerConfig};
 use crate::security::{AuditLogger, SecurityPolicy};
 use crate::tools::Tool;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+struct RegisteredServer {
+    server: Arc<McpServer>,
+    /// Names of the tools bridged from this server.
+    tools: Vec<String>,
+}
+
+/// The supervised MCP servers of this process.
+#[derive(Default)]
+pub struct McpRegistry {
+    servers: RwLock<Vec<RegisteredServer>>,
+}
+
+impl McpRegistry {
+    pub async fn servers(&self) -> Vec<Arc<McpServer>> {
+        self.servers
+            .read()
+            .await
+            .iter()
+            .map(|entry| Arc::clone(&entry.server))
+            .collect()
+    }
+
+    /// Look up a connected server by name (case-insensitive).
+    pub async fn get(&self, name: &str) -> Option<Arc<McpServer>> {
+        self.servers
+            .read()
+            .await
+            .iter()
+            .find(|entry| entry.server.name().eq_ignore_ascii_case(name))
+            .map(|entry| Arc::clone(&entry.server))
+    }
+
+    /// Track `server` together with the names of the tools bridged from it.
+    pub async fn add(&self, server: Arc<McpServer>, tools: Vec<String>) {
+        self.servers
+            .write()
+            .await
+            .push(RegisteredServer { server, tools });
+    }
+
+    /// Stop tracking a server; returns it and its tool names.
+    pub async fn remove(&self, name: &str) -> Option<(Arc<McpServer>, Vec<String>)> {
+        let mut servers = self.servers.write().await;
+        let index = servers
+            .iter()
+            .position(|entry| entry.server.name().eq_ignore_ascii_case(name))?;
+        let entry = servers.remove(index);
+        Some((entry.server, entry.tools))
+    }
+
+    /// Whether any connected server exposes resources.
+    pub async fn has_resources(&self) -> bool {
+        for server in self.servers().await {
+            if server
+                .client()
+                .await
+                .is_some_and(|c| c.supports("resources"))
+            {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Servers connected by [`discover_mcp_tools`] and the tools bridged from
+/// them.
+pub struct McpDiscovery {
+    pub registry: Arc<McpRegistry>,
+    pub tools: Vec<Box<dyn Tool>>,
+}
+
+/// Connect one server under supervision and bridge its tools through
+/// `gatekeeper`.
+pub async fn start_server(
+    server_cfg: &McpServerConfig,
+    mcp_config: &McpConfig,
+    gatekeeper: Arc<SigilGatekeeper>,
+) -> anyhow::Result<(Arc<McpServer>, Vec<Box<dyn Tool>>)> {
+    tracing::info!(
+        server = %server_cfg.name,
+        endpoint = %server_cfg.url.as_deref().unwrap_or(&server_cfg.command),
+        "Connecting to MCP server"
+    );
+    let server = McpServer::start(
+        server_cfg.clone(),
+        Duration::from_secs(mcp_config.ping_interval_secs.max(1)),
+        Duration::from_secs(mcp_config.max_backoff_secs),
+    )
+    .await?;
+    match McpToolBridge::discover_tools(Arc::clone(&server), gatekeeper).await {
+        Ok(tools) => Ok((server, tools)),
+        Err(e) => {
+            server.stop().await;
+            Err(e)
+        }
+    }
+}
 
 /// Connect to all configured MCP servers and discover their tools.
 ///
 /// Each tool is wrapped through the SIGIL gatekeeper for policy enforcement
-/// and audit logging. Returns an empty vec if no MCP servers are configured.
+/// and audit logging. Connected servers are supervised: they are pinged every
+/// `ping_interval_secs` and reconnected with backoff if they die. When any
+/// server exposes resources, `mcp_resource_read` is added to the tools.
 pub async fn discover_mcp_tools(
     mcp_config: &McpConfig,
     security: &Arc<SecurityPolicy>,
     audit: &Arc<AuditLogger>,
-) -> Vec<Box<dyn Tool>> {
+) -> McpDiscovery {
+    let registry = Arc::new(McpRegistry::default());
+    let mut mcp_tools: Vec<Box<dyn Tool>> = Vec::new();
     if !mcp_config.enabled || mcp_config.servers.is_empty() {
-        return Vec::new();
+        return McpDiscovery {
+            registry,
+            tools: mcp_tools,
+        };
     }
 
-    let mut mcp_tools: Vec<Box<dyn Tool>> = Vec::new();
+    let gatekeeper = Arc::new(SigilGatekeeper::new(security.clone(), Some(audit.clone())));
 
     for server_cfg in &mcp_config.servers {
-        tracing::info!(
-            server = %server_cfg.name,
-            command = %server_cfg.command,
-            "Connecting to MCP server"
-        );
-
-        match McpClient::connect(
-            &server_cfg.name,
-            &server_cfg.command,
-            &server_cfg.args,
-            &server_cfg.env,
-        )
-        .await
-        {
-            Ok(client) => {
-                let client = Arc::new(client);
-                let gatekeeper = Arc::new(SigilGatekeeper::new(
-                    security.clone(),
-                    Some(audit.clone()),
-                ));
-
-                match McpToolBridge::discover_tools(client, gatekeeper).await {
-                    Ok(discovered) => {
-                        let count = discovered.len();
-                        tracing::info!(
-                            server = %server_cfg.name,
-                            count,
-                            "MCP tools discovered"
-                        );
-                        mcp_tools.extend(discovered);
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            server = %server_cfg.name,
-                            error = %e,
-                            "Failed to discover MCP tools"
-                        );
-                    }
-                }
+        match start_server(server_cfg, mcp_config, gatekeeper.clone()).await {
+            Ok((server, discovered)) => {
+                tracing::info!(
+                    server = %server_cfg.name,
+                    count = discovered.len(),
+                    "MCP tools discovered"
+                );
+                let names = discovered.iter().map(|t| t.name().to_string()).collect();
+                registry.add(server, names).await;
+                mcp_tools.extend(discovered);
             }
             Err(e) => {
                 tracing::warn!(
@@ -86,5 +170,15 @@ pub async fn discover_mcp_tools(
         }
     }
 
-    mcp_tools
+    if registry.has_resources().await {
+        mcp_tools.push(Box::new(McpResourceReadTool::new(
+            Arc::clone(&registry),
+            gatekeeper,
+        )));
+    }
+
+    McpDiscovery {
+        registry,
+        tools: mcp_tools,
+    }
 }
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! `mcp_resource_read` — MCP resources as readable context for the agent.
+
+use crate::mcp::gatekeeper::SigilGatekeeper;
+use crate::mcp::McpRegistry;
+use crate::tools::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Upper bound on resource text handed back to the model.
+const MAX_OUTPUT_CHARS: usize = 50_000;
+
+/// Lists and reads resources of all connected MCP servers.
+pub struct McpResourceReadTool {
+    registry: Arc<McpRegistry>,
+    gatekeeper: Arc<SigilGatekeeper>,
+}
+
+impl McpResourceReadTool {
+    pub fn new(registry: Arc<McpRegistry>, gatekeeper: Arc<SigilGatekeeper>) -> Self {
+        Self {
+            registry,
+            gatekeeper,
+        }
+    }
+
+    async fn list(&self, server: Option<&str>) -> String {
+        let mut listing = String::new();
+        for s in self.registry.servers().await {
+            if server.is_some_and(|name| !s.name().eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            for r in s.resources().await {
+                let _ = write!(listing, "- [{}] {}", s.name(), r.uri);
+                if !r.name.is_empty() {
+                    let _ = write!(listing, " — {}", r.name);
+                }
+                if let Some(description) = &r.description {
+                    let _ = write!(listing, ": {description}");
+                }
+                listing.push('\n');
+            }
+        }
+        if listing.is_empty() {
+            "No MCP resources available.".into()
+        } else {
+            listing
+        }
+    }
+
+    async fn read(&self, uri: &str, server: Option<&str>) -> anyhow::Result<String> {
+        let target = match server {
+            Some(name) => self.registry.get(name).await,
+            None => {
+                let mut owner = None;
+                for s in self.registry.servers().await {
+                    if s.resources().await.iter().any(|r| r.uri == uri) {
+                        owner = Some(s);
+                        break;
+                    }
+                }
+                owner
+            }
+        };
+        let Some(target) = target else {
+            anyhow::bail!("No MCP server exposes '{uri}'; pass `server` or list resources first");
+        };
+        target.read_resource(uri).await
+    }
+}
+
+#[async_trait]
+impl Tool for McpResourceReadTool {
+    fn name(&self) -> &str {
+        "mcp_resource_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a resource (file, document, record) exposed by a connected MCP server. \
+         Call without `uri` to list the available resources."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "uri": {
+                    "type": "string",
+                    "description": "Resource URI to read; omit to list resources"
+                },
+                "server": {
+                    "type": "string",
+                    "description": "MCP server name; needed for URIs that are not listed"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Err(reason) = self.gatekeeper.gate_request(self.name()) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("SIGIL: {reason}")),
+                data: None,
+            });
+        }
+
+        let server = args.get("server").and_then(|v| v.as_str());
+        let Some(uri) = args.get("uri").and_then(|v| v.as_str()) else {
+            return Ok(ToolResult {
+                success: true,
+                output: self.list(server).await,
+                error: None,
+                data: None,
+            });
+        };
+
+        Ok(match self.read(uri, server).await {
+            Ok(text) => ToolResult {
+                success: true,
+                output: crate::util::truncate_with_ellipsis(&text, MAX_OUTPUT_CHARS),
+                error: None,
+                data: None,
+            },
+            Err(e) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("{e:#}")),
+                data: None,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SecurityPolicy;
+
+    fn tool() -> McpResourceReadTool {
+        McpResourceReadTool::new(
+            Arc::new(McpRegistry::default()),
+            Arc::new(SigilGatekeeper::new(
+                Arc::new(SecurityPolicy::default()),
+                None,
+            )),
+        )
+    }
+
+    #[tokio::test]
+    async fn lists_nothing_without_servers() {
+        let result = tool().execute(json!({})).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "No MCP resources available.");
+    }
+
+    #[tokio::test]
+    async fn unknown_uri_is_an_error() {
+        let result = tool()
+            .execute(json!({ "uri": "file:///notes.md" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("No MCP server exposes"));
+    }
+}
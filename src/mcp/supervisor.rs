@@ -0,0 +1,302 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! MCP server supervision — liveness pings and auto-reconnect.
+//!
+//! Every configured server is owned by an [`McpServer`] handle. Bridged tools
+//! look up the current client through it, so a reconnect is picked up by
+//! tools that were registered before the server went away. A background task
+//! pings the server, and on failure reconnects with exponential backoff and
+//! re-discovers its tools, resources and prompts. Status is reported to the health registry as
+//! `mcp:<name>`.
+
+use crate::config::schema::McpServerConfig;
+use crate::mcp::transport::{McpClient, McpPromptInfo, McpResourceInfo, McpToolInfo};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
+
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF_SECS: u64 = 2;
+
+/// Health registry component name of an MCP server.
+pub fn component_name(server: &str) -> String {
+    format!("mcp:{server}")
+}
+
+/// A supervised connection to one MCP server.
+pub struct McpServer {
+    config: McpServerConfig,
+    client: RwLock<Option<Arc<McpClient>>>,
+    tools: RwLock<Vec<McpToolInfo>>,
+    resources: RwLock<Vec<McpResourceInfo>>,
+    prompts: RwLock<Vec<McpPromptInfo>>,
+    /// Wakes the monitor early when a tool call hits a transport error.
+    check_now: Notify,
+    /// Set by [`McpServer::stop`]; the monitor exits instead of reconnecting.
+    stopped: AtomicBool,
+}
+
+impl McpServer {
+    /// Connect and discover tools. On success a monitor task is spawned that
+    /// lives as long as the returned handle.
+    pub async fn start(
+        config: McpServerConfig,
+        ping_interval: Duration,
+        max_backoff: Duration,
+    ) -> anyhow::Result<Arc<Self>> {
+        let server = Arc::new(Self {
+            config,
+            client: RwLock::new(None),
+            tools: RwLock::new(Vec::new()),
+            resources: RwLock::new(Vec::new()),
+            prompts: RwLock::new(Vec::new()),
+            check_now: Notify::new(),
+            stopped: AtomicBool::new(false),
+        });
+        let component = component_name(server.name());
+        if let Err(e) = server.connect().await {
+            crate::health::mark_component_error(&component, format!("{e:#}"));
+            return Err(e);
+        }
+        crate::health::mark_component_ok(&component);
+        tokio::spawn(monitor(Arc::downgrade(&server), ping_interval, max_backoff));
+        Ok(server)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// The live client, or `None` while the server is down.
+    pub async fn client(&self) -> Option<Arc<McpClient>> {
+        self.client.read().await.clone()
+    }
+
+    /// Tools from the most recent discovery.
+    pub async fn tools(&self) -> Vec<McpToolInfo> {
+        self.tools.read().await.clone()
+    }
+
+    /// Resources from the most recent discovery.
+    pub async fn resources(&self) -> Vec<McpResourceInfo> {
+        self.resources.read().await.clone()
+    }
+
+    /// Prompt templates from the most recent discovery.
+    pub async fn prompts(&self) -> Vec<McpPromptInfo> {
+        self.prompts.read().await.clone()
+    }
+
+    async fn live_client(&self) -> anyhow::Result<Arc<McpClient>> {
+        self.client()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("MCP server '{}' is unavailable", self.name()))
+    }
+
+    /// Read a resource as text. Binary contents are summarized, not inlined.
+    pub async fn read_resource(&self, uri: &str) -> anyhow::Result<String> {
+        let client = self.live_client().await?;
+        let contents = client.read_resource(uri).await.inspect_err(|_| {
+            self.report_failure();
+        })?;
+        Ok(contents
+            .iter()
+            .map(|c| match (&c.text, &c.blob) {
+                (Some(text), _) => text.clone(),
+                (None, Some(blob)) => format!(
+                    "[binary {} from {}, {} base64 chars]",
+                    c.mime_type.as_deref().unwrap_or("data"),
+                    c.uri,
+                    blob.len()
+                ),
+                (None, None) => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+
+    /// Render a prompt template to plain text, one paragraph per message.
+    pub async fn render_prompt(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> anyhow::Result<String> {
+        let client = self.live_client().await?;
+        let messages = client.get_prompt(name, arguments).await.inspect_err(|_| {
+            self.report_failure();
+        })?;
+        Ok(messages
+            .iter()
+            .filter_map(|m| m.content.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+
+    /// Ask the monitor to check the server right away.
+    pub fn report_failure(&self) {
+        self.check_now.notify_one();
+    }
+
+    /// (Re)connect and re-run discovery.
+    async fn connect(&self) -> anyhow::Result<()> {
+        let client = McpClient::connect_server(&self.config).await?;
+        let tools = client.list_tools().await?;
+        // Resources and prompts are optional capabilities; a server that
+        // fails to list them still serves its tools.
+        let resources = if client.supports("resources") {
+            client.list_resources().await.unwrap_or_else(|e| {
+                tracing::warn!(server = %self.name(), error = %e, "Failed to list MCP resources");
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        let prompts = if client.supports("prompts") {
+            client.list_prompts().await.unwrap_or_else(|e| {
+                tracing::warn!(server = %self.name(), error = %e, "Failed to list MCP prompts");
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        *self.tools.write().await = tools;
+        *self.resources.write().await = resources;
+        *self.prompts.write().await = prompts;
+        *self.client.write().await = Some(Arc::new(client));
+        Ok(())
+    }
+
+    async fn is_alive(&self) -> bool {
+        let Some(client) = self.client().await else {
+            return false;
+        };
+        matches!(
+            tokio::time::timeout(PING_TIMEOUT, client.ping()).await,
+            Ok(Ok(()))
+        )
+    }
+
+    /// Disconnect for good: stop the monitor and drop the server from the
+    /// health registry.
+    pub async fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.disconnect().await;
+        self.check_now.notify_one();
+        crate::health::remove_component(&component_name(self.name()));
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Drop the current client. Stdio servers are killed once in-flight
+    /// calls release their reference.
+    async fn disconnect(&self) {
+        self.client.write().await.take();
+    }
+}
+
+/// Ping `server` every `ping_interval`; reconnect with backoff when it stops
+/// answering. Exits once the last handle is dropped.
+async fn monitor(server: Weak<McpServer>, ping_interval: Duration, max_backoff: Duration) {
+    let max_backoff_secs = max_backoff.as_secs().max(INITIAL_BACKOFF_SECS);
+    loop {
+        {
+            let Some(server) = server.upgrade() else {
+                return;
+            };
+            tokio::select! {
+                () = tokio::time::sleep(ping_interval) => {}
+                () = server.check_now.notified() => {}
+            }
+        }
+
+        let Some(server) = server.upgrade() else {
+            return;
+        };
+        if server.is_stopped() {
+            return;
+        }
+        let component = component_name(server.name());
+        if server.is_alive().await {
+            crate::health::mark_component_ok(&component);
+            continue;
+        }
+
+        tracing::warn!(server = %server.name(), "MCP server stopped responding; reconnecting");
+        crate::health::mark_component_error(&component, "server stopped responding");
+        server.disconnect().await;
+
+        let mut backoff = INITIAL_BACKOFF_SECS;
+        loop {
+            crate::health::bump_component_restart(&component);
+            let result = server.connect().await;
+            if server.is_stopped() {
+                // Stopped while connecting.
+                server.disconnect().await;
+                return;
+            }
+            match result {
+                Ok(()) => {
+                    tracing::info!(
+                        server = %server.name(),
+                        tools = server.tools().await.len(),
+                        "MCP server reconnected"
+                    );
+                    crate::health::mark_component_ok(&component);
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(server = %server.name(), error = %e, "MCP reconnect failed");
+                    crate::health::mark_component_error(&component, format!("{e:#}"));
+                }
+            }
+            if Arc::strong_count(&server) == 1 {
+                // Only the monitor still holds the server; stop retrying.
+                return;
+            }
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            // Double backoff AFTER sleeping so first error uses initial_backoff
+            backoff = backoff.saturating_mul(2).min(max_backoff_secs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing_server() -> McpServerConfig {
+        McpServerConfig {
+            name: format!("missing-{}", uuid::Uuid::new_v4()),
+            command: "/nonexistent/mcp-server-binary".into(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            url: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_start_is_reported_to_health() {
+        let config = missing_server();
+        let component = component_name(&config.name);
+        let result =
+            McpServer::start(config, Duration::from_secs(30), Duration::from_secs(60)).await;
+        assert!(result.is_err());
+
+        let snapshot = crate::health::snapshot();
+        let entry = snapshot.components.get(&component).unwrap();
+        assert_eq!(entry.status, "error");
+        assert!(entry.last_error.is_some());
+    }
+
+    #[test]
+    fn component_names_are_prefixed() {
+        assert_eq!(component_name("github"), "mcp:github");
+    }
+}
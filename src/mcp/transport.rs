@@ -2,20 +2,32 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-//! MCP transport layer — lightweight JSON-RPC client over stdio.
+//! MCP transport layer — lightweight JSON-RPC client.
 //!
-//! Implements the MCP client protocol using `serde_json` + `tokio::process`.
-//! No external MCP SDK needed — the protocol is simple JSON-RPC 2.0 over stdin/stdout.
+//! Implements the MCP client protocol using `serde_json` over three
+//! transports: stdio of a spawned process, Streamable HTTP (JSON or SSE
+//! responses) and WebSocket. No external MCP SDK needed — the protocol is
+//! simple JSON-RPC 2.0.
 
+use crate::config::McpServerConfig;
 use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Upper bound for one request to a remote MCP server.
+const REMOTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
 
 // ── JSON-RPC types ──────────────────────────────────────────────
 
@@ -32,7 +44,6 @@ struct JsonRpcRequest {
 struct JsonRpcResponse {
     #[allow(dead_code)]
     jsonrpc: String,
-    #[allow(dead_code)]
     id: Option<u64>,
     result: Option<Value>,
     error: Option<JsonRpcError>,
@@ -45,6 +56,48 @@ struct JsonRpcError {
     message: String,
 }
 
+/// Interpret one incoming message as the response to request `id`.
+///
+/// Returns `None` for notifications, server requests and responses to other
+/// requests, which the caller skips.
+fn match_response(message: &str, id: u64) -> Option<Result<Value>> {
+    let resp: JsonRpcResponse = serde_json::from_str(message.trim()).ok()?;
+    if resp.id != Some(id) {
+        return None;
+    }
+    Some(match resp.error {
+        Some(err) => Err(anyhow::anyhow!("MCP error: {}", err.message)),
+        None => Ok(resp.result.unwrap_or(Value::Null)),
+    })
+}
+
+/// Incremental parser for `text/event-stream` bodies; yields the `data` of
+/// each complete event.
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let event = String::from_utf8_lossy(&event);
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
 // ── MCP model types ─────────────────────────────────────────────
 
 /// An MCP tool definition returned by `tools/list`.
@@ -56,6 +109,9 @@ pub struct McpToolInfo {
     pub description: Option<String>,
     #[serde(default)]
     pub input_schema: Value,
+    /// Schema of `structuredContent` in results (MCP 2025-06-18)
+    #[serde(default)]
+    pub output_schema: Option<Value>,
 }
 
 /// The result of calling an MCP tool.
@@ -66,6 +122,9 @@ pub struct McpCallToolResult {
     pub content: Vec<McpContent>,
     #[serde(default)]
     pub is_error: Option<bool>,
+    /// Structured result alongside the text content
+    #[serde(default)]
+    pub structured_content: Option<Value>,
 }
 
 /// A content block in an MCP tool result.
@@ -77,18 +136,291 @@ pub struct McpContent {
     pub text: Option<String>,
 }
 
+/// A resource advertised by `resources/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResourceInfo {
+    pub uri: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// One entry of a `resources/read` result: either `text` or base64 `blob`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResourceContents {
+    pub uri: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub blob: Option<String>,
+}
+
+/// A prompt template advertised by `prompts/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// An argument of an MCP prompt template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A message of a rendered prompt (`prompts/get`).
+#[derive(Debug, Deserialize)]
+pub struct McpPromptMessage {
+    pub role: String,
+    pub content: McpContent,
+}
+
+// ── Transports ──────────────────────────────────────────────────
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Streamable HTTP: every message is POSTed to one endpoint; the server
+/// answers with JSON or an SSE stream and may assign a session id.
+struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: reqwest::header::HeaderMap,
+    session_id: Mutex<Option<String>>,
+}
+
+impl HttpTransport {
+    fn new(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("Invalid header name '{name}'"))?,
+                reqwest::header::HeaderValue::from_str(value)
+                    .with_context(|| format!("Invalid value for header '{name}'"))?,
+            );
+        }
+        crate::network::egress::check(url, None)?;
+        Ok(Self {
+            client: crate::network::egress::client_builder(None)
+                .timeout(REMOTE_REQUEST_TIMEOUT)
+                .build()?,
+            url: url.to_string(),
+            headers: header_map,
+            session_id: Mutex::new(None),
+        })
+    }
+
+    /// POST one message. With `id`, wait for and return its response.
+    async fn send(&self, message: &Value, id: Option<u64>) -> Result<Value> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(message);
+        if let Some(session) = self.session_id.lock().await.clone() {
+            request = request.header("Mcp-Session-Id", session);
+        }
+        let mut response = request.send().await.context("Failed to reach MCP server")?;
+
+        if let Some(session) = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            *self.session_id.lock().await = Some(session.to_string());
+        }
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("MCP server returned HTTP {status}");
+        }
+        let Some(id) = id else {
+            return Ok(Value::Null);
+        };
+
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+        if !is_sse {
+            let body = response.text().await?;
+            return match_response(&body, id)
+                .unwrap_or_else(|| Err(anyhow::anyhow!("MCP server sent no response")));
+        }
+
+        let mut parser = SseParser::default();
+        while let Some(chunk) = response.chunk().await? {
+            for data in parser.feed(&chunk) {
+                if let Some(result) = match_response(&data, id) {
+                    return result;
+                }
+            }
+        }
+        anyhow::bail!("MCP server closed the event stream without a response")
+    }
+}
+
+/// One JSON-RPC message per WebSocket text frame.
+struct WsTransport {
+    stream: Mutex<WsStream>,
+}
+
+impl WsTransport {
+    async fn connect(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        let mut request = url.into_client_request()?;
+        for (name, value) in headers {
+            request.headers_mut().insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("Invalid header name '{name}'"))?,
+                HeaderValue::from_str(value)
+                    .with_context(|| format!("Invalid value for header '{name}'"))?,
+            );
+        }
+        let (stream, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .context("WebSocket handshake with MCP server failed")?;
+        Ok(Self {
+            stream: Mutex::new(stream),
+        })
+    }
+
+    async fn send(&self, message: &Value, id: Option<u64>) -> Result<Value> {
+        let mut stream = self.stream.lock().await;
+        stream.send(Message::Text(message.to_string())).await?;
+        let Some(id) = id else {
+            return Ok(Value::Null);
+        };
+        tokio::time::timeout(REMOTE_REQUEST_TIMEOUT, Self::read_response(&mut stream, id))
+            .await
+            .context("MCP request timed out")?
+    }
+
+    async fn read_response(stream: &mut WsStream, id: u64) -> Result<Value> {
+        while let Some(frame) = stream.next().await {
+            match frame? {
+                Message::Text(text) => {
+                    if let Some(result) = match_response(&text, id) {
+                        return result;
+                    }
+                }
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        anyhow::bail!("MCP server closed the WebSocket")
+    }
+}
+
+enum Transport {
+    Stdio {
+        child: Mutex<Child>,
+        stdin: Mutex<tokio::process::ChildStdin>,
+        stdout: Mutex<BufReader<tokio::process::ChildStdout>>,
+    },
+    Http(HttpTransport),
+    WebSocket(WsTransport),
+}
+
+impl Transport {
+    async fn send_stdio(
+        stdin: &Mutex<tokio::process::ChildStdin>,
+        stdout: &Mutex<BufReader<tokio::process::ChildStdout>>,
+        message: &Value,
+        id: Option<u64>,
+    ) -> Result<Value> {
+        let mut payload = serde_json::to_string(message)?;
+        payload.push('\n');
+
+        // Write request
+        {
+            let mut stdin = stdin.lock().await;
+            stdin
+                .write_all(payload.as_bytes())
+                .await
+                .context("Failed to write to MCP server stdin")?;
+            stdin.flush().await?;
+        }
+        let Some(id) = id else {
+            return Ok(Value::Null);
+        };
+
+        // Read response
+        let mut line = String::new();
+        let mut stdout = stdout.lock().await;
+        loop {
+            line.clear();
+            let n = stdout
+                .read_line(&mut line)
+                .await
+                .context("Failed to read from MCP server stdout")?;
+            if n == 0 {
+                anyhow::bail!("MCP server closed stdout unexpectedly");
+            }
+            if let Some(result) = match_response(&line, id) {
+                return result;
+            }
+            // If it's a notification or other message, skip it
+        }
+    }
+
+    async fn send(&self, message: &Value, id: Option<u64>) -> Result<Value> {
+        match self {
+            Self::Stdio { stdin, stdout, .. } => Self::send_stdio(stdin, stdout, message, id).await,
+            Self::Http(http) => http.send(message, id).await,
+            Self::WebSocket(ws) => ws.send(message, id).await,
+        }
+    }
+}
+
+/// Deserialize the array `field` of a result; a missing field is empty.
+fn result_list<T: serde::de::DeserializeOwned>(result: &Value, field: &str) -> Result<Vec<T>> {
+    match result.get(field) {
+        Some(list) => Ok(serde_json::from_value(list.clone())?),
+        None => Ok(Vec::new()),
+    }
+}
+
 // ── MCP Client ──────────────────────────────────────────────────
 
-/// A connected MCP client that communicates with an MCP server over stdio.
+/// A connected MCP client that communicates with an MCP server over stdio,
+/// Streamable HTTP or WebSocket.
 pub struct McpClient {
-    child: Arc<Mutex<Child>>,
-    stdin: Arc<Mutex<tokio::process::ChildStdin>>,
-    stdout: Arc<Mutex<BufReader<tokio::process::ChildStdout>>>,
+    transport: Transport,
     next_id: AtomicU64,
     server_name: String,
+    /// `capabilities` from the server's initialize result.
+    capabilities: Value,
 }
 
 impl McpClient {
+    /// Connect to a configured server: `url` selects a remote transport
+    /// (`ws://`/`wss://` for WebSocket, `http(s)://` for Streamable HTTP),
+    /// otherwise `command` is spawned.
+    pub async fn connect_server(config: &McpServerConfig) -> Result<Self> {
+        match config.url.as_deref() {
+            Some(url) => Self::connect_remote(&config.name, url, &config.headers).await,
+            None => Self::connect(&config.name, &config.command, &config.args, &config.env).await,
+        }
+    }
+
     /// Spawn an MCP server as a child process and connect via stdio.
     pub async fn connect(
         name: &str,
@@ -105,7 +437,8 @@ impl McpClient {
         }
         cmd.stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null());
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true);
 
         let mut child = cmd
             .spawn()
@@ -120,12 +453,41 @@ impl McpClient {
             .take()
             .context("Failed to capture MCP server stdout")?;
 
-        let client = Self {
-            child: Arc::new(Mutex::new(child)),
-            stdin: Arc::new(Mutex::new(stdin)),
-            stdout: Arc::new(Mutex::new(BufReader::new(stdout))),
+        Self::initialize(
+            name,
+            Transport::Stdio {
+                child: Mutex::new(child),
+                stdin: Mutex::new(stdin),
+                stdout: Mutex::new(BufReader::new(stdout)),
+            },
+        )
+        .await
+    }
+
+    /// Connect to a remote MCP server, sending `headers` (e.g.
+    /// `Authorization`) with every HTTP request or the WebSocket handshake.
+    pub async fn connect_remote(
+        name: &str,
+        url: &str,
+        headers: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let transport = if url.starts_with("ws://") || url.starts_with("wss://") {
+            Transport::WebSocket(WsTransport::connect(url, headers).await?)
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            Transport::Http(HttpTransport::new(url, headers)?)
+        } else {
+            anyhow::bail!("Unsupported MCP server URL '{url}': use http(s):// or ws(s)://");
+        };
+        Self::initialize(name, transport).await
+    }
+
+    /// Run the initialize handshake over a fresh transport.
+    async fn initialize(name: &str, transport: Transport) -> Result<Self> {
+        let mut client = Self {
+            transport,
             next_id: AtomicU64::new(1),
             server_name: name.to_string(),
+            capabilities: Value::Null,
         };
 
         // Send initialize request
@@ -133,7 +495,7 @@ impl McpClient {
             .request(
                 "initialize",
                 Some(serde_json::json!({
-                    "protocolVersion": "2024-11-05",
+                    "protocolVersion": "2025-03-26",
                     "capabilities": {},
                     "clientInfo": {
                         "name": "mymolt",
@@ -150,6 +512,11 @@ impl McpClient {
             "Connected to MCP server"
         );
 
+        client.capabilities = init_result
+            .get("capabilities")
+            .cloned()
+            .unwrap_or(Value::Null);
+
         // Send initialized notification
         client.notify("notifications/initialized", None).await?;
 
@@ -165,47 +532,9 @@ impl McpClient {
             method: method.to_string(),
             params,
         };
-
-        let mut payload = serde_json::to_string(&request)?;
-        payload.push('\n');
-
-        // Write request
-        {
-            let mut stdin = self.stdin.lock().await;
-            stdin
-                .write_all(payload.as_bytes())
-                .await
-                .context("Failed to write to MCP server stdin")?;
-            stdin.flush().await?;
-        }
-
-        // Read response
-        let mut line = String::new();
-        {
-            let mut stdout = self.stdout.lock().await;
-            loop {
-                line.clear();
-                let n = stdout
-                    .read_line(&mut line)
-                    .await
-                    .context("Failed to read from MCP server stdout")?;
-                if n == 0 {
-                    anyhow::bail!("MCP server closed stdout unexpectedly");
-                }
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    continue;
-                }
-                // Try to parse as a JSON-RPC response
-                if let Ok(resp) = serde_json::from_str::<JsonRpcResponse>(trimmed) {
-                    if let Some(err) = resp.error {
-                        anyhow::bail!("MCP error: {}", err.message);
-                    }
-                    return Ok(resp.result.unwrap_or(Value::Null));
-                }
-                // If it's a notification or other message, skip it
-            }
-        }
+        self.transport
+            .send(&serde_json::to_value(&request)?, Some(id))
+            .await
     }
 
     /// Send a JSON-RPC notification (no response expected).
@@ -215,26 +544,16 @@ impl McpClient {
             "method": method,
             "params": params.unwrap_or(Value::Null)
         });
-
-        let mut payload = serde_json::to_string(&notification)?;
-        payload.push('\n');
-
-        let mut stdin = self.stdin.lock().await;
-        stdin.write_all(payload.as_bytes()).await?;
-        stdin.flush().await?;
+        self.transport.send(&notification, None).await?;
         Ok(())
     }
 
     /// Discover available tools from the MCP server.
     pub async fn list_tools(&self) -> Result<Vec<McpToolInfo>> {
         let result = self.request("tools/list", None).await?;
-        let tools: Vec<McpToolInfo> = serde_json::from_value(
-            result
-                .get("tools")
-                .cloned()
-                .unwrap_or(Value::Array(vec![])),
-        )
-        .context("Failed to parse tools/list response")?;
+        let tools: Vec<McpToolInfo> =
+            serde_json::from_value(result.get("tools").cloned().unwrap_or(Value::Array(vec![])))
+                .context("Failed to parse tools/list response")?;
         Ok(tools)
     }
 
@@ -254,6 +573,51 @@ impl McpClient {
         Ok(call_result)
     }
 
+    /// Whether the server declared `capability` (e.g. `"resources"`) during
+    /// initialization.
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities
+            .get(capability)
+            .is_some_and(|c| !c.is_null())
+    }
+
+    /// List the resources the server exposes.
+    pub async fn list_resources(&self) -> Result<Vec<McpResourceInfo>> {
+        let result = self.request("resources/list", None).await?;
+        result_list(&result, "resources").context("Failed to parse resources/list response")
+    }
+
+    /// Read the contents of a resource.
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<McpResourceContents>> {
+        let result = self
+            .request("resources/read", Some(serde_json::json!({ "uri": uri })))
+            .await?;
+        result_list(&result, "contents").context("Failed to parse resources/read response")
+    }
+
+    /// List the prompt templates the server exposes.
+    pub async fn list_prompts(&self) -> Result<Vec<McpPromptInfo>> {
+        let result = self.request("prompts/list", None).await?;
+        result_list(&result, "prompts").context("Failed to parse prompts/list response")
+    }
+
+    /// Render a prompt template with `arguments`.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, String>,
+    ) -> Result<Vec<McpPromptMessage>> {
+        let params = serde_json::json!({ "name": name, "arguments": arguments });
+        let result = self.request("prompts/get", Some(params)).await?;
+        result_list(&result, "messages").context("Failed to parse prompts/get response")
+    }
+
+    /// Liveness check (`ping` request).
+    pub async fn ping(&self) -> Result<()> {
+        self.request("ping", None).await?;
+        Ok(())
+    }
+
     /// Get the server name.
     pub fn server_name(&self) -> &str {
         &self.server_name
@@ -261,8 +625,26 @@ impl McpClient {
 
     /// Gracefully shut down the MCP server connection.
     pub async fn shutdown(self) -> Result<()> {
-        let mut child = self.child.lock().await;
-        let _ = child.kill().await;
+        match self.transport {
+            Transport::Stdio { child, .. } => {
+                let _ = child.lock().await.kill().await;
+            }
+            Transport::Http(http) => {
+                // Streamable HTTP sessions are ended with a DELETE.
+                if let Some(session) = http.session_id.lock().await.clone() {
+                    let _ = http
+                        .client
+                        .delete(&http.url)
+                        .headers(http.headers.clone())
+                        .header("Mcp-Session-Id", session)
+                        .send()
+                        .await;
+                }
+            }
+            Transport::WebSocket(ws) => {
+                let _ = ws.stream.lock().await.close(None).await;
+            }
+        }
         Ok(())
     }
 }
@@ -277,9 +659,57 @@ mod tests {
         let _name: fn(&McpClient) -> &str = McpClient::server_name;
     }
 
+    #[test]
+    fn resource_and_prompt_lists_parse() {
+        let result = serde_json::json!({
+            "resources": [{"uri": "file:///notes.md", "name": "notes", "mimeType": "text/markdown"}],
+            "prompts": [{"name": "review", "arguments": [{"name": "diff", "required": true}]}]
+        });
+        let resources: Vec<McpResourceInfo> = result_list(&result, "resources").unwrap();
+        assert_eq!(resources[0].mime_type.as_deref(), Some("text/markdown"));
+        let prompts: Vec<McpPromptInfo> = result_list(&result, "prompts").unwrap();
+        assert!(prompts[0].arguments[0].required);
+        let missing: Vec<McpPromptInfo> = result_list(&result, "tools").unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn responses_are_matched_by_id() {
+        let ok = r#"{"jsonrpc":"2.0","id":7,"result":{"tools":[]}}"#;
+        assert_eq!(
+            match_response(ok, 7).unwrap().unwrap()["tools"],
+            serde_json::json!([])
+        );
+        assert!(match_response(ok, 8).is_none());
+
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{}}"#;
+        assert!(match_response(notification, 7).is_none());
+
+        let err = r#"{"jsonrpc":"2.0","id":3,"error":{"code":-32601,"message":"nope"}}"#;
+        assert!(match_response(err, 3).unwrap().is_err());
+    }
+
+    #[test]
+    fn sse_events_are_parsed_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: message\r\ndata: {\"a\":").is_empty());
+        let events = parser.feed(b"1}\r\n\r\n: keep-alive\n\ndata: line1\ndata: line2\n\n");
+        assert_eq!(events, vec![r#"{"a":1}"#, "line1\nline2"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_url_schemes() {
+        let err = McpClient::connect_remote("x", "ftp://example.org", &HashMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Unsupported MCP server URL"));
+    }
+
     #[test]
     fn mcp_tool_info_deserializes() {
-        let json = r#"{"name": "test_tool", "description": "A test", "inputSchema": {"type": "object"}}"#;
+        let json =
+            r#"{"name": "test_tool", "description": "A test", "inputSchema": {"type": "object"}}"#;
         let tool: McpToolInfo = serde_json::from_str(json).unwrap();
         assert_eq!(tool.name, "test_tool");
         assert_eq!(tool.description.as_deref(), Some("A test"));
@@ -287,11 +717,22 @@ mod tests {
 
     #[test]
     fn mcp_call_result_deserializes() {
-        let json =
-            r#"{"content": [{"type": "text", "text": "hello"}], "isError": false}"#;
+        let json = r#"{"content": [{"type": "text", "text": "hello"}], "isError": false}"#;
         let result: McpCallToolResult = serde_json::from_str(json).unwrap();
         assert_eq!(result.content.len(), 1);
         assert_eq!(result.content[0].text.as_deref(), Some("hello"));
         assert_eq!(result.is_error, Some(false));
+        assert!(result.structured_content.is_none());
+    }
+
+    #[test]
+    fn mcp_structured_content_deserializes() {
+        let json = r#"{"content": [{"type": "text", "text": "22°C"}], "structuredContent": {"temperature": 22}}"#;
+        let result: McpCallToolResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.structured_content.unwrap()["temperature"], 22);
+
+        let json = r#"{"name": "weather", "inputSchema": {}, "outputSchema": {"type": "object"}}"#;
+        let tool: McpToolInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(tool.output_schema.unwrap()["type"], "object");
     }
 }
@@ -0,0 +1,884 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! HNSW approximate nearest-neighbour index over memory embeddings.
+//!
+//! Hierarchical Navigable Small World graphs (Malkov & Yashunin): search
+//! cost grows roughly logarithmically with the number of entries instead of
+//! linearly. Vectors are normalized on insert, so distance is
+//! `1 - cosine similarity`.
+//!
+//! Only the graph is persisted; vectors live in SQLite and are re-attached
+//! when the index is opened. A missing, corrupt or stale index file is
+//! rebuilt from the vectors.
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"MMHNSW01";
+/// Links per node on upper layers; layer 0 keeps twice as many.
+const M: usize = 16;
+const EF_CONSTRUCTION: usize = 100;
+const EF_SEARCH: usize = 64;
+const MAX_LEVEL: usize = 16;
+/// Save after this many unsaved changes (and on drop).
+const SAVE_EVERY: usize = 256;
+
+/// Distance ordered by `f32::total_cmp`, for the heaps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Dist(f32);
+
+impl Eq for Dist {}
+
+impl PartialOrd for Dist {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Dist {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+struct Node {
+    key: String,
+    vector: Vec<f32>,
+    /// Neighbours per layer, `links[0]` being the bottom layer.
+    links: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// In-memory HNSW graph. Removed entries stay as tombstones that searches
+/// pass through but never return.
+#[derive(Default)]
+pub struct Hnsw {
+    dim: usize,
+    nodes: Vec<Node>,
+    by_key: HashMap<String, u32>,
+    entry: Option<u32>,
+    deleted: usize,
+}
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+    let norm = vector
+        .iter()
+        .map(|x| f64::from(*x) * f64::from(*x))
+        .sum::<f64>()
+        .sqrt();
+    if !norm.is_finite() || norm < f64::EPSILON {
+        return vector.to_vec();
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    vector
+        .iter()
+        .map(|x| (f64::from(*x) / norm) as f32)
+        .collect()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    1.0 - a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>()
+}
+
+fn max_links(layer: usize) -> usize {
+    if layer == 0 {
+        M * 2
+    } else {
+        M
+    }
+}
+
+fn random_level() -> usize {
+    let ml = 1.0 / (M as f64).ln();
+    let uniform: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let level = (-uniform.ln() * ml).floor() as usize;
+    level.min(MAX_LEVEL)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_u32(n: usize) -> u32 {
+    n as u32
+}
+
+impl Hnsw {
+    /// Build an index from scratch. Vectors whose dimension differs from the
+    /// first one are skipped.
+    pub fn build<'a>(vectors: impl IntoIterator<Item = (&'a str, &'a [f32])>) -> Self {
+        let mut index = Self::default();
+        for (key, vector) in vectors {
+            index.insert(key, vector);
+        }
+        index
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Number of live entries.
+    pub fn len(&self) -> usize {
+        self.by_key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_key.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.by_key.contains_key(key)
+    }
+
+    fn top_level(&self) -> usize {
+        self.entry
+            .map_or(0, |e| self.nodes[e as usize].links.len() - 1)
+    }
+
+    fn dist_to(&self, query: &[f32], node: u32) -> f32 {
+        distance(query, &self.nodes[node as usize].vector)
+    }
+
+    /// Insert or replace `key`. Returns `false` (and changes nothing) for an
+    /// empty vector or one of another dimension than the index.
+    pub fn insert(&mut self, key: &str, vector: &[f32]) -> bool {
+        if vector.is_empty() || (self.dim != 0 && vector.len() != self.dim) {
+            return false;
+        }
+        self.dim = vector.len();
+        self.remove(key);
+
+        let vector = normalize(vector);
+        let level = random_level();
+        let id = to_u32(self.nodes.len());
+        self.nodes.push(Node {
+            key: key.to_string(),
+            vector,
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.by_key.insert(key.to_string(), id);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return true;
+        };
+
+        let query = self.nodes[id as usize].vector.clone();
+        let top = self.top_level();
+        let mut nearest = entry;
+        for layer in (level + 1..=top).rev() {
+            nearest = self.greedy_closest(&query, nearest, layer);
+        }
+
+        let mut entry_points = vec![nearest];
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &entry_points, EF_CONSTRUCTION, layer, &mut 0);
+            let neighbours = self.select_neighbours(&found, max_links(layer));
+            for &n in &neighbours {
+                self.link(n, id, layer);
+            }
+            self.nodes[id as usize].links[layer] = neighbours;
+            entry_points = found.into_iter().map(|(_, n)| n).collect();
+        }
+
+        if level > top {
+            self.entry = Some(id);
+        }
+        true
+    }
+
+    /// Add `to` to the links of `from`, pruning them back to size.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        let links = &self.nodes[from as usize].links[layer];
+        if links.len() < max_links(layer) {
+            self.nodes[from as usize].links[layer].push(to);
+            return;
+        }
+        let base = &self.nodes[from as usize].vector;
+        let mut candidates: Vec<(Dist, u32)> = links
+            .iter()
+            .chain(std::iter::once(&to))
+            .map(|&n| (Dist(distance(base, &self.nodes[n as usize].vector)), n))
+            .collect();
+        candidates.sort_unstable();
+        let pruned = self.select_neighbours(&candidates, max_links(layer));
+        self.nodes[from as usize].links[layer] = pruned;
+    }
+
+    /// Neighbour selection heuristic: prefer candidates closer to the base
+    /// than to any neighbour already picked, then fill up with the closest
+    /// remaining ones. `candidates` must be sorted nearest first.
+    fn select_neighbours(&self, candidates: &[(Dist, u32)], m: usize) -> Vec<u32> {
+        let mut picked: Vec<u32> = Vec::with_capacity(m);
+        let mut skipped = Vec::new();
+        for &(Dist(d), c) in candidates {
+            if picked.len() >= m {
+                break;
+            }
+            let vector = &self.nodes[c as usize].vector;
+            if picked
+                .iter()
+                .all(|&p| distance(vector, &self.nodes[p as usize].vector) > d)
+            {
+                picked.push(c);
+            } else {
+                skipped.push(c);
+            }
+        }
+        for c in skipped {
+            if picked.len() >= m {
+                break;
+            }
+            picked.push(c);
+        }
+        picked
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut best = start;
+        let mut best_dist = self.dist_to(query, start);
+        loop {
+            let mut improved = false;
+            for &n in &self.nodes[best as usize].links[layer] {
+                let d = self.dist_to(query, n);
+                if d < best_dist {
+                    best = n;
+                    best_dist = d;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Best-first search of one layer; returns up to `ef` nodes, nearest
+    /// first. `evaluations` counts distance computations.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+        evaluations: &mut usize,
+    ) -> Vec<(Dist, u32)> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<(Dist, u32)>> = BinaryHeap::new();
+        let mut found: BinaryHeap<(Dist, u32)> = BinaryHeap::new();
+        for &ep in entry_points {
+            let d = Dist(self.dist_to(query, ep));
+            *evaluations += 1;
+            candidates.push(Reverse((d, ep)));
+            found.push((d, ep));
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(Reverse((d, current))) = candidates.pop() {
+            if found.len() >= ef && found.peek().is_some_and(|(worst, _)| d > *worst) {
+                break;
+            }
+            for &n in &self.nodes[current as usize].links[layer] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let dn = Dist(self.dist_to(query, n));
+                *evaluations += 1;
+                if found.len() < ef || found.peek().is_some_and(|(worst, _)| dn < *worst) {
+                    candidates.push(Reverse((dn, n)));
+                    found.push((dn, n));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    /// The `k` entries most similar to `query` as `(key, cosine similarity)`.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        self.search_counted(query, k).0
+    }
+
+    /// [`Hnsw::search`] plus the number of distance computations it took.
+    fn search_counted(&self, query: &[f32], k: usize) -> (Vec<(String, f32)>, usize) {
+        let mut evaluations = 0;
+        let Some(entry) = self.entry else {
+            return (Vec::new(), evaluations);
+        };
+        if k == 0 || query.len() != self.dim {
+            return (Vec::new(), evaluations);
+        }
+        let query = normalize(query);
+        let mut nearest = entry;
+        for layer in (1..=self.top_level()).rev() {
+            nearest = self.greedy_closest(&query, nearest, layer);
+        }
+        // Tombstones take up slots in the candidate list; widen it so they do
+        // not crowd out live results.
+        let ef = (EF_SEARCH.max(k) * self.nodes.len()) / self.len().max(1);
+        let found = self.search_layer(&query, &[nearest], ef, 0, &mut evaluations);
+        let results = found
+            .into_iter()
+            .filter(|(_, n)| !self.nodes[*n as usize].deleted)
+            .take(k)
+            .map(|(Dist(d), n)| {
+                (
+                    self.nodes[n as usize].key.clone(),
+                    (1.0 - d).clamp(0.0, 1.0),
+                )
+            })
+            .collect();
+        (results, evaluations)
+    }
+
+    /// Remove `key`; returns whether it was present.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let Some(id) = self.by_key.remove(key) else {
+            return false;
+        };
+        self.nodes[id as usize].deleted = true;
+        self.deleted += 1;
+        true
+    }
+
+    /// Share of nodes that are tombstones.
+    fn tombstone_ratio(&self) -> f64 {
+        if self.nodes.is_empty() {
+            0.0
+        } else {
+            self.deleted as f64 / self.nodes.len() as f64
+        }
+    }
+
+    /// Rebuild without tombstones.
+    pub fn compact(&mut self) {
+        let live: Vec<(String, Vec<f32>)> = self
+            .nodes
+            .iter()
+            .filter(|n| !n.deleted)
+            .map(|n| (n.key.clone(), n.vector.clone()))
+            .collect();
+        *self = Self::build(live.iter().map(|(k, v)| (k.as_str(), v.as_slice())));
+    }
+
+    /// Serialize the graph. Vectors are only written for tombstones, which
+    /// have no row left to reload them from.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&to_u32(self.dim).to_le_bytes());
+        out.extend_from_slice(&self.entry.unwrap_or(u32::MAX).to_le_bytes());
+        out.extend_from_slice(&to_u32(self.nodes.len()).to_le_bytes());
+        for node in &self.nodes {
+            out.extend_from_slice(&to_u32(node.key.len()).to_le_bytes());
+            out.extend_from_slice(node.key.as_bytes());
+            out.push(u8::from(node.deleted));
+            if node.deleted {
+                out.extend_from_slice(&super::vector::vec_to_bytes(&node.vector));
+            }
+            out.extend_from_slice(&to_u32(node.links.len()).to_le_bytes());
+            for links in &node.links {
+                out.extend_from_slice(&to_u32(links.len()).to_le_bytes());
+                for link in links {
+                    out.extend_from_slice(&link.to_le_bytes());
+                }
+            }
+        }
+        let checksum = Sha256::digest(&out);
+        out.extend_from_slice(&checksum);
+        out
+    }
+
+    /// Parse [`Hnsw::to_bytes`] output and attach live vectors from
+    /// `vectors`. Entries without a vector are unlinked.
+    fn from_bytes(bytes: &[u8], vectors: &HashMap<String, Vec<f32>>) -> anyhow::Result<Loaded> {
+        let Some((body, checksum)) = bytes.split_at_checked(bytes.len().saturating_sub(32)) else {
+            anyhow::bail!("index file truncated");
+        };
+        if checksum.len() != 32 || Sha256::digest(body).as_slice() != checksum {
+            anyhow::bail!("index checksum mismatch");
+        }
+        let mut reader = Reader(body);
+        if reader.take(MAGIC.len())? != MAGIC {
+            anyhow::bail!("not an index file");
+        }
+        let dim = reader.len()?;
+        let entry = reader.u32()?;
+        let count = reader.len()?;
+
+        let mut index = Self {
+            dim,
+            entry: (entry != u32::MAX).then_some(entry),
+            ..Self::default()
+        };
+        let mut missing = HashSet::new();
+        for id in 0..count {
+            let key_len = reader.len()?;
+            let key = String::from_utf8(reader.take(key_len)?.to_vec())?;
+            let deleted = reader.take(1)?[0] != 0;
+            let vector = if deleted {
+                index.deleted += 1;
+                super::vector::bytes_to_vec(reader.take(dim * 4)?)
+            } else if let Some(v) = vectors.get(&key).filter(|v| v.len() == dim) {
+                index.by_key.insert(key.clone(), to_u32(id));
+                normalize(v)
+            } else {
+                missing.insert(to_u32(id));
+                Vec::new()
+            };
+            let levels = reader.len()?;
+            if levels == 0 || levels > MAX_LEVEL + 1 {
+                anyhow::bail!("invalid level count {levels}");
+            }
+            let mut links = Vec::with_capacity(levels);
+            for _ in 0..levels {
+                let n = reader.len()?;
+                let layer = (0..n)
+                    .map(|_| reader.u32())
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                if layer.iter().any(|&l| l as usize >= count) {
+                    anyhow::bail!("link out of range");
+                }
+                links.push(layer);
+            }
+            index.nodes.push(Node {
+                key,
+                vector,
+                links,
+                deleted,
+            });
+        }
+        if !reader.0.is_empty() {
+            anyhow::bail!("trailing bytes in index file");
+        }
+        let top = index.nodes.iter().map(|n| n.links.len()).max();
+        if index
+            .entry
+            .map(|e| index.nodes.get(e as usize).map(|n| n.links.len()))
+            != top.map(Some)
+        {
+            anyhow::bail!("invalid entry point");
+        }
+        for node in &index.nodes {
+            for (layer, links) in node.links.iter().enumerate() {
+                if links
+                    .iter()
+                    .any(|&l| index.nodes[l as usize].links.len() <= layer)
+                {
+                    anyhow::bail!("link to a node without that layer");
+                }
+            }
+        }
+
+        index.unlink(&missing);
+        let new = vectors
+            .keys()
+            .filter(|key| !index.by_key.contains_key(*key))
+            .cloned()
+            .collect();
+        Ok(Loaded {
+            graph: index,
+            new,
+            unlinked: missing.len(),
+        })
+    }
+
+    /// Drop nodes whose vectors are gone from every neighbour list and turn
+    /// them into unreachable tombstones.
+    fn unlink(&mut self, ids: &HashSet<u32>) {
+        if ids.is_empty() {
+            return;
+        }
+        for node in &mut self.nodes {
+            for links in &mut node.links {
+                links.retain(|l| !ids.contains(l));
+            }
+        }
+        for &id in ids {
+            let node = &mut self.nodes[id as usize];
+            node.links = vec![Vec::new()];
+            node.vector = vec![0.0; self.dim];
+            node.deleted = true;
+            self.deleted += 1;
+        }
+        if self.entry.is_some_and(|e| ids.contains(&e)) {
+            self.entry = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| !n.deleted)
+                .max_by_key(|(_, n)| n.links.len())
+                .map(|(i, _)| to_u32(i));
+        }
+    }
+}
+
+struct Loaded {
+    graph: Hnsw,
+    /// Keys with a vector that the graph does not know yet
+    new: Vec<String>,
+    /// Entries dropped because their vector is gone
+    unlinked: usize,
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let Some((head, rest)) = self.0.split_at_checked(n) else {
+            anyhow::bail!("index file truncated");
+        };
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn len(&mut self) -> anyhow::Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+}
+
+/// The dimension most vectors have; mixed dimensions only occur while
+/// switching embedders.
+fn dominant_dim(vectors: &HashMap<String, Vec<f32>>) -> usize {
+    let mut by_dim: HashMap<usize, usize> = HashMap::new();
+    for v in vectors.values() {
+        *by_dim.entry(v.len()).or_default() += 1;
+    }
+    by_dim
+        .into_iter()
+        .max_by_key(|&(dim, count)| (count, dim))
+        .map_or(0, |(dim, _)| dim)
+}
+
+/// An [`Hnsw`] graph saved to a file next to the database.
+pub struct VectorIndex {
+    path: PathBuf,
+    graph: Hnsw,
+    unsaved: usize,
+}
+
+impl VectorIndex {
+    /// Open the index at `path` for `vectors` (the source of truth, keyed by
+    /// memory id). A missing, corrupt or foreign file is rebuilt; entries
+    /// added or removed while the index was closed are caught up.
+    pub fn open(path: &Path, vectors: &HashMap<String, Vec<f32>>) -> Self {
+        let dim = dominant_dim(vectors);
+        let loaded = std::fs::read(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Hnsw::from_bytes(&bytes, vectors));
+        let (graph, unsaved) = match loaded {
+            // The embedder changed; old vectors cannot be compared to new ones.
+            Ok(loaded) if loaded.graph.dim != dim && dim != 0 => {
+                tracing::info!("embedding dimension changed; rebuilding vector index");
+                (Self::rebuild(vectors, dim), 1)
+            }
+            Ok(Loaded {
+                mut graph,
+                new,
+                unlinked,
+            }) => {
+                let added = new
+                    .iter()
+                    .filter(|key| graph.insert(key, &vectors[*key]))
+                    .count();
+                (graph, unlinked + added)
+            }
+            Err(e) => {
+                if path.exists() {
+                    tracing::warn!("vector index unreadable ({e}); rebuilding");
+                }
+                (Self::rebuild(vectors, dim), 1)
+            }
+        };
+        let mut index = Self {
+            path: path.to_path_buf(),
+            graph,
+            unsaved,
+        };
+        if index.unsaved > 0 {
+            index.save_logged();
+        }
+        index
+    }
+
+    fn rebuild(vectors: &HashMap<String, Vec<f32>>, dim: usize) -> Hnsw {
+        Hnsw::build(
+            vectors
+                .iter()
+                .filter(|(_, v)| v.len() == dim)
+                .map(|(k, v)| (k.as_str(), v.as_slice())),
+        )
+    }
+
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        self.graph.search(query, k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    /// Insert or replace `key`. A vector of a new dimension means the
+    /// embedder changed, so the old vectors are dropped.
+    pub fn insert(&mut self, key: &str, vector: &[f32]) {
+        if !self.graph.insert(key, vector) && !vector.is_empty() {
+            self.graph = Hnsw::default();
+            self.graph.insert(key, vector);
+        }
+        self.changed();
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.graph.remove(key) {
+            self.changed();
+        }
+    }
+
+    fn changed(&mut self) {
+        self.unsaved += 1;
+        if self.unsaved >= SAVE_EVERY {
+            self.save_logged();
+        }
+    }
+
+    /// Write the index atomically, compacting it first when more than a
+    /// quarter of it is tombstones.
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        if self.graph.tombstone_ratio() > 0.25 {
+            self.graph.compact();
+        }
+        let tmp = self.path.with_extension("hnsw.tmp");
+        std::fs::write(&tmp, self.graph.to_bytes())?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    fn save_logged(&mut self) {
+        if let Err(e) = self.save() {
+            tracing::warn!("failed to save vector index: {e}");
+        }
+    }
+}
+
+impl Drop for VectorIndex {
+    fn drop(&mut self) {
+        if self.unsaved > 0 {
+            self.save_logged();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    fn random_vectors(n: usize, dim: usize) -> Vec<(String, Vec<f32>)> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|i| {
+                let v = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                (format!("m{i}"), v)
+            })
+            .collect()
+    }
+
+    fn brute_force(vectors: &[(String, Vec<f32>)], query: &[f32], k: usize) -> Vec<String> {
+        let query = normalize(query);
+        let mut scored: Vec<(Dist, &str)> = vectors
+            .iter()
+            .map(|(key, v)| (Dist(distance(&query, &normalize(v))), key.as_str()))
+            .collect();
+        scored.sort_unstable();
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(_, k)| k.to_string())
+            .collect()
+    }
+
+    fn build(vectors: &[(String, Vec<f32>)]) -> Hnsw {
+        Hnsw::build(vectors.iter().map(|(k, v)| (k.as_str(), v.as_slice())))
+    }
+
+    /// Mean recall@k of `index` against exact search over `queries`.
+    fn recall_at(
+        index: &Hnsw,
+        vectors: &[(String, Vec<f32>)],
+        queries: &[(String, Vec<f32>)],
+        k: usize,
+    ) -> f64 {
+        let mut hits = 0;
+        for (_, q) in queries {
+            let exact = brute_force(vectors, q, k);
+            hits += index
+                .search(q, k)
+                .iter()
+                .filter(|(key, _)| exact.contains(key))
+                .count();
+        }
+        hits as f64 / (queries.len() * k) as f64
+    }
+
+    #[test]
+    fn finds_nearest_neighbours() {
+        let vectors = random_vectors(2_000, 16);
+        let index = build(&vectors);
+        assert_eq!(index.len(), 2_000);
+
+        let queries = random_vectors(50, 16);
+        assert!(recall_at(&index, &vectors, &queries, 10) > 0.9);
+
+        // An entry is its own nearest neighbour.
+        let (key, vector) = &vectors[123];
+        let results = index.search(vector, 1);
+        assert_eq!(&results[0].0, key);
+        assert!(results[0].1 > 0.999);
+    }
+
+    #[test]
+    fn removed_and_replaced_entries() {
+        let vectors = random_vectors(500, 8);
+        let mut index = build(&vectors);
+        let (key, vector) = &vectors[7];
+
+        assert!(index.remove(key));
+        assert!(!index.remove(key));
+        assert!(index.search(vector, 10).iter().all(|(k, _)| k != key));
+
+        let replacement: Vec<f32> = vector.iter().map(|x| -x).collect();
+        assert!(index.insert(key, &replacement));
+        assert_eq!(index.search(&replacement, 1)[0].0, *key);
+        assert!(!index.insert("other", &[1.0, 2.0]));
+
+        index.compact();
+        assert_eq!(index.len(), 500);
+        assert_eq!(index.deleted, 0);
+    }
+
+    #[test]
+    fn roundtrip_reattaches_vectors_and_reports_gaps() {
+        let vectors = random_vectors(300, 8);
+        let mut index = build(&vectors);
+        index.remove("m1");
+        let bytes = index.to_bytes();
+
+        let mut current: HashMap<String, Vec<f32>> = vectors.into_iter().collect();
+        current.remove("m1");
+        current.remove("m2");
+        current.insert("fresh".into(), vec![0.5; 8]);
+
+        let Loaded {
+            graph: loaded,
+            new,
+            unlinked,
+        } = Hnsw::from_bytes(&bytes, &current).unwrap();
+        assert_eq!(new, vec!["fresh".to_string()]);
+        assert_eq!(unlinked, 1);
+        assert_eq!(loaded.len(), 298);
+        assert!(!loaded.contains("m2"));
+        let (key, vector) = current.iter().find(|(k, _)| k.as_str() == "m10").unwrap();
+        assert_eq!(&loaded.search(vector, 1)[0].0, key);
+    }
+
+    #[test]
+    fn corrupt_bytes_are_rejected() {
+        let vectors = random_vectors(50, 4);
+        let bytes = build(&vectors).to_bytes();
+        let current: HashMap<String, Vec<f32>> = vectors.into_iter().collect();
+
+        let mut flipped = bytes.clone();
+        flipped[20] ^= 0xff;
+        assert!(Hnsw::from_bytes(&flipped, &current).is_err());
+        assert!(Hnsw::from_bytes(&bytes[..bytes.len() - 1], &current).is_err());
+        assert!(Hnsw::from_bytes(b"", &current).is_err());
+    }
+
+    #[test]
+    fn vector_index_persists_and_rebuilds_on_corruption() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.hnsw");
+        let vectors: HashMap<String, Vec<f32>> = random_vectors(200, 8).into_iter().collect();
+
+        {
+            let mut index = VectorIndex::open(&path, &vectors);
+            assert_eq!(index.len(), 200);
+            index.remove("m3");
+        }
+        assert!(path.exists());
+
+        let mut current = vectors.clone();
+        current.remove("m3");
+        let index = VectorIndex::open(&path, &current);
+        assert_eq!(index.len(), 199);
+        assert_eq!(index.unsaved, 0);
+        drop(index);
+
+        std::fs::write(&path, b"garbage").unwrap();
+        let index = VectorIndex::open(&path, &current);
+        assert_eq!(index.len(), 199);
+        let (key, vector) = current.iter().next().unwrap();
+        assert_eq!(&index.search(vector, 1)[0].0, key);
+    }
+
+    /// Search cost at 100k entries versus 10k: a linear scan does 10x the
+    /// work, HNSW far less. Run with
+    /// `cargo test --release hnsw -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark: builds a 100k-entry index"]
+    fn benchmark_sublinear_search_at_100k() {
+        const DIM: usize = 32;
+        let queries = random_vectors(100, DIM);
+        let mut cost = Vec::new();
+        for n in [10_000, 100_000] {
+            let vectors = random_vectors(n, DIM);
+            let started = Instant::now();
+            let index = build(&vectors);
+            let build_time = started.elapsed();
+
+            let started = Instant::now();
+            let evaluations: usize = queries
+                .iter()
+                .map(|(_, q)| index.search_counted(q, 10).1)
+                .sum();
+            let ann_time = started.elapsed() / 100;
+
+            let started = Instant::now();
+            for (_, q) in &queries[..10] {
+                brute_force(&vectors, q, 10);
+            }
+            let linear_time = started.elapsed() / 10;
+
+            let recall = recall_at(&index, &vectors, &queries[..20], 10);
+            println!(
+                "n={n}: build {build_time:?}, hnsw {ann_time:?}/query ({} distances), linear {linear_time:?}/query, recall@10 {recall:.3}",
+                evaluations / 100
+            );
+            assert!(ann_time < linear_time);
+            assert!(recall > 0.8);
+            cost.push(evaluations);
+        }
+        // 10x the entries, well under 10x the distance computations.
+        assert!(cost[1] < cost[0] * 4, "search cost grew linearly: {cost:?}");
+    }
+}
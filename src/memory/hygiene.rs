@@ -2,11 +2,17 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use super::hnsw::Hnsw;
+use super::scoped::scope_of;
+use super::sqlite::dedup_hash;
+use super::traits::normalize_tags;
+use super::vector;
 use crate::config::MemoryConfig;
 use anyhow::Result;
 use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration as StdDuration, SystemTime};
@@ -21,6 +27,8 @@ struct HygieneReport {
     purged_memory_archives: u64,
     purged_session_archives: u64,
     pruned_conversation_rows: u64,
+    #[serde(default)]
+    squashed_duplicate_rows: u64,
 }
 
 impl HygieneReport {
@@ -30,6 +38,7 @@ impl HygieneReport {
             + self.purged_memory_archives
             + self.purged_session_archives
             + self.pruned_conversation_rows
+            + self.squashed_duplicate_rows
     }
 }
 
@@ -63,18 +72,24 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
             workspace_dir,
             config.conversation_retention_days,
         )?,
+        squashed_duplicate_rows: if config.dedup {
+            squash_duplicate_rows(workspace_dir, config.dedup_similarity)?
+        } else {
+            0
+        },
     };
 
     write_state(workspace_dir, &report)?;
 
     if report.total_actions() > 0 {
         tracing::info!(
-            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={}",
+            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={} squashed_duplicate_rows={}",
             report.archived_memory_files,
             report.archived_session_files,
             report.purged_memory_archives,
             report.purged_session_archives,
             report.pruned_conversation_rows,
+            report.squashed_duplicate_rows,
         );
     }
 
@@ -314,14 +329,137 @@ fn prune_conversation_rows(workspace_dir: &Path, retention_days: u32) -> Result<
     conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
     let cutoff = (Local::now() - Duration::days(i64::from(retention_days))).to_rfc3339();
 
-    let affected = conn.execute(
-        "DELETE FROM memories WHERE category = 'conversation' AND updated_at < ?1",
-        params![cutoff],
-    )?;
+    // Pinned rows are kept; databases from before tags have none.
+    let has_tags = conn
+        .prepare("SELECT 1 FROM pragma_table_info('memories') WHERE name = 'tags'")?
+        .exists([])?;
+    let sql = if has_tags {
+        "DELETE FROM memories WHERE category = 'conversation' AND updated_at < ?1
+           AND instr(tags, '\"pinned\"') = 0"
+    } else {
+        "DELETE FROM memories WHERE category = 'conversation' AND updated_at < ?1"
+    };
+    let affected = conn.execute(sql, params![cutoff])?;
 
     Ok(u64::try_from(affected).unwrap_or(0))
 }
 
+struct ConversationRow {
+    id: String,
+    key: String,
+    hash: String,
+    embedding: Option<Vec<f32>>,
+    ref_count: i64,
+    updated_at: String,
+    tags: Vec<String>,
+}
+
+/// Squash duplicate conversation rows into the oldest copy in the same scope,
+/// adding up reference counts and tags. Rows are duplicates when their
+/// normalized content is equal or their embeddings are `similarity` alike.
+fn squash_duplicate_rows(workspace_dir: &Path, similarity: f64) -> Result<u64> {
+    let db_path = workspace_dir.join("memory").join("brain.db");
+    if !db_path.exists() {
+        return Ok(0);
+    }
+
+    let mut conn = Connection::open(db_path)?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+    // Databases from before dedup are upgraded when the backend opens them.
+    let upgraded = conn
+        .prepare("SELECT 1 FROM pragma_table_info('memories') WHERE name = 'ref_count'")?
+        .exists([])?;
+    if !upgraded {
+        return Ok(0);
+    }
+
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, key, content, embedding, ref_count, updated_at, tags, content_hash FROM memories
+             WHERE category = 'conversation' ORDER BY created_at, rowid",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let embedding: Option<Vec<u8>> = row.get(3)?;
+            let tags: String = row.get(6)?;
+            // Encrypted content cannot be hashed here; rows stored since
+            // dedup carry their hash, older ones are plaintext.
+            let hash: Option<String> = row.get(7)?;
+            let content: String = row.get(2)?;
+            Ok(ConversationRow {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                hash: hash.unwrap_or_else(|| dedup_hash(&content)),
+                embedding: embedding.as_deref().map(vector::bytes_to_vec),
+                ref_count: row.get(4)?,
+                updated_at: row.get(5)?,
+                tags: serde_json::from_str(&tags).unwrap_or_default(),
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+
+    // Kept rows, by scope and hash and in a graph keyed by row index.
+    let mut by_hash: HashMap<(&str, String), usize> = HashMap::new();
+    let mut graph = Hnsw::default();
+    let mut squashed: Vec<(usize, usize)> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let scope = scope_of(&row.key);
+        let hash = row.hash.clone();
+        let target = by_hash.get(&(scope, hash.clone())).copied().or_else(|| {
+            let embedding = row.embedding.as_deref()?;
+            graph
+                .search(embedding, 8)
+                .into_iter()
+                .filter(|(_, sim)| f64::from(*sim) >= similarity)
+                .filter_map(|(kept, _)| kept.parse::<usize>().ok())
+                .find(|&kept| scope_of(&rows[kept].key) == scope)
+        });
+        match target {
+            Some(kept) => squashed.push((i, kept)),
+            None => {
+                by_hash.insert((scope, hash), i);
+                if let Some(embedding) = &row.embedding {
+                    graph.insert(&i.to_string(), embedding);
+                }
+            }
+        }
+    }
+    if squashed.is_empty() {
+        return Ok(0);
+    }
+
+    let mut merged: HashMap<usize, (i64, &str, Vec<String>)> = HashMap::new();
+    for &(dup, kept) in &squashed {
+        let entry = merged.entry(kept).or_insert_with(|| {
+            let row = &rows[kept];
+            (row.ref_count, row.updated_at.as_str(), row.tags.clone())
+        });
+        let dup = &rows[dup];
+        entry.0 += dup.ref_count;
+        entry.1 = entry.1.max(dup.updated_at.as_str());
+        entry.2.extend_from_slice(&dup.tags);
+    }
+
+    let tx = conn.transaction()?;
+    for (kept, (ref_count, updated_at, tags)) in &merged {
+        tx.execute(
+            "UPDATE memories SET ref_count = ?2, updated_at = ?3, tags = ?4 WHERE id = ?1",
+            params![
+                rows[*kept].id,
+                ref_count,
+                updated_at,
+                serde_json::to_string(&normalize_tags(tags))?
+            ],
+        )?;
+    }
+    for &(dup, _) in &squashed {
+        tx.execute("DELETE FROM memories WHERE id = ?1", params![rows[dup].id])?;
+    }
+    tx.commit()?;
+
+    Ok(u64::try_from(squashed.len()).unwrap_or(0))
+}
+
 fn memory_date_from_filename(filename: &str) -> Option<NaiveDate> {
     let stem = filename.strip_suffix(".md")?;
     let date_part = stem.split('_').next().unwrap_or(stem);
@@ -512,13 +650,23 @@ mod tests {
         mem.store("core_keep", "durable", MemoryCategory::Core)
             .await
             .unwrap();
+        mem.store_with_metadata(
+            "conv_pinned",
+            "remember this",
+            MemoryCategory::Conversation,
+            &["pinned".into()],
+            &serde_json::Value::Null,
+        )
+        .await
+        .unwrap();
         drop(mem);
 
         let db_path = workspace.join("memory").join("brain.db");
         let conn = Connection::open(&db_path).unwrap();
         let old_cutoff = (Local::now() - Duration::days(60)).to_rfc3339();
         conn.execute(
-            "UPDATE memories SET created_at = ?1, updated_at = ?1 WHERE key = 'conv_old'",
+            "UPDATE memories SET created_at = ?1, updated_at = ?1
+             WHERE key IN ('conv_old', 'conv_pinned')",
             params![old_cutoff],
         )
         .unwrap();
@@ -540,5 +688,43 @@ mod tests {
             mem2.get("core_keep").await.unwrap().is_some(),
             "core memory should remain"
         );
+        assert!(
+            mem2.get("conv_pinned").await.unwrap().is_some(),
+            "pinned conversation rows should remain"
+        );
+    }
+
+    #[tokio::test]
+    async fn squashes_duplicate_conversation_rows() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+
+        // Stored without dedup, as by older versions.
+        let mem = SqliteMemory::new(workspace).unwrap();
+        for key in ["m1", "m2", "m3"] {
+            mem.store(key, "Good  night", MemoryCategory::Conversation)
+                .await
+                .unwrap();
+        }
+        mem.store("user:luca:m4", "good night", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+        mem.store("note", "good night", MemoryCategory::Core)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let mut cfg = default_cfg();
+        cfg.archive_after_days = 0;
+        cfg.purge_after_days = 0;
+        cfg.conversation_retention_days = 0;
+
+        run_if_due(&cfg, workspace).unwrap();
+
+        let mem = SqliteMemory::new(workspace).unwrap();
+        assert_eq!(mem.count().await.unwrap(), 3);
+        assert_eq!(mem.get("m1").await.unwrap().unwrap().ref_count, 3);
+        assert!(mem.get("m2").await.unwrap().is_none());
+        assert_eq!(mem.get("user:luca:m4").await.unwrap().unwrap().ref_count, 1);
     }
 }
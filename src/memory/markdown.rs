@@ -2,12 +2,28 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{
+    normalize_tags, recall_filtered_fallback, Memory, MemoryCategory, MemoryEntry, RecallFilter,
+};
 use async_trait::async_trait;
 use chrono::Local;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// Tags and metadata ride along at the end of an entry line in an HTML
+/// comment, so they stay invisible when the file is rendered.
+const META_OPEN: &str = " <!-- meta: ";
+const META_CLOSE: &str = " -->";
+
+#[derive(Default, Serialize, Deserialize)]
+struct EntryMeta {
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    metadata: serde_json::Value,
+}
+
 /// Markdown-based memory — plain files as source of truth
 ///
 /// Layout:
@@ -87,6 +103,7 @@ impl MarkdownMemory {
             .map(|(i, line)| {
                 let trimmed = line.trim();
                 let clean = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+                let (clean, meta) = Self::split_meta(clean);
                 MemoryEntry {
                     id: format!("{filename}:{i}"),
                     key: format!("{filename}:{i}"),
@@ -95,11 +112,48 @@ impl MarkdownMemory {
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    tags: meta.tags,
+                    metadata: meta.metadata,
+                    ref_count: 1,
+                    access_count: 0,
+                    last_accessed: None,
                 }
             })
             .collect()
     }
 
+    /// Split the meta comment off an entry line.
+    fn split_meta(line: &str) -> (&str, EntryMeta) {
+        line.strip_suffix(META_CLOSE)
+            .and_then(|rest| rest.rsplit_once(META_OPEN))
+            .and_then(|(content, json)| serde_json::from_str(json).ok().map(|meta| (content, meta)))
+            .unwrap_or((line, EntryMeta::default()))
+    }
+
+    /// The meta comment for an entry line; empty without tags and metadata.
+    fn meta_comment(tags: &[String], metadata: &serde_json::Value) -> String {
+        let meta = EntryMeta {
+            tags: normalize_tags(tags),
+            metadata: metadata.clone(),
+        };
+        if meta.tags.is_empty() && meta.metadata.is_null() {
+            return String::new();
+        }
+        // `>` only occurs inside JSON strings, so escaping it keeps `-->` out.
+        let json = serde_json::to_string(&meta)
+            .unwrap_or_default()
+            .replace('>', "\\u003e");
+        format!("{META_OPEN}{json}{META_CLOSE}")
+    }
+
+    /// The key an entry was stored under (`- **key**: content`).
+    fn stored_key(content: &str) -> Option<&str> {
+        content
+            .strip_prefix("**")?
+            .split_once("**:")
+            .map(|(key, _)| key)
+    }
+
     async fn read_all_entries(&self) -> anyhow::Result<Vec<MemoryEntry>> {
         let mut entries = Vec::new();
 
@@ -148,7 +202,20 @@ impl Memory for MarkdownMemory {
         content: &str,
         category: MemoryCategory,
     ) -> anyhow::Result<()> {
-        let entry = format!("- **{key}**: {content}");
+        self.store_with_metadata(key, content, category, &[], &serde_json::Value::Null)
+            .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let meta = Self::meta_comment(tags, metadata);
+        let entry = format!("- **{key}**: {content}{meta}");
         let path = match category {
             MemoryCategory::Core => self.core_path(),
             _ => self.daily_path(),
@@ -189,6 +256,29 @@ impl Memory for MarkdownMemory {
         Ok(scored)
     }
 
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        // Entry keys are line positions; match the prefix against the stored
+        // key in the content instead.
+        let Some(prefix) = filter.key_prefix.as_deref() else {
+            return recall_filtered_fallback(self, query, limit, filter).await;
+        };
+        let rest = RecallFilter {
+            key_prefix: None,
+            ..filter.clone()
+        };
+        let candidates = recall_filtered_fallback(self, query, usize::MAX, &rest).await?;
+        Ok(candidates
+            .into_iter()
+            .filter(|e| Self::stored_key(&e.content).is_some_and(|key| key.starts_with(prefix)))
+            .take(limit)
+            .collect())
+    }
+
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let all = self.read_all_entries().await?;
         Ok(all
@@ -340,6 +430,62 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[tokio::test]
+    async fn markdown_recall_filtered_uses_stored_keys_and_dates() {
+        let (_tmp, mem) = temp_workspace();
+        mem.store(
+            "trip:alps",
+            "Hiking in the Alps #travel",
+            MemoryCategory::Daily,
+        )
+        .await
+        .unwrap();
+        mem.store("pref:food", "Likes ramen #travel", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let trips = RecallFilter {
+            key_prefix: Some("trip:".into()),
+            tags: vec!["travel".into()],
+            ..RecallFilter::default()
+        };
+        let results = mem.recall_filtered("", 10, &trips).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("Alps"));
+
+        // Core entries have no date, so a time bound excludes them
+        let today = RecallFilter {
+            created_after: Some(chrono::Utc::now() - chrono::Duration::days(2)),
+            ..RecallFilter::default()
+        };
+        let results = mem.recall_filtered("travel", 10, &today).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].category, MemoryCategory::Daily);
+    }
+
+    #[tokio::test]
+    async fn markdown_tags_and_metadata_roundtrip() {
+        let (tmp, mem) = temp_workspace();
+        mem.store_with_metadata(
+            "pim:event",
+            "Dentist on Friday",
+            MemoryCategory::Core,
+            &["#Health".into()],
+            &serde_json::json!({ "link": "<cal:42>" }),
+        )
+        .await
+        .unwrap();
+
+        let raw = sync_fs::read_to_string(tmp.path().join("MEMORY.md")).unwrap();
+        assert!(raw.contains("<!-- meta: "));
+        assert!(!raw.contains("42>"));
+
+        let entries = mem.list(Some(&MemoryCategory::Core)).await.unwrap();
+        assert_eq!(entries[0].content, "**pim:event**: Dentist on Friday");
+        assert_eq!(entries[0].tags, vec!["health"]);
+        assert_eq!(entries[0].metadata["link"], "<cal:42>");
+    }
+
     #[tokio::test]
     async fn markdown_empty_count() {
         let (_tmp, mem) = temp_workspace();
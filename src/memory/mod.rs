@@ -4,8 +4,10 @@
 
 pub mod chunker;
 pub mod embeddings;
+pub mod hnsw;
 pub mod hygiene;
 pub mod markdown;
+pub mod ranking;
 pub mod scoped;
 pub mod sqlite;
 pub mod traits;
@@ -18,15 +20,16 @@ pub use sqlite::SqliteMemory;
 pub mod simple;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{MemoryCategory, MemoryEntry, RecallFilter};
 
-use crate::config::MemoryConfig;
+use crate::config::{MemoryConfig, SensitivityConfig};
 use std::path::Path;
 use std::sync::Arc;
 
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
+    sensitivity: &SensitivityConfig,
     workspace_dir: &Path,
     api_key: Option<&str>,
     audit: Arc<crate::security::AuditLogger>,
@@ -46,6 +49,10 @@ pub fn create_memory(
                     config.embedding_dimensions,
                 ));
 
+            let cipher = config
+                .encrypt_at_rest
+                .then(|| crate::security::SecretStore::new(&workspace_dir.join(".mymolt"), true));
+
             #[allow(clippy::cast_possible_truncation)]
             let mem = SqliteMemory::with_embedder(
                 workspace_dir,
@@ -53,22 +60,40 @@ pub fn create_memory(
                 config.vector_weight as f32,
                 config.keyword_weight as f32,
                 config.embedding_cache_size,
-            )?;
+            )?
+            .with_dedup(
+                config
+                    .dedup
+                    .then_some(config.dedup_similarity.clamp(0.0, 1.0) as f32),
+            )
+            .with_ranking(ranking::RankingWeights::from_config(config))
+            .with_encryption(cipher);
             Box::new(mem)
         }
-        "markdown" | "none" => Box::new(MarkdownMemory::new(workspace_dir)),
+        "markdown" | "none" => {
+            if config.encrypt_at_rest {
+                tracing::warn!("memory.encrypt_at_rest only applies to the sqlite backend");
+            }
+            Box::new(MarkdownMemory::new(workspace_dir))
+        }
         other => {
             tracing::warn!("Unknown memory backend '{other}', falling back to markdown");
             Box::new(MarkdownMemory::new(workspace_dir))
         }
     };
 
+    let scanner = sovereign::SensitivityScanner::for_languages(
+        &config.sensitivity_languages,
+        &config.sensitivity_extra_keywords,
+    )?
+    .configure(sensitivity)?;
+
     // Wrap with SovereignMemory (The Guard)
-    Ok(Box::new(sovereign::SovereignMemory::new(
-        Arc::from(backend),
-        workspace_dir,
-        audit,
-    )))
+    Ok(Box::new(
+        sovereign::SovereignMemory::new(Arc::from(backend), workspace_dir, audit)
+            .with_scanner(scanner)
+            .with_mode(config.sovereign.mode),
+    ))
 }
 
 #[cfg(test)]
@@ -90,7 +115,8 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem = create_memory(&cfg, tmp.path(), None, audit).unwrap();
+        let mem =
+            create_memory(&cfg, &SensitivityConfig::default(), tmp.path(), None, audit).unwrap();
         // Wrapped in sovereign
         assert_eq!(mem.name(), "sovereign");
     }
@@ -109,7 +135,8 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem = create_memory(&cfg, tmp.path(), None, audit).unwrap();
+        let mem =
+            create_memory(&cfg, &SensitivityConfig::default(), tmp.path(), None, audit).unwrap();
         assert_eq!(mem.name(), "sovereign");
     }
 
@@ -127,7 +154,8 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem = create_memory(&cfg, tmp.path(), None, audit).unwrap();
+        let mem =
+            create_memory(&cfg, &SensitivityConfig::default(), tmp.path(), None, audit).unwrap();
         assert_eq!(mem.name(), "sovereign");
     }
 
@@ -145,7 +173,8 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem = create_memory(&cfg, tmp.path(), None, audit).unwrap();
+        let mem =
+            create_memory(&cfg, &SensitivityConfig::default(), tmp.path(), None, audit).unwrap();
         assert_eq!(mem.name(), "sovereign");
     }
 }
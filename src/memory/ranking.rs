@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Recall ranking beyond relevance: importance and recency.
+//!
+//! Backends score recall results by how well they match the query.
+//! [`rerank`] blends that with how important an entry is — how often it was
+//! recalled, how many duplicates were squashed into it, whether it is a core
+//! fact — and how recently it was stored or recalled. Recency halves every
+//! `half_life_days`; entries tagged [`PINNED_TAG`] count as fully important
+//! and never decay.
+
+use super::traits::{parse_timestamp, MemoryCategory, MemoryEntry};
+use crate::config::MemoryConfig;
+use chrono::{DateTime, Utc};
+use std::cmp::Ordering;
+
+/// Tag that exempts an entry from decay.
+pub const PINNED_TAG: &str = "pinned";
+
+/// Recalls plus squashed duplicates at which importance reaches 1.
+const SATURATING_USES: f64 = 20.0;
+
+/// Baseline importance of core facts, before any use.
+const CORE_IMPORTANCE: f64 = 0.3;
+
+/// Weights of the three ranking signals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingWeights {
+    pub similarity: f64,
+    pub importance: f64,
+    pub recency: f64,
+    pub half_life_days: f64,
+}
+
+impl RankingWeights {
+    /// Weights from the config; `None` when only relevance counts.
+    pub fn from_config(config: &MemoryConfig) -> Option<Self> {
+        let weights = Self {
+            similarity: config.similarity_weight.max(0.0),
+            importance: config.importance_weight.max(0.0),
+            recency: config.recency_weight.max(0.0),
+            half_life_days: config.recency_half_life_days,
+        };
+        (weights.importance > 0.0 || weights.recency > 0.0).then_some(weights)
+    }
+}
+
+pub fn is_pinned(entry: &MemoryEntry) -> bool {
+    entry.tags.iter().any(|tag| tag == PINNED_TAG)
+}
+
+/// 0–1, growing logarithmically with recalls and squashed duplicates.
+pub fn importance(entry: &MemoryEntry) -> f64 {
+    if is_pinned(entry) {
+        return 1.0;
+    }
+    let uses = f64::from(entry.access_count) + f64::from(entry.ref_count.saturating_sub(1));
+    let base = if entry.category == MemoryCategory::Core {
+        CORE_IMPORTANCE
+    } else {
+        0.0
+    };
+    let earned = uses.ln_1p() / SATURATING_USES.ln_1p();
+    (base + (1.0 - base) * earned).min(1.0)
+}
+
+/// 0–1, halving every `half_life_days` since the entry was stored or last
+/// recalled. An entry of unknown age counts as one half-life old.
+pub fn recency(entry: &MemoryEntry, now: DateTime<Utc>, half_life_days: f64) -> f64 {
+    if is_pinned(entry) || half_life_days <= 0.0 {
+        return 1.0;
+    }
+    let last_touched = entry
+        .last_accessed
+        .as_deref()
+        .and_then(parse_timestamp)
+        .into_iter()
+        .chain(parse_timestamp(&entry.timestamp))
+        .max();
+    let Some(last_touched) = last_touched else {
+        return 0.5;
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let age_days = (now - last_touched).num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(age_days / half_life_days)
+}
+
+/// Re-score and re-sort recall results by the weighted blend of relevance,
+/// importance and recency. Relevance is taken relative to the best result,
+/// so it does not matter how the backend scales its scores.
+pub fn rerank(entries: &mut [MemoryEntry], weights: &RankingWeights, now: DateTime<Utc>) {
+    let best = entries
+        .iter()
+        .filter_map(|entry| entry.score)
+        .fold(0.0_f64, f64::max);
+    for entry in entries.iter_mut() {
+        let relevance = match entry.score {
+            Some(score) if best > 0.0 => score / best,
+            _ => 0.0,
+        };
+        entry.score = Some(
+            weights.similarity * relevance
+                + weights.importance * importance(entry)
+                + weights.recency * recency(entry, now, weights.half_life_days),
+        );
+    }
+    entries.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, score: f64, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: key.into(),
+            category: MemoryCategory::Conversation,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: Some(score),
+            tags: Vec::new(),
+            metadata: serde_json::Value::Null,
+            ref_count: 1,
+            access_count: 0,
+            last_accessed: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2026-06-01T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn recency_halves_per_half_life_unless_pinned() {
+        let mut old = entry("old", 1.0, "2026-05-02T00:00:00Z");
+        assert!((recency(&old, now(), 30.0) - 0.5).abs() < 1e-9);
+
+        old.last_accessed = Some("2026-06-01T00:00:00Z".into());
+        assert!((recency(&old, now(), 30.0) - 1.0).abs() < 1e-9);
+
+        let mut ancient = entry("ancient", 1.0, "2020-01-01T00:00:00Z");
+        ancient.tags = vec![PINNED_TAG.into()];
+        assert!((recency(&ancient, now(), 30.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn importance_grows_with_use_and_saturates() {
+        let mut e = entry("k", 1.0, "2026-06-01T00:00:00Z");
+        assert!(importance(&e).abs() < 1e-9);
+        e.access_count = 4;
+        let used = importance(&e);
+        assert!(used > 0.0 && used < 1.0);
+        e.access_count = 500;
+        assert!((importance(&e) - 1.0).abs() < 1e-9);
+
+        let mut core = entry("core", 1.0, "2026-06-01T00:00:00Z");
+        core.category = MemoryCategory::Core;
+        assert!((importance(&core) - CORE_IMPORTANCE).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rerank_lifts_fresh_and_important_entries() {
+        let weights = RankingWeights {
+            similarity: 1.0,
+            importance: 0.5,
+            recency: 0.5,
+            half_life_days: 30.0,
+        };
+        let stale = entry("stale", 10.0, "2025-06-01T00:00:00Z");
+        let mut favourite = entry("favourite", 8.0, "2026-05-30T00:00:00Z");
+        favourite.access_count = 10;
+        let mut results = vec![stale, favourite];
+
+        rerank(&mut results, &weights, now());
+
+        assert_eq!(results[0].key, "favourite");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn from_config_is_none_for_relevance_only() {
+        let config = MemoryConfig {
+            importance_weight: 0.0,
+            recency_weight: 0.0,
+            ..MemoryConfig::default()
+        };
+        assert!(RankingWeights::from_config(&config).is_none());
+        assert!(RankingWeights::from_config(&MemoryConfig::default()).is_some());
+    }
+}
@@ -7,11 +7,182 @@
 //! In family mode, each user gets a private scope for their conversations
 //! while the `shared` scope is visible to everyone. The scoping is implemented
 //! by prefixing memory keys with the user scope.
+//!
+//! The owner's turns run without a scope and see [`OwnerMemory`]: unscoped
+//! and shared entries, but not what family members or channel senders keep
+//! in their own scopes.
+//!
+//! Memory tools are built once and shared by all turns; a turn run inside
+//! [`with_turn_scope`] makes them use its scope via [`confine_to_turn`].
 
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{erasure_matches, Memory, MemoryCategory, MemoryEntry, RecallFilter};
+use crate::identity::family::SCOPE_SHARED;
 use async_trait::async_trait;
+use std::future::Future;
 use std::sync::Arc;
 
+tokio::task_local! {
+    static TURN_SCOPE: Option<String>;
+}
+
+/// `memory` confined to `scope`, or the owner's view of it for `None`.
+pub fn confine(memory: &Arc<dyn Memory>, scope: Option<String>) -> Arc<dyn Memory> {
+    match scope {
+        Some(scope) => Arc::new(ScopedMemory::new(Arc::clone(memory), scope)),
+        None => Arc::new(OwnerMemory::new(Arc::clone(memory))),
+    }
+}
+
+/// Run an agent turn with memory tools confined to `scope`.
+pub async fn with_turn_scope<F: Future>(scope: Option<String>, fut: F) -> F::Output {
+    TURN_SCOPE.scope(scope, fut).await
+}
+
+/// `memory` confined to the scope of the running turn, if any.
+pub fn confine_to_turn(memory: &Arc<dyn Memory>) -> Arc<dyn Memory> {
+    confine(memory, TURN_SCOPE.try_with(Clone::clone).ok().flatten())
+}
+
+/// Scope a stored key belongs to (`user:<slug>`, `shared` or
+/// `channel:<channel>:<user>`); `""` for keys stored without a scope.
+pub fn scope_of(key: &str) -> &str {
+    let separators = match key.split(':').next() {
+        Some("user") => 2,
+        Some(SCOPE_SHARED) => 1,
+        Some("channel") => 3,
+        _ => return "",
+    };
+    key.match_indices(':')
+        .nth(separators - 1)
+        .map_or("", |(i, _)| &key[..i])
+}
+
+/// Whether `key` lies in a member's or channel sender's private scope.
+fn is_private(key: &str) -> bool {
+    !matches!(scope_of(key), "" | SCOPE_SHARED)
+}
+
+/// The owner's view of memory: unscoped and shared entries. Entries in a
+/// family member's (`user:`) or channel sender's (`channel:`) scope are
+/// neither returned nor changed, so those stay private to their owners.
+pub struct OwnerMemory {
+    inner: Arc<dyn Memory>,
+}
+
+impl OwnerMemory {
+    pub fn new(inner: Arc<dyn Memory>) -> Self {
+        Self { inner }
+    }
+
+    fn visible(entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        entries
+            .into_iter()
+            .filter(|e| !is_private(&e.key))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Memory for OwnerMemory {
+    fn name(&self) -> &str {
+        "owner"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+    ) -> anyhow::Result<()> {
+        if is_private(key) {
+            anyhow::bail!("'{key}' is in another member's memory scope");
+        }
+        self.inner.store(key, content, category).await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        if is_private(key) {
+            anyhow::bail!("'{key}' is in another member's memory scope");
+        }
+        self.inner
+            .store_with_metadata(key, content, category, tags, metadata)
+            .await
+    }
+
+    async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
+        let all = self.inner.recall(query, limit * 3).await?;
+        let mut visible = Self::visible(all);
+        visible.truncate(limit);
+        Ok(visible)
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let all = self.inner.recall_filtered(query, limit * 3, filter).await?;
+        let mut visible = Self::visible(all);
+        visible.truncate(limit);
+        Ok(visible)
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        if is_private(key) {
+            return Ok(None);
+        }
+        self.inner.get(key).await
+    }
+
+    async fn list(&self, category: Option<&MemoryCategory>) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(Self::visible(self.inner.list(category).await?))
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        if is_private(key) {
+            return Ok(false);
+        }
+        self.inner.forget(key).await
+    }
+
+    async fn forget_matching(
+        &self,
+        query: &str,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        // Removed one by one, so members' entries are never touched
+        let matching: Vec<MemoryEntry> = self
+            .list(filter.category.as_ref())
+            .await?
+            .into_iter()
+            .filter(|e| erasure_matches(e, query, filter))
+            .collect();
+        let mut removed = Vec::with_capacity(matching.len());
+        for entry in matching {
+            if self.inner.forget(&entry.key).await? {
+                removed.push(entry);
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        Ok(self.list(None).await?.len())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+}
+
 /// Scoped memory: wraps an inner `Memory` to provide per-user isolation.
 ///
 /// - `store()` always prefixes the key with the user scope.
@@ -38,6 +209,26 @@ impl ScopedMemory {
         format!("{}:{}", self.user_scope, key)
     }
 
+    /// Keep only this user's and shared entries, with scope prefixes
+    /// stripped from the keys for clean display.
+    fn visible(&self, entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        let user_prefix = format!("{}:", self.user_scope);
+        let shared_prefix = format!("{}:", crate::identity::family::SCOPE_SHARED);
+
+        entries
+            .into_iter()
+            .filter(|e| e.key.starts_with(&user_prefix) || e.key.starts_with(&shared_prefix))
+            .map(|mut e| {
+                if let Some(clean) = e.key.strip_prefix(&user_prefix) {
+                    e.key = clean.to_string();
+                } else if let Some(clean) = e.key.strip_prefix(&shared_prefix) {
+                    e.key = format!("[shared] {clean}");
+                }
+                e
+            })
+            .collect()
+    }
+
     /// Prefix a key with the shared scope.
     fn shared_key(key: &str) -> String {
         format!("{}:{}", crate::identity::family::SCOPE_SHARED, key)
@@ -61,30 +252,56 @@ impl Memory for ScopedMemory {
         self.inner.store(&scoped, content, category).await
     }
 
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let scoped = self.scoped_key(key);
+        self.inner
+            .store_with_metadata(&scoped, content, category, tags, metadata)
+            .await
+    }
+
     async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
         // Recall from inner (which has both scoped and shared entries),
         // then filter to only this user's scope + shared.
         let all = self.inner.recall(query, limit * 3).await?;
+        let mut filtered = self.visible(all);
+        filtered.truncate(limit);
+        Ok(filtered)
+    }
 
-        let user_prefix = format!("{}:", self.user_scope);
-        let shared_prefix = format!("{}:", crate::identity::family::SCOPE_SHARED);
-
-        let filtered: Vec<MemoryEntry> = all
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        // Key prefixes are relative to the scope, so they are applied after
+        // the scope prefix is stripped.
+        let inner_filter = RecallFilter {
+            key_prefix: None,
+            ..filter.clone()
+        };
+        let all = self
+            .inner
+            .recall_filtered(query, limit * 3, &inner_filter)
+            .await?;
+        Ok(self
+            .visible(all)
             .into_iter()
-            .filter(|e| e.key.starts_with(&user_prefix) || e.key.starts_with(&shared_prefix))
-            .take(limit)
-            .map(|mut e| {
-                // Strip scope prefix from key for clean display
-                if let Some(clean) = e.key.strip_prefix(&user_prefix) {
-                    e.key = clean.to_string();
-                } else if let Some(clean) = e.key.strip_prefix(&shared_prefix) {
-                    e.key = format!("[shared] {clean}");
-                }
-                e
+            .filter(|e| {
+                filter.key_prefix.as_deref().is_none_or(|prefix| {
+                    let key = e.key.strip_prefix("[shared] ").unwrap_or(&e.key);
+                    key.starts_with(prefix)
+                })
             })
-            .collect();
-
-        Ok(filtered)
+            .take(limit)
+            .collect())
     }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
@@ -116,6 +333,29 @@ impl Memory for ScopedMemory {
         self.inner.forget(&scoped).await
     }
 
+    async fn forget_matching(
+        &self,
+        query: &str,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        // Like `forget`, limited to the own scope.
+        let prefix = format!("{}:", self.user_scope);
+        let inner_filter = RecallFilter {
+            key_prefix: Some(format!(
+                "{prefix}{}",
+                filter.key_prefix.as_deref().unwrap_or_default()
+            )),
+            ..filter.clone()
+        };
+        let mut removed = self.inner.forget_matching(query, &inner_filter).await?;
+        for entry in &mut removed {
+            if let Some(clean) = entry.key.strip_prefix(&prefix) {
+                entry.key = clean.to_string();
+            }
+        }
+        Ok(removed)
+    }
+
     async fn count(&self) -> anyhow::Result<usize> {
         let all = self.inner.list(None).await?;
         let user_prefix = format!("{}:", self.user_scope);
@@ -168,7 +408,11 @@ mod tests {
 
         // Store shared entry directly in inner
         inner
-            .store("shared:family_plan", "vacation in July", MemoryCategory::Core)
+            .store(
+                "shared:family_plan",
+                "vacation in July",
+                MemoryCategory::Core,
+            )
             .await
             .unwrap();
 
@@ -183,12 +427,14 @@ mod tests {
 
         // Should see own scoped entry
         assert!(
-            keys.iter().any(|k| k.contains("benjamin") && k.contains("private_note")),
+            keys.iter()
+                .any(|k| k.contains("benjamin") && k.contains("private_note")),
             "Should see own entry, got: {keys:?}"
         );
         // Should see shared entry
         assert!(
-            keys.iter().any(|k| k.contains("shared") && k.contains("family_plan")),
+            keys.iter()
+                .any(|k| k.contains("shared") && k.contains("family_plan")),
             "Should see shared entry, got: {keys:?}"
         );
         // Should NOT see Maria's entry
@@ -252,4 +498,112 @@ mod tests {
         let (_inner, scoped) = make_scoped("user:test");
         assert!(scoped.health_check().await);
     }
+
+    #[tokio::test]
+    async fn turn_scope_confines_shared_tools() {
+        let inner: Arc<dyn Memory> = Arc::new(SimpleMemory::new());
+
+        with_turn_scope(Some("user:luca".into()), async {
+            confine_to_turn(&inner)
+                .store("pet", "Hamster", MemoryCategory::Conversation)
+                .await
+                .unwrap();
+        })
+        .await;
+        confine_to_turn(&inner)
+            .store("car", "Blue", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        assert!(inner.get("user:luca:pet").await.unwrap().is_some());
+        assert!(inner.get("car").await.unwrap().is_some());
+        let other = confine(&inner, Some("user:maria".into()));
+        assert!(other.get("pet").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn scope_of_stored_keys() {
+        assert_eq!(scope_of("user:luca:pet"), "user:luca");
+        assert_eq!(scope_of("shared:wifi"), "shared");
+        assert_eq!(
+            scope_of("channel:matrix:@ann_example.org:matrix_@ann:example.org_1"),
+            "channel:matrix:@ann_example.org"
+        );
+        assert_eq!(scope_of("telegram_42_7"), "");
+        assert_eq!(scope_of("user:"), "");
+    }
+
+    #[tokio::test]
+    async fn forget_matching_stays_in_own_scope() {
+        let (inner, scoped) = make_scoped("user:luca");
+        scoped
+            .store("pet", "Hamster named Kiwi", MemoryCategory::Core)
+            .await
+            .unwrap();
+        inner
+            .store("user:maria:pet", "Kiwi the cat", MemoryCategory::Core)
+            .await
+            .unwrap();
+        inner
+            .store("shared:fruit", "Kiwi is in the fridge", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let removed = scoped
+            .forget_matching("kiwi", &RecallFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].key, "pet");
+        assert!(inner.get("user:maria:pet").await.unwrap().is_some());
+        assert!(inner.get("shared:fruit").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn owner_and_members_do_not_see_each_others_entries() {
+        let inner: Arc<dyn Memory> = Arc::new(SimpleMemory::new());
+        let owner = confine(&inner, None);
+        let child = confine(&inner, Some("user:luca".into()));
+        owner
+            .store("owner_pet", "Kiwi the parrot", MemoryCategory::Core)
+            .await
+            .unwrap();
+        child
+            .store("child_pet", "Kiwi the hamster", MemoryCategory::Core)
+            .await
+            .unwrap();
+        inner
+            .store("shared:fruit", "Kiwi in the fridge", MemoryCategory::Core)
+            .await
+            .unwrap();
+        let keys = |entries: Vec<MemoryEntry>| {
+            let mut keys: Vec<String> = entries.into_iter().map(|e| e.key).collect();
+            keys.sort();
+            keys
+        };
+
+        assert_eq!(
+            keys(owner.list(None).await.unwrap()),
+            vec!["owner_pet", "shared:fruit"]
+        );
+        assert!(owner.get("user:luca:child_pet").await.unwrap().is_none());
+        assert!(!owner.forget("user:luca:child_pet").await.unwrap());
+        assert!(owner
+            .store("user:luca:child_pet", "overwritten", MemoryCategory::Core)
+            .await
+            .is_err());
+        assert_eq!(
+            keys(child.list(None).await.unwrap()),
+            vec!["shared:fruit", "user:luca:child_pet"]
+        );
+        assert!(child.get("owner_pet").await.unwrap().is_none());
+
+        let removed = owner
+            .forget_matching("kiwi", &RecallFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+        let kept = inner.get("user:luca:child_pet").await.unwrap().unwrap();
+        assert_eq!(kept.content, "Kiwi the hamster");
+    }
 }
@@ -2,7 +2,7 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{normalize_tags, Memory, MemoryCategory, MemoryEntry};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::RwLock;
@@ -25,7 +25,31 @@ impl Memory for SimpleMemory {
         "simple"
     }
 
-    async fn store(&self, key: &str, content: &str, category: MemoryCategory) -> anyhow::Result<()> {
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+    ) -> anyhow::Result<()> {
+        let (tags, metadata) = self
+            .store
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|e| (e.tags.clone(), e.metadata.clone()))
+            .unwrap_or_default();
+        self.store_with_metadata(key, content, category, &tags, &metadata)
+            .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
         let entry = MemoryEntry {
             key: key.to_string(),
             content: content.to_string(),
@@ -34,6 +58,11 @@ impl Memory for SimpleMemory {
             id: uuid::Uuid::new_v4().to_string(),
             session_id: None,
             score: None,
+            tags: normalize_tags(tags),
+            metadata: metadata.clone(),
+            ref_count: 1,
+            access_count: 0,
+            last_accessed: None,
         };
         self.store.write().unwrap().insert(key.to_string(), entry);
         Ok(())
@@ -2,17 +2,54 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+use crate::config::{SensitivityConfig, SovereignMode};
+use crate::memory::{Memory, MemoryCategory, MemoryEntry, RecallFilter};
 use crate::security::VaultManager;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use regex::Regex;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 use crate::security::AuditLogger;
 
 use aho_corasick::AhoCorasick;
 
+/// PIN/code trigger words and "is"-style connectors for one language.
+struct LocaleTriggers {
+    code: &'static str,
+    keywords: &'static [&'static str],
+    connectors: &'static [&'static str],
+}
+
+/// Built-in trigger tables. Keywords are matched case-insensitively at a word start.
+const LOCALE_TRIGGERS: &[LocaleTriggers] = &[
+    LocaleTriggers {
+        code: "en",
+        keywords: &["pin", "conf", "cvv", "cvc", "code", "passcode", "tan"],
+        connectors: &["is"],
+    },
+    LocaleTriggers {
+        code: "de",
+        keywords: &[
+            "pin",
+            "geheimzahl",
+            "geheimnummer",
+            "tan",
+            "code",
+            "zugangscode",
+            "sicherheitscode",
+            "kartenprüfnummer",
+            "prüfziffer",
+            "prüfnummer",
+        ],
+        connectors: &["ist", "lautet", "lauten", "heißt"],
+    },
+];
+
+/// Languages enabled when nothing is configured (the target user base is German-speaking).
+pub const DEFAULT_SENSITIVITY_LANGUAGES: &[&str] = &["en", "de"];
+
 /// Scans text for sensitive patterns.
 ///
 /// Uses a two-phase approach for performance:
@@ -24,15 +61,62 @@ use aho_corasick::AhoCorasick;
 pub struct SensitivityScanner {
     /// Fixed-prefix pre-filter (single-pass Aho-Corasick automaton).
     prefix_filter: AhoCorasick,
-    /// Full regex patterns for precise matching.
-    /// Indices 0..3 are prefix-guarded, 4..6 always run.
+    /// Full regex patterns for precise matching. The first
+    /// `prefix_guarded_count` are prefix-guarded, the rest always run.
     patterns: Vec<(String, Regex)>,
     /// Number of prefix-guarded patterns at the start of `patterns`.
     prefix_guarded_count: usize,
+    /// Values never treated as sensitive, keyed by [`allowlist_key`].
+    allowlist: HashSet<String>,
 }
 
 impl SensitivityScanner {
+    /// Scanner with the default languages (English + German).
     pub fn new() -> Self {
+        Self::for_languages(&[], &[]).expect("built-in sensitivity languages are valid")
+    }
+
+    /// Scanner whose PIN/code triggers cover `languages` (ISO 639-1 codes, empty =
+    /// defaults) plus any `extra_keywords` for languages without a built-in table.
+    pub fn for_languages(languages: &[String], extra_keywords: &[String]) -> Result<Self> {
+        let codes: Vec<&str> = if languages.is_empty() {
+            DEFAULT_SENSITIVITY_LANGUAGES.to_vec()
+        } else {
+            languages.iter().map(String::as_str).collect()
+        };
+
+        let mut keywords: Vec<String> = Vec::new();
+        let mut connectors: Vec<String> = Vec::new();
+        for code in codes {
+            let Some(locale) = LOCALE_TRIGGERS
+                .iter()
+                .find(|l| l.code.eq_ignore_ascii_case(code))
+            else {
+                anyhow::bail!(
+                    "Unsupported sensitivity language '{code}' (built-in: {}); use sensitivity_extra_keywords instead",
+                    LOCALE_TRIGGERS.iter().map(|l| l.code).collect::<Vec<_>>().join(", ")
+                );
+            };
+            keywords.extend(locale.keywords.iter().map(|k| (*k).to_string()));
+            connectors.extend(locale.connectors.iter().map(|c| (*c).to_string()));
+        }
+        keywords.extend(
+            extra_keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty()),
+        );
+        keywords.sort();
+        keywords.dedup();
+        connectors.sort();
+        connectors.dedup();
+        // Longest first so alternation prefers "geheimzahl" over "geheim…" prefixes.
+        keywords.sort_by_key(|k| std::cmp::Reverse(k.chars().count()));
+
+        Ok(Self::build(&keywords, &connectors))
+    }
+
+    fn build(keywords: &[String], connectors: &[String]) -> Self {
         // Prefix-guarded patterns (indices 0..3): only checked when prefix is found
         let prefix_patterns = vec![
             (
@@ -66,8 +150,14 @@ impl SensitivityScanner {
             ),
             (
                 "Bank PIN".into(),
-                // Match "PIN is 1234", "PIN: 1234", "code 1234", "cvv 123"
-                Regex::new(r"(?i)(pin|conf|cvv|code)\s*(?:is|:|=|-)?\s*(\d{3,8})").unwrap(),
+                // Match "PIN is 1234", "PIN: 1234", "Geheimzahl lautet 12 34", "TAN = 123.456".
+                // Digits may be grouped by spaces, dots, or hyphens as dictated/transcribed.
+                Regex::new(&format!(
+                    r"(?i)\b(?:{})\s*(?:(?:{})\b)?\s*[:=-]?\s*(\d(?:[ .-]?\d){{2,7}})",
+                    keywords.iter().map(|k| regex::escape(k)).collect::<Vec<_>>().join("|"),
+                    connectors.iter().map(|c| regex::escape(c)).collect::<Vec<_>>().join("|"),
+                ))
+                .unwrap(),
             ),
         ];
 
@@ -82,6 +172,7 @@ impl SensitivityScanner {
             prefix_filter,
             patterns,
             prefix_guarded_count,
+            allowlist: HashSet::new(),
         }
     }
 
@@ -95,7 +186,7 @@ impl SensitivityScanner {
             if i < self.prefix_guarded_count && !has_prefix {
                 continue;
             }
-            if re.is_match(text) {
+            if self.matches(re, text) {
                 return Some(name.clone());
             }
         }
@@ -105,33 +196,146 @@ impl SensitivityScanner {
     /// Replace all sensitive matches in `text` with `[REDACTED:{pattern_name}]`.
     /// Returns `(redacted_text, Vec<pattern_names_found>)`.
     pub fn redact(&self, text: &str) -> (String, Vec<String>) {
+        let (result, secrets) = self.mask(text, false);
+        let mut found: Vec<String> = secrets.into_iter().map(|(name, _)| name).collect();
+        found.dedup();
+        (result, found)
+    }
+
+    /// Like [`redact`](Self::redact), but keeps the words around a secret
+    /// ("PIN is [REDACTED:Bank PIN]") and returns each match as
+    /// `(pattern_name, secret)`.
+    pub fn extract(&self, text: &str) -> (String, Vec<(String, String)>) {
+        self.mask(text, true)
+    }
+
+    /// Apply `[security.sensitivity]`: turn off built-in patterns, add custom
+    /// ones and skip allowlisted values. Unknown pattern names and invalid
+    /// regexes are errors, so a typo fails at startup instead of letting
+    /// secrets through.
+    pub fn configure(mut self, config: &SensitivityConfig) -> Result<Self> {
+        let built_in = self
+            .patterns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        for name in &config.disabled {
+            let Some(i) = self
+                .patterns
+                .iter()
+                .position(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            else {
+                anyhow::bail!(
+                    "Unknown sensitivity pattern '{name}' in security.sensitivity.disabled (built-in: {built_in})"
+                );
+            };
+            self.patterns.remove(i);
+            if i < self.prefix_guarded_count {
+                self.prefix_guarded_count -= 1;
+            }
+        }
+
+        for (name, pattern) in &config.patterns {
+            if name.trim().is_empty() {
+                anyhow::bail!("Sensitivity patterns in security.sensitivity.patterns need a name");
+            }
+            if self.patterns.iter().any(|(n, _)| n == name) {
+                anyhow::bail!("Sensitivity pattern '{name}' is already built in; disable it first");
+            }
+            let re = Regex::new(pattern)
+                .with_context(|| format!("Invalid regex for sensitivity pattern '{name}'"))?;
+            self.patterns.push((name.clone(), re));
+        }
+
+        self.allowlist = config
+            .allowlist
+            .iter()
+            .map(|value| allowlist_key(value))
+            .filter(|key| !key.is_empty())
+            .collect();
+        Ok(self)
+    }
+
+    /// Replace every sensitive match with `[REDACTED:{pattern_name}]`: the
+    /// whole match, or with `keep_context` only the secret captured in the
+    /// pattern's first group.
+    fn mask(&self, text: &str, keep_context: bool) -> (String, Vec<(String, String)>) {
         let has_prefix = self.prefix_filter.is_match(text);
         let mut result = text.to_string();
-        let mut found = Vec::new();
+        let mut secrets = Vec::new();
         for (i, (name, re)) in self.patterns.iter().enumerate() {
             if i < self.prefix_guarded_count && !has_prefix {
                 continue;
             }
-            if re.is_match(&result) {
-                found.push(name.clone());
-                result = re
-                    .replace_all(&result, format!("[REDACTED:{}]", name))
-                    .to_string();
-            }
+            result = re
+                .replace_all(&result, |caps: &regex::Captures| {
+                    let whole = caps.get(0).expect("group 0 always matches");
+                    if self.is_allowed(caps) {
+                        return whole.as_str().to_string();
+                    }
+                    let secret = if keep_context {
+                        caps.get(1).unwrap_or(whole)
+                    } else {
+                        whole
+                    };
+                    secrets.push((name.clone(), secret.as_str().to_string()));
+                    format!(
+                        "{}[REDACTED:{}]{}",
+                        &whole.as_str()[..secret.start() - whole.start()],
+                        name,
+                        &whole.as_str()[secret.end() - whole.start()..]
+                    )
+                })
+                .to_string();
         }
-        (result, found)
+        (result, secrets)
+    }
+
+    fn matches(&self, re: &Regex, text: &str) -> bool {
+        if self.allowlist.is_empty() {
+            return re.is_match(text);
+        }
+        re.captures_iter(text).any(|caps| !self.is_allowed(&caps))
+    }
+
+    /// Whether a match, or the secret captured in it, is allowlisted.
+    fn is_allowed(&self, caps: &regex::Captures) -> bool {
+        !self.allowlist.is_empty()
+            && caps
+                .iter()
+                .take(2)
+                .flatten()
+                .any(|m| self.allowlist.contains(&allowlist_key(m.as_str())))
     }
 }
 
+/// Allowlist entries and matches are compared without whitespace and case.
+fn allowlist_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether stored `content` had sensitive parts moved into the vault.
+pub fn is_vaulted(content: &str) -> bool {
+    content.starts_with("[VAULT:") || content.contains("[REDACTED:")
+}
+
 /// The "Guard" that intercepts memory operations.
 ///
 /// Wraps an underlying memory backend and intercepts strict `store` calls.
 /// If sensitive content is detected, it is encrypted into the Vault,
-/// and only an "Opaque Pointer" is stored in the underlying memory.
+/// and only an "Opaque Pointer" is stored in the underlying memory — or, in
+/// [`SovereignMode::Redact`], the content with just the secrets vaulted and
+/// masked.
 pub struct SovereignMemory {
     inner: Arc<dyn Memory>,
     vault: VaultManager,
     scanner: SensitivityScanner,
+    mode: SovereignMode,
     recipient: String,
     audit: Arc<AuditLogger>,
 }
@@ -147,57 +351,68 @@ impl SovereignMemory {
             inner,
             vault: VaultManager::new(workspace_dir),
             scanner: SensitivityScanner::new(),
+            mode: SovereignMode::default(),
             recipient,
             audit,
         }
     }
-}
 
-#[async_trait]
-impl Memory for SovereignMemory {
-    fn name(&self) -> &str {
-        "sovereign"
+    /// Replace the default scanner (e.g. one built for the configured languages).
+    pub fn with_scanner(mut self, scanner: SensitivityScanner) -> Self {
+        self.scanner = scanner;
+        self
     }
 
-    async fn store(&self, key: &str, content: &str, category: MemoryCategory) -> Result<()> {
-        // 1. Safety Check: Avoid infinite recursion.
-        // The VaultManager itself calls `store` to save metadata index.
-        // We must identify these calls and let them pass through.
-        if let MemoryCategory::Custom(ref s) = category {
-            if s == "vault" {
-                return self.inner.store(key, content, category).await;
+    /// Choose between vaulting whole entries and redacting secrets inline.
+    pub fn with_mode(mut self, mode: SovereignMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Vault `content` if it is sensitive and return what to store in its
+    /// place; safe content is returned unchanged.
+    async fn redact(&self, key: &str, content: &str) -> Result<String> {
+        // 1. Scan for Sensitivity
+        let sensitive = match self.mode {
+            SovereignMode::Vault => self.scanner.scan(content).map(|reason| {
+                // This replaces the actual sensitive content with a safe placeholder.
+                let pointer = format!("[VAULT: {} - Access Required]", reason);
+                (reason, content.to_string(), pointer)
+            }),
+            SovereignMode::Redact => {
+                let (redacted, secrets) = self.scanner.extract(content);
+                // Only the secrets go to the vault, one "pattern: secret" per line.
+                let mut reasons: Vec<&str> =
+                    secrets.iter().map(|(name, _)| name.as_str()).collect();
+                reasons.dedup();
+                let vaulted: Vec<String> = secrets
+                    .iter()
+                    .map(|(name, secret)| format!("{}: {}", name, secret))
+                    .collect();
+                (!secrets.is_empty())
+                    .then(|| (reasons.join(", "), vaulted.join("\n"), redacted))
             }
-        }
+        };
 
-        // 2. Scan for Sensitivity
-        if let Some(reason) = self.scanner.scan(content) {
+        if let Some((reason, secret, placeholder)) = sensitive {
             tracing::info!(
                 "🛡️ Sovereign Interceptor: Detected sensitive data ({}) for key '{}'. Vaulting...",
                 reason,
                 key
             );
 
-            // 3. Encrypt to Vault
+            // 2. Encrypt to Vault
             // We pass `self.inner` (Arc<dyn Memory>) to `encrypt_to_vault`.
             // The VaultManager will use it to store the metadata.
-            let description = format!("Vaulted content for {}: {}", key, reason);
+            let description = Self::vault_description(key, &reason);
             
             // Note: `encrypt_to_vault` expects generic M: Memory. 
             // Since we carry `Arc<dyn Memory>`, we dereferencing it creates a trait object match.
             self.vault
-                .encrypt_to_vault(
-                    self.inner.as_ref(),
-                    content.to_string(),
-                    &description,
-                    &self.recipient,
-                )
+                .encrypt_to_vault(self.inner.as_ref(), secret, &description, &self.recipient)
                 .await?;
 
-            // 4. Store Opaque Pointer in Cleartext Memory
-            // This replaces the actual sensitive content with a safe placeholder.
-            let pointer = format!("[VAULT: {} - Access Required]", reason);
-
-            // 5. Log Sigil Interception for UI Transparency
+            // 3. Log Sigil Interception for UI Transparency
             let _ = self.audit.log(
                 &crate::security::AuditEvent::new(crate::security::AuditEventType::SigilInterception)
                     .with_action(
@@ -208,17 +423,81 @@ impl Memory for SovereignMemory {
                     )
             );
 
-            return self.inner.store(key, &pointer, category).await;
+            // 4. Store the placeholder in Cleartext Memory
+            return Ok(placeholder);
+        }
+
+        Ok(content.to_string())
+    }
+
+    /// Description of content vaulted for `key`; it is what links the
+    /// memory entry to its vault entry.
+    fn vault_description(key: &str, reason: &str) -> String {
+        format!("Vaulted content for {}: {}", key, reason)
+    }
+
+    /// Erase the vault entries holding content vaulted for `key`, along
+    /// with their index entries.
+    async fn erase_vaulted(&self, key: &str) -> Result<usize> {
+        let prefix = Self::vault_description(key, "");
+        let mut erased = 0;
+        for meta in self.vault.list_entries()? {
+            if meta.description.starts_with(&prefix) && self.vault.erase(&meta.id)? {
+                self.inner.forget(&format!("vault:{}", meta.id)).await?;
+                erased += 1;
+            }
+        }
+        Ok(erased)
+    }
+}
+
+#[async_trait]
+impl Memory for SovereignMemory {
+    fn name(&self) -> &str {
+        "sovereign"
+    }
+
+    async fn store(&self, key: &str, content: &str, category: MemoryCategory) -> Result<()> {
+        // 1. Safety Check: Avoid infinite recursion.
+        // The VaultManager itself calls `store` to save metadata index.
+        // We must identify these calls and let them pass through.
+        if let MemoryCategory::Custom(ref s) = category {
+            if s == "vault" {
+                return self.inner.store(key, content, category).await;
+            }
         }
 
-        // 5. Pass through safe content
-        self.inner.store(key, content, category).await
+        let content = self.redact(key, content).await?;
+        self.inner.store(key, &content, category).await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> Result<()> {
+        let content = self.redact(key, content).await?;
+        self.inner
+            .store_with_metadata(key, &content, category, tags, metadata)
+            .await
     }
 
     async fn recall(&self, query: &str, limit: usize) -> Result<Vec<MemoryEntry>> {
         self.inner.recall(query, limit).await
     }
 
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> Result<Vec<MemoryEntry>> {
+        self.inner.recall_filtered(query, limit, filter).await
+    }
+
     async fn get(&self, key: &str) -> Result<Option<MemoryEntry>> {
         self.inner.get(key).await
     }
@@ -228,7 +507,25 @@ impl Memory for SovereignMemory {
     }
 
     async fn forget(&self, key: &str) -> Result<bool> {
-        self.inner.forget(key).await
+        let vaulted = self
+            .inner
+            .get(key)
+            .await?
+            .is_some_and(|e| is_vaulted(&e.content));
+        let removed = self.inner.forget(key).await?;
+        if removed && vaulted {
+            self.erase_vaulted(key).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn forget_matching(&self, query: &str, filter: &RecallFilter) -> Result<Vec<MemoryEntry>> {
+        let removed = self.inner.forget_matching(query, filter).await?;
+        // Vaulted content outlives the placeholder stored in its place.
+        for entry in removed.iter().filter(|e| is_vaulted(&e.content)) {
+            self.erase_vaulted(&entry.key).await?;
+        }
+        Ok(removed)
     }
 
     async fn count(&self) -> Result<usize> {
@@ -340,6 +637,55 @@ mod tests {
         assert_eq!(result, Some("Google API Key".to_string()));
     }
 
+    #[test]
+    fn scanner_detects_german_pin() {
+        let scanner = SensitivityScanner::new();
+        assert_eq!(scanner.scan("Meine PIN ist 4711"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("Die Geheimzahl lautet 1234"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("TAN: 123456"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("Kartenprüfnummer 987"), Some("Bank PIN".to_string()));
+    }
+
+    #[test]
+    fn scanner_detects_grouped_digits() {
+        let scanner = SensitivityScanner::new();
+        // STT often transcribes dictated digits in groups
+        assert_eq!(scanner.scan("PIN ist 12 34"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("TAN = 123.456"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("code is 12-34-56"), Some("Bank PIN".to_string()));
+    }
+
+    #[test]
+    fn scanner_ignores_german_prose() {
+        let scanner = SensitivityScanner::new();
+        assert!(scanner.scan("Der Stand 2024 ist gut").is_none());
+        assert!(scanner.scan("Wir treffen uns um 15 Uhr").is_none());
+    }
+
+    #[test]
+    fn scanner_english_only_skips_german_triggers() {
+        let scanner = SensitivityScanner::for_languages(&["en".into()], &[]).unwrap();
+        assert!(scanner.scan("Die Geheimzahl lautet 1234").is_none());
+        assert_eq!(scanner.scan("PIN is 1234"), Some("Bank PIN".to_string()));
+    }
+
+    #[test]
+    fn scanner_extra_keywords_cover_other_languages() {
+        let scanner =
+            SensitivityScanner::for_languages(&[], &["Codice".into(), "clave".into()]).unwrap();
+        assert_eq!(scanner.scan("il codice: 5521"), Some("Bank PIN".to_string()));
+        assert_eq!(scanner.scan("clave 9090"), Some("Bank PIN".to_string()));
+    }
+
+    #[test]
+    fn scanner_rejects_unknown_language() {
+        let err = SensitivityScanner::for_languages(&["xx".into()], &[])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("Unsupported sensitivity language 'xx'"));
+    }
+
     #[test]
     fn scanner_passes_safe_text() {
         let scanner = SensitivityScanner::new();
@@ -348,6 +694,55 @@ mod tests {
         assert!(scanner.scan("Set a reminder for 3pm").is_none());
     }
 
+    #[test]
+    fn scanner_configure_adds_disables_and_allowlists() {
+        let config = SensitivityConfig {
+            patterns: [("Employee ID".to_string(), r"EMP-[0-9]{6}".to_string())].into(),
+            disabled: vec!["credit card".into()],
+            allowlist: vec!["de89 3704 0044 0532 0130 00".into()],
+        };
+        let scanner = SensitivityScanner::new().configure(&config).unwrap();
+
+        assert_eq!(scanner.scan("Badge EMP-123456").as_deref(), Some("Employee ID"));
+        assert!(scanner.scan("Card 4111 1111 1111 1111").is_none());
+        // The allowlisted test IBAN passes, any other one does not.
+        assert!(scanner.scan("Test transfer to DE89 3704 0044 0532 0130 00").is_none());
+        assert_eq!(
+            scanner.scan("Pay GB82 1234 5698 7654 3210 00").as_deref(),
+            Some("IBAN")
+        );
+        let (text, found) = scanner.redact("EMP-123456 paid DE89 3704 0044 0532 0130 00");
+        assert_eq!(text, "[REDACTED:Employee ID] paid DE89 3704 0044 0532 0130 00");
+        assert_eq!(found, vec!["Employee ID".to_string()]);
+    }
+
+    #[test]
+    fn scanner_configure_rejects_bad_config() {
+        let unknown = SensitivityConfig {
+            disabled: vec!["Passport".into()],
+            ..SensitivityConfig::default()
+        };
+        assert!(SensitivityScanner::new().configure(&unknown).is_err());
+
+        let invalid = SensitivityConfig {
+            patterns: [("Broken".to_string(), "EMP-[0-9".to_string())].into(),
+            ..SensitivityConfig::default()
+        };
+        assert!(SensitivityScanner::new().configure(&invalid).is_err());
+    }
+
+    #[test]
+    fn scanner_extract_keeps_context_and_returns_secrets() {
+        let scanner = SensitivityScanner::new();
+        let (text, secrets) = scanner.extract("Call the bank, my PIN is 4711, thanks");
+        assert_eq!(text, "Call the bank, my PIN is [REDACTED:Bank PIN], thanks");
+        assert_eq!(secrets, vec![("Bank PIN".to_string(), "4711".to_string())]);
+
+        let (text, secrets) = scanner.extract("Tell me about quantum physics");
+        assert_eq!(text, "Tell me about quantum physics");
+        assert!(secrets.is_empty());
+    }
+
     // ── SovereignMemory Sigil Tests ──────────────────────────────
 
     #[tokio::test]
@@ -406,6 +801,75 @@ mod tests {
         assert!(stored.content.contains("[VAULT:"));
     }
 
+    #[tokio::test]
+    async fn forget_matching_erases_linked_vault_entries() {
+        let sovereign = make_sovereign();
+        sovereign
+            .store("user_msg_009", "My PIN is 4711", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+        sovereign
+            .store("user_msg_010", "Dinner at eight", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+        assert_eq!(sovereign.vault.list_entries().unwrap().len(), 1);
+
+        let removed = sovereign
+            .forget_matching("user_msg_009", &RecallFilter::default())
+            .await
+            .unwrap();
+
+        assert!(removed.iter().any(|e| e.key == "user_msg_009"));
+        assert!(sovereign.vault.list_entries().unwrap().is_empty());
+        // Only the unrelated message is left; the vault index entry is gone too.
+        assert_eq!(sovereign.inner.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn forget_erases_linked_vault_entry() {
+        let sovereign = make_sovereign();
+        sovereign
+            .store("user_msg_011", "My PIN is 4711", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+        assert_eq!(sovereign.vault.list_entries().unwrap().len(), 1);
+
+        assert!(sovereign.forget("user_msg_011").await.unwrap());
+
+        assert!(sovereign.vault.list_entries().unwrap().is_empty());
+        assert_eq!(sovereign.inner.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn redact_mode_keeps_context_and_vaults_only_secrets() {
+        let sovereign = make_sovereign().with_mode(SovereignMode::Redact);
+        sovereign
+            .store(
+                "user_msg_012",
+                "Booked the flight to Lisbon, my card PIN is 9876",
+                MemoryCategory::Conversation,
+            )
+            .await
+            .unwrap();
+
+        let stored = sovereign.inner.get("user_msg_012").await.unwrap().unwrap();
+        assert_eq!(
+            stored.content,
+            "Booked the flight to Lisbon, my card PIN is [REDACTED:Bank PIN]"
+        );
+        assert!(is_vaulted(&stored.content));
+        let vaulted = sovereign.vault.list_entries().unwrap();
+        assert_eq!(vaulted.len(), 1);
+        assert!(vaulted[0].description.contains("Bank PIN"));
+
+        // Recall still finds the conversation by its context.
+        let results = sovereign.recall("Lisbon", 5).await.unwrap();
+        assert!(results.iter().any(|e| e.key == "user_msg_012"));
+
+        assert!(sovereign.forget("user_msg_012").await.unwrap());
+        assert!(sovereign.vault.list_entries().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn sigil_passes_harmless_text_through() {
         let sovereign = make_sovereign();
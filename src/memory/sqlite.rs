@@ -3,22 +3,34 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::hnsw::VectorIndex;
+use super::ranking::{self, RankingWeights};
+use super::scoped;
+use super::traits::{normalize_tags, Memory, MemoryCategory, MemoryEntry, RecallFilter};
 use super::vector;
+use crate::security::SecretStore;
 use async_trait::async_trait;
-use chrono::Local;
-use rusqlite::{params, Connection};
+use chrono::{Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// Columns read by [`SqliteMemory::row_to_entry`], in order.
+const ENTRY_COLUMNS: &str =
+    "id, key, content, category, created_at, tags, metadata, ref_count, access_count, last_accessed_at";
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
-/// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
+/// - **Vector DB**: embeddings stored as BLOB, HNSW index for cosine search
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
+/// - **Dedup**: repeated conversation memories bump a reference count
+/// - **Ranking**: recall counts accesses and can blend in importance and recency
+/// - **Encryption at rest**: optional ChaCha20-Poly1305 sealing of content
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
 pub struct SqliteMemory {
     conn: Mutex<Connection>,
@@ -27,6 +39,15 @@ pub struct SqliteMemory {
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    /// Opened on the first vector search.
+    ann: Mutex<Option<VectorIndex>>,
+    /// Similarity at which a new conversation memory is squashed into an
+    /// existing one; `None` disables dedup.
+    dedup_similarity: Option<f32>,
+    /// Importance and recency weights for recall; `None` ranks by relevance.
+    ranking: Option<RankingWeights>,
+    /// Seals content before it is written; `None` stores plaintext.
+    cipher: Option<SecretStore>,
 }
 
 impl SqliteMemory {
@@ -78,6 +99,96 @@ impl SqliteMemory {
             vector_weight,
             keyword_weight,
             cache_max,
+            ann: Mutex::new(None),
+            dedup_similarity: None,
+            ranking: None,
+            cipher: None,
+        })
+    }
+
+    /// Squash new conversation memories into an existing one of the same
+    /// scope when the content is equal or at least `similarity` alike.
+    pub fn with_dedup(mut self, similarity: Option<f32>) -> Self {
+        self.dedup_similarity = similarity;
+        self
+    }
+
+    /// Rank recall results by relevance, importance and recency, see
+    /// [`ranking::rerank`].
+    pub fn with_ranking(mut self, weights: Option<RankingWeights>) -> Self {
+        self.ranking = weights;
+        self
+    }
+
+    /// Encrypt content at rest with the workspace secret key. Keys, tags,
+    /// metadata and embeddings stay readable; keyword search then scans the
+    /// decrypted rows, since the FTS index only sees ciphertext.
+    pub fn with_encryption(mut self, cipher: Option<SecretStore>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Encrypt the content of every plaintext row, or with `encrypt = false`
+    /// decrypt every sealed one; returns how many rows changed. Needs a store
+    /// opened [`with_encryption`](Self::with_encryption).
+    pub fn convert_encryption(&self, encrypt: bool) -> anyhow::Result<usize> {
+        let Some(cipher) = &self.cipher else {
+            anyhow::bail!("Memory store was opened without a secret store");
+        };
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let rows: Vec<(String, String)> = {
+            let mut stmt = conn.prepare("SELECT id, content FROM memories")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        for (id, content) in rows {
+            let converted = match (encrypt, SecretStore::is_encrypted(&content)) {
+                _ if content.is_empty() => continue,
+                (true, false) => cipher.encrypt(&content)?,
+                (false, true) => cipher.decrypt(&content)?,
+                _ => continue,
+            };
+            tx.execute(
+                "UPDATE memories SET content = ?2 WHERE id = ?1",
+                params![id, converted],
+            )?;
+            changed += 1;
+        }
+        tx.commit()?;
+        // Drop the pages and WAL frames that still hold the old content.
+        if changed > 0 {
+            conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")?;
+        }
+        Ok(changed)
+    }
+
+    fn seal(&self, content: &str) -> anyhow::Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(content),
+            None => Ok(content.to_string()),
+        }
+    }
+
+    /// Decrypt stored content; plaintext rows pass through, so stores being
+    /// converted stay readable.
+    fn reveal(&self, stored: String) -> rusqlite::Result<String> {
+        if !SecretStore::is_encrypted(&stored) {
+            return Ok(stored);
+        }
+        let revealed = match &self.cipher {
+            Some(cipher) => cipher.decrypt(&stored),
+            None => Err(anyhow::anyhow!(
+                "Memory content is encrypted; set memory.encrypt_at_rest = true"
+            )),
+        };
+        revealed.map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, e.into())
         })
     }
 
@@ -127,14 +238,216 @@ impl SqliteMemory {
             );
             CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
         )?;
+
+        // Tags (JSON array) and metadata (JSON) were added later; upgrade
+        // databases created before.
+        let has_tags = conn
+            .prepare("SELECT 1 FROM pragma_table_info('memories') WHERE name = 'tags'")?
+            .exists([])?;
+        if !has_tags {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+                 ALTER TABLE memories ADD COLUMN metadata TEXT NOT NULL DEFAULT 'null';",
+            )?;
+        }
+        // Likewise the dedup hash and reference count; hygiene hashes rows
+        // stored before.
+        let has_ref_count = conn
+            .prepare("SELECT 1 FROM pragma_table_info('memories') WHERE name = 'ref_count'")?
+            .exists([])?;
+        if !has_ref_count {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN ref_count INTEGER NOT NULL DEFAULT 1;
+                 ALTER TABLE memories ADD COLUMN content_hash TEXT;",
+            )?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_memories_hash ON memories(content_hash);",
+        )?;
+        // And the access statistics used for ranking.
+        let has_access_count = conn
+            .prepare("SELECT 1 FROM pragma_table_info('memories') WHERE name = 'access_count'")?
+            .exists([])?;
+        if !has_access_count {
+            conn.execute_batch(
+                "ALTER TABLE memories ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0;
+                 ALTER TABLE memories ADD COLUMN last_accessed_at TEXT;",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Insert or update `key`. Tags and metadata of an existing entry are
+    /// kept unless `meta` is given.
+    async fn upsert(
+        &self,
+        key: &str,
+        content: &str,
+        category: &MemoryCategory,
+        meta: Option<(&[String], &serde_json::Value)>,
+    ) -> anyhow::Result<()> {
+        // Compute embedding (async, before lock)
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let now = Local::now().to_rfc3339();
+        let cat = Self::category_to_str(category);
+        let new_id = Uuid::new_v4().to_string();
+        let hash = dedup_hash(content);
+
+        if let Some(similarity) = self.dedup_similarity {
+            if *category == MemoryCategory::Conversation {
+                if let Some(id) =
+                    self.find_duplicate(&conn, key, &hash, embedding.as_deref(), similarity)?
+                {
+                    return Self::squash_into(&conn, &id, &now, meta.map(|(tags, _)| tags));
+                }
+            }
+        }
+
+        let stored = self.seal(content)?;
+        let tags =
+            meta.map(|(tags, _)| serde_json::to_string(&normalize_tags(tags)).unwrap_or_default());
+        let metadata = meta.map(|(_, metadata)| metadata.to_string());
+
+        // An update keeps the row's original id.
+        let id: String = conn.query_row(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, tags, metadata, content_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, '[]'), COALESCE(?9, 'null'), ?10)
+             ON CONFLICT(key) DO UPDATE SET
+                content = excluded.content,
+                category = excluded.category,
+                embedding = excluded.embedding,
+                updated_at = excluded.updated_at,
+                tags = COALESCE(?8, tags),
+                metadata = COALESCE(?9, metadata),
+                content_hash = excluded.content_hash
+             RETURNING id",
+            params![new_id, key, stored, cat, embedding_bytes, now, now, tags, metadata, hash],
+            |row| row.get(0),
+        )?;
+
+        self.update_index(|index| match &embedding {
+            Some(embedding) => index.insert(&id, embedding),
+            None => index.remove(&id),
+        });
+        Ok(())
+    }
+
+    /// Id of a conversation memory in the same scope as the new `key` with
+    /// the same normalized content, or an embedding at least `similarity`
+    /// alike. Rewriting an existing key is never a duplicate.
+    fn find_duplicate(
+        &self,
+        conn: &Connection,
+        key: &str,
+        hash: &str,
+        embedding: Option<&[f32]>,
+        similarity: f32,
+    ) -> anyhow::Result<Option<String>> {
+        let exists = conn
+            .prepare("SELECT 1 FROM memories WHERE key = ?1")?
+            .exists(params![key])?;
+        if exists {
+            return Ok(None);
+        }
+
+        let scope = scoped::scope_of(key);
+        let same_scope = |id: &str| -> anyhow::Result<bool> {
+            let other: Option<String> = conn
+                .query_row(
+                    "SELECT key FROM memories WHERE id = ?1 AND category = 'conversation'",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(other.is_some_and(|other| scoped::scope_of(&other) == scope))
+        };
+
+        let mut stmt = conn.prepare("SELECT id FROM memories WHERE content_hash = ?1")?;
+        let exact: Vec<String> = stmt
+            .query_map(params![hash], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for id in exact {
+            if same_scope(&id)? {
+                return Ok(Some(id));
+            }
+        }
+
+        let Some(embedding) = embedding else {
+            return Ok(None);
+        };
+        for (id, sim) in self.vector_search(conn, embedding, 8)? {
+            if sim >= similarity && same_scope(&id)? {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Count one more reference to the memory `id`, adding any new `tags`.
+    fn squash_into(
+        conn: &Connection,
+        id: &str,
+        now: &str,
+        tags: Option<&[String]>,
+    ) -> anyhow::Result<()> {
+        let merged = match tags {
+            Some(tags) if !tags.is_empty() => {
+                let existing: String = conn.query_row(
+                    "SELECT tags FROM memories WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )?;
+                let mut all: Vec<String> = serde_json::from_str(&existing).unwrap_or_default();
+                all.extend_from_slice(tags);
+                Some(serde_json::to_string(&normalize_tags(&all))?)
+            }
+            _ => None,
+        };
+        conn.execute(
+            "UPDATE memories SET ref_count = ref_count + 1, updated_at = ?2,
+                tags = COALESCE(?3, tags)
+             WHERE id = ?1",
+            params![id, now, merged],
+        )?;
         Ok(())
     }
 
+    /// Map a row selected with [`ENTRY_COLUMNS`].
+    fn row_to_entry(
+        &self,
+        row: &rusqlite::Row,
+        score: Option<f64>,
+    ) -> rusqlite::Result<MemoryEntry> {
+        let tags: String = row.get(5)?;
+        let metadata: String = row.get(6)?;
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: self.reveal(row.get(2)?)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: None,
+            score,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+            ref_count: row.get(7)?,
+            access_count: row.get(8)?,
+            last_accessed: row.get(9)?,
+        })
+    }
+
     fn category_to_str(cat: &MemoryCategory) -> String {
         match cat {
             MemoryCategory::Core => "core".into(),
             MemoryCategory::Daily => "daily".into(),
             MemoryCategory::Conversation => "conversation".into(),
+            MemoryCategory::Knowledge => "knowledge".into(),
             MemoryCategory::Custom(name) => name.clone(),
         }
     }
@@ -144,6 +457,7 @@ impl SqliteMemory {
             "core" => MemoryCategory::Core,
             "daily" => MemoryCategory::Daily,
             "conversation" => MemoryCategory::Conversation,
+            "knowledge" => MemoryCategory::Knowledge,
             other => MemoryCategory::Custom(other.to_string()),
         }
     }
@@ -271,133 +585,103 @@ impl SqliteMemory {
         Ok(results)
     }
 
-    /// Vector similarity search: scan embeddings and compute cosine similarity
-    fn vector_search(
+    /// Keyword search over decrypted content for encrypted stores, scored
+    /// by how often the query words occur in key and content.
+    fn scan_keywords(
+        &self,
         conn: &Connection,
-        query_embedding: &[f32],
+        query: &str,
         limit: usize,
     ) -> anyhow::Result<Vec<(String, f32)>> {
-        let mut stmt =
-            conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
 
+        let mut stmt = conn.prepare("SELECT id, key, content FROM memories")?;
         let rows = stmt.query_map([], |row| {
-            let id: String = row.get(0)?;
-            let blob: Vec<u8> = row.get(1)?;
-            Ok((id, blob))
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
         })?;
-
-        let mut scored: Vec<(String, f32)> = Vec::new();
+        let mut results = Vec::new();
         for row in rows {
-            let (id, blob) = row?;
-            let emb = vector::bytes_to_vec(&blob);
-            let sim = vector::cosine_similarity(query_embedding, &emb);
-            if sim > 0.0 {
-                scored.push((id, sim));
+            let (id, key, content) = row?;
+            let text = format!("{key} {}", self.reveal(content)?).to_lowercase();
+            let hits: usize = words.iter().map(|w| text.matches(w.as_str()).count()).sum();
+            if hits > 0 {
+                #[allow(clippy::cast_precision_loss)]
+                results.push((id, hits as f32));
             }
         }
-
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        scored.truncate(limit);
-        Ok(scored)
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        Ok(results)
     }
 
-    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
-    pub async fn reindex(&self) -> anyhow::Result<usize> {
-        // Step 1: Rebuild FTS5
-        {
-            let conn = self
-                .conn
-                .lock()
-                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-
-            conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
-        }
-
-        // Step 2: Re-embed all memories that lack embeddings
-        if self.embedder.dimensions() == 0 {
-            return Ok(0);
+    /// Approximate nearest neighbours from the HNSW index, which is opened
+    /// (and caught up with the table) on first use.
+    fn vector_search(
+        &self,
+        conn: &Connection,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let mut ann = self
+            .ann
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        if ann.is_none() {
+            *ann = Some(VectorIndex::open(
+                &self.db_path.with_extension("hnsw"),
+                &Self::load_embeddings(conn)?,
+            ));
         }
-
-        let entries: Vec<(String, String)> = {
-            let conn = self
-                .conn
-                .lock()
-                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-
-            let mut stmt =
-                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
-            let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?;
-            rows.filter_map(std::result::Result::ok).collect()
+        let Some(index) = ann.as_ref() else {
+            return Ok(Vec::new());
         };
 
-        let mut count = 0;
-        for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
-                let bytes = vector::vec_to_bytes(&emb);
-                let conn = self
-                    .conn
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-                conn.execute(
-                    "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-                    params![bytes, id],
-                )?;
-                count += 1;
-            }
-        }
-
-        Ok(count)
+        let mut scored = index.search(query_embedding, limit);
+        scored.retain(|(_, sim)| *sim > 0.0);
+        Ok(scored)
     }
-}
 
-#[async_trait]
-impl Memory for SqliteMemory {
-    fn name(&self) -> &str {
-        "sqlite"
+    fn load_embeddings(conn: &Connection) -> anyhow::Result<HashMap<String, Vec<f32>>> {
+        let mut stmt =
+            conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| {
+            let id: String = row.get(0)?;
+            let blob: Vec<u8> = row.get(1)?;
+            Ok((id, vector::bytes_to_vec(&blob)))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
     }
 
-    async fn store(
-        &self,
-        key: &str,
-        content: &str,
-        category: MemoryCategory,
-    ) -> anyhow::Result<()> {
-        // Compute embedding (async, before lock)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
-
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-        let now = Local::now().to_rfc3339();
-        let cat = Self::category_to_str(&category);
-        let id = Uuid::new_v4().to_string();
-
-        conn.execute(
-            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-             ON CONFLICT(key) DO UPDATE SET
-                content = excluded.content,
-                category = excluded.category,
-                embedding = excluded.embedding,
-                updated_at = excluded.updated_at",
-            params![id, key, content, cat, embedding_bytes, now, now],
-        )?;
-
-        Ok(())
+    /// Apply a change to the vector index if it is open; a closed index
+    /// catches up when it is opened.
+    fn update_index(&self, change: impl FnOnce(&mut VectorIndex)) {
+        if let Ok(mut ann) = self.ann.lock() {
+            if let Some(index) = ann.as_mut() {
+                change(index);
+            }
+        }
     }
 
-    async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
+    /// Best `limit` matches for `query`, without counting the access.
+    async fn search(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
         if query.trim().is_empty() {
             return Ok(Vec::new());
         }
 
+        // Reranking can lift candidates past the most relevant ones.
+        let pool = if self.ranking.is_some() {
+            limit * 2
+        } else {
+            limit
+        };
+
         // Compute query embedding (async, before lock)
         let query_embedding = self.get_or_compute_embedding(query).await?;
 
@@ -407,11 +691,15 @@ impl Memory for SqliteMemory {
             .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
 
         // FTS5 BM25 keyword search
-        let keyword_results = Self::fts5_search(&conn, query, limit * 2).unwrap_or_default();
+        let keyword_results = if self.cipher.is_some() {
+            self.scan_keywords(&conn, query, pool * 2)?
+        } else {
+            Self::fts5_search(&conn, query, pool * 2).unwrap_or_default()
+        };
 
         // Vector similarity search (if embeddings available)
         let vector_results = if let Some(ref qe) = query_embedding {
-            Self::vector_search(&conn, qe, limit * 2).unwrap_or_default()
+            self.vector_search(&conn, qe, pool * 2).unwrap_or_default()
         } else {
             Vec::new()
         };
@@ -434,33 +722,26 @@ impl Memory for SqliteMemory {
                 &keyword_results,
                 self.vector_weight,
                 self.keyword_weight,
-                limit,
+                pool,
             )
         };
 
         // Fetch full entries for merged results
         let mut results = Vec::new();
         for scored in &merged {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories WHERE id = ?1",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories WHERE id = ?1"
+            ))?;
             if let Ok(entry) = stmt.query_row(params![scored.id], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: None,
-                    score: Some(f64::from(scored.final_score)),
-                })
+                self.row_to_entry(row, Some(f64::from(scored.final_score)))
             }) {
                 results.push(entry);
             }
         }
 
-        // If hybrid returned nothing, fall back to LIKE search
-        if results.is_empty() {
+        // If hybrid returned nothing, fall back to LIKE search (which cannot
+        // see into sealed content)
+        if results.is_empty() && self.cipher.is_none() {
             let keywords: Vec<String> =
                 query.split_whitespace().map(|w| format!("%{w}%")).collect();
             if !keywords.is_empty() {
@@ -473,7 +754,7 @@ impl Memory for SqliteMemory {
                     .collect();
                 let where_clause = conditions.join(" OR ");
                 let sql = format!(
-                    "SELECT id, key, content, category, created_at FROM memories
+                    "SELECT {ENTRY_COLUMNS} FROM memories
                      WHERE {where_clause}
                      ORDER BY updated_at DESC
                      LIMIT ?{}",
@@ -486,19 +767,11 @@ impl Memory for SqliteMemory {
                     param_values.push(Box::new(kw.clone()));
                 }
                 #[allow(clippy::cast_possible_wrap)]
-                param_values.push(Box::new(limit as i64));
+                param_values.push(Box::new(pool as i64));
                 let params_ref: Vec<&dyn rusqlite::types::ToSql> =
                     param_values.iter().map(AsRef::as_ref).collect();
                 let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok(MemoryEntry {
-                        id: row.get(0)?,
-                        key: row.get(1)?,
-                        content: row.get(2)?,
-                        category: Self::str_to_category(&row.get::<_, String>(3)?),
-                        timestamp: row.get(4)?,
-                        session_id: None,
-                        score: Some(1.0),
-                    })
+                    self.row_to_entry(row, Some(1.0))
                 })?;
                 for row in rows {
                     results.push(row?);
@@ -506,36 +779,195 @@ impl Memory for SqliteMemory {
             }
         }
 
+        if let Some(weights) = &self.ranking {
+            ranking::rerank(&mut results, weights, Utc::now());
+        }
         results.truncate(limit);
         Ok(results)
     }
 
+    /// Count one more recall of each of `entries`.
+    fn record_access(&self, entries: &[MemoryEntry]) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let now = Local::now().to_rfc3339();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE memories SET access_count = access_count + 1, last_accessed_at = ?2
+                 WHERE id = ?1",
+            )?;
+            for entry in entries {
+                stmt.execute(params![entry.id, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
+    #[allow(dead_code)]
+    pub async fn reindex(&self) -> anyhow::Result<usize> {
+        // Step 1: Rebuild FTS5
+        {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+
+            conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
+        }
+
+        // Step 2: Re-embed all memories that lack embeddings
+        if self.embedder.dimensions() == 0 {
+            return Ok(0);
+        }
+
+        let entries: Vec<(String, String)> = {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+
+            let mut stmt =
+                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, self.reveal(row.get(1)?)?))
+            })?;
+            rows.filter_map(std::result::Result::ok).collect()
+        };
+
+        let mut count = 0;
+        for (id, content) in &entries {
+            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
+                let bytes = vector::vec_to_bytes(&emb);
+                let conn = self
+                    .conn
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+                conn.execute(
+                    "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                    params![bytes, id],
+                )?;
+                self.update_index(|index| index.insert(id, &emb));
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl Memory for SqliteMemory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+    ) -> anyhow::Result<()> {
+        self.upsert(key, content, &category, None).await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        self.upsert(key, content, &category, Some((tags, metadata)))
+            .await
+    }
+
+    async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
+        let results = self.search(query, limit).await?;
+        self.record_access(&results)?;
+        Ok(results)
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if filter.is_empty() {
+            return self.recall(query, limit).await;
+        }
+        if !query.trim().is_empty() {
+            // Rank everything, keep what passes; only what is returned counts
+            // as accessed.
+            let total = self.count().await?;
+            let results: Vec<MemoryEntry> = self
+                .search(query, total.max(limit))
+                .await?
+                .into_iter()
+                .filter(|entry| filter.matches(entry))
+                .take(limit)
+                .collect();
+            self.record_access(&results)?;
+            return Ok(results);
+        }
+
+        // No query: newest first. Category and key prefix are matched in SQL;
+        // time bounds and tags need parsing and are checked per row.
+        let results = {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE (?1 IS NULL OR category = ?1)
+                   AND (?2 IS NULL OR substr(key, 1, length(?2)) = ?2)
+                 ORDER BY created_at DESC"
+            ))?;
+            let category = filter.category.as_ref().map(Self::category_to_str);
+            let rows = stmt.query_map(params![category, filter.key_prefix], |row| {
+                self.row_to_entry(row, None)
+            })?;
+
+            let mut results = Vec::new();
+            for row in rows {
+                let entry = row?;
+                if filter.matches(&entry) {
+                    results.push(entry);
+                    if results.len() >= limit {
+                        break;
+                    }
+                }
+            }
+            results
+        };
+        self.record_access(&results)?;
+        Ok(results)
+    }
+
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, key, content, category, created_at FROM memories WHERE key = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories WHERE key = ?1"
+        ))?;
 
-        let mut rows = stmt.query_map(params![key], |row| {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: None,
-                score: None,
-            })
-        })?;
+        let mut rows = stmt.query_map(params![key], |row| self.row_to_entry(row, None))?;
 
-        match rows.next() {
-            Some(Ok(entry)) => Ok(Some(entry)),
-            _ => Ok(None),
-        }
+        // Content sealed with an unavailable key is an error, not a miss.
+        Ok(rows.next().transpose()?)
     }
 
     async fn list(&self, category: Option<&MemoryCategory>) -> anyhow::Result<Vec<MemoryEntry>> {
@@ -546,33 +978,24 @@ impl Memory for SqliteMemory {
 
         let mut results = Vec::new();
 
-        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: None,
-                score: None,
-            })
-        };
+        let row_mapper =
+            |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> { self.row_to_entry(row, None) };
 
         if let Some(cat) = category {
             let cat_str = Self::category_to_str(cat);
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories
-                 WHERE category = ?1 ORDER BY updated_at DESC",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE category = ?1 ORDER BY updated_at DESC"
+            ))?;
             let rows = stmt.query_map(params![cat_str], row_mapper)?;
             for row in rows {
                 results.push(row?);
             }
         } else {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories
-                 ORDER BY updated_at DESC",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 ORDER BY updated_at DESC"
+            ))?;
             let rows = stmt.query_map([], row_mapper)?;
             for row in rows {
                 results.push(row?);
@@ -587,8 +1010,19 @@ impl Memory for SqliteMemory {
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-        let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
-        Ok(affected > 0)
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM memories WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(id) = id else {
+            return Ok(false);
+        };
+        conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        self.update_index(|index| index.remove(&id));
+        Ok(true)
     }
 
     async fn count(&self) -> anyhow::Result<usize> {
@@ -609,6 +1043,17 @@ impl Memory for SqliteMemory {
     }
 }
 
+/// Hash of `content` with case and whitespace normalized, so copies of a
+/// message that differ only in those are recognized as duplicates.
+pub(super) fn dedup_hash(content: &str) -> String {
+    let normalized = content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    SqliteMemory::content_hash(&normalized)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1017,6 +1462,116 @@ mod tests {
         assert_eq!(mem.unwrap().name(), "sqlite");
     }
 
+    /// Letter histogram: texts sharing letters are similar.
+    struct LetterEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for LetterEmbedding {
+        fn name(&self) -> &str {
+            "letters"
+        }
+
+        fn dimensions(&self) -> usize {
+            26
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0; 26];
+                    for c in text
+                        .to_ascii_lowercase()
+                        .bytes()
+                        .filter(u8::is_ascii_lowercase)
+                    {
+                        v[usize::from(c - b'a')] += 1.0;
+                    }
+                    v
+                })
+                .collect())
+        }
+    }
+
+    fn letter_sqlite(dir: &Path) -> SqliteMemory {
+        SqliteMemory::with_embedder(dir, Arc::new(LetterEmbedding), 1.0, 0.0, 1000).unwrap()
+    }
+
+    #[tokio::test]
+    async fn vector_index_tracks_store_and_forget() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let mem = letter_sqlite(tmp.path());
+            mem.store("a", "aaaa", MemoryCategory::Core).await.unwrap();
+            mem.store("z", "zzzz", MemoryCategory::Core).await.unwrap();
+
+            // The first vector search opens the index from the table.
+            let results = mem.recall("aaa", 1).await.unwrap();
+            assert_eq!(results[0].key, "a");
+
+            // Later writes go straight into the open index.
+            mem.store("y", "yyyy", MemoryCategory::Core).await.unwrap();
+            assert_eq!(mem.recall("yyy", 1).await.unwrap()[0].key, "y");
+            mem.forget("y").await.unwrap();
+            assert!(mem
+                .recall("yyy", 5)
+                .await
+                .unwrap()
+                .iter()
+                .all(|e| e.key != "y"));
+        }
+
+        let index_path = tmp.path().join("memory").join("brain.hnsw");
+        assert!(index_path.exists());
+
+        // Reopened from the file, and rebuilt when the file is damaged.
+        let mem = letter_sqlite(tmp.path());
+        assert_eq!(mem.recall("zz", 1).await.unwrap()[0].key, "z");
+        drop(mem);
+        std::fs::write(&index_path, b"not an index").unwrap();
+        let mem = letter_sqlite(tmp.path());
+        assert_eq!(mem.recall("zz", 1).await.unwrap()[0].key, "z");
+    }
+
+    #[tokio::test]
+    async fn dedup_squashes_repeated_conversation_memories() {
+        let tmp = TempDir::new().unwrap();
+        let mem = letter_sqlite(tmp.path()).with_dedup(Some(0.95));
+        let conv = || MemoryCategory::Conversation;
+        let text = "Remind me to water the plants";
+        mem.store("m1", text, conv()).await.unwrap();
+        // Equal after normalizing case and whitespace.
+        mem.store("m2", "remind me to  WATER the plants", conv())
+            .await
+            .unwrap();
+        // Same embedding, different text.
+        mem.store_with_metadata(
+            "m3",
+            &format!("{text}!"),
+            conv(),
+            &["home".into()],
+            &serde_json::Value::Null,
+        )
+        .await
+        .unwrap();
+        // Another scope, a non-conversation category and other content stay.
+        mem.store("user:luca:m4", text, conv()).await.unwrap();
+        mem.store("plants", text, MemoryCategory::Core)
+            .await
+            .unwrap();
+        mem.store("m5", "quiz jazz box", conv()).await.unwrap();
+        // Rewriting a key is an update, not a duplicate.
+        mem.store("m5", "quiz jazz box", conv()).await.unwrap();
+
+        assert_eq!(mem.count().await.unwrap(), 4);
+        let kept = mem.get("m1").await.unwrap().unwrap();
+        assert_eq!(kept.ref_count, 3);
+        assert_eq!(kept.tags, vec!["home"]);
+        assert!(mem.get("m2").await.unwrap().is_none());
+        assert_eq!(mem.get("user:luca:m4").await.unwrap().unwrap().ref_count, 1);
+        assert_eq!(mem.get("m5").await.unwrap().unwrap().ref_count, 1);
+    }
+
     // ── Reindex test ─────────────────────────────────────────────
 
     #[tokio::test]
@@ -1073,6 +1628,58 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn recall_counts_accesses() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("drink", "Prefers green tea", MemoryCategory::Core)
+            .await
+            .unwrap();
+        mem.store("food", "Allergic to nuts", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        mem.recall("tea", 5).await.unwrap();
+        mem.recall("tea", 5).await.unwrap();
+
+        let drink = mem.get("drink").await.unwrap().unwrap();
+        assert_eq!(drink.access_count, 2);
+        assert!(drink.last_accessed.is_some());
+        let food = mem.get("food").await.unwrap().unwrap();
+        assert_eq!(food.access_count, 0);
+        assert!(food.last_accessed.is_none());
+    }
+
+    #[tokio::test]
+    async fn ranking_lifts_pinned_entries() {
+        let (_tmp, mem) = temp_sqlite();
+        let mem = mem.with_ranking(Some(RankingWeights {
+            similarity: 1.0,
+            importance: 1.0,
+            recency: 0.0,
+            half_life_days: 30.0,
+        }));
+        mem.store(
+            "chatter",
+            "tea tea tea, always tea",
+            MemoryCategory::Conversation,
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "rule",
+            "No tea after six",
+            MemoryCategory::Conversation,
+            &[ranking::PINNED_TAG.into()],
+            &serde_json::Value::Null,
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("tea", 2).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].key, "rule");
+    }
+
     // ── Edge cases: FTS5 special characters ──────────────────────
 
     #[tokio::test]
@@ -1234,6 +1841,128 @@ mod tests {
         assert!(!results.is_empty());
     }
 
+    #[tokio::test]
+    async fn recall_filtered_by_category_prefix_and_tags() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store(
+            "chat:1",
+            "Talked about Rust #work",
+            MemoryCategory::Conversation,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "chat:2",
+            "Talked about hiking",
+            MemoryCategory::Conversation,
+        )
+        .await
+        .unwrap();
+        mem.store("pref:lang", "Prefers Rust #work", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let conversations = RecallFilter {
+            category: Some(MemoryCategory::Conversation),
+            ..RecallFilter::default()
+        };
+        let results = mem.recall_filtered("", 10, &conversations).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        let tagged = RecallFilter {
+            tags: vec!["#Work".into()],
+            key_prefix: Some("chat:".into()),
+            ..RecallFilter::default()
+        };
+        let results = mem.recall_filtered("Rust", 10, &tagged).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "chat:1");
+    }
+
+    #[tokio::test]
+    async fn recall_filtered_by_time_range() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("recent", "fresh note", MemoryCategory::Daily)
+            .await
+            .unwrap();
+
+        let last_hour = RecallFilter {
+            created_after: Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+            ..RecallFilter::default()
+        };
+        assert_eq!(
+            mem.recall_filtered("", 10, &last_hour).await.unwrap().len(),
+            1
+        );
+
+        let before_yesterday = RecallFilter {
+            created_before: Some(chrono::Utc::now() - chrono::Duration::days(1)),
+            ..RecallFilter::default()
+        };
+        assert!(mem
+            .recall_filtered("note", 10, &before_yesterday)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn tags_and_metadata_persist() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store_with_metadata(
+            "trip",
+            "Alps in June",
+            MemoryCategory::Core,
+            &["#Travel".into(), "travel".into()],
+            &serde_json::json!({ "importance": 0.9 }),
+        )
+        .await
+        .unwrap();
+
+        let entry = mem.get("trip").await.unwrap().unwrap();
+        assert_eq!(entry.tags, vec!["travel"]);
+        assert_eq!(entry.metadata["importance"], 0.9);
+
+        // A plain store updates the content and keeps tags and metadata.
+        mem.store("trip", "Alps in July", MemoryCategory::Core)
+            .await
+            .unwrap();
+        let entry = mem.get("trip").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Alps in July");
+        assert_eq!(entry.tags, vec!["travel"]);
+
+        let tagged = RecallFilter {
+            tags: vec!["travel".into()],
+            ..RecallFilter::default()
+        };
+        assert_eq!(mem.recall_filtered("", 10, &tagged).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn schema_upgrade_adds_tag_columns() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("memory").join("brain.db");
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE memories (
+                    id TEXT PRIMARY KEY, key TEXT NOT NULL UNIQUE, content TEXT NOT NULL,
+                    category TEXT NOT NULL DEFAULT 'core', embedding BLOB,
+                    created_at TEXT NOT NULL, updated_at TEXT NOT NULL
+                );
+                INSERT INTO memories VALUES ('1', 'old', 'from before', 'core', NULL, 'x', 'x');",
+            )
+            .unwrap();
+        }
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let entry = mem.get("old").await.unwrap().unwrap();
+        assert!(entry.tags.is_empty());
+        assert!(entry.metadata.is_null());
+        assert_eq!(entry.ref_count, 1);
+    }
+
     // ── Edge cases: schema idempotency ───────────────────────────
 
     #[tokio::test]
@@ -1389,4 +2118,90 @@ mod tests {
         let all = mem.list(None).await.unwrap();
         assert!(all.is_empty());
     }
+
+    // ── Encryption at rest ───────────────────────────────────────
+
+    fn encrypted_sqlite() -> (TempDir, SqliteMemory) {
+        let tmp = TempDir::new().unwrap();
+        let cipher = SecretStore::new(&tmp.path().join(".mymolt"), true);
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_encryption(Some(cipher));
+        (tmp, mem)
+    }
+
+    fn raw_content(mem: &SqliteMemory, key: &str) -> String {
+        let conn = mem.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT content FROM memories WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn encrypted_content_is_sealed_on_disk() {
+        let (_tmp, mem) = encrypted_sqlite();
+        mem.store("doctor", "Appointment with Dr. Weber", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let raw = raw_content(&mem, "doctor");
+        assert!(SecretStore::is_encrypted(&raw));
+        assert!(!raw.contains("Weber"));
+
+        let entry = mem.get("doctor").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Appointment with Dr. Weber");
+        let results = mem.recall("weber", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "Appointment with Dr. Weber");
+    }
+
+    #[tokio::test]
+    async fn convert_encryption_round_trips_existing_store() {
+        let tmp = TempDir::new().unwrap();
+        let plain = SqliteMemory::new(tmp.path()).unwrap();
+        plain
+            .store("a", "first secret", MemoryCategory::Core)
+            .await
+            .unwrap();
+        plain
+            .store("b", "second secret", MemoryCategory::Daily)
+            .await
+            .unwrap();
+        drop(plain);
+
+        let cipher = SecretStore::new(&tmp.path().join(".mymolt"), true);
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_encryption(Some(cipher));
+        assert_eq!(mem.convert_encryption(true).unwrap(), 2);
+        assert_eq!(mem.convert_encryption(true).unwrap(), 0);
+        assert!(SecretStore::is_encrypted(&raw_content(&mem, "a")));
+        assert_eq!(
+            mem.get("b").await.unwrap().unwrap().content,
+            "second secret"
+        );
+
+        assert_eq!(mem.convert_encryption(false).unwrap(), 2);
+        assert_eq!(raw_content(&mem, "a"), "first secret");
+    }
+
+    #[tokio::test]
+    async fn sealed_content_needs_the_key() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = SecretStore::new(&tmp.path().join(".mymolt"), true);
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_encryption(Some(cipher));
+        mem.store("k", "hidden", MemoryCategory::Core)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let plain = SqliteMemory::new(tmp.path()).unwrap();
+        assert!(plain.get("k").await.is_err());
+        assert!(plain.convert_encryption(false).is_err());
+    }
 }
@@ -3,6 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A single memory entry
@@ -15,6 +16,25 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    /// Lowercase labels without `#`, see [`normalize_tags`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Free-form data for features built on top of memory; `null` when unset
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub metadata: serde_json::Value,
+    /// How many stores were squashed into this entry as duplicates (1 if none)
+    #[serde(default = "default_ref_count")]
+    pub ref_count: u32,
+    /// How often recall returned this entry
+    #[serde(default)]
+    pub access_count: u32,
+    /// When recall last returned this entry (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_accessed: Option<String>,
+}
+
+fn default_ref_count() -> u32 {
+    1
 }
 
 /// Memory categories for organization
@@ -27,6 +47,8 @@ pub enum MemoryCategory {
     Daily,
     /// Conversation context
     Conversation,
+    /// Chunks of documents from the knowledge folder
+    Knowledge,
     /// User-defined custom category
     Custom(String),
 }
@@ -37,11 +59,143 @@ impl std::fmt::Display for MemoryCategory {
             Self::Core => write!(f, "core"),
             Self::Daily => write!(f, "daily"),
             Self::Conversation => write!(f, "conversation"),
+            Self::Knowledge => write!(f, "knowledge"),
             Self::Custom(name) => write!(f, "{name}"),
         }
     }
 }
 
+/// Narrows `Memory::recall_filtered` results. The default filter matches
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecallFilter {
+    pub category: Option<MemoryCategory>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub key_prefix: Option<String>,
+    /// Tags the entry must all carry, either in [`MemoryEntry::tags`] or as
+    /// `#hashtags` in the content (without `#`, case-insensitive)
+    pub tags: Vec<String>,
+}
+
+impl RecallFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether `entry` passes every condition. Entries without a parseable
+    /// timestamp fail time bounds.
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        if self.category.as_ref().is_some_and(|c| c != &entry.category) {
+            return false;
+        }
+        if self
+            .key_prefix
+            .as_deref()
+            .is_some_and(|prefix| !entry.key.starts_with(prefix))
+        {
+            return false;
+        }
+        self.matches_time(&entry.timestamp) && self.matches_tags(entry)
+    }
+
+    pub(crate) fn matches_time(&self, timestamp: &str) -> bool {
+        if self.created_after.is_none() && self.created_before.is_none() {
+            return true;
+        }
+        let Some(created) = parse_timestamp(timestamp) else {
+            return false;
+        };
+        self.created_after.is_none_or(|after| created >= after)
+            && self.created_before.is_none_or(|before| created < before)
+    }
+
+    pub(crate) fn matches_tags(&self, entry: &MemoryEntry) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+        let found = hashtags(&entry.content);
+        normalize_tags(&self.tags)
+            .iter()
+            .all(|tag| entry.tags.contains(tag) || found.contains(tag))
+    }
+}
+
+/// Lowercased `#hashtags` in `content`.
+pub fn hashtags(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')'))
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| {
+            tag.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .to_lowercase()
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Lowercase, strip `#` and whitespace, and drop empty and duplicate tags.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Entry timestamps are RFC 3339, or a bare `YYYY-MM-DD` (start of that day,
+/// UTC) for date-named markdown logs.
+pub(super) fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(timestamp, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|t| t.and_utc())
+        })
+}
+
+/// Generic `recall_filtered`: rank everything (or list newest first for an
+/// empty query) and keep what passes the filter.
+pub async fn recall_filtered_fallback<M: Memory + ?Sized>(
+    memory: &M,
+    query: &str,
+    limit: usize,
+    filter: &RecallFilter,
+) -> anyhow::Result<Vec<MemoryEntry>> {
+    if filter.is_empty() {
+        return memory.recall(query, limit).await;
+    }
+    let candidates = if query.trim().is_empty() {
+        let mut all = memory.list(filter.category.as_ref()).await?;
+        all.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        all
+    } else {
+        let total = memory.count().await?;
+        memory.recall(query, total.max(limit)).await?
+    };
+    Ok(candidates
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .take(limit)
+        .collect())
+}
+
+/// Whether `forget_matching` selects `entry`: `query` occurs in its key or
+/// content (case-insensitive; empty matches all) and `filter` matches.
+pub fn erasure_matches(entry: &MemoryEntry, query: &str, filter: &RecallFilter) -> bool {
+    let query = query.trim().to_lowercase();
+    let text_matches = query.is_empty()
+        || entry.key.to_lowercase().contains(&query)
+        || entry.content.to_lowercase().contains(&query);
+    text_matches && filter.matches(entry)
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -52,9 +206,35 @@ pub trait Memory: Send + Sync {
     async fn store(&self, key: &str, content: &str, category: MemoryCategory)
         -> anyhow::Result<()>;
 
+    /// Store a memory entry with tags and free-form metadata, replacing those
+    /// of an existing entry. Backends that cannot persist them store the
+    /// plain entry.
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let _ = (tags, metadata);
+        self.store(key, content, category).await
+    }
+
     /// Recall memories matching a query (keyword search)
     async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall memories matching a query and `filter`. With an empty query,
+    /// the newest entries passing the filter are returned.
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        recall_filtered_fallback(self, query, limit, filter).await
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
@@ -64,6 +244,23 @@ pub trait Memory: Send + Sync {
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
+    /// Remove every memory selected by [`erasure_matches`] and return the
+    /// removed entries. Unlike `recall`, matching is literal, so exactly what
+    /// was asked for is erased. An empty query and filter erase everything.
+    async fn forget_matching(
+        &self,
+        query: &str,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut removed = Vec::new();
+        for entry in self.list(filter.category.as_ref()).await? {
+            if erasure_matches(&entry, query, filter) && self.forget(&entry.key).await? {
+                removed.push(entry);
+            }
+        }
+        Ok(removed)
+    }
+
     /// Count total memories
     async fn count(&self) -> anyhow::Result<usize>;
 
@@ -80,6 +277,7 @@ mod tests {
         assert_eq!(MemoryCategory::Core.to_string(), "core");
         assert_eq!(MemoryCategory::Daily.to_string(), "daily");
         assert_eq!(MemoryCategory::Conversation.to_string(), "conversation");
+        assert_eq!(MemoryCategory::Knowledge.to_string(), "knowledge");
         assert_eq!(
             MemoryCategory::Custom("project_notes".into()).to_string(),
             "project_notes"
@@ -97,6 +295,78 @@ mod tests {
         assert_eq!(conversation, "\"conversation\"");
     }
 
+    fn entry(key: &str, content: &str, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category: MemoryCategory::Conversation,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+            tags: Vec::new(),
+            metadata: serde_json::Value::Null,
+            ref_count: 1,
+            access_count: 0,
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn recall_filter_checks_every_condition() {
+        let e = entry(
+            "chat:42",
+            "Planned the trip #Travel, #2026.",
+            "2026-03-10T12:00:00+01:00",
+        );
+        assert!(RecallFilter::default().is_empty());
+        assert!(RecallFilter::default().matches(&e));
+
+        let filter = RecallFilter {
+            category: Some(MemoryCategory::Conversation),
+            created_after: Some("2026-03-10T00:00:00Z".parse().unwrap()),
+            created_before: Some("2026-03-11T00:00:00Z".parse().unwrap()),
+            key_prefix: Some("chat:".into()),
+            tags: vec!["travel".into(), "#2026".into()],
+        };
+        assert!(filter.matches(&e));
+
+        let wrong_tag = RecallFilter {
+            tags: vec!["work".into()],
+            ..filter.clone()
+        };
+        assert!(!wrong_tag.matches(&e));
+        let too_late = RecallFilter {
+            created_after: Some("2026-03-10T12:00:00Z".parse().unwrap()),
+            ..filter.clone()
+        };
+        assert!(!too_late.matches(&e));
+        let core = RecallFilter {
+            category: Some(MemoryCategory::Core),
+            ..filter
+        };
+        assert!(!core.matches(&e));
+    }
+
+    #[test]
+    fn time_bounds_accept_bare_dates_and_reject_unparseable() {
+        let filter = RecallFilter {
+            created_after: Some("2026-03-01T00:00:00Z".parse().unwrap()),
+            ..RecallFilter::default()
+        };
+        assert!(filter.matches(&entry("2026-03-02:0", "daily", "2026-03-02")));
+        assert!(!filter.matches(&entry("2026-02-28:0", "daily", "2026-02-28")));
+        assert!(!filter.matches(&entry("MEMORY:0", "core", "MEMORY")));
+    }
+
+    #[test]
+    fn hashtags_are_lowercased_and_trimmed() {
+        assert_eq!(
+            hashtags("Done #Work; see (#rust-lang) #x_y. # alone"),
+            vec!["work", "rust-lang", "x_y"]
+        );
+    }
+
     #[test]
     fn memory_entry_roundtrip_preserves_optional_fields() {
         let entry = MemoryEntry {
@@ -107,6 +377,11 @@ mod tests {
             timestamp: "2026-02-16T00:00:00Z".into(),
             session_id: Some("session-abc".into()),
             score: Some(0.98),
+            tags: vec!["lang".into()],
+            metadata: serde_json::json!({ "importance": 0.8 }),
+            ref_count: 3,
+            access_count: 2,
+            last_accessed: Some("2026-02-17T00:00:00Z".into()),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -118,5 +393,53 @@ mod tests {
         assert_eq!(parsed.category, MemoryCategory::Core);
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
+        assert_eq!(parsed.tags, vec!["lang"]);
+        assert_eq!(parsed.metadata["importance"], 0.8);
+        assert_eq!(parsed.ref_count, 3);
+        assert_eq!(parsed.access_count, 2);
+        assert_eq!(
+            parsed.last_accessed.as_deref(),
+            Some("2026-02-17T00:00:00Z")
+        );
+
+        let legacy: MemoryEntry = serde_json::from_str(
+            r#"{"id":"1","key":"k","content":"c","category":"core","timestamp":"t","session_id":null,"score":null}"#,
+        )
+        .unwrap();
+        assert!(legacy.tags.is_empty());
+        assert!(legacy.metadata.is_null());
+        assert_eq!(legacy.ref_count, 1);
+        assert_eq!(legacy.access_count, 0);
+        assert!(legacy.last_accessed.is_none());
+    }
+
+    #[test]
+    fn tags_match_entry_tags_and_hashtags() {
+        let mut e = entry("k", "Note #Travel", "2026-03-10");
+        e.tags = vec!["pim".into()];
+        let filter = RecallFilter {
+            tags: vec!["#PIM".into(), "travel".into()],
+            ..RecallFilter::default()
+        };
+        assert!(filter.matches(&e));
+        assert_eq!(
+            normalize_tags(&[" #Work ".into(), "work".into(), "#".into()]),
+            vec!["work"]
+        );
+    }
+
+    #[test]
+    fn erasure_matches_text_literally() {
+        let e = entry("whatsapp_+49170_1", "Lunch with Anna", "2026-03-10");
+        let any = RecallFilter::default();
+        assert!(erasure_matches(&e, "anna", &any));
+        assert!(erasure_matches(&e, "+49170", &any));
+        assert!(erasure_matches(&e, " ", &any));
+        assert!(!erasure_matches(&e, "dinner", &any));
+        let core_only = RecallFilter {
+            category: Some(MemoryCategory::Core),
+            ..RecallFilter::default()
+        };
+        assert!(!erasure_matches(&e, "anna", &core_only));
     }
 }
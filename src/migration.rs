@@ -4,6 +4,7 @@
 
 use crate::config::Config;
 use crate::memory::{MarkdownMemory, Memory, MemoryCategory, SqliteMemory};
+use crate::security::{keystore, SecretStore, VaultManager};
 use anyhow::{bail, Context, Result};
 use directories::UserDirs;
 use rusqlite::{Connection, OpenFlags, OptionalExtension};
@@ -32,9 +33,84 @@ pub async fn handle_command(command: crate::MigrateCommands, config: &Config) ->
         crate::MigrateCommands::Openclaw { source, dry_run } => {
             migrate_openclaw_memory(config, source, dry_run).await
         }
+        crate::MigrateCommands::EncryptMemory { decrypt } => encrypt_memory(config, !decrypt),
+        crate::MigrateCommands::VaultKeys { rotate } => vault_keys(config, rotate),
+        crate::MigrateCommands::KeyBackend => migrate_key_backend(config),
     }
 }
 
+/// Move every master key file into `secrets.key_backend`, removing the
+/// copy in the previous backend. Keys already there are left alone.
+fn migrate_key_backend(config: &Config) -> Result<()> {
+    let backend = config.secrets.key_backend;
+    let config_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let key_files = [
+        config_dir.join(".secret_key"),
+        config.workspace_dir.join(".mymolt/.secret_key"),
+        config.workspace_dir.join("hoodik/.secret_key"),
+        config.workspace_dir.join("hoodik/keys/admin.key"),
+    ];
+
+    let mut moved = 0;
+    for path in key_files.iter().filter(|p| p.exists()) {
+        if keystore::migrate(path, backend)
+            .with_context(|| format!("Failed to move key {}", path.display()))?
+        {
+            println!("  🔑 {}", path.display());
+            moved += 1;
+        }
+    }
+    println!(
+        "✅ Moved {moved} master keys to the {} backend",
+        format!("{backend:?}").to_lowercase()
+    );
+    Ok(())
+}
+
+/// Re-wrap vault data keys for the current recipients, optionally under a
+/// new admin keypair. Payloads are not re-encrypted.
+fn vault_keys(config: &Config, rotate: bool) -> Result<()> {
+    let vault =
+        VaultManager::new(&config.workspace_dir).with_key_backend(config.secrets.key_backend);
+    if rotate {
+        let changed = vault.rotate_admin_key()?;
+        println!("✅ Rotated the vault admin key and re-wrapped {changed} entries");
+    } else {
+        let changed = vault.rewrap_all()?;
+        println!("✅ Re-wrapped {changed} vault entries for the current recipients");
+    }
+    Ok(())
+}
+
+/// Convert the `SQLite` memory store in place. No backup is taken: it would
+/// leave a plaintext copy behind, and the conversion runs in one transaction.
+fn encrypt_memory(config: &Config, encrypt: bool) -> Result<()> {
+    if config.memory.backend != "sqlite" {
+        bail!(
+            "Encryption at rest needs the sqlite memory backend (configured: {})",
+            config.memory.backend
+        );
+    }
+
+    let cipher = SecretStore::new(&config.workspace_dir.join(".mymolt"), true);
+    let memory = SqliteMemory::new(&config.workspace_dir)?.with_encryption(Some(cipher));
+    let changed = memory.convert_encryption(encrypt)?;
+
+    let (done, setting) = if encrypt {
+        ("Encrypted", true)
+    } else {
+        ("Decrypted", false)
+    };
+    println!("✅ {done} {changed} memory entries");
+    if config.memory.encrypt_at_rest != setting {
+        println!("  Set memory.encrypt_at_rest = {setting} in config.toml to match.");
+    }
+    Ok(())
+}
+
 async fn migrate_openclaw_memory(
     config: &Config,
     source_workspace: Option<PathBuf>,
@@ -117,7 +193,15 @@ async fn migrate_openclaw_memory(
 
 fn target_memory_backend(config: &Config) -> Result<Box<dyn Memory>> {
     match config.memory.backend.as_str() {
-        "sqlite" => Ok(Box::new(SqliteMemory::new(&config.workspace_dir)?)),
+        "sqlite" => {
+            let cipher = config
+                .memory
+                .encrypt_at_rest
+                .then(|| SecretStore::new(&config.workspace_dir.join(".mymolt"), true));
+            Ok(Box::new(
+                SqliteMemory::new(&config.workspace_dir)?.with_encryption(cipher),
+            ))
+        }
         "markdown" | "none" => Ok(Box::new(MarkdownMemory::new(&config.workspace_dir))),
         other => {
             tracing::warn!(
@@ -314,6 +398,7 @@ fn parse_category(raw: &str) -> MemoryCategory {
         "core" | "" => MemoryCategory::Core,
         "daily" => MemoryCategory::Daily,
         "conversation" => MemoryCategory::Conversation,
+        "knowledge" => MemoryCategory::Knowledge,
         other => MemoryCategory::Custom(other.to_string()),
     }
 }
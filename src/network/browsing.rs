@@ -0,0 +1,353 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Sovereign Browser storage — per-profile history and bookmarks.
+//!
+//! Each person has their own browsing profile: every family member under
+//! their memory scope, and the owner's devices under [`OWNER_PROFILE`]. A
+//! profile is kept in its own file under `<workspace>/data/browse/`,
+//! encrypted at rest with the workspace [`SecretStore`] key. Repeat visits
+//! to the same page update a single history entry instead of appending
+//! duplicates.
+
+use crate::identity::family::member_scope;
+use crate::identity::UserRole;
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Oldest visits beyond this count are dropped from a profile's history.
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Browsing profile of the owner's devices.
+pub const OWNER_PROFILE: &str = "owner";
+
+/// Browsing profile of the family member called `member`, or of the owner.
+pub fn profile_key(member: Option<&str>) -> String {
+    member.map_or_else(|| OWNER_PROFILE.to_string(), member_scope)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub visited_at: String,
+    pub visit_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkEntry {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BrowsingProfile {
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    #[serde(default)]
+    bookmarks: Vec<BookmarkEntry>,
+}
+
+/// Encrypted history and bookmark storage, one profile per person.
+pub struct BrowsingStore {
+    dir: PathBuf,
+    secrets: SecretStore,
+    /// Serializes read-modify-write cycles on the profile files.
+    lock: Mutex<()>,
+}
+
+impl BrowsingStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            dir: workspace_dir.join("data/browse"),
+            secrets: SecretStore::new(workspace_dir, true),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Whether someone with role `viewer` may read and manage the browsing
+    /// data of another person with role `owner`.
+    ///
+    /// Everyone sees their own profile (not checked here); Adults and Root
+    /// additionally see the profiles of less privileged roles (e.g. a
+    /// Child's history).
+    pub fn can_view(viewer: UserRole, owner: UserRole) -> bool {
+        viewer >= UserRole::Adult && viewer > owner
+    }
+
+    // ── History ─────────────────────────────────────────────────────
+
+    /// Record a page visit, merging repeat visits to the same page.
+    pub fn record_visit(&self, profile: &str, url: &str, title: &str) -> Result<HistoryEntry> {
+        let key = normalize_url(url);
+        self.update(profile, |data| {
+            let now = Utc::now().to_rfc3339();
+            let entry = if let Some(pos) = data
+                .history
+                .iter()
+                .position(|e| normalize_url(&e.url) == key)
+            {
+                let mut entry = data.history.remove(pos);
+                entry.visited_at = now;
+                entry.visit_count = entry.visit_count.saturating_add(1);
+                if !title.is_empty() {
+                    entry.title = title.to_string();
+                }
+                entry
+            } else {
+                HistoryEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    url: url.to_string(),
+                    title: title.to_string(),
+                    visited_at: now,
+                    visit_count: 1,
+                }
+            };
+            // Most recent first
+            data.history.insert(0, entry.clone());
+            data.history.truncate(MAX_HISTORY_ENTRIES);
+            entry
+        })
+    }
+
+    /// List history, most recent first, optionally filtered by `query`.
+    pub fn history(&self, profile: &str, query: Option<&str>) -> Result<Vec<HistoryEntry>> {
+        let _guard = self.lock.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let data = self.load(profile)?;
+        Ok(data
+            .history
+            .into_iter()
+            .filter(|e| matches_query(&e.url, &e.title, query))
+            .collect())
+    }
+
+    /// Remove one history entry. Returns `false` if no entry had that id.
+    pub fn delete_history(&self, profile: &str, id: &str) -> Result<bool> {
+        self.update(profile, |data| {
+            let before = data.history.len();
+            data.history.retain(|e| e.id != id);
+            data.history.len() != before
+        })
+    }
+
+    /// Clear a profile's entire history. Returns the number of removed entries.
+    pub fn clear_history(&self, profile: &str) -> Result<usize> {
+        self.update(profile, |data| std::mem::take(&mut data.history).len())
+    }
+
+    // ── Bookmarks ───────────────────────────────────────────────────
+
+    /// Save a bookmark. Bookmarking an already saved page updates its title.
+    pub fn add_bookmark(&self, profile: &str, url: &str, title: &str) -> Result<BookmarkEntry> {
+        let key = normalize_url(url);
+        self.update(profile, |data| {
+            if let Some(existing) = data
+                .bookmarks
+                .iter_mut()
+                .find(|b| normalize_url(&b.url) == key)
+            {
+                if !title.is_empty() {
+                    existing.title = title.to_string();
+                }
+                return existing.clone();
+            }
+            let entry = BookmarkEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                url: url.to_string(),
+                title: title.to_string(),
+                created_at: Utc::now().to_rfc3339(),
+            };
+            data.bookmarks.push(entry.clone());
+            entry
+        })
+    }
+
+    /// List bookmarks in creation order, optionally filtered by `query`.
+    pub fn bookmarks(&self, profile: &str, query: Option<&str>) -> Result<Vec<BookmarkEntry>> {
+        let _guard = self.lock.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let data = self.load(profile)?;
+        Ok(data
+            .bookmarks
+            .into_iter()
+            .filter(|b| matches_query(&b.url, &b.title, query))
+            .collect())
+    }
+
+    /// Remove one bookmark. Returns `false` if no bookmark had that id.
+    pub fn delete_bookmark(&self, profile: &str, id: &str) -> Result<bool> {
+        self.update(profile, |data| {
+            let before = data.bookmarks.len();
+            data.bookmarks.retain(|b| b.id != id);
+            data.bookmarks.len() != before
+        })
+    }
+
+    // ── Persistence ─────────────────────────────────────────────────
+
+    fn profile_path(&self, profile: &str) -> PathBuf {
+        // Profile keys are `owner` or `user:<slug>`; keep file names portable
+        let name = profile.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_");
+        self.dir.join(format!("{name}.enc"))
+    }
+
+    fn load(&self, profile: &str) -> Result<BrowsingProfile> {
+        let path = self.profile_path(profile);
+        if !path.exists() {
+            return Ok(BrowsingProfile::default());
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let json = self
+            .secrets
+            .decrypt(raw.trim())
+            .context("Failed to decrypt browsing data")?;
+        serde_json::from_str(&json).context("Corrupt browsing data")
+    }
+
+    fn save(&self, profile: &str, data: &BrowsingProfile) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string(data)?;
+        let encrypted = self.secrets.encrypt(&json)?;
+        let path = self.profile_path(profile);
+        let tmp = path.with_extension("enc.tmp");
+        fs::write(&tmp, encrypted)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn update<T>(&self, profile: &str, f: impl FnOnce(&mut BrowsingProfile) -> T) -> Result<T> {
+        let _guard = self.lock.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut data = self.load(profile)?;
+        let out = f(&mut data);
+        self.save(profile, &data)?;
+        Ok(out)
+    }
+}
+
+/// Canonical form used to detect repeat visits: no fragment, no trailing slash,
+/// lowercase scheme and host.
+fn normalize_url(url: &str) -> String {
+    let without_fragment = url.split('#').next().unwrap_or(url).trim();
+    let trimmed = without_fragment.trim_end_matches('/');
+    match trimmed.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            format!("{}://{}{}", scheme.to_lowercase(), host.to_lowercase(), path)
+        }
+        None => trimmed.to_string(),
+    }
+}
+
+fn matches_query(url: &str, title: &str, query: Option<&str>) -> bool {
+    match query.map(str::trim).filter(|q| !q.is_empty()) {
+        Some(q) => {
+            let q = q.to_lowercase();
+            url.to_lowercase().contains(&q) || title.to_lowercase().contains(&q)
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_visits_are_deduplicated() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BrowsingStore::new(tmp.path());
+
+        store.record_visit("user:anna", "https://example.com/", "Example").unwrap();
+        store.record_visit("user:anna", "https://other.org", "Other").unwrap();
+        let again = store
+            .record_visit("user:anna", "https://EXAMPLE.com#top", "")
+            .unwrap();
+
+        assert_eq!(again.visit_count, 2);
+        assert_eq!(again.title, "Example");
+
+        let history = store.history("user:anna", None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].url, "https://example.com/");
+    }
+
+    #[test]
+    fn history_search_matches_url_and_title() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BrowsingStore::new(tmp.path());
+        store.record_visit("user:mia", "https://kids.example/dinos", "Dinosaurs").unwrap();
+        store.record_visit("user:mia", "https://maths.example", "Fractions").unwrap();
+
+        assert_eq!(store.history("user:mia", Some("dino")).unwrap().len(), 1);
+        assert_eq!(store.history("user:mia", Some("MATHS")).unwrap().len(), 1);
+        assert_eq!(store.history("user:mia", Some("  ")).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn profiles_are_isolated_and_encrypted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BrowsingStore::new(tmp.path());
+        store.record_visit("user:mia", "https://secret-site.example", "Secret").unwrap();
+        store.record_visit(&profile_key(Some("Anna")), "https://a.example", "A").unwrap();
+
+        assert!(store.history(&profile_key(Some("Ben")), None).unwrap().is_empty());
+        assert!(store.history(&profile_key(None), None).unwrap().is_empty());
+        assert_eq!(store.history("user:anna", None).unwrap().len(), 1);
+
+        let raw = fs::read_to_string(tmp.path().join("data/browse/user_mia.enc")).unwrap();
+        assert!(SecretStore::is_encrypted(&raw));
+        assert!(!raw.contains("secret-site"));
+    }
+
+    #[test]
+    fn delete_and_clear_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BrowsingStore::new(tmp.path());
+        let a = store.record_visit("user:anna", "https://a.example", "A").unwrap();
+        store.record_visit("user:anna", "https://b.example", "B").unwrap();
+
+        assert!(store.delete_history("user:anna", &a.id).unwrap());
+        assert!(!store.delete_history("user:anna", &a.id).unwrap());
+        assert_eq!(store.clear_history("user:anna").unwrap(), 1);
+        assert!(store.history("user:anna", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn bookmarks_upsert_search_and_delete() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = BrowsingStore::new(tmp.path());
+        let first = store.add_bookmark("user:helga", "https://news.example/", "News").unwrap();
+        let second = store
+            .add_bookmark("user:helga", "https://news.example", "Daily News")
+            .unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.title, "Daily News");
+
+        store.add_bookmark("user:helga", "https://weather.example", "Weather").unwrap();
+        assert_eq!(store.bookmarks("user:helga", None).unwrap().len(), 2);
+        assert_eq!(store.bookmarks("user:helga", Some("daily")).unwrap().len(), 1);
+
+        assert!(store.delete_bookmark("user:helga", &first.id).unwrap());
+        assert_eq!(store.bookmarks("user:helga", None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn adults_can_view_child_profiles() {
+        assert!(BrowsingStore::can_view(UserRole::Adult, UserRole::Child));
+        assert!(BrowsingStore::can_view(UserRole::Root, UserRole::Adult));
+        // Siblings and other Adults keep their history to themselves
+        assert!(!BrowsingStore::can_view(UserRole::Child, UserRole::Child));
+        assert!(!BrowsingStore::can_view(UserRole::Adult, UserRole::Adult));
+        assert!(!BrowsingStore::can_view(UserRole::Child, UserRole::Adult));
+        assert!(!BrowsingStore::can_view(UserRole::Senior, UserRole::Child));
+    }
+}
@@ -0,0 +1,405 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Role-based web content filter.
+//!
+//! Decides whether a URL is allowed for a role by its host: a domain matches
+//! itself and all of its subdomains, and top-level-domain entries (`xxx`,
+//! `casino`, …) match every host under them. Substrings elsewhere in the URL
+//! never count, so `alphabet.com` is not "gambling" because it contains `bet`.
+//!
+//! Policies come from `[family.content_filter]`. A blocked user may appeal;
+//! an Adult approves through the confirmation gate, which grants a temporary
+//! per-role override for that domain.
+
+use crate::config::{ContentCategory, ContentFilterConfig, RoleContentPolicy};
+use crate::identity::UserRole;
+use crate::security::confirmation::ConfirmationGate;
+use ammonia::Url;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// How long an appeal waits for an Adult before it is auto-denied.
+pub const APPEAL_TIMEOUT_SECS: u64 = 15 * 60;
+
+const ADULT_DOMAINS: &[&str] = &[
+    "xxx",
+    "porn",
+    "sex",
+    "adult",
+    "pornhub.com",
+    "xvideos.com",
+    "xnxx.com",
+    "xhamster.com",
+    "redtube.com",
+    "youporn.com",
+    "onlyfans.com",
+    "chaturbate.com",
+    "stripchat.com",
+    "tinder.com",
+    "grindr.com",
+];
+
+const GAMBLING_DOMAINS: &[&str] = &[
+    "bet",
+    "casino",
+    "poker",
+    "bet365.com",
+    "pokerstars.com",
+    "williamhill.com",
+    "betfair.com",
+    "tipico.de",
+    "bwin.com",
+    "unibet.com",
+    "draftkings.com",
+    "fanduel.com",
+    "888casino.com",
+    "stake.com",
+];
+
+const VIOLENCE_DOMAINS: &[&str] = &[
+    "bestgore.fun",
+    "theync.com",
+    "kaotic.com",
+    "goregrish.com",
+    "liveleak.com",
+];
+
+const SOCIAL_DOMAINS: &[&str] = &[
+    "facebook.com",
+    "instagram.com",
+    "tiktok.com",
+    "snapchat.com",
+    "x.com",
+    "twitter.com",
+    "reddit.com",
+    "discord.com",
+    "threads.net",
+    "tumblr.com",
+];
+
+fn builtin_domains(category: ContentCategory) -> &'static [&'static str] {
+    match category {
+        ContentCategory::Adult => ADULT_DOMAINS,
+        ContentCategory::Gambling => GAMBLING_DOMAINS,
+        ContentCategory::Violence => VIOLENCE_DOMAINS,
+        ContentCategory::Social => SOCIAL_DOMAINS,
+    }
+}
+
+pub(crate) fn role_key(role: UserRole) -> &'static str {
+    match role {
+        UserRole::Root => "root",
+        UserRole::Adult => "adult",
+        UserRole::Senior => "senior",
+        UserRole::Child => "child",
+    }
+}
+
+/// Lowercased host of a URL (or bare domain), without a leading `www.`.
+pub fn host_of(url: &str) -> Option<String> {
+    let trimmed = url.trim();
+    let parsed = Url::parse(trimmed)
+        .or_else(|_| Url::parse(&format!("https://{trimmed}")))
+        .ok()?;
+    let host = parsed.host_str()?.trim_end_matches('.').to_lowercase();
+    Some(
+        host.strip_prefix("www.")
+            .map(str::to_string)
+            .unwrap_or(host),
+    )
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+pub(crate) fn host_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_start_matches("*.").trim_matches('.');
+    !domain.is_empty()
+        && (host == domain
+            || host
+                .strip_suffix(domain)
+                .is_some_and(|rest| rest.ends_with('.')))
+}
+
+/// Why a URL was blocked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockReason {
+    pub host: String,
+    /// `None` when blocked by an explicit `block_domains` entry.
+    pub category: Option<ContentCategory>,
+}
+
+impl std::fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.category {
+            Some(c) => write!(
+                f,
+                "Content filtered: {} is in the '{}' category",
+                self.host,
+                serde_json::to_value(c)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default()
+            ),
+            None => write!(
+                f,
+                "Content filtered: {} is blocked for your role",
+                self.host
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Override {
+    host: String,
+    role: UserRole,
+    expires_at: DateTime<Utc>,
+}
+
+pub struct ContentFilter {
+    config: ContentFilterConfig,
+    overrides: Mutex<Vec<Override>>,
+}
+
+impl ContentFilter {
+    pub fn new(config: ContentFilterConfig) -> Self {
+        Self {
+            config,
+            overrides: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn policy(&self, role: UserRole) -> Option<&RoleContentPolicy> {
+        self.config.policies.get(role_key(role))
+    }
+
+    /// All domains (built-in plus configured) for a category.
+    fn domains(&self, category: ContentCategory) -> impl Iterator<Item = &str> {
+        builtin_domains(category).iter().copied().chain(
+            self.config
+                .extra_domains
+                .get(&category)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )
+    }
+
+    fn has_override(&self, host: &str, role: UserRole) -> bool {
+        let now = Utc::now();
+        let mut overrides = self
+            .overrides
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        overrides.retain(|o| o.expires_at > now);
+        overrides
+            .iter()
+            .any(|o| o.role == role && host_matches(host, &o.host))
+    }
+
+    /// Check a URL for a role. Returns why it is blocked, or `None` if allowed.
+    pub fn check(&self, url: &str, role: UserRole) -> Option<BlockReason> {
+        let policy = self.policy(role)?;
+        let Some(host) = host_of(url) else {
+            // Unparseable URLs are blocked for filtered roles.
+            return Some(BlockReason {
+                host: url.to_string(),
+                category: None,
+            });
+        };
+
+        if policy.allow_domains.iter().any(|d| host_matches(&host, d))
+            || self.has_override(&host, role)
+        {
+            return None;
+        }
+        if policy.block_domains.iter().any(|d| host_matches(&host, d)) {
+            return Some(BlockReason {
+                host,
+                category: None,
+            });
+        }
+        policy
+            .blocked_categories
+            .iter()
+            .copied()
+            .find(|&c| self.domains(c).any(|d| host_matches(&host, d)))
+            .map(|category| BlockReason {
+                host,
+                category: Some(category),
+            })
+    }
+
+    /// Whether a role has any filtering at all.
+    pub fn is_filtered(&self, role: UserRole) -> bool {
+        self.policy(role)
+            .is_some_and(|p| !p.blocked_categories.is_empty() || !p.block_domains.is_empty())
+    }
+
+    /// Every domain blocked for a role, for browser-extension block rules.
+    pub fn blocked_domains(&self, role: UserRole) -> Vec<String> {
+        let Some(policy) = self.policy(role) else {
+            return Vec::new();
+        };
+        let mut domains: Vec<String> = policy
+            .blocked_categories
+            .iter()
+            .flat_map(|&c| self.domains(c))
+            .chain(policy.block_domains.iter().map(String::as_str))
+            .map(|d| d.trim().trim_start_matches("*.").to_lowercase())
+            .filter(|d| !policy.allow_domains.iter().any(|a| host_matches(d, a)))
+            .collect();
+        domains.sort();
+        domains.dedup();
+        domains
+    }
+
+    /// Temporarily allow `host` for `role`.
+    pub fn grant_override(&self, host: &str, role: UserRole) {
+        let minutes = i64::try_from(self.config.appeal_override_minutes).unwrap_or(60);
+        self.overrides
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(Override {
+                host: host.to_string(),
+                role,
+                expires_at: Utc::now() + Duration::minutes(minutes),
+            });
+    }
+
+    /// Ask an Adult (via the confirmation gate) to unblock `host` for `role`,
+    /// on behalf of `requester` (a member's name, or the role).
+    ///
+    /// Returns immediately; the override is granted in the background once approved.
+    pub fn appeal(
+        self: &Arc<Self>,
+        gate: &Arc<ConfirmationGate>,
+        host: String,
+        role: UserRole,
+        requester: &str,
+        reason: &str,
+    ) {
+        let summary = if reason.trim().is_empty() {
+            format!("{requester} asks to open {host}")
+        } else {
+            format!("{requester} asks to open {host}: \"{}\"", reason.trim())
+        };
+        let filter = Arc::clone(self);
+        let gate = Arc::clone(gate);
+        tokio::spawn(async move {
+            let approved = gate
+                .request_with_timeout(
+                    "content_filter_appeal",
+                    &summary,
+                    "medium",
+                    APPEAL_TIMEOUT_SECS,
+                )
+                .await;
+            if approved {
+                tracing::info!("Content filter appeal approved: {host} for {role:?}");
+                filter.grant_override(&host, role);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> ContentFilter {
+        let mut config = ContentFilterConfig::default();
+        let child = config.policies.get_mut("child").unwrap();
+        child.allow_domains.push("kids.youtube.com".into());
+        child.block_domains.push("youtube.com".into());
+        config
+            .extra_domains
+            .insert(ContentCategory::Gambling, vec!["lotto.example".into()]);
+        ContentFilter::new(config)
+    }
+
+    #[test]
+    fn matches_domains_not_substrings() {
+        let f = filter();
+        assert!(f
+            .check("https://www.pokerstars.com/play", UserRole::Child)
+            .is_some());
+        assert!(f.check("https://m.facebook.com", UserRole::Child).is_some());
+        assert!(f.check("https://win.casino/", UserRole::Child).is_some());
+        // The old substring list blocked all of these.
+        assert!(f.check("https://alphabet.com", UserRole::Child).is_none());
+        assert!(f
+            .check("https://en.wikipedia.org/wiki/Adult", UserRole::Child)
+            .is_none());
+        assert!(f
+            .check("https://notfacebook.com", UserRole::Child)
+            .is_none());
+    }
+
+    #[test]
+    fn policies_apply_per_role() {
+        let f = filter();
+        let reason = f.check("https://lotto.example", UserRole::Child).unwrap();
+        assert_eq!(reason.category, Some(ContentCategory::Gambling));
+        assert!(f.check("https://lotto.example", UserRole::Adult).is_none());
+        assert!(f.is_filtered(UserRole::Child));
+        assert!(!f.is_filtered(UserRole::Senior));
+    }
+
+    #[test]
+    fn allow_list_beats_block_list() {
+        let f = filter();
+        assert!(f
+            .check("https://kids.youtube.com/watch", UserRole::Child)
+            .is_none());
+        let reason = f
+            .check("https://youtube.com/watch", UserRole::Child)
+            .unwrap();
+        assert_eq!(reason.category, None);
+        assert!(!f
+            .blocked_domains(UserRole::Child)
+            .contains(&"kids.youtube.com".to_string()));
+        assert!(f
+            .blocked_domains(UserRole::Child)
+            .contains(&"youtube.com".to_string()));
+    }
+
+    #[test]
+    fn override_unblocks_for_that_role_only() {
+        let f = filter();
+        f.grant_override("reddit.com", UserRole::Child);
+        assert!(f
+            .check("https://old.reddit.com/r/rust", UserRole::Child)
+            .is_none());
+        assert!(f.check("https://tiktok.com", UserRole::Child).is_some());
+    }
+
+    #[tokio::test]
+    async fn approved_appeal_grants_override() {
+        let f = Arc::new(filter());
+        let gate = ConfirmationGate::new(5);
+        let mut rx = gate.subscribe();
+
+        f.appeal(
+            &gate,
+            "reddit.com".into(),
+            UserRole::Child,
+            "Luca",
+            "school project",
+        );
+        let request = rx.recv().await.unwrap();
+        assert!(request.description.contains("Luca asks to open reddit.com"));
+        assert!(request.description.contains("school project"));
+        assert!(gate.resolve(&request.id, true).await);
+
+        for _ in 0..50 {
+            if f.check("https://reddit.com", UserRole::Child).is_none() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("override was not granted");
+    }
+}
@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Page digests — map-reduce summarization and chunked translation.
+//!
+//! Long pages are split with [`crate::memory::chunker`] so each provider call
+//! stays within a comfortable context size. Summaries are produced per chunk
+//! (map) and then merged into bullets plus key entities (reduce); translations
+//! are translated chunk by chunk and reassembled in order.
+
+use crate::memory::chunker::chunk_markdown;
+use crate::providers::Provider;
+use anyhow::Result;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+/// Approximate tokens per chunk sent to the provider.
+const CHUNK_TOKENS: usize = 1500;
+
+/// Pages beyond this many chunks are cut off (≈ 72k tokens of text) and
+/// reported as `truncated`.
+const MAX_CHUNKS: usize = 48;
+
+/// Provider calls in flight per request.
+const CONCURRENCY: usize = 4;
+
+/// Average adult silent reading speed.
+const WORDS_PER_MINUTE: usize = 230;
+
+const MAP_PROMPT: &str = "You condense one section of a web page. Reply with 3-6 terse \
+    factual notes, one per line starting with \"- \". Keep names, numbers and dates exact. \
+    Ignore navigation, ads and cookie banners.";
+
+const REDUCE_PROMPT: &str = "You merge notes about a web page into a final summary. Reply \
+    with JSON only, no prose or code fences: {\"bullets\": [\"...\"], \"entities\": [\"...\"]}. \
+    Give 3-7 bullets, most important first. Entities are the key people, organisations, \
+    places, products or dates, at most 10.";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageSummary {
+    pub bullets: Vec<String>,
+    pub entities: Vec<String>,
+    pub reading_time_minutes: u32,
+    /// Number of chunks the page was split into.
+    pub chunks: usize,
+    /// The page was longer than [`MAX_CHUNKS`] chunks; only its beginning
+    /// was summarized.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageTranslation {
+    pub text: String,
+    pub language: String,
+    pub chunks: usize,
+    /// The page was longer than [`MAX_CHUNKS`] chunks; only its beginning
+    /// was translated.
+    pub truncated: bool,
+}
+
+/// Estimated reading time, rounded up, at least one minute for non-empty text.
+pub fn reading_time_minutes(text: &str) -> u32 {
+    let words = text.split_whitespace().count();
+    if words == 0 {
+        return 0;
+    }
+    u32::try_from(words.div_ceil(WORDS_PER_MINUTE)).unwrap_or(u32::MAX)
+}
+
+/// Chunks of `text`, at most [`MAX_CHUNKS`], and whether any were dropped.
+fn split(text: &str) -> (Vec<String>, bool) {
+    let mut chunks = chunk_markdown(text, CHUNK_TOKENS);
+    let truncated = chunks.len() > MAX_CHUNKS;
+    chunks.truncate(MAX_CHUNKS);
+    (chunks.into_iter().map(|c| c.content).collect(), truncated)
+}
+
+/// Parse the reducer's reply, tolerating code fences or a plain bullet list.
+fn parse_summary(reply: &str) -> (Vec<String>, Vec<String>) {
+    #[derive(Deserialize)]
+    struct Reduced {
+        #[serde(default)]
+        bullets: Vec<String>,
+        #[serde(default)]
+        entities: Vec<String>,
+    }
+
+    if let (Some(start), Some(end)) = (reply.find('{'), reply.rfind('}')) {
+        if start < end {
+            if let Ok(r) = serde_json::from_str::<Reduced>(&reply[start..=end]) {
+                return (r.bullets, r.entities);
+            }
+        }
+    }
+
+    let bullets = reply
+        .lines()
+        .map(str::trim)
+        .filter_map(|l| l.strip_prefix("- ").or_else(|| l.strip_prefix("* ")))
+        .map(str::to_string)
+        .collect();
+    (bullets, Vec::new())
+}
+
+async fn ask(
+    provider: &dyn Provider,
+    system: &str,
+    message: &str,
+    model: &str,
+    temperature: f64,
+) -> Result<String> {
+    let response = provider
+        .chat_with_system(Some(system), message, model, temperature)
+        .await?;
+    Ok(response.text.unwrap_or_default())
+}
+
+/// Summarize page text. `instruction` tailors tone (e.g. for a child profile).
+pub async fn summarize(
+    provider: &dyn Provider,
+    model: &str,
+    temperature: f64,
+    text: &str,
+    instruction: &str,
+) -> Result<PageSummary> {
+    let (chunks, truncated) = split(text);
+    anyhow::ensure!(!chunks.is_empty(), "Page has no text to summarize");
+
+    // A single chunk goes straight to the reducer.
+    let notes = if chunks.len() == 1 {
+        chunks[0].clone()
+    } else {
+        stream::iter(chunks.clone())
+            .map(|chunk| async move { ask(provider, MAP_PROMPT, &chunk, model, temperature).await })
+            .buffered(CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?
+            .join("\n")
+    };
+
+    let system = format!("{REDUCE_PROMPT}\n\nStyle: {instruction}");
+    let reply = ask(provider, &system, &notes, model, temperature).await?;
+    let (bullets, entities) = parse_summary(&reply);
+
+    Ok(PageSummary {
+        bullets,
+        entities,
+        reading_time_minutes: reading_time_minutes(text),
+        chunks: chunks.len(),
+        truncated,
+    })
+}
+
+/// Translate page text into `language`, chunk by chunk, preserving order.
+pub async fn translate(
+    provider: &dyn Provider,
+    model: &str,
+    temperature: f64,
+    text: &str,
+    language: &str,
+) -> Result<PageTranslation> {
+    let (chunks, truncated) = split(text);
+    anyhow::ensure!(!chunks.is_empty(), "Page has no text to translate");
+
+    let system = format!(
+        "Translate the user's text into {language}. Reply with the translation only. \
+         Preserve paragraphs, headings, names and numbers."
+    );
+    let system = system.as_str();
+    let parts = stream::iter(chunks.clone())
+        .map(|chunk| async move { ask(provider, system, &chunk, model, temperature).await })
+        .buffered(CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(PageTranslation {
+        text: parts.join("\n\n"),
+        language: language.to_string(),
+        chunks: chunks.len(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatResponse;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Map calls echo a note; the reduce call returns fenced JSON.
+    #[derive(Default)]
+    struct ScriptedProvider {
+        map_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            let system = system_prompt.unwrap_or_default();
+            if system.starts_with("Translate") {
+                return Ok(ChatResponse::with_text(message.to_uppercase()));
+            }
+            if system == MAP_PROMPT {
+                self.map_calls.fetch_add(1, Ordering::SeqCst);
+                return Ok(ChatResponse::with_text("- note"));
+            }
+            Ok(ChatResponse::with_text(
+                "```json\n{\"bullets\":[\"First\",\"Second\"],\"entities\":[\"Berlin\"]}\n```",
+            ))
+        }
+    }
+
+    fn long_page() -> String {
+        (0..40)
+            .map(|i| format!("## Section {i}\n{}\n", "word ".repeat(400)))
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    #[test]
+    fn reading_time_rounds_up() {
+        assert_eq!(reading_time_minutes(""), 0);
+        assert_eq!(reading_time_minutes("one two"), 1);
+        assert_eq!(reading_time_minutes(&"w ".repeat(461)), 3);
+    }
+
+    #[test]
+    fn parse_summary_falls_back_to_bullet_lines() {
+        let (bullets, entities) = parse_summary("Summary:\n- alpha\n* beta\nnoise");
+        assert_eq!(bullets, vec!["alpha", "beta"]);
+        assert!(entities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn long_page_is_map_reduced() {
+        let provider = ScriptedProvider::default();
+        let page = long_page();
+        let summary = summarize(&provider, "m", 0.3, &page, "neutral")
+            .await
+            .unwrap();
+
+        assert!(summary.chunks > 1);
+        assert_eq!(provider.map_calls.load(Ordering::SeqCst), summary.chunks);
+        assert_eq!(summary.bullets, vec!["First", "Second"]);
+        assert_eq!(summary.entities, vec!["Berlin"]);
+        assert_eq!(summary.reading_time_minutes, reading_time_minutes(&page));
+        assert!(!summary.truncated);
+    }
+
+    #[tokio::test]
+    async fn short_page_skips_map_step() {
+        let provider = ScriptedProvider::default();
+        let summary = summarize(&provider, "m", 0.3, "A short page.", "neutral")
+            .await
+            .unwrap();
+        assert_eq!(summary.chunks, 1);
+        assert_eq!(provider.map_calls.load(Ordering::SeqCst), 0);
+        assert!(summarize(&provider, "m", 0.3, "   ", "neutral")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn translation_preserves_chunk_order() {
+        let provider = ScriptedProvider::default();
+        let page = long_page();
+        let out = translate(&provider, "m", 0.3, &page, "German")
+            .await
+            .unwrap();
+        let first = out.text.find("SECTION 0").unwrap();
+        let last = out.text.find("SECTION 39").unwrap();
+        assert!(first < last);
+        assert_eq!(out.language, "German");
+        assert!(!out.truncated);
+    }
+
+    #[tokio::test]
+    async fn overlong_page_is_reported_as_truncated() {
+        let provider = ScriptedProvider::default();
+        let page = long_page().repeat(3);
+        let summary = summarize(&provider, "m", 0.3, &page, "neutral")
+            .await
+            .unwrap();
+        assert_eq!(summary.chunks, MAX_CHUNKS);
+        assert!(summary.truncated);
+
+        let out = translate(&provider, "m", 0.3, &page, "German")
+            .await
+            .unwrap();
+        assert!(out.truncated);
+    }
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Egress control (`[security.egress]`).
+//!
+//! Tools and the browser proxy build their HTTP clients through
+//! [`client_builder`], which routes them through the configured proxy and
+//! re-checks every redirect hop, and call [`check`] before sending. Both go
+//! through the process-wide [`EgressGuard`] installed at startup, so the
+//! global allow/deny lists, per-role restrictions and host auditing apply
+//! the same way everywhere.
+
+use super::content_filter::{host_matches, role_key};
+use crate::config::{EgressConfig, EgressRoleConfig};
+use crate::identity::UserRole;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use serde::Serialize;
+use std::fmt;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// Redirect hops followed before a request fails.
+const MAX_REDIRECTS: usize = 10;
+
+static GUARD: OnceLock<RwLock<Arc<EgressGuard>>> = OnceLock::new();
+
+fn slot() -> &'static RwLock<Arc<EgressGuard>> {
+    GUARD.get_or_init(|| RwLock::new(Arc::new(EgressGuard::default())))
+}
+
+/// Make `guard` the one every later request is checked against.
+pub fn install(guard: EgressGuard) {
+    *slot().write().unwrap_or_else(PoisonError::into_inner) = Arc::new(guard);
+}
+
+/// Build a guard from `config` and install it. An invalid proxy is logged
+/// and requests go out directly.
+pub fn install_from_config(config: &EgressConfig, audit: Option<Arc<AuditLogger>>) {
+    let guard = EgressGuard::from_config(config, audit.clone()).unwrap_or_else(|e| {
+        tracing::warn!("Egress proxy disabled: {e}");
+        EgressGuard::from_config(
+            &EgressConfig {
+                proxy: None,
+                ..config.clone()
+            },
+            audit,
+        )
+        .expect("egress config without proxy is valid")
+    });
+    install(guard);
+}
+
+/// The installed guard; allows everything until one is installed.
+pub fn guard() -> Arc<EgressGuard> {
+    Arc::clone(&slot().read().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Check a request to `url` made on behalf of `role` (if known) against
+/// the installed guard.
+pub fn check(url: &str, role: Option<UserRole>) -> Result<(), EgressDenial> {
+    guard().check(url, role)
+}
+
+/// Client builder using the installed guard's proxy and redirect checks.
+pub fn client_builder(role: Option<UserRole>) -> reqwest::ClientBuilder {
+    guard().client_builder(role)
+}
+
+/// Why an outbound request was refused
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EgressDenial {
+    pub url: String,
+    /// Host of `url`, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for EgressDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Egress to {} denied: {}", self.url, self.reason)
+    }
+}
+
+impl std::error::Error for EgressDenial {}
+
+/// Global and per-role domain policy for outbound requests.
+#[derive(Default)]
+pub struct EgressGuard {
+    config: EgressConfig,
+    proxy: Option<reqwest::Proxy>,
+    audit: Option<Arc<AuditLogger>>,
+}
+
+impl EgressGuard {
+    /// Guard for `config`; fails when the proxy URL is invalid.
+    pub fn from_config(
+        config: &EgressConfig,
+        audit: Option<Arc<AuditLogger>>,
+    ) -> anyhow::Result<Self> {
+        let proxy = config
+            .proxy
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(reqwest::Proxy::all)
+            .transpose()?;
+        Ok(Self {
+            config: config.clone(),
+            proxy,
+            audit,
+        })
+    }
+
+    /// Check a request to `url`, audit its host and return why it is
+    /// refused, if it is.
+    pub fn check(&self, url: &str, role: Option<UserRole>) -> Result<(), EgressDenial> {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.trim_end_matches('.').to_lowercase()));
+        let result = match &host {
+            Some(host) => self.check_host(host, role),
+            None => Err("URL has no host".to_string()),
+        };
+        self.record(host.as_deref().unwrap_or(url), role, result.as_ref().err());
+        result.map_err(|reason| EgressDenial {
+            url: url.to_string(),
+            host,
+            reason,
+        })
+    }
+
+    fn check_host(&self, host: &str, role: Option<UserRole>) -> Result<(), String> {
+        let role_rules: Option<&EgressRoleConfig> =
+            role.and_then(|r| self.config.roles.get(role_key(r)));
+        let role_name = role.map_or("", role_key);
+
+        if let Some(denied) = find_match(host, &self.config.denied_domains) {
+            return Err(format!("'{denied}' is in egress.denied_domains"));
+        }
+        if let Some(denied) = role_rules.and_then(|r| find_match(host, &r.denied_domains)) {
+            return Err(format!("'{denied}' is denied for the {role_name} role"));
+        }
+        if !self.config.allowed_domains.is_empty()
+            && find_match(host, &self.config.allowed_domains).is_none()
+        {
+            return Err(format!("'{host}' is not in egress.allowed_domains"));
+        }
+        if let Some(rules) = role_rules.filter(|r| !r.allowed_domains.is_empty()) {
+            if find_match(host, &rules.allowed_domains).is_none() {
+                return Err(format!("'{host}' is not allowed for the {role_name} role"));
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&self, host: &str, role: Option<UserRole>, denied: Option<&String>) {
+        if let Some(reason) = denied {
+            tracing::warn!(host, ?role, "Egress denied: {reason}");
+        }
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        if denied.is_none() && !self.config.audit_hosts {
+            return;
+        }
+        let event_type = if denied.is_some() {
+            AuditEventType::PolicyViolation
+        } else {
+            AuditEventType::NetworkEgress
+        };
+        let action = match role {
+            Some(role) => format!("egress:{}:{host}", role_key(role)),
+            None => format!("egress:{host}"),
+        };
+        let _ = audit.log(
+            &AuditEvent::new(event_type)
+                .with_actor("egress".to_string(), None, None)
+                .with_action(action, "low".to_string(), false, denied.is_none()),
+        );
+    }
+
+    /// Client builder routed through the proxy, following at most
+    /// [`MAX_REDIRECTS`] hops and only to hosts this guard allows.
+    pub fn client_builder(self: &Arc<Self>, role: Option<UserRole>) -> reqwest::ClientBuilder {
+        let guard = Arc::clone(self);
+        let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::custom(
+            move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                match guard.check(attempt.url().as_str(), role) {
+                    Ok(()) => attempt.follow(),
+                    Err(denial) => attempt.error(denial),
+                }
+            },
+        ));
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        builder
+    }
+}
+
+/// First entry of `domains` that `host` falls under.
+fn find_match<'a>(host: &str, domains: &'a [String]) -> Option<&'a str> {
+    domains
+        .iter()
+        .map(String::as_str)
+        .find(|d| host_matches(host, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn guard(config: EgressConfig) -> EgressGuard {
+        EgressGuard::from_config(&config, None).unwrap()
+    }
+
+    #[test]
+    fn default_guard_allows_everything() {
+        let g = EgressGuard::default();
+        assert!(g.check("https://example.com/a", None).is_ok());
+        assert!(g
+            .check("http://10.0.0.1:8080/", Some(UserRole::Child))
+            .is_ok());
+        assert!(g.check("not a url", None).is_err());
+    }
+
+    #[test]
+    fn denied_domains_win_over_allowed() {
+        let g = guard(EgressConfig {
+            allowed_domains: vec!["example.com".into()],
+            denied_domains: vec!["secret.example.com".into()],
+            ..EgressConfig::default()
+        });
+        assert!(g.check("https://api.example.com/v1", None).is_ok());
+        let denial = g.check("https://secret.example.com/", None).unwrap_err();
+        assert_eq!(denial.host.as_deref(), Some("secret.example.com"));
+        assert!(denial.reason.contains("denied_domains"));
+        assert!(g.check("https://notexample.com/", None).is_err());
+    }
+
+    #[test]
+    fn role_rules_narrow_the_global_lists() {
+        let g = guard(EgressConfig {
+            denied_domains: vec!["pastebin.com".into()],
+            roles: BTreeMap::from([(
+                "child".to_string(),
+                EgressRoleConfig {
+                    allowed_domains: vec!["wikipedia.org".into()],
+                    ..EgressRoleConfig::default()
+                },
+            )]),
+            ..EgressConfig::default()
+        });
+        let wiki = "https://de.wikipedia.org/wiki/Rust";
+        let other = "https://example.com/";
+        assert!(g.check(wiki, Some(UserRole::Child)).is_ok());
+        assert!(g.check(other, Some(UserRole::Child)).is_err());
+        assert!(g.check(other, Some(UserRole::Adult)).is_ok());
+        assert!(g.check(other, None).is_ok());
+        assert!(g
+            .check("https://pastebin.com/raw/x", Some(UserRole::Root))
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_proxy_is_rejected() {
+        let bad = EgressConfig {
+            proxy: Some("::not a proxy::".into()),
+            ..EgressConfig::default()
+        };
+        assert!(EgressGuard::from_config(&bad, None).is_err());
+        let good = EgressConfig {
+            proxy: Some("http://10.100.0.1:3128".into()),
+            ..EgressConfig::default()
+        };
+        let g = Arc::new(guard(good));
+        assert!(g.client_builder(None).build().is_ok());
+    }
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Bounded page fetching for the browse proxy.
+//!
+//! Bodies are streamed and cut off at a configured size so a huge page cannot
+//! exhaust memory on small devices. Binary content types are rejected before
+//! any of the body is read, and the text is decoded using the charset from the
+//! `Content-Type` header, a `<meta>` tag or a BOM, falling back to UTF-8 (or
+//! windows-1252 when the bytes are not valid UTF-8).
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use futures::StreamExt;
+
+/// How far into the body to look for a `<meta charset>` declaration.
+const META_SNIFF_BYTES: usize = 1024;
+
+#[derive(Debug)]
+pub struct FetchedPage {
+    pub body: String,
+    /// Name of the encoding the body was decoded from.
+    pub charset: &'static str,
+    /// The body was cut at the size limit.
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request failed or the remote returned a non-success status.
+    Remote(String),
+    /// Not an HTML or text document.
+    UnsupportedType(String),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Remote(e) => write!(f, "Failed to fetch: {e}"),
+            Self::UnsupportedType(t) => write!(f, "Unsupported content type '{t}'"),
+        }
+    }
+}
+
+/// Whether a `Content-Type` is something the proxy can render as a page.
+fn is_textual(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/xhtml+xml"
+                | "application/xml"
+                | "application/rss+xml"
+                | "application/atom+xml"
+        )
+}
+
+fn mime_of(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// Value of a `charset=` parameter in a header or `<meta>` content.
+fn charset_param(s: &str) -> Option<&str> {
+    let lower = s.to_ascii_lowercase();
+    let start = lower.find("charset=")? + "charset=".len();
+    let rest = &s[start..];
+    let rest = rest.trim_start_matches(['"', '\'']);
+    let end = rest
+        .find(|c: char| c == '"' || c == '\'' || c == ';' || c == '>' || c.is_whitespace())
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|c| !c.is_empty())
+}
+
+/// Charset declared by a `<meta charset>` or `<meta http-equiv>` tag.
+fn sniff_meta_charset(head: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&head[..head.len().min(META_SNIFF_BYTES)]);
+    let lower = head.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find("<meta") {
+        let start = from + pos;
+        let end = lower[start..].find('>').map_or(lower.len(), |e| start + e);
+        if let Some(label) = charset_param(&head[start..end]) {
+            if let Some(encoding) = Encoding::for_label(label.as_bytes()) {
+                return Some(encoding);
+            }
+        }
+        from = end;
+    }
+    None
+}
+
+/// Decode a body: BOM, then declared charset, then `<meta>`, then UTF-8 or
+/// windows-1252 depending on whether the bytes are valid UTF-8.
+pub fn decode(bytes: &[u8], content_type: Option<&str>) -> (String, &'static str) {
+    let declared = content_type
+        .and_then(charset_param)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .or_else(|| sniff_meta_charset(bytes));
+    let encoding = match declared {
+        Some(e) => e,
+        // An incomplete sequence at the very end is a cut at the size limit.
+        None => match std::str::from_utf8(bytes) {
+            Ok(_) => UTF_8,
+            Err(e) if e.error_len().is_none() => UTF_8,
+            Err(_) => WINDOWS_1252,
+        },
+    };
+    let (text, used, _) = encoding.decode(bytes);
+    (text.into_owned(), used.name())
+}
+
+/// Fetch a page, streaming at most `max_bytes` of the body.
+///
+/// Longer bodies are cut off and the connection dropped, so memory use is
+/// bounded regardless of what the server declares.
+pub async fn fetch_page(
+    client: &reqwest::Client,
+    url: &str,
+    max_bytes: usize,
+) -> Result<FetchedPage, FetchError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| FetchError::Remote(e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::Remote(format!("remote returned {status}")));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if let Some(ct) = &content_type {
+        let mime = mime_of(ct);
+        if !mime.is_empty() && !is_textual(&mime) {
+            return Err(FetchError::UnsupportedType(mime));
+        }
+    }
+    let mut body = Vec::new();
+    let mut truncated = false;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| FetchError::Remote(e.to_string()))?;
+        let room = max_bytes - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    // Without a declared type, refuse anything that looks binary.
+    if content_type.is_none() && body[..body.len().min(META_SNIFF_BYTES)].contains(&0) {
+        return Err(FetchError::UnsupportedType(
+            "application/octet-stream".into(),
+        ));
+    }
+
+    let (body, charset) = decode(&body, content_type.as_deref());
+    Ok(FetchedPage {
+        body,
+        charset,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gates_content_types() {
+        assert!(is_textual(&mime_of("text/html; charset=utf-8")));
+        assert!(is_textual(&mime_of("application/xhtml+xml")));
+        assert!(!is_textual(&mime_of("application/pdf")));
+        assert!(!is_textual(&mime_of("image/png")));
+        assert!(!is_textual(&mime_of("application/octet-stream")));
+    }
+
+    #[test]
+    fn parses_charset_params() {
+        assert_eq!(
+            charset_param("text/html; charset=ISO-8859-1"),
+            Some("ISO-8859-1")
+        );
+        assert_eq!(charset_param("text/html; charset=\"utf-8\""), Some("utf-8"));
+        assert_eq!(charset_param("text/html"), None);
+    }
+
+    #[test]
+    fn decodes_declared_and_sniffed_charsets() {
+        // "Grüße" in ISO-8859-1
+        let latin1 = b"<p>Gr\xfc\xdfe</p>";
+        let (text, charset) = decode(latin1, Some("text/html; charset=iso-8859-1"));
+        assert_eq!(text, "<p>Grüße</p>");
+        assert_eq!(charset, "windows-1252");
+
+        let meta = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1251\"></head>\xcf\xf0\xe8\xe2\xe5\xf2";
+        let (text, charset) = decode(meta, Some("text/html"));
+        assert!(text.ends_with("Привет"));
+        assert_eq!(charset, "windows-1251");
+
+        let (text, _) = decode(b"<meta charset='shift_jis'>\x93\xfa\x96\x7b", None);
+        assert!(text.ends_with("日本"));
+    }
+
+    #[test]
+    fn falls_back_from_invalid_utf8() {
+        let (text, charset) = decode("Grüße".as_bytes(), None);
+        assert_eq!((text.as_str(), charset), ("Grüße", "UTF-8"));
+
+        let (text, charset) = decode(b"Gr\xfc\xdfe", None);
+        assert_eq!((text.as_str(), charset), ("Grüße", "windows-1252"));
+
+        // A multi-byte character cut at the size limit stays UTF-8.
+        let cut = &"Grüße".as_bytes()[..3];
+        assert_eq!(decode(cut, None).1, "UTF-8");
+    }
+}
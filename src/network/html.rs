@@ -0,0 +1,372 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! HTML pipeline for the Sovereign Browser proxy.
+//!
+//! - [`sanitize_html`] — whitelist-based cleaning (ammonia): drops scripts,
+//!   styles, inline event handlers and tracker resources, strips tracking
+//!   query parameters and rewrites relative URLs against the page URL.
+//! - [`extract_article`] — readability-style extraction (scraper): scores
+//!   content containers by paragraph text and link density and returns the
+//!   main article as clean text and sanitized HTML.
+
+use ammonia::{Builder, Url, UrlRelative};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Hosts (and their subdomains) whose resources are dropped from proxied pages.
+const TRACKER_HOSTS: &[&str] = &[
+    "google-analytics.com",
+    "googletagmanager.com",
+    "googlesyndication.com",
+    "doubleclick.net",
+    "adservice.google.com",
+    "facebook.net",
+    "connect.facebook.com",
+    "scorecardresearch.com",
+    "quantserve.com",
+    "hotjar.com",
+    "criteo.com",
+    "taboola.com",
+    "outbrain.com",
+    "adnxs.com",
+    "amazon-adsystem.com",
+];
+
+/// Query parameters that only serve cross-site tracking.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "dclid", "msclkid", "mc_eid", "igshid"];
+
+/// Elements whose text never belongs to the article body.
+const BOILERPLATE_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "button", "svg",
+    "iframe", "template",
+];
+
+/// Block-level elements emitted as separate paragraphs in article text.
+const TEXT_BLOCK_SELECTOR: &str = "p, h1, h2, h3, h4, h5, h6, li, blockquote, pre, figcaption";
+
+/// Readable main content of a page.
+#[derive(Debug, Clone, Default)]
+pub struct Article {
+    pub title: String,
+    pub byline: Option<String>,
+    /// Paragraphs separated by blank lines.
+    pub text: String,
+    /// Sanitized HTML of the main content container.
+    pub html: String,
+}
+
+fn is_tracker_host(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    TRACKER_HOSTS
+        .iter()
+        .any(|t| host == *t || host.ends_with(&format!(".{t}")))
+}
+
+/// Remove `utm_*` and click-id parameters. Returns `None` if nothing changed.
+fn strip_tracking_params(url: &Url) -> Option<Url> {
+    let is_tracking = |k: &str| k.starts_with("utm_") || TRACKING_PARAMS.contains(&k);
+    if !url.query_pairs().any(|(k, _)| is_tracking(&k)) {
+        return None;
+    }
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !is_tracking(k))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    let mut cleaned = url.clone();
+    if kept.is_empty() {
+        cleaned.set_query(None);
+    } else {
+        cleaned.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(cleaned)
+}
+
+/// Sanitize a page for display inside the dashboard.
+///
+/// `base_url` is the page's own URL, used to make relative links absolute.
+/// Links for which `is_blocked_link` returns `true` lose their `href`
+/// (used to defuse filtered destinations for restricted roles).
+pub fn sanitize_html(
+    html: &str,
+    base_url: Option<&str>,
+    is_blocked_link: impl Fn(&str) -> bool + Send + Sync + 'static,
+) -> String {
+    let base = base_url.and_then(|u| Url::parse(u).ok());
+    let filter_base = base.clone();
+
+    let mut builder = Builder::default();
+    builder
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .add_generic_attributes(&["class", "id"])
+        .add_tag_attributes("img", &["loading"])
+        .attribute_filter(move |element, attribute, value| {
+            if !matches!(attribute, "href" | "src" | "cite") {
+                return Some(Cow::Borrowed(value));
+            }
+            let parsed = match &filter_base {
+                Some(b) => b.join(value),
+                None => Url::parse(value),
+            };
+            match parsed {
+                Ok(url) => {
+                    if url.host_str().is_some_and(is_tracker_host) {
+                        return None;
+                    }
+                    if element == "a" && attribute == "href" && is_blocked_link(url.as_str()) {
+                        return None;
+                    }
+                    match strip_tracking_params(&url) {
+                        Some(cleaned) => Some(Cow::Owned(cleaned.to_string())),
+                        None => Some(Cow::Borrowed(value)),
+                    }
+                }
+                // Relative URLs without a base cannot be checked and pass through.
+                Err(_) => Some(Cow::Borrowed(value)),
+            }
+        });
+    builder.url_relative(match base {
+        Some(url) => UrlRelative::RewriteWithBase(url),
+        None => UrlRelative::PassThrough,
+    });
+
+    builder.clean(html).to_string()
+}
+
+/// Page title: `<title>`, falling back to `og:title`, then the first `<h1>`.
+pub fn extract_title(html: &str) -> String {
+    title_of(&Html::parse_document(html))
+}
+
+fn title_of(doc: &Html) -> String {
+    let first_text = |selector: &str| {
+        Selector::parse(selector)
+            .ok()
+            .and_then(|s| {
+                doc.select(&s)
+                    .next()
+                    .map(|e| collapse_ws(&e.text().collect::<String>()))
+            })
+            .filter(|t| !t.is_empty())
+    };
+    first_text("title")
+        .or_else(|| meta_content(doc, "meta[property='og:title']"))
+        .or_else(|| first_text("h1"))
+        .unwrap_or_default()
+}
+
+fn meta_content(doc: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    doc.select(&selector)
+        .find_map(|e| e.value().attr("content"))
+        .map(collapse_ws)
+        .filter(|c| !c.is_empty())
+}
+
+fn collapse_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_boilerplate(el: &ElementRef<'_>) -> bool {
+    BOILERPLATE_TAGS.contains(&el.value().name())
+}
+
+/// True if `el` or one of its ancestors is navigation/script chrome.
+fn inside_boilerplate(el: &ElementRef<'_>) -> bool {
+    is_boilerplate(el)
+        || el
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| is_boilerplate(&a))
+}
+
+/// Visible text of an element, skipping nested boilerplate (scripts, nav, ...).
+fn visible_text(el: &ElementRef<'_>) -> String {
+    let mut out = String::new();
+    for node in el.descendants() {
+        if let Node::Text(text) = node.value() {
+            let in_boilerplate = node
+                .ancestors()
+                .take_while(|a| a.id() != el.id())
+                .filter_map(ElementRef::wrap)
+                .any(|a| is_boilerplate(&a));
+            if !in_boilerplate {
+                out.push_str(text);
+            }
+        }
+    }
+    collapse_ws(&out)
+}
+
+/// Share of an element's text that sits inside links, in percent.
+fn link_density_pct(el: &ElementRef<'_>, links: &Selector) -> usize {
+    let total = visible_text(el).len();
+    if total == 0 {
+        return 0;
+    }
+    let linked: usize = el.select(links).map(|a| visible_text(&a).len()).sum();
+    (linked * 100 / total).min(100)
+}
+
+/// Extract the main article of a page.
+///
+/// Readability-style scoring: every paragraph credits its text length to its
+/// parent and half of it to its grandparent; the best container wins after a
+/// link-density penalty, with a bonus for semantic `<article>`/`<main>`.
+pub fn extract_article(html: &str, base_url: Option<&str>) -> Article {
+    let doc = Html::parse_document(html);
+    let paragraphs = Selector::parse("p, pre, blockquote").expect("static selector");
+    let links = Selector::parse("a").expect("static selector");
+    let blocks = Selector::parse(TEXT_BLOCK_SELECTOR).expect("static selector");
+    let body = Selector::parse("body").expect("static selector");
+
+    let mut scores = HashMap::new();
+    for p in doc.select(&paragraphs).filter(|p| !inside_boilerplate(p)) {
+        let len = visible_text(&p).len();
+        let mut ancestors = p.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = ancestors.next() {
+            *scores.entry(parent.id()).or_insert(0usize) += len;
+        }
+        if let Some(grandparent) = ancestors.next() {
+            *scores.entry(grandparent.id()).or_default() += len / 2;
+        }
+    }
+
+    let best = scores
+        .into_iter()
+        .filter_map(|(id, raw)| {
+            let el = ElementRef::wrap(doc.tree.get(id)?)?;
+            let mut s = raw * (100 - link_density_pct(&el, &links)) / 100;
+            if matches!(el.value().name(), "article" | "main") {
+                s += s / 4;
+            }
+            Some((s, el))
+        })
+        .max_by_key(|(s, el)| (*s, std::cmp::Reverse(el.id())))
+        .map(|(_, el)| el)
+        .or_else(|| doc.select(&body).next());
+
+    let Some(root) = best else {
+        return Article {
+            title: title_of(&doc),
+            ..Article::default()
+        };
+    };
+
+    // Emit each text block once; skip blocks nested inside an emitted block.
+    let mut emitted = HashSet::new();
+    let mut paragraphs_out = Vec::new();
+    for block in root.select(&blocks) {
+        if inside_boilerplate(&block) || block.ancestors().any(|a| emitted.contains(&a.id())) {
+            continue;
+        }
+        emitted.insert(block.id());
+        let text = visible_text(&block);
+        if !text.is_empty() {
+            paragraphs_out.push(text);
+        }
+    }
+    let text = if paragraphs_out.is_empty() {
+        visible_text(&root)
+    } else {
+        paragraphs_out.join("\n\n")
+    };
+
+    Article {
+        title: title_of(&doc),
+        byline: meta_content(&doc, "meta[name='author']"),
+        text,
+        html: sanitize_html(&root.inner_html(), base_url, |_| false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title> Example  News </title>
+        <meta name="author" content="Jane Doe">
+        <script>track()</script><style>p{color:red}</style></head>
+        <body>
+          <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+          <article>
+            <h1>Big Story</h1>
+            <p>First paragraph of the story with enough words to count.</p>
+            <p onclick="evil()">Second paragraph <a href="/more?utm_source=x&amp;id=7">read more</a>.</p>
+            <img src="https://www.google-analytics.com/collect?v=1">
+            <img src="/img/photo.jpg">
+            <script>alert(1)</script>
+          </article>
+          <footer>Copyright footer text that is quite long but irrelevant.</footer>
+        </body></html>"#;
+
+    #[test]
+    fn sanitize_removes_scripts_and_handlers() {
+        let out = sanitize_html(PAGE, None, |_| false);
+        assert!(!out.contains("<script"));
+        assert!(!out.contains("alert(1)"));
+        assert!(!out.contains("track()"));
+        assert!(!out.contains("onclick"));
+        assert!(!out.contains("color:red"));
+    }
+
+    #[test]
+    fn sanitize_drops_trackers_and_tracking_params() {
+        let out = sanitize_html(PAGE, Some("https://news.example/story"), |_| false);
+        assert!(!out.contains("google-analytics"));
+        assert!(!out.contains("utm_source"));
+        assert!(out.contains("id=7"));
+    }
+
+    #[test]
+    fn sanitize_rewrites_relative_urls() {
+        let out = sanitize_html(PAGE, Some("https://news.example/story"), |_| false);
+        assert!(out.contains("https://news.example/img/photo.jpg"));
+        assert!(out.contains("https://news.example/about"));
+    }
+
+    #[test]
+    fn sanitize_defuses_blocked_links() {
+        let out = sanitize_html(
+            r#"<a href="https://casino.example">win</a><a href="https://ok.example">ok</a>"#,
+            None,
+            |url| url.contains("casino"),
+        );
+        assert!(!out.contains("casino.example"));
+        assert!(out.contains("https://ok.example"));
+    }
+
+    #[test]
+    fn title_prefers_title_tag_then_og_then_h1() {
+        assert_eq!(extract_title(PAGE), "Example News");
+        assert_eq!(
+            extract_title(r#"<meta property="og:title" content="OG Title"><h1>H</h1>"#),
+            "OG Title"
+        );
+        assert_eq!(extract_title("<body><h1>Heading</h1></body>"), "Heading");
+        assert_eq!(extract_title("<p>nothing</p>"), "");
+    }
+
+    #[test]
+    fn article_extraction_skips_chrome() {
+        let article = extract_article(PAGE, Some("https://news.example/story"));
+        assert_eq!(article.title, "Example News");
+        assert_eq!(article.byline.as_deref(), Some("Jane Doe"));
+        assert!(article.text.starts_with("Big Story\n\nFirst paragraph"));
+        assert!(article.text.contains("Second paragraph read more."));
+        assert!(!article.text.contains("Home"));
+        assert!(!article.text.contains("Copyright"));
+        assert!(!article.text.contains("alert"));
+        assert!(article.html.contains("https://news.example/img/photo.jpg"));
+    }
+
+    #[test]
+    fn article_extraction_handles_plain_documents() {
+        let article = extract_article("<body>Just some text</body>", None);
+        assert_eq!(article.text, "Just some text");
+        assert!(extract_article("", None).text.is_empty());
+    }
+}
@@ -3,5 +3,13 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 pub mod adblock;
+pub mod browsing;
+pub mod content_filter;
+pub mod digest;
+pub mod egress;
+pub mod fetch;
+pub mod html;
 pub mod vpn;
+pub use browsing::BrowsingStore;
+pub use content_filter::ContentFilter;
 pub use vpn::VpnManager;
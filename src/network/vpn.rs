@@ -122,6 +122,28 @@ impl VpnManager {
         Ok(())
     }
 
+    /// Take the WireGuard interface down. Returns `false` if it was not up.
+    /// Peers are kept; the next `init` brings the interface back.
+    pub fn shutdown(&self) -> anyhow::Result<bool> {
+        #[cfg(target_os = "linux")]
+        {
+            let link = Path::new("/sys/class/net").join(&self.wg_interface);
+            if !link.exists() {
+                return Ok(false);
+            }
+            let status = std::process::Command::new("wg-quick")
+                .arg("down")
+                .arg(&self.wg_interface)
+                .status()?;
+            if !status.success() {
+                bail!("wg-quick down {} failed with {status}", self.wg_interface);
+            }
+            Ok(true)
+        }
+        #[cfg(not(target_os = "linux"))]
+        Ok(false)
+    }
+
     // --- Helpers ---
 
     fn load_config(&self) -> anyhow::Result<VpnConfig> {
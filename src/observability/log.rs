@@ -70,6 +70,27 @@ impl Observer for LogObserver {
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
             }
+            // Contents stay out of logs.
+            ObserverEvent::Message { .. } | ObserverEvent::ToolIo { .. } => {}
+            ObserverEvent::VoiceTurn {
+                channel,
+                stt,
+                agent,
+                first_byte,
+                tts,
+                total,
+            } => {
+                let ms = |d: &std::time::Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    channel = %channel,
+                    stt_ms = ms(stt),
+                    agent_ms = ms(agent),
+                    first_byte_ms = ms(first_byte),
+                    tts_ms = ?tts.as_ref().map(ms),
+                    total_ms = ms(total),
+                    "voice.turn"
+                );
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 info!(channel = %channel, direction = %direction, "channel.message");
             }
@@ -79,6 +100,10 @@ impl Observer for LogObserver {
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
+            ObserverEvent::SubAgent { agent, event } => {
+                let _span = tracing::info_span!("subagent", agent = %agent).entered();
+                self.record_event(event);
+            }
         }
     }
 
@@ -151,6 +176,14 @@ mod tests {
             success: false,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
+        obs.record_event(&ObserverEvent::VoiceTurn {
+            channel: "dashboard".into(),
+            stt: Duration::from_millis(300),
+            agent: Duration::from_millis(900),
+            first_byte: Duration::from_millis(1210),
+            tts: Some(Duration::from_millis(400)),
+            total: Duration::from_millis(1610),
+        });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "outbound".into(),
@@ -6,6 +6,8 @@ pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
+pub mod recorder;
+pub mod subagent;
 pub mod traits;
 pub mod verbose;
 
@@ -13,13 +15,31 @@ pub use self::log::LogObserver;
 pub use self::multi::MultiObserver;
 pub use noop::NoopObserver;
 pub use otel::OtelObserver;
+pub use recorder::RecorderObserver;
 pub use traits::{Observer, ObserverEvent};
 pub use verbose::VerboseObserver;
 
 use crate::config::ObservabilityConfig;
+use std::path::Path;
 
-/// Factory: create the right observer from config
-pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
+/// Factory: create the right observer from config, plus a
+/// [`RecorderObserver`] writing to `<workspace_dir>/recordings` when
+/// `record_runs` is set
+pub fn create_observer(config: &ObservabilityConfig, workspace_dir: &Path) -> Box<dyn Observer> {
+    let backend = create_backend(config);
+    if !config.record_runs {
+        return backend;
+    }
+    Box::new(MultiObserver::new(vec![
+        backend,
+        Box::new(RecorderObserver::new(
+            recorder::recordings_dir(workspace_dir),
+            config.max_recordings,
+        )),
+    ]))
+}
+
+fn create_backend(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
         "otel" | "opentelemetry" | "otlp" => {
@@ -64,7 +84,7 @@ mod tests {
             backend: "none".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "noop");
     }
 
     #[test]
@@ -73,7 +93,7 @@ mod tests {
             backend: "noop".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "noop");
     }
 
     #[test]
@@ -82,7 +102,7 @@ mod tests {
             backend: "log".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "log");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "log");
     }
 
     #[test]
@@ -91,8 +111,9 @@ mod tests {
             backend: "otel".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "otel");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "otel");
     }
 
     #[test]
@@ -101,8 +122,9 @@ mod tests {
             backend: "opentelemetry".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "otel");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "otel");
     }
 
     #[test]
@@ -111,8 +133,9 @@ mod tests {
             backend: "otlp".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "otel");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "otel");
     }
 
     #[test]
@@ -121,7 +144,7 @@ mod tests {
             backend: "xyzzy_unknown".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "noop");
     }
 
     #[test]
@@ -130,7 +153,7 @@ mod tests {
             backend: String::new(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "noop");
     }
 
     #[test]
@@ -139,6 +162,16 @@ mod tests {
             backend: "xyzzy_garbage_123".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "noop");
+    }
+
+    #[test]
+    fn factory_record_runs_adds_recorder() {
+        let cfg = ObservabilityConfig {
+            backend: "log".into(),
+            record_runs: true,
+            ..ObservabilityConfig::default()
+        };
+        assert_eq!(create_observer(&cfg, Path::new("/tmp")).name(), "multi");
     }
 }
@@ -3,7 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
-use opentelemetry::metrics::{Counter, Gauge, Histogram};
+use opentelemetry::metrics::{Counter, Gauge, Histogram, ObservableGauge};
 use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
@@ -25,11 +25,15 @@ pub struct OtelObserver {
     tool_duration: Histogram<f64>,
     channel_messages: Counter<u64>,
     heartbeat_ticks: Counter<u64>,
+    voice_stage_duration: Histogram<f64>,
     errors: Counter<u64>,
     request_latency: Histogram<f64>,
     tokens_used: Counter<u64>,
     active_sessions: Gauge<u64>,
     queue_depth: Gauge<u64>,
+    // Observed from the provider circuit breakers at each collection
+    _provider_circuit: ObservableGauge<u64>,
+    _provider_health: ObservableGauge<f64>,
 }
 
 impl OtelObserver {
@@ -127,6 +131,12 @@ impl OtelObserver {
             .with_description("Total heartbeat ticks")
             .build();
 
+        let voice_stage_duration = meter
+            .f64_histogram("mymolt.voice.stage.duration")
+            .with_description("Voice turn stage duration in seconds")
+            .with_unit("s")
+            .build();
+
         let errors = meter
             .u64_counter("mymolt.errors")
             .with_description("Total errors by component")
@@ -153,6 +163,28 @@ impl OtelObserver {
             .with_description("Current message queue depth")
             .build();
 
+        let provider_circuit = meter
+            .u64_observable_gauge("mymolt.provider.circuit")
+            .with_description("Provider circuit state: 0 closed, 1 half-open, 2 open")
+            .with_callback(|gauge| {
+                for status in crate::providers::circuit::snapshot() {
+                    let attrs = [KeyValue::new("provider", status.provider)];
+                    gauge.observe(status.state.level(), &attrs);
+                }
+            })
+            .build();
+
+        let provider_health = meter
+            .f64_observable_gauge("mymolt.provider.health")
+            .with_description("Provider health score, 1.0 healthy to 0.0 unusable")
+            .with_callback(|gauge| {
+                for status in crate::providers::circuit::snapshot() {
+                    let attrs = [KeyValue::new("provider", status.provider)];
+                    gauge.observe(status.health_score, &attrs);
+                }
+            })
+            .build();
+
         Ok(Self {
             tracer_provider,
             meter_provider: meter_provider_clone,
@@ -164,11 +196,14 @@ impl OtelObserver {
             tool_duration,
             channel_messages,
             heartbeat_ticks,
+            voice_stage_duration,
             errors,
             request_latency,
             tokens_used,
             active_sessions,
             queue_depth,
+            _provider_circuit: provider_circuit,
+            _provider_health: provider_health,
         })
     }
 }
@@ -289,7 +324,54 @@ impl Observer for OtelObserver {
                 self.tool_duration
                     .record(secs, &[KeyValue::new("tool", tool.clone())]);
             }
-            ObserverEvent::TurnComplete => {}
+            ObserverEvent::TurnComplete
+            | ObserverEvent::Message { .. }
+            | ObserverEvent::ToolIo { .. } => {}
+            ObserverEvent::VoiceTurn {
+                channel,
+                stt,
+                agent,
+                first_byte,
+                tts,
+                total,
+            } => {
+                let mut stages = vec![
+                    ("stt", *stt),
+                    ("agent", *agent),
+                    ("first_byte", *first_byte),
+                    ("total", *total),
+                ];
+                if let Some(tts) = tts {
+                    stages.push(("tts", *tts));
+                }
+                for (stage, duration) in &stages {
+                    self.voice_stage_duration.record(
+                        duration.as_secs_f64(),
+                        &[
+                            KeyValue::new("channel", channel.clone()),
+                            KeyValue::new("stage", *stage),
+                        ],
+                    );
+                }
+
+                let start_time = SystemTime::now()
+                    .checked_sub(*total)
+                    .unwrap_or(SystemTime::now());
+                let mut span = tracer.build(
+                    opentelemetry::trace::SpanBuilder::from_name("voice.turn")
+                        .with_kind(SpanKind::Internal)
+                        .with_start_time(start_time)
+                        .with_attributes(
+                            stages
+                                .iter()
+                                .map(|(stage, d)| {
+                                    KeyValue::new(format!("{stage}_s"), d.as_secs_f64())
+                                })
+                                .collect::<Vec<_>>(),
+                        ),
+                );
+                span.end();
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 self.channel_messages.add(
                     1,
@@ -318,6 +400,8 @@ impl Observer for OtelObserver {
                 self.errors
                     .add(1, &[KeyValue::new("component", component.clone())]);
             }
+            // Counted like the parent's own calls; the provider label tells them apart.
+            ObserverEvent::SubAgent { event, .. } => self.record_event(event),
         }
     }
 
@@ -417,6 +501,14 @@ mod tests {
             success: false,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
+        obs.record_event(&ObserverEvent::VoiceTurn {
+            channel: "dashboard".into(),
+            stt: Duration::from_millis(300),
+            agent: Duration::from_millis(900),
+            first_byte: Duration::from_millis(1210),
+            tts: None,
+            total: Duration::from_millis(1210),
+        });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "inbound".into(),
@@ -0,0 +1,428 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Recording of agent turns for replay (`[observability] record_runs`).
+//!
+//! The agent loop runs each turn inside [`recorded`], which gives it a fresh
+//! run id. While the turn runs, [`RecorderObserver`] appends every event —
+//! messages, LLM calls, tool calls with their arguments and output, timings —
+//! as one JSON line to `<run id>.jsonl`. Contents pass through the
+//! [`SensitivityScanner`] before they reach disk. Events outside a recorded
+//! turn (heartbeats, channel traffic) are ignored, and concurrent turns
+//! sharing one observer land in separate files.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::memory::sovereign::SensitivityScanner;
+use crate::providers::scrub_secret_patterns;
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Longest message, argument or output kept per step, in characters.
+const MAX_FIELD_CHARS: usize = 64 * 1024;
+
+tokio::task_local! {
+    static RUN: Arc<Run>;
+}
+
+struct Run {
+    id: String,
+    started: Instant,
+}
+
+/// Run `fut` as one recorded agent turn.
+pub async fn recorded<F: Future>(fut: F) -> F::Output {
+    let id = format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%S%3f"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let run = Arc::new(Run {
+        id,
+        started: Instant::now(),
+    });
+    RUN.scope(run, fut).await
+}
+
+/// Where recordings of `workspace_dir` are kept.
+pub fn recordings_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("recordings")
+}
+
+/// One line of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedStep {
+    pub at: DateTime<Utc>,
+    /// Milliseconds since the turn started
+    pub elapsed_ms: u64,
+    /// Sub-agent that produced the step (`parent/child` when nested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(flatten)]
+    pub step: Step,
+}
+
+/// What happened in a recorded step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    Message {
+        role: String,
+        content: String,
+    },
+    LlmRequest {
+        provider: String,
+        model: String,
+        messages: usize,
+    },
+    LlmResponse {
+        duration_ms: u64,
+        success: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    ToolStart {
+        tool: String,
+    },
+    ToolCall {
+        tool: String,
+        duration_ms: u64,
+        success: bool,
+    },
+    ToolIo {
+        tool: String,
+        arguments: String,
+        output: String,
+    },
+    Error {
+        component: String,
+        message: String,
+    },
+}
+
+/// Overview of one recording, for listing
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub steps: usize,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub tool_calls: usize,
+    /// Last error of the turn, if it failed
+    pub error: Option<String>,
+}
+
+/// Observer writing recorded turns to `dir`
+pub struct RecorderObserver {
+    dir: PathBuf,
+    max_recordings: usize,
+    scanner: SensitivityScanner,
+    write_lock: Mutex<()>,
+}
+
+impl RecorderObserver {
+    pub fn new(dir: PathBuf, max_recordings: usize) -> Self {
+        Self {
+            dir,
+            max_recordings,
+            scanner: SensitivityScanner::new(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let (redacted, _) = self.scanner.redact(&scrub_secret_patterns(text));
+        truncate_with_ellipsis(&redacted, MAX_FIELD_CHARS)
+    }
+
+    /// The step for `event` with contents redacted, and the sub-agent that
+    /// produced it.
+    fn step(&self, event: &ObserverEvent) -> Option<(Option<String>, Step)> {
+        let step = match event {
+            ObserverEvent::Message { role, content } => Step::Message {
+                role: role.clone(),
+                content: self.redact(content),
+            },
+            ObserverEvent::LlmRequest {
+                provider,
+                model,
+                messages_count,
+            } => Step::LlmRequest {
+                provider: provider.clone(),
+                model: model.clone(),
+                messages: *messages_count,
+            },
+            ObserverEvent::LlmResponse {
+                duration,
+                success,
+                error_message,
+                ..
+            } => Step::LlmResponse {
+                duration_ms: millis(*duration),
+                success: *success,
+                error: error_message.clone(),
+            },
+            ObserverEvent::ToolCallStart { tool } => Step::ToolStart { tool: tool.clone() },
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => Step::ToolCall {
+                tool: tool.clone(),
+                duration_ms: millis(*duration),
+                success: *success,
+            },
+            ObserverEvent::ToolIo {
+                tool,
+                arguments,
+                output,
+            } => Step::ToolIo {
+                tool: tool.clone(),
+                arguments: self.redact(arguments),
+                output: self.redact(output),
+            },
+            ObserverEvent::Error { component, message } => Step::Error {
+                component: component.clone(),
+                message: self.redact(message),
+            },
+            ObserverEvent::SubAgent { agent, event } => {
+                let (inner, step) = self.step(event)?;
+                let agent = match inner {
+                    Some(inner) => format!("{agent}/{inner}"),
+                    None => agent.clone(),
+                };
+                return Some((Some(agent), step));
+            }
+            _ => return None,
+        };
+        Some((None, step))
+    }
+
+    fn append(&self, run: &Run, line: &str) -> Result<()> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.jsonl", run.id));
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{line}")?;
+        if is_new {
+            self.prune()?;
+        }
+        Ok(())
+    }
+
+    /// Delete the oldest recordings beyond `max_recordings`.
+    fn prune(&self) -> Result<()> {
+        let ids = recording_ids(&self.dir)?;
+        let excess = ids.len().saturating_sub(self.max_recordings);
+        for id in &ids[..excess] {
+            std::fs::remove_file(self.dir.join(format!("{id}.jsonl")))?;
+        }
+        Ok(())
+    }
+}
+
+impl Observer for RecorderObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let Ok(run) = RUN.try_with(Arc::clone) else {
+            return;
+        };
+        let Some((agent, step)) = self.step(event) else {
+            return;
+        };
+        let step = RecordedStep {
+            at: Utc::now(),
+            elapsed_ms: millis(run.started.elapsed()),
+            agent,
+            step,
+        };
+        let result = serde_json::to_string(&step)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| self.append(&run, &line));
+        if let Err(e) = result {
+            tracing::warn!("Failed to record step of run {}: {e}", run.id);
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "recorder"
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Ids of the recordings in `dir`, oldest first.
+fn recording_ids(dir: &Path) -> Result<Vec<String>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            name.strip_suffix(".jsonl").map(str::to_string)
+        })
+        .filter(|id| is_valid_id(id))
+        .collect();
+    // Ids start with the start time, so name order is age order.
+    ids.sort();
+    Ok(ids)
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// The steps of recording `id`, or `None` if there is no such recording.
+pub fn load_run(dir: &Path, id: &str) -> Result<Option<Vec<RecordedStep>>> {
+    if !is_valid_id(id) {
+        return Ok(None);
+    }
+    let content = match std::fs::read_to_string(dir.join(format!("{id}.jsonl"))) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    // A line cut short by a crash mid-write is skipped, not fatal.
+    Ok(Some(
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    ))
+}
+
+/// Summaries of every recording in `dir`, newest first.
+pub fn list_runs(dir: &Path) -> Result<Vec<RunSummary>> {
+    let mut runs = Vec::new();
+    for id in recording_ids(dir)?.into_iter().rev() {
+        let Some(steps) = load_run(dir, &id)? else {
+            continue;
+        };
+        let Some(first) = steps.first() else {
+            continue;
+        };
+        let (provider, model) = steps
+            .iter()
+            .find_map(|s| match &s.step {
+                Step::LlmRequest {
+                    provider, model, ..
+                } => Some((Some(provider.clone()), Some(model.clone()))),
+                _ => None,
+            })
+            .unwrap_or_default();
+        runs.push(RunSummary {
+            started_at: first.at,
+            duration_ms: steps.last().map_or(0, |s| s.elapsed_ms),
+            steps: steps.len(),
+            provider,
+            model,
+            tool_calls: steps
+                .iter()
+                .filter(|s| matches!(s.step, Step::ToolCall { .. }))
+                .count(),
+            error: steps.iter().rev().find_map(|s| match &s.step {
+                Step::Error { message, .. } => Some(message.clone()),
+                _ => None,
+            }),
+            id,
+        });
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tool_io(arguments: &str) -> ObserverEvent {
+        ObserverEvent::ToolIo {
+            tool: "shell".into(),
+            arguments: arguments.into(),
+            output: "ok".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_redacted_steps_per_turn() {
+        let tmp = TempDir::new().unwrap();
+        let recorder = RecorderObserver::new(tmp.path().to_path_buf(), 10);
+
+        recorder.record_event(&tool_io("outside any turn"));
+        recorded(async {
+            recorder.record_event(&ObserverEvent::Message {
+                role: "user".into(),
+                content: "deploy it".into(),
+            });
+            recorder.record_event(&tool_io(r#"{"token":"sk-abcdef1234567890abcdef"}"#));
+            recorder.record_event(&ObserverEvent::SubAgent {
+                agent: "coder".into(),
+                event: Box::new(ObserverEvent::ToolCallStart {
+                    tool: "file_write".into(),
+                }),
+            });
+            recorder.record_event(&ObserverEvent::HeartbeatTick);
+        })
+        .await;
+        // Ids order by start time to the millisecond.
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        recorded(async {
+            recorder.record_event(&ObserverEvent::Error {
+                component: "agent".into(),
+                message: "budget exceeded".into(),
+            });
+        })
+        .await;
+
+        let runs = list_runs(tmp.path()).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].error.as_deref(), Some("budget exceeded"));
+
+        let steps = load_run(tmp.path(), &runs[1].id).unwrap().unwrap();
+        assert_eq!(steps.len(), 3);
+        let Step::ToolIo { arguments, .. } = &steps[1].step else {
+            panic!("expected tool io, got {:?}", steps[1].step);
+        };
+        assert!(!arguments.contains("sk-abcdef"), "{arguments}");
+        assert_eq!(steps[2].agent.as_deref(), Some("coder"));
+    }
+
+    #[tokio::test]
+    async fn keeps_only_the_newest_recordings() {
+        let tmp = TempDir::new().unwrap();
+        let recorder = RecorderObserver::new(tmp.path().to_path_buf(), 2);
+        for _ in 0..3 {
+            recorded(async { recorder.record_event(&ObserverEvent::TurnComplete) }).await;
+            recorded(async { recorder.record_event(&tool_io("{}")) }).await;
+        }
+        assert_eq!(list_runs(tmp.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn unknown_or_unsafe_ids_are_not_found() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_run(tmp.path(), "missing").unwrap().is_none());
+        assert!(load_run(tmp.path(), "../config").unwrap().is_none());
+        assert!(list_runs(&tmp.path().join("absent")).unwrap().is_empty());
+    }
+}
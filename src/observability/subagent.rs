@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Forwarding of sub-agent progress to the parent run's observer.
+//!
+//! Tools do not get an observer, and the parent's observer is borrowed per
+//! turn. The agent loop therefore runs each tool inside [`forward_to`], which
+//! sets a task-local sender; a delegating tool calls [`emit`] and the events
+//! reach the parent's observer as [`ObserverEvent::SubAgent`] while the tool
+//! is still running.
+
+use super::traits::{Observer, ObserverEvent};
+use std::future::Future;
+use tokio::sync::mpsc;
+
+tokio::task_local! {
+    static PARENT_EVENTS: mpsc::UnboundedSender<ObserverEvent>;
+}
+
+/// Run `fut`, delivering events sent with [`emit`] to `observer` as they
+/// arrive.
+pub async fn forward_to<F: Future>(observer: &dyn Observer, fut: F) -> F::Output {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fut = PARENT_EVENTS.scope(tx, fut);
+    tokio::pin!(fut);
+    loop {
+        tokio::select! {
+            biased;
+            Some(event) = rx.recv() => observer.record_event(&event),
+            output = &mut fut => {
+                while let Ok(event) = rx.try_recv() {
+                    observer.record_event(&event);
+                }
+                return output;
+            }
+        }
+    }
+}
+
+/// Report `event` from sub-agent `agent` to the enclosing [`forward_to`].
+/// Outside one the event is dropped.
+pub fn emit(agent: &str, event: ObserverEvent) {
+    let _ = PARENT_EVENTS.try_with(|tx| {
+        tx.send(ObserverEvent::SubAgent {
+            agent: agent.to_string(),
+            event: Box::new(event),
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::traits::ObserverMetric;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recording(Mutex<Vec<String>>);
+
+    impl Observer for Recording {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::SubAgent { agent, event } = event {
+                self.0.lock().unwrap().push(format!("{agent}:{event:?}"));
+            }
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    #[tokio::test]
+    async fn events_reach_parent_before_tool_finishes() {
+        let observer = Recording::default();
+        let seen_during = forward_to(&observer, async {
+            emit("researcher", ObserverEvent::TurnComplete);
+            tokio::task::yield_now().await;
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            observer.0.lock().unwrap().len()
+        })
+        .await;
+        assert_eq!(seen_during, 1);
+        assert_eq!(*observer.0.lock().unwrap(), vec!["researcher:TurnComplete"]);
+    }
+
+    #[tokio::test]
+    async fn emit_outside_scope_is_dropped() {
+        emit("researcher", ObserverEvent::HeartbeatTick);
+        let observer = Recording::default();
+        forward_to(&observer, async {
+            emit("coder", ObserverEvent::HeartbeatTick);
+        })
+        .await;
+        assert_eq!(observer.0.lock().unwrap().len(), 1);
+    }
+}
@@ -43,6 +43,33 @@ pub enum ObserverEvent {
     },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
+    /// A message in the conversation sent to the LLM, or the LLM's reply.
+    ///
+    /// Unlike the other events this carries prompt contents; only
+    /// [`RecorderObserver`](super::RecorderObserver) persists it.
+    Message {
+        role: String,
+        content: String,
+    },
+    /// Arguments and output of a finished tool call, following its
+    /// [`ObserverEvent::ToolCall`]. Carries contents like `Message`.
+    ToolIo {
+        tool: String,
+        arguments: String,
+        output: String,
+    },
+    /// Stage timings of one end-to-end voice turn
+    /// (audio receive → STT → agent → first response byte → TTS).
+    ///
+    /// `first_byte` is measured from audio receipt, so it includes `stt` and `agent`.
+    VoiceTurn {
+        channel: String,
+        stt: Duration,
+        agent: Duration,
+        first_byte: Duration,
+        tts: Option<Duration>,
+        total: Duration,
+    },
     ChannelMessage {
         channel: String,
         direction: String,
@@ -52,6 +79,11 @@ pub enum ObserverEvent {
         component: String,
         message: String,
     },
+    /// An event from a delegated sub-agent, forwarded to the parent run.
+    SubAgent {
+        agent: String,
+        event: Box<ObserverEvent>,
+    },
 }
 
 /// Numeric metrics
@@ -153,4 +185,24 @@ mod tests {
         assert!(matches!(cloned_event, ObserverEvent::ToolCall { .. }));
         assert!(matches!(cloned_metric, ObserverMetric::RequestLatency(_)));
     }
+
+    #[test]
+    fn voice_turn_event_is_cloneable() {
+        let event = ObserverEvent::VoiceTurn {
+            channel: "dashboard".into(),
+            stt: Duration::from_millis(300),
+            agent: Duration::from_millis(1200),
+            first_byte: Duration::from_millis(1510),
+            tts: None,
+            total: Duration::from_millis(1510),
+        };
+
+        match event.clone() {
+            ObserverEvent::VoiceTurn { stt, tts, .. } => {
+                assert_eq!(stt, Duration::from_millis(300));
+                assert!(tts.is_none());
+            }
+            _ => panic!("Expected VoiceTurn variant"),
+        }
+    }
 }
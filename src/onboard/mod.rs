@@ -2,6 +2,7 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+pub mod templates;
 pub mod wizard;
 
 pub use wizard::{run_channels_repair_wizard, run_models_refresh, run_quick_setup, run_wizard};
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! First-run workspace templates for the web onboarding flow.
+//!
+//! Where `mymolt onboard` targets terminal users, these templates back
+//! `/api/onboarding`: a household preset plus a few answers produce a guided
+//! SOUL.md, example skills and starter cron jobs. Existing files are never
+//! overwritten, except a SOUL.md that still holds only the bare default
+//! skeleton (its identity bindings are carried over).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Household presets offered on the first onboarding screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HouseholdPreset {
+    /// One adult running their own instance.
+    #[default]
+    Solo,
+    /// Parents and children sharing one instance.
+    Family,
+    /// An older relative, supported by family members.
+    SeniorCare,
+}
+
+impl HouseholdPreset {
+    pub const ALL: [Self; 3] = [Self::Solo, Self::Family, Self::SeniorCare];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Solo => "Just me",
+            Self::Family => "My family",
+            Self::SeniorCare => "Caring for a senior",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Solo => "A personal assistant for one adult with full control.",
+            Self::Family => {
+                "Shared assistant for parents and kids — children get filtered browsing and no shell."
+            }
+            Self::SeniorCare => {
+                "Voice-first, patient assistant for an older relative, with family members as Adults."
+            }
+        }
+    }
+
+    /// Roles typically added as household members for this preset.
+    pub fn suggested_roles(self) -> &'static [&'static str] {
+        match self {
+            Self::Solo => &[],
+            Self::Family => &["adult", "child"],
+            Self::SeniorCare => &["senior", "adult"],
+        }
+    }
+
+    fn communication_style(self) -> &'static str {
+        match self {
+            Self::Solo => "Be direct and concise. Skip small talk unless I start it.",
+            Self::Family => {
+                "Be warm and clear. Adapt to who is talking: simple and encouraging with children, \
+                 practical with adults."
+            }
+            Self::SeniorCare => {
+                "Be patient and friendly. Use short sentences, avoid jargon, and repeat \
+                 important details such as dates and times."
+            }
+        }
+    }
+}
+
+/// A household member added during onboarding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseholdMember {
+    pub name: String,
+    /// "adult", "senior" or "child" — Root is reserved for the owner.
+    pub role: String,
+}
+
+/// Answers collected by the onboarding flow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnboardingProfile {
+    #[serde(default)]
+    pub agent_name: String,
+    #[serde(default)]
+    pub user_name: String,
+    #[serde(default)]
+    pub timezone: String,
+    #[serde(default)]
+    pub language: String,
+    #[serde(default)]
+    pub preset: HouseholdPreset,
+    #[serde(default)]
+    pub members: Vec<HouseholdMember>,
+    /// Install the example skills.
+    #[serde(default = "default_true")]
+    pub example_skills: bool,
+    /// Schedule the preset's starter cron jobs.
+    #[serde(default)]
+    pub starter_jobs: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn or_default<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        fallback
+    } else {
+        trimmed
+    }
+}
+
+/// Persisted onboarding completion marker (`state/onboarding.json`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingState {
+    pub completed_at: DateTime<Utc>,
+    pub preset: HouseholdPreset,
+}
+
+/// Files written (or left alone) by [`scaffold`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScaffoldReport {
+    pub created: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+fn state_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join("onboarding.json")
+}
+
+/// Completion marker, or `None` if onboarding has not finished yet.
+pub fn load_state(workspace_dir: &Path) -> Option<OnboardingState> {
+    let raw = fs::read_to_string(state_path(workspace_dir)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn mark_complete(workspace_dir: &Path, preset: HouseholdPreset) -> Result<OnboardingState> {
+    let state = OnboardingState {
+        completed_at: Utc::now(),
+        preset,
+    };
+    let path = state_path(workspace_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&state)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(state)
+}
+
+/// SOUL.md with guided sections the user (and agent) fill in over time.
+pub fn soul_skeleton(profile: &OnboardingProfile) -> String {
+    let agent = or_default(&profile.agent_name, "MyMolt");
+    let user = or_default(&profile.user_name, "my human");
+    let tz = or_default(&profile.timezone, "UTC");
+    let language = or_default(&profile.language, "English");
+    let style = profile.preset.communication_style();
+
+    let household = if profile.members.is_empty() {
+        "- (Nobody else yet — add family members under Family in the dashboard)\n".to_string()
+    } else {
+        profile.members.iter().fold(String::new(), |mut out, m| {
+            let _ = writeln!(out, "- **{}** ({})", m.name.trim(), m.role);
+            out
+        })
+    };
+
+    format!(
+        "# SOUL.md — {agent}\n\n\
+         > Guided template. Replace the hints in parentheses with your own words;\n\
+         > {agent} reads this file every session.\n\n\
+         ## Who I Am\n\n\
+         I am **{agent}**, the personal assistant of {user}. I run on their own hardware\n\
+         and keep what I learn private.\n\n\
+         ## Who I Help\n\n\
+         - **Name:** {user}\n\
+         - **Timezone:** {tz}\n\
+         - **Language:** {language}\n\n\
+         ## Household\n\n\
+         {household}\n\
+         ## How I Communicate\n\n\
+         {style}\n\n\
+         ## Daily Routines\n\n\
+         - (e.g. Remind me to take my medication at 8:00)\n\
+         - (e.g. Summarize my calendar every Monday morning)\n\n\
+         ## Boundaries\n\n\
+         - Ask before anything leaves this device (emails, messages, posts).\n\
+         - Never share one household member's private notes with another.\n\
+         - (Add your own rules here)\n\n\
+         ## Identity Bindings\n\n"
+    )
+}
+
+/// Example skills as `(directory, SKILL.md content)`.
+fn example_skills() -> [(&'static str, &'static str); 3] {
+    [
+        (
+            "daily-briefing",
+            "# Daily Briefing\n\n\
+             Give a short morning briefing: today's calendar events, open reminders and\n\
+             anything noted in MEMORY.md under Open Loops. Keep it under ten lines.\n",
+        ),
+        (
+            "shopping-list",
+            "# Shopping List\n\n\
+             Keep a shared shopping list as a PIM note titled \"Shopping List\". Add,\n\
+             remove and read items on request; group them by aisle when reading aloud.\n",
+        ),
+        (
+            "homework-helper",
+            "# Homework Helper\n\n\
+             Help children understand homework without giving away answers. Ask guiding\n\
+             questions, explain one step at a time and praise effort.\n",
+        ),
+    ]
+}
+
+/// Starter cron jobs for a preset as `(crontab expression, command)`.
+pub fn starter_jobs(preset: HouseholdPreset) -> Vec<(&'static str, &'static str)> {
+    let mut jobs = vec![(
+        "0 7 * * *",
+        "mymolt agent -m \"Run the daily-briefing skill\"",
+    )];
+    match preset {
+        HouseholdPreset::Solo => {}
+        HouseholdPreset::Family => jobs.push((
+            "0 18 * * Sun",
+            "mymolt agent -m \"Summarize next week's family calendar\"",
+        )),
+        HouseholdPreset::SeniorCare => jobs.push((
+            "0 20 * * *",
+            "mymolt agent -m \"Ask how the day went and note anything worrying in the diary\"",
+        )),
+    }
+    jobs
+}
+
+/// Whether an existing SOUL.md is only the bare skeleton created by `Soul::load`,
+/// plus any identity bindings in its managed block.
+fn is_bare_soul(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("- **") && !l.starts_with("<!-- mymolt:"))
+        .all(|l| l == "# Soul" || l == "## Identity Bindings")
+}
+
+/// Write the onboarding templates into the workspace.
+pub fn scaffold(workspace_dir: &Path, profile: &OnboardingProfile) -> Result<ScaffoldReport> {
+    let mut report = ScaffoldReport::default();
+
+    let soul_path = workspace_dir.join("SOUL.md");
+    match fs::read_to_string(&soul_path) {
+        Ok(existing) if !is_bare_soul(&existing) => report.skipped.push("SOUL.md".into()),
+        existing => {
+            let bindings: String = existing
+                .unwrap_or_default()
+                .lines()
+                .filter(|l| l.trim_start().starts_with("- **"))
+                .fold(String::new(), |mut out, l| {
+                    let _ = writeln!(out, "{l}");
+                    out
+                });
+            fs::create_dir_all(workspace_dir)?;
+            fs::write(&soul_path, soul_skeleton(profile) + &bindings)?;
+            report.created.push("SOUL.md".into());
+        }
+    }
+
+    if profile.example_skills {
+        let skills_dir = crate::skills::skills_dir(workspace_dir);
+        for (name, content) in example_skills() {
+            if name == "homework-helper" && profile.preset != HouseholdPreset::Family {
+                continue;
+            }
+            let dir = skills_dir.join(name);
+            let rel = format!("skills/{name}/SKILL.md");
+            if dir.exists() {
+                report.skipped.push(rel);
+                continue;
+            }
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join("SKILL.md"), content)?;
+            report.created.push(rel);
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(preset: HouseholdPreset) -> OnboardingProfile {
+        OnboardingProfile {
+            agent_name: "Molty".into(),
+            user_name: "Helga".into(),
+            preset,
+            example_skills: true,
+            ..OnboardingProfile::default()
+        }
+    }
+
+    #[test]
+    fn scaffold_replaces_bare_soul_and_keeps_bindings() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("SOUL.md"),
+            "# Soul\n\n## Identity Bindings\n\n- **Google**: 123 (Level 2)\n",
+        )
+        .unwrap();
+
+        let report = scaffold(tmp.path(), &profile(HouseholdPreset::SeniorCare)).unwrap();
+        let soul = fs::read_to_string(tmp.path().join("SOUL.md")).unwrap();
+
+        assert!(report.created.contains(&"SOUL.md".to_string()));
+        assert!(soul.contains("## How I Communicate"));
+        assert!(soul.contains("short sentences"));
+        assert!(soul.contains("- **Google**: 123 (Level 2)"));
+        assert!(tmp.path().join("skills/daily-briefing/SKILL.md").exists());
+        assert!(!tmp.path().join("skills/homework-helper").exists());
+    }
+
+    #[test]
+    fn scaffold_never_overwrites_user_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join("SOUL.md"),
+            "# Mine\n\n## Rules\nBe brief.\n",
+        )
+        .unwrap();
+        fs::create_dir_all(tmp.path().join("skills/shopping-list")).unwrap();
+
+        let report = scaffold(tmp.path(), &profile(HouseholdPreset::Family)).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("SOUL.md")).unwrap(),
+            "# Mine\n\n## Rules\nBe brief.\n"
+        );
+        assert!(report.skipped.contains(&"SOUL.md".to_string()));
+        assert!(report
+            .skipped
+            .contains(&"skills/shopping-list/SKILL.md".to_string()));
+        assert!(report
+            .created
+            .contains(&"skills/homework-helper/SKILL.md".to_string()));
+    }
+
+    #[test]
+    fn starter_jobs_have_valid_schedules() {
+        for preset in HouseholdPreset::ALL {
+            for (expr, _) in starter_jobs(preset) {
+                // Crontab syntax gains a seconds field in `cron::add_job`.
+                let normalized = format!("0 {expr}");
+                assert!(
+                    <cron::Schedule as std::str::FromStr>::from_str(&normalized).is_ok(),
+                    "{expr}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn completion_marker_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(load_state(tmp.path()).is_none());
+        mark_complete(tmp.path(), HouseholdPreset::Family).unwrap();
+        assert_eq!(
+            load_state(tmp.path()).unwrap().preset,
+            HouseholdPreset::Family
+        );
+    }
+}
@@ -5,8 +5,8 @@
 use crate::config::schema::{IrcConfig, WhatsAppConfig};
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    HeartbeatConfig, IMessageConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, TelegramConfig, WebhookConfig,
+    HeartbeatConfig, IMessageConfig, KeyBackend, MatrixConfig, MemoryConfig, ObservabilityConfig,
+    RuntimeConfig, SecretsConfig, SlackConfig, SovereignConfig, TelegramConfig, WebhookConfig,
 };
 use crate::hardware::{self, HardwareConfig};
 use anyhow::{Context, Result};
@@ -124,13 +124,25 @@ pub fn run_wizard() -> Result<Config> {
         secrets: secrets_config,
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
+        db_query: crate::config::DbQueryConfig::default(),
+        feeds: crate::config::FeedsConfig::default(),
+        plugins: crate::config::PluginsConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         hardware: hardware_config,
         agents: std::collections::HashMap::new(),
         security: crate::config::SecurityConfig::default(),
         stt: crate::config::SttConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        vision: crate::config::VisionConfig::default(),
         mcp: crate::config::McpConfig::default(),
         family: crate::config::FamilyConfig::default(),
+        sync: crate::config::SyncConfig::default(),
+        capture: crate::config::CaptureConfig::default(),
+        location: crate::config::LocationConfig::default(),
+        executor: crate::config::ExecutorConfig::default(),
+        automations: crate::config::AutomationsConfig::default(),
+        agent: crate::config::AgentConfig::default(),
+        ingest: crate::config::IngestConfig::default(),
     };
 
     println!(
@@ -299,6 +311,16 @@ pub fn run_quick_setup(
             0
         },
         chunk_max_tokens: 512,
+        sensitivity_languages: vec!["en".into(), "de".into()],
+        sensitivity_extra_keywords: Vec::new(),
+        dedup: true,
+        dedup_similarity: 0.95,
+        similarity_weight: 1.0,
+        importance_weight: 0.2,
+        recency_weight: 0.1,
+        recency_half_life_days: 30.0,
+        encrypt_at_rest: false,
+        sovereign: SovereignConfig::default(),
     };
 
     let config = Config {
@@ -322,13 +344,25 @@ pub fn run_quick_setup(
         secrets: SecretsConfig::default(),
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
+        db_query: crate::config::DbQueryConfig::default(),
+        feeds: crate::config::FeedsConfig::default(),
+        plugins: crate::config::PluginsConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         hardware: HardwareConfig::default(),
         agents: std::collections::HashMap::new(),
         security: crate::config::SecurityConfig::default(),
         stt: crate::config::SttConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        vision: crate::config::VisionConfig::default(),
         mcp: crate::config::McpConfig::default(),
         family: crate::config::FamilyConfig::default(),
+        sync: crate::config::SyncConfig::default(),
+        capture: crate::config::CaptureConfig::default(),
+        location: crate::config::LocationConfig::default(),
+        executor: crate::config::ExecutorConfig::default(),
+        automations: crate::config::AutomationsConfig::default(),
+        agent: crate::config::AgentConfig::default(),
+        ingest: crate::config::IngestConfig::default(),
     };
 
     config.save()?;
@@ -1725,7 +1759,10 @@ fn setup_tool_mode() -> Result<(ComposioConfig, SecretsConfig)> {
         .default(true)
         .interact()?;
 
-    let secrets_config = SecretsConfig { encrypt };
+    let secrets_config = SecretsConfig {
+        encrypt,
+        key_backend: KeyBackend::default(),
+    };
 
     if encrypt {
         println!(
@@ -2086,6 +2123,16 @@ fn setup_memory() -> Result<MemoryConfig> {
         keyword_weight: 0.3,
         embedding_cache_size: if backend == "sqlite" { 10000 } else { 0 },
         chunk_max_tokens: 512,
+        sensitivity_languages: vec!["en".into(), "de".into()],
+        sensitivity_extra_keywords: Vec::new(),
+        dedup: true,
+        dedup_similarity: 0.95,
+        similarity_weight: 1.0,
+        importance_weight: 0.2,
+        recency_weight: 0.1,
+        recency_half_life_days: 30.0,
+        encrypt_at_rest: false,
+        sovereign: SovereignConfig::default(),
     })
 }
 
@@ -2109,6 +2156,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
         email: None,
         irc: None,
         lark: None,
+        message_dedup_ttl_secs: 86_400,
+        groups: Vec::new(),
     };
 
     loop {
@@ -2270,6 +2319,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                 config.telegram = Some(TelegramConfig {
                     bot_token: token,
                     allowed_users,
+                    mode: crate::config::TelegramMode::default(),
                 });
             }
             1 => {
@@ -2361,6 +2411,9 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     guild_id: if guild.is_empty() { None } else { Some(guild) },
                     allowed_users,
                     listen_to_bots: false,
+                    allowed_guilds: vec![],
+                    allowed_channels: vec![],
+                    gateway: false,
                 });
             }
             2 => {
@@ -2603,6 +2656,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     access_token,
                     room_id,
                     allowed_users,
+                    e2ee: false,
                 });
             }
             5 => {
@@ -3224,8 +3278,11 @@ fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()>
            - Use when: inspecting project files, configs, or logs.\n\
            - Don't use when: you only need a quick string search (prefer targeted search first).\n\
          - **file_write** — Write file contents\n\
-           - Use when: applying focused edits, scaffolding files, or updating docs/code.\n\
-           - Don't use when: unsure about side effects or when the file should remain user-owned.\n\
+           - Use when: creating new files, scaffolding, or replacing a file entirely.\n\
+           - Don't use when: changing part of an existing file (use file_patch), unsure about side effects, or when the file should remain user-owned.\n\
+         - **file_patch** — Edit part of a file with a unified diff or search/replace blocks\n\
+           - Use when: applying focused edits to existing code or docs; preview with dry_run first.\n\
+           - Don't use when: creating a new file.\n\
          - **memory_store** — Save to memory\n\
            - Use when: preserving durable preferences, decisions, or key context.\n\
            - Don't use when: info is transient, noisy, or sensitive without explicit need.\n\
@@ -3899,6 +3956,7 @@ mod tests {
             "shell",
             "file_read",
             "file_write",
+            "file_patch",
             "memory_store",
             "memory_recall",
             "memory_forget",
@@ -2,7 +2,14 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::providers::traits::{ChatResponse as ProviderChatResponse, Provider};
+//! Anthropic Messages API, with native tool calling: tools are offered as
+//! `tools` definitions and `tool_use` blocks in the reply become
+//! [`ToolCall`]s.
+
+use crate::providers::traits::{
+    ChatMessage, ChatResponse as ProviderChatResponse, Provider, ToolCall,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -21,6 +28,8 @@ struct ChatRequest {
     system: Option<String>,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<ToolDefinition>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +38,13 @@ struct Message {
     content: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ToolDefinition {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiChatResponse {
     content: Vec<ContentBlock>,
@@ -36,7 +52,93 @@ struct ApiChatResponse {
 
 #[derive(Debug, Deserialize)]
 struct ContentBlock {
+    // "text" or "tool_use"
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
     text: String,
+    // `tool_use` blocks
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    input: Option<serde_json::Value>,
+}
+
+impl From<&ToolSpec> for ToolDefinition {
+    fn from(spec: &ToolSpec) -> Self {
+        Self {
+            name: spec.name.clone(),
+            description: spec.description.clone(),
+            input_schema: spec.parameters.clone(),
+        }
+    }
+}
+
+/// The system prompt and the turns of `messages`. Anthropic takes the
+/// system prompt apart and wants turns to alternate, so consecutive turns
+/// of one role are joined.
+fn split_messages(messages: &[ChatMessage]) -> (Option<String>, Vec<Message>) {
+    let mut system: Option<String> = None;
+    let mut turns: Vec<Message> = Vec::new();
+    for message in messages {
+        if message.role == "system" {
+            match &mut system {
+                Some(prompt) => {
+                    prompt.push_str("\n\n");
+                    prompt.push_str(&message.content);
+                }
+                None => system = Some(message.content.clone()),
+            }
+            continue;
+        }
+        let role = if message.role == "assistant" {
+            "assistant"
+        } else {
+            "user"
+        };
+        match turns.last_mut() {
+            Some(last) if last.role == role => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&message.content);
+            }
+            _ => turns.push(Message {
+                role: role.to_string(),
+                content: message.content.clone(),
+            }),
+        }
+    }
+    (system, turns)
+}
+
+fn map_response(response: ApiChatResponse) -> Option<ProviderChatResponse> {
+    let mut text = Vec::new();
+    let mut tool_calls = Vec::new();
+    for (index, block) in response.content.into_iter().enumerate() {
+        match block.kind.as_str() {
+            "tool_use" => {
+                let Some(name) = block.name else { continue };
+                tool_calls.push(ToolCall {
+                    id: block.id.unwrap_or_else(|| format!("call_{index}")),
+                    name,
+                    arguments: block
+                        .input
+                        .unwrap_or_else(|| serde_json::json!({}))
+                        .to_string(),
+                });
+            }
+            _ if !block.text.is_empty() => text.push(block.text),
+            _ => {}
+        }
+    }
+    if text.is_empty() && tool_calls.is_empty() {
+        return None;
+    }
+    Some(ProviderChatResponse {
+        text: (!text.is_empty()).then(|| text.join("\n")),
+        tool_calls,
+    })
 }
 
 impl AnthropicProvider {
@@ -66,40 +168,20 @@ impl AnthropicProvider {
     fn is_setup_token(token: &str) -> bool {
         token.starts_with("sk-ant-oat01-")
     }
-}
 
-#[async_trait]
-impl Provider for AnthropicProvider {
-    async fn chat_with_system(
-        &self,
-        system_prompt: Option<&str>,
-        message: &str,
-        model: &str,
-        temperature: f64,
-    ) -> anyhow::Result<ProviderChatResponse> {
+    async fn send(&self, request: &ChatRequest) -> anyhow::Result<ProviderChatResponse> {
         let credential = self.credential.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
             )
         })?;
 
-        let request = ChatRequest {
-            model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt.map(ToString::to_string),
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: message.to_string(),
-            }],
-            temperature,
-        };
-
         let mut request = self
             .client
             .post(format!("{}/v1/messages", self.base_url))
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
-            .json(&request);
+            .json(request);
 
         if Self::is_setup_token(credential) {
             request = request.header("Authorization", format!("Bearer {credential}"));
@@ -115,12 +197,64 @@ impl Provider for AnthropicProvider {
 
         let chat_response: ApiChatResponse = response.json().await?;
 
-        chat_response
-            .content
-            .into_iter()
-            .next()
-            .map(|c| ProviderChatResponse::with_text(c.text))
-            .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
+        map_response(chat_response).ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
+    }
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let request = ChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system: system_prompt.map(ToString::to_string),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: message.to_string(),
+            }],
+            temperature,
+            tools: Vec::new(),
+        };
+        self.send(&request).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        self.chat_with_tools(messages, &[], model, temperature)
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let (system, messages) = split_messages(messages);
+        let request = ChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system,
+            messages,
+            temperature,
+            tools: tools.iter().map(ToolDefinition::from).collect(),
+        };
+        self.send(&request).await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        true
     }
 }
 
@@ -216,6 +350,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.7,
+            tools: Vec::new(),
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(
@@ -237,6 +372,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.7,
+            tools: Vec::new(),
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"system\":\"You are MyMolt\""));
@@ -276,9 +412,67 @@ mod tests {
                 system: None,
                 messages: vec![],
                 temperature: temp,
+                tools: Vec::new(),
             };
             let json = serde_json::to_string(&req).unwrap();
             assert!(json.contains(&format!("{temp}")));
         }
     }
+
+    #[test]
+    fn tools_serialize_as_definitions() {
+        let spec = ToolSpec {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({"type": "object", "properties": {"command": {"type": "string"}}}),
+            output_schema: None,
+        };
+        let req = ChatRequest {
+            model: "claude-sonnet-4".to_string(),
+            max_tokens: 4096,
+            system: None,
+            messages: vec![],
+            temperature: 0.0,
+            tools: vec![ToolDefinition::from(&spec)],
+        };
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["tools"][0]["name"], "shell");
+        assert_eq!(
+            json["tools"][0]["input_schema"]["properties"]["command"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn tool_use_blocks_become_tool_calls() {
+        let json = r#"{"content":[
+            {"type":"text","text":"Checking."},
+            {"type":"tool_use","id":"toolu_01","name":"shell","input":{"command":"pwd"}}
+        ]}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        let mapped = map_response(resp).unwrap();
+        assert_eq!(mapped.text.as_deref(), Some("Checking."));
+        assert_eq!(mapped.tool_calls.len(), 1);
+        assert_eq!(mapped.tool_calls[0].id, "toolu_01");
+        assert_eq!(mapped.tool_calls[0].name, "shell");
+        assert_eq!(mapped.tool_calls[0].arguments, r#"{"command":"pwd"}"#);
+
+        let empty: ApiChatResponse = serde_json::from_str(r#"{"content":[]}"#).unwrap();
+        assert!(map_response(empty).is_none());
+    }
+
+    #[test]
+    fn history_keeps_system_apart_and_alternates_turns() {
+        let (system, turns) = split_messages(&[
+            ChatMessage::system("You are MyMolt"),
+            ChatMessage::user("hi"),
+            ChatMessage::assistant("<tool_call>{}</tool_call>"),
+            ChatMessage::user("[Tool results]"),
+            ChatMessage::user("and then?"),
+        ]);
+        assert_eq!(system.as_deref(), Some("You are MyMolt"));
+        let roles: Vec<_> = turns.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user"]);
+        assert_eq!(turns[2].content, "[Tool results]\n\nand then?");
+    }
 }
@@ -0,0 +1,356 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Per-provider circuit breakers.
+//!
+//! A provider whose calls keep failing is taken out of rotation for a
+//! cooldown, so [`ReliableProvider`](super::reliable::ReliableProvider)
+//! fails over to the next configured provider at once instead of retrying
+//! a dead endpoint. Once the cooldown is over a single trial call goes
+//! through (half-open); its outcome closes the circuit or opens it again.
+//!
+//! Breakers are shared by provider name across the process, so the gateway
+//! and every channel see the same state. `/health` and the OTel metrics
+//! read it through [`snapshot`].
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Calls kept per provider for the error rate and health score.
+const OUTCOME_WINDOW: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through.
+    Closed,
+    /// Calls are skipped until the cooldown is over.
+    Open,
+    /// One trial call decides whether the circuit closes again.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// Numeric form for metrics: 0 closed, 1 half-open, 2 open.
+    pub fn level(self) -> u64 {
+        match self {
+            Self::Closed => 0,
+            Self::HalfOpen => 1,
+            Self::Open => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerSettings {
+    /// Consecutive failures that open the circuit; 0 never opens it.
+    pub failure_threshold: u32,
+    /// How long an open circuit skips the provider.
+    pub cooldown: Duration,
+}
+
+impl BreakerSettings {
+    /// Tracks outcomes but never opens.
+    pub fn disabled() -> Self {
+        Self {
+            failure_threshold: 0,
+            cooldown: Duration::ZERO,
+        }
+    }
+}
+
+/// State of one provider's breaker, as shown by `/health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub provider: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Share of failed calls among the last calls (0.0–1.0)
+    pub error_rate: f64,
+    /// 1.0 healthy … 0.0 unusable; 0 while the circuit is open
+    pub health_score: f64,
+    /// Times the circuit has opened
+    pub trips: u64,
+    /// Seconds until an open circuit lets a trial call through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    settings: BreakerSettings,
+    state: CircuitState,
+    consecutive_failures: u32,
+    /// `true` for a success, newest last
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    /// When the half-open trial call started; a trial that never reports
+    /// back (e.g. a cancelled turn) goes stale after one cooldown.
+    trial_started: Option<Instant>,
+    trips: u64,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    provider: String,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(provider: &str, settings: BreakerSettings) -> Self {
+        Self {
+            provider: provider.to_string(),
+            inner: Mutex::new(BreakerInner {
+                settings,
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                outcomes: VecDeque::with_capacity(OUTCOME_WINDOW),
+                opened_at: None,
+                trial_started: None,
+                trips: 0,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn set_settings(&self, settings: BreakerSettings) {
+        self.lock().settings = settings;
+    }
+
+    /// Whether a call may go to the provider now. Moves an open circuit
+    /// whose cooldown is over to half-open and admits its trial call.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.lock();
+        let cooldown = inner.settings.cooldown;
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                if inner.opened_at.is_some_and(|at| at.elapsed() < cooldown) {
+                    return false;
+                }
+                inner.state = CircuitState::HalfOpen;
+                inner.trial_started = Some(Instant::now());
+                tracing::info!(
+                    provider = self.provider,
+                    "Circuit half-open, sending a trial call"
+                );
+                true
+            }
+            CircuitState::HalfOpen => {
+                if inner
+                    .trial_started
+                    .is_some_and(|at| at.elapsed() < cooldown)
+                {
+                    return false;
+                }
+                inner.trial_started = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        push_outcome(&mut inner.outcomes, true);
+        inner.consecutive_failures = 0;
+        if inner.state != CircuitState::Closed {
+            tracing::info!(
+                provider = self.provider,
+                "Circuit closed, provider recovered"
+            );
+        }
+        inner.state = CircuitState::Closed;
+        inner.opened_at = None;
+        inner.trial_started = None;
+    }
+
+    /// Count a failure that says something about the provider (server
+    /// errors, timeouts, rate limits), not about the request.
+    pub fn record_failure(&self, error: &anyhow::Error) {
+        let mut inner = self.lock();
+        push_outcome(&mut inner.outcomes, false);
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let threshold = inner.settings.failure_threshold;
+        let trips = match inner.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => threshold > 0 && inner.consecutive_failures >= threshold,
+            CircuitState::Open => false,
+        };
+        if trips {
+            inner.state = CircuitState::Open;
+            inner.opened_at = Some(Instant::now());
+            inner.trial_started = None;
+            inner.trips += 1;
+            tracing::warn!(
+                provider = self.provider,
+                failures = inner.consecutive_failures,
+                cooldown_secs = inner.settings.cooldown.as_secs(),
+                "Circuit open, failing over: {}",
+                super::sanitize_api_error(&error.to_string())
+            );
+        }
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.lock();
+        let failed = inner.outcomes.iter().filter(|ok| !**ok).count();
+        #[allow(clippy::cast_precision_loss)]
+        let error_rate = if inner.outcomes.is_empty() {
+            0.0
+        } else {
+            failed as f64 / inner.outcomes.len() as f64
+        };
+        let retry_in_secs = match (inner.state, inner.opened_at) {
+            (CircuitState::Open, Some(at)) => Some(
+                inner
+                    .settings
+                    .cooldown
+                    .saturating_sub(at.elapsed())
+                    .as_secs(),
+            ),
+            _ => None,
+        };
+        BreakerStatus {
+            provider: self.provider.clone(),
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            error_rate,
+            health_score: if inner.state == CircuitState::Open {
+                0.0
+            } else {
+                1.0 - error_rate
+            },
+            trips: inner.trips,
+            retry_in_secs,
+        }
+    }
+}
+
+fn push_outcome(outcomes: &mut VecDeque<bool>, ok: bool) {
+    if outcomes.len() == OUTCOME_WINDOW {
+        outcomes.pop_front();
+    }
+    outcomes.push_back(ok);
+}
+
+static BREAKERS: OnceLock<Mutex<BTreeMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+
+fn breakers() -> &'static Mutex<BTreeMap<String, Arc<CircuitBreaker>>> {
+    BREAKERS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// The process-wide breaker for `provider`, created on first use. Later
+/// calls apply `settings`, so a config reload takes effect.
+pub fn shared(provider: &str, settings: BreakerSettings) -> Arc<CircuitBreaker> {
+    let mut map = breakers().lock().unwrap_or_else(PoisonError::into_inner);
+    let breaker = map
+        .entry(provider.to_string())
+        .or_insert_with(|| Arc::new(CircuitBreaker::new(provider, settings)));
+    breaker.set_settings(settings);
+    Arc::clone(breaker)
+}
+
+/// Every shared breaker, by provider name.
+pub fn snapshot() -> Vec<BreakerStatus> {
+    breakers()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values()
+        .map(|breaker| breaker.status())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(threshold: u32, cooldown: Duration) -> BreakerSettings {
+        BreakerSettings {
+            failure_threshold: threshold,
+            cooldown,
+        }
+    }
+
+    fn fail(breaker: &CircuitBreaker) {
+        breaker.record_failure(&anyhow::anyhow!("503 Service Unavailable"));
+    }
+
+    #[test]
+    fn opens_after_threshold_consecutive_failures() {
+        let breaker = CircuitBreaker::new("p", settings(3, Duration::from_mins(1)));
+        fail(&breaker);
+        fail(&breaker);
+        breaker.record_success();
+        fail(&breaker);
+        fail(&breaker);
+        assert!(breaker.allow());
+        fail(&breaker);
+
+        let status = breaker.status();
+        assert_eq!(status.state, CircuitState::Open);
+        assert_eq!(status.trips, 1);
+        assert!(status.health_score.abs() < f64::EPSILON);
+        assert!((status.error_rate - 5.0 / 6.0).abs() < 1e-9);
+        assert!(status.retry_in_secs.is_some());
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn half_open_trial_closes_or_reopens() {
+        let breaker = CircuitBreaker::new("p", settings(1, Duration::ZERO));
+        fail(&breaker);
+        assert_eq!(breaker.status().state, CircuitState::Open);
+
+        // Cooldown over: one trial call, which fails and reopens
+        assert!(breaker.allow());
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        fail(&breaker);
+        assert_eq!(breaker.status().state, CircuitState::Open);
+        assert_eq!(breaker.status().trips, 2);
+
+        assert!(breaker.allow());
+        breaker.record_success();
+        let status = breaker.status();
+        assert_eq!(status.state, CircuitState::Closed);
+        assert_eq!(status.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn half_open_admits_one_trial_at_a_time() {
+        let breaker = CircuitBreaker::new("p", settings(1, Duration::from_mins(1)));
+        fail(&breaker);
+        breaker.lock().opened_at = Instant::now().checked_sub(Duration::from_mins(2));
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn disabled_breaker_never_opens() {
+        let breaker = CircuitBreaker::new("p", BreakerSettings::disabled());
+        for _ in 0..50 {
+            fail(&breaker);
+        }
+        assert!(breaker.allow());
+        assert_eq!(breaker.status().state, CircuitState::Closed);
+        assert!((breaker.status().error_rate - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn shared_breakers_are_one_per_provider() {
+        let name = format!("circuit-test-{}", uuid::Uuid::new_v4());
+        let first = shared(&name, settings(2, Duration::from_mins(1)));
+        let second = shared(&name, settings(1, Duration::from_mins(1)));
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // The later settings apply
+        fail(&first);
+        let status = snapshot().into_iter().find(|s| s.provider == name).unwrap();
+        assert_eq!(status.state, CircuitState::Open);
+    }
+}
@@ -116,6 +116,37 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
+}
+
+/// `response_format` asking for JSON that matches a schema.
+#[derive(Debug, Serialize)]
+pub(crate) struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    json_schema: JsonSchemaFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSchemaFormat {
+    name: &'static str,
+    schema: serde_json::Value,
+    /// Strict mode rejects schemas with optional properties
+    strict: bool,
+}
+
+impl ResponseFormat {
+    pub(crate) fn json_schema(schema: &serde_json::Value) -> Self {
+        Self {
+            kind: "json_schema",
+            json_schema: JsonSchemaFormat {
+                name: "response",
+                schema: schema.clone(),
+                strict: false,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -316,16 +347,13 @@ impl OpenAiCompatibleProvider {
             .map(ChatResponse::with_text)
             .ok_or_else(|| anyhow::anyhow!("No response from {} Responses API", self.name))
     }
-}
 
-#[async_trait]
-impl Provider for OpenAiCompatibleProvider {
-    async fn chat_with_system(
+    async fn send_history(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
+        messages: &[ChatMessage],
         model: &str,
         temperature: f64,
+        response_format: Option<ResponseFormat>,
     ) -> anyhow::Result<ChatResponse> {
         let api_key = self.api_key.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
@@ -334,28 +362,22 @@ impl Provider for OpenAiCompatibleProvider {
             )
         })?;
 
-        let mut messages = Vec::new();
-
-        if let Some(sys) = system_prompt {
-            messages.push(Message {
-                role: "system".to_string(),
-                content: sys.to_string(),
-            });
-        }
-
-        messages.push(Message {
-            role: "user".to_string(),
-            content: message.to_string(),
-        });
+        let api_messages: Vec<Message> = messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.content.clone(),
+            })
+            .collect();
 
         let request = ChatRequest {
             model: model.to_string(),
-            messages,
+            messages: api_messages,
             temperature,
+            response_format,
         };
 
         let url = self.chat_completions_url();
-
         let response = self
             .apply_auth_header(self.client.post(&url).json(&request), api_key)
             .send()
@@ -363,22 +385,42 @@ impl Provider for OpenAiCompatibleProvider {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error = response.text().await?;
-            let sanitized = super::sanitize_api_error(&error);
 
+            // Not every compatible API knows `response_format`
+            if request.response_format.is_some()
+                && matches!(
+                    status,
+                    reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+                )
+            {
+                tracing::debug!("{} rejected response_format, asking without it", self.name);
+                return self.chat_with_history(messages, model, temperature).await;
+            }
+
+            // Mirror chat_with_system: 404 may mean this provider uses the Responses API
             if status == reqwest::StatusCode::NOT_FOUND {
-                return self
-                    .chat_via_responses(api_key, system_prompt, message, model)
-                    .await
-                    .map_err(|responses_err| {
-                        anyhow::anyhow!(
-                            "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
-                            self.name
+                // Extract system prompt and last user message for responses fallback
+                let system = messages.iter().find(|m| m.role == "system");
+                let last_user = messages.iter().rfind(|m| m.role == "user");
+                if let Some(user_msg) = last_user {
+                    return self
+                        .chat_via_responses(
+                            api_key,
+                            system.map(|m| m.content.as_str()),
+                            &user_msg.content,
+                            model,
                         )
-                    });
+                        .await
+                        .map_err(|responses_err| {
+                            anyhow::anyhow!(
+                                "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
+                                self.name
+                            )
+                        });
+                }
             }
 
-            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+            return Err(super::api_error(&self.name, response).await);
         }
 
         let chat_response: ApiChatResponse = response.json().await?;
@@ -391,10 +433,14 @@ impl Provider for OpenAiCompatibleProvider {
 
         Ok(map_response_message(choice.message))
     }
+}
 
-    async fn chat_with_history(
+#[async_trait]
+impl Provider for OpenAiCompatibleProvider {
+    async fn chat_with_system(
         &self,
-        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+        message: &str,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
@@ -405,21 +451,29 @@ impl Provider for OpenAiCompatibleProvider {
             )
         })?;
 
-        let api_messages: Vec<Message> = messages
-            .iter()
-            .map(|m| Message {
-                role: m.role.clone(),
-                content: m.content.clone(),
-            })
-            .collect();
+        let mut messages = Vec::new();
+
+        if let Some(sys) = system_prompt {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: sys.to_string(),
+            });
+        }
+
+        messages.push(Message {
+            role: "user".to_string(),
+            content: message.to_string(),
+        });
 
         let request = ChatRequest {
             model: model.to_string(),
-            messages: api_messages,
+            messages,
             temperature,
+            response_format: None,
         };
 
         let url = self.chat_completions_url();
+
         let response = self
             .apply_auth_header(self.client.post(&url).json(&request), api_key)
             .send()
@@ -427,31 +481,22 @@ impl Provider for OpenAiCompatibleProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let error = response.text().await?;
+            let sanitized = super::sanitize_api_error(&error);
 
-            // Mirror chat_with_system: 404 may mean this provider uses the Responses API
             if status == reqwest::StatusCode::NOT_FOUND {
-                // Extract system prompt and last user message for responses fallback
-                let system = messages.iter().find(|m| m.role == "system");
-                let last_user = messages.iter().rfind(|m| m.role == "user");
-                if let Some(user_msg) = last_user {
-                    return self
-                        .chat_via_responses(
-                            api_key,
-                            system.map(|m| m.content.as_str()),
-                            &user_msg.content,
-                            model,
+                return self
+                    .chat_via_responses(api_key, system_prompt, message, model)
+                    .await
+                    .map_err(|responses_err| {
+                        anyhow::anyhow!(
+                            "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
+                            self.name
                         )
-                        .await
-                        .map_err(|responses_err| {
-                            anyhow::anyhow!(
-                                "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
-                                self.name
-                            )
-                        });
-                }
+                    });
             }
 
-            return Err(super::api_error(&self.name, response).await);
+            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
         }
 
         let chat_response: ApiChatResponse = response.json().await?;
@@ -464,6 +509,27 @@ impl Provider for OpenAiCompatibleProvider {
 
         Ok(map_response_message(choice.message))
     }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.send_history(messages, model, temperature, None).await
+    }
+
+    async fn chat_with_schema(
+        &self,
+        messages: &[ChatMessage],
+        schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let format = ResponseFormat::json_schema(schema);
+        self.send_history(messages, model, temperature, Some(format))
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -522,6 +588,7 @@ mod tests {
                 },
             ],
             temperature: 0.7,
+            response_format: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("llama-3.3-70b"));
@@ -529,6 +596,33 @@ mod tests {
         assert!(json.contains("user"));
     }
 
+    #[test]
+    fn response_format_requests_json_schema() {
+        let req = ChatRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![],
+            temperature: 0.0,
+            response_format: Some(ResponseFormat::json_schema(
+                &serde_json::json!({"type": "object"}),
+            )),
+        };
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["response_format"]["type"], "json_schema");
+        assert_eq!(
+            json["response_format"]["json_schema"]["schema"]["type"],
+            "object"
+        );
+
+        let plain = ChatRequest {
+            response_format: None,
+            ..req
+        };
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("response_format")
+            .is_none());
+    }
+
     #[test]
     fn response_deserializes() {
         let json = r#"{"choices":[{"message":{"content":"Hello from Venice!"}}]}"#;
@@ -6,8 +6,12 @@
 //! - Direct API key (`GEMINI_API_KEY` env var or config)
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
+//!
+//! Tools are offered natively as `functionDeclarations`; `functionCall`
+//! parts in the reply become [`ToolCall`]s.
 
-use crate::providers::traits::{ChatResponse, Provider};
+use crate::providers::traits::{ChatMessage, ChatResponse, Provider, ToolCall};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use directories::UserDirs;
 use reqwest::Client;
@@ -65,6 +69,8 @@ struct GenerateContentRequest {
     system_instruction: Option<Content>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<GeminiTool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -79,6 +85,20 @@ struct Part {
     text: String,
 }
 
+#[derive(Debug, Serialize)]
+struct GeminiTool {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionDeclaration {
+    name: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Serialize)]
 struct GenerationConfig {
     temperature: f64,
@@ -105,6 +125,15 @@ struct CandidateContent {
 #[derive(Debug, Deserialize)]
 struct ResponsePart {
     text: Option<String>,
+    #[serde(rename = "functionCall")]
+    function_call: Option<FunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -112,6 +141,119 @@ struct ApiError {
     message: String,
 }
 
+/// Gemini accepts only an OpenAPI subset of JSON Schema: drop the keywords
+/// it rejects, and the parameters of tools that take none.
+fn gemini_schema(schema: &serde_json::Value) -> Option<serde_json::Value> {
+    fn strip(value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => map
+                .iter()
+                .filter(|(key, _)| {
+                    !matches!(
+                        key.as_str(),
+                        "$schema" | "additionalProperties" | "default" | "examples"
+                    )
+                })
+                .map(|(key, value)| (key.clone(), strip(value)))
+                .collect(),
+            serde_json::Value::Array(items) => items.iter().map(strip).collect(),
+            other => other.clone(),
+        }
+    }
+    let no_properties = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+        .is_none_or(serde_json::Map::is_empty);
+    if no_properties {
+        return None;
+    }
+    Some(strip(schema))
+}
+
+fn function_declarations(tools: &[ToolSpec]) -> Vec<GeminiTool> {
+    if tools.is_empty() {
+        return Vec::new();
+    }
+    vec![GeminiTool {
+        function_declarations: tools
+            .iter()
+            .map(|spec| FunctionDeclaration {
+                name: spec.name.clone(),
+                description: spec.description.clone(),
+                parameters: gemini_schema(&spec.parameters),
+            })
+            .collect(),
+    }]
+}
+
+/// Turns of `messages` as Gemini contents (roles "user" and "model"), and
+/// the system messages joined into one instruction.
+fn split_messages(messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+    let mut system = Vec::new();
+    let mut contents: Vec<Content> = Vec::new();
+    for message in messages {
+        if message.role == "system" {
+            system.push(message.content.clone());
+            continue;
+        }
+        let role = if message.role == "assistant" {
+            "model"
+        } else {
+            "user"
+        };
+        match contents.last_mut() {
+            Some(last) if last.role.as_deref() == Some(role) => last.parts.push(Part {
+                text: message.content.clone(),
+            }),
+            _ => contents.push(Content {
+                role: Some(role.to_string()),
+                parts: vec![Part {
+                    text: message.content.clone(),
+                }],
+            }),
+        }
+    }
+    let system_instruction = (!system.is_empty()).then(|| Content {
+        role: None,
+        parts: vec![Part {
+            text: system.join("\n\n"),
+        }],
+    });
+    (system_instruction, contents)
+}
+
+/// Text and function calls of the first candidate.
+fn map_response(response: GenerateContentResponse) -> Option<ChatResponse> {
+    let parts = response
+        .candidates
+        .and_then(|c| c.into_iter().next())
+        .map(|c| c.content.parts)?;
+    let mut text = Vec::new();
+    let mut tool_calls = Vec::new();
+    for part in parts {
+        if let Some(call) = part.function_call {
+            tool_calls.push(ToolCall {
+                id: format!("call_{}", tool_calls.len()),
+                name: call.name,
+                arguments: if call.args.is_null() {
+                    "{}".to_string()
+                } else {
+                    call.args.to_string()
+                },
+            });
+        } else if let Some(part_text) = part.text {
+            text.push(part_text);
+        }
+    }
+    if text.is_empty() && tool_calls.is_empty() {
+        return None;
+    }
+    Some(ChatResponse {
+        text: (!text.is_empty()).then(|| text.concat()),
+        tool_calls,
+    })
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // GEMINI CLI TOKEN STRUCTURES
 // ══════════════════════════════════════════════════════════════════════════════
@@ -254,16 +396,11 @@ impl GeminiProvider {
             _ => req,
         }
     }
-}
 
-#[async_trait]
-impl Provider for GeminiProvider {
-    async fn chat_with_system(
+    async fn generate(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
         model: &str,
-        temperature: f64,
+        request: &GenerateContentRequest,
     ) -> anyhow::Result<ChatResponse> {
         let auth = self.auth.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
@@ -275,7 +412,39 @@ impl Provider for GeminiProvider {
             )
         })?;
 
-        // Build request
+        let url = Self::build_generate_content_url(model, auth);
+
+        let response = self
+            .build_generate_content_request(auth, &url, request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Gemini API error ({status}): {error_text}");
+        }
+
+        let result: GenerateContentResponse = response.json().await?;
+
+        // Check for API error in response body
+        if let Some(err) = result.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+
+        map_response(result).ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+    }
+}
+
+#[async_trait]
+impl Provider for GeminiProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
         let system_instruction = system_prompt.map(|sys| Content {
             role: None,
             parts: vec![Part {
@@ -295,36 +464,43 @@ impl Provider for GeminiProvider {
                 temperature,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
         };
+        self.generate(model, &request).await
+    }
 
-        let url = Self::build_generate_content_url(model, auth);
-
-        let response = self
-            .build_generate_content_request(auth, &url, &request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Gemini API error ({status}): {error_text}");
-        }
-
-        let result: GenerateContentResponse = response.json().await?;
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.chat_with_tools(messages, &[], model, temperature)
+            .await
+    }
 
-        // Check for API error in response body
-        if let Some(err) = result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
-        }
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (system_instruction, contents) = split_messages(messages);
+        let request = GenerateContentRequest {
+            contents,
+            system_instruction,
+            generation_config: GenerationConfig {
+                temperature,
+                max_output_tokens: 8192,
+            },
+            tools: function_declarations(tools),
+        };
+        self.generate(model, &request).await
+    }
 
-        // Extract text from response
-        result
-            .candidates
-            .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content.parts.into_iter().next())
-            .and_then(|p| p.text)
-            .map(ChatResponse::with_text)
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+    fn supports_native_tools(&self) -> bool {
+        true
     }
 }
 
@@ -449,6 +625,7 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
         };
 
         let request = provider
@@ -485,6 +662,7 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
         };
 
         let request = provider
@@ -514,6 +692,7 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -562,4 +741,77 @@ mod tests {
         assert!(response.error.is_some());
         assert_eq!(response.error.unwrap().message, "Invalid API key");
     }
+
+    #[test]
+    fn tools_serialize_as_function_declarations() {
+        let tools = function_declarations(&[
+            ToolSpec {
+                name: "shell".into(),
+                description: "Run a command".into(),
+                parameters: serde_json::json!({
+                    "$schema": "http://json-schema.org/draft-07/schema#",
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {"command": {"type": "string", "default": "ls"}},
+                    "required": ["command"]
+                }),
+                output_schema: None,
+            },
+            ToolSpec {
+                name: "memory_list".into(),
+                description: "List memories".into(),
+                parameters: serde_json::json!({"type": "object", "properties": {}}),
+                output_schema: None,
+            },
+        ]);
+        let json = serde_json::to_value(&tools).unwrap();
+        let declarations = &json[0]["functionDeclarations"];
+        assert_eq!(declarations[0]["name"], "shell");
+        let parameters = &declarations[0]["parameters"];
+        assert!(parameters.get("$schema").is_none());
+        assert!(parameters.get("additionalProperties").is_none());
+        assert!(parameters["properties"]["command"].get("default").is_none());
+        assert_eq!(parameters["required"][0], "command");
+        assert!(declarations[1].get("parameters").is_none());
+
+        assert!(function_declarations(&[]).is_empty());
+    }
+
+    #[test]
+    fn function_call_parts_become_tool_calls() {
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "parts": [
+                        {"text": "Let me look."},
+                        {"functionCall": {"name": "shell", "args": {"command": "pwd"}}},
+                        {"functionCall": {"name": "memory_list"}}
+                    ]
+                }
+            }]
+        }"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let mapped = map_response(response).unwrap();
+        assert_eq!(mapped.text.as_deref(), Some("Let me look."));
+        assert_eq!(mapped.tool_calls.len(), 2);
+        assert_eq!(mapped.tool_calls[0].id, "call_0");
+        assert_eq!(mapped.tool_calls[0].arguments, r#"{"command":"pwd"}"#);
+        assert_eq!(mapped.tool_calls[1].name, "memory_list");
+        assert_eq!(mapped.tool_calls[1].arguments, "{}");
+    }
+
+    #[test]
+    fn history_maps_roles_to_user_and_model() {
+        let (system, contents) = split_messages(&[
+            ChatMessage::system("You are MyMolt"),
+            ChatMessage::user("hi"),
+            ChatMessage::assistant("hello"),
+            ChatMessage::user("[Tool results]"),
+            ChatMessage::user("and then?"),
+        ]);
+        assert_eq!(system.unwrap().parts[0].text, "You are MyMolt");
+        let roles: Vec<_> = contents.iter().filter_map(|c| c.role.as_deref()).collect();
+        assert_eq!(roles, ["user", "model", "user"]);
+        assert_eq!(contents[2].parts.len(), 2);
+    }
 }
@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+use super::traits::{ChatMessage, ChatResponse};
+use super::Provider;
+use crate::config::{Config, LeakGuardMode};
+use crate::security::confirmation::ConfirmationGate;
+use crate::security::leak_guard::LeakGuard;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Whether every provider `config` may route to with `primary` runs on this
+/// machine, so prompts never leave it.
+pub fn all_local(config: &Config, primary: &str) -> bool {
+    std::iter::once(primary)
+        .chain(
+            config
+                .reliability
+                .fallback_providers
+                .iter()
+                .map(String::as_str),
+        )
+        .chain(config.model_routes.iter().map(|r| r.provider.as_str()))
+        .all(is_local)
+}
+
+fn is_local(name: &str) -> bool {
+    if name == "ollama" {
+        return true;
+    }
+    let Some(url) = name
+        .strip_prefix("custom:")
+        .or_else(|| name.strip_prefix("anthropic-custom:"))
+    else {
+        return false;
+    };
+    let Some(host) = reqwest::Url::parse(url).ok().and_then(|u| {
+        u.host_str()
+            .map(|h| h.trim_matches(['[', ']']).to_lowercase())
+    }) else {
+        return false;
+    };
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Provider wrapper that runs the leak guard over prompts before they are
+/// sent, refusing them or asking the user first.
+pub struct GuardedProvider {
+    inner: Arc<dyn Provider>,
+    guard: Arc<LeakGuard>,
+    confirm_gate: Option<Arc<ConfirmationGate>>,
+    audit: Option<Arc<AuditLogger>>,
+    /// Hashes of messages the user already let through, so a conversation
+    /// history is not confirmed again on every turn.
+    approved: Mutex<HashSet<u64>>,
+}
+
+impl GuardedProvider {
+    /// `inner` behind the leak guard, or `inner` itself when there is no
+    /// guard, it does not watch prompts or all providers are local.
+    pub fn wrap(
+        inner: Arc<dyn Provider>,
+        local: bool,
+        guard: Option<Arc<LeakGuard>>,
+        confirm_gate: Option<Arc<ConfirmationGate>>,
+        audit: Option<Arc<AuditLogger>>,
+    ) -> Arc<dyn Provider> {
+        match guard {
+            Some(guard) if !local && guard.watches_prompts() => Arc::new(Self {
+                inner,
+                guard,
+                confirm_gate,
+                audit,
+                approved: Mutex::new(HashSet::new()),
+            }),
+            _ => inner,
+        }
+    }
+
+    /// Refuse `text` if it carries a secret the user does not let through.
+    async fn check(&self, text: &str) -> anyhow::Result<()> {
+        let Some(finding) = self.guard.find(text) else {
+            return Ok(());
+        };
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self
+            .approved
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(&hash)
+        {
+            return Ok(());
+        }
+
+        let approved = match (self.guard.mode(), &self.confirm_gate) {
+            (LeakGuardMode::Confirm, Some(gate)) => {
+                let summary = format!("The prompt to the AI provider contains {finding}.");
+                gate.request("provider", &summary).await
+            }
+            _ => false,
+        };
+        tracing::warn!(approved, "Leak guard: prompt contains {finding}");
+        if let Some(audit) = &self.audit {
+            let event_type = if approved {
+                AuditEventType::SecurityEvent
+            } else {
+                AuditEventType::PolicyViolation
+            };
+            let _ = audit.log(
+                &AuditEvent::new(event_type)
+                    .with_actor("leak_guard".to_string(), None, None)
+                    .with_action(
+                        format!("leak:provider:{finding}"),
+                        "high".to_string(),
+                        approved,
+                        approved,
+                    ),
+            );
+        }
+        if !approved {
+            anyhow::bail!(
+                "Blocked by leak guard: the prompt contains {finding}, which may not leave this machine."
+            );
+        }
+        self.approved
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(hash);
+        Ok(())
+    }
+
+    /// Check the turns of a conversation. The system prompt is ours and
+    /// assistant turns came from the provider; everything else may carry
+    /// user or tool data.
+    async fn check_history(&self, messages: &[ChatMessage]) -> anyhow::Result<()> {
+        for message in messages
+            .iter()
+            .filter(|m| m.role != "system" && m.role != "assistant")
+        {
+            self.check(&message.content).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Provider for GuardedProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.check(message).await?;
+        self.inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.chat_with_tools(messages, &[], model, temperature)
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.check_history(messages).await?;
+        self.inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    async fn chat_with_schema(
+        &self,
+        messages: &[ChatMessage],
+        schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.check_history(messages).await?;
+        self.inner
+            .chat_with_schema(messages, schema, model, temperature)
+            .await
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LeakGuardConfig;
+    use crate::memory::sovereign::SensitivityScanner;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ChatResponse::with_text("ok"))
+        }
+    }
+
+    fn guard() -> Arc<LeakGuard> {
+        Arc::new(LeakGuard::new(
+            &LeakGuardConfig::default(),
+            SensitivityScanner::new(),
+            vec![(
+                "channels.telegram.bot_token".into(),
+                "123456:ABCdefGHI".into(),
+            )],
+        ))
+    }
+
+    #[tokio::test]
+    async fn prompts_with_secrets_are_not_sent() {
+        let inner = Arc::new(CountingProvider {
+            calls: AtomicUsize::new(0),
+        });
+        let provider = GuardedProvider::wrap(inner.clone(), false, Some(guard()), None, None);
+
+        let err = provider
+            .chat("my bot token is 123456:ABCdefGHI", "m", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'channels.telegram.bot_token'"));
+        let history = [
+            ChatMessage::system("You are helpful"),
+            ChatMessage::user("key: sk-abcdefghijklmnopqrstuvwxyz"),
+        ];
+        assert!(provider
+            .chat_with_history(&history, "m", 0.0)
+            .await
+            .is_err());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 0);
+
+        provider
+            .chat("What's the weather?", "m", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn local_providers_are_not_wrapped() {
+        assert!(is_local("ollama"));
+        assert!(is_local("custom:http://localhost:1234/v1"));
+        assert!(is_local("custom:http://127.0.0.1:8080"));
+        assert!(!is_local("custom:https://llm.example.com"));
+        assert!(!is_local("openrouter"));
+
+        let mut config = Config::default();
+        assert!(all_local(&config, "ollama"));
+        config.reliability.fallback_providers = vec!["openrouter".into()];
+        assert!(!all_local(&config, "ollama"));
+    }
+}
@@ -3,22 +3,28 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 pub mod anthropic;
+pub mod circuit;
 pub mod compatible;
 pub mod gemini;
+pub mod guarded;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+pub mod structured;
 pub mod traits;
 pub mod mock_voice;
 pub mod stt;
+pub mod tts;
+pub mod vision;
 
 #[allow(unused_imports)]
 pub use traits::{ChatMessage, ChatResponse, Provider, ToolCall};
 
 use compatible::{AuthStyle, OpenAiCompatibleProvider};
 use reliable::ReliableProvider;
+use std::sync::Arc;
 
 const MAX_API_ERROR_CHARS: usize = 200;
 
@@ -134,6 +140,7 @@ pub fn resolve_api_key(name: &str, api_key: Option<&str>) -> Option<String> {
         "opencode" | "opencode-zen" => vec!["OPENCODE_API_KEY"],
         "vercel" | "vercel-ai" => vec!["VERCEL_API_KEY"],
         "cloudflare" | "cloudflare-ai" => vec!["CLOUDFLARE_API_KEY"],
+        "elevenlabs" => vec!["ELEVENLABS_API_KEY"],
         _ => vec![],
     };
 
@@ -193,7 +200,7 @@ pub fn create_provider(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<
         "openai" => Ok(Box::new(openai::OpenAiProvider::new(key))),
         // Ollama is a local service that doesn't use API keys.
         // The api_key parameter is ignored to avoid it being misinterpreted as a base_url.
-        "ollama" => Ok(Box::new(ollama::OllamaProvider::new(None))),
+        "ollama" => Ok(Box::new(ollama::OllamaProvider::from_env())),
         "gemini" | "google" | "google-gemini" => {
             Ok(Box::new(gemini::GeminiProvider::new(key)))
         }
@@ -350,21 +357,26 @@ pub fn create_resilient_provider(
         reliability.provider_backoff_ms,
     )
     .with_api_keys(reliability.api_keys.clone())
-    .with_model_fallbacks(reliability.model_fallbacks.clone());
+    .with_model_fallbacks(reliability.model_fallbacks.clone())
+    .with_circuit_breakers(circuit::BreakerSettings {
+        failure_threshold: reliability.circuit_failure_threshold,
+        cooldown: std::time::Duration::from_secs(reliability.circuit_cooldown_secs),
+    });
 
     Ok(Box::new(reliable))
 }
 
 /// Create a RouterProvider if model routes are configured, otherwise return a
 /// standard resilient provider. The router wraps individual providers per route,
-/// each with its own retry/fallback chain.
+/// each with its own retry/fallback chain, and takes routes, budgets and the
+/// default model from `models`.
 pub fn create_routed_provider(
     primary_name: &str,
     api_key: Option<&str>,
     reliability: &crate::config::ReliabilityConfig,
-    model_routes: &[crate::config::ModelRouteConfig],
-    default_model: &str,
+    models: &Arc<router::ModelRouter>,
 ) -> anyhow::Result<Box<dyn Provider>> {
+    let model_routes = models.routes();
     if model_routes.is_empty() {
         return create_resilient_provider(primary_name, api_key, reliability);
     }
@@ -399,24 +411,9 @@ pub fn create_routed_provider(
         }
     }
 
-    // Build route table
-    let routes: Vec<(String, router::Route)> = model_routes
-        .iter()
-        .map(|r| {
-            (
-                r.hint.clone(),
-                router::Route {
-                    provider_name: r.provider.clone(),
-                    model: r.model.clone(),
-                },
-            )
-        })
-        .collect();
-
     Ok(Box::new(router::RouterProvider::new(
         providers,
-        routes,
-        default_model.to_string(),
+        Arc::clone(models),
     )))
 }
 
@@ -720,6 +717,9 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            tool_retries: std::collections::HashMap::new(),
+            circuit_failure_threshold: 5,
+            circuit_cooldown_secs: 60,
         };
 
         let provider = create_resilient_provider("openrouter", Some("sk-test"), &reliability);
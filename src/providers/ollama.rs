@@ -2,14 +2,37 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::providers::traits::{ChatResponse as ProviderChatResponse, Provider};
+//! Local models served by Ollama, for fully offline operation.
+//!
+//! The server is `OLLAMA_HOST` (as for the `ollama` CLI), by default
+//! `http://localhost:11434`. Besides chatting, the provider checks the
+//! server is up, lists installed models and pulls new ones. Each model's
+//! context window is read from `/api/show` and requested as `num_ctx`, up
+//! to [`MAX_CONTEXT_TOKENS`]; Ollama would otherwise cut long conversations
+//! at its small default window without saying so.
+
+use crate::providers::traits::{ChatMessage, ChatResponse as ProviderChatResponse, Provider};
 use async_trait::async_trait;
+use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Largest context window requested; larger windows cost a lot of memory.
+pub const MAX_CONTEXT_TOKENS: usize = 32_768;
+
+/// Context window assumed when `/api/show` does not name one.
+const DEFAULT_CONTEXT_TOKENS: usize = 4_096;
+
+/// Tokens kept free for the reply when trimming history.
+const REPLY_TOKENS: usize = 1_024;
 
 pub struct OllamaProvider {
     base_url: String,
     client: Client,
+    /// Context window of each model used so far
+    context_lengths: Mutex<HashMap<String, usize>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +52,8 @@ struct Message {
 #[derive(Debug, Serialize)]
 struct Options {
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,6 +66,104 @@ struct ResponseMessage {
     content: String,
 }
 
+/// A model installed on the Ollama server (`/api/tags`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModel {
+    pub name: String,
+    /// Size on disk in bytes
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelDetails {
+    #[serde(default)]
+    pub family: String,
+    /// e.g. "8.0B"
+    #[serde(default)]
+    pub parameter_size: String,
+    /// e.g. "`Q4_K_M`"
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+impl LocalModel {
+    /// One-line summary for model pickers, e.g. "8.0B · `Q4_K_M` · 4.9 GB".
+    pub fn summary(&self) -> String {
+        #[allow(clippy::cast_precision_loss)]
+        let gigabytes = self.size as f64 / 1e9;
+        [
+            self.details.parameter_size.clone(),
+            self.details.quantization_level.clone(),
+            format!("{gigabytes:.1} GB"),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<LocalModel>,
+}
+
+/// One progress line of `/api/pull`, e.g. `{"status":"pulling 6a0746a1ec1a",
+/// "total":4661211424,"completed":1048576}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullProgress {
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Server address from an `OLLAMA_HOST` value, which may omit the scheme.
+fn host_url(host: &str) -> String {
+    let host = host.trim();
+    if host.contains("://") {
+        host.to_string()
+    } else {
+        format!("http://{host}")
+    }
+}
+
+/// The context window in a `/api/show` response: `model_info` has it as
+/// `<architecture>.context_length`.
+fn context_length(show: &serde_json::Value) -> Option<usize> {
+    show.get("model_info")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+        .and_then(|n| usize::try_from(n).ok())
+}
+
+/// Rough token count of a message (four characters per token).
+fn estimated_tokens(message: &Message) -> usize {
+    message.content.chars().count() / 4 + 4
+}
+
+/// Drop the oldest messages after the system prompt until the rest fits
+/// `budget` tokens. The latest message is always kept.
+fn fit_history(mut messages: Vec<Message>, budget: usize) -> Vec<Message> {
+    let first = usize::from(messages.first().is_some_and(|m| m.role == "system"));
+    let mut total: usize = messages.iter().map(estimated_tokens).sum();
+    while total > budget && messages.len() > first + 1 {
+        total -= estimated_tokens(&messages.remove(first));
+    }
+    messages
+}
+
 impl OllamaProvider {
     pub fn new(base_url: Option<&str>) -> Self {
         Self {
@@ -53,7 +176,177 @@ impl OllamaProvider {
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            context_lengths: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The server named by `OLLAMA_HOST`, or the local default.
+    pub fn from_env() -> Self {
+        let host = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|h| !h.trim().is_empty())
+            .map(|h| host_url(&h));
+        Self::new(host.as_deref())
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn not_running(&self, e: impl std::fmt::Display) -> anyhow::Error {
+        anyhow::anyhow!(
+            "{e}. Is Ollama running at {}? (brew install ollama && ollama serve)",
+            self.base_url
+        )
+    }
+
+    /// Server version; fails when Ollama is not reachable.
+    pub async fn version(&self) -> anyhow::Result<String> {
+        let url = format!("{}/api/version", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await
+            .map_err(|e| self.not_running(e))?;
+        if !response.status().is_success() {
+            return Err(self.not_running(super::api_error("Ollama", response).await));
+        }
+        let body: serde_json::Value = response.json().await?;
+        Ok(body["version"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Models installed on the server.
+    pub async fn list_models(&self) -> anyhow::Result<Vec<LocalModel>> {
+        let url = format!("{}/api/tags", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| self.not_running(e))?;
+        if !response.status().is_success() {
+            anyhow::bail!("{}", super::api_error("Ollama", response).await);
+        }
+        let tags: TagsResponse = response.json().await?;
+        Ok(tags.models)
+    }
+
+    /// Context window requested for `model`: its own, up to
+    /// [`MAX_CONTEXT_TOKENS`]. Looked up once per model.
+    pub async fn context_window(&self, model: &str) -> usize {
+        if let Some(&n) = self
+            .context_lengths
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(model)
+        {
+            return n;
+        }
+        let url = format!("{}/api/show", self.base_url);
+        let show = async {
+            self.client
+                .post(&url)
+                .json(&serde_json::json!({ "model": model }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await
+        };
+        let n = match show.await {
+            Ok(show) => context_length(&show).unwrap_or(DEFAULT_CONTEXT_TOKENS),
+            Err(e) => {
+                // Not cached, so the next turn asks again
+                tracing::debug!("Ollama: no model info for {model}: {e}");
+                return DEFAULT_CONTEXT_TOKENS;
+            }
+        }
+        .min(MAX_CONTEXT_TOKENS);
+        self.context_lengths
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(model.to_string(), n);
+        n
+    }
+
+    /// Download `model`, calling `on_progress` with each progress line.
+    pub async fn pull(
+        &self,
+        model: &str,
+        mut on_progress: impl FnMut(PullProgress) + Send,
+    ) -> anyhow::Result<()> {
+        let url = format!("{}/api/pull", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "model": model, "stream": true }))
+            // Large models take far longer than a chat turn
+            .timeout(std::time::Duration::from_hours(6))
+            .send()
+            .await
+            .map_err(|e| self.not_running(e))?;
+        if !response.status().is_success() {
+            anyhow::bail!("{}", super::api_error("Ollama", response).await);
+        }
+
+        let mut body = response.bytes_stream();
+        let mut buffer = Vec::new();
+        while let Some(chunk) = body.next().await {
+            buffer.extend_from_slice(&chunk?);
+            while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+                let progress: PullProgress = serde_json::from_slice(&line)?;
+                if let Some(error) = &progress.error {
+                    anyhow::bail!("Ollama could not pull {model}: {error}");
+                }
+                on_progress(progress);
+            }
         }
+        Ok(())
+    }
+
+    async fn chat_messages(
+        &self,
+        messages: Vec<Message>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let num_ctx = self.context_window(model).await;
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: fit_history(messages, num_ctx.saturating_sub(REPLY_TOKENS)),
+            stream: false,
+            options: Options {
+                temperature,
+                num_ctx: Some(num_ctx),
+            },
+        };
+
+        let url = format!("{}/api/chat", self.base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| self.not_running(e))?;
+
+        if !response.status().is_success() {
+            let err = super::api_error("Ollama", response).await;
+            return Err(self.not_running(err));
+        }
+
+        let chat_response: ApiChatResponse = response.json().await?;
+        Ok(ProviderChatResponse::with_text(
+            chat_response.message.content,
+        ))
     }
 }
 
@@ -80,26 +373,27 @@ impl Provider for OllamaProvider {
             content: message.to_string(),
         });
 
-        let request = ChatRequest {
-            model: model.to_string(),
-            messages,
-            stream: false,
-            options: Options { temperature },
-        };
-
-        let url = format!("{}/api/chat", self.base_url);
-
-        let response = self.client.post(&url).json(&request).send().await?;
+        self.chat_messages(messages, model, temperature).await
+    }
 
-        if !response.status().is_success() {
-            let err = super::api_error("Ollama", response).await;
-            anyhow::bail!("{err}. Is Ollama running? (brew install ollama && ollama serve)");
-        }
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let messages = messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.content.clone(),
+            })
+            .collect();
+        self.chat_messages(messages, model, temperature).await
+    }
 
-        let chat_response: ApiChatResponse = response.json().await?;
-        Ok(ProviderChatResponse::with_text(
-            chat_response.message.content,
-        ))
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.version().await.map(|_| ())
     }
 }
 
@@ -146,13 +440,17 @@ mod tests {
                 },
             ],
             stream: false,
-            options: Options { temperature: 0.7 },
+            options: Options {
+                temperature: 0.7,
+                num_ctx: Some(8192),
+            },
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"stream\":false"));
         assert!(json.contains("llama3"));
         assert!(json.contains("system"));
         assert!(json.contains("\"temperature\":0.7"));
+        assert!(json.contains("\"num_ctx\":8192"));
     }
 
     #[test]
@@ -164,7 +462,10 @@ mod tests {
                 content: "test".to_string(),
             }],
             stream: false,
-            options: Options { temperature: 0.0 },
+            options: Options {
+                temperature: 0.0,
+                num_ctx: None,
+            },
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("\"role\":\"system\""));
@@ -191,4 +492,61 @@ mod tests {
         let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
         assert!(resp.message.content.contains("line1"));
     }
+
+    #[test]
+    fn host_url_adds_missing_scheme() {
+        assert_eq!(host_url("0.0.0.0:11434"), "http://0.0.0.0:11434");
+        assert_eq!(host_url("https://gpu.lan"), "https://gpu.lan");
+    }
+
+    #[test]
+    fn context_length_comes_from_model_info() {
+        let show = serde_json::json!({
+            "model_info": {
+                "general.architecture": "llama",
+                "llama.context_length": 131_072,
+                "llama.embedding_length": 4096
+            }
+        });
+        assert_eq!(context_length(&show), Some(131_072));
+        assert_eq!(context_length(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn history_is_trimmed_oldest_first_keeping_the_system_prompt() {
+        let message = |role: &str, content: &str| Message {
+            role: role.into(),
+            content: content.into(),
+        };
+        let messages = vec![
+            message("system", "be brief"),
+            message("user", &"a".repeat(400)),
+            message("assistant", &"b".repeat(400)),
+            message("user", "and now?"),
+        ];
+        let fitted = fit_history(messages, 120);
+        let contents: Vec<_> = fitted.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[0], "be brief");
+        assert!(contents[1].starts_with('b'));
+        assert_eq!(contents[2], "and now?");
+
+        // The latest message stays even when it alone is too long
+        let fitted = fit_history(vec![message("user", &"c".repeat(4000))], 10);
+        assert_eq!(fitted.len(), 1);
+    }
+
+    #[test]
+    fn tags_and_pull_progress_deserialize() {
+        let json = r#"{"models":[{"name":"llama3.1:8b","size":4920753328,
+            "details":{"family":"llama","parameter_size":"8.0B","quantization_level":"Q4_K_M"}}]}"#;
+        let tags: TagsResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(tags.models[0].name, "llama3.1:8b");
+        assert_eq!(tags.models[0].summary(), "8.0B · Q4_K_M · 4.9 GB");
+
+        let line = r#"{"status":"pulling 6a0746a1ec1a","digest":"sha256:6a07","total":100,"completed":40}"#;
+        let progress: PullProgress = serde_json::from_str(line).unwrap();
+        assert_eq!(progress.completed, Some(40));
+        assert_eq!(progress.error, None);
+    }
 }
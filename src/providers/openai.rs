@@ -2,7 +2,8 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
-use crate::providers::traits::{ChatResponse, Provider};
+use crate::providers::compatible::ResponseFormat;
+use crate::providers::traits::{ChatMessage, ChatResponse, Provider};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -17,6 +18,8 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +54,33 @@ impl OpenAiProvider {
                 .unwrap_or_else(|_| Client::new()),
         }
     }
+
+    async fn send(&self, request: &ChatRequest) -> anyhow::Result<ChatResponse> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {api_key}"))
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+
+        let chat_response: ApiChatResponse = response.json().await?;
+
+        chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| ChatResponse::with_text(c.message.content))
+            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+    }
 }
 
 #[async_trait]
@@ -62,10 +92,6 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let api_key = self.api_key.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
-
         let mut messages = Vec::new();
 
         if let Some(sys) = system_prompt {
@@ -84,28 +110,31 @@ impl Provider for OpenAiProvider {
             model: model.to_string(),
             messages,
             temperature,
+            response_format: None,
         };
+        self.send(&request).await
+    }
 
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {api_key}"))
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
-        }
-
-        let chat_response: ApiChatResponse = response.json().await?;
-
-        chat_response
-            .choices
-            .into_iter()
-            .next()
-            .map(|c| ChatResponse::with_text(c.message.content))
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+    async fn chat_with_schema(
+        &self,
+        messages: &[ChatMessage],
+        schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.clone(),
+                    content: m.content.clone(),
+                })
+                .collect(),
+            temperature,
+            response_format: Some(ResponseFormat::json_schema(schema)),
+        };
+        self.send(&request).await
     }
 }
 
@@ -163,6 +192,7 @@ mod tests {
                 },
             ],
             temperature: 0.7,
+            response_format: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"role\":\"system\""));
@@ -179,6 +209,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.0,
+            response_format: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("system"));
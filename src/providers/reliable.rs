@@ -2,11 +2,15 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use super::circuit::{self, BreakerSettings, CircuitBreaker};
 use super::traits::{ChatMessage, ChatResponse};
 use super::Provider;
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Check if an error is non-retryable (client errors that won't resolve with retries).
@@ -71,6 +75,8 @@ fn parse_retry_after_ms(err: &anyhow::Error) -> Option<u64> {
 /// Provider wrapper with retry, fallback, auth rotation, and model failover.
 pub struct ReliableProvider {
     providers: Vec<(String, Box<dyn Provider>)>,
+    /// One per provider, same order; skips providers whose circuit is open.
+    breakers: Vec<Arc<CircuitBreaker>>,
     max_retries: u32,
     base_backoff_ms: u64,
     /// Extra API keys for rotation (index tracks round-robin position).
@@ -86,8 +92,13 @@ impl ReliableProvider {
         max_retries: u32,
         base_backoff_ms: u64,
     ) -> Self {
+        let breakers = providers
+            .iter()
+            .map(|(name, _)| Arc::new(CircuitBreaker::new(name, BreakerSettings::disabled())))
+            .collect();
         Self {
             providers,
+            breakers,
             max_retries,
             base_backoff_ms: base_backoff_ms.max(50),
             api_keys: Vec::new(),
@@ -108,6 +119,17 @@ impl ReliableProvider {
         self
     }
 
+    /// Use the process-wide circuit breakers of the providers, which open
+    /// after repeated failures so calls fail over at once.
+    pub fn with_circuit_breakers(mut self, settings: BreakerSettings) -> Self {
+        self.breakers = self
+            .providers
+            .iter()
+            .map(|(name, _)| circuit::shared(name, settings))
+            .collect();
+        self
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -126,49 +148,32 @@ impl ReliableProvider {
         Some(&self.api_keys[idx])
     }
 
-    /// Compute backoff duration, respecting Retry-After if present.
-    fn compute_backoff(&self, base: u64, err: &anyhow::Error) -> u64 {
-        if let Some(retry_after) = parse_retry_after_ms(err) {
-            // Use Retry-After but cap at 30s to avoid indefinite waits
-            retry_after.min(30_000).max(base)
-        } else {
-            base
-        }
-    }
-}
-
-#[async_trait]
-impl Provider for ReliableProvider {
-    async fn warmup(&self) -> anyhow::Result<()> {
-        for (name, provider) in &self.providers {
-            tracing::info!(provider = name, "Warming up provider connection pool");
-            if let Err(e) = provider.warmup().await {
-                tracing::warn!(provider = name, "Warmup failed (non-fatal): {e}");
-            }
-        }
-        Ok(())
-    }
-
-    async fn chat_with_system(
-        &self,
-        system_prompt: Option<&str>,
-        message: &str,
-        model: &str,
-        temperature: f64,
+    /// Run `call` against each model of the chain on each provider in turn,
+    /// with retries, key rotation and circuit breakers, until one succeeds.
+    async fn failover<'a>(
+        &'a self,
+        model: &'a str,
+        call: impl Fn(&'a dyn Provider, &'a str) -> BoxFuture<'a, anyhow::Result<ChatResponse>>,
     ) -> anyhow::Result<ChatResponse> {
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
         for current_model in &models {
-            for (provider_name, provider) in &self.providers {
+            for ((provider_name, provider), breaker) in self.providers.iter().zip(&self.breakers) {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_system(system_prompt, message, current_model, temperature)
-                        .await
-                    {
+                    if !breaker.allow() {
+                        failures.push(format!("{provider_name}/{current_model}: circuit open"));
+                        tracing::debug!(
+                            provider = provider_name,
+                            "Circuit open, skipping provider"
+                        );
+                        break;
+                    }
+                    match call(provider.as_ref(), current_model).await {
                         Ok(resp) => {
+                            breaker.record_success();
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
@@ -183,6 +188,9 @@ impl Provider for ReliableProvider {
                         Err(e) => {
                             let non_retryable = is_non_retryable(&e);
                             let rate_limited = is_rate_limited(&e);
+                            if !non_retryable {
+                                breaker.record_failure(&e);
+                            }
 
                             failures.push(format!(
                                 "{provider_name}/{current_model} attempt {}/{}: {e}",
@@ -248,93 +256,84 @@ impl Provider for ReliableProvider {
         )
     }
 
+    /// Compute backoff duration, respecting Retry-After if present.
+    fn compute_backoff(&self, base: u64, err: &anyhow::Error) -> u64 {
+        if let Some(retry_after) = parse_retry_after_ms(err) {
+            // Use Retry-After but cap at 30s to avoid indefinite waits
+            retry_after.min(30_000).max(base)
+        } else {
+            base
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for ReliableProvider {
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in &self.providers {
+            tracing::info!(provider = name, "Warming up provider connection pool");
+            if let Err(e) = provider.warmup().await {
+                tracing::warn!(provider = name, "Warmup failed (non-fatal): {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Fallbacks may land on any provider, so tools go natively only when
+    /// every one of them takes them.
+    fn supports_native_tools(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_native_tools())
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.failover(model, |provider, current_model| {
+            provider.chat_with_system(system_prompt, message, current_model, temperature)
+        })
+        .await
+    }
+
     async fn chat_with_history(
         &self,
         messages: &[ChatMessage],
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let models = self.model_chain(model);
-        let mut failures = Vec::new();
-
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
-                let mut backoff_ms = self.base_backoff_ms;
-
-                for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_history(messages, current_model, temperature)
-                        .await
-                    {
-                        Ok(resp) => {
-                            if attempt > 0 || *current_model != model {
-                                tracing::info!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    attempt,
-                                    original_model = model,
-                                    "Provider recovered (failover/retry)"
-                                );
-                            }
-                            return Ok(resp);
-                        }
-                        Err(e) => {
-                            let non_retryable = is_non_retryable(&e);
-                            let rate_limited = is_rate_limited(&e);
-
-                            failures.push(format!(
-                                "{provider_name}/{current_model} attempt {}/{}: {e}",
-                                attempt + 1,
-                                self.max_retries + 1
-                            ));
-
-                            if rate_limited {
-                                if let Some(new_key) = self.rotate_key() {
-                                    tracing::info!(
-                                        provider = provider_name,
-                                        "Rate limited, rotated API key (key ending ...{})",
-                                        &new_key[new_key.len().saturating_sub(4)..]
-                                    );
-                                }
-                            }
-
-                            if non_retryable {
-                                tracing::warn!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    "Non-retryable error, moving on"
-                                );
-                                break;
-                            }
-
-                            if attempt < self.max_retries {
-                                let wait = self.compute_backoff(backoff_ms, &e);
-                                tracing::warn!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    attempt = attempt + 1,
-                                    backoff_ms = wait,
-                                    "Provider call failed, retrying"
-                                );
-                                tokio::time::sleep(Duration::from_millis(wait)).await;
-                                backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
-                            }
-                        }
-                    }
-                }
+        self.chat_with_tools(messages, &[], model, temperature)
+            .await
+    }
 
-                tracing::warn!(
-                    provider = provider_name,
-                    model = *current_model,
-                    "Exhausted retries, trying next provider/model"
-                );
-            }
-        }
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.failover(model, |provider, current_model| {
+            provider.chat_with_tools(messages, tools, current_model, temperature)
+        })
+        .await
+    }
 
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+    async fn chat_with_schema(
+        &self,
+        messages: &[ChatMessage],
+        schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.failover(model, |provider, current_model| {
+            provider.chat_with_schema(messages, schema, current_model, temperature)
+        })
+        .await
     }
 }
 
@@ -816,6 +815,89 @@ mod tests {
         assert_eq!(provider.compute_backoff(500, &err), 500);
     }
 
+    #[tokio::test]
+    async fn open_circuit_fails_over_without_calling_provider() {
+        let primary_name = format!("primary-{}", uuid::Uuid::new_v4());
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+
+        let provider = ReliableProvider::new(
+            vec![
+                (
+                    primary_name.clone(),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&primary_calls),
+                        fail_until_attempt: usize::MAX,
+                        response: "never",
+                        error: "503 Service Unavailable",
+                    }),
+                ),
+                (
+                    format!("fallback-{}", uuid::Uuid::new_v4()),
+                    Box::new(MockProvider {
+                        calls: Arc::clone(&fallback_calls),
+                        fail_until_attempt: 0,
+                        response: "from fallback",
+                        error: "fallback down",
+                    }),
+                ),
+            ],
+            2,
+            1,
+        )
+        .with_circuit_breakers(BreakerSettings {
+            failure_threshold: 2,
+            cooldown: Duration::from_mins(1),
+        });
+
+        // The second failure opens the circuit and cuts the retries short
+        let first = provider.chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(first.text_or_empty(), "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+
+        // Open: the primary is skipped outright
+        let second = provider.chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(second.text_or_empty(), "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+
+        let status = circuit::snapshot()
+            .into_iter()
+            .find(|s| s.provider == primary_name)
+            .unwrap();
+        assert_eq!(status.state, circuit::CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn client_errors_do_not_open_the_circuit() {
+        let name = format!("primary-{}", uuid::Uuid::new_v4());
+        let provider = ReliableProvider::new(
+            vec![(
+                name.clone(),
+                Box::new(MockProvider {
+                    calls: Arc::new(AtomicUsize::new(0)),
+                    fail_until_attempt: usize::MAX,
+                    response: "never",
+                    error: "400 Bad Request",
+                }),
+            )],
+            0,
+            1,
+        )
+        .with_circuit_breakers(BreakerSettings {
+            failure_threshold: 1,
+            cooldown: Duration::from_mins(1),
+        });
+
+        assert!(provider.chat("hello", "test", 0.0).await.is_err());
+        assert!(provider.chat("hello", "test", 0.0).await.is_err());
+        let status = circuit::snapshot()
+            .into_iter()
+            .find(|s| s.provider == name)
+            .unwrap();
+        assert_eq!(status.state, circuit::CircuitState::Closed);
+    }
+
     // ── Arc<ModelAwareMock> Provider impl for test ──
 
     #[async_trait]
@@ -2,16 +2,221 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+//! Model routing by task type and cost (`[[model_routes]]`).
+//!
+//! [`ModelRouter`] picks the model for each request: callers ask for a
+//! [`TaskClass`] and get the class's route (`hint:<class>`) when one is
+//! configured, else the default model, which the dashboard can switch at
+//! runtime. [`RouterProvider`] then sends `hint:` requests to the route's
+//! provider and model. When that provider fails, it tries the route's
+//! `fallbacks` and finally the default model; routes whose daily token
+//! budget is spent are skipped until local midnight.
+
 use super::traits::{ChatMessage, ChatResponse};
 use super::Provider;
+use crate::agent::budget::estimate_tokens;
+use crate::config::{Config, ModelRouteConfig, VisionConfig};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// Kinds of request that can have a route of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskClass {
+    /// Plain conversation without tools
+    Chat,
+    /// Agent turns that may call tools
+    Tools,
+    /// Summaries of pages and documents
+    Summarize,
+    /// Image understanding (`image_describe`)
+    Vision,
+}
+
+impl TaskClass {
+    /// Route hint of the class.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Tools => "tools",
+            Self::Summarize => "summarize",
+            Self::Vision => "vision",
+        }
+    }
+
+    /// Class of an agent turn, by whether it gets tools.
+    pub fn for_turn(has_tools: bool) -> Self {
+        if has_tools {
+            Self::Tools
+        } else {
+            Self::Chat
+        }
+    }
+}
 
-/// A single route: maps a task hint to a provider + model combo.
-#[derive(Debug, Clone)]
-pub struct Route {
-    pub provider_name: String,
-    pub model: String,
+/// Tokens a route used today.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteUsage {
+    pub hint: String,
+    /// Estimated prompt + reply tokens since local midnight
+    pub tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_token_budget: Option<u64>,
+}
+
+/// Picks the model for each request; see the module docs.
+pub struct ModelRouter {
+    default_model: RwLock<String>,
+    routes: Vec<ModelRouteConfig>,
+    /// hint → (day, estimated tokens used that day)
+    usage: Mutex<HashMap<String, (NaiveDate, u64)>>,
+}
+
+impl ModelRouter {
+    pub fn new(default_model: impl Into<String>, routes: &[ModelRouteConfig]) -> Self {
+        Self {
+            default_model: RwLock::new(default_model.into()),
+            routes: routes.to_vec(),
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn routes(&self) -> &[ModelRouteConfig] {
+        &self.routes
+    }
+
+    fn route(&self, hint: &str) -> Option<&ModelRouteConfig> {
+        self.routes.iter().find(|r| r.hint == hint)
+    }
+
+    /// The model used when no route applies.
+    pub fn default_model(&self) -> String {
+        self.default_model
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Switch the default model; returns the previous one.
+    pub fn set_default_model(&self, model: impl Into<String>) -> String {
+        let mut current = self
+            .default_model
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        std::mem::replace(&mut *current, model.into())
+    }
+
+    /// Model parameter for a request of `class`: `hint:<class>` when the
+    /// class has a route, else the default model.
+    pub fn model_for(&self, class: TaskClass) -> String {
+        match self.route(class.hint()) {
+            Some(route) => format!("hint:{}", route.hint),
+            None => self.default_model(),
+        }
+    }
+
+    /// Model parameters to try for `model`, in order. A `hint:` gets its
+    /// route, then the route's fallbacks, then the default model; routes
+    /// over their budget are left out. Other models are used as they are.
+    pub fn chain(&self, model: &str) -> Vec<String> {
+        let Some(hint) = model.strip_prefix("hint:") else {
+            return vec![model.to_string()];
+        };
+        let mut chain = Vec::new();
+        match self.route(hint) {
+            Some(route) => {
+                for name in std::iter::once(&route.hint).chain(&route.fallbacks) {
+                    let candidate = match self.route(name) {
+                        Some(fallback) if self.within_budget(fallback) => {
+                            format!("hint:{name}")
+                        }
+                        Some(_) => {
+                            tracing::debug!(hint = name.as_str(), "Route over its daily budget");
+                            continue;
+                        }
+                        // Not a route: a model of the default provider
+                        None => name.clone(),
+                    };
+                    if !chain.contains(&candidate) {
+                        chain.push(candidate);
+                    }
+                }
+            }
+            None => tracing::warn!(hint = hint, "Unknown route hint, using the default model"),
+        }
+        let default_model = self.default_model();
+        if !chain.contains(&default_model) {
+            chain.push(default_model);
+        }
+        chain
+    }
+
+    fn within_budget(&self, route: &ModelRouteConfig) -> bool {
+        route
+            .daily_token_budget
+            .is_none_or(|budget| self.used_today(&route.hint) < budget)
+    }
+
+    fn used_today(&self, hint: &str) -> u64 {
+        let today = Local::now().date_naive();
+        self.usage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(hint)
+            .filter(|(day, _)| *day == today)
+            .map_or(0, |(_, tokens)| *tokens)
+    }
+
+    /// Charge `tokens` to the route of `model`, if it is a `hint:`.
+    pub fn record_usage(&self, model: &str, tokens: u64) {
+        let Some(hint) = model.strip_prefix("hint:") else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        let mut usage = self.usage.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = usage.entry(hint.to_string()).or_insert((today, 0));
+        if entry.0 != today {
+            *entry = (today, 0);
+        }
+        entry.1 += tokens;
+    }
+
+    /// Today's usage of every route.
+    pub fn usage(&self) -> Vec<RouteUsage> {
+        self.routes
+            .iter()
+            .map(|route| RouteUsage {
+                hint: route.hint.clone(),
+                tokens: self.used_today(&route.hint),
+                daily_token_budget: route.daily_token_budget,
+            })
+            .collect()
+    }
+}
+
+/// `[vision]` with the provider and model of a `vision` route, if any.
+pub fn routed_vision(config: &Config) -> VisionConfig {
+    let mut vision = config.vision.clone();
+    if let Some(route) = config
+        .model_routes
+        .iter()
+        .find(|r| r.hint == TaskClass::Vision.hint())
+    {
+        if route.provider != vision.provider {
+            // The endpoint and key belong to the configured provider
+            vision.api_url = None;
+            vision.api_key = None;
+        }
+        vision.provider.clone_from(&route.provider);
+        vision.model.clone_from(&route.model);
+        if route.api_key.is_some() {
+            vision.api_key.clone_from(&route.api_key);
+        }
+    }
+    vision
 }
 
 /// Multi-model router — routes requests to different provider+model combos
@@ -19,26 +224,22 @@ pub struct Route {
 ///
 /// The model parameter can be:
 /// - A regular model name (e.g. "anthropic/claude-sonnet-4") → uses default provider
-/// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table
+/// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table,
+///   falling back along the route's chain when a provider fails
 ///
 /// This wraps multiple pre-created providers and selects the right one per request.
 pub struct RouterProvider {
     routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
-    default_model: String,
+    models: Arc<ModelRouter>,
 }
 
 impl RouterProvider {
-    /// Create a new router with a default provider and optional routes.
+    /// Create a new router over `models`' routes.
     ///
     /// `providers` is a list of (name, provider) pairs. The first one is the default.
-    /// `routes` maps hint names to Route structs containing provider_name and model.
-    pub fn new(
-        providers: Vec<(String, Box<dyn Provider>)>,
-        routes: Vec<(String, Route)>,
-        default_model: String,
-    ) -> Self {
+    pub fn new(providers: Vec<(String, Box<dyn Provider>)>, models: Arc<ModelRouter>) -> Self {
         // Build provider name → index lookup
         let name_to_index: HashMap<&str, usize> = providers
             .iter()
@@ -47,16 +248,17 @@ impl RouterProvider {
             .collect();
 
         // Resolve routes to provider indices
-        let resolved_routes: HashMap<String, (usize, String)> = routes
-            .into_iter()
-            .filter_map(|(hint, route)| {
-                let index = name_to_index.get(route.provider_name.as_str()).copied();
+        let resolved_routes: HashMap<String, (usize, String)> = models
+            .routes()
+            .iter()
+            .filter_map(|route| {
+                let index = name_to_index.get(route.provider.as_str()).copied();
                 match index {
-                    Some(i) => Some((hint, (i, route.model))),
+                    Some(i) => Some((route.hint.clone(), (i, route.model.clone()))),
                     None => {
                         tracing::warn!(
-                            hint = hint,
-                            provider = route.provider_name,
+                            hint = route.hint,
+                            provider = route.provider,
                             "Route references unknown provider, skipping"
                         );
                         None
@@ -69,28 +271,69 @@ impl RouterProvider {
             routes: resolved_routes,
             providers,
             default_index: 0,
-            default_model,
+            models,
         }
     }
 
-    /// Resolve a model parameter to a (provider, actual_model) pair.
+    /// Resolve a model parameter to a (provider_index, actual_model) pair.
     ///
     /// If the model starts with "hint:", look up the hint in the route table.
     /// Otherwise, use the default provider with the given model name.
-    /// Resolve a model parameter to a (provider_index, actual_model) pair.
-    fn resolve(&self, model: &str) -> (usize, String) {
-        if let Some(hint) = model.strip_prefix("hint:") {
-            if let Some((idx, resolved_model)) = self.routes.get(hint) {
-                return (*idx, resolved_model.clone());
-            }
-            tracing::warn!(
-                hint = hint,
-                "Unknown route hint, falling back to default provider"
-            );
+    fn resolve(&self, model: &str) -> Option<(usize, String)> {
+        match model.strip_prefix("hint:") {
+            Some(hint) => self.routes.get(hint).cloned(),
+            None => Some((self.default_index, model.to_string())),
         }
+    }
 
-        // Not a hint or hint not found — use default provider with the model as-is
-        (self.default_index, model.to_string())
+    /// (model parameter, provider index, model) for each attempt at `model`.
+    fn attempts(&self, model: &str) -> Vec<(String, usize, String)> {
+        self.models
+            .chain(model)
+            .into_iter()
+            .filter_map(|candidate| {
+                let (index, resolved) = self.resolve(&candidate)?;
+                Some((candidate, index, resolved))
+            })
+            .collect()
+    }
+
+    /// Try each attempt at `model` until one succeeds, charging its route.
+    async fn dispatch<'a>(
+        &'a self,
+        model: &str,
+        prompt_tokens: u64,
+        call: impl Fn(
+            &'a dyn Provider,
+            String,
+        ) -> futures::future::BoxFuture<'a, anyhow::Result<ChatResponse>>,
+    ) -> anyhow::Result<ChatResponse> {
+        let mut last_error = None;
+        for (candidate, index, resolved) in self.attempts(model) {
+            let (provider_name, provider) = &self.providers[index];
+            tracing::info!(
+                provider = provider_name.as_str(),
+                model = resolved.as_str(),
+                "Router dispatching request"
+            );
+            match call(provider.as_ref(), resolved).await {
+                Ok(response) => {
+                    let reply_tokens = estimate_tokens(response.text_or_empty());
+                    self.models
+                        .record_usage(&candidate, prompt_tokens + reply_tokens);
+                    return Ok(response);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        provider = provider_name.as_str(),
+                        model = candidate.as_str(),
+                        "Routed request failed, trying the next model: {e}"
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No provider for model {model}")))
     }
 }
 
@@ -103,31 +346,77 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let prompt_tokens =
+            estimate_tokens(system_prompt.unwrap_or_default()) + estimate_tokens(message);
+        self.dispatch(model, prompt_tokens, |provider, resolved| {
+            Box::pin(async move {
+                provider
+                    .chat_with_system(system_prompt, message, &resolved, temperature)
+                    .await
+            })
+        })
+        .await
+    }
 
-        let (provider_name, provider) = &self.providers[provider_idx];
-        tracing::info!(
-            provider = provider_name.as_str(),
-            model = resolved_model.as_str(),
-            "Router dispatching request"
-        );
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let prompt_tokens = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        self.dispatch(model, prompt_tokens, |provider, resolved| {
+            Box::pin(async move {
+                provider
+                    .chat_with_history(messages, &resolved, temperature)
+                    .await
+            })
+        })
+        .await
+    }
 
-        provider
-            .chat_with_system(system_prompt, message, &resolved_model, temperature)
-            .await
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let prompt_tokens = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        self.dispatch(model, prompt_tokens, |provider, resolved| {
+            Box::pin(async move {
+                provider
+                    .chat_with_tools(messages, tools, &resolved, temperature)
+                    .await
+            })
+        })
+        .await
     }
 
-    async fn chat_with_history(
+    async fn chat_with_schema(
         &self,
         messages: &[ChatMessage],
+        schema: &serde_json::Value,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (provider_idx, resolved_model) = self.resolve(model);
-        let (_, provider) = &self.providers[provider_idx];
-        provider
-            .chat_with_history(messages, &resolved_model, temperature)
-            .await
+        let prompt_tokens = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        self.dispatch(model, prompt_tokens, |provider, resolved| {
+            Box::pin(async move {
+                provider
+                    .chat_with_schema(messages, schema, &resolved, temperature)
+                    .await
+            })
+        })
+        .await
+    }
+
+    /// A turn may be routed to any provider, so tools go natively only
+    /// when every one of them takes them.
+    fn supports_native_tools(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_native_tools())
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -182,13 +471,38 @@ mod tests {
         ) -> anyhow::Result<ChatResponse> {
             self.calls.fetch_add(1, Ordering::SeqCst);
             *self.last_model.lock().unwrap() = model.to_string();
+            if self.response == "error" {
+                anyhow::bail!("provider unavailable");
+            }
             Ok(ChatResponse::with_text(self.response))
         }
     }
 
+    fn route(hint: &str, provider: &str, model: &str) -> ModelRouteConfig {
+        ModelRouteConfig {
+            hint: hint.to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            api_key: None,
+            fallbacks: Vec::new(),
+            daily_token_budget: None,
+        }
+    }
+
     fn make_router(
         providers: Vec<(&'static str, &'static str)>,
         routes: Vec<(&str, &str, &str)>,
+    ) -> (RouterProvider, Vec<Arc<MockProvider>>) {
+        let routes: Vec<ModelRouteConfig> = routes
+            .iter()
+            .map(|(hint, provider_name, model)| route(hint, provider_name, model))
+            .collect();
+        make_router_with(providers, routes)
+    }
+
+    fn make_router_with(
+        providers: Vec<(&'static str, &'static str)>,
+        routes: Vec<ModelRouteConfig>,
     ) -> (RouterProvider, Vec<Arc<MockProvider>>) {
         let mocks: Vec<Arc<MockProvider>> = providers
             .iter()
@@ -206,20 +520,8 @@ mod tests {
             })
             .collect();
 
-        let route_list: Vec<(String, Route)> = routes
-            .iter()
-            .map(|(hint, provider_name, model)| {
-                (
-                    hint.to_string(),
-                    Route {
-                        provider_name: provider_name.to_string(),
-                        model: model.to_string(),
-                    },
-                )
-            })
-            .collect();
-
-        let router = RouterProvider::new(provider_list, route_list, "default-model".to_string());
+        let models = Arc::new(ModelRouter::new("default-model", &routes));
+        let router = RouterProvider::new(provider_list, models);
 
         (router, mocks)
     }
@@ -280,8 +582,8 @@ mod tests {
         let result = router.chat("hello", "hint:nonexistent", 0.5).await.unwrap();
         assert_eq!(result.text_or_empty(), "default-response");
         assert_eq!(mocks[0].call_count(), 1);
-        // Falls back to default with the hint as model name
-        assert_eq!(mocks[0].last_model(), "hint:nonexistent");
+        // Falls back to the default model
+        assert_eq!(mocks[0].last_model(), "default-model");
     }
 
     #[tokio::test]
@@ -307,7 +609,7 @@ mod tests {
     fn resolve_preserves_model_for_non_hints() {
         let (router, _) = make_router(vec![("default", "ok")], vec![]);
 
-        let (idx, model) = router.resolve("gpt-4o");
+        let (idx, model) = router.resolve("gpt-4o").unwrap();
         assert_eq!(idx, 0);
         assert_eq!(model, "gpt-4o");
     }
@@ -319,7 +621,7 @@ mod tests {
             vec![("reasoning", "smart", "claude-opus")],
         );
 
-        let (idx, model) = router.resolve("hint:reasoning");
+        let (idx, model) = router.resolve("hint:reasoning").unwrap();
         assert_eq!(idx, 1);
         assert_eq!(model, "claude-opus");
     }
@@ -351,8 +653,7 @@ mod tests {
                 "default".into(),
                 Box::new(Arc::clone(&mock)) as Box<dyn Provider>,
             )],
-            vec![],
-            "model".into(),
+            Arc::new(ModelRouter::new("model", &[])),
         );
 
         let result = router
@@ -362,4 +663,95 @@ mod tests {
         assert_eq!(result.text_or_empty(), "response");
         assert_eq!(mock.call_count(), 1);
     }
+
+    #[test]
+    fn task_classes_use_their_route_or_the_default_model() {
+        let models = ModelRouter::new("gpt-4o", &[route("summarize", "ollama", "llama3.1:8b")]);
+        assert_eq!(models.model_for(TaskClass::Summarize), "hint:summarize");
+        assert_eq!(models.model_for(TaskClass::for_turn(true)), "gpt-4o");
+
+        assert_eq!(models.set_default_model("gpt-4o-mini"), "gpt-4o");
+        assert_eq!(models.model_for(TaskClass::Chat), "gpt-4o-mini");
+        assert_eq!(
+            models.chain("hint:summarize"),
+            ["hint:summarize", "gpt-4o-mini"]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_route_falls_back_along_its_chain() {
+        let (router, mocks) = make_router_with(
+            vec![
+                ("default", "default-response"),
+                ("cloud", "error"),
+                ("local", "local-response"),
+            ],
+            vec![
+                ModelRouteConfig {
+                    fallbacks: vec!["cheap".into()],
+                    ..route("tools", "cloud", "big-model")
+                },
+                route("cheap", "local", "small-model"),
+            ],
+        );
+
+        let result = router.chat("hello", "hint:tools", 0.5).await.unwrap();
+        assert_eq!(result.text_or_empty(), "local-response");
+        assert_eq!(mocks[1].call_count(), 1);
+        assert_eq!(mocks[2].last_model(), "small-model");
+        assert_eq!(mocks[0].call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn default_model_is_the_last_fallback() {
+        let (router, mocks) = make_router_with(
+            vec![("default", "default-response"), ("cloud", "error")],
+            vec![ModelRouteConfig {
+                fallbacks: vec!["gpt-4o-mini".into()],
+                ..route("chat", "cloud", "big-model")
+            }],
+        );
+        assert_eq!(
+            router.models.chain("hint:chat"),
+            ["hint:chat", "gpt-4o-mini", "default-model"]
+        );
+
+        let result = router.chat("hello", "hint:chat", 0.5).await.unwrap();
+        assert_eq!(result.text_or_empty(), "default-response");
+        assert_eq!(mocks[0].last_model(), "gpt-4o-mini");
+    }
+
+    #[tokio::test]
+    async fn spent_budget_skips_the_route_until_midnight() {
+        let (router, mocks) = make_router_with(
+            vec![("default", "default-response"), ("cloud", "cloud-response")],
+            vec![ModelRouteConfig {
+                daily_token_budget: Some(10),
+                ..route("chat", "cloud", "big-model")
+            }],
+        );
+
+        let long_message = "word ".repeat(20);
+        let first = router.chat(&long_message, "hint:chat", 0.5).await.unwrap();
+        assert_eq!(first.text_or_empty(), "cloud-response");
+        let usage = router.models.usage();
+        assert!(usage[0].tokens >= 25, "{usage:?}");
+
+        let second = router.chat("hello", "hint:chat", 0.5).await.unwrap();
+        assert_eq!(second.text_or_empty(), "default-response");
+        assert_eq!(mocks[1].call_count(), 1);
+    }
+
+    #[test]
+    fn vision_route_replaces_the_vision_provider() {
+        let mut config = Config::default();
+        config.vision.api_url = Some("https://vision.example/v1".into());
+        assert_eq!(routed_vision(&config).api_url, config.vision.api_url);
+
+        config.model_routes = vec![route("vision", "ollama", "llava")];
+        let vision = routed_vision(&config);
+        assert_eq!(vision.provider, "ollama");
+        assert_eq!(vision.model, "llava");
+        assert_eq!(vision.api_url, None);
+    }
 }
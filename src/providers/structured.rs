@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Structured output — replies constrained to a caller's JSON schema.
+//!
+//! The schema always goes into the system prompt; providers with a native
+//! mode (OpenAI `response_format`) get it there as well through
+//! [`Provider::chat_with_schema`]. Every reply is parsed and checked
+//! against the schema, and a reply that fails is sent back with the
+//! problem for repair.
+//!
+//! The check covers the keywords models need to be held to: `type`,
+//! `enum`, `properties`, `required`, `additionalProperties: false` and
+//! `items`. Other keywords are not enforced.
+
+use super::traits::{ChatMessage, Provider};
+use serde_json::Value;
+
+/// Repair rounds after the first reply.
+pub const MAX_REPAIRS: usize = 2;
+
+fn schema_instructions(schema: &Value) -> String {
+    let schema = serde_json::to_string_pretty(schema).unwrap_or_default();
+    format!(
+        "Answer with a single JSON value that matches this JSON schema, and nothing else \
+         — no prose, no code fences:\n{schema}"
+    )
+}
+
+/// The JSON value in a reply: the whole text, or the outermost object or
+/// array when the model wrapped it in prose or a code fence.
+pub fn extract_json(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Ok(value) = serde_json::from_str(text) {
+        return Some(value);
+    }
+    let start = text.find(['{', '['])?;
+    let end = text.rfind(['}', ']'])?;
+    if start >= end {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+/// Check `value` against `schema`; the error names the first mismatch.
+pub fn validate(value: &Value, schema: &Value) -> Result<(), String> {
+    check(value, schema, "$")
+}
+
+fn check(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        return Ok(());
+    };
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!(
+                "{path} must be one of {}",
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|kind| has_type(value, kind)) {
+        return Err(format!("{path} must be of type {}", types.join(" or ")));
+    }
+    match value {
+        Value::Object(map) => {
+            let required = schema.get("required").and_then(Value::as_array);
+            for key in required.into_iter().flatten().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    return Err(format!("{path} is missing \"{key}\""));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (key, item) in map {
+                match properties.and_then(|p| p.get(key)) {
+                    Some(property) => check(item, property, &format!("{path}.{key}"))?,
+                    None if closed => return Err(format!("{path} has unexpected \"{key}\"")),
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(item, item_schema, &format!("{path}[{index}]"))?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Ask for JSON matching `schema`, repairing invalid replies up to
+/// [`MAX_REPAIRS`] times. Backs [`Provider::chat_structured`].
+pub async fn complete<P: Provider + ?Sized>(
+    provider: &P,
+    system_prompt: Option<&str>,
+    message: &str,
+    schema: &Value,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<Value> {
+    let instructions = schema_instructions(schema);
+    let system = match system_prompt {
+        Some(prompt) if !prompt.trim().is_empty() => format!("{prompt}\n\n{instructions}"),
+        _ => instructions,
+    };
+    let mut messages = vec![ChatMessage::system(system), ChatMessage::user(message)];
+
+    let mut problem = String::new();
+    for attempt in 0..=MAX_REPAIRS {
+        let reply = provider
+            .chat_with_schema(&messages, schema, model, temperature)
+            .await?
+            .text
+            .unwrap_or_default();
+        problem = match extract_json(&reply) {
+            Some(value) => match validate(&value, schema) {
+                Ok(()) => return Ok(value),
+                Err(mismatch) => mismatch,
+            },
+            None => "the reply is not valid JSON".to_string(),
+        };
+        tracing::debug!(attempt, "Structured reply rejected: {problem}");
+        messages.push(ChatMessage::assistant(reply));
+        messages.push(ChatMessage::user(format!(
+            "That does not match the schema: {problem}. Reply again with only the corrected JSON."
+        )));
+    }
+    anyhow::bail!(
+        "No reply matched the schema after {} attempts: {problem}",
+        MAX_REPAIRS + 1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ChatResponse;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    fn person_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "role": {"enum": ["admin", "user"]}
+            },
+            "required": ["name", "age"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn extract_json_finds_value_in_prose_and_fences() {
+        assert_eq!(extract_json(" {\"a\": 1} "), Some(json!({"a": 1})));
+        assert_eq!(
+            extract_json("Sure:\n```json\n{\"a\": [1, 2]}\n```"),
+            Some(json!({"a": [1, 2]}))
+        );
+        assert_eq!(extract_json("[1, 2]"), Some(json!([1, 2])));
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[test]
+    fn validate_reports_first_mismatch() {
+        let schema = person_schema();
+        assert!(validate(&json!({"name": "Ada", "age": 36, "tags": ["x"]}), &schema).is_ok());
+        assert_eq!(
+            validate(&json!({"name": "Ada"}), &schema).unwrap_err(),
+            "$ is missing \"age\""
+        );
+        assert_eq!(
+            validate(&json!({"name": "Ada", "age": 36.5}), &schema).unwrap_err(),
+            "$.age must be of type integer"
+        );
+        assert_eq!(
+            validate(&json!({"name": "Ada", "age": 1, "tags": [3]}), &schema).unwrap_err(),
+            "$.tags[0] must be of type string"
+        );
+        assert!(
+            validate(&json!({"name": "Ada", "age": 1, "role": "root"}), &schema)
+                .unwrap_err()
+                .starts_with("$.role must be one of")
+        );
+        assert_eq!(
+            validate(&json!({"name": "Ada", "age": 1, "extra": true}), &schema).unwrap_err(),
+            "$ has unexpected \"extra\""
+        );
+    }
+
+    struct ScriptedProvider {
+        replies: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: &[&'static str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                seen: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            anyhow::bail!("not used")
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen.lock().unwrap().push(messages.to_vec());
+            let reply = self.replies.lock().unwrap().pop().unwrap_or("{}");
+            Ok(ChatResponse::with_text(reply))
+        }
+    }
+
+    #[tokio::test]
+    async fn chat_structured_repairs_invalid_replies() {
+        let provider = ScriptedProvider::new(&[
+            "I think the answer is Ada.",
+            "{\"name\": \"Ada\"}",
+            "{\"name\": \"Ada\", \"age\": 36}",
+        ]);
+        let value = provider
+            .chat_structured(
+                Some("Extract people."),
+                "Ada, 36",
+                &person_schema(),
+                "m",
+                0.0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(value, json!({"name": "Ada", "age": 36}));
+
+        let seen = provider.seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        assert!(seen[0][0].content.starts_with("Extract people."));
+        assert!(seen[0][0].content.contains("\"required\""));
+        assert!(seen[2].last().unwrap().content.contains("missing \"age\""));
+    }
+
+    #[tokio::test]
+    async fn chat_structured_gives_up_after_repairs() {
+        let provider = ScriptedProvider::new(&["nope", "nope", "nope", "{\"name\": \"x\"}"]);
+        let err = provider
+            .chat_structured(None, "?", &person_schema(), "m", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("after 3 attempts"));
+        assert_eq!(provider.seen.lock().unwrap().len(), MAX_REPAIRS + 1);
+    }
+}
@@ -2,6 +2,7 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -135,6 +136,55 @@ pub trait Provider: Send + Sync {
             .await
     }
 
+    /// Multi-turn conversation offering `tools` in the provider's native
+    /// tool-calling schema; calls come back in [`ChatResponse::tool_calls`].
+    /// The default ignores `tools` and leaves them to the system prompt's
+    /// text protocol (see [`Provider::supports_native_tools`]).
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.chat_with_history(messages, model, temperature).await
+    }
+
+    /// Whether `chat_with_tools` passes tools natively, so the system prompt
+    /// need not describe the `<tool_call>` protocol.
+    fn supports_native_tools(&self) -> bool {
+        false
+    }
+
+    /// Multi-turn conversation asking for JSON that matches `schema`, in
+    /// the provider's native structured-output mode where it has one. The
+    /// default ignores `schema`; [`Provider::chat_structured`] states it in
+    /// the system prompt either way.
+    async fn chat_with_schema(
+        &self,
+        messages: &[ChatMessage],
+        _schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.chat_with_history(messages, model, temperature).await
+    }
+
+    /// A JSON value matching `schema`, checked before it is returned.
+    /// Replies that do not parse or match go back to the model with the
+    /// problem, up to [`structured::MAX_REPAIRS`](super::structured::MAX_REPAIRS)
+    /// times.
+    async fn chat_structured(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        schema: &serde_json::Value,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<serde_json::Value> {
+        super::structured::complete(self, system_prompt, message, schema, model, temperature).await
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
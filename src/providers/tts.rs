@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+/// Upper bound for a single synthesis chunk. Replies are split on sentence
+/// boundaries below this size so the first audio arrives early.
+pub const MAX_TTS_CHUNK_CHARS: usize = 400;
+
+/// Interface for Text-to-Speech providers.
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    /// Synthesize text to audio.
+    ///
+    /// Returns the encoded audio bytes in the format reported by [`TtsProvider::format`].
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>>;
+
+    /// Audio format of synthesized output (e.g. "mp3", "wav").
+    fn format(&self) -> &str;
+
+    /// Return the provider name (e.g., "OpenAI TTS").
+    fn name(&self) -> &str;
+}
+
+/// Split a reply into speakable chunks on sentence boundaries.
+///
+/// Sentences longer than `max_chars` are hard-split on whitespace.
+pub fn split_into_speech_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    fn push_piece(piece: &str, max_chars: usize, current: &mut String, chunks: &mut Vec<String>) {
+        let piece = piece.trim();
+        if piece.is_empty() {
+            return;
+        }
+        // Hard-split oversized sentences on whitespace.
+        let words: Vec<&str> = if piece.chars().count() > max_chars {
+            piece.split_whitespace().collect()
+        } else {
+            vec![piece]
+        };
+        for word in words {
+            if !current.is_empty() && current.chars().count() + word.chars().count() + 1 > max_chars {
+                chunks.push(std::mem::take(current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?' | '\n') {
+            let end = i + c.len_utf8();
+            push_piece(&text[start..end], max_chars, &mut current, &mut chunks);
+            start = end;
+        }
+    }
+    push_piece(&text[start..], max_chars, &mut current, &mut chunks);
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// OpenAI TTS Implementation
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub struct OpenAiTtsProvider {
+    client: Client,
+    api_key: String,
+    model: String,
+    voice: String,
+}
+
+impl OpenAiTtsProvider {
+    pub fn new(api_key: String, model: Option<String>, voice: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            model: model.unwrap_or_else(|| "tts-1".to_string()),
+            voice: voice.unwrap_or_else(|| "alloy".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsProvider for OpenAiTtsProvider {
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": text,
+            "voice": self.voice,
+            "response_format": "mp3",
+        });
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/audio/speech")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send request to OpenAI TTS API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("OpenAI TTS API error: {}", error_text);
+        }
+
+        Ok(response
+            .bytes()
+            .await
+            .context("Failed to read OpenAI TTS audio")?
+            .to_vec())
+    }
+
+    fn format(&self) -> &str {
+        "mp3"
+    }
+
+    fn name(&self) -> &str {
+        "OpenAI TTS"
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// ElevenLabs Implementation
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub struct ElevenLabsTtsProvider {
+    client: Client,
+    api_key: String,
+    model: String,
+    voice_id: String,
+}
+
+impl ElevenLabsTtsProvider {
+    pub fn new(api_key: String, model: Option<String>, voice: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            model: model.unwrap_or_else(|| "eleven_multilingual_v2".to_string()),
+            // "Rachel" — ElevenLabs' default premade voice
+            voice_id: voice.unwrap_or_else(|| "21m00Tcm4TlvDq8ikWAM".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl TtsProvider for ElevenLabsTtsProvider {
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let body = serde_json::json!({
+            "text": text,
+            "model_id": self.model,
+        });
+
+        let response = self
+            .client
+            .post(format!(
+                "https://api.elevenlabs.io/v1/text-to-speech/{}",
+                self.voice_id
+            ))
+            .header("xi-api-key", &self.api_key)
+            .header("Accept", "audio/mpeg")
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send request to ElevenLabs API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("ElevenLabs API error: {}", error_text);
+        }
+
+        Ok(response
+            .bytes()
+            .await
+            .context("Failed to read ElevenLabs audio")?
+            .to_vec())
+    }
+
+    fn format(&self) -> &str {
+        "mp3"
+    }
+
+    fn name(&self) -> &str {
+        "ElevenLabs"
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Local Piper Implementation
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Fully local synthesis via the `piper` binary — no audio leaves the device.
+pub struct PiperTtsProvider {
+    binary: String,
+    model_path: PathBuf,
+}
+
+impl PiperTtsProvider {
+    pub fn new(model_path: PathBuf) -> Self {
+        Self {
+            binary: "piper".to_string(),
+            model_path,
+        }
+    }
+}
+
+#[async_trait]
+impl TtsProvider for PiperTtsProvider {
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let out_path =
+            std::env::temp_dir().join(format!("mymolt-piper-{}.wav", uuid::Uuid::new_v4()));
+
+        let mut child = tokio::process::Command::new(&self.binary)
+            .arg("--model")
+            .arg(&self.model_path)
+            .arg("--output_file")
+            .arg(&out_path)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to spawn piper (is it installed and on PATH?)")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .await
+                .context("Failed to write text to piper")?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .await
+            .context("Failed to wait for piper")?;
+
+        let result = if output.status.success() {
+            tokio::fs::read(&out_path)
+                .await
+                .context("Failed to read piper output")
+        } else {
+            Err(anyhow::anyhow!(
+                "piper exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        };
+        let _ = tokio::fs::remove_file(&out_path).await;
+        result
+    }
+
+    fn format(&self) -> &str {
+        "wav"
+    }
+
+    fn name(&self) -> &str {
+        "Piper (local)"
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Factory
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub fn create_tts_provider(
+    provider_name: &str,
+    api_key: Option<&str>,
+    model: Option<String>,
+    voice: Option<String>,
+) -> Result<Box<dyn TtsProvider>> {
+    let require_key = || {
+        api_key
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("TTS provider {} requires an API key", provider_name))
+    };
+
+    match provider_name {
+        "openai" => Ok(Box::new(OpenAiTtsProvider::new(require_key()?, model, voice))),
+        "elevenlabs" => Ok(Box::new(ElevenLabsTtsProvider::new(
+            require_key()?,
+            model,
+            voice,
+        ))),
+        "piper" => {
+            let model_path = model.ok_or_else(|| {
+                anyhow::anyhow!("TTS provider piper requires `model` (path to an .onnx voice)")
+            })?;
+            Ok(Box::new(PiperTtsProvider::new(PathBuf::from(
+                shellexpand::tilde(&model_path).as_ref(),
+            ))))
+        }
+        _ => anyhow::bail!("Unsupported TTS provider: {}", provider_name),
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Mock TTS Provider (for testing)
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A mock TTS provider that returns the input text as "audio" bytes.
+/// Used for unit and integration tests so we never call a real API.
+pub struct MockTtsProvider;
+
+#[async_trait]
+impl TtsProvider for MockTtsProvider {
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        Ok(text.as_bytes().to_vec())
+    }
+
+    fn format(&self) -> &str {
+        "wav"
+    }
+
+    fn name(&self) -> &str {
+        "Mock TTS"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Factory Tests ──────────────────────────────────────────────
+
+    #[test]
+    fn factory_creates_openai_provider() {
+        let provider = create_tts_provider("openai", Some("sk-test"), None, None).unwrap();
+        assert_eq!(provider.name(), "OpenAI TTS");
+        assert_eq!(provider.format(), "mp3");
+    }
+
+    #[test]
+    fn factory_creates_elevenlabs_provider() {
+        let provider = create_tts_provider("elevenlabs", Some("xi-test"), None, None).unwrap();
+        assert_eq!(provider.name(), "ElevenLabs");
+    }
+
+    #[test]
+    fn factory_requires_key_for_cloud_providers() {
+        let err = create_tts_provider("openai", None, None, None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("requires an API key"));
+    }
+
+    #[test]
+    fn factory_piper_requires_model_path() {
+        assert!(create_tts_provider("piper", None, None, None).is_err());
+        let provider =
+            create_tts_provider("piper", None, Some("/voices/de.onnx".into()), None).unwrap();
+        assert_eq!(provider.format(), "wav");
+    }
+
+    #[test]
+    fn factory_rejects_unknown_provider() {
+        let err = create_tts_provider("bark", Some("k"), None, None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("Unsupported TTS provider: bark"));
+    }
+
+    #[test]
+    fn openai_provider_defaults() {
+        let provider = OpenAiTtsProvider::new("sk-test".into(), None, None);
+        assert_eq!(provider.model, "tts-1");
+        assert_eq!(provider.voice, "alloy");
+    }
+
+    // ── Chunking Tests ─────────────────────────────────────────────
+
+    #[test]
+    fn chunks_merge_short_sentences() {
+        let chunks = split_into_speech_chunks("Hi. How are you? Fine!", 400);
+        assert_eq!(chunks, vec!["Hi. How are you? Fine!"]);
+    }
+
+    #[test]
+    fn chunks_split_at_sentence_boundaries() {
+        let chunks = split_into_speech_chunks("First sentence. Second sentence.", 20);
+        assert_eq!(chunks, vec!["First sentence.", "Second sentence."]);
+    }
+
+    #[test]
+    fn chunks_hard_split_long_sentences() {
+        let text = "word ".repeat(50);
+        let chunks = split_into_speech_chunks(&text, 24);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 24));
+    }
+
+    #[test]
+    fn chunks_empty_input() {
+        assert!(split_into_speech_chunks("   \n ", 100).is_empty());
+    }
+
+    #[tokio::test]
+    async fn mock_provider_echoes_text() {
+        let provider: std::sync::Arc<dyn TtsProvider> = std::sync::Arc::new(MockTtsProvider);
+        assert_eq!(provider.synthesize("hallo").await.unwrap(), b"hallo");
+    }
+}
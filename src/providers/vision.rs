@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// Interface for multimodal (image understanding) providers.
+#[async_trait]
+pub trait VisionProvider: Send + Sync {
+    /// Ask the model `prompt` about an image.
+    ///
+    /// # Arguments
+    /// * `image` - Raw image bytes (PNG, JPEG, GIF or WebP).
+    /// * `mime` - MIME type of the image (e.g. "image/png").
+    /// * `prompt` - Instruction for the model.
+    async fn describe(&self, image: &[u8], mime: &str, prompt: &str) -> Result<String>;
+
+    /// Return the provider name (e.g. "openai").
+    fn name(&self) -> &str;
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// OpenAI-compatible Chat Completions Implementation
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Any `/chat/completions` endpoint that accepts `image_url` content parts
+/// (OpenAI, OpenRouter, Ollama, LM Studio, vLLM, ...).
+pub struct OpenAiCompatibleVisionProvider {
+    client: Client,
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl OpenAiCompatibleVisionProvider {
+    pub fn new(name: &str, base_url: &str, api_key: Option<String>, model: String) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(120))
+                .build()
+                .unwrap_or_default(),
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[async_trait]
+impl VisionProvider for OpenAiCompatibleVisionProvider {
+    async fn describe(&self, image: &[u8], mime: &str, prompt: &str) -> Result<String> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(image);
+        let body = json!({
+            "model": self.model,
+            "messages": [{
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": prompt},
+                    {"type": "image_url", "image_url": {"url": format!("data:{mime};base64,{encoded}")}}
+                ]
+            }]
+        });
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to send request to {} vision API", self.name))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("{} vision API error ({status}): {error_text}", self.name);
+        }
+
+        let resp: CompletionResponse = response
+            .json()
+            .await
+            .context("Failed to parse vision response")?;
+        resp.choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .context("Vision response contained no text")
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Factory
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Default endpoint for the known OpenAI-compatible vision providers.
+fn default_base_url(provider_name: &str) -> Option<&'static str> {
+    match provider_name {
+        "openai" => Some("https://api.openai.com/v1"),
+        "openrouter" => Some("https://openrouter.ai/api/v1"),
+        "ollama" => Some("http://localhost:11434/v1"),
+        _ => None,
+    }
+}
+
+/// Build a vision provider. `api_url` overrides the provider's default
+/// endpoint and makes any OpenAI-compatible server usable.
+pub fn create_vision_provider(
+    provider_name: &str,
+    api_key: Option<String>,
+    model: &str,
+    api_url: Option<&str>,
+) -> Result<Box<dyn VisionProvider>> {
+    let base_url = api_url
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .or_else(|| default_base_url(provider_name))
+        .with_context(|| {
+            format!(
+                "Unsupported vision provider: {provider_name} (use openai, openrouter, ollama, \
+                 or set vision.api_url to an OpenAI-compatible endpoint)"
+            )
+        })?;
+    if model.trim().is_empty() {
+        anyhow::bail!("vision.model must name a multimodal model");
+    }
+    Ok(Box::new(OpenAiCompatibleVisionProvider::new(
+        provider_name,
+        base_url,
+        api_key,
+        model.to_string(),
+    )))
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Mock Vision Provider (for testing)
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A mock vision provider that returns a pre-configured reply and records
+/// the prompt it was given. Used so tests never call a real API.
+pub struct MockVisionProvider {
+    pub reply: String,
+    pub prompts: std::sync::Mutex<Vec<String>>,
+}
+
+impl MockVisionProvider {
+    pub fn new(reply: impl Into<String>) -> Self {
+        Self {
+            reply: reply.into(),
+            prompts: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl VisionProvider for MockVisionProvider {
+    async fn describe(&self, _image: &[u8], _mime: &str, prompt: &str) -> Result<String> {
+        self.prompts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(prompt.to_string());
+        Ok(self.reply.clone())
+    }
+
+    fn name(&self) -> &str {
+        "Mock Vision"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factory_knows_providers_and_custom_endpoints() {
+        let provider = create_vision_provider("openai", Some("sk-test".into()), "gpt-4o-mini", None);
+        assert_eq!(provider.unwrap().name(), "openai");
+        let local = create_vision_provider("lmstudio", None, "llava", Some("http://localhost:1234/v1"));
+        assert!(local.is_ok());
+    }
+
+    #[test]
+    fn factory_rejects_unknown_provider_and_empty_model() {
+        let err = create_vision_provider("deepseek", None, "x", None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("Unsupported vision provider: deepseek"));
+        assert!(create_vision_provider("ollama", None, " ", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_provider_records_prompt() {
+        let provider = MockVisionProvider::new("a cat");
+        let reply = provider.describe(&[1, 2, 3], "image/png", "what is this?").await;
+        assert_eq!(reply.unwrap(), "a cat");
+        assert_eq!(provider.prompts.lock().unwrap().as_slice(), ["what is this?"]);
+    }
+}
@@ -3,7 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::RuntimeAdapter;
-use crate::config::DockerRuntimeConfig;
+use crate::config::{DockerRuntimeConfig, ResourceLimitsConfig};
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 
@@ -54,44 +54,13 @@ impl DockerRuntime {
 
         Ok(resolved)
     }
-}
-
-impl RuntimeAdapter for DockerRuntime {
-    fn name(&self) -> &str {
-        "docker"
-    }
-
-    fn has_shell_access(&self) -> bool {
-        true
-    }
-
-    fn has_filesystem_access(&self) -> bool {
-        self.config.mount_workspace
-    }
-
-    fn storage_path(&self) -> PathBuf {
-        if self.config.mount_workspace {
-            PathBuf::from("/workspace/.mymolt")
-        } else {
-            PathBuf::from("/tmp/.mymolt")
-        }
-    }
-
-    fn supports_long_running(&self) -> bool {
-        false
-    }
 
-    fn memory_budget(&self) -> u64 {
-        self.config
-            .memory_limit_mb
-            .map_or(0, |mb| mb.saturating_mul(1024 * 1024))
-    }
-
-    fn build_shell_command(
+    fn build_command(
         &self,
         command: &str,
         workspace_dir: &Path,
-    ) -> anyhow::Result<tokio::process::Command> {
+        limits: Option<&ResourceLimitsConfig>,
+    ) -> Result<tokio::process::Command> {
         let mut process = tokio::process::Command::new("docker");
         process
             .arg("run")
@@ -104,10 +73,36 @@ impl RuntimeAdapter for DockerRuntime {
             process.arg("--network").arg(network);
         }
 
-        if let Some(memory_limit_mb) = self.config.memory_limit_mb.filter(|mb| *mb > 0) {
+        // The stricter of the runtime and per-command memory limits wins.
+        let command_memory_mb = limits
+            .map(|l| u64::from(l.max_memory_mb))
+            .filter(|mb| *mb > 0);
+        let memory_limit_mb = match (
+            self.config.memory_limit_mb.filter(|mb| *mb > 0),
+            command_memory_mb,
+        ) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(memory_limit_mb) = memory_limit_mb {
             process.arg("--memory").arg(format!("{memory_limit_mb}m"));
         }
 
+        if let Some(limits) = limits {
+            if limits.max_subprocesses > 0 {
+                // The shell itself counts towards the pids limit.
+                process
+                    .arg("--pids-limit")
+                    .arg((u64::from(limits.max_subprocesses) + 1).to_string());
+            }
+            if limits.max_cpu_time_seconds > 0 {
+                let secs = limits.max_cpu_time_seconds;
+                process
+                    .arg("--ulimit")
+                    .arg(format!("cpu={secs}:{}", secs.saturating_add(1)));
+            }
+        }
+
         if let Some(cpu_limit) = self.config.cpu_limit.filter(|cpus| *cpus > 0.0) {
             process.arg("--cpus").arg(cpu_limit.to_string());
         }
@@ -141,6 +136,61 @@ impl RuntimeAdapter for DockerRuntime {
     }
 }
 
+impl RuntimeAdapter for DockerRuntime {
+    fn name(&self) -> &str {
+        "docker"
+    }
+
+    fn has_shell_access(&self) -> bool {
+        true
+    }
+
+    fn has_filesystem_access(&self) -> bool {
+        self.config.mount_workspace
+    }
+
+    fn storage_path(&self) -> PathBuf {
+        if self.config.mount_workspace {
+            PathBuf::from("/workspace/.mymolt")
+        } else {
+            PathBuf::from("/tmp/.mymolt")
+        }
+    }
+
+    fn supports_long_running(&self) -> bool {
+        false
+    }
+
+    fn memory_budget(&self) -> u64 {
+        self.config
+            .memory_limit_mb
+            .map_or(0, |mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    fn isolates_commands(&self) -> bool {
+        true
+    }
+
+    fn build_shell_command(
+        &self,
+        command: &str,
+        workspace_dir: &Path,
+    ) -> anyhow::Result<tokio::process::Command> {
+        self.build_command(command, workspace_dir, None)
+    }
+
+    /// Limits become container cgroup limits; host rlimits would only
+    /// constrain the `docker` client.
+    fn build_limited_shell_command(
+        &self,
+        command: &str,
+        workspace_dir: &Path,
+        limits: &ResourceLimitsConfig,
+    ) -> anyhow::Result<tokio::process::Command> {
+        self.build_command(command, workspace_dir, Some(limits))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +201,12 @@ mod tests {
         assert_eq!(runtime.name(), "docker");
     }
 
+    #[test]
+    fn docker_runtime_isolates_commands() {
+        let runtime = DockerRuntime::new(DockerRuntimeConfig::default());
+        assert!(runtime.isolates_commands());
+    }
+
     #[test]
     fn docker_runtime_memory_budget() {
         let mut cfg = DockerRuntimeConfig::default();
@@ -187,6 +243,33 @@ mod tests {
         assert!(debug.contains("echo hello"));
     }
 
+    #[test]
+    fn docker_limited_command_uses_container_limits() {
+        let cfg = DockerRuntimeConfig {
+            memory_limit_mb: Some(1024),
+            mount_workspace: false,
+            ..DockerRuntimeConfig::default()
+        };
+        let runtime = DockerRuntime::new(cfg);
+        let limits = ResourceLimitsConfig {
+            max_memory_mb: 256,
+            max_cpu_time_seconds: 30,
+            max_subprocesses: 10,
+            ..ResourceLimitsConfig::default()
+        };
+
+        let command = runtime
+            .build_limited_shell_command("make", &std::env::temp_dir(), &limits)
+            .unwrap();
+        let debug = format!("{command:?}");
+
+        assert!(debug.contains("\"256m\""));
+        assert!(!debug.contains("1024m"));
+        assert!(debug.contains("--pids-limit"));
+        assert!(debug.contains("\"11\""));
+        assert!(debug.contains("cpu=30:31"));
+    }
+
     #[test]
     fn docker_workspace_allowlist_blocks_outside_paths() {
         let cfg = DockerRuntimeConfig {
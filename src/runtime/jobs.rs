@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Background shell jobs for commands that outlive a single tool call.
+//!
+//! Jobs run the command the runtime adapter builds, with stdout and stderr
+//! captured into one ring buffer per job. A job is killed after
+//! [`JOB_TIMEOUT`], finished jobs are forgotten after [`JOB_RETENTION`], and
+//! dropping the manager kills whatever is still running.
+
+use super::traits::RuntimeAdapter;
+use crate::security::detect::ToolSandbox;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Notify;
+
+/// Jobs that may run at the same time.
+pub const MAX_RUNNING_JOBS: usize = 4;
+/// Output kept per job; older output is dropped.
+pub const JOB_OUTPUT_BYTES: usize = 256 * 1024;
+/// Jobs still running after this long are killed.
+pub const JOB_TIMEOUT: Duration = Duration::from_secs(2 * 60 * 60);
+/// Finished jobs are forgotten after this long.
+pub const JOB_RETENTION: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum JobState {
+    Running,
+    /// `code` is `None` when the process was ended by a signal.
+    Exited { code: Option<i32> },
+    Killed,
+    TimedOut,
+}
+
+impl JobState {
+    pub fn describe(self) -> String {
+        match self {
+            Self::Running => "running".into(),
+            Self::Exited { code: Some(code) } => format!("exited with code {code}"),
+            Self::Exited { code: None } => "ended by a signal".into(),
+            Self::Killed => "killed".into(),
+            Self::TimedOut => format!("killed after {}h timeout", JOB_TIMEOUT.as_secs() / 3600),
+        }
+    }
+}
+
+/// The last `cap` bytes of a job's output, plus how many were written in total.
+#[derive(Debug)]
+pub struct OutputRing {
+    buf: VecDeque<u8>,
+    cap: usize,
+    total: u64,
+}
+
+impl OutputRing {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            buf: VecDeque::with_capacity(cap.min(8192)),
+            cap,
+            total: 0,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        let bytes = &bytes[bytes.len().saturating_sub(self.cap)..];
+        let overflow = (self.buf.len() + bytes.len()).saturating_sub(self.cap);
+        self.buf.drain(..overflow);
+        self.buf.extend(bytes);
+    }
+
+    /// Total bytes ever written; the offset to pass to [`Self::since`] next time.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Offset of the oldest byte still buffered.
+    pub fn start(&self) -> u64 {
+        self.total - self.buf.len() as u64
+    }
+
+    /// Output written after `offset`, at most `limit` bytes of it, and whether
+    /// some of it was already dropped from the buffer.
+    pub fn since(&self, offset: u64, limit: usize) -> (Vec<u8>, bool) {
+        let skip = usize::try_from(offset.saturating_sub(self.start())).unwrap_or(usize::MAX);
+        let bytes = self.buf.iter().skip(skip).take(limit).copied().collect();
+        (bytes, offset < self.start())
+    }
+
+    /// The last `limit` bytes.
+    pub fn tail(&self, limit: usize) -> Vec<u8> {
+        self.buf
+            .iter()
+            .skip(self.buf.len().saturating_sub(limit))
+            .copied()
+            .collect()
+    }
+}
+
+/// Snapshot of a job for status output.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub command: String,
+    pub started_at: String,
+    pub elapsed_secs: u64,
+    #[serde(flatten)]
+    pub state: JobState,
+    pub output_bytes: u64,
+}
+
+struct Job {
+    command: String,
+    started_at: String,
+    started: Instant,
+    finished: Option<Instant>,
+    state: JobState,
+    output: Arc<Mutex<OutputRing>>,
+    kill: Arc<Notify>,
+}
+
+impl Job {
+    fn info(&self, id: &str) -> JobInfo {
+        let end = self.finished.unwrap_or_else(Instant::now);
+        JobInfo {
+            id: id.to_string(),
+            command: self.command.clone(),
+            started_at: self.started_at.clone(),
+            elapsed_secs: end.duration_since(self.started).as_secs(),
+            state: self.state,
+            output_bytes: lock(&self.output).total(),
+        }
+    }
+}
+
+type JobTable = Mutex<HashMap<String, Job>>;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs and tracks background jobs for one runtime.
+pub struct JobManager {
+    runtime: Arc<dyn RuntimeAdapter>,
+    jobs: Arc<JobTable>,
+}
+
+impl JobManager {
+    pub fn new(runtime: Arc<dyn RuntimeAdapter>) -> Self {
+        Self {
+            runtime,
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Start `command` in `workspace_dir` and `sandbox` with exactly the
+    /// variables in `env`. Returns the job id.
+    pub fn start(
+        &self,
+        command: &str,
+        workspace_dir: &Path,
+        env: &[(String, String)],
+        sandbox: &ToolSandbox,
+    ) -> anyhow::Result<String> {
+        anyhow::ensure!(
+            self.runtime.has_shell_access() && self.runtime.supports_long_running(),
+            "The {} runtime does not support background jobs",
+            self.runtime.name()
+        );
+        self.prune();
+        let running = lock(&self.jobs)
+            .values()
+            .filter(|j| j.state == JobState::Running)
+            .count();
+        anyhow::ensure!(
+            running < MAX_RUNNING_JOBS,
+            "{running} jobs are already running (limit {MAX_RUNNING_JOBS}); wait for one or kill it"
+        );
+
+        let mut cmd = self.runtime.build_shell_command(command, workspace_dir)?;
+        sandbox.wrap(&mut cmd, workspace_dir)?;
+        cmd.env_clear()
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        // Own process group, so a kill also reaches what the shell started.
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd.spawn()?;
+
+        let output = Arc::new(Mutex::new(OutputRing::new(JOB_OUTPUT_BYTES)));
+        let pumps = [
+            child.stdout.take().map(|s| pump(s, Arc::clone(&output))),
+            child.stderr.take().map(|s| pump(s, Arc::clone(&output))),
+        ];
+        let kill = Arc::new(Notify::new());
+        let id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        lock(&self.jobs).insert(
+            id.clone(),
+            Job {
+                command: command.to_string(),
+                started_at: chrono::Local::now().to_rfc3339(),
+                started: Instant::now(),
+                finished: None,
+                state: JobState::Running,
+                output,
+                kill: Arc::clone(&kill),
+            },
+        );
+
+        let jobs = Arc::downgrade(&self.jobs);
+        let job_id = id.clone();
+        tokio::spawn(async move {
+            let state = tokio::select! {
+                status = child.wait() => JobState::Exited { code: status.ok().and_then(|s| s.code()) },
+                () = kill.notified() => JobState::Killed,
+                () = tokio::time::sleep(JOB_TIMEOUT) => JobState::TimedOut,
+            };
+            if matches!(state, JobState::Killed | JobState::TimedOut) {
+                terminate(&mut child).await;
+            }
+            // Let the readers drain what is left in the pipes.
+            let _ = tokio::time::timeout(Duration::from_secs(2), async {
+                for pump in pumps.into_iter().flatten() {
+                    let _ = pump.await;
+                }
+            })
+            .await;
+            finish(&jobs, &job_id, state);
+        });
+        Ok(id)
+    }
+
+    /// All known jobs, oldest first.
+    pub fn list(&self) -> Vec<JobInfo> {
+        self.prune();
+        let jobs = lock(&self.jobs);
+        let mut infos: Vec<JobInfo> = jobs.iter().map(|(id, job)| job.info(id)).collect();
+        infos.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        infos
+    }
+
+    pub fn status(&self, id: &str) -> Option<JobInfo> {
+        lock(&self.jobs).get(id).map(|job| job.info(id))
+    }
+
+    /// Output of job `id`: from byte `since` if given, otherwise the last
+    /// `limit` bytes. Returns the text, the offset to continue from, and
+    /// whether output before `since` was already dropped.
+    pub fn logs(&self, id: &str, since: Option<u64>, limit: usize) -> Option<(String, u64, bool)> {
+        let jobs = lock(&self.jobs);
+        let ring = lock(&jobs.get(id)?.output);
+        let (bytes, dropped, next) = match since {
+            Some(offset) => {
+                let (bytes, dropped) = ring.since(offset, limit);
+                let next = offset.clamp(ring.start(), ring.total()) + bytes.len() as u64;
+                (bytes, dropped, next)
+            }
+            None => (ring.tail(limit), false, ring.total()),
+        };
+        Some((String::from_utf8_lossy(&bytes).into_owned(), next, dropped))
+    }
+
+    /// Ask job `id` to stop. `None` if unknown, `Some(false)` if it already finished.
+    pub fn kill(&self, id: &str) -> Option<bool> {
+        let jobs = lock(&self.jobs);
+        let job = jobs.get(id)?;
+        if job.state != JobState::Running {
+            return Some(false);
+        }
+        job.kill.notify_one();
+        Some(true)
+    }
+
+    fn prune(&self) {
+        lock(&self.jobs).retain(|_, job| job.finished.is_none_or(|t| t.elapsed() < JOB_RETENTION));
+    }
+}
+
+impl Drop for JobManager {
+    fn drop(&mut self) {
+        for job in lock(&self.jobs).values() {
+            if job.state == JobState::Running {
+                job.kill.notify_one();
+            }
+        }
+    }
+}
+
+fn pump<R>(mut reader: R, output: Arc<Mutex<OutputRing>>) -> tokio::task::JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 8192];
+        while let Ok(n) = reader.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            lock(&output).push(&buf[..n]);
+        }
+    })
+}
+
+fn finish(jobs: &Weak<JobTable>, id: &str, state: JobState) {
+    let Some(jobs) = jobs.upgrade() else {
+        return;
+    };
+    let mut table = lock(&jobs);
+    if let Some(job) = table.get_mut(id) {
+        job.state = state;
+        job.finished = Some(Instant::now());
+    }
+}
+
+/// Kill the job's whole process group, then the shell itself.
+async fn terminate(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id().and_then(|pid| i32::try_from(pid).ok()) {
+        // SAFETY: kill(2) with a negative pid only signals that process group.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+
+    fn manager() -> JobManager {
+        JobManager::new(Arc::new(NativeRuntime::new()))
+    }
+
+    async fn wait_until_done(manager: &JobManager, id: &str) -> JobInfo {
+        for _ in 0..100 {
+            let info = manager.status(id).unwrap();
+            if info.state != JobState::Running {
+                return info;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("job {id} did not finish");
+    }
+
+    #[test]
+    fn output_ring_keeps_the_tail_and_tracks_offsets() {
+        let mut ring = OutputRing::new(8);
+        ring.push(b"hello ");
+        ring.push(b"world!");
+        assert_eq!(ring.total(), 12);
+        assert_eq!(ring.tail(100), b"o world!");
+        assert_eq!(ring.since(10, 100), (b"d!".to_vec(), false));
+        assert_eq!(ring.since(0, 3), (b"o w".to_vec(), true));
+        ring.push(b"0123456789");
+        assert_eq!(ring.tail(100), b"23456789");
+    }
+
+    #[tokio::test]
+    async fn job_runs_in_background_and_keeps_output() {
+        let manager = manager();
+        let id = manager
+            .start(
+                "echo out; echo err >&2; exit 3",
+                &std::env::temp_dir(),
+                &[],
+                &ToolSandbox::none(),
+            )
+            .unwrap();
+        let info = wait_until_done(&manager, &id).await;
+        assert_eq!(info.state, JobState::Exited { code: Some(3) });
+
+        let (text, next, dropped) = manager.logs(&id, None, 1024).unwrap();
+        assert!(text.contains("out") && text.contains("err"));
+        assert!(!dropped);
+        let (rest, _, _) = manager.logs(&id, Some(next), 1024).unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn kill_stops_a_running_job() {
+        let manager = manager();
+        let id = manager
+            .start("sleep 30", &std::env::temp_dir(), &[], &ToolSandbox::none())
+            .unwrap();
+        assert_eq!(manager.kill(&id), Some(true));
+        let info = wait_until_done(&manager, &id).await;
+        assert_eq!(info.state, JobState::Killed);
+        assert_eq!(manager.kill(&id), Some(false));
+        assert_eq!(manager.kill("nope"), None);
+    }
+
+    #[tokio::test]
+    async fn running_jobs_are_capped() {
+        let manager = manager();
+        for _ in 0..MAX_RUNNING_JOBS {
+            manager
+                .start("sleep 30", &std::env::temp_dir(), &[], &ToolSandbox::none())
+                .unwrap();
+        }
+        let err = manager
+            .start("true", &std::env::temp_dir(), &[], &ToolSandbox::none())
+            .unwrap_err();
+        assert!(err.to_string().contains("already running"));
+    }
+}
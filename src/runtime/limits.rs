@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! OS-level resource limits for shell commands run on the host.
+//!
+//! On Unix the limits are rlimits set in the child before `exec`; on Windows
+//! the child is placed in a job object right after it is spawned. The Docker
+//! runtime passes its limits to `docker run` instead (cgroups).
+
+use crate::config::ResourceLimitsConfig;
+
+/// Set the CPU-time and memory rlimits of `limits` on `cmd`'s child.
+#[cfg(unix)]
+pub fn apply(cmd: &mut tokio::process::Command, limits: &ResourceLimitsConfig) {
+    let cpu_secs = limits.max_cpu_time_seconds;
+    let memory_bytes = u64::from(limits.max_memory_mb) * 1024 * 1024;
+    if cpu_secs == 0 && memory_bytes == 0 {
+        return;
+    }
+
+    // SAFETY: the closure runs in the forked child before exec and only calls
+    // setrlimit, which is async-signal-safe; it captures nothing but integers.
+    unsafe {
+        cmd.pre_exec(move || {
+            // The soft limit sends SIGXCPU, the hard one a second later SIGKILL.
+            if cpu_secs > 0
+                && libc::setrlimit(libc::RLIMIT_CPU, &rlimit(cpu_secs, cpu_secs.saturating_add(1))) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            // RLIMIT_DATA rather than RLIMIT_AS: runtimes such as V8 and rustc
+            // reserve far more address space than they ever touch.
+            if memory_bytes > 0
+                && libc::setrlimit(libc::RLIMIT_DATA, &rlimit(memory_bytes, memory_bytes)) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Limits are applied after spawning on this platform (see [`contain`]).
+#[cfg(not(unix))]
+pub fn apply(_cmd: &mut tokio::process::Command, _limits: &ResourceLimitsConfig) {}
+
+// `rlim_t` is 32 bits wide on some targets (e.g. 32-bit ARM Linux).
+#[cfg(unix)]
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn rlimit(soft: u64, hard: u64) -> libc::rlimit {
+    libc::rlimit {
+        rlim_cur: libc::rlim_t::try_from(soft).unwrap_or(libc::RLIM_INFINITY),
+        rlim_max: libc::rlim_t::try_from(hard).unwrap_or(libc::RLIM_INFINITY),
+    }
+}
+
+/// Keeps a spawned command contained until dropped. On Windows this holds
+/// the job object, and closing it kills whatever the command left running;
+/// elsewhere it is empty.
+pub struct Containment {
+    #[cfg(windows)]
+    _job: Option<windows::JobObject>,
+}
+
+/// Place `child` under `limits` where that can only happen after spawning.
+#[cfg(windows)]
+pub fn contain(
+    child: &tokio::process::Child,
+    limits: &ResourceLimitsConfig,
+) -> std::io::Result<Containment> {
+    let Some(process) = child.raw_handle() else {
+        // Already exited; nothing left to limit.
+        return Ok(Containment { _job: None });
+    };
+    let job = windows::JobObject::new(limits)?;
+    job.assign(process)?;
+    Ok(Containment { _job: Some(job) })
+}
+
+/// Place `child` under `limits` where that can only happen after spawning.
+#[cfg(not(windows))]
+#[allow(clippy::unnecessary_wraps)]
+pub fn contain(
+    _child: &tokio::process::Child,
+    _limits: &ResourceLimitsConfig,
+) -> std::io::Result<Containment> {
+    Ok(Containment {})
+}
+
+#[cfg(windows)]
+mod windows {
+    use crate::config::ResourceLimitsConfig;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_ACTIVE_PROCESS, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        JOB_OBJECT_LIMIT_PROCESS_MEMORY, JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+
+    /// Job object time limits are counted in 100ns ticks.
+    const TICKS_PER_SECOND: i64 = 10_000_000;
+
+    pub struct JobObject(HANDLE);
+
+    // SAFETY: a job object handle may be used and closed from any thread.
+    unsafe impl Send for JobObject {}
+
+    impl JobObject {
+        pub fn new(limits: &ResourceLimitsConfig) -> std::io::Result<Self> {
+            // SAFETY: null attributes and name create an anonymous job object.
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let job = Self(handle);
+
+            // SAFETY: the struct is plain old data; all-zero means "no limits".
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+            let basic = &mut info.BasicLimitInformation;
+            basic.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if limits.max_cpu_time_seconds > 0 {
+                basic.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                basic.PerProcessUserTimeLimit = i64::try_from(limits.max_cpu_time_seconds)
+                    .unwrap_or(i64::MAX)
+                    .saturating_mul(TICKS_PER_SECOND);
+            }
+            if limits.max_subprocesses > 0 {
+                basic.LimitFlags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+                // The shell itself counts as one active process.
+                basic.ActiveProcessLimit = limits.max_subprocesses.saturating_add(1);
+            }
+            if limits.max_memory_mb > 0 {
+                basic.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                info.ProcessMemoryLimit = usize::try_from(limits.max_memory_mb)
+                    .unwrap_or(usize::MAX)
+                    .saturating_mul(1024 * 1024);
+            }
+
+            let size = u32::try_from(std::mem::size_of_val(&info)).unwrap_or(u32::MAX);
+            // SAFETY: `info` is a valid JOBOBJECT_EXTENDED_LIMIT_INFORMATION of `size` bytes.
+            let ok = unsafe {
+                SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    std::ptr::from_ref(&info).cast(),
+                    size,
+                )
+            };
+            if ok == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+
+        pub fn assign(&self, process: std::os::windows::io::RawHandle) -> std::io::Result<()> {
+            // SAFETY: both handles are open for the duration of the call.
+            if unsafe { AssignProcessToJobObject(self.0, process) } == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for JobObject {
+        fn drop(&mut self) {
+            // SAFETY: the handle came from CreateJobObjectW and is closed once.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    async fn ulimit(flag: &str, limits: &ResourceLimitsConfig) -> String {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(format!("ulimit {flag}"));
+        apply(&mut cmd, limits);
+        let output = cmd.output().await.unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn unix_rlimits_are_set_in_the_child() {
+        let limits = ResourceLimitsConfig {
+            max_cpu_time_seconds: 7,
+            max_memory_mb: 64,
+            ..ResourceLimitsConfig::default()
+        };
+        assert_eq!(ulimit("-t", &limits).await, "7");
+        assert_eq!(ulimit("-d", &limits).await, (64 * 1024).to_string());
+    }
+
+    #[tokio::test]
+    async fn zero_means_unlimited() {
+        let limits = ResourceLimitsConfig {
+            max_cpu_time_seconds: 0,
+            max_memory_mb: 0,
+            ..ResourceLimitsConfig::default()
+        };
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg("ulimit -t");
+        let inherited = String::from_utf8_lossy(&cmd.output().await.unwrap().stdout)
+            .trim()
+            .to_string();
+        assert_eq!(ulimit("-t", &limits).await, inherited);
+    }
+}
@@ -3,6 +3,8 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 pub mod docker;
+pub mod jobs;
+pub mod limits;
 pub mod native;
 pub mod traits;
 
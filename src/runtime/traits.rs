@@ -2,6 +2,8 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use crate::config::ResourceLimitsConfig;
+use crate::security::detect::ToolSandbox;
 use std::path::{Path, PathBuf};
 
 /// Runtime adapter — abstracts platform differences so the same agent
@@ -27,12 +29,50 @@ pub trait RuntimeAdapter: Send + Sync {
         0
     }
 
+    /// Whether commands already run isolated from the host, so tool
+    /// sandboxes are not applied
+    fn isolates_commands(&self) -> bool {
+        false
+    }
+
     /// Build a shell command process for this runtime.
     fn build_shell_command(
         &self,
         command: &str,
         workspace_dir: &Path,
     ) -> anyhow::Result<tokio::process::Command>;
+
+    /// Build a shell command that runs under `limits`. The default sets OS
+    /// limits on the host process (see [`super::limits`]).
+    fn build_limited_shell_command(
+        &self,
+        command: &str,
+        workspace_dir: &Path,
+        limits: &ResourceLimitsConfig,
+    ) -> anyhow::Result<tokio::process::Command> {
+        let mut process = self.build_shell_command(command, workspace_dir)?;
+        super::limits::apply(&mut process, limits);
+        Ok(process)
+    }
+
+    /// Build a shell command that runs under `limits` in `sandbox`. The
+    /// sandbox wraps the command before the limits are set, so they apply
+    /// to the sandboxed process tree.
+    fn build_sandboxed_shell_command(
+        &self,
+        command: &str,
+        workspace_dir: &Path,
+        limits: &ResourceLimitsConfig,
+        sandbox: &ToolSandbox,
+    ) -> anyhow::Result<tokio::process::Command> {
+        if sandbox.profile() == crate::config::SandboxProfile::None {
+            return self.build_limited_shell_command(command, workspace_dir, limits);
+        }
+        let mut process = self.build_shell_command(command, workspace_dir)?;
+        sandbox.wrap(&mut process, workspace_dir)?;
+        super::limits::apply(&mut process, limits);
+        Ok(process)
+    }
 }
 
 #[cfg(test)]
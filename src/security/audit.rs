@@ -3,17 +3,46 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 //! Audit logging for security events
-
-use crate::config::AuditConfig;
-use anyhow::Result;
+//!
+//! Every event carries a sequence number and the SHA-256 of the line written
+//! before it, so editing, inserting or removing lines breaks the chain (see
+//! [`AuditLogger::verify_log`]). Signed events additionally carry an HMAC or
+//! Ed25519 signature over everything else in the event.
+
+use crate::config::{AuditConfig, AuditSignature};
+use crate::security::detect::ToolSandbox;
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signer, SigningKey, Verifier};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+/// Signing key files, next to the log and encrypted with the secret store.
+const SIGNING_KEY_FILE: &str = "audit_signing.key";
+const ED25519_KEY_FILE: &str = "audit_ed25519.key";
+/// Hex Ed25519 public key, for checking signatures elsewhere.
+const ED25519_PUBLIC_FILE: &str = "audit_ed25519.pub";
+const SIGNATURE_PREFIX: &str = "hmac-sha256:";
+const ED25519_PREFIX: &str = "ed25519:";
+const HASH_PREFIX: &str = "sha256:";
+
+/// How far back from the end of the log to look for the last line.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Serializes appends across every logger in the process, so each event
+/// chains to the line actually written before it.
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
 /// Audit event types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -27,6 +56,9 @@ pub enum AuditEventType {
     SecurityEvent,
     SigilInterception,
     DelegationCrossing,
+    DataErasure,
+    VaultAccess,
+    NetworkEgress,
 }
 
 /// Actor information (who performed the action)
@@ -61,6 +93,9 @@ pub struct SecurityContext {
     pub policy_violation: bool,
     pub rate_limit_remaining: Option<u32>,
     pub sandbox_backend: Option<String>,
+    /// Sandbox profile the command ran under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_profile: Option<String>,
 }
 
 /// Complete audit event
@@ -73,6 +108,15 @@ pub struct AuditEvent {
     pub action: Option<Action>,
     pub result: Option<ExecutionResult>,
     pub security: SecurityContext,
+    /// Position in the hash chain, starting at 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    /// SHA-256 of the previous log line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    /// Signature over the rest of the event, see [`AuditLogger::verify`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl AuditEvent {
@@ -89,7 +133,11 @@ impl AuditEvent {
                 policy_violation: false,
                 rate_limit_remaining: None,
                 sandbox_backend: None,
+                sandbox_profile: None,
             },
+            seq: None,
+            prev_hash: None,
+            signature: None,
         }
     }
 
@@ -147,6 +195,50 @@ impl AuditEvent {
         self.security.sandbox_backend = sandbox_backend;
         self
     }
+
+    /// Record the sandbox backend and the profile it enforced
+    pub fn with_sandbox(mut self, sandbox: &ToolSandbox) -> Self {
+        self.security.sandbox_backend = Some(sandbox.backend().to_string());
+        self.security.sandbox_profile = Some(sandbox.profile().to_string());
+        self
+    }
+}
+
+/// Outcome of [`AuditLogger::verify_log`]
+#[derive(Debug, Default, Serialize)]
+pub struct AuditVerification {
+    /// Log files checked, oldest first
+    pub files: Vec<PathBuf>,
+    pub events: usize,
+    pub signed: usize,
+    /// Events written before hash chaining, which the chain cannot vouch for
+    pub unchained: usize,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl AuditVerification {
+    /// Whether no gaps, broken links or bad signatures were found.
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn flag(&mut self, file: &Path, line: usize, seq: Option<u64>, problem: String) {
+        self.issues.push(AuditIssue {
+            file: file.to_path_buf(),
+            line,
+            seq,
+            problem,
+        });
+    }
+}
+
+/// A problem found in the audit log
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditIssue {
+    pub file: PathBuf,
+    pub line: usize,
+    pub seq: Option<u64>,
+    pub problem: String,
 }
 
 /// Audit logger
@@ -154,34 +246,244 @@ pub struct AuditLogger {
     log_path: PathBuf,
     config: AuditConfig,
     buffer: Mutex<Vec<AuditEvent>>,
+    mymolt_dir: PathBuf,
+    signing_key: OnceLock<Vec<u8>>,
+    ed25519_key: OnceLock<SigningKey>,
 }
 
 impl AuditLogger {
     /// Create a new audit logger
     pub fn new(config: AuditConfig, mymolt_dir: PathBuf) -> Result<Self> {
         let log_path = mymolt_dir.join(&config.log_path);
-        Ok(Self {
+        let logger = Self {
             log_path,
             config,
             buffer: Mutex::new(Vec::new()),
-        })
+            mymolt_dir,
+            signing_key: OnceLock::new(),
+            ed25519_key: OnceLock::new(),
+        };
+        if let Err(e) = logger.purge_expired() {
+            tracing::warn!("Failed to purge expired audit logs: {e}");
+        }
+        Ok(logger)
     }
 
     pub fn log_path(&self) -> &std::path::Path {
         &self.log_path
     }
 
-    /// Log an event
+    /// Log an event, signed when `sign_events` is set
     pub fn log(&self, event: &AuditEvent) -> Result<()> {
+        self.write(event, self.config.sign_events)
+    }
+
+    /// Log an event with a signature, whatever `sign_events` says; for
+    /// records that must hold up later, such as data erasure.
+    pub fn log_signed(&self, event: &AuditEvent) -> Result<()> {
+        self.write(event, true)
+    }
+
+    /// Whether `event` carries a valid signature from this installation.
+    pub fn verify(&self, event: &AuditEvent) -> Result<bool> {
+        let Some(signature) = event.signature.as_deref() else {
+            return Ok(false);
+        };
+        if let Some(tag) = signature.strip_prefix(SIGNATURE_PREFIX) {
+            let Ok(tag) = hex::decode(tag) else {
+                return Ok(false);
+            };
+            return Ok(self.mac(event)?.verify_slice(&tag).is_ok());
+        }
+        if let Some(signature) = signature.strip_prefix(ED25519_PREFIX) {
+            let Ok(signature) = hex::decode(signature) else {
+                return Ok(false);
+            };
+            let Ok(signature) = ed25519_dalek::Signature::from_slice(&signature) else {
+                return Ok(false);
+            };
+            return Ok(self
+                .ed25519_key()?
+                .verifying_key()
+                .verify(&Self::signed_bytes(event)?, &signature)
+                .is_ok());
+        }
+        Ok(false)
+    }
+
+    /// Check every retained log file, oldest first: the sequence has no
+    /// gaps, each event names the hash of the line before it, and signed
+    /// events verify. Removing events from the very end of the newest file
+    /// leaves no gap and is not detected.
+    pub fn verify_log(&self) -> Result<AuditVerification> {
+        let mut report = AuditVerification::default();
+        // Sequence number and hash of the previous line, if it was readable
+        let mut prev: Option<(Option<u64>, String)> = None;
+
+        for path in self.log_files() {
+            let content = read_log_file(&path)?;
+            for (index, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let number = index + 1;
+                report.events += 1;
+                let event: AuditEvent = match serde_json::from_str(line) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        report.flag(&path, number, None, format!("unreadable event: {e}"));
+                        prev = None;
+                        continue;
+                    }
+                };
+
+                match (event.seq, &prev) {
+                    (None, Some((Some(_), _))) => {
+                        report.unchained += 1;
+                        report.flag(&path, number, None, "event outside the hash chain".into());
+                    }
+                    (None, _) => report.unchained += 1,
+                    (Some(seq), Some((prev_seq, prev_hash))) => {
+                        let expected = prev_seq.map_or(1, |s| s + 1);
+                        if seq > expected {
+                            let missing = format!("events {expected}..{} missing", seq - 1);
+                            report.flag(&path, number, Some(seq), missing);
+                        } else if seq < expected {
+                            let problem = format!("sequence goes back to {seq}");
+                            report.flag(&path, number, Some(seq), problem);
+                        } else if event.prev_hash.as_deref() != Some(prev_hash.as_str()) {
+                            let problem = "previous event was altered (hash chain broken)";
+                            report.flag(&path, number, Some(seq), problem.into());
+                        }
+                    }
+                    // The start of the retained history; older files rotated out.
+                    (Some(_), None) => {}
+                }
+
+                if event.signature.is_some() {
+                    report.signed += 1;
+                    match self.verify(&event) {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            report.flag(&path, number, event.seq, "invalid signature".into());
+                        }
+                        Err(e) => {
+                            let problem = format!("signature not checked: {e}");
+                            report.flag(&path, number, event.seq, problem);
+                        }
+                    }
+                }
+                prev = Some((event.seq, line_hash(line)));
+            }
+            report.files.push(path);
+        }
+        Ok(report)
+    }
+
+    fn sign(&self, event: &AuditEvent) -> Result<String> {
+        match self.config.signature {
+            AuditSignature::Hmac => {
+                let tag = self.mac(event)?.finalize().into_bytes();
+                Ok(format!("{SIGNATURE_PREFIX}{}", hex::encode(tag)))
+            }
+            AuditSignature::Ed25519 => {
+                let signature = self.ed25519_key()?.sign(&Self::signed_bytes(event)?);
+                Ok(format!(
+                    "{ED25519_PREFIX}{}",
+                    hex::encode(signature.to_bytes())
+                ))
+            }
+        }
+    }
+
+    /// `event` without its signature, as signed.
+    fn signed_bytes(event: &AuditEvent) -> Result<Vec<u8>> {
+        let unsigned = AuditEvent {
+            signature: None,
+            ..event.clone()
+        };
+        Ok(serde_json::to_vec(&unsigned)?)
+    }
+
+    /// HMAC state over `event` without its signature.
+    fn mac(&self, event: &AuditEvent) -> Result<Hmac<Sha256>> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_key()?)
+            .expect("HMAC accepts any key length");
+        mac.update(&Self::signed_bytes(event)?);
+        Ok(mac)
+    }
+
+    /// The HMAC signing key, created on first use.
+    fn signing_key(&self) -> Result<&[u8]> {
+        if let Some(key) = self.signing_key.get() {
+            return Ok(key.as_slice());
+        }
+        let key = self.load_or_create_key(SIGNING_KEY_FILE)?;
+        Ok(self.signing_key.get_or_init(|| key).as_slice())
+    }
+
+    /// The Ed25519 signing key, created on first use along with the public
+    /// key file.
+    fn ed25519_key(&self) -> Result<&SigningKey> {
+        if let Some(key) = self.ed25519_key.get() {
+            return Ok(key);
+        }
+        let seed: [u8; 32] = self
+            .load_or_create_key(ED25519_KEY_FILE)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Corrupt audit Ed25519 key"))?;
+        let key = SigningKey::from_bytes(&seed);
+        let public_path = self.mymolt_dir.join(ED25519_PUBLIC_FILE);
+        if !public_path.exists() {
+            std::fs::write(&public_path, hex::encode(key.verifying_key().as_bytes()))?;
+        }
+        Ok(self.ed25519_key.get_or_init(|| key))
+    }
+
+    /// 32 random bytes kept in `file`, encrypted with the secret store.
+    fn load_or_create_key(&self, file: &str) -> Result<Vec<u8>> {
+        let path = self.mymolt_dir.join(file);
+        let secrets = SecretStore::new(&self.mymolt_dir, true);
+        if path.exists() {
+            let raw = std::fs::read_to_string(&path).context("Failed to read audit signing key")?;
+            return hex::decode(secrets.decrypt(raw.trim())?.trim())
+                .context("Corrupt audit signing key");
+        }
+        let key: [u8; 32] = rand::random();
+        std::fs::create_dir_all(&self.mymolt_dir)?;
+        std::fs::write(&path, secrets.encrypt(&hex::encode(key))?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(key.to_vec())
+    }
+
+    fn write(&self, event: &AuditEvent, sign: bool) -> Result<()> {
         if !self.config.enabled {
             return Ok(());
         }
+        let _append = APPEND_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
 
         // Check log size and rotate if needed
         self.rotate_if_needed()?;
 
+        // Chain to the last line written, wherever it now lives
+        let mut event = event.clone();
+        let (seq, prev_hash) = match self.last_line()? {
+            Some(line) => (chain_seq(&line), Some(line_hash(&line))),
+            None => (0, None),
+        };
+        event.seq = Some(seq + 1);
+        event.prev_hash = prev_hash;
+        event.signature = None;
+        if sign {
+            event.signature = Some(self.sign(&event)?);
+        }
+
         // Serialize and write
-        let line = serde_json::to_string(event)?;
+        let line = serde_json::to_string(&event)?;
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -193,6 +495,92 @@ impl AuditLogger {
         Ok(())
     }
 
+    /// The last line of the log, or of the most recent rotated file when the
+    /// log was just rotated.
+    fn last_line(&self) -> Result<Option<String>> {
+        if let Ok(mut file) = std::fs::File::open(&self.log_path) {
+            let len = file.metadata()?.len();
+            file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))?;
+            let mut tail = String::new();
+            file.read_to_string(&mut tail)?;
+            if let Some(line) = tail.lines().rev().find(|l| !l.trim().is_empty()) {
+                return Ok(Some(line.to_string()));
+            }
+        }
+        let Some(rotated) = self.rotated_files().pop() else {
+            return Ok(None);
+        };
+        Ok(read_log_file(&rotated)?
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .map(str::to_string))
+    }
+
+    /// Every retained log line, oldest first, across rotated and compressed
+    /// files.
+    pub fn read_lines(&self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        for path in self.log_files() {
+            let content = read_log_file(&path)?;
+            lines.extend(
+                content
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(str::to_string),
+            );
+        }
+        Ok(lines)
+    }
+
+    /// Retained log files, oldest first, ending with the live log.
+    pub fn log_files(&self) -> Vec<PathBuf> {
+        let mut files = self.rotated_files();
+        if self.log_path.exists() {
+            files.push(self.log_path.clone());
+        }
+        files
+    }
+
+    /// Rotated log files, oldest first: numbered files from before
+    /// timestamped rotation (highest number oldest), then timestamped ones.
+    fn rotated_files(&self) -> Vec<PathBuf> {
+        let (Some(dir), Some(name)) = (
+            self.log_path.parent(),
+            self.log_path.file_name().and_then(|n| n.to_str()),
+        ) else {
+            return Vec::new();
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let prefix = format!("{name}.");
+        let mut numbered = Vec::new();
+        let mut stamped = Vec::new();
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(middle) = file_name.to_str().and_then(|f| {
+                let rest = f.strip_prefix(&prefix)?;
+                rest.strip_suffix(".log.gz")
+                    .or_else(|| rest.strip_suffix(".log"))
+            }) else {
+                continue;
+            };
+            match middle.parse::<u32>() {
+                Ok(n) => numbered.push((n, entry.path())),
+                Err(_) => stamped.push((middle.to_string(), entry.path())),
+            }
+        }
+        numbered.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+        stamped.sort();
+        numbered
+            .into_iter()
+            .map(|(_, path)| path)
+            .chain(stamped.into_iter().map(|(_, path)| path))
+            .collect()
+    }
+
     /// Log a command execution event
     pub fn log_command(
         &self,
@@ -217,29 +605,122 @@ impl AuditLogger {
         self.log(&event)
     }
 
-    /// Rotate log if it exceeds max size
+    /// Rotate log if it exceeds max size or its oldest event is older than
+    /// `rotate_days`, then purge expired files
     fn rotate_if_needed(&self) -> Result<()> {
         if let Ok(metadata) = std::fs::metadata(&self.log_path) {
             let current_size_mb = metadata.len() / (1024 * 1024);
-            if current_size_mb >= self.config.max_size_mb as u64 {
+            if current_size_mb >= u64::from(self.config.max_size_mb) || self.is_due_by_age() {
                 self.rotate()?;
+                self.purge_expired()?;
             }
         }
         Ok(())
     }
 
-    /// Rotate the log file
-    fn rotate(&self) -> Result<()> {
-        for i in (1..10).rev() {
-            let old_name = format!("{}.{}.log", self.log_path.display(), i);
-            let new_name = format!("{}.{}.log", self.log_path.display(), i + 1);
-            let _ = std::fs::rename(&old_name, &new_name);
+    /// Whether the first event in the log is older than `rotate_days`.
+    fn is_due_by_age(&self) -> bool {
+        #[derive(Deserialize)]
+        struct Stamp {
+            timestamp: DateTime<Utc>,
+        }
+
+        if self.config.rotate_days == 0 {
+            return false;
+        }
+        let Ok(file) = std::fs::File::open(&self.log_path) else {
+            return false;
+        };
+        let mut first = String::new();
+        if BufReader::new(file).read_line(&mut first).is_err() {
+            return false;
         }
+        serde_json::from_str::<Stamp>(&first).is_ok_and(|s| {
+            Utc::now() - s.timestamp >= chrono::Duration::days(i64::from(self.config.rotate_days))
+        })
+    }
 
-        let rotated = format!("{}.1.log", self.log_path.display());
+    /// Rotate the log file to `<log>.<timestamp>.log`, gzipped when
+    /// `compress` is set
+    fn rotate(&self) -> Result<()> {
+        let rotated = loop {
+            let stamp = Utc::now().format("%Y%m%dT%H%M%S%.9f");
+            let candidate = PathBuf::from(format!("{}.{stamp}.log", self.log_path.display()));
+            if !candidate.exists() && !gz_path(&candidate).exists() {
+                break candidate;
+            }
+        };
         std::fs::rename(&self.log_path, &rotated)?;
+
+        if self.config.compress {
+            let compressed = gz_path(&rotated);
+            let staged = compressed.with_extension("gz.tmp");
+            let mut encoder = GzEncoder::new(
+                std::fs::File::create(&staged)?,
+                flate2::Compression::default(),
+            );
+            std::io::copy(&mut std::fs::File::open(&rotated)?, &mut encoder)?;
+            encoder.finish()?.sync_all()?;
+            std::fs::rename(&staged, &compressed)?;
+            std::fs::remove_file(&rotated)?;
+        }
         Ok(())
     }
+
+    /// Delete rotated files last written more than `retention_days` ago.
+    fn purge_expired(&self) -> Result<()> {
+        if self.config.retention_days == 0 {
+            return Ok(());
+        }
+        let retention = Duration::from_secs(u64::from(self.config.retention_days) * 86_400);
+        let Some(cutoff) = SystemTime::now().checked_sub(retention) else {
+            return Ok(());
+        };
+        for path in self.rotated_files() {
+            if std::fs::metadata(&path)?.modified()? < cutoff {
+                std::fs::remove_file(&path)?;
+                tracing::info!("Purged expired audit log {}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Contents of a log file, decompressing `.gz` files.
+fn read_log_file(path: &Path) -> Result<String> {
+    let mut content = String::new();
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if path.extension().is_some_and(|e| e == "gz") {
+        GzDecoder::new(file).read_to_string(&mut content)?;
+    } else {
+        BufReader::new(file).read_to_string(&mut content)?;
+    }
+    Ok(content)
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.gz", path.display()))
+}
+
+/// Hash of a log line as written, which the next event records.
+fn line_hash(line: &str) -> String {
+    format!(
+        "{HASH_PREFIX}{}",
+        hex::encode(Sha256::digest(line.as_bytes()))
+    )
+}
+
+/// Sequence number of a logged line; 0 for events from before chaining.
+fn chain_seq(line: &str) -> u64 {
+    #[derive(Deserialize)]
+    struct Chain {
+        seq: Option<u64>,
+    }
+    serde_json::from_str::<Chain>(line)
+        .ok()
+        .and_then(|c| c.seq)
+        .unwrap_or(0)
 }
 
 #[cfg(test)]
@@ -336,16 +817,190 @@ mod tests {
         let event2 = AuditEvent::new(AuditEventType::SecurityEvent);
         logger.log(&event2)?;
 
-        // The rotated file should exist
-        let rotated = format!("{}.1.log", log_path.display());
-        assert!(
-            std::path::Path::new(&rotated).exists(),
-            "Rotated log file should exist at {rotated}"
-        );
+        // The rotated file should exist, compressed by default
+        let rotated = logger.rotated_files();
+        assert_eq!(rotated.len(), 1);
+        assert!(rotated[0].to_string_lossy().ends_with(".log.gz"));
 
         // The main log should still exist (with the new event)
         assert!(log_path.exists());
 
         Ok(())
     }
+
+    #[test]
+    fn signed_events_verify_and_detect_tampering() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let logger = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf())?;
+        let event = AuditEvent::new(AuditEventType::DataErasure).with_action(
+            "Erased 3 memories".to_string(),
+            "high".to_string(),
+            true,
+            true,
+        );
+
+        logger.log_signed(&event)?;
+        let line = std::fs::read_to_string(tmp.path().join("audit.log"))?;
+        let mut logged: AuditEvent = serde_json::from_str(line.trim())?;
+        assert!(logged.signature.is_some());
+        assert!(logger.verify(&logged)?);
+
+        // The key survives a restart.
+        let reopened = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf())?;
+        assert!(reopened.verify(&logged)?);
+
+        logged.action.as_mut().unwrap().command = Some("Erased 1 memory".to_string());
+        assert!(!logger.verify(&logged)?);
+        assert!(!logger.verify(&event)?);
+        Ok(())
+    }
+
+    fn log_lines(dir: &Path) -> Vec<String> {
+        std::fs::read_to_string(dir.join("audit.log"))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn write_lines(dir: &Path, lines: &[String]) {
+        std::fs::write(dir.join("audit.log"), lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn events_are_chained_and_edits_or_gaps_are_found() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let logger = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf())?;
+        for command in ["ls", "cat notes.md", "rm notes.md", "ls"] {
+            let event = AuditEvent::new(AuditEventType::CommandExecution).with_action(
+                command.to_string(),
+                "low".to_string(),
+                true,
+                true,
+            );
+            logger.log(&event)?;
+        }
+        logger.log_signed(&AuditEvent::new(AuditEventType::DataErasure))?;
+
+        let report = logger.verify_log()?;
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!((report.events, report.signed), (5, 1));
+        let original = log_lines(tmp.path());
+        let second: AuditEvent = serde_json::from_str(&original[1])?;
+        assert_eq!(second.seq, Some(2));
+        assert_eq!(second.prev_hash, Some(line_hash(&original[0])));
+
+        // Rewriting a command breaks the link from the next event.
+        let mut edited = original.clone();
+        edited[2] = edited[2].replace("rm notes.md", "ls -la");
+        write_lines(tmp.path(), &edited);
+        let report = logger.verify_log()?;
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].seq, Some(4));
+
+        // Dropping a line leaves a gap.
+        let mut dropped = original.clone();
+        dropped.remove(2);
+        write_lines(tmp.path(), &dropped);
+        let report = logger.verify_log()?;
+        assert!(report.issues[0].problem.contains("events 3..3 missing"));
+
+        // Re-signing is impossible without the key.
+        let mut forged = original;
+        forged[4] = forged[4].replace("data_erasure", "security_event");
+        write_lines(tmp.path(), &forged);
+        let report = logger.verify_log()?;
+        assert_eq!(report.issues[0].problem, "invalid signature");
+        Ok(())
+    }
+
+    #[test]
+    fn ed25519_signatures_verify_and_export_the_public_key() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            signature: AuditSignature::Ed25519,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        logger.log_signed(&AuditEvent::new(AuditEventType::VaultAccess))?;
+
+        let line = &log_lines(tmp.path())[0];
+        let logged: AuditEvent = serde_json::from_str(line)?;
+        assert!(logged
+            .signature
+            .as_deref()
+            .unwrap()
+            .starts_with(ED25519_PREFIX));
+        assert!(logger.verify(&logged)?);
+        assert!(tmp.path().join(ED25519_PUBLIC_FILE).exists());
+        assert!(logger.verify_log()?.is_intact());
+        Ok(())
+    }
+
+    #[test]
+    fn chain_continues_across_rotation_and_legacy_lines() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let legacy = serde_json::to_string(&AuditEvent::new(AuditEventType::AuthSuccess))?;
+        write_lines(tmp.path(), &[legacy]);
+        let config = AuditConfig {
+            max_size_mb: 0,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+
+        let report = logger.verify_log()?;
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!(report.files.len(), 3);
+        assert_eq!((report.events, report.unchained), (3, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn rotates_by_age_and_reads_compressed_and_legacy_files() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let mut old = AuditEvent::new(AuditEventType::AuthSuccess);
+        old.timestamp = Utc::now() - chrono::Duration::days(31);
+        write_lines(tmp.path(), &[serde_json::to_string(&old)?]);
+        std::fs::write(tmp.path().join("audit.log.2.log"), "two\n")?;
+        std::fs::write(tmp.path().join("audit.log.1.log"), "one\n")?;
+
+        let logger = AuditLogger::new(AuditConfig::default(), tmp.path().to_path_buf())?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+
+        let files = logger.log_files();
+        assert_eq!(files.len(), 4);
+        assert!(files[2].to_string_lossy().ends_with(".log.gz"));
+        let lines = logger.read_lines()?;
+        assert_eq!(lines.len(), 4);
+        assert_eq!(&lines[..2], ["two", "one"]);
+        assert!(lines[2].contains(&old.event_id));
+        assert_eq!(log_lines(tmp.path()).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn retention_purges_expired_rotated_files() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let expired = tmp
+            .path()
+            .join("audit.log.20250101T000000.000000000.log.gz");
+        let recent = tmp.path().join("audit.log.1.log");
+        std::fs::write(&expired, b"")?;
+        std::fs::write(&recent, "kept\n")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&expired)?
+            .set_modified(SystemTime::now() - chrono::Duration::days(8).to_std()?)?;
+
+        let config = AuditConfig {
+            retention_days: 7,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        assert!(!expired.exists());
+        assert_eq!(logger.log_files(), vec![recent]);
+        Ok(())
+    }
 }
@@ -4,7 +4,9 @@
 
 //! Bubblewrap sandbox (user namespaces for Linux/macOS)
 
-use crate::security::traits::Sandbox;
+use crate::config::SandboxProfile;
+use crate::security::traits::{rewrap, Sandbox};
+use std::path::Path;
 use std::process::Command;
 
 /// Bubblewrap sandbox backend
@@ -70,6 +72,23 @@ impl Sandbox for BubblewrapSandbox {
         Self::is_installed()
     }
 
+    fn supports(&self, _profile: SandboxProfile) -> bool {
+        true
+    }
+
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SandboxProfile,
+        workspace_dir: &Path,
+    ) -> std::io::Result<()> {
+        if profile == SandboxProfile::None {
+            return Ok(());
+        }
+        rewrap(cmd, "bwrap", profile_args(profile, workspace_dir));
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "bubblewrap"
     }
@@ -79,6 +98,29 @@ impl Sandbox for BubblewrapSandbox {
     }
 }
 
+/// Bubblewrap flags for `profile`. Later binds take precedence, so the
+/// workspace and `/tmp` stay writable under a read-only root.
+fn profile_args(profile: SandboxProfile, workspace_dir: &Path) -> Vec<String> {
+    let root = if profile.restricts_filesystem() {
+        "--ro-bind"
+    } else {
+        "--bind"
+    };
+    let mut args: Vec<String> = [root, "/", "/", "--dev", "/dev", "--proc", "/proc"]
+        .map(String::from)
+        .to_vec();
+    if profile.restricts_filesystem() {
+        let workspace = workspace_dir.display().to_string();
+        args.extend(["--bind".into(), workspace.clone(), workspace]);
+        args.extend(["--bind", "/tmp", "/tmp"].map(String::from));
+    }
+    if profile.restricts_network() {
+        args.push("--unshare-net".into());
+    }
+    args.push("--die-with-parent".into());
+    args
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,4 +137,17 @@ mod tests {
         // Either way, the name should still work
         assert_eq!(BubblewrapSandbox.name(), "bubblewrap");
     }
+
+    #[test]
+    fn bubblewrap_profile_args() {
+        let workspace = Path::new("/srv/workspace");
+        let readonly = profile_args(SandboxProfile::ReadonlyFs, workspace).join(" ");
+        assert!(readonly.starts_with("--ro-bind / /"));
+        assert!(readonly.contains("--bind /srv/workspace /srv/workspace"));
+        assert!(!readonly.contains("--unshare-net"));
+
+        let offline = profile_args(SandboxProfile::NoNetwork, workspace).join(" ");
+        assert!(offline.starts_with("--bind / /"));
+        assert!(offline.contains("--unshare-net"));
+    }
 }
@@ -10,16 +10,150 @@
 //!
 //! 1. Generates a unique request ID
 //! 2. Stores a `oneshot::Sender` in a pending map
-//! 3. Notifies all registered listeners (WebSocket, Telegram, CLI)
-//! 4. Awaits the `oneshot::Receiver` with a 30-second timeout
+//! 3. Notifies all registered listeners (WebSocket, the chat relay in
+//!    `channels::confirm_relay`, CLI)
+//! 4. Awaits the `oneshot::Receiver` with the configured timeout
 //!
-//! The frontend resolves the request by calling `gate.resolve(id, approved)`.
+//! The frontend or a chat reply resolves the request by calling
+//! `gate.resolve_as(id, approved, role)`; approving needs the role the
+//! tool's [`ConfirmationPolicy`] names.
 //! If no response arrives within the timeout, the request is auto-denied.
-
+//!
+//! An approval may be remembered for a few minutes: further calls of the
+//! tool in the same session (see [`with_session`]) then go through without
+//! asking again.
+//!
+//! Destructive actions can require dual control: the request stays pending,
+//! with the approvals so far listed in `approved_by`, until enough distinct
+//! people have approved. A single denial still denies it. Each person
+//! approves under one [`approver_identity`], on the dashboard and in chat.
+
+use crate::config::ConfirmationConfig;
+use crate::identity::family::member_scope;
+use crate::identity::UserRole;
+use crate::network::content_filter::role_key;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::{oneshot, Mutex, broadcast};
 
+tokio::task_local! {
+    static SESSION: String;
+}
+
+/// Run `fut` as part of `session` (e.g. one chat or dashboard connection),
+/// so approvals remembered there cover its tool calls.
+pub async fn with_session<F: Future>(session: String, fut: F) -> F::Output {
+    SESSION.scope(session, fut).await
+}
+
+/// Session of the running task; `None` outside [`with_session`], where
+/// approvals are never remembered.
+pub fn current_session() -> Option<String> {
+    SESSION.try_with(Clone::clone).ok()
+}
+
+/// Approver identity of the owner: the dashboard without a member session,
+/// and chat recipients not in the family registry.
+pub const OWNER_APPROVER: &str = "owner";
+
+/// The person behind an answer: the family member called `member`, else the
+/// owner. Dashboard and chat answers of one person map to the same identity,
+/// so they count once towards dual control.
+pub fn approver_identity(member: Option<&str>) -> String {
+    member.map_or_else(|| OWNER_APPROVER.to_string(), member_scope)
+}
+
+/// How requests for one tool are confirmed, resolved from
+/// `[security.confirmation]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationPolicy {
+    /// Whether the tool's policy turns confirmation on or off
+    pub required: Option<bool>,
+    /// Risk level shown with the request
+    pub risk_level: String,
+    /// Least role that may approve
+    pub approver: UserRole,
+    /// Seconds until auto-deny
+    pub timeout_secs: u64,
+    /// How long an approval covers further calls in the same session
+    pub remember: Duration,
+    /// Distinct people who must approve
+    pub approvals: u32,
+}
+
+impl ConfirmationPolicy {
+    /// Policy for `tool`: its own settings, then those of its risk level,
+    /// then the defaults.
+    pub fn for_tool(config: &ConfirmationConfig, tool: &str) -> Self {
+        Self::with_default_timeout(config, tool, config.timeout_secs)
+    }
+
+    /// Like [`Self::for_tool`], waiting `timeout_secs` unless the tool or
+    /// its risk level sets a timeout. For requests that take longer to
+    /// answer than a tool call, such as vault access.
+    pub fn with_default_timeout(
+        config: &ConfirmationConfig,
+        tool: &str,
+        timeout_secs: u64,
+    ) -> Self {
+        let own = config.tools.get(tool);
+        let risk_level = own
+            .and_then(|p| p.risk.as_deref())
+            .unwrap_or("high")
+            .to_lowercase();
+        let by_risk = config.risk.get(&risk_level);
+        let approver = own
+            .and_then(|p| p.approver.as_deref())
+            .or_else(|| by_risk.and_then(|p| p.approver.as_deref()))
+            .map(|role| role.trim().to_lowercase());
+        let approver = match approver.as_deref() {
+            None | Some("adult") => UserRole::Adult,
+            Some("root") => UserRole::Root,
+            Some("senior") => UserRole::Senior,
+            Some("child") => UserRole::Child,
+            Some(unknown) => {
+                tracing::warn!(
+                    "Confirmation policy for {tool}: unknown approver role '{unknown}', requiring root"
+                );
+                UserRole::Root
+            }
+        };
+        let timeout_secs = own
+            .and_then(|p| p.timeout_secs)
+            .or_else(|| by_risk.and_then(|p| p.timeout_secs))
+            .unwrap_or(timeout_secs);
+        let remember_minutes = own
+            .and_then(|p| p.remember_minutes)
+            .or_else(|| by_risk.and_then(|p| p.remember_minutes))
+            .unwrap_or(0);
+        let approvals = own
+            .and_then(|p| p.approvals)
+            .or_else(|| by_risk.and_then(|p| p.approvals))
+            .unwrap_or(1);
+        Self {
+            required: own.and_then(|p| p.required),
+            risk_level,
+            approver,
+            timeout_secs,
+            remember: Duration::from_secs(remember_minutes.saturating_mul(60)),
+            approvals: approvals.max(1),
+        }
+    }
+}
+
+/// What became of an answer to a confirmation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// The request was approved or denied
+    Resolved,
+    /// The approval was counted; more people must approve
+    Partial { approvals: usize, required: u32 },
+    /// No such request (expired or already answered)
+    NotFound,
+}
+
 /// A pending confirmation request sent to the user.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ConfirmationRequest {
@@ -35,6 +169,12 @@ pub struct ConfirmationRequest {
     pub requested_at: String,
     /// Seconds until auto-deny.
     pub timeout_secs: u64,
+    /// Least role that may approve.
+    pub approver: UserRole,
+    /// Distinct people who must approve.
+    pub approvals_required: u32,
+    /// Who has approved so far.
+    pub approved_by: Vec<String>,
 }
 
 /// Response to a confirmation request.
@@ -46,6 +186,9 @@ pub struct ConfirmationResponse {
     pub approved: bool,
 }
 
+/// Gate shared by the gateway and the channels running in one process.
+static SHARED: OnceLock<Arc<ConfirmationGate>> = OnceLock::new();
+
 /// The confirmation gate: manages pending requests and their resolution.
 pub struct ConfirmationGate {
     /// Pending requests waiting for user response.
@@ -54,6 +197,8 @@ pub struct ConfirmationGate {
     notify_tx: broadcast::Sender<ConfirmationRequest>,
     /// Default timeout in seconds.
     timeout_secs: u64,
+    /// Remembered approvals: expiry keyed by (session, tool).
+    grants: Mutex<HashMap<(String, String), Instant>>,
 }
 
 impl ConfirmationGate {
@@ -66,9 +211,17 @@ impl ConfirmationGate {
             pending: Mutex::new(HashMap::new()),
             notify_tx,
             timeout_secs,
+            grants: Mutex::new(HashMap::new()),
         })
     }
 
+    /// The gate shared by everything in this process, so a request raised
+    /// by a channel can be answered on the dashboard and vice versa.
+    /// Created with `timeout_secs` on first use.
+    pub fn shared(timeout_secs: u64) -> Arc<Self> {
+        Arc::clone(SHARED.get_or_init(|| Self::new(timeout_secs)))
+    }
+
     /// Subscribe to confirmation request notifications.
     ///
     /// WebSocket handlers, Telegram channels, and CLI loops call this
@@ -82,17 +235,93 @@ impl ConfirmationGate {
     /// Returns `true` if approved, `false` if denied or timed out.
     /// The caller (SecurityWrapper) should block on this.
     pub async fn request(&self, tool_name: &str, args_summary: &str) -> bool {
+        self.request_with_timeout(tool_name, args_summary, "high", self.timeout_secs)
+            .await
+    }
+
+    /// Like [`Self::request`], with an explicit risk level and timeout.
+    ///
+    /// Used for requests a person may take minutes to answer, such as a
+    /// child's appeal against the content filter.
+    pub async fn request_with_timeout(
+        &self,
+        tool_name: &str,
+        args_summary: &str,
+        risk_level: &str,
+        timeout_secs: u64,
+    ) -> bool {
         let id = uuid::Uuid::new_v4().to_string();
-        let (tx, rx) = oneshot::channel();
+        self.request_with_id(&id, tool_name, args_summary, risk_level, timeout_secs)
+            .await
+    }
 
-        let req = ConfirmationRequest {
-            id: id.clone(),
+    /// Like [`Self::request_with_timeout`], under a caller-chosen request ID.
+    ///
+    /// Lets the caller refer to the request before it resolves, e.g. to
+    /// resolve it from a chat reply.
+    pub async fn request_with_id(
+        &self,
+        id: &str,
+        tool_name: &str,
+        args_summary: &str,
+        risk_level: &str,
+        timeout_secs: u64,
+    ) -> bool {
+        self.submit(ConfirmationRequest {
+            id: id.to_string(),
             tool_name: tool_name.to_string(),
             description: args_summary.to_string(),
-            risk_level: "high".to_string(),
+            risk_level: risk_level.to_string(),
             requested_at: chrono::Utc::now().to_rfc3339(),
-            timeout_secs: self.timeout_secs,
-        };
+            timeout_secs,
+            approver: UserRole::default(),
+            approvals_required: 1,
+            approved_by: Vec::new(),
+        })
+        .await
+    }
+
+    /// Request confirmation for `tool_name` with the risk level, timeout
+    /// and approver role of `policy`.
+    pub async fn request_with_policy(
+        &self,
+        tool_name: &str,
+        args_summary: &str,
+        policy: &ConfirmationPolicy,
+    ) -> bool {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.request_with_id_and_policy(&id, tool_name, args_summary, policy)
+            .await
+    }
+
+    /// Like [`Self::request_with_policy`], under a caller-chosen request ID.
+    pub async fn request_with_id_and_policy(
+        &self,
+        id: &str,
+        tool_name: &str,
+        args_summary: &str,
+        policy: &ConfirmationPolicy,
+    ) -> bool {
+        self.submit(ConfirmationRequest {
+            id: id.to_string(),
+            tool_name: tool_name.to_string(),
+            description: args_summary.to_string(),
+            risk_level: policy.risk_level.clone(),
+            requested_at: chrono::Utc::now().to_rfc3339(),
+            timeout_secs: policy.timeout_secs,
+            approver: policy.approver,
+            approvals_required: policy.approvals,
+            approved_by: Vec::new(),
+        })
+        .await
+    }
+
+    /// Publish `req` and wait for its answer.
+    async fn submit(&self, req: ConfirmationRequest) -> bool {
+        let id = req.id.clone();
+        let tool_name = req.tool_name.clone();
+        let timeout_secs = req.timeout_secs;
+        let (tx, rx) = oneshot::channel();
 
         // Store the pending sender
         {
@@ -106,7 +335,7 @@ impl ConfirmationGate {
 
         // Await response with timeout
         let result = tokio::time::timeout(
-            std::time::Duration::from_secs(self.timeout_secs),
+            std::time::Duration::from_secs(timeout_secs),
             rx,
         )
         .await;
@@ -128,9 +357,9 @@ impl ConfirmationGate {
                 // Timeout — auto-deny
                 tracing::info!(
                     request_id = %id,
-                    tool = tool_name,
+                    tool = %tool_name,
                     "Confirmation timed out after {}s — auto-denied",
-                    self.timeout_secs
+                    timeout_secs
                 );
                 false
             }
@@ -139,8 +368,8 @@ impl ConfirmationGate {
 
     /// Resolve a pending confirmation request.
     ///
-    /// Called by the frontend (via API/WebSocket) or CLI when the user
-    /// approves or denies the action.
+    /// Skips the role and dual-control checks; answers from people go
+    /// through [`Self::resolve_as`].
     ///
     /// Returns `true` if the request was found and resolved, `false` if
     /// the request ID was not found (expired or already resolved).
@@ -170,6 +399,76 @@ impl ConfirmationGate {
         }
     }
 
+    /// Resolve a request answered by `identity` with `role`.
+    ///
+    /// Anyone may deny; approving needs at least the request's approver
+    /// role, and requests under dual control stay pending until enough
+    /// distinct identities have approved. Returns `Err` with the reason
+    /// when the approval does not count.
+    pub async fn resolve_as(
+        &self,
+        id: &str,
+        approved: bool,
+        role: UserRole,
+        identity: &str,
+    ) -> Result<Answer, String> {
+        if approved {
+            let mut pending = self.pending.lock().await;
+            let Some((req, _)) = pending.get_mut(id) else {
+                return Ok(Answer::NotFound);
+            };
+            if role < req.approver {
+                return Err(format!(
+                    "Approving '{}' requires the {} role.",
+                    req.tool_name,
+                    role_key(req.approver)
+                ));
+            }
+            if req.approved_by.iter().any(|a| a == identity) {
+                return Err(format!(
+                    "'{}' needs approval from {} different people; {identity} already approved.",
+                    req.tool_name, req.approvals_required
+                ));
+            }
+            req.approved_by.push(identity.to_string());
+            let approvals = req.approved_by.len();
+            let required = req.approvals_required;
+            if approvals < required as usize {
+                tracing::info!(
+                    request_id = %id,
+                    identity,
+                    "Confirmation approved {approvals} of {required} times"
+                );
+                return Ok(Answer::Partial {
+                    approvals,
+                    required,
+                });
+            }
+        }
+        Ok(if self.resolve(id, approved).await {
+            Answer::Resolved
+        } else {
+            Answer::NotFound
+        })
+    }
+
+    /// Remember an approval of `tool_name` in `session` for `duration`.
+    pub async fn grant(&self, session: &str, tool_name: &str, duration: Duration) {
+        self.grants.lock().await.insert(
+            (session.to_string(), tool_name.to_string()),
+            Instant::now() + duration,
+        );
+    }
+
+    /// Whether an approval of `tool_name` remembered in `session` is still
+    /// valid.
+    pub async fn is_granted(&self, session: &str, tool_name: &str) -> bool {
+        let mut grants = self.grants.lock().await;
+        let now = Instant::now();
+        grants.retain(|_, expires| *expires > now);
+        grants.contains_key(&(session.to_string(), tool_name.to_string()))
+    }
+
     /// Get all pending confirmation requests.
     pub async fn get_pending(&self) -> Vec<ConfirmationRequest> {
         self.pending.lock().await.values().map(|(req, _)| req.clone()).collect()
@@ -203,7 +502,8 @@ mod tests {
             let mut pending = gate.pending.lock().await;
             pending.insert("test-id".to_string(), (ConfirmationRequest {
                 id: "test-id".into(), tool_name: "test".into(), description: "test".into(),
-                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5
+                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5,
+                approver: UserRole::Adult, approvals_required: 1, approved_by: Vec::new(),
             }, tx));
         }
 
@@ -224,7 +524,8 @@ mod tests {
             let mut pending = gate.pending.lock().await;
             pending.insert("deny-id".to_string(), (ConfirmationRequest {
                 id: "deny-id".into(), tool_name: "test".into(), description: "test".into(),
-                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5
+                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5,
+                approver: UserRole::Adult, approvals_required: 1, approved_by: Vec::new(),
             }, tx));
         }
 
@@ -261,7 +562,8 @@ mod tests {
             let mut pending = gate.pending.lock().await;
             pending.insert("a".into(), (ConfirmationRequest {
                 id: "a".into(), tool_name: "test".into(), description: "test".into(),
-                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5
+                risk_level: "high".into(), requested_at: "now".into(), timeout_secs: 5,
+                approver: UserRole::Adult, approvals_required: 1, approved_by: Vec::new(),
             }, tx));
         }
         assert_eq!(gate.pending_count().await, 1);
@@ -270,6 +572,148 @@ mod tests {
         assert_eq!(gate.pending_count().await, 0);
     }
 
+    #[test]
+    fn tool_policy_overrides_risk_policy_and_defaults() {
+        let config: ConfirmationConfig = toml::from_str(
+            r#"
+            timeout_secs = 45
+            [risk.high]
+            approver = "root"
+            timeout_secs = 120
+            [risk.medium]
+            remember_minutes = 10
+            [tools.email_send]
+            risk = "medium"
+            [tools.shell]
+            approver = "Senior"
+            required = true
+            "#,
+        )
+        .unwrap();
+
+        let shell = ConfirmationPolicy::for_tool(&config, "shell");
+        assert_eq!(shell.required, Some(true));
+        assert_eq!(shell.approver, UserRole::Senior);
+        assert_eq!(shell.timeout_secs, 120);
+        assert!(shell.remember.is_zero());
+
+        let email = ConfirmationPolicy::for_tool(&config, "email_send");
+        assert_eq!(email.risk_level, "medium");
+        assert_eq!(email.approver, UserRole::Adult);
+        assert_eq!(email.timeout_secs, 45);
+        assert_eq!(email.remember.as_secs(), 600);
+
+        let other = ConfirmationPolicy::for_tool(&config, "file_write");
+        assert_eq!(other.required, None);
+        assert_eq!(other.approver, UserRole::Root);
+    }
+
+    #[test]
+    fn unknown_approver_role_requires_root() {
+        let config: ConfirmationConfig = toml::from_str(
+            r#"
+            [risk.medium]
+            approver = "admin"
+            [tools.shell]
+            approver = "roott"
+            [tools.email_send]
+            risk = "medium"
+            "#,
+        )
+        .unwrap();
+
+        let shell = ConfirmationPolicy::for_tool(&config, "shell");
+        assert_eq!(shell.approver, UserRole::Root);
+        let email = ConfirmationPolicy::for_tool(&config, "email_send");
+        assert_eq!(email.approver, UserRole::Root);
+        let other = ConfirmationPolicy::for_tool(&config, "file_write");
+        assert_eq!(other.approver, UserRole::Adult);
+    }
+
+    #[tokio::test]
+    async fn approving_needs_the_approver_role() {
+        let gate = ConfirmationGate::new(5);
+        let mut rx = gate.subscribe();
+        let policy = ConfirmationPolicy {
+            required: None,
+            risk_level: "high".into(),
+            approver: UserRole::Root,
+            timeout_secs: 5,
+            remember: Duration::ZERO,
+            approvals: 1,
+        };
+        let pending = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move { gate.request_with_policy("shell", "reboot", &policy).await })
+        };
+        let req = rx.recv().await.unwrap();
+        assert_eq!(req.approver, UserRole::Root);
+
+        let err = gate
+            .resolve_as(&req.id, true, UserRole::Adult, "dashboard")
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Approving 'shell' requires the root role.");
+        assert_eq!(
+            gate.resolve_as(&req.id, false, UserRole::Child, "user:mia")
+                .await,
+            Ok(Answer::Resolved)
+        );
+        assert!(!pending.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn dual_control_needs_two_distinct_approvers() {
+        let gate = ConfirmationGate::new(5);
+        let config: ConfirmationConfig =
+            toml::from_str("[tools.vault_reveal]\napprovals = 2").unwrap();
+        let policy = ConfirmationPolicy::with_default_timeout(&config, "vault_reveal", 120);
+        assert_eq!((policy.approvals, policy.timeout_secs), (2, 120));
+        let pending = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move {
+                gate.request_with_id_and_policy("r1", "vault_reveal", "Reveal", &policy)
+                    .await
+            })
+        };
+        while gate.pending_count().await == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let first = gate
+            .resolve_as("r1", true, UserRole::Root, "dashboard")
+            .await;
+        assert_eq!(
+            first,
+            Ok(Answer::Partial {
+                approvals: 1,
+                required: 2
+            })
+        );
+        assert!(gate
+            .resolve_as("r1", true, UserRole::Root, "dashboard")
+            .await
+            .is_err());
+        assert_eq!(gate.get_pending().await[0].approved_by, ["dashboard"]);
+        let second = gate
+            .resolve_as("r1", true, UserRole::Adult, "user:anna")
+            .await;
+        assert_eq!(second, Ok(Answer::Resolved));
+        assert!(pending.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn grants_are_per_session_and_expire() {
+        let gate = ConfirmationGate::new(5);
+        gate.grant("dashboard:1", "shell", Duration::from_secs(90))
+            .await;
+        gate.grant("dashboard:1", "email_send", Duration::ZERO)
+            .await;
+        assert!(gate.is_granted("dashboard:1", "shell").await);
+        assert!(!gate.is_granted("dashboard:2", "shell").await);
+        assert!(!gate.is_granted("dashboard:1", "email_send").await);
+    }
+
     #[tokio::test]
     async fn broadcast_notifies_subscribers() {
         let gate = ConfirmationGate::new(1);
@@ -4,118 +4,168 @@
 
 //! Auto-detection of available security features
 
-use crate::config::{SandboxBackend, SecurityConfig};
-use crate::security::traits::Sandbox;
+use crate::config::{SandboxBackend, SandboxConfig, SandboxProfile, SecurityConfig};
+use crate::security::traits::{NoopSandbox, Sandbox};
+use std::path::Path;
 use std::sync::Arc;
 
 /// Create a sandbox based on auto-detection or explicit config
 pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
-    let backend = &config.sandbox.backend;
+    select(&config.sandbox, SandboxProfile::None).unwrap_or_else(|| {
+        tracing::info!("No sandbox backend available, using application-layer security");
+        Arc::new(NoopSandbox)
+    })
+}
 
-    // If explicitly disabled, return noop
-    if matches!(backend, SandboxBackend::None) || config.sandbox.enabled == Some(false) {
-        return Arc::new(super::traits::NoopSandbox);
+/// Create the sandbox for a tool whose commands need `profile`: the
+/// configured backend, or on auto the strongest available host backend
+/// that can enforce the profile. Without one the tool runs unsandboxed.
+pub fn create_tool_sandbox(config: &SandboxConfig, profile: SandboxProfile) -> ToolSandbox {
+    if profile == SandboxProfile::None {
+        return ToolSandbox::none();
     }
-
-    // If specific backend requested, try that
-    match backend {
-        SandboxBackend::Landlock => {
-            #[cfg(feature = "sandbox-landlock")]
-            {
-                #[cfg(target_os = "linux")]
-                {
-                    if let Ok(sandbox) = super::landlock::LandlockSandbox::new() {
-                        return Arc::new(sandbox);
-                    }
-                }
+    match select(config, profile) {
+        Some(sandbox) => ToolSandbox {
+            sandbox,
+            requested: profile,
+            effective: profile,
+        },
+        None => {
+            if config.enabled != Some(false) && matches!(config.backend, SandboxBackend::Auto) {
+                tracing::warn!(
+                    "No sandbox backend can enforce the {profile} profile, falling back to application-layer"
+                );
             }
-            tracing::warn!(
-                "Landlock requested but not available, falling back to application-layer"
-            );
-            Arc::new(super::traits::NoopSandbox)
-        }
-        SandboxBackend::Firejail => {
-            #[cfg(target_os = "linux")]
-            {
-                if let Ok(sandbox) = super::firejail::FirejailSandbox::new() {
-                    return Arc::new(sandbox);
-                }
+            ToolSandbox {
+                requested: profile,
+                ..ToolSandbox::none()
             }
-            tracing::warn!(
-                "Firejail requested but not available, falling back to application-layer"
-            );
-            Arc::new(super::traits::NoopSandbox)
         }
-        SandboxBackend::Bubblewrap => {
-            #[cfg(feature = "sandbox-bubblewrap")]
-            {
-                #[cfg(any(target_os = "linux", target_os = "macos"))]
-                {
-                    if let Ok(sandbox) = super::bubblewrap::BubblewrapSandbox::new() {
-                        return Arc::new(sandbox);
-                    }
-                }
-            }
-            tracing::warn!(
-                "Bubblewrap requested but not available, falling back to application-layer"
-            );
-            Arc::new(super::traits::NoopSandbox)
-        }
-        SandboxBackend::Docker => {
-            if let Ok(sandbox) = super::docker::DockerSandbox::new() {
-                return Arc::new(sandbox);
-            }
-            tracing::warn!("Docker requested but not available, falling back to application-layer");
-            Arc::new(super::traits::NoopSandbox)
-        }
-        SandboxBackend::Auto | SandboxBackend::None => {
-            // Auto-detect best available
-            detect_best_sandbox()
+    }
+}
+
+/// The sandbox a tool runs its commands in and the profile it enforces
+#[derive(Clone)]
+pub struct ToolSandbox {
+    sandbox: Arc<dyn Sandbox>,
+    requested: SandboxProfile,
+    effective: SandboxProfile,
+}
+
+impl ToolSandbox {
+    /// No sandbox
+    pub fn none() -> Self {
+        Self {
+            sandbox: Arc::new(NoopSandbox),
+            requested: SandboxProfile::None,
+            effective: SandboxProfile::None,
         }
     }
+
+    /// Name of the backend
+    pub fn backend(&self) -> &str {
+        self.sandbox.name()
+    }
+
+    /// The profile the tool asked for
+    pub fn requested(&self) -> SandboxProfile {
+        self.requested
+    }
+
+    /// The profile commands actually run under
+    pub fn profile(&self) -> SandboxProfile {
+        self.effective
+    }
+
+    /// Wrap `cmd` so it runs under the effective profile.
+    pub fn wrap(
+        &self,
+        cmd: &mut tokio::process::Command,
+        workspace_dir: &Path,
+    ) -> std::io::Result<()> {
+        self.sandbox
+            .wrap_command_with_profile(cmd.as_std_mut(), self.effective, workspace_dir)
+    }
 }
 
-/// Auto-detect the best available sandbox
-fn detect_best_sandbox() -> Arc<dyn Sandbox> {
-    #[cfg(target_os = "linux")]
-    {
-        // Try Landlock first (native, no dependencies)
-        #[cfg(feature = "sandbox-landlock")]
-        {
+/// The configured backend, or the strongest available one, that can
+/// enforce `profile`
+fn select(config: &SandboxConfig, profile: SandboxProfile) -> Option<Arc<dyn Sandbox>> {
+    if matches!(config.backend, SandboxBackend::None) || config.enabled == Some(false) {
+        return None;
+    }
+    if matches!(config.backend, SandboxBackend::Auto) {
+        // Docker runs tool commands in another image, without the host's
+        // programs, so tools only use it when it is configured.
+        let sandbox = auto_order()
+            .iter()
+            .filter(|backend| {
+                profile == SandboxProfile::None || !matches!(backend, SandboxBackend::Docker)
+            })
+            .filter_map(probe)
+            .find(|sandbox| sandbox.supports(profile))?;
+        tracing::info!("{} sandbox enabled", sandbox.name());
+        return Some(sandbox);
+    }
+    let sandbox = probe(&config.backend).filter(|sandbox| sandbox.supports(profile));
+    if sandbox.is_none() {
+        tracing::warn!(
+            "{:?} requested but not available for the {profile} profile, falling back to application-layer",
+            config.backend
+        );
+    }
+    sandbox
+}
+
+/// Backends auto-detection tries on this platform, strongest first.
+/// Landlock restricts the whole process, so tool profiles skip it.
+fn auto_order() -> &'static [SandboxBackend] {
+    if cfg!(target_os = "linux") {
+        &[
+            SandboxBackend::Landlock,
+            SandboxBackend::Bubblewrap,
+            SandboxBackend::Firejail,
+            SandboxBackend::Docker,
+        ]
+    } else if cfg!(target_os = "macos") {
+        &[SandboxBackend::Bubblewrap, SandboxBackend::Docker]
+    } else {
+        &[SandboxBackend::Docker]
+    }
+}
+
+/// Instantiate `backend` if it is compiled in and installed.
+fn probe(backend: &SandboxBackend) -> Option<Arc<dyn Sandbox>> {
+    match backend {
+        SandboxBackend::Landlock => {
+            #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
             if let Ok(sandbox) = super::landlock::LandlockSandbox::probe() {
-                tracing::info!("Landlock sandbox enabled (Linux kernel 5.13+)");
-                return Arc::new(sandbox);
+                return Some(Arc::new(sandbox));
             }
+            None
         }
-
-        // Try Firejail second (user-space tool)
-        if let Ok(sandbox) = super::firejail::FirejailSandbox::probe() {
-            tracing::info!("Firejail sandbox enabled");
-            return Arc::new(sandbox);
+        SandboxBackend::Firejail => {
+            #[cfg(target_os = "linux")]
+            if let Ok(sandbox) = super::firejail::FirejailSandbox::probe() {
+                return Some(Arc::new(sandbox));
+            }
+            None
         }
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        // Try Bubblewrap on macOS
-        #[cfg(feature = "sandbox-bubblewrap")]
-        {
+        SandboxBackend::Bubblewrap => {
+            #[cfg(all(
+                feature = "sandbox-bubblewrap",
+                any(target_os = "linux", target_os = "macos")
+            ))]
             if let Ok(sandbox) = super::bubblewrap::BubblewrapSandbox::probe() {
-                tracing::info!("Bubblewrap sandbox enabled");
-                return Arc::new(sandbox);
+                return Some(Arc::new(sandbox));
             }
+            None
         }
+        SandboxBackend::Docker => super::docker::DockerSandbox::probe()
+            .ok()
+            .map(|sandbox| Arc::new(sandbox) as Arc<dyn Sandbox>),
+        SandboxBackend::Auto | SandboxBackend::None => None,
     }
-
-    // Docker is heavy but works everywhere if docker is installed
-    if let Ok(sandbox) = super::docker::DockerSandbox::probe() {
-        tracing::info!("Docker sandbox enabled");
-        return Arc::new(sandbox);
-    }
-
-    // Fallback: application-layer security only
-    tracing::info!("No sandbox backend available, using application-layer security");
-    Arc::new(super::traits::NoopSandbox)
 }
 
 #[cfg(test)]
@@ -125,7 +175,7 @@ mod tests {
 
     #[test]
     fn detect_best_sandbox_returns_something() {
-        let sandbox = detect_best_sandbox();
+        let sandbox = create_sandbox(&SecurityConfig::default());
         // Should always return at least NoopSandbox
         assert!(sandbox.is_available());
     }
@@ -137,6 +187,7 @@ mod tests {
                 enabled: Some(false),
                 backend: SandboxBackend::None,
                 firejail_args: Vec::new(),
+                profiles: std::collections::BTreeMap::new(),
             },
             ..Default::default()
         };
@@ -151,6 +202,7 @@ mod tests {
                 enabled: None, // Auto-detect
                 backend: SandboxBackend::Auto,
                 firejail_args: Vec::new(),
+                profiles: std::collections::BTreeMap::new(),
             },
             ..Default::default()
         };
@@ -158,4 +210,35 @@ mod tests {
         // Should return some sandbox (at least NoopSandbox)
         assert!(sandbox.is_available());
     }
+
+    #[test]
+    fn tool_sandbox_without_profile_is_noop() {
+        let sandbox = create_tool_sandbox(&SandboxConfig::default(), SandboxProfile::None);
+        assert_eq!(sandbox.backend(), "none");
+        assert_eq!(sandbox.profile(), SandboxProfile::None);
+    }
+
+    #[test]
+    fn disabled_sandbox_reports_unenforced_profile() {
+        let config = SandboxConfig {
+            enabled: Some(false),
+            ..SandboxConfig::default()
+        };
+        let sandbox = create_tool_sandbox(&config, SandboxProfile::Full);
+        assert_eq!(sandbox.backend(), "none");
+        assert_eq!(sandbox.requested(), SandboxProfile::Full);
+        assert_eq!(sandbox.profile(), SandboxProfile::None);
+    }
+
+    #[test]
+    fn auto_tool_sandbox_enforces_profile_or_nothing() {
+        let sandbox = create_tool_sandbox(&SandboxConfig::default(), SandboxProfile::NoNetwork);
+        match sandbox.backend() {
+            "none" => assert_eq!(sandbox.profile(), SandboxProfile::None),
+            backend => {
+                assert!(!["landlock", "docker"].contains(&backend));
+                assert_eq!(sandbox.profile(), SandboxProfile::NoNetwork);
+            }
+        }
+    }
 }
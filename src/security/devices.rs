@@ -0,0 +1,576 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Paired devices — named clients holding expiring bearer tokens.
+//!
+//! Pairing registers a device and hands it an access token plus a refresh
+//! token. The access token expires after `[gateway] device_token_ttl_secs`;
+//! the device trades its refresh token for a fresh pair before then, which
+//! also rotates the refresh token. Revoking a device invalidates both at
+//! once. Only SHA-256 hashes are persisted, to
+//! `<workspace>/.mymolt/devices.json`.
+//!
+//! A family member logging in gets a device of their own, tied to them by
+//! `member` and scoped to their role.
+//!
+//! Bearer tokens paired before the registry existed (`[gateway]
+//! paired_tokens`) are adopted as Root devices on startup, so they expire and
+//! can be revoked like any other.
+
+use super::pairing::{constant_time_eq, generate_token, hash_token};
+use crate::identity::UserRole;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// How stale `last_seen_at` may get on disk; it is always current in memory.
+const LAST_SEEN_PERSIST_SECS: i64 = 300;
+
+/// Scopes a device may hold, one per [`UserRole`].
+pub const SCOPES: [&str; 4] = ["root", "adult", "senior", "child"];
+
+/// Name of a device adopted from `[gateway] paired_tokens`.
+const LEGACY_DEVICE_NAME: &str = "Paired device (legacy token)";
+
+/// Trim and lower-case `scopes`, rejecting an empty list and unknown values.
+pub fn normalize_scopes(scopes: Vec<String>) -> std::result::Result<Vec<String>, String> {
+    if scopes.is_empty() {
+        return Err(format!("A device needs at least one scope ({SCOPES:?})"));
+    }
+    scopes
+        .into_iter()
+        .map(|scope| {
+            let scope = scope.trim().to_lowercase();
+            if SCOPES.contains(&scope.as_str()) {
+                Ok(scope)
+            } else {
+                Err(format!(
+                    "Unknown scope '{scope}' (expected one of {SCOPES:?})"
+                ))
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    pub id: String,
+    pub name: String,
+    /// Roles the device acts with ("root", "adult", "senior", "child"); the
+    /// highest applies.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Family member this is a login session of; `None` for the owner's devices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// When the current access token stops working
+    pub expires_at: DateTime<Utc>,
+    /// When the current refresh token stops working
+    pub refresh_expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub revoked: bool,
+    token_hash: String,
+    refresh_hash: String,
+}
+
+impl Device {
+    /// Role requests with this device's token run as. Unknown scopes are
+    /// ignored; a device without a known one gets the least role, Child.
+    pub fn role(&self) -> UserRole {
+        self.scopes
+            .iter()
+            .filter_map(|scope| match scope.to_lowercase().as_str() {
+                "root" => Some(UserRole::Root),
+                "adult" => Some(UserRole::Adult),
+                "senior" => Some(UserRole::Senior),
+                "child" => Some(UserRole::Child),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(UserRole::Child)
+    }
+
+    /// Whether the access token is valid at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && now < self.expires_at
+    }
+}
+
+/// Tokens handed to a device; the plaintext is never stored.
+#[derive(Debug, Clone)]
+pub struct IssuedTokens {
+    pub device: Device,
+    pub token: String,
+    pub refresh_token: String,
+}
+
+fn generate_refresh_token() -> String {
+    format!(
+        "zr_{}{}",
+        uuid::Uuid::new_v4().as_simple(),
+        uuid::Uuid::new_v4().as_simple()
+    )
+}
+
+/// Persistent registry of paired devices.
+pub struct DeviceRegistry {
+    path: PathBuf,
+    token_ttl: Duration,
+    refresh_ttl: Duration,
+    devices: Mutex<Vec<Device>>,
+}
+
+impl DeviceRegistry {
+    /// Load the registry from the workspace, dropping devices whose refresh
+    /// token expired over a day ago. A missing file is an empty registry; an
+    /// unreadable or corrupt one is an error rather than a silent reset.
+    pub fn new(workspace_dir: &Path, token_ttl_secs: u64, refresh_ttl_secs: u64) -> Result<Self> {
+        let path = workspace_dir.join(".mymolt").join("devices.json");
+        let mut devices: Vec<Device> = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let cutoff = Utc::now() - Duration::days(1);
+        devices.retain(|d| d.refresh_expires_at > cutoff);
+        // Devices paired before scopes existed are the owner's
+        let mut unscoped = 0;
+        for device in devices.iter_mut().filter(|d| d.scopes.is_empty()) {
+            device.scopes = vec!["root".into()];
+            unscoped += 1;
+        }
+        let secs = |s: u64| Duration::seconds(i64::try_from(s).unwrap_or(i64::MAX).max(1));
+        let registry = Self {
+            path,
+            token_ttl: secs(token_ttl_secs),
+            refresh_ttl: secs(refresh_ttl_secs),
+            devices: Mutex::new(devices),
+        };
+        if unscoped > 0 {
+            tracing::info!("Gave {unscoped} device(s) paired without scopes the root scope");
+            registry.persist(&registry.lock())?;
+        }
+        Ok(registry)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Device>> {
+        self.devices
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn persist(&self, devices: &[Device]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(devices)?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Give `device` a new token pair. Returns the plaintext tokens.
+    fn issue(&self, device: &mut Device, now: DateTime<Utc>) -> (String, String) {
+        let token = generate_token();
+        let refresh_token = generate_refresh_token();
+        device.token_hash = hash_token(&token);
+        device.refresh_hash = hash_token(&refresh_token);
+        device.expires_at = now + self.token_ttl;
+        device.refresh_expires_at = now + self.refresh_ttl;
+        (token, refresh_token)
+    }
+
+    /// Register a newly paired device.
+    pub fn register(&self, name: &str, scopes: Vec<String>) -> Result<IssuedTokens> {
+        self.insert(name, None, scopes)
+    }
+
+    /// Start a login session for family member `member`, acting with `role`.
+    pub fn register_member(
+        &self,
+        name: &str,
+        member: &str,
+        role: UserRole,
+    ) -> Result<IssuedTokens> {
+        let scope = format!("{role:?}").to_lowercase();
+        self.insert(name, Some(member.to_string()), vec![scope])
+    }
+
+    fn insert(
+        &self,
+        name: &str,
+        member: Option<String>,
+        scopes: Vec<String>,
+    ) -> Result<IssuedTokens> {
+        let scopes = normalize_scopes(scopes).map_err(anyhow::Error::msg)?;
+        let now = Utc::now();
+        let mut device = Device {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            scopes,
+            member,
+            created_at: now,
+            last_seen_at: Some(now),
+            expires_at: now,
+            refresh_expires_at: now,
+            revoked: false,
+            token_hash: String::new(),
+            refresh_hash: String::new(),
+        };
+        let (token, refresh_token) = self.issue(&mut device, now);
+
+        let mut devices = self.lock();
+        devices.push(device.clone());
+        self.persist(&devices)?;
+        Ok(IssuedTokens {
+            device,
+            token,
+            refresh_token,
+        })
+    }
+
+    /// Adopt bearer token hashes from `[gateway] paired_tokens` as Root
+    /// devices, skipping hashes a device already holds. Their access tokens
+    /// expire after the usual lifetime and they have no usable refresh
+    /// token, so their clients pair again by then. Returns how many were
+    /// adopted.
+    pub fn adopt_legacy(&self, token_hashes: &[String]) -> Result<usize> {
+        let now = Utc::now();
+        let mut devices = self.lock();
+        let mut adopted = 0;
+        for hash in token_hashes {
+            if devices.iter().any(|d| d.token_hash == *hash) {
+                continue;
+            }
+            devices.push(Device {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: LEGACY_DEVICE_NAME.to_string(),
+                scopes: vec!["root".into()],
+                member: None,
+                created_at: now,
+                last_seen_at: None,
+                expires_at: now + self.token_ttl,
+                refresh_expires_at: now + self.token_ttl,
+                revoked: false,
+                token_hash: hash.clone(),
+                refresh_hash: hash_token(&generate_refresh_token()),
+            });
+            adopted += 1;
+        }
+        if adopted > 0 {
+            self.persist(&devices)?;
+        }
+        Ok(adopted)
+    }
+
+    /// The active device holding `token`, noting that it was seen.
+    pub fn authenticate(&self, token: &str) -> Option<Device> {
+        let hashed = hash_token(token);
+        let now = Utc::now();
+        let mut devices = self.lock();
+        let device = devices
+            .iter_mut()
+            .find(|d| constant_time_eq(&d.token_hash, &hashed))
+            .filter(|d| d.is_active(now))?;
+        let stale = device
+            .last_seen_at
+            .is_none_or(|seen| (now - seen).num_seconds() >= LAST_SEEN_PERSIST_SECS);
+        device.last_seen_at = Some(now);
+        let found = device.clone();
+        if stale {
+            if let Err(e) = self.persist(&devices) {
+                tracing::warn!("Failed to persist device activity: {e}");
+            }
+        }
+        Some(found)
+    }
+
+    /// Trade a refresh token for a new token pair. Returns `None` if the
+    /// refresh token is unknown, expired or its device was revoked.
+    pub fn refresh(&self, refresh_token: &str) -> Result<Option<IssuedTokens>> {
+        let hashed = hash_token(refresh_token);
+        let now = Utc::now();
+        let mut devices = self.lock();
+        let Some(device) = devices
+            .iter_mut()
+            .find(|d| constant_time_eq(&d.refresh_hash, &hashed))
+            .filter(|d| !d.revoked && now < d.refresh_expires_at)
+        else {
+            return Ok(None);
+        };
+        let (token, refresh_token) = self.issue(device, now);
+        device.last_seen_at = Some(now);
+        let device = device.clone();
+        self.persist(&devices)?;
+        Ok(Some(IssuedTokens {
+            device,
+            token,
+            refresh_token,
+        }))
+    }
+
+    /// All devices, most recently seen first.
+    pub fn list(&self) -> Vec<Device> {
+        let mut out = self.lock().clone();
+        out.sort_by_key(|d| std::cmp::Reverse(d.last_seen_at));
+        out
+    }
+
+    /// Whether any device may still use or refresh its tokens.
+    pub fn has_active(&self) -> bool {
+        let now = Utc::now();
+        self.lock()
+            .iter()
+            .any(|d| !d.revoked && now < d.refresh_expires_at)
+    }
+
+    /// Rename a device and/or change its scopes. Returns `None` if the id
+    /// is unknown.
+    pub fn update(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        scopes: Option<Vec<String>>,
+    ) -> Result<Option<Device>> {
+        let scopes = scopes
+            .map(normalize_scopes)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let mut devices = self.lock();
+        let Some(device) = devices.iter_mut().find(|d| d.id == id) else {
+            return Ok(None);
+        };
+        if let Some(name) = name {
+            device.name = name.to_string();
+        }
+        if let Some(scopes) = scopes {
+            device.scopes = scopes;
+        }
+        let device = device.clone();
+        self.persist(&devices)?;
+        Ok(Some(device))
+    }
+
+    /// Revoke a device immediately. Returns `false` if the id is unknown.
+    pub fn revoke(&self, id: &str) -> Result<bool> {
+        let mut devices = self.lock();
+        let Some(device) = devices.iter_mut().find(|d| d.id == id) else {
+            return Ok(false);
+        };
+        device.revoked = true;
+        self.persist(&devices)?;
+        Ok(true)
+    }
+
+    /// End every session of family member `member`. Returns how many were
+    /// still active.
+    pub fn revoke_member(&self, member: &str) -> Result<usize> {
+        let mut devices = self.lock();
+        let mut revoked = 0;
+        for device in devices.iter_mut().filter(|d| {
+            !d.revoked
+                && d.member
+                    .as_deref()
+                    .is_some_and(|m| m.eq_ignore_ascii_case(member))
+        }) {
+            device.revoked = true;
+            revoked += 1;
+        }
+        if revoked > 0 {
+            self.persist(&devices)?;
+        }
+        Ok(revoked)
+    }
+
+    /// Revoke every device. Returns how many were still active.
+    pub fn revoke_all(&self) -> Result<usize> {
+        let mut devices = self.lock();
+        let mut revoked = 0;
+        for device in devices.iter_mut().filter(|d| !d.revoked) {
+            device.revoked = true;
+            revoked += 1;
+        }
+        self.persist(&devices)?;
+        Ok(revoked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(dir: &Path) -> DeviceRegistry {
+        DeviceRegistry::new(dir, 3600, 86_400).unwrap()
+    }
+
+    #[test]
+    fn registered_device_authenticates_and_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let issued = devices.register("Laptop", vec!["root".into()]).unwrap();
+
+        assert!(issued.token.starts_with("zc_"));
+        let device = devices.authenticate(&issued.token).unwrap();
+        assert_eq!(device.name, "Laptop");
+        assert_eq!(device.role(), UserRole::Root);
+        assert!(devices.authenticate("zc_wrong").is_none());
+
+        let raw = std::fs::read_to_string(tmp.path().join(".mymolt/devices.json")).unwrap();
+        assert!(!raw.contains(&issued.token));
+        assert!(!raw.contains(&issued.refresh_token));
+
+        let reloaded = registry(tmp.path());
+        assert!(reloaded.authenticate(&issued.token).is_some());
+    }
+
+    #[test]
+    fn refresh_rotates_both_tokens() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let issued = devices.register("Phone", vec!["root".into()]).unwrap();
+
+        let renewed = devices.refresh(&issued.refresh_token).unwrap().unwrap();
+        assert_eq!(renewed.device.id, issued.device.id);
+        assert!(devices.authenticate(&issued.token).is_none());
+        assert!(devices.authenticate(&renewed.token).is_some());
+        assert!(devices.refresh(&issued.refresh_token).unwrap().is_none());
+        assert!(devices.refresh(&renewed.refresh_token).unwrap().is_some());
+    }
+
+    #[test]
+    fn expired_tokens_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let issued = devices.register("Tablet", vec!["root".into()]).unwrap();
+        devices.lock()[0].expires_at = Utc::now() - Duration::seconds(1);
+
+        assert!(devices.authenticate(&issued.token).is_none());
+        let renewed = devices.refresh(&issued.refresh_token).unwrap().unwrap();
+        assert!(devices.authenticate(&renewed.token).is_some());
+
+        devices.lock()[0].refresh_expires_at = Utc::now() - Duration::seconds(1);
+        assert!(devices.refresh(&renewed.refresh_token).unwrap().is_none());
+    }
+
+    #[test]
+    fn revoked_device_loses_both_tokens() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let lost = devices
+            .register("Lost laptop", vec!["root".into()])
+            .unwrap();
+        let kept = devices.register("Desktop", vec!["root".into()]).unwrap();
+
+        assert!(devices.revoke(&lost.device.id).unwrap());
+        assert!(!devices.revoke("missing").unwrap());
+        assert!(devices.authenticate(&lost.token).is_none());
+        assert!(devices.refresh(&lost.refresh_token).unwrap().is_none());
+        assert!(devices.authenticate(&kept.token).is_some());
+
+        assert_eq!(devices.revoke_all().unwrap(), 1);
+        assert!(!devices.has_active());
+    }
+
+    #[test]
+    fn update_renames_and_rescopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let issued = devices.register("iPad", vec!["root".into()]).unwrap();
+
+        let device = devices
+            .update(
+                &issued.device.id,
+                Some("Kids iPad"),
+                Some(vec!["child".into()]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(device.name, "Kids iPad");
+        assert_eq!(device.role(), UserRole::Child);
+        assert_eq!(
+            devices.authenticate(&issued.token).unwrap().role(),
+            UserRole::Child
+        );
+        assert!(devices
+            .update("missing", Some("x"), None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn member_sessions_carry_role_and_end_together() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let owner = devices.register("Laptop", vec!["root".into()]).unwrap();
+        let tablet = devices
+            .register_member("Luca's tablet", "Luca", UserRole::Child)
+            .unwrap();
+        let phone = devices
+            .register_member("Luca's phone", "Luca", UserRole::Child)
+            .unwrap();
+
+        let session = devices.authenticate(&tablet.token).unwrap();
+        assert_eq!(session.member.as_deref(), Some("Luca"));
+        assert_eq!(session.role(), UserRole::Child);
+
+        assert_eq!(devices.revoke_member("luca").unwrap(), 2);
+        assert!(devices.authenticate(&tablet.token).is_none());
+        assert!(devices.authenticate(&phone.token).is_none());
+        assert!(devices.authenticate(&owner.token).is_some());
+    }
+
+    #[test]
+    fn scopes_are_validated_and_unknown_ones_grant_least_role() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        assert!(devices.register("Laptop", Vec::new()).is_err());
+        assert!(devices.register("Laptop", vec!["owner".into()]).is_err());
+        let issued = devices.register("Laptop", vec![" Adult ".into()]).unwrap();
+        assert_eq!(issued.device.scopes, vec!["adult"]);
+        assert!(devices
+            .update(&issued.device.id, None, Some(Vec::new()))
+            .is_err());
+
+        devices.lock()[0].scopes = vec!["superuser".into()];
+        assert_eq!(
+            devices.authenticate(&issued.token).unwrap().role(),
+            UserRole::Child
+        );
+    }
+
+    #[test]
+    fn corrupt_registry_fails_to_load() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".mymolt/devices.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(DeviceRegistry::new(tmp.path(), 3600, 86_400).is_err());
+    }
+
+    #[test]
+    fn legacy_tokens_become_revocable_root_devices() {
+        let tmp = tempfile::tempdir().unwrap();
+        let devices = registry(tmp.path());
+        let hashes = vec![hash_token("zc_legacy")];
+
+        assert_eq!(devices.adopt_legacy(&hashes).unwrap(), 1);
+        assert_eq!(devices.adopt_legacy(&hashes).unwrap(), 0);
+        let device = devices.authenticate("zc_legacy").unwrap();
+        assert_eq!(device.role(), UserRole::Root);
+        assert!(devices.revoke(&device.id).unwrap());
+        assert!(devices.authenticate("zc_legacy").is_none());
+        assert_eq!(devices.adopt_legacy(&hashes).unwrap(), 0);
+    }
+}
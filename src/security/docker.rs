@@ -4,7 +4,9 @@
 
 //! Docker sandbox (container isolation)
 
-use crate::security::traits::Sandbox;
+use crate::config::SandboxProfile;
+use crate::security::traits::{rewrap, Sandbox};
+use std::path::Path;
 use std::process::Command;
 
 /// Docker sandbox backend
@@ -48,6 +50,32 @@ impl DockerSandbox {
         Self::new()
     }
 
+    /// `docker run` flags for `profile`. The workspace is mounted at the
+    /// same path, so commands see the paths they were given.
+    fn profile_args(
+        &self,
+        profile: SandboxProfile,
+        workspace_dir: &Path,
+        current_dir: Option<&Path>,
+    ) -> Vec<String> {
+        let workspace = workspace_dir.display().to_string();
+        let mut args: Vec<String> = ["run", "--rm", "-i", "--memory", "512m", "--cpus", "1.0"]
+            .map(String::from)
+            .to_vec();
+        args.push("--volume".into());
+        args.push(format!("{workspace}:{workspace}:rw"));
+        args.push("--workdir".into());
+        args.push(current_dir.map_or(workspace, |dir| dir.display().to_string()));
+        if profile.restricts_filesystem() {
+            args.extend(["--read-only", "--tmpfs", "/tmp"].map(String::from));
+        }
+        if profile.restricts_network() {
+            args.extend(["--network", "none"].map(String::from));
+        }
+        args.push(self.image.clone());
+        args
+    }
+
     fn is_installed() -> bool {
         Command::new("docker")
             .arg("--version")
@@ -88,6 +116,24 @@ impl Sandbox for DockerSandbox {
         Self::is_installed()
     }
 
+    fn supports(&self, _profile: SandboxProfile) -> bool {
+        true
+    }
+
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SandboxProfile,
+        workspace_dir: &Path,
+    ) -> std::io::Result<()> {
+        if profile == SandboxProfile::None {
+            return Ok(());
+        }
+        let args = self.profile_args(profile, workspace_dir, cmd.get_current_dir());
+        rewrap(cmd, "docker", args);
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "docker"
     }
@@ -121,4 +167,20 @@ mod tests {
             Err(_) => assert!(!DockerSandbox::is_installed()),
         }
     }
+
+    #[test]
+    fn docker_profile_args() {
+        let sandbox = DockerSandbox::default();
+        let args = sandbox
+            .profile_args(SandboxProfile::Full, Path::new("/srv/ws"), None)
+            .join(" ");
+        assert!(args.contains("--volume /srv/ws:/srv/ws:rw --workdir /srv/ws"));
+        assert!(args.contains("--read-only --tmpfs /tmp"));
+        assert!(args.ends_with("--network none alpine:latest"));
+
+        let args = sandbox
+            .profile_args(SandboxProfile::ReadonlyFs, Path::new("/srv/ws"), None)
+            .join(" ");
+        assert!(!args.contains("--network"));
+    }
 }
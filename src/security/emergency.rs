@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Emergency stop shared by every part of the process.
+//!
+//! The panic button engages the [`global`] stop. While it is engaged agent
+//! loops abort at their next await point, the vault refuses to unwrap its
+//! key and the daemon's background components stay halted. Pairing again
+//! releases it.
+
+use anyhow::{bail, Result};
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::watch;
+
+static GLOBAL: OnceLock<Arc<EmergencyStop>> = OnceLock::new();
+
+/// The stop engaged by the panic button.
+pub fn global() -> &'static Arc<EmergencyStop> {
+    GLOBAL.get_or_init(|| Arc::new(EmergencyStop::new()))
+}
+
+/// Whether the [`global`] stop is engaged.
+pub fn is_engaged() -> bool {
+    global().is_engaged()
+}
+
+/// Completes once the [`global`] stop is engaged.
+pub async fn engaged() {
+    global().engaged().await;
+}
+
+/// Latching stop flag that tasks can wait on.
+#[derive(Debug)]
+pub struct EmergencyStop {
+    state: watch::Sender<bool>,
+}
+
+impl Default for EmergencyStop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmergencyStop {
+    pub fn new() -> Self {
+        Self {
+            state: watch::Sender::new(false),
+        }
+    }
+
+    /// Engage the stop. Returns `false` if it already was.
+    pub fn engage(&self) -> bool {
+        !self.state.send_replace(true)
+    }
+
+    /// Release the stop. Returns `false` if it was not engaged.
+    pub fn release(&self) -> bool {
+        self.state.send_replace(false)
+    }
+
+    pub fn is_engaged(&self) -> bool {
+        *self.state.borrow()
+    }
+
+    /// Completes once the stop is engaged.
+    pub async fn engaged(&self) {
+        let mut rx = self.state.subscribe();
+        // The sender lives in `self`, so the channel cannot close while we wait.
+        let _ = rx.wait_for(|engaged| *engaged).await;
+    }
+
+    /// Completes once the stop is released.
+    pub async fn released(&self) {
+        let mut rx = self.state.subscribe();
+        let _ = rx.wait_for(|engaged| !*engaged).await;
+    }
+
+    /// Run `task` while the stop is released. Waits for a release first and
+    /// aborts `task` when the stop is engaged.
+    pub async fn supervise<F>(&self, task: F) -> Result<()>
+    where
+        F: Future<Output = Result<()>>,
+    {
+        self.released().await;
+        tokio::select! {
+            result = task => result,
+            () = self.engaged() => bail!("stopped by the emergency stop"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn engage_and_release_report_transitions() {
+        let stop = EmergencyStop::new();
+        assert!(!stop.is_engaged());
+        assert!(!stop.release());
+        assert!(stop.engage());
+        assert!(!stop.engage());
+        assert!(stop.is_engaged());
+        assert!(stop.release());
+        assert!(!stop.is_engaged());
+    }
+
+    #[tokio::test]
+    async fn engaging_wakes_waiters() {
+        let stop = Arc::new(EmergencyStop::new());
+        let waiter = {
+            let stop = Arc::clone(&stop);
+            tokio::spawn(async move { stop.engaged().await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+        stop.engage();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn supervise_aborts_on_engage_and_waits_for_release() {
+        let stop = Arc::new(EmergencyStop::new());
+        let run = {
+            let stop = Arc::clone(&stop);
+            tokio::spawn(async move { stop.supervise(std::future::pending()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        stop.engage();
+        let err = run.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("emergency stop"));
+
+        let run = {
+            let stop = Arc::clone(&stop);
+            tokio::spawn(async move { stop.supervise(async { Ok(()) }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!run.is_finished());
+        stop.release();
+        run.await.unwrap().unwrap();
+    }
+}
@@ -6,7 +6,9 @@
 //!
 //! Firejail is a SUID sandbox program that Linux applications use to sandbox themselves.
 
-use crate::security::traits::Sandbox;
+use crate::config::SandboxProfile;
+use crate::security::traits::{rewrap, Sandbox};
+use std::path::Path;
 use std::process::Command;
 
 /// Firejail sandbox backend for Linux
@@ -77,6 +79,23 @@ impl Sandbox for FirejailSandbox {
         Self::is_installed()
     }
 
+    fn supports(&self, _profile: SandboxProfile) -> bool {
+        true
+    }
+
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SandboxProfile,
+        workspace_dir: &Path,
+    ) -> std::io::Result<()> {
+        if profile == SandboxProfile::None {
+            return Ok(());
+        }
+        rewrap(cmd, "firejail", profile_args(profile, workspace_dir));
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "firejail"
     }
@@ -86,6 +105,23 @@ impl Sandbox for FirejailSandbox {
     }
 }
 
+/// Firejail flags for `profile`. The home directory stays visible, since
+/// the workspace usually lives in it.
+fn profile_args(profile: SandboxProfile, workspace_dir: &Path) -> Vec<String> {
+    let mut args: Vec<String> = ["--noprofile", "--quiet", "--private-dev", "--nosound"]
+        .map(String::from)
+        .to_vec();
+    if profile.restricts_filesystem() {
+        args.push("--read-only=/".into());
+        args.push(format!("--read-write={}", workspace_dir.display()));
+        args.push("--read-write=/tmp".into());
+    }
+    if profile.restricts_network() {
+        args.push("--net=none".into());
+    }
+    args
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +165,21 @@ mod tests {
             assert_eq!(cmd.get_program().to_string_lossy(), "firejail");
         }
     }
+
+    #[test]
+    fn firejail_profile_args() {
+        let workspace = Path::new("/home/u/.mymolt/workspace");
+        let readonly = profile_args(SandboxProfile::ReadonlyFs, workspace);
+        assert!(readonly.contains(&"--read-only=/".to_string()));
+        assert!(readonly.contains(&"--read-write=/home/u/.mymolt/workspace".to_string()));
+        assert!(!readonly.contains(&"--net=none".to_string()));
+
+        let offline = profile_args(SandboxProfile::NoNetwork, workspace);
+        assert!(offline.contains(&"--net=none".to_string()));
+        assert!(!offline.contains(&"--read-only=/".to_string()));
+
+        let full = profile_args(SandboxProfile::Full, workspace);
+        assert!(full.contains(&"--net=none".to_string()));
+        assert!(full.contains(&"--read-only=/".to_string()));
+    }
 }
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Master key backends.
+//!
+//! Master keys — the `SecretStore` key and the vault admin private key — are
+//! plain files by default, so a copy of the workspace decrypts offline. With
+//! `secrets.key_backend = "keyring"` or `"tpm"` the key lives in the OS
+//! keyring or sealed to this machine's TPM, and the key file only holds a
+//! reference:
+//!
+//! - `keyring:<account>` — entry `<account>` of service `mymolt`
+//! - `tpm:<name>` — sealed object `<name>.tpm.pub` / `<name>.tpm.priv` next
+//!   to the key file
+//!
+//! Readers go through [`open`], which follows whichever reference the file
+//! holds regardless of the configured backend.
+
+use crate::config::KeyBackend;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use zeroize::Zeroizing;
+
+const KEYRING_SERVICE: &str = "mymolt";
+const KEYRING_PREFIX: &str = "keyring:";
+const TPM_PREFIX: &str = "tpm:";
+
+/// Whether key file `contents` is a reference into a backend rather than
+/// the key itself.
+pub fn is_reference(contents: &str) -> bool {
+    let contents = contents.trim();
+    contents.starts_with(KEYRING_PREFIX) || contents.starts_with(TPM_PREFIX)
+}
+
+/// Resolve the contents of key file `path` to the key.
+pub fn open(path: &Path, contents: &str) -> Result<Zeroizing<String>> {
+    let contents = contents.trim();
+    if let Some(account) = contents.strip_prefix(KEYRING_PREFIX) {
+        os_keyring::get(account)
+    } else if let Some(name) = contents.strip_prefix(TPM_PREFIX) {
+        let (public, private) = sealed_paths(path, name)?;
+        tpm_unseal(&public, &private)
+    } else {
+        Ok(Zeroizing::new(contents.to_string()))
+    }
+}
+
+/// Store `key` for key file `path` in `backend`. Returns what to write to
+/// the key file: the key itself for [`KeyBackend::File`], a reference
+/// otherwise.
+pub fn seal(path: &Path, backend: KeyBackend, key: &str) -> Result<Zeroizing<String>> {
+    let name = uuid::Uuid::new_v4().to_string();
+    match backend {
+        KeyBackend::File => Ok(Zeroizing::new(key.to_string())),
+        KeyBackend::Keyring => {
+            os_keyring::set(&name, key)?;
+            Ok(Zeroizing::new(format!("{KEYRING_PREFIX}{name}")))
+        }
+        KeyBackend::Tpm => {
+            let (public, private) = sealed_paths(path, &name)?;
+            tpm_seal(&public, &private, key)?;
+            Ok(Zeroizing::new(format!("{TPM_PREFIX}{name}")))
+        }
+    }
+}
+
+/// Remove the backend copy that key file `contents` refers to, if any.
+pub fn discard(path: &Path, contents: &str) -> Result<()> {
+    let contents = contents.trim();
+    if let Some(account) = contents.strip_prefix(KEYRING_PREFIX) {
+        os_keyring::delete(account)
+    } else if let Some(name) = contents.strip_prefix(TPM_PREFIX) {
+        let (public, private) = sealed_paths(path, name)?;
+        for blob in [public, private] {
+            if blob.exists() {
+                fs::remove_file(&blob)?;
+            }
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Re-store key file `path` in `backend`, removing the previous backend
+/// copy. Returns whether the file changed.
+pub fn migrate(path: &Path, backend: KeyBackend) -> Result<bool> {
+    let contents = Zeroizing::new(fs::read_to_string(path)?);
+    let current = match contents.trim() {
+        c if c.starts_with(KEYRING_PREFIX) => KeyBackend::Keyring,
+        c if c.starts_with(TPM_PREFIX) => KeyBackend::Tpm,
+        _ => KeyBackend::File,
+    };
+    if current == backend {
+        return Ok(false);
+    }
+
+    let key = open(path, &contents)?;
+    let sealed = seal(path, backend, &key)?;
+    fs::write(path, sealed.as_bytes())?;
+    discard(path, &contents)?;
+    Ok(true)
+}
+
+/// Blob paths of sealed object `name`, kept next to key file `path`.
+fn sealed_paths(path: &Path, name: &str) -> Result<(PathBuf, PathBuf)> {
+    anyhow::ensure!(
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+        "Invalid sealed key reference in {}",
+        path.display()
+    );
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    Ok((
+        dir.join(format!("{name}.tpm.pub")),
+        dir.join(format!("{name}.tpm.priv")),
+    ))
+}
+
+// ── TPM 2.0 (tpm2-tools) ────────────────────────────────────────────────
+
+fn tpm_seal(public: &Path, private: &Path, key: &str) -> Result<()> {
+    with_primary(|primary| {
+        run(
+            Command::new("tpm2_create")
+                .arg("-C")
+                .arg(primary)
+                .arg("-u")
+                .arg(public)
+                .arg("-r")
+                .arg(private)
+                .args(["-i", "-"]),
+            Some(key.as_bytes()),
+        )
+        .map(|_| ())
+    })
+}
+
+fn tpm_unseal(public: &Path, private: &Path) -> Result<Zeroizing<String>> {
+    with_primary(|primary| {
+        let object = primary.with_file_name("key.ctx");
+        run(
+            Command::new("tpm2_load")
+                .arg("-C")
+                .arg(primary)
+                .arg("-u")
+                .arg(public)
+                .arg("-r")
+                .arg(private)
+                .arg("-c")
+                .arg(&object),
+            None,
+        )?;
+        let key = run(Command::new("tpm2_unseal").arg("-c").arg(&object), None)?;
+        Ok(Zeroizing::new(
+            String::from_utf8(key.to_vec()).context("Unsealed key is not valid UTF-8")?,
+        ))
+    })
+}
+
+/// Run `f` with the owner-hierarchy primary key loaded into a scratch
+/// context file. The primary is derived from the TPM's seed, so it is the
+/// same on every call and only ever exists inside this TPM.
+fn with_primary<T>(f: impl FnOnce(&Path) -> Result<T>) -> Result<T> {
+    let dir = std::env::temp_dir().join(format!("mymolt-tpm-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir)?;
+    let primary = dir.join("primary.ctx");
+    let result = run(
+        Command::new("tpm2_createprimary")
+            .args(["-C", "o", "-c"])
+            .arg(&primary),
+        None,
+    )
+    .and_then(|_| f(&primary));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn run(command: &mut Command, input: Option<&[u8]>) -> Result<Zeroizing<Vec<u8>>> {
+    let tool = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                anyhow::anyhow!("TPM key backend needs tpm2-tools (`{tool}` not found)")
+            }
+            _ => anyhow::anyhow!("Failed to run {tool}: {e}"),
+        })?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "{tool} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(Zeroizing::new(output.stdout))
+}
+
+// ── OS keyring ──────────────────────────────────────────────────────────
+
+#[cfg(feature = "os-keyring")]
+mod os_keyring {
+    use super::KEYRING_SERVICE;
+    use anyhow::{Context, Result};
+    use zeroize::Zeroizing;
+
+    fn entry(account: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, account).context("Cannot open the OS keyring")
+    }
+
+    pub fn get(account: &str) -> Result<Zeroizing<String>> {
+        let key = entry(account)?
+            .get_password()
+            .with_context(|| format!("Key {account} not found in the OS keyring"))?;
+        Ok(Zeroizing::new(key))
+    }
+
+    pub fn set(account: &str, key: &str) -> Result<()> {
+        entry(account)?
+            .set_password(key)
+            .context("Cannot store the key in the OS keyring")
+    }
+
+    pub fn delete(account: &str) -> Result<()> {
+        match entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Cannot remove the key from the OS keyring"),
+        }
+    }
+}
+
+#[cfg(not(feature = "os-keyring"))]
+mod os_keyring {
+    use anyhow::{bail, Result};
+    use zeroize::Zeroizing;
+
+    const UNAVAILABLE: &str =
+        "OS keyring support is not compiled in (build with --features os-keyring)";
+
+    pub fn get(_account: &str) -> Result<Zeroizing<String>> {
+        bail!(UNAVAILABLE)
+    }
+
+    pub fn set(_account: &str, _key: &str) -> Result<()> {
+        bail!(UNAVAILABLE)
+    }
+
+    pub fn delete(_account: &str) -> Result<()> {
+        bail!(UNAVAILABLE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn file_backend_keeps_the_key_in_the_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(".secret_key");
+        let sealed = seal(&path, KeyBackend::File, "00ff").unwrap();
+        assert_eq!(sealed.as_str(), "00ff");
+        assert!(!is_reference(&sealed));
+        assert_eq!(open(&path, "00ff\n").unwrap().as_str(), "00ff");
+        fs::write(&path, "00ff").unwrap();
+        assert!(!migrate(&path, KeyBackend::File).unwrap());
+    }
+
+    #[test]
+    fn references_are_recognised_and_checked() {
+        assert!(is_reference("keyring:3f2a"));
+        assert!(is_reference("tpm:3f2a\n"));
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("admin.key");
+        assert!(open(&path, "tpm:../../etc/passwd").is_err());
+        assert!(discard(&path, "plain-key").is_ok());
+    }
+}
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Leak guard (`[security.leak_guard]`).
+//!
+//! The last check before data leaves the machine. `SecurityWrapper` runs it
+//! over the arguments of outbound tools and `GuardedProvider` over prompts
+//! to remote providers. Text is scanned with the [`SensitivityScanner`] —
+//! the same patterns that decide what the sovereign memory guard vaults —
+//! and for the credentials held in the config itself. Findings name the
+//! pattern or config key, never the secret.
+
+use crate::config::{Config, LeakGuardConfig, LeakGuardMode};
+use crate::memory::sovereign::SensitivityScanner;
+use anyhow::Result;
+use std::fmt;
+
+/// Config values shorter than this are not treated as credentials.
+const MIN_SECRET_LEN: usize = 8;
+
+/// Config keys whose string values are credentials.
+const CREDENTIAL_KEY_SUFFIXES: &[&str] = &[
+    "api_key",
+    "api_keys",
+    "token",
+    "tokens",
+    "secret",
+    "password",
+    "encrypt_key",
+];
+
+/// Finds secrets in data about to leave the machine.
+pub struct LeakGuard {
+    mode: LeakGuardMode,
+    tools: Vec<String>,
+    prompts: bool,
+    scanner: SensitivityScanner,
+    /// Credentials from the config as (config key, value)
+    known: Vec<(String, String)>,
+}
+
+impl fmt::Debug for LeakGuard {
+    // Never print the known credentials.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LeakGuard")
+            .field("mode", &self.mode)
+            .field("tools", &self.tools)
+            .field("prompts", &self.prompts)
+            .field("known", &self.known.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LeakGuard {
+    /// Guard using the sensitivity scanner as configured for memory and the
+    /// credentials found in `config`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let scanner = SensitivityScanner::for_languages(
+            &config.memory.sensitivity_languages,
+            &config.memory.sensitivity_extra_keywords,
+        )?
+        .configure(&config.security.sensitivity)?;
+        let mut known = Vec::new();
+        collect_credentials(None, &serde_json::to_value(config)?, false, &mut known);
+        Ok(Self::new(&config.security.leak_guard, scanner, known))
+    }
+
+    pub fn new(
+        config: &LeakGuardConfig,
+        scanner: SensitivityScanner,
+        mut known: Vec<(String, String)>,
+    ) -> Self {
+        known.retain(|(_, value)| value.len() >= MIN_SECRET_LEN);
+        Self {
+            mode: config.mode,
+            tools: config.tools.clone(),
+            prompts: config.prompts,
+            scanner,
+            known,
+        }
+    }
+
+    pub fn mode(&self) -> LeakGuardMode {
+        self.mode
+    }
+
+    /// Whether the arguments of tool `name` are scanned.
+    pub fn watches_tool(&self, name: &str) -> bool {
+        self.mode != LeakGuardMode::Off && self.tools.iter().any(|t| t == name)
+    }
+
+    /// Whether prompts to remote providers are scanned.
+    pub fn watches_prompts(&self) -> bool {
+        self.mode != LeakGuardMode::Off && self.prompts
+    }
+
+    /// What in `text` must not leave the machine, described without the
+    /// secret itself.
+    pub fn find(&self, text: &str) -> Option<String> {
+        if let Some((key, _)) = self
+            .known
+            .iter()
+            .find(|(_, value)| text.contains(value.as_str()))
+        {
+            return Some(format!("the credential configured as '{key}'"));
+        }
+        self.scanner
+            .scan(text)
+            .map(|pattern| format!("a value matching {pattern}"))
+    }
+}
+
+/// Collect the string values under credential keys of the serialized
+/// config, keyed by their dotted path.
+fn collect_credentials(
+    path: Option<&str>,
+    value: &serde_json::Value,
+    is_credential: bool,
+    out: &mut Vec<(String, String)>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = path.map_or_else(|| key.clone(), |p| format!("{p}.{key}"));
+                let credential = is_credential
+                    || CREDENTIAL_KEY_SUFFIXES
+                        .iter()
+                        .any(|suffix| key.to_lowercase().ends_with(suffix));
+                collect_credentials(Some(&child_path), child, credential, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_credentials(path, item, is_credential, out);
+            }
+        }
+        serde_json::Value::String(s) if is_credential && !s.trim().is_empty() => {
+            out.push((path.unwrap_or_default().to_string(), s.clone()));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(known: &[(&str, &str)]) -> LeakGuard {
+        LeakGuard::new(
+            &LeakGuardConfig::default(),
+            SensitivityScanner::new(),
+            known
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn finds_known_credentials_and_patterns_without_echoing_them() {
+        let g = guard(&[
+            ("composio.api_key", "cmp-live-0123456789"),
+            ("short", "abc"),
+        ]);
+        let finding = g.find(r#"{"body": "key=cmp-live-0123456789"}"#).unwrap();
+        assert_eq!(finding, "the credential configured as 'composio.api_key'");
+        assert!(g.find("sk-abcdefghijklmnopqrstuvwxyz123456").is_some());
+        // Values too short to be credentials are ignored.
+        assert!(g.find("abc def").is_none());
+        assert!(g.find("Weather in Berlin").is_none());
+    }
+
+    #[test]
+    fn collects_credentials_from_config() {
+        let mut config = Config::default();
+        config.api_key = Some("sk-or-v1-abcdef0123456789".into());
+        config.composio.api_key = Some("cmp-0123456789".into());
+        config.default_model = Some("anthropic/claude-sonnet-4".into());
+        let g = LeakGuard::from_config(&config).unwrap();
+        let keys: Vec<&str> = g.known.iter().map(|(k, _)| k.as_str()).collect();
+        assert!(keys.contains(&"api_key"));
+        assert!(keys.contains(&"composio.api_key"));
+        assert!(g.find("anthropic/claude-sonnet-4").is_none());
+        assert!(!format!("{g:?}").contains("cmp-0123456789"));
+    }
+
+    #[test]
+    fn tools_and_prompts_follow_the_mode() {
+        let mut g = guard(&[]);
+        assert!(g.watches_tool("http_request"));
+        assert!(!g.watches_tool("file_read"));
+        assert!(g.watches_prompts());
+        g.mode = LeakGuardMode::Off;
+        assert!(!g.watches_tool("http_request"));
+        assert!(!g.watches_prompts());
+    }
+}
@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Lockout of API clients that keep failing authentication
+//! (`[gateway.auth_lockout]`).
+//!
+//! The gateway records every invalid bearer token per client key. After
+//! `max_failures` of them the client is locked out; each further lockout
+//! doubles in length up to `max_lockout_secs`. A successful authentication
+//! forgets the client, and so does a quiet period of `max_lockout_secs`
+//! after its last lockout ended.
+
+use crate::config::AuthLockoutConfig;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A lockout that has just started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lockout {
+    /// Invalid tokens from the client since it was last forgotten.
+    pub failures: u32,
+    /// Lockouts of the client so far, this one included.
+    pub strike: u32,
+    pub duration: Duration,
+}
+
+#[derive(Debug)]
+struct Client {
+    /// Failures since the last lockout started
+    pending: u32,
+    total: u32,
+    strikes: u32,
+    locked_until: Option<Instant>,
+    last_failure: Instant,
+}
+
+/// Failed-authentication counters per client key.
+#[derive(Debug)]
+pub struct AuthLockout {
+    config: AuthLockoutConfig,
+    clients: Mutex<HashMap<String, Client>>,
+}
+
+impl AuthLockout {
+    pub fn new(config: AuthLockoutConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether Root is told about new lockouts.
+    pub fn notify_root(&self) -> bool {
+        self.config.notify_root
+    }
+
+    /// `Err(seconds)` while `client` is locked out.
+    pub fn check(&self, client: &str) -> Result<(), u64> {
+        self.check_at(client, Instant::now())
+    }
+
+    /// Count an invalid token from `client`. Returns the lockout it starts,
+    /// if any.
+    pub fn record_failure(&self, client: &str) -> Option<Lockout> {
+        self.record_failure_at(client, Instant::now())
+    }
+
+    /// Forget `client` after it authenticated.
+    pub fn record_success(&self, client: &str) {
+        self.lock().remove(client);
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), u64> {
+        let clients = self.lock();
+        match clients.get(client).and_then(|c| c.locked_until) {
+            Some(until) if until > now => Err((until - now).as_secs().max(1)),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_failure_at(&self, client: &str, now: Instant) -> Option<Lockout> {
+        if self.config.max_failures == 0 {
+            return None;
+        }
+        let quiet = Duration::from_secs(self.max_lockout_secs());
+        let mut clients = self.lock();
+        clients.retain(|_, c| {
+            let settled = c
+                .locked_until
+                .map_or(c.last_failure, |until| until.max(c.last_failure));
+            now.saturating_duration_since(settled) < quiet
+        });
+
+        let entry = clients.entry(client.to_string()).or_insert(Client {
+            pending: 0,
+            total: 0,
+            strikes: 0,
+            locked_until: None,
+            last_failure: now,
+        });
+        entry.last_failure = now;
+        entry.total += 1;
+        if entry.locked_until.is_some_and(|until| until > now) {
+            return None;
+        }
+        entry.pending += 1;
+        if entry.pending < self.config.max_failures {
+            return None;
+        }
+
+        entry.pending = 0;
+        entry.strikes += 1;
+        let duration = Duration::from_secs(self.lockout_secs(entry.strikes));
+        entry.locked_until = Some(now + duration);
+        Some(Lockout {
+            failures: entry.total,
+            strike: entry.strikes,
+            duration,
+        })
+    }
+
+    fn max_lockout_secs(&self) -> u64 {
+        self.config.max_lockout_secs.max(self.config.lockout_secs)
+    }
+
+    /// Length of the `strike`-th lockout: doubled each time, capped.
+    fn lockout_secs(&self, strike: u32) -> u64 {
+        let factor = 1_u64 << strike.saturating_sub(1).min(32);
+        self.config
+            .lockout_secs
+            .saturating_mul(factor)
+            .min(self.max_lockout_secs())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Client>> {
+        self.clients
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lockout(max_failures: u32) -> AuthLockout {
+        AuthLockout::new(AuthLockoutConfig {
+            max_failures,
+            lockout_secs: 60,
+            max_lockout_secs: 200,
+            notify_root: false,
+        })
+    }
+
+    #[test]
+    fn locks_out_after_max_failures() {
+        let guard = lockout(3);
+        let now = Instant::now();
+        assert_eq!(guard.record_failure_at("1.2.3.4", now), None);
+        assert_eq!(guard.record_failure_at("1.2.3.4", now), None);
+        assert_eq!(guard.check_at("1.2.3.4", now), Ok(()));
+
+        let started = guard.record_failure_at("1.2.3.4", now).unwrap();
+        assert_eq!(started.failures, 3);
+        assert_eq!(started.strike, 1);
+        assert_eq!(started.duration.as_secs(), 60);
+        assert_eq!(guard.check_at("1.2.3.4", now), Err(60));
+        assert_eq!(guard.check_at("5.6.7.8", now), Ok(()));
+        assert_eq!(guard.check_at("1.2.3.4", now + started.duration), Ok(()));
+    }
+
+    #[test]
+    fn lockouts_escalate_up_to_the_maximum() {
+        let guard = lockout(1);
+        let mut now = Instant::now();
+        let mut durations = Vec::new();
+        for _ in 0..4 {
+            let started = guard.record_failure_at("client", now).unwrap();
+            durations.push(started.duration.as_secs());
+            now += started.duration;
+        }
+        assert_eq!(durations, [60, 120, 200, 200]);
+    }
+
+    #[test]
+    fn failures_during_a_lockout_do_not_extend_it() {
+        let guard = lockout(1);
+        let now = Instant::now();
+        assert!(guard.record_failure_at("client", now).is_some());
+        assert_eq!(
+            guard.record_failure_at("client", now + Duration::from_secs(10)),
+            None
+        );
+        assert_eq!(
+            guard.check_at("client", now + Duration::from_secs(10)),
+            Err(50)
+        );
+    }
+
+    #[test]
+    fn success_and_quiet_periods_forget_the_client() {
+        let guard = lockout(2);
+        let now = Instant::now();
+        guard.record_failure_at("a", now);
+        guard.record_success("a");
+        assert_eq!(guard.record_failure_at("a", now), None);
+        assert!(guard.record_failure_at("a", now).is_some());
+
+        // 60s lockout, then 200s without failures resets the escalation
+        let later = now + Duration::from_secs(260);
+        guard.record_failure_at("a", later);
+        let started = guard.record_failure_at("a", later).unwrap();
+        assert_eq!(started.strike, 1);
+    }
+
+    #[test]
+    fn zero_max_failures_disables_lockouts() {
+        let guard = lockout(0);
+        for _ in 0..10 {
+            assert_eq!(guard.record_failure("client"), None);
+        }
+        assert_eq!(guard.check("client"), Ok(()));
+    }
+}
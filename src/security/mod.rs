@@ -7,15 +7,26 @@ pub mod audit;
 pub mod bubblewrap;
 pub mod confirmation;
 pub mod detect;
+pub mod devices;
 pub mod docker;
+pub mod emergency;
 #[cfg(target_os = "linux")]
 pub mod firejail;
+pub mod injection;
+pub mod keystore;
 #[cfg(feature = "sandbox-landlock")]
 pub mod landlock;
+pub mod leak_guard;
+pub mod lockout;
 pub mod pairing;
 pub mod policy;
+pub mod quotas;
+pub mod route_policy;
 pub mod secrets;
+pub mod share;
 pub mod sigil_bridge;
+pub mod tool_rules;
+pub mod totp;
 pub mod traits;
 pub mod vault;
 pub mod workers;
@@ -31,6 +42,7 @@ pub use pairing::PairingGuard;
 pub use policy::{AutonomyLevel, SecurityPolicy};
 #[allow(unused_imports)]
 pub use secrets::SecretStore;
+pub use tool_rules::PolicyDenial;
 #[allow(unused_imports)]
 pub use traits::{NoopSandbox, Sandbox};
 
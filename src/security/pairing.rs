@@ -94,6 +94,21 @@ impl PairingGuard {
     /// Attempt to pair with the given code. Returns a bearer token on success.
     /// Returns `Err(lockout_seconds)` if locked out due to brute force.
     pub fn try_pair(&self, code: &str) -> Result<Option<String>, u64> {
+        if !self.redeem_code(code)? {
+            return Ok(None);
+        }
+        let token = generate_token();
+        self.paired_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(hash_token(&token));
+        Ok(Some(token))
+    }
+
+    /// Check and consume the one-time code without issuing a token, for
+    /// callers that issue their own (the gateway registers a device).
+    /// Returns `Err(lockout_seconds)` if locked out due to brute force.
+    pub fn redeem_code(&self, code: &str) -> Result<bool, u64> {
         // Check brute force lockout
         {
             let attempts = self
@@ -125,17 +140,11 @@ impl PairingGuard {
                             .unwrap_or_else(std::sync::PoisonError::into_inner);
                         *attempts = (0, None);
                     }
-                    let token = generate_token();
-                    let mut tokens = self
-                        .paired_tokens
-                        .lock()
-                        .unwrap_or_else(std::sync::PoisonError::into_inner);
-                    tokens.insert(hash_token(&token));
 
                     // Consume the pairing code so it cannot be reused
                     *pairing_code = None;
 
-                    return Ok(Some(token));
+                    return Ok(true);
                 }
             }
         }
@@ -152,7 +161,7 @@ impl PairingGuard {
             }
         }
 
-        Ok(None)
+        Ok(false)
     }
 
     /// Check if a bearer token is valid (compares against stored hashes).
@@ -185,6 +194,35 @@ impl PairingGuard {
             .unwrap_or_else(std::sync::PoisonError::into_inner);
         tokens.iter().cloned().collect()
     }
+
+    /// Forget the paired token hashes without touching the pairing state,
+    /// once the device registry has adopted them.
+    pub fn clear_tokens(&self) {
+        self.paired_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Forget every paired token and require pairing with a fresh one-time
+    /// code, which is returned. Used by the emergency stop.
+    pub fn revoke_all(&self) -> String {
+        let code = generate_code();
+        self.require_pairing.store(true, Ordering::Relaxed);
+        self.paired_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        *self
+            .failed_attempts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = (0, None);
+        *self
+            .pairing_code
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(code.clone());
+        code
+    }
 }
 
 /// Generate a 6-digit numeric pairing code using cryptographically secure randomness.
@@ -212,12 +250,12 @@ fn generate_code() -> String {
 }
 
 /// Generate a cryptographically-adequate bearer token (hex-encoded).
-fn generate_token() -> String {
+pub(crate) fn generate_token() -> String {
     format!("zc_{}", uuid::Uuid::new_v4().as_simple())
 }
 
 /// SHA-256 hash a bearer token for storage. Returns lowercase hex.
-fn hash_token(token: &str) -> String {
+pub(crate) fn hash_token(token: &str) -> String {
     format!("{:x}", Sha256::digest(token.as_bytes()))
 }
 
@@ -359,6 +397,19 @@ mod tests {
         assert!(!guard.is_authenticated("wrong"));
     }
 
+    #[test]
+    fn revoke_all_drops_tokens_and_requires_new_code() {
+        let guard = PairingGuard::new(false, &["zc_old".into()]);
+        let code = guard.revoke_all();
+        assert!(guard.require_pairing());
+        assert!(!guard.is_paired());
+        assert!(!guard.is_authenticated("zc_old"));
+        assert_eq!(guard.pairing_code().as_deref(), Some(code.as_str()));
+
+        let token = guard.try_pair(&code).unwrap().unwrap();
+        assert!(guard.is_authenticated(&token));
+    }
+
     // ── Token hashing ────────────────────────────────────────
 
     #[test]
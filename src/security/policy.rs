@@ -5,7 +5,8 @@
 use crate::identity::soul::TrustLevel;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 /// How much autonomy the agent has
@@ -29,6 +30,29 @@ pub enum CommandRiskLevel {
     High,
 }
 
+/// The user's trust level, shared by a policy and all its clones so that a
+/// change of identity bindings reaches every component holding the policy.
+#[derive(Debug, Clone)]
+pub struct SharedTrustLevel(Arc<AtomicU8>);
+
+impl SharedTrustLevel {
+    pub fn new(level: TrustLevel) -> Self {
+        Self(Arc::new(AtomicU8::new(level as u8)))
+    }
+
+    pub fn get(&self) -> TrustLevel {
+        match self.0.load(Ordering::Acquire) {
+            3 => TrustLevel::High,
+            2 => TrustLevel::Medium,
+            _ => TrustLevel::Low,
+        }
+    }
+
+    pub fn set(&self, level: TrustLevel) {
+        self.0.store(level as u8, Ordering::Release);
+    }
+}
+
 /// Sliding-window action tracker for rate limiting.
 #[derive(Debug)]
 pub struct ActionTracker {
@@ -100,7 +124,7 @@ pub struct SecurityPolicy {
     pub confirmation_required: std::collections::HashMap<String, String>,
     pub tracker: ActionTracker,
     /// Current user's trust level (resolved from Soul identity bindings).
-    pub trust_level: TrustLevel,
+    pub trust_level: SharedTrustLevel,
     /// Minimum trust required for shell command execution.
     pub required_trust_for_shell: TrustLevel,
     /// Minimum trust required for agent-to-agent delegation.
@@ -109,6 +133,25 @@ pub struct SecurityPolicy {
     pub required_trust_for_vault: TrustLevel,
     /// Minimum trust required for MCP tool calls.
     pub required_trust_for_mcp: TrustLevel,
+    /// Minimum trust per capability (tool name, `prefix_*` pattern or
+    /// gateway operation), from `[security.trust.tiers]`.
+    pub trust_tiers: std::collections::BTreeMap<String, TrustLevel>,
+    /// CPU, memory, process and output limits for each shell command.
+    pub resource_limits: crate::config::ResourceLimitsConfig,
+    /// Maximum calls per UTC day, keyed by tool name.
+    pub tool_daily_budgets: std::collections::HashMap<String, u32>,
+    /// Path, command and domain rules, keyed by tool name.
+    pub tool_rules: std::collections::BTreeMap<String, crate::config::ToolRuleConfig>,
+    /// Flags or strips injected instructions in tool results.
+    pub injection: std::sync::Arc<super::injection::InjectionScanner>,
+    /// Scans outbound tool arguments for secrets; set by callers that have
+    /// the full config.
+    pub leak_guard: Option<std::sync::Arc<super::leak_guard::LeakGuard>>,
+    /// Approver roles, timeouts and remembered approvals per tool and risk
+    /// level.
+    pub confirmation: crate::config::ConfirmationConfig,
+    /// Sandbox backend and per-tool profile overrides.
+    pub sandbox: crate::config::SandboxConfig,
 }
 
 impl Default for SecurityPolicy {
@@ -175,18 +218,26 @@ impl Default for SecurityPolicy {
                 m
             },
             // Trust defaults: user starts at Low, sensitive operations require High
-            trust_level: TrustLevel::Low,
+            trust_level: SharedTrustLevel::new(TrustLevel::Low),
             required_trust_for_shell: TrustLevel::Low,
             required_trust_for_delegation: TrustLevel::High,
             required_trust_for_vault: TrustLevel::High,
             required_trust_for_mcp: TrustLevel::Low,
+            trust_tiers: crate::config::TrustConfig::default().tier_requirements(),
+            resource_limits: crate::config::ResourceLimitsConfig::default(),
+            tool_daily_budgets: std::collections::HashMap::new(),
+            tool_rules: std::collections::BTreeMap::new(),
+            injection: std::sync::Arc::default(),
+            leak_guard: None,
+            confirmation: crate::config::ConfirmationConfig::default(),
+            sandbox: crate::config::SandboxConfig::default(),
         }
     }
 }
 
 /// Skip leading environment variable assignments (e.g. `FOO=bar cmd args`).
 /// Returns the remainder starting at the first non-assignment word.
-fn skip_env_assignments(s: &str) -> &str {
+pub(super) fn skip_env_assignments(s: &str) -> &str {
     let mut rest = s;
     loop {
         let Some(word) = rest.split_whitespace().next() else {
@@ -237,6 +288,11 @@ impl SecurityPolicy {
 
     /// Check if an action requires user confirmation.
     pub fn requires_confirmation(&self, skill: &str, action: &str) -> bool {
+        // A tool's confirmation policy overrides the skill/action map
+        if let Some(required) = self.confirmation.tools.get(skill).and_then(|p| p.required) {
+            return required;
+        }
+
         // Check exact match "skill:action"
         let key = format!("{}:{}", skill, action);
         if let Some(policy) = self.confirmation_required.get(&key) {
@@ -588,7 +644,7 @@ impl SecurityPolicy {
             disabled_skills: security_config.disabled_skills.clone(),
             confirmation_required: security_config.confirmation_required.clone(),
             tracker: ActionTracker::new(),
-            trust_level: TrustLevel::Low,
+            trust_level: SharedTrustLevel::new(TrustLevel::Low),
             required_trust_for_shell: crate::config::TrustConfig::parse_level(
                 &security_config.trust.shell,
             ),
@@ -601,25 +657,71 @@ impl SecurityPolicy {
             required_trust_for_mcp: crate::config::TrustConfig::parse_level(
                 &security_config.trust.mcp,
             ),
+            trust_tiers: security_config.trust.tier_requirements(),
+            resource_limits: security_config.resources.clone(),
+            tool_daily_budgets: autonomy_config.tool_daily_budgets.clone(),
+            tool_rules: security_config.tools.clone(),
+            injection: std::sync::Arc::new(super::injection::InjectionScanner::from_config(
+                &security_config.injection,
+            )),
+            leak_guard: None,
+            confirmation: security_config.confirmation.clone(),
+            sandbox: security_config.sandbox.clone(),
         }
     }
 
+    /// The sandbox for `tool`'s commands. `[security.sandbox.profiles]`
+    /// overrides the profile the tool declares.
+    pub fn tool_sandbox(
+        &self,
+        tool: &str,
+        declared: crate::config::SandboxProfile,
+    ) -> super::detect::ToolSandbox {
+        let profile = self.sandbox.profiles.get(tool).copied().unwrap_or(declared);
+        super::detect::create_tool_sandbox(&self.sandbox, profile)
+    }
+
     /// Check if the current trust level meets a requirement.
     /// Returns `Ok(())` if allowed, `Err(reason)` if denied.
     pub fn check_trust(&self, required: TrustLevel) -> Result<(), String> {
-        if self.trust_level >= required {
+        let current = self.trust_level();
+        if current >= required {
             Ok(())
         } else {
             Err(format!(
-                "Requires {:?} trust, current level is {:?}",
-                required, self.trust_level
+                "Requires {required:?} trust, current level is {current:?}"
             ))
         }
     }
 
-    /// Set the trust level (called after identity resolution).
-    pub fn set_trust_level(&mut self, level: TrustLevel) {
-        self.trust_level = level;
+    /// Minimum trust the tiers require for `capability`; `Low` when no
+    /// tier lists it.
+    pub fn required_trust_for(&self, capability: &str) -> TrustLevel {
+        self.trust_tiers
+            .iter()
+            .filter(|(pattern, _)| match pattern.strip_suffix('*') {
+                Some(prefix) => capability.starts_with(prefix),
+                None => pattern.as_str() == capability,
+            })
+            .map(|(_, level)| *level)
+            .max()
+            .unwrap_or(TrustLevel::Low)
+    }
+
+    /// Check the current trust level against the tier of `capability`.
+    pub fn check_capability(&self, capability: &str) -> Result<(), String> {
+        self.check_trust(self.required_trust_for(capability))
+    }
+
+    /// The current trust level.
+    pub fn trust_level(&self) -> TrustLevel {
+        self.trust_level.get()
+    }
+
+    /// Set the trust level (called after identity resolution, and when a
+    /// binding is removed). Applies to every clone of this policy.
+    pub fn set_trust_level(&self, level: TrustLevel) {
+        self.trust_level.set(level);
     }
 }
 
@@ -902,6 +1004,7 @@ mod tests {
             max_cost_per_day_cents: 1000,
             require_approval_for_medium_risk: false,
             block_high_risk_commands: false,
+            tool_daily_budgets: std::collections::HashMap::from([("http_request".into(), 50)]),
         };
         let workspace = PathBuf::from("/tmp/test-workspace");
         let security = crate::config::SecurityConfig::default();
@@ -915,6 +1018,7 @@ mod tests {
         assert_eq!(policy.max_cost_per_day_cents, 1000);
         assert!(!policy.require_approval_for_medium_risk);
         assert!(!policy.block_high_risk_commands);
+        assert_eq!(policy.tool_daily_budgets.get("http_request"), Some(&50));
         assert_eq!(policy.workspace_dir, PathBuf::from("/tmp/test-workspace"));
     }
 
@@ -998,6 +1102,42 @@ mod tests {
         assert_eq!(cloned.count(), 2); // clone is independent
     }
 
+    // ── Trust level ─────────────────────────────────────────
+
+    #[test]
+    fn trust_level_is_shared_with_clones() {
+        let policy = SecurityPolicy::default();
+        policy.set_trust_level(TrustLevel::High);
+        let cloned = policy.clone();
+        assert!(cloned.check_trust(TrustLevel::High).is_ok());
+
+        policy.set_trust_level(TrustLevel::Medium);
+        assert_eq!(cloned.trust_level(), TrustLevel::Medium);
+        assert!(cloned.check_trust(TrustLevel::High).is_err());
+    }
+
+    #[test]
+    fn trust_tiers_gate_capabilities() {
+        let policy = SecurityPolicy {
+            trust_tiers: std::collections::BTreeMap::from([
+                ("shell".into(), TrustLevel::Medium),
+                ("vault_reveal".into(), TrustLevel::Medium),
+                ("calendar_*".into(), TrustLevel::High),
+            ]),
+            ..SecurityPolicy::default()
+        };
+        assert!(policy.check_capability("shell").is_err());
+        assert!(policy.check_capability("calendar_write").is_err());
+        assert!(policy.check_capability("vault_reveal").is_err());
+        assert!(policy.check_capability("file_read").is_ok());
+
+        policy.set_trust_level(TrustLevel::Medium);
+        assert!(policy.check_capability("shell").is_ok());
+        assert!(policy.check_capability("vault_reveal").is_ok());
+        assert!(policy.check_capability("calendar_write").is_err());
+        assert!(policy.check_capability("vpn").is_err());
+    }
+
     // ── Edge cases: command injection ────────────────────────
 
     #[test]
@@ -1216,6 +1356,7 @@ mod tests {
             max_cost_per_day_cents: 100,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            tool_daily_budgets: std::collections::HashMap::new(),
         };
         let workspace = PathBuf::from("/tmp/test");
         let security = crate::config::SecurityConfig::default();
@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Daily usage quotas of family members (`[family.quotas]`).
+//!
+//! The gateway charges a member's sessions for every agent turn, every
+//! minute with browsing activity and every call of a tool that has a limit.
+//! Limits come from the member's own `quotas`, or else from their role's;
+//! an Adult can grant extra usage for the rest of the day. Usage is kept in
+//! memory and starts over at local midnight.
+//!
+//! Tool calls are charged inside [`with_member`], which the gateway wraps
+//! around a member's agent turn: `SecurityWrapper` calls [`charge_tool`].
+
+use crate::config::{FamilyConfig, UsageLimits};
+use crate::identity::UserRole;
+use crate::network::content_filter::role_key;
+use chrono::{Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+tokio::task_local! {
+    static MEMBER: MemberQuota;
+}
+
+/// Something a member uses up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "quota", content = "tool", rename_all = "snake_case")]
+pub enum Quota {
+    ChatTurns,
+    BrowseMinutes,
+    /// Calls of one tool
+    Tool(String),
+}
+
+impl Quota {
+    fn limit(&self, limits: &UsageLimits) -> Option<u32> {
+        match self {
+            Self::ChatTurns => limits.chat_turns,
+            Self::BrowseMinutes => limits.browse_minutes,
+            Self::Tool(tool) => limits.tools.get(tool).copied(),
+        }
+    }
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChatTurns => f.write_str("chat turns"),
+            Self::BrowseMinutes => f.write_str("browsing minutes"),
+            Self::Tool(tool) => write!(f, "'{tool}' calls"),
+        }
+    }
+}
+
+/// A member ran out of a quota for today.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaExceeded {
+    pub member: String,
+    pub role: UserRole,
+    #[serde(flatten)]
+    pub quota: Quota,
+    /// Today's limit, extensions included
+    pub limit: u32,
+}
+
+impl QuotaExceeded {
+    /// What the assistant answers instead: a gentle note for a child, the
+    /// plain limit for everyone else.
+    pub fn reply(&self) -> String {
+        if self.role > UserRole::Child {
+            return self.to_string();
+        }
+        let activity = match self.quota {
+            Quota::ChatTurns => "chatting",
+            Quota::BrowseMinutes => "browsing",
+            Quota::Tool(_) => "of that",
+        };
+        format!(
+            "That's all the {activity} for today, {}! Time for a break — I'll be here \
+             again tomorrow. If you really need a bit more, ask a grown-up.",
+            self.member
+        )
+    }
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Daily limit reached: {} has used {} {} today",
+            self.member, self.limit, self.quota
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+/// One limited quota of a member, as shown to Adults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuotaUsage {
+    #[serde(flatten)]
+    pub quota: Quota,
+    pub used: u32,
+    /// Today's limit, extensions included
+    pub limit: u32,
+    /// Extra granted for today
+    pub extra: u32,
+}
+
+/// The limits that apply to `member`: their own, else their role's.
+pub fn limits_for(family: &FamilyConfig, member: &str, role: UserRole) -> UsageLimits {
+    family
+        .members
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(member))
+        .and_then(|m| m.quotas.clone())
+        .or_else(|| family.quotas.get(role_key(role)).cloned())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    used: HashMap<Quota, u32>,
+    extra: HashMap<Quota, u32>,
+    /// Minute of the day last counted as browsing
+    browse_minute: Option<u32>,
+}
+
+impl Usage {
+    fn limit(&self, quota: &Quota, base: u32) -> u32 {
+        base.saturating_add(self.extra.get(quota).copied().unwrap_or(0))
+    }
+}
+
+#[derive(Debug)]
+struct Day {
+    date: NaiveDate,
+    /// Keyed by lowercased member name
+    members: HashMap<String, Usage>,
+}
+
+/// Today's usage of every family member.
+#[derive(Debug)]
+pub struct UsageQuotas {
+    day: Mutex<Day>,
+}
+
+impl Default for UsageQuotas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageQuotas {
+    pub fn new() -> Self {
+        Self {
+            day: Mutex::new(Day {
+                date: Local::now().date_naive(),
+                members: HashMap::new(),
+            }),
+        }
+    }
+
+    /// `member`'s quotas for one turn or request.
+    pub fn for_member(
+        self: &Arc<Self>,
+        family: &FamilyConfig,
+        member: &str,
+        role: UserRole,
+    ) -> MemberQuota {
+        MemberQuota {
+            quotas: Arc::clone(self),
+            member: member.to_string(),
+            role,
+            limits: limits_for(family, member, role),
+        }
+    }
+
+    /// Give `member` `amount` more of `quota` for the rest of today.
+    pub fn extend(&self, member: &str, quota: Quota, amount: u32) {
+        self.extend_at(member, quota, amount, Local::now().naive_local());
+    }
+
+    /// Today's usage of each quota `limits` restricts.
+    pub fn usage(&self, member: &str, limits: &UsageLimits) -> Vec<QuotaUsage> {
+        self.usage_at(member, limits, Local::now().naive_local())
+    }
+
+    fn today<R>(&self, now: NaiveDateTime, f: impl FnOnce(&mut HashMap<String, Usage>) -> R) -> R {
+        let mut day = self.day.lock().unwrap_or_else(PoisonError::into_inner);
+        if day.date != now.date() {
+            day.date = now.date();
+            day.members.clear();
+        }
+        f(&mut day.members)
+    }
+
+    fn charge_at(
+        &self,
+        member: &MemberQuota,
+        quota: Quota,
+        now: NaiveDateTime,
+    ) -> Result<(), QuotaExceeded> {
+        let Some(base) = quota.limit(&member.limits) else {
+            return Ok(());
+        };
+        // Browsing is charged once per minute, however many pages load in it
+        let minute = (quota == Quota::BrowseMinutes).then(|| now.num_seconds_from_midnight() / 60);
+        self.today(now, |members| {
+            let usage = members.entry(member.member.to_lowercase()).or_default();
+            if minute.is_some() && usage.browse_minute == minute {
+                return Ok(());
+            }
+            let limit = usage.limit(&quota, base);
+            let used = usage.used.entry(quota.clone()).or_default();
+            if *used >= limit {
+                return Err(QuotaExceeded {
+                    member: member.member.clone(),
+                    role: member.role,
+                    quota,
+                    limit,
+                });
+            }
+            *used += 1;
+            if minute.is_some() {
+                usage.browse_minute = minute;
+            }
+            Ok(())
+        })
+    }
+
+    fn extend_at(&self, member: &str, quota: Quota, amount: u32, now: NaiveDateTime) {
+        self.today(now, |members| {
+            let extra = members
+                .entry(member.to_lowercase())
+                .or_default()
+                .extra
+                .entry(quota)
+                .or_default();
+            *extra = extra.saturating_add(amount);
+        });
+    }
+
+    fn usage_at(&self, member: &str, limits: &UsageLimits, now: NaiveDateTime) -> Vec<QuotaUsage> {
+        let mut tools: Vec<&String> = limits.tools.keys().collect();
+        tools.sort();
+        let quotas = [Quota::ChatTurns, Quota::BrowseMinutes]
+            .into_iter()
+            .chain(tools.into_iter().map(|t| Quota::Tool(t.clone())));
+        self.today(now, |members| {
+            let usage = members.get(&member.to_lowercase());
+            quotas
+                .filter_map(|quota| {
+                    let base = quota.limit(limits)?;
+                    let count = |map: fn(&Usage) -> &HashMap<Quota, u32>| {
+                        usage.and_then(|u| map(u).get(&quota)).copied().unwrap_or(0)
+                    };
+                    let (used, extra) = (count(|u| &u.used), count(|u| &u.extra));
+                    Some(QuotaUsage {
+                        limit: base.saturating_add(extra),
+                        quota,
+                        used,
+                        extra,
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// A member's limits for one turn or request, charged to their usage.
+#[derive(Debug, Clone)]
+pub struct MemberQuota {
+    quotas: Arc<UsageQuotas>,
+    member: String,
+    role: UserRole,
+    limits: UsageLimits,
+}
+
+impl MemberQuota {
+    /// Use up one of `quota`, or say why not.
+    pub fn charge(&self, quota: Quota) -> Result<(), QuotaExceeded> {
+        self.quotas
+            .charge_at(self, quota, Local::now().naive_local())
+    }
+}
+
+/// Run a member's agent turn so that their tool calls are charged to them.
+pub async fn with_member<F: Future>(quota: Option<MemberQuota>, fut: F) -> F::Output {
+    match quota {
+        Some(quota) => MEMBER.scope(quota, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Charge a call of `tool` to the member of the running turn; always `Ok`
+/// outside [`with_member`].
+pub fn charge_tool(tool: &str) -> Result<(), QuotaExceeded> {
+    MEMBER
+        .try_with(|quota| quota.charge(Quota::Tool(tool.to_string())))
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FamilyMemberConfig;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn family() -> FamilyConfig {
+        let mut family = FamilyConfig::default();
+        family.quotas.insert(
+            "child".into(),
+            UsageLimits {
+                chat_turns: Some(2),
+                browse_minutes: Some(2),
+                tools: HashMap::from([("web_search".into(), 1)]),
+            },
+        );
+        family.members.push(FamilyMemberConfig {
+            name: "Mia".into(),
+            role: "child".into(),
+            channels: HashMap::new(),
+            pin_hash: None,
+            oidc: None,
+            quotas: Some(UsageLimits {
+                chat_turns: Some(5),
+                ..UsageLimits::default()
+            }),
+        });
+        family
+    }
+
+    #[test]
+    fn turns_run_out_until_extended_or_the_next_day() {
+        let quotas = Arc::new(UsageQuotas::new());
+        let luca = quotas.for_member(&family(), "Luca", UserRole::Child);
+
+        assert!(quotas
+            .charge_at(&luca, Quota::ChatTurns, at(1, 9, 0))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::ChatTurns, at(1, 9, 1))
+            .is_ok());
+        let exceeded = quotas
+            .charge_at(&luca, Quota::ChatTurns, at(1, 9, 2))
+            .unwrap_err();
+        assert_eq!(exceeded.limit, 2);
+        assert!(exceeded.reply().contains("ask a grown-up"));
+
+        quotas.extend_at("luca", Quota::ChatTurns, 1, at(1, 9, 3));
+        assert!(quotas
+            .charge_at(&luca, Quota::ChatTurns, at(1, 9, 4))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::ChatTurns, at(1, 9, 5))
+            .is_err());
+        assert_eq!(
+            quotas.usage_at("Luca", &luca.limits, at(1, 9, 6))[0],
+            QuotaUsage {
+                quota: Quota::ChatTurns,
+                used: 3,
+                limit: 3,
+                extra: 1,
+            }
+        );
+
+        assert!(quotas
+            .charge_at(&luca, Quota::ChatTurns, at(2, 7, 0))
+            .is_ok());
+    }
+
+    #[test]
+    fn browsing_is_charged_per_minute() {
+        let quotas = Arc::new(UsageQuotas::new());
+        let luca = quotas.for_member(&family(), "Luca", UserRole::Child);
+
+        // Two pages in the first minute, one in the second
+        assert!(quotas
+            .charge_at(&luca, Quota::BrowseMinutes, at(1, 15, 0))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::BrowseMinutes, at(1, 15, 0))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::BrowseMinutes, at(1, 15, 1))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::BrowseMinutes, at(1, 15, 1))
+            .is_ok());
+        assert!(quotas
+            .charge_at(&luca, Quota::BrowseMinutes, at(1, 15, 2))
+            .is_err());
+    }
+
+    #[test]
+    fn member_limits_replace_role_limits() {
+        let family = family();
+        assert_eq!(
+            limits_for(&family, "mia", UserRole::Child).chat_turns,
+            Some(5)
+        );
+        assert!(limits_for(&family, "mia", UserRole::Child)
+            .browse_minutes
+            .is_none());
+        assert_eq!(
+            limits_for(&family, "Luca", UserRole::Child).chat_turns,
+            Some(2)
+        );
+        assert_eq!(
+            limits_for(&family, "Anna", UserRole::Adult),
+            UsageLimits::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn tools_are_charged_to_the_member_of_the_turn() {
+        let quotas = Arc::new(UsageQuotas::new());
+        let luca = quotas.for_member(&family(), "Luca", UserRole::Child);
+
+        assert!(charge_tool("web_search").is_ok());
+        with_member(Some(luca), async {
+            assert!(charge_tool("web_search").is_ok());
+            assert!(charge_tool("shell").is_ok());
+            let exceeded = charge_tool("web_search").unwrap_err();
+            assert_eq!(exceeded.quota, Quota::Tool("web_search".into()));
+        })
+        .await;
+        assert!(charge_tool("web_search").is_ok());
+    }
+
+    #[test]
+    fn quotas_name_tools_in_json() {
+        let quota: Quota =
+            serde_json::from_value(serde_json::json!({ "quota": "tool", "tool": "web_search" }))
+                .unwrap();
+        assert_eq!(quota, Quota::Tool("web_search".into()));
+        let quota: Quota =
+            serde_json::from_value(serde_json::json!({ "quota": "chat_turns" })).unwrap();
+        assert_eq!(quota, Quota::ChatTurns);
+    }
+}
@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Role-to-route policy matrix for the gateway API.
+//!
+//! Each rule maps a path pattern and optional methods to the least
+//! [`UserRole`] a request needs. Rules from `[[gateway.route_policy]]` are
+//! checked first, then the built-in matrix; the first match decides.
+//! API routes no rule matches require Root, so a new endpoint stays closed
+//! until the matrix names it. The gateway applies the matrix as middleware
+//! in front of every API route.
+
+use crate::config::RoutePolicyRuleConfig;
+use crate::identity::UserRole;
+use serde::Serialize;
+
+/// Where a rule comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSource {
+    Config,
+    Builtin,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteRule {
+    /// Path pattern; `*` matches one segment, `**` any number of them
+    pub path: String,
+    /// Upper-case HTTP methods; empty means all
+    pub methods: Vec<String>,
+    pub min_role: UserRole,
+    pub source: RuleSource,
+}
+
+impl RouteRule {
+    fn new(path: &str, methods: &[&str], min_role: UserRole, source: RuleSource) -> Self {
+        Self {
+            path: path.to_string(),
+            methods: methods.iter().map(|m| m.to_uppercase()).collect(),
+            min_role,
+            source,
+        }
+    }
+
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        (self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method)))
+            && path_matches(&self.path, path)
+    }
+}
+
+/// The built-in matrix: the roles the API required inline before the matrix
+/// existed, and `Child` for the routes every family member uses.
+const BUILTIN: &[(&str, &[&str], UserRole)] = &[
+    ("/api/agent/cancel/*", &[], UserRole::Child),
+    ("/api/agent/queue", &[], UserRole::Child),
+    ("/api/agent/runs", &[], UserRole::Root),
+    ("/api/agent/runs/*", &[], UserRole::Root),
+    ("/api/auth/passkey/**", &[], UserRole::Root),
+    ("/api/auth/providers", &[], UserRole::Child),
+    ("/api/auth/totp", &[], UserRole::Root),
+    ("/api/auth/totp/**", &[], UserRole::Root),
+    ("/api/automations", &[], UserRole::Root),
+    ("/api/automations/*", &["DELETE"], UserRole::Root),
+    ("/api/automations/*/toggle", &[], UserRole::Root),
+    ("/api/automations/events/*", &[], UserRole::Adult),
+    ("/api/browse/**", &[], UserRole::Child),
+    ("/api/capabilities", &[], UserRole::Child),
+    ("/api/capture", &[], UserRole::Child),
+    ("/api/config/adblock", &["GET"], UserRole::Child),
+    ("/api/config/adblock/toggle", &[], UserRole::Adult),
+    ("/api/config/model", &["GET"], UserRole::Adult),
+    ("/api/config/model", &[], UserRole::Root),
+    ("/api/config/models", &["GET"], UserRole::Adult),
+    ("/api/config/models", &["POST"], UserRole::Root),
+    ("/api/config/voice_echo", &[], UserRole::Adult),
+    ("/api/devices", &[], UserRole::Root),
+    ("/api/devices/*", &["PATCH", "DELETE"], UserRole::Root),
+    ("/api/dns/rules", &[], UserRole::Child),
+    ("/api/expenses/**", &[], UserRole::Adult),
+    ("/api/family/**", &[], UserRole::Root),
+    ("/api/git/forge", &[], UserRole::Root),
+    ("/api/identity", &["GET"], UserRole::Child),
+    ("/api/identity/*/*", &["DELETE"], UserRole::Root),
+    ("/api/identity/passkeys/**", &[], UserRole::Root),
+    ("/api/integrations/**", &[], UserRole::Root),
+    ("/api/location/**", &[], UserRole::Child),
+    ("/api/mcp/**", &[], UserRole::Root),
+    ("/api/memory/**", &[], UserRole::Root),
+    ("/api/ollama", &[], UserRole::Root),
+    ("/api/ollama/**", &[], UserRole::Root),
+    ("/api/onboarding", &["GET"], UserRole::Child),
+    ("/api/onboarding/complete", &[], UserRole::Root),
+    ("/api/onboarding/preview", &[], UserRole::Root),
+    ("/api/pim/calendar.ics", &[], UserRole::Child),
+    ("/api/pim/contacts.vcf", &[], UserRole::Child),
+    ("/api/pim/email", &[], UserRole::Root),
+    ("/api/pim/import-ics", &[], UserRole::Adult),
+    ("/api/pim/import-vcf", &[], UserRole::Adult),
+    ("/api/pim/tasks", &[], UserRole::Child),
+    ("/api/quotas", &[], UserRole::Adult),
+    ("/api/quotas/*/extend", &[], UserRole::Adult),
+    ("/api/quotas/me", &[], UserRole::Child),
+    ("/api/security/audit/verify", &[], UserRole::Root),
+    ("/api/security/confirm", &[], UserRole::Child),
+    ("/api/security/confirm/pending", &[], UserRole::Child),
+    ("/api/security/overview", &[], UserRole::Adult),
+    ("/api/security/policy/**", &[], UserRole::Root),
+    ("/api/security/sigil", &[], UserRole::Root),
+    ("/api/share/**", &[], UserRole::Adult),
+    ("/api/skills", &["GET"], UserRole::Child),
+    ("/api/skills", &["POST"], UserRole::Root),
+    ("/api/skills/*", &["DELETE"], UserRole::Root),
+    ("/api/soul/diary/**", &[], UserRole::Senior),
+    ("/api/structured", &[], UserRole::Adult),
+    ("/api/system/cron", &["GET"], UserRole::Adult),
+    ("/api/system/cron/**", &[], UserRole::Root),
+    ("/api/system/panic", &[], UserRole::Child),
+    ("/api/system/status", &[], UserRole::Child),
+    ("/api/system/tools/stats", &[], UserRole::Adult),
+    ("/api/system/widgets", &[], UserRole::Child),
+    ("/api/vault", &["GET"], UserRole::Root),
+    ("/api/vault/*", &["PUT", "DELETE"], UserRole::Root),
+    ("/api/vault/*/reveal", &[], UserRole::Root),
+    ("/api/vault/*/rotate", &[], UserRole::Root),
+    ("/api/vault/autofill-log", &[], UserRole::Child),
+    ("/api/vault/match", &[], UserRole::Child),
+    ("/api/voice/**", &[], UserRole::Root),
+    ("/api/vpn/**", &[], UserRole::Root),
+];
+
+/// API routes that authenticate with something other than a bearer token:
+/// an OIDC login state, a PIN, a refresh token or a guest share token.
+const PUBLIC: &[&str] = &[
+    "/api/auth/callback/*",
+    "/api/auth/login/*",
+    "/api/auth/member",
+    "/api/devices/refresh",
+    "/api/guest/**",
+];
+
+/// The effective route policy matrix.
+#[derive(Debug, Clone)]
+pub struct RoutePolicy {
+    rules: Vec<RouteRule>,
+}
+
+impl RoutePolicy {
+    /// Config rules followed by the built-in matrix. A config rule naming
+    /// an unknown role requires Root.
+    pub fn new(config: &[RoutePolicyRuleConfig]) -> Self {
+        let configured = config.iter().map(|rule| {
+            let min_role = parse_role(&rule.min_role).unwrap_or_else(|| {
+                tracing::warn!(
+                    "Route policy for {}: unknown role '{}', requiring root",
+                    rule.path,
+                    rule.min_role
+                );
+                UserRole::Root
+            });
+            let methods: Vec<&str> = rule.methods.iter().map(String::as_str).collect();
+            RouteRule::new(&rule.path, &methods, min_role, RuleSource::Config)
+        });
+        let builtin = BUILTIN
+            .iter()
+            .map(|(path, methods, role)| RouteRule::new(path, methods, *role, RuleSource::Builtin));
+        Self {
+            rules: configured.chain(builtin).collect(),
+        }
+    }
+
+    /// The rule deciding a request, if any.
+    pub fn rule_for(&self, method: &str, path: &str) -> Option<&RouteRule> {
+        self.rules.iter().find(|rule| rule.matches(method, path))
+    }
+
+    /// Least role a request needs: the deciding rule's, or Root for an API
+    /// route no rule covers. `None` for public API routes and for paths
+    /// outside `/api`, whose handlers authenticate on their own.
+    pub fn required_role(&self, method: &str, path: &str) -> Option<UserRole> {
+        if !path_matches("/api/**", path) || PUBLIC.iter().any(|p| path_matches(p, path)) {
+            return None;
+        }
+        Some(
+            self.rule_for(method, path)
+                .map_or(UserRole::Root, |rule| rule.min_role),
+        )
+    }
+
+    /// All rules in the order they are checked.
+    pub fn rules(&self) -> &[RouteRule] {
+        &self.rules
+    }
+}
+
+impl Default for RoutePolicy {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+fn parse_role(role: &str) -> Option<UserRole> {
+    match role.trim().to_lowercase().as_str() {
+        "root" => Some(UserRole::Root),
+        "adult" => Some(UserRole::Adult),
+        "senior" => Some(UserRole::Senior),
+        "child" => Some(UserRole::Child),
+        _ => None,
+    }
+}
+
+/// Match `path` against `pattern` segment by segment.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| matches(rest, &path[skip..])),
+            Some((segment, rest)) => path.split_first().is_some_and(|(head, tail)| {
+                (*segment == "*" || segment == head) && matches(rest, tail)
+            }),
+        }
+    }
+    fn segments(s: &str) -> Vec<&str> {
+        s.split('/').filter(|s| !s.is_empty()).collect()
+    }
+    matches(&segments(pattern), &segments(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_match_segments() {
+        assert!(path_matches("/api/vault/*/reveal", "/api/vault/abc/reveal"));
+        assert!(!path_matches("/api/vault/*/reveal", "/api/vault/reveal"));
+        assert!(path_matches("/api/mcp/**", "/api/mcp"));
+        assert!(path_matches("/api/mcp/**", "/api/mcp/prompts/srv/p/insert"));
+        assert!(!path_matches("/api/mcp/**", "/api/mcpx"));
+        assert!(path_matches("/api/share/", "/api/share"));
+    }
+
+    #[test]
+    fn builtin_matrix_keeps_existing_requirements() {
+        let policy = RoutePolicy::default();
+        let role = |method, path| policy.rule_for(method, path).map(|r| r.min_role);
+
+        assert_eq!(role("POST", "/api/vault/x/reveal"), Some(UserRole::Root));
+        assert_eq!(role("GET", "/api/vault/match"), Some(UserRole::Child));
+        assert_eq!(role("POST", "/api/devices/refresh"), None);
+        assert_eq!(role("DELETE", "/api/devices/abc"), Some(UserRole::Root));
+        assert_eq!(role("GET", "/api/system/cron"), Some(UserRole::Adult));
+        assert_eq!(role("POST", "/api/system/cron"), Some(UserRole::Root));
+        assert_eq!(role("GET", "/api/skills"), Some(UserRole::Child));
+        assert_eq!(role("GET", "/api/soul/diary"), Some(UserRole::Senior));
+        assert_eq!(
+            role("DELETE", "/api/soul/diary/abc"),
+            Some(UserRole::Senior)
+        );
+        assert_eq!(
+            role("POST", "/api/automations/events/x"),
+            Some(UserRole::Adult)
+        );
+        assert_eq!(role("GET", "/api/identity/passkeys"), Some(UserRole::Root));
+        assert_eq!(
+            role("DELETE", "/api/identity/eIDAS/DE-1"),
+            Some(UserRole::Root)
+        );
+        assert_eq!(role("POST", "/api/identity/eidas/verify"), None);
+        assert_eq!(
+            role("POST", "/api/auth/passkey/step-up/finish"),
+            Some(UserRole::Root)
+        );
+    }
+
+    #[test]
+    fn unmatched_api_routes_require_root() {
+        let policy = RoutePolicy::default();
+        let required = |method, path| policy.required_role(method, path);
+
+        assert_eq!(
+            required("POST", "/api/config/pairing"),
+            Some(UserRole::Root)
+        );
+        assert_eq!(
+            required("POST", "/api/not-yet-in-the-matrix"),
+            Some(UserRole::Root)
+        );
+        assert_eq!(required("GET", "/api/system/status"), Some(UserRole::Child));
+        assert_eq!(required("POST", "/api/devices/refresh"), None);
+        assert_eq!(required("POST", "/api/guest/ask"), None);
+        assert_eq!(required("GET", "/ws/chat"), None);
+    }
+
+    #[test]
+    fn config_rules_take_precedence() {
+        let policy = RoutePolicy::new(&[
+            RoutePolicyRuleConfig {
+                path: "/api/expenses/**".into(),
+                methods: vec!["get".into()],
+                min_role: "Senior".into(),
+            },
+            RoutePolicyRuleConfig {
+                path: "/api/capture".into(),
+                methods: Vec::new(),
+                min_role: "owner".into(),
+            },
+        ]);
+        let rule = policy.rule_for("GET", "/api/expenses/summary").unwrap();
+        assert_eq!(rule.min_role, UserRole::Senior);
+        assert_eq!(rule.source, RuleSource::Config);
+        let rule = policy.rule_for("POST", "/api/expenses").unwrap();
+        assert_eq!(rule.min_role, UserRole::Adult);
+        assert_eq!(rule.source, RuleSource::Builtin);
+        assert_eq!(
+            policy.rule_for("POST", "/api/capture").unwrap().min_role,
+            UserRole::Root
+        );
+    }
+}
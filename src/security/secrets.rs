@@ -19,7 +19,13 @@
 //   - Ciphertext tampering (authenticated encryption)
 //
 // For sovereign users who prefer plaintext, `secrets.encrypt = false` disables this.
+//
+// With `secrets.key_backend = "keyring"` or `"tpm"`, a newly created key is
+// kept in the OS keyring or sealed to the TPM and the key file only holds a
+// reference to it (see `keystore`).
 
+use crate::config::KeyBackend;
+use crate::security::keystore;
 use anyhow::{Context, Result};
 use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
 use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
@@ -39,6 +45,8 @@ pub struct SecretStore {
     key_path: PathBuf,
     /// Whether encryption is enabled
     enabled: bool,
+    /// Where a newly created key is kept
+    backend: KeyBackend,
 }
 
 impl SecretStore {
@@ -47,9 +55,17 @@ impl SecretStore {
         Self {
             key_path: mymolt_dir.join(".secret_key"),
             enabled,
+            backend: KeyBackend::default(),
         }
     }
 
+    /// Keep a newly created key in `backend`. An existing key is read from
+    /// wherever its key file points.
+    pub fn with_backend(mut self, backend: KeyBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
     /// Encrypt a plaintext secret. Returns hex-encoded ciphertext prefixed with `enc2:`.
     /// Format: `enc2:<hex(nonce ‖ ciphertext ‖ tag)>` (12 + N + 16 bytes).
     /// If encryption is disabled, returns the plaintext as-is.
@@ -117,15 +133,17 @@ impl SecretStore {
     /// Load the encryption key from disk, or create one if it doesn't exist.
     fn load_or_create_key(&self) -> Result<Vec<u8>> {
         if self.key_path.exists() {
-            let hex_key =
+            let contents =
                 fs::read_to_string(&self.key_path).context("Failed to read secret key file")?;
+            let hex_key = keystore::open(&self.key_path, &contents)?;
             hex_decode(hex_key.trim()).context("Secret key file is corrupt")
         } else {
             let key = generate_random_key();
             if let Some(parent) = self.key_path.parent() {
                 fs::create_dir_all(parent)?;
             }
-            fs::write(&self.key_path, hex_encode(&key))
+            let stored = keystore::seal(&self.key_path, self.backend, &hex_encode(&key))?;
+            fs::write(&self.key_path, stored.as_bytes())
                 .context("Failed to write secret key file")?;
 
             // Set restrictive permissions
@@ -262,4 +280,15 @@ mod tests {
         let result = store.decrypt(&tampered);
         assert!(result.is_err(), "Tampered ciphertext must be rejected");
     }
+
+    #[test]
+    fn key_file_reference_without_backend_cannot_decrypt() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), true);
+        let encrypted = store.encrypt("sk-secret").unwrap();
+
+        // A copied workspace whose key lives in a TPM only has the reference.
+        fs::write(tmp.path().join(".secret_key"), "tpm:missing-object").unwrap();
+        assert!(store.decrypt(&encrypted).is_err());
+    }
 }
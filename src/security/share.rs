@@ -0,0 +1,302 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Guest share links — expiring, scope-limited tokens for visitors.
+//!
+//! A share token lets someone without a paired device ask the agent
+//! questions within a narrow scope (e.g. one knowledge collection) until
+//! it expires, runs out of uses, or is revoked. Tokens are never stored
+//! in plaintext: only their SHA-256 hash is persisted to
+//! `<workspace>/.mymolt/share_tokens.json`.
+
+use super::pairing::constant_time_eq;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Longest lifetime a share link may be created with (7 days).
+pub const MAX_SHARE_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// Default lifetime when none is requested (24 hours).
+pub const DEFAULT_SHARE_TTL_SECS: u64 = 24 * 3600;
+
+/// What a guest holding the token may do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShareScope {
+    /// Ask questions. With a `collection`, answers may draw on memories in
+    /// that category only; without one, the agent answers from general
+    /// knowledge and sees no memories at all. Never any tools.
+    Ask { collection: Option<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareGrant {
+    pub id: String,
+    pub label: String,
+    pub scope: ShareScope,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub max_uses: Option<u32>,
+    #[serde(default)]
+    pub uses: u32,
+    #[serde(default)]
+    pub revoked: bool,
+    /// Family memory scope of the member who issued the link; `None` for
+    /// the owner. Guest answers only draw on memories visible there.
+    #[serde(default)]
+    pub memory_scope: Option<String>,
+    token_hash: String,
+}
+
+impl ShareGrant {
+    /// Whether the grant can still be used at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked && now < self.expires_at && self.max_uses.is_none_or(|max| self.uses < max)
+    }
+}
+
+/// Persistent registry of issued share tokens.
+pub struct ShareTokenStore {
+    path: PathBuf,
+    grants: Mutex<Vec<ShareGrant>>,
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn generate_token() -> String {
+    // Two v4 UUIDs → 244 bits of CSPRNG output.
+    format!(
+        "ms_{}{}",
+        uuid::Uuid::new_v4().as_simple(),
+        uuid::Uuid::new_v4().as_simple()
+    )
+}
+
+impl ShareTokenStore {
+    /// Load the store from the workspace, dropping grants that expired over a day ago.
+    pub fn new(workspace_dir: &Path) -> Self {
+        let path = workspace_dir.join(".mymolt").join("share_tokens.json");
+        let mut grants: Vec<ShareGrant> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        let cutoff = Utc::now() - Duration::days(1);
+        grants.retain(|g| g.expires_at > cutoff);
+        Self {
+            path,
+            grants: Mutex::new(grants),
+        }
+    }
+
+    fn persist(&self, grants: &[ShareGrant]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(grants)?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Issue a new token for the issuer's `memory_scope`. Returns the
+    /// plaintext token (shown once) and its grant.
+    pub fn create(
+        &self,
+        label: &str,
+        scope: ShareScope,
+        memory_scope: Option<String>,
+        ttl_secs: u64,
+        max_uses: Option<u32>,
+    ) -> Result<(String, ShareGrant)> {
+        anyhow::ensure!(ttl_secs > 0, "Share link lifetime must be positive");
+        anyhow::ensure!(
+            ttl_secs <= MAX_SHARE_TTL_SECS,
+            "Share links may live at most {} hours",
+            MAX_SHARE_TTL_SECS / 3600
+        );
+        anyhow::ensure!(max_uses != Some(0), "max_uses must be at least 1");
+
+        let token = generate_token();
+        let now = Utc::now();
+        let grant = ShareGrant {
+            id: uuid::Uuid::new_v4().to_string(),
+            label: label.to_string(),
+            scope,
+            created_at: now,
+            expires_at: now + Duration::seconds(i64::try_from(ttl_secs)?),
+            max_uses,
+            uses: 0,
+            revoked: false,
+            memory_scope,
+            token_hash: hash_token(&token),
+        };
+
+        let mut grants = self
+            .grants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        grants.push(grant.clone());
+        self.persist(&grants)?;
+        Ok((token, grant))
+    }
+
+    /// Validate a presented token and count one use. Returns the grant if active.
+    pub fn redeem(&self, token: &str) -> Option<ShareGrant> {
+        let hashed = hash_token(token);
+        let now = Utc::now();
+        let mut grants = self
+            .grants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let grant = grants
+            .iter_mut()
+            .find(|g| constant_time_eq(&g.token_hash, &hashed))?;
+        if !grant.is_active(now) {
+            return None;
+        }
+        grant.uses += 1;
+        let redeemed = grant.clone();
+        if let Err(e) = self.persist(&grants) {
+            tracing::warn!("Failed to persist share token usage: {e}");
+        }
+        Some(redeemed)
+    }
+
+    /// Look up a token without counting a use.
+    pub fn peek(&self, token: &str) -> Option<ShareGrant> {
+        let hashed = hash_token(token);
+        let grants = self
+            .grants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        grants
+            .iter()
+            .find(|g| constant_time_eq(&g.token_hash, &hashed))
+            .filter(|g| g.is_active(Utc::now()))
+            .cloned()
+    }
+
+    /// All grants, newest first.
+    pub fn list(&self) -> Vec<ShareGrant> {
+        let grants = self
+            .grants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut out = grants.clone();
+        out.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        out
+    }
+
+    /// Revoke a grant immediately. Returns `false` if the id is unknown.
+    pub fn revoke(&self, id: &str) -> Result<bool> {
+        let mut grants = self
+            .grants
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let Some(grant) = grants.iter_mut().find(|g| g.id == id) else {
+            return Ok(false);
+        };
+        grant.revoked = true;
+        self.persist(&grants)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ask(collection: Option<&str>) -> ShareScope {
+        ShareScope::Ask {
+            collection: collection.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn token_roundtrip_and_hash_only_persistence() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ShareTokenStore::new(tmp.path());
+        let (token, grant) = store
+            .create(
+                "visitor",
+                ask(Some("recipes")),
+                Some("user:luca".into()),
+                3600,
+                None,
+            )
+            .unwrap();
+
+        assert!(token.starts_with("ms_"));
+        let redeemed = store.redeem(&token).unwrap();
+        assert_eq!(redeemed.id, grant.id);
+        assert_eq!(redeemed.uses, 1);
+        assert!(store.redeem("ms_wrong").is_none());
+
+        let raw = std::fs::read_to_string(tmp.path().join(".mymolt/share_tokens.json")).unwrap();
+        assert!(!raw.contains(&token));
+
+        // Survives a restart
+        let reloaded = ShareTokenStore::new(tmp.path());
+        let grant = reloaded.peek(&token).unwrap();
+        assert_eq!(grant.uses, 1);
+        assert_eq!(grant.memory_scope.as_deref(), Some("user:luca"));
+    }
+
+    #[test]
+    fn max_uses_exhausts_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ShareTokenStore::new(tmp.path());
+        let (token, _) = store.create("once", ask(None), None, 60, Some(1)).unwrap();
+        assert!(store.redeem(&token).is_some());
+        assert!(store.redeem(&token).is_none());
+    }
+
+    #[test]
+    fn revoked_token_is_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ShareTokenStore::new(tmp.path());
+        let (token, grant) = store.create("coworker", ask(None), None, 60, None).unwrap();
+        assert!(store.revoke(&grant.id).unwrap());
+        assert!(!store.revoke("missing").unwrap());
+        assert!(store.peek(&token).is_none());
+        assert!(store.redeem(&token).is_none());
+    }
+
+    #[test]
+    fn create_enforces_limits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ShareTokenStore::new(tmp.path());
+        assert!(store.create("x", ask(None), None, 0, None).is_err());
+        assert!(store
+            .create("x", ask(None), None, MAX_SHARE_TTL_SECS + 1, None)
+            .is_err());
+        assert!(store.create("x", ask(None), None, 60, Some(0)).is_err());
+    }
+
+    #[test]
+    fn expired_grant_is_inactive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ShareTokenStore::new(tmp.path());
+        let (_, grant) = store.create("x", ask(None), None, 60, None).unwrap();
+        assert!(grant.is_active(Utc::now()));
+        assert!(!grant.is_active(grant.expires_at + Duration::seconds(1)));
+    }
+
+    #[test]
+    fn scope_serializes_with_kind_tag() {
+        let json = serde_json::to_string(&ask(Some("docs"))).unwrap();
+        assert_eq!(json, r#"{"kind":"ask","collection":"docs"}"#);
+    }
+}
@@ -0,0 +1,376 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Per-tool permission rules (`[security.tools.<tool>]`).
+//!
+//! `SecurityWrapper` checks every call against the rules of its tool before
+//! running it. Arguments are found by key at any depth: `path`, `paths`,
+//! `files` and keys ending in `_path` hold paths, `url` and keys ending in
+//! `_url` hold URLs, `command` holds a shell command. A refused call gets a
+//! [`PolicyDenial`] saying which rule refused which value, so the model can
+//! pick an allowed alternative instead of retrying blindly.
+
+use super::policy::{skip_env_assignments, SecurityPolicy};
+use crate::config::{ToolRuleConfig, TrustConfig};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// Why a tool call was refused by its rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PolicyDenial {
+    pub tool: String,
+    /// Rule that refused the call, e.g. `allowed_paths`
+    pub rule: &'static str,
+    /// Argument value that was refused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub reason: String,
+    /// What the rule would accept instead
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+}
+
+impl PolicyDenial {
+    /// Error text for the model: the denial as JSON, so it can be read field
+    /// by field.
+    pub fn to_tool_error(&self) -> String {
+        format!(
+            "Policy denied: {}",
+            serde_json::to_string(self).unwrap_or_else(|_| self.to_string())
+        )
+    }
+}
+
+impl fmt::Display for PolicyDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Denied by security.tools.{}.{}: {}",
+            self.tool, self.rule, self.reason
+        )
+    }
+}
+
+impl SecurityPolicy {
+    /// Check a call of `tool` with `args` against `[security.tools.<tool>]`.
+    pub fn check_tool_rules(
+        &self,
+        tool: &str,
+        args: &serde_json::Value,
+    ) -> Result<(), PolicyDenial> {
+        let Some(rules) = self.tool_rules.get(tool) else {
+            return Ok(());
+        };
+        let deny = |rule, value: Option<&str>, reason: String, allowed: &[String]| PolicyDenial {
+            tool: tool.to_string(),
+            rule,
+            value: value.map(str::to_string),
+            reason,
+            allowed: allowed.to_vec(),
+        };
+
+        if let Some(min_trust) = &rules.min_trust {
+            self.check_trust(TrustConfig::parse_level(min_trust))
+                .map_err(|reason| deny("min_trust", None, reason, &[]))?;
+        }
+
+        let mut values = Arguments::default();
+        values.collect(None, args);
+
+        for command in &values.commands {
+            if let Some(base) =
+                command_bases(command).find(|base| rules.denied_commands.iter().any(|d| d == base))
+            {
+                return Err(deny(
+                    "denied_commands",
+                    Some(command),
+                    format!("'{base}' may not be run by this tool"),
+                    &[],
+                ));
+            }
+        }
+
+        for path in &values.paths {
+            self.check_path_rules(rules, path)
+                .map_err(|(rule, reason, allowed)| deny(rule, Some(path), reason, allowed))?;
+        }
+
+        if !rules.domains.is_empty() {
+            let allowed = self.allowed_domains(rules);
+            for url in &values.urls {
+                let host = reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(|h| h.trim_end_matches('.').to_lowercase()));
+                let Some(host) = host else {
+                    return Err(deny(
+                        "domains",
+                        Some(url),
+                        "URL has no host".into(),
+                        &allowed,
+                    ));
+                };
+                if !allowed.iter().any(|d| host_matches(&host, d)) {
+                    return Err(deny(
+                        "domains",
+                        Some(url),
+                        format!("'{host}' is not allowed at {:?} trust", self.trust_level()),
+                        &allowed,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_path_rules<'a>(
+        &self,
+        rules: &'a ToolRuleConfig,
+        path: &str,
+    ) -> Result<(), (&'static str, String, &'a [String])> {
+        let resolved = self.resolve_rule_path(path);
+        if let Some(denied) = rules
+            .denied_paths
+            .iter()
+            .find(|d| resolved.starts_with(self.resolve_rule_path(d)))
+        {
+            return Err(("denied_paths", format!("path is under '{denied}'"), &[]));
+        }
+        if !rules.allowed_paths.is_empty()
+            && !rules
+                .allowed_paths
+                .iter()
+                .any(|a| resolved.starts_with(self.resolve_rule_path(a)))
+        {
+            return Err((
+                "allowed_paths",
+                "path is outside the allowed paths".into(),
+                &rules.allowed_paths,
+            ));
+        }
+        Ok(())
+    }
+
+    /// `path` made absolute against the workspace, with `..` and symlinks of
+    /// its existing part resolved.
+    fn resolve_rule_path(&self, path: &str) -> PathBuf {
+        let expanded = PathBuf::from(shellexpand::tilde(path).as_ref());
+        let joined = if expanded.is_absolute() {
+            expanded
+        } else {
+            self.workspace_dir.join(expanded)
+        };
+
+        let mut normalized = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                Component::CurDir => {}
+                other => normalized.push(other),
+            }
+        }
+
+        // Canonicalize the longest existing prefix; the rest does not exist
+        // yet (a file about to be written) and cannot be a symlink.
+        let mut existing = normalized.as_path();
+        let mut rest = Vec::new();
+        while let Some(parent) = existing.parent() {
+            if existing.exists() {
+                break;
+            }
+            rest.push(existing.file_name().unwrap_or_default().to_os_string());
+            existing = parent;
+        }
+        let mut resolved = existing
+            .canonicalize()
+            .unwrap_or_else(|_| existing.to_path_buf());
+        resolved.extend(rest.iter().rev());
+        resolved
+    }
+
+    /// Hosts allowed at the current trust level and every level below it.
+    fn allowed_domains(&self, rules: &ToolRuleConfig) -> Vec<String> {
+        rules
+            .domains
+            .iter()
+            .filter(|(level, _)| TrustConfig::parse_level(level) <= self.trust_level())
+            .flat_map(|(_, hosts)| hosts.iter().map(|h| h.trim().to_lowercase()))
+            .collect()
+    }
+}
+
+/// String arguments sorted by what they hold.
+#[derive(Default)]
+struct Arguments {
+    paths: Vec<String>,
+    urls: Vec<String>,
+    commands: Vec<String>,
+}
+
+impl Arguments {
+    fn collect(&mut self, key: Option<&str>, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (k, v) in map {
+                    self.collect(Some(k), v);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.collect(key, item);
+                }
+            }
+            serde_json::Value::String(s) => match key {
+                Some("path" | "paths" | "files") => self.paths.push(s.clone()),
+                Some(k) if k.ends_with("_path") => self.paths.push(s.clone()),
+                Some("url") => self.urls.push(s.clone()),
+                Some(k) if k.ends_with("_url") => self.urls.push(s.clone()),
+                Some("command") => self.commands.push(s.clone()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Executable names of every segment of shell `command`.
+fn command_bases(command: &str) -> impl Iterator<Item = &str> {
+    command
+        .split(['\n', ';', '|', '&'])
+        .filter_map(|segment| {
+            skip_env_assignments(segment.trim())
+                .split_whitespace()
+                .next()
+        })
+        .map(|word| word.rsplit('/').next().unwrap_or(word))
+}
+
+fn host_matches(host: &str, allowed: &str) -> bool {
+    allowed == "*"
+        || host == allowed
+        || host
+            .strip_suffix(allowed)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::soul::TrustLevel;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn policy(workspace: &Path, tool: &str, rules: ToolRuleConfig) -> SecurityPolicy {
+        SecurityPolicy {
+            workspace_dir: workspace.to_path_buf(),
+            tool_rules: BTreeMap::from([(tool.to_string(), rules)]),
+            ..SecurityPolicy::default()
+        }
+    }
+
+    #[test]
+    fn paths_are_confined_to_allowed_dirs() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("projects/app")).unwrap();
+        let p = policy(
+            tmp.path(),
+            "file_write",
+            ToolRuleConfig {
+                allowed_paths: vec!["projects".into()],
+                denied_paths: vec!["projects/app/.git".into()],
+                ..ToolRuleConfig::default()
+            },
+        );
+
+        assert!(p
+            .check_tool_rules("file_write", &json!({"path": "projects/app/new.rs"}))
+            .is_ok());
+        let denial = p
+            .check_tool_rules("file_write", &json!({"path": "projects/../notes.md"}))
+            .unwrap_err();
+        assert_eq!(denial.rule, "allowed_paths");
+        assert_eq!(denial.allowed, vec!["projects"]);
+        let denial = p
+            .check_tool_rules(
+                "file_write",
+                &json!({"edits": [{"path": "projects/app/.git/config"}]}),
+            )
+            .unwrap_err();
+        assert_eq!(denial.rule, "denied_paths");
+        // Other tools are not affected.
+        assert!(p
+            .check_tool_rules("file_read", &json!({"path": "notes.md"}))
+            .is_ok());
+    }
+
+    #[test]
+    fn denied_commands_are_found_in_any_segment() {
+        let p = policy(
+            Path::new("/tmp"),
+            "shell",
+            ToolRuleConfig {
+                denied_commands: vec!["curl".into()],
+                ..ToolRuleConfig::default()
+            },
+        );
+        assert!(p
+            .check_tool_rules("shell", &json!({"command": "ls -la"}))
+            .is_ok());
+        let denial = p
+            .check_tool_rules(
+                "shell",
+                &json!({"command": "ls && FOO=1 /usr/bin/curl http://x"}),
+            )
+            .unwrap_err();
+        assert_eq!(denial.rule, "denied_commands");
+        assert!(denial.to_tool_error().starts_with("Policy denied: {"));
+    }
+
+    #[test]
+    fn domains_widen_with_trust_level() {
+        let p = policy(
+            Path::new("/tmp"),
+            "http_request",
+            ToolRuleConfig {
+                domains: BTreeMap::from([
+                    ("low".into(), vec!["wikipedia.org".into()]),
+                    ("high".into(), vec!["*".into()]),
+                ]),
+                ..ToolRuleConfig::default()
+            },
+        );
+        let wiki = json!({"url": "https://de.wikipedia.org/wiki/Rust"});
+        let other = json!({"url": "https://example.com/"});
+        assert!(p.check_tool_rules("http_request", &wiki).is_ok());
+        let denial = p.check_tool_rules("http_request", &other).unwrap_err();
+        assert_eq!(denial.allowed, vec!["wikipedia.org"]);
+        assert!(p
+            .check_tool_rules("http_request", &json!({"url": "https://notwikipedia.org"}))
+            .is_err());
+
+        p.set_trust_level(TrustLevel::High);
+        assert!(p.check_tool_rules("http_request", &other).is_ok());
+    }
+
+    #[test]
+    fn min_trust_gates_the_whole_tool() {
+        let p = policy(
+            Path::new("/tmp"),
+            "git_operations",
+            ToolRuleConfig {
+                min_trust: Some("medium".into()),
+                ..ToolRuleConfig::default()
+            },
+        );
+        let denial = p
+            .check_tool_rules("git_operations", &json!({}))
+            .unwrap_err();
+        assert_eq!(denial.rule, "min_trust");
+        p.set_trust_level(TrustLevel::Medium);
+        assert!(p.check_tool_rules("git_operations", &json!({})).is_ok());
+    }
+}
@@ -0,0 +1,418 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! TOTP second factor (RFC 6238) for Root-level API actions.
+//!
+//! Enrollment is optional: Root starts it at `POST /api/auth/totp`, scans the
+//! QR code and confirms with a first code, which activates it and returns
+//! single-use recovery codes. From then on Root-only endpoints require a
+//! valid code in `X-TOTP-Code`. Codes are accepted one step either side of
+//! the current one to allow for clock drift, and each step only once.
+//!
+//! The shared secret and the recovery codes are encrypted with the
+//! [`SecretStore`] key in `<workspace>/.mymolt/totp.json`.
+
+use super::pairing::constant_time_eq;
+use super::SecretStore;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Seconds per code.
+const STEP_SECS: u64 = 30;
+/// Digits per code.
+const DIGITS: u32 = 6;
+/// Steps accepted either side of the current one.
+const DRIFT_STEPS: u64 = 1;
+/// Recovery codes issued at enrollment.
+const RECOVERY_CODES: usize = 10;
+/// Issuer shown in authenticator apps.
+const ISSUER: &str = "MyMolt";
+
+/// Persisted state; `secret` and `recovery_codes` are `SecretStore` ciphertext.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TotpState {
+    secret: Option<String>,
+    /// Whether the secret was confirmed with a code; unconfirmed secrets are
+    /// a pending enrollment and not enforced.
+    confirmed: bool,
+    /// JSON array of unused recovery codes
+    recovery_codes: Option<String>,
+    /// Last step a code was accepted for, to refuse replays
+    last_step: u64,
+}
+
+/// A started enrollment, to be shown to the user once.
+#[derive(Debug, Clone)]
+pub struct Enrollment {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI for the QR code
+    pub uri: String,
+}
+
+/// What a code was accepted as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verified {
+    Totp,
+    /// A recovery code, now used up; this many remain
+    Recovery(usize),
+}
+
+/// Root's TOTP enrollment.
+pub struct TotpStore {
+    path: PathBuf,
+    secrets: SecretStore,
+    state: Mutex<TotpState>,
+}
+
+impl TotpStore {
+    /// Load the enrollment. A missing file means nothing is enrolled; an
+    /// unreadable or corrupt one is an error, since treating it as "not
+    /// enrolled" would silently turn the second factor off.
+    pub fn new(workspace_dir: &Path, secrets: SecretStore) -> Result<Self> {
+        let path = workspace_dir.join(".mymolt").join("totp.json");
+        let state = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TotpState::default(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self {
+            path,
+            secrets,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TotpState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn persist(&self, state: &TotpState) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(state)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    /// Whether codes are required.
+    pub fn is_enrolled(&self) -> bool {
+        self.lock().confirmed
+    }
+
+    /// Unused recovery codes.
+    pub fn recovery_codes_left(&self) -> Result<usize> {
+        Ok(self.recovery_codes(&self.lock())?.len())
+    }
+
+    /// Start an enrollment with a fresh secret, replacing any pending one.
+    /// Fails while enrolled; disable the current enrollment first.
+    pub fn begin_enrollment(&self, account: &str) -> Result<Enrollment> {
+        let secret: [u8; 20] = rand::thread_rng().gen();
+        let secret = base32_encode(&secret);
+        let mut state = self.lock();
+        if state.confirmed {
+            anyhow::bail!("TOTP is already enrolled; disable it before enrolling again");
+        }
+        state.secret = Some(self.secrets.encrypt(&secret)?);
+        state.last_step = 0;
+        self.persist(&state)?;
+        Ok(Enrollment {
+            uri: format!(
+                "otpauth://totp/{ISSUER}:{account}?secret={secret}&issuer={ISSUER}\
+                 &algorithm=SHA1&digits={DIGITS}&period={STEP_SECS}"
+            ),
+            secret,
+        })
+    }
+
+    /// Activate a pending enrollment with its first code. Returns the
+    /// recovery codes, or `None` if the code is wrong or nothing is pending.
+    pub fn confirm_enrollment(&self, code: &str) -> Result<Option<Vec<String>>> {
+        self.confirm_enrollment_at(code, unix_now())
+    }
+
+    /// Check a code from the authenticator app or a recovery code. Returns
+    /// `None` for wrong or replayed codes, and when not enrolled.
+    pub fn verify(&self, code: &str) -> Result<Option<Verified>> {
+        self.verify_at(code, unix_now())
+    }
+
+    /// Remove the enrollment.
+    pub fn disable(&self) -> Result<()> {
+        let mut state = self.lock();
+        *state = TotpState::default();
+        self.persist(&state)
+    }
+
+    fn confirm_enrollment_at(&self, code: &str, now: u64) -> Result<Option<Vec<String>>> {
+        let mut state = self.lock();
+        if state.confirmed {
+            return Ok(None);
+        }
+        let Some(step) = self.match_step(&state, code, now)? else {
+            return Ok(None);
+        };
+        let codes: Vec<String> = (0..RECOVERY_CODES).map(|_| recovery_code()).collect();
+        state.recovery_codes = Some(self.secrets.encrypt(&serde_json::to_string(&codes)?)?);
+        state.confirmed = true;
+        state.last_step = step;
+        self.persist(&state)?;
+        Ok(Some(codes))
+    }
+
+    fn verify_at(&self, code: &str, now: u64) -> Result<Option<Verified>> {
+        let mut state = self.lock();
+        if !state.confirmed {
+            return Ok(None);
+        }
+        if let Some(step) = self.match_step(&state, code, now)? {
+            state.last_step = step;
+            self.persist(&state)?;
+            return Ok(Some(Verified::Totp));
+        }
+
+        let code = code.trim().to_lowercase();
+        let mut codes = self.recovery_codes(&state)?;
+        let Some(index) = codes.iter().position(|c| constant_time_eq(c, &code)) else {
+            return Ok(None);
+        };
+        codes.remove(index);
+        state.recovery_codes = Some(self.secrets.encrypt(&serde_json::to_string(&codes)?)?);
+        self.persist(&state)?;
+        Ok(Some(Verified::Recovery(codes.len())))
+    }
+
+    /// The step `code` is valid for, within the drift window and after the
+    /// last accepted step.
+    fn match_step(&self, state: &TotpState, code: &str, now: u64) -> Result<Option<u64>> {
+        let code = code.trim();
+        if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(None);
+        }
+        let Some(secret) = &state.secret else {
+            return Ok(None);
+        };
+        let secret =
+            base32_decode(&self.secrets.decrypt(secret)?).context("TOTP secret is corrupt")?;
+        let current = now / STEP_SECS;
+        let first = current.saturating_sub(DRIFT_STEPS).max(state.last_step + 1);
+        Ok((first..=current + DRIFT_STEPS).find(|&step| {
+            let expected = format!("{:0width$}", hotp(&secret, step), width = DIGITS as usize);
+            constant_time_eq(&expected, code)
+        }))
+    }
+
+    fn recovery_codes(&self, state: &TotpState) -> Result<Vec<String>> {
+        match &state.recovery_codes {
+            Some(encrypted) => Ok(serde_json::from_str(&self.secrets.decrypt(encrypted)?)?),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// HOTP value (RFC 4226) for `counter`, truncated to [`DIGITS`].
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let mut mac = <Hmac<Sha1> as Mac>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = usize::from(digest[19] & 0x0f);
+    let value = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    value % 10_u32.pow(DIGITS)
+}
+
+/// `xxxxx-xxxxx` from an alphabet without look-alike characters.
+fn recovery_code() -> String {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let mut rng = rand::thread_rng();
+    let mut pick = || char::from(ALPHABET[rng.gen_range(0..ALPHABET.len())]);
+    let head: String = (0..5).map(|_| pick()).collect();
+    let tail: String = (0..5).map(|_| pick()).collect();
+    format!("{head}-{tail}")
+}
+
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Unpadded RFC 4648 base32, as authenticator apps expect.
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0_u32, 0_u32);
+    for &byte in data {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(char::from(BASE32[((buffer >> bits) & 0x1f) as usize]));
+        }
+    }
+    if bits > 0 {
+        out.push(char::from(BASE32[((buffer << (5 - bits)) & 0x1f) as usize]));
+    }
+    out
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0_u32, 0_u32);
+    for c in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32.iter().position(|&b| b == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | u32::try_from(value).ok()?;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(u8::try_from((buffer >> bits) & 0xff).ok()?);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(dir: &Path) -> TotpStore {
+        TotpStore::new(dir, SecretStore::new(&dir.join(".mymolt"), true)).unwrap()
+    }
+
+    fn code_at(enrollment: &Enrollment, now: u64) -> String {
+        let secret = base32_decode(&enrollment.secret).unwrap();
+        format!("{:06}", hotp(&secret, now / STEP_SECS))
+    }
+
+    #[test]
+    fn hotp_matches_rfc_6238_vectors() {
+        let secret = b"12345678901234567890";
+        assert_eq!(hotp(secret, 59 / STEP_SECS), 287_082);
+        assert_eq!(hotp(secret, 1_111_111_109 / STEP_SECS), 81_804);
+        assert_eq!(hotp(secret, 1_234_567_890 / STEP_SECS), 5_924);
+    }
+
+    #[test]
+    fn base32_roundtrips() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("MZXW6YTBOI").unwrap(), b"foobar");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn enrollment_is_enforced_only_once_confirmed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let totp = store(tmp.path());
+        let now = 1_700_000_000;
+        let enrollment = totp.begin_enrollment("root").unwrap();
+        assert!(enrollment
+            .uri
+            .starts_with("otpauth://totp/MyMolt:root?secret="));
+        assert!(!totp.is_enrolled());
+        assert_eq!(
+            totp.verify_at(&code_at(&enrollment, now), now).unwrap(),
+            None
+        );
+
+        assert!(totp.confirm_enrollment_at("000000", now).unwrap().is_none());
+        let codes = totp
+            .confirm_enrollment_at(&code_at(&enrollment, now), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert!(totp.is_enrolled());
+        assert!(store(tmp.path()).is_enrolled());
+
+        let raw = std::fs::read_to_string(tmp.path().join(".mymolt/totp.json")).unwrap();
+        assert!(!raw.contains(&enrollment.secret));
+        assert!(!raw.contains(&codes[0]));
+    }
+
+    #[test]
+    fn codes_are_accepted_within_drift_and_only_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let totp = store(tmp.path());
+        let now = 1_700_000_000;
+        let enrollment = totp.begin_enrollment("root").unwrap();
+        totp.confirm_enrollment_at(&code_at(&enrollment, now), now)
+            .unwrap();
+
+        // Same step as the confirmation: replay
+        assert_eq!(
+            totp.verify_at(&code_at(&enrollment, now), now).unwrap(),
+            None
+        );
+
+        let later = now + 10 * STEP_SECS;
+        let behind = code_at(&enrollment, later - STEP_SECS);
+        assert_eq!(
+            totp.verify_at(&behind, later).unwrap(),
+            Some(Verified::Totp)
+        );
+        assert_eq!(totp.verify_at(&behind, later).unwrap(), None);
+
+        let ahead = code_at(&enrollment, later + STEP_SECS);
+        assert_eq!(totp.verify_at(&ahead, later).unwrap(), Some(Verified::Totp));
+
+        let stale = code_at(&enrollment, later - 5 * STEP_SECS);
+        let much_later = later + 5 * STEP_SECS;
+        assert_eq!(totp.verify_at(&stale, much_later).unwrap(), None);
+    }
+
+    #[test]
+    fn recovery_codes_work_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let totp = store(tmp.path());
+        let now = 1_700_000_000;
+        let enrollment = totp.begin_enrollment("root").unwrap();
+        let codes = totp
+            .confirm_enrollment_at(&code_at(&enrollment, now), now)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            totp.verify_at(&codes[3].to_uppercase(), now).unwrap(),
+            Some(Verified::Recovery(RECOVERY_CODES - 1))
+        );
+        assert_eq!(totp.verify_at(&codes[3], now).unwrap(), None);
+        assert_eq!(totp.recovery_codes_left().unwrap(), RECOVERY_CODES - 1);
+
+        totp.disable().unwrap();
+        assert!(!totp.is_enrolled());
+        assert_eq!(totp.verify_at(&codes[4], now).unwrap(), None);
+    }
+
+    #[test]
+    fn corrupt_enrollment_file_fails_to_load() {
+        let tmp = tempfile::tempdir().unwrap();
+        let totp = store(tmp.path());
+        totp.begin_enrollment("root").unwrap();
+
+        let path = tmp.path().join(".mymolt/totp.json");
+        std::fs::write(&path, "{ \"secret\": ").unwrap();
+        let secrets = SecretStore::new(&tmp.path().join(".mymolt"), true);
+        assert!(TotpStore::new(tmp.path(), secrets).is_err());
+    }
+}
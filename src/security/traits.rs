@@ -4,7 +4,10 @@
 
 //! Sandbox trait for pluggable OS-level isolation
 
+use crate::config::SandboxProfile;
 use async_trait::async_trait;
+use std::ffi::OsStr;
+use std::path::Path;
 use std::process::Command;
 
 /// Sandbox backend for OS-level isolation
@@ -21,6 +24,53 @@ pub trait Sandbox: Send + Sync {
 
     /// Description of what this sandbox provides
     fn description(&self) -> &str;
+
+    /// Whether this backend can enforce `profile` on a single command
+    fn supports(&self, profile: SandboxProfile) -> bool {
+        profile == SandboxProfile::None
+    }
+
+    /// Wrap a command so it runs under `profile`, with `workspace_dir`
+    /// left writable
+    fn wrap_command_with_profile(
+        &self,
+        cmd: &mut Command,
+        profile: SandboxProfile,
+        workspace_dir: &Path,
+    ) -> std::io::Result<()> {
+        let _ = (cmd, workspace_dir);
+        if profile == SandboxProfile::None {
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("{} cannot enforce the {profile} profile", self.name()),
+            ))
+        }
+    }
+}
+
+/// Replace `cmd` with `program wrapper_args.. <original command>`, keeping
+/// its working directory and environment.
+pub(crate) fn rewrap<I, S>(cmd: &mut Command, program: &str, wrapper_args: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut wrapped = Command::new(program);
+    wrapped.args(wrapper_args);
+    wrapped.arg(cmd.get_program());
+    wrapped.args(cmd.get_args());
+    if let Some(dir) = cmd.get_current_dir() {
+        wrapped.current_dir(dir);
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => wrapped.env(key, value),
+            None => wrapped.env_remove(key),
+        };
+    }
+    *cmd = wrapped;
 }
 
 /// No-op sandbox (always available, provides no additional isolation)
@@ -82,4 +132,31 @@ mod tests {
             original_args
         );
     }
+
+    #[test]
+    fn noop_sandbox_only_supports_no_profile() {
+        let mut cmd = Command::new("echo");
+        assert!(NoopSandbox.supports(SandboxProfile::None));
+        assert!(!NoopSandbox.supports(SandboxProfile::ReadonlyFs));
+        assert!(NoopSandbox
+            .wrap_command_with_profile(&mut cmd, SandboxProfile::Full, Path::new("/tmp"))
+            .is_err());
+    }
+
+    #[test]
+    fn rewrap_keeps_directory_and_environment() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "true"])
+            .current_dir("/tmp")
+            .env("LANG", "C");
+        rewrap(&mut cmd, "bwrap", ["--die-with-parent"]);
+
+        assert_eq!(cmd.get_program(), "bwrap");
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["--die-with-parent", "sh", "-c", "true"]);
+        assert_eq!(cmd.get_current_dir(), Some(Path::new("/tmp")));
+        assert!(cmd
+            .get_envs()
+            .any(|(k, v)| k == "LANG" && v == Some(OsStr::new("C"))));
+    }
 }
@@ -2,21 +2,32 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use crate::config::KeyBackend;
 use crate::memory::{Memory, MemoryCategory};
-use anyhow::{Context, Result};
+use crate::security::{emergency, keystore};
+use anyhow::{ensure, Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 // ── Hoodik-native E2EE ──────────────────────────────────────────────────
 // We use a hybrid encryption scheme:
 //   1. Generate a random 256-bit symmetric key (ChaCha20-Poly1305).
 //   2. Encrypt the plaintext with ChaCha20-Poly1305 (AEAD).
-//   3. Encrypt the symmetric key with the Hoodik admin RSA public key.
-//   4. Store:  base64(RSA-encrypted-sym-key) || "." || base64(nonce||ciphertext)
+//   3. Encrypt the symmetric key with every recipient's RSA public key: the
+//      Hoodik admin key plus any extra keys in `hoodik/keys/recipients/`
+//      (e.g. a family Root or a recovery key).
+//   4. Store:  fp1 ":" base64(RSA-wrapped-key) [ "," fp2 ":" ... ] "." base64(nonce||ciphertext)
+//      where fp is the recipient key fingerprint. Entries written before
+//      multi-recipient support hold a single untagged wrapped key.
+//
+// Rotating or adding keys only re-wraps the symmetric key; payloads stay as
+// they are.
 //
 // This removes the external `age` CLI dependency and uses the same crypto
 // stack as the rest of the Hoodik system.
@@ -33,6 +44,64 @@ pub struct VaultMetadata {
     pub vault_path: PathBuf,
     pub created_at: String,
     pub tags: Vec<String>,
+    /// When the content was last replaced or re-encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Fingerprints of the keys the entry's data key is wrapped for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipients: Vec<String>,
+}
+
+/// A public key data keys are wrapped for.
+struct Recipient {
+    fingerprint: String,
+    pem: String,
+}
+
+/// A private key able to unwrap data keys, with the fingerprint of its
+/// public half.
+struct UnwrapKey {
+    fingerprint: String,
+    pem: Zeroizing<String>,
+}
+
+/// A parsed vault file: wrapped data keys, tagged with the recipient
+/// fingerprint (`None` in legacy envelopes), and the encrypted payload.
+struct Envelope<'a> {
+    wrapped: Vec<(Option<&'a str>, &'a str)>,
+    data: &'a str,
+}
+
+impl<'a> Envelope<'a> {
+    fn parse(envelope: &'a str) -> Result<Self> {
+        let (keys, data) = envelope
+            .split_once('.')
+            .context("Invalid vault envelope (missing '.' separator)")?;
+        let wrapped = if keys.contains(':') {
+            keys.split(',')
+                .map(|part| {
+                    part.split_once(':')
+                        .map(|(fp, key)| (Some(fp), key))
+                        .context("Invalid vault envelope (untagged key)")
+                })
+                .collect::<Result<_>>()?
+        } else {
+            vec![(None, keys)]
+        };
+        Ok(Self { wrapped, data })
+    }
+
+    fn recipients(&self) -> Vec<String> {
+        self.wrapped
+            .iter()
+            .filter_map(|(fp, _)| fp.map(str::to_string))
+            .collect()
+    }
+}
+
+/// SHA-256 of a public key PEM, hex encoded.
+fn fingerprint(pem: &str) -> String {
+    hex::encode(Sha256::digest(pem.trim().as_bytes()))
 }
 
 pub struct VaultManager {
@@ -40,6 +109,8 @@ pub struct VaultManager {
     vault_dir: PathBuf,
     meta_dir: PathBuf,
     journal_dir: PathBuf,
+    /// Where a newly generated admin private key is kept
+    key_backend: KeyBackend,
 }
 
 impl VaultManager {
@@ -50,32 +121,125 @@ impl VaultManager {
             meta_dir: base.join("data/meta"),
             journal_dir: base.join("data/journal"),
             base_dir: base,
+            key_backend: KeyBackend::default(),
         }
     }
 
+    /// Keep admin private keys generated by [`Self::rotate_admin_key`] in
+    /// `backend`.
+    pub fn with_key_backend(mut self, backend: KeyBackend) -> Self {
+        self.key_backend = backend;
+        self
+    }
+
     // ── Key helpers ──────────────────────────────────────────────────
 
+    fn keys_dir(&self) -> PathBuf {
+        self.base_dir.join("hoodik/keys")
+    }
+
     /// Load the Hoodik admin RSA public key PEM.
     fn load_admin_pubkey(&self) -> Result<String> {
-        let path = self.base_dir.join("hoodik/keys/admin.pub");
+        let path = self.keys_dir().join("admin.pub");
         fs::read_to_string(&path)
             .with_context(|| format!("Cannot read Hoodik admin public key at {}", path.display()))
     }
 
-    /// Load the Hoodik admin RSA private key PEM.
-    fn load_admin_privkey(&self) -> Result<String> {
-        let path = self.base_dir.join("hoodik/keys/admin.key");
-        fs::read_to_string(&path)
-            .with_context(|| format!("Cannot read Hoodik admin private key at {}", path.display()))
+    /// Load the private key `<name>.key` together with the fingerprint of
+    /// `<name>.pub`.
+    fn load_unwrap_key(&self, name: &str) -> Result<UnwrapKey> {
+        ensure!(
+            !emergency::is_engaged(),
+            "The vault is locked by the emergency stop; pair again to unlock it"
+        );
+        let priv_path = self.keys_dir().join(format!("{name}.key"));
+        let contents = Zeroizing::new(fs::read_to_string(&priv_path).with_context(|| {
+            format!(
+                "Cannot read Hoodik admin private key at {}",
+                priv_path.display()
+            )
+        })?);
+        let pem = keystore::open(&priv_path, &contents)?;
+        let pub_path = self.keys_dir().join(format!("{name}.pub"));
+        let public = fs::read_to_string(&pub_path)
+            .with_context(|| format!("Cannot read public key at {}", pub_path.display()))?;
+        Ok(UnwrapKey {
+            fingerprint: fingerprint(&public),
+            pem,
+        })
+    }
+
+    /// `admin_pem` followed by every `recipients/*.pub`, sorted by file name
+    /// and without duplicates.
+    fn recipients(&self, admin_pem: String) -> Result<Vec<Recipient>> {
+        let mut pems = vec![admin_pem];
+        let dir = self.keys_dir().join("recipients");
+        if dir.exists() {
+            let mut paths = fs::read_dir(&dir)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            paths.retain(|p| p.extension().is_some_and(|e| e == "pub"));
+            paths.sort();
+            for path in paths {
+                pems.push(
+                    fs::read_to_string(&path)
+                        .with_context(|| format!("Cannot read recipient key {}", path.display()))?,
+                );
+            }
+        }
+
+        let mut seen = BTreeSet::new();
+        Ok(pems
+            .into_iter()
+            .map(|pem| Recipient {
+                fingerprint: fingerprint(&pem),
+                pem,
+            })
+            .filter(|r| seen.insert(r.fingerprint.clone()))
+            .collect())
+    }
+
+    /// RSA-wrap a base64 data key for each recipient.
+    fn wrap_data_key(sym_key_b64: &str, recipients: &[Recipient]) -> Result<String> {
+        let wrapped = recipients
+            .iter()
+            .map(|r| {
+                cryptfns::rsa::public::encrypt(sym_key_b64, &r.pem)
+                    .map(|key| format!("{}:{key}", r.fingerprint))
+                    .map_err(|e| anyhow::anyhow!("RSA key-wrap failed: {e}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(wrapped.join(","))
+    }
+
+    /// RSA-unwrap the data key of `envelope` with the first of `keys` it is
+    /// wrapped for. Returns the base64 data key.
+    fn unwrap_data_key(envelope: &Envelope, keys: &[UnwrapKey]) -> Result<Zeroizing<String>> {
+        let mut last_error = None;
+        for key in keys {
+            for (fp, wrapped) in &envelope.wrapped {
+                if fp.is_some_and(|fp| fp != key.fingerprint) {
+                    continue;
+                }
+                match cryptfns::rsa::private::decrypt(wrapped, &key.pem) {
+                    Ok(sym_key_b64) => return Ok(Zeroizing::new(sym_key_b64)),
+                    Err(e) => last_error = Some(e.to_string()),
+                }
+            }
+        }
+        match last_error {
+            Some(e) => anyhow::bail!("RSA key-unwrap failed: {e}"),
+            None => anyhow::bail!("Vault entry is not wrapped for the admin key"),
+        }
     }
 
     // ── Hybrid encrypt / decrypt ────────────────────────────────────
 
     /// Hybrid-encrypt: ChaCha20 for data, RSA for key wrapping.
     ///
-    /// Output format (UTF-8): `<base64(rsa-encrypted-sym-key)>.<base64(nonce||aead-ciphertext)>`
+    /// Output format (UTF-8): `<fp>:<base64(rsa-wrapped-key)>[,...].<base64(nonce||aead-ciphertext)>`
     fn hybrid_encrypt(&self, plaintext: &[u8]) -> Result<String> {
-        let pubkey_pem = self.load_admin_pubkey()?;
+        let recipients = self.recipients(self.load_admin_pubkey()?)?;
 
         // 1. Random 256-bit symmetric key
         let sym_key = ChaCha20Poly1305::generate_key(&mut OsRng);
@@ -93,30 +257,25 @@ impl VaultManager {
         blob.extend_from_slice(&ciphertext);
         let data_b64 = cryptfns::base64::encode(&blob);
 
-        // 3. RSA-wrap the symmetric key
-        let wrapped = cryptfns::rsa::public::encrypt(
-            &cryptfns::base64::encode(sym_key.as_slice()),
-            &pubkey_pem,
-        )
-        .map_err(|e| anyhow::anyhow!("RSA key-wrap failed: {e}"))?;
+        // 3. RSA-wrap the symmetric key for every recipient
+        let sym_key_b64 = Zeroizing::new(cryptfns::base64::encode(sym_key.as_slice()));
+        let wrapped = Self::wrap_data_key(&sym_key_b64, &recipients)?;
 
         Ok(format!("{wrapped}.{data_b64}"))
     }
 
     /// Hybrid-decrypt: RSA-unwrap the symmetric key, then ChaCha20 decrypt.
     fn hybrid_decrypt(&self, envelope: &str) -> Result<String> {
-        let privkey_pem = self.load_admin_privkey()?;
-
-        let (wrapped_key_b64, data_b64) = envelope
-            .split_once('.')
-            .context("Invalid vault envelope (missing '.' separator)")?;
+        let admin_key = self.load_unwrap_key("admin")?;
+        let envelope = Envelope::parse(envelope)?;
 
         // 1. RSA-unwrap
-        let sym_key_b64 = cryptfns::rsa::private::decrypt(wrapped_key_b64, &privkey_pem)
-            .map_err(|e| anyhow::anyhow!("RSA key-unwrap failed: {e}"))?;
+        let sym_key_b64 = Self::unwrap_data_key(&envelope, std::slice::from_ref(&admin_key))?;
 
-        let sym_key_bytes = cryptfns::base64::decode(&sym_key_b64)
-            .map_err(|e| anyhow::anyhow!("Sym-key decode failed: {e}"))?;
+        let sym_key_bytes = Zeroizing::new(
+            cryptfns::base64::decode(sym_key_b64.as_str())
+                .map_err(|e| anyhow::anyhow!("Sym-key decode failed: {e}"))?,
+        );
 
         anyhow::ensure!(
             sym_key_bytes.len() == 32,
@@ -128,7 +287,7 @@ impl VaultManager {
         let cipher = ChaCha20Poly1305::new(key);
 
         // 2. Decode data blob
-        let blob = cryptfns::base64::decode(&data_b64)
+        let blob = cryptfns::base64::decode(envelope.data)
             .map_err(|e| anyhow::anyhow!("Data blob decode failed: {e}"))?;
 
         anyhow::ensure!(
@@ -155,7 +314,7 @@ impl VaultManager {
         memory: &M,
         mut plaintext: String,
         description: &str,
-        _recipient: &str, // kept for API compat; we always use the admin key
+        _recipient: &str, // kept for API compat; recipients come from `hoodik/keys`
     ) -> Result<PathBuf> {
         let id = uuid::Uuid::new_v4().to_string();
         let vault_filename = format!("{}.vault", id);
@@ -180,6 +339,8 @@ impl VaultManager {
             vault_path: vault_path.clone(),
             created_at: Utc::now().to_rfc3339(),
             tags: vec!["vault".to_string()],
+            updated_at: None,
+            recipients: Envelope::parse(&encrypted)?.recipients(),
         };
 
         let meta_content = serde_json::to_string_pretty(&metadata)?;
@@ -203,6 +364,7 @@ impl VaultManager {
 
     /// Decrypt data from vault using Hoodik-native hybrid E2EE.
     pub fn decrypt_from_vault(&self, id: &str) -> Result<String> {
+        Self::check_id(id)?;
         let vault_path = self.vault_dir.join(format!("{}.vault", id));
         if !vault_path.exists() {
             anyhow::bail!("Vault entry {} not found", id);
@@ -232,6 +394,194 @@ impl VaultManager {
         Ok(())
     }
 
+    /// Metadata of entry `id`, or `None` if there is no such entry.
+    pub fn metadata(&self, id: &str) -> Result<Option<VaultMetadata>> {
+        Self::check_id(id)?;
+        let meta_path = self.meta_dir.join(format!("{}.json", id));
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&meta_path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Replace the content of entry `id`, encrypted under a fresh data key.
+    pub fn update(&self, id: &str, mut plaintext: String) -> Result<VaultMetadata> {
+        let encrypted = self.hybrid_encrypt(plaintext.as_bytes());
+        plaintext.zeroize();
+        self.replace_ciphertext(id, &encrypted?, "updated")
+    }
+
+    /// Re-encrypt entry `id` under a fresh data key, wrapped with the
+    /// current admin key.
+    pub fn rotate(&self, id: &str) -> Result<VaultMetadata> {
+        let mut plaintext = self.decrypt_from_vault(id)?;
+        let encrypted = self.hybrid_encrypt(plaintext.as_bytes());
+        plaintext.zeroize();
+        self.replace_ciphertext(id, &encrypted?, "rotated")
+    }
+
+    /// Swap in new ciphertext for entry `id`, shred the old one and commit.
+    fn replace_ciphertext(&self, id: &str, encrypted: &str, verb: &str) -> Result<VaultMetadata> {
+        let meta = self.write_ciphertext(id, encrypted)?;
+        self.commit_to_git(&format!("{} {}", verb, id))?;
+        Ok(meta)
+    }
+
+    /// Swap in new ciphertext for entry `id` and shred the old one.
+    fn write_ciphertext(&self, id: &str, encrypted: &str) -> Result<VaultMetadata> {
+        let mut meta = self
+            .metadata(id)?
+            .with_context(|| format!("Vault entry {} not found", id))?;
+        let vault_path = self.vault_dir.join(format!("{}.vault", id));
+        let staged = vault_path.with_extension("vault.new");
+        let old = vault_path.with_extension("vault.old");
+        fs::write(&staged, encrypted)?;
+        fs::rename(&vault_path, &old)?;
+        fs::rename(&staged, &vault_path)?;
+        Self::shred(&old)?;
+
+        meta.updated_at = Some(Utc::now().to_rfc3339());
+        meta.recipients = Envelope::parse(encrypted)?.recipients();
+        fs::write(
+            self.meta_dir.join(format!("{}.json", id)),
+            serde_json::to_string_pretty(&meta)?,
+        )?;
+        Ok(meta)
+    }
+
+    // ── Key management ──────────────────────────────────────────────
+
+    /// Re-wrap every entry's data key for the current recipients, e.g.
+    /// after adding or removing a key in `hoodik/keys/recipients/`.
+    /// Returns the number of entries changed.
+    pub fn rewrap_all(&self) -> Result<usize> {
+        let recipients = self.recipients(self.load_admin_pubkey()?)?;
+        let keys = [self.load_unwrap_key("admin")?];
+        let changed = self.rewrap_entries(&keys, &recipients)?;
+        if changed > 0 {
+            self.commit_to_git(&format!("re-wrapped {} entries", changed))?;
+        }
+        Ok(changed)
+    }
+
+    /// Replace the admin keypair and re-wrap every entry's data key for the
+    /// new key and the other recipients.
+    ///
+    /// The new pair is staged as `admin.next.{key,pub}` until every entry is
+    /// re-wrapped, so an interrupted rotation picks up where it stopped when
+    /// run again. The old private key is shredded once the new pair is in
+    /// place. Returns the number of entries changed.
+    pub fn rotate_admin_key(&self) -> Result<usize> {
+        let dir = self.keys_dir();
+        let (next_key, next_pub) = (dir.join("admin.next.key"), dir.join("admin.next.pub"));
+        if !(next_key.exists() && next_pub.exists()) {
+            let private = cryptfns::rsa::private::generate()
+                .map_err(|e| anyhow::anyhow!("Keygen error: {e}"))?;
+            let public = cryptfns::rsa::public::from_private(&private)
+                .map_err(|e| anyhow::anyhow!("Pubkey error: {e}"))?;
+            let priv_pem = Zeroizing::new(
+                cryptfns::rsa::private::to_string(&private)
+                    .map_err(|e| anyhow::anyhow!("PEM error: {e}"))?,
+            );
+            let pub_pem = cryptfns::rsa::public::to_string(&public)
+                .map_err(|e| anyhow::anyhow!("PEM error: {e}"))?;
+            let stored = keystore::seal(&next_key, self.key_backend, &priv_pem)?;
+            fs::write(&next_key, stored.as_bytes())?;
+            fs::write(&next_pub, pub_pem)?;
+        }
+
+        let recipients = self.recipients(fs::read_to_string(&next_pub)?)?;
+        let keys = [
+            self.load_unwrap_key("admin")?,
+            self.load_unwrap_key("admin.next")?,
+        ];
+        let changed = self.rewrap_entries(&keys, &recipients)?;
+
+        let old_key = dir.join("admin.key.old");
+        fs::rename(dir.join("admin.key"), &old_key)?;
+        fs::rename(&next_key, dir.join("admin.key"))?;
+        fs::rename(&next_pub, dir.join("admin.pub"))?;
+        keystore::discard(&old_key, &Zeroizing::new(fs::read_to_string(&old_key)?))?;
+        Self::shred(&old_key)?;
+
+        self.commit_to_git(&format!(
+            "rotated admin key, re-wrapped {} entries",
+            changed
+        ))?;
+        Ok(changed)
+    }
+
+    /// Re-wrap the data key of every entry not yet wrapped for exactly
+    /// `recipients`, unwrapping with the first of `keys` that fits.
+    fn rewrap_entries(&self, keys: &[UnwrapKey], recipients: &[Recipient]) -> Result<usize> {
+        let wanted: BTreeSet<&str> = recipients.iter().map(|r| r.fingerprint.as_str()).collect();
+        let mut changed = 0;
+        for meta in self.list_entries()? {
+            let vault_path = self.vault_dir.join(format!("{}.vault", meta.id));
+            let current = fs::read_to_string(&vault_path)?;
+            let envelope = Envelope::parse(&current)?;
+            let wrapped_for: BTreeSet<&str> =
+                envelope.wrapped.iter().filter_map(|(fp, _)| *fp).collect();
+            if wrapped_for == wanted && envelope.wrapped.len() == wanted.len() {
+                continue;
+            }
+
+            let sym_key_b64 = Self::unwrap_data_key(&envelope, keys)
+                .with_context(|| format!("Cannot re-wrap vault entry {}", meta.id))?;
+            let wrapped = Self::wrap_data_key(&sym_key_b64, recipients)?;
+            self.write_ciphertext(&meta.id, &format!("{wrapped}.{}", envelope.data))?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+
+    /// Delete the ciphertext and metadata of entry `id` and commit the
+    /// removal. Returns whether the entry existed.
+    pub fn erase(&self, id: &str) -> Result<bool> {
+        Self::check_id(id)?;
+        let mut existed = false;
+        for path in [
+            self.vault_dir.join(format!("{}.vault", id)),
+            self.meta_dir.join(format!("{}.json", id)),
+        ] {
+            if path.exists() {
+                Self::shred(&path)?;
+                existed = true;
+            }
+        }
+        if existed {
+            self.commit_to_git(&format!("erased {}", id))?;
+        }
+        Ok(existed)
+    }
+
+    /// Overwrite a file with random bytes before removing it. Best effort:
+    /// journaling and copy-on-write filesystems may keep older blocks, and
+    /// committed ciphertext stays in the vault's git history.
+    fn shred(path: &Path) -> Result<()> {
+        use rand::RngCore;
+        use std::io::Write;
+
+        let len = usize::try_from(fs::metadata(path)?.len()).unwrap_or(0);
+        let mut noise = vec![0u8; len];
+        rand::thread_rng().fill_bytes(&mut noise);
+        let mut file = fs::OpenOptions::new().write(true).open(path)?;
+        file.write_all(&noise)?;
+        file.sync_all()?;
+        drop(file);
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    fn check_id(id: &str) -> Result<()> {
+        anyhow::ensure!(
+            !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+            "Invalid vault entry id"
+        );
+        Ok(())
+    }
+
     pub fn list_entries(&self) -> Result<Vec<VaultMetadata>> {
         let mut entries = Vec::new();
         if !self.meta_dir.exists() {
@@ -262,17 +612,22 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// Helper: generate an RSA keypair as `(private, public)` PEMs.
+    fn keypair() -> (String, String) {
+        let priv_key = cryptfns::rsa::private::generate().unwrap();
+        let pub_key = cryptfns::rsa::public::from_private(&priv_key).unwrap();
+        (
+            cryptfns::rsa::private::to_string(&priv_key).unwrap(),
+            cryptfns::rsa::public::to_string(&pub_key).unwrap(),
+        )
+    }
+
     /// Helper: generate Hoodik RSA keys in the expected directory structure.
     fn setup_hoodik_keys(root: &Path) {
         let key_dir = root.join("hoodik/keys");
         std::fs::create_dir_all(&key_dir).unwrap();
 
-        let priv_key = cryptfns::rsa::private::generate().unwrap();
-        let pub_key = cryptfns::rsa::public::from_private(&priv_key).unwrap();
-
-        let priv_pem = cryptfns::rsa::private::to_string(&priv_key).unwrap();
-        let pub_pem = cryptfns::rsa::public::to_string(&pub_key).unwrap();
-
+        let (priv_pem, pub_pem) = keypair();
         std::fs::write(key_dir.join("admin.key"), priv_pem).unwrap();
         std::fs::write(key_dir.join("admin.pub"), pub_pem).unwrap();
     }
@@ -350,6 +705,107 @@ mod tests {
         assert_eq!(decrypted, secret);
     }
 
+    #[tokio::test]
+    async fn update_rotate_and_erase_lifecycle() {
+        let tmp = TempDir::new().unwrap();
+        setup_hoodik_keys(tmp.path());
+        let vault = VaultManager::new(tmp.path());
+        let memory = crate::memory::simple::SimpleMemory::new();
+        vault
+            .encrypt_to_vault(&memory, "PIN 4711".into(), "Bank card", "")
+            .await
+            .unwrap();
+        let id = vault.list_entries().unwrap().remove(0).id;
+        let vault_file = tmp.path().join(format!("data/vault/{}.vault", id));
+
+        let meta = vault.update(&id, "PIN 0815".into()).unwrap();
+        assert!(meta.updated_at.is_some());
+        assert_eq!(vault.decrypt_from_vault(&id).unwrap(), "PIN 0815");
+
+        let before = fs::read_to_string(&vault_file).unwrap();
+        vault.rotate(&id).unwrap();
+        assert_ne!(fs::read_to_string(&vault_file).unwrap(), before);
+        assert_eq!(vault.decrypt_from_vault(&id).unwrap(), "PIN 0815");
+        // No staged or shredded copies are left behind.
+        assert_eq!(
+            fs::read_dir(tmp.path().join("data/vault")).unwrap().count(),
+            1
+        );
+
+        assert!(vault.erase(&id).unwrap());
+        assert!(vault.metadata(&id).unwrap().is_none());
+        assert!(!vault_file.exists());
+        assert!(vault.update(&id, "gone".into()).is_err());
+    }
+
+    #[tokio::test]
+    async fn recovery_key_can_decrypt_and_rotation_keeps_payloads() {
+        let tmp = TempDir::new().unwrap();
+        setup_hoodik_keys(tmp.path());
+        let key_dir = tmp.path().join("hoodik/keys");
+        let vault = VaultManager::new(tmp.path());
+        let memory = crate::memory::simple::SimpleMemory::new();
+
+        // An entry from before the recovery key existed is re-wrapped for it.
+        vault
+            .encrypt_to_vault(&memory, "PIN 4711".into(), "Bank card", "")
+            .await
+            .unwrap();
+        let id = vault.list_entries().unwrap().remove(0).id;
+        assert_eq!(vault.metadata(&id).unwrap().unwrap().recipients.len(), 1);
+
+        let (recovery_priv, recovery_pub) = keypair();
+        fs::create_dir_all(key_dir.join("recipients")).unwrap();
+        fs::write(key_dir.join("recipients/recovery.pub"), &recovery_pub).unwrap();
+        assert_eq!(vault.rewrap_all().unwrap(), 1);
+        assert_eq!(vault.rewrap_all().unwrap(), 0, "already wrapped");
+        let meta = vault.metadata(&id).unwrap().unwrap();
+        assert!(meta.recipients.contains(&fingerprint(&recovery_pub)));
+
+        // Rotation swaps the admin key but leaves the payload untouched.
+        let vault_file = tmp.path().join(format!("data/vault/{}.vault", id));
+        let payload = |envelope: String| envelope.split_once('.').unwrap().1.to_string();
+        let before = payload(fs::read_to_string(&vault_file).unwrap());
+        let old_admin = fs::read_to_string(key_dir.join("admin.pub")).unwrap();
+        assert_eq!(vault.rotate_admin_key().unwrap(), 1);
+        assert_eq!(payload(fs::read_to_string(&vault_file).unwrap()), before);
+        assert_ne!(
+            fs::read_to_string(key_dir.join("admin.pub")).unwrap(),
+            old_admin
+        );
+        assert!(!key_dir.join("admin.next.key").exists());
+        assert!(!key_dir.join("admin.key.old").exists());
+        assert_eq!(vault.decrypt_from_vault(&id).unwrap(), "PIN 4711");
+        let meta = vault.metadata(&id).unwrap().unwrap();
+        assert!(!meta.recipients.contains(&fingerprint(&old_admin)));
+
+        // With only the recovery key, the entry still opens.
+        fs::write(key_dir.join("admin.key"), recovery_priv).unwrap();
+        fs::write(key_dir.join("admin.pub"), recovery_pub).unwrap();
+        assert_eq!(vault.decrypt_from_vault(&id).unwrap(), "PIN 4711");
+    }
+
+    #[test]
+    fn legacy_single_key_envelopes_still_decrypt() {
+        let tmp = TempDir::new().unwrap();
+        setup_hoodik_keys(tmp.path());
+        let vault = VaultManager::new(tmp.path());
+
+        let tagged = vault.hybrid_encrypt(b"old secret").unwrap();
+        let (keys, data) = tagged.split_once('.').unwrap();
+        let (_, wrapped) = keys.split_once(':').unwrap();
+        let legacy = format!("{wrapped}.{data}");
+        assert_eq!(vault.hybrid_decrypt(&legacy).unwrap(), "old secret");
+    }
+
+    #[test]
+    fn entry_ids_cannot_escape_the_vault() {
+        let tmp = TempDir::new().unwrap();
+        let vault = VaultManager::new(tmp.path());
+        assert!(vault.decrypt_from_vault("../../etc/passwd").is_err());
+        assert!(vault.metadata("../config").is_err());
+    }
+
     #[test]
     fn list_entries_empty_when_no_meta() {
         let tmp = TempDir::new().unwrap();
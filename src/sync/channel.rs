@@ -0,0 +1,402 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Authenticated, end-to-end encrypted transport between two `MyMolt` instances.
+//!
+//! Handshake (mutually authenticated, forward secret — modelled on Noise `KK`):
+//!
+//! ```text
+//! I → R:  "MMSYNC02" ‖ e_i ‖ s_i          (R rejects unknown s_i)
+//! R → I:  e_r
+//! keys  = HKDF-SHA256(salt = SHA256("MMSYNC02" ‖ e_i ‖ s_i ‖ e_r ‖ s_r),
+//!                     ikm  = DH(e_i,e_r) ‖ DH(s_i,e_r) ‖ DH(e_i,s_r),
+//!                     info = "mymolt-sync-v2")
+//! → (initiator→responder key, responder→initiator key)
+//! ```
+//!
+//! Both sides know each other's static key in advance (`[[sync.peers]]`).
+//! `DH(s_i,e_r)` needs the initiator's static secret and `DH(e_i,s_r)` the
+//! responder's, so only the two configured peers can derive the keys, and
+//! `DH(e_i,e_r)` makes them forward secret. Low-order keys are rejected. The
+//! handshake itself proves nothing: a peer is authenticated by the first
+//! frame it sends that decrypts. Where this differs from Noise `KK`: `s_i`
+//! travels in the clear (the responder looks the peer up by it, so the
+//! initiator's identity is not hidden from observers), there is no
+//! `DH(s_i,s_r)` term, and keys come from one HKDF over the transcript rather
+//! than a running chaining key.
+//!
+//! Messages are split into frames of at most [`MAX_FRAME_BYTES`]: a length
+//! prefix and a ChaCha20-Poly1305 ciphertext under a per-direction counter
+//! nonce, whose plaintext is a flag byte (more frames follow, or last) and a
+//! chunk of the message. A receiver buffers one frame before it can check
+//! the sender, and no more than [`MAX_MESSAGE_BYTES`] after that.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+const MAGIC: &[u8; 8] = b"MMSYNC02";
+const HKDF_INFO: &[u8] = b"mymolt-sync-v2";
+
+/// Upper bound for a single encrypted frame.
+pub const MAX_FRAME_BYTES: usize = 64 * 1024;
+
+/// Upper bound for a message split into frames (snapshots of a large memory store).
+pub const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Message bytes per frame, after the flag byte and the 16-byte AEAD tag.
+const CHUNK_BYTES: usize = MAX_FRAME_BYTES - 1 - 16;
+
+/// Frame flags: more frames of the message follow, or this one ends it.
+const MORE: u8 = 0;
+const LAST: u8 = 1;
+
+/// Encode a public key the way it is written in `[[sync.peers]]`.
+pub fn encode_key(key: &PublicKey) -> String {
+    base64::engine::general_purpose::STANDARD.encode(key.as_bytes())
+}
+
+/// Parse a base64 X25519 public key from config.
+pub fn decode_key(encoded: &str) -> Result<PublicKey> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("Sync public key is not valid base64")?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Sync public key must be 32 bytes"))?;
+    Ok(PublicKey::from(bytes))
+}
+
+/// An established session: one AEAD key and nonce counter per direction.
+pub struct SecureChannel<S> {
+    stream: S,
+    send: ChaCha20Poly1305,
+    recv: ChaCha20Poly1305,
+    send_counter: u64,
+    recv_counter: u64,
+    /// Static key of the authenticated remote peer.
+    remote: PublicKey,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SecureChannel<S> {
+    /// Open a session to a peer whose static key is known in advance.
+    pub async fn initiate(
+        mut stream: S,
+        local: &StaticSecret,
+        remote: &PublicKey,
+    ) -> Result<Self> {
+        let ephemeral = StaticSecret::random_from_rng(OsRng);
+        let e_i = PublicKey::from(&ephemeral);
+        let s_i = PublicKey::from(local);
+
+        let mut hello = Vec::with_capacity(72);
+        hello.extend_from_slice(MAGIC);
+        hello.extend_from_slice(e_i.as_bytes());
+        hello.extend_from_slice(s_i.as_bytes());
+        stream.write_all(&hello).await?;
+        stream.flush().await?;
+
+        let mut reply = [0u8; 32];
+        stream
+            .read_exact(&mut reply)
+            .await
+            .context("Peer closed the connection during handshake (is our key in its peer list?)")?;
+        let e_r = PublicKey::from(reply);
+
+        let ikm = [
+            dh(&ephemeral, &e_r)?,
+            dh(local, &e_r)?,
+            dh(&ephemeral, remote)?,
+        ];
+        let (i2r, r2i) = derive_keys(&ikm, &[&e_i, &s_i, &e_r, remote]);
+        Ok(Self::new(stream, &i2r, &r2i, *remote))
+    }
+
+    /// Accept a session, allowing only initiators whose static key `is_trusted`.
+    pub async fn respond(
+        mut stream: S,
+        local: &StaticSecret,
+        is_trusted: impl Fn(&PublicKey) -> bool,
+    ) -> Result<Self> {
+        let mut hello = [0u8; 72];
+        stream.read_exact(&mut hello).await?;
+        anyhow::ensure!(&hello[..8] == MAGIC, "Not a MyMolt sync handshake");
+
+        let mut e_bytes = [0u8; 32];
+        e_bytes.copy_from_slice(&hello[8..40]);
+        let mut s_bytes = [0u8; 32];
+        s_bytes.copy_from_slice(&hello[40..72]);
+        let e_i = PublicKey::from(e_bytes);
+        let s_i = PublicKey::from(s_bytes);
+        anyhow::ensure!(is_trusted(&s_i), "Rejected sync from unknown peer {}", encode_key(&s_i));
+
+        let ephemeral = StaticSecret::random_from_rng(OsRng);
+        let e_r = PublicKey::from(&ephemeral);
+        stream.write_all(e_r.as_bytes()).await?;
+        stream.flush().await?;
+
+        let s_r = PublicKey::from(local);
+        let ikm = [
+            dh(&ephemeral, &e_i)?,
+            dh(&ephemeral, &s_i)?,
+            dh(local, &e_i)?,
+        ];
+        let (i2r, r2i) = derive_keys(&ikm, &[&e_i, &s_i, &e_r, &s_r]);
+        Ok(Self::new(stream, &r2i, &i2r, s_i))
+    }
+
+    fn new(stream: S, send_key: &[u8; 32], recv_key: &[u8; 32], remote: PublicKey) -> Self {
+        Self {
+            stream,
+            send: ChaCha20Poly1305::new(Key::from_slice(send_key)),
+            recv: ChaCha20Poly1305::new(Key::from_slice(recv_key)),
+            send_counter: 0,
+            recv_counter: 0,
+            remote,
+        }
+    }
+
+    /// Static key of the authenticated remote peer.
+    pub fn remote_key(&self) -> &PublicKey {
+        &self.remote
+    }
+
+    /// Encrypt and send one message, split into frames.
+    pub async fn send(&mut self, message: &[u8]) -> Result<()> {
+        anyhow::ensure!(
+            message.len() <= MAX_MESSAGE_BYTES,
+            "Sync message exceeds {MAX_MESSAGE_BYTES} bytes"
+        );
+        let frames = message.len().div_ceil(CHUNK_BYTES).max(1);
+        for (i, chunk) in (0..frames).zip(message.chunks(CHUNK_BYTES).chain([&[][..]])) {
+            let flag = if i + 1 == frames { LAST } else { MORE };
+            self.send_frame(flag, chunk).await?;
+        }
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Receive and decrypt one message.
+    pub async fn recv(&mut self) -> Result<Vec<u8>> {
+        let mut message = Vec::new();
+        loop {
+            let frame = self.recv_frame().await?;
+            let (&flag, chunk) = frame.split_first().context("Empty sync frame")?;
+            anyhow::ensure!(
+                message.len() + chunk.len() <= MAX_MESSAGE_BYTES,
+                "Sync message exceeds {MAX_MESSAGE_BYTES} bytes"
+            );
+            message.extend_from_slice(chunk);
+            match flag {
+                LAST => return Ok(message),
+                MORE => {}
+                _ => anyhow::bail!("Malformed sync frame"),
+            }
+        }
+    }
+
+    async fn send_frame(&mut self, flag: u8, chunk: &[u8]) -> Result<()> {
+        let mut plaintext = Vec::with_capacity(1 + chunk.len());
+        plaintext.push(flag);
+        plaintext.extend_from_slice(chunk);
+        let nonce = counter_nonce(self.send_counter);
+        self.send_counter += 1;
+        let ciphertext = self
+            .send
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| anyhow::anyhow!("Sync encryption failed: {e}"))?;
+        let len = u32::try_from(ciphertext.len()).context("Sync frame too large")?;
+        self.stream.write_all(&len.to_be_bytes()).await?;
+        self.stream.write_all(&ciphertext).await?;
+        Ok(())
+    }
+
+    async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len).await?;
+        let len = u32::from_be_bytes(len) as usize;
+        anyhow::ensure!(len <= MAX_FRAME_BYTES, "Sync frame exceeds {MAX_FRAME_BYTES} bytes");
+
+        let mut ciphertext = vec![0u8; len];
+        self.stream.read_exact(&mut ciphertext).await?;
+        let nonce = counter_nonce(self.recv_counter);
+        self.recv_counter += 1;
+        self.recv
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow::anyhow!("Sync frame failed authentication — wrong key or tampered data"))
+    }
+}
+
+fn dh(secret: &StaticSecret, public: &PublicKey) -> Result<[u8; 32]> {
+    let shared = secret.diffie_hellman(public);
+    anyhow::ensure!(shared.was_contributory(), "Peer sent a low-order public key");
+    Ok(shared.to_bytes())
+}
+
+fn counter_nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&counter.to_le_bytes());
+    nonce
+}
+
+/// HKDF-SHA256 over the three DH results, salted with the handshake transcript.
+/// Returns `(initiator→responder, responder→initiator)` keys.
+fn derive_keys(ikm: &[[u8; 32]; 3], transcript: &[&PublicKey; 4]) -> ([u8; 32], [u8; 32]) {
+    let mut hasher = Sha256::new();
+    hasher.update(MAGIC);
+    for key in transcript {
+        hasher.update(key.as_bytes());
+    }
+    let salt = hasher.finalize();
+
+    let mut extract = <Hmac<Sha256> as Mac>::new_from_slice(&salt).expect("HMAC accepts any key length");
+    for part in ikm {
+        extract.update(part);
+    }
+    let mut prk = extract.finalize().into_bytes();
+
+    let expand = |prev: &[u8], counter: u8| -> [u8; 32] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&prk).expect("HMAC accepts any key length");
+        mac.update(prev);
+        mac.update(HKDF_INFO);
+        mac.update(&[counter]);
+        mac.finalize().into_bytes().into()
+    };
+    let k1 = expand(&[], 1);
+    let k2 = expand(&k1, 2);
+    prk.zeroize();
+    (k1, k2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair() -> (StaticSecret, PublicKey) {
+        let secret = StaticSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret);
+        (secret, public)
+    }
+
+    #[tokio::test]
+    async fn handshake_and_frames_roundtrip() {
+        let (a_secret, a_public) = keypair();
+        let (b_secret, b_public) = keypair();
+        let (a_io, b_io) = tokio::io::duplex(1024);
+
+        let responder = tokio::spawn(async move {
+            let mut ch = SecureChannel::respond(b_io, &b_secret, |k| *k == a_public)
+                .await
+                .unwrap();
+            let msg = ch.recv().await.unwrap();
+            ch.send(b"pong").await.unwrap();
+            (msg, *ch.remote_key())
+        });
+
+        let mut ch = SecureChannel::initiate(a_io, &a_secret, &b_public).await.unwrap();
+        ch.send(b"ping").await.unwrap();
+        assert_eq!(ch.recv().await.unwrap(), b"pong");
+
+        let (msg, remote) = responder.await.unwrap();
+        assert_eq!(msg, b"ping");
+        assert_eq!(remote, a_public);
+    }
+
+    #[tokio::test]
+    async fn responder_rejects_unknown_initiator() {
+        let (a_secret, _) = keypair();
+        let (b_secret, b_public) = keypair();
+        let (a_io, b_io) = tokio::io::duplex(1024);
+
+        let responder =
+            tokio::spawn(async move { SecureChannel::respond(b_io, &b_secret, |_| false).await });
+        let initiator = SecureChannel::initiate(a_io, &a_secret, &b_public).await;
+
+        assert!(responder.await.unwrap().is_err());
+        assert!(initiator.is_err());
+    }
+
+    #[tokio::test]
+    async fn wrong_responder_key_fails_authentication() {
+        let (a_secret, a_public) = keypair();
+        let (b_secret, _) = keypair();
+        let (_, impostor_public) = keypair();
+        let (a_io, b_io) = tokio::io::duplex(1024);
+
+        let responder = tokio::spawn(async move {
+            let mut ch = SecureChannel::respond(b_io, &b_secret, |k| *k == a_public)
+                .await
+                .unwrap();
+            ch.recv().await
+        });
+
+        // Initiator believes it talks to `impostor_public`, so keys diverge.
+        let mut ch = SecureChannel::initiate(a_io, &a_secret, &impostor_public)
+            .await
+            .unwrap();
+        ch.send(b"secret").await.unwrap();
+        assert!(responder.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn large_and_empty_messages_span_frames() {
+        let (a_secret, a_public) = keypair();
+        let (b_secret, b_public) = keypair();
+        let (a_io, b_io) = tokio::io::duplex(1024);
+        let large: Vec<u8> = (0..=u8::MAX).cycle().take(3 * CHUNK_BYTES + 5).collect();
+        let expected = large.clone();
+
+        let responder = tokio::spawn(async move {
+            let mut ch = SecureChannel::respond(b_io, &b_secret, |k| *k == a_public)
+                .await
+                .unwrap();
+            (ch.recv().await.unwrap(), ch.recv().await.unwrap())
+        });
+
+        let mut ch = SecureChannel::initiate(a_io, &a_secret, &b_public)
+            .await
+            .unwrap();
+        ch.send(&large).await.unwrap();
+        ch.send(b"").await.unwrap();
+
+        let (received, empty) = responder.await.unwrap();
+        assert_eq!(received, expected);
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn oversized_frame_is_rejected_before_reading_it() {
+        let (a_secret, a_public) = keypair();
+        let (b_secret, b_public) = keypair();
+        let (a_io, b_io) = tokio::io::duplex(1024);
+
+        let responder = tokio::spawn(async move {
+            let mut ch = SecureChannel::respond(b_io, &b_secret, |k| *k == a_public)
+                .await
+                .unwrap();
+            ch.recv().await
+        });
+
+        let mut ch = SecureChannel::initiate(a_io, &a_secret, &b_public)
+            .await
+            .unwrap();
+        ch.stream.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+        let err = responder.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("exceeds"));
+    }
+
+    #[test]
+    fn key_encoding_roundtrip() {
+        let (_, public) = keypair();
+        assert_eq!(decode_key(&encode_key(&public)).unwrap(), public);
+        assert!(decode_key("dG9vIHNob3J0").is_err());
+    }
+}
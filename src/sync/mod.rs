@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Peer sync — one brain across your own devices, no cloud in between.
+//!
+//! Each instance holds a static X25519 identity (`mymolt sync id` prints the
+//! public half). Peers listed in `[[sync.peers]]` connect directly over an
+//! end-to-end encrypted [`channel::SecureChannel`], swap [`state::SyncSnapshot`]s
+//! and merge them with the conflict rules in [`state`].
+
+pub mod channel;
+pub mod state;
+
+use crate::config::{Config, SyncPeerConfig};
+use crate::memory::{self, Memory};
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
+use channel::SecureChannel;
+use rand::rngs::OsRng;
+use state::{SyncReport, SyncSnapshot};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const SESSION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Load this device's sync identity, creating it on first use.
+///
+/// Stored encrypted at `<workspace>/.mymolt/sync_identity.key`.
+pub fn load_or_create_identity(workspace: &Path) -> Result<StaticSecret> {
+    let dir = workspace.join(".mymolt");
+    let path = dir.join("sync_identity.key");
+    let secrets = SecretStore::new(&dir, true);
+
+    if path.exists() {
+        let raw = std::fs::read_to_string(&path).context("Failed to read sync identity")?;
+        let hex_key = secrets.decrypt(raw.trim())?;
+        let bytes: [u8; 32] = hex::decode(hex_key.trim())
+            .context("Corrupt sync identity")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Sync identity must be 32 bytes"))?;
+        return Ok(StaticSecret::from(bytes));
+    }
+
+    let secret = StaticSecret::random_from_rng(OsRng);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&path, secrets.encrypt(&hex::encode(secret.to_bytes()))?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(secret)
+}
+
+/// Base64 public key of this device, for the peer's `[[sync.peers]]` entry.
+pub fn public_identity(workspace: &Path) -> Result<String> {
+    let secret = load_or_create_identity(workspace)?;
+    Ok(channel::encode_key(&PublicKey::from(&secret)))
+}
+
+fn device_name() -> String {
+    hostname::get().map_or_else(|_| "unknown".into(), |h| h.to_string_lossy().to_string())
+}
+
+/// Shared state for one running sync component.
+pub struct SyncEngine {
+    workspace: std::path::PathBuf,
+    identity: StaticSecret,
+    peers: Vec<(SyncPeerConfig, PublicKey)>,
+    memory: Arc<dyn Memory>,
+    /// Serializes merges from concurrent inbound and outbound sessions.
+    merge_lock: tokio::sync::Mutex<()>,
+}
+
+impl SyncEngine {
+    pub fn new(config: &Config) -> Result<Self> {
+        let peers = config
+            .sync
+            .peers
+            .iter()
+            .map(|p| {
+                channel::decode_key(&p.public_key)
+                    .with_context(|| format!("Invalid public_key for sync peer '{}'", p.name))
+                    .map(|key| (p.clone(), key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let audit = Arc::new(crate::security::AuditLogger::new(
+            config.security.audit.clone(),
+            config.workspace_dir.clone(),
+        )?);
+        let memory: Arc<dyn Memory> = Arc::from(memory::create_memory(
+            &config.memory,
+            &config.security.sensitivity,
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+            audit,
+        )?);
+
+        Ok(Self {
+            identity: load_or_create_identity(&config.workspace_dir)?,
+            workspace: config.workspace_dir.clone(),
+            peers,
+            memory,
+            merge_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    fn is_trusted(&self, key: &PublicKey) -> bool {
+        self.peers.iter().any(|(_, k)| k == key)
+    }
+
+    async fn snapshot(&self) -> Result<SyncSnapshot> {
+        state::collect_snapshot(&device_name(), &self.workspace, self.memory.as_ref()).await
+    }
+
+    async fn apply(&self, remote: SyncSnapshot) -> Result<SyncReport> {
+        state::apply_snapshot(remote, &self.workspace, self.memory.as_ref()).await
+    }
+
+    /// Run one sync session against a configured peer.
+    pub async fn sync_with_peer(&self, peer: &SyncPeerConfig) -> Result<SyncReport> {
+        let (_, key) = self
+            .peers
+            .iter()
+            .find(|(p, _)| p.name == peer.name)
+            .with_context(|| format!("Unknown sync peer '{}'", peer.name))?;
+
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&peer.address))
+            .await
+            .with_context(|| format!("Timed out connecting to {}", peer.address))??;
+
+        tokio::time::timeout(SESSION_TIMEOUT, async {
+            let mut ch = SecureChannel::initiate(stream, &self.identity, key).await?;
+            let _guard = self.merge_lock.lock().await;
+
+            ch.send(&serde_json::to_vec(&self.snapshot().await?)?).await?;
+            let remote: SyncSnapshot = serde_json::from_slice(&ch.recv().await?)?;
+            self.apply(remote).await
+        })
+        .await
+        .context("Sync session timed out")?
+    }
+
+    /// Serve one inbound session: receive the peer's snapshot, answer with ours, merge.
+    async fn handle_inbound(&self, stream: TcpStream) -> Result<SyncReport> {
+        tokio::time::timeout(SESSION_TIMEOUT, async {
+            let mut ch =
+                SecureChannel::respond(stream, &self.identity, |k| self.is_trusted(k)).await?;
+            let remote: SyncSnapshot = serde_json::from_slice(&ch.recv().await?)?;
+
+            let _guard = self.merge_lock.lock().await;
+            // Reply with our pre-merge state so the initiator sees our own changes.
+            ch.send(&serde_json::to_vec(&self.snapshot().await?)?).await?;
+            self.apply(remote).await
+        })
+        .await
+        .context("Sync session timed out")?
+    }
+
+    /// Sync once with every configured peer, logging failures.
+    pub async fn sync_all(&self) -> Vec<(String, Result<SyncReport>)> {
+        let mut results = Vec::with_capacity(self.peers.len());
+        for (peer, _) in &self.peers {
+            results.push((peer.name.clone(), self.sync_with_peer(peer).await));
+        }
+        results
+    }
+}
+
+/// Daemon component: accept inbound sessions and sync with peers periodically.
+pub async fn run(config: Config) -> Result<()> {
+    let engine = Arc::new(SyncEngine::new(&config)?);
+    let listener = TcpListener::bind(&config.sync.listen)
+        .await
+        .with_context(|| format!("Failed to bind sync listener on {}", config.sync.listen))?;
+    tracing::info!(
+        "🔄 Sync listening on {} ({} peer(s))",
+        config.sync.listen,
+        engine.peers.len()
+    );
+    crate::health::mark_component_ok("sync");
+
+    let server = {
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!("Sync accept failed: {e}");
+                        continue;
+                    }
+                };
+                let engine = engine.clone();
+                tokio::spawn(async move {
+                    match engine.handle_inbound(stream).await {
+                        Ok(report) => tracing::info!("🔄 Inbound sync from {addr}: {report:?}"),
+                        Err(e) => tracing::warn!("Inbound sync from {addr} failed: {e}"),
+                    }
+                });
+            }
+        })
+    };
+
+    let mut interval = tokio::time::interval(Duration::from_secs(config.sync.interval_secs.max(10)));
+    loop {
+        interval.tick().await;
+        if server.is_finished() {
+            anyhow::bail!("Sync listener stopped");
+        }
+        for (peer, result) in engine.sync_all().await {
+            match result {
+                Ok(report) => tracing::debug!("🔄 Synced with {peer}: {report:?}"),
+                Err(e) => tracing::warn!("Sync with {peer} failed: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_is_created_once_and_encrypted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let first = public_identity(tmp.path()).unwrap();
+        let second = public_identity(tmp.path()).unwrap();
+        assert_eq!(first, second);
+
+        let raw = std::fs::read_to_string(tmp.path().join(".mymolt/sync_identity.key")).unwrap();
+        assert!(SecretStore::is_encrypted(&raw));
+    }
+
+    #[tokio::test]
+    async fn two_engines_sync_over_tcp() {
+        let dir_a = tempfile::tempdir().unwrap();
+        let dir_b = tempfile::tempdir().unwrap();
+        let key_a = public_identity(dir_a.path()).unwrap();
+        let key_b = public_identity(dir_b.path()).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let config_for = |dir: &Path, name: &str, address: &str, key: &str| {
+            let mut config = Config {
+                workspace_dir: dir.to_path_buf(),
+                ..Config::default()
+            };
+            config.sync.peers.push(SyncPeerConfig {
+                name: name.into(),
+                address: address.into(),
+                public_key: key.into(),
+            });
+            config
+        };
+        let engine_a = SyncEngine::new(&config_for(dir_a.path(), "server", &addr, &key_b)).unwrap();
+        let engine_b =
+            Arc::new(SyncEngine::new(&config_for(dir_b.path(), "desktop", "", &key_a)).unwrap());
+
+        engine_a
+            .memory
+            .store("lang", "German", crate::memory::MemoryCategory::Core)
+            .await
+            .unwrap();
+        engine_b
+            .memory
+            .store("city", "Berlin", crate::memory::MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let server = {
+            let engine_b = engine_b.clone();
+            tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                engine_b.handle_inbound(stream).await.unwrap()
+            })
+        };
+
+        let report = engine_a.sync_all().await.remove(0).1.unwrap();
+        let inbound = server.await.unwrap();
+
+        assert_eq!(report.memories, 1);
+        assert_eq!(inbound.memories, 1);
+        assert!(engine_a.memory.get("city").await.unwrap().is_some());
+        assert!(engine_b.memory.get("lang").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,365 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Sync snapshots and conflict resolution.
+//!
+//! Both sides exchange a full snapshot and merge the other's into their own:
+//!
+//! - **Memories**: last-writer-wins per key by timestamp.
+//! - **PIM**: union by item id; an item edited on both sides resolves to the
+//!   same winner on both devices (larger canonical JSON), so peers converge.
+//! - **SOUL.md**: last-writer-wins on the whole document by modification time.
+//!
+//! Location reminders are device-local and never leave this device.
+//!
+//! Deletions are not propagated — a record removed on one device is restored
+//! by the next sync unless it is removed on both.
+
+use crate::identity::Soul;
+use crate::memory::{Memory, MemoryCategory};
+use crate::security::SecretStore;
+use crate::tools::pim::{self, CalendarEvent, Contact, Expense, Note, PimStore, Task};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    pub key: String,
+    pub content: String,
+    pub category: MemoryCategory,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoulRecord {
+    pub content: String,
+    pub modified_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncSnapshot {
+    pub device: String,
+    #[serde(default)]
+    pub memories: Vec<MemoryRecord>,
+    #[serde(default)]
+    pub events: Vec<CalendarEvent>,
+    #[serde(default)]
+    pub contacts: Vec<Contact>,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub expenses: Vec<Expense>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub soul: Option<SoulRecord>,
+}
+
+/// What a merge changed locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SyncReport {
+    pub memories: usize,
+    pub pim_items: usize,
+    pub soul: bool,
+}
+
+/// Memory categories that reference device-local files and must not travel.
+fn is_local_only(category: &MemoryCategory) -> bool {
+    matches!(category, MemoryCategory::Custom(name) if name == "vault")
+}
+
+fn pim_secrets(workspace: &Path) -> Option<SecretStore> {
+    Some(SecretStore::new(&workspace.join(".mymolt"), true))
+}
+
+/// Collect everything this device shares with its peers.
+pub async fn collect_snapshot(
+    device: &str,
+    workspace: &Path,
+    memory: &dyn Memory,
+) -> Result<SyncSnapshot> {
+    let memories = memory
+        .list(None)
+        .await?
+        .into_iter()
+        .filter(|e| !is_local_only(&e.category))
+        .map(|e| MemoryRecord {
+            key: e.key,
+            content: e.content,
+            category: e.category,
+            timestamp: e.timestamp,
+        })
+        .collect();
+
+    let store = pim::load_store(workspace, &pim_secrets(workspace));
+
+    let soul_path = Soul::new(workspace).path;
+    let soul = match fs::read_to_string(&soul_path) {
+        Ok(content) => {
+            let modified: DateTime<Utc> = fs::metadata(&soul_path)?.modified()?.into();
+            Some(SoulRecord {
+                content,
+                modified_at: modified.to_rfc3339(),
+            })
+        }
+        Err(_) => None,
+    };
+
+    Ok(SyncSnapshot {
+        device: device.to_string(),
+        memories,
+        events: store.events,
+        contacts: store.contacts,
+        notes: store.notes,
+        expenses: store.expenses,
+        tasks: store.tasks,
+        soul,
+    })
+}
+
+/// Merge a peer's snapshot into local state.
+pub async fn apply_snapshot(
+    remote: SyncSnapshot,
+    workspace: &Path,
+    memory: &dyn Memory,
+) -> Result<SyncReport> {
+    let mut report = SyncReport::default();
+
+    for record in remote.memories {
+        if is_local_only(&record.category) {
+            continue;
+        }
+        let local = memory.get(&record.key).await?;
+        let take_remote = match &local {
+            None => true,
+            Some(l) => remote_wins(&l.timestamp, &l.content, &record.timestamp, &record.content),
+        };
+        if take_remote {
+            memory.store(&record.key, &record.content, record.category).await?;
+            report.memories += 1;
+        }
+    }
+
+    let secrets = pim_secrets(workspace);
+    let mut store = pim::load_store(workspace, &secrets);
+    let changed = merge_pim(
+        &mut store,
+        PimStore {
+            events: remote.events,
+            contacts: remote.contacts,
+            notes: remote.notes,
+            expenses: remote.expenses,
+            location_reminders: Vec::new(),
+            tasks: remote.tasks,
+        },
+    );
+    if changed > 0 {
+        pim::save_store(workspace, &store, &secrets)?;
+        report.pim_items = changed;
+    }
+
+    if let Some(remote_soul) = remote.soul {
+        let path = Soul::new(workspace).path;
+        let take_remote = match fs::read_to_string(&path) {
+            Err(_) => true,
+            Ok(local) => {
+                let modified: DateTime<Utc> = fs::metadata(&path)?.modified()?.into();
+                remote_wins(
+                    &modified.to_rfc3339(),
+                    &local,
+                    &remote_soul.modified_at,
+                    &remote_soul.content,
+                )
+            }
+        };
+        if take_remote {
+            fs::write(&path, &remote_soul.content)?;
+            report.soul = true;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Last-writer-wins. Identical content never wins, so a record that was just
+/// synced (and re-stamped locally) does not bounce back and forth.
+fn remote_wins(local_ts: &str, local: &str, remote_ts: &str, remote: &str) -> bool {
+    if local == remote {
+        return false;
+    }
+    match (
+        DateTime::parse_from_rfc3339(local_ts),
+        DateTime::parse_from_rfc3339(remote_ts),
+    ) {
+        (Ok(l), Ok(r)) if l != r => r > l,
+        (Ok(_), Ok(_)) => remote > local,
+        // Unparseable timestamps (e.g. other backends' formats) compare lexically.
+        _ => (remote_ts, remote) > (local_ts, local),
+    }
+}
+
+/// Union two PIM stores by id. Returns the number of local items added or replaced.
+fn merge_pim(local: &mut PimStore, remote: PimStore) -> usize {
+    merge_by_id(&mut local.events, remote.events, |e| &e.id)
+        + merge_by_id(&mut local.contacts, remote.contacts, |c| &c.id)
+        + merge_by_id(&mut local.notes, remote.notes, |n| &n.id)
+        + merge_by_id(&mut local.expenses, remote.expenses, |e| &e.id)
+        + merge_by_id(&mut local.tasks, remote.tasks, |t| &t.id)
+}
+
+fn merge_by_id<T: Serialize>(
+    local: &mut Vec<T>,
+    remote: Vec<T>,
+    id: impl Fn(&T) -> &String,
+) -> usize {
+    let canonical = |item: &T| serde_json::to_string(item).unwrap_or_default();
+    let mut changed = 0;
+    for item in remote {
+        match local.iter().position(|l| id(l) == id(&item)) {
+            None => {
+                local.push(item);
+                changed += 1;
+            }
+            Some(pos) => {
+                // Same id edited on both devices: pick a deterministic winner.
+                if canonical(&item) > canonical(&local[pos]) {
+                    local[pos] = item;
+                    changed += 1;
+                }
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+
+    fn note(id: &str, content: &str) -> Note {
+        Note {
+            id: id.into(),
+            title: "t".into(),
+            content: content.into(),
+            created_at: "2026-01-01T00:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn last_writer_wins_by_timestamp() {
+        assert!(remote_wins(
+            "2026-01-01T00:00:00+00:00",
+            "old",
+            "2026-01-02T00:00:00+00:00",
+            "new"
+        ));
+        assert!(!remote_wins(
+            "2026-01-02T00:00:00+00:00",
+            "newer",
+            "2026-01-01T00:00:00+00:00",
+            "older"
+        ));
+        assert!(!remote_wins(
+            "2026-01-01T00:00:00+00:00",
+            "same",
+            "2026-01-09T00:00:00+00:00",
+            "same"
+        ));
+    }
+
+    #[test]
+    fn timestamp_ties_resolve_identically_on_both_sides() {
+        let ts = "2026-01-01T00:00:00+00:00";
+        assert_ne!(remote_wins(ts, "a", ts, "b"), remote_wins(ts, "b", ts, "a"));
+    }
+
+    #[test]
+    fn pim_merge_unions_and_converges() {
+        let mut a = PimStore {
+            notes: vec![note("1", "alpha"), note("2", "edited on a")],
+            ..PimStore::default()
+        };
+        let mut b = PimStore {
+            notes: vec![note("2", "edited on b"), note("3", "gamma")],
+            ..PimStore::default()
+        };
+        let a_before = a.clone();
+
+        merge_pim(&mut a, b.clone());
+        merge_pim(&mut b, a_before);
+
+        let ids = |s: &PimStore| {
+            let mut v: Vec<_> = s.notes.iter().map(|n| (n.id.clone(), n.content.clone())).collect();
+            v.sort();
+            v
+        };
+        assert_eq!(ids(&a).len(), 3);
+        assert_eq!(ids(&a), ids(&b));
+    }
+
+    #[test]
+    fn completed_task_wins_merge_on_both_sides() {
+        let open = Task {
+            id: "t1".into(),
+            done: false,
+            title: "Pay rent".into(),
+            due: Some("2026-02-01".into()),
+            priority: pim::TaskPriority::High,
+            tags: vec!["home".into()],
+            notes: String::new(),
+            created_at: "2026-01-01T00:00:00Z".into(),
+            completed_at: None,
+        };
+        let done = Task {
+            done: true,
+            completed_at: Some("2026-01-30T09:00:00Z".into()),
+            ..open.clone()
+        };
+        for (local, remote) in [(open.clone(), done.clone()), (done, open)] {
+            let mut store = PimStore {
+                tasks: vec![local],
+                ..PimStore::default()
+            };
+            let remote = PimStore {
+                tasks: vec![remote],
+                ..PimStore::default()
+            };
+            merge_pim(&mut store, remote);
+            assert!(store.tasks[0].done);
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_roundtrip_between_workspaces() {
+        let dir_a = tempfile::tempdir().unwrap();
+        let dir_b = tempfile::tempdir().unwrap();
+        let mem_a = SqliteMemory::new(dir_a.path()).unwrap();
+        let mem_b = SqliteMemory::new(dir_b.path()).unwrap();
+
+        mem_a.store("favorite_color", "blue", MemoryCategory::Core).await.unwrap();
+        mem_a
+            .store("vault:123", "bank", MemoryCategory::Custom("vault".into()))
+            .await
+            .unwrap();
+        fs::write(dir_a.path().join("SOUL.md"), "# Soul\nI like tea.").unwrap();
+
+        let snapshot = collect_snapshot("desktop", dir_a.path(), &mem_a).await.unwrap();
+        let report = apply_snapshot(snapshot, dir_b.path(), &mem_b).await.unwrap();
+
+        assert_eq!(report.memories, 1);
+        assert!(report.soul);
+        assert_eq!(mem_b.get("favorite_color").await.unwrap().unwrap().content, "blue");
+        assert!(mem_b.get("vault:123").await.unwrap().is_none());
+        assert!(fs::read_to_string(dir_b.path().join("SOUL.md")).unwrap().contains("tea"));
+
+        // A second pass is a no-op
+        let snapshot = collect_snapshot("desktop", dir_a.path(), &mem_a).await.unwrap();
+        let report = apply_snapshot(snapshot, dir_b.path(), &mem_b).await.unwrap();
+        assert_eq!(report, SyncReport::default());
+    }
+}
@@ -0,0 +1,690 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Create, list and extract zip and tar(.gz) archives inside the workspace.
+//!
+//! Every entry name is checked before anything is extracted: absolute paths
+//! and `..` components reject the whole archive, and links are skipped so an
+//! entry cannot point outside the extraction directory. Entry count and the
+//! number of bytes written are capped, which also stops decompression bombs.
+
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Most entries an archive may have, for creating and extracting.
+const MAX_ENTRIES: usize = 10_000;
+/// Most bytes written when extracting, or read when creating.
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+/// Entries listed in the tool output before truncating.
+const MAX_LISTED: usize = 200;
+
+/// Create and extract archives with path sandboxing
+pub struct ArchiveTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ArchiveTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl Format {
+    fn from_path(path: &str) -> Option<Self> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if lower.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// `data/set.tar.gz` → `data/set`
+    fn strip_extension(self, path: &str) -> String {
+        let lower = path.to_ascii_lowercase();
+        let ext = [".tar.gz", ".tgz", ".tar", ".zip"]
+            .into_iter()
+            .find(|ext| lower.ends_with(ext))
+            .map_or(0, str::len);
+        path[..path.len() - ext].to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Dir,
+    Link,
+}
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    size: u64,
+    kind: EntryKind,
+}
+
+/// Path of an entry relative to the extraction directory, or `None` if it
+/// is absolute or climbs out with `..`.
+fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    let mut out = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+fn tar_reader(path: &Path, format: Format) -> io::Result<tar::Archive<Box<dyn Read>>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if format == Format::TarGz {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// Read the entry table without extracting anything.
+fn list_entries(path: &Path, format: Format) -> anyhow::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    if format == Format::Zip {
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i)?;
+            let kind = if file.is_symlink() {
+                EntryKind::Link
+            } else if file.is_dir() {
+                EntryKind::Dir
+            } else {
+                EntryKind::File
+            };
+            entries.push(Entry {
+                name: file.name().to_string(),
+                size: file.size(),
+                kind,
+            });
+            anyhow::ensure!(entries.len() <= MAX_ENTRIES, "more than {MAX_ENTRIES} entries");
+        }
+    } else {
+        let mut archive = tar_reader(path, format)?;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let kind = match entry.header().entry_type() {
+                tar::EntryType::Directory => EntryKind::Dir,
+                tar::EntryType::Regular | tar::EntryType::Continuous => EntryKind::File,
+                // Symlinks, hard links, devices and FIFOs are never extracted.
+                _ => EntryKind::Link,
+            };
+            entries.push(Entry {
+                name: entry.path()?.to_string_lossy().into_owned(),
+                size: entry.size(),
+                kind,
+            });
+            anyhow::ensure!(entries.len() <= MAX_ENTRIES, "more than {MAX_ENTRIES} entries");
+        }
+    }
+    Ok(entries)
+}
+
+/// Writes entries below `dest`, enforcing the byte budget and refusing to
+/// follow symlinks that already exist there.
+struct Extractor {
+    /// Canonical extraction directory
+    dest: PathBuf,
+    overwrite: bool,
+    files: usize,
+    written: u64,
+}
+
+impl Extractor {
+    fn create_dir(&self, name: &str) -> anyhow::Result<()> {
+        let relative = safe_entry_path(name)
+            .ok_or_else(|| anyhow::anyhow!("entry '{name}' escapes the extraction directory"))?;
+        let dir = self.dest.join(relative);
+        fs::create_dir_all(&dir)?;
+        anyhow::ensure!(
+            fs::canonicalize(&dir)?.starts_with(&self.dest),
+            "entry '{name}' resolves outside the extraction directory"
+        );
+        Ok(())
+    }
+
+    fn write_file(&mut self, name: &str, reader: &mut dyn Read) -> anyhow::Result<()> {
+        let relative = safe_entry_path(name)
+            .ok_or_else(|| anyhow::anyhow!("entry '{name}' escapes the extraction directory"))?;
+        let target = self.dest.join(&relative);
+        let (Some(parent), Some(file_name)) = (target.parent(), target.file_name()) else {
+            anyhow::bail!("entry '{name}' has no file name");
+        };
+        fs::create_dir_all(parent)?;
+        let parent = fs::canonicalize(parent)?;
+        anyhow::ensure!(
+            parent.starts_with(&self.dest),
+            "entry '{name}' resolves outside the extraction directory"
+        );
+        let target = parent.join(file_name);
+        if let Ok(meta) = fs::symlink_metadata(&target) {
+            anyhow::ensure!(!meta.file_type().is_symlink(), "refusing to write through symlink '{name}'");
+            anyhow::ensure!(self.overwrite, "'{}' already exists (set overwrite to replace it)", relative.display());
+        }
+
+        let remaining = MAX_TOTAL_BYTES - self.written;
+        let mut file = File::create(&target)?;
+        let copied = io::copy(&mut reader.take(remaining + 1), &mut file)?;
+        if copied > remaining {
+            drop(file);
+            let _ = fs::remove_file(&target);
+            anyhow::bail!("extracted data exceeds {} MB", MAX_TOTAL_BYTES / 1024 / 1024);
+        }
+        self.written += copied;
+        self.files += 1;
+        Ok(())
+    }
+}
+
+fn extract(path: &Path, format: Format, extractor: &mut Extractor) -> anyhow::Result<()> {
+    if format == Format::Zip {
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let name = file.name().to_string();
+            if file.is_symlink() {
+                continue;
+            }
+            if file.is_dir() {
+                extractor.create_dir(&name)?;
+            } else {
+                extractor.write_file(&name, &mut file)?;
+            }
+        }
+    } else {
+        let mut archive = tar_reader(path, format)?;
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            match entry.header().entry_type() {
+                tar::EntryType::Directory => extractor.create_dir(&name)?,
+                tar::EntryType::Regular | tar::EntryType::Continuous => {
+                    extractor.write_file(&name, &mut entry)?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Regular files under `path` (recursively), skipping symlinks.
+fn collect_files(path: &Path, out: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if meta.is_file() {
+        out.push(path.to_path_buf());
+    } else if meta.is_dir() {
+        let mut children: Vec<PathBuf> = fs::read_dir(path)?
+            .map(|e| e.map(|e| e.path()))
+            .collect::<io::Result<_>>()?;
+        children.sort();
+        for child in children {
+            collect_files(&child, out)?;
+        }
+    }
+    anyhow::ensure!(out.len() <= MAX_ENTRIES, "more than {MAX_ENTRIES} files");
+    Ok(())
+}
+
+/// Write `files` (with their archive names) to `path`. Returns the input bytes.
+fn create(path: &Path, format: Format, files: &[(PathBuf, String)]) -> anyhow::Result<u64> {
+    let total: u64 = files
+        .iter()
+        .map(|(file, _)| fs::metadata(file).map(|m| m.len()))
+        .sum::<io::Result<u64>>()?;
+    anyhow::ensure!(
+        total <= MAX_TOTAL_BYTES,
+        "the sources are larger than {} MB",
+        MAX_TOTAL_BYTES / 1024 / 1024
+    );
+
+    let out = File::create(path)?;
+    match format {
+        Format::Zip => {
+            let mut zip = zip::ZipWriter::new(out);
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated);
+            for (file, name) in files {
+                zip.start_file(name.as_str(), options)?;
+                io::copy(&mut File::open(file)?, &mut zip)?;
+            }
+            zip.finish()?;
+        }
+        Format::Tar => {
+            let mut tar = tar::Builder::new(out);
+            for (file, name) in files {
+                tar.append_path_with_name(file, name)?;
+            }
+            tar.into_inner()?;
+        }
+        Format::TarGz => {
+            let gz = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+            let mut tar = tar::Builder::new(gz);
+            for (file, name) in files {
+                tar.append_path_with_name(file, name)?;
+            }
+            tar.into_inner()?.finish()?;
+        }
+    }
+    Ok(total)
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+impl ArchiveTool {
+    /// Resolve an existing workspace path, rejecting anything outside it.
+    async fn resolve_existing(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve '{path}': {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(format!("Resolved path escapes workspace: {}", resolved.display()));
+        }
+        Ok(resolved)
+    }
+
+    /// Create `path` as a directory if needed and resolve it.
+    async fn resolve_dir(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let full = self.security.workspace_dir.join(path);
+        tokio::fs::create_dir_all(&full)
+            .await
+            .map_err(|e| format!("Failed to create '{path}': {e}"))?;
+        self.resolve_existing(path).await
+    }
+
+    async fn list(&self, archive: &str, format: Format) -> ToolResult {
+        let resolved = match self.resolve_existing(archive).await {
+            Ok(p) => p,
+            Err(e) => return failure(e),
+        };
+        let entries = match tokio::task::spawn_blocking(move || list_entries(&resolved, format)).await {
+            Ok(Ok(entries)) => entries,
+            Ok(Err(e)) => return failure(format!("Failed to read archive: {e}")),
+            Err(e) => return failure(format!("Archive task failed: {e}")),
+        };
+
+        let total: u64 = entries.iter().map(|e| e.size).sum();
+        let mut output = format!("{archive}: {} entries, {total} bytes uncompressed\n", entries.len());
+        for entry in entries.iter().take(MAX_LISTED) {
+            let marker = match entry.kind {
+                EntryKind::File => "",
+                EntryKind::Dir => " (dir)",
+                EntryKind::Link => " (link, not extracted)",
+            };
+            let unsafe_marker = if safe_entry_path(&entry.name).is_none() {
+                " (unsafe path)"
+            } else {
+                ""
+            };
+            let _ = writeln!(output, "{}  {}{marker}{unsafe_marker}", entry.size, entry.name);
+        }
+        if entries.len() > MAX_LISTED {
+            let _ = writeln!(output, "… and {} more", entries.len() - MAX_LISTED);
+        }
+        ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: None,
+        }
+    }
+
+    async fn extract(&self, archive: &str, format: Format, args: &serde_json::Value) -> ToolResult {
+        let destination = args
+            .get("destination")
+            .and_then(|v| v.as_str())
+            .map_or_else(|| format.strip_extension(archive), String::from);
+        let overwrite = args.get("overwrite").and_then(serde_json::Value::as_bool).unwrap_or(false);
+
+        let source = match self.resolve_existing(archive).await {
+            Ok(p) => p,
+            Err(e) => return failure(e),
+        };
+        if !self.security.is_path_allowed(&destination) {
+            return failure(format!("Path not allowed by security policy: {destination}"));
+        }
+        if !self.security.record_action() {
+            return failure("Rate limit exceeded: action budget exhausted".into());
+        }
+        let dest = match self.resolve_dir(&destination).await {
+            Ok(p) => p,
+            Err(e) => return failure(e),
+        };
+
+        let result = tokio::task::spawn_blocking(move || {
+            // Validate every entry before writing the first one.
+            let entries = list_entries(&source, format)?;
+            if let Some(bad) = entries.iter().find(|e| safe_entry_path(&e.name).is_none() && e.kind != EntryKind::Link) {
+                anyhow::bail!("entry '{}' escapes the extraction directory; nothing was extracted", bad.name);
+            }
+            let mut extractor = Extractor {
+                dest,
+                overwrite,
+                files: 0,
+                written: 0,
+            };
+            extract(&source, format, &mut extractor)?;
+            let links = entries.iter().filter(|e| e.kind == EntryKind::Link).count();
+            Ok((extractor.files, extractor.written, links))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((files, bytes, links))) => {
+                let mut output = format!("Extracted {files} files ({bytes} bytes) to {destination}");
+                if links > 0 {
+                    let _ = write!(output, "; skipped {links} link(s)");
+                }
+                ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                    data: None,
+                }
+            }
+            Ok(Err(e)) => failure(format!("Extraction stopped: {e}")),
+            Err(e) => failure(format!("Archive task failed: {e}")),
+        }
+    }
+
+    async fn create(&self, archive: &str, format: Format, args: &serde_json::Value) -> ToolResult {
+        let sources: Vec<&str> = args
+            .get("sources")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        if sources.is_empty() {
+            return failure("'create' needs at least one path in 'sources'".into());
+        }
+        let overwrite = args.get("overwrite").and_then(serde_json::Value::as_bool).unwrap_or(false);
+
+        let workspace = match tokio::fs::canonicalize(&self.security.workspace_dir).await {
+            Ok(p) => p,
+            Err(e) => return failure(format!("Failed to resolve workspace: {e}")),
+        };
+        let mut roots = Vec::with_capacity(sources.len());
+        for source in sources {
+            match self.resolve_existing(source).await {
+                Ok(p) => roots.push(p),
+                Err(e) => return failure(e),
+            }
+        }
+
+        if !self.security.is_path_allowed(archive) {
+            return failure(format!("Path not allowed by security policy: {archive}"));
+        }
+        let archive_path = Path::new(archive);
+        let Some(file_name) = archive_path.file_name() else {
+            return failure("Invalid archive path: missing file name".into());
+        };
+        let parent = match archive_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            Some(dir) => match self.resolve_dir(&dir.to_string_lossy()).await {
+                Ok(p) => p,
+                Err(e) => return failure(e),
+            },
+            None => workspace.clone(),
+        };
+        let target = parent.join(file_name);
+        if let Ok(meta) = tokio::fs::symlink_metadata(&target).await {
+            if meta.file_type().is_symlink() {
+                return failure(format!("Refusing to write through symlink: {archive}"));
+            }
+            if !overwrite {
+                return failure(format!("{archive} already exists (set overwrite to replace it)"));
+            }
+        }
+        if !self.security.record_action() {
+            return failure("Rate limit exceeded: action budget exhausted".into());
+        }
+
+        let output_path = target.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut paths = Vec::new();
+            for root in &roots {
+                collect_files(root, &mut paths)?;
+            }
+            let files: Vec<(PathBuf, String)> = paths
+                .into_iter()
+                .filter(|p| *p != output_path)
+                .filter_map(|p| {
+                    let name = p.strip_prefix(&workspace).ok()?.to_string_lossy().replace('\\', "/");
+                    Some((p, name))
+                })
+                .collect();
+            let bytes = create(&output_path, format, &files).inspect_err(|_| {
+                let _ = fs::remove_file(&output_path);
+            })?;
+            Ok::<_, anyhow::Error>((files.len(), bytes))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((files, bytes))) => ToolResult {
+                success: true,
+                output: format!("Created {archive} with {files} files ({bytes} bytes before compression)"),
+                error: None,
+                data: None,
+            },
+            Ok(Err(e)) => failure(format!("Failed to create archive: {e}")),
+            Err(e) => failure(format!("Archive task failed: {e}")),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ArchiveTool {
+    fn name(&self) -> &str {
+        "archive"
+    }
+
+    fn description(&self) -> &str {
+        "Create, list or extract zip and tar.gz archives in the workspace. Extraction rejects entries \
+         that would land outside the destination, skips links and stops at 1 GB."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["create", "list", "extract"]
+                },
+                "archive": {
+                    "type": "string",
+                    "description": "Archive path relative to the workspace (.zip, .tar, .tar.gz or .tgz)"
+                },
+                "sources": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "For create: files or directories to pack, relative to the workspace"
+                },
+                "destination": {
+                    "type": "string",
+                    "description": "For extract: target directory (default: the archive path without extension)"
+                },
+                "overwrite": {
+                    "type": "boolean",
+                    "description": "Replace existing files (default false)"
+                }
+            },
+            "required": ["action", "archive"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let archive = args
+            .get("archive")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'archive' parameter"))?;
+        let Some(format) = Format::from_path(archive) else {
+            return Ok(failure(format!(
+                "Unsupported archive type: {archive} (use .zip, .tar, .tar.gz or .tgz)"
+            )));
+        };
+
+        if action != "list" {
+            if !self.security.can_act() {
+                return Ok(failure("Action blocked: autonomy is read-only".into()));
+            }
+            if self.security.is_rate_limited() {
+                return Ok(failure("Rate limit exceeded: too many actions in the last hour".into()));
+            }
+        }
+
+        Ok(match action {
+            "list" => self.list(archive, format).await,
+            "extract" => self.extract(archive, format, &args).await,
+            "create" => self.create(archive, format, &args).await,
+            other => failure(format!("Unknown action '{other}' (use create, list or extract)")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn tool(workspace: &Path) -> ArchiveTool {
+        ArchiveTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[test]
+    fn safe_entry_path_rejects_escapes() {
+        assert_eq!(safe_entry_path("a/./b.txt"), Some(PathBuf::from("a/b.txt")));
+        assert_eq!(safe_entry_path("../evil"), None);
+        assert_eq!(safe_entry_path("a/../../evil"), None);
+        assert_eq!(safe_entry_path("/etc/passwd"), None);
+        assert_eq!(safe_entry_path("..\\evil"), None);
+        assert_eq!(safe_entry_path("./"), None);
+    }
+
+    #[tokio::test]
+    async fn create_list_and_extract_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("data/nested")).unwrap();
+        fs::write(dir.path().join("data/a.txt"), "alpha").unwrap();
+        fs::write(dir.path().join("data/nested/b.csv"), "1,2,3").unwrap();
+        let tool = tool(dir.path());
+
+        for archive in ["out/data.zip", "out/data.tar.gz"] {
+            let created = tool
+                .execute(json!({"action": "create", "archive": archive, "sources": ["data"]}))
+                .await
+                .unwrap();
+            assert!(created.success, "{:?}", created.error);
+            assert!(created.output.contains("2 files"));
+
+            let listed = tool.execute(json!({"action": "list", "archive": archive})).await.unwrap();
+            assert!(listed.output.contains("data/nested/b.csv"));
+
+            let extracted = tool
+                .execute(json!({"action": "extract", "archive": archive, "destination": "restored"}))
+                .await
+                .unwrap();
+            assert!(extracted.success, "{:?}", extracted.error);
+            let restored = fs::read_to_string(dir.path().join("restored/data/nested/b.csv")).unwrap();
+            assert_eq!(restored, "1,2,3");
+
+            // Existing files are kept unless overwrite is set.
+            let again = tool
+                .execute(json!({"action": "extract", "archive": archive, "destination": "restored"}))
+                .await
+                .unwrap();
+            assert!(again.error.unwrap().contains("already exists"));
+            fs::remove_dir_all(dir.path().join("restored")).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn extract_rejects_traversal_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut builder = tar::Builder::new(File::create(dir.path().join("evil.tar")).unwrap());
+        for (name, data) in [("ok.txt", &b"fine"[..]), ("../escaped.txt", &b"gotcha"[..])] {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder.append(&header, data).unwrap();
+        }
+        builder.into_inner().unwrap();
+
+        let result = tool(dir.path())
+            .execute(json!({"action": "extract", "archive": "evil.tar", "destination": "out"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("escapes the extraction directory"));
+        assert!(!dir.path().join("escaped.txt").exists());
+        assert!(!dir.path().join("out/ok.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn create_requires_write_autonomy() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "alpha").unwrap();
+        let tool = ArchiveTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({"action": "create", "archive": "a.zip", "sources": ["a.txt"]}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
@@ -515,6 +515,7 @@ impl BrowserTool {
                 success: true,
                 output: serde_json::to_string_pretty(&output).unwrap_or_default(),
                 error: None,
+                data: None,
             })
         }
 
@@ -549,12 +550,14 @@ impl BrowserTool {
                 success: true,
                 output,
                 error: None,
+                data: None,
             })
         } else {
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: resp.error,
+                data: None,
             })
         }
     }
@@ -664,6 +667,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                data: None,
             });
         }
 
@@ -672,6 +676,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                data: None,
             });
         }
 
@@ -682,6 +687,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(error.to_string()),
+                    data: None,
                 });
             }
         };
@@ -844,6 +850,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Unknown action: {action_str}")),
+                    data: None,
                 });
             }
         };
@@ -30,7 +30,7 @@ impl ComposioTool {
     pub fn new(api_key: &str) -> Self {
         Self {
             api_key: api_key.to_string(),
-            client: Client::builder()
+            client: crate::network::egress::client_builder(None)
                 .timeout(std::time::Duration::from_secs(60))
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
@@ -443,12 +443,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            data: None,
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to list actions: {e}")),
+                        data: None,
                     }),
                 }
             }
@@ -475,12 +477,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            data: None,
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Action execution failed: {e}")),
+                        data: None,
                     }),
                 }
             }
@@ -504,12 +508,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output: format!("Open this URL to connect {target}:\n{url}"),
                             error: None,
+                            data: None,
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to get connection URL: {e}")),
+                        data: None,
                     }),
                 }
             }
@@ -520,6 +526,7 @@ impl Tool for ComposioTool {
                 error: Some(format!(
                     "Unknown action '{action}'. Use 'list', 'execute', or 'connect'."
                 )),
+                data: None,
             }),
         }
     }
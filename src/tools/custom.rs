@@ -0,0 +1,441 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! User-defined declarative tools.
+//!
+//! Each `.toml` file in `<workspace>/custom_tools/` defines one tool whose
+//! action is an HTTP call or a shell command with `{{arg}}` placeholders:
+//!
+//! ```toml
+//! name = "weather"
+//! description = "Current weather for a city"
+//!
+//! [parameters]
+//! type = "object"
+//! required = ["city"]
+//! properties.city = { type = "string", description = "City name" }
+//!
+//! [action]
+//! kind = "http"
+//! url = "https://wttr.in/{{city}}?format=3"
+//! ```
+//!
+//! Actions run through the built-in `http_request` and `shell` tools, so the
+//! domain allowlist, command allowlist and resource limits still apply.
+//! Arguments are percent-encoded in URLs and single-quoted in commands.
+
+use super::traits::{Tool, ToolResult};
+use super::{HttpRequestTool, ShellTool};
+use crate::config::HttpRequestConfig;
+use crate::runtime::RuntimeAdapter;
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// What a custom tool does when called.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CustomAction {
+    Http {
+        url: String,
+        #[serde(default = "default_method")]
+        method: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default)]
+        body: Option<String>,
+    },
+    Shell {
+        command: String,
+    },
+}
+
+fn default_method() -> String {
+    "GET".into()
+}
+
+/// One tool definition file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomToolDef {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments (default: no arguments)
+    #[serde(default = "default_parameters")]
+    pub parameters: serde_json::Value,
+    pub action: CustomAction,
+}
+
+fn default_parameters() -> serde_json::Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+impl CustomToolDef {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            bail!(
+                "Tool name '{}' must be lowercase letters, digits and '_'",
+                self.name
+            );
+        }
+        if self.description.trim().is_empty() {
+            bail!("Tool '{}' needs a description", self.name);
+        }
+        if !self.parameters.is_object() {
+            bail!(
+                "Tool '{}': parameters must be a JSON schema object",
+                self.name
+            );
+        }
+        Ok(())
+    }
+}
+
+pub fn custom_tools_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("custom_tools")
+}
+
+fn parse_def(path: &Path) -> Result<CustomToolDef> {
+    let raw = std::fs::read_to_string(path)?;
+    let def: CustomToolDef = toml::from_str(&raw)?;
+    def.validate()?;
+    Ok(def)
+}
+
+/// Load every valid definition from the workspace, sorted by name. Invalid
+/// files are skipped with a warning.
+pub fn load_custom_tools(workspace_dir: &Path) -> Vec<CustomToolDef> {
+    let Ok(entries) = std::fs::read_dir(custom_tools_dir(workspace_dir)) else {
+        return Vec::new();
+    };
+    let mut defs: Vec<CustomToolDef> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => true,
+            Some("yaml" | "yml") => {
+                tracing::warn!(
+                    "Skipping custom tool {}: YAML definitions are no longer read, convert it to TOML",
+                    path.display()
+                );
+                false
+            }
+            _ => false,
+        })
+        .filter_map(|path| match parse_def(&path) {
+            Ok(def) => Some(def),
+            Err(e) => {
+                tracing::warn!("Skipping custom tool {}: {e:#}", path.display());
+                None
+            }
+        })
+        .collect();
+    defs.sort_by(|a, b| a.name.cmp(&b.name));
+    defs
+}
+
+// ── Templates ───────────────────────────────────────────────────
+
+/// Text of argument `name`; strings are used as-is, other values as JSON.
+fn arg_text(args: &serde_json::Value, name: &str) -> String {
+    match args.get(name) {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Replace each `{{name}}` in `template` with `encode(argument)`.
+fn render(
+    template: &str,
+    args: &serde_json::Value,
+    encode: impl Fn(&str) -> String,
+) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .with_context(|| format!("Unclosed placeholder in template: {template}"))?;
+        out.push_str(&encode(&arg_text(args, after[..end].trim())));
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+// ── Tool ────────────────────────────────────────────────────────
+
+/// A declarative tool running its action through a built-in tool.
+pub struct CustomTool {
+    def: CustomToolDef,
+    backend: Box<dyn Tool>,
+}
+
+impl CustomTool {
+    /// Translate the call into arguments for the backing tool.
+    fn backend_args(&self, args: &serde_json::Value) -> Result<serde_json::Value> {
+        let required = self
+            .def
+            .parameters
+            .get("required")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str());
+        for name in required {
+            if args.get(name).is_none_or(serde_json::Value::is_null) {
+                bail!("Missing '{name}' parameter");
+            }
+        }
+
+        match &self.def.action {
+            CustomAction::Http {
+                url,
+                method,
+                headers,
+                body,
+            } => {
+                let mut rendered = serde_json::Map::new();
+                for (key, value) in headers {
+                    rendered.insert(key.clone(), render(value, args, str::to_string)?.into());
+                }
+                let mut call = json!({
+                    "url": render(url, args, percent_encode)?,
+                    "method": method,
+                    "headers": rendered,
+                });
+                if let Some(body) = body {
+                    call["body"] = render(body, args, str::to_string)?.into();
+                }
+                Ok(call)
+            }
+            CustomAction::Shell { command } => Ok(json!({
+                "command": render(command, args, shell_quote)?,
+            })),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for CustomTool {
+    fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    fn description(&self) -> &str {
+        &self.def.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.def.parameters.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        match self.backend_args(&args) {
+            Ok(call) => self.backend.execute(call).await,
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+                data: None,
+            }),
+        }
+    }
+}
+
+/// Build tools from `defs`. Definitions named like a tool in `taken` are
+/// skipped so built-ins cannot be shadowed.
+pub fn custom_tools(
+    defs: Vec<CustomToolDef>,
+    taken: &[&str],
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn RuntimeAdapter>,
+    http_config: &HttpRequestConfig,
+) -> Vec<Box<dyn Tool>> {
+    defs.into_iter()
+        .filter(|def| {
+            let clash = taken.contains(&def.name.as_str());
+            if clash {
+                tracing::warn!("Custom tool '{}' clashes with a built-in tool", def.name);
+            }
+            !clash
+        })
+        .map(|def| {
+            let backend: Box<dyn Tool> = match def.action {
+                CustomAction::Http { .. } => Box::new(HttpRequestTool::new(
+                    security.clone(),
+                    http_config.allowed_domains.clone(),
+                    http_config.max_response_size,
+                    http_config.timeout_secs,
+                )),
+                CustomAction::Shell { .. } => {
+                    Box::new(ShellTool::new(security.clone(), runtime.clone()))
+                }
+            };
+            Box::new(CustomTool { def, backend }) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echoes the arguments it receives.
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "echo"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: args.to_string(),
+                error: None,
+                data: Some(args),
+            })
+        }
+    }
+
+    const WEATHER: &str = r#"
+name = "weather"
+description = "Current weather for a city"
+
+[parameters]
+type = "object"
+required = ["city"]
+properties.city = { type = "string" }
+
+[action]
+kind = "http"
+url = "https://wttr.in/{{city}}?format=3"
+headers = { X-City = "{{ city }}" }
+"#;
+
+    fn tool(def: CustomToolDef) -> CustomTool {
+        CustomTool {
+            def,
+            backend: Box::new(EchoTool),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_action_encodes_url_arguments() {
+        let def: CustomToolDef = toml::from_str(WEATHER).unwrap();
+        def.validate().unwrap();
+        let weather = tool(def);
+
+        let result = weather.execute(json!({"city": "São Paulo"})).await.unwrap();
+        let call = result.data.unwrap();
+        assert_eq!(call["url"], "https://wttr.in/S%C3%A3o%20Paulo?format=3");
+        assert_eq!(call["method"], "GET");
+        assert_eq!(call["headers"]["X-City"], "São Paulo");
+
+        let missing = weather.execute(json!({})).await.unwrap();
+        assert!(!missing.success);
+        assert!(missing.error.unwrap().contains("city"));
+    }
+
+    #[tokio::test]
+    async fn shell_action_quotes_arguments() {
+        let def: CustomToolDef = toml::from_str(
+            r#"
+name = "count_lines"
+description = "Count lines in a file"
+[action]
+kind = "shell"
+command = "wc -l {{path}}"
+"#,
+        )
+        .unwrap();
+        let result = tool(def)
+            .execute(json!({"path": "it's $(rm -rf /)"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.data.unwrap()["command"],
+            r"wc -l 'it'\''s $(rm -rf /)'"
+        );
+    }
+
+    #[test]
+    fn loads_valid_files_and_skips_clashes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = custom_tools_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("weather.toml"), WEATHER).unwrap();
+        std::fs::write(dir.join("broken.toml"), "name = [").unwrap();
+        std::fs::write(
+            dir.join("shell.toml"),
+            "name = \"shell\"\ndescription = \"x\"\naction = { kind = \"shell\", command = \"ls\" }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("legacy.yaml"), "name: legacy\ndescription: x\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let defs = load_custom_tools(tmp.path());
+        assert_eq!(
+            defs.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(),
+            vec!["shell", "weather"]
+        );
+
+        let security = Arc::new(SecurityPolicy::default());
+        let runtime: Arc<dyn RuntimeAdapter> = Arc::new(crate::runtime::NativeRuntime::new());
+        let tools = custom_tools(
+            defs,
+            &["shell"],
+            &security,
+            &runtime,
+            &HttpRequestConfig::default(),
+        );
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "weather");
+    }
+
+    #[test]
+    fn rejects_bad_names() {
+        let mut def: CustomToolDef = toml::from_str(WEATHER).unwrap();
+        def.name = "Weather Now".into();
+        assert!(def.validate().is_err());
+    }
+}
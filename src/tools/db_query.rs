@@ -0,0 +1,828 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! `db_query` — SQL against the databases named in `[db_query.connections]`.
+//!
+//! SQLite files must live in the workspace. Postgres DSNs need the
+//! `db-postgres` feature. In read-only mode (the default) SQLite files are
+//! opened read-only and only read-only statements are prepared; Postgres
+//! queries run inside a `READ ONLY` transaction that is always rolled back.
+//!
+//! Statements that reach other files are refused in every mode: SQLite
+//! connections may attach no databases, and `ATTACH`, `DETACH`, `VACUUM` and
+//! `COPY` are rejected by their first keyword, read past comments.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::DbQueryConfig;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use rusqlite::limits::Limit;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where a named connection points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    /// Workspace-relative SQLite file.
+    Sqlite(String),
+    Postgres(String),
+}
+
+impl Target {
+    /// `sqlite:path`, a bare `*.db`/`*.sqlite` path, or `postgres://…`.
+    fn parse(connection: &str) -> Option<Self> {
+        let connection = connection.trim();
+        if connection.starts_with("postgres://") || connection.starts_with("postgresql://") {
+            return Some(Self::Postgres(connection.to_string()));
+        }
+        if let Some(path) = connection.strip_prefix("sqlite:") {
+            let path = path.trim_start_matches("//");
+            return (!path.is_empty()).then(|| Self::Sqlite(path.to_string()));
+        }
+        let lower = connection.to_ascii_lowercase();
+        [".db", ".sqlite", ".sqlite3"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+            .then(|| Self::Sqlite(connection.to_string()))
+    }
+}
+
+/// Result rows, cut off at the row and size limits.
+#[derive(Debug, Default)]
+struct QueryOutput {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    /// Rows changed by a write statement.
+    affected: Option<u64>,
+    truncated: bool,
+}
+
+impl QueryOutput {
+    fn to_json(&self) -> Value {
+        let mut out = json!({
+            "columns": self.columns,
+            "rows": self.rows,
+            "row_count": self.rows.len(),
+            "truncated": self.truncated,
+        });
+        if let Some(affected) = self.affected {
+            out["affected_rows"] = json!(affected);
+        }
+        out
+    }
+}
+
+/// Row and size limits applied while rows are collected.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_rows: usize,
+    max_bytes: usize,
+}
+
+impl Limits {
+    /// Add `row` unless a limit is reached; returns `false` when collection
+    /// should stop.
+    fn push(&self, output: &mut QueryOutput, bytes: &mut usize, row: Vec<Value>) -> bool {
+        if output.rows.len() >= self.max_rows {
+            output.truncated = true;
+            return false;
+        }
+        *bytes += serde_json::to_string(&row).map_or(0, |s| s.len());
+        if *bytes > self.max_bytes {
+            output.truncated = true;
+            return false;
+        }
+        output.rows.push(row);
+        true
+    }
+}
+
+/// Reject SQL with more than one statement. Semicolons inside quotes and
+/// comments are ignored; a trailing one is fine.
+fn single_statement(sql: &str) -> Result<(), String> {
+    let mut chars = sql.chars().peekable();
+    let mut ended = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                for q in chars.by_ref() {
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for q in chars.by_ref() {
+                    if q == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for q in chars.by_ref() {
+                    if prev == '*' && q == '/' {
+                        break;
+                    }
+                    prev = q;
+                }
+            }
+            ';' => ended = true,
+            c if ended && !c.is_whitespace() => {
+                return Err("Only one SQL statement per call is allowed".into());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Statements refused in every mode: they reach other files.
+///
+/// Postgres nests block comments and SQLite does not, so the statement is
+/// refused if either reading starts with a blocked keyword.
+fn blocked_keyword(sql: &str) -> Option<&'static str> {
+    [false, true].into_iter().find_map(|nested| {
+        let first = first_keyword(sql, nested);
+        ["ATTACH", "DETACH", "VACUUM", "COPY"]
+            .into_iter()
+            .find(|k| *k == first)
+    })
+}
+
+/// First keyword of `sql`, upper-cased, after whitespace, parentheses and
+/// comments.
+fn first_keyword(sql: &str, nested: bool) -> String {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = after_block_comment(comment, nested);
+        } else {
+            break;
+        }
+    }
+    rest.split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase()
+}
+
+/// Text after the block comment whose `/*` precedes `rest`.
+fn after_block_comment(mut rest: &str, nested: bool) -> &str {
+    let mut depth = 1;
+    while depth > 0 {
+        let open = if nested { rest.find("/*") } else { None };
+        match (open, rest.find("*/")) {
+            (Some(open), Some(close)) if open < close => {
+                depth += 1;
+                rest = &rest[open + 2..];
+            }
+            (_, Some(close)) => {
+                depth -= 1;
+                rest = &rest[close + 2..];
+            }
+            (_, None) => return "",
+        }
+    }
+    rest
+}
+
+fn sqlite_param(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn sqlite_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(t) => json!(String::from_utf8_lossy(t)),
+        ValueRef::Blob(b) => json!(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+/// Run one statement on an open SQLite connection (blocking).
+fn run_sqlite(
+    conn: &Connection,
+    sql: &str,
+    params: &[Value],
+    read_only: bool,
+    limits: Limits,
+) -> Result<QueryOutput, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| format!("SQL error: {e}"))?;
+    if read_only && !stmt.readonly() {
+        return Err("Read-only mode: only SELECT and other read-only statements are allowed".into());
+    }
+    if stmt.parameter_count() != params.len() {
+        return Err(format!(
+            "Statement expects {} parameter(s), got {}",
+            stmt.parameter_count(),
+            params.len()
+        ));
+    }
+    let values: Vec<SqlValue> = params.iter().map(sqlite_param).collect();
+    let mut output = QueryOutput {
+        columns: stmt.column_names().into_iter().map(String::from).collect(),
+        ..QueryOutput::default()
+    };
+
+    if output.columns.is_empty() {
+        let changed = stmt
+            .execute(rusqlite::params_from_iter(values.iter()))
+            .map_err(|e| format!("SQL error: {e}"))?;
+        output.affected = Some(changed as u64);
+        return Ok(output);
+    }
+
+    let mut rows = stmt
+        .query(rusqlite::params_from_iter(values.iter()))
+        .map_err(|e| format!("SQL error: {e}"))?;
+    let mut bytes = 0;
+    while let Some(row) = rows.next().map_err(|e| format!("SQL error: {e}"))? {
+        let values = (0..output.columns.len())
+            .map(|i| row.get_ref(i).map(sqlite_value))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("SQL error: {e}"))?;
+        if !limits.push(&mut output, &mut bytes, values) {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(feature = "db-postgres")]
+mod postgres {
+    use super::{Limits, QueryOutput};
+    use futures_util::{pin_mut, TryStreamExt};
+    use serde_json::{json, Value};
+    use tokio_postgres::types::{ToSql, Type};
+    use tokio_postgres::{NoTls, Row};
+
+    type Param = Box<dyn ToSql + Sync + Send>;
+
+    /// Convert a JSON argument to the type Postgres inferred for `$n`.
+    fn param(value: &Value, ty: &Type) -> Result<Param, String> {
+        let mismatch = || format!("Parameter {value} does not fit type {ty}");
+        let boxed: Param = match *ty {
+            Type::BOOL => Box::new(match value {
+                Value::Null => None,
+                v => Some(v.as_bool().ok_or_else(mismatch)?),
+            }),
+            Type::INT2 | Type::INT4 | Type::INT8 => {
+                let n = match value {
+                    Value::Null => None,
+                    v => Some(v.as_i64().ok_or_else(mismatch)?),
+                };
+                match *ty {
+                    Type::INT2 => Box::new(n.map(i16::try_from).transpose().map_err(|_| mismatch())?),
+                    Type::INT4 => Box::new(n.map(i32::try_from).transpose().map_err(|_| mismatch())?),
+                    _ => Box::new(n),
+                }
+            }
+            Type::FLOAT4 | Type::FLOAT8 => Box::new(match value {
+                Value::Null => None,
+                v => Some(v.as_f64().ok_or_else(mismatch)?),
+            }),
+            Type::JSON | Type::JSONB => Box::new(value.clone()),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => {
+                Box::new(match value {
+                    Value::Null => None,
+                    Value::String(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
+                })
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported parameter type {ty}; cast the placeholder, e.g. $1::text"
+                ))
+            }
+        };
+        Ok(boxed)
+    }
+
+    fn cell(row: &Row, i: usize, ty: &Type) -> Value {
+        fn get<'a, T: tokio_postgres::types::FromSql<'a> + serde::Serialize>(
+            row: &'a Row,
+            i: usize,
+        ) -> Value {
+            row.try_get::<_, Option<T>>(i)
+                .map_or(Value::Null, |v| json!(v))
+        }
+        match *ty {
+            Type::BOOL => get::<bool>(row, i),
+            Type::INT2 => get::<i16>(row, i),
+            Type::INT4 => get::<i32>(row, i),
+            Type::INT8 => get::<i64>(row, i),
+            Type::OID => get::<u32>(row, i),
+            Type::FLOAT4 => get::<f32>(row, i),
+            Type::FLOAT8 => get::<f64>(row, i),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => {
+                get::<String>(row, i)
+            }
+            Type::JSON | Type::JSONB => get::<Value>(row, i),
+            Type::TIMESTAMP => get::<chrono::NaiveDateTime>(row, i),
+            Type::TIMESTAMPTZ => get::<chrono::DateTime<chrono::Utc>>(row, i),
+            Type::DATE => get::<chrono::NaiveDate>(row, i),
+            _ => json!(format!("<{ty}: cast to text>")),
+        }
+    }
+
+    pub(super) async fn run(
+        dsn: &str,
+        sql: &str,
+        params: &[Value],
+        read_only: bool,
+        limits: Limits,
+        timeout_secs: u64,
+    ) -> Result<QueryOutput, String> {
+        let (mut client, connection) = tokio_postgres::connect(dsn, NoTls)
+            .await
+            .map_err(|e| format!("Postgres connection failed: {e}"))?;
+        let driver = tokio::spawn(connection);
+
+        let result = async {
+            let tx = client
+                .build_transaction()
+                .read_only(read_only)
+                .start()
+                .await
+                .map_err(|e| format!("Postgres error: {e}"))?;
+            tx.batch_execute(&format!("SET LOCAL statement_timeout = {}", timeout_secs * 1000))
+                .await
+                .map_err(|e| format!("Postgres error: {e}"))?;
+            let stmt = tx.prepare(sql).await.map_err(|e| format!("SQL error: {e}"))?;
+            if stmt.params().len() != params.len() {
+                return Err(format!(
+                    "Statement expects {} parameter(s), got {}",
+                    stmt.params().len(),
+                    params.len()
+                ));
+            }
+            let bound = params
+                .iter()
+                .zip(stmt.params())
+                .map(|(v, ty)| param(v, ty))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut output = QueryOutput {
+                columns: stmt.columns().iter().map(|c| c.name().to_string()).collect(),
+                ..QueryOutput::default()
+            };
+            let rows = tx
+                .query_raw(&stmt, bound.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)))
+                .await
+                .map_err(|e| format!("SQL error: {e}"))?;
+            pin_mut!(rows);
+            let mut bytes = 0;
+            while let Some(row) = rows.try_next().await.map_err(|e| format!("SQL error: {e}"))? {
+                let values = stmt
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| cell(&row, i, c.type_()))
+                    .collect();
+                if !limits.push(&mut output, &mut bytes, values) {
+                    break;
+                }
+            }
+            if output.columns.is_empty() {
+                output.affected = rows.rows_affected();
+            }
+            if read_only {
+                tx.rollback().await.map_err(|e| format!("Postgres error: {e}"))?;
+            } else {
+                tx.commit().await.map_err(|e| format!("Postgres error: {e}"))?;
+            }
+            Ok(output)
+        }
+        .await;
+
+        drop(client);
+        driver.abort();
+        result
+    }
+}
+
+/// Tool that runs SQL against configured SQLite and Postgres databases.
+pub struct DbQueryTool {
+    security: Arc<SecurityPolicy>,
+    connections: BTreeMap<String, Target>,
+    read_only: bool,
+    limits: Limits,
+    timeout: Duration,
+    description: String,
+}
+
+impl DbQueryTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: &DbQueryConfig) -> Self {
+        let mut connections = BTreeMap::new();
+        for (name, connection) in &config.connections {
+            match Target::parse(connection) {
+                Some(target) => {
+                    connections.insert(name.clone(), target);
+                }
+                None => tracing::warn!("db_query: ignoring unrecognised connection '{name}'"),
+            }
+        }
+        let names: Vec<&str> = connections.keys().map(String::as_str).collect();
+        let mode = if config.read_only { "read-only " } else { "" };
+        let description = format!(
+            "Run {mode}SQL against the user's databases and return rows as JSON. Use `?` (SQLite) \
+             or `$1` (Postgres) placeholders with `params` instead of inlining values. \
+             Databases: {}.",
+            if names.is_empty() { "none configured".to_string() } else { names.join(", ") }
+        );
+        Self {
+            security,
+            connections,
+            read_only: config.read_only,
+            limits: Limits {
+                max_rows: config.max_rows.max(1),
+                max_bytes: config.max_output_bytes.max(1024),
+            },
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+            description,
+        }
+    }
+
+    /// Resolve a workspace SQLite path, refusing anything outside it.
+    async fn sqlite_path(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve database path: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            ));
+        }
+        Ok(resolved)
+    }
+
+    async fn query_sqlite(
+        &self,
+        path: &str,
+        sql: String,
+        params: Vec<Value>,
+    ) -> Result<QueryOutput, String> {
+        let path = self.sqlite_path(path).await?;
+        let flags = if self.read_only {
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX
+        } else {
+            // Never create: only existing databases are queried.
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX
+        };
+        let conn = Connection::open_with_flags(&path, flags)
+            .map_err(|e| format!("Failed to open database: {e}"))?;
+        // ATTACH counts as read-only but opens any file on the host.
+        conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+        let interrupt = conn.get_interrupt_handle();
+        let (read_only, limits) = (self.read_only, self.limits);
+        let task =
+            tokio::task::spawn_blocking(move || run_sqlite(&conn, &sql, &params, read_only, limits));
+
+        match tokio::time::timeout(self.timeout, task).await {
+            Ok(joined) => joined.map_err(|e| format!("Query task failed: {e}"))?,
+            Err(_) => {
+                interrupt.interrupt();
+                Err(format!("Query timed out after {}s", self.timeout.as_secs()))
+            }
+        }
+    }
+
+    #[cfg(feature = "db-postgres")]
+    async fn query_postgres(
+        &self,
+        dsn: &str,
+        sql: &str,
+        params: &[Value],
+    ) -> Result<QueryOutput, String> {
+        let run = postgres::run(
+            dsn,
+            sql,
+            params,
+            self.read_only,
+            self.limits,
+            self.timeout.as_secs(),
+        );
+        // The server-side statement_timeout normally fires first.
+        tokio::time::timeout(self.timeout + Duration::from_secs(5), run)
+            .await
+            .unwrap_or_else(|_| Err(format!("Query timed out after {}s", self.timeout.as_secs())))
+    }
+
+    #[cfg(not(feature = "db-postgres"))]
+    #[allow(clippy::unused_async)]
+    async fn query_postgres(
+        &self,
+        _dsn: &str,
+        _sql: &str,
+        _params: &[Value],
+    ) -> Result<QueryOutput, String> {
+        Err("Postgres support is not compiled in (build with --features db-postgres)".into())
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+#[async_trait]
+impl Tool for DbQueryTool {
+    fn name(&self) -> &str {
+        "db_query"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "database": {
+                    "type": "string",
+                    "enum": self.connections.keys().collect::<Vec<_>>(),
+                    "description": "Name of the configured database"
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "A single SQL statement"
+                },
+                "params": {
+                    "type": "array",
+                    "description": "Values bound to the statement's placeholders, in order"
+                }
+            },
+            "required": ["database", "sql"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let database = args
+            .get("database")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'database' parameter"))?;
+        let sql = args
+            .get("sql")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'sql' parameter"))?;
+        let params = match args.get("params") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(items)) => items.clone(),
+            Some(_) => return Ok(failure("'params' must be an array".into())),
+        };
+
+        let Some(target) = self.connections.get(database) else {
+            return Ok(failure(format!(
+                "Unknown database '{database}'. Configured: {}",
+                self.connections.keys().cloned().collect::<Vec<_>>().join(", ")
+            )));
+        };
+        if let Err(e) = single_statement(sql) {
+            return Ok(failure(e));
+        }
+        if let Some(keyword) = blocked_keyword(sql) {
+            return Ok(failure(format!("{keyword} statements are not allowed")));
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+        if !self.read_only && !self.security.can_act() {
+            return Ok(failure(
+                "Action blocked: database writes require higher autonomy level".into(),
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted".into()));
+        }
+
+        let result = match target {
+            Target::Sqlite(path) => self.query_sqlite(path, sql.to_string(), params).await,
+            Target::Postgres(dsn) => self.query_postgres(dsn, sql, &params).await,
+        };
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => return Ok(failure(e)),
+        };
+
+        let data = output.to_json();
+        let mut text = serde_json::to_string_pretty(&data).unwrap_or_default();
+        if output.truncated {
+            text.push_str("\n... [result truncated; add LIMIT or select fewer columns]");
+        }
+        Ok(ToolResult {
+            success: true,
+            output: text,
+            error: None,
+            data: Some(data),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use std::collections::HashMap;
+
+    fn setup(read_only: bool) -> (tempfile::TempDir, DbQueryTool) {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = Connection::open(dir.path().join("shop.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price REAL, photo BLOB);
+             INSERT INTO items (name, price, photo) VALUES ('apple', 0.5, x'0102'), ('pear', 0.75, NULL), ('fig', 2.0, NULL);",
+        )
+        .unwrap();
+        let config = DbQueryConfig {
+            enabled: true,
+            connections: HashMap::from([
+                ("shop".to_string(), "sqlite:shop.db".to_string()),
+                ("outside".to_string(), "sqlite:../elsewhere.db".to_string()),
+            ]),
+            read_only,
+            max_rows: 2,
+            ..DbQueryConfig::default()
+        };
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = DbQueryTool::new(security, &config);
+        (dir, tool)
+    }
+
+    #[test]
+    fn parses_connection_strings() {
+        assert_eq!(Target::parse("sqlite:data/app.db"), Some(Target::Sqlite("data/app.db".into())));
+        assert_eq!(Target::parse("notes.sqlite3"), Some(Target::Sqlite("notes.sqlite3".into())));
+        assert_eq!(
+            Target::parse("postgres://reader@localhost/shop"),
+            Some(Target::Postgres("postgres://reader@localhost/shop".into()))
+        );
+        assert_eq!(Target::parse("mysql://localhost/db"), None);
+    }
+
+    #[test]
+    fn statement_checks() {
+        assert!(single_statement("SELECT 1;").is_ok());
+        assert!(single_statement("SELECT ';' -- ; trailing\n").is_ok());
+        assert!(single_statement("SELECT 1; DROP TABLE items").is_err());
+        assert_eq!(blocked_keyword("  attach 'x.db' AS x"), Some("ATTACH"));
+        assert_eq!(blocked_keyword("SELECT 1"), None);
+        assert_eq!(blocked_keyword("SELECT 1 -- ATTACH"), None);
+    }
+
+    #[test]
+    fn blocked_keywords_are_found_behind_comments() {
+        assert_eq!(
+            blocked_keyword("-- x\nATTACH '/home/u/cookies.sqlite' AS c"),
+            Some("ATTACH")
+        );
+        assert_eq!(
+            blocked_keyword("/**/COPY (SELECT 1) TO PROGRAM 'id'"),
+            Some("COPY")
+        );
+        // Postgres reads `/* /* */ SELECT */` as one comment.
+        assert_eq!(
+            blocked_keyword("/* /* */ SELECT */ COPY items TO PROGRAM 'id'"),
+            Some("COPY")
+        );
+        assert_eq!(
+            blocked_keyword("/* /* */ VACUUM INTO '/tmp/copy.db'"),
+            Some("VACUUM")
+        );
+    }
+
+    #[tokio::test]
+    async fn sqlite_connections_cannot_attach_databases() {
+        let (dir, tool) = setup(true);
+        Connection::open(dir.path().join("other.db")).unwrap();
+
+        let result = tool
+            .execute(json!({"database": "shop", "sql": "-- x\nATTACH 'other.db' AS o"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("ATTACH statements"));
+
+        // Even past the keyword check, SQLite refuses to attach.
+        let attach = format!("ATTACH '{}' AS o", dir.path().join("other.db").display());
+        let err = tool
+            .query_sqlite("shop.db", attach, Vec::new())
+            .await
+            .unwrap_err();
+        assert!(err.contains("too many attached databases"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn selects_with_params_and_row_limit() {
+        let (_dir, tool) = setup(true);
+
+        let result = tool
+            .execute(json!({
+                "database": "shop",
+                "sql": "SELECT name, price, photo FROM items WHERE price < ? ORDER BY id",
+                "params": [1.0]
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let data = result.data.unwrap();
+        assert_eq!(data["columns"], json!(["name", "price", "photo"]));
+        assert_eq!(data["rows"][0], json!(["apple", 0.5, "<blob 2 bytes>"]));
+        assert_eq!(data["truncated"], json!(false));
+
+        let all = tool
+            .execute(json!({"database": "shop", "sql": "SELECT * FROM items"}))
+            .await
+            .unwrap();
+        let data = all.data.unwrap();
+        assert_eq!(data["row_count"], json!(2));
+        assert_eq!(data["truncated"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn read_only_mode_rejects_writes_and_escapes() {
+        let (_dir, tool) = setup(true);
+
+        let result = tool
+            .execute(json!({"database": "shop", "sql": "DELETE FROM items"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Read-only mode"));
+
+        let result = tool
+            .execute(json!({"database": "shop", "sql": "SELECT 1; DELETE FROM items"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("one SQL statement"));
+
+        let result = tool
+            .execute(json!({"database": "outside", "sql": "SELECT 1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let result = tool
+            .execute(json!({"database": "nope", "sql": "SELECT 1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Unknown database"));
+    }
+
+    #[tokio::test]
+    async fn write_mode_reports_affected_rows() {
+        let (_dir, tool) = setup(false);
+
+        let result = tool
+            .execute(json!({
+                "database": "shop",
+                "sql": "UPDATE items SET price = price * 2 WHERE name = ?",
+                "params": ["fig"]
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.data.unwrap()["affected_rows"], json!(1));
+
+        let result = tool
+            .execute(json!({"database": "shop", "sql": "SELECT ?", "params": []}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("expects 1 parameter"));
+    }
+}
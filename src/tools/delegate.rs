@@ -5,13 +5,14 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::DelegateAgentConfig;
 use crate::memory::sovereign::SensitivityScanner;
+use crate::observability::{subagent, ObserverEvent};
 use crate::providers::{self, Provider};
 use crate::security::{AuditEvent, AuditEventType, AuditLogger};
 use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Default timeout for sub-agent provider calls.
 const DELEGATE_TIMEOUT_SECS: u64 = 120;
@@ -169,6 +170,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'agent' parameter must not be empty".into()),
+                data: None,
             });
         }
 
@@ -183,6 +185,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'prompt' parameter must not be empty".into()),
+                data: None,
             });
         }
 
@@ -209,6 +212,7 @@ impl Tool for DelegateTool {
                             available.join(", ")
                         }
                     )),
+                    data: None,
                 });
             }
         };
@@ -224,6 +228,7 @@ impl Tool for DelegateTool {
                     depth = self.depth,
                     max = agent_config.max_depth
                 )),
+                data: None,
             });
         }
 
@@ -244,6 +249,7 @@ impl Tool for DelegateTool {
                             "Failed to create provider '{}' for agent '{agent_name}': {e}",
                             agent_config.provider
                         )),
+                        data: None,
                     });
                 }
             };
@@ -270,6 +276,24 @@ impl Tool for DelegateTool {
 
         let temperature = agent_config.temperature.unwrap_or(0.7);
 
+        // Stream the sub-agent's progress to the parent run's observer
+        let started_at = Instant::now();
+        subagent::emit(
+            agent_name,
+            ObserverEvent::AgentStart {
+                provider: agent_config.provider.clone(),
+                model: agent_config.model.clone(),
+            },
+        );
+        subagent::emit(
+            agent_name,
+            ObserverEvent::LlmRequest {
+                provider: agent_config.provider.clone(),
+                model: agent_config.model.clone(),
+                messages_count: if agent_config.system_prompt.is_some() { 2 } else { 1 },
+            },
+        );
+
         // Wrap the provider call in a timeout to prevent indefinite blocking
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_TIMEOUT_SECS),
@@ -282,6 +306,29 @@ impl Tool for DelegateTool {
         )
         .await;
 
+        let error_message = match &result {
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => Some(providers::sanitize_api_error(&e.to_string())),
+            Err(_) => Some(format!("timed out after {DELEGATE_TIMEOUT_SECS}s")),
+        };
+        subagent::emit(
+            agent_name,
+            ObserverEvent::LlmResponse {
+                provider: agent_config.provider.clone(),
+                model: agent_config.model.clone(),
+                duration: started_at.elapsed(),
+                success: error_message.is_none(),
+                error_message,
+            },
+        );
+        subagent::emit(
+            agent_name,
+            ObserverEvent::AgentEnd {
+                duration: started_at.elapsed(),
+                tokens_used: None,
+            },
+        );
+
         let result = match result {
             Ok(inner) => inner,
             Err(_elapsed) => {
@@ -291,6 +338,7 @@ impl Tool for DelegateTool {
                     error: Some(format!(
                         "Agent '{agent_name}' timed out after {DELEGATE_TIMEOUT_SECS}s"
                     )),
+                    data: None,
                 });
             }
         };
@@ -329,12 +377,14 @@ impl Tool for DelegateTool {
                         model = agent_config.model
                     ),
                     error: None,
+                    data: None,
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Agent '{agent_name}' failed: {e}",)),
+                data: None,
             }),
         }
     }
@@ -525,4 +575,40 @@ mod tests {
                     .contains("Unknown agent")
         );
     }
+
+    #[tokio::test]
+    async fn sub_agent_progress_reaches_parent_observer() {
+        struct Recording(std::sync::Mutex<Vec<(String, ObserverEvent)>>);
+
+        impl crate::observability::Observer for Recording {
+            fn record_event(&self, event: &ObserverEvent) {
+                if let ObserverEvent::SubAgent { agent, event } = event {
+                    self.0.lock().unwrap().push((agent.clone(), (**event).clone()));
+                }
+            }
+
+            fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+            fn name(&self) -> &str {
+                "recording"
+            }
+        }
+
+        let tool = DelegateTool::new(sample_agents(), None, Arc::new(SensitivityScanner::new()), None);
+        let observer = Recording(std::sync::Mutex::new(Vec::new()));
+        subagent::forward_to(
+            &observer,
+            tool.execute(json!({"agent": "researcher", "prompt": "test"})),
+        )
+        .await
+        .unwrap();
+
+        let events = observer.0.lock().unwrap();
+        assert!(events.iter().all(|(agent, _)| agent == "researcher"));
+        assert!(matches!(events[0].1, ObserverEvent::AgentStart { .. }));
+        assert!(events
+            .iter()
+            .any(|(_, e)| matches!(e, ObserverEvent::LlmResponse { .. })));
+        assert!(matches!(events.last().unwrap().1, ObserverEvent::AgentEnd { .. }));
+    }
 }
@@ -0,0 +1,775 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Sovereign PIM — email tools over the user's own IMAP/SMTP account.
+//!
+//! `email_list` and `email_read` talk IMAP directly (read-only `EXAMINE`,
+//! `BODY.PEEK`, so nothing is marked as read); `email_send` goes out over
+//! SMTP and always asks the user through the [`ConfirmationGate`] first.
+//! The account is kept encrypted next to the PIM store and managed through
+//! `/api/pim/email`; no mail is routed through a third-party service.
+
+use crate::channels::email_channel::EmailChannel;
+use crate::security::confirmation::ConfirmationGate;
+use crate::security::secrets::SecretStore;
+use crate::tools::{Tool, ToolResult};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use mail_parser::{Address, MessageParser};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::rustls;
+
+const IMAP_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_FOLDER: &str = "INBOX";
+const DEFAULT_LIST_LIMIT: usize = 10;
+const MAX_LIST_LIMIT: usize = 50;
+/// Longest message body handed to the model.
+const MAX_BODY_CHARS: usize = 20_000;
+
+// ── Account ─────────────────────────────────────────────────────
+
+/// IMAP/SMTP credentials for the email tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailAccount {
+    pub imap_host: String,
+    /// IMAP over TLS (default: 993)
+    #[serde(default = "default_imap_port")]
+    pub imap_port: u16,
+    pub smtp_host: String,
+    /// SMTP submission port (default: 587 with STARTTLS)
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// Use TLS for SMTP (default: true)
+    #[serde(default = "default_true")]
+    pub smtp_tls: bool,
+    pub username: String,
+    pub password: String,
+    /// Sender address, e.g. `Jane Doe <jane@example.org>`
+    pub from_address: String,
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn account_path(workspace: &Path) -> PathBuf {
+    workspace.join(".mymolt").join("email_account.json")
+}
+
+impl EmailAccount {
+    /// The stored account, if one was set up.
+    pub fn load(workspace: &Path, secrets: Option<&SecretStore>) -> Option<Self> {
+        let raw = std::fs::read_to_string(account_path(workspace)).ok()?;
+        let json = match secrets {
+            Some(store) => store.decrypt(&raw).unwrap_or(raw),
+            None => raw,
+        };
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, workspace: &Path, secrets: Option<&SecretStore>) -> Result<()> {
+        let path = account_path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let data = match secrets {
+            Some(store) => store.encrypt(&json)?,
+            None => json,
+        };
+        std::fs::write(&path, data)?;
+        Ok(())
+    }
+
+    /// Remove the stored account. Returns whether one existed.
+    pub fn remove(workspace: &Path) -> Result<bool> {
+        match std::fs::remove_file(account_path(workspace)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.imap_host.trim().is_empty() || self.smtp_host.trim().is_empty() {
+            bail!("IMAP and SMTP hosts are required");
+        }
+        if self.username.is_empty() || self.password.is_empty() {
+            bail!("Username and password are required");
+        }
+        self.from_address
+            .parse::<Mailbox>()
+            .map_err(|e| anyhow!("Invalid from address '{}': {e}", self.from_address))?;
+        Ok(())
+    }
+
+    fn smtp_transport(&self) -> Result<SmtpTransport> {
+        let creds = Credentials::new(self.username.clone(), self.password.clone());
+        let builder = if self.smtp_tls {
+            SmtpTransport::starttls_relay(&self.smtp_host)?
+        } else {
+            SmtpTransport::builder_dangerous(&self.smtp_host)
+        };
+        Ok(builder.port(self.smtp_port).credentials(creds).build())
+    }
+}
+
+// ── IMAP ────────────────────────────────────────────────────────
+
+/// Header summary of one message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmailSummary {
+    pub uid: u32,
+    pub from: String,
+    pub subject: String,
+    /// RFC 3339, empty when the message has no date
+    pub date: String,
+    pub unread: bool,
+}
+
+/// Minimal blocking IMAP client; run it inside `spawn_blocking`.
+struct ImapSession {
+    tls: rustls::StreamOwned<rustls::ClientConnection, TcpStream>,
+    next_tag: u32,
+}
+
+impl ImapSession {
+    fn connect(account: &EmailAccount) -> Result<Self> {
+        let tcp = TcpStream::connect((&*account.imap_host, account.imap_port))?;
+        tcp.set_read_timeout(Some(IMAP_TIMEOUT))?;
+        let server_name = rustls_pki_types::ServerName::try_from(account.imap_host.clone())?;
+        let conn =
+            rustls::ClientConnection::new(EmailChannel::build_imap_tls_config()?, server_name)?;
+        let mut session = Self {
+            tls: rustls::StreamOwned::new(conn, tcp),
+            next_tag: 1,
+        };
+        session.read_line()?; // greeting
+        let login = format!(
+            "LOGIN {} {}",
+            quote(&account.username),
+            quote(&account.password)
+        );
+        session.command(&login).map_err(|_| anyhow!("IMAP login failed"))?;
+        Ok(session)
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let mut buf = Vec::new();
+        let mut byte = [0_u8; 1];
+        while !buf.ends_with(b"\r\n") {
+            if self.tls.read(&mut byte)? == 0 {
+                bail!("IMAP connection closed");
+            }
+            buf.push(byte[0]);
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// Send `cmd` and collect the response up to the tagged status line.
+    /// A literal (`{n}`) is returned as one element after the line announcing it.
+    fn command(&mut self, cmd: &str) -> Result<Vec<String>> {
+        let tag = format!("T{}", self.next_tag);
+        self.next_tag += 1;
+        self.tls.write_all(format!("{tag} {cmd}\r\n").as_bytes())?;
+        self.tls.flush()?;
+
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if let Some(status) = line.strip_prefix(&format!("{tag} ")) {
+                if !status.starts_with("OK") {
+                    bail!("IMAP command failed: {}", status.trim());
+                }
+                return Ok(lines);
+            }
+            let literal = literal_len(&line);
+            lines.push(line);
+            if let Some(len) = literal {
+                let mut buf = vec![0_u8; len];
+                self.tls.read_exact(&mut buf)?;
+                lines.push(String::from_utf8_lossy(&buf).into_owned());
+            }
+        }
+    }
+
+    fn logout(mut self) {
+        let _ = self.command("LOGOUT");
+    }
+}
+
+/// IMAP quoted string.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Length of the literal announced at the end of `line` (`... {123}\r\n`).
+fn literal_len(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    let open = line.rfind('{')?;
+    line.strip_suffix('}')?.get(open + 1..)?.parse().ok()
+}
+
+/// UIDs from `* SEARCH` responses.
+fn parse_search(lines: &[String]) -> Vec<u32> {
+    lines
+        .iter()
+        .filter_map(|line| line.strip_prefix("* SEARCH"))
+        .flat_map(str::split_whitespace)
+        .filter_map(|uid| uid.parse().ok())
+        .collect()
+}
+
+/// `(metadata, literal)` pairs of a FETCH response. The metadata joins the
+/// line announcing the literal with the line closing it, since servers may
+/// put `UID` and `FLAGS` on either side.
+fn fetch_literals(lines: &[String]) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if literal_len(&lines[i]).is_some() && i + 1 < lines.len() {
+            let mut meta = lines[i].clone();
+            if let Some(closing) = lines.get(i + 2) {
+                meta.push_str(closing);
+            }
+            out.push((meta, lines[i + 1].clone()));
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+/// The number following `UID ` in FETCH metadata.
+fn fetch_uid(meta: &str) -> Option<u32> {
+    let rest = &meta[meta.find("UID ")? + 4..];
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+fn format_addresses(address: Option<&Address>) -> String {
+    address
+        .map(|a| {
+            a.clone()
+                .into_list()
+                .iter()
+                .map(|addr| match (addr.name(), addr.address()) {
+                    (Some(name), Some(email)) => format!("{name} <{email}>"),
+                    (_, Some(email)) => email.to_string(),
+                    (Some(name), None) => name.to_string(),
+                    (None, None) => String::new(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
+
+fn summarize(meta: &str, headers: &str) -> Option<EmailSummary> {
+    let parsed = MessageParser::default().parse(headers.as_bytes())?;
+    Some(EmailSummary {
+        uid: fetch_uid(meta)?,
+        from: format_addresses(parsed.from()),
+        subject: parsed.subject().unwrap_or("(no subject)").to_string(),
+        date: parsed.date().map(|d| d.to_rfc3339()).unwrap_or_default(),
+        unread: !meta.contains("\\Seen"),
+    })
+}
+
+fn list_messages(
+    account: &EmailAccount,
+    folder: &str,
+    limit: usize,
+    unread_only: bool,
+) -> Result<Vec<EmailSummary>> {
+    let mut session = ImapSession::connect(account)?;
+    session.command(&format!("EXAMINE {}", quote(folder)))?;
+    let search = session.command(if unread_only { "UID SEARCH UNSEEN" } else { "UID SEARCH ALL" })?;
+    let mut uids = parse_search(&search);
+    uids.sort_unstable();
+    let newest = &uids[uids.len().saturating_sub(limit)..];
+
+    let mut summaries = Vec::new();
+    if !newest.is_empty() {
+        let set = newest.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+        let fetch = session.command(&format!(
+            "UID FETCH {set} (UID FLAGS BODY.PEEK[HEADER.FIELDS (FROM SUBJECT DATE)])"
+        ))?;
+        summaries = fetch_literals(&fetch)
+            .iter()
+            .filter_map(|(meta, headers)| summarize(meta, headers))
+            .collect();
+        summaries.sort_by(|a, b| b.uid.cmp(&a.uid));
+    }
+    session.logout();
+    Ok(summaries)
+}
+
+fn read_message(account: &EmailAccount, folder: &str, uid: u32) -> Result<Option<String>> {
+    let mut session = ImapSession::connect(account)?;
+    session.command(&format!("EXAMINE {}", quote(folder)))?;
+    let fetch = session.command(&format!("UID FETCH {uid} BODY.PEEK[]"))?;
+    session.logout();
+
+    let Some((_, raw)) = fetch_literals(&fetch).into_iter().next() else {
+        return Ok(None);
+    };
+    let Some(parsed) = MessageParser::default().parse(raw.as_bytes()) else {
+        bail!("Message {uid} could not be parsed");
+    };
+    let mut out = String::new();
+    let _ = writeln!(out, "From: {}", format_addresses(parsed.from()));
+    let _ = writeln!(out, "To: {}", format_addresses(parsed.to()));
+    if parsed.cc().is_some() {
+        let _ = writeln!(out, "Cc: {}", format_addresses(parsed.cc()));
+    }
+    let _ = writeln!(out, "Subject: {}", parsed.subject().unwrap_or("(no subject)"));
+    if let Some(date) = parsed.date() {
+        let _ = writeln!(out, "Date: {}", date.to_rfc3339());
+    }
+    let body = EmailChannel::extract_text(&parsed);
+    let _ = write!(out, "\n{}", crate::util::truncate_with_ellipsis(&body, MAX_BODY_CHARS));
+    Ok(Some(out))
+}
+
+// ── Tools ───────────────────────────────────────────────────────
+
+fn no_account() -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some("No email account configured. Set one up via PUT /api/pim/email.".into()),
+        data: None,
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+fn folder_arg(args: &serde_json::Value) -> String {
+    args.get("folder")
+        .and_then(|v| v.as_str())
+        .filter(|f| !f.trim().is_empty())
+        .unwrap_or(DEFAULT_FOLDER)
+        .to_string()
+}
+
+/// Where the email tools find their account.
+#[derive(Clone)]
+struct AccountSource {
+    workspace: PathBuf,
+    secrets: Option<SecretStore>,
+}
+
+impl AccountSource {
+    fn load(&self) -> Option<EmailAccount> {
+        EmailAccount::load(&self.workspace, self.secrets.as_ref())
+    }
+}
+
+// ── Email List Tool ─────────────────────────────────────────────
+
+pub struct EmailListTool {
+    source: AccountSource,
+}
+
+#[async_trait]
+impl Tool for EmailListTool {
+    fn name(&self) -> &str {
+        "email_list"
+    }
+
+    fn description(&self) -> &str {
+        "List the newest emails in a mailbox folder (sender, subject, date, unread flag). \
+         Does not mark anything as read."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "folder": {"type": "string", "description": "Mailbox folder (default: INBOX)"},
+                "limit": {"type": "integer", "description": "How many messages, newest first (default: 10, max: 50)"},
+                "unread_only": {"type": "boolean", "description": "Only list unread messages"}
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "uid": {"type": "integer", "description": "Pass to email_read"},
+                    "from": {"type": "string"},
+                    "subject": {"type": "string"},
+                    "date": {"type": "string"},
+                    "unread": {"type": "boolean"}
+                }
+            }
+        }))
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(account) = self.source.load() else {
+            return Ok(no_account());
+        };
+        let folder = folder_arg(&args);
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIST_LIMIT, |l| l as usize)
+            .clamp(1, MAX_LIST_LIMIT);
+        let unread_only = args
+            .get("unread_only")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        let listed = {
+            let folder = folder.clone();
+            tokio::task::spawn_blocking(move || list_messages(&account, &folder, limit, unread_only))
+                .await?
+        };
+        let summaries = match listed {
+            Ok(s) => s,
+            Err(e) => return Ok(failure(format!("IMAP error: {e}"))),
+        };
+
+        if summaries.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No {}messages in {folder}.", if unread_only { "unread " } else { "" }),
+                error: None,
+                data: Some(serde_json::json!([])),
+            });
+        }
+        let mut output = format!("📬 {} message(s) in {folder}:\n", summaries.len());
+        for s in &summaries {
+            let marker = if s.unread { "●" } else { " " };
+            let _ = writeln!(output, "{marker} [{}] {} — {} — {}", s.uid, s.date, s.from, s.subject);
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(serde_json::to_value(&summaries)?),
+        })
+    }
+}
+
+// ── Email Read Tool ─────────────────────────────────────────────
+
+pub struct EmailReadTool {
+    source: AccountSource,
+}
+
+#[async_trait]
+impl Tool for EmailReadTool {
+    fn name(&self) -> &str {
+        "email_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read one email by its uid from email_list. Returns headers and the text body."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "uid": {"type": "integer", "description": "Message uid from email_list"},
+                "folder": {"type": "string", "description": "Mailbox folder (default: INBOX)"}
+            },
+            "required": ["uid"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let uid = args
+            .get("uid")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|u| u32::try_from(u).ok())
+            .ok_or_else(|| anyhow!("Missing 'uid' parameter"))?;
+        let Some(account) = self.source.load() else {
+            return Ok(no_account());
+        };
+        let folder = folder_arg(&args);
+
+        let read = {
+            let folder = folder.clone();
+            tokio::task::spawn_blocking(move || read_message(&account, &folder, uid)).await?
+        };
+        match read {
+            Ok(Some(output)) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+                data: None,
+            }),
+            Ok(None) => Ok(failure(format!("Message {uid} not found in {folder}."))),
+            Err(e) => Ok(failure(format!("IMAP error: {e}"))),
+        }
+    }
+}
+
+// ── Email Send Tool ─────────────────────────────────────────────
+
+pub struct EmailSendTool {
+    source: AccountSource,
+    /// Every send is confirmed by the user; without a gate nothing is sent.
+    confirm_gate: Option<Arc<ConfirmationGate>>,
+}
+
+fn parse_mailboxes(list: &str) -> Result<Vec<Mailbox>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|a| a.parse::<Mailbox>().map_err(|e| anyhow!("Invalid address '{a}': {e}")))
+        .collect()
+}
+
+fn build_message(
+    account: &EmailAccount,
+    to: &[Mailbox],
+    cc: &[Mailbox],
+    subject: &str,
+    body: &str,
+) -> Result<Message> {
+    if to.is_empty() {
+        bail!("At least one recipient is required");
+    }
+    let mut builder = Message::builder()
+        .from(account.from_address.parse()?)
+        .subject(subject);
+    for mailbox in to {
+        builder = builder.to(mailbox.clone());
+    }
+    for mailbox in cc {
+        builder = builder.cc(mailbox.clone());
+    }
+    Ok(builder.body(body.to_string())?)
+}
+
+#[async_trait]
+impl Tool for EmailSendTool {
+    fn name(&self) -> &str {
+        "email_send"
+    }
+
+    fn description(&self) -> &str {
+        "Send a plain-text email from the user's account. The user must approve every send."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "to": {"type": "string", "description": "Recipient address(es), comma-separated"},
+                "cc": {"type": "string", "description": "Cc address(es), comma-separated"},
+                "subject": {"type": "string"},
+                "body": {"type": "string", "description": "Plain-text body"}
+            },
+            "required": ["to", "subject", "body"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let to = args
+            .get("to")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'to' parameter"))?;
+        let subject = args
+            .get("subject")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'subject' parameter"))?;
+        let body = args
+            .get("body")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'body' parameter"))?;
+        let cc = args.get("cc").and_then(|v| v.as_str()).unwrap_or("");
+
+        let Some(account) = self.source.load() else {
+            return Ok(no_account());
+        };
+        let message = match parse_mailboxes(to).and_then(|to_list| {
+            let cc_list = parse_mailboxes(cc)?;
+            build_message(&account, &to_list, &cc_list, subject, body)
+        }) {
+            Ok(m) => m,
+            Err(e) => return Ok(failure(e.to_string())),
+        };
+
+        let Some(gate) = &self.confirm_gate else {
+            return Ok(failure(
+                "Sending email requires user confirmation but no confirmation channel is available."
+                    .into(),
+            ));
+        };
+        let summary = format!(
+            "Send email to {to}{} with subject \"{subject}\": {}",
+            if cc.is_empty() { String::new() } else { format!(" (cc {cc})") },
+            crate::util::truncate_with_ellipsis(body, 200)
+        );
+        if !gate.request(self.name(), &summary).await {
+            return Ok(failure("User denied sending the email (or the request timed out).".into()));
+        }
+
+        let sent = tokio::task::spawn_blocking(move || {
+            account.smtp_transport()?.send(&message)?;
+            anyhow::Ok(())
+        })
+        .await?;
+        match sent {
+            Ok(()) => {
+                tracing::info!("Email sent via email_send tool");
+                Ok(ToolResult {
+                    success: true,
+                    output: format!("✉️ Email sent to {to}."),
+                    error: None,
+                    data: None,
+                })
+            }
+            Err(e) => Ok(failure(format!("SMTP error: {e}"))),
+        }
+    }
+}
+
+/// Create the email tools. `confirm_gate` is required for `email_send` to
+/// actually send.
+pub fn email_tools(
+    workspace: &Path,
+    secrets: Option<SecretStore>,
+    confirm_gate: Option<Arc<ConfirmationGate>>,
+) -> Vec<Box<dyn Tool>> {
+    let source = AccountSource {
+        workspace: workspace.to_path_buf(),
+        secrets,
+    };
+    vec![
+        Box::new(EmailListTool {
+            source: source.clone(),
+        }),
+        Box::new(EmailReadTool {
+            source: source.clone(),
+        }),
+        Box::new(EmailSendTool {
+            source,
+            confirm_gate,
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|l| (*l).to_string()).collect()
+    }
+
+    fn account() -> EmailAccount {
+        EmailAccount {
+            imap_host: "imap.example.org".into(),
+            imap_port: 993,
+            smtp_host: "smtp.example.org".into(),
+            smtp_port: 587,
+            smtp_tls: true,
+            username: "jane".into(),
+            password: "secret".into(),
+            from_address: "Jane <jane@example.org>".into(),
+        }
+    }
+
+    #[test]
+    fn fetch_response_yields_summaries() {
+        let headers = "From: Bob <bob@example.org>\r\nSubject: Lunch?\r\nDate: Tue, 1 Sep 2026 12:00:00 +0000\r\n\r\n";
+        let response = lines(&[
+            "* 1 FETCH (UID 41 FLAGS (\\Seen) BODY[HEADER.FIELDS (FROM SUBJECT DATE)] {81}\r\n",
+            headers,
+            ")\r\n",
+            "* 2 FETCH (FLAGS () BODY[HEADER.FIELDS (FROM SUBJECT DATE)] {81}\r\n",
+            headers,
+            " UID 42)\r\n",
+        ]);
+        let summaries: Vec<EmailSummary> = fetch_literals(&response)
+            .iter()
+            .filter_map(|(meta, h)| summarize(meta, h))
+            .collect();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].uid, 41);
+        assert!(!summaries[0].unread);
+        assert_eq!(summaries[1].uid, 42);
+        assert!(summaries[1].unread);
+        assert_eq!(summaries[1].from, "Bob <bob@example.org>");
+        assert_eq!(summaries[1].subject, "Lunch?");
+        assert!(summaries[1].date.starts_with("2026-09-01T12:00:00"));
+    }
+
+    #[test]
+    fn imap_helpers_parse_search_literals_and_quote() {
+        assert_eq!(parse_search(&lines(&["* SEARCH 3 7 12\r\n"])), vec![3, 7, 12]);
+        assert!(parse_search(&lines(&["* SEARCH\r\n"])).is_empty());
+        assert_eq!(literal_len("* 1 FETCH (BODY[] {2048}\r\n"), Some(2048));
+        assert_eq!(literal_len("* OK ready\r\n"), None);
+        assert_eq!(quote(r#"pa"ss\word"#), r#""pa\"ss\\word""#);
+    }
+
+    #[tokio::test]
+    async fn send_requires_account_valid_address_and_gate() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tools = email_tools(tmp.path(), None, None);
+        let send = tools.iter().find(|t| t.name() == "email_send").unwrap();
+        let args = serde_json::json!({"to": "bob@example.org", "subject": "Hi", "body": "Hello"});
+
+        let result = send.execute(args.clone()).await.unwrap();
+        assert!(result.error.unwrap().contains("No email account"));
+
+        account().save(tmp.path(), None).unwrap();
+        let bad = serde_json::json!({"to": "not an address", "subject": "Hi", "body": "Hello"});
+        let result = send.execute(bad).await.unwrap();
+        assert!(result.error.unwrap().contains("Invalid address"));
+
+        let result = send.execute(args).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("no confirmation channel"));
+    }
+
+    #[test]
+    fn account_roundtrips_encrypted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let secrets = Some(SecretStore::new(&tmp.path().join(".mymolt"), true));
+        account().save(tmp.path(), secrets.as_ref()).unwrap();
+        let raw = std::fs::read_to_string(account_path(tmp.path())).unwrap();
+        assert!(!raw.contains("secret"));
+        assert_eq!(EmailAccount::load(tmp.path(), secrets.as_ref()), Some(account()));
+        assert!(EmailAccount::remove(tmp.path()).unwrap());
+        assert_eq!(EmailAccount::load(tmp.path(), secrets.as_ref()), None);
+    }
+}
@@ -0,0 +1,724 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! RSS/Atom subscriptions — the `feeds` tool and the news digest.
+//!
+//! Subscriptions live in `.mymolt/feeds.json`. Every fetched item is stored
+//! in the memory backend (category `feeds`), so `memory_recall` can search
+//! old news, and new items queue up for the next digest. With
+//! `[feeds.digest]` configured, [`digest_automation`] adds a cron rule to
+//! the automation engine that asks the agent to summarize the queue and
+//! sends the briefing to a channel.
+
+use super::traits::{Tool, ToolResult};
+use crate::automations::{Action, Automation, Target, Trigger};
+use crate::channels::email_channel::EmailChannel;
+use crate::config::FeedsConfig;
+use crate::memory::{Memory, MemoryCategory};
+use crate::network::egress;
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Memory category for feed items.
+const FEEDS_CATEGORY: &str = "feeds";
+/// Longest item summary kept, in characters.
+const MAX_SUMMARY_CHARS: usize = 500;
+/// New items waiting for the digest; older ones are dropped first.
+const MAX_PENDING_ITEMS: usize = 500;
+/// Latest items shown when subscribing.
+const PREVIEW_ITEMS: usize = 5;
+
+const DIGEST_PROMPT: &str = "Call the `feeds` tool with action \"digest\". Summarize the new \
+     items as a short news briefing grouped by feed: one line per story with its link, most \
+     important first. If there are no new items, reply only with \"No new feed items.\"";
+
+// ── Store ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Feed {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    pub added_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_fetched: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedItem {
+    pub feed: String,
+    pub title: String,
+    pub link: String,
+    #[serde(default)]
+    pub published: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub summary: String,
+}
+
+impl FeedItem {
+    fn memory_content(&self) -> String {
+        let mut content = format!("[{}] {}\n{}", self.feed, self.title, self.link);
+        if let Some(published) = self.published {
+            let _ = write!(
+                content,
+                "\nPublished: {}",
+                published.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
+        if !self.summary.is_empty() {
+            let _ = write!(content, "\n\n{}", self.summary);
+        }
+        content
+    }
+}
+
+/// Subscriptions and the items not yet digested.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FeedStore {
+    #[serde(default)]
+    pub feeds: Vec<Feed>,
+    #[serde(default)]
+    pub pending: Vec<FeedItem>,
+    #[serde(default)]
+    pub last_digest: Option<DateTime<Utc>>,
+}
+
+fn store_path(workspace: &Path) -> PathBuf {
+    workspace.join(".mymolt").join("feeds.json")
+}
+
+impl FeedStore {
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(store_path(workspace))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, workspace: &Path) -> Result<()> {
+        let path = store_path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn find(&self, name_or_url: &str) -> Option<usize> {
+        self.feeds
+            .iter()
+            .position(|f| f.name.eq_ignore_ascii_case(name_or_url) || f.url == name_or_url)
+    }
+
+    fn queue(&mut self, items: impl IntoIterator<Item = FeedItem>) {
+        self.pending.extend(items);
+        if self.pending.len() > MAX_PENDING_ITEMS {
+            let excess = self.pending.len() - MAX_PENDING_ITEMS;
+            self.pending.drain(..excess);
+        }
+    }
+}
+
+// ── Fetching ────────────────────────────────────────────────────
+
+/// Only public http(s) hosts: feeds must not reach into the LAN.
+fn validate_feed_url(url: &str) -> Result<()> {
+    let host = super::http_request::extract_host(url)?;
+    if super::http_request::is_private_or_local_host(&host) {
+        bail!("Blocked local/private host: {host}");
+    }
+    Ok(())
+}
+
+/// Short, stable memory key for an item.
+fn item_key(feed: &str, id: &str) -> String {
+    let digest = hex::encode(Sha256::digest(id.as_bytes()));
+    format!("feed:{feed}:{}", &digest[..16])
+}
+
+/// Parse an RSS or Atom document into items, newest first.
+fn parse_feed(
+    feed_name: &str,
+    bytes: &[u8],
+    max_items: usize,
+) -> Result<(String, Vec<(String, FeedItem)>)> {
+    let parsed = feed_rs::parser::parse(bytes).context("Not a valid RSS or Atom feed")?;
+    let title = parsed.title.map(|t| t.content).unwrap_or_default();
+    let mut entries = parsed.entries;
+    entries.sort_by(|a, b| b.published.or(b.updated).cmp(&a.published.or(a.updated)));
+
+    let items = entries
+        .into_iter()
+        .take(max_items)
+        .filter_map(|entry| {
+            let link = entry
+                .links
+                .first()
+                .map(|l| l.href.clone())
+                .unwrap_or_default();
+            let id = if entry.id.is_empty() {
+                link.clone()
+            } else {
+                entry.id.clone()
+            };
+            if id.is_empty() {
+                return None;
+            }
+            let summary = entry
+                .summary
+                .map(|s| s.content)
+                .or_else(|| entry.content.and_then(|c| c.body))
+                .map(|html| EmailChannel::strip_html(&html))
+                .unwrap_or_default();
+            let item = FeedItem {
+                feed: feed_name.to_string(),
+                title: entry.title.map_or_else(
+                    || "(untitled)".to_string(),
+                    |t| EmailChannel::strip_html(&t.content),
+                ),
+                link,
+                published: entry.published.or(entry.updated),
+                summary: crate::util::truncate_with_ellipsis(&summary, MAX_SUMMARY_CHARS),
+            };
+            Some((item_key(feed_name, &id), item))
+        })
+        .collect();
+    Ok((title, items))
+}
+
+/// Derive a subscription name from the feed title or URL host.
+fn default_name(title: &str, url: &str) -> String {
+    let source = if title.trim().is_empty() {
+        super::http_request::extract_host(url).unwrap_or_default()
+    } else {
+        title.to_string()
+    };
+    let slug: String = source
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "feed".into()
+    } else {
+        crate::util::truncate_with_ellipsis(&slug, 40)
+            .trim_end_matches("...")
+            .to_string()
+    }
+}
+
+// ── Digest automation ───────────────────────────────────────────
+
+/// The cron rule behind `[feeds.digest]`, if configured.
+pub fn digest_automation(config: &FeedsConfig) -> Option<Automation> {
+    let digest = config.digest.as_ref().filter(|_| config.enabled)?;
+    Some(Automation {
+        id: "feeds-digest".into(),
+        name: "Feed digest".into(),
+        enabled: true,
+        trigger: Trigger::Cron {
+            expression: digest.schedule.clone(),
+        },
+        conditions: Vec::new(),
+        action: Action::Prompt {
+            prompt: DIGEST_PROMPT.into(),
+            reply: Some(Target {
+                channel: digest.channel.clone(),
+                recipient: digest.recipient.clone(),
+            }),
+        },
+    })
+}
+
+// ── Tool ────────────────────────────────────────────────────────
+
+/// Tool to manage feed subscriptions and read new items.
+pub struct FeedsTool {
+    security: Arc<SecurityPolicy>,
+    memory: Arc<dyn Memory>,
+    workspace_dir: PathBuf,
+    config: FeedsConfig,
+    /// Serializes read-modify-write of the store.
+    lock: Mutex<()>,
+}
+
+impl FeedsTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        memory: Arc<dyn Memory>,
+        workspace_dir: &Path,
+        config: FeedsConfig,
+    ) -> Self {
+        Self {
+            security,
+            memory,
+            workspace_dir: workspace_dir.to_path_buf(),
+            config,
+            lock: Mutex::new(()),
+        }
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+        validate_feed_url(url)?;
+        egress::check(url, None)?;
+        let client = egress::client_builder(None)
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .user_agent("MyMolt feed reader")
+            // Redirects are re-checked so a feed cannot bounce into the LAN
+            // or out of the egress policy.
+            .redirect(reqwest::redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= 5
+                    || validate_feed_url(attempt.url().as_str()).is_err()
+                    || egress::check(attempt.url().as_str(), None).is_err()
+                {
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()?;
+        let mut response = client.get(url).send().await?.error_for_status()?;
+
+        let max = self.config.max_feed_bytes;
+        if response
+            .content_length()
+            .is_some_and(|len| len > max as u64)
+        {
+            bail!("Feed is larger than {max} bytes");
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max {
+                bail!("Feed is larger than {max} bytes");
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Fetch one feed and store items not seen before. Returns the feed
+    /// title and the new items.
+    async fn refresh_feed(&self, feed: &Feed) -> Result<(String, Vec<FeedItem>)> {
+        let bytes = self.fetch(&feed.url).await?;
+        let (title, items) = parse_feed(&feed.name, &bytes, self.config.max_items_per_feed)?;
+        let category = MemoryCategory::Custom(FEEDS_CATEGORY.into());
+        let mut new_items = Vec::new();
+        for (key, item) in items {
+            if self.memory.get(&key).await?.is_some() {
+                continue;
+            }
+            self.memory
+                .store(&key, &item.memory_content(), category.clone())
+                .await?;
+            new_items.push(item);
+        }
+        Ok((title, new_items))
+    }
+
+    /// Refresh `names` (all when empty), queue new items for the digest.
+    async fn refresh(
+        &self,
+        store: &mut FeedStore,
+        names: &[usize],
+    ) -> (Vec<FeedItem>, Vec<String>) {
+        let mut new_items = Vec::new();
+        let mut errors = Vec::new();
+        let indices: Vec<usize> = if names.is_empty() {
+            (0..store.feeds.len()).collect()
+        } else {
+            names.to_vec()
+        };
+        for i in indices {
+            let feed = store.feeds[i].clone();
+            match self.refresh_feed(&feed).await {
+                Ok((title, items)) => {
+                    store.feeds[i].last_fetched = Some(Utc::now());
+                    if !title.is_empty() {
+                        store.feeds[i].title = title;
+                    }
+                    new_items.extend(items);
+                }
+                Err(e) => errors.push(format!("{}: {e}", feed.name)),
+            }
+        }
+        store.queue(new_items.iter().cloned());
+        (new_items, errors)
+    }
+
+    async fn subscribe(&self, args: &serde_json::Value) -> Result<ToolResult> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
+        if let Err(e) = validate_feed_url(url) {
+            return Ok(failure(e.to_string()));
+        }
+        let _guard = self.lock.lock().await;
+        let mut store = FeedStore::load(&self.workspace_dir);
+        if store.find(url).is_some() {
+            return Ok(failure(format!("Already subscribed to {url}")));
+        }
+        if store.feeds.len() >= self.config.max_feeds {
+            return Ok(failure(format!(
+                "Feed limit reached ({} feeds); unsubscribe from one first",
+                self.config.max_feeds
+            )));
+        }
+
+        let bytes = match self.fetch(url).await {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(failure(format!("Failed to fetch feed: {e}"))),
+        };
+        let (title, _) = match parse_feed("", &bytes, 0) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(failure(e.to_string())),
+        };
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map_or_else(|| default_name(&title, url), String::from);
+        if store.find(&name).is_some() {
+            return Ok(failure(format!("A feed named '{name}' already exists")));
+        }
+
+        let feed = Feed {
+            name: name.clone(),
+            url: url.to_string(),
+            title: title.clone(),
+            added_at: Utc::now(),
+            last_fetched: None,
+        };
+        // Items already published are remembered but not queued, so the
+        // first digest is not flooded with the feed's backlog.
+        let (_, items) = self.refresh_feed(&feed).await?;
+        store.feeds.push(Feed {
+            last_fetched: Some(Utc::now()),
+            ..feed
+        });
+        store.save(&self.workspace_dir)?;
+
+        let mut output = format!("Subscribed to {title} as '{name}'. Latest items:");
+        for item in items.iter().take(PREVIEW_ITEMS) {
+            let _ = write!(output, "\n- {} ({})", item.title, item.link);
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(json!({ "name": name, "title": title, "stored_items": items.len() })),
+        })
+    }
+
+    async fn unsubscribe(&self, args: &serde_json::Value) -> Result<ToolResult> {
+        let target = args
+            .get("name")
+            .or_else(|| args.get("url"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
+        let _guard = self.lock.lock().await;
+        let mut store = FeedStore::load(&self.workspace_dir);
+        let Some(index) = store.find(target) else {
+            return Ok(failure(format!("No feed named '{target}'")));
+        };
+        let feed = store.feeds.remove(index);
+        store.pending.retain(|item| item.feed != feed.name);
+        store.save(&self.workspace_dir)?;
+        Ok(ToolResult {
+            success: true,
+            output: format!("Unsubscribed from '{}' ({})", feed.name, feed.url),
+            error: None,
+            data: None,
+        })
+    }
+
+    async fn list(&self) -> Result<ToolResult> {
+        let store = FeedStore::load(&self.workspace_dir);
+        let output = if store.feeds.is_empty() {
+            "No feed subscriptions.".to_string()
+        } else {
+            let mut out = format!(
+                "{} feed(s), {} new item(s) waiting for the digest:",
+                store.feeds.len(),
+                store.pending.len()
+            );
+            for feed in &store.feeds {
+                let _ = write!(out, "\n- {}: {} ({})", feed.name, feed.title, feed.url);
+            }
+            out
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(json!({ "feeds": store.feeds, "pending": store.pending.len() })),
+        })
+    }
+
+    async fn refresh_action(&self, args: &serde_json::Value) -> Result<ToolResult> {
+        let _guard = self.lock.lock().await;
+        let mut store = FeedStore::load(&self.workspace_dir);
+        let selected = match args.get("name").and_then(|v| v.as_str()) {
+            Some(name) => match store.find(name) {
+                Some(i) => vec![i],
+                None => return Ok(failure(format!("No feed named '{name}'"))),
+            },
+            None => Vec::new(),
+        };
+        let (items, errors) = self.refresh(&mut store, &selected).await;
+        store.save(&self.workspace_dir)?;
+        Ok(items_result(
+            &format!("{} new item(s)", items.len()),
+            &items,
+            &errors,
+        ))
+    }
+
+    async fn digest(&self) -> Result<ToolResult> {
+        let _guard = self.lock.lock().await;
+        let mut store = FeedStore::load(&self.workspace_dir);
+        let (_, errors) = self.refresh(&mut store, &[]).await;
+
+        let limit = self
+            .config
+            .digest
+            .as_ref()
+            .map_or(MAX_PENDING_ITEMS, |d| d.max_items);
+        let take = store.pending.len().min(limit);
+        let items: Vec<FeedItem> = store.pending.drain(..take).collect();
+        let since = store.last_digest;
+        store.last_digest = Some(Utc::now());
+        store.save(&self.workspace_dir)?;
+
+        let heading = match since {
+            Some(since) => format!(
+                "{} new item(s) since {}",
+                items.len(),
+                since.format("%Y-%m-%d %H:%M UTC")
+            ),
+            None => format!("{} new item(s)", items.len()),
+        };
+        let mut result = items_result(&heading, &items, &errors);
+        if !store.pending.is_empty() {
+            let _ = write!(
+                result.output,
+                "\n({} more item(s) left for the next digest)",
+                store.pending.len()
+            );
+        }
+        Ok(result)
+    }
+}
+
+fn items_result(heading: &str, items: &[FeedItem], errors: &[String]) -> ToolResult {
+    let mut output = format!("{heading}.");
+    let mut current_feed = "";
+    for item in items {
+        if item.feed != current_feed {
+            current_feed = &item.feed;
+            let _ = write!(output, "\n\n## {current_feed}");
+        }
+        let _ = write!(output, "\n- {} — {}", item.title, item.link);
+        if !item.summary.is_empty() {
+            let _ = write!(output, "\n  {}", item.summary);
+        }
+    }
+    for error in errors {
+        let _ = write!(output, "\n⚠️ {error}");
+    }
+    ToolResult {
+        success: true,
+        output,
+        error: None,
+        data: Some(json!({ "items": items, "errors": errors })),
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+#[async_trait]
+impl Tool for FeedsTool {
+    fn name(&self) -> &str {
+        "feeds"
+    }
+
+    fn description(&self) -> &str {
+        "Manage RSS/Atom feed subscriptions: subscribe, unsubscribe, list, refresh (fetch new \
+         items), digest (new items since the last digest). Items are also saved to memory."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["subscribe", "unsubscribe", "list", "refresh", "digest"],
+                    "description": "What to do"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Feed URL (for 'subscribe')"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Short feed name (optional for 'subscribe'; selects the feed for 'unsubscribe' and 'refresh')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        if action == "list" {
+            return self.list().await;
+        }
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(failure(
+                "Rate limit exceeded: action budget exhausted".into(),
+            ));
+        }
+
+        match action {
+            "subscribe" => self.subscribe(&args).await,
+            "unsubscribe" => self.unsubscribe(&args).await,
+            "refresh" => self.refresh_action(&args).await,
+            "digest" => self.digest().await,
+            other => Ok(failure(format!(
+                "Unknown action '{other}'. Use: subscribe, unsubscribe, list, refresh, digest"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FeedDigestConfig;
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Example News</title>
+<item><title>Older story</title><link>https://example.org/1</link><guid>1</guid>
+<pubDate>Mon, 06 Jan 2025 08:00:00 GMT</pubDate><description>&lt;p&gt;First &lt;b&gt;one&lt;/b&gt;&lt;/p&gt;</description></item>
+<item><title>Newer story</title><link>https://example.org/2</link><guid>2</guid>
+<pubDate>Tue, 07 Jan 2025 08:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title><id>urn:blog</id>
+<updated>2025-01-07T08:00:00Z</updated>
+<entry><title>Hello</title><id>urn:post:1</id><link href="https://blog.example/hello"/>
+<updated>2025-01-07T08:00:00Z</updated><summary>Short intro</summary></entry>
+</feed>"#;
+
+    #[test]
+    fn parses_rss_and_atom_newest_first() {
+        let (title, items) = parse_feed("news", RSS.as_bytes(), 10).unwrap();
+        assert_eq!(title, "Example News");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].1.title, "Newer story");
+        assert_eq!(items[1].1.summary, "First one");
+        assert_eq!(items[1].0, item_key("news", "1"));
+
+        let (title, items) = parse_feed("blog", ATOM.as_bytes(), 10).unwrap();
+        assert_eq!(title, "Blog");
+        assert_eq!(items[0].1.link, "https://blog.example/hello");
+
+        let (_, limited) = parse_feed("news", RSS.as_bytes(), 1).unwrap();
+        assert_eq!(limited.len(), 1);
+        assert!(parse_feed("x", b"<html></html>", 10).is_err());
+    }
+
+    #[test]
+    fn names_and_urls_are_checked() {
+        assert_eq!(
+            default_name("Example News!", "https://example.org/rss"),
+            "example-news"
+        );
+        assert_eq!(
+            default_name("", "https://blog.example.org/feed"),
+            "blog-example-org"
+        );
+        assert!(validate_feed_url("https://example.org/feed.xml").is_ok());
+        assert!(validate_feed_url("http://192.168.1.1/rss").is_err());
+        assert!(validate_feed_url("http://localhost:8080/rss").is_err());
+        assert!(validate_feed_url("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn pending_queue_is_bounded_and_store_roundtrips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut store = FeedStore::default();
+        let item = |n: usize| FeedItem {
+            feed: "news".into(),
+            title: format!("Story {n}"),
+            link: format!("https://example.org/{n}"),
+            published: None,
+            summary: String::new(),
+        };
+        store.queue((0..MAX_PENDING_ITEMS + 3).map(item));
+        assert_eq!(store.pending.len(), MAX_PENDING_ITEMS);
+        assert_eq!(store.pending[0].title, "Story 3");
+
+        store.save(tmp.path()).unwrap();
+        assert_eq!(FeedStore::load(tmp.path()).pending.len(), MAX_PENDING_ITEMS);
+    }
+
+    #[test]
+    fn digest_rule_only_when_enabled_and_configured() {
+        let mut config = FeedsConfig {
+            enabled: true,
+            ..FeedsConfig::default()
+        };
+        assert!(digest_automation(&config).is_none());
+
+        config.digest = Some(FeedDigestConfig {
+            schedule: "0 7 * * *".into(),
+            channel: "telegram".into(),
+            recipient: "12345".into(),
+            max_items: 30,
+        });
+        let rule = digest_automation(&config).unwrap();
+        assert!(rule.validate().is_ok());
+        assert!(matches!(rule.action, Action::Prompt { reply: Some(_), .. }));
+
+        config.enabled = false;
+        assert!(digest_automation(&config).is_none());
+    }
+}
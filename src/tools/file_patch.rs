@@ -0,0 +1,637 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Surgical file edits from a unified diff or search/replace blocks.
+//!
+//! Unlike `file_write`, only the targeted lines change. Every hunk is checked
+//! against the file before anything is written, so a stale or misplaced patch
+//! fails as a whole. `dry_run` previews the change; otherwise the original is
+//! copied to `.mymolt/backups/` first.
+
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Largest file `file_patch` edits.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Preview lines returned before truncating.
+const MAX_PREVIEW_LINES: usize = 200;
+
+/// Apply diffs or search/replace edits to a file with path sandboxing
+pub struct FilePatchTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl FilePatchTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+/// One contiguous change, for the preview.
+#[derive(Debug, PartialEq, Eq)]
+struct Change {
+    /// 1-based line in the original file
+    line: usize,
+    removed: Vec<String>,
+    added: Vec<String>,
+}
+
+/// A hunk of a unified diff.
+#[derive(Debug)]
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new_len: usize,
+    /// `' '`, `'-'` or `'+'` with the line text
+    lines: Vec<(char, String)>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter(|(c, _)| *c != '+')
+            .map(|(_, text)| text.as_str())
+            .collect()
+    }
+
+    fn counts(&self) -> (usize, usize) {
+        let old = self.lines.iter().filter(|(c, _)| *c != '+').count();
+        let new = self.lines.iter().filter(|(c, _)| *c != '-').count();
+        (old, new)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.counts() == (self.old_len, self.new_len)
+    }
+}
+
+/// `-a,b +c,d @@ ...` (the part after the leading `@@`).
+fn parse_header(header: &str) -> Option<Hunk> {
+    let mut parts = header.split_whitespace();
+    let range = |part: Option<&str>, sign: char| -> Option<(usize, usize)> {
+        let range = part?.strip_prefix(sign)?;
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = range(parts.next(), '-')?;
+    let (_, new_len) = range(parts.next(), '+')?;
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_len,
+        lines: Vec::new(),
+    })
+}
+
+/// Parse the hunks of a single-file unified diff. `diff`, `index`, `---` and
+/// `+++` lines before the first hunk are ignored.
+fn parse_hunks(patch: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut current: Option<Hunk> = None;
+
+    for line in patch.lines() {
+        if let Some(hunk) = current.as_mut() {
+            match line.chars().next() {
+                Some(c @ (' ' | '-' | '+')) => hunk.lines.push((c, line[1..].to_string())),
+                // "\ No newline at end of file"
+                Some('\\') => continue,
+                // Editors strip the space of blank context lines.
+                None => hunk.lines.push((' ', String::new())),
+                Some(_) => {
+                    return Err(format!(
+                        "Hunk {} ends early at '{line}': its header promises {} old and {} new lines",
+                        hunks.len() + 1,
+                        hunk.old_len,
+                        hunk.new_len
+                    ))
+                }
+            }
+            if hunk.is_complete() {
+                hunks.extend(current.take());
+            }
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("@@") {
+            let hunk = parse_header(header).ok_or_else(|| format!("Malformed hunk header: {line}"))?;
+            if hunk.is_complete() {
+                hunks.push(hunk);
+            } else {
+                current = Some(hunk);
+            }
+        } else if !hunks.is_empty() && (line.starts_with("--- ") || line.starts_with("diff ")) {
+            return Err("The patch touches more than one file; send one file per call".into());
+        }
+    }
+
+    if let Some(hunk) = current {
+        let (old, new) = hunk.counts();
+        return Err(format!(
+            "Hunk {} is incomplete: its header promises {} old and {} new lines, the body has {old} and {new}",
+            hunks.len() + 1,
+            hunk.old_len,
+            hunk.new_len
+        ));
+    }
+    if hunks.is_empty() {
+        return Err("No hunks (@@ -a,b +c,d @@) found in the patch".into());
+    }
+    Ok(hunks)
+}
+
+fn matches_at(lines: &[String], start: usize, old: &[&str]) -> bool {
+    lines
+        .get(start..start + old.len())
+        .is_some_and(|window| window.iter().zip(old).all(|(a, b)| a == b))
+}
+
+/// Apply `hunks` to `lines`. A hunk whose context is not at the stated line
+/// is applied where it matches exactly once further down (like `patch`
+/// with an offset); anything else rejects the whole patch.
+fn apply_hunks(lines: &[String], hunks: &[Hunk]) -> Result<(Vec<String>, Vec<Change>), String> {
+    let mut out = Vec::with_capacity(lines.len());
+    let mut changes = Vec::new();
+    let mut cursor = 0;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let n = i + 1;
+        let old = hunk.old_lines();
+        // `-5,0` inserts after line 5.
+        let stated = if hunk.old_len == 0 {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        if stated < cursor {
+            return Err(format!("Hunk {n} overlaps the previous hunk (line {})", hunk.old_start));
+        }
+        if stated + old.len() > lines.len() {
+            return Err(format!(
+                "Hunk {n}: lines {}-{} are outside the file ({} lines)",
+                stated + 1,
+                stated + old.len(),
+                lines.len()
+            ));
+        }
+
+        let start = if matches_at(lines, stated, &old) {
+            stated
+        } else {
+            let found: Vec<usize> = if old.is_empty() {
+                Vec::new()
+            } else {
+                (cursor..=lines.len() - old.len())
+                    .filter(|&s| matches_at(lines, s, &old))
+                    .collect()
+            };
+            match found.as_slice() {
+                [only] => *only,
+                [] => {
+                    let (offset, expected) = old
+                        .iter()
+                        .enumerate()
+                        .find(|(k, line)| lines[stated + k] != **line)
+                        .map_or((0, ""), |(k, line)| (k, *line));
+                    return Err(format!(
+                        "Hunk {n} does not match line {}: expected {expected:?}, found {:?}",
+                        stated + offset + 1,
+                        lines[stated + offset]
+                    ));
+                }
+                _ => {
+                    return Err(format!(
+                        "Hunk {n} does not match line {} and its context occurs {} times; add more context",
+                        hunk.old_start,
+                        found.len()
+                    ))
+                }
+            }
+        };
+
+        out.extend_from_slice(&lines[cursor..start]);
+        let mut line = start;
+        let mut pending: Option<Change> = None;
+        for (c, text) in &hunk.lines {
+            match c {
+                '-' => {
+                    pending
+                        .get_or_insert_with(|| change_at(line))
+                        .removed
+                        .push(text.clone());
+                    line += 1;
+                }
+                '+' => {
+                    pending
+                        .get_or_insert_with(|| change_at(line))
+                        .added
+                        .push(text.clone());
+                    out.push(text.clone());
+                }
+                _ => {
+                    changes.extend(pending.take());
+                    out.push(text.clone());
+                    line += 1;
+                }
+            }
+        }
+        changes.extend(pending);
+        cursor = start + old.len();
+    }
+
+    out.extend_from_slice(&lines[cursor..]);
+    Ok((out, changes))
+}
+
+fn change_at(index: usize) -> Change {
+    Change {
+        line: index + 1,
+        removed: Vec::new(),
+        added: Vec::new(),
+    }
+}
+
+/// Apply search/replace `edits` in order. Each search text must occur exactly once.
+fn apply_edits(text: &str, edits: &[(String, String)]) -> Result<(String, Vec<Change>), String> {
+    let mut text = text.to_string();
+    let mut changes = Vec::new();
+    for (i, (search, replace)) in edits.iter().enumerate() {
+        let n = i + 1;
+        if search.is_empty() {
+            return Err(format!("Edit {n}: 'search' is empty"));
+        }
+        let mut found = text.match_indices(search.as_str()).map(|(pos, _)| pos);
+        let (Some(pos), None) = (found.next(), found.next()) else {
+            let count = text.matches(search.as_str()).count();
+            return Err(if count == 0 {
+                format!("Edit {n}: search text not found")
+            } else {
+                format!("Edit {n}: search text occurs {count} times; add surrounding lines to make it unique")
+            });
+        };
+        changes.push(Change {
+            line: text[..pos].matches('\n').count() + 1,
+            removed: search.lines().map(String::from).collect(),
+            added: replace.lines().map(String::from).collect(),
+        });
+        text.replace_range(pos..pos + search.len(), replace);
+    }
+    Ok((text, changes))
+}
+
+/// Apply a unified diff to `text` (line endings already normalized to `\n`).
+fn apply_patch(text: &str, patch: &str) -> Result<(String, Vec<Change>), String> {
+    let hunks = parse_hunks(&patch.replace("\r\n", "\n"))?;
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let (lines, changes) = apply_hunks(&lines, &hunks)?;
+    let mut out = lines.join("\n");
+    if text.ends_with('\n') && !out.is_empty() {
+        out.push('\n');
+    }
+    Ok((out, changes))
+}
+
+fn render_preview(changes: &[Change]) -> String {
+    let mut out = String::new();
+    let mut lines = 0;
+    for change in changes {
+        let _ = writeln!(out, "@@ line {} @@", change.line);
+        for (sign, text) in change
+            .removed
+            .iter()
+            .map(|t| ('-', t))
+            .chain(change.added.iter().map(|t| ('+', t)))
+        {
+            if lines == MAX_PREVIEW_LINES {
+                out.push_str("… (preview truncated)\n");
+                return out;
+            }
+            let _ = writeln!(out, "{sign}{text}");
+            lines += 1;
+        }
+    }
+    out
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+/// Copy the original to `.mymolt/backups/<path>.<timestamp>`; returns the
+/// backup path relative to the workspace.
+async fn backup(workspace: &Path, path: &str, content: &str) -> anyhow::Result<String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let relative = Path::new(".mymolt")
+        .join("backups")
+        .join(format!("{}.{stamp}", path.trim_start_matches("./")));
+    let full = workspace.join(&relative);
+    if let Some(parent) = full.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&full, content).await?;
+    Ok(relative.display().to_string())
+}
+
+#[async_trait]
+impl Tool for FilePatchTool {
+    fn name(&self) -> &str {
+        "file_patch"
+    }
+
+    fn description(&self) -> &str {
+        "Edit part of an existing file in the workspace, either with a unified diff (`patch`) or with \
+         search/replace blocks (`edits`). The whole patch is validated first and nothing is written if \
+         any part does not match. Use dry_run to preview; the original is backed up before writing."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Relative path to the file within the workspace"
+                },
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff for this file, with @@ -a,b +c,d @@ hunk headers"
+                },
+                "edits": {
+                    "type": "array",
+                    "description": "Search/replace blocks applied in order; each search text must occur exactly once",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "search": {"type": "string"},
+                            "replace": {"type": "string"}
+                        },
+                        "required": ["search", "replace"]
+                    }
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Only validate and preview the change (default false)"
+                },
+                "backup": {
+                    "type": "boolean",
+                    "description": "Copy the original to .mymolt/backups/ before writing (default true)"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let dry_run = args.get("dry_run").and_then(serde_json::Value::as_bool).unwrap_or(false);
+        let keep_backup = args.get("backup").and_then(serde_json::Value::as_bool).unwrap_or(true);
+
+        let patch = args.get("patch").and_then(|v| v.as_str());
+        let edits = match args.get("edits").and_then(|v| v.as_array()) {
+            Some(items) => {
+                let mut edits = Vec::with_capacity(items.len());
+                for item in items {
+                    let field = |name: &str| item.get(name).and_then(|v| v.as_str()).map(|s| s.replace("\r\n", "\n"));
+                    match (field("search"), field("replace")) {
+                        (Some(search), Some(replace)) => edits.push((search, replace)),
+                        _ => return Ok(failure("Each edit needs 'search' and 'replace' strings".into())),
+                    }
+                }
+                Some(edits)
+            }
+            None => None,
+        };
+        if patch.is_some() == edits.is_some() {
+            return Ok(failure("Provide either 'patch' or 'edits'".into()));
+        }
+
+        // A dry run only reads, so it is allowed in read-only mode.
+        if !dry_run && !self.security.can_act() {
+            return Ok(failure("Action blocked: autonomy is read-only".into()));
+        }
+        if !dry_run && self.security.is_rate_limited() {
+            return Ok(failure("Rate limit exceeded: too many actions in the last hour".into()));
+        }
+
+        // Security check: validate path is within workspace
+        if !self.security.is_path_allowed(path) {
+            return Ok(failure(format!("Path not allowed by security policy: {path}")));
+        }
+
+        let full_path = self.security.workspace_dir.join(path);
+        let resolved = match tokio::fs::canonicalize(&full_path).await {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Failed to resolve file path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Ok(failure(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            )));
+        }
+
+        let size = tokio::fs::metadata(&resolved).await.map(|m| m.len()).unwrap_or(0);
+        if size > MAX_FILE_BYTES {
+            return Ok(failure(format!(
+                "File too large: {size} bytes (limit: {MAX_FILE_BYTES} bytes)"
+            )));
+        }
+        let original = match tokio::fs::read_to_string(&resolved).await {
+            Ok(text) => text,
+            Err(e) => return Ok(failure(format!("Failed to read file: {e}"))),
+        };
+
+        let crlf = original.contains("\r\n");
+        let text = if crlf { original.replace("\r\n", "\n") } else { original.clone() };
+        let applied = match (patch, &edits) {
+            (Some(patch), _) => apply_patch(&text, patch),
+            (None, Some(edits)) => apply_edits(&text, edits),
+            (None, None) => unreachable!("checked above"),
+        };
+        let (patched, changes) = match applied {
+            Ok(result) => result,
+            Err(e) => return Ok(failure(format!("Patch not applied: {e}"))),
+        };
+        if patched == text {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No changes: {path} already matches the patch"),
+                error: None,
+                data: None,
+            });
+        }
+
+        let added: usize = changes.iter().map(|c| c.added.len()).sum();
+        let removed: usize = changes.iter().map(|c| c.removed.len()).sum();
+        let preview = render_preview(&changes);
+        let summary = format!("{} change(s), +{added} -{removed} lines", changes.len());
+        let data = |backup: Option<&str>| {
+            json!({
+                "dry_run": dry_run,
+                "changes": changes.len(),
+                "added": added,
+                "removed": removed,
+                "backup": backup,
+            })
+        };
+
+        if dry_run {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("Dry run: {path} would get {summary}\n{preview}"),
+                error: None,
+                data: Some(data(None)),
+            });
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted".into()));
+        }
+
+        let backup_path = if keep_backup {
+            match backup(&self.security.workspace_dir, path, &original).await {
+                Ok(p) => Some(p),
+                Err(e) => return Ok(failure(format!("Failed to write backup, file left unchanged: {e}"))),
+            }
+        } else {
+            None
+        };
+
+        let patched = if crlf { patched.replace('\n', "\r\n") } else { patched };
+        match tokio::fs::write(&resolved, &patched).await {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: match &backup_path {
+                    Some(b) => format!("Patched {path}: {summary} (backup: {b})\n{preview}"),
+                    None => format!("Patched {path}: {summary}\n{preview}"),
+                },
+                error: None,
+                data: Some(data(backup_path.as_deref())),
+            }),
+            Err(e) => Ok(failure(format!("Failed to write file: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn test_security(workspace: std::path::PathBuf, autonomy: AutonomyLevel) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    const SOURCE: &str = "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n\nfn helper() {\n    todo!()\n}\n";
+
+    #[test]
+    fn unified_diff_applies_with_offset_and_keeps_trailing_newline() {
+        // Stated at line 4, but the context starts at line 6.
+        let patch = "--- a/main.rs\n+++ b/main.rs\n@@ -4,3 +4,3 @@\n fn helper() {\n-    todo!()\n+    42\n }\n";
+        let (out, changes) = apply_patch(SOURCE, patch).unwrap();
+        assert!(out.ends_with("fn helper() {\n    42\n}\n"));
+        assert_eq!(
+            changes,
+            vec![Change {
+                line: 7,
+                removed: vec!["    todo!()".into()],
+                added: vec!["    42".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn unified_diff_rejects_bad_hunks() {
+        let mismatch = "@@ -2,1 +2,1 @@\n-    let x = 2;\n+    let x = 3;\n";
+        assert!(apply_patch(SOURCE, mismatch).unwrap_err().contains("does not match line 2"));
+
+        let outside = "@@ -40,1 +40,1 @@\n-}\n+};\n";
+        assert!(apply_patch(SOURCE, outside).unwrap_err().contains("outside the file"));
+
+        let short = "@@ -2,2 +2,2 @@\n-    let x = 1;\n+    let x = 2;\n";
+        assert!(apply_patch(SOURCE, short).unwrap_err().contains("incomplete"));
+
+        let ambiguous = "@@ -1,1 +1,1 @@\n-}\n+};\n";
+        assert!(apply_patch(SOURCE, ambiguous).unwrap_err().contains("2 times"));
+    }
+
+    #[test]
+    fn search_replace_requires_unique_match() {
+        let edits = vec![("let x = 1;".to_string(), "let x = 2;".to_string())];
+        let (out, changes) = apply_edits(SOURCE, &edits).unwrap();
+        assert!(out.contains("let x = 2;"));
+        assert_eq!(changes[0].line, 2);
+
+        let ambiguous = vec![("}\n".to_string(), "};\n".to_string())];
+        assert!(apply_edits(SOURCE, &ambiguous).unwrap_err().contains("2 times"));
+        let missing = vec![("nope".to_string(), String::new())];
+        assert!(apply_edits(SOURCE, &missing).unwrap_err().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn file_patch_dry_run_then_apply_with_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("main.rs"), SOURCE.replace('\n', "\r\n"))
+            .await
+            .unwrap();
+        let args = json!({
+            "path": "main.rs",
+            "edits": [{"search": "todo!()", "replace": "42"}],
+            "dry_run": true
+        });
+
+        // Dry runs are read-only and work even without write autonomy.
+        let readonly = FilePatchTool::new(test_security(dir.path().to_path_buf(), AutonomyLevel::ReadOnly));
+        let result = readonly.execute(args.clone()).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("+42"));
+        let on_disk = tokio::fs::read_to_string(dir.path().join("main.rs")).await.unwrap();
+        assert!(on_disk.contains("todo!()"));
+
+        let mut apply = args;
+        apply["dry_run"] = json!(false);
+        assert!(!readonly.execute(apply.clone()).await.unwrap().success);
+
+        let tool = FilePatchTool::new(test_security(dir.path().to_path_buf(), AutonomyLevel::Supervised));
+        let result = tool.execute(apply).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let on_disk = tokio::fs::read_to_string(dir.path().join("main.rs")).await.unwrap();
+        assert!(on_disk.contains("    42\r\n}\r\n"));
+
+        let backup = result.data.unwrap()["backup"].as_str().unwrap().to_string();
+        assert!(backup.starts_with(".mymolt/backups/main.rs."));
+        let saved = tokio::fs::read_to_string(dir.path().join(backup)).await.unwrap();
+        assert!(saved.contains("todo!()"));
+    }
+
+    #[tokio::test]
+    async fn file_patch_blocks_path_traversal() {
+        let tool = FilePatchTool::new(test_security(std::env::temp_dir(), AutonomyLevel::Supervised));
+        let result = tool
+            .execute(json!({"path": "../../etc/passwd", "edits": [{"search": "root", "replace": "x"}]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+    }
+}
@@ -55,6 +55,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                data: None,
             });
         }
 
@@ -64,6 +65,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Path not allowed by security policy: {path}")),
+                data: None,
             });
         }
 
@@ -77,6 +79,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    data: None,
                 });
             }
         };
@@ -89,6 +92,7 @@ impl Tool for FileReadTool {
                     "Resolved path escapes workspace: {}",
                     resolved_path.display()
                 )),
+                data: None,
             });
         }
 
@@ -103,6 +107,7 @@ impl Tool for FileReadTool {
                             "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
                             meta.len()
                         )),
+                        data: None,
                     });
                 }
             }
@@ -111,6 +116,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to read file metadata: {e}")),
+                    data: None,
                 });
             }
         }
@@ -120,6 +126,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                data: None,
             });
         }
 
@@ -128,11 +135,13 @@ impl Tool for FileReadTool {
                 success: true,
                 output: contents,
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to read file: {e}")),
+                data: None,
             }),
         }
     }
@@ -62,6 +62,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                data: None,
             });
         }
 
@@ -70,6 +71,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                data: None,
             });
         }
 
@@ -79,6 +81,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Path not allowed by security policy: {path}")),
+                data: None,
             });
         }
 
@@ -89,6 +92,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Invalid path: missing parent directory".into()),
+                data: None,
             });
         };
 
@@ -103,6 +107,7 @@ impl Tool for FileWriteTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    data: None,
                 });
             }
         };
@@ -115,6 +120,7 @@ impl Tool for FileWriteTool {
                     "Resolved path escapes workspace: {}",
                     resolved_parent.display()
                 )),
+                data: None,
             });
         }
 
@@ -123,6 +129,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Invalid path: missing file name".into()),
+                data: None,
             });
         };
 
@@ -138,6 +145,7 @@ impl Tool for FileWriteTool {
                         "Refusing to write through symlink: {}",
                         resolved_target.display()
                     )),
+                    data: None,
                 });
             }
         }
@@ -147,6 +155,7 @@ impl Tool for FileWriteTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                data: None,
             });
         }
 
@@ -155,11 +164,13 @@ impl Tool for FileWriteTool {
                 success: true,
                 output: format!("Written {} bytes to {path}", content.len()),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to write file: {e}")),
+                data: None,
             }),
         }
     }
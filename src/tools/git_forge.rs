@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Pull request creation on GitHub or Gitea for the `git_operations` tool.
+//!
+//! The access token is kept encrypted in the workspace and managed through
+//! `/api/git/forge`; the model never sees it. Owner and repository are taken
+//! from the remote URL, so only the forge kind and token are configured.
+
+use crate::network::egress;
+use crate::security::secrets::SecretStore;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const FORGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ── Account ─────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Github,
+    Gitea,
+}
+
+/// Forge credentials for `pr_create`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForgeAccount {
+    pub kind: ForgeKind,
+    /// API base URL. Default: `https://api.github.com` for GitHub,
+    /// `https://<remote host>/api/v1` for Gitea.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    pub token: String,
+}
+
+fn account_path(workspace: &Path) -> PathBuf {
+    workspace.join(".mymolt").join("git_forge.json")
+}
+
+impl ForgeAccount {
+    /// The stored account, if one was set up.
+    pub fn load(workspace: &Path, secrets: Option<&SecretStore>) -> Option<Self> {
+        let raw = std::fs::read_to_string(account_path(workspace)).ok()?;
+        let json = match secrets {
+            Some(store) => store.decrypt(&raw).unwrap_or(raw),
+            None => raw,
+        };
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, workspace: &Path, secrets: Option<&SecretStore>) -> Result<()> {
+        let path = account_path(workspace);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        let data = match secrets {
+            Some(store) => store.encrypt(&json)?,
+            None => json,
+        };
+        std::fs::write(&path, data)?;
+        Ok(())
+    }
+
+    /// Remove the stored account. Returns whether one existed.
+    pub fn remove(workspace: &Path) -> Result<bool> {
+        match std::fs::remove_file(account_path(workspace)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.token.trim().is_empty() {
+            bail!("Token is required");
+        }
+        if let Some(url) = &self.api_url {
+            let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid api_url: {e}"))?;
+            if parsed.scheme() != "https" {
+                bail!("api_url must use https");
+            }
+        }
+        Ok(())
+    }
+}
+
+// ── Remote ──────────────────────────────────────────────────────
+
+/// Host, owner and repository of a git remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRepo {
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RemoteRepo {
+    /// Parse `https://host/owner/repo(.git)`, `ssh://git@host[:port]/owner/repo`
+    /// and scp-style `git@host:owner/repo.git` remotes.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            (host.split(':').next()?, path)
+        } else {
+            let (user_host, path) = url.split_once(':')?;
+            (user_host.rsplit('@').next()?, path)
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let (owner, repo) = path.rsplit_once('/')?;
+        if host.is_empty() || owner.is_empty() || repo.is_empty() {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+// ── Pull requests ───────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub title: String,
+    pub body: String,
+    pub head: String,
+    pub base: String,
+    pub draft: bool,
+}
+
+/// The created pull request.
+#[derive(Debug, Deserialize)]
+pub struct CreatedPullRequest {
+    pub number: u64,
+    pub html_url: String,
+}
+
+/// Endpoint for creating a pull request in `remote`.
+fn pulls_url(account: &ForgeAccount, remote: &RemoteRepo) -> String {
+    let base = match (&account.api_url, account.kind) {
+        (Some(url), _) => url.trim_end_matches('/').to_string(),
+        (None, ForgeKind::Github) => "https://api.github.com".to_string(),
+        (None, ForgeKind::Gitea) => format!("https://{}/api/v1", remote.host),
+    };
+    format!("{base}/repos/{}/{}/pulls", remote.owner, remote.repo)
+}
+
+/// Open a pull request. GitHub and Gitea take the same request body.
+pub async fn create_pull_request(
+    account: &ForgeAccount,
+    remote: &RemoteRepo,
+    pr: &PullRequest,
+) -> Result<CreatedPullRequest> {
+    let url = pulls_url(account, remote);
+    egress::check(&url, None)?;
+    let client = egress::client_builder(None)
+        .timeout(FORGE_TIMEOUT)
+        .user_agent("MyMolt")
+        .build()?;
+    let auth = match account.kind {
+        ForgeKind::Github => format!("Bearer {}", account.token),
+        ForgeKind::Gitea => format!("token {}", account.token),
+    };
+    let mut body = json!({
+        "title": pr.title,
+        "body": pr.body,
+        "head": pr.head,
+        "base": pr.base,
+    });
+    if pr.draft && account.kind == ForgeKind::Github {
+        body["draft"] = json!(true);
+    }
+
+    let response = client
+        .post(url)
+        .header(reqwest::header::AUTHORIZATION, auth)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to reach the forge API")?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        bail!(
+            "Forge API error ({status}): {}",
+            crate::util::truncate_with_ellipsis(&text, 300)
+        );
+    }
+    response
+        .json()
+        .await
+        .context("Failed to parse the forge response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(host: &str, owner: &str, repo: &str) -> RemoteRepo {
+        RemoteRepo {
+            host: host.into(),
+            owner: owner.into(),
+            repo: repo.into(),
+        }
+    }
+
+    #[test]
+    fn parses_common_remote_formats() {
+        let expected = remote("github.com", "beykuet", "MyMolt");
+        assert_eq!(
+            RemoteRepo::parse("https://github.com/beykuet/MyMolt.git"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            RemoteRepo::parse("git@github.com:beykuet/MyMolt.git"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            RemoteRepo::parse("ssh://git@github.com:22/beykuet/MyMolt"),
+            Some(expected)
+        );
+        assert_eq!(
+            RemoteRepo::parse("https://git.example.org/group/sub/tool/"),
+            Some(remote("git.example.org", "group/sub", "tool"))
+        );
+        assert_eq!(RemoteRepo::parse("/srv/git/repo.git"), None);
+        assert_eq!(RemoteRepo::parse("https://github.com/only-owner"), None);
+    }
+
+    #[test]
+    fn pulls_url_per_forge() {
+        let github = ForgeAccount {
+            kind: ForgeKind::Github,
+            api_url: None,
+            token: "t".into(),
+        };
+        let repo = remote("git.example.org", "team", "app");
+        assert_eq!(
+            pulls_url(&github, &repo),
+            "https://api.github.com/repos/team/app/pulls"
+        );
+
+        let gitea = ForgeAccount {
+            kind: ForgeKind::Gitea,
+            ..github.clone()
+        };
+        assert_eq!(
+            pulls_url(&gitea, &repo),
+            "https://git.example.org/api/v1/repos/team/app/pulls"
+        );
+
+        let enterprise = ForgeAccount {
+            api_url: Some("https://ghe.example.org/api/v3/".into()),
+            ..github
+        };
+        assert_eq!(
+            pulls_url(&enterprise, &repo),
+            "https://ghe.example.org/api/v3/repos/team/app/pulls"
+        );
+    }
+
+    #[test]
+    fn account_roundtrips_encrypted_and_validates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = Some(SecretStore::new(&tmp.path().join(".mymolt"), true));
+        let account = ForgeAccount {
+            kind: ForgeKind::Gitea,
+            api_url: None,
+            token: "gitea-secret-token".into(),
+        };
+        account.save(tmp.path(), secrets.as_ref()).unwrap();
+
+        let raw = std::fs::read_to_string(account_path(tmp.path())).unwrap();
+        assert!(!raw.contains("gitea-secret-token"));
+        assert_eq!(ForgeAccount::load(tmp.path(), secrets.as_ref()), Some(account.clone()));
+        assert!(ForgeAccount::remove(tmp.path()).unwrap());
+        assert_eq!(ForgeAccount::load(tmp.path(), secrets.as_ref()), None);
+
+        assert!(account.validate().is_ok());
+        let insecure = ForgeAccount {
+            api_url: Some("http://git.example.org/api/v1".into()),
+            ..account
+        };
+        assert!(insecure.validate().is_err());
+    }
+}
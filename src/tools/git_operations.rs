@@ -2,7 +2,10 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use super::git_forge::{self, ForgeAccount, PullRequest, RemoteRepo};
 use super::traits::{Tool, ToolResult};
+use crate::security::confirmation::ConfirmationGate;
+use crate::security::secrets::SecretStore;
 use crate::security::{AutonomyLevel, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
@@ -10,9 +13,15 @@ use std::sync::Arc;
 
 /// Git operations tool for structured repository management.
 /// Provides safe, parsed git operations with JSON output.
+///
+/// `push` and `pr_create` leave the machine, so both are confirmed by the
+/// user through the [`ConfirmationGate`]; without a gate they are refused.
 pub struct GitOperationsTool {
     security: Arc<SecurityPolicy>,
     workspace_dir: std::path::PathBuf,
+    confirm_gate: Option<Arc<ConfirmationGate>>,
+    /// Decrypts the forge token for `pr_create`.
+    secrets: Option<SecretStore>,
 }
 
 impl GitOperationsTool {
@@ -20,6 +29,89 @@ impl GitOperationsTool {
         Self {
             security,
             workspace_dir,
+            confirm_gate: None,
+            secrets: None,
+        }
+    }
+
+    /// Enable `push` and `pr_create`, confirmed through `gate`.
+    #[must_use]
+    pub fn with_publishing(
+        mut self,
+        gate: Option<Arc<ConfirmationGate>>,
+        secrets: Option<SecretStore>,
+    ) -> Self {
+        self.confirm_gate = gate;
+        self.secrets = secrets;
+        self
+    }
+
+    /// Validate a branch or ref name supplied by the model.
+    fn validate_ref(name: &str) -> anyhow::Result<()> {
+        if name.is_empty()
+            || name.len() > 200
+            || name.starts_with('-')
+            || name.contains("..")
+            || name.contains("@{")
+            || name.ends_with(".lock")
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'))
+        {
+            anyhow::bail!("Invalid branch or ref name: {name}");
+        }
+        Ok(())
+    }
+
+    /// Pathspecs for `diff`: a single string or an array, each kept inside
+    /// the workspace by the security policy.
+    fn diff_paths(&self, args: &serde_json::Value) -> anyhow::Result<Vec<String>> {
+        let paths: Vec<String> = match args.get("files") {
+            None | Some(serde_json::Value::Null) => vec![".".into()],
+            Some(serde_json::Value::String(path)) => vec![path.clone()],
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(String::from)
+                        .ok_or_else(|| anyhow::anyhow!("'files' entries must be strings"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(_) => anyhow::bail!("'files' must be a path or a list of paths"),
+        };
+        for path in &paths {
+            if path.starts_with('-') || !self.security.is_path_allowed(path) {
+                anyhow::bail!("Path not allowed by security policy: {path}");
+            }
+        }
+        Ok(paths)
+    }
+
+    async fn current_branch(&self) -> anyhow::Result<String> {
+        let branch = self
+            .run_git_command(&["rev-parse", "--abbrev-ref", "HEAD"])
+            .await?;
+        let branch = branch.trim();
+        if branch == "HEAD" {
+            anyhow::bail!("HEAD is detached; check out a branch first");
+        }
+        Ok(branch.to_string())
+    }
+
+    /// Ask the user before anything leaves the machine.
+    async fn confirm(&self, summary: &str) -> Option<ToolResult> {
+        let Some(gate) = &self.confirm_gate else {
+            return Some(failure(
+                "This git operation requires user confirmation but no confirmation channel is available."
+                    .into(),
+            ));
+        };
+        if gate.request(self.name(), summary).await {
+            None
+        } else {
+            Some(failure(
+                "User denied the git operation (or the request timed out).".into(),
+            ))
         }
     }
 
@@ -48,7 +140,15 @@ impl GitOperationsTool {
     fn requires_write_access(&self, operation: &str) -> bool {
         matches!(
             operation,
-            "commit" | "add" | "checkout" | "branch" | "stash" | "reset" | "revert"
+            "commit"
+                | "add"
+                | "checkout"
+                | "branch"
+                | "stash"
+                | "reset"
+                | "revert"
+                | "push"
+                | "pr_create"
         )
     }
 
@@ -125,25 +225,68 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            data: None,
         })
     }
 
     async fn git_diff(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let files = args.get("files").and_then(|v| v.as_str()).unwrap_or(".");
+        let files = match self.diff_paths(&args) {
+            Ok(files) => files,
+            Err(e) => return Ok(failure(e.to_string())),
+        };
         let cached = args
             .get("cached")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let base = args.get("base").and_then(|v| v.as_str());
+        if let Some(base) = base {
+            if let Err(e) = Self::validate_ref(base) {
+                return Ok(failure(e.to_string()));
+            }
+        }
+        let stat = args.get("stat").and_then(|v| v.as_bool()).unwrap_or(false);
 
-        let mut git_args = vec!["diff", "--unified=3"];
+        let mut git_args = vec!["diff"];
+        git_args.push(if stat { "--numstat" } else { "--unified=3" });
         if cached {
             git_args.push("--cached");
         }
+        if let Some(base) = base {
+            git_args.push(base);
+        }
         git_args.push("--");
-        git_args.push(files);
+        git_args.extend(files.iter().map(String::as_str));
 
         let output = self.run_git_command(&git_args).await?;
 
+        if stat {
+            let changed: Vec<_> = output
+                .lines()
+                .filter_map(|line| {
+                    let mut parts = line.splitn(3, '\t');
+                    let added = parts.next()?;
+                    let deleted = parts.next()?;
+                    let file = parts.next()?;
+                    // Binary files report "-" for both counts.
+                    Some(json!({
+                        "file": file,
+                        "added": added.parse::<u64>().ok(),
+                        "deleted": deleted.parse::<u64>().ok(),
+                    }))
+                })
+                .collect();
+            return Ok(ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&json!({
+                    "files": changed,
+                    "file_count": changed.len(),
+                }))
+                .unwrap_or_default(),
+                error: None,
+                data: None,
+            });
+        }
+
         // Parse diff into structured hunks
         let mut result = serde_json::Map::new();
         let mut hunks = Vec::new();
@@ -201,6 +344,7 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            data: None,
         })
     }
 
@@ -237,10 +381,19 @@ impl GitOperationsTool {
             output: serde_json::to_string_pretty(&json!({ "commits": commits }))
                 .unwrap_or_default(),
             error: None,
+            data: None,
         })
     }
 
-    async fn git_branch(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+    async fn git_branch(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("list");
+        if action != "list" {
+            return self.git_branch_change(action, &args).await;
+        }
+
         let output = self
             .run_git_command(&["branch", "--format=%(refname:short)|%(HEAD)"])
             .await?;
@@ -269,6 +422,65 @@ impl GitOperationsTool {
             }))
             .unwrap_or_default(),
             error: None,
+            data: None,
+        })
+    }
+
+    /// Create, switch to, or delete a branch.
+    async fn git_branch_change(
+        &self,
+        action: &str,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(name) = args.get("branch").and_then(|v| v.as_str()) else {
+            return Ok(failure(format!(
+                "Missing 'branch' parameter for branch {action}"
+            )));
+        };
+        if let Err(e) = Self::validate_ref(name) {
+            return Ok(failure(e.to_string()));
+        }
+        let start = args.get("start_point").and_then(|v| v.as_str());
+        if let Some(start) = start {
+            if let Err(e) = Self::validate_ref(start) {
+                return Ok(failure(e.to_string()));
+            }
+        }
+
+        let (output, done) = match action {
+            // `switch -c` creates and checks out in one step.
+            "create" => {
+                let mut git_args = vec!["switch", "-c", name];
+                git_args.extend(start);
+                (
+                    self.run_git_command(&git_args).await,
+                    format!("Created and switched to branch: {name}"),
+                )
+            }
+            "switch" => (
+                self.run_git_command(&["switch", name]).await,
+                format!("Switched to branch: {name}"),
+            ),
+            // `-d` refuses to drop unmerged work.
+            "delete" => (
+                self.run_git_command(&["branch", "-d", name]).await,
+                format!("Deleted branch: {name}"),
+            ),
+            _ => {
+                return Ok(failure(format!(
+                    "Unknown branch action: {action}. Use: list, create, switch, delete"
+                )))
+            }
+        };
+
+        Ok(match output {
+            Ok(_) => ToolResult {
+                success: true,
+                output: done,
+                error: None,
+                data: None,
+            },
+            Err(e) => failure(format!("Branch {action} failed: {e}")),
         })
     }
 
@@ -304,11 +516,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Committed: {message}"),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Commit failed: {e}")),
+                data: None,
             }),
         }
     }
@@ -326,11 +540,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Staged: {paths}"),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Add failed: {e}")),
+                data: None,
             }),
         }
     }
@@ -362,11 +578,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Switched to branch: {branch_name}"),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Checkout failed: {e}")),
+                data: None,
             }),
         }
     }
@@ -377,19 +595,38 @@ impl GitOperationsTool {
             .and_then(|v| v.as_str())
             .unwrap_or("push");
 
+        let index = args.get("index").and_then(|v| v.as_u64()).unwrap_or(0);
+        let stash_ref = format!("stash@{{{index}}}");
+
         let output = match action {
             "push" | "save" => {
-                self.run_git_command(&["stash", "push", "-m", "auto-stash"])
-                    .await
+                let message = args
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or("auto-stash");
+                let mut git_args = vec!["stash", "push", "-m", message];
+                if args
+                    .get("include_untracked")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+                {
+                    git_args.push("--include-untracked");
+                }
+                self.run_git_command(&git_args).await
             }
-            "pop" => self.run_git_command(&["stash", "pop"]).await,
-            "list" => self.run_git_command(&["stash", "list"]).await,
-            "drop" => {
-                let index = args.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as i32;
-                self.run_git_command(&["stash", "drop", &format!("stash@{{{index}}}")])
+            "pop" => self.run_git_command(&["stash", "pop", &stash_ref]).await,
+            "apply" => self.run_git_command(&["stash", "apply", &stash_ref]).await,
+            "show" => {
+                self.run_git_command(&["stash", "show", "--stat", &stash_ref])
                     .await
             }
-            _ => anyhow::bail!("Unknown stash action: {action}. Use: push, pop, list, drop"),
+            "list" => self.run_git_command(&["stash", "list"]).await,
+            "drop" => self.run_git_command(&["stash", "drop", &stash_ref]).await,
+            _ => anyhow::bail!(
+                "Unknown stash action: {action}. Use: push, pop, apply, show, list, drop"
+            ),
         };
 
         match output {
@@ -397,14 +634,148 @@ impl GitOperationsTool {
                 success: true,
                 output: out,
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Stash {action} failed: {e}")),
+                data: None,
             }),
         }
     }
+
+    async fn git_push(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let remote = args
+            .get("remote")
+            .and_then(|v| v.as_str())
+            .unwrap_or("origin");
+        let branch = match args.get("branch").and_then(|v| v.as_str()) {
+            Some(branch) => branch.to_string(),
+            None => match self.current_branch().await {
+                Ok(branch) => branch,
+                Err(e) => return Ok(failure(e.to_string())),
+            },
+        };
+        for name in [remote, branch.as_str()] {
+            if let Err(e) = Self::validate_ref(name) {
+                return Ok(failure(e.to_string()));
+            }
+        }
+
+        // Never forced: a rejected push is reported, not overwritten.
+        if let Some(denied) = self
+            .confirm(&format!("git push {remote} {branch} (sets upstream)"))
+            .await
+        {
+            return Ok(denied);
+        }
+        let output = self
+            .run_git_command(&["push", "--set-upstream", remote, &branch])
+            .await;
+
+        Ok(match output {
+            Ok(_) => ToolResult {
+                success: true,
+                output: format!("Pushed {branch} to {remote}"),
+                error: None,
+                data: None,
+            },
+            Err(e) => failure(format!("Push failed: {e}")),
+        })
+    }
+
+    async fn git_pr_create(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let title = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'title' parameter"))?;
+        let body = args.get("body").and_then(|v| v.as_str()).unwrap_or("");
+        let base = args.get("base").and_then(|v| v.as_str()).unwrap_or("main");
+        let remote_name = args
+            .get("remote")
+            .and_then(|v| v.as_str())
+            .unwrap_or("origin");
+        let head = match args.get("branch").and_then(|v| v.as_str()) {
+            Some(branch) => branch.to_string(),
+            None => match self.current_branch().await {
+                Ok(branch) => branch,
+                Err(e) => return Ok(failure(e.to_string())),
+            },
+        };
+        for name in [base, remote_name, head.as_str()] {
+            if let Err(e) = Self::validate_ref(name) {
+                return Ok(failure(e.to_string()));
+            }
+        }
+
+        let Some(account) = ForgeAccount::load(&self.workspace_dir, self.secrets.as_ref()) else {
+            return Ok(failure(
+                "No GitHub/Gitea token configured. Ask the user to add one in Settings → Git \
+                 (PUT /api/git/forge)."
+                    .into(),
+            ));
+        };
+        let url = self
+            .run_git_command(&["remote", "get-url", remote_name])
+            .await?;
+        let Some(remote) = RemoteRepo::parse(&url) else {
+            return Ok(failure(format!(
+                "Cannot determine owner/repository from remote '{remote_name}': {}",
+                url.trim()
+            )));
+        };
+
+        let pr = PullRequest {
+            title: title.to_string(),
+            body: body.to_string(),
+            head,
+            base: base.to_string(),
+            draft: args.get("draft").and_then(|v| v.as_bool()).unwrap_or(false),
+        };
+        let summary = format!(
+            "Open pull request \"{}\" on {}/{}/{} ({} → {}): {}",
+            pr.title,
+            remote.host,
+            remote.owner,
+            remote.repo,
+            pr.head,
+            pr.base,
+            crate::util::truncate_with_ellipsis(&pr.body, 200)
+        );
+        if let Some(denied) = self.confirm(&summary).await {
+            return Ok(denied);
+        }
+
+        Ok(
+            match git_forge::create_pull_request(&account, &remote, &pr).await {
+                Ok(created) => ToolResult {
+                    success: true,
+                    output: format!(
+                        "Opened pull request #{}: {}",
+                        created.number, created.html_url
+                    ),
+                    error: None,
+                    data: Some(json!({
+                        "number": created.number,
+                        "url": created.html_url,
+                    })),
+                },
+                Err(e) => failure(format!("Pull request failed: {e}")),
+            },
+        )
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
 }
 
 #[async_trait]
@@ -414,7 +785,7 @@ impl Tool for GitOperationsTool {
     }
 
     fn description(&self) -> &str {
-        "Perform structured Git operations (status, diff, log, branch, commit, add, checkout, stash). Provides parsed JSON output and integrates with security policy for autonomy controls."
+        "Perform structured Git operations (status, diff, log, branch, commit, add, checkout, stash, push, pr_create). Provides parsed JSON output and integrates with security policy for autonomy controls. push and pr_create must be approved by the user."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -423,12 +794,12 @@ impl Tool for GitOperationsTool {
             "properties": {
                 "operation": {
                     "type": "string",
-                    "enum": ["status", "diff", "log", "branch", "commit", "add", "checkout", "stash"],
+                    "enum": ["status", "diff", "log", "branch", "commit", "add", "checkout", "stash", "push", "pr_create"],
                     "description": "Git operation to perform"
                 },
                 "message": {
                     "type": "string",
-                    "description": "Commit message (for 'commit' operation)"
+                    "description": "Commit message (for 'commit'), or stash message (for 'stash' push)"
                 },
                 "paths": {
                     "type": "string",
@@ -436,28 +807,61 @@ impl Tool for GitOperationsTool {
                 },
                 "branch": {
                     "type": "string",
-                    "description": "Branch name (for 'checkout' operation)"
+                    "description": "Branch name (for 'checkout', 'branch' create/switch/delete, 'push', and the head of 'pr_create'; default: current branch)"
                 },
-                "files": {
+                "start_point": {
                     "type": "string",
-                    "description": "File or path to diff (for 'diff' operation, default: '.')"
+                    "description": "Commit or branch to start from (for 'branch' create, default: HEAD)"
+                },
+                "files": {
+                    "type": ["string", "array"],
+                    "items": {"type": "string"},
+                    "description": "Path or list of paths to diff (for 'diff' operation, default: '.')"
                 },
                 "cached": {
                     "type": "boolean",
                     "description": "Show staged changes (for 'diff' operation)"
                 },
+                "base": {
+                    "type": "string",
+                    "description": "Branch or commit to diff against (for 'diff'), or target branch (for 'pr_create', default: main)"
+                },
+                "stat": {
+                    "type": "boolean",
+                    "description": "Only list changed files with added/deleted line counts (for 'diff' operation)"
+                },
                 "limit": {
                     "type": "integer",
                     "description": "Number of log entries (for 'log' operation, default: 10)"
                 },
                 "action": {
                     "type": "string",
-                    "enum": ["push", "pop", "list", "drop"],
-                    "description": "Stash action (for 'stash' operation)"
+                    "enum": ["list", "create", "switch", "delete", "push", "pop", "apply", "show", "drop"],
+                    "description": "Branch action: list (default), create, switch, delete. Stash action: push (default), pop, apply, show, list, drop"
                 },
                 "index": {
                     "type": "integer",
-                    "description": "Stash index (for 'stash' with 'drop' action)"
+                    "description": "Stash index (for 'stash' pop/apply/show/drop, default: 0)"
+                },
+                "include_untracked": {
+                    "type": "boolean",
+                    "description": "Also stash untracked files (for 'stash' push)"
+                },
+                "remote": {
+                    "type": "string",
+                    "description": "Remote name (for 'push' and 'pr_create', default: origin)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Pull request title (for 'pr_create')"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Pull request description (for 'pr_create')"
+                },
+                "draft": {
+                    "type": "boolean",
+                    "description": "Open the pull request as a draft (for 'pr_create', GitHub only)"
                 }
             },
             "required": ["operation"]
@@ -472,6 +876,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'operation' parameter".into()),
+                    data: None,
                 });
             }
         };
@@ -494,6 +899,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Not in a git repository".into()),
+                    data: None,
                 });
             }
         }
@@ -507,6 +913,7 @@ impl Tool for GitOperationsTool {
                     error: Some(
                         "Action blocked: git write operations require higher autonomy level".into(),
                     ),
+                    data: None,
                 });
             }
 
@@ -516,6 +923,7 @@ impl Tool for GitOperationsTool {
                         success: false,
                         output: String::new(),
                         error: Some("Action blocked: read-only mode".into()),
+                        data: None,
                     });
                 }
                 AutonomyLevel::Supervised => {
@@ -533,6 +941,7 @@ impl Tool for GitOperationsTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                data: None,
             });
         }
 
@@ -546,10 +955,13 @@ impl Tool for GitOperationsTool {
             "add" => self.git_add(args).await,
             "checkout" => self.git_checkout(args).await,
             "stash" => self.git_stash(args).await,
+            "push" => self.git_push(args).await,
+            "pr_create" => self.git_pr_create(args).await,
             _ => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Unknown operation: {operation}")),
+                data: None,
             }),
         }
     }
@@ -692,7 +1104,7 @@ mod tests {
 
         let tool = test_tool(tmp.path());
 
-        let result = tool.execute(json!({"operation": "push"})).await.unwrap();
+        let result = tool.execute(json!({"operation": "rebase"})).await.unwrap();
         assert!(!result.success);
         assert!(result
             .error
@@ -700,4 +1112,157 @@ mod tests {
             .unwrap_or("")
             .contains("Unknown operation"));
     }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn init_repo(dir: &Path) {
+        git(dir, &["init", "-q", "-b", "main"]);
+        // Stash and commit need an identity even on machines without one.
+        git(dir, &["config", "user.name", "Test"]);
+        git(dir, &["config", "user.email", "test@example.org"]);
+        std::fs::write(dir.join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "one\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+    }
+
+    #[test]
+    fn validate_ref_rejects_option_and_revision_syntax() {
+        assert!(GitOperationsTool::validate_ref("feature/login-fix").is_ok());
+        assert!(GitOperationsTool::validate_ref("v1.2.3").is_ok());
+        assert!(GitOperationsTool::validate_ref("--force").is_err());
+        assert!(GitOperationsTool::validate_ref("main..evil").is_err());
+        assert!(GitOperationsTool::validate_ref("main@{1}").is_err());
+        assert!(GitOperationsTool::validate_ref("a b").is_err());
+        assert!(GitOperationsTool::validate_ref("").is_err());
+    }
+
+    #[tokio::test]
+    async fn branch_create_switch_and_delete() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        let tool = test_tool(tmp.path());
+
+        let result = tool
+            .execute(json!({"operation": "branch", "action": "create", "branch": "feature/x"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let result = tool
+            .execute(json!({"operation": "branch"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("\"current\": \"feature/x\""));
+
+        let result = tool
+            .execute(json!({"operation": "branch", "action": "switch", "branch": "main"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let result = tool
+            .execute(json!({"operation": "branch", "action": "delete", "branch": "feature/x"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let result = tool
+            .execute(json!({"operation": "branch", "action": "create", "branch": "-D"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Invalid branch"));
+    }
+
+    #[tokio::test]
+    async fn diff_filters_paths_and_reports_stats() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "changed\n").unwrap();
+        let tool = test_tool(tmp.path());
+
+        let result = tool
+            .execute(json!({"operation": "diff", "files": ["a.txt"], "stat": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("\"file\": \"a.txt\""));
+        assert!(result.output.contains("\"added\": 1"));
+        assert!(!result.output.contains("b.txt"));
+
+        let result = tool
+            .execute(json!({"operation": "diff", "base": "main", "files": "b.txt"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("+changed"));
+
+        let result = tool
+            .execute(json!({"operation": "diff", "files": ["/etc/passwd"]}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn stash_with_message_and_untracked() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("new.txt"), "draft\n").unwrap();
+        let tool = test_tool(tmp.path());
+
+        let result = tool
+            .execute(json!({
+                "operation": "stash",
+                "message": "wip login",
+                "include_untracked": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(!tmp.path().join("new.txt").exists());
+
+        let list = tool
+            .execute(json!({"operation": "stash", "action": "list"}))
+            .await
+            .unwrap();
+        assert!(list.output.contains("wip login"));
+
+        let result = tool
+            .execute(json!({"operation": "stash", "action": "pop"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(tmp.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn push_and_pr_need_confirmation_and_token() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        git(
+            tmp.path(),
+            &["remote", "add", "origin", "https://github.com/example/app.git"],
+        );
+        let tool = test_tool(tmp.path());
+
+        let result = tool
+            .execute(json!({"operation": "push"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("requires user confirmation"));
+
+        let result = tool
+            .execute(json!({"operation": "pr_create", "title": "Fix login"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("No GitHub/Gitea token"));
+    }
 }
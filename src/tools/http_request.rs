@@ -3,6 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Tool, ToolResult};
+use crate::network::egress;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -109,7 +110,8 @@ impl HttpRequestTool {
         headers: Vec<(String, String)>,
         body: Option<&str>,
     ) -> anyhow::Result<reqwest::Response> {
-        let client = reqwest::Client::builder()
+        egress::check(url, None)?;
+        let client = egress::client_builder(None)
             .timeout(Duration::from_secs(self.timeout_secs))
             .build()?;
 
@@ -193,6 +195,7 @@ impl Tool for HttpRequestTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                data: None,
             });
         }
 
@@ -201,6 +204,7 @@ impl Tool for HttpRequestTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                data: None,
             });
         }
 
@@ -211,6 +215,7 @@ impl Tool for HttpRequestTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    data: None,
                 })
             }
         };
@@ -222,6 +227,7 @@ impl Tool for HttpRequestTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    data: None,
                 })
             }
         };
@@ -272,12 +278,14 @@ impl Tool for HttpRequestTool {
                     } else {
                         None
                     },
+                    data: None,
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("HTTP request failed: {e}")),
+                data: None,
             }),
         }
     }
@@ -324,7 +332,7 @@ fn normalize_domain(raw: &str) -> Option<String> {
     Some(d)
 }
 
-fn extract_host(url: &str) -> anyhow::Result<String> {
+pub(crate) fn extract_host(url: &str) -> anyhow::Result<String> {
     let rest = url
         .strip_prefix("http://")
         .or_else(|| url.strip_prefix("https://"))
@@ -371,7 +379,7 @@ fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
     })
 }
 
-fn is_private_or_local_host(host: &str) -> bool {
+pub(crate) fn is_private_or_local_host(host: &str) -> bool {
     let has_local_tld = host
         .rsplit('.')
         .next()
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! iCalendar (RFC 5545) export and import for the PIM calendar.
+//!
+//! Covers what a [`CalendarEvent`] stores: `UID`, `SUMMARY`, `DTSTART` (a
+//! date or a local time) and `DESCRIPTION`. Imported events keep their `UID`
+//! as id, so importing the same calendar twice adds nothing. Recurring
+//! events are imported as their first occurrence; time zones are not
+//! converted except for UTC times.
+
+use crate::tools::pim::CalendarEvent;
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Longest line before folding, in octets (RFC 5545 §3.1).
+const FOLD_AT: usize = 75;
+
+/// Serialize `events` as a `VCALENDAR`.
+pub fn export_calendar(events: &[CalendarEvent]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//MyMolt//PIM//EN", "CALSCALE:GREGORIAN"] {
+        push_line(&mut out, line);
+    }
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape(&event.id)));
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(&mut out, &dtstart(event));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.title)));
+        if !event.description.is_empty() {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(&event.description)));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn dtstart(event: &CalendarEvent) -> String {
+    let date = event.date.replace('-', "");
+    match event
+        .time
+        .as_deref()
+        .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok())
+    {
+        Some(time) => format!("DTSTART:{date}T{}", time.format("%H%M%S")),
+        None => format!("DTSTART;VALUE=DATE:{date}"),
+    }
+}
+
+/// Append `line` with CRLF, folding it into 75-octet chunks.
+pub(crate) fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > FOLD_AT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Events parsed from an iCalendar file.
+#[derive(Debug, Default)]
+pub struct ParsedCalendar {
+    pub events: Vec<CalendarEvent>,
+    /// `VEVENT`s without a usable `DTSTART`.
+    pub skipped: usize,
+}
+
+/// Parse the `VEVENT`s in `text`. Events without a `UID` get a random id.
+pub fn parse_calendar(text: &str) -> ParsedCalendar {
+    let mut parsed = ParsedCalendar::default();
+    let mut current: Option<Vec<(String, String, String)>> = None;
+
+    for line in unfold(text) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match (name.as_str(), value.trim()) {
+            ("BEGIN", v) if v.eq_ignore_ascii_case("VEVENT") => current = Some(Vec::new()),
+            ("END", v) if v.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(props) = current.take() {
+                    match event_from(&props) {
+                        Some(event) => parsed.events.push(event),
+                        None => parsed.skipped += 1,
+                    }
+                }
+            }
+            _ => {
+                if let Some(props) = current.as_mut() {
+                    props.push((name, params, value));
+                }
+            }
+        }
+    }
+    parsed
+}
+
+/// Join folded lines (continuations start with a space or tab).
+pub(crate) fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAMS:VALUE` → uppercase name, params, value. The value starts at
+/// the first colon outside a quoted parameter.
+pub(crate) fn split_property(line: &str) -> Option<(String, String, String)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.trim().to_uppercase(), params.to_uppercase(), value.to_string()))
+}
+
+fn event_from(props: &[(String, String, String)]) -> Option<CalendarEvent> {
+    let get = |name: &str| props.iter().find(|(n, _, _)| n == name);
+    let (_, _, start) = get("DTSTART")?;
+    let (date, time) = parse_start(start.trim())?;
+    Some(CalendarEvent {
+        id: get("UID")
+            .map(|(_, _, v)| unescape(v.trim()))
+            .filter(|uid| !uid.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        title: get("SUMMARY").map_or_else(|| "(untitled)".to_string(), |(_, _, v)| unescape(v)),
+        date: date.format("%Y-%m-%d").to_string(),
+        time: time.map(|t| t.format("%H:%M").to_string()),
+        description: get("DESCRIPTION").map(|(_, _, v)| unescape(v)).unwrap_or_default(),
+    })
+}
+
+/// `YYYYMMDD`, `YYYYMMDDTHHMMSS` (local/floating) or `...Z` (UTC, shown in
+/// local time).
+fn parse_start(value: &str) -> Option<(NaiveDate, Option<chrono::NaiveTime>)> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = Utc.from_utc_datetime(&naive).with_timezone(&Local).naive_local();
+        return Some((local.date(), Some(local.time())));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((naive.date(), Some(naive.time())));
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(|date| (date, None))
+}
+
+/// Add `events` whose id is not in `calendar` yet. Returns how many were added.
+pub fn merge_events(calendar: &mut Vec<CalendarEvent>, events: Vec<CalendarEvent>) -> usize {
+    let mut added = 0;
+    for event in events {
+        if !calendar.iter().any(|e| e.id == event.id) {
+            calendar.push(event);
+            added += 1;
+        }
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, time: Option<&str>, description: &str) -> CalendarEvent {
+        CalendarEvent {
+            id: id.into(),
+            title: "Dentist, Dr. Weiß; room 2".into(),
+            date: "2026-03-14".into(),
+            time: time.map(String::from),
+            description: description.into(),
+        }
+    }
+
+    #[test]
+    fn export_then_import_roundtrips() {
+        let events = vec![
+            event("a1", Some("09:30"), "Bring the\ninsurance card"),
+            event("b2", None, &"long ".repeat(40)),
+        ];
+        let ics = export_calendar(&events);
+        assert!(ics.contains("DTSTART:20260314T093000\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260314\r\n"));
+        assert!(ics.contains("SUMMARY:Dentist\\, Dr. Weiß\\; room 2\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= FOLD_AT));
+
+        let parsed = parse_calendar(&ics);
+        assert_eq!(parsed.skipped, 0);
+        assert_eq!(parsed.events.len(), 2);
+        for (got, want) in parsed.events.iter().zip(&events) {
+            assert_eq!(got.id, want.id);
+            assert_eq!(got.title, want.title);
+            assert_eq!(got.date, want.date);
+            assert_eq!(got.time, want.time);
+            assert_eq!(got.description, want.description);
+        }
+    }
+
+    #[test]
+    fn import_handles_foreign_calendars() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:abc@google.com\nDTSTART;TZID=\"Europe/Berlin\":20260401T180000\nSUMMARY:Choir\n  practice\nEND:VEVENT\nBEGIN:VEVENT\nSUMMARY:No start\nEND:VEVENT\nBEGIN:VEVENT\nDTSTART;VALUE=DATE:20260501\nSUMMARY:Holiday\nEND:VEVENT\nEND:VCALENDAR\n";
+        let parsed = parse_calendar(ics);
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.events.len(), 2);
+        assert_eq!(parsed.events[0].id, "abc@google.com");
+        assert_eq!(parsed.events[0].title, "Choir practice");
+        assert_eq!(parsed.events[0].time.as_deref(), Some("18:00"));
+        assert_eq!(parsed.events[1].date, "2026-05-01");
+        assert!(parsed.events[1].time.is_none());
+        assert!(!parsed.events[1].id.is_empty());
+    }
+
+    #[test]
+    fn merge_events_dedups_by_uid() {
+        let mut calendar = vec![event("a1", None, "")];
+        let added = merge_events(&mut calendar, vec![event("a1", None, "changed"), event("b2", None, "")]);
+        assert_eq!(added, 1);
+        assert_eq!(calendar.len(), 2);
+        assert_eq!(calendar[0].description, "");
+    }
+}
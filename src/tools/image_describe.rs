@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+use super::image_info::ImageInfoTool;
+use super::traits::{Tool, ToolResult};
+use crate::capture::ocr;
+use crate::config::VisionConfig;
+use crate::memory::sovereign::SensitivityScanner;
+use crate::providers::vision::VisionProvider;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+const DESCRIBE_PROMPT: &str = "Describe this image. Reply with JSON only: \
+     {\"caption\": \"one or two sentences\", \"objects\": [\"notable objects\"], \
+     \"text\": \"any legible text, verbatim\"}.";
+
+/// Structured answer requested from the vision model.
+#[derive(Debug, Default, Deserialize)]
+struct Description {
+    #[serde(default)]
+    caption: String,
+    #[serde(default)]
+    objects: Vec<String>,
+    #[serde(default)]
+    text: String,
+}
+
+impl Description {
+    /// Parse the JSON object in a model reply, falling back to using the
+    /// whole reply as the caption when the model ignored the format.
+    fn parse(reply: &str) -> Self {
+        let json = match (reply.find('{'), reply.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &reply[start..=end],
+            _ => "",
+        };
+        serde_json::from_str(json).unwrap_or_else(|_| Self {
+            caption: reply.trim().to_string(),
+            ..Self::default()
+        })
+    }
+}
+
+/// Tool that asks a separately configured multimodal model about an image.
+///
+/// Before anything is uploaded the image is OCR'd locally and refused if
+/// its text trips the sensitivity scanner; the question is redacted on the
+/// way out and the answer on the way back, as for delegation.
+pub struct ImageDescribeTool {
+    security: Arc<SecurityPolicy>,
+    provider: Arc<dyn VisionProvider>,
+    scanner: Arc<SensitivityScanner>,
+    config: VisionConfig,
+}
+
+impl ImageDescribeTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        provider: Arc<dyn VisionProvider>,
+        scanner: Arc<SensitivityScanner>,
+        config: VisionConfig,
+    ) -> Self {
+        Self {
+            security,
+            provider,
+            scanner,
+            config,
+        }
+    }
+
+    /// Local pre-upload check. `Err` carries the reason to refuse.
+    async fn scan_image(&self, image: &[u8]) -> Result<(), String> {
+        if !self.config.scan_images {
+            return Ok(());
+        }
+        let text = ocr::recognize(image, &self.config.ocr_languages)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Refusing to upload an image that could not be checked for sensitive text ({e}). \
+                     Install tesseract or set vision.scan_images = false."
+                )
+            })?;
+        match self.scanner.scan(&text) {
+            Some(pattern) => {
+                tracing::warn!(pattern, "image_describe: upload blocked by sensitivity scan");
+                Err(format!(
+                    "Refusing to upload: the image contains sensitive text ({pattern})"
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+#[async_trait]
+impl Tool for ImageDescribeTool {
+    fn name(&self) -> &str {
+        "image_describe"
+    }
+
+    fn description(&self) -> &str {
+        "Describe an image in the workspace with a multimodal model: returns a caption, detected \
+         objects and legible text. The image is uploaded to the configured vision provider."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the image file (PNG, JPEG, GIF, WebP), relative to the workspace"
+                },
+                "question": {
+                    "type": "string",
+                    "description": "Optional question about the image, answered in the caption"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let question = args
+            .get("question")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|q| !q.is_empty());
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+
+        if !self.security.is_path_allowed(path) {
+            return Ok(failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+        let resolved = match tokio::fs::canonicalize(self.security.workspace_dir.join(path)).await
+        {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Failed to resolve image path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Ok(failure(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            )));
+        }
+        match tokio::fs::metadata(&resolved).await {
+            Ok(meta) if !meta.is_file() => return Ok(failure(format!("Not a file: {path}"))),
+            Ok(meta) if meta.len() > self.config.max_image_bytes => {
+                return Ok(failure(format!(
+                    "Image too large: {} bytes (max {} bytes)",
+                    meta.len(),
+                    self.config.max_image_bytes
+                )));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(failure(format!("Failed to read image metadata: {e}"))),
+        }
+
+        let image = match tokio::fs::read(&resolved).await {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(failure(format!("Failed to read image: {e}"))),
+        };
+        let mime = match ImageInfoTool::detect_format(&image) {
+            "png" => "image/png",
+            "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            other => {
+                return Ok(failure(format!(
+                    "Unsupported image format '{other}': use PNG, JPEG, GIF or WebP"
+                )))
+            }
+        };
+
+        if let Err(reason) = self.scan_image(&image).await {
+            return Ok(failure(reason));
+        }
+
+        // ── SIGIL: Outgoing scan ── redact secrets in the question
+        let mut prompt = DESCRIBE_PROMPT.to_string();
+        let mut redactions = Vec::new();
+        if let Some(question) = question {
+            let (safe_question, found) = self.scanner.redact(question);
+            redactions.extend(found);
+            let _ = write!(
+                prompt,
+                " Answer this question about the image in the caption: {safe_question}"
+            );
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted".into()));
+        }
+
+        let reply = match self.provider.describe(&image, mime, &prompt).await {
+            Ok(reply) => reply,
+            Err(e) => return Ok(failure(format!("Vision provider failed: {e}"))),
+        };
+
+        // ── SIGIL: Incoming scan ── redact secrets the model read off the image
+        let (safe_reply, found) = self.scanner.redact(&reply);
+        redactions.extend(found);
+        if !redactions.is_empty() {
+            tracing::warn!(patterns = ?redactions, "image_describe: redacted sensitive content");
+        }
+        let description = Description::parse(&safe_reply);
+
+        let mut output = format!("Image: {path}\nCaption: {}", description.caption);
+        if !description.objects.is_empty() {
+            let _ = write!(output, "\nObjects: {}", description.objects.join(", "));
+        }
+        if !description.text.trim().is_empty() {
+            let _ = write!(output, "\nText:\n{}", description.text.trim());
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(json!({
+                "caption": description.caption,
+                "objects": description.objects,
+                "text": description.text,
+                "provider": self.provider.name(),
+                "redacted": redactions,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::vision::MockVisionProvider;
+    use crate::security::AutonomyLevel;
+
+    /// 1x1 transparent PNG.
+    const PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89,
+    ];
+
+    fn tool(
+        workspace: &std::path::Path,
+        provider: Arc<MockVisionProvider>,
+        scan_images: bool,
+    ) -> ImageDescribeTool {
+        ImageDescribeTool::new(
+            Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::Supervised,
+                workspace_dir: workspace.to_path_buf(),
+                ..SecurityPolicy::default()
+            }),
+            provider,
+            Arc::new(SensitivityScanner::new()),
+            VisionConfig {
+                enabled: true,
+                scan_images,
+                ..VisionConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn parses_json_and_plain_replies() {
+        let parsed = Description::parse(
+            "Sure!\n```json\n{\"caption\": \"A red car\", \"objects\": [\"car\"], \"text\": \"B-MM 42\"}\n```",
+        );
+        assert_eq!(parsed.caption, "A red car");
+        assert_eq!(parsed.objects, vec!["car"]);
+        assert_eq!(parsed.text, "B-MM 42");
+
+        let plain = Description::parse("A red car parked outside.");
+        assert_eq!(plain.caption, "A red car parked outside.");
+        assert!(plain.objects.is_empty());
+    }
+
+    #[tokio::test]
+    async fn describes_image_and_redacts_question_and_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("dialog.png"), PNG).unwrap();
+        let provider = Arc::new(MockVisionProvider::new(
+            r#"{"caption": "An error dialog", "objects": ["window"], "text": "IBAN DE89370400440532013000"}"#,
+        ));
+        let tool = tool(dir.path(), Arc::clone(&provider), false);
+
+        let result = tool
+            .execute(json!({
+                "path": "dialog.png",
+                "question": "Is my key sk-abcdefghijklmnopqrstuvwxyz012345 shown?"
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("Caption: An error dialog"));
+        assert!(result.output.contains("[REDACTED:"));
+        assert!(!result.output.contains("DE89370400440532013000"));
+
+        let prompts = provider.prompts.lock().unwrap();
+        assert!(!prompts[0].contains("sk-abcdefghijklmnopqrstuvwxyz012345"));
+    }
+
+    #[tokio::test]
+    async fn refuses_unsupported_formats_and_unscanned_uploads() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"plain text").unwrap();
+        std::fs::write(dir.path().join("shot.png"), PNG).unwrap();
+        let provider = Arc::new(MockVisionProvider::new("unused"));
+
+        let tool_unscanned = tool(dir.path(), Arc::clone(&provider), false);
+        let result = tool_unscanned
+            .execute(json!({"path": "notes.txt"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Unsupported image format"));
+
+        if !ocr::is_available().await {
+            let result = tool(dir.path(), Arc::clone(&provider), true)
+                .execute(json!({"path": "shot.png"}))
+                .await
+                .unwrap();
+            assert!(result.error.unwrap().contains("could not be checked"));
+            assert!(provider.prompts.lock().unwrap().is_empty());
+        }
+    }
+}
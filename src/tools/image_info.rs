@@ -28,7 +28,7 @@ impl ImageInfoTool {
     }
 
     /// Detect image format from first few bytes (magic numbers).
-    fn detect_format(bytes: &[u8]) -> &'static str {
+    pub(crate) fn detect_format(bytes: &[u8]) -> &'static str {
         if bytes.len() < 4 {
             return "unknown";
         }
@@ -170,6 +170,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Path not allowed: {path_str} (must be within workspace)"
                 )),
+                data: None,
             });
         }
 
@@ -178,6 +179,7 @@ impl Tool for ImageInfoTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("File not found: {path_str}")),
+                data: None,
             });
         }
 
@@ -194,6 +196,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Image too large: {file_size} bytes (max {MAX_IMAGE_BYTES} bytes)"
                 )),
+                data: None,
             });
         }
 
@@ -228,6 +231,7 @@ impl Tool for ImageInfoTool {
             success: true,
             output,
             error: None,
+            data: None,
         })
     }
 }
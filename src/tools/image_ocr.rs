@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+use super::traits::{Tool, ToolResult};
+use crate::capture::ocr;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Maximum recognized text returned (100 KB).
+const MAX_OCR_TEXT_BYTES: usize = 102_400;
+
+/// Tool to extract text from an image in the workspace with local OCR.
+///
+/// Uses the same `tesseract` pipeline as photo capture, so the image never
+/// leaves the machine.
+pub struct ImageOcrTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ImageOcrTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+#[async_trait]
+impl Tool for ImageOcrTool {
+    fn name(&self) -> &str {
+        "image_ocr"
+    }
+
+    fn description(&self) -> &str {
+        "Extract text from an image (screenshot, scanned page, photo of a document) with local OCR. \
+         Supports language selection, e.g. 'eng' or 'deu+eng'."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the image file, relative to the workspace"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Tesseract language code(s) joined with '+', e.g. 'eng', 'deu+eng' (default: eng)"
+                },
+                "list_languages": {
+                    "type": "boolean",
+                    "description": "Only list the installed OCR languages"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if args
+            .get("list_languages")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+        {
+            return Ok(match ocr::list_languages().await {
+                Ok(languages) => ToolResult {
+                    success: true,
+                    output: format!("Installed OCR languages: {}", languages.join(", ")),
+                    error: None,
+                    data: Some(json!({ "languages": languages })),
+                },
+                Err(e) => failure(e.to_string()),
+            });
+        }
+
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let language = args
+            .get("language")
+            .and_then(|v| v.as_str())
+            .unwrap_or("eng");
+        if !ocr::valid_languages(language) {
+            return Ok(failure(format!(
+                "Invalid language '{language}': use Tesseract codes like 'eng' or 'deu+eng'"
+            )));
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+
+        if !self.security.is_path_allowed(path) {
+            return Ok(failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+        let resolved = match tokio::fs::canonicalize(self.security.workspace_dir.join(path)).await
+        {
+            Ok(p) => p,
+            Err(e) => return Ok(failure(format!("Failed to resolve image path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Ok(failure(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            )));
+        }
+        // Check the size before reading so a huge file is never loaded.
+        match tokio::fs::metadata(&resolved).await {
+            Ok(meta) if !meta.is_file() => return Ok(failure(format!("Not a file: {path}"))),
+            Ok(meta) if meta.len() > ocr::MAX_IMAGE_BYTES as u64 => {
+                return Ok(failure(format!(
+                    "Image too large: {} bytes (max {} bytes)",
+                    meta.len(),
+                    ocr::MAX_IMAGE_BYTES
+                )));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(failure(format!("Failed to read image metadata: {e}"))),
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted".into()));
+        }
+
+        let image = match tokio::fs::read(&resolved).await {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(failure(format!("Failed to read image: {e}"))),
+        };
+        let mut text = match ocr::recognize(&image, language).await {
+            Ok(text) => text,
+            Err(e) => return Ok(failure(format!("OCR failed: {e}"))),
+        };
+
+        let truncated = text.len() > MAX_OCR_TEXT_BYTES;
+        if truncated {
+            text.truncate(text.floor_char_boundary(MAX_OCR_TEXT_BYTES));
+        }
+        let mut output = if text.is_empty() {
+            format!("No text recognized in {path}.")
+        } else {
+            format!("Text in {path} ({language}):\n{text}")
+        };
+        if truncated {
+            output.push_str("\n... [text truncated at 100KB]");
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(json!({
+                "path": path,
+                "language": language,
+                "text": text,
+                "truncated": truncated,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn tool(workspace: &std::path::Path) -> ImageOcrTool {
+        ImageOcrTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[test]
+    fn image_ocr_tool_schema() {
+        let tool = tool(&std::env::temp_dir());
+        assert_eq!(tool.name(), "image_ocr");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["path"].is_object());
+        assert!(schema["properties"]["language"].is_object());
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_languages_and_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = tool(dir.path());
+
+        let result = tool
+            .execute(json!({"path": "shot.png", "language": "eng; rm -rf /"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Invalid language"));
+
+        let result = tool
+            .execute(json!({"path": "../outside.png"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let result = tool.execute(json!({"path": "missing.png"})).await.unwrap();
+        assert!(result.error.unwrap().contains("Failed to resolve"));
+    }
+
+    #[tokio::test]
+    async fn reports_missing_engine_or_reads_image() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("blank.png"), b"\x89PNG\r\n\x1a\n").unwrap();
+        let result = tool(dir.path())
+            .execute(json!({"path": "blank.png"}))
+            .await
+            .unwrap();
+        if !ocr::is_available().await {
+            assert!(result.error.unwrap().contains("not installed"));
+        }
+    }
+}
@@ -3,6 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Tool, ToolResult};
+use crate::memory::scoped::confine_to_turn;
 use crate::memory::Memory;
 use async_trait::async_trait;
 use serde_json::json;
@@ -48,21 +49,24 @@ impl Tool for MemoryForgetTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'key' parameter"))?;
 
-        match self.memory.forget(key).await {
+        match confine_to_turn(&self.memory).forget(key).await {
             Ok(true) => Ok(ToolResult {
                 success: true,
                 output: format!("Forgot memory: {key}"),
                 error: None,
+                data: None,
             }),
             Ok(false) => Ok(ToolResult {
                 success: true,
                 output: format!("No memory found with key: {key}"),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to forget memory: {e}")),
+                data: None,
             }),
         }
     }
@@ -3,8 +3,10 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::scoped::confine_to_turn;
+use crate::memory::{Memory, MemoryCategory, RecallFilter};
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde_json::json;
 use std::fmt::Write;
 use std::sync::Arc;
@@ -20,6 +22,71 @@ impl MemoryRecallTool {
     }
 }
 
+pub(crate) fn parse_category(name: &str) -> MemoryCategory {
+    match name {
+        "core" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        "knowledge" => MemoryCategory::Knowledge,
+        other => MemoryCategory::Custom(other.to_string()),
+    }
+}
+
+/// RFC 3339, `YYYY-MM-DD`, or an age like `30m`, `24h`, `7d`, `2w` (that long ago).
+fn parse_time_bound(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Some(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+    {
+        return Ok(day.and_utc());
+    }
+    let split = value.len().saturating_sub(1);
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| {
+        anyhow::anyhow!("Invalid time '{value}': use RFC 3339, YYYY-MM-DD or e.g. 7d")
+    })?;
+    let age = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => anyhow::bail!("Invalid time '{value}': use RFC 3339, YYYY-MM-DD or e.g. 7d"),
+    };
+    Ok(Utc::now() - age)
+}
+
+/// Filter from the optional `category`, `since`, `until`, `key_prefix` and
+/// `tags` fields; also used by the memory erasure API.
+pub(crate) fn parse_filter(args: &serde_json::Value) -> anyhow::Result<RecallFilter> {
+    let text = |name: &str| {
+        args.get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    Ok(RecallFilter {
+        category: text("category").map(parse_category),
+        created_after: text("since").map(parse_time_bound).transpose()?,
+        created_before: text("until").map(parse_time_bound).transpose()?,
+        key_prefix: text("key_prefix").map(str::to_string),
+        tags: args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str())
+                    .map(|t| t.trim_start_matches('#').to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
 #[async_trait]
 impl Tool for MemoryRecallTool {
     fn name(&self) -> &str {
@@ -27,7 +94,7 @@ impl Tool for MemoryRecallTool {
     }
 
     fn description(&self) -> &str {
-        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance."
+        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance. Narrow by category, time range (since/until), key prefix or #tags; without a query the newest matching memories are returned."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -36,22 +103,73 @@ impl Tool for MemoryRecallTool {
             "properties": {
                 "query": {
                     "type": "string",
-                    "description": "Keywords or phrase to search for in memory"
+                    "description": "Keywords or phrase to search for in memory; optional when filtering"
                 },
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Only this category: core, daily, conversation, knowledge or a custom name"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Created at or after: RFC 3339, YYYY-MM-DD, or an age like 24h, 7d, 2w"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Created before: RFC 3339, YYYY-MM-DD, or an age like 24h, 7d, 2w"
+                },
+                "key_prefix": {
+                    "type": "string",
+                    "description": "Only keys starting with this prefix"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "#hashtags the memory must contain (all of them)"
                 }
-            },
-            "required": ["query"]
+            }
         })
     }
 
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "key": { "type": "string" },
+                    "content": { "type": "string" },
+                    "category": { "type": "string" },
+                    "timestamp": { "type": "string" },
+                    "score": { "type": ["number", "null"] },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "metadata": {},
+                    "ref_count": { "type": "integer" }
+                }
+            }
+        }))
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let query = args
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        let filter = match parse_filter(&args) {
+            Ok(filter) => filter,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    data: None,
+                })
+            }
+        };
+        let query = match args.get("query").and_then(|v| v.as_str()) {
+            Some(query) => query,
+            None if !filter.is_empty() => "",
+            None => anyhow::bail!("Missing 'query' parameter"),
+        };
 
         #[allow(clippy::cast_possible_truncation)]
         let limit = args
@@ -59,11 +177,13 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit).await {
+        let memory = confine_to_turn(&self.memory);
+        match memory.recall_filtered(query, limit, &filter).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
                 error: None,
+                data: None,
             }),
             Ok(entries) => {
                 let mut output = format!("Found {} memories:\n", entries.len());
@@ -71,22 +191,49 @@ impl Tool for MemoryRecallTool {
                     let score = entry
                         .score
                         .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let tags = if entry.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" #{}", entry.tags.join(" #"))
+                    };
+                    let repeated = if entry.ref_count > 1 {
+                        format!(" (×{})", entry.ref_count)
+                    } else {
+                        String::new()
+                    };
                     let _ = writeln!(
                         output,
-                        "- [{}] {}: {}{score}",
+                        "- [{}] {}: {}{tags}{repeated}{score}",
                         entry.category, entry.key, entry.content
                     );
                 }
+                let data = entries
+                    .iter()
+                    .map(|entry| {
+                        json!({
+                            "key": entry.key,
+                            "content": entry.content,
+                            "category": entry.category.to_string(),
+                            "timestamp": entry.timestamp,
+                            "score": entry.score,
+                            "tags": entry.tags,
+                            "metadata": entry.metadata,
+                            "ref_count": entry.ref_count,
+                        })
+                    })
+                    .collect();
                 Ok(ToolResult {
                     success: true,
                     output,
                     error: None,
+                    data: Some(serde_json::Value::Array(data)),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Memory recall failed: {e}")),
+                data: None,
             }),
         }
     }
@@ -95,7 +242,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::SqliteMemory;
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -128,6 +275,9 @@ mod tests {
         assert!(result.success);
         assert!(result.output.contains("Rust"));
         assert!(result.output.contains("Found 1"));
+        let data = result.data.unwrap();
+        assert_eq!(data[0]["key"], "lang");
+        assert_eq!(data[0]["category"], "core");
     }
 
     #[tokio::test]
@@ -160,6 +310,51 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn recall_by_filter_without_query() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store("lang", "User prefers Rust #prefs", MemoryCategory::Core)
+            .await
+            .unwrap();
+        mem.store("chat_1", "Talked about Rust", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"category": "conversation", "since": "7d"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert_eq!(result.data.unwrap()[0]["key"], "chat_1");
+
+        let result = tool
+            .execute(json!({"query": "Rust", "tags": ["#prefs"]}))
+            .await
+            .unwrap();
+        assert_eq!(result.data.unwrap()[0]["key"], "lang");
+    }
+
+    #[tokio::test]
+    async fn recall_rejects_bad_time() {
+        let (_tmp, mem) = seeded_mem();
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool.execute(json!({"since": "last week"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Invalid time"));
+    }
+
+    #[test]
+    fn time_bounds_parse() {
+        let day = parse_time_bound("2026-03-01").unwrap();
+        assert_eq!(day.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        let week = parse_time_bound("1w").unwrap();
+        let age = Utc::now() - week;
+        assert!(age >= Duration::days(7) && age < Duration::days(8));
+        assert!(parse_time_bound("7x").is_err());
+    }
+
     #[test]
     fn name_and_schema() {
         let (_tmp, mem) = seeded_mem();
@@ -3,6 +3,7 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Tool, ToolResult};
+use crate::memory::scoped::confine_to_turn;
 use crate::memory::{Memory, MemoryCategory};
 use async_trait::async_trait;
 use serde_json::json;
@@ -45,6 +46,15 @@ impl Tool for MemoryStoreTool {
                     "type": "string",
                     "enum": ["core", "daily", "conversation"],
                     "description": "Memory category: core (permanent), daily (session), conversation (chat)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels for later filtering (e.g. ['travel', 'family']); replace existing tags. 'pinned' keeps the memory from fading in recall ranking"
+                },
+                "metadata": {
+                    "type": "object",
+                    "description": "Structured data to keep with the memory; replaces existing metadata"
                 }
             },
             "required": ["key", "content"]
@@ -68,16 +78,41 @@ impl Tool for MemoryStoreTool {
             _ => MemoryCategory::Core,
         };
 
-        match self.memory.store(key, content, category).await {
+        let tags: Option<Vec<String>> = args.get("tags").and_then(|v| v.as_array()).map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str().map(str::to_string))
+                .collect()
+        });
+        let metadata = args.get("metadata").filter(|v| !v.is_null());
+
+        // Without tags or metadata, existing ones are left alone.
+        let memory = confine_to_turn(&self.memory);
+        let stored = if tags.is_some() || metadata.is_some() {
+            memory
+                .store_with_metadata(
+                    key,
+                    content,
+                    category,
+                    &tags.unwrap_or_default(),
+                    metadata.unwrap_or(&serde_json::Value::Null),
+                )
+                .await
+        } else {
+            memory.store(key, content, category).await
+        };
+
+        match stored {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
                 error: None,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to store memory: {e}")),
+                data: None,
             }),
         }
     }
@@ -132,6 +167,26 @@ mod tests {
         assert!(result.success);
     }
 
+    #[tokio::test]
+    async fn store_with_tags_and_metadata() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone());
+        let result = tool
+            .execute(json!({
+                "key": "trip",
+                "content": "Alps in June",
+                "tags": ["#Travel"],
+                "metadata": {"importance": 0.9}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let entry = mem.get("trip").await.unwrap().unwrap();
+        assert_eq!(entry.tags, vec!["travel"]);
+        assert_eq!(entry.metadata["importance"], 0.9);
+    }
+
     #[tokio::test]
     async fn store_missing_key() {
         let (_tmp, mem) = test_mem();
@@ -2,39 +2,62 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+pub mod archive;
 pub mod browser;
 pub mod browser_open;
 pub mod composio;
+pub mod custom;
+pub mod db_query;
 pub mod delegate;
+pub mod email;
+pub mod feeds;
+pub mod file_patch;
 pub mod file_read;
 pub mod file_write;
+pub mod git_forge;
 pub mod git_operations;
 pub mod http_request;
+pub mod ics;
+pub mod image_describe;
 pub mod image_info;
+pub mod image_ocr;
 pub mod memory_forget;
 pub mod memory_recall;
 pub mod memory_store;
 pub mod pim;
+pub mod registry;
 pub mod screenshot;
 pub mod security;
 pub mod shell;
+pub mod shell_jobs;
+pub mod stats;
 pub mod traits;
+pub mod vcard;
+pub mod wasm;
 
+pub use archive::ArchiveTool;
 pub use browser::BrowserTool;
 pub use browser_open::BrowserOpenTool;
 pub use composio::ComposioTool;
+pub use db_query::DbQueryTool;
 pub use delegate::DelegateTool;
+pub use feeds::FeedsTool;
+pub use file_patch::FilePatchTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use git_operations::GitOperationsTool;
 pub use http_request::HttpRequestTool;
+pub use image_describe::ImageDescribeTool;
 pub use image_info::ImageInfoTool;
+pub use image_ocr::ImageOcrTool;
 pub use memory_forget::MemoryForgetTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
+pub use registry::ToolRegistry;
 pub use screenshot::ScreenshotTool;
 pub use security::SecurityWrapper;
 pub use shell::ShellTool;
+pub use shell_jobs::shell_job_tools;
 pub use traits::Tool;
 #[allow(unused_imports)]
 pub use traits::{ToolResult, ToolSpec};
@@ -43,6 +66,7 @@ use crate::config::DelegateAgentConfig;
 use crate::memory::sovereign::SensitivityScanner;
 use crate::memory::Memory;
 use crate::runtime::{NativeRuntime, RuntimeAdapter};
+use crate::security::confirmation::ConfirmationGate;
 use crate::security::{AuditLogger, SecurityPolicy};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -61,6 +85,7 @@ pub fn default_tools_with_runtime(
         Box::new(ShellTool::new(security.clone(), runtime)),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
+        Box::new(FilePatchTool::new(security.clone())),
     ];
 
     tools
@@ -77,12 +102,18 @@ pub fn all_tools(
     composio_key: Option<&str>,
     browser_config: &crate::config::BrowserConfig,
     http_config: &crate::config::HttpRequestConfig,
+    vision_config: &crate::config::VisionConfig,
+    db_config: &crate::config::DbQueryConfig,
+    feeds_config: &crate::config::FeedsConfig,
+    plugins_config: &crate::config::PluginsConfig,
+    reliability: &crate::config::ReliabilityConfig,
     workspace_dir: &std::path::Path,
     agents: &HashMap<String, DelegateAgentConfig>,
     fallback_api_key: Option<&str>,
     extra_tools: Vec<Box<dyn Tool>>,
     audit: Option<Arc<AuditLogger>>,
     actor_name: Option<String>,
+    confirm_gate: Option<Arc<ConfirmationGate>>,
 ) -> Vec<Box<dyn Tool>> {
     all_tools_with_runtime(
         security,
@@ -91,12 +122,18 @@ pub fn all_tools(
         composio_key,
         browser_config,
         http_config,
+        vision_config,
+        db_config,
+        feeds_config,
+        plugins_config,
+        reliability,
         workspace_dir,
         agents,
         fallback_api_key,
         extra_tools,
         audit,
         actor_name,
+        confirm_gate,
     )
 }
 
@@ -109,26 +146,49 @@ pub fn all_tools_with_runtime(
     composio_key: Option<&str>,
     browser_config: &crate::config::BrowserConfig,
     http_config: &crate::config::HttpRequestConfig,
+    vision_config: &crate::config::VisionConfig,
+    db_config: &crate::config::DbQueryConfig,
+    feeds_config: &crate::config::FeedsConfig,
+    plugins_config: &crate::config::PluginsConfig,
+    reliability: &crate::config::ReliabilityConfig,
     workspace_dir: &std::path::Path,
     agents: &HashMap<String, DelegateAgentConfig>,
     fallback_api_key: Option<&str>,
     extra_tools: Vec<Box<dyn Tool>>,
     audit: Option<Arc<AuditLogger>>,
     actor_name: Option<String>,
+    confirm_gate: Option<Arc<ConfirmationGate>>,
 ) -> Vec<Box<dyn Tool>> {
+    let job_runtime = (runtime.has_shell_access() && runtime.supports_long_running())
+        .then(|| runtime.clone());
+    let custom_runtime = runtime.clone();
+    // Workspace secret store (PIM data, email account, git forge token)
+    let pim_secrets = {
+        let mymolt_dir = workspace_dir.join(".mymolt");
+        Some(crate::security::secrets::SecretStore::new(
+            &mymolt_dir,
+            true,
+        ))
+    };
     let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(ShellTool::new(security.clone(), runtime)),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
+        Box::new(FilePatchTool::new(security.clone())),
         Box::new(MemoryStoreTool::new(memory.clone())),
         Box::new(MemoryRecallTool::new(memory.clone())),
-        Box::new(MemoryForgetTool::new(memory)),
-        Box::new(GitOperationsTool::new(
-            security.clone(),
-            workspace_dir.to_path_buf(),
-        )),
+        Box::new(MemoryForgetTool::new(memory.clone())),
+        Box::new(
+            GitOperationsTool::new(security.clone(), workspace_dir.to_path_buf())
+                .with_publishing(confirm_gate.clone(), pim_secrets.clone()),
+        ),
+        Box::new(ArchiveTool::new(security.clone())),
     ];
 
+    if let Some(runtime) = job_runtime {
+        tools.extend(shell_job_tools(security.clone(), runtime));
+    }
+
     if browser_config.enabled {
         // Add legacy browser_open tool for simple URL opening
         tools.push(Box::new(BrowserOpenTool::new(
@@ -159,6 +219,43 @@ pub fn all_tools_with_runtime(
     // Vision tools are always available
     tools.push(Box::new(ScreenshotTool::new(security.clone())));
     tools.push(Box::new(ImageInfoTool::new(security.clone())));
+    tools.push(Box::new(ImageOcrTool::new(security.clone())));
+
+    if db_config.enabled {
+        tools.push(Box::new(DbQueryTool::new(security.clone(), db_config)));
+    }
+
+    if feeds_config.enabled {
+        tools.push(Box::new(FeedsTool::new(
+            security.clone(),
+            memory,
+            workspace_dir,
+            feeds_config.clone(),
+        )));
+    }
+
+    if vision_config.enabled {
+        // Only the vision provider's own key: the chat key must not reach a
+        // custom endpoint.
+        let api_key = vision_config
+            .api_key
+            .clone()
+            .or_else(|| crate::providers::resolve_api_key(&vision_config.provider, None));
+        match crate::providers::vision::create_vision_provider(
+            &vision_config.provider,
+            api_key,
+            &vision_config.model,
+            vision_config.api_url.as_deref(),
+        ) {
+            Ok(provider) => tools.push(Box::new(ImageDescribeTool::new(
+                security.clone(),
+                Arc::from(provider),
+                Arc::new(SensitivityScanner::new()),
+                vision_config.clone(),
+            ))),
+            Err(e) => tracing::warn!("image_describe disabled: {e}"),
+        }
+    }
 
     if let Some(key) = composio_key {
         if !key.is_empty() {
@@ -173,29 +270,49 @@ pub fn all_tools_with_runtime(
                 agents.clone(),
                 fallback_api_key.map(String::from),
                 Arc::new(SensitivityScanner::new()),
-                audit,
+                audit.clone(),
             )
             .with_actor(actor_name),
         ));
     }
 
     // Add PIM tools (calendar, contacts, notes) — encrypted at rest
-    let pim_secrets = {
-        let mymolt_dir = workspace_dir.join(".mymolt");
-        Some(crate::security::secrets::SecretStore::new(
-            &mymolt_dir,
-            true,
-        ))
-    };
+    tools.extend(email::email_tools(workspace_dir, pim_secrets.clone(), confirm_gate));
     tools.extend(pim::pim_tools(workspace_dir, pim_secrets));
 
-    // Add MCP tools (already gated by SigilGatekeeper, no extra SecurityWrapper needed)
-    let mcp_count = extra_tools.len();
+    // User-defined declarative tools from `<workspace>/custom_tools/`
+    let taken: Vec<String> = tools.iter().map(|t| t.name().to_string()).collect();
+    let taken: Vec<&str> = taken.iter().map(String::as_str).collect();
+    tools.extend(custom::custom_tools(
+        custom::load_custom_tools(workspace_dir),
+        &taken,
+        security,
+        &custom_runtime,
+        http_config,
+    ));
+
+    // Sandboxed WASM plugins from `<workspace>/plugins/`
+    let taken: Vec<String> = tools.iter().map(|t| t.name().to_string()).collect();
+    let taken: Vec<&str> = taken.iter().map(String::as_str).collect();
+    tools.extend(wasm::plugin_tools(workspace_dir, &taken, plugins_config));
+
+    let stats = open_stats(workspace_dir);
+    let wrap = |tool: Box<dyn Tool>| {
+        wrap_tool(tool, security, reliability, stats.as_ref(), audit.as_ref())
+    };
     let mut wrapped: Vec<Box<dyn Tool>> = tools
         .into_iter()
-        .map(|t| Box::new(SecurityWrapper::new(t, security.clone())) as Box<dyn Tool>)
+        .map(|t| Box::new(wrap(t)) as Box<dyn Tool>)
         .collect();
-    wrapped.extend(extra_tools);
+
+    // Add MCP tools (already gated by SigilGatekeeper, so only budgets,
+    // retries and stats are applied)
+    let mcp_count = extra_tools.len();
+    wrapped.extend(
+        extra_tools
+            .into_iter()
+            .map(|t| Box::new(wrap(t).externally_gated()) as Box<dyn Tool>),
+    );
 
     if mcp_count > 0 {
         tracing::info!(count = mcp_count, "MCP tools added to registry");
@@ -204,6 +321,55 @@ pub fn all_tools_with_runtime(
     wrapped
 }
 
+fn open_stats(workspace_dir: &std::path::Path) -> Option<Arc<stats::ToolStats>> {
+    match stats::ToolStats::open(workspace_dir) {
+        Ok(stats) => Some(Arc::new(stats)),
+        Err(e) => {
+            tracing::warn!("Tool stats and daily budgets disabled: {e}");
+            None
+        }
+    }
+}
+
+fn wrap_tool(
+    tool: Box<dyn Tool>,
+    security: &Arc<SecurityPolicy>,
+    reliability: &crate::config::ReliabilityConfig,
+    stats: Option<&Arc<stats::ToolStats>>,
+    audit: Option<&Arc<AuditLogger>>,
+) -> SecurityWrapper {
+    let mut wrapper = SecurityWrapper::new(tool, security.clone());
+    if let Some(audit) = audit {
+        wrapper = wrapper.with_audit(Arc::clone(audit));
+    }
+    if let Some(policy) = reliability.tool_retry(wrapper.name()) {
+        wrapper = wrapper.with_retry(policy.clone(), audit.cloned());
+    }
+    if let Some(stats) = stats {
+        wrapper = wrapper.with_stats(Arc::clone(stats));
+    }
+    wrapper
+}
+
+/// Wrap MCP tools connected after startup the way `all_tools_with_runtime`
+/// wraps `extra_tools`.
+pub fn wrap_mcp_tools(
+    tools: Vec<Box<dyn Tool>>,
+    security: &Arc<SecurityPolicy>,
+    reliability: &crate::config::ReliabilityConfig,
+    workspace_dir: &std::path::Path,
+    audit: Option<Arc<AuditLogger>>,
+) -> Vec<Box<dyn Tool>> {
+    let stats = open_stats(workspace_dir);
+    tools
+        .into_iter()
+        .map(|t| {
+            let wrapper = wrap_tool(t, security, reliability, stats.as_ref(), audit.as_ref());
+            Box::new(wrapper.externally_gated()) as Box<dyn Tool>
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,10 +377,10 @@ mod tests {
     use tempfile::TempDir;
 
     #[test]
-    fn default_tools_has_three() {
+    fn default_tools_has_four() {
         let security = Arc::new(SecurityPolicy::default());
         let tools = default_tools(security);
-        assert_eq!(tools.len(), 3);
+        assert_eq!(tools.len(), 4);
     }
 
     #[test]
@@ -232,8 +398,10 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, audit).unwrap());
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&mem_cfg, &Default::default(), tmp.path(), None, audit)
+                .unwrap(),
+        );
 
         let browser = BrowserConfig {
             enabled: false,
@@ -249,12 +417,18 @@ mod tests {
             None,
             &browser,
             &http,
+            &crate::config::VisionConfig::default(),
+            &crate::config::DbQueryConfig::default(),
+            &crate::config::FeedsConfig::default(),
+            &crate::config::PluginsConfig::default(),
+            &crate::config::ReliabilityConfig::default(),
             tmp.path(),
             &HashMap::new(),
             None,
             Vec::new(),
             None,
             None,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(!names.contains(&"browser_open"));
@@ -275,8 +449,10 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, audit).unwrap());
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&mem_cfg, &Default::default(), tmp.path(), None, audit)
+                .unwrap(),
+        );
 
         let browser = BrowserConfig {
             enabled: true,
@@ -292,12 +468,18 @@ mod tests {
             None,
             &browser,
             &http,
+            &crate::config::VisionConfig::default(),
+            &crate::config::DbQueryConfig::default(),
+            &crate::config::FeedsConfig::default(),
+            &crate::config::PluginsConfig::default(),
+            &crate::config::ReliabilityConfig::default(),
             tmp.path(),
             &HashMap::new(),
             None,
             Vec::new(),
             None,
             None,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"browser_open"));
@@ -311,6 +493,7 @@ mod tests {
         assert!(names.contains(&"shell"));
         assert!(names.contains(&"file_read"));
         assert!(names.contains(&"file_write"));
+        assert!(names.contains(&"file_patch"));
     }
 
     #[test]
@@ -363,6 +546,7 @@ mod tests {
             success: true,
             output: "hello".into(),
             error: None,
+            data: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -377,6 +561,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            data: None,
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -390,6 +575,7 @@ mod tests {
             name: "test".into(),
             description: "A test tool".into(),
             parameters: serde_json::json!({"type": "object"}),
+            output_schema: None,
         };
         let json = serde_json::to_string(&spec).unwrap();
         let parsed: ToolSpec = serde_json::from_str(&json).unwrap();
@@ -412,8 +598,10 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, audit).unwrap());
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&mem_cfg, &Default::default(), tmp.path(), None, audit)
+                .unwrap(),
+        );
 
         let browser = BrowserConfig::default();
         let http = crate::config::HttpRequestConfig::default();
@@ -437,12 +625,18 @@ mod tests {
             None,
             &browser,
             &http,
+            &crate::config::VisionConfig::default(),
+            &crate::config::DbQueryConfig::default(),
+            &crate::config::FeedsConfig::default(),
+            &crate::config::PluginsConfig::default(),
+            &crate::config::ReliabilityConfig::default(),
             tmp.path(),
             &agents,
             Some("sk-test"),
             Vec::new(),
             None,
             None,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"delegate"));
@@ -463,8 +657,10 @@ mod tests {
             )
             .unwrap(),
         );
-        let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, audit).unwrap());
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&mem_cfg, &Default::default(), tmp.path(), None, audit)
+                .unwrap(),
+        );
 
         let browser = BrowserConfig::default();
         let http = crate::config::HttpRequestConfig::default();
@@ -475,12 +671,18 @@ mod tests {
             None,
             &browser,
             &http,
+            &crate::config::VisionConfig::default(),
+            &crate::config::DbQueryConfig::default(),
+            &crate::config::FeedsConfig::default(),
+            &crate::config::PluginsConfig::default(),
+            &crate::config::ReliabilityConfig::default(),
             tmp.path(),
             &HashMap::new(),
             None,
             Vec::new(),
             None,
             None,
+            None,
         );
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(!names.contains(&"delegate"));
@@ -4,8 +4,8 @@
 
 //! Sovereign PIM — Personal Information Manager tools.
 //!
-//! Local-first Calendar, Contacts, and Notes stored as encrypted JSON
-//! inside the workspace. Data stays sovereign — no cloud sync.
+//! Local-first Calendar, Contacts, Notes, Expenses, Tasks and location reminders
+//! stored as encrypted JSON inside the workspace. Data stays sovereign — no cloud sync.
 //! When a `SecretStore` is provided, PIM data is encrypted at rest
 //! using ChaCha20-Poly1305.
 
@@ -13,9 +13,12 @@ use crate::security::secrets::SecretStore;
 use crate::tools::{Tool, ToolResult};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::SystemTime;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 // ── Data types ──────────────────────────────────────────────────
 
@@ -38,6 +41,11 @@ pub struct Contact {
     pub email: Option<String>,
     #[serde(default)]
     pub phone: Option<String>,
+    /// Further addresses, e.g. from a vCard import
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_emails: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_phones: Vec<String>,
     #[serde(default)]
     pub notes: String,
 }
@@ -50,23 +58,321 @@ pub struct Note {
     pub created_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expense {
+    pub id: String,
+    pub amount: f64,
+    /// ISO 4217 code, e.g. `EUR`
+    pub currency: String,
+    #[serde(default = "default_expense_category")]
+    pub category: String,
+    /// `YYYY-MM-DD`
+    pub date: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+fn default_expense_category() -> String {
+    "other".into()
+}
+
+/// Spending for one month, per currency and category.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ExpenseSummary {
+    /// `YYYY-MM`
+    pub month: String,
+    pub count: usize,
+    /// Largest total first.
+    pub totals: Vec<CurrencyTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CurrencyTotal {
+    pub currency: String,
+    pub total: f64,
+    /// Largest total first.
+    pub categories: Vec<CategoryTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total: f64,
+    pub count: usize,
+}
+
+/// Whether `month` is a `YYYY-MM` string.
+pub fn is_valid_month(month: &str) -> bool {
+    month.len() == 7
+        && chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").is_ok()
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Summarize the expenses dated in `month` (`YYYY-MM`).
+pub fn summarize_expenses(expenses: &[Expense], month: &str) -> ExpenseSummary {
+    let mut by_currency: BTreeMap<&str, BTreeMap<&str, (f64, usize)>> = BTreeMap::new();
+    let mut count = 0;
+    for e in expenses.iter().filter(|e| e.date.starts_with(month)) {
+        let entry = by_currency
+            .entry(e.currency.as_str())
+            .or_default()
+            .entry(e.category.as_str())
+            .or_default();
+        entry.0 += e.amount;
+        entry.1 += 1;
+        count += 1;
+    }
+
+    let mut totals: Vec<CurrencyTotal> = by_currency
+        .into_iter()
+        .map(|(currency, categories)| {
+            let mut categories: Vec<CategoryTotal> = categories
+                .into_iter()
+                .map(|(category, (total, count))| CategoryTotal {
+                    category: category.to_string(),
+                    total: round_cents(total),
+                    count,
+                })
+                .collect();
+            categories.sort_by(|a, b| b.total.total_cmp(&a.total));
+            CurrencyTotal {
+                currency: currency.to_string(),
+                total: round_cents(categories.iter().map(|c| c.total).sum()),
+                categories,
+            }
+        })
+        .collect();
+    totals.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+    ExpenseSummary {
+        month: month.to_string(),
+        count,
+        totals,
+    }
+}
+
+/// Task priority, lowest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl TaskPriority {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" | "normal" => Some(Self::Medium),
+            "high" | "urgent" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    /// Serialized right after `id`: sync resolves conflicting edits by the
+    /// larger JSON, so a completed copy wins over an open one.
+    #[serde(default)]
+    pub done: bool,
+    pub title: String,
+    /// `YYYY-MM-DD`
+    #[serde(default)]
+    pub due: Option<String>,
+    #[serde(default)]
+    pub priority: TaskPriority,
+    /// Lowercase tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+impl Task {
+    /// Open and due before `today`.
+    pub fn is_overdue(&self, today: chrono::NaiveDate) -> bool {
+        !self.done
+            && self
+                .due
+                .as_deref()
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .is_some_and(|due| due < today)
+    }
+}
+
+/// Which tasks a query returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    #[default]
+    Open,
+    Overdue,
+    Done,
+    All,
+}
+
+impl TaskStatus {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "open" | "pending" => Some(Self::Open),
+            "overdue" => Some(Self::Overdue),
+            "done" | "completed" => Some(Self::Done),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+/// Tasks matching `status` and `tag`: open tasks by due date (undated last)
+/// and priority, completed tasks newest first.
+pub fn query_tasks(
+    tasks: &[Task],
+    status: TaskStatus,
+    tag: Option<&str>,
+    today: chrono::NaiveDate,
+) -> Vec<Task> {
+    let tag = tag.map(|t| t.trim().to_lowercase());
+    let mut matched: Vec<Task> = tasks
+        .iter()
+        .filter(|t| match status {
+            TaskStatus::Open => !t.done,
+            TaskStatus::Overdue => t.is_overdue(today),
+            TaskStatus::Done => t.done,
+            TaskStatus::All => true,
+        })
+        .filter(|t| tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+        .cloned()
+        .collect();
+    matched.sort_by(|a, b| {
+        a.done
+            .cmp(&b.done)
+            .then_with(|| b.completed_at.cmp(&a.completed_at))
+            .then_with(|| match (&a.due, &b.due) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| b.priority.cmp(&a.priority))
+    });
+    matched
+}
+
+/// When a location reminder fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocationTrigger {
+    Arrive,
+    Leave,
+}
+
+impl LocationTrigger {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "arrive" | "arrived" | "arrival" => Some(Self::Arrive),
+            "leave" | "left" | "departure" => Some(Self::Leave),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Arrive => "arrive",
+            Self::Leave => "leave",
+        }
+    }
+
+    fn describe(self, place: &str) -> String {
+        match self {
+            Self::Arrive => format!("arriving at {place}"),
+            Self::Leave => format!("leaving {place}"),
+        }
+    }
+}
+
+/// A reminder tied to a named place ("home", "work"), never to coordinates.
+///
+/// Location reminders stay on this device and are not shared with sync peers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationReminder {
+    pub id: String,
+    /// Lowercase place label
+    pub place: String,
+    pub trigger: LocationTrigger,
+    pub text: String,
+    /// Keep the reminder after it fires
+    #[serde(default)]
+    pub repeat: bool,
+    pub created_at: String,
+}
+
+/// Normalize a place label for matching: trimmed and lowercase.
+pub fn normalize_place(place: &str) -> String {
+    place.trim().to_lowercase()
+}
+
+/// Reminders that fire for an event at `place`. One-off reminders are removed
+/// from the store; repeating ones stay.
+pub(crate) fn fire_location_reminders(
+    store: &mut PimStore,
+    place: &str,
+    trigger: LocationTrigger,
+) -> Vec<LocationReminder> {
+    let place = normalize_place(place);
+    let matches = |r: &LocationReminder| r.place == place && r.trigger == trigger;
+    let fired: Vec<LocationReminder> = store
+        .location_reminders
+        .iter()
+        .filter(|r| matches(r))
+        .cloned()
+        .collect();
+    store
+        .location_reminders
+        .retain(|r| r.repeat || !matches(r));
+    fired
+}
+
 // ── Storage ─────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct PimStore {
+pub(crate) struct PimStore {
     #[serde(default)]
-    events: Vec<CalendarEvent>,
+    pub(crate) events: Vec<CalendarEvent>,
     #[serde(default)]
-    contacts: Vec<Contact>,
+    pub(crate) contacts: Vec<Contact>,
     #[serde(default)]
-    notes: Vec<Note>,
+    pub(crate) notes: Vec<Note>,
+    #[serde(default)]
+    pub(crate) expenses: Vec<Expense>,
+    #[serde(default)]
+    pub(crate) location_reminders: Vec<LocationReminder>,
+    #[serde(default)]
+    pub(crate) tasks: Vec<Task>,
 }
 
 fn pim_path(workspace: &std::path::Path) -> PathBuf {
     workspace.join(".mymolt").join("pim.json")
 }
 
-fn load_store(workspace: &std::path::Path, secrets: &Option<SecretStore>) -> PimStore {
+pub(crate) fn load_store(workspace: &std::path::Path, secrets: &Option<SecretStore>) -> PimStore {
     let path = pim_path(workspace);
     if path.exists() {
         let raw = match std::fs::read_to_string(&path) {
@@ -85,7 +391,7 @@ fn load_store(workspace: &std::path::Path, secrets: &Option<SecretStore>) -> Pim
     }
 }
 
-fn save_store(
+pub(crate) fn save_store(
     workspace: &std::path::Path,
     store: &PimStore,
     secrets: &Option<SecretStore>,
@@ -111,29 +417,66 @@ fn save_store(
 /// Read-only tools (list/search/read) acquire a read lock, so
 /// multiple concurrent readers proceed without blocking.
 /// Write tools (add/create) acquire a write lock and flush to disk.
+/// The gateway, sync and capture write the file directly; a file newer
+/// than the last load or flush is reloaded before the next access.
 pub struct PimState {
     store: RwLock<PimStore>,
     workspace: PathBuf,
     secrets: Option<SecretStore>,
+    /// Modification time of `pim.json` as last loaded or written.
+    seen_mtime: std::sync::Mutex<Option<SystemTime>>,
 }
 
 impl PimState {
     pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        let seen_mtime = file_mtime(&workspace);
         let store = load_store(&workspace, &secrets);
         Self {
             store: RwLock::new(store),
             workspace,
             secrets,
+            seen_mtime: std::sync::Mutex::new(seen_mtime),
+        }
+    }
+
+    /// Reload the store if the file changed on disk since we last saw it.
+    async fn reload_if_changed(&self) {
+        let current = file_mtime(&self.workspace);
+        let changed = {
+            let mut seen = self.seen_mtime.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            std::mem::replace(&mut *seen, current) != current
+        };
+        if changed {
+            *self.store.write().await = load_store(&self.workspace, &self.secrets);
         }
     }
 
+    async fn read(&self) -> RwLockReadGuard<'_, PimStore> {
+        self.reload_if_changed().await;
+        self.store.read().await
+    }
+
+    async fn write(&self) -> RwLockWriteGuard<'_, PimStore> {
+        self.reload_if_changed().await;
+        self.store.write().await
+    }
+
     /// Flush the in-memory store to disk (encrypted if SecretStore is set).
     async fn flush(&self) -> anyhow::Result<()> {
         let store = self.store.read().await;
-        save_store(&self.workspace, &store, &self.secrets)
+        save_store(&self.workspace, &store, &self.secrets)?;
+        *self.seen_mtime.lock().unwrap_or_else(std::sync::PoisonError::into_inner) =
+            file_mtime(&self.workspace);
+        Ok(())
     }
 }
 
+fn file_mtime(workspace: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(pim_path(workspace))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
 // ── Calendar Add Tool ────────────────────────────────────────
 
 pub struct CalendarAddTool {
@@ -206,7 +549,7 @@ impl Tool for CalendarAddTool {
         );
 
         {
-            let mut store = self.state.store.write().await;
+            let mut store = self.state.write().await;
             store.events.push(event);
         }
         self.state.flush().await?;
@@ -215,6 +558,7 @@ impl Tool for CalendarAddTool {
             success: true,
             output: summary,
             error: None,
+            data: None,
         })
     }
 }
@@ -266,7 +610,7 @@ impl Tool for CalendarListTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(10) as usize;
 
-        let store = self.state.store.read().await;
+        let store = self.state.read().await;
 
         let mut events: Vec<&CalendarEvent> = store
             .events
@@ -281,6 +625,7 @@ impl Tool for CalendarListTool {
                 success: true,
                 output: "📅 No upcoming events.".into(),
                 error: None,
+                data: None,
             });
         }
 
@@ -301,6 +646,7 @@ impl Tool for CalendarListTool {
             success: true,
             output: lines.join("\n"),
             error: None,
+            data: None,
         })
     }
 }
@@ -351,7 +697,7 @@ impl Tool for ContactsAddTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
 
-        let mut store = self.state.store.write().await;
+        let mut store = self.state.write().await;
 
         // Update existing or create new
         let existing = store.contacts.iter_mut().find(|c| c.name == name);
@@ -371,6 +717,7 @@ impl Tool for ContactsAddTool {
                 success: true,
                 output: format!("👤 Updated contact: {name}"),
                 error: None,
+                data: None,
             });
         }
 
@@ -379,6 +726,8 @@ impl Tool for ContactsAddTool {
             name: name.to_string(),
             email: args.get("email").and_then(|v| v.as_str()).map(String::from),
             phone: args.get("phone").and_then(|v| v.as_str()).map(String::from),
+            other_emails: Vec::new(),
+            other_phones: Vec::new(),
             notes: args
                 .get("notes")
                 .and_then(|v| v.as_str())
@@ -394,6 +743,7 @@ impl Tool for ContactsAddTool {
             success: true,
             output: format!("👤 Added contact: {name}"),
             error: None,
+            data: None,
         })
     }
 }
@@ -442,7 +792,7 @@ impl Tool for ContactsSearchTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?
             .to_lowercase();
 
-        let store = self.state.store.read().await;
+        let store = self.state.read().await;
 
         let matches: Vec<String> = store
             .contacts
@@ -450,10 +800,10 @@ impl Tool for ContactsSearchTool {
             .filter(|c| c.name.to_lowercase().contains(&query))
             .map(|c| {
                 let mut parts = vec![format!("👤 {}", c.name)];
-                if let Some(ref email) = c.email {
+                for email in c.email.iter().chain(&c.other_emails) {
                     parts.push(format!("  📧 {email}"));
                 }
-                if let Some(ref phone) = c.phone {
+                for phone in c.phone.iter().chain(&c.other_phones) {
                     parts.push(format!("  📞 {phone}"));
                 }
                 if !c.notes.is_empty() {
@@ -468,6 +818,7 @@ impl Tool for ContactsSearchTool {
                 success: true,
                 output: format!("No contacts matching '{query}'."),
                 error: None,
+                data: None,
             });
         }
 
@@ -475,6 +826,120 @@ impl Tool for ContactsSearchTool {
             success: true,
             output: matches.join("\n\n"),
             error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Contacts Import Tool ────────────────────────────────────
+
+/// Largest `.vcf` file `contacts_import` reads.
+const MAX_VCARD_BYTES: u64 = 10 * 1024 * 1024;
+
+pub struct ContactsImportTool {
+    state: Arc<PimState>,
+}
+
+impl ContactsImportTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+
+    fn read_vcard(&self, path: &str) -> Result<String, String> {
+        let relative = std::path::Path::new(path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir))
+        {
+            return Err(format!("Path '{path}' must be relative to the workspace"));
+        }
+        let full = self.state.workspace.join(relative);
+        let size = std::fs::metadata(&full)
+            .map_err(|e| format!("Cannot read '{path}': {e}"))?
+            .len();
+        if size > MAX_VCARD_BYTES {
+            return Err(format!("'{path}' is larger than {} MB", MAX_VCARD_BYTES / 1024 / 1024));
+        }
+        std::fs::read_to_string(&full).map_err(|e| format!("Cannot read '{path}': {e}"))
+    }
+}
+
+#[async_trait]
+impl Tool for ContactsImportTool {
+    fn name(&self) -> &str {
+        "contacts_import"
+    }
+
+    fn description(&self) -> &str {
+        "Import contacts from a vCard (.vcf) file in the workspace or from vCard text. \
+         Contacts with the same name are merged: missing emails, phones and notes are added, nothing is replaced."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string", "description": "Path of a .vcf file, relative to the workspace"},
+                "vcard": {"type": "string", "description": "vCard text, instead of a file"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let text = match (
+            args.get("path").and_then(|v| v.as_str()),
+            args.get("vcard").and_then(|v| v.as_str()),
+        ) {
+            (Some(path), _) => self.read_vcard(path),
+            (None, Some(vcard)) => Ok(vcard.to_string()),
+            (None, None) => Err("Provide 'path' or 'vcard'".to_string()),
+        };
+        let parsed = match text {
+            Ok(text) => crate::tools::vcard::parse_vcards(&text),
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e),
+                    data: None,
+                })
+            }
+        };
+        if parsed.contacts.is_empty() && parsed.skipped == 0 {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("No vCard found.".into()),
+                data: None,
+            });
+        }
+
+        let skipped = parsed.skipped;
+        let mut store = self.state.write().await;
+        let counts = crate::tools::vcard::merge_contacts(&mut store.contacts, parsed.contacts);
+        drop(store);
+        if counts.added + counts.updated > 0 {
+            self.state.flush().await?;
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "👤 Imported contacts: {} added, {} updated, {} unchanged, {skipped} skipped (no name)",
+                counts.added, counts.updated, counts.unchanged
+            ),
+            error: None,
+            data: Some(serde_json::json!({
+                "added": counts.added,
+                "updated": counts.updated,
+                "unchanged": counts.unchanged,
+                "skipped": skipped,
+            })),
         })
     }
 }
@@ -537,7 +1002,7 @@ impl Tool for NotesCreateTool {
 
         let summary = format!("📝 Created note: {}", note.title);
         {
-            let mut store = self.state.store.write().await;
+            let mut store = self.state.write().await;
             store.notes.push(note);
         }
         self.state.flush().await?;
@@ -546,6 +1011,7 @@ impl Tool for NotesCreateTool {
             success: true,
             output: summary,
             error: None,
+            data: None,
         })
     }
 }
@@ -599,7 +1065,7 @@ impl Tool for NotesSearchTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(10) as usize;
 
-        let store = self.state.store.read().await;
+        let store = self.state.read().await;
 
         let matches: Vec<String> = store
             .notes
@@ -624,6 +1090,7 @@ impl Tool for NotesSearchTool {
                 success: true,
                 output: format!("No notes matching '{query}'."),
                 error: None,
+                data: None,
             });
         }
 
@@ -631,6 +1098,7 @@ impl Tool for NotesSearchTool {
             success: true,
             output: matches.join("\n\n"),
             error: None,
+            data: None,
         })
     }
 }
@@ -678,7 +1146,7 @@ impl Tool for NotesReadTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'title' parameter"))?;
 
-        let store = self.state.store.read().await;
+        let store = self.state.read().await;
 
         let note = store.notes.iter().find(|n| n.title == title);
 
@@ -690,111 +1158,949 @@ impl Tool for NotesReadTool {
                     n.title, n.created_at, n.content
                 ),
                 error: None,
+                data: None,
             }),
             None => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Note '{}' not found.", title)),
+                data: None,
             }),
         }
     }
 }
 
-/// Create all PIM tools for a given workspace.
-///
-/// All tools share a single `PimState` with `RwLock` — readers
-/// proceed concurrently, writers serialize and flush to disk.
-/// If `secrets` is provided, PIM data will be encrypted at rest.
-pub fn pim_tools(workspace: &std::path::Path, secrets: Option<SecretStore>) -> Vec<Box<dyn Tool>> {
-    let state = Arc::new(PimState::new(workspace.to_path_buf(), secrets));
-    vec![
-        Box::new(CalendarAddTool::from_state(state.clone())),
-        Box::new(CalendarListTool::from_state(state.clone())),
-        Box::new(ContactsAddTool::from_state(state.clone())),
-        Box::new(ContactsSearchTool::from_state(state.clone())),
-        Box::new(NotesCreateTool::from_state(state.clone())),
-        Box::new(NotesSearchTool::from_state(state.clone())),
-        Box::new(NotesReadTool::from_state(state)),
-    ]
-}
+// ── Expense Add Tool ────────────────────────────────────────
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct ExpenseAddTool {
+    state: Arc<PimState>,
+}
 
-    fn test_workspace() -> (tempfile::TempDir, PathBuf) {
-        let dir = tempfile::tempdir().unwrap();
-        let path = dir.path().to_path_buf();
-        (dir, path)
+impl ExpenseAddTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
     }
-
-    // ── Calendar Tests ──────────────────────────────────────────
-
-    #[tokio::test]
-    async fn calendar_add_and_list() {
-        let (_dir, ws) = test_workspace();
-        let state = Arc::new(PimState::new(ws, None));
-        let add = CalendarAddTool::from_state(state.clone());
-        let list = CalendarListTool::from_state(state);
-
-        // Add event
-        let result = add
-            .execute(serde_json::json!({
-                "title": "Team standup",
-                "date": "2026-03-01",
-                "time": "09:00"
-            }))
-            .await
-            .unwrap();
-        assert!(result.success);
-        assert!(result.output.contains("Team standup"));
-
-        // List events (same shared state)
-        let result = list
-            .execute(serde_json::json!({}))
-            .await
-            .unwrap();
-        assert!(result.success);
-        assert!(result.output.contains("Team standup"));
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
     }
+}
 
-    #[tokio::test]
-    async fn calendar_list_empty() {
-        let (_dir, ws) = test_workspace();
-        let list = CalendarListTool::new(ws, None);
-        let result = list.execute(serde_json::json!({})).await.unwrap();
-        assert!(result.success);
-        assert!(result.output.contains("No upcoming"));
+#[async_trait]
+impl Tool for ExpenseAddTool {
+    fn name(&self) -> &str {
+        "expense_add"
     }
 
-    #[tokio::test]
-    async fn calendar_list_with_date_filter() {
-        let (_dir, ws) = test_workspace();
-        let state = Arc::new(PimState::new(ws, None));
-        let add = CalendarAddTool::from_state(state.clone());
-        let list = CalendarListTool::from_state(state);
-
-        add.execute(serde_json::json!({"title": "Past", "date": "2020-01-01"}))
-            .await
-            .unwrap();
-        add.execute(serde_json::json!({"title": "Future", "date": "2030-01-01"}))
-            .await
-            .unwrap();
-
-        let result = list
-            .execute(serde_json::json!({"from": "2025-01-01"}))
-            .await
-            .unwrap();
-        assert!(result.output.contains("Future"));
-        assert!(!result.output.contains("Past"));
+    fn description(&self) -> &str {
+        "Record a household expense. Provide amount, optionally currency (default EUR), category, date (YYYY-MM-DD, default today) and a note."
     }
 
-    // ── Contacts Tests ──────────────────────────────────────────
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "amount": {"type": "number", "description": "Amount spent, e.g. 23.45"},
+                "currency": {"type": "string", "description": "ISO currency code. Default: EUR"},
+                "category": {"type": "string", "description": "e.g. groceries, transport, school, health. Default: other"},
+                "date": {"type": "string", "description": "Date in YYYY-MM-DD format. Default: today"},
+                "note": {"type": "string", "description": "Optional note, e.g. the shop"}
+            },
+            "required": ["amount"]
+        })
+    }
 
-    #[tokio::test]
-    async fn contacts_add_and_search() {
-        let (_dir, ws) = test_workspace();
-        let state = Arc::new(PimState::new(ws, None));
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let amount = args
+            .get("amount")
+            .and_then(serde_json::Value::as_f64)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'amount' parameter"))?;
+        if !amount.is_finite() || amount <= 0.0 {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Amount must be a positive number.".into()),
+                data: None,
+            });
+        }
+        let currency = args
+            .get("currency")
+            .and_then(|v| v.as_str())
+            .map_or_else(|| "EUR".to_string(), |c| c.trim().to_uppercase());
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Invalid currency '{currency}' (expected e.g. EUR)")),
+                data: None,
+            });
+        }
+        let category = args
+            .get("category")
+            .and_then(|v| v.as_str())
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(default_expense_category);
+        let date = args
+            .get("date")
+            .and_then(|v| v.as_str())
+            .map_or_else(
+                || chrono::Local::now().format("%Y-%m-%d").to_string(),
+                String::from,
+            );
+        if chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").is_err() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Invalid date '{date}' (expected YYYY-MM-DD)")),
+                data: None,
+            });
+        }
+        let note = args
+            .get("note")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let expense = Expense {
+            id: uuid::Uuid::new_v4().to_string(),
+            amount: round_cents(amount),
+            currency,
+            category,
+            date,
+            note,
+        };
+        let summary = format!(
+            "💶 Recorded {:.2} {} ({}) on {}",
+            expense.amount, expense.currency, expense.category, expense.date
+        );
+
+        {
+            let mut store = self.state.write().await;
+            store.expenses.push(expense);
+        }
+        self.state.flush().await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: summary,
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Expense List Tool ───────────────────────────────────────
+
+pub struct ExpenseListTool {
+    state: Arc<PimState>,
+}
+
+impl ExpenseListTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for ExpenseListTool {
+    fn name(&self) -> &str {
+        "expense_list"
+    }
+
+    fn description(&self) -> &str {
+        "List recorded expenses, newest first. Optionally filter by month (YYYY-MM) and category."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "month": {"type": "string", "description": "Only expenses in this month (YYYY-MM)"},
+                "category": {"type": "string", "description": "Only expenses in this category"},
+                "limit": {"type": "integer", "description": "Maximum number of expenses to return. Default: 20"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let month = args.get("month").and_then(|v| v.as_str()).unwrap_or("");
+        let category = args
+            .get("category")
+            .and_then(|v| v.as_str())
+            .map(str::to_lowercase);
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|l| usize::try_from(l).ok())
+            .unwrap_or(20);
+
+        let store = self.state.read().await;
+
+        let mut expenses: Vec<&Expense> = store
+            .expenses
+            .iter()
+            .filter(|e| e.date.starts_with(month))
+            .filter(|e| category.as_ref().is_none_or(|c| &e.category == c))
+            .collect();
+        expenses.sort_by(|a, b| b.date.cmp(&a.date));
+        expenses.truncate(limit);
+
+        if expenses.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "💶 No expenses found.".into(),
+                error: None,
+                data: None,
+            });
+        }
+
+        let lines: Vec<String> = expenses
+            .iter()
+            .map(|e| {
+                let note = if e.note.is_empty() {
+                    String::new()
+                } else {
+                    format!(" — {}", e.note)
+                };
+                format!(
+                    "💶 {} {:.2} {} ({}){note}",
+                    e.date, e.amount, e.currency, e.category
+                )
+            })
+            .collect();
+
+        Ok(ToolResult {
+            success: true,
+            output: lines.join("\n"),
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Expense Summary Tool ────────────────────────────────────
+
+pub struct ExpenseSummaryTool {
+    state: Arc<PimState>,
+}
+
+impl ExpenseSummaryTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for ExpenseSummaryTool {
+    fn name(&self) -> &str {
+        "expense_summary"
+    }
+
+    fn description(&self) -> &str {
+        "Summarize spending for a month (YYYY-MM, default this month): totals per currency and category."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "month": {"type": "string", "description": "Month in YYYY-MM format. Default: this month"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let month = args
+            .get("month")
+            .and_then(|v| v.as_str())
+            .map_or_else(|| chrono::Local::now().format("%Y-%m").to_string(), String::from);
+        if !is_valid_month(&month) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Invalid month '{month}' (expected YYYY-MM)")),
+                data: None,
+            });
+        }
+
+        let store = self.state.read().await;
+        let summary = summarize_expenses(&store.expenses, &month);
+
+        if summary.count == 0 {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("💶 No expenses in {month}."),
+                error: None,
+                data: None,
+            });
+        }
+
+        let mut output = format!("💶 {month}: {} expenses", summary.count);
+        for total in &summary.totals {
+            let _ = write!(output, "\nTotal {:.2} {}", total.total, total.currency);
+            for c in &total.categories {
+                let _ = write!(output, "\n  {}: {:.2} ({}×)", c.category, c.total, c.count);
+            }
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Location Reminder Add Tool ──────────────────────────────
+
+pub struct LocationReminderAddTool {
+    state: Arc<PimState>,
+}
+
+impl LocationReminderAddTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for LocationReminderAddTool {
+    fn name(&self) -> &str {
+        "location_reminder_add"
+    }
+
+    fn description(&self) -> &str {
+        "Create a reminder that fires when the user arrives at or leaves a named place (e.g. home, work, school) as reported by their phone."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "place": {"type": "string", "description": "Place name, e.g. home, work, school"},
+                "when": {"type": "string", "enum": ["arrive", "leave"], "description": "Fire on arrival or when leaving"},
+                "text": {"type": "string", "description": "What to remind the user of"},
+                "repeat": {"type": "boolean", "description": "Fire every time instead of once. Default: false"}
+            },
+            "required": ["place", "when", "text"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let place = args
+            .get("place")
+            .and_then(|v| v.as_str())
+            .map(normalize_place)
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'place' parameter"))?;
+        let when = args
+            .get("when")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'when' parameter"))?;
+        let Some(trigger) = LocationTrigger::parse(when) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Invalid 'when' value '{when}' (expected arrive or leave)")),
+                data: None,
+            });
+        };
+        let text = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'text' parameter"))?
+            .to_string();
+        let repeat = args
+            .get("repeat")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        let reminder = LocationReminder {
+            id: uuid::Uuid::new_v4().to_string(),
+            place,
+            trigger,
+            text,
+            repeat,
+            created_at: chrono::Local::now().to_rfc3339(),
+        };
+        let summary = format!(
+            "📍 Reminder set for {}: {}",
+            reminder.trigger.describe(&reminder.place),
+            reminder.text
+        );
+
+        {
+            let mut store = self.state.write().await;
+            store.location_reminders.push(reminder);
+        }
+        self.state.flush().await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: summary,
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Location Reminder List Tool ─────────────────────────────
+
+pub struct LocationReminderListTool {
+    state: Arc<PimState>,
+}
+
+impl LocationReminderListTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for LocationReminderListTool {
+    fn name(&self) -> &str {
+        "location_reminder_list"
+    }
+
+    fn description(&self) -> &str {
+        "List pending location reminders, optionally for one place."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "place": {"type": "string", "description": "Only reminders for this place"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let place = args.get("place").and_then(|v| v.as_str()).map(normalize_place);
+
+        let store = self.state.read().await;
+        let lines: Vec<String> = store
+            .location_reminders
+            .iter()
+            .filter(|r| place.as_ref().is_none_or(|p| &r.place == p))
+            .map(|r| {
+                let repeat = if r.repeat { " (every time)" } else { "" };
+                format!("📍 When {}: {}{repeat}", r.trigger.describe(&r.place), r.text)
+            })
+            .collect();
+
+        if lines.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "📍 No location reminders.".into(),
+                error: None,
+                data: None,
+            });
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output: lines.join("\n"),
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Task Add Tool ───────────────────────────────────────────
+
+/// Tags from a JSON array or a comma-separated string, lowercased.
+fn parse_tags(value: Option<&serde_json::Value>) -> Vec<String> {
+    let raw: Vec<&str> = match value {
+        Some(serde_json::Value::Array(items)) => items.iter().filter_map(|v| v.as_str()).collect(),
+        Some(serde_json::Value::String(s)) => s.split(',').collect(),
+        _ => Vec::new(),
+    };
+    let mut tags: Vec<String> = raw
+        .into_iter()
+        .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn describe_task(task: &Task, today: chrono::NaiveDate) -> String {
+    let check = if task.done { "✅" } else { "⬜" };
+    let mut line = format!("{check} {}", task.title);
+    if let Some(due) = &task.due {
+        let overdue = if task.is_overdue(today) { " ⚠️ overdue" } else { "" };
+        let _ = write!(line, " (due {due}{overdue})");
+    }
+    if task.priority != TaskPriority::Medium {
+        let _ = write!(line, " [{}]", task.priority.as_str());
+    }
+    for tag in &task.tags {
+        let _ = write!(line, " #{tag}");
+    }
+    let _ = write!(line, " — id {}", task.id);
+    line
+}
+
+pub struct TaskAddTool {
+    state: Arc<PimState>,
+}
+
+impl TaskAddTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskAddTool {
+    fn name(&self) -> &str {
+        "task_add"
+    }
+
+    fn description(&self) -> &str {
+        "Add a to-do task with an optional due date (YYYY-MM-DD), priority (low, medium, high) and tags."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string", "description": "What needs to be done"},
+                "due": {"type": "string", "description": "Due date in YYYY-MM-DD format"},
+                "priority": {"type": "string", "enum": ["low", "medium", "high"], "description": "Default: medium"},
+                "tags": {"type": "array", "items": {"type": "string"}, "description": "e.g. [\"household\", \"school\"]"},
+                "notes": {"type": "string", "description": "Optional details"}
+            },
+            "required": ["title"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let title = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'title' parameter"))?
+            .to_string();
+        let due = args.get("due").and_then(|v| v.as_str()).map(str::trim);
+        if let Some(due) = due {
+            if chrono::NaiveDate::parse_from_str(due, "%Y-%m-%d").is_err() {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Invalid due date '{due}' (expected YYYY-MM-DD)")),
+                    data: None,
+                });
+            }
+        }
+        let priority = match args.get("priority").and_then(|v| v.as_str()) {
+            None => TaskPriority::default(),
+            Some(p) => match TaskPriority::parse(p) {
+                Some(priority) => priority,
+                None => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Invalid priority '{p}' (expected low, medium or high)")),
+                        data: None,
+                    })
+                }
+            },
+        };
+
+        let task = Task {
+            id: uuid::Uuid::new_v4().to_string(),
+            done: false,
+            title,
+            due: due.map(String::from),
+            priority,
+            tags: parse_tags(args.get("tags")),
+            notes: args
+                .get("notes")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            completed_at: None,
+        };
+        let summary = format!(
+            "📋 Task added: {}",
+            describe_task(&task, chrono::Local::now().date_naive())
+        );
+
+        {
+            let mut store = self.state.write().await;
+            store.tasks.push(task);
+        }
+        self.state.flush().await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: summary,
+            error: None,
+            data: None,
+        })
+    }
+}
+
+// ── Task List Tool ──────────────────────────────────────────
+
+pub struct TaskListTool {
+    state: Arc<PimState>,
+}
+
+impl TaskListTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskListTool {
+    fn name(&self) -> &str {
+        "task_list"
+    }
+
+    fn description(&self) -> &str {
+        "List tasks. By default open tasks ordered by due date and priority; use status 'overdue' for tasks past their due date."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "status": {"type": "string", "enum": ["open", "overdue", "done", "all"], "description": "Default: open"},
+                "tag": {"type": "string", "description": "Only tasks with this tag"},
+                "limit": {"type": "integer", "description": "Maximum number of tasks to return. Default: 20"}
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "done": {"type": "boolean"},
+                    "title": {"type": "string"},
+                    "due": {"type": ["string", "null"]},
+                    "priority": {"type": "string", "enum": ["low", "medium", "high"]},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "notes": {"type": "string"},
+                    "created_at": {"type": "string"},
+                    "completed_at": {"type": ["string", "null"]}
+                }
+            }
+        }))
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let status = match args.get("status").and_then(|v| v.as_str()) {
+            None => TaskStatus::default(),
+            Some(s) => match TaskStatus::parse(s) {
+                Some(status) => status,
+                None => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Invalid status '{s}' (expected open, overdue, done or all)"
+                        )),
+                        data: None,
+                    })
+                }
+            },
+        };
+        let tag = args.get("tag").and_then(|v| v.as_str());
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|l| usize::try_from(l).ok())
+            .unwrap_or(20);
+        let today = chrono::Local::now().date_naive();
+
+        let mut tasks = {
+            let store = self.state.read().await;
+            query_tasks(&store.tasks, status, tag, today)
+        };
+        tasks.truncate(limit);
+
+        if tasks.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "📋 No tasks found.".into(),
+                error: None,
+                data: Some(serde_json::json!([])),
+            });
+        }
+
+        let lines: Vec<String> = tasks.iter().map(|t| describe_task(t, today)).collect();
+        Ok(ToolResult {
+            success: true,
+            output: lines.join("\n"),
+            error: None,
+            data: Some(serde_json::to_value(&tasks)?),
+        })
+    }
+}
+
+// ── Task Complete Tool ──────────────────────────────────────
+
+pub struct TaskCompleteTool {
+    state: Arc<PimState>,
+}
+
+impl TaskCompleteTool {
+    pub fn new(workspace: PathBuf, secrets: Option<SecretStore>) -> Self {
+        Self {
+            state: Arc::new(PimState::new(workspace, secrets)),
+        }
+    }
+    fn from_state(state: Arc<PimState>) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskCompleteTool {
+    fn name(&self) -> &str {
+        "task_complete"
+    }
+
+    fn description(&self) -> &str {
+        "Mark an open task as done, by id or by its exact title."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "description": "Task id from task_list"},
+                "title": {"type": "string", "description": "Exact task title (case-insensitive), if no id is given"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = args.get("id").and_then(|v| v.as_str());
+        let title = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .map(|t| t.trim().to_lowercase());
+        if id.is_none() && title.is_none() {
+            anyhow::bail!("Missing 'id' or 'title' parameter");
+        }
+
+        let completed = {
+            let mut store = self.state.write().await;
+            let matches: Vec<usize> = store
+                .tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| !t.done)
+                .filter(|(_, t)| match id {
+                    Some(id) => t.id == id,
+                    None => title.as_ref().is_some_and(|title| &t.title.to_lowercase() == title),
+                })
+                .map(|(i, _)| i)
+                .collect();
+            match matches.as_slice() {
+                [index] => {
+                    let task = &mut store.tasks[*index];
+                    task.done = true;
+                    task.completed_at = Some(chrono::Local::now().to_rfc3339());
+                    Ok(task.title.clone())
+                }
+                [] => Err("No open task matches.".to_string()),
+                _ => Err(format!(
+                    "{} open tasks have that title; use the id instead.",
+                    matches.len()
+                )),
+            }
+        };
+
+        match completed {
+            Ok(title) => {
+                self.state.flush().await?;
+                Ok(ToolResult {
+                    success: true,
+                    output: format!("✅ Completed: {title}"),
+                    error: None,
+                    data: None,
+                })
+            }
+            Err(error) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+                data: None,
+            }),
+        }
+    }
+}
+
+/// Create all PIM tools for a given workspace.
+///
+/// All tools share a single `PimState` with `RwLock` — readers
+/// proceed concurrently, writers serialize and flush to disk.
+/// If `secrets` is provided, PIM data will be encrypted at rest.
+pub fn pim_tools(workspace: &std::path::Path, secrets: Option<SecretStore>) -> Vec<Box<dyn Tool>> {
+    let state = Arc::new(PimState::new(workspace.to_path_buf(), secrets));
+    vec![
+        Box::new(CalendarAddTool::from_state(state.clone())),
+        Box::new(CalendarListTool::from_state(state.clone())),
+        Box::new(ContactsAddTool::from_state(state.clone())),
+        Box::new(ContactsSearchTool::from_state(state.clone())),
+        Box::new(ContactsImportTool::from_state(state.clone())),
+        Box::new(NotesCreateTool::from_state(state.clone())),
+        Box::new(NotesSearchTool::from_state(state.clone())),
+        Box::new(NotesReadTool::from_state(state.clone())),
+        Box::new(ExpenseAddTool::from_state(state.clone())),
+        Box::new(ExpenseListTool::from_state(state.clone())),
+        Box::new(ExpenseSummaryTool::from_state(state.clone())),
+        Box::new(LocationReminderAddTool::from_state(state.clone())),
+        Box::new(LocationReminderListTool::from_state(state.clone())),
+        Box::new(TaskAddTool::from_state(state.clone())),
+        Box::new(TaskListTool::from_state(state.clone())),
+        Box::new(TaskCompleteTool::from_state(state)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_workspace() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_path_buf();
+        (dir, path)
+    }
+
+    // ── Calendar Tests ──────────────────────────────────────────
+
+    #[tokio::test]
+    async fn calendar_add_and_list() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
+        let add = CalendarAddTool::from_state(state.clone());
+        let list = CalendarListTool::from_state(state);
+
+        // Add event
+        let result = add
+            .execute(serde_json::json!({
+                "title": "Team standup",
+                "date": "2026-03-01",
+                "time": "09:00"
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Team standup"));
+
+        // List events (same shared state)
+        let result = list
+            .execute(serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Team standup"));
+    }
+
+    #[tokio::test]
+    async fn calendar_list_picks_up_external_writes() {
+        let (_dir, ws) = test_workspace();
+        let list = CalendarListTool::new(ws.clone(), None);
+
+        // e.g. an ICS import through the gateway
+        let mut store = load_store(&ws, &None);
+        store.events.push(CalendarEvent {
+            id: "ext-1".into(),
+            title: "Imported concert".into(),
+            date: "2099-06-01".into(),
+            time: None,
+            description: String::new(),
+        });
+        save_store(&ws, &store, &None).unwrap();
+
+        let result = list.execute(serde_json::json!({})).await.unwrap();
+        assert!(result.output.contains("Imported concert"));
+    }
+
+    #[tokio::test]
+    async fn calendar_list_empty() {
+        let (_dir, ws) = test_workspace();
+        let list = CalendarListTool::new(ws, None);
+        let result = list.execute(serde_json::json!({})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No upcoming"));
+    }
+
+    #[tokio::test]
+    async fn calendar_list_with_date_filter() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
+        let add = CalendarAddTool::from_state(state.clone());
+        let list = CalendarListTool::from_state(state);
+
+        add.execute(serde_json::json!({"title": "Past", "date": "2020-01-01"}))
+            .await
+            .unwrap();
+        add.execute(serde_json::json!({"title": "Future", "date": "2030-01-01"}))
+            .await
+            .unwrap();
+
+        let result = list
+            .execute(serde_json::json!({"from": "2025-01-01"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Future"));
+        assert!(!result.output.contains("Past"));
+    }
+
+    // ── Contacts Tests ──────────────────────────────────────────
+
+    #[tokio::test]
+    async fn contacts_add_and_search() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
         let add = ContactsAddTool::from_state(state.clone());
         let search = ContactsSearchTool::from_state(state);
 
@@ -853,6 +2159,32 @@ mod tests {
         assert!(result.output.contains("No contacts"));
     }
 
+    #[tokio::test]
+    async fn contacts_import_from_workspace_file() {
+        let (_dir, ws) = test_workspace();
+        std::fs::write(
+            ws.join("phone.vcf"),
+            "BEGIN:VCARD\nVERSION:3.0\nFN:Alice\nEMAIL:alice@example.com\nTEL:+49 1\nEND:VCARD\n",
+        )
+        .unwrap();
+        let state = Arc::new(PimState::new(ws, None));
+        let import = ContactsImportTool::from_state(state.clone());
+        let search = ContactsSearchTool::from_state(state);
+
+        let result = import.execute(serde_json::json!({"path": "phone.vcf"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.data.unwrap()["added"], 1);
+        let again = import.execute(serde_json::json!({"path": "phone.vcf"})).await.unwrap();
+        assert_eq!(again.data.unwrap()["unchanged"], 1);
+
+        let found = search.execute(serde_json::json!({"query": "alice"})).await.unwrap();
+        assert!(found.output.contains("alice@example.com"));
+        assert!(found.output.contains("+49 1"));
+
+        let escape = import.execute(serde_json::json!({"path": "../etc/passwd"})).await.unwrap();
+        assert!(!escape.success);
+    }
+
     // ── Notes Tests ─────────────────────────────────────────────
 
     #[tokio::test]
@@ -927,18 +2259,225 @@ mod tests {
 
     // ── PIM Tools Factory ───────────────────────────────────────
 
+    // ── Expense Tests ───────────────────────────────────────────
+
+    #[tokio::test]
+    async fn expense_add_list_and_summary() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
+        let add = ExpenseAddTool::from_state(state.clone());
+        let list = ExpenseListTool::from_state(state.clone());
+        let summary = ExpenseSummaryTool::from_state(state);
+
+        for (amount, category, date) in [
+            (23.456, "Groceries", "2026-03-02"),
+            (10.0, "groceries", "2026-03-15"),
+            (45.0, "transport", "2026-03-20"),
+            (99.0, "transport", "2026-04-01"),
+        ] {
+            let result = add
+                .execute(serde_json::json!({
+                    "amount": amount, "category": category, "date": date
+                }))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+        }
+
+        let result = list
+            .execute(serde_json::json!({"month": "2026-03", "category": "groceries"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("23.46 EUR"));
+        assert!(!result.output.contains("transport"));
+
+        let result = summary
+            .execute(serde_json::json!({"month": "2026-03"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("3 expenses"));
+        assert!(result.output.contains("Total 78.46 EUR"));
+        assert!(result.output.contains("transport: 45.00"));
+    }
+
+    #[tokio::test]
+    async fn expense_add_rejects_invalid_input() {
+        let (_dir, ws) = test_workspace();
+        let add = ExpenseAddTool::new(ws, None);
+        for args in [
+            serde_json::json!({"amount": -5}),
+            serde_json::json!({"amount": 5, "currency": "euros"}),
+            serde_json::json!({"amount": 5, "date": "yesterday"}),
+        ] {
+            assert!(!add.execute(args).await.unwrap().success);
+        }
+    }
+
+    #[test]
+    fn summarize_expenses_groups_by_currency_and_category() {
+        let expense = |amount, currency: &str, category: &str| Expense {
+            id: String::new(),
+            amount,
+            currency: currency.into(),
+            category: category.into(),
+            date: "2026-05-10".into(),
+            note: String::new(),
+        };
+        let expenses = vec![
+            expense(0.1, "EUR", "food"),
+            expense(0.2, "EUR", "food"),
+            expense(50.0, "EUR", "school"),
+            expense(12.0, "CHF", "food"),
+        ];
+        let summary = summarize_expenses(&expenses, "2026-05");
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.totals[0].currency, "EUR");
+        assert!((summary.totals[0].total - 50.3).abs() < f64::EPSILON);
+        assert_eq!(summary.totals[0].categories[0].category, "school");
+        assert_eq!(summary.totals[1].currency, "CHF");
+        assert_eq!(summarize_expenses(&expenses, "2026-06").count, 0);
+        assert!(is_valid_month("2026-05"));
+        assert!(!is_valid_month("2026-13"));
+        assert!(!is_valid_month("2026-5"));
+    }
+
+    // ── Location Reminder Tests ─────────────────────────────────
+
+    #[tokio::test]
+    async fn location_reminders_fire_once_unless_repeating() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
+        let add = LocationReminderAddTool::from_state(state.clone());
+        let list = LocationReminderListTool::from_state(state.clone());
+
+        for (place, when, text, repeat) in [
+            ("Home", "arrive", "Take the bins out", false),
+            ("work", "leave", "Buy milk", true),
+            ("home", "leave", "Lock the door", false),
+        ] {
+            let result = add
+                .execute(serde_json::json!({
+                    "place": place, "when": when, "text": text, "repeat": repeat
+                }))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+        }
+        let result = list
+            .execute(serde_json::json!({"place": "home"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("arriving at home: Take the bins out"));
+        assert!(!result.output.contains("Buy milk"));
+
+        let mut store = state.store.write().await;
+        let fired = fire_location_reminders(&mut store, " HOME ", LocationTrigger::Arrive);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].text, "Take the bins out");
+        assert!(fire_location_reminders(&mut store, "home", LocationTrigger::Arrive).is_empty());
+
+        for _ in 0..2 {
+            let fired = fire_location_reminders(&mut store, "work", LocationTrigger::Leave);
+            assert_eq!(fired.len(), 1);
+        }
+        assert_eq!(store.location_reminders.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn location_reminder_add_rejects_unknown_trigger() {
+        let (_dir, ws) = test_workspace();
+        let add = LocationReminderAddTool::new(ws, None);
+        let result = add
+            .execute(serde_json::json!({"place": "home", "when": "nearby", "text": "x"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(LocationTrigger::parse("Arrived"), Some(LocationTrigger::Arrive));
+    }
+
+    // ── Task Tests ──────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn tasks_add_list_overdue_and_complete() {
+        let (_dir, ws) = test_workspace();
+        let state = Arc::new(PimState::new(ws, None));
+        let add = TaskAddTool::from_state(state.clone());
+        let list = TaskListTool::from_state(state.clone());
+        let complete = TaskCompleteTool::from_state(state.clone());
+
+        for args in [
+            serde_json::json!({"title": "Pay rent", "due": "2020-01-01", "priority": "high", "tags": ["home"]}),
+            serde_json::json!({"title": "Buy paint", "tags": "Home, #DIY"}),
+            serde_json::json!({"title": "Renew passport", "due": "2999-12-31", "priority": "low"}),
+        ] {
+            let result = add.execute(args).await.unwrap();
+            assert!(result.success, "{:?}", result.error);
+        }
+
+        let result = list.execute(serde_json::json!({})).await.unwrap();
+        assert!(result.output.contains("overdue"));
+        let data = result.data.unwrap();
+        let titles: Vec<&str> = data
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["Pay rent", "Renew passport", "Buy paint"]);
+
+        let result = list.execute(serde_json::json!({"status": "overdue"})).await.unwrap();
+        assert_eq!(result.data.unwrap().as_array().unwrap().len(), 1);
+        let result = list.execute(serde_json::json!({"tag": "diy"})).await.unwrap();
+        assert!(result.output.contains("Buy paint"));
+        assert!(!result.output.contains("Pay rent"));
+
+        let result = complete.execute(serde_json::json!({"title": "pay RENT"})).await.unwrap();
+        assert!(result.success);
+        let result = complete.execute(serde_json::json!({"title": "Pay rent"})).await.unwrap();
+        assert!(!result.success);
+        let result = list.execute(serde_json::json!({"status": "overdue"})).await.unwrap();
+        assert!(result.data.unwrap().as_array().unwrap().is_empty());
+        let result = list.execute(serde_json::json!({"status": "done"})).await.unwrap();
+        assert!(result.output.contains("✅ Pay rent"));
+    }
+
+    #[tokio::test]
+    async fn task_add_rejects_bad_due_date_and_priority() {
+        let (_dir, ws) = test_workspace();
+        let add = TaskAddTool::new(ws, None);
+        let result = add
+            .execute(serde_json::json!({"title": "x", "due": "tomorrow"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Invalid due date"));
+        let result = add
+            .execute(serde_json::json!({"title": "x", "priority": "asap"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Invalid priority"));
+    }
+
     #[test]
-    fn pim_tools_creates_all_seven() {
+    fn pim_tools_creates_all_sixteen() {
         let (_dir, ws) = test_workspace();
         let tools = pim_tools(&ws, None);
-        assert_eq!(tools.len(), 7);
+        assert_eq!(tools.len(), 16);
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"calendar_add"));
         assert!(names.contains(&"calendar_list"));
         assert!(names.contains(&"contacts_add"));
         assert!(names.contains(&"contacts_search"));
+        assert!(names.contains(&"contacts_import"));
         assert!(names.contains(&"notes_create"));
         assert!(names.contains(&"notes_search"));
         assert!(names.contains(&"notes_read"));
+        assert!(names.contains(&"expense_add"));
+        assert!(names.contains(&"expense_list"));
+        assert!(names.contains(&"expense_summary"));
+        assert!(names.contains(&"location_reminder_add"));
+        assert!(names.contains(&"location_reminder_list"));
+        assert!(names.contains(&"task_add"));
+        assert!(names.contains(&"task_list"));
+        assert!(names.contains(&"task_complete"));
     }
 }
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Live tool registry of the gateway.
+//!
+//! Tools can be added and removed while the gateway runs (hot-added MCP
+//! servers). Readers take a snapshot; a running agent turn keeps the tool set
+//! it started with, and the next turn sees the change.
+
+use crate::tools::traits::{Tool, ToolResult, ToolSpec};
+use async_trait::async_trait;
+use std::sync::{Arc, PoisonError, RwLock};
+
+#[async_trait]
+impl Tool for Arc<dyn Tool> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        (**self).parameters_schema()
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        (**self).output_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        (**self).execute(args).await
+    }
+
+    fn spec(&self) -> ToolSpec {
+        (**self).spec()
+    }
+}
+
+struct Tools {
+    shared: Vec<Arc<dyn Tool>>,
+    snapshot: Arc<Vec<Box<dyn Tool>>>,
+}
+
+impl Tools {
+    fn new(shared: Vec<Arc<dyn Tool>>) -> Self {
+        let snapshot = Arc::new(
+            shared
+                .iter()
+                .map(|tool| Box::new(Arc::clone(tool)) as Box<dyn Tool>)
+                .collect(),
+        );
+        Self { shared, snapshot }
+    }
+}
+
+/// A tool set that can change at runtime.
+pub struct ToolRegistry {
+    tools: RwLock<Tools>,
+}
+
+impl ToolRegistry {
+    pub fn new(tools: Vec<Box<dyn Tool>>) -> Self {
+        Self {
+            tools: RwLock::new(Tools::new(tools.into_iter().map(Arc::from).collect())),
+        }
+    }
+
+    /// The current tools.
+    pub fn snapshot(&self) -> Arc<Vec<Box<dyn Tool>>> {
+        let tools = self.tools.read().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(&tools.snapshot)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.snapshot().iter().any(|t| t.name() == name)
+    }
+
+    /// Add `tools`. Fails without adding anything if a name is taken.
+    pub fn extend(&self, tools: Vec<Box<dyn Tool>>) -> anyhow::Result<()> {
+        let mut current = self.tools.write().unwrap_or_else(PoisonError::into_inner);
+        for (i, tool) in tools.iter().enumerate() {
+            let name = tool.name();
+            if current.shared.iter().any(|t| t.name() == name)
+                || tools[..i].iter().any(|t| t.name() == name)
+            {
+                anyhow::bail!("A tool named '{name}' is already registered");
+            }
+        }
+        let mut shared = current.shared.clone();
+        shared.extend(tools.into_iter().map(Arc::from));
+        *current = Tools::new(shared);
+        Ok(())
+    }
+
+    /// Remove the tools called `names`; returns how many were removed.
+    pub fn remove(&self, names: &[String]) -> usize {
+        let mut current = self.tools.write().unwrap_or_else(PoisonError::into_inner);
+        let before = current.shared.len();
+        let shared: Vec<Arc<dyn Tool>> = current
+            .shared
+            .iter()
+            .filter(|t| !names.iter().any(|n| n == t.name()))
+            .cloned()
+            .collect();
+        let removed = before - shared.len();
+        *current = Tools::new(shared);
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NamedTool(&'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: self.0.into(),
+                error: None,
+                data: None,
+            })
+        }
+    }
+
+    fn names(registry: &ToolRegistry) -> Vec<String> {
+        registry
+            .snapshot()
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn snapshots_survive_changes() {
+        let registry = ToolRegistry::new(vec![Box::new(NamedTool("shell"))]);
+        let before = registry.snapshot();
+
+        registry
+            .extend(vec![Box::new(NamedTool("mcp_search"))])
+            .unwrap();
+        assert_eq!(names(&registry), vec!["shell", "mcp_search"]);
+        assert_eq!(before.len(), 1);
+
+        let result = registry.snapshot()[1]
+            .execute(serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "mcp_search");
+
+        assert_eq!(registry.remove(&["mcp_search".into()]), 1);
+        assert_eq!(names(&registry), vec!["shell"]);
+    }
+
+    #[test]
+    fn name_clashes_are_rejected() {
+        let registry = ToolRegistry::new(vec![Box::new(NamedTool("shell"))]);
+        assert!(registry.extend(vec![Box::new(NamedTool("shell"))]).is_err());
+        assert!(registry
+            .extend(vec![Box::new(NamedTool("a")), Box::new(NamedTool("a"))])
+            .is_err());
+        assert!(registry.contains("shell"));
+        assert!(!registry.contains("a"));
+    }
+}
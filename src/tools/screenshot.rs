@@ -80,6 +80,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Screenshot not supported on this platform".into()),
+                data: None,
             });
         };
 
@@ -115,12 +116,14 @@ impl ScreenshotTool {
                                 "No screenshot tool found. Install gnome-screenshot, scrot, or ImageMagick."
                                     .into(),
                             ),
+                            data: None,
                         });
                     }
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Screenshot command failed: {stderr}")),
+                        data: None,
                     });
                 }
 
@@ -130,6 +133,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute screenshot command: {e}")),
+                data: None,
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -137,6 +141,7 @@ impl ScreenshotTool {
                 error: Some(format!(
                     "Screenshot timed out after {SCREENSHOT_TIMEOUT_SECS}s"
                 )),
+                data: None,
             }),
         }
     }
@@ -155,6 +160,7 @@ impl ScreenshotTool {
                         meta.len(),
                     ),
                     error: None,
+                    data: None,
                 });
             }
         }
@@ -192,12 +198,14 @@ impl ScreenshotTool {
                     success: true,
                     output: output_msg,
                     error: None,
+                    data: None,
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: format!("Screenshot saved to: {}", output_path.display()),
                 error: Some(format!("Failed to read screenshot file: {e}")),
+                data: None,
             }),
         }
     }
@@ -235,6 +243,7 @@ impl Tool for ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                data: None,
             });
         }
         self.capture(args).await
@@ -3,32 +3,156 @@
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
 use super::traits::{Tool, ToolResult};
-use crate::runtime::RuntimeAdapter;
+use crate::config::{ResourceLimitsConfig, SandboxProfile};
+use crate::runtime::{limits, RuntimeAdapter};
+use crate::security::detect::ToolSandbox;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
-use std::sync::Arc;
+use std::fmt::Write as _;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 
 /// Maximum shell command execution time before kill.
 const SHELL_TIMEOUT_SECS: u64 = 60;
-/// Maximum output size in bytes (1MB).
-const MAX_OUTPUT_BYTES: usize = 1_048_576;
+/// Maximum `stdin` payload in bytes (1MB).
+const MAX_STDIN_BYTES: usize = 1_048_576;
 /// Environment variables safe to pass to shell commands.
 /// Only functional variables are included — never API keys or secrets.
 const SAFE_ENV_VARS: &[&str] = &[
     "PATH", "HOME", "TERM", "LANG", "LC_ALL", "LC_CTYPE", "USER", "SHELL", "TMPDIR",
 ];
 
+/// The [`SAFE_ENV_VARS`] that are set in this process, to pass on to a child.
+pub(crate) fn safe_env() -> Vec<(String, String)> {
+    SAFE_ENV_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok().map(|val| ((*var).to_string(), val)))
+        .collect()
+}
+
+/// The sandbox `tool` runs its commands in on `runtime`, none when the
+/// runtime isolates commands itself.
+fn tool_sandbox(
+    security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
+    tool: &str,
+    declared: SandboxProfile,
+) -> ToolSandbox {
+    if runtime.isolates_commands() {
+        ToolSandbox::none()
+    } else {
+        security.tool_sandbox(tool, declared)
+    }
+}
+
+/// Most bytes kept of each output stream (`0` in the config = unlimited).
+fn output_cap(limits: &ResourceLimitsConfig) -> usize {
+    match limits.max_output_bytes {
+        0 => usize::MAX,
+        max => max,
+    }
+}
+
+/// Read `reader` to the end into `sink`. Keeps one byte more than `max` so
+/// truncation is visible, and discards the rest so the child never blocks on
+/// a full pipe.
+async fn drain<R: AsyncRead + Unpin>(
+    mut reader: R,
+    sink: &Mutex<Vec<u8>>,
+    max: usize,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; 8192];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        let mut sink = sink.lock().unwrap_or_else(PoisonError::into_inner);
+        let room = max.saturating_add(1).saturating_sub(sink.len());
+        sink.extend_from_slice(&buf[..n.min(room)]);
+    }
+}
+
+/// Run `cmd` under `limits`, feeding it `stdin` and collecting stdout and
+/// stderr. With `merge_stderr` both streams land in the first buffer in
+/// arrival order.
+async fn run_command(
+    mut cmd: tokio::process::Command,
+    stdin: Option<&[u8]>,
+    merge_stderr: bool,
+    limits: &ResourceLimitsConfig,
+) -> std::io::Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+    let mut child = cmd.spawn()?;
+    let _containment = limits::contain(&child, limits)?;
+    let (Some(out_pipe), Some(err_pipe)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(std::io::Error::other("command output was not captured"));
+    };
+
+    let input = child.stdin.take();
+    let feed = async move {
+        if let (Some(mut pipe), Some(data)) = (input, stdin) {
+            // A command that exits without reading all of its input closes
+            // the pipe early; that is the command's business, not an error.
+            let _ = pipe.write_all(data).await;
+        }
+        // The pipe is dropped here, so the command sees end-of-file.
+    };
+    let stdout = Mutex::new(Vec::new());
+    let stderr = Mutex::new(Vec::new());
+    let err_sink = if merge_stderr { &stdout } else { &stderr };
+    let max = output_cap(limits);
+    let ((), out_read, err_read) = tokio::join!(
+        feed,
+        drain(out_pipe, &stdout, max),
+        drain(err_pipe, err_sink, max)
+    );
+    out_read?;
+    err_read?;
+
+    let status = child.wait().await?;
+    Ok((
+        status,
+        stdout.into_inner().unwrap_or_else(PoisonError::into_inner),
+        stderr.into_inner().unwrap_or_else(PoisonError::into_inner),
+    ))
+}
+
 /// Shell command execution tool with sandboxing
 pub struct ShellTool {
     security: Arc<SecurityPolicy>,
     runtime: Arc<dyn RuntimeAdapter>,
+    /// Detected on first use
+    sandbox: OnceLock<ToolSandbox>,
 }
 
 impl ShellTool {
     pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
-        Self { security, runtime }
+        Self {
+            security,
+            runtime,
+            sandbox: OnceLock::new(),
+        }
+    }
+
+    fn tool_sandbox(&self) -> &ToolSandbox {
+        self.sandbox.get_or_init(|| {
+            tool_sandbox(
+                &self.security,
+                self.runtime.as_ref(),
+                self.name(),
+                self.sandbox_profile(),
+            )
+        })
     }
 }
 
@@ -39,7 +163,7 @@ impl Tool for ShellTool {
     }
 
     fn description(&self) -> &str {
-        "Execute a shell command in the workspace directory"
+        "Execute a shell command in the workspace directory, optionally feeding it standard input"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -54,12 +178,30 @@ impl Tool for ShellTool {
                     "type": "boolean",
                     "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
                     "default": false
+                },
+                "stdin": {
+                    "type": "string",
+                    "description": "Text passed to the command's standard input (max 1MB), e.g. a diff for `patch -p1` or SQL for `psql`"
+                },
+                "merge_stderr": {
+                    "type": "boolean",
+                    "description": "Interleave stderr into the output instead of returning it separately",
+                    "default": false
                 }
             },
             "required": ["command"]
         })
     }
 
+    /// Commands may write only to the workspace and `/tmp`.
+    fn sandbox_profile(&self) -> SandboxProfile {
+        SandboxProfile::ReadonlyFs
+    }
+
+    fn sandbox(&self) -> Option<&ToolSandbox> {
+        Some(self.tool_sandbox())
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let command = args
             .get("command")
@@ -69,12 +211,27 @@ impl Tool for ShellTool {
             .get("approved")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let stdin = args.get("stdin").and_then(|v| v.as_str());
+        let merge_stderr = args
+            .get("merge_stderr")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        if stdin.is_some_and(|input| input.len() > MAX_STDIN_BYTES) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("stdin exceeds the {MAX_STDIN_BYTES} byte limit")),
+                data: None,
+            });
+        }
 
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                data: None,
             });
         }
 
@@ -85,6 +242,7 @@ impl Tool for ShellTool {
                     success: false,
                     output: String::new(),
                     error: Some(reason),
+                    data: None,
                 });
             }
         }
@@ -94,65 +252,74 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                data: None,
             });
         }
 
         // Execute with timeout to prevent hanging commands.
         // Clear the environment to prevent leaking API keys and other secrets
         // (CWE-200), then re-add only safe, functional variables.
-        let mut cmd = match self
-            .runtime
-            .build_shell_command(command, &self.security.workspace_dir)
-        {
+        let limits = &self.security.resource_limits;
+        let mut cmd = match self.runtime.build_sandboxed_shell_command(
+            command,
+            &self.security.workspace_dir,
+            limits,
+            self.tool_sandbox(),
+        ) {
             Ok(cmd) => cmd,
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to build runtime command: {e}")),
+                    data: None,
                 });
             }
         };
         cmd.env_clear();
+        // Dropping the future (timeout or cancelled agent run) kills the process.
+        cmd.kill_on_drop(true);
 
-        for var in SAFE_ENV_VARS {
-            if let Ok(val) = std::env::var(var) {
-                cmd.env(var, val);
-            }
-        }
+        cmd.envs(safe_env());
 
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
+        let result = tokio::time::timeout(
+            Duration::from_secs(SHELL_TIMEOUT_SECS),
+            run_command(cmd, stdin.map(str::as_bytes), merge_stderr, limits),
+        )
+        .await;
 
         match result {
-            Ok(Ok(output)) => {
-                let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            Ok(Ok((status, stdout, stderr))) => {
+                let mut stdout = String::from_utf8_lossy(&stdout).to_string();
+                let mut stderr = String::from_utf8_lossy(&stderr).to_string();
 
                 // Truncate output to prevent OOM
-                if stdout.len() > MAX_OUTPUT_BYTES {
-                    stdout.truncate(stdout.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stdout.push_str("\n... [output truncated at 1MB]");
+                let max = output_cap(limits);
+                if stdout.len() > max {
+                    stdout.truncate(stdout.floor_char_boundary(max));
+                    let _ = write!(stdout, "\n... [output truncated at {max} bytes]");
                 }
-                if stderr.len() > MAX_OUTPUT_BYTES {
-                    stderr.truncate(stderr.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stderr.push_str("\n... [stderr truncated at 1MB]");
+                if stderr.len() > max {
+                    stderr.truncate(stderr.floor_char_boundary(max));
+                    let _ = write!(stderr, "\n... [stderr truncated at {max} bytes]");
                 }
 
                 Ok(ToolResult {
-                    success: output.status.success(),
+                    success: status.success(),
                     output: stdout,
                     error: if stderr.is_empty() {
                         None
                     } else {
                         Some(stderr)
                     },
+                    data: None,
                 })
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute command: {e}")),
+                data: None,
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -160,6 +327,7 @@ impl Tool for ShellTool {
                 error: Some(format!(
                     "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
                 )),
+                data: None,
             }),
         }
     }
@@ -195,6 +363,26 @@ mod tests {
         assert!(!tool.description().is_empty());
     }
 
+    #[test]
+    fn shell_tool_sandbox_profile_can_be_overridden() {
+        let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
+        assert_eq!(tool.sandbox_profile(), SandboxProfile::ReadonlyFs);
+        assert_eq!(
+            tool.sandbox().unwrap().requested(),
+            SandboxProfile::ReadonlyFs
+        );
+
+        let mut security = SecurityPolicy::default();
+        security
+            .sandbox
+            .profiles
+            .insert("shell".into(), SandboxProfile::None);
+        let tool = ShellTool::new(Arc::new(security), test_runtime());
+        let sandbox = tool.sandbox().unwrap();
+        assert_eq!(sandbox.requested(), SandboxProfile::None);
+        assert_eq!(sandbox.backend(), "none");
+    }
+
     #[test]
     fn shell_tool_schema_has_command() {
         let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
@@ -219,6 +407,43 @@ mod tests {
         assert!(result.error.is_none());
     }
 
+    #[tokio::test]
+    async fn shell_feeds_stdin_to_the_command() {
+        let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
+        let result = tool
+            .execute(json!({"command": "cat", "stdin": "line one\nline two\n"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "line one\nline two\n");
+
+        let oversized = "x".repeat(MAX_STDIN_BYTES + 1);
+        let result = tool
+            .execute(json!({"command": "cat", "stdin": oversized}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("stdin exceeds"));
+    }
+
+    #[tokio::test]
+    async fn shell_merges_stderr_into_output() {
+        let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
+        let command = "ls ./mymolt-definitely-missing-path";
+        let separate = tool.execute(json!({"command": command})).await.unwrap();
+        assert!(separate.output.is_empty());
+        let stderr = separate.error.unwrap();
+        assert!(!stderr.is_empty());
+
+        let merged = tool
+            .execute(json!({"command": command, "merge_stderr": true}))
+            .await
+            .unwrap();
+        assert!(!merged.success);
+        assert!(merged.error.is_none());
+        assert_eq!(merged.output, stderr);
+    }
+
     #[tokio::test]
     async fn shell_blocks_disallowed_command() {
         let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
@@ -261,6 +486,27 @@ mod tests {
         assert!(!result.success);
     }
 
+    #[tokio::test]
+    async fn shell_output_is_capped_by_resource_limits() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: std::env::temp_dir(),
+            resource_limits: ResourceLimitsConfig {
+                max_output_bytes: 8,
+                ..ResourceLimitsConfig::default()
+            },
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security, test_runtime());
+        let result = tool
+            .execute(json!({"command": "echo 0123456789abcdef"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("01234567\n"));
+        assert!(result.output.contains("[output truncated at 8 bytes]"));
+    }
+
     fn test_security_with_env_cmd() -> Arc<SecurityPolicy> {
         Arc::new(SecurityPolicy {
             autonomy: AutonomyLevel::Supervised,
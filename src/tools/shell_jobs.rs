@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Background shell jobs: start a long command, then poll its status and
+//! output or kill it. Commands pass the same policy checks as `shell`.
+
+use super::shell::safe_env;
+use super::traits::{Tool, ToolResult};
+use crate::config::SandboxProfile;
+use crate::runtime::jobs::{JobInfo, JobManager, JobState};
+use crate::runtime::RuntimeAdapter;
+use crate::security::detect::ToolSandbox;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::sync::{Arc, OnceLock};
+
+/// Output returned by `shell_job_logs` when no `max_bytes` is given.
+const DEFAULT_LOG_BYTES: usize = 8 * 1024;
+/// Most output `shell_job_logs` returns per call.
+const MAX_LOG_BYTES: usize = 64 * 1024;
+
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        data: None,
+    }
+}
+
+fn describe(info: &JobInfo) -> String {
+    format!(
+        "[{}] {} — {} ({}s, {} bytes of output)",
+        info.id,
+        info.command,
+        info.state.describe(),
+        info.elapsed_secs,
+        info.output_bytes
+    )
+}
+
+fn job_id(args: &serde_json::Value) -> anyhow::Result<&str> {
+    args.get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))
+}
+
+// ── shell_job_start ─────────────────────────────────────────
+
+pub struct ShellJobStartTool {
+    security: Arc<SecurityPolicy>,
+    jobs: Arc<JobManager>,
+    /// Detected on first use
+    sandbox: OnceLock<ToolSandbox>,
+}
+
+impl ShellJobStartTool {
+    /// Jobs only run on runtimes with long-running processes, which never
+    /// isolate commands themselves.
+    fn tool_sandbox(&self) -> &ToolSandbox {
+        self.sandbox.get_or_init(|| {
+            self.security
+                .tool_sandbox(self.name(), self.sandbox_profile())
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for ShellJobStartTool {
+    fn name(&self) -> &str {
+        "shell_job_start"
+    }
+
+    fn description(&self) -> &str {
+        "Start a long-running shell command (build, test suite, download) in the background and return \
+         a job id at once. Check on it with shell_job_status and shell_job_logs; stop it with shell_job_kill."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "The shell command to run in the workspace directory"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    fn sandbox_profile(&self) -> SandboxProfile {
+        SandboxProfile::ReadonlyFs
+    }
+
+    fn sandbox(&self) -> Option<&ToolSandbox> {
+        Some(self.tool_sandbox())
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let command = args
+            .get("command")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
+        let approved = args
+            .get("approved")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour".into(),
+            ));
+        }
+        if let Err(reason) = self.security.validate_command_execution(command, approved) {
+            return Ok(failure(reason));
+        }
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted".into()));
+        }
+
+        match self.jobs.start(
+            command,
+            &self.security.workspace_dir,
+            &safe_env(),
+            self.tool_sandbox(),
+        ) {
+            Ok(id) => Ok(ToolResult {
+                success: true,
+                output: format!("Started job {id}: {command}"),
+                error: None,
+                data: Some(json!({ "id": id })),
+            }),
+            Err(e) => Ok(failure(format!("Failed to start job: {e}"))),
+        }
+    }
+}
+
+// ── shell_job_status ────────────────────────────────────────
+
+pub struct ShellJobStatusTool {
+    jobs: Arc<JobManager>,
+}
+
+#[async_trait]
+impl Tool for ShellJobStatusTool {
+    fn name(&self) -> &str {
+        "shell_job_status"
+    }
+
+    fn description(&self) -> &str {
+        "Show whether a background job is still running and its exit code. Without an id, lists all jobs."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "description": "Job id from shell_job_start"}
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let infos = match args.get("id").and_then(|v| v.as_str()) {
+            Some(id) => match self.jobs.status(id) {
+                Some(info) => vec![info],
+                None => return Ok(failure(format!("Unknown job '{id}'"))),
+            },
+            None => self.jobs.list(),
+        };
+        let output = if infos.is_empty() {
+            "No background jobs.".to_string()
+        } else {
+            infos.iter().map(describe).collect::<Vec<_>>().join("\n")
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(serde_json::to_value(&infos)?),
+        })
+    }
+}
+
+// ── shell_job_logs ──────────────────────────────────────────
+
+pub struct ShellJobLogsTool {
+    jobs: Arc<JobManager>,
+}
+
+#[async_trait]
+impl Tool for ShellJobLogsTool {
+    fn name(&self) -> &str {
+        "shell_job_logs"
+    }
+
+    fn description(&self) -> &str {
+        "Read the output (stdout and stderr) of a background job. Without 'since' returns the latest \
+         output; pass the returned next_offset as 'since' to read only what is new."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "description": "Job id from shell_job_start"},
+                "since": {"type": "integer", "description": "Byte offset to continue from (next_offset of the previous call)"},
+                "max_bytes": {"type": "integer", "description": "Most bytes to return (default 8192, max 65536)"}
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = job_id(&args)?;
+        let since = args.get("since").and_then(serde_json::Value::as_u64);
+        let limit = args
+            .get("max_bytes")
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|n| usize::try_from(n).ok())
+            .unwrap_or(DEFAULT_LOG_BYTES)
+            .min(MAX_LOG_BYTES);
+
+        let (Some((text, next_offset, dropped)), Some(info)) =
+            (self.jobs.logs(id, since, limit), self.jobs.status(id))
+        else {
+            return Ok(failure(format!("Unknown job '{id}'")));
+        };
+
+        let mut output = String::new();
+        if dropped {
+            output.push_str("[older output was dropped]\n");
+        }
+        output.push_str(&text);
+        let _ = write!(
+            output,
+            "\n[job {id} {}; next_offset {next_offset}]",
+            info.state.describe()
+        );
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            data: Some(json!({
+                "next_offset": next_offset,
+                "running": info.state == JobState::Running,
+                "dropped": dropped,
+            })),
+        })
+    }
+}
+
+// ── shell_job_kill ──────────────────────────────────────────
+
+pub struct ShellJobKillTool {
+    jobs: Arc<JobManager>,
+}
+
+#[async_trait]
+impl Tool for ShellJobKillTool {
+    fn name(&self) -> &str {
+        "shell_job_kill"
+    }
+
+    fn description(&self) -> &str {
+        "Stop a background job and everything it started."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "description": "Job id from shell_job_start"}
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = job_id(&args)?;
+        Ok(match self.jobs.kill(id) {
+            Some(true) => ToolResult {
+                success: true,
+                output: format!("Killing job {id}"),
+                error: None,
+                data: None,
+            },
+            Some(false) => ToolResult {
+                success: true,
+                output: format!("Job {id} already finished"),
+                error: None,
+                data: None,
+            },
+            None => failure(format!("Unknown job '{id}'")),
+        })
+    }
+}
+
+/// The four job tools, sharing one [`JobManager`] for `runtime`.
+pub fn shell_job_tools(
+    security: Arc<SecurityPolicy>,
+    runtime: Arc<dyn RuntimeAdapter>,
+) -> Vec<Box<dyn Tool>> {
+    let jobs = Arc::new(JobManager::new(runtime));
+    vec![
+        Box::new(ShellJobStartTool {
+            security,
+            jobs: Arc::clone(&jobs),
+            sandbox: OnceLock::new(),
+        }),
+        Box::new(ShellJobStatusTool {
+            jobs: Arc::clone(&jobs),
+        }),
+        Box::new(ShellJobLogsTool {
+            jobs: Arc::clone(&jobs),
+        }),
+        Box::new(ShellJobKillTool { jobs }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::AutonomyLevel;
+
+    fn tools(autonomy: AutonomyLevel) -> Vec<Box<dyn Tool>> {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: std::env::temp_dir(),
+            ..SecurityPolicy::default()
+        });
+        shell_job_tools(security, Arc::new(NativeRuntime::new()))
+    }
+
+    #[tokio::test]
+    async fn start_poll_and_read_logs() {
+        let tools = tools(AutonomyLevel::Supervised);
+        let started = tools[0].execute(json!({"command": "echo building"})).await.unwrap();
+        assert!(started.success, "{:?}", started.error);
+        let id = started.data.unwrap()["id"].as_str().unwrap().to_string();
+
+        let mut running = true;
+        for _ in 0..100 {
+            let status = tools[1].execute(json!({"id": id})).await.unwrap();
+            running = status.output.contains("running");
+            if !running {
+                assert!(status.output.contains("exited with code 0"));
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert!(!running);
+
+        let logs = tools[2].execute(json!({"id": id})).await.unwrap();
+        assert!(logs.output.contains("building"));
+        let next = logs.data.unwrap()["next_offset"].as_u64().unwrap();
+        let again = tools[2].execute(json!({"id": id, "since": next})).await.unwrap();
+        assert!(!again.output.contains("building"));
+    }
+
+    #[tokio::test]
+    async fn start_is_checked_against_the_command_policy() {
+        let tools = tools(AutonomyLevel::ReadOnly);
+        let result = tools[0].execute(json!({"command": "echo hi"})).await.unwrap();
+        assert!(!result.success);
+        let unknown = tools[3].execute(json!({"id": "missing"})).await.unwrap();
+        assert!(!unknown.success);
+    }
+}
@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Tool usage analytics.
+//!
+//! `SecurityWrapper` records every tool call here: one row per tool and
+//! UTC day in `.mymolt/tool_stats.db`. The daily counts also back the
+//! per-tool budgets (`autonomy.tool_daily_budgets`).
+
+use anyhow::{Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Aggregated usage of one tool over a time window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolUsage {
+    pub tool: String,
+    pub calls: u64,
+    pub failures: u64,
+    pub failure_rate: f64,
+    pub avg_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Output and error text produced, in bytes.
+    pub bytes: u64,
+    pub calls_today: u64,
+}
+
+/// SQLite-backed per-tool, per-day counters.
+pub struct ToolStats {
+    conn: Mutex<Connection>,
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+impl ToolStats {
+    /// Open (or create) the stats database of `workspace_dir`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = workspace_dir.join(".mymolt").join("tool_stats.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open tool stats DB: {}", db_path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             CREATE TABLE IF NOT EXISTS tool_usage (
+                tool        TEXT NOT NULL,
+                day         TEXT NOT NULL,
+                calls       INTEGER NOT NULL DEFAULT 0,
+                failures    INTEGER NOT NULL DEFAULT 0,
+                total_ms    INTEGER NOT NULL DEFAULT 0,
+                max_ms      INTEGER NOT NULL DEFAULT 0,
+                bytes       INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (tool, day)
+             );",
+        )
+        .context("Failed to initialize tool stats DB")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Record one call of `tool`.
+    pub fn record(&self, tool: &str, success: bool, latency: Duration, bytes: usize) -> Result<()> {
+        let ms = i64::try_from(latency.as_millis()).unwrap_or(i64::MAX);
+        let bytes = i64::try_from(bytes).unwrap_or(i64::MAX);
+        self.conn().execute(
+            "INSERT INTO tool_usage (tool, day, calls, failures, total_ms, max_ms, bytes)
+             VALUES (?1, ?2, 1, ?3, ?4, ?4, ?5)
+             ON CONFLICT (tool, day) DO UPDATE SET
+                calls    = calls + 1,
+                failures = failures + excluded.failures,
+                total_ms = total_ms + excluded.total_ms,
+                max_ms   = MAX(max_ms, excluded.max_ms),
+                bytes    = bytes + excluded.bytes",
+            params![tool, today(), i64::from(!success), ms, bytes],
+        )?;
+        Ok(())
+    }
+
+    /// Calls of `tool` so far today (UTC).
+    pub fn calls_today(&self, tool: &str) -> Result<u64> {
+        let calls: Option<i64> = self
+            .conn()
+            .query_row(
+                "SELECT calls FROM tool_usage WHERE tool = ?1 AND day = ?2",
+                params![tool, today()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(calls.map_or(0, |c| u64::try_from(c).unwrap_or(0)))
+    }
+
+    /// Usage per tool over the last `days` days (including today), most
+    /// used first.
+    pub fn summary(&self, days: u32) -> Result<Vec<ToolUsage>> {
+        let since = (Utc::now() - ChronoDuration::days(i64::from(days.max(1)) - 1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let today = today();
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT tool, SUM(calls), SUM(failures), SUM(total_ms), MAX(max_ms), SUM(bytes),
+                    SUM(CASE WHEN day = ?2 THEN calls ELSE 0 END)
+             FROM tool_usage WHERE day >= ?1
+             GROUP BY tool ORDER BY SUM(calls) DESC, tool",
+        )?;
+        let rows = stmt.query_map(params![since, today], |row| {
+            let count = |i: usize| -> rusqlite::Result<u64> {
+                Ok(u64::try_from(row.get::<_, i64>(i)?).unwrap_or(0))
+            };
+            let calls = count(1)?;
+            let failures = count(2)?;
+            let total_ms = count(3)?;
+            Ok(ToolUsage {
+                tool: row.get(0)?,
+                calls,
+                failures,
+                failure_rate: if calls == 0 {
+                    0.0
+                } else {
+                    failures as f64 / calls as f64
+                },
+                avg_latency_ms: total_ms.checked_div(calls).unwrap_or(0),
+                max_latency_ms: count(4)?,
+                bytes: count(5)?,
+                calls_today: count(6)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_aggregates_calls() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stats = ToolStats::open(tmp.path()).unwrap();
+        stats
+            .record("http_request", true, Duration::from_millis(100), 40)
+            .unwrap();
+        stats
+            .record("http_request", false, Duration::from_millis(300), 10)
+            .unwrap();
+        stats
+            .record("shell", true, Duration::from_millis(5), 0)
+            .unwrap();
+
+        assert_eq!(stats.calls_today("http_request").unwrap(), 2);
+        assert_eq!(stats.calls_today("file_read").unwrap(), 0);
+
+        let summary = stats.summary(7).unwrap();
+        assert_eq!(summary.len(), 2);
+        let http = &summary[0];
+        assert_eq!(http.tool, "http_request");
+        assert_eq!(http.failures, 1);
+        assert!((http.failure_rate - 0.5).abs() < f64::EPSILON);
+        assert_eq!(http.avg_latency_ms, 200);
+        assert_eq!(http.max_latency_ms, 300);
+        assert_eq!(http.bytes, 50);
+        assert_eq!(http.calls_today, 2);
+    }
+
+    #[test]
+    fn counts_persist_across_reopen() {
+        let tmp = tempfile::tempdir().unwrap();
+        ToolStats::open(tmp.path())
+            .unwrap()
+            .record("shell", true, Duration::ZERO, 0)
+            .unwrap();
+        let reopened = ToolStats::open(tmp.path()).unwrap();
+        assert_eq!(reopened.calls_today("shell").unwrap(), 1);
+    }
+}
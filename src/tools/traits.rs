@@ -2,6 +2,8 @@
 // Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
 // Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
 
+use crate::config::SandboxProfile;
+use crate::security::detect::ToolSandbox;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +13,21 @@ pub struct ToolResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Machine-readable result matching [`Tool::output_schema`] (tables, file
+    /// lists, API responses). `output` stays the human-readable text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl ToolResult {
+    /// What the agent loop hands back to the model: `data` as JSON when the
+    /// tool returned structured data, `output` otherwise.
+    pub fn model_content(&self) -> String {
+        match &self.data {
+            Some(data) => data.to_string(),
+            None => self.output.clone(),
+        }
+    }
 }
 
 /// Description of a tool for the LLM
@@ -19,6 +36,9 @@ pub struct ToolSpec {
     pub name: String,
     pub description: String,
     pub parameters: serde_json::Value,
+    /// JSON schema of `ToolResult::data`, for tools that return structured data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
 }
 
 /// Core tool trait — implement for any capability
@@ -33,6 +53,21 @@ pub trait Tool: Send + Sync {
     /// JSON schema for parameters
     fn parameters_schema(&self) -> serde_json::Value;
 
+    /// JSON schema of the structured `data` this tool returns, if any
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Isolation the commands this tool runs need
+    fn sandbox_profile(&self) -> SandboxProfile {
+        SandboxProfile::None
+    }
+
+    /// The sandbox this tool runs its commands in, for tools that run any
+    fn sandbox(&self) -> Option<&ToolSandbox> {
+        None
+    }
+
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
@@ -42,6 +77,7 @@ pub trait Tool: Send + Sync {
             name: self.name().to_string(),
             description: self.description().to_string(),
             parameters: self.parameters_schema(),
+            output_schema: self.output_schema(),
         }
     }
 }
@@ -80,6 +116,7 @@ mod tests {
                     .unwrap_or_default()
                     .to_string(),
                 error: None,
+                data: None,
             })
         }
     }
@@ -108,12 +145,19 @@ mod tests {
         assert!(result.error.is_none());
     }
 
+    #[test]
+    fn tools_are_unsandboxed_by_default() {
+        assert_eq!(DummyTool.sandbox_profile(), SandboxProfile::None);
+        assert!(DummyTool.sandbox().is_none());
+    }
+
     #[test]
     fn tool_result_serialization_roundtrip() {
         let result = ToolResult {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            data: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -121,5 +165,23 @@ mod tests {
 
         assert!(!parsed.success);
         assert_eq!(parsed.error.as_deref(), Some("boom"));
+        assert!(!json.contains("\"data\""));
+    }
+
+    #[test]
+    fn model_content_prefers_structured_data() {
+        let mut result = ToolResult {
+            success: true,
+            output: "2 files".into(),
+            error: None,
+            data: None,
+        };
+        assert_eq!(result.model_content(), "2 files");
+
+        result.data = Some(serde_json::json!(["a.rs", "b.rs"]));
+        assert_eq!(result.model_content(), r#"["a.rs","b.rs"]"#);
+        let parsed: ToolResult =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(parsed.data, result.data);
     }
 }
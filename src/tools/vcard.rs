@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! vCard (RFC 6350) export and import for the PIM contacts.
+//!
+//! Exports vCard 4.0 and reads 2.1, 3.0 and 4.0 as written by phone and
+//! desktop address books. `FN` (or `N`), `EMAIL`, `TEL` and `NOTE` are
+//! mapped; the preferred email and phone become the primary ones. An
+//! imported contact is merged into an existing one with the same `UID` or
+//! name, so importing the same address book twice changes nothing.
+
+use crate::tools::ics::{escape, push_line, split_property, unescape, unfold};
+use crate::tools::pim::Contact;
+
+/// `(name, params, value)` of one content line.
+type Property = (String, String, String);
+
+/// Serialize `contacts` as vCard 4.0.
+pub fn export_contacts(contacts: &[Contact]) -> String {
+    let mut out = String::new();
+    for contact in contacts {
+        push_line(&mut out, "BEGIN:VCARD");
+        push_line(&mut out, "VERSION:4.0");
+        push_line(&mut out, &format!("UID:{}", escape(&contact.id)));
+        push_line(&mut out, &format!("FN:{}", escape(&contact.name)));
+        for (i, email) in contact.email.iter().chain(&contact.other_emails).enumerate() {
+            let pref = if i == 0 { ";PREF=1" } else { "" };
+            push_line(&mut out, &format!("EMAIL{pref}:{}", escape(email)));
+        }
+        for (i, phone) in contact.phone.iter().chain(&contact.other_phones).enumerate() {
+            let pref = if i == 0 { ";PREF=1" } else { "" };
+            push_line(&mut out, &format!("TEL;VALUE=text{pref}:{}", escape(phone)));
+        }
+        if !contact.notes.is_empty() {
+            push_line(&mut out, &format!("NOTE:{}", escape(&contact.notes)));
+        }
+        push_line(&mut out, "END:VCARD");
+    }
+    out
+}
+
+/// Contacts parsed from a vCard file.
+#[derive(Debug, Default)]
+pub struct ParsedContacts {
+    pub contacts: Vec<Contact>,
+    /// Cards without a name.
+    pub skipped: usize,
+}
+
+/// Parse the `VCARD`s in `text`. Cards without a `UID` get a random id.
+pub fn parse_vcards(text: &str) -> ParsedContacts {
+    let mut parsed = ParsedContacts::default();
+    let mut current: Option<Vec<Property>> = None;
+
+    for line in unfold(text) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        // Apple groups related lines as `item1.EMAIL`.
+        let name = name.rsplit('.').next().unwrap_or_default().to_string();
+        match (name.as_str(), value.trim()) {
+            ("BEGIN", v) if v.eq_ignore_ascii_case("VCARD") => current = Some(Vec::new()),
+            ("END", v) if v.eq_ignore_ascii_case("VCARD") => {
+                if let Some(props) = current.take() {
+                    match contact_from(&props) {
+                        Some(contact) => parsed.contacts.push(contact),
+                        None => parsed.skipped += 1,
+                    }
+                }
+            }
+            _ => {
+                if let Some(props) = current.as_mut() {
+                    props.push((name, params, value));
+                }
+            }
+        }
+    }
+    parsed
+}
+
+fn contact_from(props: &[Property]) -> Option<Contact> {
+    let get = |name: &str| {
+        props
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, _, v)| v.trim())
+    };
+    let name = get("FN")
+        .map(unescape)
+        .filter(|n| !n.trim().is_empty())
+        .or_else(|| get("N").and_then(name_from_n))?;
+
+    let mut emails = values(props, "EMAIL", "mailto:").into_iter();
+    let mut phones = values(props, "TEL", "tel:").into_iter();
+    let notes: Vec<String> = props
+        .iter()
+        .filter(|(n, _, v)| n == "NOTE" && !v.trim().is_empty())
+        .map(|(_, _, v)| unescape(v.trim()))
+        .collect();
+
+    Some(Contact {
+        id: get("UID")
+            .map(unescape)
+            .filter(|uid| !uid.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        name: name.trim().to_string(),
+        email: emails.next(),
+        phone: phones.next(),
+        other_emails: emails.collect(),
+        other_phones: phones.collect(),
+        notes: notes.join("\n"),
+    })
+}
+
+/// `N:Family;Given;Additional;Prefix;Suffix` → "Prefix Given Additional Family Suffix".
+fn name_from_n(value: &str) -> Option<String> {
+    let parts: Vec<&str> = value.split(';').map(str::trim).collect();
+    let part = |i: usize| parts.get(i).copied().unwrap_or_default();
+    let name = [part(3), part(1), part(2), part(0), part(4)]
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .map(unescape)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+/// All values of `name`, preferred ones (`PREF` or `TYPE=pref`) first,
+/// without a URI `scheme` and without duplicates.
+fn values(props: &[Property], name: &str, scheme: &str) -> Vec<String> {
+    let mut found: Vec<(bool, String)> = props
+        .iter()
+        .filter(|(n, _, _)| n == name)
+        .map(|(_, params, v)| {
+            let v = v.trim();
+            let v = match v.get(..scheme.len()) {
+                Some(prefix) if prefix.eq_ignore_ascii_case(scheme) => &v[scheme.len()..],
+                _ => v,
+            };
+            (params.contains("PREF"), unescape(v.trim()))
+        })
+        .filter(|(_, v)| !v.is_empty())
+        .collect();
+    found.sort_by_key(|(pref, _)| !pref);
+
+    let mut out: Vec<String> = Vec::new();
+    for (_, value) in found {
+        if !out.contains(&value) {
+            out.push(value);
+        }
+    }
+    out
+}
+
+/// Outcome of [`merge_contacts`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeCounts {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+/// Merge `imported` into `contacts`. A contact with the same id or name
+/// (case-insensitive) gains the emails, phones and notes it is missing;
+/// nothing it already has is replaced.
+pub fn merge_contacts(contacts: &mut Vec<Contact>, imported: Vec<Contact>) -> MergeCounts {
+    let mut counts = MergeCounts::default();
+    for contact in imported {
+        let existing = contacts
+            .iter_mut()
+            .find(|c| c.id == contact.id || c.name.eq_ignore_ascii_case(&contact.name));
+        let Some(existing) = existing else {
+            contacts.push(contact);
+            counts.added += 1;
+            continue;
+        };
+
+        let mut changed = absorb(
+            &mut existing.email,
+            &mut existing.other_emails,
+            contact.email.into_iter().chain(contact.other_emails),
+        );
+        changed |= absorb(
+            &mut existing.phone,
+            &mut existing.other_phones,
+            contact.phone.into_iter().chain(contact.other_phones),
+        );
+        if !contact.notes.is_empty() && !existing.notes.contains(&contact.notes) {
+            if !existing.notes.is_empty() {
+                existing.notes.push('\n');
+            }
+            existing.notes.push_str(&contact.notes);
+            changed = true;
+        }
+
+        if changed {
+            counts.updated += 1;
+        } else {
+            counts.unchanged += 1;
+        }
+    }
+    counts
+}
+
+/// Add the `values` not yet in `primary` or `others`. Returns whether any was added.
+fn absorb(
+    primary: &mut Option<String>,
+    others: &mut Vec<String>,
+    values: impl Iterator<Item = String>,
+) -> bool {
+    let mut changed = false;
+    for value in values {
+        if primary.as_deref() == Some(value.as_str()) || others.contains(&value) {
+            continue;
+        }
+        if primary.is_none() {
+            *primary = Some(value);
+        } else {
+            others.push(value);
+        }
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(id: &str, name: &str, email: Option<&str>) -> Contact {
+        Contact {
+            id: id.into(),
+            name: name.into(),
+            email: email.map(String::from),
+            phone: None,
+            other_emails: Vec::new(),
+            other_phones: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn export_then_import_roundtrips() {
+        let mut anna = contact("c1", "Anna Schmidt, MD", Some("anna@example.org"));
+        anna.other_emails.push("a.schmidt@work.example".into());
+        anna.phone = Some("+49 30 123456".into());
+        anna.notes = "Met at the\nspring fair".into();
+        let contacts = vec![anna, contact("c2", "Ben", None)];
+
+        let vcf = export_contacts(&contacts);
+        assert!(vcf.contains("VERSION:4.0\r\n"));
+        assert!(vcf.contains("FN:Anna Schmidt\\, MD\r\n"));
+        assert!(vcf.contains("EMAIL;PREF=1:anna@example.org\r\n"));
+
+        let parsed = parse_vcards(&vcf);
+        assert_eq!(parsed.skipped, 0);
+        assert_eq!(parsed.contacts.len(), 2);
+        for (got, want) in parsed.contacts.iter().zip(&contacts) {
+            assert_eq!(got.id, want.id);
+            assert_eq!(got.name, want.name);
+            assert_eq!(got.email, want.email);
+            assert_eq!(got.other_emails, want.other_emails);
+            assert_eq!(got.phone, want.phone);
+            assert_eq!(got.notes, want.notes);
+        }
+    }
+
+    #[test]
+    fn import_handles_phone_exports() {
+        let vcf = "BEGIN:VCARD\nVERSION:3.0\nN:Meier;Clara;;Dr.;\nitem1.EMAIL;type=INTERNET:clara@home.example\nEMAIL;TYPE=INTERNET,pref:clara@work.example\nTEL;TYPE=CELL:+49 170 1\nEND:VCARD\nBEGIN:VCARD\nVERSION:4.0\nTEL;VALUE=uri:tel:+1-555-0100\nEND:VCARD\nBEGIN:VCARD\nVERSION:2.1\nFN:Dora\nTEL;VALUE=uri:tel:+1-555-0199\nEND:VCARD\n";
+        let parsed = parse_vcards(vcf);
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.contacts.len(), 2);
+
+        let clara = &parsed.contacts[0];
+        assert_eq!(clara.name, "Dr. Clara Meier");
+        assert_eq!(clara.email.as_deref(), Some("clara@work.example"));
+        assert_eq!(clara.other_emails, vec!["clara@home.example".to_string()]);
+        assert_eq!(clara.phone.as_deref(), Some("+49 170 1"));
+        assert_eq!(parsed.contacts[1].phone.as_deref(), Some("+1-555-0199"));
+    }
+
+    #[test]
+    fn merge_adds_missing_details_without_replacing() {
+        let mut contacts = vec![contact("c1", "Anna", Some("anna@example.org"))];
+        let mut update = contact("other-id", "anna", Some("anna@work.example"));
+        update.phone = Some("+49 1".into());
+
+        let counts = merge_contacts(
+            &mut contacts,
+            vec![update, contact("c2", "Ben", None), contact("c1", "Anna", Some("anna@example.org"))],
+        );
+        assert_eq!(counts, MergeCounts { added: 1, updated: 1, unchanged: 1 });
+        assert_eq!(contacts[0].email.as_deref(), Some("anna@example.org"));
+        assert_eq!(contacts[0].other_emails, vec!["anna@work.example".to_string()]);
+        assert_eq!(contacts[0].phone.as_deref(), Some("+49 1"));
+    }
+}
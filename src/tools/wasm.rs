@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! WASM plugin host for third-party tools.
+//!
+//! Every `<workspace>/plugins/*.wasm` file is a WebAssembly component
+//! implementing `wit/plugin.wit`. Components are instantiated without any
+//! imports, so a plugin cannot touch the filesystem, network, clock or
+//! environment; it only sees the JSON arguments it is called with. Each call
+//! runs in a fresh instance bounded by `[plugins]` memory, fuel and time
+//! limits. Needs the `wasm-plugins` feature.
+
+use crate::config::PluginsConfig;
+use crate::tools::traits::Tool;
+use std::path::{Path, PathBuf};
+
+pub fn plugins_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("plugins")
+}
+
+/// `.wasm` files in the plugin directory, sorted.
+fn plugin_files(workspace_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(plugins_dir(workspace_dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg_attr(not(feature = "wasm-plugins"), allow(dead_code))]
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        anyhow::bail!("Plugin name '{name}' must be 1-64 lowercase letters, digits and '_'");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "wasm-plugins")]
+mod host {
+    use super::validate_name;
+    use crate::config::PluginsConfig;
+    use crate::tools::traits::{Tool, ToolResult};
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use wasmtime::component::{Component, Linker};
+    use wasmtime::{Engine, Store, StoreLimits, StoreLimitsBuilder};
+
+    wasmtime::component::bindgen!({
+        path: "wit/plugin.wit",
+        world: "plugin",
+    });
+
+    /// Compiled plugins share one engine; every call gets its own store.
+    pub struct PluginHost {
+        engine: Engine,
+        /// Empty: plugins are given no imports.
+        linker: Linker<StoreLimits>,
+        config: PluginsConfig,
+    }
+
+    impl PluginHost {
+        pub fn new(config: &PluginsConfig) -> Result<Self> {
+            let mut engine_config = wasmtime::Config::new();
+            engine_config.wasm_component_model(true);
+            engine_config.consume_fuel(true);
+            let engine = Engine::new(&engine_config)?;
+            Ok(Self {
+                linker: Linker::new(&engine),
+                engine,
+                config: config.clone(),
+            })
+        }
+
+        fn instantiate(&self, component: &Component) -> Result<(Store<StoreLimits>, Plugin)> {
+            let limits = StoreLimitsBuilder::new()
+                .memory_size(self.config.max_memory_mb.saturating_mul(1024 * 1024))
+                .instances(1)
+                .build();
+            let mut store = Store::new(&self.engine, limits);
+            store.limiter(|limits| limits);
+            store.set_fuel(self.config.fuel)?;
+            let plugin = Plugin::instantiate(&mut store, component, &self.linker)
+                .context("Plugin needs imports the host does not provide")?;
+            Ok((store, plugin))
+        }
+
+        /// Compile `path` and read the plugin's name, description and schema.
+        pub fn load(self: &Arc<Self>, path: &Path) -> Result<WasmPluginTool> {
+            let component = Component::from_file(&self.engine, path)?;
+            let (mut store, plugin) = self.instantiate(&component)?;
+            let name = plugin.call_name(&mut store)?;
+            validate_name(&name)?;
+            let description = plugin.call_description(&mut store)?;
+            let parameters: serde_json::Value =
+                serde_json::from_str(&plugin.call_parameters_schema(&mut store)?)
+                    .context("parameters-schema is not valid JSON")?;
+            if !parameters.is_object() {
+                anyhow::bail!("parameters-schema must be a JSON object");
+            }
+            Ok(WasmPluginTool {
+                host: Arc::clone(self),
+                component,
+                name,
+                description,
+                parameters,
+            })
+        }
+
+        fn execute(&self, component: &Component, args: &str) -> Result<Result<String, String>> {
+            let (mut store, plugin) = self.instantiate(component)?;
+            plugin.call_execute(&mut store, args)
+        }
+    }
+
+    /// A tool backed by a WASM component.
+    pub struct WasmPluginTool {
+        host: Arc<PluginHost>,
+        component: Component,
+        name: String,
+        description: String,
+        parameters: serde_json::Value,
+    }
+
+    #[async_trait]
+    impl Tool for WasmPluginTool {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn description(&self) -> &str {
+            &self.description
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            self.parameters.clone()
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            let host = Arc::clone(&self.host);
+            let component = self.component.clone();
+            let config = &self.host.config;
+            // Running out of fuel stops the instance; the timeout only stops
+            // waiting for it.
+            let call =
+                tokio::task::spawn_blocking(move || host.execute(&component, &args.to_string()));
+            let outcome =
+                match tokio::time::timeout(Duration::from_secs(config.timeout_secs), call).await {
+                    Ok(joined) => joined?,
+                    Err(_) => Err(anyhow::anyhow!(
+                        "Plugin timed out after {}s",
+                        config.timeout_secs
+                    )),
+                };
+            let truncate =
+                |text: String| crate::util::truncate_with_ellipsis(&text, config.max_output_chars);
+            Ok(match outcome {
+                Ok(Ok(output)) => ToolResult {
+                    success: true,
+                    output: truncate(output),
+                    error: None,
+                    data: None,
+                },
+                Ok(Err(message)) => ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(truncate(message)),
+                    data: None,
+                },
+                Err(trap) => ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Plugin '{}' failed: {trap:#}", self.name)),
+                    data: None,
+                },
+            })
+        }
+    }
+
+    pub fn load_all(files: &[std::path::PathBuf], config: &PluginsConfig) -> Vec<Box<dyn Tool>> {
+        let host = match PluginHost::new(config) {
+            Ok(host) => Arc::new(host),
+            Err(e) => {
+                tracing::warn!("WASM plugins disabled: {e:#}");
+                return Vec::new();
+            }
+        };
+        files
+            .iter()
+            .filter_map(|path| match host.load(path) {
+                Ok(tool) => Some(Box::new(tool) as Box<dyn Tool>),
+                Err(e) => {
+                    tracing::warn!("Skipping plugin {}: {e:#}", path.display());
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Load the workspace's WASM plugins as tools. Plugins named like a tool in
+/// `taken` are skipped so built-ins cannot be shadowed.
+pub fn plugin_tools(
+    workspace_dir: &Path,
+    taken: &[&str],
+    config: &PluginsConfig,
+) -> Vec<Box<dyn Tool>> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    let files = plugin_files(workspace_dir);
+    if files.is_empty() {
+        return Vec::new();
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    let tools = host::load_all(&files, config);
+    #[cfg(not(feature = "wasm-plugins"))]
+    let tools: Vec<Box<dyn Tool>> = {
+        tracing::warn!(
+            count = files.len(),
+            "WASM plugins found but MyMolt was built without the `wasm-plugins` feature"
+        );
+        Vec::new()
+    };
+
+    tools
+        .into_iter()
+        .filter(|tool| {
+            let clash = taken.contains(&tool.name());
+            if clash {
+                tracing::warn!("Plugin '{}' clashes with a built-in tool", tool.name());
+            }
+            !clash
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_wasm_files_are_plugins() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(plugin_files(tmp.path()).is_empty());
+
+        let dir = plugins_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.wasm"), b"\0asm").unwrap();
+        std::fs::write(dir.join("a.wasm"), b"\0asm").unwrap();
+        std::fs::write(dir.join("README.md"), "plugins").unwrap();
+        assert_eq!(
+            plugin_files(tmp.path()),
+            vec![dir.join("a.wasm"), dir.join("b.wasm")]
+        );
+    }
+
+    #[test]
+    fn invalid_plugins_are_not_loaded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = plugins_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.wasm"), b"not wasm").unwrap();
+
+        let mut config = PluginsConfig::default();
+        assert!(plugin_tools(tmp.path(), &[], &config).is_empty());
+        config.enabled = true;
+        assert!(plugin_tools(tmp.path(), &[], &config).is_empty());
+    }
+
+    #[test]
+    fn plugin_names_are_validated() {
+        assert!(validate_name("word_count").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Shell").is_err());
+        assert!(validate_name("mcp:evil").is_err());
+    }
+}
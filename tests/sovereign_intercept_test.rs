@@ -1,7 +1,7 @@
 use anyhow::Result;
 use std::sync::Arc;
 use tempfile::TempDir;
-    use mymolt_core::config::{AuditConfig, MemoryConfig};
+    use mymolt_core::config::{AuditConfig, MemoryConfig, SensitivityConfig};
     use mymolt_core::memory::{create_memory, MemoryCategory};
     use mymolt_core::security::{AuditLogger, VaultManager};
 
@@ -32,7 +32,7 @@ use tempfile::TempDir;
         )?);
         
         // Create memory (this will be SovereignMemory wrapping MarkdownMemory)
-        let memory = create_memory(&config, tmp.path(), None, audit)?;
+        let memory = create_memory(&config, &SensitivityConfig::default(), tmp.path(), None, audit)?;
     
     // Ensure we are using the Sovereign wrapper
     assert_eq!(memory.name(), "sovereign");